@@ -73,6 +73,12 @@ struct Vertex {
 #endif
 
     @location(20) texture_index: u32,
+#ifdef BLOCK_PAINT
+    @location(21) paint_color: vec4<f32>,
+#endif
+#ifdef BLOCK_LIGHT
+    @location(22) block_light: vec4<f32>,
+#endif
 };
 
 
@@ -102,6 +108,12 @@ struct VertexOutput {
 #endif
 
     @location(20) texture_index: u32,
+#ifdef BLOCK_PAINT
+    @location(21) paint_color: vec4<f32>,
+#endif
+#ifdef BLOCK_LIGHT
+    @location(22) block_light: vec4<f32>,
+#endif
 }
 
 #ifdef MORPH_TARGETS
@@ -194,6 +206,12 @@ fn vertex(vertex_no_morph: Vertex) -> VertexOutput {
 #endif
 
     out.texture_index = vertex.texture_index;
+#ifdef BLOCK_PAINT
+    out.paint_color = vertex.paint_color;
+#endif
+#ifdef BLOCK_LIGHT
+    out.block_light = vertex.block_light;
+#endif
 
     return out;
 }
@@ -224,6 +242,11 @@ fn fragment(
     var out: FragmentOutput;
     if (pbr_input.material.flags & STANDARD_MATERIAL_FLAGS_UNLIT_BIT) == 0u {
         out.color = apply_pbr_lighting(pbr_input);
+
+#ifdef BLOCK_LIGHT
+        // Normal lighting is scaled by how much sky light reaches this block, then light from nearby light blocks is added on top.
+        out.color = vec4(out.color.rgb * in.block_light.a + pbr_input.material.base_color.rgb * in.block_light.rgb, out.color.a);
+#endif
     } else {
         out.color = pbr_input.material.base_color;
     }
@@ -381,6 +404,10 @@ fn pbr_input_from_standard_material(
     }
 #endif // VERTEX_UVS
 
+#ifdef BLOCK_PAINT
+    pbr_input.material.base_color = vec4(pbr_input.material.base_color.rgb * in.paint_color.rgb, pbr_input.material.base_color.a);
+#endif
+
     pbr_input.material.flags = pbr_bindings::material.flags;
 
     // NOTE: Unlit bit not set means == 0 is true, so the true case is if lit
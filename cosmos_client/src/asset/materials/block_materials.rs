@@ -24,6 +24,15 @@ pub const ATTRIBUTE_TEXTURE_INDEX: MeshVertexAttribute =
     // See the MeshVertexAttribute docs for more info.
     MeshVertexAttribute::new("ArrayTextureIndex", 923840841, VertexFormat::Uint32);
 
+/// The color a block has been painted, which its texture is multiplied by
+///
+/// Unpainted blocks should be white.
+pub const ATTRIBUTE_PAINT_COLOR: MeshVertexAttribute = MeshVertexAttribute::new("PaintColor", 923840842, VertexFormat::Unorm8x4);
+
+/// How much light reaches a block face. The red, green, and blue channels are the color of the block light reaching
+/// it, and the alpha channel is how much of the normal (sky) lighting reaches it.
+pub const ATTRIBUTE_BLOCK_LIGHT: MeshVertexAttribute = MeshVertexAttribute::new("BlockLight", 923840843, VertexFormat::Unorm8x4);
+
 /// An enum to define which UV attribute to use for a texture.
 ///
 /// It is used for every texture in the [`ArrayTextureMaterial`].
@@ -1144,12 +1153,34 @@ impl Material for ArrayTextureMaterial {
             depth_stencil.bias.constant = (key.bind_group_data.bits() >> STANDARD_MATERIAL_KEY_DEPTH_BIAS_SHIFT) as i32;
         }
 
-        let vertex_layout = layout.0.get_layout(&[
+        let mut vertex_attributes = vec![
             Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
             Mesh::ATTRIBUTE_NORMAL.at_shader_location(1),
             Mesh::ATTRIBUTE_UV_0.at_shader_location(2),
             ATTRIBUTE_TEXTURE_INDEX.at_shader_location(20),
-        ])?;
+        ];
+
+        // Only structure meshes have paint colors, not things like items
+        if layout.0.contains(ATTRIBUTE_PAINT_COLOR) {
+            vertex_attributes.push(ATTRIBUTE_PAINT_COLOR.at_shader_location(21));
+
+            descriptor.vertex.shader_defs.push("BLOCK_PAINT".into());
+            if let Some(fragment) = descriptor.fragment.as_mut() {
+                fragment.shader_defs.push("BLOCK_PAINT".into());
+            }
+        }
+
+        // Only chunks have their lighting calculated
+        if layout.0.contains(ATTRIBUTE_BLOCK_LIGHT) {
+            vertex_attributes.push(ATTRIBUTE_BLOCK_LIGHT.at_shader_location(22));
+
+            descriptor.vertex.shader_defs.push("BLOCK_LIGHT".into());
+            if let Some(fragment) = descriptor.fragment.as_mut() {
+                fragment.shader_defs.push("BLOCK_LIGHT".into());
+            }
+        }
+
+        let vertex_layout = layout.0.get_layout(&vertex_attributes)?;
 
         descriptor.vertex.buffers = vec![vertex_layout];
 
@@ -1,6 +1,10 @@
+use bevy::utils::HashSet;
 use block_materials::ArrayTextureMaterial;
 
-use crate::asset::asset_loading::{AllTexturesDoneLoadingEvent, AssetsDoneLoadingEvent, CosmosTextureAtlas};
+use crate::{
+    asset::asset_loading::{AllTexturesDoneLoadingEvent, AssetsDoneLoadingEvent, CosmosTextureAtlas},
+    structure::systems::cloaking_system::SeeThroughCloak,
+};
 
 use super::super::*;
 
@@ -12,6 +16,16 @@ pub(crate) struct UnlitMaterial(pub Vec<Handle<ArrayTextureMaterial>>);
 pub(crate) struct TransparentMaterial(pub Vec<Handle<ArrayTextureMaterial>>);
 #[derive(Resource)]
 pub(crate) struct UnlitTransparentMaterial(pub Vec<Handle<ArrayTextureMaterial>>);
+#[derive(Resource)]
+/// Used in place of every other main material for structures that are cloaked
+pub(crate) struct CloakedMaterial(pub Vec<Handle<ArrayTextureMaterial>>);
+
+/// How visible a cloaked structure is to the players that can still see it
+const CLOAKED_ALPHA: f32 = 0.15;
+
+#[derive(Component)]
+/// The material this mesh had before it was swapped out for the [`CloakedMaterial`]
+struct UncloakedMaterial(Handle<ArrayTextureMaterial>);
 
 fn respond_to_add_materials_event(
     material_registry: Res<Registry<MaterialDefinition>>,
@@ -85,6 +99,81 @@ fn create_transparent_material(image_handle: Handle<Image>, unlit: bool) -> Arra
     }
 }
 
+fn create_cloaked_material(image_handle: Handle<Image>) -> ArrayTextureMaterial {
+    ArrayTextureMaterial {
+        base_color: Color::srgba(1.0, 1.0, 1.0, CLOAKED_ALPHA),
+        base_color_texture: Some(image_handle),
+        alpha_mode: AlphaMode::Blend,
+        metallic: 0.0,
+        reflectance: 0.0,
+        perceptual_roughness: 1.0,
+        ..Default::default()
+    }
+}
+
+/// Swaps the materials of every mesh on a [`SeeThroughCloak`] structure with their [`CloakedMaterial`] equivalent,
+/// and puts the original materials back once the structure decloaks.
+///
+/// This runs every frame because re-rendered chunks are given their normal materials again.
+fn apply_cloaked_materials(
+    mut commands: Commands,
+    q_see_through: Query<Entity, With<SeeThroughCloak>>,
+    q_children: Query<&Children>,
+    mut q_material: Query<&mut MeshMaterial3d<ArrayTextureMaterial>>,
+    q_uncloaked_material: Query<(Entity, &UncloakedMaterial)>,
+    default_material: Res<DefaultMaterial>,
+    unlit_material: Res<UnlitMaterial>,
+    transparent_material: Res<TransparentMaterial>,
+    unlit_transparent_material: Res<UnlitTransparentMaterial>,
+    cloaked_material: Res<CloakedMaterial>,
+) {
+    let is_cloaked_material = |handle: &Handle<ArrayTextureMaterial>| cloaked_material.0.iter().any(|x| x.id() == handle.id());
+
+    let mut see_through_meshes = HashSet::new();
+
+    for structure_ent in q_see_through.iter() {
+        for ent in q_children.iter_descendants(structure_ent) {
+            let Ok(mut material) = q_material.get_mut(ent) else {
+                continue;
+            };
+
+            see_through_meshes.insert(ent);
+
+            if is_cloaked_material(&material.0) {
+                continue;
+            }
+
+            let Some(idx) = [
+                &default_material.0,
+                &unlit_material.0,
+                &transparent_material.0,
+                &unlit_transparent_material.0,
+            ]
+            .into_iter()
+            .find_map(|handles| handles.iter().position(|x| x.id() == material.0.id())) else {
+                continue;
+            };
+
+            commands.entity(ent).insert(UncloakedMaterial(material.0.clone_weak()));
+            material.0 = cloaked_material.0[idx].clone_weak();
+        }
+    }
+
+    for (ent, uncloaked_material) in q_uncloaked_material.iter() {
+        if see_through_meshes.contains(&ent) {
+            continue;
+        }
+
+        if let Ok(mut material) = q_material.get_mut(ent) {
+            if is_cloaked_material(&material.0) {
+                material.0 = uncloaked_material.0.clone_weak();
+            }
+        }
+
+        commands.entity(ent).remove::<UncloakedMaterial>();
+    }
+}
+
 fn create_materials(
     mut commands: Commands,
     mut material_registry: ResMut<Registry<MaterialDefinition>>,
@@ -99,6 +188,7 @@ fn create_materials(
             let mut unlit_default_material = vec![];
             let mut transparent_material = vec![];
             let mut unlit_transparent_material = vec![];
+            let mut cloaked_material = vec![];
 
             for dimension_atlas in atlas.texture_atlases() {
                 default_material.push(materials.add(create_main_material(dimension_atlas.get_atlas_handle().clone(), false)));
@@ -106,12 +196,14 @@ fn create_materials(
                 transparent_material.push(materials.add(create_transparent_material(dimension_atlas.get_atlas_handle().clone(), false)));
                 unlit_transparent_material
                     .push(materials.add(create_transparent_material(dimension_atlas.get_atlas_handle().clone(), true)));
+                cloaked_material.push(materials.add(create_cloaked_material(dimension_atlas.get_atlas_handle().clone())));
             }
 
             commands.insert_resource(DefaultMaterial(default_material));
             commands.insert_resource(UnlitMaterial(unlit_default_material));
             commands.insert_resource(TransparentMaterial(transparent_material));
             commands.insert_resource(UnlitTransparentMaterial(unlit_transparent_material));
+            commands.insert_resource(CloakedMaterial(cloaked_material));
 
             material_registry.register(MaterialDefinition::new("cosmos:main", None));
             material_registry.register(MaterialDefinition::new("cosmos:illuminated", None));
@@ -128,6 +220,9 @@ pub(super) fn register(app: &mut App) {
         (
             respond_to_remove_materails_event.in_set(MaterialsSystemSet::ProcessRemoveMaterialsEvents),
             respond_to_add_materials_event.in_set(MaterialsSystemSet::ProcessAddMaterialsEvents),
+            apply_cloaked_materials
+                .after(MaterialsSystemSet::ProcessAddMaterialsEvents)
+                .run_if(in_state(GameState::Playing)),
         ),
     )
     .add_systems(
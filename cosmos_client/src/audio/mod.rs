@@ -15,7 +15,7 @@ use bevy::{
         query::{Changed, With},
         removal_detection::RemovedComponents,
         schedule::{IntoSystemConfigs, IntoSystemSetConfigs},
-        system::{Commands, Query, ResMut, Resource},
+        system::{Commands, Query, Res, ResMut, Resource},
     },
     hierarchy::DespawnRecursiveExt,
     prelude::{Deref, DerefMut, SystemSet, Transform},
@@ -23,8 +23,14 @@ use bevy::{
     utils::hashbrown::HashMap,
 };
 use bevy_kira_audio::{prelude::*, AudioSystemSet};
+use cosmos_core::registry::Registry;
+
+use crate::settings::{Setting, SettingsRegistry, SettingsSet};
+
+use self::sound_effects::SoundCategory;
 
 pub mod music;
+pub mod sound_effects;
 
 /// Contains information for a specific audio emission.
 ///
@@ -42,6 +48,8 @@ pub struct AudioEmission {
     pub stop_tween: AudioTween,
     /// A weak-cloned handle that is being played. This is to prevent too many of the same audio source blowing people's ears out
     pub handle: Handle<AudioSource>,
+    /// The category this sound belongs to, which determines which volume setting applies to it
+    pub category: SoundCategory,
 }
 
 impl Default for AudioEmission {
@@ -52,6 +60,7 @@ impl Default for AudioEmission {
             instance: Default::default(),
             handle: Default::default(),
             stop_tween: Default::default(),
+            category: Default::default(),
         }
     }
 }
@@ -120,6 +129,7 @@ fn run_spacial_audio(
     receiver: Query<&GlobalTransform, With<AudioReceiver>>,
     emitters: Query<(&GlobalTransform, &CosmosAudioEmitter)>,
     mut audio_instances: ResMut<Assets<AudioInstance>>,
+    sfx_volume: Res<SoundEffectsVolume>,
 ) {
     let Ok(receiver_transform) = receiver.get_single() else {
         return;
@@ -143,7 +153,9 @@ fn run_spacial_audio(
                 continue;
             };
 
-            let volume = emission.peak_volume * (1.0 - sound_path.length() / emission.max_distance).clamp(0., 1.).powi(2) as f64;
+            let volume = sfx_volume.category_percent(emission.category)
+                * emission.peak_volume
+                * (1.0 - sound_path.length() / emission.max_distance).clamp(0., 1.).powi(2) as f64;
 
             instance.set_volume(volume, AudioTween::default());
             instance.set_panning(panning, AudioTween::default());
@@ -170,6 +182,54 @@ fn run_spacial_audio(
     }
 }
 
+#[derive(Resource, Debug, Clone)]
+/// The volume multiplier applied to every spacial sound effect - [0.0, 1.0].
+///
+/// This already factors in the master volume.
+pub struct SoundEffectsVolume {
+    volume: f64,
+    category_volumes: HashMap<SoundCategory, f64>,
+}
+
+impl Default for SoundEffectsVolume {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            category_volumes: Default::default(),
+        }
+    }
+}
+
+impl SoundEffectsVolume {
+    /// Returns the volume as a decimal percent [0.0, 1.0]
+    pub fn percent(&self) -> f64 {
+        self.volume
+    }
+
+    /// Returns the volume sounds of this category should be played at as a decimal percent [0.0, 1.0]
+    pub fn category_percent(&self, category: SoundCategory) -> f64 {
+        self.volume * self.category_volumes.get(&category).copied().unwrap_or(1.0)
+    }
+}
+
+fn load_sound_effects_volume(settings: Res<Registry<Setting>>, mut sfx_volume: ResMut<SoundEffectsVolume>) {
+    let master = settings.i32_or("cosmos:master_volume", 100) as f64 / 100.0;
+    sfx_volume.volume = master * settings.i32_or("cosmos:sound_effects_volume", 100) as f64 / 100.0;
+
+    for category in [
+        SoundCategory::Blocks,
+        SoundCategory::Weapons,
+        SoundCategory::Ships,
+        SoundCategory::Explosions,
+    ] {
+        if let Some(setting) = category.volume_setting() {
+            sfx_volume
+                .category_volumes
+                .insert(category, settings.i32_or(setting, 100) as f64 / 100.0);
+        }
+    }
+}
+
 type AttachedAudioSourcesType = Vec<(Handle<AudioInstance>, AudioTween)>;
 
 #[derive(Default, Resource)]
@@ -272,6 +332,7 @@ pub enum AudioSet {
 
 pub(super) fn register(app: &mut App) {
     music::register(app);
+    sound_effects::register(app);
 
     app.configure_sets(Update, (AudioSet::CreateSounds, AudioSet::ProcessSounds).chain());
 
@@ -283,6 +344,8 @@ pub(super) fn register(app: &mut App) {
                 .chain(),
         )
         .add_systems(PostUpdate, (stop_audio_sources, run_spacial_audio).chain())
+        .add_systems(Update, load_sound_effects_volume.in_set(SettingsSet::LoadSettings))
         .init_resource::<AttachedAudioSources>()
-        .init_resource::<BufferedStopAudio>();
+        .init_resource::<BufferedStopAudio>()
+        .init_resource::<SoundEffectsVolume>();
 }
@@ -1,35 +1,72 @@
 //! Controls the playing of music as the player explores the universe
+//!
+//! The music that is played depends on what the player is doing (see [`DesiredMusicAtmosphere`]).
+//! When that changes while a song is playing, the current song is crossfaded into one that fits the
+//! new atmosphere.
+//!
+//! Songs are defined in `assets/<namespace>/sounds/music/music.json` manifests, so mods can add their own
+//! music by adding a manifest in their own namespace folder.
 
 use std::{fs, time::Duration};
 
 use bevy::prelude::*;
 use bevy_inspector_egui::prelude::*;
-use bevy_kira_audio::{Audio, AudioControl, AudioEasing, AudioSource, AudioTween};
-use cosmos_core::{state::GameState, utils::random::random_range};
+use bevy_kira_audio::{Audio, AudioControl, AudioEasing, AudioInstance, AudioSource, AudioTween};
+use cosmos_core::{
+    netty::client::LocalPlayer,
+    physics::location::Location,
+    projectiles::missile::Explosion,
+    state::GameState,
+    structure::{block_health::events::BlockTakeDamageEvent, ship::pilot::Pilot, station::Station},
+    utils::random::random_range,
+};
 use rand::seq::IteratorRandom;
 use serde::{Deserialize, Serialize};
 
+use crate::structure::systems::{
+    laser_cannon_system::LaserCannonSystemFiredEvent, missile_launcher_system::MissileLauncherSystemFiredEvent,
+};
+
 use super::{PlayMusicEvent, PlayingBackgroundSong, VolumeSetting};
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Reflect)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Reflect, Default)]
 /// Describes the "Atmosphere"/mood the music should be played in.
 pub enum MusicAtmosphere {
+    #[default]
     /// A calm environment, such as peacefully flying around or walking on a planet
     Calm,
+    /// Being aboard a station
+    Station,
     /// Intense action, such as being in combat
     Intense,
 }
 
+impl MusicAtmosphere {
+    /// The atmosphere to play songs from if there are no songs for this one
+    pub fn fallback(&self) -> Option<Self> {
+        match self {
+            Self::Station => Some(Self::Calm),
+            Self::Calm | Self::Intense => None,
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Reflect)]
 /// A song that plays in the background.
 ///
-/// These are automatically generated by looking in the `assets/cosmos/sounds/music` directory.
-/// Make sure each `.ogg` file has a `.json` file with the same name that specifies its metadata.
+/// These are automatically generated from the `music.json` manifest in each namespace's `sounds/music` directory.
 pub struct BackgroundSong {
     atmosphere: MusicAtmosphere,
     handle: Handle<AudioSource>,
 }
 
+impl BackgroundSong {
+    /// The atmosphere this song fits
+    pub fn atmosphere(&self) -> MusicAtmosphere {
+        self.atmosphere
+    }
+}
+
 #[derive(Default, Reflect, Resource, InspectorOptions, Debug)]
 #[reflect(Resource, InspectorOptions)]
 /// Contains the music the game can play
@@ -50,60 +87,195 @@ impl MusicController {
             .filter(|x| x.atmosphere == atmosphere)
             .choose(&mut rand::thread_rng())
     }
+
+    /// Selects a random song that matches this atmosphere, or its [`MusicAtmosphere::fallback`] if there are none.
+    pub fn random_song_or_fallback(&self, atmosphere: MusicAtmosphere) -> Option<&BackgroundSong> {
+        self.random_song(atmosphere)
+            .or_else(|| atmosphere.fallback().and_then(|fallback| self.random_song(fallback)))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct MusicDefinition {
+struct TrackDefinition {
+    /// Relative to the manifest's directory
+    file: String,
     atmosphere: MusicAtmosphere,
 }
 
-fn load_default_songs(asset_server: Res<AssetServer>, mut music_controller: ResMut<MusicController>) {
-    let Ok(entries) = fs::read_dir("assets/cosmos/sounds/music/") else {
-        error!("Missing music directory - unable to load background music!");
+#[derive(Debug, Serialize, Deserialize)]
+struct MusicManifest {
+    tracks: Vec<TrackDefinition>,
+}
+
+const MUSIC_MANIFEST: &str = "sounds/music/music.json";
+
+fn load_songs(asset_server: Res<AssetServer>, mut music_controller: ResMut<MusicController>) {
+    let Ok(namespaces) = fs::read_dir("assets/") else {
+        error!("Missing assets directory - unable to load background music!");
         return;
     };
 
-    for entry in entries {
-        match entry {
-            Ok(entry) => {
-                let path = entry.path();
-                let Some(extension) = path.extension() else {
-                    continue;
-                };
-                if !extension.eq_ignore_ascii_case("ogg") {
-                    continue;
-                }
-
-                let def_path = path.with_extension("json");
-                let Ok(info_file) = fs::read_to_string(&def_path) else {
-                    error!("Missing music info file for {path:?}");
-                    continue;
-                };
-
-                let music_def = match serde_json::from_str::<MusicDefinition>(&info_file) {
-                    Ok(music_def) => music_def,
-                    Err(e) => {
-                        error!("Invalid music definition file for {:?}\n{e:?}", def_path);
-                        continue;
-                    }
-                };
-
-                let song = BackgroundSong {
-                    atmosphere: music_def.atmosphere,
-                    handle: asset_server.load(format!("cosmos/sounds/music/{}", path.file_name().expect("How?").to_str().unwrap())),
-                };
-
-                info!("Adding song {song:?}");
-
-                music_controller.add_song(song);
-            }
+    for namespace in namespaces.flatten() {
+        let Ok(namespace) = namespace.file_name().into_string() else {
+            continue;
+        };
+
+        let manifest_path = format!("assets/{namespace}/{MUSIC_MANIFEST}");
+        let Ok(manifest) = fs::read_to_string(&manifest_path) else {
+            continue;
+        };
+
+        let manifest = match serde_json::from_str::<MusicManifest>(&manifest) {
+            Ok(manifest) => manifest,
             Err(e) => {
-                error!("{e:?}");
+                error!("Invalid music manifest {manifest_path}\n{e:?}");
+                continue;
             }
+        };
+
+        for track in manifest.tracks {
+            let song = BackgroundSong {
+                atmosphere: track.atmosphere,
+                handle: asset_server.load(format!("{namespace}/sounds/music/{}", track.file)),
+            };
+
+            info!("Adding song {song:?}");
+
+            music_controller.add_song(song);
         }
     }
 }
 
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// The atmosphere that best fits what the player is currently doing
+pub struct DesiredMusicAtmosphere(pub MusicAtmosphere);
+
+#[derive(Resource, Debug, Default)]
+/// Seconds until the player is no longer considered to be in combat
+struct CombatCooldown(f32);
+
+/// How long after the last sign of combat the player is still considered in combat
+const COMBAT_DURATION_SEC: f32 = 30.0;
+/// Weapons fired or explosions within this distance of the player count as combat
+const HOSTILE_FIRE_DISTANCE: f32 = 300.0;
+/// How long it takes to fade from one song to another
+const CROSSFADE_DURATION: Duration = Duration::from_secs(3);
+
+fn detect_combat(
+    time: Res<Time>,
+    mut cooldown: ResMut<CombatCooldown>,
+    q_local_player: Query<(&Location, Option<&Parent>, Option<&Pilot>), With<LocalPlayer>>,
+    q_location: Query<&Location>,
+    q_explosions: Query<&Location, Added<Explosion>>,
+    mut evr_block_damage: EventReader<BlockTakeDamageEvent>,
+    mut evr_laser_fired: EventReader<LaserCannonSystemFiredEvent>,
+    mut evr_missile_fired: EventReader<MissileLauncherSystemFiredEvent>,
+) {
+    cooldown.0 = (cooldown.0 - time.delta_secs()).max(0.0);
+
+    let Ok((player_loc, parent, pilot)) = q_local_player.get_single() else {
+        evr_block_damage.clear();
+        evr_laser_fired.clear();
+        evr_missile_fired.clear();
+        return;
+    };
+
+    let own_structure = pilot.map(|p| p.entity).or_else(|| parent.map(|p| p.get()));
+    let is_nearby = |loc: &Location| loc.distance_sqrd(player_loc) < HOSTILE_FIRE_DISTANCE * HOSTILE_FIRE_DISTANCE;
+
+    let own_structure_damaged = evr_block_damage
+        .read()
+        .any(|ev| Some(ev.structure_entity) == own_structure && ev.causer != own_structure);
+
+    let hostile_fire = evr_laser_fired
+        .read()
+        .map(|ev| ev.0)
+        .chain(evr_missile_fired.read().map(|ev| ev.0))
+        .filter(|&shooter| Some(shooter) != own_structure)
+        .any(|shooter| q_location.get(shooter).is_ok_and(is_nearby));
+
+    let nearby_explosion = q_explosions.iter().any(is_nearby);
+
+    if own_structure_damaged || hostile_fire || nearby_explosion {
+        cooldown.0 = COMBAT_DURATION_SEC;
+    }
+}
+
+fn update_desired_atmosphere(
+    cooldown: Res<CombatCooldown>,
+    q_local_player: Query<Option<&Parent>, With<LocalPlayer>>,
+    q_station: Query<(), With<Station>>,
+    mut desired: ResMut<DesiredMusicAtmosphere>,
+) {
+    let atmosphere = if cooldown.0 > 0.0 {
+        MusicAtmosphere::Intense
+    } else if q_local_player
+        .get_single()
+        .ok()
+        .flatten()
+        .is_some_and(|parent| q_station.contains(parent.get()))
+    {
+        MusicAtmosphere::Station
+    } else {
+        MusicAtmosphere::Calm
+    };
+
+    desired.set_if_neq(DesiredMusicAtmosphere(atmosphere));
+}
+
+fn play_song(commands: &mut Commands, audio: &Audio, volume: &VolumeSetting, song: &BackgroundSong) {
+    let handle = audio
+        .play(song.handle.clone())
+        .with_volume(volume.percent())
+        .fade_in(AudioTween::new(CROSSFADE_DURATION, AudioEasing::InOutPowi(2)))
+        .handle();
+
+    commands.insert_resource(PlayingBackgroundSong {
+        instance: handle,
+        atmosphere: song.atmosphere,
+    });
+}
+
+/// Combat music should start right away, rather than waiting for the next song to be scheduled.
+fn start_combat_music(desired: Res<DesiredMusicAtmosphere>, mut evw_play_music: EventWriter<PlayMusicEvent>) {
+    if desired.0 == MusicAtmosphere::Intense {
+        evw_play_music.send(PlayMusicEvent {
+            atmosphere: MusicAtmosphere::Intense,
+        });
+    }
+}
+
+fn crossfade_to_desired_atmosphere(
+    mut commands: Commands,
+    desired: Res<DesiredMusicAtmosphere>,
+    playing: Res<PlayingBackgroundSong>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    audio: Res<Audio>,
+    volume: Res<VolumeSetting>,
+    jukebox: Res<MusicController>,
+) {
+    let fits = playing.atmosphere == desired.0 || desired.0.fallback() == Some(playing.atmosphere);
+    if fits {
+        return;
+    }
+
+    let next_song = jukebox.random_song_or_fallback(desired.0);
+
+    // If there's nothing to swap to, only cut the song off if it's combat music that no longer makes sense.
+    if next_song.is_none() && playing.atmosphere != MusicAtmosphere::Intense {
+        return;
+    }
+
+    if let Some(instance) = audio_instances.get_mut(&playing.instance) {
+        instance.stop(AudioTween::new(CROSSFADE_DURATION, AudioEasing::InOutPowi(2)));
+    }
+
+    match next_song {
+        Some(song) => play_song(&mut commands, &audio, &volume, song),
+        None => commands.remove_resource::<PlayingBackgroundSong>(),
+    }
+}
+
 fn start_playing(
     mut commands: Commands,
     mut evr_play_music: EventReader<PlayMusicEvent>,
@@ -115,18 +287,14 @@ fn start_playing(
         return;
     };
 
-    let Some(song) = jukebox.random_song(ev.atmosphere) else {
-        warn!("Missing song for atmosphere: {:?}", ev.atmosphere);
+    let Some(song) = jukebox.random_song_or_fallback(ev.atmosphere) else {
+        if ev.atmosphere != MusicAtmosphere::Intense {
+            warn!("Missing song for atmosphere: {:?}", ev.atmosphere);
+        }
         return;
     };
 
-    let handle = audio
-        .play(song.handle.clone())
-        .with_volume(volume.percent())
-        .fade_in(AudioTween::new(Duration::from_secs(2), AudioEasing::InOutPowi(2)))
-        .handle();
-
-    commands.insert_resource(PlayingBackgroundSong(handle));
+    play_song(&mut commands, &audio, &volume, song);
 }
 
 #[derive(Reflect, Resource, InspectorOptions, Debug)]
@@ -135,30 +303,47 @@ struct NextSongTime(f32);
 const MIN_DELAY_SEC: f32 = 5.0 * 60.0; // 5min
 const MAX_DELAY_SEC: f32 = 20.0 * 60.0; // 20min
 
-fn trigger_music_playing(mut next_song_time: ResMut<NextSongTime>, mut event_writer: EventWriter<PlayMusicEvent>, time: Res<Time>) {
+fn trigger_music_playing(
+    mut next_song_time: ResMut<NextSongTime>,
+    mut event_writer: EventWriter<PlayMusicEvent>,
+    time: Res<Time>,
+    desired: Res<DesiredMusicAtmosphere>,
+) {
     if next_song_time.0 > time.elapsed_secs() {
         return;
     }
 
     next_song_time.0 = time.elapsed_secs() + random_range(MIN_DELAY_SEC, MAX_DELAY_SEC);
 
-    event_writer.send(PlayMusicEvent {
-        atmosphere: MusicAtmosphere::Calm,
-    });
+    event_writer.send(PlayMusicEvent { atmosphere: desired.0 });
 }
 
 pub(super) fn register(app: &mut App) {
     let initial_delay = random_range(MIN_DELAY_SEC, MAX_DELAY_SEC);
-    app.init_resource::<MusicController>().insert_resource(NextSongTime(initial_delay));
+    app.init_resource::<MusicController>()
+        .init_resource::<DesiredMusicAtmosphere>()
+        .init_resource::<CombatCooldown>()
+        .insert_resource(NextSongTime(initial_delay));
 
-    app.add_systems(OnEnter(GameState::Loading), load_default_songs);
+    app.add_systems(OnEnter(GameState::Loading), load_songs);
 
     app.add_systems(
         Update,
-        (trigger_music_playing, start_playing.run_if(on_event::<PlayMusicEvent>))
+        (
+            (detect_combat, update_desired_atmosphere).chain(),
+            crossfade_to_desired_atmosphere
+                .run_if(resource_changed::<DesiredMusicAtmosphere>)
+                .run_if(resource_exists::<PlayingBackgroundSong>),
+            (
+                start_combat_music.run_if(resource_changed::<DesiredMusicAtmosphere>),
+                trigger_music_playing,
+                start_playing.run_if(on_event::<PlayMusicEvent>),
+            )
+                .chain()
+                .run_if(not(resource_exists::<PlayingBackgroundSong>)),
+        )
             .chain()
-            .run_if(in_state(GameState::Playing))
-            .run_if(not(resource_exists::<PlayingBackgroundSong>)),
+            .run_if(in_state(GameState::Playing)),
     )
     .register_type::<MusicController>()
     .register_type::<NextSongTime>();
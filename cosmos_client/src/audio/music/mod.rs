@@ -11,7 +11,10 @@ use crate::settings::{Setting, SettingsRegistry, SettingsSet};
 pub mod dynamic_music;
 
 #[derive(Resource)]
-struct PlayingBackgroundSong(Handle<AudioInstance>);
+struct PlayingBackgroundSong {
+    instance: Handle<AudioInstance>,
+    atmosphere: MusicAtmosphere,
+}
 
 #[derive(Reflect, Resource, InspectorOptions)]
 #[reflect(Resource, InspectorOptions)]
@@ -37,7 +40,7 @@ fn monitor_background_song(
     mut audio_instances: ResMut<Assets<AudioInstance>>,
     mut commands: Commands,
 ) {
-    if let Some(instance) = audio_instances.get_mut(&bg_song.0) {
+    if let Some(instance) = audio_instances.get_mut(&bg_song.instance) {
         if instance.state() == PlaybackState::Stopped {
             commands.remove_resource::<PlayingBackgroundSong>();
         }
@@ -49,7 +52,7 @@ fn adjust_volume(
     volume: Res<VolumeSetting>,
     background_song: Res<PlayingBackgroundSong>,
 ) {
-    let Some(instance) = audio_instances.get_mut(&background_song.0) else {
+    let Some(instance) = audio_instances.get_mut(&background_song.instance) else {
         return;
     };
 
@@ -64,7 +67,8 @@ pub struct PlayMusicEvent {
 }
 
 fn load_volume(settings: Res<Registry<Setting>>, mut music_volume: ResMut<VolumeSetting>) {
-    music_volume.0 = settings.i32_or("cosmos:music_volume", 100) as f64 / 100.0;
+    let master = settings.i32_or("cosmos:master_volume", 100) as f64 / 100.0;
+    music_volume.0 = master * settings.i32_or("cosmos:music_volume", 100) as f64 / 100.0;
 }
 
 pub(super) fn register(app: &mut App) {
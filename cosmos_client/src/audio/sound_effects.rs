@@ -0,0 +1,277 @@
+//! Sound effects that are played positionally in the world, such as block placing or weapons firing.
+//!
+//! Every sound effect is stored in the [`Registry<SoundEffect>`], so systems only need to know the
+//! unlocalized name of the sound they want to play. Use [`SoundEffect::play`] to create an
+//! [`AudioEmission`] and add it to a [`super::CosmosAudioEmitter`] positioned at the source.
+
+use bevy::{asset::LoadState, prelude::*};
+use bevy_kira_audio::prelude::*;
+use cosmos_core::{
+    registry::{self, identifiable::Identifiable, Registry},
+    state::GameState,
+};
+
+use crate::asset::asset_loader::load_assets;
+
+use super::AudioEmission;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+/// Every sound effect belongs to a category, which each have their own volume setting.
+pub enum SoundCategory {
+    #[default]
+    /// Only affected by the overall sound effects volume
+    General,
+    /// Blocks being placed, broken, or damaged
+    Blocks,
+    /// Weapons being fired
+    Weapons,
+    /// Ship sounds, such as thrusters
+    Ships,
+    /// Explosions
+    Explosions,
+}
+
+impl SoundCategory {
+    /// The setting that controls the volume of this category, if it has one
+    pub fn volume_setting(&self) -> Option<&'static str> {
+        match self {
+            Self::General => None,
+            Self::Blocks => Some("cosmos:block_sounds_volume"),
+            Self::Weapons => Some("cosmos:weapon_sounds_volume"),
+            Self::Ships => Some("cosmos:ship_sounds_volume"),
+            Self::Explosions => Some("cosmos:explosion_sounds_volume"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A sound that can be played in the world.
+///
+/// A sound effect can have multiple variations, and a random one will be chosen each time it's played.
+pub struct SoundEffect {
+    id: u16,
+    unlocalized_name: String,
+    sounds: Vec<Handle<AudioSource>>,
+    category: SoundCategory,
+    max_distance: f32,
+    peak_volume: f64,
+}
+
+impl SoundEffect {
+    /// Creates a sound effect that can be heard up to 100 blocks away
+    pub fn new(unlocalized_name: impl Into<String>, sounds: Vec<Handle<AudioSource>>, category: SoundCategory) -> Self {
+        Self {
+            id: 0,
+            unlocalized_name: unlocalized_name.into(),
+            sounds,
+            category,
+            max_distance: 100.0,
+            peak_volume: 1.0,
+        }
+    }
+
+    /// Sets the maximum distance this sound can be heard from
+    pub fn with_max_distance(mut self, max_distance: f32) -> Self {
+        self.max_distance = max_distance;
+        self
+    }
+
+    /// Sets the loudest this sound will be played at
+    pub fn with_peak_volume(mut self, peak_volume: f64) -> Self {
+        self.peak_volume = peak_volume;
+        self
+    }
+
+    /// The category this sound belongs to
+    pub fn category(&self) -> SoundCategory {
+        self.category
+    }
+
+    /// The maximum distance this sound can be heard from
+    pub fn max_distance(&self) -> f32 {
+        self.max_distance
+    }
+
+    /// Picks a random variation of this sound (weakly cloned).
+    ///
+    /// Returns `None` if this sound has no variations that loaded.
+    pub fn random_sound(&self) -> Option<Handle<AudioSource>> {
+        if self.sounds.is_empty() {
+            return None;
+        }
+
+        Some(self.sounds[rand::random::<usize>() % self.sounds.len()].clone_weak())
+    }
+
+    /// Starts playing a random variation of this sound, returning the emission to add to a [`super::CosmosAudioEmitter`].
+    ///
+    /// The sound starts muted, and its volume will be set based on its distance from the player.
+    pub fn play(&self, audio: &Audio) -> Option<AudioEmission> {
+        let handle = self.random_sound()?;
+        let instance = audio.play(handle.clone_weak()).with_volume(0.0).handle();
+
+        Some(self.create_emission(handle, instance))
+    }
+
+    /// Same as [`Self::play`], but the sound will loop until it is removed from its emitter.
+    pub fn play_looped(&self, audio: &Audio, stop_tween: AudioTween) -> Option<AudioEmission> {
+        let handle = self.random_sound()?;
+        let instance = audio.play(handle.clone_weak()).with_volume(0.0).looped().handle();
+
+        Some(AudioEmission {
+            stop_tween,
+            ..self.create_emission(handle, instance)
+        })
+    }
+
+    fn create_emission(&self, handle: Handle<AudioSource>, instance: Handle<AudioInstance>) -> AudioEmission {
+        AudioEmission {
+            instance,
+            handle,
+            max_distance: self.max_distance,
+            peak_volume: self.peak_volume,
+            category: self.category,
+            ..Default::default()
+        }
+    }
+}
+
+impl Identifiable for SoundEffect {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+}
+
+struct SoundEffectDefinition {
+    unlocalized_name: &'static str,
+    paths: &'static [&'static str],
+    category: SoundCategory,
+    max_distance: f32,
+    peak_volume: f64,
+}
+
+const SOUND_EFFECTS: &[SoundEffectDefinition] = &[
+    SoundEffectDefinition {
+        unlocalized_name: "cosmos:block_break",
+        paths: &["cosmos/sounds/sfx/break.ogg"],
+        category: SoundCategory::Blocks,
+        max_distance: 16.0,
+        peak_volume: 1.0,
+    },
+    SoundEffectDefinition {
+        unlocalized_name: "cosmos:block_place",
+        paths: &["cosmos/sounds/sfx/place.ogg"],
+        category: SoundCategory::Blocks,
+        max_distance: 16.0,
+        peak_volume: 1.0,
+    },
+    SoundEffectDefinition {
+        unlocalized_name: "cosmos:block_damage",
+        paths: &["cosmos/sounds/sfx/thud.ogg"],
+        category: SoundCategory::Blocks,
+        max_distance: 100.0,
+        peak_volume: 1.0,
+    },
+    SoundEffectDefinition {
+        unlocalized_name: "cosmos:laser_fire",
+        paths: &[
+            "cosmos/sounds/sfx/laser-fire-1.ogg",
+            "cosmos/sounds/sfx/laser-fire-2.ogg",
+            "cosmos/sounds/sfx/laser-fire-3.ogg",
+        ],
+        category: SoundCategory::Weapons,
+        max_distance: 100.0,
+        peak_volume: 1.0,
+    },
+    SoundEffectDefinition {
+        unlocalized_name: "cosmos:missile_launch",
+        paths: &["cosmos/sounds/sfx/missile-launch-1.ogg", "cosmos/sounds/sfx/missile-launch-2.ogg"],
+        category: SoundCategory::Weapons,
+        max_distance: 100.0,
+        peak_volume: 1.0,
+    },
+    SoundEffectDefinition {
+        unlocalized_name: "cosmos:thruster_running",
+        paths: &["cosmos/sounds/sfx/thruster-running.ogg"],
+        category: SoundCategory::Ships,
+        max_distance: 100.0,
+        peak_volume: 1.5,
+    },
+    SoundEffectDefinition {
+        unlocalized_name: "cosmos:engine_idle",
+        paths: &["cosmos/sounds/sfx/engine-idle.ogg"],
+        category: SoundCategory::Ships,
+        max_distance: 20.0,
+        peak_volume: 0.75,
+    },
+    SoundEffectDefinition {
+        unlocalized_name: "cosmos:explosion",
+        paths: &[
+            "cosmos/sounds/sfx/explosion-1.ogg",
+            "cosmos/sounds/sfx/explosion-2.ogg",
+            "cosmos/sounds/sfx/explosion-3.ogg",
+            "cosmos/sounds/sfx/explosion-4.ogg",
+        ],
+        category: SoundCategory::Explosions,
+        max_distance: 200.0,
+        peak_volume: 1.0,
+    },
+    SoundEffectDefinition {
+        unlocalized_name: "cosmos:ping",
+        paths: &["cosmos/sounds/sfx/beep-beep.ogg"],
+        category: SoundCategory::General,
+        max_distance: 16.0,
+        peak_volume: 0.75,
+    },
+];
+
+struct LoadingSoundEffects;
+
+pub(super) fn register(app: &mut App) {
+    registry::create_registry::<SoundEffect>(app, "cosmos:sound_effects");
+
+    load_assets::<AudioSource, LoadingSoundEffects>(
+        app,
+        GameState::PreLoading,
+        SOUND_EFFECTS.iter().flat_map(|def| def.paths.iter().copied()).collect(),
+        |mut commands, handles| {
+            let mut handles = handles.into_iter();
+
+            let sound_effects = SOUND_EFFECTS
+                .iter()
+                .map(|def| {
+                    let sounds = handles
+                        .by_ref()
+                        .take(def.paths.len())
+                        .filter(|(_, state)| matches!(state, LoadState::Loaded))
+                        .map(|(handle, _)| handle)
+                        .collect::<Vec<_>>();
+
+                    if sounds.is_empty() {
+                        warn!("No sounds loaded for sound effect {}", def.unlocalized_name);
+                    }
+
+                    SoundEffect::new(def.unlocalized_name, sounds, def.category)
+                        .with_max_distance(def.max_distance)
+                        .with_peak_volume(def.peak_volume)
+                })
+                .collect::<Vec<_>>();
+
+            commands.queue(move |world: &mut World| {
+                let mut registry = world.resource_mut::<Registry<SoundEffect>>();
+
+                for sound_effect in sound_effects {
+                    registry.register(sound_effect);
+                }
+            });
+        },
+    );
+}
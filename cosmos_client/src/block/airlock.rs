@@ -0,0 +1,404 @@
+//! The menu used to link an airlock controller to its doors & cycle it
+
+use bevy::{color::palettes::css, prelude::*, utils::HashSet};
+use cosmos_core::{
+    block::{
+        specific_blocks::{
+            airlock::{Airlock, AirlockSide, AirlockState, CycleAirlockEvent, OpenAirlockEvent, SetAirlockDoorEvent, AIRLOCK_DOOR_RANGE},
+            door::{connected_door_blocks, door_open_at},
+        },
+        Block,
+    },
+    ecs::NeedsDespawned,
+    netty::{
+        sync::{
+            events::client_event::{NettyEventReceived, NettyEventWriter},
+            mapping::{Mappable, NetworkMapping},
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::{BlockCoordinate, Structure, StructureBlock},
+    registry::Registry,
+    state::GameState,
+};
+
+use crate::{
+    lang::Localization,
+    rendering::MainCamera,
+    ui::{
+        components::{
+            button::{register_button, Button, ButtonEvent, ButtonStyles},
+            window::GuiWindow,
+        },
+        font::DefaultFont,
+        OpenMenu, UiSystemSet,
+    },
+};
+
+#[derive(Component, Debug)]
+struct OpenAirlock(StructureBlock);
+
+#[derive(Component, Debug)]
+struct AirlockContents;
+
+#[derive(Component, Debug)]
+/// What clicking this button will link to a side of the airlock. A door of `None` unlinks that side.
+struct DoorChoice {
+    side: AirlockSide,
+    door: Option<BlockCoordinate>,
+}
+
+#[derive(Event, Debug)]
+struct DoorChoiceClicked(Entity);
+
+impl ButtonEvent for DoorChoiceClicked {
+    fn create_event(entity: Entity) -> Self {
+        Self(entity)
+    }
+}
+
+#[derive(Event, Debug)]
+struct CycleClicked;
+
+impl ButtonEvent for CycleClicked {
+    fn create_event(_: Entity) -> Self {
+        Self
+    }
+}
+
+fn button_styles() -> ButtonStyles {
+    ButtonStyles {
+        background_color: Srgba::hex("555555").unwrap().into(),
+        hover_background_color: Srgba::hex("777777").unwrap().into(),
+        press_background_color: Srgba::hex("333333").unwrap().into(),
+        foreground_color: css::WHITE.into(),
+        hover_foreground_color: css::WHITE.into(),
+        press_foreground_color: css::WHITE.into(),
+    }
+}
+
+fn describe_state(state: AirlockState) -> &'static str {
+    match state {
+        AirlockState::Sealed => "Sealed",
+        AirlockState::Open(AirlockSide::Inner) => "Inner door open",
+        AirlockState::Open(AirlockSide::Outer) => "Outer door open",
+        AirlockState::Cycling { .. } => "Cycling...",
+    }
+}
+
+/// Describes where a door is relative to the controller
+fn describe_door(controller: BlockCoordinate, door: BlockCoordinate) -> String {
+    let offset = door - controller;
+
+    format!("Door at ({}, {}, {})", offset.x, offset.y, offset.z)
+}
+
+/// Finds one block of each door close enough to be linked to this controller
+fn doors_in_range(structure: &Structure, controller: BlockCoordinate, blocks: &Registry<Block>) -> Vec<BlockCoordinate> {
+    let dims = structure.block_dimensions();
+
+    let range =
+        |center: u64, max: u64| center.saturating_sub(AIRLOCK_DOOR_RANGE)..=(center + AIRLOCK_DOOR_RANGE).min(max.saturating_sub(1));
+
+    let mut seen = HashSet::new();
+    let mut doors = vec![];
+
+    for z in range(controller.z, dims.z) {
+        for y in range(controller.y, dims.y) {
+            for x in range(controller.x, dims.x) {
+                let coords = BlockCoordinate::new(x, y, z);
+
+                if seen.contains(&coords) || door_open_at(structure, coords, blocks).is_none() {
+                    continue;
+                }
+
+                seen.extend(connected_door_blocks(structure, coords, blocks));
+                doors.push(coords);
+            }
+        }
+    }
+
+    doors
+}
+
+fn open_airlock(
+    mut commands: Commands,
+    q_open: Query<Entity, With<OpenAirlock>>,
+    mut nevr_open: EventReader<NettyEventReceived<OpenAirlockEvent>>,
+    network_mapping: Res<NetworkMapping>,
+) {
+    let Some(ev) = nevr_open.read().last() else {
+        return;
+    };
+
+    if let Ok(ent) = q_open.get_single() {
+        commands.entity(ent).insert(NeedsDespawned);
+    }
+
+    let Ok(block) = ev.0.map(&network_mapping) else {
+        error!("Bad network mapping - {:?}", ev.0);
+        return;
+    };
+
+    commands.spawn((OpenAirlock(block), Name::new("Open Airlock")));
+}
+
+fn create_airlock_window(
+    mut commands: Commands,
+    q_added: Query<Entity, Added<OpenAirlock>>,
+    q_cam: Query<Entity, With<MainCamera>>,
+    localization: Res<Localization>,
+) {
+    for ent in q_added.iter() {
+        let Ok(cam) = q_cam.get_single() else {
+            return;
+        };
+
+        commands
+            .entity(ent)
+            .insert((
+                TargetCamera(cam),
+                OpenMenu::new(0),
+                BackgroundColor(Srgba::hex("2D2D2D").unwrap().into()),
+                Node {
+                    width: Val::Px(450.0),
+                    margin: UiRect::all(Val::Auto),
+                    ..Default::default()
+                },
+                GuiWindow {
+                    title: localization.get("cosmos:window.airlock").into(),
+                    body_styles: Node {
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(20.0)),
+                        ..Default::default()
+                    },
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    Name::new("Airlock contents"),
+                    AirlockContents,
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(8.0),
+                        ..Default::default()
+                    },
+                ));
+            });
+    }
+}
+
+fn populate_airlock_window(
+    mut commands: Commands,
+    q_menu: Query<&OpenAirlock>,
+    q_contents: Query<Entity, With<AirlockContents>>,
+    q_added_contents: Query<(), Added<AirlockContents>>,
+    q_changed_airlocks: Query<(), Changed<Airlock>>,
+    q_structure: Query<&Structure>,
+    q_airlock: Query<&Airlock>,
+    blocks: Res<Registry<Block>>,
+    font: Res<DefaultFont>,
+) {
+    let (Ok(menu), Ok(contents_ent)) = (q_menu.get_single(), q_contents.get_single()) else {
+        return;
+    };
+
+    if q_added_contents.is_empty() && q_changed_airlocks.is_empty() {
+        return;
+    }
+
+    let Ok(structure) = q_structure.get(menu.0.structure()) else {
+        return;
+    };
+
+    let controller = menu.0.coords();
+    let airlock = structure.query_block_data(controller, &q_airlock).copied().unwrap_or_default();
+
+    let text_style = TextFont {
+        font: font.0.clone_weak(),
+        font_size: 20.0,
+        ..Default::default()
+    };
+
+    let row_node = Node {
+        flex_direction: FlexDirection::Row,
+        justify_content: JustifyContent::SpaceBetween,
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(8.0),
+        ..Default::default()
+    };
+
+    let button_node = Node {
+        width: Val::Px(90.0),
+        height: Val::Px(36.0),
+        ..Default::default()
+    };
+
+    let linked_doors = [AirlockSide::Inner, AirlockSide::Outer]
+        .into_iter()
+        .filter_map(|side| airlock.door(side))
+        .flat_map(|door| connected_door_blocks(structure, door, &blocks))
+        .collect::<HashSet<_>>();
+
+    let unlinked_doors = doors_in_range(structure, controller, &blocks)
+        .into_iter()
+        .filter(|door| !linked_doors.contains(door))
+        .collect::<Vec<_>>();
+
+    let mut ecmds = commands.entity(contents_ent);
+    ecmds.despawn_descendants();
+
+    ecmds.with_children(|p| {
+        p.spawn((Name::new("Airlock status"), row_node.clone())).with_children(|p| {
+            p.spawn((Text::new(describe_state(airlock.state)), text_style.clone()));
+            p.spawn((
+                Name::new("Cycle button"),
+                button_node.clone(),
+                Button::<CycleClicked> {
+                    button_styles: Some(button_styles()),
+                    text: Some(("Cycle".into(), text_style.clone(), Default::default())),
+                    ..Default::default()
+                },
+            ));
+        });
+
+        for (side, label) in [(AirlockSide::Inner, "Inner Door"), (AirlockSide::Outer, "Outer Door")] {
+            p.spawn((
+                Text::new(label),
+                text_style.clone(),
+                Node {
+                    margin: UiRect::top(Val::Px(10.0)),
+                    ..Default::default()
+                },
+            ));
+
+            p.spawn((Name::new("Linked door"), row_node.clone()))
+                .with_children(|p| match airlock.door(side) {
+                    Some(door) => {
+                        p.spawn((Text::new(describe_door(controller, door)), text_style.clone()));
+                        p.spawn((
+                            Name::new("Unlink door button"),
+                            DoorChoice { side, door: None },
+                            button_node.clone(),
+                            Button::<DoorChoiceClicked> {
+                                button_styles: Some(button_styles()),
+                                text: Some(("Unlink".into(), text_style.clone(), Default::default())),
+                                ..Default::default()
+                            },
+                        ));
+                    }
+                    None => {
+                        p.spawn((Text::new("Nothing"), text_style.clone(), TextColor(css::GRAY.into())));
+                    }
+                });
+        }
+
+        p.spawn((
+            Text::new("Doors In Range"),
+            text_style.clone(),
+            Node {
+                margin: UiRect::top(Val::Px(10.0)),
+                ..Default::default()
+            },
+        ));
+
+        if unlinked_doors.is_empty() {
+            p.spawn((
+                Text::new("No other doors in range"),
+                text_style.clone(),
+                TextColor(css::GRAY.into()),
+            ));
+        }
+
+        for door in unlinked_doors {
+            p.spawn((Name::new("Door in range"), row_node.clone())).with_children(|p| {
+                p.spawn((
+                    Text::new(describe_door(controller, door)),
+                    text_style.clone(),
+                    Node {
+                        flex_grow: 1.0,
+                        ..Default::default()
+                    },
+                ));
+
+                for (side, label) in [(AirlockSide::Inner, "Inner"), (AirlockSide::Outer, "Outer")] {
+                    p.spawn((
+                        Name::new("Link door button"),
+                        DoorChoice { side, door: Some(door) },
+                        button_node.clone(),
+                        Button::<DoorChoiceClicked> {
+                            button_styles: Some(button_styles()),
+                            text: Some((label.into(), text_style.clone(), Default::default())),
+                            ..Default::default()
+                        },
+                    ));
+                }
+            });
+        }
+    });
+}
+
+fn on_door_choice_clicked(
+    mut evr_door_choice: EventReader<DoorChoiceClicked>,
+    q_choice: Query<&DoorChoice>,
+    q_menu: Query<&OpenAirlock>,
+    network_mapping: Res<NetworkMapping>,
+    mut nevw_set_door: NettyEventWriter<SetAirlockDoorEvent>,
+) {
+    for ev in evr_door_choice.read() {
+        let (Ok(choice), Ok(menu)) = (q_choice.get(ev.0), q_menu.get_single()) else {
+            continue;
+        };
+
+        let Ok(controller) = menu.0.map_to_server(&network_mapping) else {
+            continue;
+        };
+
+        nevw_set_door.send(SetAirlockDoorEvent {
+            controller,
+            side: choice.side,
+            door: choice.door,
+        });
+    }
+}
+
+fn on_cycle_clicked(
+    mut evr_cycle: EventReader<CycleClicked>,
+    q_menu: Query<&OpenAirlock>,
+    network_mapping: Res<NetworkMapping>,
+    mut nevw_cycle: NettyEventWriter<CycleAirlockEvent>,
+) {
+    if evr_cycle.read().next().is_none() {
+        return;
+    }
+
+    let Ok(menu) = q_menu.get_single() else {
+        return;
+    };
+
+    if let Ok(controller) = menu.0.map_to_server(&network_mapping) {
+        nevw_cycle.send(CycleAirlockEvent(controller));
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<DoorChoiceClicked>(app);
+    register_button::<CycleClicked>(app);
+
+    app.add_systems(
+        Update,
+        (
+            open_airlock.in_set(NetworkingSystemsSet::Between),
+            (
+                create_airlock_window,
+                populate_airlock_window,
+                on_door_choice_clicked,
+                on_cycle_clicked,
+            )
+                .chain()
+                .in_set(UiSystemSet::DoUi),
+        )
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,198 @@
+//! The menu used to change what a clock outputs
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    block::specific_blocks::clock::{ClockMode, ClockSettings, OpenClockSettingsEvent, SetClockSettingsEvent},
+    ecs::NeedsDespawned,
+    netty::{
+        sync::{
+            events::client_event::{NettyEventReceived, NettyEventWriter},
+            mapping::{Mappable, NetworkMapping},
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::{Structure, StructureBlock},
+    state::GameState,
+};
+
+use crate::{
+    lang::Localization,
+    rendering::MainCamera,
+    ui::{
+        components::{
+            button::{register_button, Button, ButtonEvent, ButtonStyles},
+            window::GuiWindow,
+        },
+        font::DefaultFont,
+        OpenMenu, UiSystemSet,
+    },
+};
+
+#[derive(Component, Debug)]
+struct OpenClockSettings(StructureBlock);
+
+#[derive(Component, Debug)]
+/// The mode a clock will be set to when this button is clicked
+struct ClockModeButton(ClockMode);
+
+#[derive(Event, Debug)]
+struct ClockModeClicked(Entity);
+
+impl ButtonEvent for ClockModeClicked {
+    fn create_event(btn_entity: Entity) -> Self {
+        Self(btn_entity)
+    }
+}
+
+fn open_clock_settings(
+    mut commands: Commands,
+    q_open_settings: Query<Entity, With<OpenClockSettings>>,
+    mut nevr_open_settings: EventReader<NettyEventReceived<OpenClockSettingsEvent>>,
+    network_mapping: Res<NetworkMapping>,
+) {
+    let Some(ev) = nevr_open_settings.read().last() else {
+        return;
+    };
+
+    if let Ok(ent) = q_open_settings.get_single() {
+        commands.entity(ent).insert(NeedsDespawned);
+    }
+
+    let Ok(block) = ev.0.map(&network_mapping) else {
+        error!("Bad network mapping - {:?}", ev.0);
+        return;
+    };
+
+    commands.spawn((OpenClockSettings(block), Name::new("Open Clock Settings")));
+}
+
+fn populate_clock_settings(
+    mut commands: Commands,
+    q_added_settings: Query<(Entity, &OpenClockSettings), Added<OpenClockSettings>>,
+    q_cam: Query<Entity, With<MainCamera>>,
+    q_structure: Query<&Structure>,
+    q_clock_settings: Query<&ClockSettings>,
+    font: Res<DefaultFont>,
+    localization: Res<Localization>,
+) {
+    for (ent, open_settings) in q_added_settings.iter() {
+        let Ok(cam) = q_cam.get_single() else {
+            return;
+        };
+
+        let current_mode = q_structure
+            .get(open_settings.0.structure())
+            .ok()
+            .and_then(|s| s.query_block_data(open_settings.0.coords(), &q_clock_settings))
+            .map(|settings| settings.mode)
+            .unwrap_or_default();
+
+        let text_style = TextFont {
+            font: font.0.clone_weak(),
+            font_size: 24.0,
+            ..Default::default()
+        };
+
+        let mut ecmds = commands.entity(ent);
+
+        ecmds.insert((
+            TargetCamera(cam),
+            OpenMenu::new(0),
+            BackgroundColor(Srgba::hex("2D2D2D").unwrap().into()),
+            Node {
+                width: Val::Px(400.0),
+                margin: UiRect::all(Val::Auto),
+                ..Default::default()
+            },
+            GuiWindow {
+                title: localization.get("cosmos:window.clock").into(),
+                body_styles: Node {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(20.0)),
+                    row_gap: Val::Px(10.0),
+                    ..Default::default()
+                },
+            },
+        ));
+
+        ecmds.with_children(|p| {
+            for mode in ClockMode::ALL {
+                // The current mode is highlighted so the player knows what the clock is set to
+                let background_color = if mode == current_mode {
+                    Srgba::hex("3A6E3A").unwrap().into()
+                } else {
+                    Srgba::hex("555555").unwrap().into()
+                };
+
+                p.spawn((
+                    Name::new("Clock Mode Button"),
+                    ClockModeButton(mode),
+                    Node {
+                        height: Val::Px(50.0),
+                        ..Default::default()
+                    },
+                    Button::<ClockModeClicked> {
+                        button_styles: Some(ButtonStyles {
+                            background_color,
+                            hover_background_color: Srgba::hex("777777").unwrap().into(),
+                            press_background_color: Srgba::hex("333333").unwrap().into(),
+                            foreground_color: css::WHITE.into(),
+                            hover_foreground_color: css::WHITE.into(),
+                            press_foreground_color: css::WHITE.into(),
+                        }),
+                        text: Some((
+                            localization.get(mode.unlocalized_name()).into(),
+                            text_style.clone(),
+                            Default::default(),
+                        )),
+                        ..Default::default()
+                    },
+                ));
+            }
+        });
+    }
+}
+
+fn on_clock_mode_clicked(
+    mut commands: Commands,
+    mut evr_clicked: EventReader<ClockModeClicked>,
+    q_mode_button: Query<&ClockModeButton>,
+    q_open_settings: Query<(Entity, &OpenClockSettings)>,
+    mut nevw_set_clock_settings: NettyEventWriter<SetClockSettingsEvent>,
+    network_mapping: Res<NetworkMapping>,
+) {
+    let Some(ev) = evr_clicked.read().last() else {
+        return;
+    };
+
+    let Ok(mode_button) = q_mode_button.get(ev.0) else {
+        return;
+    };
+
+    let Ok((ent, open_settings)) = q_open_settings.get_single() else {
+        return;
+    };
+
+    if let Ok(block) = open_settings.0.map_to_server(&network_mapping) {
+        nevw_set_clock_settings.send(SetClockSettingsEvent {
+            block,
+            settings: ClockSettings { mode: mode_button.0 },
+        });
+    }
+
+    commands.entity(ent).insert(NeedsDespawned);
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<ClockModeClicked>(app);
+
+    app.add_systems(
+        Update,
+        (
+            open_clock_settings.in_set(NetworkingSystemsSet::Between),
+            (populate_clock_settings, on_clock_mode_clicked).chain().in_set(UiSystemSet::DoUi),
+        )
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,498 @@
+//! The menu used to link energy relays together, and the beams drawn between linked relays
+
+use bevy::{
+    color::palettes::css,
+    pbr::{NotShadowCaster, NotShadowReceiver},
+    prelude::*,
+};
+use cosmos_core::{
+    block::{
+        data::BlockData,
+        specific_blocks::energy_relay::{
+            within_relay_range, EnergyRelayLink, OpenEnergyRelayEvent, SetEnergyRelayLinkEvent, ENERGY_RELAY_BLOCK,
+        },
+        Block,
+    },
+    ecs::NeedsDespawned,
+    netty::{
+        sync::{
+            events::client_event::{NettyEventReceived, NettyEventWriter},
+            mapping::{Mappable, NetworkMapping},
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    physics::location::Location,
+    prelude::{Structure, StructureBlock},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::structure_name::StructureName,
+};
+
+use crate::{
+    lang::Localization,
+    rendering::MainCamera,
+    ui::{
+        components::{
+            button::{register_button, Button, ButtonEvent, ButtonStyles},
+            window::GuiWindow,
+        },
+        font::DefaultFont,
+        OpenMenu, UiSystemSet,
+    },
+};
+
+const BEAM_WIDTH: f32 = 0.15;
+
+#[derive(Component, Debug)]
+struct OpenEnergyRelay(StructureBlock);
+
+#[derive(Component, Debug)]
+struct EnergyRelayContents;
+
+#[derive(Component, Debug)]
+struct LinkTarget(StructureBlock);
+
+#[derive(Event, Debug)]
+struct LinkClicked(Entity);
+
+impl ButtonEvent for LinkClicked {
+    fn create_event(entity: Entity) -> Self {
+        Self(entity)
+    }
+}
+
+#[derive(Event, Debug)]
+struct UnlinkClicked;
+
+impl ButtonEvent for UnlinkClicked {
+    fn create_event(_: Entity) -> Self {
+        Self
+    }
+}
+
+fn button_styles() -> ButtonStyles {
+    ButtonStyles {
+        background_color: Srgba::hex("555555").unwrap().into(),
+        hover_background_color: Srgba::hex("777777").unwrap().into(),
+        press_background_color: Srgba::hex("333333").unwrap().into(),
+        foreground_color: css::WHITE.into(),
+        hover_foreground_color: css::WHITE.into(),
+        press_foreground_color: css::WHITE.into(),
+    }
+}
+
+type RelayStructureQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Structure,
+        &'static Location,
+        &'static GlobalTransform,
+        Option<&'static StructureName>,
+    ),
+>;
+
+fn relay_location(s_block: StructureBlock, q_structure: &RelayStructureQuery) -> Option<Location> {
+    let (_, structure, loc, g_trans, _) = q_structure.get(s_block.structure()).ok()?;
+
+    Some(structure.block_world_location(s_block.coords(), g_trans, loc))
+}
+
+fn open_energy_relay(
+    mut commands: Commands,
+    q_open: Query<Entity, With<OpenEnergyRelay>>,
+    mut nevr_open: EventReader<NettyEventReceived<OpenEnergyRelayEvent>>,
+    network_mapping: Res<NetworkMapping>,
+) {
+    let Some(ev) = nevr_open.read().last() else {
+        return;
+    };
+
+    if let Ok(ent) = q_open.get_single() {
+        commands.entity(ent).insert(NeedsDespawned);
+    }
+
+    let Ok(block) = ev.0.map(&network_mapping) else {
+        error!("Bad network mapping - {:?}", ev.0);
+        return;
+    };
+
+    commands.spawn((OpenEnergyRelay(block), Name::new("Open Energy Relay")));
+}
+
+fn create_energy_relay_window(
+    mut commands: Commands,
+    q_added: Query<Entity, Added<OpenEnergyRelay>>,
+    q_cam: Query<Entity, With<MainCamera>>,
+    localization: Res<Localization>,
+) {
+    for ent in q_added.iter() {
+        let Ok(cam) = q_cam.get_single() else {
+            return;
+        };
+
+        commands
+            .entity(ent)
+            .insert((
+                TargetCamera(cam),
+                OpenMenu::new(0),
+                BackgroundColor(Srgba::hex("2D2D2D").unwrap().into()),
+                Node {
+                    width: Val::Px(450.0),
+                    margin: UiRect::all(Val::Auto),
+                    ..Default::default()
+                },
+                GuiWindow {
+                    title: localization.get("cosmos:window.energy_relay").into(),
+                    body_styles: Node {
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(20.0)),
+                        ..Default::default()
+                    },
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    Name::new("Energy relay contents"),
+                    EnergyRelayContents,
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(8.0),
+                        ..Default::default()
+                    },
+                ));
+            });
+    }
+}
+
+fn populate_energy_relay_window(
+    mut commands: Commands,
+    q_menu: Query<&OpenEnergyRelay>,
+    q_contents: Query<Entity, With<EnergyRelayContents>>,
+    q_added_contents: Query<(), Added<EnergyRelayContents>>,
+    q_changed_links: Query<(), Changed<EnergyRelayLink>>,
+    mut removed_links: RemovedComponents<EnergyRelayLink>,
+    q_structure: RelayStructureQuery,
+    q_link: Query<&EnergyRelayLink>,
+    blocks: Res<Registry<Block>>,
+    font: Res<DefaultFont>,
+) {
+    let links_removed = removed_links.read().next().is_some();
+
+    let (Ok(menu), Ok(contents_ent)) = (q_menu.get_single(), q_contents.get_single()) else {
+        return;
+    };
+
+    if q_added_contents.is_empty() && q_changed_links.is_empty() && !links_removed {
+        return;
+    }
+
+    let Some(relay_loc) = relay_location(menu.0, &q_structure) else {
+        return;
+    };
+
+    let current_link = q_structure
+        .get(menu.0.structure())
+        .ok()
+        .and_then(|(_, s, _, _, _)| s.query_block_data(menu.0.coords(), &q_link))
+        .map(|x| x.0);
+
+    let text_style = TextFont {
+        font: font.0.clone_weak(),
+        font_size: 20.0,
+        ..Default::default()
+    };
+
+    let row_node = Node {
+        flex_direction: FlexDirection::Row,
+        justify_content: JustifyContent::SpaceBetween,
+        align_items: AlignItems::Center,
+        ..Default::default()
+    };
+
+    let button_node = Node {
+        width: Val::Px(110.0),
+        height: Val::Px(36.0),
+        ..Default::default()
+    };
+
+    let describe = |s_block: StructureBlock| {
+        let name = q_structure
+            .get(s_block.structure())
+            .ok()
+            .and_then(|(_, _, _, _, name)| name)
+            .map(|x| x.name().to_owned())
+            .unwrap_or_else(|| "Unnamed structure".into());
+
+        let distance = relay_location(s_block, &q_structure)
+            .map(|loc| loc.distance_sqrd(&relay_loc).sqrt())
+            .unwrap_or_default();
+
+        format!("{name} ({distance:.0}m)")
+    };
+
+    let relay_id = blocks.from_id(ENERGY_RELAY_BLOCK).map(|x| x.id());
+
+    let mut ecmds = commands.entity(contents_ent);
+    ecmds.despawn_descendants();
+
+    ecmds.with_children(|p| {
+        p.spawn((Text::new("Linked To"), text_style.clone()));
+
+        p.spawn((Name::new("Current link"), row_node.clone()))
+            .with_children(|p| match current_link {
+                Some(link) => {
+                    p.spawn((Text::new(describe(link)), text_style.clone()));
+                    p.spawn((
+                        Name::new("Unlink button"),
+                        button_node.clone(),
+                        Button::<UnlinkClicked> {
+                            button_styles: Some(button_styles()),
+                            text: Some(("Unlink".into(), text_style.clone(), Default::default())),
+                            ..Default::default()
+                        },
+                    ));
+                }
+                None => {
+                    p.spawn((Text::new("Nothing"), text_style.clone(), TextColor(css::GRAY.into())));
+                }
+            });
+
+        p.spawn((
+            Text::new("Relays In Range"),
+            text_style.clone(),
+            Node {
+                margin: UiRect::top(Val::Px(10.0)),
+                ..Default::default()
+            },
+        ));
+
+        let mut any_in_range = false;
+
+        for (structure_ent, structure, loc, g_trans, _) in q_structure.iter() {
+            // Linking to a relay on the same structure would do nothing
+            if structure_ent == menu.0.structure() {
+                continue;
+            }
+
+            let (Some(relay_id), Some(block_counts)) = (relay_id, structure.block_counts()) else {
+                continue;
+            };
+
+            for coords in block_counts.positions(relay_id) {
+                let s_block = StructureBlock::new(coords, structure_ent);
+
+                if Some(s_block) == current_link || !within_relay_range(&relay_loc, &structure.block_world_location(coords, g_trans, loc)) {
+                    continue;
+                }
+
+                any_in_range = true;
+
+                p.spawn((Name::new("Relay in range"), row_node.clone())).with_children(|p| {
+                    p.spawn((Text::new(describe(s_block)), text_style.clone()));
+                    p.spawn((
+                        Name::new("Link button"),
+                        LinkTarget(s_block),
+                        button_node.clone(),
+                        Button::<LinkClicked> {
+                            button_styles: Some(button_styles()),
+                            text: Some(("Link".into(), text_style.clone(), Default::default())),
+                            ..Default::default()
+                        },
+                    ));
+                });
+            }
+        }
+
+        if !any_in_range {
+            p.spawn((
+                Text::new("No other relays in range"),
+                text_style.clone(),
+                TextColor(css::GRAY.into()),
+            ));
+        }
+    });
+}
+
+fn on_link_clicked(
+    mut evr_link: EventReader<LinkClicked>,
+    q_target: Query<&LinkTarget>,
+    q_menu: Query<&OpenEnergyRelay>,
+    network_mapping: Res<NetworkMapping>,
+    mut nevw_set_link: NettyEventWriter<SetEnergyRelayLinkEvent>,
+) {
+    for ev in evr_link.read() {
+        let (Ok(target), Ok(menu)) = (q_target.get(ev.0), q_menu.get_single()) else {
+            continue;
+        };
+
+        let (Ok(relay), Ok(target)) = (menu.0.map_to_server(&network_mapping), target.0.map_to_server(&network_mapping)) else {
+            continue;
+        };
+
+        nevw_set_link.send(SetEnergyRelayLinkEvent {
+            relay,
+            target: Some(target),
+        });
+    }
+}
+
+fn on_unlink_clicked(
+    mut evr_unlink: EventReader<UnlinkClicked>,
+    q_menu: Query<&OpenEnergyRelay>,
+    network_mapping: Res<NetworkMapping>,
+    mut nevw_set_link: NettyEventWriter<SetEnergyRelayLinkEvent>,
+) {
+    if evr_unlink.read().next().is_none() {
+        return;
+    }
+
+    let Ok(menu) = q_menu.get_single() else {
+        return;
+    };
+
+    if let Ok(relay) = menu.0.map_to_server(&network_mapping) {
+        nevw_set_link.send(SetEnergyRelayLinkEvent { relay, target: None });
+    }
+}
+
+#[derive(Resource, Debug)]
+struct RelayBeamAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+#[derive(Component, Debug)]
+/// A beam drawn between two linked relays. This is a child of the structure of the relay it starts at.
+struct RelayBeam {
+    /// The block data entity of the relay this beam starts at
+    link_data_entity: Entity,
+}
+
+#[derive(Component, Debug)]
+struct HasRelayBeam;
+
+fn create_relay_beam_assets(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>, mut materials: ResMut<Assets<StandardMaterial>>) {
+    let color = css::AQUA;
+
+    commands.insert_resource(RelayBeamAssets {
+        mesh: meshes.add(Cuboid::new(BEAM_WIDTH, BEAM_WIDTH, 1.0)),
+        material: materials.add(StandardMaterial {
+            unlit: true,
+            base_color: color.with_alpha(0.6).into(),
+            emissive: color.into(),
+            alpha_mode: AlphaMode::Add,
+            ..Default::default()
+        }),
+    });
+}
+
+fn add_relay_beams(
+    mut commands: Commands,
+    q_links: Query<(Entity, &BlockData), (With<EnergyRelayLink>, Without<HasRelayBeam>)>,
+    assets: Res<RelayBeamAssets>,
+) {
+    for (ent, block_data) in q_links.iter() {
+        let structure_ent = block_data.identifier.block.structure();
+
+        commands.entity(ent).insert(HasRelayBeam);
+        commands.entity(structure_ent).with_children(|p| {
+            p.spawn((
+                Name::new("Energy relay beam"),
+                RelayBeam { link_data_entity: ent },
+                Visibility::Hidden,
+                Transform::default(),
+                Mesh3d(assets.mesh.clone_weak()),
+                MeshMaterial3d(assets.material.clone_weak()),
+                NotShadowCaster,
+                NotShadowReceiver,
+            ));
+        });
+    }
+}
+
+fn update_relay_beams(
+    mut commands: Commands,
+    mut q_beams: Query<(Entity, &RelayBeam, &mut Transform, &mut Visibility)>,
+    q_links: Query<(&BlockData, &EnergyRelayLink)>,
+    q_link: Query<&EnergyRelayLink>,
+    q_structure: Query<(&Structure, &Location, &GlobalTransform)>,
+) {
+    for (beam_ent, beam, mut trans, mut vis) in q_beams.iter_mut() {
+        let Ok((block_data, link)) = q_links.get(beam.link_data_entity) else {
+            commands.entity(beam_ent).insert(NeedsDespawned);
+            if let Some(mut ecmds) = commands.get_entity(beam.link_data_entity) {
+                ecmds.remove::<HasRelayBeam>();
+            }
+            continue;
+        };
+
+        let relay = block_data.identifier.block;
+        let partner = link.0;
+
+        let (Ok((structure, loc, g_trans)), Ok((partner_structure, partner_loc, partner_g_trans))) =
+            (q_structure.get(relay.structure()), q_structure.get(partner.structure()))
+        else {
+            vis.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+
+        let links_back = partner_structure.query_block_data(partner.coords(), &q_link).map(|x| x.0) == Some(relay);
+        let in_range = within_relay_range(
+            &structure.block_world_location(relay.coords(), g_trans, loc),
+            &partner_structure.block_world_location(partner.coords(), partner_g_trans, partner_loc),
+        );
+
+        // Each link is stored on both of its relays, so only one of their beams is shown
+        if !links_back || !in_range || relay.structure() > partner.structure() {
+            vis.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+
+        let start = structure.block_relative_position(relay.coords());
+        let partner_world_pos = partner_g_trans.transform_point(partner_structure.block_relative_position(partner.coords()));
+        let end = g_trans.affine().inverse().transform_point3(partner_world_pos);
+
+        let length = start.distance(end);
+        if length <= f32::EPSILON {
+            vis.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+
+        *trans = Transform::from_translation((start + end) / 2.0)
+            .looking_at(end, Vec3::Y)
+            .with_scale(Vec3::new(1.0, 1.0, length));
+        vis.set_if_neq(Visibility::Inherited);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<LinkClicked>(app);
+    register_button::<UnlinkClicked>(app);
+
+    app.add_systems(Startup, create_relay_beam_assets).add_systems(
+        Update,
+        (
+            open_energy_relay.in_set(NetworkingSystemsSet::Between),
+            (
+                create_energy_relay_window,
+                populate_energy_relay_window,
+                on_link_clicked,
+                on_unlink_clicked,
+            )
+                .chain()
+                .in_set(UiSystemSet::DoUi),
+        )
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    );
+
+    app.add_systems(
+        Update,
+        (add_relay_beams, update_relay_beams)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,353 @@
+//! Renders the hologram floating above a holographic projector.
+
+use std::time::Duration;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    color::palettes::css,
+    prelude::*,
+    render::mesh::{Indices, PrimitiveTopology},
+    time::common_conditions::on_timer,
+    utils::HashSet,
+};
+use cosmos_core::{
+    block::{
+        block_face::BlockFace,
+        data::BlockData,
+        specific_blocks::holo_projector::{HoloProjector, HoloProjectorMode},
+    },
+    ecs::NeedsDespawned,
+    events::block_events::BlockChangedEvent,
+    prelude::Structure,
+    state::GameState,
+    structure::{
+        shields::Shield,
+        systems::{energy_storage_system::EnergyStorageSystem, StructureSystems},
+    },
+};
+
+/// The miniature is made of at most this many cells along its longest side
+const MINIATURE_RESOLUTION: f32 = 32.0;
+/// How large the longest side of the miniature is
+const MINIATURE_SIZE: f32 = 2.0;
+/// How fast the miniature spins (radians/sec)
+const MINIATURE_SPIN_SPEED: f32 = 0.5;
+
+const BAR_WIDTH: f32 = 1.5;
+const BAR_HEIGHT: f32 = 0.15;
+
+#[derive(Component, Debug)]
+/// The root of a hologram, placed above its projector
+struct Hologram {
+    data_entity: Entity,
+    mode: HoloProjectorMode,
+}
+
+#[derive(Component, Debug)]
+/// Placed on the block data entity of a projector whose hologram has been created
+struct RenderedHologram(Entity);
+
+#[derive(Debug, Clone, Copy)]
+enum StatKind {
+    Energy,
+    Shield,
+}
+
+#[derive(Component, Debug)]
+struct HologramBar {
+    kind: StatKind,
+    structure: Entity,
+}
+
+#[derive(Component, Debug)]
+struct HologramMiniature {
+    structure: Entity,
+}
+
+#[derive(Component, Debug)]
+struct MiniatureNeedsRebuilt;
+
+fn hologram_material(materials: &mut Assets<StandardMaterial>, color: Srgba) -> Handle<StandardMaterial> {
+    materials.add(StandardMaterial {
+        base_color: color.with_alpha(0.5).into(),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        double_sided: true,
+        cull_mode: None,
+        ..Default::default()
+    })
+}
+
+/// Creates a mesh of the structure that is downsampled into a grid of at most [`MINIATURE_RESOLUTION`] cells along
+/// its longest side, only including the faces between filled & empty cells.
+fn build_miniature_mesh(structure: &Structure) -> Mesh {
+    let dims = structure.block_dimensions();
+    let max_dim = dims.x.max(dims.y).max(dims.z).max(1) as f32;
+    let cell_size = (max_dim / MINIATURE_RESOLUTION).ceil().max(1.0) as u64;
+
+    let filled = structure
+        .all_blocks_iter(false)
+        .map(|c| ((c.x / cell_size) as i32, (c.y / cell_size) as i32, (c.z / cell_size) as i32))
+        .collect::<HashSet<_>>();
+
+    let grid_dims = Vec3::new(dims.x as f32, dims.y as f32, dims.z as f32) / cell_size as f32;
+    let scale = MINIATURE_SIZE / grid_dims.max_element().max(1.0);
+    let offset = grid_dims / 2.0;
+
+    let mut positions = vec![];
+    let mut normals = vec![];
+    let mut indices = vec![];
+
+    for &(x, y, z) in filled.iter() {
+        let cell = [x, y, z];
+
+        for axis in 0..3 {
+            for sign in [-1, 1] {
+                let mut neighbor = cell;
+                neighbor[axis] += sign;
+                if filled.contains(&(neighbor[0], neighbor[1], neighbor[2])) {
+                    continue;
+                }
+
+                let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+                let mut normal = [0.0; 3];
+                normal[axis] = sign as f32;
+
+                let start = positions.len() as u32;
+                for (du, dv) in [(0, 0), (1, 0), (1, 1), (0, 1)] {
+                    let mut corner = cell.map(|c| c as f32);
+                    if sign > 0 {
+                        corner[axis] += 1.0;
+                    }
+                    corner[u] += du as f32;
+                    corner[v] += dv as f32;
+
+                    positions.push(((Vec3::from(corner) - offset) * scale).to_array());
+                    normals.push(normal);
+                }
+
+                indices.extend([start, start + 1, start + 2, start, start + 2, start + 3]);
+            }
+        }
+    }
+
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_indices(Indices::U32(indices))
+}
+
+fn remove_dead_holograms(mut commands: Commands, q_hologram: Query<(Entity, &Hologram)>, q_projector: Query<(), With<HoloProjector>>) {
+    for (entity, hologram) in q_hologram.iter() {
+        if q_projector.contains(hologram.data_entity) {
+            continue;
+        }
+
+        commands.entity(entity).insert(NeedsDespawned);
+        if let Some(mut ecmds) = commands.get_entity(hologram.data_entity) {
+            ecmds.remove::<RenderedHologram>();
+        }
+    }
+}
+
+fn create_holograms(
+    mut commands: Commands,
+    q_changed_projector: Query<(Entity, &HoloProjector, &BlockData, Option<&RenderedHologram>), Changed<HoloProjector>>,
+    mut q_hologram: Query<(&Hologram, &mut Visibility)>,
+    q_structure: Query<&Structure>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (data_entity, projector, block_data, rendered) in q_changed_projector.iter() {
+        let visibility = if projector.enabled {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        if let Some(rendered) = rendered {
+            if let Ok((hologram, mut vis)) = q_hologram.get_mut(rendered.0) {
+                if hologram.mode == projector.mode {
+                    vis.set_if_neq(visibility);
+                    continue;
+                }
+
+                commands.entity(rendered.0).insert(NeedsDespawned);
+            }
+        }
+
+        let block = block_data.identifier.block;
+        let structure_entity = block.structure();
+        let Ok(structure) = q_structure.get(structure_entity) else {
+            continue;
+        };
+
+        let up = structure.block_rotation(block.coords()).direction_of(BlockFace::Top).as_vec3();
+        let translation = structure.block_relative_position(block.coords()) + up * 0.6;
+
+        let mut ecmds = commands.spawn((
+            Name::new("Hologram"),
+            Hologram {
+                data_entity,
+                mode: projector.mode,
+            },
+            Transform::from_translation(translation).looking_to(up.any_orthonormal_vector(), up),
+            visibility,
+        ));
+        ecmds.set_parent(structure_entity);
+
+        match projector.mode {
+            HoloProjectorMode::ShipStats => {
+                let bar_mesh = meshes.add(Rectangle::new(BAR_WIDTH, BAR_HEIGHT));
+                let frame_material = hologram_material(&mut materials, css::DARK_SLATE_GRAY);
+
+                ecmds.with_children(|p| {
+                    for (i, (kind, color)) in [(StatKind::Energy, css::YELLOW), (StatKind::Shield, css::AQUA)]
+                        .into_iter()
+                        .enumerate()
+                    {
+                        let height = 0.5 + i as f32 * BAR_HEIGHT * 2.0;
+
+                        p.spawn((
+                            Name::new("Hologram Bar Frame"),
+                            Mesh3d(bar_mesh.clone()),
+                            MeshMaterial3d(frame_material.clone()),
+                            Transform::from_xyz(0.0, height, 0.0),
+                        ));
+
+                        p.spawn((
+                            Name::new("Hologram Bar"),
+                            HologramBar {
+                                kind,
+                                structure: structure_entity,
+                            },
+                            Mesh3d(bar_mesh.clone()),
+                            MeshMaterial3d(hologram_material(&mut materials, color)),
+                            // Slightly in front of the frame to avoid z-fighting
+                            Transform::from_xyz(0.0, height, 0.01),
+                        ));
+                    }
+                });
+            }
+            HoloProjectorMode::Miniature => {
+                let material = hologram_material(&mut materials, css::AQUA);
+                let mesh = meshes.add(build_miniature_mesh(structure));
+
+                ecmds.with_children(|p| {
+                    p.spawn((
+                        Name::new("Hologram Miniature"),
+                        HologramMiniature {
+                            structure: structure_entity,
+                        },
+                        Mesh3d(mesh),
+                        MeshMaterial3d(material),
+                        Transform::from_xyz(0.0, MINIATURE_SIZE / 2.0 + 0.2, 0.0),
+                    ));
+                });
+            }
+        }
+
+        let hologram_entity = ecmds.id();
+        commands.entity(data_entity).insert(RenderedHologram(hologram_entity));
+    }
+}
+
+fn update_stat_bars(
+    mut q_bars: Query<(&HologramBar, &mut Transform, &InheritedVisibility)>,
+    q_systems: Query<&StructureSystems>,
+    q_energy_storage_system: Query<&EnergyStorageSystem>,
+    q_shields: Query<(&Shield, &Parent)>,
+) {
+    for (bar, mut transform, inherited_visibility) in q_bars.iter_mut() {
+        if !inherited_visibility.get() {
+            continue;
+        }
+
+        let percent = match bar.kind {
+            StatKind::Energy => q_systems
+                .get(bar.structure)
+                .ok()
+                .and_then(|systems| systems.query(&q_energy_storage_system).ok())
+                .filter(|ess| ess.get_capacity() != 0.0)
+                .map(|ess| ess.get_energy() / ess.get_capacity())
+                .unwrap_or(0.0),
+            StatKind::Shield => {
+                let (strength, max_strength) = q_shields
+                    .iter()
+                    .filter(|(_, parent)| parent.get() == bar.structure)
+                    .fold((0.0, 0.0), |(strength, max), (shield, _)| {
+                        (strength + shield.strength, max + shield.max_strength)
+                    });
+
+                if max_strength != 0.0 {
+                    strength / max_strength
+                } else {
+                    0.0
+                }
+            }
+        }
+        .clamp(0.0, 1.0);
+
+        // Keeps the bar's left side in place as it shrinks
+        transform.scale.x = percent.max(f32::EPSILON);
+        transform.translation.x = -(1.0 - percent) * BAR_WIDTH / 2.0;
+    }
+}
+
+fn flag_changed_miniatures(
+    mut commands: Commands,
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    q_miniature: Query<(Entity, &HologramMiniature), Without<MiniatureNeedsRebuilt>>,
+) {
+    let changed_structures = evr_block_changed.read().map(|ev| ev.block.structure()).collect::<HashSet<_>>();
+
+    if changed_structures.is_empty() {
+        return;
+    }
+
+    for (entity, miniature) in q_miniature.iter() {
+        if changed_structures.contains(&miniature.structure) {
+            commands.entity(entity).insert(MiniatureNeedsRebuilt);
+        }
+    }
+}
+
+/// Rebuilding miniatures is expensive, so this only happens every so often instead of every block change.
+fn rebuild_miniatures(
+    mut commands: Commands,
+    mut q_miniature: Query<(Entity, &HologramMiniature, &mut Mesh3d), With<MiniatureNeedsRebuilt>>,
+    q_structure: Query<&Structure>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (entity, miniature, mut mesh) in q_miniature.iter_mut() {
+        commands.entity(entity).remove::<MiniatureNeedsRebuilt>();
+
+        let Ok(structure) = q_structure.get(miniature.structure) else {
+            continue;
+        };
+
+        mesh.0 = meshes.add(build_miniature_mesh(structure));
+    }
+}
+
+fn spin_miniatures(time: Res<Time>, mut q_miniature: Query<&mut Transform, With<HologramMiniature>>) {
+    for mut transform in q_miniature.iter_mut() {
+        transform.rotate_local_y(MINIATURE_SPIN_SPEED * time.delta_secs());
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            remove_dead_holograms,
+            create_holograms,
+            update_stat_bars,
+            flag_changed_miniatures,
+            rebuild_miniatures.run_if(on_timer(Duration::from_secs(1))),
+            spin_miniatures,
+        )
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    );
+}
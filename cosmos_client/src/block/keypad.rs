@@ -0,0 +1,217 @@
+//! The menu used to type a code into a keypad, or change a keypad's code
+
+use bevy::{a11y::Focus, color::palettes::css, prelude::*};
+use cosmos_core::{
+    block::specific_blocks::keypad::{EnterKeypadCodeEvent, KeypadPurpose, OpenKeypadEvent, MAX_KEYPAD_CODE_LENGTH},
+    ecs::NeedsDespawned,
+    netty::{
+        sync::{
+            events::client_event::{NettyEventReceived, NettyEventWriter},
+            mapping::{Mappable, NetworkMapping},
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::StructureBlock,
+    state::GameState,
+};
+
+use crate::{
+    lang::Localization,
+    rendering::MainCamera,
+    ui::{
+        components::{
+            button::{register_button, Button, ButtonEvent, ButtonStyles},
+            text_input::{InputType, InputValue, TextInput},
+            window::GuiWindow,
+        },
+        font::DefaultFont,
+        OpenMenu, UiSystemSet,
+    },
+};
+
+#[derive(Component, Debug)]
+struct OpenKeypadPrompt {
+    block: StructureBlock,
+    purpose: KeypadPurpose,
+}
+
+#[derive(Component, Debug)]
+struct KeypadCodeInput;
+
+#[derive(Event, Debug)]
+struct EnterCodeClicked;
+
+impl ButtonEvent for EnterCodeClicked {
+    fn create_event(_: Entity) -> Self {
+        Self
+    }
+}
+
+/// Allows partially typed codes, the server checks the full code
+fn is_partial_keypad_code(code: &str) -> bool {
+    code.len() <= MAX_KEYPAD_CODE_LENGTH && code.chars().all(|c| c.is_ascii_digit())
+}
+
+fn open_keypad_prompt(
+    mut commands: Commands,
+    q_open_prompt: Query<Entity, With<OpenKeypadPrompt>>,
+    mut nevr_open_prompt: EventReader<NettyEventReceived<OpenKeypadEvent>>,
+    network_mapping: Res<NetworkMapping>,
+) {
+    let Some(ev) = nevr_open_prompt.read().last() else {
+        return;
+    };
+
+    if let Ok(ent) = q_open_prompt.get_single() {
+        commands.entity(ent).insert(NeedsDespawned);
+    }
+
+    let Ok(block) = ev.block.map(&network_mapping) else {
+        error!("Bad network mapping - {:?}", ev.block);
+        return;
+    };
+
+    commands.spawn((
+        OpenKeypadPrompt {
+            block,
+            purpose: ev.purpose,
+        },
+        Name::new("Open Keypad Prompt"),
+    ));
+}
+
+fn populate_keypad_prompt(
+    mut commands: Commands,
+    q_added_prompt: Query<(Entity, &OpenKeypadPrompt), Added<OpenKeypadPrompt>>,
+    q_cam: Query<Entity, With<MainCamera>>,
+    font: Res<DefaultFont>,
+    localization: Res<Localization>,
+    mut focus: ResMut<Focus>,
+) {
+    for (ent, prompt) in q_added_prompt.iter() {
+        let Ok(cam) = q_cam.get_single() else {
+            return;
+        };
+
+        let title = match prompt.purpose {
+            KeypadPurpose::SetCode => "cosmos:window.set_keypad_code",
+            KeypadPurpose::Enter => "cosmos:window.keypad",
+        };
+
+        let text_style = TextFont {
+            font: font.0.clone_weak(),
+            font_size: 24.0,
+            ..Default::default()
+        };
+
+        let mut ecmds = commands.entity(ent);
+
+        ecmds.insert((
+            TargetCamera(cam),
+            OpenMenu::new(0),
+            BackgroundColor(Srgba::hex("2D2D2D").unwrap().into()),
+            Node {
+                width: Val::Px(400.0),
+                margin: UiRect::all(Val::Auto),
+                ..Default::default()
+            },
+            GuiWindow {
+                title: localization.get(title).into(),
+                body_styles: Node {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(20.0)),
+                    ..Default::default()
+                },
+            },
+        ));
+
+        ecmds.with_children(|p| {
+            let input_ent = p
+                .spawn((
+                    KeypadCodeInput,
+                    text_style.clone(),
+                    TextInput {
+                        input_type: InputType::Custom(is_partial_keypad_code),
+                        ..Default::default()
+                    },
+                    BorderColor(Srgba::hex("555555").unwrap().into()),
+                    BackgroundColor(Srgba::hex("111111").unwrap().into()),
+                    Node {
+                        border: UiRect::all(Val::Px(2.0)),
+                        width: Val::Percent(100.0),
+                        padding: UiRect::all(Val::Px(4.0)),
+                        ..Default::default()
+                    },
+                ))
+                .id();
+
+            focus.0 = Some(input_ent);
+
+            p.spawn((
+                Name::new("Enter Keypad Code Button"),
+                Node {
+                    height: Val::Px(50.0),
+                    margin: UiRect::top(Val::Px(20.0)),
+                    ..Default::default()
+                },
+                Button::<EnterCodeClicked> {
+                    button_styles: Some(ButtonStyles {
+                        background_color: Srgba::hex("555555").unwrap().into(),
+                        hover_background_color: Srgba::hex("777777").unwrap().into(),
+                        press_background_color: Srgba::hex("333333").unwrap().into(),
+                        foreground_color: css::WHITE.into(),
+                        hover_foreground_color: css::WHITE.into(),
+                        press_foreground_color: css::WHITE.into(),
+                    }),
+                    text: Some(("Enter".into(), text_style, Default::default())),
+                    ..Default::default()
+                },
+            ));
+        });
+    }
+}
+
+fn on_enter_code(
+    mut commands: Commands,
+    mut evr_enter: EventReader<EnterCodeClicked>,
+    q_open_prompt: Query<(Entity, &OpenKeypadPrompt)>,
+    q_input: Query<&InputValue, With<KeypadCodeInput>>,
+    mut nevw_enter_keypad_code: NettyEventWriter<EnterKeypadCodeEvent>,
+    network_mapping: Res<NetworkMapping>,
+) {
+    if evr_enter.read().next().is_none() {
+        return;
+    }
+
+    let Ok((ent, prompt)) = q_open_prompt.get_single() else {
+        return;
+    };
+
+    let Ok(input) = q_input.get_single() else {
+        return;
+    };
+
+    if let Ok(block) = prompt.block.map_to_server(&network_mapping) {
+        nevw_enter_keypad_code.send(EnterKeypadCodeEvent {
+            block,
+            code: input.value().to_owned(),
+            purpose: prompt.purpose,
+        });
+    }
+
+    commands.entity(ent).insert(NeedsDespawned);
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<EnterCodeClicked>(app);
+
+    app.add_systems(
+        Update,
+        (
+            open_keypad_prompt.in_set(NetworkingSystemsSet::Between),
+            (populate_keypad_prompt, on_enter_code).chain().in_set(UiSystemSet::DoUi),
+        )
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    );
+}
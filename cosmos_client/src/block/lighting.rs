@@ -18,14 +18,10 @@ use serde::{Deserialize, Serialize};
 pub struct BlockLightProperties {
     /// The color of that light
     pub color: Color,
-    /// How intense it should be in lumens,
+    /// The light level at this block, which goes down by one for every block the light spreads through.
     ///
-    /// See https://docs.rs/bevy/latest/bevy/pbr/struct.PointLight.html for a table of valus.
-    pub intensity: f32,
-    /// How far this light will reach
-    pub range: f32,
-    /// Ignored for now due to performance issues. Shadows are currently always disabled.
-    pub shadows_disabled: bool,
+    /// This is capped at [`crate::rendering::structure_renderer::chunk_rendering::light_propagation::MAX_LIGHT_LEVEL`].
+    pub level: u8,
 }
 
 #[derive(Debug, Clone, Reflect, Default, Serialize, Deserialize)]
@@ -68,9 +64,7 @@ fn register_all_lights(blocks: Res<Registry<Block>>, mut registry: ResMut<Regist
     register_light(
         BlockLightProperties {
             color: Color::WHITE,
-            intensity: 600_000.0,
-            range: 12.0,
-            ..Default::default()
+            level: 15,
         },
         &mut registry,
         &blocks,
@@ -86,9 +80,7 @@ fn register_all_lights(blocks: Res<Registry<Block>>, mut registry: ResMut<Regist
                 alpha: 1.0,
             }
             .into(),
-            intensity: 20_000.0,
-            range: 6.0,
-            ..Default::default()
+            level: 6,
         },
         &mut registry,
         &blocks,
@@ -104,9 +96,7 @@ fn register_all_lights(blocks: Res<Registry<Block>>, mut registry: ResMut<Regist
                 alpha: 1.0,
             }
             .into(),
-            intensity: 20_000.0,
-            range: 6.0,
-            ..Default::default()
+            level: 6,
         },
         &mut registry,
         &blocks,
@@ -1,9 +1,27 @@
-//! This just handles lighting for now.
+//! Client-side logic for blocks, such as lighting, sign text, airlocks, holograms, storage locks, keypads, timers, clocks, energy relays, and warp gates.
 
 use bevy::prelude::App;
 
+pub mod airlock;
+pub mod clock;
+pub mod energy_relay;
+pub mod holo_projector;
+pub mod keypad;
 pub mod lighting;
+pub mod lock_code;
+pub mod sign;
+pub mod timer;
+pub mod warp_gate;
 
 pub(super) fn register(app: &mut App) {
     lighting::register(app);
+    holo_projector::register(app);
+    sign::register(app);
+    lock_code::register(app);
+    keypad::register(app);
+    timer::register(app);
+    airlock::register(app);
+    clock::register(app);
+    energy_relay::register(app);
+    warp_gate::register(app);
 }
@@ -0,0 +1,242 @@
+//! Renders the text of sign blocks onto their front face.
+//!
+//! Each sign's text is rendered into its own texture by a 2d camera, which is then displayed on
+//! a quad in front of the sign. The camera only renders for a few frames after the text changes.
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::{
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        view::RenderLayers,
+    },
+    text::TextBounds,
+    utils::HashMap,
+};
+use cosmos_core::{
+    block::{block_face::BlockFace, data::BlockData, specific_blocks::sign::SignText},
+    ecs::NeedsDespawned,
+    prelude::Structure,
+    state::GameState,
+};
+
+use crate::ui::font::DefaultFont;
+
+mod ui;
+
+/// Every sign gets its own render layer, starting at this one, so their cameras only see their own text.
+const FIRST_SIGN_RENDER_LAYER: usize = 64;
+
+const SIGN_TEXTURE_WIDTH: u32 = 512;
+const SIGN_TEXTURE_HEIGHT: u32 = 256;
+
+/// The camera rendering a sign's text is disabled after this many frames, since the text doesn't change often.
+const SIGN_RENDER_FRAMES: u8 = 3;
+
+#[derive(Resource, Debug, Default)]
+struct SignRenderLayers {
+    next: usize,
+    free: Vec<usize>,
+    /// Sign display entity -> the render layer it is using
+    in_use: HashMap<Entity, usize>,
+}
+
+impl SignRenderLayers {
+    fn take(&mut self, display_entity: Entity) -> usize {
+        let layer = self.free.pop().unwrap_or_else(|| {
+            let layer = FIRST_SIGN_RENDER_LAYER + self.next;
+            self.next += 1;
+            layer
+        });
+
+        self.in_use.insert(display_entity, layer);
+
+        layer
+    }
+}
+
+#[derive(Component, Debug)]
+/// Placed on the block data entity of a sign that is being rendered
+struct RenderedSign {
+    text_entity: Entity,
+    camera_entity: Entity,
+}
+
+#[derive(Component, Debug)]
+/// The quad displaying a sign's text in the world. The text and the camera rendering it are children of this.
+struct SignDisplay {
+    data_entity: Entity,
+}
+
+#[derive(Component, Debug)]
+struct SignCameraFramesLeft(u8);
+
+fn create_sign_texture(images: &mut Assets<Image>) -> Handle<Image> {
+    let size = Extent3d {
+        width: SIGN_TEXTURE_WIDTH,
+        height: SIGN_TEXTURE_HEIGHT,
+        ..default()
+    };
+
+    let mut image = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Bgra8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    // You need to set these texture usage flags in order to use the image as a render target
+    image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+
+    images.add(image)
+}
+
+fn render_sign_text(
+    mut commands: Commands,
+    q_changed_sign: Query<(Entity, &SignText, &BlockData, Option<&RenderedSign>), Changed<SignText>>,
+    mut q_text: Query<&mut Text2d>,
+    q_structure: Query<&Structure>,
+    mut render_layers: ResMut<SignRenderLayers>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    font: Res<DefaultFont>,
+) {
+    for (data_entity, sign_text, block_data, rendered_sign) in q_changed_sign.iter() {
+        if let Some(rendered_sign) = rendered_sign {
+            if let Ok(mut text) = q_text.get_mut(rendered_sign.text_entity) {
+                text.0 = sign_text.text().to_owned();
+                commands
+                    .entity(rendered_sign.camera_entity)
+                    .insert(SignCameraFramesLeft(SIGN_RENDER_FRAMES));
+                continue;
+            }
+        }
+
+        let block = block_data.identifier.block;
+        let Ok(structure) = q_structure.get(block.structure()) else {
+            continue;
+        };
+
+        let rotation = structure.block_rotation(block.coords());
+        let normal = rotation.direction_of(BlockFace::Front).as_vec3();
+        let up = rotation.direction_of(BlockFace::Top).as_vec3();
+        // Slightly in front of the face to avoid z-fighting with the block
+        let translation = structure.block_relative_position(block.coords()) + normal * 0.505;
+
+        let image = create_sign_texture(&mut images);
+
+        let mut ecmds = commands.spawn((
+            Name::new("Sign Display"),
+            SignDisplay { data_entity },
+            Mesh3d(meshes.add(Rectangle::new(0.9, 0.45))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color_texture: Some(image.clone()),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..Default::default()
+            })),
+            Transform::from_translation(translation).looking_to(-normal, up),
+        ));
+        ecmds.set_parent(block.structure());
+
+        let display_entity = ecmds.id();
+        let render_layer = render_layers.take(display_entity);
+
+        let mut camera_entity = Entity::PLACEHOLDER;
+        let mut text_entity = Entity::PLACEHOLDER;
+
+        // The camera + text share the display's transform, so they stay lined up with each other.
+        ecmds.with_children(|p| {
+            camera_entity = p
+                .spawn((
+                    Name::new("Sign Text Camera"),
+                    Camera2d,
+                    Camera {
+                        order: -10,
+                        clear_color: ClearColorConfig::Custom(Color::NONE),
+                        target: image.into(),
+                        ..Default::default()
+                    },
+                    SignCameraFramesLeft(SIGN_RENDER_FRAMES),
+                    RenderLayers::layer(render_layer),
+                ))
+                .id();
+
+            text_entity = p
+                .spawn((
+                    Name::new("Sign Text"),
+                    Text2d::new(sign_text.text()),
+                    TextFont {
+                        font: font.0.clone_weak(),
+                        font_size: 48.0,
+                        ..Default::default()
+                    },
+                    TextColor(Color::WHITE),
+                    TextLayout::new_with_justify(JustifyText::Center),
+                    TextBounds::new(SIGN_TEXTURE_WIDTH as f32 - 32.0, SIGN_TEXTURE_HEIGHT as f32 - 16.0),
+                    RenderLayers::layer(render_layer),
+                ))
+                .id();
+        });
+
+        commands.entity(data_entity).insert(RenderedSign {
+            text_entity,
+            camera_entity,
+        });
+    }
+}
+
+fn disable_idle_sign_cameras(mut commands: Commands, mut q_camera: Query<(Entity, &mut Camera, &mut SignCameraFramesLeft)>) {
+    for (entity, mut camera, mut frames_left) in q_camera.iter_mut() {
+        camera.is_active = frames_left.0 != 0;
+
+        if frames_left.0 == 0 {
+            commands.entity(entity).remove::<SignCameraFramesLeft>();
+        } else {
+            frames_left.0 -= 1;
+        }
+    }
+}
+
+fn remove_dead_signs(
+    mut commands: Commands,
+    q_sign_display: Query<&SignDisplay>,
+    q_sign_text: Query<(), With<SignText>>,
+    mut render_layers: ResMut<SignRenderLayers>,
+) {
+    let render_layers = render_layers.as_mut();
+
+    render_layers.in_use.retain(|&display_entity, &mut layer| {
+        // The display could have been despawned along with its structure
+        let alive = q_sign_display.get(display_entity).is_ok_and(|display| {
+            if q_sign_text.contains(display.data_entity) {
+                return true;
+            }
+
+            commands.entity(display_entity).insert(NeedsDespawned);
+            if let Some(mut ecmds) = commands.get_entity(display.data_entity) {
+                ecmds.remove::<RenderedSign>();
+            }
+
+            false
+        });
+
+        if !alive {
+            render_layers.free.push(layer);
+        }
+
+        alive
+    });
+}
+
+pub(super) fn register(app: &mut App) {
+    ui::register(app);
+
+    app.init_resource::<SignRenderLayers>().add_systems(
+        Update,
+        (remove_dead_signs, render_sign_text, disable_idle_sign_cameras)
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,212 @@
+//! The menu used to edit the text of a sign
+
+use bevy::{a11y::Focus, color::palettes::css, prelude::*};
+use cosmos_core::{
+    block::specific_blocks::sign::{OpenSignEditorEvent, SetSignTextEvent, SignText, MAX_SIGN_TEXT_LENGTH},
+    ecs::NeedsDespawned,
+    netty::{
+        sync::{
+            events::client_event::{NettyEventReceived, NettyEventWriter},
+            mapping::{Mappable, NetworkMapping},
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::{Structure, StructureBlock},
+    state::GameState,
+};
+
+use crate::{
+    lang::Localization,
+    rendering::MainCamera,
+    ui::{
+        components::{
+            button::{register_button, Button, ButtonEvent, ButtonStyles},
+            text_input::{InputType, InputValue, TextInput},
+            window::GuiWindow,
+        },
+        font::DefaultFont,
+        OpenMenu, UiSystemSet,
+    },
+};
+
+#[derive(Component, Debug, Reflect)]
+struct OpenSignEditor(StructureBlock);
+
+#[derive(Component, Debug)]
+struct SignTextInput;
+
+#[derive(Event, Debug)]
+struct SaveSignClicked;
+
+impl ButtonEvent for SaveSignClicked {
+    fn create_event(_: Entity) -> Self {
+        Self
+    }
+}
+
+fn open_sign_editor(
+    mut commands: Commands,
+    q_open_editor: Query<Entity, With<OpenSignEditor>>,
+    mut nevr_open_sign_editor: EventReader<NettyEventReceived<OpenSignEditorEvent>>,
+    network_mapping: Res<NetworkMapping>,
+) {
+    let Some(ev) = nevr_open_sign_editor.read().last() else {
+        return;
+    };
+
+    if let Ok(ent) = q_open_editor.get_single() {
+        commands.entity(ent).insert(NeedsDespawned);
+    }
+
+    let Ok(s_block) = ev.0.map(&network_mapping) else {
+        error!("Bad network mapping - {:?}", ev.0);
+        return;
+    };
+
+    commands.spawn((OpenSignEditor(s_block), Name::new("Open Sign Editor")));
+}
+
+fn populate_sign_editor(
+    mut commands: Commands,
+    q_added_editor: Query<(Entity, &OpenSignEditor), Added<OpenSignEditor>>,
+    q_structure: Query<&Structure>,
+    q_sign_text: Query<&SignText>,
+    q_cam: Query<Entity, With<MainCamera>>,
+    font: Res<DefaultFont>,
+    localization: Res<Localization>,
+    mut focus: ResMut<Focus>,
+) {
+    for (ent, editor) in q_added_editor.iter() {
+        let Ok(cam) = q_cam.get_single() else {
+            return;
+        };
+
+        let current_text = q_structure
+            .get(editor.0.structure())
+            .ok()
+            .and_then(|structure| structure.query_block_data(editor.0.coords(), &q_sign_text))
+            .map(|sign_text| sign_text.text().to_owned())
+            .unwrap_or_default();
+
+        let text_style = TextFont {
+            font: font.0.clone_weak(),
+            font_size: 24.0,
+            ..Default::default()
+        };
+
+        let mut ecmds = commands.entity(ent);
+
+        ecmds.insert((
+            TargetCamera(cam),
+            OpenMenu::new(0),
+            BackgroundColor(Srgba::hex("2D2D2D").unwrap().into()),
+            Node {
+                width: Val::Px(600.0),
+                margin: UiRect::all(Val::Auto),
+                ..Default::default()
+            },
+            GuiWindow {
+                title: localization.get("cosmos:window.sign").into(),
+                body_styles: Node {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(20.0)),
+                    ..Default::default()
+                },
+            },
+        ));
+
+        ecmds.with_children(|p| {
+            let input_ent = p
+                .spawn((
+                    SignTextInput,
+                    text_style.clone(),
+                    TextInput {
+                        input_type: InputType::Text {
+                            max_length: Some(MAX_SIGN_TEXT_LENGTH),
+                        },
+                        cursor_pos: current_text.len(),
+                        ..Default::default()
+                    },
+                    InputValue::new(current_text),
+                    BorderColor(Srgba::hex("555555").unwrap().into()),
+                    BackgroundColor(Srgba::hex("111111").unwrap().into()),
+                    Node {
+                        border: UiRect::all(Val::Px(2.0)),
+                        width: Val::Percent(100.0),
+                        min_height: Val::Px(45.0),
+                        padding: UiRect::all(Val::Px(4.0)),
+                        ..Default::default()
+                    },
+                ))
+                .id();
+
+            focus.0 = Some(input_ent);
+
+            p.spawn((
+                Name::new("Save Sign Button"),
+                Node {
+                    height: Val::Px(50.0),
+                    margin: UiRect::top(Val::Px(20.0)),
+                    ..Default::default()
+                },
+                Button::<SaveSignClicked> {
+                    button_styles: Some(ButtonStyles {
+                        background_color: Srgba::hex("555555").unwrap().into(),
+                        hover_background_color: Srgba::hex("777777").unwrap().into(),
+                        press_background_color: Srgba::hex("333333").unwrap().into(),
+                        foreground_color: css::WHITE.into(),
+                        hover_foreground_color: css::WHITE.into(),
+                        press_foreground_color: css::WHITE.into(),
+                    }),
+                    text: Some(("Save".into(), text_style, Default::default())),
+                    ..Default::default()
+                },
+            ));
+        });
+    }
+}
+
+fn on_save_sign(
+    mut commands: Commands,
+    mut evr_save: EventReader<SaveSignClicked>,
+    q_open_editor: Query<(Entity, &OpenSignEditor)>,
+    q_input: Query<&InputValue, With<SignTextInput>>,
+    mut nevw_set_sign_text: NettyEventWriter<SetSignTextEvent>,
+    network_mapping: Res<NetworkMapping>,
+) {
+    if evr_save.read().next().is_none() {
+        return;
+    }
+
+    let Ok((ent, editor)) = q_open_editor.get_single() else {
+        return;
+    };
+
+    let Ok(input) = q_input.get_single() else {
+        return;
+    };
+
+    if let Ok(block) = editor.0.map_to_server(&network_mapping) {
+        nevw_set_sign_text.send(SetSignTextEvent {
+            block,
+            text: input.value().to_owned(),
+        });
+    }
+
+    commands.entity(ent).insert(NeedsDespawned);
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<SaveSignClicked>(app);
+
+    app.add_systems(
+        Update,
+        (
+            open_sign_editor.in_set(NetworkingSystemsSet::Between),
+            (populate_sign_editor, on_save_sign).chain().in_set(UiSystemSet::DoUi),
+        )
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    )
+    .register_type::<OpenSignEditor>();
+}
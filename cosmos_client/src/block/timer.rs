@@ -0,0 +1,255 @@
+//! The menu used to change how often a timer pulses
+
+use bevy::{a11y::Focus, color::palettes::css, prelude::*};
+use cosmos_core::{
+    block::specific_blocks::timer::{OpenTimerSettingsEvent, SetTimerSettingsEvent, TimerSettings, MAX_TIMER_INTERVAL},
+    ecs::NeedsDespawned,
+    logic::LOGIC_TICKS_PER_SECOND,
+    netty::{
+        sync::{
+            events::client_event::{NettyEventReceived, NettyEventWriter},
+            mapping::{Mappable, NetworkMapping},
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::{Structure, StructureBlock},
+    state::GameState,
+};
+
+use crate::{
+    lang::Localization,
+    rendering::MainCamera,
+    ui::{
+        components::{
+            button::{register_button, Button, ButtonEvent, ButtonStyles},
+            text_input::{InputType, InputValue, TextInput},
+            window::GuiWindow,
+        },
+        font::DefaultFont,
+        OpenMenu, UiSystemSet,
+    },
+};
+
+#[derive(Component, Debug)]
+struct OpenTimerSettings(StructureBlock);
+
+#[derive(Component, Debug)]
+struct TimerIntervalInput;
+
+#[derive(Component, Debug)]
+struct TimerPulseLengthInput;
+
+#[derive(Event, Debug)]
+struct SaveTimerClicked;
+
+impl ButtonEvent for SaveTimerClicked {
+    fn create_event(_: Entity) -> Self {
+        Self
+    }
+}
+
+fn ticks_to_seconds(ticks: u32) -> String {
+    format!("{}", ticks as f32 / LOGIC_TICKS_PER_SECOND as f32)
+}
+
+fn seconds_to_ticks(seconds: &str) -> Option<u32> {
+    let seconds = seconds.parse::<f32>().ok()?;
+
+    Some((seconds * LOGIC_TICKS_PER_SECOND as f32).round() as u32)
+}
+
+fn open_timer_settings(
+    mut commands: Commands,
+    q_open_settings: Query<Entity, With<OpenTimerSettings>>,
+    mut nevr_open_settings: EventReader<NettyEventReceived<OpenTimerSettingsEvent>>,
+    network_mapping: Res<NetworkMapping>,
+) {
+    let Some(ev) = nevr_open_settings.read().last() else {
+        return;
+    };
+
+    if let Ok(ent) = q_open_settings.get_single() {
+        commands.entity(ent).insert(NeedsDespawned);
+    }
+
+    let Ok(block) = ev.0.map(&network_mapping) else {
+        error!("Bad network mapping - {:?}", ev.0);
+        return;
+    };
+
+    commands.spawn((OpenTimerSettings(block), Name::new("Open Timer Settings")));
+}
+
+fn populate_timer_settings(
+    mut commands: Commands,
+    q_added_settings: Query<(Entity, &OpenTimerSettings), Added<OpenTimerSettings>>,
+    q_cam: Query<Entity, With<MainCamera>>,
+    q_structure: Query<&Structure>,
+    q_timer_settings: Query<&TimerSettings>,
+    font: Res<DefaultFont>,
+    localization: Res<Localization>,
+    mut focus: ResMut<Focus>,
+) {
+    for (ent, open_settings) in q_added_settings.iter() {
+        let Ok(cam) = q_cam.get_single() else {
+            return;
+        };
+
+        let settings = q_structure
+            .get(open_settings.0.structure())
+            .ok()
+            .and_then(|s| s.query_block_data(open_settings.0.coords(), &q_timer_settings))
+            .copied()
+            .unwrap_or_default();
+
+        let text_style = TextFont {
+            font: font.0.clone_weak(),
+            font_size: 24.0,
+            ..Default::default()
+        };
+
+        let input_type = || InputType::Decimal {
+            min: 0.0,
+            max: (MAX_TIMER_INTERVAL / LOGIC_TICKS_PER_SECOND as u32) as f64,
+        };
+
+        let input_node = Node {
+            border: UiRect::all(Val::Px(2.0)),
+            width: Val::Percent(100.0),
+            padding: UiRect::all(Val::Px(4.0)),
+            margin: UiRect::bottom(Val::Px(10.0)),
+            ..Default::default()
+        };
+
+        let mut ecmds = commands.entity(ent);
+
+        ecmds.insert((
+            TargetCamera(cam),
+            OpenMenu::new(0),
+            BackgroundColor(Srgba::hex("2D2D2D").unwrap().into()),
+            Node {
+                width: Val::Px(400.0),
+                margin: UiRect::all(Val::Auto),
+                ..Default::default()
+            },
+            GuiWindow {
+                title: localization.get("cosmos:window.timer").into(),
+                body_styles: Node {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(20.0)),
+                    ..Default::default()
+                },
+            },
+        ));
+
+        ecmds.with_children(|p| {
+            p.spawn((Text::new("Interval (seconds)"), text_style.clone()));
+
+            let interval_ent = p
+                .spawn((
+                    TimerIntervalInput,
+                    text_style.clone(),
+                    TextInput {
+                        input_type: input_type(),
+                        ..Default::default()
+                    },
+                    InputValue::new(ticks_to_seconds(settings.interval)),
+                    BorderColor(Srgba::hex("555555").unwrap().into()),
+                    BackgroundColor(Srgba::hex("111111").unwrap().into()),
+                    input_node.clone(),
+                ))
+                .id();
+
+            focus.0 = Some(interval_ent);
+
+            p.spawn((Text::new("Pulse Length (seconds)"), text_style.clone()));
+
+            p.spawn((
+                TimerPulseLengthInput,
+                text_style.clone(),
+                TextInput {
+                    input_type: input_type(),
+                    ..Default::default()
+                },
+                InputValue::new(ticks_to_seconds(settings.pulse_length)),
+                BorderColor(Srgba::hex("555555").unwrap().into()),
+                BackgroundColor(Srgba::hex("111111").unwrap().into()),
+                input_node,
+            ));
+
+            p.spawn((
+                Name::new("Save Timer Button"),
+                Node {
+                    height: Val::Px(50.0),
+                    margin: UiRect::top(Val::Px(10.0)),
+                    ..Default::default()
+                },
+                Button::<SaveTimerClicked> {
+                    button_styles: Some(ButtonStyles {
+                        background_color: Srgba::hex("555555").unwrap().into(),
+                        hover_background_color: Srgba::hex("777777").unwrap().into(),
+                        press_background_color: Srgba::hex("333333").unwrap().into(),
+                        foreground_color: css::WHITE.into(),
+                        hover_foreground_color: css::WHITE.into(),
+                        press_foreground_color: css::WHITE.into(),
+                    }),
+                    text: Some(("Save".into(), text_style, Default::default())),
+                    ..Default::default()
+                },
+            ));
+        });
+    }
+}
+
+fn on_save_timer(
+    mut commands: Commands,
+    mut evr_save: EventReader<SaveTimerClicked>,
+    q_open_settings: Query<(Entity, &OpenTimerSettings)>,
+    q_interval: Query<&InputValue, With<TimerIntervalInput>>,
+    q_pulse_length: Query<&InputValue, With<TimerPulseLengthInput>>,
+    mut nevw_set_timer_settings: NettyEventWriter<SetTimerSettingsEvent>,
+    network_mapping: Res<NetworkMapping>,
+) {
+    if evr_save.read().next().is_none() {
+        return;
+    }
+
+    let Ok((ent, open_settings)) = q_open_settings.get_single() else {
+        return;
+    };
+
+    let (Ok(interval), Ok(pulse_length)) = (q_interval.get_single(), q_pulse_length.get_single()) else {
+        return;
+    };
+
+    let (Some(interval), Some(pulse_length)) = (seconds_to_ticks(interval.value()), seconds_to_ticks(pulse_length.value())) else {
+        return;
+    };
+
+    let settings = TimerSettings { interval, pulse_length };
+
+    // Leave the menu open so the player can fix their input
+    if !settings.is_valid() {
+        return;
+    }
+
+    if let Ok(block) = open_settings.0.map_to_server(&network_mapping) {
+        nevw_set_timer_settings.send(SetTimerSettingsEvent { block, settings });
+    }
+
+    commands.entity(ent).insert(NeedsDespawned);
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<SaveTimerClicked>(app);
+
+    app.add_systems(
+        Update,
+        (
+            open_timer_settings.in_set(NetworkingSystemsSet::Between),
+            (populate_timer_settings, on_save_timer).chain().in_set(UiSystemSet::DoUi),
+        )
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,320 @@
+//! Renders the portals of linked warp gates, which brighten as they charge, and the flashes of ships going through them.
+
+use bevy::{
+    color::palettes::css,
+    pbr::{NotShadowCaster, NotShadowReceiver},
+    prelude::*,
+};
+use cosmos_core::{
+    block::{
+        data::BlockData,
+        multiblock::machine::MultiblockPattern,
+        specific_blocks::warp_gate::{
+            gate_center_offset, WarpGateChargeEvent, WarpGateDestination, WarpGateJumpEvent, WARP_GATE_CHARGE_TIME,
+            WARP_GATE_OPENING_HALF_WIDTH, WARP_GATE_PATTERN,
+        },
+    },
+    ecs::NeedsDespawned,
+    netty::{
+        sync::{
+            events::client_event::NettyEventReceived,
+            mapping::{Mappable, NetworkMapping},
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    physics::location::Location,
+    prelude::Structure,
+    registry::Registry,
+    state::GameState,
+};
+
+use crate::netty::gameplay::sync::interpolation::InterpolationBuffer;
+
+/// How see-through a portal is when it isn't charging
+const IDLE_ALPHA: f32 = 0.15;
+/// How see-through a portal is when it is fully charged
+const CHARGED_ALPHA: f32 = 0.6;
+
+/// How long (in seconds) the flash of a ship going through a gate lasts
+const FLASH_DURATION: f32 = 1.0;
+/// How much larger than the gate's opening a flash grows before it fades out
+const FLASH_GROWTH: f32 = 4.0;
+
+#[derive(Component, Debug)]
+/// The surface filling a linked warp gate's opening
+struct WarpGatePortal {
+    data_entity: Entity,
+    material: Handle<StandardMaterial>,
+    /// The time (from [`Time::elapsed_secs`]) the gate started charging
+    charging_since: Option<f32>,
+}
+
+#[derive(Component, Debug)]
+/// Placed on the block data entity of a gate whose portal has been created
+struct RenderedPortal(Entity);
+
+#[derive(Component, Debug)]
+struct WarpFlash {
+    age: f32,
+    material: Handle<StandardMaterial>,
+}
+
+fn warp_material(color: Srgba) -> StandardMaterial {
+    StandardMaterial {
+        base_color: color.into(),
+        emissive: LinearRgba::from(color) * 2.0,
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        double_sided: true,
+        cull_mode: None,
+        ..Default::default()
+    }
+}
+
+fn remove_dead_portals(mut commands: Commands, q_portal: Query<(Entity, &WarpGatePortal)>, q_gate: Query<(), With<WarpGateDestination>>) {
+    for (entity, portal) in q_portal.iter() {
+        if q_gate.contains(portal.data_entity) {
+            continue;
+        }
+
+        commands.entity(entity).insert(NeedsDespawned);
+        if let Some(mut ecmds) = commands.get_entity(portal.data_entity) {
+            ecmds.remove::<RenderedPortal>();
+        }
+    }
+}
+
+fn create_portals(
+    mut commands: Commands,
+    q_new_gate: Query<(Entity, &BlockData), (With<WarpGateDestination>, Without<RenderedPortal>)>,
+    q_structure: Query<&Structure>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (data_entity, block_data) in q_new_gate.iter() {
+        let block = block_data.identifier.block;
+        let Ok(structure) = q_structure.get(block.structure()) else {
+            continue;
+        };
+
+        let rotation = structure.block_rotation(block.coords());
+        let material = materials.add(warp_material(css::DEEP_SKY_BLUE.with_alpha(IDLE_ALPHA)));
+        let size = WARP_GATE_OPENING_HALF_WIDTH * 2.0;
+
+        let portal = commands
+            .spawn((
+                Name::new("Warp Gate Portal"),
+                WarpGatePortal {
+                    data_entity,
+                    material: material.clone(),
+                    charging_since: None,
+                },
+                Mesh3d(meshes.add(Rectangle::new(size, size))),
+                MeshMaterial3d(material),
+                Transform::from_translation(structure.block_relative_position(block.coords()) + gate_center_offset(rotation))
+                    .with_rotation(rotation.as_quat()),
+                Visibility::Hidden,
+                NotShadowCaster,
+                NotShadowReceiver,
+            ))
+            .set_parent(block.structure())
+            .id();
+
+        commands.entity(data_entity).insert(RenderedPortal(portal));
+    }
+}
+
+/// Portals are only shown while their gate is fully built
+fn show_formed_portals(
+    mut q_portal: Query<(&WarpGatePortal, &mut Visibility)>,
+    q_block_data: Query<&BlockData>,
+    q_structure: Query<&Structure>,
+    patterns: Res<Registry<MultiblockPattern>>,
+) {
+    let Some(pattern) = patterns.from_id(WARP_GATE_PATTERN) else {
+        return;
+    };
+
+    for (portal, mut visibility) in q_portal.iter_mut() {
+        let Ok(block_data) = q_block_data.get(portal.data_entity) else {
+            continue;
+        };
+
+        let block = block_data.identifier.block;
+        let formed = q_structure
+            .get(block.structure())
+            .is_ok_and(|structure| pattern.check(structure, block.coords()).is_ok());
+
+        visibility.set_if_neq(if formed { Visibility::Inherited } else { Visibility::Hidden });
+    }
+}
+
+fn on_gate_charge(
+    mut nevr_charge: EventReader<NettyEventReceived<WarpGateChargeEvent>>,
+    network_mapping: Res<NetworkMapping>,
+    q_structure: Query<&Structure>,
+    q_rendered: Query<&RenderedPortal>,
+    mut q_portal: Query<&mut WarpGatePortal>,
+    time: Res<Time>,
+) {
+    for ev in nevr_charge.read() {
+        let Ok(gate) = ev.gate.map(&network_mapping) else {
+            continue;
+        };
+
+        let Some(mut portal) = q_structure
+            .get(gate.structure())
+            .ok()
+            .and_then(|structure| structure.block_data(gate.coords()))
+            .and_then(|data_entity| q_rendered.get(data_entity).ok())
+            .and_then(|rendered| q_portal.get_mut(rendered.0).ok())
+        else {
+            continue;
+        };
+
+        portal.charging_since = ev.charging.then_some(time.elapsed_secs());
+    }
+}
+
+/// Portals brighten and pulse faster the closer they are to being charged
+fn animate_portals(q_portal: Query<&WarpGatePortal>, mut materials: ResMut<Assets<StandardMaterial>>, time: Res<Time>) {
+    let now = time.elapsed_secs();
+
+    for portal in q_portal.iter() {
+        let Some(material) = materials.get_mut(&portal.material) else {
+            continue;
+        };
+
+        let progress = portal
+            .charging_since
+            .map(|since| ((now - since) / WARP_GATE_CHARGE_TIME).clamp(0.0, 1.0))
+            .unwrap_or(0.0);
+
+        let pulse = (now * (2.0 + 6.0 * progress)).sin() * 0.5 + 0.5;
+        let alpha = IDLE_ALPHA + (CHARGED_ALPHA - IDLE_ALPHA) * progress + 0.1 * pulse * progress;
+
+        material.base_color.set_alpha(alpha);
+    }
+}
+
+fn spawn_flash(
+    commands: &mut Commands,
+    location: Location,
+    rotation: Quat,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) {
+    let material = materials.add(warp_material(css::WHITE.with_alpha(0.8)));
+
+    commands.spawn((
+        Name::new("Warp Flash"),
+        WarpFlash {
+            age: 0.0,
+            material: material.clone(),
+        },
+        location,
+        Transform::from_rotation(rotation),
+        Mesh3d(meshes.add(Circle::new(WARP_GATE_OPENING_HALF_WIDTH))),
+        MeshMaterial3d(material),
+        NotShadowCaster,
+        NotShadowReceiver,
+    ));
+}
+
+fn on_gate_jump(
+    mut commands: Commands,
+    mut nevr_jump: EventReader<NettyEventReceived<WarpGateJumpEvent>>,
+    network_mapping: Res<NetworkMapping>,
+    q_structure: Query<(&Structure, &Location, &GlobalTransform)>,
+    q_rendered: Query<&RenderedPortal>,
+    mut q_portal: Query<&mut WarpGatePortal>,
+    mut q_interpolation: Query<&mut InterpolationBuffer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for ev in nevr_jump.read() {
+        // Otherwise the ship would be seen flying across the sector to the other gate
+        if let Some(mut buffer) = network_mapping
+            .client_from_server(&ev.ship)
+            .and_then(|ship| q_interpolation.get_mut(ship).ok())
+        {
+            buffer.clear();
+        }
+
+        if let Some((gate, (structure, loc, g_trans))) = ev
+            .gate
+            .map(&network_mapping)
+            .ok()
+            .and_then(|gate| q_structure.get(gate.structure()).ok().map(|x| (gate, x)))
+        {
+            let rotation = structure.block_rotation(gate.coords());
+            let structure_rotation = g_trans.compute_transform().rotation;
+            let gate_location =
+                structure.block_world_location(gate.coords(), g_trans, loc) + structure_rotation * gate_center_offset(rotation);
+
+            spawn_flash(
+                &mut commands,
+                gate_location,
+                structure_rotation * rotation.as_quat(),
+                &mut meshes,
+                &mut materials,
+            );
+
+            if let Some(mut portal) = structure
+                .block_data(gate.coords())
+                .and_then(|data_entity| q_rendered.get(data_entity).ok())
+                .and_then(|rendered| q_portal.get_mut(rendered.0).ok())
+            {
+                portal.charging_since = None;
+            }
+        }
+
+        spawn_flash(
+            &mut commands,
+            ev.destination.location,
+            ev.destination.rotation,
+            &mut meshes,
+            &mut materials,
+        );
+    }
+}
+
+fn update_flashes(
+    mut commands: Commands,
+    mut q_flash: Query<(Entity, &mut WarpFlash, &mut Transform)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time>,
+) {
+    for (entity, mut flash, mut transform) in q_flash.iter_mut() {
+        flash.age += time.delta_secs();
+
+        let t = flash.age / FLASH_DURATION;
+        if t >= 1.0 {
+            commands.entity(entity).insert(NeedsDespawned);
+            continue;
+        }
+
+        transform.scale = Vec3::splat(1.0 + t * FLASH_GROWTH);
+        if let Some(material) = materials.get_mut(&flash.material) {
+            material.base_color.set_alpha(0.8 * (1.0 - t));
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            remove_dead_portals,
+            create_portals,
+            show_formed_portals,
+            on_gate_charge,
+            on_gate_jump,
+            animate_portals,
+            update_flashes,
+        )
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
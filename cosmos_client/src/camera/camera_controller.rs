@@ -16,6 +16,8 @@ use crate::{
     window::setup::{CursorFlags, CursorFlagsSet, DeltaCursorPosition},
 };
 
+use super::photo_mode::not_in_photo_mode;
+
 /// Attach this to the player to give it a first person camera
 #[derive(Component, Default, Debug)]
 pub struct CameraHelper {
@@ -57,6 +59,7 @@ pub(super) fn register(app: &mut App) {
     app.add_systems(
         Update,
         process_player_camera
+            .run_if(not_in_photo_mode)
             .in_set(NetworkingSystemsSet::Between)
             .after(CursorFlagsSet::ApplyCursorFlagsUpdates)
             .run_if(in_state(GameState::Playing)),
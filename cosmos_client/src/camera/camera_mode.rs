@@ -0,0 +1,241 @@
+//! Lets the player switch between viewing the world in first person and third person.
+//!
+//! In third person, the camera follows behind the player while on foot, and orbits the ship while piloting.
+//!
+//! Gameplay logic (movement, steering, sending where the player is looking) is all based on the
+//! first-person camera [`Transform`]. To avoid every system having to care about the camera mode,
+//! the third-person offset is only applied right before transforms are propagated for rendering,
+//! and the first-person transform is restored at the start of the next frame.
+//!
+//! The camera's [`GlobalTransform`] still includes the offset, so anything that needs to know where the
+//! player is looking from (such as raycasting) should use [`PlayerEye`] instead.
+
+use std::f32::consts::PI;
+
+use bevy::{ecs::system::SystemParam, input::mouse::MouseWheel, prelude::*, transform::TransformSystem};
+use bevy_rapier3d::{
+    pipeline::QueryFilter,
+    plugin::ReadRapierContext,
+    prelude::{CollisionGroups, Group},
+};
+use cosmos_core::{
+    netty::{client::LocalPlayer, system_sets::NetworkingSystemsSet},
+    physics::structure_physics::ChunkPhysicsPart,
+    state::GameState,
+    structure::{shields::SHIELD_COLLISION_GROUP, ship::pilot::Pilot},
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    rendering::MainCamera,
+    settings::MouseSensitivity,
+    ui::components::show_cursor::no_open_menus,
+    window::setup::{CursorFlags, DeltaCursorPosition},
+};
+
+use super::photo_mode::not_in_photo_mode;
+
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// How the player's camera is currently viewing the world
+pub enum CameraMode {
+    #[default]
+    /// Looking out of the player's eyes (or the selected ship camera)
+    FirstPerson,
+    /// Following behind the player, or orbiting the ship while piloting
+    ThirdPerson,
+}
+
+impl CameraMode {
+    /// If the player is holding the orbit key in third person, meaning mouse movement should move the
+    /// orbit camera rather than steering the ship.
+    pub fn is_orbiting(&self, inputs: &impl InputHandler) -> bool {
+        *self == Self::ThirdPerson && inputs.check_pressed(CosmosInputs::OrbitCamera)
+    }
+}
+
+#[derive(SystemParam)]
+/// Where the local player is looking from in first person, regardless of the [`CameraMode`].
+///
+/// Use this instead of the [`MainCamera`]'s [`GlobalTransform`] for anything that depends on where the player
+/// is looking, since that is moved behind the player in third person.
+pub struct PlayerEye<'w, 's> {
+    q_player: Query<'w, 's, &'static GlobalTransform, With<LocalPlayer>>,
+    q_camera: Query<'w, 's, &'static Transform, With<MainCamera>>,
+}
+
+impl PlayerEye<'_, '_> {
+    /// The global transform of the player's eye - the first-person camera.
+    ///
+    /// Returns `None` if there is no local player or camera.
+    pub fn global_transform(&self) -> Option<GlobalTransform> {
+        let player_g_trans = self.q_player.get_single().ok()?;
+        let camera_trans = self.q_camera.get_single().ok()?;
+
+        // The camera is a child of the player, and its transform is always the first-person one outside of rendering
+        Some(player_g_trans.mul_transform(*camera_trans))
+    }
+}
+
+#[derive(Resource, Debug, Clone, Copy)]
+/// The state of the orbit camera used while piloting in third person
+pub struct OrbitCamera {
+    /// Rotation around the ship's up axis
+    pub yaw: f32,
+    /// Rotation around the ship's right axis
+    pub pitch: f32,
+    /// How far away from the ship the camera wants to be
+    pub distance: f32,
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        Self {
+            yaw: 0.0,
+            pitch: 0.0,
+            distance: DEFAULT_ORBIT_DISTANCE,
+        }
+    }
+}
+
+const THIRD_PERSON_DISTANCE: f32 = 4.0;
+const DEFAULT_ORBIT_DISTANCE: f32 = 30.0;
+const MIN_ORBIT_DISTANCE: f32 = 5.0;
+const MAX_ORBIT_DISTANCE: f32 = 200.0;
+/// How far the camera is kept from anything it would otherwise clip into
+const CAMERA_COLLISION_PADDING: f32 = 0.3;
+
+#[derive(Component, Debug)]
+/// The first-person transform of the camera, stored while the third-person offset is applied.
+struct FirstPersonTransform(Transform);
+
+fn toggle_camera_mode(inputs: InputChecker, mut camera_mode: ResMut<CameraMode>, mut orbit: ResMut<OrbitCamera>) {
+    if !inputs.check_just_pressed(CosmosInputs::ToggleCameraMode) {
+        return;
+    }
+
+    *camera_mode = match *camera_mode {
+        CameraMode::FirstPerson => CameraMode::ThirdPerson,
+        CameraMode::ThirdPerson => CameraMode::FirstPerson,
+    };
+
+    orbit.yaw = 0.0;
+    orbit.pitch = 0.0;
+}
+
+/// While the orbit key is held, the mouse moves the orbit camera rather than steering the ship.
+fn control_orbit_camera(
+    inputs: InputChecker,
+    camera_mode: Res<CameraMode>,
+    q_pilot: Query<(), (With<LocalPlayer>, With<Pilot>)>,
+    mut orbit: ResMut<OrbitCamera>,
+    cursor_delta: Res<DeltaCursorPosition>,
+    cursor_flags: Res<CursorFlags>,
+    sensitivity: Res<MouseSensitivity>,
+    mut evr_mouse_wheel: EventReader<MouseWheel>,
+) {
+    if q_pilot.is_empty() || !camera_mode.is_orbiting(&inputs) {
+        evr_mouse_wheel.clear();
+        return;
+    }
+
+    if cursor_flags.is_cursor_locked() {
+        orbit.yaw -= cursor_delta.x * 0.005 * sensitivity.0;
+        orbit.pitch = (orbit.pitch - cursor_delta.y * 0.005 * sensitivity.0).clamp(-PI / 2.0 + 0.001, PI / 2.0 - 0.001);
+    }
+
+    for ev in evr_mouse_wheel.read() {
+        // Zoom proportionally so it feels the same up close and far away
+        orbit.distance = (orbit.distance * (1.0 - ev.y.signum() * 0.1)).clamp(MIN_ORBIT_DISTANCE, MAX_ORBIT_DISTANCE);
+    }
+}
+
+pub(super) fn restore_first_person_transform(mut commands: Commands, mut q_camera: Query<(Entity, &mut Transform, &FirstPersonTransform)>) {
+    for (entity, mut transform, first_person) in q_camera.iter_mut() {
+        *transform = first_person.0;
+        commands.entity(entity).remove::<FirstPersonTransform>();
+    }
+}
+
+pub(super) fn apply_third_person_offset(
+    mut commands: Commands,
+    camera_mode: Res<CameraMode>,
+    orbit: Res<OrbitCamera>,
+    q_player: Query<(Entity, &GlobalTransform, Option<&Pilot>), With<LocalPlayer>>,
+    mut q_camera: Query<(Entity, &mut Transform), With<MainCamera>>,
+    q_chunk_physics_part: Query<&ChunkPhysicsPart>,
+    rapier_context_access: ReadRapierContext,
+) {
+    if *camera_mode != CameraMode::ThirdPerson {
+        return;
+    }
+
+    let Ok((player_entity, player_g_trans, pilot)) = q_player.get_single() else {
+        return;
+    };
+
+    let Ok((camera_entity, mut camera_trans)) = q_camera.get_single_mut() else {
+        return;
+    };
+
+    let first_person = *camera_trans;
+
+    let (rotation, distance) = match pilot {
+        Some(_) => (
+            first_person.rotation * Quat::from_euler(EulerRot::YXZ, orbit.yaw, orbit.pitch, 0.0),
+            orbit.distance,
+        ),
+        None => (first_person.rotation, THIRD_PERSON_DISTANCE),
+    };
+
+    let focus = player_g_trans.transform_point(first_person.translation);
+    let backwards = (player_g_trans.rotation() * rotation * Vec3::Z).normalize_or_zero();
+
+    // While piloting, the camera starts inside the ship, so only other structures should block it.
+    let own_structure = pilot.map(|p| p.entity);
+    let not_own_structure =
+        |e: Entity| own_structure.is_none_or(|own| q_chunk_physics_part.get(e).ok().map(|x| x.structure_entity) != Some(own));
+
+    let rapier_context = rapier_context_access.single();
+    let distance = rapier_context
+        .cast_ray(
+            focus,
+            backwards,
+            distance,
+            true,
+            QueryFilter::new()
+                .exclude_rigid_body(player_entity)
+                .groups(CollisionGroups::new(
+                    Group::ALL & !SHIELD_COLLISION_GROUP,
+                    Group::ALL & !SHIELD_COLLISION_GROUP,
+                ))
+                .predicate(&not_own_structure),
+        )
+        .map(|(_, toi)| (toi - CAMERA_COLLISION_PADDING).max(0.0))
+        .unwrap_or(distance);
+
+    camera_trans.rotation = rotation;
+    camera_trans.translation = first_person.translation + rotation * Vec3::Z * distance;
+
+    commands.entity(camera_entity).insert(FirstPersonTransform(first_person));
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<CameraMode>()
+        .init_resource::<OrbitCamera>()
+        .add_systems(PreUpdate, restore_first_person_transform)
+        .add_systems(
+            Update,
+            (toggle_camera_mode, control_orbit_camera)
+                .chain()
+                .run_if(no_open_menus)
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            PostUpdate,
+            apply_third_person_offset
+                .before(TransformSystem::TransformPropagate)
+                .run_if(not_in_photo_mode)
+                .run_if(in_state(GameState::Playing)),
+        );
+}
@@ -0,0 +1,107 @@
+//! Shakes the camera for things like weapons firing or the player's ship being hit.
+//!
+//! Add to the [`CameraShake`] resource's trauma to shake the camera. Just like the third-person offset (see
+//! [`super::camera_mode`]), the shake is only applied right before transforms are propagated, and is removed at the
+//! start of the next frame so gameplay logic never sees it.
+//!
+//! How strong the shake is can be changed with the `cosmos:camera_shake` setting.
+
+use bevy::{prelude::*, transform::TransformSystem};
+use cosmos_core::{registry::Registry, state::GameState};
+use rand::Rng;
+
+use crate::{
+    rendering::MainCamera,
+    settings::{Setting, SettingsRegistry},
+};
+
+use super::{
+    camera_mode::{apply_third_person_offset, restore_first_person_transform},
+    photo_mode::not_in_photo_mode,
+};
+
+/// How far the camera can be moved by a full strength shake
+const MAX_SHAKE_OFFSET: f32 = 0.15;
+/// How far the camera can be rotated (in radians) by a full strength shake
+const MAX_SHAKE_ANGLE: f32 = 0.02;
+/// How much trauma goes away every second
+const TRAUMA_DECAY: f32 = 1.5;
+
+#[derive(Resource, Debug, Default)]
+/// How much the camera should currently be shaking
+pub struct CameraShake {
+    trauma: f32,
+}
+
+impl CameraShake {
+    /// Shakes the camera more. Trauma is capped at 1, and goes away over time.
+    ///
+    /// The shake grows with the square of the trauma, so small amounts (around 0.1) barely register while 1 is violent.
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    /// The current trauma, from 0 to 1
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+}
+
+#[derive(Component, Debug)]
+/// The camera's transform before the shake was applied
+struct UnshakenTransform(Transform);
+
+fn restore_unshaken_transform(mut commands: Commands, mut q_camera: Query<(Entity, &mut Transform, &UnshakenTransform)>) {
+    for (entity, mut transform, unshaken) in q_camera.iter_mut() {
+        *transform = unshaken.0;
+        commands.entity(entity).remove::<UnshakenTransform>();
+    }
+}
+
+fn apply_camera_shake(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<Registry<Setting>>,
+    mut shake: ResMut<CameraShake>,
+    mut q_camera: Query<(Entity, &mut Transform), With<MainCamera>>,
+) {
+    if shake.trauma <= 0.0 {
+        return;
+    }
+
+    let strength = shake.trauma * shake.trauma * settings.i32_or("cosmos:camera_shake", 100).clamp(0, 200) as f32 / 100.0;
+    shake.trauma = (shake.trauma - TRAUMA_DECAY * time.delta_secs()).max(0.0);
+
+    if strength <= 0.0 {
+        return;
+    }
+
+    let Ok((entity, mut transform)) = q_camera.get_single_mut() else {
+        return;
+    };
+
+    commands.entity(entity).insert(UnshakenTransform(*transform));
+
+    let mut rng = rand::thread_rng();
+    let mut rand_unit = || rng.gen_range(-1.0..=1.0_f32);
+
+    let offset = Vec3::new(rand_unit(), rand_unit(), rand_unit()) * MAX_SHAKE_OFFSET * strength;
+    let angle = MAX_SHAKE_ANGLE * strength;
+    let rotation = Quat::from_euler(EulerRot::YXZ, rand_unit() * angle, rand_unit() * angle, rand_unit() * angle);
+
+    transform.translation += transform.rotation * offset;
+    transform.rotation *= rotation;
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<CameraShake>()
+        .add_systems(PreUpdate, restore_unshaken_transform.before(restore_first_person_transform))
+        .add_systems(
+            PostUpdate,
+            apply_camera_shake
+                .after(apply_third_person_offset)
+                .before(TransformSystem::TransformPropagate)
+                .run_if(not_in_photo_mode)
+                .run_if(in_state(GameState::Playing)),
+        );
+}
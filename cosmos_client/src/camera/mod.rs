@@ -3,7 +3,13 @@
 use bevy::prelude::App;
 
 pub mod camera_controller;
+pub mod camera_mode;
+pub mod camera_shake;
+pub mod photo_mode;
 
 pub(super) fn register(app: &mut App) {
     camera_controller::register(app);
+    camera_mode::register(app);
+    camera_shake::register(app);
+    photo_mode::register(app);
 }
@@ -0,0 +1,274 @@
+//! Photo mode hides all the UI and lets the player fly the camera around (within a limited radius)
+//! to take screenshots.
+//!
+//! Like the third-person camera, the photo camera is only applied right before transforms are
+//! propagated and the first-person transform is restored at the start of the next frame, so the
+//! player's gameplay logic never sees the photo camera.
+
+use std::{f32::consts::PI, path::PathBuf};
+
+use bevy::{
+    input::mouse::MouseWheel,
+    prelude::*,
+    render::view::screenshot::{save_to_disk, Screenshot},
+    transform::TransformSystem,
+};
+use cosmos_core::{netty::system_sets::NetworkingSystemsSet, registry::Registry, state::GameState};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    rendering::MainCamera,
+    settings::{DesiredFov, MouseSensitivity, Setting, SettingsRegistry},
+    ui::components::show_cursor::ShowCursor,
+    window::setup::{CursorFlags, CursorFlagsSet, DeltaCursorPosition},
+};
+
+/// How far the photo camera can move away from the player's eyes
+const MAX_PHOTO_DISTANCE: f32 = 20.0;
+const PHOTO_CAMERA_SPEED: f32 = 4.0;
+const PHOTO_CAMERA_SPRINT_MULTIPLIER: f32 = 4.0;
+/// Radians per second
+const ROLL_SPEED: f32 = PI / 2.0;
+const MIN_PHOTO_FOV: f32 = 10.0;
+const MAX_PHOTO_FOV: f32 = 120.0;
+
+#[derive(Resource, Debug, Clone, Copy)]
+/// If this resource exists, the player is in photo mode.
+///
+/// The camera is positioned relative to where it would normally be (the player's eyes).
+pub struct PhotoMode {
+    /// Offset from the first-person camera position, relative to the camera's parent
+    pub offset: Vec3,
+    /// Rotation around the camera's up axis, relative to the first-person rotation when photo mode was entered
+    pub yaw: f32,
+    /// Rotation around the camera's right axis
+    pub pitch: f32,
+    /// Rotation around the camera's forward axis
+    pub roll: f32,
+    /// The field of view (in degrees) while in photo mode
+    pub fov: f32,
+    /// The first-person rotation when photo mode was entered, so looking around in first person
+    /// doesn't affect the photo camera
+    base_rotation: Quat,
+}
+
+impl PhotoMode {
+    fn new(base_rotation: Quat, fov: f32) -> Self {
+        Self {
+            offset: Vec3::ZERO,
+            yaw: 0.0,
+            pitch: 0.0,
+            roll: 0.0,
+            fov,
+            base_rotation,
+        }
+    }
+
+    /// The rotation of the photo camera, relative to the camera's parent
+    pub fn rotation(&self) -> Quat {
+        self.base_rotation * Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, self.roll)
+    }
+}
+
+#[derive(Component, Debug)]
+/// The first-person transform of the camera, stored while the photo camera is applied.
+struct PhotoModeFirstPersonTransform(Transform);
+
+#[derive(Component, Debug)]
+/// The visibility a UI node had before photo mode hid it
+struct HiddenByPhotoMode(Visibility);
+
+/// A run condition that returns true if the player is not in photo mode
+pub fn not_in_photo_mode(photo_mode: Option<Res<PhotoMode>>) -> bool {
+    photo_mode.is_none()
+}
+
+fn toggle_photo_mode(
+    mut commands: Commands,
+    inputs: InputChecker,
+    photo_mode: Option<Res<PhotoMode>>,
+    desired_fov: Res<DesiredFov>,
+    q_camera: Query<&Transform, With<MainCamera>>,
+    q_show_cursor: Query<(), With<ShowCursor>>,
+) {
+    if photo_mode.is_some() {
+        // Opening any menu (such as the pause menu) leaves photo mode so the menu is actually visible
+        if inputs.check_just_pressed(CosmosInputs::TogglePhotoMode) || !q_show_cursor.is_empty() {
+            commands.remove_resource::<PhotoMode>();
+        }
+
+        return;
+    }
+
+    if !inputs.check_just_pressed(CosmosInputs::TogglePhotoMode) || !q_show_cursor.is_empty() {
+        return;
+    }
+
+    let Ok(camera_trans) = q_camera.get_single() else {
+        return;
+    };
+
+    commands.insert_resource(PhotoMode::new(camera_trans.rotation, desired_fov.0));
+}
+
+fn control_photo_camera(
+    inputs: InputChecker,
+    time: Res<Time>,
+    mut photo_mode: ResMut<PhotoMode>,
+    desired_fov: Res<DesiredFov>,
+    cursor_delta: Res<DeltaCursorPosition>,
+    cursor_flags: Res<CursorFlags>,
+    sensitivity: Res<MouseSensitivity>,
+    mut evr_mouse_wheel: EventReader<MouseWheel>,
+) {
+    if inputs.check_just_pressed(CosmosInputs::ResetPhotoCamera) {
+        let base_rotation = photo_mode.base_rotation;
+        *photo_mode = PhotoMode::new(base_rotation, desired_fov.0);
+    }
+
+    if cursor_flags.is_cursor_locked() {
+        photo_mode.yaw -= cursor_delta.x * 0.005 * sensitivity.0;
+        photo_mode.pitch = (photo_mode.pitch + cursor_delta.y * 0.005 * sensitivity.0).clamp(-PI / 2.0 + 0.001, PI / 2.0 - 0.001);
+    }
+
+    let delta = time.delta_secs();
+
+    if inputs.check_pressed(CosmosInputs::RollLeft) {
+        photo_mode.roll += ROLL_SPEED * delta;
+    }
+    if inputs.check_pressed(CosmosInputs::RollRight) {
+        photo_mode.roll -= ROLL_SPEED * delta;
+    }
+
+    for ev in evr_mouse_wheel.read() {
+        photo_mode.fov = (photo_mode.fov - ev.y.signum() * 5.0).clamp(MIN_PHOTO_FOV, MAX_PHOTO_FOV);
+    }
+
+    let mut movement = Vec3::ZERO;
+    if inputs.check_pressed(CosmosInputs::MoveForward) {
+        movement -= Vec3::Z;
+    }
+    if inputs.check_pressed(CosmosInputs::MoveBackward) {
+        movement += Vec3::Z;
+    }
+    if inputs.check_pressed(CosmosInputs::MoveLeft) {
+        movement -= Vec3::X;
+    }
+    if inputs.check_pressed(CosmosInputs::MoveRight) {
+        movement += Vec3::X;
+    }
+    if inputs.check_pressed(CosmosInputs::MoveUp) {
+        movement += Vec3::Y;
+    }
+    if inputs.check_pressed(CosmosInputs::MoveDown) {
+        movement -= Vec3::Y;
+    }
+
+    let mut speed = PHOTO_CAMERA_SPEED;
+    if inputs.check_pressed(CosmosInputs::Sprint) {
+        speed *= PHOTO_CAMERA_SPRINT_MULTIPLIER;
+    }
+
+    let offset = photo_mode.offset + photo_mode.rotation() * movement.normalize_or_zero() * speed * delta;
+    photo_mode.offset = offset.clamp_length_max(MAX_PHOTO_DISTANCE);
+}
+
+fn hide_ui(mut commands: Commands, mut q_ui: Query<(Entity, &mut Visibility), (With<Node>, Without<Parent>, Without<HiddenByPhotoMode>)>) {
+    for (entity, mut vis) in q_ui.iter_mut() {
+        commands.entity(entity).insert(HiddenByPhotoMode(*vis));
+        *vis = Visibility::Hidden;
+    }
+}
+
+fn on_leave_photo_mode(
+    mut commands: Commands,
+    mut q_ui: Query<(Entity, &mut Visibility, &HiddenByPhotoMode)>,
+    mut q_projection: Query<&mut Projection, With<MainCamera>>,
+    desired_fov: Res<DesiredFov>,
+) {
+    for (entity, mut vis, hidden) in q_ui.iter_mut() {
+        *vis = hidden.0;
+        commands.entity(entity).remove::<HiddenByPhotoMode>();
+    }
+
+    for mut projection in q_projection.iter_mut() {
+        if let Projection::Perspective(persp) = projection.as_mut() {
+            persp.fov = desired_fov.0.to_radians();
+        }
+    }
+}
+
+fn take_photo(inputs: InputChecker, mut commands: Commands, settings: Res<Registry<Setting>>) {
+    if !inputs.check_just_pressed(CosmosInputs::TakePhoto) {
+        return;
+    }
+
+    let directory = PathBuf::from(settings.str_or("cosmos:screenshot_directory", "screenshots"));
+
+    if let Err(e) = std::fs::create_dir_all(&directory) {
+        error!("Unable to create screenshot directory {directory:?} - {e:?}");
+        return;
+    }
+
+    let file_name = format!("{}.png", chrono::Local::now().format("%Y-%m-%d_%H-%M-%S%.3f"));
+
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(directory.join(file_name)));
+}
+
+fn leave_photo_mode(mut commands: Commands) {
+    commands.remove_resource::<PhotoMode>();
+}
+
+fn restore_first_person_transform(mut commands: Commands, mut q_camera: Query<(Entity, &mut Transform, &PhotoModeFirstPersonTransform)>) {
+    for (entity, mut transform, first_person) in q_camera.iter_mut() {
+        *transform = first_person.0;
+        commands.entity(entity).remove::<PhotoModeFirstPersonTransform>();
+    }
+}
+
+fn apply_photo_camera(
+    mut commands: Commands,
+    photo_mode: Res<PhotoMode>,
+    mut q_camera: Query<(Entity, &mut Transform, &mut Projection), With<MainCamera>>,
+) {
+    let Ok((camera_entity, mut camera_trans, mut projection)) = q_camera.get_single_mut() else {
+        return;
+    };
+
+    let first_person = *camera_trans;
+
+    camera_trans.translation = first_person.translation + photo_mode.offset;
+    camera_trans.rotation = photo_mode.rotation();
+
+    if let Projection::Perspective(persp) = projection.as_mut() {
+        persp.fov = photo_mode.fov.to_radians();
+    }
+
+    commands.entity(camera_entity).insert(PhotoModeFirstPersonTransform(first_person));
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(PreUpdate, restore_first_person_transform)
+        .add_systems(
+            Update,
+            (
+                toggle_photo_mode,
+                (control_photo_camera, take_photo, hide_ui).run_if(resource_exists::<PhotoMode>),
+                on_leave_photo_mode.run_if(resource_removed::<PhotoMode>),
+            )
+                .chain()
+                .after(CursorFlagsSet::ApplyCursorFlagsUpdates)
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            PostUpdate,
+            apply_photo_camera
+                .before(TransformSystem::TransformPropagate)
+                .run_if(resource_exists::<PhotoMode>)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(OnExit(GameState::Playing), leave_photo_mode);
+}
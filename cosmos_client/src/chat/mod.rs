@@ -1,4 +1,4 @@
-//! Client-side chat logic
+//! Client-side chat logic. Messages starting with `#` are only sent to the player's party.
 
 use bevy::{
     a11y::Focus,
@@ -38,6 +38,9 @@ use crate::{
     },
 };
 
+/// Messages starting with this are only sent to the player's party
+const PARTY_CHAT_PREFIX: char = '#';
+
 #[derive(Component)]
 struct ChatContainer;
 
@@ -280,7 +283,12 @@ fn send_chat_msg(
         return;
     }
 
-    nevw.send(ClientSendChatMessageEvent::Global(value.to_owned()));
+    let message = match value.strip_prefix(PARTY_CHAT_PREFIX) {
+        Some(party_msg) => ClientSendChatMessageEvent::Party(party_msg.trim_start().to_owned()),
+        None => ClientSendChatMessageEvent::Global(value.to_owned()),
+    };
+
+    nevw.send(message);
 
     // Set val to "" in case toggle chat box and send message are bound to different keys
     val.set_value("");
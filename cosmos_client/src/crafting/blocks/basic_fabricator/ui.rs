@@ -1,8 +1,8 @@
 use bevy::{
     app::Update,
-    color::{palettes::css, Color, Srgba},
+    color::{Color, Srgba},
     core::Name,
-    log::{error, info},
+    log::{error, info, warn},
     prelude::{
         in_state, resource_exists, Added, App, BuildChildren, ChildBuild, Commands, Component, Entity, Event, EventReader,
         IntoSystemConfigs, Query, Res, Text, With,
@@ -25,6 +25,7 @@ use cosmos_core::{
         sync::{
             events::client_event::NettyEventWriter,
             mapping::{Mappable, NetworkMapping},
+            registry::client::RegistryIdMap,
         },
         system_sets::NetworkingSystemsSet,
     },
@@ -36,7 +37,7 @@ use cosmos_core::{
 use crate::{
     input::inputs::{CosmosInputs, InputChecker, InputHandler},
     inventory::{CustomInventoryRender, InventoryNeedsDisplayed, InventorySide},
-    lang::Lang,
+    lang::{Lang, Localization},
     rendering::MainCamera,
     ui::{
         components::{
@@ -46,6 +47,7 @@ use crate::{
         },
         font::DefaultFont,
         item_renderer::RenderItem,
+        theme::UiTheme,
         OpenMenu, UiSystemSet,
     },
 };
@@ -85,6 +87,7 @@ fn populate_menu(
     crafting_recipes: Res<BasicFabricatorRecipes>,
     items: Res<Registry<Item>>,
     lang: Res<Lang<Item>>,
+    localization: Res<Localization>,
     q_structure: Query<&Structure>,
     q_inventory: Query<&Inventory>,
     q_cam: Query<Entity, With<MainCamera>>,
@@ -139,7 +142,7 @@ fn populate_menu(
                 ..Default::default()
             },
             GuiWindow {
-                title: "Basic Fabricator".into(),
+                title: localization.get("cosmos:window.basic_fabricator").into(),
                 body_styles: Node {
                     flex_direction: FlexDirection::Column,
                     ..Default::default()
@@ -326,6 +329,7 @@ fn listen_create(
     mut evr_create: EventReader<CreateClickedEvent>,
     mut nevw_craft_event: NettyEventWriter<CraftBasicFabricatorRecipeEvent>,
     network_mapping: Res<NetworkMapping>,
+    item_ids: Res<RegistryIdMap<Item>>,
     input_handler: InputChecker,
 ) {
     for _ in evr_create.read() {
@@ -357,18 +361,20 @@ fn listen_create(
                 1
             };
 
+            let Some(recipe) = recipe.0.remap_item_ids(|id| item_ids.to_server(id)) else {
+                warn!("Recipe uses items the server doesn't have - {:?}", recipe.0);
+                continue;
+            };
+
             info!("Sending craft {quantity} event!");
 
-            nevw_craft_event.send(CraftBasicFabricatorRecipeEvent {
-                block,
-                recipe: recipe.0.clone(),
-                quantity,
-            });
+            nevw_craft_event.send(CraftBasicFabricatorRecipeEvent { block, recipe, quantity });
         }
     }
 }
 
 fn color_fabricate_button(
+    theme: Res<UiTheme>,
     q_open_fab_menu: Query<&OpenBasicFabricatorMenu>,
     q_structure: Query<&Structure>,
     q_selected_recipe: Query<&Recipe, With<SelectedRecipe>>,
@@ -398,14 +404,7 @@ fn color_fabricate_button(
     if recipe.0.max_can_create(inventory.iter().flatten()) == 0 {
         btn.button_styles = Some(ButtonStyles::default());
     } else {
-        btn.button_styles = Some(ButtonStyles {
-            background_color: css::GREEN.into(),
-            foreground_color: css::WHITE.into(),
-            hover_background_color: css::GREEN.into(),
-            hover_foreground_color: css::WHITE.into(),
-            press_background_color: css::GREEN.into(),
-            press_foreground_color: css::WHITE.into(),
-        });
+        btn.button_styles = Some(theme.positive_button_styles());
     }
 }
 
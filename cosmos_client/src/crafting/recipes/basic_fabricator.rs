@@ -1,16 +1,28 @@
 use bevy::{
     app::Update,
     log::info,
-    prelude::{App, Commands, EventReader, IntoSystemConfigs},
+    prelude::{App, Commands, EventReader, IntoSystemConfigs, Res},
 };
 use cosmos_core::{
     crafting::recipes::basic_fabricator::SyncBasicFabricatorRecipesEvent,
-    netty::{sync::events::client_event::NettyEventReceived, system_sets::NetworkingSystemsSet},
+    item::Item,
+    netty::{
+        sync::{events::client_event::NettyEventReceived, registry::client::RegistryIdMap},
+        system_sets::NetworkingSystemsSet,
+    },
 };
 
-fn sync_recipes(mut commands: Commands, mut nevr: EventReader<NettyEventReceived<SyncBasicFabricatorRecipesEvent>>) {
+fn sync_recipes(
+    mut commands: Commands,
+    item_ids: Res<RegistryIdMap<Item>>,
+    mut nevr: EventReader<NettyEventReceived<SyncBasicFabricatorRecipesEvent>>,
+) {
     for ev in nevr.read() {
-        let recipes = ev.0.clone();
+        let mut recipes = ev.0.clone();
+        if !item_ids.is_identity() {
+            recipes.remap_item_ids(|id| item_ids.to_client(id));
+        }
+
         info!("Received basic fabricator recipes from server {recipes:?}");
         commands.insert_resource(recipes);
     }
@@ -0,0 +1,557 @@
+//! The menu of market terminals, used to see every order on the server and post or cancel your own
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    economy::market::{
+        CancelMarketOrderEvent, MarketOrder, MarketOrderKind, MarketOrdersChangedEvent, MarketOrdersEvent, OpenMarketEvent,
+        PostMarketOrderEvent, MAX_MARKET_ORDER_QUANTITY,
+    },
+    ecs::NeedsDespawned,
+    entities::player::Player,
+    item::Item,
+    netty::{
+        client::LocalPlayer,
+        sync::{
+            events::client_event::{NettyEventReceived, NettyEventWriter},
+            mapping::{Mappable, NetworkMapping},
+            registry::client::RegistryIdMap,
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::StructureBlock,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+};
+
+use crate::{
+    lang::{Lang, Localization},
+    rendering::MainCamera,
+    ui::{
+        components::{
+            button::{register_button, Button, ButtonEvent, ButtonStyles},
+            scollable_container::ScrollBox,
+            text_input::{InputType, InputValue, TextInput},
+            window::GuiWindow,
+        },
+        font::DefaultFont,
+        OpenMenu, UiSystemSet,
+    },
+};
+
+#[derive(Resource, Debug, Default)]
+/// The last orders the server sent
+struct MarketOrders(Vec<MarketOrder>);
+
+#[derive(Component, Debug)]
+struct OpenMarket {
+    terminal: StructureBlock,
+    kind: MarketOrderKind,
+}
+
+#[derive(Component, Debug)]
+struct MarketOrderList;
+
+#[derive(Component, Debug)]
+struct OrderKindLabel;
+
+#[derive(Component, Debug)]
+struct MarketStatus;
+
+#[derive(Component, Debug)]
+struct ItemNameInput;
+
+#[derive(Component, Debug)]
+struct QuantityInput;
+
+#[derive(Component, Debug)]
+struct PriceInput;
+
+#[derive(Component, Debug)]
+struct KindChoice(MarketOrderKind);
+
+#[derive(Component, Debug)]
+struct CancelTarget(u64);
+
+#[derive(Event, Debug)]
+struct KindClicked(Entity);
+
+impl ButtonEvent for KindClicked {
+    fn create_event(entity: Entity) -> Self {
+        Self(entity)
+    }
+}
+
+#[derive(Event, Debug)]
+struct PostClicked;
+
+impl ButtonEvent for PostClicked {
+    fn create_event(_: Entity) -> Self {
+        Self
+    }
+}
+
+#[derive(Event, Debug)]
+struct CancelClicked(Entity);
+
+impl ButtonEvent for CancelClicked {
+    fn create_event(entity: Entity) -> Self {
+        Self(entity)
+    }
+}
+
+fn button_styles() -> ButtonStyles {
+    ButtonStyles {
+        background_color: Srgba::hex("555555").unwrap().into(),
+        hover_background_color: Srgba::hex("777777").unwrap().into(),
+        press_background_color: Srgba::hex("333333").unwrap().into(),
+        foreground_color: css::WHITE.into(),
+        hover_foreground_color: css::WHITE.into(),
+        press_foreground_color: css::WHITE.into(),
+    }
+}
+
+fn kind_description(kind: MarketOrderKind) -> &'static str {
+    match kind {
+        MarketOrderKind::Buy => "Posting a buy order",
+        MarketOrderKind::Sell => "Posting a sell order",
+    }
+}
+
+fn on_receive_orders(mut nevr_orders: EventReader<NettyEventReceived<MarketOrdersEvent>>, mut orders: ResMut<MarketOrders>) {
+    if let Some(ev) = nevr_orders.read().last() {
+        orders.0 = ev.orders.clone();
+    }
+}
+
+fn on_orders_changed(mut nevr_changed: EventReader<NettyEventReceived<MarketOrdersChangedEvent>>, mut orders: ResMut<MarketOrders>) {
+    for ev in nevr_changed.read() {
+        ev.apply(&mut orders.0);
+    }
+}
+
+fn open_market(
+    mut commands: Commands,
+    q_open: Query<Entity, With<OpenMarket>>,
+    mut nevr_open: EventReader<NettyEventReceived<OpenMarketEvent>>,
+    network_mapping: Res<NetworkMapping>,
+) {
+    let Some(ev) = nevr_open.read().last() else {
+        return;
+    };
+
+    if let Ok(ent) = q_open.get_single() {
+        commands.entity(ent).insert(NeedsDespawned);
+    }
+
+    let Ok(terminal) = ev.terminal.map(&network_mapping) else {
+        error!("Bad network mapping - {:?}", ev.terminal);
+        return;
+    };
+
+    commands.spawn((
+        OpenMarket {
+            terminal,
+            kind: MarketOrderKind::Buy,
+        },
+        Name::new("Open Market"),
+    ));
+}
+
+fn create_market_window(
+    mut commands: Commands,
+    q_added: Query<Entity, Added<OpenMarket>>,
+    q_cam: Query<Entity, With<MainCamera>>,
+    font: Res<DefaultFont>,
+    localization: Res<Localization>,
+) {
+    for ent in q_added.iter() {
+        let Ok(cam) = q_cam.get_single() else {
+            return;
+        };
+
+        let text_style = TextFont {
+            font: font.0.clone_weak(),
+            font_size: 20.0,
+            ..Default::default()
+        };
+
+        let input_node = Node {
+            border: UiRect::all(Val::Px(2.0)),
+            width: Val::Percent(100.0),
+            padding: UiRect::all(Val::Px(4.0)),
+            margin: UiRect::bottom(Val::Px(8.0)),
+            ..Default::default()
+        };
+
+        let input_colors = (
+            BorderColor(Srgba::hex("555555").unwrap().into()),
+            BackgroundColor(Srgba::hex("111111").unwrap().into()),
+        );
+
+        let button_node = Node {
+            flex_grow: 1.0,
+            height: Val::Px(36.0),
+            ..Default::default()
+        };
+
+        commands
+            .entity(ent)
+            .insert((
+                TargetCamera(cam),
+                OpenMenu::new(0),
+                BackgroundColor(Srgba::hex("2D2D2D").unwrap().into()),
+                Node {
+                    width: Val::Px(800.0),
+                    height: Val::Px(600.0),
+                    margin: UiRect::all(Val::Auto),
+                    ..Default::default()
+                },
+                GuiWindow {
+                    title: localization.get("cosmos:window.market").into(),
+                    body_styles: Node {
+                        flex_direction: FlexDirection::Row,
+                        padding: UiRect::all(Val::Px(20.0)),
+                        column_gap: Val::Px(20.0),
+                        ..Default::default()
+                    },
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    Name::new("Market orders"),
+                    ScrollBox::default(),
+                    Node {
+                        flex_grow: 1.0,
+                        ..Default::default()
+                    },
+                ))
+                .with_children(|p| {
+                    p.spawn((
+                        MarketOrderList,
+                        Node {
+                            flex_direction: FlexDirection::Column,
+                            row_gap: Val::Px(8.0),
+                            ..Default::default()
+                        },
+                    ));
+                });
+
+                p.spawn((
+                    Name::new("Post order form"),
+                    Node {
+                        width: Val::Px(280.0),
+                        flex_direction: FlexDirection::Column,
+                        ..Default::default()
+                    },
+                ))
+                .with_children(|p| {
+                    p.spawn((
+                        OrderKindLabel,
+                        Text::new(kind_description(MarketOrderKind::Buy)),
+                        text_style.clone(),
+                        Node {
+                            margin: UiRect::bottom(Val::Px(8.0)),
+                            ..Default::default()
+                        },
+                    ));
+
+                    p.spawn(Node {
+                        column_gap: Val::Px(8.0),
+                        margin: UiRect::bottom(Val::Px(12.0)),
+                        ..Default::default()
+                    })
+                    .with_children(|p| {
+                        for (kind, text) in [(MarketOrderKind::Buy, "Buy"), (MarketOrderKind::Sell, "Sell")] {
+                            p.spawn((
+                                KindChoice(kind),
+                                button_node.clone(),
+                                Button::<KindClicked> {
+                                    button_styles: Some(button_styles()),
+                                    text: Some((text.into(), text_style.clone(), Default::default())),
+                                    ..Default::default()
+                                },
+                            ));
+                        }
+                    });
+
+                    p.spawn((Text::new("Item"), text_style.clone()));
+                    p.spawn((
+                        ItemNameInput,
+                        text_style.clone(),
+                        TextInput {
+                            input_type: InputType::Text { max_length: Some(64) },
+                            ..Default::default()
+                        },
+                        input_colors.clone(),
+                        input_node.clone(),
+                    ));
+
+                    p.spawn((Text::new("Quantity"), text_style.clone()));
+                    p.spawn((
+                        QuantityInput,
+                        text_style.clone(),
+                        TextInput {
+                            input_type: InputType::Integer {
+                                min: 1,
+                                max: MAX_MARKET_ORDER_QUANTITY as i64,
+                            },
+                            ..Default::default()
+                        },
+                        InputValue::new("1"),
+                        input_colors.clone(),
+                        input_node.clone(),
+                    ));
+
+                    p.spawn((Text::new("Price per item"), text_style.clone()));
+                    p.spawn((
+                        PriceInput,
+                        text_style.clone(),
+                        TextInput {
+                            input_type: InputType::Integer { min: 1, max: i64::MAX },
+                            ..Default::default()
+                        },
+                        InputValue::new("1"),
+                        input_colors,
+                        input_node,
+                    ));
+
+                    p.spawn((
+                        Name::new("Post order button"),
+                        Node {
+                            height: Val::Px(50.0),
+                            margin: UiRect::top(Val::Px(8.0)),
+                            ..Default::default()
+                        },
+                        Button::<PostClicked> {
+                            button_styles: Some(button_styles()),
+                            text: Some(("Post Order".into(), text_style.clone(), Default::default())),
+                            ..Default::default()
+                        },
+                    ));
+
+                    p.spawn((
+                        MarketStatus,
+                        Text::new(""),
+                        text_style,
+                        TextColor(css::INDIAN_RED.into()),
+                        Node {
+                            margin: UiRect::top(Val::Px(8.0)),
+                            ..Default::default()
+                        },
+                    ));
+                });
+            });
+    }
+}
+
+fn populate_order_list(
+    mut commands: Commands,
+    q_list: Query<Entity, With<MarketOrderList>>,
+    q_added_list: Query<(), Added<MarketOrderList>>,
+    q_local_player: Query<&Player, With<LocalPlayer>>,
+    orders: Res<MarketOrders>,
+    items: Res<Registry<Item>>,
+    lang: Res<Lang<Item>>,
+    font: Res<DefaultFont>,
+) {
+    let Ok(list_ent) = q_list.get_single() else {
+        return;
+    };
+
+    if q_added_list.is_empty() && !orders.is_changed() {
+        return;
+    }
+
+    let local_name = q_local_player.get_single().ok().map(|p| p.name());
+
+    let text_style = TextFont {
+        font: font.0.clone_weak(),
+        font_size: 18.0,
+        ..Default::default()
+    };
+
+    let mut sorted = orders.0.iter().collect::<Vec<_>>();
+    sorted.sort_by(|a, b| a.item.cmp(&b.item).then(a.price_per_item.cmp(&b.price_per_item)));
+
+    let mut ecmds = commands.entity(list_ent);
+    ecmds.despawn_descendants();
+
+    ecmds.with_children(|p| {
+        if sorted.is_empty() {
+            p.spawn((Text::new("There are no orders"), text_style.clone(), TextColor(css::GRAY.into())));
+            return;
+        }
+
+        for order in sorted {
+            let item_name = items
+                .from_id(&order.item)
+                .and_then(|item| lang.get_name(item))
+                .unwrap_or(&order.item);
+
+            let (verb, color) = match order.kind {
+                MarketOrderKind::Buy => ("Buying", css::LIGHT_GREEN),
+                MarketOrderKind::Sell => ("Selling", css::LIGHT_SALMON),
+            };
+
+            p.spawn((
+                Name::new("Market order"),
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    justify_content: JustifyContent::SpaceBetween,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    Text::new(format!(
+                        "{verb} {}x {item_name} for ${} each ({})",
+                        order.quantity, order.price_per_item, order.owner
+                    )),
+                    text_style.clone(),
+                    TextColor(color.into()),
+                ));
+
+                if Some(order.owner.as_str()) == local_name {
+                    p.spawn((
+                        Name::new("Cancel order button"),
+                        CancelTarget(order.id),
+                        Node {
+                            width: Val::Px(90.0),
+                            height: Val::Px(30.0),
+                            ..Default::default()
+                        },
+                        Button::<CancelClicked> {
+                            button_styles: Some(button_styles()),
+                            text: Some(("Cancel".into(), text_style.clone(), Default::default())),
+                            ..Default::default()
+                        },
+                    ));
+                }
+            });
+        }
+    });
+}
+
+fn on_kind_clicked(
+    mut evr_kind: EventReader<KindClicked>,
+    q_choice: Query<&KindChoice>,
+    mut q_menu: Query<&mut OpenMarket>,
+    mut q_label: Query<&mut Text, With<OrderKindLabel>>,
+) {
+    for ev in evr_kind.read() {
+        let (Ok(choice), Ok(mut menu)) = (q_choice.get(ev.0), q_menu.get_single_mut()) else {
+            continue;
+        };
+
+        menu.kind = choice.0;
+
+        if let Ok(mut label) = q_label.get_single_mut() {
+            label.0 = kind_description(choice.0).into();
+        }
+    }
+}
+
+/// Finds the item with this display name or unlocalized name, ignoring case
+fn find_item<'a>(name: &str, items: &'a Registry<Item>, lang: &Lang<Item>) -> Option<&'a Item> {
+    let name = name.trim();
+
+    items.iter().find(|item| {
+        item.unlocalized_name().eq_ignore_ascii_case(name) || lang.get_name(item).is_some_and(|x| x.eq_ignore_ascii_case(name))
+    })
+}
+
+fn on_post_clicked(
+    mut evr_post: EventReader<PostClicked>,
+    q_menu: Query<&OpenMarket>,
+    q_item: Query<&InputValue, With<ItemNameInput>>,
+    q_quantity: Query<&InputValue, With<QuantityInput>>,
+    q_price: Query<&InputValue, With<PriceInput>>,
+    mut q_status: Query<&mut Text, With<MarketStatus>>,
+    items: Res<Registry<Item>>,
+    lang: Res<Lang<Item>>,
+    item_ids: Res<RegistryIdMap<Item>>,
+    network_mapping: Res<NetworkMapping>,
+    mut nevw_post: NettyEventWriter<PostMarketOrderEvent>,
+) {
+    if evr_post.read().next().is_none() {
+        return;
+    }
+
+    let (Ok(menu), Ok(item), Ok(quantity), Ok(price), Ok(mut status)) = (
+        q_menu.get_single(),
+        q_item.get_single(),
+        q_quantity.get_single(),
+        q_price.get_single(),
+        q_status.get_single_mut(),
+    ) else {
+        return;
+    };
+
+    let Some(item_id) = find_item(item.value(), &items, &lang).and_then(|item| item_ids.to_server(item.id())) else {
+        status.0 = "Unknown item".into();
+        return;
+    };
+
+    let (Ok(quantity), Ok(price_per_item)) = (quantity.value().parse::<u32>(), price.value().parse::<u64>()) else {
+        status.0 = "Enter a quantity and price".into();
+        return;
+    };
+
+    status.0 = String::new();
+
+    if let Ok(terminal) = menu.terminal.map_to_server(&network_mapping) {
+        nevw_post.send(PostMarketOrderEvent {
+            terminal,
+            kind: menu.kind,
+            item_id,
+            quantity,
+            price_per_item,
+        });
+    }
+}
+
+fn on_cancel_clicked(
+    mut evr_cancel: EventReader<CancelClicked>,
+    q_target: Query<&CancelTarget>,
+    q_menu: Query<&OpenMarket>,
+    network_mapping: Res<NetworkMapping>,
+    mut nevw_cancel: NettyEventWriter<CancelMarketOrderEvent>,
+) {
+    for ev in evr_cancel.read() {
+        let (Ok(target), Ok(menu)) = (q_target.get(ev.0), q_menu.get_single()) else {
+            continue;
+        };
+
+        if let Ok(terminal) = menu.terminal.map_to_server(&network_mapping) {
+            nevw_cancel.send(CancelMarketOrderEvent {
+                terminal,
+                order_id: target.0,
+            });
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<KindClicked>(app);
+    register_button::<PostClicked>(app);
+    register_button::<CancelClicked>(app);
+
+    app.init_resource::<MarketOrders>().add_systems(
+        Update,
+        (
+            (on_receive_orders, on_orders_changed.after(on_receive_orders), open_market).in_set(NetworkingSystemsSet::Between),
+            (
+                create_market_window,
+                populate_order_list,
+                on_kind_clicked,
+                on_post_clicked,
+                on_cancel_clicked,
+            )
+                .chain()
+                .in_set(UiSystemSet::DoUi),
+        )
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -5,6 +5,8 @@ use cosmos_core::economy::Credits;
 
 use crate::ui::reactivity::{add_reactable_type, ReactableValue};
 
+mod market;
+
 impl ReactableValue for Credits {
     fn as_value(&self) -> String {
         format!("{}", self.amount())
@@ -22,4 +24,6 @@ impl ReactableValue for Credits {
 
 pub(super) fn register(app: &mut App) {
     add_reactable_type::<Credits>(app);
+
+    market::register(app);
 }
@@ -0,0 +1,159 @@
+//! Draws creatures and animates them walking around
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use cosmos_core::{
+    entities::creature::{Creature, CreatureType},
+    netty::system_sets::NetworkingSystemsSet,
+    registry::Registry,
+    state::GameState,
+};
+
+/// How far (in radians) legs swing back & forth at full speed
+const MAX_LEG_SWING: f32 = 0.6;
+/// How far (relative to the creature's height) the body bobs up & down at full speed
+const MAX_BODY_BOB: f32 = 0.08;
+/// The speed at which a creature's walk animation is at its strongest
+const FULL_ANIMATION_SPEED: f32 = 3.0;
+
+#[derive(Component, Debug, Default)]
+/// How far along a creature is in its walk cycle
+struct WalkCycle(f32);
+
+#[derive(Component, Debug)]
+/// A leg that swings around its top as the creature walks
+struct CreatureLeg {
+    phase_offset: f32,
+}
+
+#[derive(Component, Debug)]
+/// The body of a creature, which bobs as it walks
+struct CreatureBody {
+    resting_height: f32,
+    height: f32,
+}
+
+fn on_add_creature(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    q_added_creature: Query<(Entity, &Creature), Added<Creature>>,
+    creature_types: Res<Registry<CreatureType>>,
+) {
+    for (ent, creature) in q_added_creature.iter() {
+        let Some(creature_type) = creature_types.try_from_numeric_id(creature.creature_type) else {
+            continue;
+        };
+
+        let props = creature_type.properties();
+        let half = props.half_size;
+        let [r, g, b] = props.color;
+
+        let material = materials.add(StandardMaterial {
+            base_color: Color::srgb(r, g, b),
+            perceptual_roughness: 0.9,
+            ..Default::default()
+        });
+
+        // The top half of the creature is its body & the bottom half is its legs
+        let body_height = half.y;
+        let leg_length = half.y;
+        let head_size = half.x.min(half.y) * 0.9;
+        let leg_width = half.x * 0.3;
+
+        commands
+            .entity(ent)
+            .insert((Visibility::default(), WalkCycle::default()))
+            .with_children(|p| {
+                p.spawn((
+                    Name::new("Creature body"),
+                    CreatureBody {
+                        resting_height: body_height / 2.0,
+                        height: half.y * 2.0,
+                    },
+                    Mesh3d(meshes.add(Cuboid::new(half.x * 2.0, body_height, half.z * 2.0))),
+                    MeshMaterial3d(material.clone()),
+                    Transform::from_xyz(0.0, body_height / 2.0, 0.0),
+                ))
+                .with_children(|p| {
+                    // Creatures walk towards -Z, so that's where their head goes
+                    p.spawn((
+                        Name::new("Creature head"),
+                        Mesh3d(meshes.add(Cuboid::new(head_size, head_size, head_size))),
+                        MeshMaterial3d(material.clone()),
+                        Transform::from_xyz(0.0, body_height / 4.0, -half.z - head_size / 2.0),
+                    ));
+                });
+
+                let leg_mesh = meshes.add(Cuboid::new(leg_width, leg_length, leg_width));
+
+                for (i, (x, z)) in [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)].into_iter().enumerate() {
+                    // Diagonal legs move together, like a trotting animal
+                    let phase_offset = if i == 0 || i == 3 { 0.0 } else { std::f32::consts::PI };
+
+                    p.spawn((
+                        Name::new("Creature leg"),
+                        CreatureLeg { phase_offset },
+                        Transform::from_xyz(x * half.x * 0.6, 0.0, z * half.z * 0.6),
+                        Visibility::default(),
+                    ))
+                    .with_children(|p| {
+                        p.spawn((
+                            Mesh3d(leg_mesh.clone()),
+                            MeshMaterial3d(material.clone()),
+                            Transform::from_xyz(0.0, -leg_length / 2.0, 0.0),
+                        ));
+                    });
+                }
+            });
+    }
+}
+
+/// How strongly the walk animation should play, from 0.0 (standing still) to 1.0 (full speed)
+fn animation_strength(velocity: Option<&Velocity>) -> f32 {
+    velocity.map(|v| (v.linvel.length() / FULL_ANIMATION_SPEED).min(1.0)).unwrap_or(0.0)
+}
+
+fn advance_walk_cycles(time: Res<Time>, mut q_creatures: Query<(&mut WalkCycle, Option<&Velocity>), With<Creature>>) {
+    for (mut cycle, velocity) in q_creatures.iter_mut() {
+        let speed = velocity.map(|v| v.linvel.length()).unwrap_or(0.0);
+
+        cycle.0 = (cycle.0 + time.delta_secs() * speed * 4.0) % std::f32::consts::TAU;
+    }
+}
+
+fn animate_creatures(
+    q_creatures: Query<(&WalkCycle, Option<&Velocity>), With<Creature>>,
+    mut q_legs: Query<(&CreatureLeg, &Parent, &mut Transform), Without<CreatureBody>>,
+    mut q_bodies: Query<(&CreatureBody, &Parent, &mut Transform), Without<CreatureLeg>>,
+) {
+    for (leg, parent, mut transform) in q_legs.iter_mut() {
+        let Ok((cycle, velocity)) = q_creatures.get(parent.get()) else {
+            continue;
+        };
+
+        let swing = (cycle.0 + leg.phase_offset).sin() * MAX_LEG_SWING * animation_strength(velocity);
+
+        transform.rotation = Quat::from_rotation_x(swing);
+    }
+
+    for (body, parent, mut transform) in q_bodies.iter_mut() {
+        let Ok((cycle, velocity)) = q_creatures.get(parent.get()) else {
+            continue;
+        };
+
+        let bob = (cycle.0 * 2.0).sin().abs() * MAX_BODY_BOB * body.height * animation_strength(velocity);
+
+        transform.translation.y = body.resting_height + bob;
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (on_add_creature, advance_walk_cycles, animate_creatures)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
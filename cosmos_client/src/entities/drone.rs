@@ -0,0 +1,78 @@
+//! Draws drones, and the sparks of the blocks they're working on
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    entities::drone::{Drone, DroneActivity, DRONE_RADIUS},
+    netty::system_sets::NetworkingSystemsSet,
+    state::GameState,
+    structure::Structure,
+};
+
+fn on_add_drone(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    q_added_drone: Query<Entity, Added<Drone>>,
+) {
+    for ent in q_added_drone.iter() {
+        commands.entity(ent).insert(Visibility::default()).with_children(|p| {
+            p.spawn((
+                Name::new("Drone body"),
+                Mesh3d(meshes.add(Sphere::new(DRONE_RADIUS))),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: Srgba::hex("C9A227").unwrap().into(),
+                    metallic: 0.6,
+                    perceptual_roughness: 0.4,
+                    ..Default::default()
+                })),
+                Transform::default(),
+            ));
+
+            p.spawn((
+                Name::new("Drone eye"),
+                Mesh3d(meshes.add(Cuboid::new(DRONE_RADIUS, DRONE_RADIUS * 0.4, DRONE_RADIUS * 0.4))),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: css::AQUA.into(),
+                    emissive: LinearRgba::rgb(0.0, 4.0, 4.0),
+                    ..Default::default()
+                })),
+                Transform::from_xyz(0.0, 0.0, -DRONE_RADIUS),
+            ));
+        });
+    }
+}
+
+/// Working drones shoot a flickering welding beam at their block
+fn draw_welding_beams(
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+    q_drones: Query<(&Drone, &GlobalTransform, &Parent)>,
+    q_structure: Query<(&Structure, &GlobalTransform)>,
+) {
+    // Flickers a few times a second
+    let brightness = 0.6 + 0.4 * (time.elapsed_secs() * 25.0).sin().abs();
+
+    for (drone, drone_g_trans, parent) in q_drones.iter() {
+        let DroneActivity::Working(coords) = drone.activity else {
+            continue;
+        };
+
+        let Ok((structure, g_trans)) = q_structure.get(parent.get()) else {
+            continue;
+        };
+
+        let target = g_trans.transform_point(structure.block_relative_position(coords));
+
+        gizmos.line(drone_g_trans.translation(), target, Color::from(css::ORANGE).with_alpha(brightness));
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (on_add_drone, draw_welding_beams)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,292 @@
+//! Draws NPCs, lets the player talk to them, and shows what they say
+
+use bevy::{color::palettes::css, prelude::*};
+use bevy_rapier3d::{plugin::ReadRapierContext, prelude::QueryFilter};
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    entities::npc::{ChooseDialogueOptionEvent, InteractNpcEvent, Npc, NpcDialogueEvent, NPC_HALF_HEIGHT, NPC_RADIUS},
+    netty::{
+        client::LocalPlayer,
+        sync::{
+            events::client_event::{NettyEventReceived, NettyEventWriter},
+            mapping::NetworkMapping,
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    state::GameState,
+    structure::ship::pilot::Pilot,
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    rendering::MainCamera,
+    ui::{
+        components::{
+            button::{register_button, Button, ButtonEvent, ButtonStyles},
+            show_cursor::no_open_menus,
+            window::GuiWindow,
+        },
+        font::DefaultFont,
+        OpenMenu, UiSystemSet,
+    },
+};
+
+/// How far away (in blocks) the player can talk to NPCs from
+const TALK_DISTANCE: f32 = 10.0;
+
+#[derive(Component, Debug)]
+/// The window showing what an NPC is saying
+struct OpenDialogue {
+    /// The server's entity for the NPC being talked to
+    server_npc: Entity,
+}
+
+#[derive(Component, Debug)]
+struct DialogueOptionIndex(usize);
+
+#[derive(Event, Debug)]
+struct DialogueOptionClicked(Entity);
+
+impl ButtonEvent for DialogueOptionClicked {
+    fn create_event(entity: Entity) -> Self {
+        Self(entity)
+    }
+}
+
+#[derive(Event, Debug)]
+struct LeaveDialogueClicked;
+
+impl ButtonEvent for LeaveDialogueClicked {
+    fn create_event(_: Entity) -> Self {
+        Self
+    }
+}
+
+fn button_styles() -> ButtonStyles {
+    ButtonStyles {
+        background_color: Srgba::hex("555555").unwrap().into(),
+        hover_background_color: Srgba::hex("777777").unwrap().into(),
+        press_background_color: Srgba::hex("333333").unwrap().into(),
+        foreground_color: css::WHITE.into(),
+        hover_foreground_color: css::WHITE.into(),
+        press_foreground_color: css::WHITE.into(),
+    }
+}
+
+fn on_add_npc(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    q_added_npc: Query<Entity, Added<Npc>>,
+) {
+    for ent in q_added_npc.iter() {
+        let material = materials.add(StandardMaterial {
+            base_color: Srgba::hex("3B5B8C").unwrap().into(),
+            perceptual_roughness: 0.8,
+            ..Default::default()
+        });
+
+        let head_radius = NPC_RADIUS * 0.8;
+
+        commands.entity(ent).insert(Visibility::default()).with_children(|p| {
+            p.spawn((
+                Name::new("NPC body"),
+                Mesh3d(meshes.add(Capsule3d::new(NPC_RADIUS, NPC_HALF_HEIGHT * 2.0))),
+                MeshMaterial3d(material.clone()),
+                Transform::default(),
+            ));
+
+            p.spawn((
+                Name::new("NPC head"),
+                Mesh3d(meshes.add(Sphere::new(head_radius))),
+                MeshMaterial3d(material),
+                Transform::from_xyz(0.0, NPC_HALF_HEIGHT + NPC_RADIUS + head_radius * 0.5, 0.0),
+            ));
+        });
+    }
+}
+
+fn talk_to_npcs(
+    inputs: InputChecker,
+    q_player: Query<Entity, (With<LocalPlayer>, Without<Pilot>)>,
+    q_cam: Query<&GlobalTransform, With<MainCamera>>,
+    q_npc: Query<(), With<Npc>>,
+    rapier_context_access: ReadRapierContext,
+    network_mapping: Res<NetworkMapping>,
+    mut nevw_interact: NettyEventWriter<InteractNpcEvent>,
+) {
+    if !inputs.check_just_pressed(CosmosInputs::Interact) {
+        return;
+    }
+
+    let (Ok(player_ent), Ok(cam_trans)) = (q_player.get_single(), q_cam.get_single()) else {
+        return;
+    };
+
+    let Some((hit, _)) = rapier_context_access.single().cast_ray(
+        cam_trans.translation(),
+        cam_trans.forward().into(),
+        TALK_DISTANCE,
+        true,
+        QueryFilter::new().exclude_rigid_body(player_ent),
+    ) else {
+        return;
+    };
+
+    if !q_npc.contains(hit) {
+        return;
+    }
+
+    if let Some(npc) = network_mapping.server_from_client(&hit) {
+        nevw_interact.send(InteractNpcEvent { npc });
+    }
+}
+
+fn show_dialogue(
+    mut commands: Commands,
+    mut nevr_dialogue: EventReader<NettyEventReceived<NpcDialogueEvent>>,
+    q_open: Query<Entity, With<OpenDialogue>>,
+    q_npc: Query<&Npc>,
+    q_cam: Query<Entity, With<MainCamera>>,
+    network_mapping: Res<NetworkMapping>,
+    font: Res<DefaultFont>,
+) {
+    let Some(ev) = nevr_dialogue.read().last() else {
+        return;
+    };
+
+    if let Ok(ent) = q_open.get_single() {
+        commands.entity(ent).insert(NeedsDespawned);
+    }
+
+    let Some(line) = &ev.line else {
+        return;
+    };
+
+    let Ok(cam) = q_cam.get_single() else {
+        return;
+    };
+
+    let npc_name = network_mapping
+        .client_from_server(&ev.npc)
+        .and_then(|npc| q_npc.get(npc).ok())
+        .map(|npc| npc.name.clone())
+        .unwrap_or_default();
+
+    let text_style = TextFont {
+        font: font.0.clone_weak(),
+        font_size: 20.0,
+        ..Default::default()
+    };
+
+    let option_node = Node {
+        min_height: Val::Px(40.0),
+        padding: UiRect::horizontal(Val::Px(10.0)),
+        ..Default::default()
+    };
+
+    commands
+        .spawn((
+            Name::new("NPC Dialogue"),
+            OpenDialogue { server_npc: ev.npc },
+            TargetCamera(cam),
+            OpenMenu::new(0),
+            BackgroundColor(Srgba::hex("2D2D2D").unwrap().into()),
+            Node {
+                width: Val::Px(600.0),
+                margin: UiRect::all(Val::Auto),
+                ..Default::default()
+            },
+            GuiWindow {
+                title: npc_name,
+                body_styles: Node {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(20.0)),
+                    row_gap: Val::Px(8.0),
+                    ..Default::default()
+                },
+            },
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new(line.text.clone()),
+                text_style.clone(),
+                Node {
+                    margin: UiRect::bottom(Val::Px(12.0)),
+                    ..Default::default()
+                },
+            ));
+
+            for (i, option) in line.options.iter().enumerate() {
+                p.spawn((
+                    Name::new("Dialogue option"),
+                    DialogueOptionIndex(i),
+                    option_node.clone(),
+                    Button::<DialogueOptionClicked> {
+                        button_styles: Some(button_styles()),
+                        text: Some((option.clone(), text_style.clone(), Default::default())),
+                        ..Default::default()
+                    },
+                ));
+            }
+
+            if line.options.is_empty() {
+                p.spawn((
+                    Name::new("Leave dialogue"),
+                    option_node,
+                    Button::<LeaveDialogueClicked> {
+                        button_styles: Some(button_styles()),
+                        text: Some(("Leave".into(), text_style, Default::default())),
+                        ..Default::default()
+                    },
+                ));
+            }
+        });
+}
+
+fn on_option_clicked(
+    mut evr_option: EventReader<DialogueOptionClicked>,
+    q_option: Query<&DialogueOptionIndex>,
+    q_open: Query<&OpenDialogue>,
+    mut nevw_choose: NettyEventWriter<ChooseDialogueOptionEvent>,
+) {
+    for ev in evr_option.read() {
+        let (Ok(option), Ok(open)) = (q_option.get(ev.0), q_open.get_single()) else {
+            continue;
+        };
+
+        nevw_choose.send(ChooseDialogueOptionEvent {
+            npc: open.server_npc,
+            option: option.0,
+        });
+    }
+}
+
+fn on_leave_clicked(mut commands: Commands, mut evr_leave: EventReader<LeaveDialogueClicked>, q_open: Query<Entity, With<OpenDialogue>>) {
+    if evr_leave.read().next().is_none() {
+        return;
+    }
+
+    if let Ok(ent) = q_open.get_single() {
+        commands.entity(ent).insert(NeedsDespawned);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<DialogueOptionClicked>(app);
+    register_button::<LeaveDialogueClicked>(app);
+
+    app.add_systems(
+        Update,
+        (on_add_npc, talk_to_npcs.run_if(no_open_menus), show_dialogue)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    )
+    .add_systems(
+        Update,
+        (on_option_clicked, on_leave_clicked)
+            .in_set(UiSystemSet::DoUi)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
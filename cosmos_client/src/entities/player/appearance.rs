@@ -0,0 +1,152 @@
+//! Builds the player model from their [`PlayerAppearance`], so everyone sees the suit each player picked
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    entities::player::{
+        appearance::{HelmetStyle, PlayerAppearance, SuitPattern},
+        Player,
+    },
+    netty::{client::LocalPlayer, system_sets::NetworkingSystemsSet},
+    state::GameState,
+};
+
+#[derive(Component, Debug)]
+/// The visible body of a player. This is a child of the player entity, and is rebuilt whenever their appearance changes.
+pub struct PlayerModel;
+
+fn color([r, g, b]: [u8; 3]) -> Color {
+    Color::srgb_u8(r, g, b)
+}
+
+fn spawn_part(p: &mut ChildBuilder, mesh: Handle<Mesh>, material: Handle<StandardMaterial>, transform: Transform) {
+    p.spawn((Mesh3d(mesh), MeshMaterial3d(material), transform));
+}
+
+fn build_player_models(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    q_players: Query<
+        (Entity, Option<&PlayerAppearance>, Option<&Children>, Has<LocalPlayer>),
+        (With<Player>, Or<(Added<Player>, Changed<PlayerAppearance>)>),
+    >,
+    q_models: Query<(), With<PlayerModel>>,
+) {
+    for (ent, appearance, children, is_local) in q_players.iter() {
+        // The player can be loaded before its appearance is synced, so the default look is used until then
+        let appearance = appearance.copied().unwrap_or_default();
+
+        for &child in children.map(|c| c.iter()).into_iter().flatten() {
+            if q_models.contains(child) {
+                commands.entity(child).despawn_recursive();
+            }
+        }
+
+        let primary = materials.add(StandardMaterial {
+            base_color: color(appearance.primary_color),
+            perceptual_roughness: 0.7,
+            ..Default::default()
+        });
+
+        let secondary = materials.add(StandardMaterial {
+            base_color: color(appearance.secondary_color),
+            perceptual_roughness: 0.7,
+            ..Default::default()
+        });
+
+        let model = commands
+            .spawn((
+                Name::new("Player Model"),
+                PlayerModel,
+                Transform::default(),
+                // The camera sits inside the local player's head, so their own model would only get in the way
+                if is_local { Visibility::Hidden } else { Visibility::default() },
+            ))
+            .with_children(|p| {
+                spawn_part(
+                    p,
+                    meshes.add(Capsule3d::new(0.4, 0.8)),
+                    primary.clone(),
+                    Transform::from_xyz(0.0, -0.2, 0.0),
+                );
+
+                match appearance.pattern {
+                    SuitPattern::Solid => {}
+                    SuitPattern::Striped => {
+                        spawn_part(
+                            p,
+                            meshes.add(Cylinder::new(0.41, 0.15)),
+                            secondary.clone(),
+                            Transform::from_xyz(0.0, 0.0, 0.0),
+                        );
+                    }
+                    SuitPattern::TwoTone => {
+                        spawn_part(
+                            p,
+                            meshes.add(Capsule3d::new(0.41, 0.3)),
+                            secondary.clone(),
+                            Transform::from_xyz(0.0, -0.55, 0.0),
+                        );
+                    }
+                }
+
+                let head = Transform::from_xyz(0.0, 0.6, 0.0);
+
+                match appearance.helmet {
+                    HelmetStyle::Standard => {
+                        spawn_part(p, meshes.add(Sphere::new(0.3)), primary.clone(), head);
+                        spawn_part(
+                            p,
+                            meshes.add(Cuboid::new(0.3, 0.12, 0.1)),
+                            materials.add(StandardMaterial {
+                                base_color: css::DARK_SLATE_GRAY.into(),
+                                perceptual_roughness: 0.2,
+                                ..Default::default()
+                            }),
+                            head * Transform::from_xyz(0.0, 0.03, -0.26),
+                        );
+                    }
+                    HelmetStyle::Visor => {
+                        spawn_part(p, meshes.add(Sphere::new(0.3)), primary.clone(), head);
+                        spawn_part(
+                            p,
+                            meshes.add(Cuboid::new(0.46, 0.16, 0.12)),
+                            materials.add(StandardMaterial {
+                                base_color: color(appearance.secondary_color),
+                                metallic: 1.0,
+                                perceptual_roughness: 0.1,
+                                ..Default::default()
+                            }),
+                            head * Transform::from_xyz(0.0, 0.03, -0.22),
+                        );
+                    }
+                    HelmetStyle::Dome => {
+                        spawn_part(p, meshes.add(Sphere::new(0.22)), secondary.clone(), head);
+                        spawn_part(
+                            p,
+                            meshes.add(Sphere::new(0.34)),
+                            materials.add(StandardMaterial {
+                                base_color: Color::srgba(0.7, 0.9, 1.0, 0.25),
+                                alpha_mode: AlphaMode::Blend,
+                                perceptual_roughness: 0.05,
+                                ..Default::default()
+                            }),
+                            head,
+                        );
+                    }
+                }
+            })
+            .id();
+
+        commands.entity(ent).add_child(model);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        build_player_models
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing).or(in_state(GameState::LoadingWorld))),
+    );
+}
@@ -0,0 +1,368 @@
+//! The character screen, where players pick the colors, pattern and helmet of their suit
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    entities::player::appearance::{HelmetStyle, PlayerAppearance, SetAppearanceEvent, SuitPattern},
+    netty::{client::LocalPlayer, sync::events::client_event::NettyEventWriter},
+    state::GameState,
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker},
+    lang::Localization,
+    rendering::MainCamera,
+    ui::{
+        components::{
+            button::{register_button, Button, ButtonEvent, ButtonStyles},
+            show_cursor::no_open_menus,
+            window::GuiWindow,
+        },
+        font::DefaultFont,
+        OpenMenu, UiSystemSet,
+    },
+};
+
+/// The colors players can pick from for their suit
+const PALETTE: [[u8; 3]; 10] = [
+    [0, 255, 0],
+    [255, 255, 255],
+    [40, 40, 40],
+    [220, 40, 40],
+    [255, 140, 0],
+    [255, 220, 0],
+    [0, 200, 200],
+    [40, 90, 230],
+    [150, 60, 220],
+    [255, 105, 180],
+];
+
+#[derive(Component, Debug)]
+/// The open character screen, with the appearance being edited
+struct CharacterScreen(PlayerAppearance);
+
+#[derive(Component, Debug, Clone, Copy)]
+enum ColorSlot {
+    Primary,
+    Secondary,
+}
+
+#[derive(Component, Debug)]
+struct ColorChoice {
+    slot: ColorSlot,
+    color: [u8; 3],
+}
+
+#[derive(Component, Debug)]
+struct PatternLabel;
+
+#[derive(Component, Debug)]
+struct HelmetLabel;
+
+#[derive(Event, Debug)]
+struct ColorClicked(Entity);
+
+impl ButtonEvent for ColorClicked {
+    fn create_event(entity: Entity) -> Self {
+        Self(entity)
+    }
+}
+
+#[derive(Event, Debug)]
+struct CyclePatternClicked;
+
+impl ButtonEvent for CyclePatternClicked {
+    fn create_event(_: Entity) -> Self {
+        Self
+    }
+}
+
+#[derive(Event, Debug)]
+struct CycleHelmetClicked;
+
+impl ButtonEvent for CycleHelmetClicked {
+    fn create_event(_: Entity) -> Self {
+        Self
+    }
+}
+
+#[derive(Event, Debug)]
+struct SaveClicked;
+
+impl ButtonEvent for SaveClicked {
+    fn create_event(_: Entity) -> Self {
+        Self
+    }
+}
+
+fn button_styles() -> ButtonStyles {
+    ButtonStyles {
+        background_color: Srgba::hex("555555").unwrap().into(),
+        hover_background_color: Srgba::hex("777777").unwrap().into(),
+        press_background_color: Srgba::hex("333333").unwrap().into(),
+        foreground_color: css::WHITE.into(),
+        hover_foreground_color: css::WHITE.into(),
+        press_foreground_color: css::WHITE.into(),
+    }
+}
+
+fn color_styles([r, g, b]: [u8; 3]) -> ButtonStyles {
+    let color = Color::srgb_u8(r, g, b);
+
+    ButtonStyles {
+        background_color: color,
+        hover_background_color: color.lighter(0.15),
+        press_background_color: color.darker(0.15),
+        ..button_styles()
+    }
+}
+
+fn pattern_name(pattern: SuitPattern) -> &'static str {
+    match pattern {
+        SuitPattern::Solid => "Pattern: Solid",
+        SuitPattern::Striped => "Pattern: Striped",
+        SuitPattern::TwoTone => "Pattern: Two Tone",
+    }
+}
+
+fn helmet_name(helmet: HelmetStyle) -> &'static str {
+    match helmet {
+        HelmetStyle::Standard => "Helmet: Standard",
+        HelmetStyle::Visor => "Helmet: Visor",
+        HelmetStyle::Dome => "Helmet: Dome",
+    }
+}
+
+fn open_character_screen(
+    mut commands: Commands,
+    inputs: InputChecker,
+    q_open: Query<(), With<CharacterScreen>>,
+    q_local_player: Query<Option<&PlayerAppearance>, With<LocalPlayer>>,
+) {
+    if !inputs.check_just_pressed(CosmosInputs::OpenCharacterScreen) || !q_open.is_empty() {
+        return;
+    }
+
+    let Ok(appearance) = q_local_player.get_single() else {
+        return;
+    };
+
+    commands.spawn((
+        CharacterScreen(appearance.copied().unwrap_or_default()),
+        Name::new("Character Screen"),
+    ));
+}
+
+fn create_character_screen(
+    mut commands: Commands,
+    q_added: Query<(Entity, &CharacterScreen), Added<CharacterScreen>>,
+    q_cam: Query<Entity, With<MainCamera>>,
+    font: Res<DefaultFont>,
+    localization: Res<Localization>,
+) {
+    for (ent, screen) in q_added.iter() {
+        let Ok(cam) = q_cam.get_single() else {
+            return;
+        };
+
+        let text_style = TextFont {
+            font: font.0.clone_weak(),
+            font_size: 20.0,
+            ..Default::default()
+        };
+
+        let button_node = Node {
+            height: Val::Px(40.0),
+            margin: UiRect::bottom(Val::Px(12.0)),
+            ..Default::default()
+        };
+
+        commands
+            .entity(ent)
+            .insert((
+                TargetCamera(cam),
+                OpenMenu::new(0),
+                BackgroundColor(Srgba::hex("2D2D2D").unwrap().into()),
+                Node {
+                    width: Val::Px(500.0),
+                    height: Val::Px(420.0),
+                    margin: UiRect::all(Val::Auto),
+                    ..Default::default()
+                },
+                GuiWindow {
+                    title: localization.get("cosmos:window.character").into(),
+                    body_styles: Node {
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(20.0)),
+                        ..Default::default()
+                    },
+                },
+            ))
+            .with_children(|p| {
+                for (slot, title) in [(ColorSlot::Primary, "Primary Color"), (ColorSlot::Secondary, "Secondary Color")] {
+                    p.spawn((Text::new(title), text_style.clone()));
+
+                    p.spawn((
+                        Name::new(title),
+                        Node {
+                            column_gap: Val::Px(6.0),
+                            margin: UiRect::vertical(Val::Px(8.0)),
+                            ..Default::default()
+                        },
+                    ))
+                    .with_children(|p| {
+                        for color in PALETTE {
+                            p.spawn((
+                                ColorChoice { slot, color },
+                                BorderColor(Color::NONE),
+                                Node {
+                                    width: Val::Px(36.0),
+                                    height: Val::Px(36.0),
+                                    border: UiRect::all(Val::Px(3.0)),
+                                    ..Default::default()
+                                },
+                                Button::<ColorClicked> {
+                                    button_styles: Some(color_styles(color)),
+                                    ..Default::default()
+                                },
+                            ));
+                        }
+                    });
+                }
+
+                p.spawn((
+                    PatternLabel,
+                    button_node.clone(),
+                    Button::<CyclePatternClicked> {
+                        button_styles: Some(button_styles()),
+                        text: Some((pattern_name(screen.0.pattern).into(), text_style.clone(), Default::default())),
+                        ..Default::default()
+                    },
+                ));
+
+                p.spawn((
+                    HelmetLabel,
+                    button_node.clone(),
+                    Button::<CycleHelmetClicked> {
+                        button_styles: Some(button_styles()),
+                        text: Some((helmet_name(screen.0.helmet).into(), text_style.clone(), Default::default())),
+                        ..Default::default()
+                    },
+                ));
+
+                p.spawn((
+                    Name::new("Save appearance button"),
+                    button_node,
+                    Button::<SaveClicked> {
+                        button_styles: Some(button_styles()),
+                        text: Some(("Save".into(), text_style, Default::default())),
+                        ..Default::default()
+                    },
+                ));
+            });
+    }
+}
+
+/// Outlines the colors currently picked, so the player can tell what they've chosen
+fn outline_chosen_colors(
+    q_screen: Query<&CharacterScreen, Changed<CharacterScreen>>,
+    mut q_choices: Query<(&ColorChoice, &mut BorderColor)>,
+) {
+    let Ok(screen) = q_screen.get_single() else {
+        return;
+    };
+
+    for (choice, mut border) in q_choices.iter_mut() {
+        let chosen = match choice.slot {
+            ColorSlot::Primary => screen.0.primary_color,
+            ColorSlot::Secondary => screen.0.secondary_color,
+        };
+
+        border.0 = if chosen == choice.color { css::WHITE.into() } else { Color::NONE };
+    }
+}
+
+fn on_color_clicked(mut evr_color: EventReader<ColorClicked>, q_choice: Query<&ColorChoice>, mut q_screen: Query<&mut CharacterScreen>) {
+    for ev in evr_color.read() {
+        let (Ok(choice), Ok(mut screen)) = (q_choice.get(ev.0), q_screen.get_single_mut()) else {
+            continue;
+        };
+
+        match choice.slot {
+            ColorSlot::Primary => screen.0.primary_color = choice.color,
+            ColorSlot::Secondary => screen.0.secondary_color = choice.color,
+        }
+    }
+}
+
+fn on_cycle_clicked(
+    mut evr_pattern: EventReader<CyclePatternClicked>,
+    mut evr_helmet: EventReader<CycleHelmetClicked>,
+    mut q_screen: Query<&mut CharacterScreen>,
+    mut q_pattern_label: Query<&mut Button<CyclePatternClicked>, With<PatternLabel>>,
+    mut q_helmet_label: Query<&mut Button<CycleHelmetClicked>, With<HelmetLabel>>,
+) {
+    let Ok(mut screen) = q_screen.get_single_mut() else {
+        return;
+    };
+
+    for _ in evr_pattern.read() {
+        screen.0.pattern = screen.0.pattern.next();
+
+        if let Ok(mut button) = q_pattern_label.get_single_mut() {
+            if let Some((text, _, _)) = button.text.as_mut() {
+                *text = pattern_name(screen.0.pattern).into();
+            }
+        }
+    }
+
+    for _ in evr_helmet.read() {
+        screen.0.helmet = screen.0.helmet.next();
+
+        if let Ok(mut button) = q_helmet_label.get_single_mut() {
+            if let Some((text, _, _)) = button.text.as_mut() {
+                *text = helmet_name(screen.0.helmet).into();
+            }
+        }
+    }
+}
+
+fn on_save_clicked(
+    mut commands: Commands,
+    mut evr_save: EventReader<SaveClicked>,
+    q_screen: Query<(Entity, &CharacterScreen)>,
+    mut nevw_set_appearance: NettyEventWriter<SetAppearanceEvent>,
+) {
+    if evr_save.read().next().is_none() {
+        return;
+    }
+
+    let Ok((ent, screen)) = q_screen.get_single() else {
+        return;
+    };
+
+    nevw_set_appearance.send(SetAppearanceEvent { appearance: screen.0 });
+    commands.entity(ent).insert(NeedsDespawned);
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<ColorClicked>(app);
+    register_button::<CyclePatternClicked>(app);
+    register_button::<CycleHelmetClicked>(app);
+    register_button::<SaveClicked>(app);
+
+    app.add_systems(
+        Update,
+        (
+            open_character_screen.run_if(no_open_menus),
+            create_character_screen,
+            on_color_clicked,
+            on_cycle_clicked,
+            outline_chosen_colors,
+            on_save_clicked,
+        )
+            .chain()
+            .in_set(UiSystemSet::DoUi)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
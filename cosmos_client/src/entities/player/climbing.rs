@@ -0,0 +1,93 @@
+//! Decides when the local player is holding onto a ladder. The climbing itself is done by [`super::player_movement`].
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::{
+        specific_blocks::{
+            ladder::{is_ladder_at, Climbing},
+            seat::Seated,
+        },
+        Block,
+    },
+    entities::player::spectator::Spectator,
+    netty::{client::LocalPlayer, system_sets::NetworkingSystemsSet},
+    prelude::Structure,
+    registry::Registry,
+    state::GameState,
+    structure::{shared::build_mode::BuildMode, ship::pilot::Pilot},
+};
+
+use super::player_movement::{process_player_movement, PlayerMovementSet};
+
+/// How far below the center of the player their feet are
+const FEET_OFFSET: f32 = 0.7;
+
+fn update_climbing(
+    mut commands: Commands,
+    q_local_player: Query<
+        (Entity, &GlobalTransform, &Parent, Has<Climbing>),
+        (
+            With<LocalPlayer>,
+            Without<Pilot>,
+            Without<BuildMode>,
+            Without<Spectator>,
+            Without<Seated>,
+        ),
+    >,
+    q_structure: Query<(&Structure, &GlobalTransform)>,
+    blocks: Res<Registry<Block>>,
+) {
+    let Ok((ent, g_trans, parent, climbing)) = q_local_player.get_single() else {
+        return;
+    };
+
+    let Ok((structure, structure_g_trans)) = q_structure.get(parent.get()) else {
+        if climbing {
+            commands.entity(ent).remove::<Climbing>();
+        }
+        return;
+    };
+
+    let center = g_trans.translation();
+    let feet = center - g_trans.up() * FEET_OFFSET;
+
+    // Checking the feet too lets players climb onto the top of a ladder instead of falling off of it
+    let on_ladder = [center, feet]
+        .into_iter()
+        .any(|point| is_ladder_at(point, structure, structure_g_trans, &blocks));
+
+    if on_ladder && !climbing {
+        commands.entity(ent).insert(Climbing);
+    } else if !on_ladder && climbing {
+        commands.entity(ent).remove::<Climbing>();
+    }
+}
+
+/// Players that sit down, start piloting, or leave their structure while climbing let go of the ladder
+fn let_go_of_ladders(
+    mut commands: Commands,
+    q_climbing: Query<
+        Entity,
+        (
+            With<LocalPlayer>,
+            With<Climbing>,
+            Or<(With<Pilot>, With<BuildMode>, With<Spectator>, With<Seated>, Without<Parent>)>,
+        ),
+    >,
+) {
+    for ent in q_climbing.iter() {
+        commands.entity(ent).remove::<Climbing>();
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (update_climbing, let_go_of_ladders)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .in_set(PlayerMovementSet::ProcessPlayerMovement)
+            .before(process_player_movement)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,56 @@
+//! Lets the player eat/drink the consumable item they're holding by using it
+
+use bevy::prelude::*;
+use cosmos_core::{
+    entities::player::spectator::Spectator,
+    inventory::{held_item_slot::HeldItemSlot, Inventory},
+    item::{
+        consumable::{Consumable, ConsumeHeldItemEvent},
+        Item,
+    },
+    netty::{client::LocalPlayer, sync::events::client_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{shared::build_mode::BuildMode, ship::pilot::Pilot},
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    ui::components::show_cursor::no_open_menus,
+};
+
+fn consume_held_item(
+    inputs: InputChecker,
+    q_local_player: Query<(&HeldItemSlot, &Inventory), (With<LocalPlayer>, Without<Pilot>, Without<BuildMode>, Without<Spectator>)>,
+    items: Res<Registry<Item>>,
+    consumables: Res<Registry<Consumable>>,
+    mut nevw_consume: NettyEventWriter<ConsumeHeldItemEvent>,
+) {
+    // Consumables can't be placed, so using them is the same as placing a block
+    if !inputs.check_just_pressed(CosmosInputs::PlaceBlock) {
+        return;
+    }
+
+    let Ok((held_item, inventory)) = q_local_player.get_single() else {
+        return;
+    };
+
+    let is_consumable = inventory
+        .itemstack_at(held_item.slot() as usize)
+        .and_then(|is| items.try_from_numeric_id(is.item_id()))
+        .is_some_and(|item| consumables.contains(item.unlocalized_name()));
+
+    if is_consumable {
+        nevw_consume.send(ConsumeHeldItemEvent);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        consume_held_item
+            .run_if(no_open_menus)
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
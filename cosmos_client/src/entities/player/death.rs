@@ -0,0 +1,35 @@
+//! Moves the local player to where they respawn once they die
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use cosmos_core::{
+    entities::player::death::PlayerRespawnEvent,
+    netty::{client::LocalPlayer, sync::events::client_event::NettyEventReceived, system_sets::NetworkingSystemsSet},
+    physics::location::Location,
+    state::GameState,
+};
+
+fn on_respawn(
+    mut nevr_respawn: EventReader<NettyEventReceived<PlayerRespawnEvent>>,
+    mut q_local_player: Query<(&mut Location, &mut Velocity), With<LocalPlayer>>,
+) {
+    let Some(ev) = nevr_respawn.read().last() else {
+        return;
+    };
+
+    let Ok((mut location, mut velocity)) = q_local_player.get_single_mut() else {
+        return;
+    };
+
+    *location = ev.0;
+    velocity.linvel = Vec3::ZERO;
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        on_respawn
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
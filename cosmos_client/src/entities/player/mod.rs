@@ -1,25 +1,24 @@
 //! Contains systems and components for the player
 
-use bevy::{color::palettes::css, prelude::*};
+use bevy::prelude::*;
 use bevy_rapier3d::prelude::{ActiveEvents, CoefficientCombineRule, Collider, Friction, LockedAxes, ReadMassProperties, RigidBody};
 use cosmos_core::{entities::player::Player, netty::system_sets::NetworkingSystemsSet, persistence::LoadingDistance, state::GameState};
 
+pub mod appearance;
+mod character_screen;
+mod climbing;
+mod consume_item;
+mod death;
 pub mod player_movement;
 pub mod render_distance;
+mod seat;
+pub mod spectator;
 
-fn on_add_player(
-    mut commands: Commands,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    q_player: Query<(Entity, &Player), Added<Player>>,
-) {
+fn on_add_player(mut commands: Commands, q_player: Query<(Entity, &Player), Added<Player>>) {
     for (ent, player) in q_player.iter() {
         commands.entity(ent).insert((
-            Mesh3d(meshes.add(Capsule3d::default())),
-            MeshMaterial3d(materials.add(StandardMaterial {
-                base_color: css::GREEN.into(),
-                ..Default::default()
-            })),
+            // The player's model is a child built by `appearance`
+            Visibility::default(),
             Collider::capsule_y(0.65, 0.25),
             LockedAxes::ROTATION_LOCKED,
             Name::new(format!("Player ({})", player.name())),
@@ -43,6 +42,13 @@ pub(super) fn register(app: &mut App) {
             .run_if(in_state(GameState::Playing).or(in_state(GameState::LoadingWorld))),
     );
 
+    appearance::register(app);
+    character_screen::register(app);
     render_distance::register(app);
     player_movement::register(app);
+    consume_item::register(app);
+    death::register(app);
+    spectator::register(app);
+    seat::register(app);
+    climbing::register(app);
 }
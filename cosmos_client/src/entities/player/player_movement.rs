@@ -6,7 +6,15 @@ use bevy_rapier3d::{
     prelude::{ActiveEvents, Collider, Sensor, Velocity},
 };
 use cosmos_core::{
-    block::specific_blocks::gravity_well::GravityWell,
+    block::specific_blocks::{
+        gravity_well::GravityWell,
+        ladder::{Climbing, CLIMB_SPEED},
+        seat::Seated,
+    },
+    entities::{
+        player::{hunger::Hunger, spectator::Spectator},
+        status_effects::{StatusEffect, StatusEffects},
+    },
     netty::{client::LocalPlayer, system_sets::NetworkingSystemsSet},
     physics::location::LocationPhysicsSet,
     prelude::Planet,
@@ -16,6 +24,7 @@ use cosmos_core::{
 };
 
 use crate::{
+    camera::photo_mode::PhotoMode,
     input::inputs::{CosmosInputs, InputChecker, InputHandler},
     rendering::MainCamera,
     structure::planet::align_player::PlayerAlignment,
@@ -80,29 +89,45 @@ pub(crate) fn process_player_movement(
             Option<&PlayerAlignment>,
             Option<&Grounded>,
             Has<GravityWell>,
+            Has<Climbing>,
+            Option<&Hunger>,
+            Option<&StatusEffects>,
+        ),
+        (
+            With<LocalPlayer>,
+            Without<Pilot>,
+            Without<BuildMode>,
+            Without<Spectator>,
+            Without<Seated>,
         ),
-        (With<LocalPlayer>, Without<Pilot>, Without<BuildMode>),
     >,
     q_camera: Query<&Transform, With<MainCamera>>,
     q_show_cursor: Query<(), With<ShowCursor>>,
     q_exerts_gravity: Query<(), With<Planet>>,
+    photo_mode: Option<Res<PhotoMode>>,
 ) {
-    let any_open_menus = !q_show_cursor.is_empty();
+    let any_open_menus = !q_show_cursor.is_empty() || photo_mode.is_some();
 
     let Ok(cam_trans) = q_camera.get_single() else {
         return;
     };
 
     // This will be err if the player is piloting a ship
-    let Ok((mut velocity, player_transform, player_alignment, grounded, under_gravity_well)) = q_local_player.get_single_mut() else {
+    let Ok((mut velocity, player_transform, player_alignment, grounded, under_gravity_well, climbing, hunger, status_effects)) =
+        q_local_player.get_single_mut()
+    else {
         return;
     };
 
-    let max_speed: f32 = if !any_open_menus && input_handler.check_pressed(CosmosInputs::Sprint) {
+    // Starving players are too weak to sprint
+    let can_sprint = hunger.is_none_or(|h| !h.is_starving());
+
+    let max_speed: f32 = if !any_open_menus && can_sprint && input_handler.check_pressed(CosmosInputs::Sprint) {
         20.0
     } else {
         3.0
     };
+    let max_speed = max_speed * (1.0 + status_effects.map(|e| e.strength(StatusEffect::Speed)).unwrap_or(0.0));
 
     let player_rot = Quat::from_affine3(&player_transform.affine());
     let player_inv_rot = player_rot.inverse();
@@ -167,6 +192,27 @@ pub(crate) fn process_player_movement(
         }
     }
 
+    // Jumping lets go of the ladder, otherwise forward climbs up & backward climbs down until the ground is reached
+    if climbing && (any_open_menus || !input_handler.check_just_pressed(CosmosInputs::Jump)) {
+        let climb_up = !any_open_menus
+            && (input_handler.check_pressed(CosmosInputs::MoveForward) || input_handler.check_pressed(CosmosInputs::MoveUp));
+        let climb_down = !any_open_menus
+            && grounded.is_none()
+            && (input_handler.check_pressed(CosmosInputs::MoveBackward) || input_handler.check_pressed(CosmosInputs::MoveDown));
+
+        new_linvel.y = match (climb_up, climb_down) {
+            (true, false) => CLIMB_SPEED,
+            (false, true) => -CLIMB_SPEED,
+            _ => 0.0,
+        };
+
+        if climb_down {
+            // Climbing down shouldn't walk the player off of the ladder
+            new_linvel.x = 0.0;
+            new_linvel.z = 0.0;
+        }
+    }
+
     if !normalize_y {
         let y = new_linvel.y;
 
@@ -4,15 +4,20 @@ use bevy::prelude::{in_state, App, Changed, Condition, IntoSystemConfigs, Query,
 use bevy_renet2::renet2::RenetClient;
 use cosmos_core::{
     entities::player::render_distance::RenderDistance,
-    netty::{client::LocalPlayer, client_reliable_messages::ClientReliableMessages, cosmos_encoder, NettyChannelClient},
+    netty::{
+        client::LocalPlayer,
+        client_reliable_messages::ClientReliableMessages,
+        network_stats::{NettyClientMessages, NettyMessage},
+        NettyChannelClient,
+    },
     state::GameState,
 };
 
 fn send_render_distance(query: Query<&RenderDistance, (With<LocalPlayer>, Changed<RenderDistance>)>, mut client: ResMut<RenetClient>) {
     if let Ok(render_distance) = query.get_single() {
-        client.send_message(
+        client.send_netty_message(
             NettyChannelClient::Reliable,
-            cosmos_encoder::serialize(&ClientReliableMessages::ChangeRenderDistance {
+            NettyMessage::new(&ClientReliableMessages::ChangeRenderDistance {
                 render_distance: *render_distance,
             }),
         );
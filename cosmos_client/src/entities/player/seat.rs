@@ -0,0 +1,100 @@
+//! Client-side logic for players sitting on chairs & lying in beds
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::specific_blocks::seat::{SeatKind, Seated, StandUpEvent},
+    entities::player::Player,
+    netty::{client::LocalPlayer, sync::events::client_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    state::GameState,
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    rendering::{CameraPlayerOffset, MainCamera},
+    ui::{
+        components::show_cursor::no_open_menus,
+        message::{HudMessage, HudMessages},
+    },
+};
+
+use super::appearance::PlayerModel;
+
+/// Where the camera is relative to a player on this kind of seat
+fn seated_camera_offset(kind: SeatKind) -> Vec3 {
+    match kind {
+        SeatKind::Chair => Vec3::new(0.0, 0.3, 0.0),
+        SeatKind::Bed => Vec3::new(0.0, -0.2, 0.0),
+    }
+}
+
+/// How a player's model is posed on this kind of seat
+fn seated_pose(kind: SeatKind) -> Transform {
+    match kind {
+        // Tucks the lower half of the body in, so it looks like they're sitting
+        SeatKind::Chair => Transform::from_xyz(0.0, 0.2, 0.0).with_scale(Vec3::new(1.0, 0.75, 1.0)),
+        SeatKind::Bed => Transform::from_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)),
+    }
+}
+
+fn stand_up(
+    inputs: InputChecker,
+    q_local_player: Query<(), (With<LocalPlayer>, With<Seated>)>,
+    mut nevw_stand_up: NettyEventWriter<StandUpEvent>,
+) {
+    if !q_local_player.is_empty() && inputs.check_just_pressed(CosmosInputs::Jump) {
+        nevw_stand_up.send(StandUpEvent);
+    }
+}
+
+fn on_sit_down(
+    q_seated: Query<&Seated, (With<LocalPlayer>, Changed<Seated>)>,
+    mut q_main_camera: Query<&mut Transform, With<MainCamera>>,
+    mut hud_messages: ResMut<HudMessages>,
+) {
+    let Ok(seated) = q_seated.get_single() else {
+        return;
+    };
+
+    if let Ok(mut cam_trans) = q_main_camera.get_single_mut() {
+        cam_trans.translation = seated_camera_offset(seated.kind);
+    }
+
+    hud_messages.display_message(HudMessage::with_string("Jump to get up".into()));
+}
+
+fn on_stand_up(
+    mut removed: RemovedComponents<Seated>,
+    q_local_player: Query<&CameraPlayerOffset, With<LocalPlayer>>,
+    mut q_main_camera: Query<&mut Transform, With<MainCamera>>,
+) {
+    for ent in removed.read() {
+        let Ok(cam_offset) = q_local_player.get(ent) else {
+            continue;
+        };
+
+        if let Ok(mut cam_trans) = q_main_camera.get_single_mut() {
+            cam_trans.translation = cam_offset.0;
+        }
+    }
+}
+
+/// Poses the models of players based on what they're sitting on. This also covers models that get rebuilt while seated.
+fn pose_player_models(mut q_models: Query<(&Parent, &mut Transform), With<PlayerModel>>, q_players: Query<Option<&Seated>, With<Player>>) {
+    for (parent, mut transform) in q_models.iter_mut() {
+        let Ok(seated) = q_players.get(parent.get()) else {
+            continue;
+        };
+
+        transform.set_if_neq(seated.map(|s| seated_pose(s.kind)).unwrap_or_default());
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (stand_up.run_if(no_open_menus), on_sit_down, on_stand_up, pose_player_models)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
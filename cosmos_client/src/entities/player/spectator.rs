@@ -0,0 +1,132 @@
+//! Client-side spectator logic - flying around & hiding invisible spectators
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use cosmos_core::{
+    entities::player::{
+        spectator::{Spectator, SpectatorTeleportEvent},
+        Player,
+    },
+    netty::{client::LocalPlayer, sync::events::client_event::NettyEventReceived, system_sets::NetworkingSystemsSet},
+    physics::location::Location,
+    state::GameState,
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    rendering::MainCamera,
+    ui::components::show_cursor::no_open_menus,
+};
+
+use super::player_movement::PlayerMovementSet;
+
+const SPECTATOR_SPEED: f32 = 10.0;
+const SPECTATOR_SPRINT_SPEED: f32 = 50.0;
+
+/// Spectators fly in whatever direction they are looking, and stop as soon as they let go of the movement keys.
+fn process_spectator_movement(
+    input_handler: InputChecker,
+    mut q_local_player: Query<(&mut Velocity, &GlobalTransform), (With<LocalPlayer>, With<Spectator>)>,
+    q_camera: Query<&Transform, With<MainCamera>>,
+) {
+    let Ok((mut velocity, player_g_trans)) = q_local_player.get_single_mut() else {
+        return;
+    };
+
+    let Ok(cam_trans) = q_camera.get_single() else {
+        return;
+    };
+
+    let mut movement = Vec3::ZERO;
+
+    if input_handler.check_pressed(CosmosInputs::MoveForward) {
+        movement += *cam_trans.forward();
+    }
+    if input_handler.check_pressed(CosmosInputs::MoveBackward) {
+        movement -= *cam_trans.forward();
+    }
+    if input_handler.check_pressed(CosmosInputs::MoveRight) {
+        movement += *cam_trans.right();
+    }
+    if input_handler.check_pressed(CosmosInputs::MoveLeft) {
+        movement -= *cam_trans.right();
+    }
+    if input_handler.check_pressed(CosmosInputs::Jump) {
+        movement += Vec3::Y;
+    }
+    if input_handler.check_pressed(CosmosInputs::SlowDown) {
+        movement -= Vec3::Y;
+    }
+
+    let speed = if input_handler.check_pressed(CosmosInputs::Sprint) {
+        SPECTATOR_SPRINT_SPEED
+    } else {
+        SPECTATOR_SPEED
+    };
+
+    velocity.linvel = player_g_trans.rotation() * movement.normalize_or_zero() * speed;
+    velocity.angvel = Vec3::ZERO;
+}
+
+/// Menus being open shouldn't let a spectator drift off forever.
+fn stop_spectator_with_menus_open(mut q_local_player: Query<&mut Velocity, (With<LocalPlayer>, With<Spectator>)>) {
+    for mut velocity in q_local_player.iter_mut() {
+        velocity.linvel = Vec3::ZERO;
+    }
+}
+
+fn on_spectator_teleport(
+    mut nevr_teleport: EventReader<NettyEventReceived<SpectatorTeleportEvent>>,
+    mut q_local_player: Query<(&mut Location, &mut Velocity), With<LocalPlayer>>,
+) {
+    for ev in nevr_teleport.read() {
+        let Ok((mut location, mut velocity)) = q_local_player.get_single_mut() else {
+            continue;
+        };
+
+        *location = ev.0;
+        velocity.linvel = Vec3::ZERO;
+    }
+}
+
+fn hide_invisible_spectators(
+    mut q_spectators: Query<(&Spectator, &mut Visibility), (With<Player>, Without<LocalPlayer>, Changed<Spectator>)>,
+) {
+    for (spectator, mut visibility) in q_spectators.iter_mut() {
+        *visibility = if spectator.invisible {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+    }
+}
+
+fn show_former_spectators(mut removed: RemovedComponents<Spectator>, mut q_players: Query<&mut Visibility, With<Player>>) {
+    for ent in removed.read() {
+        if let Ok(mut visibility) = q_players.get_mut(ent) {
+            *visibility = Visibility::Inherited;
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            on_spectator_teleport,
+            process_spectator_movement.run_if(no_open_menus),
+            stop_spectator_with_menus_open.run_if(not(no_open_menus)),
+        )
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .in_set(PlayerMovementSet::ProcessPlayerMovement)
+            .run_if(in_state(GameState::Playing)),
+    )
+    .add_systems(
+        Update,
+        (hide_invisible_spectators, show_former_spectators)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
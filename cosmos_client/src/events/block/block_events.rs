@@ -6,11 +6,15 @@ use cosmos_core::{
     block::{
         block_events::{BlockEventsSet, BlockInteractEvent},
         block_rotation::BlockRotation,
+        Block,
     },
     netty::{
         client_reliable_messages::ClientReliableMessages,
-        cosmos_encoder,
-        sync::mapping::{Mappable, NetworkMapping},
+        network_stats::{NettyClientMessages, NettyMessage},
+        sync::{
+            mapping::{Mappable, NetworkMapping},
+            registry::client::RegistryIdMap,
+        },
         system_sets::NetworkingSystemsSet,
         NettyChannelClient,
     },
@@ -50,9 +54,9 @@ fn handle_block_break(
             continue;
         };
 
-        client.send_message(
+        client.send_netty_message(
             NettyChannelClient::Reliable,
-            cosmos_encoder::serialize(&ClientReliableMessages::BreakBlock { block: sb }),
+            NettyMessage::new(&ClientReliableMessages::BreakBlock { block: sb }),
         );
     }
 }
@@ -61,17 +65,23 @@ fn handle_block_place(
     mut event_reader: EventReader<RequestBlockPlaceEvent>,
     mut client: ResMut<RenetClient>,
     network_mapping: Res<NetworkMapping>,
+    block_id_map: Res<RegistryIdMap<Block>>,
 ) {
     for ev in event_reader.read() {
         let Ok(sb) = ev.block.map_to_server(&network_mapping) else {
             continue;
         };
 
-        client.send_message(
+        let Some(block_id) = block_id_map.to_server(ev.block_id) else {
+            warn!("Tried to place a block the server doesn't have ({})!", ev.block_id);
+            continue;
+        };
+
+        client.send_netty_message(
             NettyChannelClient::Reliable,
-            cosmos_encoder::serialize(&ClientReliableMessages::PlaceBlock {
+            NettyMessage::new(&ClientReliableMessages::PlaceBlock {
                 block: sb,
-                block_id: ev.block_id,
+                block_id,
                 block_rotation: ev.block_rotation,
                 inventory_slot: ev.inventory_slot as u32,
             }),
@@ -89,9 +99,9 @@ fn handle_block_interact(
             continue;
         };
 
-        client.send_message(
+        client.send_netty_message(
             NettyChannelClient::Reliable,
-            cosmos_encoder::serialize(&ClientReliableMessages::InteractWithBlock {
+            NettyMessage::new(&ClientReliableMessages::InteractWithBlock {
                 block_including_fluids: server_structure_block,
                 block: ev.block.and_then(|b| b.map_to_server(&network_mapping).ok()),
                 alternate: ev.alternate,
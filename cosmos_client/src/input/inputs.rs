@@ -1,8 +1,11 @@
 //! Represents the cosmos input systems
 
-use bevy::{prelude::*, utils::HashMap};
+use std::fs;
 
-#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+use bevy::{input::InputSystem, prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
 /// This should be refactored into a registry, but for now, enjoy enum!
 ///
 /// Use this for input handling to allow things to be automatically changed
@@ -92,6 +95,12 @@ pub enum CosmosInputs {
     SymmetryY,
     /// Creates a Z symmetry
     SymmetryZ,
+    /// When combined with a symmetry key, the structure being built is rotated around that axis instead
+    RotateStructureFlag,
+    /// When combined with a symmetry key, the structure being built is mirrored across that axis instead
+    MirrorStructureFlag,
+    /// Cycles through the station prefabs that can be attached to station connectors
+    CycleStationPrefab,
 
     /// Focuses/unfofcuses the waypoint the player is looking at
     FocusWaypoint,
@@ -122,6 +131,8 @@ pub enum CosmosInputs {
     ToggleWaypoint,
     /// For debug only - teleports player to the selected spot on the map
     TeleportSelected,
+    /// Moves the map to the next closest star
+    SelectNextStar,
 
     /// Toggles the send-chat window
     ToggleChat,
@@ -130,10 +141,261 @@ pub enum CosmosInputs {
 
     /// Instead of crafting 1, the maximum amount will be crafted
     BulkCraft,
+
+    /// Shows/hides the network statistics debug overlay
+    ToggleNetworkStats,
+    /// Shows/hides the performance profiling debug overlay
+    ToggleProfiler,
+
+    /// Switches between the first and third person camera
+    ToggleCameraMode,
+    /// While held in third person, moving the mouse orbits the camera around the ship instead of steering it
+    OrbitCamera,
+
+    /// Cycles through the flight assist modes of the ship being piloted
+    ToggleFlightAssist,
+    /// Engages the autopilot towards the focused waypoint, or disengages it if it's already engaged
+    ToggleAutopilot,
+    /// Opens a prompt to send a message to the focused ship or station
+    HailTarget,
+    /// Enters/leaves photo mode, which hides the UI and frees the camera
+    TogglePhotoMode,
+    /// Saves a screenshot while in photo mode
+    TakePhoto,
+    /// Resets the photo camera's position, rotation & FOV
+    ResetPhotoCamera,
+    /// Shows/hides the energy overlay while in build mode
+    ToggleEnergyOverlay,
+    /// Shows/hides the minimap
+    ToggleMinimap,
+    /// Makes the minimap cover a smaller area
+    MinimapZoomIn,
+    /// Makes the minimap cover a larger area
+    MinimapZoomOut,
+    /// Shows/hides the logic debug overlay while holding a logic wrench
+    ToggleLogicDebugOverlay,
+    /// Opens the upgrade modules of the tool the player is holding
+    OpenToolModules,
+    /// Opens the menu used to choose which systems each hotbar slot activates while piloting
+    OpenActivationGroups,
+    /// Opens the menu used to choose which systems get energy first while piloting
+    OpenEnergyPriorities,
+    /// Cycles which subsystem of their targets the pilot's weapons favor
+    CycleTargetedSubsystem,
+    /// Spins the block being placed around its top face
+    SpinBlockPlacement,
+    /// Tilts the block being placed forwards
+    TiltBlockPlacement,
+    /// Rolls the block being placed sideways
+    RollBlockPlacement,
+    /// Places blocks with their default rotation again
+    ResetBlockPlacementRotation,
+    /// Pings where the player is looking (or the sector selected on the map) for the rest of their party
+    PingLocation,
+    /// Shares the sector selected on the map with the player's party, or stops sharing it
+    ToggleSharedWaypoint,
+    /// Opens the character screen, where the player can change how they look
+    OpenCharacterScreen,
+}
+
+/// Where the player's controls are saved
+const CONTROLS_FILE: &str = "settings/controls.toml";
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+/// The situations an input is used in.
+///
+/// Two inputs bound to the same key only conflict if they can be used in the same context.
+pub enum InputContext {
+    /// Used everywhere - conflicts with every other input
+    Global,
+    /// Walking around (not piloting or building)
+    OnFoot,
+    /// Piloting a ship
+    Piloting,
+    /// In build mode
+    Building,
+    /// An inventory is open
+    Inventory,
+    /// The map is open
+    Map,
+    /// The chat is open
+    Chat,
+    /// In photo mode
+    PhotoMode,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+/// What a modifier input changes when it's held down.
+///
+/// Modifiers are always held along with something else, so they can share a key with inputs that are used on
+/// their own (such as sprinting). Two modifiers only conflict if they change the same thing.
+pub enum ModifiedAction {
+    /// Clicking an item in an open inventory
+    ItemClick,
+    /// Pressing a symmetry key in build mode
+    Symmetry,
+    /// Interacting with a block
+    BlockInteraction,
+    /// Dropping the held item
+    DropItem,
+    /// Crafting something in a crafting menu
+    Craft,
 }
 
-fn init_input(mut input_handler: ResMut<CosmosInputHandler>) {
-    // In future load these from settings
+impl CosmosInputs {
+    /// The contexts this input is used in.
+    pub fn contexts(&self) -> &'static [InputContext] {
+        use InputContext as C;
+
+        match self {
+            Self::MoveForward | Self::MoveBackward | Self::MoveLeft | Self::MoveRight => {
+                &[C::OnFoot, C::Piloting, C::Building, C::PhotoMode]
+            }
+            Self::SlowDown => &[C::OnFoot, C::Piloting, C::Building],
+            Self::Jump => &[C::OnFoot, C::Building],
+            Self::Sprint => &[C::OnFoot, C::Building, C::PhotoMode],
+            Self::MoveDown | Self::MoveUp => &[C::Piloting, C::Building, C::PhotoMode],
+            Self::RollLeft | Self::RollRight => &[C::Piloting, C::PhotoMode],
+            Self::TogglePhotoMode => &[C::Global],
+            Self::TakePhoto | Self::ResetPhotoCamera => &[C::PhotoMode],
+            Self::ToggleMinimap | Self::MinimapZoomIn | Self::MinimapZoomOut => &[C::OnFoot, C::Piloting, C::Building],
+            Self::ToggleLogicDebugOverlay => &[C::OnFoot, C::Building],
+            Self::SpinBlockPlacement | Self::TiltBlockPlacement | Self::RollBlockPlacement | Self::ResetBlockPlacementRotation => {
+                &[C::OnFoot, C::Building]
+            }
+            Self::OpenToolModules => &[C::OnFoot],
+            Self::OpenActivationGroups | Self::OpenEnergyPriorities | Self::CycleTargetedSubsystem => &[C::Piloting],
+            Self::StopPiloting | Self::UseSelectedSystem | Self::ToggleFlightAssist | Self::ToggleAutopilot | Self::HailTarget => {
+                &[C::Piloting]
+            }
+            Self::SwapCameraLeft | Self::SwapCameraRight | Self::OrbitCamera => &[C::Piloting],
+            Self::ToggleCameraMode => &[C::OnFoot, C::Piloting, C::Building],
+            Self::LeaveShip | Self::CreateShip | Self::CreateStation => &[C::OnFoot],
+            Self::BreakBlock | Self::PlaceBlock | Self::Interact | Self::ToggleBuildMode => &[C::OnFoot, C::Building],
+            Self::SymmetryX | Self::SymmetryY | Self::SymmetryZ | Self::CycleStationPrefab | Self::ToggleEnergyOverlay => &[C::Building],
+            Self::ClearSymmetry | Self::RotateStructureFlag | Self::MirrorStructureFlag => &[C::Building],
+            Self::Pause | Self::PanoramaScreenshot | Self::ToggleNetworkStats | Self::ToggleProfiler => &[C::Global],
+            Self::HotbarSlot1
+            | Self::HotbarSlot2
+            | Self::HotbarSlot3
+            | Self::HotbarSlot4
+            | Self::HotbarSlot5
+            | Self::HotbarSlot6
+            | Self::HotbarSlot7
+            | Self::HotbarSlot8
+            | Self::HotbarSlot9
+            | Self::ToggleChat => &[C::OnFoot, C::Piloting, C::Building],
+            Self::FocusWaypoint => &[C::OnFoot, C::Piloting],
+            Self::ToggleInventory => &[C::OnFoot, C::Piloting, C::Building, C::Inventory],
+            Self::DropItem | Self::BulkDropFlag => &[C::OnFoot, C::Building, C::Inventory],
+            Self::AutoMoveItem | Self::BulkCraft => &[C::Inventory],
+            Self::AlternateInteraction => &[C::OnFoot, C::Building],
+            Self::ToggleMap => &[C::OnFoot, C::Piloting, C::Building, C::Map],
+            Self::ResetMapPosition | Self::ToggleWaypoint | Self::TeleportSelected | Self::SelectNextStar | Self::ToggleSharedWaypoint => {
+                &[C::Map]
+            }
+            Self::PingLocation => &[C::OnFoot, C::Piloting, C::Building, C::Map],
+            Self::OpenCharacterScreen => &[C::OnFoot],
+            Self::SendChatMessage => &[C::Chat],
+        }
+    }
+
+    /// If this input is a modifier, what it changes when held down.
+    pub fn modifies(&self) -> Option<ModifiedAction> {
+        use ModifiedAction as M;
+
+        match self {
+            Self::AutoMoveItem => Some(M::ItemClick),
+            Self::ClearSymmetry | Self::RotateStructureFlag | Self::MirrorStructureFlag => Some(M::Symmetry),
+            Self::AlternateInteraction => Some(M::BlockInteraction),
+            Self::BulkDropFlag => Some(M::DropItem),
+            Self::BulkCraft => Some(M::Craft),
+            _ => None,
+        }
+    }
+
+    /// Returns true if these two inputs can be used at the same time, meaning they should not share a binding.
+    ///
+    /// Modifiers are only compared with other modifiers (see [`ModifiedAction`]). An input without any contexts
+    /// is treated as [`InputContext::Global`].
+    pub fn shares_context_with(&self, other: CosmosInputs) -> bool {
+        match (self.modifies(), other.modifies()) {
+            (Some(a), Some(b)) => return a == b,
+            (Some(_), None) | (None, Some(_)) => return false,
+            (None, None) => {}
+        }
+
+        let is_global = |contexts: &[InputContext]| contexts.is_empty() || contexts.contains(&InputContext::Global);
+
+        let (a, b) = (self.contexts(), other.contexts());
+
+        is_global(a) || is_global(b) || a.iter().any(|c| b.contains(c))
+    }
+
+    /// A human-readable name for this input
+    pub fn display_name(&self) -> String {
+        split_camel_case(&format!("{self:?}"))
+    }
+}
+
+fn split_camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len() + 4);
+
+    for (i, c) in name.chars().enumerate() {
+        if i != 0 && (c.is_uppercase() || (c.is_ascii_digit() && !name[..i].ends_with(|x: char| x.is_ascii_digit()))) {
+            result.push(' ');
+        }
+        result.push(c);
+    }
+
+    result
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+/// A keyboard key or mouse button an input can be bound to
+pub enum InputBinding {
+    /// A keyboard key
+    Key(KeyCode),
+    /// A mouse button
+    Mouse(MouseButton),
+}
+
+impl InputBinding {
+    /// A human-readable name for this binding
+    pub fn display_name(&self) -> String {
+        match self {
+            Self::Key(key) => {
+                let name = format!("{key:?}");
+                let name = name
+                    .strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .unwrap_or(name.as_str());
+
+                split_camel_case(name)
+            }
+            Self::Mouse(button) => format!("{button:?} Mouse"),
+        }
+    }
+}
+
+/// A human-readable name for a gamepad button
+pub fn gamepad_button_display_name(button: GamepadButton) -> String {
+    format!("Gamepad {}", split_camel_case(&format!("{button:?}")))
+}
+
+#[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Serialize, Deserialize)]
+/// Everything a single [`CosmosInputs`] is bound to
+pub struct ControlBinding {
+    /// The keyboard key or mouse button used for this
+    pub binding: Option<InputBinding>,
+    /// The gamepad button that can be used as an alternative to [`Self::binding`]
+    pub gamepad: Option<GamepadButton>,
+}
+
+/// The controls every player starts with
+pub fn default_input_handler() -> CosmosInputHandler {
+    let mut input_handler = CosmosInputHandler::default();
+
     input_handler.set_keycode(CosmosInputs::MoveForward, KeyCode::KeyW);
     input_handler.set_keycode(CosmosInputs::MoveLeft, KeyCode::KeyA);
     input_handler.set_keycode(CosmosInputs::MoveBackward, KeyCode::KeyS);
@@ -179,6 +441,19 @@ fn init_input(mut input_handler: ResMut<CosmosInputHandler>) {
     input_handler.set_keycode(CosmosInputs::SymmetryX, KeyCode::KeyX);
     input_handler.set_keycode(CosmosInputs::SymmetryY, KeyCode::KeyY);
     input_handler.set_keycode(CosmosInputs::SymmetryZ, KeyCode::KeyZ);
+    input_handler.set_keycode(CosmosInputs::RotateStructureFlag, KeyCode::ControlLeft);
+    input_handler.set_keycode(CosmosInputs::MirrorStructureFlag, KeyCode::AltLeft);
+    input_handler.set_keycode(CosmosInputs::CycleStationPrefab, KeyCode::KeyH);
+    input_handler.set_keycode(CosmosInputs::ToggleEnergyOverlay, KeyCode::KeyO);
+    input_handler.set_keycode(CosmosInputs::ToggleLogicDebugOverlay, KeyCode::KeyK);
+    input_handler.set_keycode(CosmosInputs::OpenToolModules, KeyCode::KeyU);
+    input_handler.set_keycode(CosmosInputs::OpenActivationGroups, KeyCode::KeyU);
+    input_handler.set_keycode(CosmosInputs::OpenEnergyPriorities, KeyCode::KeyO);
+    input_handler.set_keycode(CosmosInputs::CycleTargetedSubsystem, KeyCode::KeyJ);
+    input_handler.set_keycode(CosmosInputs::SpinBlockPlacement, KeyCode::ArrowRight);
+    input_handler.set_keycode(CosmosInputs::TiltBlockPlacement, KeyCode::ArrowUp);
+    input_handler.set_keycode(CosmosInputs::RollBlockPlacement, KeyCode::ArrowDown);
+    input_handler.set_keycode(CosmosInputs::ResetBlockPlacementRotation, KeyCode::ArrowLeft);
 
     input_handler.set_keycode(CosmosInputs::FocusWaypoint, KeyCode::KeyF);
 
@@ -196,19 +471,99 @@ fn init_input(mut input_handler: ResMut<CosmosInputHandler>) {
     input_handler.set_keycode(CosmosInputs::ResetMapPosition, KeyCode::KeyR);
     input_handler.set_keycode(CosmosInputs::ToggleWaypoint, KeyCode::Enter);
     input_handler.set_keycode(CosmosInputs::TeleportSelected, KeyCode::KeyT);
+    input_handler.set_keycode(CosmosInputs::SelectNextStar, KeyCode::Tab);
+    input_handler.set_keycode(CosmosInputs::ToggleSharedWaypoint, KeyCode::KeyP);
+    input_handler.set_mouse_button(CosmosInputs::PingLocation, MouseButton::Middle);
+
+    input_handler.set_keycode(CosmosInputs::OpenCharacterScreen, KeyCode::KeyI);
 
     input_handler.set_keycode(CosmosInputs::ToggleChat, KeyCode::Enter);
     input_handler.set_keycode(CosmosInputs::SendChatMessage, KeyCode::Enter);
 
     input_handler.set_keycode(CosmosInputs::BulkCraft, KeyCode::ShiftLeft);
+
+    input_handler.set_keycode(CosmosInputs::ToggleNetworkStats, KeyCode::F3);
+    input_handler.set_keycode(CosmosInputs::ToggleProfiler, KeyCode::F4);
+
+    input_handler.set_keycode(CosmosInputs::ToggleCameraMode, KeyCode::F5);
+    input_handler.set_keycode(CosmosInputs::OrbitCamera, KeyCode::AltLeft);
+
+    input_handler.set_keycode(CosmosInputs::ToggleFlightAssist, KeyCode::KeyV);
+    input_handler.set_keycode(CosmosInputs::ToggleAutopilot, KeyCode::KeyP);
+    input_handler.set_keycode(CosmosInputs::HailTarget, KeyCode::KeyH);
+
+    input_handler.set_keycode(CosmosInputs::TogglePhotoMode, KeyCode::F2);
+    input_handler.set_mouse_button(CosmosInputs::TakePhoto, MouseButton::Left);
+    input_handler.set_keycode(CosmosInputs::ResetPhotoCamera, KeyCode::KeyR);
+
+    input_handler.set_keycode(CosmosInputs::ToggleMinimap, KeyCode::KeyN);
+    input_handler.set_keycode(CosmosInputs::MinimapZoomIn, KeyCode::Equal);
+    input_handler.set_keycode(CosmosInputs::MinimapZoomOut, KeyCode::Minus);
+
+    input_handler.set_gamepad_button(CosmosInputs::Jump, GamepadButton::South);
+    input_handler.set_gamepad_button(CosmosInputs::Interact, GamepadButton::West);
+    input_handler.set_gamepad_button(CosmosInputs::StopPiloting, GamepadButton::East);
+    input_handler.set_gamepad_button(CosmosInputs::ToggleInventory, GamepadButton::North);
+    input_handler.set_gamepad_button(CosmosInputs::Pause, GamepadButton::Start);
+    input_handler.set_gamepad_button(CosmosInputs::ToggleMap, GamepadButton::Select);
+    input_handler.set_gamepad_button(CosmosInputs::BreakBlock, GamepadButton::RightTrigger2);
+    input_handler.set_gamepad_button(CosmosInputs::UseSelectedSystem, GamepadButton::RightTrigger2);
+    input_handler.set_gamepad_button(CosmosInputs::PlaceBlock, GamepadButton::LeftTrigger2);
+    input_handler.set_gamepad_button(CosmosInputs::Sprint, GamepadButton::LeftThumb);
+    input_handler.set_gamepad_button(CosmosInputs::SlowDown, GamepadButton::RightThumb);
+    input_handler.set_gamepad_button(CosmosInputs::MoveUp, GamepadButton::RightTrigger);
+    input_handler.set_gamepad_button(CosmosInputs::MoveDown, GamepadButton::LeftTrigger);
+    input_handler.set_gamepad_button(CosmosInputs::MoveForward, GamepadButton::DPadUp);
+    input_handler.set_gamepad_button(CosmosInputs::MoveBackward, GamepadButton::DPadDown);
+    input_handler.set_gamepad_button(CosmosInputs::MoveLeft, GamepadButton::DPadLeft);
+    input_handler.set_gamepad_button(CosmosInputs::MoveRight, GamepadButton::DPadRight);
+
+    input_handler
 }
 
-#[derive(Resource, Default, Debug)]
+#[derive(Resource, Default, Debug, Clone, PartialEq)]
 /// Use this to check if inputs are selected
 ///
 /// You should generally prefer to use the `InputChecker` unless you're doing something super specific.
 pub struct CosmosInputHandler {
-    input_mapping: HashMap<CosmosInputs, (Option<KeyCode>, Option<MouseButton>)>,
+    input_mapping: HashMap<CosmosInputs, ControlBinding>,
+}
+
+#[derive(Resource, Default, Debug)]
+/// The combined state of every connected gamepad's buttons.
+///
+/// Inputs don't care which gamepad was used, so this lets gamepad buttons be checked the same way keys are.
+pub struct GamepadInputs(ButtonInput<GamepadButton>);
+
+impl GamepadInputs {
+    /// The raw button state of every connected gamepad combined
+    pub fn buttons(&self) -> &ButtonInput<GamepadButton> {
+        &self.0
+    }
+}
+
+fn update_gamepad_inputs(mut gamepad_inputs: ResMut<GamepadInputs>, q_gamepads: Query<&Gamepad>) {
+    let gamepad_inputs = &mut gamepad_inputs.0;
+    gamepad_inputs.clear();
+
+    let pressed = q_gamepads
+        .iter()
+        .flat_map(|gamepad| gamepad.get_pressed().copied())
+        .collect::<Vec<GamepadButton>>();
+
+    let released = gamepad_inputs
+        .get_pressed()
+        .filter(|button| !pressed.contains(button))
+        .copied()
+        .collect::<Vec<GamepadButton>>();
+
+    for button in released {
+        gamepad_inputs.release(button);
+    }
+
+    for button in pressed {
+        gamepad_inputs.press(button);
+    }
 }
 
 /// A wrapper around [`CosmosInputHandler`] and all the resources it needs.
@@ -236,6 +591,9 @@ pub trait InputHandler {
 
     /// Gets the raw mouse inputs structure (Res<ButtonInput<KeyCode>>)
     fn mouse_inputs(&self) -> &ButtonInput<MouseButton>;
+
+    /// Gets the raw combined gamepad inputs structure
+    fn gamepad_inputs(&self) -> &ButtonInput<GamepadButton>;
 }
 
 /// A wrapper around [`CosmosInputHandler`] and all the resources it needs.
@@ -245,23 +603,24 @@ pub type InputChecker<'a> = (
     Res<'a, CosmosInputHandler>,
     Res<'a, ButtonInput<KeyCode>>,
     Res<'a, ButtonInput<MouseButton>>,
+    Res<'a, GamepadInputs>,
 );
 
 impl InputHandler for InputChecker<'_> {
     fn check_just_pressed(&self, input_code: CosmosInputs) -> bool {
-        self.0.check_just_pressed(input_code, &self.1, &self.2)
+        self.0.check_just_pressed(input_code, &self.1, &self.2, &self.3 .0)
     }
 
     fn check_just_released(&self, input_code: CosmosInputs) -> bool {
-        self.0.check_just_released(input_code, &self.1, &self.2)
+        self.0.check_just_released(input_code, &self.1, &self.2, &self.3 .0)
     }
 
     fn check_pressed(&self, input_code: CosmosInputs) -> bool {
-        self.0.check_pressed(input_code, &self.1, &self.2)
+        self.0.check_pressed(input_code, &self.1, &self.2, &self.3 .0)
     }
 
     fn check_released(&self, input_code: CosmosInputs) -> bool {
-        self.0.check_released(input_code, &self.1, &self.2)
+        self.0.check_released(input_code, &self.1, &self.2, &self.3 .0)
     }
 
     fn key_inputs(&self) -> &ButtonInput<KeyCode> {
@@ -271,6 +630,10 @@ impl InputHandler for InputChecker<'_> {
     fn mouse_inputs(&self) -> &ButtonInput<MouseButton> {
         &self.2
     }
+
+    fn gamepad_inputs(&self) -> &ButtonInput<GamepadButton> {
+        &self.3 .0
+    }
 }
 
 impl CosmosInputHandler {
@@ -282,75 +645,201 @@ impl CosmosInputHandler {
     /// Check if the given input was just released.
     ///
     /// Use this to see if something was held in the last frame but is no longer being held.
-    pub fn check_just_released(&self, input_code: CosmosInputs, inputs: &ButtonInput<KeyCode>, mouse: &ButtonInput<MouseButton>) -> bool {
-        let keycode = self.keycode_for(input_code);
-        let mouse_button = self.mouse_button_for(input_code);
+    pub fn check_just_released(
+        &self,
+        input_code: CosmosInputs,
+        inputs: &ButtonInput<KeyCode>,
+        mouse: &ButtonInput<MouseButton>,
+        gamepad: &ButtonInput<GamepadButton>,
+    ) -> bool {
+        let binding = self.binding_for(input_code);
+
+        match binding.binding {
+            Some(InputBinding::Key(key)) if inputs.just_released(key) => return true,
+            Some(InputBinding::Mouse(button)) if mouse.just_released(button) => return true,
+            _ => {}
+        }
 
-        keycode.is_some() && inputs.just_released(keycode.unwrap()) || mouse_button.is_some() && mouse.just_released(mouse_button.unwrap())
+        binding.gamepad.is_some_and(|button| gamepad.just_released(button))
     }
 
     /// Check if the given input is not being used.
-    pub fn check_released(&self, input_code: CosmosInputs, inputs: &ButtonInput<KeyCode>, mouse: &ButtonInput<MouseButton>) -> bool {
-        !self.check_pressed(input_code, inputs, mouse)
+    pub fn check_released(
+        &self,
+        input_code: CosmosInputs,
+        inputs: &ButtonInput<KeyCode>,
+        mouse: &ButtonInput<MouseButton>,
+        gamepad: &ButtonInput<GamepadButton>,
+    ) -> bool {
+        !self.check_pressed(input_code, inputs, mouse, gamepad)
     }
 
     /// Checks if the given input was just pressed.
     ///
     /// Use this to see if something was pressed just this frame.
-    pub fn check_just_pressed(&self, input_code: CosmosInputs, inputs: &ButtonInput<KeyCode>, mouse: &ButtonInput<MouseButton>) -> bool {
-        let keycode = self.keycode_for(input_code);
-        let mouse_button = self.mouse_button_for(input_code);
+    pub fn check_just_pressed(
+        &self,
+        input_code: CosmosInputs,
+        inputs: &ButtonInput<KeyCode>,
+        mouse: &ButtonInput<MouseButton>,
+        gamepad: &ButtonInput<GamepadButton>,
+    ) -> bool {
+        let binding = self.binding_for(input_code);
+
+        match binding.binding {
+            Some(InputBinding::Key(key)) if inputs.just_pressed(key) => return true,
+            Some(InputBinding::Mouse(button)) if mouse.just_pressed(button) => return true,
+            _ => {}
+        }
 
-        keycode.is_some() && inputs.just_pressed(keycode.unwrap()) || mouse_button.is_some() && mouse.just_pressed(mouse_button.unwrap())
+        binding.gamepad.is_some_and(|button| gamepad.just_pressed(button))
     }
 
     /// Check if this input is currently being used.
-    pub fn check_pressed(&self, input_code: CosmosInputs, keys: &ButtonInput<KeyCode>, mouse: &ButtonInput<MouseButton>) -> bool {
-        let keycode = self.keycode_for(input_code);
-        let mouse_button = self.mouse_button_for(input_code);
+    pub fn check_pressed(
+        &self,
+        input_code: CosmosInputs,
+        keys: &ButtonInput<KeyCode>,
+        mouse: &ButtonInput<MouseButton>,
+        gamepad: &ButtonInput<GamepadButton>,
+    ) -> bool {
+        let binding = self.binding_for(input_code);
+
+        match binding.binding {
+            Some(InputBinding::Key(key)) if keys.pressed(key) => return true,
+            Some(InputBinding::Mouse(button)) if mouse.pressed(button) => return true,
+            _ => {}
+        }
 
-        keycode.is_some() && keys.pressed(keycode.unwrap()) || mouse_button.is_some() && mouse.pressed(mouse_button.unwrap())
+        binding.gamepad.is_some_and(|button| gamepad.pressed(button))
     }
 
-    fn set_keycode(&mut self, input: CosmosInputs, keycode: KeyCode) {
-        if self.input_mapping.contains_key(&input) {
-            let mapping = self.input_mapping.get_mut(&input).unwrap();
+    /// Gets everything this input is bound to
+    pub fn binding_for(&self, input: CosmosInputs) -> ControlBinding {
+        self.input_mapping.get(&input).copied().unwrap_or_default()
+    }
 
-            mapping.0 = Some(keycode);
-            mapping.1 = None;
-        } else {
-            self.input_mapping.insert(input, (Some(keycode), None));
-        }
+    /// Iterates over every input + what it's bound to
+    pub fn iter(&self) -> impl Iterator<Item = (CosmosInputs, ControlBinding)> + '_ {
+        self.input_mapping.iter().map(|(input, binding)| (*input, *binding))
+    }
+
+    /// Sets (or clears if `None`) the keyboard key or mouse button for this input.
+    pub fn set_binding(&mut self, input: CosmosInputs, binding: Option<InputBinding>) {
+        self.input_mapping.entry(input).or_default().binding = binding;
+    }
+
+    /// Sets (or clears if `None`) the gamepad button for this input.
+    pub fn set_gamepad_binding(&mut self, input: CosmosInputs, button: Option<GamepadButton>) {
+        self.input_mapping.entry(input).or_default().gamepad = button;
+    }
+
+    fn set_keycode(&mut self, input: CosmosInputs, keycode: KeyCode) {
+        self.set_binding(input, Some(InputBinding::Key(keycode)));
     }
 
     fn set_mouse_button(&mut self, input: CosmosInputs, button: MouseButton) {
-        if self.input_mapping.contains_key(&input) {
-            let mapping = self.input_mapping.get_mut(&input).unwrap();
+        self.set_binding(input, Some(InputBinding::Mouse(button)));
+    }
 
-            mapping.0 = None;
-            mapping.1 = Some(button);
-        } else {
-            self.input_mapping.insert(input, (None, Some(button)));
-        }
+    fn set_gamepad_button(&mut self, input: CosmosInputs, button: GamepadButton) {
+        self.set_gamepad_binding(input, Some(button));
+    }
+
+    /// Returns every other input that shares a binding with this input and can be used at the same
+    /// time (see [`CosmosInputs::shares_context_with`]).
+    pub fn conflicts_for(&self, input: CosmosInputs) -> Vec<CosmosInputs> {
+        let binding = self.binding_for(input);
+
+        self.input_mapping
+            .iter()
+            .filter(|(&other, other_binding)| {
+                other != input
+                    && input.shares_context_with(other)
+                    && ((binding.binding.is_some() && binding.binding == other_binding.binding)
+                        || (binding.gamepad.is_some() && binding.gamepad == other_binding.gamepad))
+            })
+            .map(|(&other, _)| other)
+            .collect()
     }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct SerializedControls(HashMap<String, ControlBinding>);
+
+fn load_controls(mut input_handler: ResMut<CosmosInputHandler>) {
+    *input_handler = default_input_handler();
 
-    fn keycode_for(&self, input: CosmosInputs) -> Option<KeyCode> {
-        if !self.input_mapping.contains_key(&input) {
-            return None;
+    let Ok(contents) = fs::read_to_string(CONTROLS_FILE) else {
+        return;
+    };
+
+    let serialized = match toml::from_str::<SerializedControls>(&contents) {
+        Ok(serialized) => serialized,
+        Err(e) => {
+            error!("Unable to parse controls file - using default controls. {e:?}");
+            return;
         }
+    };
 
-        self.input_mapping[&input].0
+    // Inputs are saved by name so the file stays readable + survives inputs being reordered.
+    let inputs = input_handler.iter().map(|(input, _)| input).collect::<Vec<_>>();
+    for input in inputs {
+        if let Some(binding) = serialized.0.get(&format!("{input:?}")) {
+            input_handler.input_mapping.insert(input, *binding);
+        }
     }
+}
 
-    fn mouse_button_for(&self, input: CosmosInputs) -> Option<MouseButton> {
-        if !self.input_mapping.contains_key(&input) {
-            return None;
+/// Saves the current controls to the controls file
+pub fn save_controls(input_handler: &CosmosInputHandler) {
+    let serialized = SerializedControls(
+        input_handler
+            .iter()
+            .map(|(input, binding)| (format!("{input:?}"), binding))
+            .collect(),
+    );
+
+    let toml = match toml::to_string(&serialized) {
+        Ok(toml) => toml,
+        Err(e) => {
+            error!("Unable to serialize controls - {e:?}");
+            return;
         }
+    };
 
-        self.input_mapping[&input].1
+    _ = fs::create_dir("settings");
+
+    if let Err(e) = fs::write(CONTROLS_FILE, toml) {
+        error!("Unable to save controls - {e:?}");
     }
 }
 
 pub(super) fn register(app: &mut App) {
-    app.insert_resource(CosmosInputHandler::new()).add_systems(Startup, init_input);
+    app.insert_resource(CosmosInputHandler::new())
+        .init_resource::<GamepadInputs>()
+        .add_systems(Startup, load_controls)
+        .add_systems(PreUpdate, update_gamepad_inputs.after(InputSystem));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_bindings_do_not_conflict() {
+        let handler = default_input_handler();
+
+        for (input, _) in handler.iter() {
+            let conflicts = handler.conflicts_for(input);
+            assert!(conflicts.is_empty(), "{input:?} conflicts with {conflicts:?} by default");
+        }
+    }
+
+    #[test]
+    fn modifiers_only_conflict_with_the_same_modifier() {
+        assert!(!CosmosInputs::AutoMoveItem.shares_context_with(CosmosInputs::SlowDown));
+        assert!(!CosmosInputs::AutoMoveItem.shares_context_with(CosmosInputs::BulkCraft));
+        assert!(CosmosInputs::RotateStructureFlag.shares_context_with(CosmosInputs::MirrorStructureFlag));
+    }
 }
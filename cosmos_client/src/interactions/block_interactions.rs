@@ -16,7 +16,7 @@ use cosmos_core::{
         Block,
     },
     blockitems::BlockItems,
-    entities::player::creative::Creative,
+    entities::player::{creative::Creative, spectator::Spectator},
     inventory::Inventory,
     item::Item,
     netty::{client::LocalPlayer, system_sets::NetworkingSystemsSet},
@@ -30,12 +30,15 @@ use cosmos_core::{
 };
 
 use crate::{
+    camera::camera_mode::PlayerEye,
     events::block::block_events::*,
     input::inputs::{CosmosInputs, InputChecker, InputHandler},
-    rendering::MainCamera,
+    structure::station::prefab::SelectedStationPrefab,
     ui::{components::show_cursor::no_open_menus, hotbar::Hotbar},
 };
 
+use super::placement_preview::BlockPlacementRotation;
+
 #[derive(Debug, Clone, Copy)]
 /// Represents a block that is being looked at by the player.
 ///
@@ -55,6 +58,22 @@ pub struct LookingAt {
 
     /// The block the player is looking at, including any fluid blocks
     pub looking_at_block: Option<LookedAtBlock>,
+
+    /// Where the block the player is holding would be placed, if they are holding a block
+    pub placing_block: Option<BlockPlacement>,
+}
+
+#[derive(Debug, Clone, Copy)]
+/// Where & how the block the player is holding would be placed
+pub struct BlockPlacement {
+    /// Where the block would be placed
+    pub block: StructureBlock,
+    /// The block that would be placed
+    pub block_id: u16,
+    /// The inventory slot the block comes from
+    pub inventory_slot: usize,
+    /// The rotation the block would be placed with
+    pub block_rotation: BlockRotation,
 }
 
 fn add_looking_at_component(q_added_player: Query<Entity, Added<LocalPlayer>>, mut commands: Commands) {
@@ -65,18 +84,22 @@ fn add_looking_at_component(q_added_player: Query<Entity, Added<LocalPlayer>>, m
 
 pub(crate) fn process_player_interaction(
     input_handler: InputChecker,
-    camera: Query<&GlobalTransform, With<MainCamera>>,
-    mut q_player: Query<(Entity, &mut Inventory, &mut LookingAt, Option<&Creative>), (With<LocalPlayer>, Without<Pilot>)>,
+    player_eye: PlayerEye,
+    mut q_player: Query<
+        (Entity, &mut Inventory, &mut LookingAt, Option<&Creative>),
+        (With<LocalPlayer>, Without<Pilot>, Without<Spectator>),
+    >,
     rapier_context_access: ReadRapierContext,
     q_chunk_physics_part: Query<&ChunkPhysicsPart>,
     q_structure: Query<(&Structure, &GlobalTransform, Option<&Planet>)>,
-    mut break_writer: EventWriter<RequestBlockBreakEvent>,
     mut place_writer: EventWriter<RequestBlockPlaceEvent>,
     mut interact_writer: EventWriter<BlockInteractEvent>,
     hotbar: Query<&Hotbar>,
     items: Res<Registry<Item>>,
     blocks: Res<Registry<Block>>,
     block_items: Res<BlockItems>,
+    selected_prefab: Res<SelectedStationPrefab>,
+    placement_rotation: Res<BlockPlacementRotation>,
     mut commands: Commands,
 ) {
     let rapier_context = rapier_context_access.single();
@@ -88,14 +111,15 @@ pub(crate) fn process_player_interaction(
 
     looking_at.looking_at_any = None;
     looking_at.looking_at_block = None;
+    looking_at.placing_block = None;
 
-    let Ok(cam_trans) = camera.get_single() else {
+    let Some(cam_trans) = player_eye.global_transform() else {
         return;
     };
 
     let Some((hit_block, mut structure, mut structure_g_transform, mut is_planet)) = send_ray(
         &rapier_context,
-        cam_trans,
+        &cam_trans,
         player_entity,
         &q_chunk_physics_part,
         &q_structure,
@@ -113,7 +137,7 @@ pub(crate) fn process_player_interaction(
     if structure.block_at(hit_block.block.coords(), &blocks).is_fluid() {
         if let Some((hit_block, s, sgt, ip)) = send_ray(
             &rapier_context,
-            cam_trans,
+            &cam_trans,
             player_entity,
             &q_chunk_physics_part,
             &q_structure,
@@ -131,106 +155,111 @@ pub(crate) fn process_player_interaction(
         looking_at.looking_at_block = Some(hit_block);
     }
 
-    if input_handler.check_just_pressed(CosmosInputs::BreakBlock) {
-        if let Some(x) = &looking_at.looking_at_block {
-            break_writer.send(RequestBlockBreakEvent { block: x.block });
-        }
-    }
+    let placing_block = (|| {
+        let looking_at_block = looking_at.looking_at_block.as_ref()?;
 
-    if input_handler.check_just_pressed(CosmosInputs::PlaceBlock) {
-        (|| {
-            let looking_at_block = looking_at.looking_at_block.as_ref()?;
+        let hotbar = hotbar.get_single().ok()?;
 
-            let hotbar = hotbar.get_single().ok()?;
+        let inventory_slot = hotbar.selected_slot();
 
-            let inventory_slot = hotbar.selected_slot();
+        let is = inventory.itemstack_at(inventory_slot)?;
 
-            let is = inventory.itemstack_at(inventory_slot)?;
+        let item = items.from_numeric_id(is.item_id());
 
-            let item = items.from_numeric_id(is.item_id());
+        let block_id = block_items.block_from_item(item)?;
 
-            let block_id = block_items.block_from_item(item)?;
+        let block = blocks.from_numeric_id(block_id);
 
-            let block = blocks.from_numeric_id(block_id);
+        let moved_point = looking_at_block.intersection.point + looking_at_block.intersection.normal * 0.75;
+        let point = structure_g_transform.compute_matrix().inverse().transform_point3(moved_point);
 
-            let moved_point = looking_at_block.intersection.point + looking_at_block.intersection.normal * 0.75;
-            let point = structure_g_transform.compute_matrix().inverse().transform_point3(moved_point);
+        let place_at_coords = structure.relative_coords_to_local_coords_checked(point.x, point.y, point.z).ok()?;
 
-            let place_at_coords = structure.relative_coords_to_local_coords_checked(point.x, point.y, point.z).ok()?;
+        if !structure.is_within_blocks(place_at_coords) {
+            return None;
+        }
 
-            if !structure.is_within_blocks(place_at_coords) {
-                return Some(0); // the return doesn't matter, it's just used for early returns
-            }
+        let block_rotation = if block.is_fully_rotatable() || block.should_face_front() {
+            let delta = UnboundBlockCoordinate::from(place_at_coords) - UnboundBlockCoordinate::from(looking_at_block.block.coords());
 
-            if creative.is_none() {
-                inventory.decrease_quantity_at(inventory_slot, 1, &mut commands);
-            }
+            // Which way the placed block extends out from the block it's placed on.
+            let perpendicular_direction = BlockDirection::from_coordinates(delta);
 
-            let block_rotation = if block.is_fully_rotatable() || block.should_face_front() {
-                let delta = UnboundBlockCoordinate::from(place_at_coords) - UnboundBlockCoordinate::from(looking_at_block.block.coords());
-
-                // Which way the placed block extends out from the block it's placed on.
-                let perpendicular_direction = BlockDirection::from_coordinates(delta);
-
-                if block.should_face_front() {
-                    // Front face always points perpendicular out from the block being placed on.
-                    BlockRotation::face_front(perpendicular_direction)
-                } else {
-                    // Fully rotatable - the top texture of the block should always face the player.
-                    let point = (point - point.floor()) - Vec3::new(0.5, 0.5, 0.5);
-
-                    // Unused coordinate is always within tolerance of +-0.25 (+ side on top/right/front).
-
-                    // The front texture always points in the direction decided by where on the anchor block the player clicked.
-                    let front_facing = match perpendicular_direction {
-                        BlockDirection::PosX | BlockDirection::NegX => {
-                            let (y, z) = if point.y.abs() > point.z.abs() {
-                                (point.y, 0.0)
-                            } else {
-                                (0.0, point.z)
-                            };
-                            BlockDirection::from_vec3(Vec3::new(0.0, y, z))
-                        }
-                        BlockDirection::PosY | BlockDirection::NegY => {
-                            // Only the largest coordinate is kept, but it's sign must be retained.
-                            let (x, z) = if point.x.abs() > point.z.abs() {
-                                (point.x, 0.0)
-                            } else {
-                                (0.0, point.z)
-                            };
-                            BlockDirection::from_vec3(Vec3::new(x, 0.0, z))
-                        }
-                        BlockDirection::PosZ | BlockDirection::NegZ => {
-                            let (x, y) = if point.x.abs() > point.y.abs() {
-                                (point.x, 0.0)
-                            } else {
-                                (0.0, point.y)
-                            };
-                            BlockDirection::from_vec3(Vec3::new(x, y, 0.0))
-                        }
-                    };
-
-                    BlockRotation::from_face_directions(perpendicular_direction, front_facing)
-                }
+            if block.should_face_front() {
+                // Front face always points perpendicular out from the block being placed on.
+                BlockRotation::face_front(perpendicular_direction)
             } else {
-                let block_up = if is_planet.is_some() {
-                    Planet::planet_face(structure, place_at_coords)
-                } else {
-                    BlockFace::Top
+                // Fully rotatable - the top texture of the block should always face the player.
+                let point = (point - point.floor()) - Vec3::new(0.5, 0.5, 0.5);
+
+                // Unused coordinate is always within tolerance of +-0.25 (+ side on top/right/front).
+
+                // The front texture always points in the direction decided by where on the anchor block the player clicked.
+                let front_facing = match perpendicular_direction {
+                    BlockDirection::PosX | BlockDirection::NegX => {
+                        let (y, z) = if point.y.abs() > point.z.abs() {
+                            (point.y, 0.0)
+                        } else {
+                            (0.0, point.z)
+                        };
+                        BlockDirection::from_vec3(Vec3::new(0.0, y, z))
+                    }
+                    BlockDirection::PosY | BlockDirection::NegY => {
+                        // Only the largest coordinate is kept, but it's sign must be retained.
+                        let (x, z) = if point.x.abs() > point.z.abs() {
+                            (point.x, 0.0)
+                        } else {
+                            (0.0, point.z)
+                        };
+                        BlockDirection::from_vec3(Vec3::new(x, 0.0, z))
+                    }
+                    BlockDirection::PosZ | BlockDirection::NegZ => {
+                        let (x, y) = if point.x.abs() > point.y.abs() {
+                            (point.x, 0.0)
+                        } else {
+                            (0.0, point.y)
+                        };
+                        BlockDirection::from_vec3(Vec3::new(x, y, 0.0))
+                    }
                 };
 
-                BlockRotation::new(block_up, BlockSubRotation::None)
+                // The player can rotate the block further themselves before placing it
+                placement_rotation.apply_to(BlockRotation::from_face_directions(perpendicular_direction, front_facing))
+            }
+        } else {
+            let block_up = if is_planet.is_some() {
+                Planet::planet_face(structure, place_at_coords)
+            } else {
+                BlockFace::Top
             };
 
+            BlockRotation::new(block_up, BlockSubRotation::None)
+        };
+
+        Some(BlockPlacement {
+            block: StructureBlock::new(place_at_coords, structure.get_entity()?),
+            block_id,
+            inventory_slot,
+            block_rotation,
+        })
+    })();
+
+    looking_at.placing_block = placing_block;
+
+    // Placing a station prefab is handled separately
+    if input_handler.check_just_pressed(CosmosInputs::PlaceBlock) && selected_prefab.0.is_none() {
+        if let Some(placement) = looking_at.placing_block {
+            if creative.is_none() {
+                inventory.decrease_quantity_at(placement.inventory_slot, 1, &mut commands);
+            }
+
             place_writer.send(RequestBlockPlaceEvent {
-                block: StructureBlock::new(place_at_coords, structure.get_entity().unwrap()),
-                inventory_slot,
-                block_id,
-                block_rotation,
+                block: placement.block,
+                inventory_slot: placement.inventory_slot,
+                block_id: placement.block_id,
+                block_rotation: placement.block_rotation,
             });
-
-            None
-        })();
+        }
     }
 
     if input_handler.check_just_pressed(CosmosInputs::Interact) {
@@ -0,0 +1,212 @@
+//! Mining blocks by holding down the break button.
+//!
+//! How long a block takes to mine depends on its hardness and the tool the player is holding.
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    block::{block_events::BlockEventsSet, Block},
+    entities::player::{creative::Creative, spectator::Spectator},
+    inventory::{held_item_slot::HeldItemSlot, Inventory},
+    item::{
+        tool::{mining_time_secs, Tool},
+        Item,
+    },
+    netty::{client::LocalPlayer, system_sets::NetworkingSystemsSet},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{ship::pilot::Pilot, structure_block::StructureBlock, Structure},
+};
+
+use crate::{
+    events::block::block_events::RequestBlockBreakEvent,
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    ui::components::show_cursor::no_open_menus,
+};
+
+use super::block_interactions::{process_player_interaction, LookingAt};
+
+#[derive(Component, Debug, Clone, Copy)]
+/// The block the local player is currently mining, and how long they have been mining it for
+pub struct MiningProgress {
+    /// The block being mined
+    pub block: StructureBlock,
+    /// How many seconds this block has been mined for
+    pub elapsed_secs: f32,
+    /// How many seconds it takes to mine this block
+    pub required_secs: f32,
+}
+
+impl MiningProgress {
+    /// How close this block is to being mined, from 0.0 to 1.0
+    pub fn fraction(&self) -> f32 {
+        if self.required_secs <= 0.0 {
+            return 1.0;
+        }
+
+        (self.elapsed_secs / self.required_secs).min(1.0)
+    }
+}
+
+fn mine_looked_at_block(
+    mut commands: Commands,
+    input_handler: InputChecker,
+    q_player: Query<
+        (
+            Entity,
+            &LookingAt,
+            &HeldItemSlot,
+            &Inventory,
+            Has<Creative>,
+            Option<&MiningProgress>,
+        ),
+        (With<LocalPlayer>, Without<Pilot>, Without<Spectator>),
+    >,
+    q_structure: Query<&Structure>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    tools: Res<Registry<Tool>>,
+    time: Res<Time>,
+    mut evw_break: EventWriter<RequestBlockBreakEvent>,
+) {
+    let Ok((player_ent, looking_at, held_item, inventory, creative, progress)) = q_player.get_single() else {
+        return;
+    };
+
+    let looked_at = looking_at
+        .looking_at_block
+        .filter(|_| input_handler.check_pressed(CosmosInputs::BreakBlock))
+        .and_then(|looked_at| {
+            let structure = q_structure.get(looked_at.block.structure()).ok()?;
+
+            Some((looked_at.block, structure.block_at(looked_at.block.coords(), &blocks)))
+        });
+
+    let Some((s_block, block)) = looked_at else {
+        if progress.is_some() {
+            commands.entity(player_ent).remove::<MiningProgress>();
+        }
+        return;
+    };
+
+    let tool = inventory
+        .itemstack_at(held_item.slot() as usize)
+        .and_then(|is| tools.from_id(items.from_numeric_id(is.item_id()).unlocalized_name()));
+
+    let required_secs = mining_time_secs(block.hardness(), tool);
+
+    // Instantly-mined blocks only break once per click, otherwise holding the button would mine everything in sight
+    if creative || required_secs <= 0.0 {
+        if input_handler.check_just_pressed(CosmosInputs::BreakBlock) {
+            evw_break.send(RequestBlockBreakEvent { block: s_block });
+        }
+        return;
+    }
+
+    let mut progress = progress.filter(|p| p.block == s_block).copied().unwrap_or(MiningProgress {
+        block: s_block,
+        elapsed_secs: 0.0,
+        required_secs,
+    });
+
+    // Switching tools part way through changes how long the rest of the block takes
+    progress.required_secs = required_secs;
+    progress.elapsed_secs += time.delta_secs();
+
+    if progress.elapsed_secs >= progress.required_secs {
+        evw_break.send(RequestBlockBreakEvent { block: s_block });
+        commands.entity(player_ent).remove::<MiningProgress>();
+    } else {
+        commands.entity(player_ent).insert(progress);
+    }
+}
+
+#[derive(Component, Debug)]
+struct MiningProgressBar;
+
+#[derive(Component, Debug)]
+struct MiningProgressBarFill;
+
+fn add_mining_progress_bar(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Mining progress"),
+            Node {
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..Default::default()
+            },
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Name::new("Mining progress bar"),
+                MiningProgressBar,
+                Visibility::Hidden,
+                Node {
+                    width: Val::Px(40.0),
+                    height: Val::Px(4.0),
+                    top: Val::Px(16.0),
+                    ..Default::default()
+                },
+                BackgroundColor(Srgba::hex("00000099").unwrap().into()),
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    Name::new("Mining progress bar fill"),
+                    MiningProgressBarFill,
+                    Node {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..Default::default()
+                    },
+                    BackgroundColor(css::WHITE.into()),
+                ));
+            });
+        });
+}
+
+fn update_mining_progress_bar(
+    q_progress: Query<&MiningProgress, With<LocalPlayer>>,
+    mut q_bar: Query<&mut Visibility, With<MiningProgressBar>>,
+    mut q_fill: Query<&mut Node, With<MiningProgressBarFill>>,
+) {
+    let progress = q_progress.get_single().ok();
+
+    for mut vis in q_bar.iter_mut() {
+        *vis = if progress.is_some() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+
+    let Some(progress) = progress else {
+        return;
+    };
+
+    for mut node in q_fill.iter_mut() {
+        node.width = Val::Percent(progress.fraction() * 100.0);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Playing), add_mining_progress_bar).add_systems(
+        Update,
+        update_mining_progress_bar
+            .after(mine_looked_at_block)
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+
+    app.add_systems(
+        Update,
+        mine_looked_at_block
+            .after(process_player_interaction)
+            .in_set(NetworkingSystemsSet::Between)
+            .in_set(BlockEventsSet::SendEventsForThisFrame)
+            .run_if(no_open_menus)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -3,7 +3,15 @@
 use bevy::prelude::App;
 
 pub mod block_interactions;
+pub mod mining;
+pub mod paint;
+pub mod placement_preview;
+pub mod welding;
 
 pub(super) fn register(app: &mut App) {
     block_interactions::register(app);
+    mining::register(app);
+    paint::register(app);
+    placement_preview::register(app);
+    welding::register(app);
 }
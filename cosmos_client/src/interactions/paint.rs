@@ -0,0 +1,233 @@
+//! Using the paint tool to paint blocks, and the color picker used to choose the paint color.
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::{
+        block_events::BlockEventsSet,
+        paint::{PaintBlockEvent, PaintColor, PAINT_TOOL_ITEM},
+        Block,
+    },
+    ecs::NeedsDespawned,
+    inventory::{held_item_slot::HeldItemSlot, Inventory},
+    item::Item,
+    netty::{
+        client::LocalPlayer,
+        sync::{
+            events::client_event::NettyEventWriter,
+            mapping::{Mappable, NetworkMapping},
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::Structure,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    lang::Localization,
+    rendering::MainCamera,
+    ui::{
+        components::{
+            button::{register_button, Button, ButtonEvent},
+            show_cursor::no_open_menus,
+            window::GuiWindow,
+        },
+        font::DefaultFont,
+        OpenMenu, UiSystemSet,
+    },
+};
+
+use super::block_interactions::{process_player_interaction, LookingAt};
+
+#[derive(Resource, Debug, Clone, Copy)]
+/// The color the paint tool will paint blocks
+pub struct SelectedPaintColor(pub PaintColor);
+
+impl Default for SelectedPaintColor {
+    fn default() -> Self {
+        Self(PaintColor::Red)
+    }
+}
+
+#[derive(Component, Debug)]
+struct PaintColorPicker;
+
+#[derive(Component, Debug)]
+struct PaintColorSwatch(PaintColor);
+
+#[derive(Event, Debug)]
+struct PaintColorClicked(Entity);
+
+impl ButtonEvent for PaintColorClicked {
+    fn create_event(btn_entity: Entity) -> Self {
+        Self(btn_entity)
+    }
+}
+
+fn use_paint_tool(
+    mut commands: Commands,
+    input_handler: InputChecker,
+    q_player: Query<(&Inventory, &HeldItemSlot, &LookingAt), With<LocalPlayer>>,
+    q_structure: Query<&Structure>,
+    items: Res<Registry<Item>>,
+    blocks: Res<Registry<Block>>,
+    selected_color: Res<SelectedPaintColor>,
+    network_mapping: Res<NetworkMapping>,
+    mut nevw_paint_block: NettyEventWriter<PaintBlockEvent>,
+) {
+    if !input_handler.check_just_pressed(CosmosInputs::PlaceBlock) {
+        return;
+    }
+
+    let Ok((inventory, held_item, looking_at)) = q_player.get_single() else {
+        return;
+    };
+
+    let Some(is) = inventory.itemstack_at(held_item.slot() as usize) else {
+        return;
+    };
+
+    if items.from_numeric_id(is.item_id()).unlocalized_name() != PAINT_TOOL_ITEM {
+        return;
+    }
+
+    if input_handler.check_pressed(CosmosInputs::AlternateInteraction) {
+        commands.spawn((Name::new("Paint color picker"), PaintColorPicker));
+        return;
+    }
+
+    let Some(looking_at) = looking_at.looking_at_block else {
+        return;
+    };
+
+    let Ok(structure) = q_structure.get(looking_at.block.structure()) else {
+        return;
+    };
+
+    let block_info = structure.block_info_at(looking_at.block.coords());
+    if block_info.get_paint_color() == selected_color.0 || !structure.block_at(looking_at.block.coords(), &blocks).can_be_painted() {
+        return;
+    }
+
+    let Ok(block) = looking_at.block.map_to_server(&network_mapping) else {
+        return;
+    };
+
+    nevw_paint_block.send(PaintBlockEvent {
+        block,
+        color: selected_color.0,
+    });
+}
+
+fn create_color_picker(
+    mut commands: Commands,
+    q_added_picker: Query<Entity, Added<PaintColorPicker>>,
+    q_cam: Query<Entity, With<MainCamera>>,
+    selected_color: Res<SelectedPaintColor>,
+    font: Res<DefaultFont>,
+    localization: Res<Localization>,
+) {
+    for ent in q_added_picker.iter() {
+        let Ok(cam) = q_cam.get_single() else {
+            return;
+        };
+
+        let text_style = TextFont {
+            font: font.0.clone_weak(),
+            font_size: 20.0,
+            ..Default::default()
+        };
+
+        commands
+            .entity(ent)
+            .insert((
+                TargetCamera(cam),
+                OpenMenu::new(0),
+                BackgroundColor(Srgba::hex("2D2D2D").unwrap().into()),
+                Node {
+                    width: Val::Px(360.0),
+                    margin: UiRect::all(Val::Auto),
+                    ..Default::default()
+                },
+                GuiWindow {
+                    title: localization.get("cosmos:window.paint_color").into(),
+                    body_styles: Node {
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        padding: UiRect::all(Val::Px(10.0)),
+                        ..Default::default()
+                    },
+                },
+            ))
+            .with_children(|p| {
+                for color in PaintColor::ALL {
+                    let (background, text) = match color.color() {
+                        Some(c) => (Color::from(c), String::new()),
+                        None => (Srgba::hex("111111").unwrap().into(), "None".to_owned()),
+                    };
+
+                    let border = if color == selected_color.0 { Color::WHITE } else { Color::BLACK };
+
+                    p.spawn((
+                        PaintColorSwatch(color),
+                        BackgroundColor(background),
+                        BorderColor(border),
+                        Node {
+                            width: Val::Px(70.0),
+                            height: Val::Px(70.0),
+                            margin: UiRect::all(Val::Px(5.0)),
+                            border: UiRect::all(Val::Px(3.0)),
+                            ..Default::default()
+                        },
+                        Button::<PaintColorClicked> {
+                            text: Some((text, text_style.clone(), Default::default())),
+                            ..Default::default()
+                        },
+                    ));
+                }
+            });
+    }
+}
+
+fn on_select_color(
+    mut commands: Commands,
+    mut evr_clicked: EventReader<PaintColorClicked>,
+    q_swatch: Query<&PaintColorSwatch>,
+    q_picker: Query<Entity, With<PaintColorPicker>>,
+    mut selected_color: ResMut<SelectedPaintColor>,
+) {
+    for ev in evr_clicked.read() {
+        let Ok(swatch) = q_swatch.get(ev.0) else {
+            continue;
+        };
+
+        selected_color.0 = swatch.0;
+
+        for ent in q_picker.iter() {
+            commands.entity(ent).insert(NeedsDespawned);
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<PaintColorClicked>(app);
+
+    app.init_resource::<SelectedPaintColor>()
+        .add_systems(
+            Update,
+            use_paint_tool
+                .after(process_player_interaction)
+                .in_set(NetworkingSystemsSet::Between)
+                .in_set(BlockEventsSet::SendEventsForThisFrame)
+                .run_if(no_open_menus)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            Update,
+            (create_color_picker, on_select_color)
+                .chain()
+                .in_set(UiSystemSet::DoUi)
+                .run_if(in_state(GameState::Playing)),
+        );
+}
@@ -0,0 +1,167 @@
+//! Lets the player rotate the block they are about to place, and shows a see-through preview of where it will go.
+
+use bevy::{
+    color::{palettes::css, Alpha},
+    pbr::{NotShadowCaster, NotShadowReceiver},
+    prelude::*,
+};
+use cosmos_core::{
+    block::{block_direction::BlockDirection, block_events::BlockEventsSet, block_rotation::BlockRotation, Block},
+    blockitems::BlockItems,
+    item::Item,
+    netty::{client::LocalPlayer, system_sets::NetworkingSystemsSet},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{ship::pilot::Pilot, Structure},
+};
+use std::f32::consts::FRAC_PI_2;
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    item::item_mesh::ItemMeshMaterial,
+    ui::components::show_cursor::no_open_menus,
+};
+
+use super::block_interactions::{process_player_interaction, LookingAt};
+
+#[derive(Resource, Debug, Clone, Copy)]
+/// The rotation the player has chosen to apply to fully rotatable blocks on top of their default placement rotation.
+///
+/// This is relative to the block's default rotation, so spinning a block always spins it around the face pointing
+/// away from the block it's placed on.
+pub struct BlockPlacementRotation(Quat);
+
+impl Default for BlockPlacementRotation {
+    fn default() -> Self {
+        Self(Quat::IDENTITY)
+    }
+}
+
+impl BlockPlacementRotation {
+    /// Applies the player's chosen rotation to a block's default placement rotation
+    pub fn apply_to(&self, rotation: BlockRotation) -> BlockRotation {
+        let rotated = rotation.as_quat() * self.0;
+
+        BlockRotation::from_face_directions(
+            BlockDirection::from_vec3(rotated * Vec3::Y),
+            BlockDirection::from_vec3(rotated * Vec3::NEG_Z),
+        )
+    }
+
+    fn rotate(&mut self, axis: Vec3) {
+        self.0 = (self.0 * Quat::from_axis_angle(axis, FRAC_PI_2)).normalize();
+    }
+}
+
+fn rotate_block_placement(input_handler: InputChecker, mut placement_rotation: ResMut<BlockPlacementRotation>) {
+    if input_handler.check_just_pressed(CosmosInputs::ResetBlockPlacementRotation) {
+        *placement_rotation = BlockPlacementRotation::default();
+    }
+    if input_handler.check_just_pressed(CosmosInputs::SpinBlockPlacement) {
+        placement_rotation.rotate(Vec3::Y);
+    }
+    if input_handler.check_just_pressed(CosmosInputs::TiltBlockPlacement) {
+        placement_rotation.rotate(Vec3::X);
+    }
+    if input_handler.check_just_pressed(CosmosInputs::RollBlockPlacement) {
+        placement_rotation.rotate(Vec3::Z);
+    }
+}
+
+#[derive(Component, Debug)]
+/// The see-through block showing where the held block will be placed
+struct PlacementGhost {
+    block_id: u16,
+}
+
+#[derive(Resource, Debug)]
+struct PlacementGhostMaterial(Handle<StandardMaterial>);
+
+fn create_ghost_material(mut commands: Commands, mut materials: ResMut<Assets<StandardMaterial>>) {
+    commands.insert_resource(PlacementGhostMaterial(materials.add(StandardMaterial {
+        base_color: css::LIGHT_SKY_BLUE.with_alpha(0.35).into(),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..Default::default()
+    })));
+}
+
+fn render_placement_ghost(
+    mut commands: Commands,
+    q_player: Query<&LookingAt, (With<LocalPlayer>, Without<Pilot>)>,
+    q_structure: Query<&Structure>,
+    mut q_ghost: Query<(Entity, &mut Transform, &mut PlacementGhost, &Parent)>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    block_items: Res<BlockItems>,
+    item_meshes: Res<Registry<ItemMeshMaterial>>,
+    ghost_material: Res<PlacementGhostMaterial>,
+) {
+    let placement = q_player.get_single().ok().and_then(|looking_at| looking_at.placing_block);
+
+    let ghost_info = placement.and_then(|placement| {
+        let structure = q_structure.get(placement.block.structure()).ok()?;
+        let item_id = block_items.item_from_block(blocks.from_numeric_id(placement.block_id))?;
+        let mesh = item_meshes.from_id(items.from_numeric_id(item_id).unlocalized_name())?;
+
+        // Slightly larger than a block so it isn't hidden inside of the blocks around it
+        let transform = Transform::from_translation(structure.block_relative_position(placement.block.coords()))
+            .with_rotation(placement.block_rotation.as_quat())
+            .with_scale(Vec3::splat(1.01));
+
+        Some((placement, transform, mesh.mesh_handle().clone_weak()))
+    });
+
+    let existing_ghost = q_ghost.get_single_mut().ok();
+
+    let Some((placement, transform, mesh)) = ghost_info else {
+        if let Some((ent, ..)) = existing_ghost {
+            commands.entity(ent).despawn_recursive();
+        }
+        return;
+    };
+
+    if let Some((ent, mut ghost_transform, mut ghost, parent)) = existing_ghost {
+        if parent.get() == placement.block.structure() {
+            *ghost_transform = transform;
+
+            if ghost.block_id != placement.block_id {
+                ghost.block_id = placement.block_id;
+                commands.entity(ent).insert(Mesh3d(mesh));
+            }
+
+            return;
+        }
+
+        commands.entity(ent).despawn_recursive();
+    }
+
+    commands.entity(placement.block.structure()).with_children(|p| {
+        p.spawn((
+            Name::new("Block placement ghost"),
+            PlacementGhost {
+                block_id: placement.block_id,
+            },
+            transform,
+            Mesh3d(mesh),
+            MeshMaterial3d(ghost_material.0.clone_weak()),
+            NotShadowCaster,
+            NotShadowReceiver,
+        ));
+    });
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<BlockPlacementRotation>()
+        .add_systems(OnEnter(GameState::Playing), create_ghost_material)
+        .add_systems(
+            Update,
+            (
+                rotate_block_placement.before(process_player_interaction).run_if(no_open_menus),
+                render_placement_ghost.after(process_player_interaction),
+            )
+                .in_set(NetworkingSystemsSet::Between)
+                .in_set(BlockEventsSet::SendEventsForThisFrame)
+                .run_if(in_state(GameState::Playing)),
+        );
+}
@@ -0,0 +1,105 @@
+//! Using the welding tool to weld a ship onto a larger ship or station.
+//!
+//! The first block clicked selects the ship to weld, and the second block (on a different structure) selects the
+//! structure to weld it onto. Alternate-clicking clears the selection.
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::block_events::BlockEventsSet,
+    inventory::{held_item_slot::HeldItemSlot, Inventory},
+    item::Item,
+    netty::{
+        client::LocalPlayer,
+        sync::{
+            events::client_event::NettyEventWriter,
+            mapping::{Mappable, NetworkMapping},
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::{Structure, StructureBlock},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::welding::{WeldStructuresEvent, WELDING_TOOL_ITEM},
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    structure::construction::{find_targeted_ghost, TargetedGhost},
+    ui::components::show_cursor::no_open_menus,
+};
+
+use super::block_interactions::{process_player_interaction, LookingAt};
+
+#[derive(Resource, Debug, Default)]
+/// The block on the ship that will be welded once a structure to weld it onto is clicked
+struct WeldingSelection(Option<StructureBlock>);
+
+fn use_welding_tool(
+    input_handler: InputChecker,
+    q_player: Query<(&Inventory, &HeldItemSlot, &LookingAt), With<LocalPlayer>>,
+    q_structure: Query<(), With<Structure>>,
+    items: Res<Registry<Item>>,
+    network_mapping: Res<NetworkMapping>,
+    mut selection: ResMut<WeldingSelection>,
+    mut nevw_weld: NettyEventWriter<WeldStructuresEvent>,
+    targeted_ghost: Res<TargetedGhost>,
+) {
+    // Welding a ghost block takes priority over welding structures together
+    if !input_handler.check_just_pressed(CosmosInputs::PlaceBlock) || targeted_ghost.0.is_some() {
+        return;
+    }
+
+    let Ok((inventory, held_item, looking_at)) = q_player.get_single() else {
+        return;
+    };
+
+    let Some(is) = inventory.itemstack_at(held_item.slot() as usize) else {
+        return;
+    };
+
+    if items.from_numeric_id(is.item_id()).unlocalized_name() != WELDING_TOOL_ITEM {
+        return;
+    }
+
+    if input_handler.check_pressed(CosmosInputs::AlternateInteraction) {
+        selection.0 = None;
+        return;
+    }
+
+    let Some(looking_at) = looking_at.looking_at_block else {
+        return;
+    };
+
+    let module = match selection.0 {
+        Some(module) if q_structure.contains(module.structure()) && module.structure() != looking_at.block.structure() => module,
+        _ => {
+            info!("Selected structure {:?} to weld.", looking_at.block.structure());
+            selection.0 = Some(looking_at.block);
+            return;
+        }
+    };
+
+    selection.0 = None;
+
+    let (Ok(module), Ok(target)) = (
+        module.map_to_server(&network_mapping),
+        looking_at.block.map_to_server(&network_mapping),
+    ) else {
+        return;
+    };
+
+    nevw_weld.send(WeldStructuresEvent { module, target });
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<WeldingSelection>().add_systems(
+        Update,
+        use_welding_tool
+            .after(process_player_interaction)
+            .after(find_targeted_ghost)
+            .in_set(NetworkingSystemsSet::Between)
+            .in_set(BlockEventsSet::SendEventsForThisFrame)
+            .run_if(no_open_menus)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
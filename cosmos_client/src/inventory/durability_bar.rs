@@ -0,0 +1,83 @@
+//! Shows how worn out an item (or how charged a battery) is at the bottom of the slot it's displayed in
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    item::{battery::BatteryCharge, tool::ItemDurability},
+    netty::system_sets::NetworkingSystemsSet,
+    state::GameState,
+};
+
+/// How wide the durability bar is when the item is undamaged
+const DURABILITY_BAR_WIDTH: f32 = 52.0;
+
+#[derive(Component, Debug, Default)]
+/// Displays the [`ItemDurability`] of the item whose data entity this points to.
+///
+/// This is hidden if that item has no durability, or hasn't been used yet. For batteries, this displays their
+/// [`BatteryCharge`] instead.
+pub struct DurabilityBar {
+    /// The data entity of the itemstack this bar is for
+    pub data_entity: Option<Entity>,
+}
+
+/// Creates a durability bar for the itemstack with this data entity.
+///
+/// This should be spawned as a child of the node the item is displayed in.
+pub fn durability_bar_bundle(data_entity: Option<Entity>) -> impl Bundle {
+    (
+        Name::new("Durability bar"),
+        DurabilityBar { data_entity },
+        Visibility::Hidden,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(6.0),
+            bottom: Val::Px(3.0),
+            width: Val::Px(DURABILITY_BAR_WIDTH),
+            height: Val::Px(4.0),
+            ..Default::default()
+        },
+        BackgroundColor(css::GREEN.into()),
+    )
+}
+
+fn update_durability_bars(
+    mut q_bars: Query<(&DurabilityBar, &mut Node, &mut BackgroundColor, &mut Visibility)>,
+    q_durability: Query<&ItemDurability>,
+    q_charge: Query<&BatteryCharge>,
+) {
+    for (bar, mut node, mut color, mut vis) in q_bars.iter_mut() {
+        if let Some(charge) = bar.data_entity.and_then(|e| q_charge.get(e).ok()) {
+            let fraction = charge.fraction();
+
+            vis.set_if_neq(Visibility::Inherited);
+            node.width = Val::Px(DURABILITY_BAR_WIDTH * fraction);
+            color.0 = css::DARK_SLATE_BLUE.mix(&css::AQUA, fraction).into();
+            continue;
+        }
+
+        let Some(durability) = bar.data_entity.and_then(|e| q_durability.get(e).ok()) else {
+            vis.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+
+        if durability.current() >= durability.max() {
+            vis.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+
+        let fraction = durability.fraction();
+
+        vis.set_if_neq(Visibility::Inherited);
+        node.width = Val::Px(DURABILITY_BAR_WIDTH * fraction);
+        color.0 = css::RED.mix(&css::GREEN, fraction).into();
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        update_durability_bars
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
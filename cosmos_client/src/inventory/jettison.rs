@@ -0,0 +1,63 @@
+//! The button on a ship storage's inventory window that jettisons its cargo as a cargo pod
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::data::BlockData,
+    item::cargo_pod::JettisonCargoEvent,
+    netty::sync::{
+        events::client_event::NettyEventWriter,
+        mapping::{Mappable, NetworkMapping},
+    },
+    state::GameState,
+};
+
+use crate::ui::{
+    components::button::{register_button, ButtonEvent},
+    UiSystemSet,
+};
+
+#[derive(Component, Debug)]
+/// Jettisons the cargo of this inventory's storage block when clicked
+pub(super) struct JettisonButton {
+    /// The client's entity for the storage block's data
+    pub inventory_holder: Entity,
+}
+
+#[derive(Event, Debug)]
+pub(super) struct JettisonCargoClicked(Entity);
+
+impl ButtonEvent for JettisonCargoClicked {
+    fn create_event(entity: Entity) -> Self {
+        Self(entity)
+    }
+}
+
+fn on_jettison_clicked(
+    mut evr_clicked: EventReader<JettisonCargoClicked>,
+    q_button: Query<&JettisonButton>,
+    q_block_data: Query<&BlockData>,
+    network_mapping: Res<NetworkMapping>,
+    mut nevw_jettison: NettyEventWriter<JettisonCargoEvent>,
+) {
+    for ev in evr_clicked.read() {
+        let Some(storage) = q_button
+            .get(ev.0)
+            .ok()
+            .and_then(|button| q_block_data.get(button.inventory_holder).ok())
+            .and_then(|block_data| block_data.identifier.block.map_to_server(&network_mapping).ok())
+        else {
+            continue;
+        };
+
+        nevw_jettison.send(JettisonCargoEvent { storage });
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<JettisonCargoClicked>(app);
+
+    app.add_systems(
+        Update,
+        on_jettison_clicked.in_set(UiSystemSet::DoUi).run_if(in_state(GameState::Playing)),
+    );
+}
@@ -14,14 +14,26 @@ use cosmos_core::{
         netty::{ClientInventoryMessages, InventoryIdentifier},
         HeldItemStack, Inventory,
     },
-    netty::{client::LocalPlayer, cosmos_encoder, sync::mapping::NetworkMapping, system_sets::NetworkingSystemsSet, NettyChannelClient},
+    netty::{
+        client::LocalPlayer,
+        network_stats::{NettyClientMessages, NettyMessage},
+        sync::mapping::NetworkMapping,
+        system_sets::NetworkingSystemsSet,
+        NettyChannelClient,
+    },
     state::GameState,
+    structure::ship::Ship,
 };
 
+use durability_bar::durability_bar_bundle;
+use jettison::{JettisonButton, JettisonCargoClicked};
+
 use crate::{
     input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    lang::Localization,
     ui::{
         components::{
+            button::{Button, ButtonStyles},
             scollable_container::ScrollBox,
             show_cursor::no_open_menus,
             window::{GuiWindow, UiWindowSystemSet},
@@ -31,6 +43,8 @@ use crate::{
     },
 };
 
+pub mod durability_bar;
+mod jettison;
 pub mod netty;
 
 fn get_server_inventory_identifier(entity: Entity, mapping: &NetworkMapping, q_block_data: &Query<&BlockData>) -> InventoryIdentifier {
@@ -151,6 +165,12 @@ struct OpenInventoryEntity(Entity);
 #[derive(Component)]
 struct InventoryRenderedItem;
 
+/// Inventory names come from the server untranslated, so they are used to find the localized title.
+fn inventory_title(localization: &Localization, name: &str) -> String {
+    let key = format!("cosmos:inventory.{}", name.to_lowercase().replace(' ', "_"));
+    localization.get_opt(&key).unwrap_or(name).to_owned()
+}
+
 fn toggle_inventory_rendering(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -164,6 +184,10 @@ fn toggle_inventory_rendering(
     mapping: Res<NetworkMapping>,
     mut removed_components: RemovedComponents<InventoryNeedsDisplayed>,
     q_block_data: Query<&BlockData>,
+    localization: Res<Localization>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    ui_scale: Res<UiScale>,
+    q_ship: Query<(), With<Ship>>,
 ) {
     for removed in removed_components.read() {
         let Ok((inventory_holder, mut local_inventory, open_inventory_entity)) = without_needs_displayed_inventories.get_mut(removed)
@@ -190,9 +214,9 @@ fn toggle_inventory_rendering(
                 // Only send information to server if there is a point to the move
                 held_item_stack.set_quantity(leftover);
 
-                client.send_message(
+                client.send_netty_message(
                     NettyChannelClient::Inventory,
-                    cosmos_encoder::serialize(&ClientInventoryMessages::DepositHeldItemstack {
+                    NettyMessage::new(&ClientInventoryMessages::DepositHeldItemstack {
                         inventory_holder: server_inventory_holder,
                         slot: displayed_item.slot_number as u32,
                         quantity: u16::MAX,
@@ -206,9 +230,9 @@ fn toggle_inventory_rendering(
 
                 if leftover != held_item_stack.quantity() {
                     // Only send information to server if there is a point to the insertion
-                    client.send_message(
+                    client.send_netty_message(
                         NettyChannelClient::Inventory,
-                        cosmos_encoder::serialize(&ClientInventoryMessages::InsertHeldItem {
+                        NettyMessage::new(&ClientInventoryMessages::InsertHeldItem {
                             inventory_holder: server_inventory_holder,
                             quantity: u16::MAX,
                         }),
@@ -218,9 +242,9 @@ fn toggle_inventory_rendering(
                 if leftover != 0 {
                     warn!("Unable to put itemstack into inventory it was taken out of - and dropping hasn't been implemented yet. Deleting for now.");
                     // Only send information to server if there is a point to the insertion
-                    client.send_message(
+                    client.send_netty_message(
                         NettyChannelClient::Inventory,
-                        cosmos_encoder::serialize(&ClientInventoryMessages::ThrowHeldItemstack { quantity: u16::MAX }),
+                        NettyMessage::new(&ClientInventoryMessages::ThrowHeldItemstack { quantity: u16::MAX }),
                     );
                 }
             }
@@ -275,10 +299,28 @@ fn toggle_inventory_rendering(
         let border_color = BorderColor(Srgba::hex("222222").unwrap().into());
 
         const MAX_INVENTORY_HEIGHT_PX: f32 = 500.0;
+        const INVENTORY_TOP_PX: f32 = 100.0;
+        // Space left at the bottom of the screen for the hotbar & its item name text
+        const HOTBAR_SPACE_PX: f32 = 120.0;
+
+        // With a large UI scale, the window may not fit the full height - so shrink the scrollable part to avoid covering the hotbar.
+        let max_height = q_window
+            .get_single()
+            .map(|window| {
+                let priority_height = if priority_slots.is_some() {
+                    INVENTORY_SLOTS_DIMS + 5.0 + inventory_border_size
+                } else {
+                    0.0
+                };
+
+                window.height() / ui_scale.0 - INVENTORY_TOP_PX - GuiWindow::TITLE_BAR_HEIGHT_PX - HOTBAR_SPACE_PX - priority_height
+            })
+            .unwrap_or(MAX_INVENTORY_HEIGHT_PX)
+            .clamp(INVENTORY_SLOTS_DIMS, MAX_INVENTORY_HEIGHT_PX);
 
         let non_hotbar_height = (((inventory.len() as f32 - inventory.priority_slots().map(|x| x.len()).unwrap_or(0) as f32) / 9.0).ceil()
             * INVENTORY_SLOTS_DIMS)
-            .min(MAX_INVENTORY_HEIGHT_PX);
+            .min(max_height);
 
         let inv_ent = commands
             .spawn((
@@ -287,7 +329,7 @@ fn toggle_inventory_rendering(
                 OpenMenu::new(0),
                 BorderColor(Color::BLACK),
                 GuiWindow {
-                    title: inventory.name().into(),
+                    title: inventory_title(&localization, inventory.name()),
                     body_styles: Node {
                         flex_direction: FlexDirection::Column,
                         ..Default::default()
@@ -297,7 +339,7 @@ fn toggle_inventory_rendering(
                     position_type: PositionType::Absolute,
                     right,
                     left,
-                    top: Val::Px(100.0),
+                    top: Val::Px(INVENTORY_TOP_PX),
                     width,
                     border: UiRect::all(Val::Px(inventory_border_size)),
                     ..default()
@@ -362,6 +404,38 @@ fn toggle_inventory_rendering(
                         }
                     });
                 }
+
+                // Cargo in a ship's storage can be jettisoned as a cargo pod
+                if q_block_data
+                    .get(inventory_holder)
+                    .is_ok_and(|block_data| q_ship.contains(block_data.identifier.block.structure()))
+                {
+                    p.spawn((
+                        Name::new("Jettison Cargo Button"),
+                        JettisonButton { inventory_holder },
+                        BackgroundColor(Srgba::hex("3D3D3D").unwrap().into()),
+                        Node {
+                            height: Val::Px(40.0),
+                            ..Default::default()
+                        },
+                        Button::<JettisonCargoClicked> {
+                            button_styles: Some(ButtonStyles {
+                                background_color: Srgba::hex("3D3D3D").unwrap().into(),
+                                hover_background_color: Srgba::hex("7A2E2E").unwrap().into(),
+                                press_background_color: Srgba::hex("5A1E1E").unwrap().into(),
+                                foreground_color: Color::WHITE,
+                                hover_foreground_color: Color::WHITE,
+                                press_foreground_color: Color::WHITE,
+                            }),
+                            text: Some((
+                                localization.get("cosmos:inventory.jettison_cargo").to_owned(),
+                                text_style.clone(),
+                                Default::default(),
+                            )),
+                            ..Default::default()
+                        },
+                    ));
+                }
             })
             .id();
 
@@ -392,9 +466,9 @@ fn drop_item(
         return;
     };
 
-    client.send_message(
+    client.send_netty_message(
         NettyChannelClient::Inventory,
-        cosmos_encoder::serialize(&ClientInventoryMessages::ThrowItemstack {
+        NettyMessage::new(&ClientInventoryMessages::ThrowItemstack {
             quantity: if input_checker.check_pressed(CosmosInputs::BulkDropFlag) {
                 is.quantity()
             } else {
@@ -577,9 +651,9 @@ fn pickup_item_into_cursor(
         }
     }
 
-    client.send_message(
+    client.send_netty_message(
         NettyChannelClient::Inventory,
-        cosmos_encoder::serialize(&ClientInventoryMessages::PickupItemstack {
+        NettyMessage::new(&ClientInventoryMessages::PickupItemstack {
             inventory_holder: server_inventory_holder,
             slot: slot_clicked as u32,
             quantity: pickup_quantity,
@@ -645,9 +719,9 @@ fn handle_interactions(
             }
             // logic is handled on server otherwise, don't feel like copying it here
 
-            client.send_message(
+            client.send_netty_message(
                 NettyChannelClient::Inventory,
-                cosmos_encoder::serialize(&ClientInventoryMessages::AutoMove {
+                NettyMessage::new(&ClientInventoryMessages::AutoMove {
                     from_slot: slot_num as u32,
                     quantity,
                     from_inventory: server_inventory_holder,
@@ -675,9 +749,9 @@ fn handle_interactions(
                     commands.entity(following_entity).insert(NeedsDespawned);
                 }
 
-                client.send_message(
+                client.send_netty_message(
                     NettyChannelClient::Inventory,
-                    cosmos_encoder::serialize(&ClientInventoryMessages::DepositHeldItemstack {
+                    NettyMessage::new(&ClientInventoryMessages::DepositHeldItemstack {
                         inventory_holder: server_inventory_holder,
                         slot: clicked_slot as u32,
                         quantity: move_quantity,
@@ -713,19 +787,19 @@ fn handle_interactions(
 
                     let message = if lmb {
                         // A swap assumes we're depositing everything, which will remove all items on the server-side.
-                        cosmos_encoder::serialize(&ClientInventoryMessages::DepositAndSwapHeldItemstack {
+                        NettyMessage::new(&ClientInventoryMessages::DepositAndSwapHeldItemstack {
                             inventory_holder: server_inventory_holder,
                             slot: clicked_slot as u32,
                         })
                     } else {
-                        cosmos_encoder::serialize(&ClientInventoryMessages::DepositHeldItemstack {
+                        NettyMessage::new(&ClientInventoryMessages::DepositHeldItemstack {
                             inventory_holder: server_inventory_holder,
                             slot: clicked_slot as u32,
                             quantity: 1,
                         })
                     };
 
-                    client.send_message(NettyChannelClient::Inventory, message);
+                    client.send_netty_message(NettyChannelClient::Inventory, message);
                 } else {
                     inventory.set_itemstack_at(clicked_slot, is_here, &mut commands);
                 }
@@ -772,6 +846,8 @@ fn create_item_stack_slot_data(item_stack: &ItemStack, ecmds: &mut EntityCommand
                 Text::new(format!("{quantity}")),
                 text_style,
             ));
+
+            p.spawn(durability_bar_bundle(item_stack.data_entity()));
         });
 }
 
@@ -834,4 +910,6 @@ pub(super) fn register(app: &mut App) {
     .register_type::<DisplayedItemFromInventory>();
 
     netty::register(app);
+    durability_bar::register(app);
+    jettison::register(app);
 }
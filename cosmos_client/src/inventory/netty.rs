@@ -12,7 +12,14 @@ use cosmos_core::{
         netty::{InventoryIdentifier, ServerInventoryMessages},
         Inventory,
     },
-    netty::{client::LocalPlayer, cosmos_encoder, sync::mapping::NetworkMapping, system_sets::NetworkingSystemsSet, NettyChannelServer},
+    item::Item,
+    netty::{
+        client::LocalPlayer,
+        network_stats::NettyClientMessages,
+        sync::{mapping::NetworkMapping, registry::client::RegistryIdMap},
+        system_sets::NetworkingSystemsSet,
+        NettyChannelServer,
+    },
     state::GameState,
     structure::Structure,
 };
@@ -22,19 +29,26 @@ use super::{HeldItemStack, InventoryNeedsDisplayed, InventorySide};
 fn sync(
     mut client: ResMut<RenetClient>,
     network_mapping: Res<NetworkMapping>,
+    item_ids: Res<RegistryIdMap<Item>>,
     mut commands: Commands,
     mut held_item_query: Query<(Entity, &mut HeldItemStack)>,
     structure_query: Query<&Structure>,
     local_player: Query<Entity, With<LocalPlayer>>,
     q_check_inventory: Query<(), With<Inventory>>,
 ) {
-    while let Some(message) = client.receive_message(NettyChannelServer::Inventory) {
-        let msg: ServerInventoryMessages = cosmos_encoder::deserialize(&message).expect("Failed to deserialize server inventory message!");
+    while let Some(message) = client.receive_netty_message::<ServerInventoryMessages>(NettyChannelServer::Inventory) {
+        let msg: ServerInventoryMessages = message.expect("Failed to deserialize server inventory message!");
 
         match msg {
             ServerInventoryMessages::HeldItemstack { itemstack } => {
                 if let Ok((entity, mut holding_itemstack)) = held_item_query.get_single_mut() {
                     if let Some(mut is) = itemstack {
+                        let Some(item_id) = item_ids.to_client(is.item_id()) else {
+                            warn!("Server sent held itemstack of unknown item {}", is.item_id());
+                            continue;
+                        };
+                        is.remap_item_id(item_id);
+
                         // Don't trigger change detection unless it actually changed
                         if is.quantity() != holding_itemstack.quantity() || is.item_id() != holding_itemstack.item_id() {
                             if let Some(de) = is.data_entity() {
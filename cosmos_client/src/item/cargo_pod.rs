@@ -0,0 +1,37 @@
+//! Renders cargo pods
+
+use bevy::prelude::*;
+use cosmos_core::{
+    item::cargo_pod::{CargoPod, CARGO_POD_HALF_SIZE},
+    netty::system_sets::NetworkingSystemsSet,
+    state::GameState,
+};
+
+fn on_add_cargo_pod(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    q_added_pod: Query<Entity, Added<CargoPod>>,
+) {
+    for ent in q_added_pod.iter() {
+        commands.entity(ent).insert((
+            Visibility::default(),
+            Mesh3d(meshes.add(Cuboid::from_length(CARGO_POD_HALF_SIZE * 2.0))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Srgba::hex("B35A1F").unwrap().into(),
+                metallic: 0.5,
+                perceptual_roughness: 0.6,
+                ..Default::default()
+            })),
+        ));
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        on_add_cargo_pod
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -2,10 +2,16 @@
 
 use bevy::prelude::App;
 
+mod cargo_pod;
 pub mod item_mesh;
 pub mod physical_item;
+pub mod scanner;
+pub mod upgrade_modules;
 
 pub(super) fn register(app: &mut App) {
+    cargo_pod::register(app);
     item_mesh::register(app);
     physical_item::register(app);
+    scanner::register(app);
+    upgrade_modules::register(app);
 }
@@ -0,0 +1,223 @@
+//! Lets the player scan for ores with a handheld scanner.
+//!
+//! Ores found by a scan are highlighted through walls for a few seconds, and while holding the scanner the player can
+//! see information about the block they are looking at.
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    block::Block,
+    ecs::NeedsDespawned,
+    entities::player::spectator::Spectator,
+    inventory::{held_item_slot::HeldItemSlot, Inventory},
+    item::{
+        scanner::{ScanResultEvent, UseScannerEvent, SCANNER_ITEM, SCAN_HIGHLIGHT_SECS},
+        Item,
+    },
+    netty::{
+        client::LocalPlayer,
+        sync::{
+            events::client_event::{NettyEventReceived, NettyEventWriter},
+            mapping::NetworkMapping,
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{coordinates::BlockCoordinate, shared::build_mode::BuildMode, ship::pilot::Pilot, Structure},
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    interactions::block_interactions::LookingAt,
+    lang::{Lang, Localization},
+    ui::{components::show_cursor::no_open_menus, font::DefaultFont},
+};
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+/// Lines drawn around scanned ores, which are visible through blocks
+struct ScannerGizmos;
+
+fn configure_scanner_gizmos(mut config_store: ResMut<GizmoConfigStore>) {
+    let (config, _) = config_store.config_mut::<ScannerGizmos>();
+    config.depth_bias = -1.0;
+}
+
+#[derive(Debug)]
+struct ScannedOres {
+    /// The client's entity for the structure these ores are in
+    structure: Entity,
+    ores: Vec<BlockCoordinate>,
+    /// When (in seconds since the game started) these stop being highlighted
+    expires_at: f32,
+}
+
+#[derive(Resource, Debug, Default)]
+/// Every ore found by recent scans
+struct ScanHighlights(Vec<ScannedOres>);
+
+#[derive(Component, Debug)]
+struct BlockInfoText;
+
+fn holding_scanner(inventory: &Inventory, held_slot: &HeldItemSlot, items: &Registry<Item>) -> bool {
+    inventory
+        .itemstack_at(held_slot.slot() as usize)
+        .is_some_and(|is| items.from_numeric_id(is.item_id()).unlocalized_name() == SCANNER_ITEM)
+}
+
+fn use_scanner(
+    inputs: InputChecker,
+    q_local_player: Query<(&HeldItemSlot, &Inventory), (With<LocalPlayer>, Without<Pilot>, Without<BuildMode>, Without<Spectator>)>,
+    items: Res<Registry<Item>>,
+    mut nevw_use_scanner: NettyEventWriter<UseScannerEvent>,
+) {
+    // Scanners can't be placed, so using them is the same as placing a block
+    if !inputs.check_just_pressed(CosmosInputs::PlaceBlock) {
+        return;
+    }
+
+    let Ok((held_slot, inventory)) = q_local_player.get_single() else {
+        return;
+    };
+
+    if holding_scanner(inventory, held_slot, &items) {
+        nevw_use_scanner.send(UseScannerEvent);
+    }
+}
+
+fn receive_scan_results(
+    mut nevr_scan_result: EventReader<NettyEventReceived<ScanResultEvent>>,
+    network_mapping: Res<NetworkMapping>,
+    time: Res<Time>,
+    mut highlights: ResMut<ScanHighlights>,
+) {
+    for ev in nevr_scan_result.read() {
+        let Some(structure) = network_mapping.client_from_server(&ev.structure) else {
+            continue;
+        };
+
+        // A new scan of the same structure replaces the old one
+        highlights.0.retain(|scanned| scanned.structure != structure);
+        highlights.0.push(ScannedOres {
+            structure,
+            ores: ev.ores.clone(),
+            expires_at: time.elapsed_secs() + SCAN_HIGHLIGHT_SECS,
+        });
+    }
+}
+
+fn draw_scan_highlights(
+    mut gizmos: Gizmos<ScannerGizmos>,
+    time: Res<Time>,
+    mut highlights: ResMut<ScanHighlights>,
+    q_structure: Query<(&Structure, &GlobalTransform)>,
+) {
+    let now = time.elapsed_secs();
+    highlights
+        .0
+        .retain(|scanned| scanned.expires_at > now && q_structure.contains(scanned.structure));
+
+    for scanned in highlights.0.iter() {
+        let Ok((structure, g_trans)) = q_structure.get(scanned.structure) else {
+            continue;
+        };
+
+        // Fade out over the last couple seconds
+        let alpha = ((scanned.expires_at - now) / 2.0).min(1.0);
+        let color = Color::from(css::GOLD).with_alpha(alpha);
+
+        for &coords in scanned.ores.iter() {
+            gizmos.cuboid(
+                Transform::from_translation(g_trans.transform_point(structure.block_relative_position(coords)))
+                    .with_rotation(g_trans.rotation())
+                    .with_scale(Vec3::splat(1.02)),
+                color,
+            );
+        }
+    }
+}
+
+fn update_block_info_text(
+    mut commands: Commands,
+    q_local_player: Query<(&HeldItemSlot, &Inventory, &LookingAt), With<LocalPlayer>>,
+    q_structure: Query<&Structure>,
+    mut q_text: Query<(Entity, &mut Text), With<BlockInfoText>>,
+    items: Res<Registry<Item>>,
+    blocks: Res<Registry<Block>>,
+    lang: Res<Lang<Block>>,
+    localization: Res<Localization>,
+    font: Res<DefaultFont>,
+) {
+    let looked_at = q_local_player
+        .get_single()
+        .ok()
+        .filter(|(held_slot, inventory, _)| holding_scanner(inventory, held_slot, &items))
+        .and_then(|(_, _, looking_at)| looking_at.looking_at_block)
+        .and_then(|looked_at| {
+            q_structure
+                .get(looked_at.block.structure())
+                .ok()
+                .map(|s| (s, looked_at.block.coords()))
+        });
+
+    let Some((structure, coords)) = looked_at else {
+        for (ent, _) in q_text.iter() {
+            commands.entity(ent).insert(NeedsDespawned);
+        }
+        return;
+    };
+
+    let block = structure.block_at(coords, &blocks);
+
+    let text = localization.format(
+        "cosmos:hud.block_scan",
+        &[
+            &lang.get_name(block).unwrap_or(block.unlocalized_name()),
+            &(structure.get_block_health(coords, &blocks).ceil()),
+            &block.hardness(),
+            &block.armor(),
+            &block.mining_resistance(),
+            &((block.flammability() * 100.0).round()),
+        ],
+    );
+
+    if let Ok((_, mut existing)) = q_text.get_single_mut() {
+        existing.0 = text;
+        return;
+    }
+
+    commands.spawn((
+        Name::new("Scanner Block Info Text"),
+        BlockInfoText,
+        Text::new(text),
+        TextFont {
+            font: font.0.clone_weak(),
+            font_size: 20.0,
+            ..Default::default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            ..Default::default()
+        },
+    ));
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_gizmo_group::<ScannerGizmos>()
+        .init_resource::<ScanHighlights>()
+        .add_systems(Startup, configure_scanner_gizmos)
+        .add_systems(
+            Update,
+            (
+                use_scanner.run_if(no_open_menus),
+                receive_scan_results,
+                draw_scan_highlights,
+                update_block_info_text,
+            )
+                .chain()
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        );
+}
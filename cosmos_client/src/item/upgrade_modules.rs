@@ -0,0 +1,424 @@
+//! The menu used to install & remove upgrade modules from mining tools and ship weapon blocks
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    entities::player::spectator::Spectator,
+    inventory::{held_item_slot::HeldItemSlot, Inventory},
+    item::{
+        tool::Tool,
+        upgrade_module::{
+            InstallUpgradeModuleEvent, InstalledModules, OpenUpgradeModulesEvent, UninstallUpgradeModuleEvent, UpgradeModuleKind,
+            UpgradeTarget, UpgradeableKind, MAX_INSTALLED_MODULES,
+        },
+        Item,
+    },
+    netty::{
+        client::LocalPlayer,
+        sync::{
+            events::client_event::{NettyEventReceived, NettyEventWriter},
+            mapping::{Mappable, NetworkMapping},
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::Structure,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{shared::build_mode::BuildMode, ship::pilot::Pilot},
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    lang::{Lang, Localization},
+    rendering::MainCamera,
+    ui::{
+        components::{
+            button::{register_button, Button, ButtonEvent, ButtonStyles},
+            show_cursor::no_open_menus,
+            window::GuiWindow,
+        },
+        font::DefaultFont,
+        OpenMenu, UiSystemSet,
+    },
+};
+
+#[derive(Component, Debug)]
+/// The upgrade modules menu that is open, and what it is for (using client entities)
+struct OpenUpgradeModules(UpgradeTarget);
+
+#[derive(Component, Debug)]
+struct UpgradeModulesContents;
+
+#[derive(Component, Debug)]
+struct ModuleIndex(usize);
+
+#[derive(Component, Debug)]
+struct ModuleInventorySlot(usize);
+
+#[derive(Event, Debug)]
+struct InstallClicked(Entity);
+
+impl ButtonEvent for InstallClicked {
+    fn create_event(entity: Entity) -> Self {
+        Self(entity)
+    }
+}
+
+#[derive(Event, Debug)]
+struct UninstallClicked(Entity);
+
+impl ButtonEvent for UninstallClicked {
+    fn create_event(entity: Entity) -> Self {
+        Self(entity)
+    }
+}
+
+fn button_styles() -> ButtonStyles {
+    ButtonStyles {
+        background_color: Srgba::hex("555555").unwrap().into(),
+        hover_background_color: Srgba::hex("777777").unwrap().into(),
+        press_background_color: Srgba::hex("333333").unwrap().into(),
+        foreground_color: css::WHITE.into(),
+        hover_foreground_color: css::WHITE.into(),
+        press_foreground_color: css::WHITE.into(),
+    }
+}
+
+fn replace_open_menu(commands: &mut Commands, q_open: &Query<Entity, With<OpenUpgradeModules>>, target: UpgradeTarget) {
+    if let Ok(ent) = q_open.get_single() {
+        commands.entity(ent).insert(NeedsDespawned);
+    }
+
+    commands.spawn((OpenUpgradeModules(target), Name::new("Open Upgrade Modules")));
+}
+
+fn open_block_modules(
+    mut commands: Commands,
+    q_open: Query<Entity, With<OpenUpgradeModules>>,
+    mut nevr_open: EventReader<NettyEventReceived<OpenUpgradeModulesEvent>>,
+    network_mapping: Res<NetworkMapping>,
+) {
+    let Some(ev) = nevr_open.read().last() else {
+        return;
+    };
+
+    let target = match ev.0 {
+        UpgradeTarget::Block(s_block) => {
+            let Ok(block) = s_block.map(&network_mapping) else {
+                error!("Bad network mapping - {:?}", s_block);
+                return;
+            };
+
+            UpgradeTarget::Block(block)
+        }
+        UpgradeTarget::HeldItem => UpgradeTarget::HeldItem,
+    };
+
+    replace_open_menu(&mut commands, &q_open, target);
+}
+
+fn open_tool_modules(
+    mut commands: Commands,
+    inputs: InputChecker,
+    q_open: Query<Entity, With<OpenUpgradeModules>>,
+    q_local_player: Query<(&HeldItemSlot, &Inventory), (With<LocalPlayer>, Without<Pilot>, Without<BuildMode>, Without<Spectator>)>,
+    items: Res<Registry<Item>>,
+    tools: Res<Registry<Tool>>,
+) {
+    if !inputs.check_just_pressed(CosmosInputs::OpenToolModules) {
+        return;
+    }
+
+    let Ok((held_item, inventory)) = q_local_player.get_single() else {
+        return;
+    };
+
+    let holding_mining_tool = inventory
+        .itemstack_at(held_item.slot() as usize)
+        .and_then(|is| tools.from_id(items.from_numeric_id(is.item_id()).unlocalized_name()))
+        .is_some_and(|tool| tool.tier().is_some());
+
+    if holding_mining_tool {
+        replace_open_menu(&mut commands, &q_open, UpgradeTarget::HeldItem);
+    }
+}
+
+fn create_upgrade_modules_window(
+    mut commands: Commands,
+    q_added: Query<Entity, Added<OpenUpgradeModules>>,
+    q_cam: Query<Entity, With<MainCamera>>,
+    localization: Res<Localization>,
+) {
+    for ent in q_added.iter() {
+        let Ok(cam) = q_cam.get_single() else {
+            return;
+        };
+
+        commands
+            .entity(ent)
+            .insert((
+                TargetCamera(cam),
+                OpenMenu::new(0),
+                BackgroundColor(Srgba::hex("2D2D2D").unwrap().into()),
+                Node {
+                    width: Val::Px(450.0),
+                    margin: UiRect::all(Val::Auto),
+                    ..Default::default()
+                },
+                GuiWindow {
+                    title: localization.get("cosmos:window.upgrade_modules").into(),
+                    body_styles: Node {
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(20.0)),
+                        ..Default::default()
+                    },
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    Name::new("Upgrade modules contents"),
+                    UpgradeModulesContents,
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(8.0),
+                        ..Default::default()
+                    },
+                ));
+            });
+    }
+}
+
+/// Finds the modules installed into what this menu is for, and what kind of thing that is
+fn target_modules<'a>(
+    target: UpgradeTarget,
+    held_item: &HeldItemSlot,
+    inventory: &Inventory,
+    q_structure: &'a Query<&Structure>,
+    q_installed_modules: &'a Query<&InstalledModules>,
+) -> (Option<&'a InstalledModules>, UpgradeableKind) {
+    match target {
+        UpgradeTarget::Block(s_block) => (
+            q_structure
+                .get(s_block.structure())
+                .ok()
+                .and_then(|s| s.query_block_data(s_block.coords(), q_installed_modules)),
+            UpgradeableKind::ShipWeapon,
+        ),
+        UpgradeTarget::HeldItem => (
+            inventory
+                .itemstack_at(held_item.slot() as usize)
+                .and_then(|is| is.data_entity())
+                .and_then(|e| q_installed_modules.get(e).ok()),
+            UpgradeableKind::Tool,
+        ),
+    }
+}
+
+fn populate_upgrade_modules_window(
+    mut commands: Commands,
+    q_menu: Query<&OpenUpgradeModules>,
+    q_contents: Query<Entity, With<UpgradeModulesContents>>,
+    q_changed_contents: Query<(), Added<UpgradeModulesContents>>,
+    q_changed_modules: Query<(), Changed<InstalledModules>>,
+    q_local_player: Query<(Ref<HeldItemSlot>, Ref<Inventory>), With<LocalPlayer>>,
+    q_structure: Query<&Structure>,
+    q_installed_modules: Query<&InstalledModules>,
+    items: Res<Registry<Item>>,
+    names: Res<Lang<Item>>,
+    font: Res<DefaultFont>,
+) {
+    let (Ok(menu), Ok(contents_ent)) = (q_menu.get_single(), q_contents.get_single()) else {
+        return;
+    };
+
+    let Ok((held_item, inventory)) = q_local_player.get_single() else {
+        return;
+    };
+
+    if q_changed_contents.is_empty() && q_changed_modules.is_empty() && !inventory.is_changed() && !held_item.is_changed() {
+        return;
+    }
+
+    let (installed, upgradeable) = target_modules(menu.0, &held_item, &inventory, &q_structure, &q_installed_modules);
+
+    let text_style = TextFont {
+        font: font.0.clone_weak(),
+        font_size: 20.0,
+        ..Default::default()
+    };
+
+    let module_name = |kind: UpgradeModuleKind| {
+        names
+            .get_name_from_id(kind.item_unlocalized_name())
+            .unwrap_or(kind.item_unlocalized_name())
+            .to_owned()
+    };
+
+    let row_node = Node {
+        flex_direction: FlexDirection::Row,
+        justify_content: JustifyContent::SpaceBetween,
+        align_items: AlignItems::Center,
+        ..Default::default()
+    };
+
+    let button_node = Node {
+        width: Val::Px(110.0),
+        height: Val::Px(36.0),
+        ..Default::default()
+    };
+
+    let n_installed = installed.map(|m| m.iter().count()).unwrap_or(0);
+
+    let mut ecmds = commands.entity(contents_ent);
+    ecmds.despawn_descendants();
+
+    ecmds.with_children(|p| {
+        p.spawn((
+            Text::new(format!("Installed ({n_installed}/{MAX_INSTALLED_MODULES})")),
+            text_style.clone(),
+        ));
+
+        for (idx, kind) in installed.into_iter().flat_map(|m| m.iter()).enumerate() {
+            p.spawn((Name::new("Installed module"), row_node.clone())).with_children(|p| {
+                p.spawn((Text::new(module_name(*kind)), text_style.clone()));
+                p.spawn((
+                    Name::new("Remove module button"),
+                    ModuleIndex(idx),
+                    button_node.clone(),
+                    Button::<UninstallClicked> {
+                        button_styles: Some(button_styles()),
+                        text: Some(("Remove".into(), text_style.clone(), Default::default())),
+                        ..Default::default()
+                    },
+                ));
+            });
+        }
+
+        p.spawn((
+            Text::new("Available"),
+            text_style.clone(),
+            Node {
+                margin: UiRect::top(Val::Px(10.0)),
+                ..Default::default()
+            },
+        ));
+
+        let mut any_available = false;
+
+        for (slot, is) in inventory.iter().enumerate() {
+            let Some(is) = is else {
+                continue;
+            };
+
+            let Some(kind) = UpgradeModuleKind::from_item_unlocalized_name(items.from_numeric_id(is.item_id()).unlocalized_name()) else {
+                continue;
+            };
+
+            if !kind.can_upgrade(upgradeable) {
+                continue;
+            }
+
+            any_available = true;
+
+            p.spawn((Name::new("Available module"), row_node.clone())).with_children(|p| {
+                p.spawn((Text::new(format!("{} x{}", module_name(kind), is.quantity())), text_style.clone()));
+
+                if n_installed < MAX_INSTALLED_MODULES {
+                    p.spawn((
+                        Name::new("Install module button"),
+                        ModuleInventorySlot(slot),
+                        button_node.clone(),
+                        Button::<InstallClicked> {
+                            button_styles: Some(button_styles()),
+                            text: Some(("Install".into(), text_style.clone(), Default::default())),
+                            ..Default::default()
+                        },
+                    ));
+                }
+            });
+        }
+
+        if !any_available {
+            p.spawn((
+                Text::new("No compatible modules in your inventory"),
+                text_style.clone(),
+                TextColor(css::GRAY.into()),
+            ));
+        }
+    });
+}
+
+fn to_server_target(target: UpgradeTarget, network_mapping: &NetworkMapping) -> Option<UpgradeTarget> {
+    match target {
+        UpgradeTarget::Block(s_block) => s_block.map_to_server(network_mapping).ok().map(UpgradeTarget::Block),
+        UpgradeTarget::HeldItem => Some(UpgradeTarget::HeldItem),
+    }
+}
+
+fn on_install_clicked(
+    mut evr_install: EventReader<InstallClicked>,
+    q_slot: Query<&ModuleInventorySlot>,
+    q_menu: Query<&OpenUpgradeModules>,
+    network_mapping: Res<NetworkMapping>,
+    mut nevw_install: NettyEventWriter<InstallUpgradeModuleEvent>,
+) {
+    for ev in evr_install.read() {
+        let (Ok(slot), Ok(menu)) = (q_slot.get(ev.0), q_menu.get_single()) else {
+            continue;
+        };
+
+        let Some(target) = to_server_target(menu.0, &network_mapping) else {
+            continue;
+        };
+
+        nevw_install.send(InstallUpgradeModuleEvent {
+            target,
+            inventory_slot: slot.0 as u32,
+        });
+    }
+}
+
+fn on_uninstall_clicked(
+    mut evr_uninstall: EventReader<UninstallClicked>,
+    q_index: Query<&ModuleIndex>,
+    q_menu: Query<&OpenUpgradeModules>,
+    network_mapping: Res<NetworkMapping>,
+    mut nevw_uninstall: NettyEventWriter<UninstallUpgradeModuleEvent>,
+) {
+    for ev in evr_uninstall.read() {
+        let (Ok(index), Ok(menu)) = (q_index.get(ev.0), q_menu.get_single()) else {
+            continue;
+        };
+
+        let Some(target) = to_server_target(menu.0, &network_mapping) else {
+            continue;
+        };
+
+        nevw_uninstall.send(UninstallUpgradeModuleEvent {
+            target,
+            module_index: index.0 as u32,
+        });
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<InstallClicked>(app);
+    register_button::<UninstallClicked>(app);
+
+    app.add_systems(
+        Update,
+        (
+            (open_block_modules, open_tool_modules.run_if(no_open_menus)).in_set(NetworkingSystemsSet::Between),
+            (
+                create_upgrade_modules_window,
+                populate_upgrade_modules_window,
+                on_install_clicked,
+                on_uninstall_clicked,
+            )
+                .chain()
+                .in_set(UiSystemSet::DoUi),
+        )
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    );
+}
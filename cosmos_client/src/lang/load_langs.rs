@@ -1,7 +1,9 @@
-use bevy::prelude::{App, Commands, OnEnter, OnExit, Res, ResMut};
+use bevy::prelude::{App, Commands, IntoSystemConfigs, OnEnter, OnExit, Res, ResMut, Update};
 use cosmos_core::{block::Block, item::Item, registry::Registry, state::GameState};
 
-use super::Lang;
+use crate::settings::{Setting, SettingsRegistry, SettingsSet};
+
+use super::{Lang, Localization, DEFAULT_LANGUAGE};
 
 fn insert_langs(
     mut item_langs: ResMut<Lang<Item>>,
@@ -19,11 +21,35 @@ fn insert_langs(
 }
 
 fn insert_resource(mut commands: Commands) {
-    commands.insert_resource(Lang::<Item>::new("en_us", vec!["items", "blocks"]));
-    commands.insert_resource(Lang::<Block>::new("en_us", vec!["blocks"]));
+    commands.insert_resource(Lang::<Item>::new(DEFAULT_LANGUAGE, vec!["items", "blocks"]));
+    commands.insert_resource(Lang::<Block>::new(DEFAULT_LANGUAGE, vec!["blocks"]));
+    commands.insert_resource(Localization::new(DEFAULT_LANGUAGE));
+}
+
+/// The settings are loaded after the langs are created, so this is also responsible for switching to the user's language on startup.
+fn reload_langs_on_language_change(
+    settings: Res<Registry<Setting>>,
+    mut localization: ResMut<Localization>,
+    mut item_langs: ResMut<Lang<Item>>,
+    mut block_langs: ResMut<Lang<Block>>,
+    mut setting_langs: ResMut<Lang<Setting>>,
+    items: Res<Registry<Item>>,
+    blocks: Res<Registry<Block>>,
+) {
+    let language = settings.str_or("cosmos:language", DEFAULT_LANGUAGE);
+
+    if language == localization.language() {
+        return;
+    }
+
+    *localization = Localization::new(language);
+    item_langs.reload(language, &items);
+    block_langs.reload(language, &blocks);
+    setting_langs.reload(language, &settings);
 }
 
 pub(super) fn register(app: &mut App) {
     app.add_systems(OnEnter(GameState::PreLoading), insert_resource)
-        .add_systems(OnExit(GameState::PostLoading), insert_langs);
+        .add_systems(OnExit(GameState::PostLoading), insert_langs)
+        .add_systems(Update, reload_langs_on_language_change.in_set(SettingsSet::LoadSettings));
 }
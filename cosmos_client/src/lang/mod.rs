@@ -2,13 +2,17 @@
 
 mod load_langs;
 
-use std::{fs, marker::PhantomData};
+use std::{fmt::Display, fs, marker::PhantomData};
 
 use bevy::{
+    log::warn,
     prelude::{App, Resource},
     utils::HashMap,
 };
-use cosmos_core::registry::identifiable::Identifiable;
+use cosmos_core::registry::{identifiable::Identifiable, Registry};
+
+/// The language used if the user hasn't chosen one. Any text missing from another language will use this language's text instead.
+pub const DEFAULT_LANGUAGE: &str = "en_us";
 
 #[derive(Resource)]
 /// Used to get the human-readable + localized text to display for identifiable types
@@ -16,12 +20,21 @@ pub struct Lang<T: Identifiable + Send + Sync> {
     map: HashMap<u16, String>,
     id_map: HashMap<String, u16>,
     lang_contents: HashMap<String, String>,
+    read_from: Vec<String>,
     _phantom: PhantomData<T>,
 }
 
 fn load_data(lang_type: &str, lang_folder: &str, map: &mut HashMap<String, String>) {
     let path = format!("assets/cosmos/lang/{lang_folder}/{lang_type}.lang");
-    let str = fs::read_to_string(path.clone()).unwrap_or_else(|_| panic!("Error reading lang file @ '{path}'!"));
+    let str = match fs::read_to_string(&path) {
+        Ok(str) => str,
+        // Other languages can be incomplete, since they will fall back to the default language.
+        Err(_) if lang_type != DEFAULT_LANGUAGE => {
+            warn!("No lang file @ '{path}' - falling back to {DEFAULT_LANGUAGE}.");
+            return;
+        }
+        Err(_) => panic!("Error reading lang file @ '{path}'!"),
+    };
 
     for line in str.split('\n').map(|x| x.trim()).filter(|x| !x.is_empty() && !x.starts_with('#')) {
         let split: Vec<&str> = line.split('=').collect();
@@ -39,23 +52,31 @@ fn load_data(lang_type: &str, lang_folder: &str, map: &mut HashMap<String, Strin
 impl<T: Identifiable + Send + Sync> Lang<T> {
     /// Creates a language instance for from a specific file.
     ///
-    /// * `lang_type` The language identifier, such as en_us
+    /// * `lang_type` The language identifier, such as en_us. Anything missing for this language will use the [`DEFAULT_LANGUAGE`].
     /// * `read_from` These are the files that should be read from for the language data. These should be sorted in order of importance - data found in the file N will override data found files N + X.
     pub fn new(lang_type: &str, read_from: Vec<&str>) -> Self {
-        let mut lang_contents = HashMap::new();
-
-        for fallback in read_from {
-            load_data(lang_type, fallback, &mut lang_contents);
-        }
+        let read_from = read_from.into_iter().map(|x| x.to_owned()).collect::<Vec<_>>();
 
         Self {
-            lang_contents,
+            lang_contents: load_lang_contents(lang_type, &read_from),
+            read_from,
             map: HashMap::default(),
             _phantom: PhantomData,
             id_map: HashMap::default(),
         }
     }
 
+    /// Reloads this for a different language, and re-registers everything in the registry.
+    pub fn reload(&mut self, lang_type: &str, registry: &Registry<T>) {
+        self.lang_contents = load_lang_contents(lang_type, &self.read_from);
+        self.map.clear();
+        self.id_map.clear();
+
+        for item in registry.iter() {
+            self.register(item);
+        }
+    }
+
     /// This is used to add a usable entry
     ///
     /// Returns true if a record existed for this or not, false if not
@@ -98,6 +119,68 @@ impl<T: Identifiable + Send + Sync> Lang<T> {
     }
 }
 
+fn load_lang_contents(lang_type: &str, read_from: &[String]) -> HashMap<String, String> {
+    let mut lang_contents = HashMap::new();
+
+    // Since existing entries aren't overwritten, the chosen language's text takes priority over the default's.
+    for lang in [lang_type, DEFAULT_LANGUAGE] {
+        for folder in read_from {
+            load_data(lang, folder, &mut lang_contents);
+        }
+    }
+
+    lang_contents
+}
+
+#[derive(Resource)]
+/// Localized text that isn't tied to any [`Identifiable`] type, such as the text in menus & the HUD.
+///
+/// This is loaded from the `ui` lang files.
+pub struct Localization {
+    language: String,
+    entries: HashMap<String, String>,
+}
+
+impl Localization {
+    /// Loads the localized text for this language (such as en_us)
+    pub fn new(lang_type: &str) -> Self {
+        Self {
+            language: lang_type.to_owned(),
+            entries: load_lang_contents(lang_type, &["ui".to_owned()]),
+        }
+    }
+
+    /// The language this text is in
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Gets the localized text for this key, or `None` if no language has it
+    pub fn get_opt(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(|x| x.as_str())
+    }
+
+    /// Gets the localized text for this key.
+    ///
+    /// If no language has text for this key, the key itself is returned so the missing entry is obvious.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.get_opt(key).unwrap_or(key)
+    }
+
+    /// Gets the localized text for this key, replacing each `{n}` in it with the nth argument.
+    ///
+    /// Arguments are numbered so languages can put them in a different order.
+    pub fn format(&self, key: &str, args: &[&dyn Display]) -> String {
+        let mut text = self.get(key).to_owned();
+
+        for (i, arg) in args.iter().enumerate() {
+            text = text.replace(&format!("{{{i}}}"), &arg.to_string());
+        }
+
+        text
+    }
+}
+
 pub(super) fn register(app: &mut App) {
     load_langs::register(app);
 }
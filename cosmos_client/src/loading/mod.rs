@@ -1,4 +1,7 @@
 //! Responsible for unloading far entities
+//!
+//! The server sends each entity's [`LoadingDistance`] along with it, so entities are unloaded here at the same
+//! distance the server unloads (and saves) them at.
 
 use bevy::{
     log::info,
@@ -9,19 +12,17 @@ use cosmos_core::{
     entities::player::Player,
     netty::{client::LocalPlayer, system_sets::NetworkingSystemsSet},
     persistence::LoadingDistance,
-    physics::location::{Location, LocationPhysicsSet, SectorUnit},
+    physics::location::{Location, LocationPhysicsSet},
 };
 
 fn unload_far_entities(
-    query: Query<(Entity, &Location, &LoadingDistance), (Without<Player>, Without<Parent>)>,
+    query: Query<(Entity, &Location, &LoadingDistance), (Without<Player>, Without<Parent>, Without<NeedsDespawned>)>,
     my_loc: Query<&Location, With<LocalPlayer>>,
     mut commands: Commands,
 ) {
     if let Ok(my_loc) = my_loc.get_single() {
-        for (ent, loc, unload_distance) in query.iter() {
-            let ul_distance = unload_distance.unload_distance() as SectorUnit;
-
-            if (loc.sector() - my_loc.sector()).abs().max_element() > ul_distance {
+        for (ent, loc, loading_distance) in query.iter() {
+            if loading_distance.should_unload(my_loc, loc) {
                 info!("Unloading {ent:?} - it's too far.");
                 commands.entity(ent).insert(NeedsDespawned);
             }
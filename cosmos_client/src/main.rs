@@ -21,6 +21,8 @@ pub mod item;
 pub mod lang;
 pub mod loading;
 pub mod netty;
+pub mod particles;
+pub mod party;
 pub mod physics;
 pub mod plugin;
 pub mod projectiles;
@@ -181,10 +183,12 @@ fn main() {
     ecs::register(&mut app);
     shop::register(&mut app);
     economy::register(&mut app);
+    party::register(&mut app);
     item::register(&mut app);
     debug::register(&mut app);
     chat::register(&mut app);
     crafting::register(&mut app);
+    particles::register(&mut app);
 
     if cfg!(feature = "print-schedule") {
         println!(
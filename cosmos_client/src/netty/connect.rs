@@ -20,7 +20,7 @@ use renet2::transport::NativeSocket;
 
 use crate::netty::lobby::{ClientLobby, MostRecentTick};
 
-fn new_netcode_transport(player_name: &str, mut host: &str, port: u16) -> NetcodeClientTransport {
+pub(super) fn new_netcode_transport(player_name: &str, mut host: &str, port: u16) -> NetcodeClientTransport {
     if host == "localhost" {
         host = "127.0.0.1"; // to_socket_addrs turns localhost into an ipv6 IP, which fails to connect to the server listening on an ipv4 address.
     }
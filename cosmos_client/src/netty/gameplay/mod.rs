@@ -7,7 +7,7 @@ use cosmos_core::{
 };
 
 pub mod receiver;
-mod sync;
+pub mod sync;
 
 /// This assumes that when an entity is removed, its location component will also be removed.
 ///
@@ -14,7 +14,7 @@ use bevy_kira_audio::prelude::AudioReceiver;
 use bevy_rapier3d::prelude::*;
 use bevy_renet2::renet2::{transport::NetcodeClientTransport, RenetClient};
 use cosmos_core::{
-    block::Block,
+    block::{blocks::AIR_BLOCK_ID, Block},
     ecs::NeedsDespawned,
     entities::player::{render_distance::RenderDistance, Player},
     events::{
@@ -26,12 +26,14 @@ use cosmos_core::{
         client::{LocalPlayer, NeedsLoadedFromServer},
         client_reliable_messages::ClientReliableMessages,
         cosmos_encoder,
-        netty_rigidbody::{NettyRigidBody, NettyRigidBodyLocation},
+        netty_rigidbody::{NettyRigidBody, NettyRigidBodyLocation, QuantizedRigidBody, ReplicatedBody},
+        network_stats::{NettyClientMessages, NettyMessage},
         server_reliable_messages::ServerReliableMessages,
         server_unreliable_messages::ServerUnreliableMessages,
         sync::{
             client_syncing::ClientReceiveComponents,
             mapping::{Mappable, NetworkMapping, ServerEntity},
+            registry::client::RegistryIdMap,
             ComponentEntityIdentifier,
         },
         system_sets::NetworkingSystemsSet,
@@ -60,10 +62,14 @@ use cosmos_core::{
 
 use crate::{
     camera::camera_controller::CameraHelper,
-    netty::lobby::{ClientLobby, PlayerInfo},
+    netty::{
+        gameplay::sync::interpolation::InterpolationBuffer,
+        lobby::{ClientLobby, PlayerInfo},
+    },
     rendering::{CameraPlayerOffset, MainCamera},
     settings::DesiredFov,
     structure::{
+        chunk_retreiver::StructureStreamingProgress,
         planet::{client_planet_builder::ClientPlanetBuilder, generation::SetTerrainGenData},
         ship::{client_ship_builder::ClientShipBuilder, ship_movement::ClientCreateShipMovementSet},
         station::client_station_builder::ClientStationBuilder,
@@ -78,6 +84,14 @@ use crate::{
 #[derive(Component)]
 struct LastRotation(Quat);
 
+#[derive(Component, Debug, Clone, Copy)]
+/// The last full body received from the server for this entity, which body deltas are applied to
+struct BodyBaseline {
+    /// The server tick this was sent at
+    tick: u64,
+    body: QuantizedRigidBody,
+}
+
 fn insert_last_rotation(mut commands: Commands, query: Query<Entity, Added<Structure>>) {
     for ent in query.iter() {
         commands.entity(ent).insert(LastRotation(Quat::IDENTITY));
@@ -143,7 +157,7 @@ pub(crate) struct LerpTowards(NettyRigidBody);
 fn lerp_towards(
     mut location_query: Query<&mut Location>,
     global_transform_query: Query<&GlobalTransform>,
-    mut query: Query<(Entity, &LerpTowards, &mut Transform, &mut Velocity), With<Location>>,
+    mut query: Query<(Entity, &LerpTowards, &mut Transform, &mut Velocity), (With<Location>, Without<InterpolationBuffer>)>,
 ) {
     for (entity, lerp_towards, mut transform, mut velocity) in query.iter_mut() {
         match lerp_towards.location {
@@ -209,12 +223,13 @@ pub(crate) fn client_sync_players(
         EventWriter<SetTerrainGenData>,
         EventWriter<BlockDataChangedEvent>,
     ),
-    (q_default_rapier_context, query_player, q_structure_systems, mut q_inventory, mut q_structure): (
+    (q_default_rapier_context, query_player, q_structure_systems, mut q_inventory, mut q_structure, q_body_baseline): (
         Query<Entity, With<DefaultRapierContext>>,
         Query<&Player>,
         Query<&StructureSystems>,
         Query<&mut Inventory>,
         Query<&mut Structure>,
+        Query<&BodyBaseline>,
     ),
     mut query_body: Query<
         (
@@ -229,7 +244,7 @@ pub(crate) fn client_sync_players(
     desired_fov: Res<DesiredFov>,
     q_needs_loaded: Query<(), With<NeedsLoadedFromServer>>,
     q_parent: Query<&Parent>,
-    blocks: Res<Registry<Block>>,
+    (blocks, block_id_map): (Res<Registry<Block>>, Res<RegistryIdMap<Block>>),
     mut pilot_change_event_writer: EventWriter<ChangePilotEvent>,
     mut requested_entities: ResMut<RequestedEntities>,
     time: Res<Time>,
@@ -253,17 +268,47 @@ pub(crate) fn client_sync_players(
         }
     });
 
-    while let Some(message) = client.receive_message(NettyChannelServer::Unreliable) {
-        let msg: ServerUnreliableMessages = cosmos_encoder::deserialize(&message).unwrap();
+    while let Some(message) = client.receive_netty_message::<ServerUnreliableMessages>(NettyChannelServer::Unreliable) {
+        let msg: ServerUnreliableMessages = message.unwrap();
 
         match msg {
             ServerUnreliableMessages::BulkBodies { bodies, time_stamp } => {
                 for (server_entity, body) in bodies.iter() {
-                    let Ok(body) = body.map(&network_mapping) else {
+                    let mapped_entity = network_mapping.client_from_server(server_entity);
+
+                    let (body, new_baseline) = match body {
+                        ReplicatedBody::Full(body) => (
+                            *body,
+                            Some(BodyBaseline {
+                                tick: time_stamp,
+                                body: *body,
+                            }),
+                        ),
+                        ReplicatedBody::Delta { baseline, delta } => {
+                            // If we don't have the full body this delta is based on, we have to wait for the next one
+                            let Some(baseline) = mapped_entity
+                                .and_then(|e| q_body_baseline.get(e).ok())
+                                .filter(|b| b.tick == *baseline)
+                            else {
+                                continue;
+                            };
+
+                            (baseline.body.apply_delta(delta), None)
+                        }
+                    };
+
+                    let Ok(body) = NettyRigidBody::from(body).map(&network_mapping) else {
                         continue;
                     };
 
-                    if let Some(entity) = network_mapping.client_from_server(server_entity) {
+                    if let Some(entity) = mapped_entity {
+                        if let Some(new_baseline) = new_baseline {
+                            // Don't let a full body that arrived out of order replace a newer one
+                            if !q_body_baseline.get(entity).is_ok_and(|b| b.tick >= new_baseline.tick) {
+                                commands.entity(entity).insert(new_baseline);
+                            }
+                        }
+
                         if q_needs_loaded.contains(entity) {
                             commands.entity(entity).remove::<NeedsLoadedFromServer>();
 
@@ -273,9 +318,9 @@ pub(crate) fn client_sync_players(
                                 seconds_since_request: 0.0,
                             });
 
-                            client.send_message(
+                            client.send_netty_message(
                                 NettyChannelClient::Reliable,
-                                cosmos_encoder::serialize(&ClientReliableMessages::RequestEntityData { entity: *server_entity }),
+                                NettyMessage::new(&ClientReliableMessages::RequestEntityData { entity: *server_entity }),
                             );
                         } else if let Ok((location, transform, velocity, net_tick, lerp_towards)) = query_body.get_mut(entity) {
                             if let Some(mut net_tick) = net_tick {
@@ -340,6 +385,10 @@ pub(crate) fn client_sync_players(
                             LerpTowards(body),
                         ));
 
+                        if let Some(new_baseline) = new_baseline {
+                            client_entity_ecmds.insert(new_baseline);
+                        }
+
                         if let Some(parent_ent) = parent_ent {
                             client_entity_ecmds.set_parent_in_place(parent_ent);
                         }
@@ -353,9 +402,9 @@ pub(crate) fn client_sync_players(
                         });
                         network_mapping.add_mapping(client_entity, *server_entity);
 
-                        client.send_message(
+                        client.send_netty_message(
                             NettyChannelClient::Reliable,
-                            cosmos_encoder::serialize(&ClientReliableMessages::RequestEntityData { entity: *server_entity }),
+                            NettyMessage::new(&ClientReliableMessages::RequestEntityData { entity: *server_entity }),
                         );
                     }
                 }
@@ -368,8 +417,8 @@ pub(crate) fn client_sync_players(
         }
     }
 
-    while let Some(message) = client.receive_message(NettyChannelServer::Reliable) {
-        let msg: ServerReliableMessages = cosmos_encoder::deserialize(&message).unwrap();
+    while let Some(message) = client.receive_netty_message::<ServerReliableMessages>(NettyChannelServer::Reliable) {
+        let msg: ServerReliableMessages = message.unwrap();
 
         match msg {
             // TODO: Get player data via the normal request entity function!
@@ -393,8 +442,13 @@ pub(crate) fn client_sync_players(
 
                 info!("Player {} ({}) connected! {body:?}", name.as_str(), id);
 
+                // If we are resuming a session after reconnecting, our old local player is kept around and reused.
+                let resumed_local_player = if client_id == id { local_player.get_single().ok() } else { None };
+
                 // The player entity may have already been created if some of their components were already synced.
-                let mut entity_cmds = if let Some(player_entity) = network_mapping.client_from_server(&server_entity) {
+                let mut entity_cmds = if let Some(player_entity) = resumed_local_player {
+                    commands.entity(player_entity)
+                } else if let Some(player_entity) = network_mapping.client_from_server(&server_entity) {
                     commands.entity(player_entity)
                 } else {
                     commands.spawn_empty()
@@ -439,12 +493,12 @@ pub(crate) fn client_sync_players(
                 let camera_offset = Vec3::new(0.0, 0.75, 0.0);
 
                 // Requests all components needed for the player
-                client.send_message(
+                client.send_netty_message(
                     NettyChannelClient::Reliable,
-                    cosmos_encoder::serialize(&ClientReliableMessages::RequestEntityData { entity: server_entity }),
+                    NettyMessage::new(&ClientReliableMessages::RequestEntityData { entity: server_entity }),
                 );
 
-                if client_id == id {
+                if client_id == id && resumed_local_player.is_none() {
                     entity_cmds
                         .insert((
                             LocalPlayer,
@@ -535,7 +589,12 @@ pub(crate) fn client_sync_players(
                 };
 
                 if let Some(mut ecmds) = commands.get_entity(entity) {
-                    ecmds.insert(chunks_needed);
+                    ecmds.insert((
+                        chunks_needed,
+                        StructureStreamingProgress {
+                            total_chunks: chunks_needed.amount_needed,
+                        },
+                    ));
                 }
             }
             ServerReliableMessages::Ship {
@@ -568,9 +627,9 @@ pub(crate) fn client_sync_players(
 
                 entity_cmds.insert((structure /*chunks_needed*/,));
 
-                client.send_message(
+                client.send_netty_message(
                     NettyChannelClient::Reliable,
-                    cosmos_encoder::serialize(&ClientReliableMessages::PilotQuery {
+                    NettyMessage::new(&ClientReliableMessages::PilotQuery {
                         ship_entity: server_entity,
                     }),
                 );
@@ -605,33 +664,42 @@ pub(crate) fn client_sync_players(
 
                 entity_cmds.insert((structure /*chunks_needed*/,));
             }
-            ServerReliableMessages::ChunkData {
+            ServerReliableMessages::ChunkBatch {
                 structure_entity: server_structure_entity,
-                serialized_chunk,
-                serialized_block_data,
-                block_entities,
+                chunks,
             } => {
-                if let Some(s_entity) = network_mapping.client_from_server(&server_structure_entity) {
-                    if let Ok(mut structure) = q_structure.get_mut(s_entity) {
-                        let chunk: Chunk = cosmos_encoder::deserialize(&serialized_chunk).expect("Unable to deserialize chunk from server");
-                        let chunk_coords = chunk.chunk_coordinates();
+                let Some(s_entity) = network_mapping.client_from_server(&server_structure_entity) else {
+                    continue;
+                };
 
-                        structure.set_chunk(chunk);
+                let Ok(mut structure) = q_structure.get_mut(s_entity) else {
+                    continue;
+                };
 
-                        for (_, block_data_entity) in block_entities {
-                            info!("New block data -- asking.");
-                            client.send_message(
-                                NettyChannelClient::Reliable,
-                                cosmos_encoder::serialize(&ClientReliableMessages::RequestEntityData { entity: block_data_entity }),
-                            );
-                        }
+                for serialized_chunk in chunks {
+                    let mut chunk: Chunk = cosmos_encoder::deserialize_compressed(&serialized_chunk.serialized_chunk)
+                        .expect("Unable to deserialize chunk from server");
 
-                        set_chunk_event_writer.send(ChunkInitEvent {
-                            coords: chunk_coords,
-                            structure_entity: s_entity,
-                            serialized_block_data: serialized_block_data.map(|x| Arc::new(Mutex::new(x))),
-                        });
+                    if !block_id_map.is_identity() {
+                        chunk.remap_block_ids(|id| block_id_map.to_client(id).unwrap_or(AIR_BLOCK_ID));
+                    }
+                    let chunk_coords = chunk.chunk_coordinates();
+
+                    structure.set_chunk(chunk);
+
+                    for (_, block_data_entity) in serialized_chunk.block_entities {
+                        info!("New block data -- asking.");
+                        client.send_netty_message(
+                            NettyChannelClient::Reliable,
+                            NettyMessage::new(&ClientReliableMessages::RequestEntityData { entity: block_data_entity }),
+                        );
                     }
+
+                    set_chunk_event_writer.send(ChunkInitEvent {
+                        coords: chunk_coords,
+                        structure_entity: s_entity,
+                        serialized_block_data: serialized_chunk.serialized_block_data.map(|x| Arc::new(Mutex::new(x))),
+                    });
                 }
             }
             ServerReliableMessages::EmptyChunk { structure_entity, coords } => {
@@ -674,7 +742,7 @@ pub(crate) fn client_sync_players(
                         for block_changed in blocks_changed_packet.0 {
                             structure.set_block_and_info_at(
                                 block_changed.coordinates.coords(),
-                                blocks.from_numeric_id(block_changed.block_id),
+                                blocks.from_numeric_id(block_id_map.to_client(block_changed.block_id).unwrap_or(AIR_BLOCK_ID)),
                                 block_changed.block_info,
                                 &blocks,
                                 Some(&mut block_change_event_writer),
@@ -0,0 +1,335 @@
+//! Smooths the movement of remote ships + players.
+//!
+//! Body updates from the server arrive at irregular intervals over an unreliable channel. Rather than
+//! snapping (or lerping) towards the most recent update, remote entities are rendered slightly in the
+//! past (see [`InterpolationSettings::delay`]) so there are almost always two updates to interpolate
+//! between. If updates are late, the entity is extrapolated using its last known velocity, but only for
+//! a short amount of time to prevent it from flying off if the server stops sending updates.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use cosmos_core::{
+    entities::player::Player,
+    netty::{
+        client::LocalPlayer,
+        netty_rigidbody::{NettyRigidBody, NettyRigidBodyLocation},
+        system_sets::NetworkingSystemsSet,
+    },
+    physics::location::{Location, LocationPhysicsSet},
+    state::GameState,
+    structure::ship::{pilot::Pilot, Ship},
+};
+
+use crate::netty::gameplay::receiver::LerpTowards;
+
+#[derive(Resource, Debug, Clone, Copy)]
+/// Configures how remote entities are interpolated
+pub struct InterpolationSettings {
+    /// How far in the past (in seconds) remote entities are rendered.
+    ///
+    /// Larger values are smoother when packets are lost or arrive unevenly, but make remote entities
+    /// appear further behind where they actually are.
+    pub delay: f32,
+    /// The maximum amount of time (in seconds) an entity will be extrapolated past its most recent update.
+    pub max_extrapolation: f32,
+    /// The maximum number of updates that will be stored per entity.
+    pub max_buffered_snapshots: usize,
+}
+
+impl Default for InterpolationSettings {
+    fn default() -> Self {
+        Self {
+            delay: 0.1,
+            max_extrapolation: 0.25,
+            max_buffered_snapshots: 32,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default, Clone, Copy)]
+/// Stats about the interpolation of remote entities for the most recent frame.
+///
+/// Useful for debugging network issues.
+pub struct InterpolationStats {
+    /// Entities that were between two updates this frame
+    pub interpolating: usize,
+    /// Entities whose newest update was older than the render time, but were still within the extrapolation window
+    pub extrapolating: usize,
+    /// Entities whose newest update was so old that they exceeded the extrapolation window
+    pub starved: usize,
+    /// The average number of updates buffered for each entity
+    pub average_buffer_len: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BufferedSnapshot {
+    /// The time (from [`Time::elapsed_secs_f64`]) this was received
+    received_at: f64,
+    body: NettyRigidBody,
+}
+
+#[derive(Component, Debug, Default)]
+/// Stores the most recent updates received from the server for a remote entity.
+pub struct InterpolationBuffer {
+    snapshots: VecDeque<BufferedSnapshot>,
+}
+
+enum Sample {
+    Interpolated(NettyRigidBody),
+    Extrapolated(NettyRigidBody),
+    Starved(NettyRigidBody),
+}
+
+fn lerp_body(a: &NettyRigidBody, b: &NettyRigidBody, t: f32) -> NettyRigidBody {
+    let location = match (a.location, b.location) {
+        (NettyRigidBodyLocation::Absolute(a_loc), NettyRigidBodyLocation::Absolute(b_loc)) => {
+            NettyRigidBodyLocation::Absolute(a_loc + a_loc.relative_coords_to(&b_loc) * t)
+        }
+        (NettyRigidBodyLocation::Relative(a_pos, a_parent), NettyRigidBodyLocation::Relative(b_pos, b_parent)) if a_parent == b_parent => {
+            NettyRigidBodyLocation::Relative(a_pos.lerp(b_pos, t), a_parent)
+        }
+        // Changed what it's relative to, there's nothing to interpolate between.
+        _ => b.location,
+    };
+
+    let a_vel = a.create_velocity();
+    let b_vel = b.create_velocity();
+
+    NettyRigidBody::new(
+        Some(Velocity {
+            linvel: a_vel.linvel.lerp(b_vel.linvel, t),
+            angvel: a_vel.angvel.lerp(b_vel.angvel, t),
+        }),
+        a.rotation.slerp(b.rotation, t),
+        location,
+    )
+}
+
+fn extrapolate_body(body: &NettyRigidBody, seconds: f32) -> NettyRigidBody {
+    let vel = body.create_velocity();
+
+    let location = match body.location {
+        NettyRigidBodyLocation::Absolute(loc) => NettyRigidBodyLocation::Absolute(loc + vel.linvel * seconds),
+        NettyRigidBodyLocation::Relative(pos, parent) => NettyRigidBodyLocation::Relative(pos + vel.linvel * seconds, parent),
+    };
+
+    NettyRigidBody::new(
+        Some(vel),
+        (Quat::from_scaled_axis(vel.angvel * seconds) * body.rotation).normalize(),
+        location,
+    )
+}
+
+impl InterpolationBuffer {
+    /// The number of updates currently buffered
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Returns true if there are no updates buffered
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Removes every buffered update, so the entity jumps straight to the next update instead of
+    /// interpolating towards it. Use this when an entity is teleported.
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+
+    fn push(&mut self, received_at: f64, body: NettyRigidBody, max_len: usize) {
+        self.snapshots.push_back(BufferedSnapshot { received_at, body });
+
+        while self.snapshots.len() > max_len.max(2) {
+            self.snapshots.pop_front();
+        }
+    }
+
+    /// Removes any snapshots that are no longer needed to interpolate at this time.
+    ///
+    /// One snapshot older than the render time is always kept, since it's needed to interpolate from.
+    fn prune(&mut self, render_time: f64) {
+        while self.snapshots.len() > 2 && self.snapshots[1].received_at <= render_time {
+            self.snapshots.pop_front();
+        }
+    }
+
+    fn sample(&self, render_time: f64, max_extrapolation: f32) -> Option<Sample> {
+        let newest = self.snapshots.back()?;
+
+        if newest.received_at <= render_time {
+            let late_by = (render_time - newest.received_at) as f32;
+
+            return Some(if late_by > max_extrapolation {
+                Sample::Starved(extrapolate_body(&newest.body, max_extrapolation))
+            } else {
+                Sample::Extrapolated(extrapolate_body(&newest.body, late_by))
+            });
+        }
+
+        let Some(after_idx) = self.snapshots.iter().position(|s| s.received_at > render_time) else {
+            return Some(Sample::Interpolated(newest.body));
+        };
+
+        if after_idx == 0 {
+            // We don't have anything older than the render time yet, so just wait at the oldest position.
+            return Some(Sample::Interpolated(self.snapshots[0].body));
+        }
+
+        let before = &self.snapshots[after_idx - 1];
+        let after = &self.snapshots[after_idx];
+
+        let span = after.received_at - before.received_at;
+        let t = if span <= f64::EPSILON {
+            1.0
+        } else {
+            ((render_time - before.received_at) / span) as f32
+        };
+
+        Some(Sample::Interpolated(lerp_body(&before.body, &after.body, t.clamp(0.0, 1.0))))
+    }
+}
+
+/// Ships piloted by the local player are not interpolated, since that would make their controls feel delayed.
+fn add_interpolation_buffer(
+    mut commands: Commands,
+    q_needs_buffer: Query<
+        (Entity, Option<&Pilot>),
+        (
+            With<LerpTowards>,
+            Or<(With<Ship>, With<Player>)>,
+            Without<LocalPlayer>,
+            Without<InterpolationBuffer>,
+        ),
+    >,
+    q_local_player: Query<(), With<LocalPlayer>>,
+) {
+    for (ent, pilot) in q_needs_buffer.iter() {
+        if pilot.is_some_and(|p| q_local_player.contains(p.entity)) {
+            continue;
+        }
+
+        commands.entity(ent).insert(InterpolationBuffer::default());
+    }
+}
+
+fn remove_buffer_from_locally_piloted(
+    mut commands: Commands,
+    q_piloted: Query<(Entity, &Pilot), (With<InterpolationBuffer>, Changed<Pilot>)>,
+    q_local_player: Query<(), With<LocalPlayer>>,
+) {
+    for (ent, pilot) in q_piloted.iter() {
+        if q_local_player.contains(pilot.entity) {
+            commands.entity(ent).remove::<InterpolationBuffer>();
+        }
+    }
+}
+
+/// [`LerpTowards`] is updated by the receiver whenever a new body is received from the server.
+fn buffer_received_bodies(
+    time: Res<Time>,
+    settings: Res<InterpolationSettings>,
+    mut q_buffers: Query<(&LerpTowards, &mut InterpolationBuffer), Changed<LerpTowards>>,
+) {
+    let now = time.elapsed_secs_f64();
+
+    for (lerp_towards, mut buffer) in q_buffers.iter_mut() {
+        buffer.push(now, **lerp_towards, settings.max_buffered_snapshots);
+    }
+}
+
+fn interpolate_bodies(
+    time: Res<Time>,
+    settings: Res<InterpolationSettings>,
+    mut stats: ResMut<InterpolationStats>,
+    q_global_transform: Query<&GlobalTransform>,
+    mut q_bodies: Query<(&mut InterpolationBuffer, &mut Location, &mut Transform, &mut Velocity)>,
+) {
+    let render_time = time.elapsed_secs_f64() - settings.delay as f64;
+
+    let mut new_stats = InterpolationStats::default();
+    let mut total_buffered = 0;
+    let mut n_buffers = 0;
+
+    for (mut buffer, mut location, mut transform, mut velocity) in q_bodies.iter_mut() {
+        buffer.prune(render_time);
+
+        total_buffered += buffer.len();
+        n_buffers += 1;
+
+        let Some(sample) = buffer.sample(render_time, settings.max_extrapolation) else {
+            continue;
+        };
+
+        let body = match sample {
+            Sample::Interpolated(body) => {
+                new_stats.interpolating += 1;
+                body
+            }
+            Sample::Extrapolated(body) => {
+                new_stats.extrapolating += 1;
+                body
+            }
+            Sample::Starved(body) => {
+                new_stats.starved += 1;
+                body
+            }
+        };
+
+        match body.location {
+            NettyRigidBodyLocation::Absolute(loc) => {
+                location.set_from(&loc);
+            }
+            NettyRigidBodyLocation::Relative(rel_trans, parent) => {
+                if let Ok(g_trans) = q_global_transform.get(parent) {
+                    let parent_rot = Quat::from_affine3(&g_trans.affine());
+                    transform.translation = parent_rot.inverse().mul_vec3(rel_trans);
+                } else {
+                    error!("Missing parent for interpolated body relative to entity {parent:?}!");
+                }
+            }
+        }
+
+        transform.rotation = body.rotation;
+        *velocity = body.create_velocity();
+    }
+
+    if n_buffers != 0 {
+        new_stats.average_buffer_len = total_buffered as f32 / n_buffers as f32;
+    }
+
+    *stats = new_stats;
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+/// Remote entity interpolation
+pub enum InterpolationSet {
+    /// Applies the interpolated body to remote entities
+    Interpolate,
+}
+
+pub(super) fn register(app: &mut App) {
+    app.configure_sets(
+        Update,
+        InterpolationSet::Interpolate
+            .before(LocationPhysicsSet::DoPhysics)
+            .in_set(NetworkingSystemsSet::Between),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            add_interpolation_buffer,
+            remove_buffer_from_locally_piloted,
+            buffer_received_bodies,
+            interpolate_bodies,
+        )
+            .chain()
+            .in_set(InterpolationSet::Interpolate)
+            .run_if(in_state(GameState::Playing).or(in_state(GameState::LoadingWorld))),
+    )
+    .init_resource::<InterpolationSettings>()
+    .init_resource::<InterpolationStats>();
+}
@@ -1,7 +1,11 @@
+//! Syncs the bodies of entities between the client and server
+
 use bevy::prelude::App;
 
+pub mod interpolation;
 mod sync_player;
 
 pub(super) fn register(app: &mut App) {
     sync_player::register(app);
+    interpolation::register(app);
 }
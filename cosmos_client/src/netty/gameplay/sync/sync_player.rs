@@ -5,8 +5,8 @@ use cosmos_core::{
     netty::{
         client::LocalPlayer,
         client_unreliable_messages::ClientUnreliableMessages,
-        cosmos_encoder,
         netty_rigidbody::{NettyRigidBody, NettyRigidBodyLocation},
+        network_stats::{NettyClientMessages, NettyMessage},
         sync::mapping::NetworkMapping,
         system_sets::NetworkingSystemsSet,
         NettyChannelClient,
@@ -48,9 +48,9 @@ fn send_position(
             looking,
         };
 
-        let serialized_message = cosmos_encoder::serialize(&msg);
+        let serialized_message = NettyMessage::new(&msg);
 
-        client.send_message(NettyChannelClient::Unreliable, serialized_message);
+        client.send_netty_message(NettyChannelClient::Unreliable, serialized_message);
     }
 }
 
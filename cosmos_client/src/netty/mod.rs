@@ -14,6 +14,8 @@ pub mod connect;
 pub mod gameplay;
 pub mod loading;
 pub mod lobby;
+pub mod reconnect;
+pub mod singleplayer;
 
 pub(super) fn register(app: &mut App) {
     loading::register(app);
@@ -38,4 +40,6 @@ pub(super) fn register(app: &mut App) {
     );
 
     gameplay::register(app);
+    reconnect::register(app);
+    singleplayer::register(app);
 }
@@ -0,0 +1,300 @@
+//! Handles recovering from an unexpected loss of connection to the server.
+//!
+//! Instead of kicking the player back to the main menu when the connection drops, the client will
+//! keep the world (and the local player) around, display an overlay, and attempt to reconnect to the
+//! same server with an exponential backoff. Once the connection is re-established, the server will
+//! re-send the player + entity data, and the existing [`NetworkMapping`] will be reused so that
+//! entities the client already knows about are updated instead of duplicated.
+
+use bevy::prelude::*;
+use bevy_renet2::renet2::{transport::NetcodeClientTransport, ClientId, DisconnectReason, RenetClient};
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    netty::{client::LocalPlayer, connection_config, sync::mapping::NetworkMapping, system_sets::NetworkingSystemsSet},
+    state::GameState,
+};
+
+use crate::{
+    lang::Localization,
+    ui::{components::show_cursor::ShowCursor, font::DefaultFont, main_menu::MainMenuSubState},
+};
+
+use super::{
+    connect::{new_netcode_transport, HostConfig},
+    lobby::ClientLobby,
+};
+
+/// The amount of seconds to wait before the first reconnect attempt.
+const INITIAL_BACKOFF_SECS: f32 = 1.0;
+/// The backoff will never exceed this amount of seconds between attempts.
+const MAX_BACKOFF_SECS: f32 = 30.0;
+/// After this many failed attempts, the player will be sent to the disconnect screen.
+const MAX_RECONNECT_ATTEMPTS: u32 = 8;
+
+#[derive(Resource, Debug)]
+/// If this resource exists, the client has lost its connection to the server and is trying to get it back.
+pub struct Reconnecting {
+    /// How many attempts have been started so far
+    attempt: u32,
+    /// Seconds until the next attempt will be started
+    next_attempt_in: f32,
+    /// If an attempt is currently in progress (a transport has been created and is trying to connect)
+    attempt_in_progress: bool,
+    /// The client id the player had before the connection was lost
+    old_client_id: ClientId,
+}
+
+impl Reconnecting {
+    /// How many attempts have been started so far
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Seconds until the next attempt will be started
+    pub fn next_attempt_in(&self) -> f32 {
+        self.next_attempt_in
+    }
+
+    fn backoff_for(attempt: u32) -> f32 {
+        (INITIAL_BACKOFF_SECS * 2.0_f32.powi(attempt as i32)).min(MAX_BACKOFF_SECS)
+    }
+}
+
+#[derive(Event, Debug)]
+/// Sent when the client successfully resumes its session with the server after losing connection
+pub struct SessionResumedEvent;
+
+#[derive(Component)]
+struct ReconnectOverlay;
+
+#[derive(Component)]
+struct ReconnectStatusText;
+
+/// Only unexpected transport failures (timeouts, dropped packets, etc) are worth retrying.
+///
+/// If the server intentionally kicked us or we quit, there is nothing to resume.
+fn should_attempt_reconnect(reason: Option<DisconnectReason>) -> bool {
+    matches!(reason, Some(DisconnectReason::Transport))
+}
+
+/// Returns true if the client lost connection and no reconnect will be attempted.
+///
+/// Used to determine if the player should be sent back to the main menu.
+pub(crate) fn is_disconnected_without_reconnect(client: Option<Res<RenetClient>>, reconnecting: Option<Res<Reconnecting>>) -> bool {
+    if reconnecting.is_some() {
+        return false;
+    }
+
+    let Some(client) = client else {
+        return true;
+    };
+
+    client.is_disconnected() && !should_attempt_reconnect(client.disconnect_reason())
+}
+
+fn on_connection_lost(
+    mut commands: Commands,
+    client: Res<RenetClient>,
+    transport: Res<NetcodeClientTransport>,
+    default_font: Res<DefaultFont>,
+    localization: Res<Localization>,
+) {
+    if !client.is_disconnected() || !should_attempt_reconnect(client.disconnect_reason()) {
+        return;
+    }
+
+    warn!("Lost connection to server - attempting to reconnect.");
+
+    commands.insert_resource(Reconnecting {
+        attempt: 0,
+        next_attempt_in: INITIAL_BACKOFF_SECS,
+        attempt_in_progress: false,
+        old_client_id: transport.client_id(),
+    });
+
+    let text_style = TextFont {
+        font_size: 32.0,
+        font: default_font.0.clone(),
+        ..Default::default()
+    };
+
+    commands
+        .spawn((
+            Name::new("Reconnect Overlay"),
+            ReconnectOverlay,
+            ShowCursor,
+            Node {
+                flex_direction: FlexDirection::Column,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(20.0),
+                ..Default::default()
+            },
+            GlobalZIndex(200),
+            BackgroundColor(
+                Srgba {
+                    red: 0.0,
+                    green: 0.0,
+                    blue: 0.0,
+                    alpha: 0.6,
+                }
+                .into(),
+            ),
+        ))
+        .with_children(|p| {
+            p.spawn((Text::new(localization.get("cosmos:reconnect.connection_lost")), text_style.clone()));
+            p.spawn((
+                ReconnectStatusText,
+                Text::new(localization.get("cosmos:reconnect.reconnecting")),
+                TextFont {
+                    font_size: 24.0,
+                    ..text_style
+                },
+            ));
+        });
+}
+
+fn attempt_reconnect(
+    mut commands: Commands,
+    mut reconnecting: ResMut<Reconnecting>,
+    client: Res<RenetClient>,
+    host_config: Res<HostConfig>,
+    time: Res<Time>,
+    mut state: ResMut<NextState<GameState>>,
+    q_overlay: Query<Entity, With<ReconnectOverlay>>,
+) {
+    if reconnecting.attempt_in_progress {
+        if !client.is_disconnected() {
+            // Still connecting, or connected (which is handled by `on_reconnected`).
+            return;
+        }
+
+        info!("Reconnect attempt {} failed.", reconnecting.attempt);
+        reconnecting.attempt_in_progress = false;
+        reconnecting.next_attempt_in = Reconnecting::backoff_for(reconnecting.attempt);
+    }
+
+    if reconnecting.attempt >= MAX_RECONNECT_ATTEMPTS {
+        warn!("Unable to reconnect after {MAX_RECONNECT_ATTEMPTS} attempts - giving up.");
+
+        for ent in q_overlay.iter() {
+            commands.entity(ent).insert(NeedsDespawned);
+        }
+        commands.remove_resource::<Reconnecting>();
+        commands.insert_resource(MainMenuSubState::Disconnect);
+        state.set(GameState::MainMenu);
+        return;
+    }
+
+    reconnecting.next_attempt_in -= time.delta_secs();
+    if reconnecting.next_attempt_in > 0.0 {
+        return;
+    }
+
+    reconnecting.attempt += 1;
+    reconnecting.attempt_in_progress = true;
+
+    info!("Reconnect attempt {}/{MAX_RECONNECT_ATTEMPTS}...", reconnecting.attempt);
+
+    commands.insert_resource(RenetClient::new(connection_config()));
+    commands.insert_resource(new_netcode_transport(
+        &host_config.name,
+        host_config.host_name.as_str(),
+        host_config.port,
+    ));
+}
+
+/// Once connected again, remove any stale information about our old session so the server's
+/// re-sent data will be applied to the entities we already have.
+fn on_reconnected(
+    mut commands: Commands,
+    reconnecting: Res<Reconnecting>,
+    client: Res<RenetClient>,
+    mut lobby: ResMut<ClientLobby>,
+    mut network_mapping: ResMut<NetworkMapping>,
+    q_overlay: Query<Entity, With<ReconnectOverlay>>,
+    q_local_player: Query<Entity, With<LocalPlayer>>,
+    mut evw_resumed: EventWriter<SessionResumedEvent>,
+) {
+    if !reconnecting.attempt_in_progress || !client.is_connected() {
+        return;
+    }
+
+    info!("Reconnected to server after {} attempt(s)!", reconnecting.attempt);
+
+    // The server gives us a brand new player entity on reconnect, so the old mapping for our player is useless.
+    // All other entities keep their server ids, so their mappings are kept as-is.
+    if let Some(old_info) = lobby.players.remove(&reconnecting.old_client_id) {
+        network_mapping.remove_mapping_from_server_entity(&old_info.server_entity);
+    } else if let Ok(local_player) = q_local_player.get_single() {
+        network_mapping.remove_mapping_from_client_entity(&local_player);
+    }
+
+    for ent in q_overlay.iter() {
+        commands.entity(ent).insert(NeedsDespawned);
+    }
+
+    commands.remove_resource::<Reconnecting>();
+    evw_resumed.send(SessionResumedEvent);
+}
+
+fn update_reconnect_text(
+    reconnecting: Res<Reconnecting>,
+    localization: Res<Localization>,
+    mut q_text: Query<&mut Text, With<ReconnectStatusText>>,
+) {
+    for mut text in q_text.iter_mut() {
+        text.0 = if reconnecting.attempt_in_progress {
+            localization.format("cosmos:reconnect.attempting", &[&reconnecting.attempt, &MAX_RECONNECT_ATTEMPTS])
+        } else {
+            localization.format(
+                "cosmos:reconnect.retrying_in",
+                &[
+                    &reconnecting.next_attempt_in.ceil(),
+                    &(reconnecting.attempt + 1),
+                    &MAX_RECONNECT_ATTEMPTS,
+                ],
+            )
+        };
+    }
+}
+
+fn remove_reconnecting(mut commands: Commands) {
+    commands.remove_resource::<Reconnecting>();
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+/// Systems that handle reconnecting to the server after losing connection
+pub enum ReconnectSet {
+    /// Detects the connection being lost and starts the reconnect process
+    DetectConnectionLost,
+    /// Attempts to reconnect and resumes the session once connected
+    Reconnect,
+}
+
+pub(super) fn register(app: &mut App) {
+    app.configure_sets(
+        Update,
+        (ReconnectSet::DetectConnectionLost, ReconnectSet::Reconnect)
+            .chain()
+            .before(NetworkingSystemsSet::ReceiveMessages)
+            .run_if(in_state(GameState::Playing)),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            on_connection_lost
+                .run_if(not(resource_exists::<Reconnecting>))
+                .in_set(ReconnectSet::DetectConnectionLost),
+            (on_reconnected, attempt_reconnect, update_reconnect_text)
+                .chain()
+                .run_if(resource_exists::<Reconnecting>)
+                .in_set(ReconnectSet::Reconnect),
+        ),
+    )
+    .add_systems(OnExit(GameState::Playing), remove_reconnecting)
+    .add_event::<SessionResumedEvent>();
+}
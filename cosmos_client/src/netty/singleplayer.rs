@@ -0,0 +1,277 @@
+//! Singleplayer support, where the client launches its own server and connects to it over localhost.
+//!
+//! The server is run as a child process in the same working directory as the client, so the
+//! singleplayer world is stored in the same `world/` directory a dedicated server run from the
+//! install directory would use.
+//!
+//! Lifecycle:
+//! - Inserting [`StartSingleplayer`] launches the server, waits for it to be ready, then connects.
+//! - While playing, opening the pause menu pauses the server's world.
+//! - Once the player leaves, the server saves and shuts itself down. If it doesn't in a reasonable
+//!   amount of time, it will be killed.
+
+use std::{
+    io::{self, BufRead, BufReader},
+    net::UdpSocket,
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use bevy::{app::AppExit, prelude::*};
+use bevy_renet2::renet2::transport::NetcodeClientTransport;
+use cosmos_core::{
+    netty::{
+        singleplayer::{SetSingleplayerPaused, SINGLEPLAYER_ARG, SINGLEPLAYER_READY_MARKER},
+        sync::events::client_event::NettyEventWriter,
+        system_sets::NetworkingSystemsSet,
+    },
+    state::GameState,
+};
+
+use crate::ui::{main_menu::MainMenuSubState, pause::Paused};
+
+use super::connect::HostConfig;
+
+/// If set, this path will be used to find the server executable instead of looking next to the client's executable.
+const SERVER_PATH_ENV_VAR: &str = "COSMOS_SERVER_PATH";
+/// After the player leaves, the server gets this many seconds to save + shut down before it is killed.
+const SHUTDOWN_TIMEOUT_SECS: f32 = 30.0;
+
+#[derive(Resource, Debug)]
+/// Insert this resource while in the main menu to launch a singleplayer server and connect to it.
+///
+/// If a previous singleplayer server is still shutting down, this will wait for it to finish first,
+/// since both would be using the same world.
+pub struct StartSingleplayer {
+    /// The name the player will join with
+    pub player_name: String,
+}
+
+#[derive(Resource, Debug)]
+/// The singleplayer server process launched by this client.
+pub struct SingleplayerServer {
+    process: Child,
+    port: u16,
+    ready: Arc<AtomicBool>,
+    /// Seconds since the player left this server, if they have
+    shutting_down_for: Option<f32>,
+}
+
+impl SingleplayerServer {
+    /// The localhost port this server is listening on
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// If the server has finished loading and is ready to accept connections
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Relaxed)
+    }
+
+    /// If the player has left this server and it is in the process of saving + shutting down
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down_for.is_some()
+    }
+
+    fn kill(&mut self) {
+        if let Err(e) = self.process.kill() {
+            error!("Failed to kill singleplayer server - {e:?}");
+        }
+    }
+}
+
+fn server_executable_path() -> io::Result<PathBuf> {
+    if let Ok(path) = std::env::var(SERVER_PATH_ENV_VAR) {
+        return Ok(PathBuf::from(path));
+    }
+
+    let client_exe = std::env::current_exe()?;
+    let dir = client_exe
+        .parent()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Client executable has no parent directory"))?;
+
+    Ok(dir.join(format!("cosmos_server{}", std::env::consts::EXE_SUFFIX)))
+}
+
+fn find_free_port() -> io::Result<u16> {
+    // The OS hands out an unused port when binding to 0. This is released right away, so there is a
+    // tiny window where something else could take it, but that's not worth worrying about.
+    Ok(UdpSocket::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+fn launch_server_process() -> io::Result<SingleplayerServer> {
+    let path = server_executable_path()?;
+    let port = find_free_port()?;
+
+    info!("Launching singleplayer server ({}) on port {port}", path.display());
+
+    let mut process = Command::new(&path)
+        .args(["--port", &port.to_string(), SINGLEPLAYER_ARG])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+
+    let ready = Arc::new(AtomicBool::new(false));
+
+    if let Some(stdout) = process.stdout.take() {
+        let ready = ready.clone();
+
+        // The server's output must always be read, otherwise it will block once the pipe fills up.
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if line.trim() == SINGLEPLAYER_READY_MARKER {
+                    ready.store(true, Ordering::Relaxed);
+                }
+
+                println!("[server] {line}");
+            }
+        });
+    }
+
+    Ok(SingleplayerServer {
+        process,
+        port,
+        ready,
+        shutting_down_for: None,
+    })
+}
+
+fn launch_server(mut commands: Commands) {
+    match launch_server_process() {
+        Ok(server) => {
+            commands.insert_resource(server);
+        }
+        Err(e) => {
+            error!("Unable to launch singleplayer server - {e:?}");
+            commands.remove_resource::<StartSingleplayer>();
+            commands.insert_resource(MainMenuSubState::Disconnect);
+        }
+    }
+}
+
+fn connect_when_ready(
+    mut commands: Commands,
+    server: Res<SingleplayerServer>,
+    start: Res<StartSingleplayer>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    if server.is_shutting_down() || !server.is_ready() {
+        return;
+    }
+
+    info!("Singleplayer server is ready - connecting.");
+
+    commands.insert_resource(HostConfig {
+        host_name: "localhost".into(),
+        port: server.port(),
+        name: start.player_name.clone(),
+    });
+    commands.remove_resource::<StartSingleplayer>();
+    state.set(GameState::Connecting);
+}
+
+fn monitor_server_process(
+    mut commands: Commands,
+    mut server: ResMut<SingleplayerServer>,
+    starting: Option<Res<StartSingleplayer>>,
+    time: Res<Time>,
+) {
+    match server.process.try_wait() {
+        Ok(Some(status)) => {
+            info!("Singleplayer server exited ({status}).");
+
+            if starting.is_some() && !server.is_shutting_down() {
+                error!("Singleplayer server exited before it was ready.");
+                commands.remove_resource::<StartSingleplayer>();
+                commands.insert_resource(MainMenuSubState::Disconnect);
+            }
+
+            commands.remove_resource::<SingleplayerServer>();
+            return;
+        }
+        Ok(None) => {}
+        Err(e) => {
+            error!("Unable to check the status of the singleplayer server - {e:?}");
+        }
+    }
+
+    if let Some(shutting_down_for) = server.shutting_down_for.as_mut() {
+        *shutting_down_for += time.delta_secs();
+
+        if *shutting_down_for >= SHUTDOWN_TIMEOUT_SECS {
+            warn!("Singleplayer server did not shut down within {SHUTDOWN_TIMEOUT_SECS}s - killing it.");
+            server.kill();
+        }
+    }
+}
+
+/// The server shuts itself down once the player leaves, so all we have to do is keep an eye on it.
+fn on_left_server(mut server: ResMut<SingleplayerServer>) {
+    if !server.is_shutting_down() {
+        info!("Left singleplayer world - waiting for server to shut down.");
+        server.shutting_down_for = Some(0.0);
+    }
+}
+
+/// Cancels starting a singleplayer world, stopping the server if it was launched.
+pub fn cancel_singleplayer(mut commands: Commands, server: Option<ResMut<SingleplayerServer>>) {
+    commands.remove_resource::<StartSingleplayer>();
+
+    // Nobody has joined this server yet, so there is nothing to save.
+    if let Some(mut server) = server {
+        if !server.is_shutting_down() {
+            server.kill();
+            server.shutting_down_for = Some(0.0);
+        }
+    }
+}
+
+fn sync_pause_with_server(paused: Option<Res<Paused>>, mut nevw_set_paused: NettyEventWriter<SetSingleplayerPaused>) {
+    nevw_set_paused.send(SetSingleplayerPaused { paused: paused.is_some() });
+}
+
+/// Let the server know we're gone right away, rather than having it wait for us to time out.
+fn disconnect_on_exit(transport: Option<ResMut<NetcodeClientTransport>>) {
+    if let Some(mut transport) = transport {
+        transport.disconnect();
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            launch_server
+                .run_if(resource_exists::<StartSingleplayer>)
+                .run_if(not(resource_exists::<SingleplayerServer>)),
+            connect_when_ready
+                .run_if(resource_exists::<StartSingleplayer>)
+                .run_if(resource_exists::<SingleplayerServer>),
+        )
+            .chain()
+            .run_if(in_state(GameState::MainMenu)),
+    )
+    .add_systems(Update, monitor_server_process.run_if(resource_exists::<SingleplayerServer>))
+    .add_systems(
+        Update,
+        sync_pause_with_server
+            .run_if(resource_exists::<SingleplayerServer>)
+            .run_if(resource_added::<Paused>.or(resource_removed::<Paused>))
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    )
+    .add_systems(
+        OnEnter(GameState::MainMenu),
+        on_left_server.run_if(resource_exists::<SingleplayerServer>),
+    )
+    .add_systems(
+        Last,
+        disconnect_on_exit
+            .run_if(resource_exists::<SingleplayerServer>)
+            .run_if(on_event::<AppExit>),
+    );
+}
@@ -0,0 +1,208 @@
+//! The json format particle effects are defined in.
+//!
+//! Each file in `assets/<namespace>/particles/` is one effect, named after the file. For example,
+//! `assets/cosmos/particles/fire.json` is the effect `cosmos:fire`.
+
+use bevy::prelude::*;
+use bevy_hanabi::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+/// How often an effect creates new particles
+pub enum SpawnerDefinition {
+    /// This many particles every second
+    Rate(f32),
+    /// `count` particles at once, every `period` seconds
+    Burst {
+        /// Particles per burst
+        count: f32,
+        /// Seconds between bursts
+        period: f32,
+    },
+    /// This many particles at once, and then nothing. Emitters for these are despawned once their particles are gone.
+    Once(f32),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+/// What the particles of an effect move relative to
+pub enum SimulationSpaceDefinition {
+    #[default]
+    /// Particles move with their emitter, such as flames on a moving ship
+    Local,
+    /// Particles stay where they were created, such as a trail left behind a thruster
+    Global,
+}
+
+fn default_cull_distance() -> f32 {
+    200.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// A particle effect, as it is written in its json file
+pub struct ParticleEffectDefinition {
+    /// The most particles of this effect that can be alive at once, per emitter
+    pub capacity: u32,
+    /// How particles are created
+    pub spawner: SpawnerDefinition,
+    /// Each particle lives for a random number of seconds between these
+    pub lifetime: [f32; 2],
+    /// Particles start somewhere within a sphere of this radius around the emitter
+    #[serde(default)]
+    pub radius: f32,
+    /// Each particle starts with a random speed between these
+    #[serde(default)]
+    pub speed: [f32; 2],
+    /// The direction particles are sent in. If not given, they go out in every direction.
+    #[serde(default)]
+    pub direction: Option<[f32; 3]>,
+    /// How far particles can stray from `direction`, where 1 is roughly 45 degrees
+    #[serde(default)]
+    pub spread: f32,
+    /// Applied to every particle each second, such as flames rising
+    #[serde(default)]
+    pub acceleration: Option<[f32; 3]>,
+    /// Slows particles down over time
+    #[serde(default)]
+    pub drag: Option<f32>,
+    /// The color of the particles over their lifetime, as `[time, [r, g, b, a]]` keys with time going from 0 to 1.
+    ///
+    /// Colors above 1 glow.
+    pub colors: Vec<(f32, [f32; 4])>,
+    /// The size of the particles over their lifetime, as `[time, size]` keys with time going from 0 to 1
+    pub sizes: Vec<(f32, f32)>,
+    /// What the particles move relative to
+    #[serde(default)]
+    pub simulation_space: SimulationSpaceDefinition,
+    /// Emitters further than this from the camera aren't shown
+    #[serde(default = "default_cull_distance")]
+    pub cull_distance: f32,
+    /// How many seconds emitters of this effect are kept around for before being despawned.
+    ///
+    /// Defaults to the longest particle lifetime for [`SpawnerDefinition::Once`] effects, and forever otherwise.
+    #[serde(default)]
+    pub duration: Option<f32>,
+}
+
+impl ParticleEffectDefinition {
+    /// How long emitters of this effect live for, or `None` if they live until something else despawns them
+    pub fn emitter_duration(&self) -> Option<f32> {
+        match (self.duration, self.spawner) {
+            (Some(duration), _) => Some(duration),
+            (None, SpawnerDefinition::Once(_)) => Some(self.lifetime[1]),
+            (None, _) => None,
+        }
+    }
+
+    /// Creates the hanabi effect this describes
+    pub fn create_effect(&self, name: &str) -> EffectAsset {
+        let mut color_gradient = Gradient::new();
+        for &(t, [r, g, b, a]) in &self.colors {
+            color_gradient.add_key(t, Vec4::new(r, g, b, a));
+        }
+
+        let mut size_gradient = Gradient::new();
+        for &(t, size) in &self.sizes {
+            size_gradient.add_key(t, Vec3::splat(size));
+        }
+
+        let spawner = match self.spawner {
+            SpawnerDefinition::Rate(rate) => Spawner::rate(rate.into()),
+            SpawnerDefinition::Burst { count, period } => Spawner::burst(count.into(), period.into()),
+            SpawnerDefinition::Once(count) => Spawner::once(count.into(), true),
+        };
+
+        let writer = ExprWriter::new();
+
+        let [min_lifetime, max_lifetime] = self.lifetime;
+        let lifetime = writer.lit(min_lifetime).uniform(writer.lit(max_lifetime)).expr();
+        let init_lifetime = SetAttributeModifier::new(Attribute::LIFETIME, lifetime);
+
+        let init_pos = SetPositionSphereModifier {
+            center: writer.lit(Vec3::ZERO).expr(),
+            radius: writer.lit(self.radius).expr(),
+            dimension: ShapeDimension::Volume,
+        };
+
+        let [min_speed, max_speed] = self.speed;
+        let speed = writer.lit(min_speed).uniform(writer.lit(max_speed));
+
+        let init_vel = self.direction.map(|direction| {
+            // A random offset in [-spread, spread] on each axis, so particles go out in a rough cone
+            let offset = (writer.rand(VectorType::VEC3F) * writer.lit(2.0) - writer.lit(1.0)) * writer.lit(self.spread);
+            let velocity = (writer.lit(Vec3::from(direction).normalize_or_zero()) + offset).normalized() * speed.clone();
+
+            SetAttributeModifier::new(Attribute::VELOCITY, velocity.expr())
+        });
+        let init_vel_sphere = SetVelocitySphereModifier {
+            center: writer.lit(Vec3::ZERO).expr(),
+            speed: speed.expr(),
+        };
+
+        let update_accel = self
+            .acceleration
+            .map(|acceleration| AccelModifier::new(writer.lit(Vec3::from(acceleration)).expr()));
+        let update_drag = self.drag.map(|drag| LinearDragModifier::new(writer.lit(drag).expr()));
+
+        let simulation_space = match self.simulation_space {
+            SimulationSpaceDefinition::Local => SimulationSpace::Local,
+            SimulationSpaceDefinition::Global => SimulationSpace::Global,
+        };
+
+        let mut effect = EffectAsset::new(self.capacity, spawner, writer.finish())
+            .with_name(name)
+            .init(init_pos)
+            .init(init_lifetime);
+
+        effect = match init_vel {
+            Some(init_vel) => effect.init(init_vel),
+            None => effect.init(init_vel_sphere),
+        };
+
+        if let Some(update_accel) = update_accel {
+            effect = effect.update(update_accel);
+        }
+
+        if let Some(update_drag) = update_drag {
+            effect = effect.update(update_drag);
+        }
+
+        effect
+            .with_simulation_space(simulation_space)
+            .render(ColorOverLifetimeModifier { gradient: color_gradient })
+            .render(SizeOverLifetimeModifier {
+                gradient: size_gradient,
+                screen_space_size: false,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn particle_definitions_parse() {
+        let Ok(dir) = fs::read_dir("assets/cosmos/particles") else {
+            return;
+        };
+
+        for file in dir.flatten() {
+            let path = file.path();
+            let json = fs::read_to_string(&path).expect("Particle definition is readable");
+
+            let def = serde_json::from_str::<ParticleEffectDefinition>(&json)
+                .unwrap_or_else(|e| panic!("Invalid particle definition {}: {e:?}", path.display()));
+
+            assert!(def.lifetime[0] <= def.lifetime[1], "{} lifetime is backwards", path.display());
+            assert!(
+                !def.colors.is_empty() && !def.sizes.is_empty(),
+                "{} needs colors and sizes",
+                path.display()
+            );
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! Particle effects, such as flames, sparks, and engine trails.
+//!
+//! Effects are loaded from the json files in `assets/<namespace>/particles/` (see [`definition`]) into the
+//! [`Registry<ParticleEffectType>`]. To show one, spawn the bundle from [`ParticleEffectType::emitter`] - either on its
+//! own with a [`cosmos_core::physics::location::Location`], or as a child of the entity (or block) it comes from.
+//!
+//! Every emitter of the same effect shares one effect asset, so hanabi can batch them together on the GPU. To keep the
+//! cost down when there are a lot of them, emitters far from the camera are hidden (which stops them being simulated),
+//! and only the [`MAX_VISIBLE_EMITTERS`] closest emitters are ever shown.
+
+use std::fs;
+
+use bevy::prelude::*;
+use bevy_hanabi::prelude::*;
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    registry::{self, identifiable::Identifiable, Registry},
+    state::GameState,
+};
+
+use crate::rendering::MainCamera;
+
+pub mod definition;
+
+use definition::ParticleEffectDefinition;
+
+/// The most particle emitters that will be shown at once. Any past this are hidden, furthest first.
+pub const MAX_VISIBLE_EMITTERS: usize = 256;
+
+#[derive(Debug, Clone)]
+/// A particle effect that can be shown in the world
+pub struct ParticleEffectType {
+    id: u16,
+    unlocalized_name: String,
+    handle: Handle<EffectAsset>,
+    cull_distance: f32,
+    duration: Option<f32>,
+}
+
+impl ParticleEffectType {
+    /// Emitters further than this from the camera aren't shown
+    pub fn cull_distance(&self) -> f32 {
+        self.cull_distance
+    }
+
+    /// The bundle for an emitter of this effect at this transform.
+    ///
+    /// Effects that only emit once despawn themselves after their particles are gone. Others are shown until they're despawned.
+    pub fn emitter(&self, transform: Transform) -> impl Bundle {
+        (
+            Name::new(format!("Particle emitter ({})", self.unlocalized_name)),
+            ParticleEmitter {
+                effect: self.id,
+                time_alive: 0.0,
+                duration: self.duration,
+            },
+            ParticleEffectBundle {
+                effect: ParticleEffect::new(self.handle.clone_weak()),
+                transform,
+                ..Default::default()
+            },
+        )
+    }
+}
+
+impl Identifiable for ParticleEffectType {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+}
+
+#[derive(Component, Debug)]
+/// Something that is showing a [`ParticleEffectType`]. Create these with [`ParticleEffectType::emitter`].
+pub struct ParticleEmitter {
+    effect: u16,
+    time_alive: f32,
+    duration: Option<f32>,
+}
+
+impl ParticleEmitter {
+    /// The id of the [`ParticleEffectType`] this is showing
+    pub fn effect_id(&self) -> u16 {
+        self.effect
+    }
+}
+
+const PARTICLES_DIR: &str = "particles";
+
+fn load_particle_effects(mut effects: ResMut<Assets<EffectAsset>>, mut registry: ResMut<Registry<ParticleEffectType>>) {
+    let Ok(namespaces) = fs::read_dir("assets/") else {
+        error!("Missing assets directory - unable to load particle effects!");
+        return;
+    };
+
+    for namespace in namespaces.flatten() {
+        let Ok(namespace) = namespace.file_name().into_string() else {
+            continue;
+        };
+
+        let Ok(files) = fs::read_dir(format!("assets/{namespace}/{PARTICLES_DIR}")) else {
+            continue;
+        };
+
+        for file in files.flatten() {
+            let path = file.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+
+            let Ok(json) = fs::read_to_string(&path) else {
+                error!("Unable to read particle effect {}", path.display());
+                continue;
+            };
+
+            let definition = match serde_json::from_str::<ParticleEffectDefinition>(&json) {
+                Ok(definition) => definition,
+                Err(e) => {
+                    error!("Invalid particle effect {}\n{e:?}", path.display());
+                    continue;
+                }
+            };
+
+            let unlocalized_name = format!("{namespace}:{name}");
+
+            registry.register(ParticleEffectType {
+                id: 0,
+                handle: effects.add(definition.create_effect(&unlocalized_name)),
+                unlocalized_name,
+                cull_distance: definition.cull_distance,
+                duration: definition.emitter_duration(),
+            });
+        }
+    }
+}
+
+/// Hanabi's auto start doesn't work on the very first particle effect created, so this starts them manually
+fn start_emitters(mut q_spawner: Query<&mut EffectInitializers, Added<ParticleEmitter>>) {
+    for mut effect_spawner in q_spawner.iter_mut() {
+        effect_spawner.reset();
+        effect_spawner.set_active(true);
+    }
+}
+
+fn despawn_finished_emitters(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_emitters: Query<(Entity, &mut ParticleEmitter), Without<NeedsDespawned>>,
+) {
+    for (ent, mut emitter) in q_emitters.iter_mut() {
+        let Some(duration) = emitter.duration else {
+            continue;
+        };
+
+        emitter.time_alive += time.delta_secs();
+        if emitter.time_alive >= duration {
+            commands.entity(ent).insert(NeedsDespawned);
+        }
+    }
+}
+
+fn cull_emitters(
+    effect_types: Res<Registry<ParticleEffectType>>,
+    q_camera: Query<&GlobalTransform, With<MainCamera>>,
+    mut q_emitters: Query<(Entity, &GlobalTransform, &ParticleEmitter, &mut Visibility)>,
+    mut in_range: Local<Vec<(Entity, f32)>>,
+) {
+    let Ok(camera) = q_camera.get_single() else {
+        return;
+    };
+
+    let camera = camera.translation();
+
+    in_range.clear();
+    for (ent, g_trans, emitter, _) in q_emitters.iter() {
+        let cull_distance = effect_types.from_numeric_id(emitter.effect).cull_distance();
+        let dist_sqrd = g_trans.translation().distance_squared(camera);
+
+        if dist_sqrd <= cull_distance * cull_distance {
+            in_range.push((ent, dist_sqrd));
+        }
+    }
+
+    if in_range.len() > MAX_VISIBLE_EMITTERS {
+        in_range.select_nth_unstable_by(MAX_VISIBLE_EMITTERS, |a, b| a.1.total_cmp(&b.1));
+        in_range.truncate(MAX_VISIBLE_EMITTERS);
+    }
+    in_range.sort_unstable_by_key(|(ent, _)| *ent);
+
+    for (ent, _, _, mut visibility) in q_emitters.iter_mut() {
+        let shown = in_range.binary_search_by_key(&ent, |(ent, _)| *ent).is_ok();
+        let desired = if shown { Visibility::Inherited } else { Visibility::Hidden };
+
+        // Avoids triggering change detection every frame
+        visibility.set_if_neq(desired);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    registry::create_registry::<ParticleEffectType>(app, "cosmos:particle_effects");
+
+    app.add_systems(OnEnter(GameState::Loading), load_particle_effects).add_systems(
+        Update,
+        (start_emitters, despawn_finished_emitters, cull_emitters)
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,112 @@
+//! Client-side party logic - keeps track of the player's party and how its members are doing, and shows the waypoints
+//! its leader has shared
+
+use bevy::{color::palettes::css, prelude::*};
+use bevy_rapier3d::prelude::Velocity;
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    netty::{client::LocalPlayer, sync::events::client_event::NettyEventReceived, system_sets::NetworkingSystemsSet},
+    party::{Party, PartyMemberStatus, PartyStatusEvent, PartyTeleportEvent, PartyUpdatedEvent},
+    physics::location::Location,
+    state::GameState,
+};
+
+use crate::{ui::ship_flight::indicators::IndicatorSettings, universe::map::MapMarker};
+
+pub mod ping;
+
+#[derive(Resource, Debug, Default)]
+/// The party the local player is in, if they're in one
+pub struct CurrentParty(pub Option<Party>);
+
+#[derive(Resource, Debug, Default)]
+/// How each member of the local player's party was doing when the server last said
+pub struct PartyStatus(pub Vec<PartyMemberStatus>);
+
+#[derive(Component, Debug)]
+/// A waypoint the party leader has shared. The entity this is on has the waypoint's [`cosmos_core::physics::location::Location`].
+pub struct SharedWaypointMarker {
+    /// The [`cosmos_core::party::SharedWaypoint::id`] of this waypoint
+    pub id: u32,
+}
+
+fn on_party_updated(
+    mut commands: Commands,
+    mut nevr_party: EventReader<NettyEventReceived<PartyUpdatedEvent>>,
+    mut current_party: ResMut<CurrentParty>,
+    mut party_status: ResMut<PartyStatus>,
+    q_markers: Query<Entity, With<SharedWaypointMarker>>,
+) {
+    let Some(ev) = nevr_party.read().last() else {
+        return;
+    };
+
+    current_party.0 = ev.party.clone();
+
+    if ev.party.is_none() {
+        party_status.0.clear();
+    }
+
+    for ent in q_markers.iter() {
+        commands.entity(ent).insert(NeedsDespawned);
+    }
+
+    let Some(party) = &ev.party else {
+        return;
+    };
+
+    for waypoint in party.waypoints.iter() {
+        commands.spawn((
+            Name::new("Shared Waypoint"),
+            SharedWaypointMarker { id: waypoint.id },
+            IndicatorSettings {
+                color: css::LIME.into(),
+                max_distance: f32::INFINITY,
+                offset: Vec3::ZERO,
+            },
+            MapMarker { color: css::LIME.into() },
+            waypoint.location,
+        ));
+    }
+}
+
+fn on_party_status(
+    mut nevr_status: EventReader<NettyEventReceived<PartyStatusEvent>>,
+    current_party: Res<CurrentParty>,
+    mut party_status: ResMut<PartyStatus>,
+) {
+    let Some(ev) = nevr_status.read().last() else {
+        return;
+    };
+
+    // A status could arrive just after leaving the party
+    if current_party.0.is_some() {
+        party_status.0 = ev.members.clone();
+    }
+}
+
+fn on_party_teleport(
+    mut nevr_teleport: EventReader<NettyEventReceived<PartyTeleportEvent>>,
+    mut q_local_player: Query<(&mut Location, &mut Velocity), With<LocalPlayer>>,
+) {
+    for ev in nevr_teleport.read() {
+        let Ok((mut location, mut velocity)) = q_local_player.get_single_mut() else {
+            continue;
+        };
+
+        *location = ev.0;
+        velocity.linvel = Vec3::ZERO;
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    ping::register(app);
+
+    app.init_resource::<CurrentParty>().init_resource::<PartyStatus>().add_systems(
+        Update,
+        (on_party_updated, on_party_status, on_party_teleport)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
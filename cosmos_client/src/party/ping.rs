@@ -0,0 +1,144 @@
+//! Pinging locations for the rest of your party, and showing their pings
+
+use bevy::{color::palettes::css, prelude::*};
+use bevy_kira_audio::Audio;
+use bevy_rapier3d::{plugin::ReadRapierContext, prelude::QueryFilter};
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    netty::{
+        client::LocalPlayer,
+        sync::events::client_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    party::{PartyPingEvent, SendPingEvent, PING_DURATION},
+    physics::location::Location,
+    registry::Registry,
+    state::GameState,
+    structure::ship::pilot::Pilot,
+};
+
+use crate::{
+    audio::{sound_effects::SoundEffect, CosmosAudioEmitter, DespawnOnNoEmissions},
+    camera::camera_mode::PlayerEye,
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    ui::{components::show_cursor::no_open_menus, ship_flight::indicators::IndicatorSettings},
+    universe::map::MapMarker,
+};
+
+use super::CurrentParty;
+
+/// The furthest away something can be pinged. If nothing is hit, the point this far in front of the camera is pinged.
+const MAX_PING_DISTANCE: f32 = 1000.0;
+
+#[derive(Component, Debug)]
+/// A location a party member pinged, which disappears after [`PING_DURATION`]
+pub struct Ping {
+    /// The name of the player who pinged this
+    pub player: String,
+    /// When this ping was received, in seconds since the game started
+    pub received_at: f32,
+}
+
+/// Pings wherever the player is looking
+fn ping_looking_at(
+    inputs: InputChecker,
+    current_party: Res<CurrentParty>,
+    q_player: Query<(Entity, &Location, &GlobalTransform, Option<&Pilot>), With<LocalPlayer>>,
+    player_eye: PlayerEye,
+    rapier_context_access: ReadRapierContext,
+    mut nevw_ping: NettyEventWriter<SendPingEvent>,
+) {
+    if current_party.0.is_none() || !inputs.check_just_pressed(CosmosInputs::PingLocation) {
+        return;
+    }
+
+    let (Ok((player_ent, player_loc, player_g_trans, pilot)), Some(cam_trans)) = (q_player.get_single(), player_eye.global_transform())
+    else {
+        return;
+    };
+
+    // The ship being piloted would otherwise get in the way
+    let exclude = pilot.map(|x| x.entity).unwrap_or(player_ent);
+
+    let origin = cam_trans.translation();
+    let direction = cam_trans.forward();
+
+    let distance = rapier_context_access
+        .single()
+        .cast_ray(
+            origin,
+            direction.into(),
+            MAX_PING_DISTANCE,
+            true,
+            QueryFilter::new().exclude_rigid_body(exclude),
+        )
+        .map(|(_, toi)| toi)
+        .unwrap_or(MAX_PING_DISTANCE);
+
+    let point = origin + direction * distance;
+
+    nevw_ping.send(SendPingEvent {
+        location: *player_loc + (point - player_g_trans.translation()),
+    });
+}
+
+fn on_party_ping(
+    mut commands: Commands,
+    mut nevr_ping: EventReader<NettyEventReceived<PartyPingEvent>>,
+    q_player: Query<Entity, With<LocalPlayer>>,
+    sound_effects: Res<Registry<SoundEffect>>,
+    audio: Res<Audio>,
+    time: Res<Time>,
+) {
+    for ev in nevr_ping.read() {
+        commands.spawn((
+            Name::new(format!("Ping ({})", ev.player)),
+            Ping {
+                player: ev.player.clone(),
+                received_at: time.elapsed_secs(),
+            },
+            IndicatorSettings {
+                color: css::YELLOW.into(),
+                max_distance: f32::INFINITY,
+                offset: Vec3::ZERO,
+            },
+            MapMarker { color: css::YELLOW.into() },
+            ev.location,
+        ));
+
+        let (Ok(player_ent), Some(emission)) = (
+            q_player.get_single(),
+            sound_effects.from_id("cosmos:ping").and_then(|x| x.play(&audio)),
+        ) else {
+            continue;
+        };
+
+        // Played on the player so it can always be heard, no matter how far away the ping is
+        commands.entity(player_ent).with_children(|p| {
+            p.spawn((
+                Name::new("Ping sound"),
+                DespawnOnNoEmissions,
+                Transform::default(),
+                CosmosAudioEmitter::with_emissions(vec![emission]),
+            ));
+        });
+    }
+}
+
+fn remove_old_pings(mut commands: Commands, q_pings: Query<(Entity, &Ping)>, time: Res<Time>) {
+    for (ent, ping) in q_pings.iter() {
+        if time.elapsed_secs() - ping.received_at >= PING_DURATION {
+            commands.entity(ent).insert(NeedsDespawned);
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (ping_looking_at.run_if(no_open_menus), on_party_ping, remove_old_pings)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,112 @@
+//! How lasers look, based on how strong they are.
+//!
+//! Tiers are defined in `assets/<namespace>/lasers/tiers.json`. A laser uses the strongest tier it meets the minimum
+//! strength of. Lasers fired through colored glass keep that color, but still get their tier's size and glow.
+
+use std::fs;
+
+use bevy::prelude::*;
+use cosmos_core::state::GameState;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// What lasers of at least a certain strength look like
+pub struct LaserTier {
+    /// Lasers at least this strong use this tier
+    pub min_strength: f32,
+    /// The color of lasers without a color of their own, as `[r, g, b]`
+    pub color: [f32; 3],
+    /// How bright the laser is. Anything above 1 glows.
+    pub glow: f32,
+    /// How thick the laser is
+    pub width: f32,
+    /// How long the laser is
+    pub length: f32,
+}
+
+impl Default for LaserTier {
+    fn default() -> Self {
+        Self {
+            min_strength: 0.0,
+            color: [1.0, 1.0, 1.0],
+            glow: 1.0,
+            width: 0.1,
+            length: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LaserTiersFile {
+    tiers: Vec<LaserTier>,
+}
+
+#[derive(Resource, Debug, Default)]
+/// Every [`LaserTier`], from weakest to strongest
+pub struct LaserTiers(Vec<LaserTier>);
+
+impl LaserTiers {
+    /// The tier a laser of this strength uses
+    pub fn tier_for(&self, strength: f32) -> LaserTier {
+        self.0
+            .iter()
+            .rev()
+            .find(|tier| strength >= tier.min_strength)
+            .or(self.0.first())
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+const TIERS_FILE: &str = "lasers/tiers.json";
+
+fn load_laser_tiers(mut commands: Commands) {
+    let mut tiers = vec![];
+
+    if let Ok(namespaces) = fs::read_dir("assets/") {
+        for namespace in namespaces.flatten() {
+            let Ok(namespace) = namespace.file_name().into_string() else {
+                continue;
+            };
+
+            let path = format!("assets/{namespace}/{TIERS_FILE}");
+            let Ok(json) = fs::read_to_string(&path) else {
+                continue;
+            };
+
+            match serde_json::from_str::<LaserTiersFile>(&json) {
+                Ok(file) => tiers.extend(file.tiers),
+                Err(e) => error!("Invalid laser tiers {path}\n{e:?}"),
+            }
+        }
+    }
+
+    tiers.sort_by(|a, b| a.min_strength.total_cmp(&b.min_strength));
+
+    commands.insert_resource(LaserTiers(tiers));
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<LaserTiers>()
+        .add_systems(OnEnter(GameState::Loading), load_laser_tiers);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strongest_matching_tier_is_used() {
+        let tier = |min_strength| LaserTier {
+            min_strength,
+            ..Default::default()
+        };
+        let tiers = LaserTiers(vec![tier(10.0), tier(50.0), tier(150.0)]);
+
+        assert_eq!(tiers.tier_for(60.0).min_strength, 50.0);
+        assert_eq!(tiers.tier_for(150.0).min_strength, 150.0);
+        // Weaker than every tier still gets the weakest one
+        assert_eq!(tiers.tier_for(1.0).min_strength, 10.0);
+        assert_eq!(LaserTiers::default().tier_for(1.0), LaserTier::default());
+    }
+}
@@ -1,32 +1,103 @@
-//! Handles the creation of lasers
+//! Handles the creation of lasers, along with their muzzle flashes and the sparks they make when they hit something
 
-use bevy::{prelude::*, utils::HashMap};
+use bevy::{
+    pbr::{NotShadowCaster, NotShadowReceiver},
+    prelude::*,
+    utils::HashMap,
+};
 use bevy_rapier3d::{plugin::RapierContextEntityLink, prelude::RapierContextSimulation};
 use bevy_renet2::renet2::*;
 use cosmos_core::{
     netty::{
-        cosmos_encoder, server_laser_cannon_system_messages::ServerStructureSystemMessages, sync::mapping::NetworkMapping,
-        system_sets::NetworkingSystemsSet, NettyChannelServer,
+        network_stats::NettyClientMessages, server_laser_cannon_system_messages::ServerStructureSystemMessages,
+        sync::mapping::NetworkMapping, system_sets::NetworkingSystemsSet, NettyChannelServer,
+    },
+    physics::location::{CosmosBundleSet, Location},
+    projectiles::{
+        causer::Causer,
+        laser::{Laser, LaserCollideEvent, LaserSystemSet},
     },
-    physics::location::CosmosBundleSet,
-    projectiles::{causer::Causer, laser::Laser},
+    registry::Registry,
     state::GameState,
 };
 
-use crate::structure::{
-    shields::ShieldRender,
-    systems::{laser_cannon_system::LaserCannonSystemFiredEvent, missile_launcher_system::MissileLauncherSystemFiredEvent},
+use crate::{
+    particles::ParticleEffectType,
+    structure::{
+        shields::ShieldRender,
+        systems::{laser_cannon_system::LaserCannonSystemFiredEvent, missile_launcher_system::MissileLauncherSystemFiredEvent},
+    },
 };
 
+use super::laser_tiers::LaserTiers;
+
+const MUZZLE_FLASH_EFFECT: &str = "cosmos:laser_muzzle_flash";
+const IMPACT_EFFECT: &str = "cosmos:laser_impact";
+
 #[derive(Resource)]
 struct LaserMesh(Handle<Mesh>);
 
 #[derive(Resource, Default)]
-struct LaserMaterials(HashMap<u32, Handle<StandardMaterial>>);
+/// Keyed by the laser's color and glow
+struct LaserMaterials(HashMap<(u32, u32), Handle<StandardMaterial>>);
 
 fn create_laser_mesh(mut meshes: ResMut<Assets<Mesh>>, mut commands: Commands) {
     commands.init_resource::<LaserMaterials>();
-    commands.insert_resource(LaserMesh(meshes.add(Mesh::from(Cuboid::new(0.1, 0.1, 1.0)))));
+    // Scaled to each laser's tier
+    commands.insert_resource(LaserMesh(meshes.add(Mesh::from(Cuboid::new(1.0, 1.0, 1.0)))));
+}
+
+/// Shows a muzzle flash where a laser was fired from.
+///
+/// The flash is attached to whatever fired the laser (if we know what that is), so it doesn't get left behind by fast ships.
+fn spawn_muzzle_flash(
+    commands: &mut Commands,
+    effect: &ParticleEffectType,
+    location: Location,
+    laser_velocity: Vec3,
+    firer: Option<(Entity, &Location, &GlobalTransform)>,
+) {
+    let rotation = Transform::IDENTITY.looking_to(laser_velocity, Vec3::Y).rotation;
+
+    match firer {
+        Some((firer_ent, firer_loc, firer_g_trans)) => {
+            let inv_rot = firer_g_trans.rotation().inverse();
+            let transform =
+                Transform::from_translation(inv_rot * firer_loc.relative_coords_to(&location)).with_rotation(inv_rot * rotation);
+
+            commands.entity(firer_ent).with_children(|p| {
+                p.spawn(effect.emitter(transform));
+            });
+        }
+        None => {
+            commands.spawn((location, effect.emitter(Transform::from_rotation(rotation))));
+        }
+    }
+}
+
+fn spawn_laser_impacts(
+    mut commands: Commands,
+    mut evr_laser_collide: EventReader<LaserCollideEvent>,
+    particles: Res<Registry<ParticleEffectType>>,
+    q_exists: Query<(), With<GlobalTransform>>,
+) {
+    let Some(impact) = particles.from_id(IMPACT_EFFECT) else {
+        evr_laser_collide.clear();
+        return;
+    };
+
+    for ev in evr_laser_collide.read() {
+        if !q_exists.contains(ev.entity_hit()) {
+            continue;
+        }
+
+        // Sparks fly back out towards where the laser came from
+        let transform = Transform::from_translation(ev.local_position_hit()).looking_to(-ev.local_direction(), Vec3::Y);
+
+        commands.entity(ev.entity_hit()).with_children(|p| {
+            p.spawn(impact.emitter(transform));
+        });
+    }
 }
 
 fn lasers_netty(
@@ -41,9 +112,14 @@ fn lasers_netty(
     mut q_shield_render: Query<&mut ShieldRender>,
     q_default_world: Query<Entity, With<RapierContextSimulation>>,
     mut laser_materials: ResMut<LaserMaterials>,
+    laser_tiers: Res<LaserTiers>,
+    particles: Res<Registry<ParticleEffectType>>,
+    q_firer: Query<(&Location, &GlobalTransform)>,
 ) {
-    while let Some(message) = client.receive_message(NettyChannelServer::StructureSystems) {
-        let msg: ServerStructureSystemMessages = cosmos_encoder::deserialize(&message).unwrap();
+    let muzzle_flash = particles.from_id(MUZZLE_FLASH_EFFECT);
+
+    while let Some(message) = client.receive_netty_message::<ServerStructureSystemMessages>(NettyChannelServer::StructureSystems) {
+        let msg: ServerStructureSystemMessages = message.unwrap();
 
         match msg {
             ServerStructureSystemMessages::CreateLaser {
@@ -55,10 +131,10 @@ fn lasers_netty(
                 mut no_hit,
                 causer,
             } => {
-                if let Some(server_entity) = no_hit {
-                    if let Some(client_entity) = network_mapping.client_from_server(&server_entity) {
-                        no_hit = Some(client_entity);
-                    }
+                // The laser can't hit whatever fired it, so this is also what the muzzle flash is attached to
+                let firer = no_hit.and_then(|server_entity| network_mapping.client_from_server(&server_entity));
+                if let Some(client_entity) = firer {
+                    no_hit = Some(client_entity);
                 }
 
                 let causer = causer.map(|c| network_mapping.client_from_server(&c.0)).and_then(|e| e.map(Causer));
@@ -73,15 +149,32 @@ fn lasers_netty(
 
                     u32::from_be_bytes([r, g, b, a])
                 }
-                let color = color.unwrap_or(Color::WHITE);
-
-                let material = laser_materials.0.entry(color_hash(color.into())).or_insert_with(|| {
-                    materials.add(StandardMaterial {
-                        base_color: color,
-                        unlit: true,
-                        ..Default::default()
-                    })
-                });
+                let tier = laser_tiers.tier_for(strength);
+                let [r, g, b] = tier.color;
+                let color = color.unwrap_or(Color::srgb(r, g, b));
+
+                let material = laser_materials
+                    .0
+                    .entry((color_hash(color.into()), tier.glow.to_bits()))
+                    .or_insert_with(|| {
+                        // Colors brighter than 1 are picked up by bloom, making the laser glow
+                        let linear = color.to_linear();
+                        materials.add(StandardMaterial {
+                            base_color: Color::LinearRgba(LinearRgba::new(
+                                linear.red * tier.glow,
+                                linear.green * tier.glow,
+                                linear.blue * tier.glow,
+                                linear.alpha,
+                            )),
+                            unlit: true,
+                            ..Default::default()
+                        })
+                    });
+
+                if let Some(muzzle_flash) = muzzle_flash {
+                    let firer = firer.and_then(|ent| q_firer.get(ent).ok().map(|(loc, g_trans)| (ent, loc, g_trans)));
+                    spawn_muzzle_flash(&mut commands, muzzle_flash, location, laser_velocity, firer);
+                }
 
                 Laser::spawn(
                     location,
@@ -94,11 +187,17 @@ fn lasers_netty(
                     &mut commands,
                     causer,
                 )
-                .insert((
-                    Visibility::default(),
-                    Mesh3d(laser_mesh.0.clone_weak()),
-                    MeshMaterial3d(material.clone_weak()),
-                ));
+                .insert(Visibility::default())
+                .with_children(|p| {
+                    p.spawn((
+                        Name::new("Laser tracer"),
+                        Mesh3d(laser_mesh.0.clone_weak()),
+                        MeshMaterial3d(material.clone_weak()),
+                        Transform::from_scale(Vec3::new(tier.width, tier.width, tier.length)),
+                        NotShadowCaster,
+                        NotShadowReceiver,
+                    ));
+                });
             }
             ServerStructureSystemMessages::LaserCannonSystemFired { ship_entity } => {
                 let Some(ship_entity) = network_mapping.client_from_server(&ship_entity) else {
@@ -133,12 +232,20 @@ fn lasers_netty(
 }
 
 pub(super) fn register(app: &mut App) {
-    app.add_systems(OnEnter(GameState::Loading), create_laser_mesh).add_systems(
-        Update,
-        lasers_netty
-            .in_set(NetworkingSystemsSet::ReceiveMessages)
-            .ambiguous_with(NetworkingSystemsSet::ReceiveMessages)
-            .before(CosmosBundleSet::HandleCosmosBundles)
-            .run_if(in_state(GameState::Playing)),
-    );
+    app.add_systems(OnEnter(GameState::Loading), create_laser_mesh)
+        .add_systems(
+            Update,
+            lasers_netty
+                .in_set(NetworkingSystemsSet::ReceiveMessages)
+                .ambiguous_with(NetworkingSystemsSet::ReceiveMessages)
+                .before(CosmosBundleSet::HandleCosmosBundles)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            Update,
+            spawn_laser_impacts
+                .after(LaserSystemSet::SendHitEvents)
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        );
 }
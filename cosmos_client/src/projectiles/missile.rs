@@ -1,20 +1,20 @@
 use std::time::Duration;
 
-use bevy::{asset::LoadState, color::palettes::css, prelude::*, utils::HashMap};
+use bevy::{color::palettes::css, prelude::*, utils::HashMap};
 use bevy_hanabi::prelude::*;
 
-use bevy_kira_audio::{Audio, AudioControl, AudioInstance, AudioSource};
+use bevy_kira_audio::Audio;
 use cosmos_core::{
     ecs::NeedsDespawned,
     netty::{client::LocalPlayer, sync::ComponentSyncingSet},
     physics::location::Location,
     projectiles::missile::{Explosion, ExplosionSystemSet, Missile},
+    registry::Registry,
     state::GameState,
 };
 
 use crate::{
-    asset::asset_loader::load_assets,
-    audio::{AudioEmission, CosmosAudioEmitter, DespawnOnNoEmissions},
+    audio::{sound_effects::SoundEffect, CosmosAudioEmitter, DespawnOnNoEmissions},
     structure::ship::PlayerParentChangingSet,
 };
 
@@ -91,7 +91,7 @@ fn respond_to_explosion(
     // q_explosions needs &Transform not &GlobalTransform since &GlobalTransform won't be setup yet.
     q_explosions: Query<(Entity, &Location, &Transform, &Explosion), Added<Explosion>>,
     audio: Res<Audio>,
-    audio_sources: Res<ExplosionAudio>,
+    sound_effects: Res<Registry<SoundEffect>>,
     mut particles: ResMut<ParticleEffectsForColor>,
     mut effects: ResMut<Assets<EffectAsset>>,
 ) {
@@ -128,23 +128,15 @@ fn respond_to_explosion(
             },
         ));
 
-        let audio_handle = audio_sources.0[rand::random::<usize>() % audio_sources.0.len()].clone_weak();
-
-        let playing_sound: Handle<AudioInstance> = audio.play(audio_handle.clone()).with_volume(0.0).handle();
-
-        commands.spawn((
-            Name::new("Explosion sound"),
-            DespawnOnNoEmissions,
-            *explosion_loc,
-            CosmosAudioEmitter::with_emissions(vec![AudioEmission {
-                instance: playing_sound,
-                handle: audio_handle,
-                max_distance: 200.0,
-                peak_volume: 1.0,
-                ..Default::default()
-            }]),
-            Transform::from_translation(transform.translation),
-        ));
+        if let Some(emission) = sound_effects.from_id("cosmos:explosion").and_then(|sound| sound.play(&audio)) {
+            commands.spawn((
+                Name::new("Explosion sound"),
+                DespawnOnNoEmissions,
+                *explosion_loc,
+                CosmosAudioEmitter::with_emissions(vec![emission]),
+                Transform::from_translation(transform.translation),
+            ));
+        }
     }
 }
 
@@ -227,32 +219,7 @@ fn create_particle_fx(color: Option<Color>, effects: &mut Assets<EffectAsset>) -
     effects.add(effect)
 }
 
-#[derive(Resource)]
-struct ExplosionAudio(Vec<Handle<AudioSource>>);
-
 pub(super) fn register(app: &mut App) {
-    load_assets::<AudioSource, ExplosionAudio>(
-        app,
-        GameState::Loading,
-        vec![
-            "cosmos/sounds/sfx/explosion-1.ogg",
-            "cosmos/sounds/sfx/explosion-2.ogg",
-            "cosmos/sounds/sfx/explosion-3.ogg",
-            "cosmos/sounds/sfx/explosion-4.ogg",
-        ],
-        |mut commands, sounds| {
-            let sounds = sounds
-                .into_iter()
-                .flat_map(|(item, state)| match state {
-                    LoadState::Loaded => Some(item),
-                    _ => None,
-                })
-                .collect::<Vec<Handle<AudioSource>>>();
-
-            commands.insert_resource(ExplosionAudio(sounds));
-        },
-    );
-
     app.add_systems(
         Update,
         (start_explosion_particle_system, respond_to_explosion)
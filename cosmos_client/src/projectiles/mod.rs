@@ -2,10 +2,12 @@
 
 use bevy::prelude::App;
 
+pub mod laser_tiers;
 mod lasers;
 mod missile;
 
 pub(super) fn register(app: &mut App) {
+    laser_tiers::register(app);
     lasers::register(app);
     missile::register(app);
 }
@@ -19,7 +19,7 @@ use bevy::{
     utils::HashMap,
 };
 use cosmos_core::{
-    block::{block_face::ALL_BLOCK_FACES, Block},
+    block::{block_face::ALL_BLOCK_FACES, specific_blocks::airlock::AIRLOCK_CONTROLLER_BLOCK, Block},
     logic::BlockLogicData,
     registry::{identifiable::Identifiable, many_to_one::ManyToOneRegistry, Registry},
     state::GameState,
@@ -45,8 +45,11 @@ use crate::{
 
 use super::RenderingModesSet;
 
+/// Blocks that light up while their logic data is on
+const LIT_BY_LOGIC_BLOCKS: [&str; 2] = ["cosmos:logic_indicator", AIRLOCK_CONTROLLER_BLOCK];
+
 fn set_custom_rendering_for_logic_indicator(mut rendering_modes: ResMut<BlockRenderingModes>, blocks: Res<Registry<Block>>) {
-    if let Some(logic_indicator) = blocks.from_id("cosmos:logic_indicator") {
+    for logic_indicator in LIT_BY_LOGIC_BLOCKS.iter().filter_map(|name| blocks.from_id(name)) {
         rendering_modes.set_rendering_mode(logic_indicator, RenderingMode::Custom);
     }
 }
@@ -76,11 +79,12 @@ fn on_render_logic_indicator(
 
             commands.entity(ev.mesh_entity_parent).remove::<LogicIndicatorRenders>();
         }
-        let logic_indicator_block = blocks
-            .from_id("cosmos:logic_indicator")
-            .expect("Logic Indicator block should exist.");
-        let logic_indicator_id = logic_indicator_block.id();
-        if !ev.block_ids.contains(&logic_indicator_id) {
+        let lit_blocks = LIT_BY_LOGIC_BLOCKS
+            .iter()
+            .filter_map(|name| blocks.from_id(name))
+            .filter(|block| ev.block_ids.contains(&block.id()))
+            .collect::<Vec<_>>();
+        if lit_blocks.is_empty() {
             continue;
         }
 
@@ -91,9 +95,11 @@ fn on_render_logic_indicator(
         let mut material_meshes: HashMap<(MaterialType, u16, u32), CosmosMeshBuilder> = HashMap::default();
 
         for block in structure.block_iter_for_chunk(ev.chunk_coordinate, true) {
-            if structure.block_id_at(block) != logic_indicator_id {
+            let block_id = structure.block_id_at(block);
+            let Some(logic_indicator_block) = lit_blocks.iter().copied().find(|b| b.id() == block_id) else {
                 continue;
-            }
+            };
+            let logic_indicator_id = logic_indicator_block.id();
 
             let Some(material_definition) = materials.get_value(logic_indicator_block) else {
                 continue;
@@ -4,12 +4,9 @@
 //!
 //! I'm sorry. I'll fix it when I feel inspired.
 
-use crate::{
-    asset::{
-        asset_loading::BlockTextureIndex,
-        materials::{AddMaterialEvent, BlockMaterialMapping, MaterialDefinition, MaterialType, MaterialsSystemSet},
-    },
-    block::lighting::BlockLighting,
+use crate::asset::{
+    asset_loading::BlockTextureIndex,
+    materials::{AddMaterialEvent, BlockMaterialMapping, MaterialDefinition, MaterialType, MaterialsSystemSet},
 };
 use bevy::{
     prelude::*,
@@ -68,7 +65,6 @@ fn recursively_process_lod(
     meshes_registry: &BlockMeshRegistry,
     block_textures: &Registry<BlockTextureIndex>,
     materials_registry: &Registry<MaterialDefinition>,
-    lighting: &Registry<BlockLighting>,
     rendering_modes: &BlockRenderingModes,
 ) {
     match current_lod {
@@ -110,7 +106,6 @@ fn recursively_process_lod(
                     meshes_registry,
                     block_textures,
                     materials_registry,
-                    lighting,
                     rendering_modes,
                 );
             });
@@ -132,7 +127,7 @@ fn recursively_process_lod(
             renderer.render(
                 materials,
                 materials_registry,
-                lighting,
+                None,
                 lod_chunk.as_ref(),
                 blocks,
                 meshes_registry,
@@ -553,7 +548,6 @@ fn trigger_lod_render(
     materials_registry: Res<ReadOnlyRegistry<MaterialDefinition>>,
     meshes_registry: Res<ReadOnlyBlockMeshRegistry>,
     block_textures: Res<ReadOnlyRegistry<BlockTextureIndex>>,
-    lighting: Res<ReadOnlyRegistry<BlockLighting>>,
     block_rendering_mode: Res<BlockRenderingModes>,
     lods_query: Query<(&LodComponent, &Structure)>,
     mut rendering_lods: ResMut<RenderingLods>,
@@ -583,7 +577,6 @@ fn trigger_lod_render(
         let materials = materials.clone();
         let meshes_registry = meshes_registry.clone();
         let materials_registry = materials_registry.clone();
-        let lighting = lighting.clone();
         // TODO: This one is expensive - make it less expensive.
         let block_rendering_modes = block_rendering_mode.clone();
 
@@ -611,7 +604,6 @@ fn trigger_lod_render(
             let meshes_registry: std::sync::RwLockReadGuard<'_, ManyToOneRegistry<Block, crate::rendering::BlockMeshInformation>> =
                 meshes_registry.registry();
             let materials_registry = materials_registry.registry();
-            let lighting = lighting.registry();
 
             recursively_process_lod(
                 &lod,
@@ -626,7 +618,6 @@ fn trigger_lod_render(
                 &meshes_registry,
                 &block_textures,
                 &materials_registry,
-                &lighting,
                 &block_rendering_modes,
             );
 
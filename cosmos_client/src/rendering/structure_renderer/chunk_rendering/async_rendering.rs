@@ -1,20 +1,18 @@
 use crate::asset::asset_loading::BlockTextureIndex;
 use crate::asset::materials::{AddMaterialEvent, BlockMaterialMapping, MaterialDefinition, MaterialType, RemoveAllMaterialsEvent};
-use crate::block::lighting::{BlockLightProperties, BlockLighting};
+use crate::block::lighting::BlockLighting;
 use crate::rendering::structure_renderer::{BlockRenderingModes, StructureRenderingSet};
 use crate::rendering::{CosmosMeshBuilder, ReadOnlyBlockMeshRegistry};
 use bevy::prelude::{
     App, Assets, BuildChildren, Commands, DespawnRecursiveExt, Entity, EventWriter, GlobalTransform, IntoSystemConfigs, Mesh, Mesh3d,
-    PointLight, Query, Res, ResMut, Transform, Update, Vec3, Visibility, With,
+    Query, Res, ResMut, Transform, Update, Vec3, Visibility, With,
 };
 use bevy::render::mesh::MeshAabb;
 use bevy::render::primitives::Aabb;
 use bevy::tasks::AsyncComputeTaskPool;
-use bevy::utils::HashMap;
 use cosmos_core::block::Block;
 use cosmos_core::netty::client::LocalPlayer;
 use cosmos_core::physics::location::SECTOR_DIMENSIONS;
-use cosmos_core::prelude::ChunkBlockCoordinate;
 use cosmos_core::registry::many_to_one::ReadOnlyManyToOneRegistry;
 use cosmos_core::registry::ReadOnlyRegistry;
 use cosmos_core::structure::chunk::{ChunkEntity, CHUNK_DIMENSIONS};
@@ -23,15 +21,15 @@ use cosmos_core::structure::Structure;
 use futures_lite::future;
 
 use super::chunk_renderer::{ChunkNeedsCustomBlocksRendered, ChunkRenderer, RenderingChunk, RenderingChunks};
+use super::light_propagation::ChunkLightLevels;
 use super::neighbor_checking::ChunkRenderingChecker;
-use super::{ChunkMeshes, ChunkNeedsRendered, ChunkRenderResult, LightEntry, LightsHolder};
+use super::{ChunkMeshes, ChunkNeedsRendered, ChunkRenderResult};
 
 fn poll_rendering_chunks(
     mut commands: Commands,
     mut rendering_chunks: ResMut<RenderingChunks>,
     mut meshes: ResMut<Assets<Mesh>>,
     q_mesh: Query<&Mesh3d>,
-    q_lights: Query<&LightsHolder>,
     q_chunk_meshes: Query<&ChunkMeshes>,
     q_chunk_entity: Query<&ChunkEntity>,
     mut evw_add_material_event: EventWriter<AddMaterialEvent>,
@@ -92,10 +90,6 @@ fn poll_rendering_chunks(
 
         let mut entities_to_add = vec![];
 
-        let new_lights = create_lighting_data(&q_lights, entity, chunk_mesh.lights, &mut commands, &mut entities_to_add);
-
-        // meshes
-
         // The first mesh a chunk has will be on the chunk entity instead of child entities,
         // so clear that out first.
         commands.entity(entity).remove::<Mesh3d>();
@@ -167,7 +161,7 @@ fn poll_rendering_chunks(
             entity_commands.add_child(ent);
         }
 
-        entity_commands.insert(new_lights).insert(chunk_meshes_component);
+        entity_commands.insert(chunk_meshes_component);
     }
 
     // Undo the reverse above
@@ -176,81 +170,6 @@ fn poll_rendering_chunks(
     evw_chunk_needs_custom_blocks_rerendered.send_batch(events_to_send);
 }
 
-fn create_lighting_data(
-    q_lights: &Query<&LightsHolder>,
-    entity: Entity,
-    rendered_lights: HashMap<ChunkBlockCoordinate, BlockLightProperties>,
-    commands: &mut Commands,
-    entities_to_add: &mut Vec<Entity>,
-) -> LightsHolder {
-    let mut new_lights = LightsHolder::default();
-
-    if let Ok(lights) = q_lights.get(entity) {
-        for light in lights.lights.iter() {
-            let mut light = *light;
-            light.valid = false;
-            new_lights.lights.push(light);
-        }
-    }
-
-    if !rendered_lights.is_empty() {
-        for light in rendered_lights {
-            let (block_light_coord, properties) = light;
-
-            let mut found = false;
-            for light in new_lights.lights.iter_mut() {
-                if light.position.x == block_light_coord.x
-                    && light.position.y == block_light_coord.y
-                    && light.position.z == block_light_coord.z
-                {
-                    if light.light == properties {
-                        light.valid = true;
-                        found = true;
-                    }
-                    break;
-                }
-            }
-
-            if !found {
-                let light_entity = commands
-                    .spawn((
-                        PointLight {
-                            color: properties.color,
-                            intensity: properties.intensity,
-                            range: properties.range,
-                            radius: 1.0,
-                            // Shadows kill all performance
-                            shadows_enabled: false, // !properties.shadows_disabled,
-                            ..Default::default()
-                        },
-                        Transform::from_xyz(
-                            block_light_coord.x as f32 - (CHUNK_DIMENSIONS as f32 / 2.0 - 0.5),
-                            block_light_coord.y as f32 - (CHUNK_DIMENSIONS as f32 / 2.0 - 0.5),
-                            block_light_coord.z as f32 - (CHUNK_DIMENSIONS as f32 / 2.0 - 0.5),
-                        ),
-                    ))
-                    .id();
-
-                new_lights.lights.push(LightEntry {
-                    entity: light_entity,
-                    light: properties,
-                    position: block_light_coord,
-                    valid: true,
-                });
-                entities_to_add.push(light_entity);
-            }
-        }
-    }
-
-    for light in new_lights.lights.iter().filter(|x| !x.valid) {
-        commands.entity(light.entity).despawn_recursive();
-    }
-
-    new_lights.lights.retain(|x| x.valid);
-
-    new_lights
-}
-
 /// Performance hot spot
 fn monitor_needs_rendered_system(
     mut commands: Commands,
@@ -323,10 +242,12 @@ fn monitor_needs_rendered_system(
                 pos_z: pos_z.as_ref(),
             };
 
+            let light_levels = ChunkLightLevels::calculate(&chunk, &chunk_checker, &blocks.registry(), &lighting.registry());
+
             let custom_blocks = renderer.render(
                 &materials.registry(),
                 &materials_registry.registry(),
-                &lighting.registry(),
+                Some(&light_levels),
                 &chunk,
                 &blocks.registry(),
                 &meshes_registry.registry(),
@@ -344,7 +265,6 @@ fn monitor_needs_rendered_system(
                 chunk_entity: entity,
                 custom_blocks,
                 // mesh: super::ChunkMesh {
-                //     lights: Default::default(),
                 //     mesh_materials: Default::default(),
                 // },
                 mesh: renderer.create_mesh(),
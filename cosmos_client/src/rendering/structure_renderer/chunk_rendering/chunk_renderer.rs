@@ -1,10 +1,12 @@
 use crate::asset::asset_loading::{BlockNeighbors, BlockTextureIndex};
+use crate::asset::materials::block_materials::{ATTRIBUTE_BLOCK_LIGHT, ATTRIBUTE_PAINT_COLOR};
 use crate::asset::materials::{BlockMaterialMapping, MaterialDefinition};
-use crate::block::lighting::{BlockLightProperties, BlockLighting};
 use crate::rendering::structure_renderer::{BlockRenderingModes, RenderingMode};
+use bevy::color::ColorToPacked;
 use bevy::ecs::event::Event;
 use bevy::log::warn;
 use bevy::prelude::{App, Deref, DerefMut, Entity, Rect, Resource, Vec3};
+use bevy::render::mesh::VertexAttributeValues;
 use bevy::tasks::Task;
 use bevy::utils::hashbrown::HashMap;
 use cosmos_core::block::{block_direction::BlockDirection, Block};
@@ -17,13 +19,13 @@ use cosmos_core::structure::coordinates::{ChunkBlockCoordinate, ChunkCoordinate}
 use cosmos_core::utils::array_utils::expand;
 use std::collections::HashSet;
 
+use super::light_propagation::ChunkLightLevels;
 use super::neighbor_checking::ChunkRendererBackend;
 use super::{BlockMeshRegistry, ChunkMesh, ChunkRenderResult, MeshBuilder, MeshInfo, MeshMaterial};
 
 #[derive(Default, Debug)]
 pub struct ChunkRenderer<M: MeshBuilder + Default> {
     meshes: HashMap<(u16, u32), MeshInfo<M>>,
-    lights: HashMap<ChunkBlockCoordinate, BlockLightProperties>,
 }
 
 impl<M: MeshBuilder + Default> ChunkRenderer<M> {
@@ -32,11 +34,13 @@ impl<M: MeshBuilder + Default> ChunkRenderer<M> {
     }
 
     /// Renders a chunk into mesh information that can then be turned into a bevy mesh
+    ///
+    /// If `light_levels` are given, each face is lit based on them. Otherwise, faces are lit normally.
     pub fn render<C: BlockStorer, R: ChunkRendererBackend<C>>(
         &mut self,
         materials_registry: &ManyToOneRegistry<Block, BlockMaterialMapping>,
         materials_definition_registry: &Registry<MaterialDefinition>,
-        lighting: &Registry<BlockLighting>,
+        light_levels: Option<&ChunkLightLevels>,
         chunk: &C,
         blocks: &Registry<Block>,
         meshes: &BlockMeshRegistry,
@@ -128,6 +132,12 @@ impl<M: MeshBuilder + Default> ChunkRenderer<M> {
 
                 let block_rotation = block_info.get_rotation();
 
+                let paint_color = block_info
+                    .get_paint_color()
+                    .color()
+                    .map(|c| c.to_u8_array())
+                    .unwrap_or([u8::MAX; 4]);
+
                 let rotation = block_rotation.as_quat();
 
                 let mut mesh_builder = None;
@@ -283,7 +293,21 @@ impl<M: MeshBuilder + Default> ChunkRenderer<M> {
                         *norm = rotation.mul_vec3((*norm).into()).into();
                     }
 
-                    let additional_info = material_definition.add_material_data(block_id, &mesh_info);
+                    let mut additional_info = material_definition.add_material_data(block_id, &mesh_info);
+
+                    if !lod {
+                        additional_info.push((
+                            ATTRIBUTE_PAINT_COLOR,
+                            VertexAttributeValues::Unorm8x4(vec![paint_color; mesh_info.positions.len()]),
+                        ));
+                    }
+
+                    if let Some(light_levels) = light_levels {
+                        additional_info.push((
+                            ATTRIBUTE_BLOCK_LIGHT,
+                            VertexAttributeValues::Unorm8x4(vec![light_levels.face_light(coords, direction); mesh_info.positions.len()]),
+                        ));
+                    }
 
                     if mesh_builder.is_none() {
                         mesh_builder = Some(
@@ -307,10 +331,6 @@ impl<M: MeshBuilder + Default> ChunkRenderer<M> {
                 }
 
                 faces.clear();
-
-                if let Some(lighting) = lighting.from_id(block.unlocalized_name()) {
-                    self.lights.insert(coords, lighting.properties);
-                }
             }
         }
 
@@ -330,9 +350,7 @@ impl<M: MeshBuilder + Default> ChunkRenderer<M> {
             });
         }
 
-        let lights = self.lights;
-
-        ChunkMesh { lights, mesh_materials }
+        ChunkMesh { mesh_materials }
     }
 }
 
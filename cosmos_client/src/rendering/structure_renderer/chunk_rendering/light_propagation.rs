@@ -0,0 +1,320 @@
+//! Works out how much light reaches each block of a chunk, which is then baked into the chunk's mesh.
+//!
+//! There are two kinds of light:
+//! - Sky light, which comes from open space. Anything that can see out along one of the axes is fully lit by it, and it
+//!   spreads inwards from there.
+//! - Block light, which comes from blocks with [`BlockLighting`].
+//!
+//! Both lose a level for every block they spread through, and can't pass through blocks that can't be seen through.
+//! Light from neighboring chunks is accounted for, so this is calculated off the main thread alongside the chunk's mesh.
+
+use std::collections::VecDeque;
+
+use bevy::color::LinearRgba;
+use cosmos_core::{
+    block::{block_direction::BlockDirection, Block},
+    prelude::ChunkBlockCoordinate,
+    registry::{identifiable::Identifiable, Registry},
+    structure::{
+        block_storage::BlockStorer,
+        chunk::{Chunk, CHUNK_DIMENSIONS},
+        coordinates::CoordinateType,
+    },
+};
+
+use crate::block::lighting::BlockLighting;
+
+use super::neighbor_checking::ChunkRenderingChecker;
+
+/// The brightest a block can be lit. Light can't reach further than this many blocks.
+pub const MAX_LIGHT_LEVEL: u8 = 15;
+
+/// How bright each light level is compared to the one above it
+const LIGHT_FALLOFF: f32 = 0.8;
+
+/// Blocks no sky light reaches still get this much of it, so interiors aren't completely black
+const MIN_SKY_LIGHT: f32 = 0.03;
+
+/// How far outside the chunk light is calculated from
+const MARGIN: i64 = MAX_LIGHT_LEVEL as i64;
+const CD: i64 = CHUNK_DIMENSIONS as i64;
+/// The size of the area light is calculated for, which is the chunk plus a margin on every side
+const SIZE: i64 = CD + MARGIN * 2;
+
+/// What is at a position around the chunk being lit
+enum Lookup<'a> {
+    Block(&'a Chunk, ChunkBlockCoordinate),
+    /// There is no chunk here, so this is open space
+    Space,
+    /// Diagonal to the chunk - these chunks aren't available, so they're treated as empty, but not open to space.
+    Unknown,
+}
+
+/// The light levels in and around a chunk
+pub struct ChunkLightLevels {
+    sky: Vec<u8>,
+    block: Vec<u8>,
+    /// Which of `colors` the block light at each position comes from
+    block_source: Vec<u16>,
+    colors: Vec<LinearRgba>,
+}
+
+/// Converts a coordinate relative to the chunk (which can be up to [`MARGIN`] outside of it) into an index
+#[inline]
+fn index(x: i64, y: i64, z: i64) -> usize {
+    (((x + MARGIN) * SIZE + (y + MARGIN)) * SIZE + (z + MARGIN)) as usize
+}
+
+#[inline]
+fn in_bounds(v: i64) -> bool {
+    (-MARGIN..CD + MARGIN).contains(&v)
+}
+
+/// Turns a position along an axis (`t`) and the other two coordinates (`u`, `v`) into x, y, z
+#[inline]
+fn axis_coords(axis: usize, t: i64, u: i64, v: i64) -> (i64, i64, i64) {
+    match axis {
+        0 => (t, u, v),
+        1 => (u, t, v),
+        _ => (u, v, t),
+    }
+}
+
+fn neighbor<'a>(neighbors: &ChunkRenderingChecker<'a>, axis: usize, positive: bool) -> Option<&'a Chunk> {
+    match (axis, positive) {
+        (0, true) => neighbors.pos_x,
+        (0, false) => neighbors.neg_x,
+        (1, true) => neighbors.pos_y,
+        (1, false) => neighbors.neg_y,
+        (_, true) => neighbors.pos_z,
+        (_, false) => neighbors.neg_z,
+    }
+}
+
+fn chunk_coords(x: i64, y: i64, z: i64) -> ChunkBlockCoordinate {
+    ChunkBlockCoordinate::new(
+        x.rem_euclid(CD) as CoordinateType,
+        y.rem_euclid(CD) as CoordinateType,
+        z.rem_euclid(CD) as CoordinateType,
+    )
+    .expect("Wrapped coordinates are always within a chunk")
+}
+
+fn lookup<'a>(chunk: &'a Chunk, neighbors: &ChunkRenderingChecker<'a>, x: i64, y: i64, z: i64) -> Lookup<'a> {
+    let mut outside = None;
+
+    for (axis, v) in [x, y, z].into_iter().enumerate() {
+        if (0..CD).contains(&v) {
+            continue;
+        }
+
+        if outside.is_some() {
+            return Lookup::Unknown;
+        }
+        outside = Some((axis, v >= CD));
+    }
+
+    let Some((axis, positive)) = outside else {
+        return Lookup::Block(chunk, chunk_coords(x, y, z));
+    };
+
+    match neighbor(neighbors, axis, positive) {
+        Some(neighbor) => Lookup::Block(neighbor, chunk_coords(x, y, z)),
+        None => Lookup::Space,
+    }
+}
+
+/// Returns the light multiplier for this light level
+fn light_factor(level: u8) -> f32 {
+    if level == 0 {
+        0.0
+    } else {
+        LIGHT_FALLOFF.powi((MAX_LIGHT_LEVEL - level.min(MAX_LIGHT_LEVEL)) as i32)
+    }
+}
+
+/// Spreads these light levels out from everything in the queue, through blocks that can be seen through
+fn spread(levels: &mut [u8], mut sources: Option<&mut [u16]>, clear: &[bool], mut queue: VecDeque<usize>) {
+    const STEPS: [(i64, i64, i64); 6] = [(1, 0, 0), (-1, 0, 0), (0, 1, 0), (0, -1, 0), (0, 0, 1), (0, 0, -1)];
+
+    while let Some(idx) = queue.pop_front() {
+        let level = levels[idx];
+        if level <= 1 {
+            continue;
+        }
+
+        let i = idx as i64;
+        let (x, y, z) = (i / (SIZE * SIZE) - MARGIN, (i / SIZE) % SIZE - MARGIN, i % SIZE - MARGIN);
+
+        for (dx, dy, dz) in STEPS {
+            let (nx, ny, nz) = (x + dx, y + dy, z + dz);
+            if !in_bounds(nx) || !in_bounds(ny) || !in_bounds(nz) {
+                continue;
+            }
+
+            let n_idx = index(nx, ny, nz);
+            if !clear[n_idx] || levels[n_idx] >= level - 1 {
+                continue;
+            }
+
+            levels[n_idx] = level - 1;
+            if let Some(sources) = sources.as_mut() {
+                sources[n_idx] = sources[idx];
+            }
+            queue.push_back(n_idx);
+        }
+    }
+}
+
+impl ChunkLightLevels {
+    /// Calculates the light levels for this chunk, using its neighbors for light that comes from outside of it
+    pub fn calculate(
+        chunk: &Chunk,
+        neighbors: &ChunkRenderingChecker,
+        blocks: &Registry<Block>,
+        lighting: &Registry<BlockLighting>,
+    ) -> Self {
+        let len = (SIZE * SIZE * SIZE) as usize;
+
+        let mut colors = vec![];
+        let mut emitters = vec![None; blocks.iter().len()];
+        for light in lighting.iter() {
+            if let Some(block) = blocks.from_id(light.unlocalized_name()) {
+                emitters[block.id() as usize] = Some((light.properties.level.min(MAX_LIGHT_LEVEL), colors.len() as u16));
+                colors.push(light.properties.color.to_linear());
+            }
+        }
+
+        let mut clear = vec![false; len];
+        let mut space = vec![false; len];
+
+        let mut block = vec![0; len];
+        let mut block_source = vec![0; len];
+        let mut block_queue = VecDeque::new();
+
+        for x in -MARGIN..CD + MARGIN {
+            for y in -MARGIN..CD + MARGIN {
+                for z in -MARGIN..CD + MARGIN {
+                    let idx = index(x, y, z);
+
+                    match lookup(chunk, neighbors, x, y, z) {
+                        Lookup::Block(chunk, coords) => {
+                            let block_id = chunk.block_at(coords);
+                            clear[idx] = blocks.from_numeric_id(block_id).is_see_through();
+
+                            if let Some((level, source)) = emitters[block_id as usize] {
+                                block[idx] = level;
+                                block_source[idx] = source;
+                                block_queue.push_back(idx);
+                            }
+                        }
+                        Lookup::Space => {
+                            clear[idx] = true;
+                            space[idx] = true;
+                        }
+                        Lookup::Unknown => {
+                            clear[idx] = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut sky = vec![0; len];
+        let mut sky_queue = VecDeque::new();
+
+        // Anything that can see out of this area along an axis, without anything in the way, is open to space.
+        for axis in 0..3 {
+            for positive in [true, false] {
+                for u in -MARGIN..CD + MARGIN {
+                    for v in -MARGIN..CD + MARGIN {
+                        let mut open = Self::escapes(neighbors, blocks, axis, positive, u, v);
+
+                        let (start, end, step) = if positive {
+                            (CD + MARGIN - 1, -MARGIN - 1, -1)
+                        } else {
+                            (-MARGIN, CD + MARGIN, 1)
+                        };
+
+                        let mut t = start;
+                        while t != end && open {
+                            let (x, y, z) = axis_coords(axis, t, u, v);
+                            let idx = index(x, y, z);
+
+                            open = clear[idx];
+                            if open && sky[idx] == 0 {
+                                sky[idx] = MAX_LIGHT_LEVEL;
+                                sky_queue.push_back(idx);
+                            }
+
+                            t += step;
+                        }
+                    }
+                }
+            }
+        }
+
+        for (idx, _) in space.iter().enumerate().filter(|(_, space)| **space) {
+            if sky[idx] == 0 {
+                sky[idx] = MAX_LIGHT_LEVEL;
+                sky_queue.push_back(idx);
+            }
+        }
+
+        spread(&mut sky, None, &clear, sky_queue);
+        spread(&mut block, Some(&mut block_source), &clear, block_queue);
+
+        Self {
+            sky,
+            block,
+            block_source,
+            colors,
+        }
+    }
+
+    /// Returns true if a ray leaving the calculated area along this axis doesn't hit anything in the rest of the
+    /// neighboring chunk. Rays that leave through a corner we don't have the chunks for are assumed to get out.
+    fn escapes(neighbors: &ChunkRenderingChecker, blocks: &Registry<Block>, axis: usize, positive: bool, u: i64, v: i64) -> bool {
+        if !(0..CD).contains(&u) || !(0..CD).contains(&v) {
+            return true;
+        }
+
+        let Some(neighbor) = neighbor(neighbors, axis, positive) else {
+            return true;
+        };
+
+        // The part of the neighboring chunk past the margin
+        let remaining = if positive { MARGIN..CD } else { 0..CD - MARGIN };
+
+        remaining.into_iter().all(|t| {
+            let (x, y, z) = axis_coords(axis, t, u, v);
+            blocks.from_numeric_id(neighbor.block_at(chunk_coords(x, y, z))).is_see_through()
+        })
+    }
+
+    /// The light that reaches this face of a block, packed for [`crate::asset::materials::block_materials::ATTRIBUTE_BLOCK_LIGHT`].
+    ///
+    /// The red, green, and blue channels are the block light and the alpha is how much sky light there is.
+    pub fn face_light(&self, coords: ChunkBlockCoordinate, direction: BlockDirection) -> [u8; 4] {
+        let (dx, dy, dz) = direction.to_i32_tuple();
+        let (x, y, z) = (coords.x as i64, coords.y as i64, coords.z as i64);
+
+        // Use whichever is brighter of the block and the one its face is touching, since blocks that aren't full
+        // are lit themselves, while full blocks are only lit by what's next to them.
+        let here = index(x, y, z);
+        let facing = index(x + dx as i64, y + dy as i64, z + dz as i64);
+
+        let sky = self.sky[here].max(self.sky[facing]);
+        let lit_by = if self.block[facing] >= self.block[here] { facing } else { here };
+
+        let sky = light_factor(sky).max(MIN_SKY_LIGHT);
+        let block = match self.colors.get(self.block_source[lit_by] as usize) {
+            Some(color) => *color * light_factor(self.block[lit_by]),
+            None => LinearRgba::BLACK,
+        };
+
+        let to_u8 = |v: f32| (v.clamp(0.0, 1.0) * u8::MAX as f32).round() as u8;
+
+        [to_u8(block.red), to_u8(block.green), to_u8(block.blue), to_u8(sky)]
+    }
+}
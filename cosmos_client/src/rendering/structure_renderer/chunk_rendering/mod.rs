@@ -1,15 +1,13 @@
-use crate::block::lighting::BlockLightProperties;
 use bevy::prelude::{App, Component, Entity, Mesh, Rect, Vec3};
 use bevy::reflect::Reflect;
 use bevy::render::mesh::{MeshVertexAttribute, VertexAttributeValues};
-use bevy::utils::hashbrown::HashMap;
-use cosmos_core::structure::coordinates::ChunkBlockCoordinate;
 use std::collections::HashSet;
 
 use super::{BlockMeshRegistry, MeshBuilder, MeshInformation};
 
 mod async_rendering;
 pub mod chunk_renderer;
+pub mod light_propagation;
 pub mod lod_rendering;
 pub mod neighbor_checking;
 
@@ -23,20 +21,6 @@ pub struct MeshMaterial {
 #[derive(Debug)]
 pub struct ChunkMesh {
     pub mesh_materials: Vec<MeshMaterial>,
-    pub lights: HashMap<ChunkBlockCoordinate, BlockLightProperties>,
-}
-
-#[derive(Debug, Reflect, Clone, Copy)]
-struct LightEntry {
-    entity: Entity,
-    light: BlockLightProperties,
-    position: ChunkBlockCoordinate,
-    valid: bool,
-}
-
-#[derive(Component, Debug, Reflect, Default)]
-struct LightsHolder {
-    lights: Vec<LightEntry>,
 }
 
 #[derive(Component, Debug, Reflect, Default)]
@@ -79,8 +63,6 @@ impl<M: MeshBuilder> MeshBuilder for MeshInfo<M> {
 }
 
 pub(super) fn register(app: &mut App) {
-    app.register_type::<LightsHolder>();
-
     async_rendering::register(app);
     chunk_renderer::register(app);
 }
@@ -1,6 +1,6 @@
 use bevy::math::Vec3;
 use cosmos_core::{
-    block::{block_direction::BlockDirection, block_face::BlockFace, Block},
+    block::{block_direction::BlockDirection, block_face::BlockFace, block_rotation::BlockRotation, Block},
     prelude::ChunkBlockCoordinate,
     registry::Registry,
     structure::{
@@ -37,6 +37,8 @@ pub fn check_block_at<C: BlockStorer>(
     blocks: &Registry<Block>,
     should_connect: &mut bool,
     actual_block: &Block,
+    actual_rotation: BlockRotation,
+    direction_to_check: BlockDirection,
     rendering_modes: &BlockRenderingModes,
 ) -> bool {
     let block_id_checking = chunk.block_at(check_coords);
@@ -45,9 +47,14 @@ pub fn check_block_at<C: BlockStorer>(
 
     let custom_rendered = rendering_modes.rendering_mode(block_id_checking);
 
+    // Non-cube blocks (such as slabs) only hide faces that their shape fully covers
+    let face_here = actual_rotation.block_face_pointing(direction_to_check);
+    let face_checking = chunk.block_rotation(check_coords).block_face_pointing(direction_to_check.inverse());
+
     // A block adjacent is custom
     custom_rendered == RenderingMode::Custom
-        || (!(actual_block.is_fluid() && block_checking == actual_block) && (block_checking.is_see_through() || !actual_block.is_full()))
+        || (!(actual_block.is_fluid() && block_checking == actual_block)
+            && (block_checking.is_transparent() || !block_checking.covers_face(face_checking) || !actual_block.covers_face(face_here)))
 }
 
 pub struct ChunkRenderingChecker<'a> {
@@ -139,7 +146,16 @@ impl ChunkRendererBackend<Chunk> for ChunkRenderingChecker<'_> {
             return true;
         };
 
-        check_block_at(chunk, check_coords, blocks, should_connect, block_here, rendering_modes)
+        check_block_at(
+            chunk,
+            check_coords,
+            blocks,
+            should_connect,
+            block_here,
+            c.block_rotation(block_coords),
+            direction_to_check,
+            rendering_modes,
+        )
     }
 
     #[inline(always)]
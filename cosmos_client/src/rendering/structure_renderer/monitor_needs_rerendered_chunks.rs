@@ -1,12 +1,18 @@
-use bevy::prelude::{App, Commands, Entity, EventReader, IntoSystemConfigs, Query, Update};
+use bevy::prelude::{App, Commands, Entity, EventReader, IntoSystemConfigs, Query, Res, Update};
 use bevy::utils::hashbrown::HashMap;
+use cosmos_core::block::Block;
 use cosmos_core::events::block_events::{BlockChangedEvent, BlockDataChangedEvent};
+use cosmos_core::registry::identifiable::Identifiable;
+use cosmos_core::registry::Registry;
 use cosmos_core::structure::chunk::CHUNK_DIMENSIONS;
-use cosmos_core::structure::coordinates::ChunkCoordinate;
+use cosmos_core::structure::coordinates::{ChunkCoordinate, CoordinateType};
 use cosmos_core::structure::events::ChunkSetEvent;
 use cosmos_core::structure::Structure;
 use std::collections::HashSet;
 
+use crate::block::lighting::BlockLighting;
+
+use super::chunk_rendering::light_propagation::MAX_LIGHT_LEVEL;
 use super::chunk_rendering::ChunkNeedsRendered;
 use super::StructureRenderingSet;
 
@@ -15,6 +21,8 @@ fn monitor_block_updates_system(
     mut evr_chunk_set_event: EventReader<ChunkSetEvent>,
     mut evr_changed_data: EventReader<BlockDataChangedEvent>,
     q_structure: Query<&Structure>,
+    blocks: Res<Registry<Block>>,
+    lighting: Res<Registry<BlockLighting>>,
     mut commands: Commands,
 ) {
     let mut chunks_todo = HashMap::<Entity, HashSet<ChunkCoordinate>>::default();
@@ -66,29 +74,45 @@ fn monitor_block_updates_system(
 
         let cc = ev.block.chunk_coords();
 
-        if ev.block.x() != 0 && ev.block.x() % CHUNK_DIMENSIONS == 0 {
+        // Blocks that change how light spreads can change the lighting of chunks up to the max light level away
+        let (old_block, new_block) = (blocks.from_numeric_id(ev.old_block), blocks.from_numeric_id(ev.new_block));
+        let emits_light = |block: &Block| lighting.from_id(block.unlocalized_name()).is_some();
+
+        let reach = if old_block.is_see_through() != new_block.is_see_through() || emits_light(old_block) || emits_light(new_block) {
+            MAX_LIGHT_LEVEL as CoordinateType
+        } else {
+            1
+        };
+
+        let (x, y, z) = (
+            ev.block.x() % CHUNK_DIMENSIONS,
+            ev.block.y() % CHUNK_DIMENSIONS,
+            ev.block.z() % CHUNK_DIMENSIONS,
+        );
+
+        let dims = structure.chunk_dimensions();
+
+        if cc.x != 0 && x < reach {
             chunks.insert(ChunkCoordinate::new(cc.x - 1, cc.y, cc.z));
         }
 
-        let dims = structure.block_dimensions();
-
-        if ev.block.x() != dims.x - 1 && (ev.block.x() + 1) % CHUNK_DIMENSIONS == 0 {
+        if cc.x < dims.x - 1 && x >= CHUNK_DIMENSIONS - reach {
             chunks.insert(ChunkCoordinate::new(cc.x + 1, cc.y, cc.z));
         }
 
-        if ev.block.y() != 0 && ev.block.y() % CHUNK_DIMENSIONS == 0 {
+        if cc.y != 0 && y < reach {
             chunks.insert(ChunkCoordinate::new(cc.x, cc.y - 1, cc.z));
         }
 
-        if ev.block.y() != dims.y - 1 && (ev.block.y() + 1) % CHUNK_DIMENSIONS == 0 {
+        if cc.y < dims.y - 1 && y >= CHUNK_DIMENSIONS - reach {
             chunks.insert(ChunkCoordinate::new(cc.x, cc.y + 1, cc.z));
         }
 
-        if ev.block.z() != 0 && ev.block.z() % CHUNK_DIMENSIONS == 0 {
+        if cc.z != 0 && z < reach {
             chunks.insert(ChunkCoordinate::new(cc.x, cc.y, cc.z - 1));
         }
 
-        if ev.block.z() != dims.z - 1 && (ev.block.z() + 1) % CHUNK_DIMENSIONS == 0 {
+        if cc.z < dims.z - 1 && z >= CHUNK_DIMENSIONS - reach {
             chunks.insert(ChunkCoordinate::new(cc.x, cc.y, cc.z + 1));
         }
 
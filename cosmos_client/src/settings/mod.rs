@@ -6,22 +6,30 @@ use bevy::{
     app::Update,
     log::error,
     prelude::{
-        in_state, not, resource_changed, resource_exists, resource_exists_and_changed, AmbientLight, App, Commands, IntoSystemConfigs,
-        IntoSystemSetConfigs, OnEnter, OnExit, Projection, Query, Res, ResMut, Resource, SystemSet, With,
+        in_state, not, resource_changed, resource_exists, resource_exists_and_changed, AmbientLight, App, Commands, DetectChangesMut,
+        IntoSystemConfigs, IntoSystemSetConfigs, OnEnter, OnExit, Projection, Query, Res, ResMut, Resource, SystemSet, With,
     },
     utils::HashMap,
 };
 use cosmos_core::{
+    entities::player::render_distance::RenderDistance,
+    netty::client::LocalPlayer,
     registry::{create_registry, identifiable::Identifiable, Registry},
     state::GameState,
+    structure::coordinates::UnboundCoordinateType,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{lang::Lang, rendering::MainCamera};
+use crate::{
+    lang::{Lang, DEFAULT_LANGUAGE},
+    rendering::MainCamera,
+};
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize, PartialOrd, Ord)]
 /// Category this setting belongs to (for display purposes only)
 pub enum SettingCategory {
+    /// Settings that don't fit in any other category, such as the language
+    General,
     /// Graphical related stuff
     Graphics,
     /// Mouse
@@ -180,7 +188,68 @@ fn on_changed_desired_fov(mut q_cam: Query<&mut Projection, With<MainCamera>>, d
     }
 }
 
+#[derive(Resource, Debug, Clone, Copy)]
+/// How many chunks around the player should be loaded on planets.
+///
+/// Chunks further than this (plus one) will be unloaded.
+pub struct PlanetRenderDistance(pub UnboundCoordinateType);
+
+impl Default for PlanetRenderDistance {
+    fn default() -> Self {
+        Self(2)
+    }
+}
+
+#[derive(Resource, Debug, Default, Clone, Copy)]
+/// The render distance the player wants. This will be applied to the local player and sent to the server,
+/// which may choose to lower it.
+pub struct DesiredRenderDistance(pub RenderDistance);
+
+fn load_render_distance(mut commands: Commands, settings: Res<Registry<Setting>>) {
+    commands.insert_resource(PlanetRenderDistance(
+        settings.i32_or("cosmos:planet_render_distance", 2).max(1) as UnboundCoordinateType
+    ));
+    commands.insert_resource(DesiredRenderDistance(RenderDistance {
+        sector_range: settings.i32_or("cosmos:render_distance", 8).max(1) as usize,
+    }));
+}
+
+/// The local player is created with the default render distance, so this also has to run whenever they are (re)created.
+fn apply_render_distance(desired: Res<DesiredRenderDistance>, mut q_render_distance: Query<&mut RenderDistance, With<LocalPlayer>>) {
+    for mut render_distance in q_render_distance.iter_mut() {
+        render_distance.set_if_neq(desired.0);
+    }
+}
+
 fn register_settings(mut registry: ResMut<Registry<Setting>>) {
+    registry.register(Setting::new(
+        "cosmos:language",
+        SettingData::String(DEFAULT_LANGUAGE.into()),
+        SettingCategory::General,
+        None,
+    ));
+
+    registry.register(Setting::new(
+        "cosmos:ui_theme",
+        SettingData::String("default".into()),
+        SettingCategory::General,
+        None,
+    ));
+
+    registry.register(Setting::new(
+        "cosmos:ui_scale",
+        SettingData::I32(100),
+        SettingCategory::General,
+        Some(SettingConstraint::I32 { min: 50, max: 200 }),
+    ));
+
+    registry.register(Setting::new(
+        "cosmos:screenshot_directory",
+        SettingData::String("screenshots".into()),
+        SettingCategory::General,
+        None,
+    ));
+
     registry.register(Setting::new(
         "cosmos:brightness",
         SettingData::I32(100),
@@ -202,6 +271,69 @@ fn register_settings(mut registry: ResMut<Registry<Setting>>) {
         Some(SettingConstraint::I32 { min: 30, max: 120 }),
     ));
 
+    registry.register(Setting::new(
+        "cosmos:camera_shake",
+        SettingData::I32(100),
+        SettingCategory::Graphics,
+        Some(SettingConstraint::I32 { min: 0, max: 200 }),
+    ));
+
+    registry.register(Setting::new(
+        "cosmos:render_distance",
+        SettingData::I32(8),
+        SettingCategory::Graphics,
+        Some(SettingConstraint::I32 { min: 1, max: 8 }),
+    ));
+
+    registry.register(Setting::new(
+        "cosmos:planet_render_distance",
+        SettingData::I32(2),
+        SettingCategory::Graphics,
+        Some(SettingConstraint::I32 { min: 1, max: 6 }),
+    ));
+
+    registry.register(Setting::new(
+        "cosmos:master_volume",
+        SettingData::I32(100),
+        SettingCategory::Audio,
+        Some(SettingConstraint::I32 { min: 0, max: 100 }),
+    ));
+
+    registry.register(Setting::new(
+        "cosmos:sound_effects_volume",
+        SettingData::I32(100),
+        SettingCategory::Audio,
+        Some(SettingConstraint::I32 { min: 0, max: 100 }),
+    ));
+
+    registry.register(Setting::new(
+        "cosmos:block_sounds_volume",
+        SettingData::I32(100),
+        SettingCategory::Audio,
+        Some(SettingConstraint::I32 { min: 0, max: 100 }),
+    ));
+
+    registry.register(Setting::new(
+        "cosmos:weapon_sounds_volume",
+        SettingData::I32(100),
+        SettingCategory::Audio,
+        Some(SettingConstraint::I32 { min: 0, max: 100 }),
+    ));
+
+    registry.register(Setting::new(
+        "cosmos:ship_sounds_volume",
+        SettingData::I32(100),
+        SettingCategory::Audio,
+        Some(SettingConstraint::I32 { min: 0, max: 100 }),
+    ));
+
+    registry.register(Setting::new(
+        "cosmos:explosion_sounds_volume",
+        SettingData::I32(100),
+        SettingCategory::Audio,
+        Some(SettingConstraint::I32 { min: 0, max: 100 }),
+    ));
+
     registry.register(Setting::new(
         "cosmos:music_volume",
         SettingData::I32(100),
@@ -289,7 +421,7 @@ fn insert_settings_lang(mut langs: ResMut<Lang<Setting>>, settings: Res<Registry
 }
 
 fn init_settings_lang(mut commands: Commands) {
-    commands.insert_resource(Lang::<Setting>::new("en_us", vec!["settings"]));
+    commands.insert_resource(Lang::<Setting>::new(DEFAULT_LANGUAGE, vec!["settings"]));
 }
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
@@ -329,12 +461,15 @@ pub(super) fn register(app: &mut App) {
 
     app.add_systems(OnEnter(GameState::PreLoading), register_settings);
 
-    app.add_systems(OnEnter(GameState::Loading), load_settings).add_systems(
-        Update,
-        (
-            (load_gamma, load_mouse_sensitivity, load_fov).in_set(SettingsSet::LoadSettings),
-            on_changed_desired_fov,
+    app.add_systems(OnEnter(GameState::Loading), load_settings)
+        .add_systems(
+            Update,
+            (
+                (load_gamma, load_mouse_sensitivity, load_fov, load_render_distance).in_set(SettingsSet::LoadSettings),
+                (on_changed_desired_fov, apply_render_distance),
+            )
+                .chain(),
         )
-            .chain(),
-    );
+        .init_resource::<PlanetRenderDistance>()
+        .init_resource::<DesiredRenderDistance>();
 }
@@ -1,13 +1,23 @@
 use bevy::{
     app::{App, Update},
-    ecs::{event::EventWriter, schedule::IntoSystemConfigs, system::ResMut},
+    ecs::{
+        event::EventWriter,
+        schedule::IntoSystemConfigs,
+        system::{Res, ResMut},
+    },
     state::condition::in_state,
 };
 use bevy_renet2::renet2::RenetClient;
 use cosmos_core::{
     ecs::mut_events::MutEvent,
-    netty::{cosmos_encoder, system_sets::NetworkingSystemsSet, NettyChannelServer},
-    shop::netty::ServerShopMessages,
+    item::Item,
+    netty::{
+        network_stats::NettyClientMessages, sync::registry::client::RegistryIdMap, system_sets::NetworkingSystemsSet, NettyChannelServer,
+    },
+    shop::{
+        netty::{ServerShopMessages, ShopPurchaseError, ShopSellError},
+        Shop,
+    },
     state::GameState,
     structure::structure_block::StructureBlock,
 };
@@ -16,12 +26,33 @@ use super::{ui::OpenShopUiEvent, PurchasedEvent, SoldEvent};
 
 fn shop_listen_netty(
     mut client: ResMut<RenetClient>,
+    item_ids: Res<RegistryIdMap<Item>>,
     mut ev_writer_open_shop_ui: EventWriter<MutEvent<OpenShopUiEvent>>,
     mut ev_writer_purchased: EventWriter<PurchasedEvent>,
     mut ev_writer_sold: EventWriter<SoldEvent>,
 ) {
-    while let Some(message) = client.receive_message(NettyChannelServer::Shop) {
-        let msg: ServerShopMessages = cosmos_encoder::deserialize(&message).expect("Bad shop message");
+    while let Some(message) = client.receive_netty_message::<ServerShopMessages>(NettyChannelServer::Shop) {
+        let mut msg: ServerShopMessages = message.expect("Bad shop message");
+
+        // Any shop sent from the server is in terms of the server's item ids
+        let shop: Option<&mut Shop> = match &mut msg {
+            ServerShopMessages::OpenShop { shop_data: shop, .. }
+            | ServerShopMessages::PurchaseResult {
+                details: Ok(shop) | Err(ShopPurchaseError::NoStock(shop)),
+                ..
+            }
+            | ServerShopMessages::SellResult {
+                details: Ok(shop) | Err(ShopSellError::NotWillingToBuyThatMany(shop)),
+                ..
+            } => Some(shop),
+            _ => None,
+        };
+
+        if let Some(shop) = shop {
+            if !item_ids.is_identity() {
+                shop.remap_item_ids(|id| item_ids.to_client(id));
+            }
+        }
 
         match msg {
             ServerShopMessages::OpenShop {
@@ -27,7 +27,13 @@ use cosmos_core::{
         NeedsDespawned,
     },
     item::Item,
-    netty::{client::LocalPlayer, cosmos_encoder, system_sets::NetworkingSystemsSet, NettyChannelClient},
+    netty::{
+        client::LocalPlayer,
+        network_stats::{NettyClientMessages, NettyMessage},
+        sync::registry::client::RegistryIdMap,
+        system_sets::NetworkingSystemsSet,
+        NettyChannelClient,
+    },
     registry::{identifiable::Identifiable, Registry},
     shop::{netty::ClientShopMessages, Shop, ShopEntry},
     state::GameState,
@@ -47,6 +53,7 @@ use crate::{
         },
         font::DefaultFont,
         reactivity::{add_reactable_type, BindValue, BindValues, ReactableFields, ReactableValue},
+        theme::UiTheme,
         OpenMenu, UiSystemSet,
     },
 };
@@ -1041,6 +1048,7 @@ fn on_buy(
     mut client: ResMut<RenetClient>,
     q_shop_ui: Query<(&ShopUi, &AmountSelected)>,
     q_buy_button: Query<&BuyOrSellButton>,
+    item_ids: Res<RegistryIdMap<Item>>,
     mut ev_reader: EventReader<BuyOrSellBtnEvent>,
 ) {
     for ev in ev_reader.read() {
@@ -1057,18 +1065,20 @@ fn on_buy(
             continue;
         };
 
+        let (ShopEntry::Buying { item_id, .. } | ShopEntry::Selling { item_id, .. }) = selected_item.entry;
+        let Some(item_id) = item_ids.to_server(item_id) else {
+            error!("Shop item {item_id} doesn't exist on the server");
+            continue;
+        };
+
         // Prevent accidental duplicate purchases
         commands.entity(ev.0).insert(Disabled);
 
         match selected_item.entry {
-            ShopEntry::Buying {
-                item_id,
-                max_quantity_buying: _,
-                price_per: _,
-            } => {
-                client.send_message(
+            ShopEntry::Buying { .. } => {
+                client.send_netty_message(
                     NettyChannelClient::Shop,
-                    cosmos_encoder::serialize(&ClientShopMessages::Sell {
+                    NettyMessage::new(&ClientShopMessages::Sell {
                         shop_block: shop_ui.structure_block.coords(),
                         structure_entity: shop_ui.structure_block.structure(),
                         item_id,
@@ -1076,14 +1086,10 @@ fn on_buy(
                     }),
                 );
             }
-            ShopEntry::Selling {
-                item_id,
-                max_quantity_selling: _,
-                price_per: _,
-            } => {
-                client.send_message(
+            ShopEntry::Selling { .. } => {
+                client.send_netty_message(
                     NettyChannelClient::Shop,
-                    cosmos_encoder::serialize(&ClientShopMessages::Buy {
+                    NettyMessage::new(&ClientShopMessages::Buy {
                         shop_block: shop_ui.structure_block.coords(),
                         structure_entity: shop_ui.structure_block.structure(),
                         item_id,
@@ -1147,6 +1153,7 @@ ShopMode::Buy,
 */
 
 fn on_change_shop_mode(
+    theme: Res<UiTheme>,
     mut q_shop: Query<
         (
             &ShopMode,
@@ -1196,17 +1203,12 @@ fn on_change_shop_mode(
             .into();
 
             btn.button_styles = Some(match shop_mode {
-                ShopMode::Buy => ButtonStyles {
-                    background_color: css::DARK_GREEN.into(),
-                    hover_background_color: css::DARK_GREEN.into(),
-                    press_background_color: css::DARK_GREEN.into(),
-                    ..Default::default()
-                },
+                ShopMode::Buy => theme.positive_button_styles(),
                 ShopMode::Sell => ButtonStyles {
-                    background_color: Srgba::hex("880000").unwrap().into(),
-                    hover_background_color: Srgba::hex("880000").unwrap().into(),
-                    press_background_color: Srgba::hex("880000").unwrap().into(),
-                    ..Default::default()
+                    background_color: theme.negative,
+                    hover_background_color: theme.negative,
+                    press_background_color: theme.negative,
+                    ..theme.button_styles()
                 },
             });
         }
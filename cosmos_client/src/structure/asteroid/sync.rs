@@ -4,8 +4,8 @@ use bevy::prelude::{resource_exists, App, Commands, IntoSystemConfigs, Query, Re
 use bevy_renet2::renet2::RenetClient;
 use cosmos_core::{
     netty::{
-        cosmos_encoder,
         netty_rigidbody::NettyRigidBodyLocation,
+        network_stats::NettyClientMessages,
         sync::mapping::{Mappable, NetworkMapping},
         system_sets::NetworkingSystemsSet,
         NettyChannelServer,
@@ -28,8 +28,8 @@ fn receive_asteroids(
     mut commands: Commands,
     network_mapping: ResMut<NetworkMapping>,
 ) {
-    while let Some(message) = client.receive_message(NettyChannelServer::Asteroid) {
-        let msg: AsteroidServerMessages = cosmos_encoder::deserialize(&message).unwrap();
+    while let Some(message) = client.receive_netty_message::<AsteroidServerMessages>(NettyChannelServer::Asteroid) {
+        let msg: AsteroidServerMessages = message.unwrap();
 
         match msg {
             AsteroidServerMessages::Asteroid {
@@ -1,126 +1,101 @@
 use bevy::prelude::{
-    resource_exists, App, BuildChildren, ChildBuild, Commands, EventReader, Handle, IntoSystemConfigs, Name, Query, Res, Resource,
-    Transform, Update,
+    App, BuildChildren, ChildBuild, Commands, Entity, EventReader, IntoSystemConfigs, Name, Query, Res, Transform, Update,
 };
-use bevy_kira_audio::{Audio, AudioControl, AudioInstance, AudioSource};
+use bevy_kira_audio::Audio;
 use cosmos_core::{
     block::block_events::BlockEventsSet,
     netty::system_sets::NetworkingSystemsSet,
-    state::GameState,
-    structure::{shared::DespawnWithStructure, Structure},
+    registry::Registry,
+    structure::{coordinates::BlockCoordinate, shared::DespawnWithStructure, Structure},
 };
 
 use crate::{
-    asset::asset_loader::load_assets,
-    audio::{AudioEmission, CosmosAudioEmitter, DespawnOnNoEmissions},
+    audio::{sound_effects::SoundEffect, CosmosAudioEmitter, DespawnOnNoEmissions},
     events::block::block_events::{RequestBlockBreakEvent, RequestBlockPlaceEvent},
 };
 
+fn play_block_sound(
+    commands: &mut Commands,
+    audio: &Audio,
+    sound: &SoundEffect,
+    structure_query: &Query<&Structure>,
+    structure_entity: Entity,
+    coords: BlockCoordinate,
+    name: &'static str,
+) {
+    let Ok(structure) = structure_query.get(structure_entity) else {
+        return;
+    };
+
+    let Some(emission) = sound.play(audio) else {
+        return;
+    };
+
+    let sound_location = structure.block_relative_position(coords);
+
+    commands.entity(structure_entity).with_children(|p| {
+        p.spawn((
+            Name::new(name),
+            DespawnWithStructure,
+            DespawnOnNoEmissions,
+            Transform::from_translation(sound_location),
+            CosmosAudioEmitter::with_emissions(vec![emission]),
+        ));
+    });
+}
+
 fn play_block_break_sound(
     mut event_reader: EventReader<RequestBlockBreakEvent>,
-    break_sound: Res<BlockBreakSound>,
+    sound_effects: Res<Registry<SoundEffect>>,
     structure_query: Query<&Structure>,
     audio: Res<Audio>,
     mut commands: Commands,
 ) {
-    for ev in event_reader.read() {
-        let Ok(structure) = structure_query.get(ev.block.structure()) else {
-            continue;
-        };
-
-        let sound_location = structure.block_relative_position(ev.block.coords());
-
-        let playing_sound: Handle<AudioInstance> = audio.play(break_sound.0.clone()).with_volume(0.0).handle();
+    let Some(break_sound) = sound_effects.from_id("cosmos:block_break") else {
+        return;
+    };
 
-        let sound_emission = CosmosAudioEmitter::with_emissions(vec![AudioEmission {
-            instance: playing_sound,
-            max_distance: 16.0,
-            ..Default::default()
-        }]);
-
-        commands.entity(ev.block.structure()).with_children(|p| {
-            p.spawn((
-                Name::new("Block break sound"),
-                DespawnWithStructure,
-                DespawnOnNoEmissions,
-                Transform::from_translation(sound_location),
-                sound_emission,
-            ));
-        });
+    for ev in event_reader.read() {
+        play_block_sound(
+            &mut commands,
+            &audio,
+            break_sound,
+            &structure_query,
+            ev.block.structure(),
+            ev.block.coords(),
+            "Block break sound",
+        );
     }
 }
 
 fn play_block_place_sound(
     mut event_reader: EventReader<RequestBlockPlaceEvent>,
-    place_sound: Res<BlockPlaceSound>,
+    sound_effects: Res<Registry<SoundEffect>>,
     structure_query: Query<&Structure>,
     audio: Res<Audio>,
     mut commands: Commands,
 ) {
-    for ev in event_reader.read() {
-        let Ok(structure) = structure_query.get(ev.block.structure()) else {
-            continue;
-        };
-
-        let sound_location = structure.block_relative_position(ev.block.coords());
-
-        let playing_sound: Handle<AudioInstance> = audio.play(place_sound.0.clone()).with_volume(0.0).handle();
+    let Some(place_sound) = sound_effects.from_id("cosmos:block_place") else {
+        return;
+    };
 
-        let sound_emission = CosmosAudioEmitter::with_emissions(vec![AudioEmission {
-            instance: playing_sound,
-            max_distance: 16.0,
-            ..Default::default()
-        }]);
-
-        commands.entity(ev.block.structure()).with_children(|p| {
-            p.spawn((
-                Name::new("Block break sound"),
-                DespawnWithStructure,
-                DespawnOnNoEmissions,
-                Transform::from_translation(sound_location),
-                sound_emission,
-            ));
-        });
+    for ev in event_reader.read() {
+        play_block_sound(
+            &mut commands,
+            &audio,
+            place_sound,
+            &structure_query,
+            ev.block.structure(),
+            ev.block.coords(),
+            "Block place sound",
+        );
     }
 }
 
-#[derive(Resource)]
-struct BlockBreakSound(Handle<AudioSource>);
-#[derive(Resource)]
-struct BlockPlaceSound(Handle<AudioSource>);
-
-struct LoadingPlaceAudio;
-struct LoadingBreakAudio;
-
 pub(super) fn register(app: &mut App) {
-    load_assets::<AudioSource, LoadingBreakAudio>(
-        app,
-        GameState::Loading,
-        vec!["cosmos/sounds/sfx/break.ogg"],
-        |mut commands, mut sounds| {
-            let sound = sounds.remove(0);
-
-            commands.insert_resource(BlockBreakSound(sound.0));
-        },
-    );
-
-    load_assets::<AudioSource, LoadingPlaceAudio>(
-        app,
-        GameState::Loading,
-        vec!["cosmos/sounds/sfx/place.ogg"],
-        |mut commands, mut sounds| {
-            let sound = sounds.remove(0);
-
-            commands.insert_resource(BlockPlaceSound(sound.0));
-        },
-    );
-
     app.add_systems(
         Update,
-        (
-            play_block_place_sound.run_if(resource_exists::<BlockPlaceSound>),
-            play_block_break_sound.run_if(resource_exists::<BlockBreakSound>),
-        )
+        (play_block_place_sound, play_block_break_sound)
             .chain()
             .in_set(NetworkingSystemsSet::Between)
             .after(BlockEventsSet::SendEventsForNextFrame),
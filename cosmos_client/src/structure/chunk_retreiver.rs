@@ -6,9 +6,13 @@ use cosmos_core::netty::client::LocalPlayer;
 use cosmos_core::netty::client_reliable_messages::ClientReliableMessages;
 use cosmos_core::netty::sync::mapping::NetworkMapping;
 use cosmos_core::netty::system_sets::NetworkingSystemsSet;
-use cosmos_core::netty::{cosmos_encoder, NettyChannelClient};
+use cosmos_core::netty::{
+    network_stats::{NettyClientMessages, NettyMessage},
+    NettyChannelClient,
+};
 use cosmos_core::physics::location::{Location, SECTOR_DIMENSIONS};
 use cosmos_core::state::GameState;
+use cosmos_core::structure::loading::ChunksNeedLoaded;
 use cosmos_core::structure::Structure;
 
 #[derive(Component, Default)]
@@ -18,6 +22,34 @@ use cosmos_core::structure::Structure;
 /// chunks like planets.
 pub struct NeedsPopulated;
 
+#[derive(Component, Debug, Clone, Copy)]
+/// Stores the total number of chunks the server said it would send for this structure.
+///
+/// Combined with [`ChunksNeedLoaded`], this is used to display how far along the structure is in being streamed in.
+pub struct StructureStreamingProgress {
+    /// The total number of chunks the server will send
+    pub total_chunks: usize,
+}
+
+impl StructureStreamingProgress {
+    /// Returns the fraction [0.0, 1.0] of chunks that have been received so far
+    pub fn fraction_loaded(&self, chunks_needed: &ChunksNeedLoaded) -> f32 {
+        if self.total_chunks == 0 {
+            return 1.0;
+        }
+
+        1.0 - (chunks_needed.amount_needed as f32 / self.total_chunks as f32).min(1.0)
+    }
+}
+
+fn remove_streaming_progress(mut commands: Commands, mut removed: RemovedComponents<ChunksNeedLoaded>) {
+    for ent in removed.read() {
+        if let Some(mut ecmds) = commands.get_entity(ent) {
+            ecmds.remove::<StructureStreamingProgress>();
+        }
+    }
+}
+
 fn populate_structures(
     player_location: Query<&Location, With<LocalPlayer>>,
     query: Query<(Entity, &Location), (With<NeedsPopulated>, With<Structure>)>,
@@ -39,9 +71,9 @@ fn populate_structures(
         if let Some(server_entity) = network_mapping.server_from_client(&entity) {
             commands.entity(entity).remove::<NeedsPopulated>();
 
-            client.send_message(
+            client.send_netty_message(
                 NettyChannelClient::Reliable,
-                cosmos_encoder::serialize(&ClientReliableMessages::SendAllChunks { server_entity }),
+                NettyMessage::new(&ClientReliableMessages::SendAllChunks { server_entity }),
             );
         }
     }
@@ -50,8 +82,10 @@ fn populate_structures(
 pub(super) fn register(app: &mut App) {
     app.add_systems(
         Update,
-        populate_structures
-            .in_set(NetworkingSystemsSet::SyncComponents)
+        (
+            populate_structures.in_set(NetworkingSystemsSet::SyncComponents),
+            remove_streaming_progress,
+        )
             .run_if(in_state(GameState::LoadingWorld).or(in_state(GameState::Playing))),
     );
 }
@@ -0,0 +1,225 @@
+//! Shows the ghost blocks of blueprints being built, and lets the player weld them into real blocks with the welding tool.
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    block::{block_events::BlockEventsSet, Block},
+    ecs::NeedsDespawned,
+    inventory::{held_item_slot::HeldItemSlot, Inventory},
+    item::Item,
+    netty::{
+        client::LocalPlayer,
+        sync::{
+            events::client_event::NettyEventWriter,
+            mapping::{Mappable, NetworkMapping},
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::{Structure, StructureBlock},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        construction::{ConstructionSite, WeldGhostEvent, MAX_GHOST_WELD_DISTANCE},
+        welding::WELDING_TOOL_ITEM,
+    },
+};
+
+use crate::{
+    camera::camera_mode::PlayerEye,
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    lang::{Lang, Localization},
+    ui::{components::show_cursor::no_open_menus, font::DefaultFont},
+};
+
+/// Ghosts further than this (in blocks) from the player aren't drawn
+const GHOST_RENDER_DISTANCE: f32 = 48.0;
+
+#[derive(Resource, Debug, Default)]
+/// The ghost block the player is looking at, if it is close enough to weld
+pub(crate) struct TargetedGhost(pub Option<StructureBlock>);
+
+#[derive(Component, Debug)]
+struct ConstructionProgressText;
+
+fn holding_welding_tool(inventory: &Inventory, held_slot: &HeldItemSlot, items: &Registry<Item>) -> bool {
+    inventory
+        .itemstack_at(held_slot.slot() as usize)
+        .is_some_and(|is| items.from_numeric_id(is.item_id()).unlocalized_name() == WELDING_TOOL_ITEM)
+}
+
+fn draw_ghosts(
+    mut gizmos: Gizmos,
+    player_eye: PlayerEye,
+    q_sites: Query<(Entity, &Structure, &GlobalTransform, &ConstructionSite)>,
+    targeted: Res<TargetedGhost>,
+) {
+    let Some(cam_trans) = player_eye.global_transform() else {
+        return;
+    };
+
+    for (ent, structure, g_trans, site) in q_sites.iter() {
+        for (coords, _, _) in site.ghosts() {
+            let position = g_trans.transform_point(structure.block_relative_position(coords));
+
+            if position.distance_squared(cam_trans.translation()) > GHOST_RENDER_DISTANCE * GHOST_RENDER_DISTANCE {
+                continue;
+            }
+
+            let color = if targeted.0 == Some(StructureBlock::new(coords, ent)) {
+                Color::from(css::LIME)
+            } else {
+                Color::from(css::DEEP_SKY_BLUE).with_alpha(0.5)
+            };
+
+            gizmos.cuboid(
+                Transform::from_translation(position)
+                    .with_rotation(g_trans.rotation())
+                    .with_scale(Vec3::splat(0.9)),
+                color,
+            );
+        }
+    }
+}
+
+/// Finds the closest ghost along the player's view that isn't hidden behind a real block
+pub(crate) fn find_targeted_ghost(
+    player_eye: PlayerEye,
+    q_sites: Query<(Entity, &Structure, &GlobalTransform, &ConstructionSite)>,
+    blocks: Res<Registry<Block>>,
+    mut targeted: ResMut<TargetedGhost>,
+) {
+    targeted.0 = None;
+
+    let Some(cam_trans) = player_eye.global_transform() else {
+        return;
+    };
+
+    let mut closest: Option<(f32, StructureBlock)> = None;
+
+    for (ent, structure, g_trans, site) in q_sites.iter() {
+        let start = g_trans.affine().inverse().transform_point3(cam_trans.translation());
+        let direction = g_trans.rotation().inverse() * cam_trans.forward();
+
+        let hit = structure
+            .raycast_iter(start, direction.into(), MAX_GHOST_WELD_DISTANCE, true)
+            .take_while(|&coords| site.ghost_at(coords).is_some() || structure.block_at(coords, &blocks).is_empty())
+            .find(|&coords| site.ghost_at(coords).is_some());
+
+        let Some(coords) = hit else {
+            continue;
+        };
+
+        let distance = structure.block_relative_position(coords).distance_squared(start);
+        if closest.is_none_or(|(d, _)| distance < d) {
+            closest = Some((distance, StructureBlock::new(coords, ent)));
+        }
+    }
+
+    targeted.0 = closest.map(|(_, block)| block);
+}
+
+fn weld_targeted_ghost(
+    input_handler: InputChecker,
+    q_player: Query<(&Inventory, &HeldItemSlot), With<LocalPlayer>>,
+    items: Res<Registry<Item>>,
+    targeted: Res<TargetedGhost>,
+    network_mapping: Res<NetworkMapping>,
+    mut nevw_weld_ghost: NettyEventWriter<WeldGhostEvent>,
+) {
+    if !input_handler.check_just_pressed(CosmosInputs::PlaceBlock) {
+        return;
+    }
+
+    let Some(ghost) = targeted.0 else {
+        return;
+    };
+
+    let Ok((inventory, held_slot)) = q_player.get_single() else {
+        return;
+    };
+
+    if !holding_welding_tool(inventory, held_slot, &items) {
+        return;
+    }
+
+    let Ok(block) = ghost.map_to_server(&network_mapping) else {
+        return;
+    };
+
+    nevw_weld_ghost.send(WeldGhostEvent { block });
+}
+
+fn update_progress_text(
+    mut commands: Commands,
+    targeted: Res<TargetedGhost>,
+    q_site: Query<&ConstructionSite>,
+    mut q_text: Query<(Entity, &mut Text), With<ConstructionProgressText>>,
+    blocks: Res<Registry<Block>>,
+    lang: Res<Lang<Block>>,
+    localization: Res<Localization>,
+    font: Res<DefaultFont>,
+) {
+    let targeted = targeted
+        .0
+        .and_then(|ghost| q_site.get(ghost.structure()).ok().map(|site| (ghost, site)))
+        .and_then(|(ghost, site)| site.ghost_at(ghost.coords()).map(|(block, _)| (block, site)));
+
+    let Some((block, site)) = targeted else {
+        for (ent, _) in q_text.iter() {
+            commands.entity(ent).insert(NeedsDespawned);
+        }
+        return;
+    };
+
+    let block_name = blocks.from_id(block).and_then(|block| lang.get_name(block)).unwrap_or(block);
+
+    let text = localization.format(
+        "cosmos:hud.construction_progress",
+        &[
+            &site.blueprint(),
+            &block_name,
+            &site.built_blocks(),
+            &site.total_blocks(),
+            &((site.progress() * 100.0).floor()),
+        ],
+    );
+
+    if let Ok((_, mut existing)) = q_text.get_single_mut() {
+        existing.0 = text;
+        return;
+    }
+
+    commands.spawn((
+        Name::new("Construction Progress Text"),
+        ConstructionProgressText,
+        Text::new(text),
+        TextFont {
+            font: font.0.clone_weak(),
+            font_size: 20.0,
+            ..Default::default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(40.0),
+            right: Val::Px(10.0),
+            ..Default::default()
+        },
+    ));
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<TargetedGhost>().add_systems(
+        Update,
+        (
+            find_targeted_ghost,
+            weld_targeted_ghost
+                .run_if(no_open_menus)
+                .in_set(BlockEventsSet::SendEventsForThisFrame),
+            draw_ghosts,
+            update_progress_text,
+        )
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
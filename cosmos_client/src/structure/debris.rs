@@ -0,0 +1,150 @@
+//! Small pieces of debris that fly off of blocks destroyed by weapons & explosions.
+//!
+//! These are purely visual. A fixed number of debris entities are reused, so destroying a large
+//! amount of blocks at once won't spawn thousands of entities.
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    netty::system_sets::NetworkingSystemsSet,
+    state::GameState,
+    structure::{block_health::events::BlockTakeDamageEvent, Structure},
+};
+
+/// The most debris entities that will ever exist at once
+const MAX_DEBRIS: usize = 256;
+/// How many pieces of debris a single destroyed block creates
+const DEBRIS_PER_BLOCK: usize = 4;
+/// The most pieces of debris that can be created in one frame
+const MAX_NEW_DEBRIS_PER_FRAME: usize = 48;
+/// How long a piece of debris lasts (seconds)
+const DEBRIS_LIFETIME: f32 = 1.5;
+/// How fast debris flies away from the destroyed block (m/s)
+const DEBRIS_SPEED: f32 = 6.0;
+const DEBRIS_SIZE: f32 = 0.2;
+
+#[derive(Component, Debug, Default)]
+struct Debris {
+    velocity: Vec3,
+    angular_velocity: Vec3,
+    time_left: f32,
+}
+
+#[derive(Resource, Default)]
+/// All the debris entities that have been created, which are reused once they are no longer visible.
+struct DebrisPool {
+    entities: Vec<Entity>,
+    /// The next entity to reuse - this is always the oldest piece of debris
+    next: usize,
+    assets: Option<(Handle<Mesh>, Handle<StandardMaterial>)>,
+}
+
+fn random_vec3() -> Vec3 {
+    Vec3::new(
+        rand::random::<f32>() - 0.5,
+        rand::random::<f32>() - 0.5,
+        rand::random::<f32>() - 0.5,
+    ) * 2.0
+}
+
+fn spawn_debris(
+    mut evr_take_damage: EventReader<BlockTakeDamageEvent>,
+    q_structure: Query<(&Structure, &GlobalTransform)>,
+    mut q_debris: Query<(&mut Debris, &mut Transform, &mut Visibility)>,
+    mut pool: ResMut<DebrisPool>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut commands: Commands,
+) {
+    let mut n_created = 0;
+
+    for ev in evr_take_damage.read() {
+        // A block with no health left has been destroyed
+        if ev.new_health != 0.0 {
+            continue;
+        }
+
+        if n_created >= MAX_NEW_DEBRIS_PER_FRAME {
+            break;
+        }
+
+        let Ok((structure, g_trans)) = q_structure.get(ev.structure_entity) else {
+            continue;
+        };
+
+        let block_position = g_trans.transform_point(structure.block_relative_position(ev.block.coords()));
+
+        for _ in 0..DEBRIS_PER_BLOCK {
+            let debris = Debris {
+                velocity: random_vec3() * DEBRIS_SPEED,
+                angular_velocity: random_vec3() * 5.0,
+                time_left: DEBRIS_LIFETIME,
+            };
+            let transform = Transform::from_translation(block_position + random_vec3() * 0.3);
+
+            if pool.entities.len() < MAX_DEBRIS {
+                let (mesh, material) = pool
+                    .assets
+                    .get_or_insert_with(|| {
+                        (
+                            meshes.add(Cuboid::from_length(DEBRIS_SIZE)),
+                            materials.add(StandardMaterial {
+                                base_color: css::DARK_GRAY.into(),
+                                perceptual_roughness: 1.0,
+                                ..Default::default()
+                            }),
+                        )
+                    })
+                    .clone();
+
+                let ent = commands
+                    .spawn((Name::new("Debris"), debris, transform, Mesh3d(mesh), MeshMaterial3d(material)))
+                    .id();
+                pool.entities.push(ent);
+            } else {
+                let ent = pool.entities[pool.next];
+                pool.next = (pool.next + 1) % MAX_DEBRIS;
+
+                let Ok((mut existing, mut existing_trans, mut visibility)) = q_debris.get_mut(ent) else {
+                    continue;
+                };
+
+                *existing = debris;
+                *existing_trans = transform;
+                *visibility = Visibility::Inherited;
+            }
+
+            n_created += 1;
+        }
+    }
+}
+
+fn update_debris(time: Res<Time>, mut q_debris: Query<(&mut Debris, &mut Transform, &mut Visibility)>) {
+    let delta = time.delta_secs();
+
+    for (mut debris, mut transform, mut visibility) in q_debris.iter_mut() {
+        if debris.time_left <= 0.0 {
+            continue;
+        }
+
+        debris.time_left -= delta;
+
+        if debris.time_left <= 0.0 {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+
+        transform.translation += debris.velocity * delta;
+        transform.rotate(Quat::from_scaled_axis(debris.angular_velocity * delta));
+        transform.scale = Vec3::splat(debris.time_left / DEBRIS_LIFETIME);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<DebrisPool>().add_systems(
+        Update,
+        (spawn_debris, update_debris)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,78 @@
+//! Shows flames on burning blocks and sparks where power blocks are arcing
+
+use bevy::prelude::*;
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    netty::sync::ComponentSyncingSet,
+    registry::Registry,
+    state::GameState,
+    structure::{hazards::StructureHazards, Structure},
+};
+
+use crate::particles::ParticleEffectType;
+
+const FIRE_EFFECT: &str = "cosmos:fire";
+const ARC_EFFECT: &str = "cosmos:arc";
+
+#[derive(Component, Debug)]
+/// A fire or arc effect, which is a child of the structure the hazard is on
+struct HazardEffect;
+
+fn despawn_effects(commands: &mut Commands, children: Option<&Children>, q_effects: &Query<(), With<HazardEffect>>) {
+    for &child in children.into_iter().flatten() {
+        if q_effects.contains(child) {
+            commands.entity(child).insert(NeedsDespawned);
+        }
+    }
+}
+
+/// Hazards only change when a fire or arc starts or stops, so every effect on the structure is just recreated when they do
+fn update_hazard_effects(
+    mut commands: Commands,
+    q_changed: Query<(Entity, &Structure, &StructureHazards, Option<&Children>), Changed<StructureHazards>>,
+    q_effects: Query<(), With<HazardEffect>>,
+    particles: Res<Registry<ParticleEffectType>>,
+) {
+    let (Some(fire), Some(arc)) = (particles.from_id(FIRE_EFFECT), particles.from_id(ARC_EFFECT)) else {
+        return;
+    };
+
+    for (ent, structure, hazards, children) in q_changed.iter() {
+        despawn_effects(&mut commands, children, &q_effects);
+
+        commands.entity(ent).with_children(|p| {
+            let effects = hazards
+                .fires()
+                .map(|(coords, _)| (coords, fire))
+                .chain(hazards.arcs().map(|(coords, _)| (coords, arc)));
+
+            for (coords, effect) in effects {
+                p.spawn((
+                    HazardEffect,
+                    effect.emitter(Transform::from_translation(structure.block_relative_position(coords))),
+                ));
+            }
+        });
+    }
+}
+
+fn remove_hazard_effects(
+    mut commands: Commands,
+    mut removed: RemovedComponents<StructureHazards>,
+    q_children: Query<&Children>,
+    q_effects: Query<(), With<HazardEffect>>,
+) {
+    for ent in removed.read() {
+        despawn_effects(&mut commands, q_children.get(ent).ok(), &q_effects);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (update_hazard_effects, remove_hazard_effects)
+            .chain()
+            .in_set(ComponentSyncingSet::PostComponentSyncing)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
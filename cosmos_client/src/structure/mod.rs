@@ -6,7 +6,10 @@ pub mod asteroid;
 mod audio;
 pub mod chunk_retreiver;
 pub mod client_structure_builder;
+pub mod construction;
+mod debris;
 mod events;
+mod hazards;
 pub mod planet;
 pub mod shared;
 pub mod shields;
@@ -22,7 +25,10 @@ pub(super) fn register(app: &mut App) {
     asteroid::register(app);
     audio::register(app);
     events::register(app);
+    debris::register(app);
+    hazards::register(app);
     shared::register(app);
     shields::register(app);
     station::register(app);
+    construction::register(app);
 }
@@ -10,8 +10,12 @@ use bevy::{
 use bevy_renet2::renet2::RenetClient;
 use cosmos_core::{
     netty::{
-        client::LocalPlayer, client_reliable_messages::ClientReliableMessages, cosmos_encoder, sync::mapping::NetworkMapping,
-        system_sets::NetworkingSystemsSet, NettyChannelClient,
+        client::LocalPlayer,
+        client_reliable_messages::ClientReliableMessages,
+        network_stats::{NettyClientMessages, NettyMessage},
+        sync::mapping::NetworkMapping,
+        system_sets::NetworkingSystemsSet,
+        NettyChannelClient,
     },
     physics::location::{Location, LocationPhysicsSet},
     state::GameState,
@@ -24,6 +28,8 @@ use cosmos_core::{
     },
 };
 
+use crate::settings::PlanetRenderDistance;
+
 pub mod align_player;
 pub mod biosphere;
 pub mod client_planet_builder;
@@ -32,11 +38,6 @@ mod lods;
 mod planet_skybox;
 mod rotate_around_planet;
 
-// #[cfg(debug_assertions)]
-const RENDER_DISTANCE: UnboundCoordinateType = 2;
-// #[cfg(not(debug_assertions))]
-// const RENDER_DISTANCE: UnboundCoordinateType = 4;
-
 fn find_player_planet_location<'a>(
     q_planets: &'a mut Query<(Entity, &Location, &mut Structure, &GlobalTransform), With<Planet>>,
     player_location: &Location,
@@ -72,6 +73,7 @@ fn load_planet_chunks(
     mut q_planets: Query<(Entity, &Location, &mut Structure, &GlobalTransform), With<Planet>>,
     mapper: Res<NetworkMapping>,
     mut client: ResMut<RenetClient>,
+    render_distance: Res<PlanetRenderDistance>,
 ) {
     let Ok(player_location) = q_player_location.get_single() else {
         return;
@@ -85,19 +87,12 @@ fn load_planet_chunks(
         return;
     };
 
+    let rd = render_distance.0;
     let mut chunks = vec![];
 
     for chunk in best_planet.chunk_iter(
-        UnboundChunkCoordinate::new(
-            ub_chunk_coords.x - RENDER_DISTANCE,
-            ub_chunk_coords.y - RENDER_DISTANCE,
-            ub_chunk_coords.z - RENDER_DISTANCE,
-        ),
-        UnboundChunkCoordinate::new(
-            ub_chunk_coords.x + RENDER_DISTANCE,
-            ub_chunk_coords.y + RENDER_DISTANCE,
-            ub_chunk_coords.z + RENDER_DISTANCE,
-        ),
+        UnboundChunkCoordinate::new(ub_chunk_coords.x - rd, ub_chunk_coords.y - rd, ub_chunk_coords.z - rd),
+        UnboundChunkCoordinate::new(ub_chunk_coords.x + rd, ub_chunk_coords.y + rd, ub_chunk_coords.z + rd),
         true,
     ) {
         if let ChunkIteratorResult::EmptyChunk { position } = chunk {
@@ -110,9 +105,9 @@ fn load_planet_chunks(
     for coordinate in chunks {
         best_planet.set_chunk(Chunk::new(coordinate));
 
-        client.send_message(
+        client.send_netty_message(
             NettyChannelClient::Reliable,
-            cosmos_encoder::serialize(&ClientReliableMessages::SendSingleChunk {
+            NettyMessage::new(&ClientReliableMessages::SendSingleChunk {
                 structure_entity: server_entity,
                 chunk: coordinate,
             }),
@@ -122,12 +117,15 @@ fn load_planet_chunks(
 
 /// This system unloads chunks that are too far for a player to see.
 ///
+/// Lowering the [`PlanetRenderDistance`] will cause the newly out of range chunks to be unloaded here.
+///
 /// Put systems that mess with chunks before this.
 pub(crate) fn unload_chunks_far_from_players(
     q_player: Query<&Location, With<LocalPlayer>>,
     mut q_planets: Query<(&Location, &mut Structure, &GlobalTransform), With<Planet>>,
     mut event_writer: EventWriter<ChunkUnloadEvent>,
     mut commands: Commands,
+    render_distance: Res<PlanetRenderDistance>,
 ) {
     let Ok(player) = q_player.get_single() else {
         return;
@@ -144,7 +142,7 @@ pub(crate) fn unload_chunks_far_from_players(
 
         let ub_chunk_coords = UnboundChunkCoordinate::for_unbound_block_coordinate(ub_coords);
 
-        let rd = RENDER_DISTANCE + 1;
+        let rd = render_distance.0 + 1;
 
         let mut chunks = Vec::new();
 
@@ -14,13 +14,19 @@ use bevy_rapier3d::prelude::Velocity;
 use bevy_renet2::renet2::RenetClient;
 use cosmos_core::{
     block::block_events::BlockEventsSet,
-    netty::{client::LocalPlayer, client_reliable_messages::ClientReliableMessages, cosmos_encoder, NettyChannelClient},
+    netty::{
+        client::LocalPlayer,
+        client_reliable_messages::ClientReliableMessages,
+        network_stats::{NettyClientMessages, NettyMessage},
+        sync::events::client_event::NettyEventWriter,
+        NettyChannelClient,
+    },
     state::GameState,
     structure::{
         chunk::CHUNK_DIMENSIONSF,
         coordinates::BlockCoordinate,
         shared::{
-            build_mode::{BuildAxis, BuildMode, BuildModeSet, ExitBuildModeEvent},
+            build_mode::{BuildAxis, BuildMode, BuildModeSet, ExitBuildModeEvent, StructureTransformation, TransformStructureEvent},
             DespawnWithStructure,
         },
         Structure,
@@ -43,9 +49,9 @@ fn exit_build_mode(
     mut client: ResMut<RenetClient>,
 ) {
     if local_player_in_build_mode.get_single().is_ok() && input_handler.check_just_pressed(CosmosInputs::ToggleBuildMode) {
-        client.send_message(
+        client.send_netty_message(
             NettyChannelClient::Reliable,
-            cosmos_encoder::serialize(&ClientReliableMessages::ExitBuildMode),
+            NettyMessage::new(&ClientReliableMessages::ExitBuildMode),
         );
     }
 }
@@ -130,6 +136,10 @@ fn place_symmetries(
         return;
     };
 
+    if input_handler.check_pressed(CosmosInputs::RotateStructureFlag) || input_handler.check_pressed(CosmosInputs::MirrorStructureFlag) {
+        return;
+    }
+
     let clearing = input_handler.check_pressed(CosmosInputs::ClearSymmetry);
 
     let looking_at_block = if !clearing {
@@ -143,9 +153,9 @@ fn place_symmetries(
     }
 
     if input_handler.check_just_pressed(CosmosInputs::SymmetryX) {
-        client.send_message(
+        client.send_netty_message(
             NettyChannelClient::Reliable,
-            cosmos_encoder::serialize(&ClientReliableMessages::SetSymmetry {
+            NettyMessage::new(&ClientReliableMessages::SetSymmetry {
                 axis: BuildAxis::X,
                 coordinate: looking_at_block.map(|block| block.x()),
             }),
@@ -153,9 +163,9 @@ fn place_symmetries(
     }
 
     if input_handler.check_just_pressed(CosmosInputs::SymmetryY) {
-        client.send_message(
+        client.send_netty_message(
             NettyChannelClient::Reliable,
-            cosmos_encoder::serialize(&ClientReliableMessages::SetSymmetry {
+            NettyMessage::new(&ClientReliableMessages::SetSymmetry {
                 axis: BuildAxis::Y,
                 coordinate: looking_at_block.map(|block| block.y()),
             }),
@@ -163,9 +173,9 @@ fn place_symmetries(
     }
 
     if input_handler.check_just_pressed(CosmosInputs::SymmetryZ) {
-        client.send_message(
+        client.send_netty_message(
             NettyChannelClient::Reliable,
-            cosmos_encoder::serialize(&ClientReliableMessages::SetSymmetry {
+            NettyMessage::new(&ClientReliableMessages::SetSymmetry {
                 axis: BuildAxis::Z,
                 coordinate: looking_at_block.map(|block| block.z()),
             }),
@@ -173,6 +183,36 @@ fn place_symmetries(
     }
 }
 
+fn transform_structure(
+    input_handler: InputChecker,
+    q_in_build_mode: Query<(), (With<LocalPlayer>, With<BuildMode>)>,
+    mut nevw_transform_structure: NettyEventWriter<TransformStructureEvent>,
+) {
+    if q_in_build_mode.get_single().is_err() {
+        return;
+    }
+
+    let transformation: fn(BuildAxis) -> StructureTransformation = if input_handler.check_pressed(CosmosInputs::RotateStructureFlag) {
+        StructureTransformation::Rotate
+    } else if input_handler.check_pressed(CosmosInputs::MirrorStructureFlag) {
+        StructureTransformation::Mirror
+    } else {
+        return;
+    };
+
+    let axis = if input_handler.check_just_pressed(CosmosInputs::SymmetryX) {
+        BuildAxis::X
+    } else if input_handler.check_just_pressed(CosmosInputs::SymmetryY) {
+        BuildAxis::Y
+    } else if input_handler.check_just_pressed(CosmosInputs::SymmetryZ) {
+        BuildAxis::Z
+    } else {
+        return;
+    };
+
+    nevw_transform_structure.send(TransformStructureEvent(transformation(axis)));
+}
+
 fn clear_visuals(
     parent_query: Query<&Parent>,
     visuals_query: Query<&SymmetryVisuals>,
@@ -347,6 +387,7 @@ pub(super) fn register(app: &mut App) {
         (
             (
                 place_symmetries,
+                transform_structure,
                 exit_build_mode,
                 control_build_mode
                     .in_set(PlayerMovementSet::ProcessPlayerMovement)
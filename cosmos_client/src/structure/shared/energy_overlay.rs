@@ -0,0 +1,326 @@
+//! A build mode overlay that shows which blocks generate, store, and consume energy.
+//!
+//! Energy isn't carried by wires - every block on a structure shares one pool of energy. So rather than drawing
+//! connections, every consumer is colored by the state of that pool: red once it's empty, yellow while it's low, and
+//! orange otherwise. The text shows the pool itself, including how fast it's actually filling or draining.
+
+use bevy::{
+    color::palettes::css,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use cosmos_core::{
+    block::Block,
+    ecs::NeedsDespawned,
+    events::block_events::BlockChangedEvent,
+    netty::client::LocalPlayer,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        coordinates::BlockCoordinate,
+        shared::build_mode::BuildMode,
+        systems::{
+            energy_generation_system::EnergyGenerationSystem,
+            energy_roles::{BlockEnergyRoles, EnergyRoles},
+            energy_storage_system::EnergyStorageSystem,
+            StructureSystems,
+        },
+        Structure,
+    },
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    lang::Localization,
+    ui::{components::show_cursor::no_open_menus, font::DefaultFont},
+};
+
+/// Below this fraction of the structure's capacity, consumers are shown as running low on energy
+const LOW_ENERGY_FRACTION: f32 = 0.2;
+/// How often (in seconds) the net energy rate is measured
+const NET_RATE_SAMPLE_SECS: f32 = 1.0;
+
+#[derive(Resource, Debug, Default)]
+struct EnergyOverlay {
+    enabled: bool,
+    /// The structure the cached blocks are from
+    structure: Option<Entity>,
+    generators: HashSet<BlockCoordinate>,
+    storage: HashSet<BlockCoordinate>,
+    consumers: HashSet<BlockCoordinate>,
+    /// The energy the structure had when the net rate was last measured, and when that was
+    last_sample: Option<(f32, f32)>,
+    /// How much the structure's energy actually changed per second, after everything drew from it
+    net_rate: f32,
+}
+
+impl EnergyOverlay {
+    fn clear(&mut self) {
+        self.generators.clear();
+        self.storage.clear();
+        self.consumers.clear();
+        self.last_sample = None;
+        self.net_rate = 0.0;
+    }
+
+    fn remove(&mut self, coords: BlockCoordinate) {
+        self.generators.remove(&coords);
+        self.storage.remove(&coords);
+        self.consumers.remove(&coords);
+    }
+
+    fn add(&mut self, coords: BlockCoordinate, roles: EnergyRoles) {
+        if roles.generator {
+            self.generators.insert(coords);
+        }
+        if roles.storage {
+            self.storage.insert(coords);
+        }
+        if roles.consumer {
+            self.consumers.insert(coords);
+        }
+    }
+}
+
+#[derive(Component)]
+struct EnergyOverlayText;
+
+fn toggle_energy_overlay(
+    inputs: InputChecker,
+    q_building: Query<(), (With<LocalPlayer>, With<BuildMode>)>,
+    mut overlay: ResMut<EnergyOverlay>,
+) {
+    if q_building.is_empty() || !inputs.check_just_pressed(CosmosInputs::ToggleEnergyOverlay) {
+        return;
+    }
+
+    overlay.enabled = !overlay.enabled;
+    // Forces the blocks to be recalculated in case they changed while the overlay was off
+    overlay.structure = None;
+}
+
+/// Scans the whole structure when the overlay is shown for it, then only looks at the blocks that change after that
+fn update_energy_overlay_blocks(
+    q_building: Query<&Parent, (With<LocalPlayer>, With<BuildMode>)>,
+    q_structure: Query<&Structure>,
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    blocks: Res<Registry<Block>>,
+    energy_roles: Res<Registry<BlockEnergyRoles>>,
+    mut overlay: ResMut<EnergyOverlay>,
+) {
+    let roles_of = |block_id: u16| {
+        energy_roles
+            .from_id(blocks.from_numeric_id(block_id).unlocalized_name())
+            .map(|roles| roles.roles())
+    };
+
+    if !overlay.enabled {
+        evr_block_changed.clear();
+        return;
+    }
+
+    let structure_entity = q_building.get_single().ok().map(|p| p.get());
+
+    if overlay.structure != structure_entity {
+        // Any changes are included in the full scan
+        evr_block_changed.clear();
+
+        let overlay = overlay.as_mut();
+        overlay.structure = structure_entity;
+        overlay.clear();
+
+        let Some(structure) = structure_entity.and_then(|e| q_structure.get(e).ok()) else {
+            return;
+        };
+
+        for coords in structure.all_blocks_iter(false) {
+            if let Some(roles) = roles_of(structure.block_id_at(coords)) {
+                overlay.add(coords, roles);
+            }
+        }
+
+        return;
+    }
+
+    // Only the last change to each block matters
+    let changes = evr_block_changed
+        .read()
+        .filter(|ev| Some(ev.block.structure()) == structure_entity)
+        .map(|ev| (ev.block.coords(), ev.new_block))
+        .collect::<HashMap<_, _>>();
+
+    for (coords, new_block) in changes {
+        overlay.remove(coords);
+
+        if let Some(roles) = roles_of(new_block) {
+            overlay.add(coords, roles);
+        }
+    }
+}
+
+fn measure_net_energy_rate(
+    mut overlay: ResMut<EnergyOverlay>,
+    q_systems: Query<&StructureSystems>,
+    q_energy_storage: Query<&EnergyStorageSystem>,
+    time: Res<Time>,
+) {
+    if !overlay.enabled {
+        return;
+    }
+
+    let Some(energy) = overlay
+        .structure
+        .and_then(|e| q_systems.get(e).ok())
+        .and_then(|systems| systems.query(&q_energy_storage).ok())
+        .map(|es| es.get_energy())
+    else {
+        return;
+    };
+
+    let now = time.elapsed_secs();
+
+    match overlay.last_sample {
+        Some((last_energy, last_time)) => {
+            let elapsed = now - last_time;
+            if elapsed >= NET_RATE_SAMPLE_SECS {
+                overlay.net_rate = (energy - last_energy) / elapsed;
+                overlay.last_sample = Some((energy, now));
+            }
+        }
+        None => overlay.last_sample = Some((energy, now)),
+    }
+}
+
+fn draw_energy_overlay(
+    mut gizmos: Gizmos,
+    overlay: Res<EnergyOverlay>,
+    q_building: Query<(), (With<LocalPlayer>, With<BuildMode>)>,
+    q_structure: Query<(&Structure, &GlobalTransform, &StructureSystems)>,
+    q_energy_storage: Query<&EnergyStorageSystem>,
+) {
+    if !overlay.enabled || q_building.is_empty() {
+        return;
+    }
+
+    let Some((structure, g_trans, systems)) = overlay.structure.and_then(|e| q_structure.get(e).ok()) else {
+        return;
+    };
+
+    let (energy, capacity) = systems
+        .query(&q_energy_storage)
+        .map(|es| (es.get_energy(), es.get_capacity()))
+        .unwrap_or_default();
+
+    let mut outline = |coords: BlockCoordinate, scale: f32, color: Srgba| {
+        gizmos.cuboid(
+            Transform::from_translation(g_trans.transform_point(structure.block_relative_position(coords)))
+                .with_rotation(g_trans.rotation())
+                .with_scale(Vec3::splat(scale)),
+            color,
+        );
+    };
+
+    // Slightly different sizes so blocks with multiple roles show all of them
+    for &coords in overlay.generators.iter() {
+        outline(coords, 1.02, css::LIME);
+    }
+    for &coords in overlay.storage.iter() {
+        outline(coords, 1.06, css::AQUA);
+    }
+
+    let consumer_color = if energy <= 0.0 {
+        css::RED
+    } else if energy < capacity * LOW_ENERGY_FRACTION {
+        css::YELLOW
+    } else {
+        css::ORANGE
+    };
+
+    for &coords in overlay.consumers.iter() {
+        outline(coords, 1.1, consumer_color);
+    }
+}
+
+fn update_energy_overlay_text(
+    mut commands: Commands,
+    overlay: Res<EnergyOverlay>,
+    q_building: Query<(), (With<LocalPlayer>, With<BuildMode>)>,
+    q_systems: Query<&StructureSystems>,
+    q_energy_storage: Query<&EnergyStorageSystem>,
+    q_energy_generation: Query<&EnergyGenerationSystem>,
+    mut q_text: Query<(Entity, &mut Text), With<EnergyOverlayText>>,
+    localization: Res<Localization>,
+    font: Res<DefaultFont>,
+) {
+    let systems = overlay
+        .structure
+        .filter(|_| overlay.enabled && !q_building.is_empty())
+        .and_then(|e| q_systems.get(e).ok());
+
+    let Some(systems) = systems else {
+        for (ent, _) in q_text.iter() {
+            commands.entity(ent).insert(NeedsDespawned);
+        }
+        return;
+    };
+
+    let (energy, capacity) = systems
+        .query(&q_energy_storage)
+        .map(|es| (es.get_energy(), es.get_capacity()))
+        .unwrap_or_default();
+    let generation = systems
+        .query(&q_energy_generation)
+        .map(|eg| eg.energy_generation_rate())
+        .unwrap_or_default();
+
+    let text = localization.format(
+        "cosmos:hud.energy_overlay",
+        &[
+            &(energy as i64),
+            &(capacity as i64),
+            &(generation as i64),
+            &format!("{:+}", overlay.net_rate as i64),
+            &overlay.generators.len(),
+            &overlay.storage.len(),
+            &overlay.consumers.len(),
+        ],
+    );
+
+    if let Ok((_, mut existing)) = q_text.get_single_mut() {
+        existing.0 = text;
+        return;
+    }
+
+    commands.spawn((
+        Name::new("Energy Overlay Text"),
+        EnergyOverlayText,
+        Text::new(text),
+        TextFont {
+            font: font.0.clone_weak(),
+            font_size: 20.0,
+            ..Default::default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..Default::default()
+        },
+    ));
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<EnergyOverlay>().add_systems(
+        Update,
+        (
+            toggle_energy_overlay.run_if(no_open_menus),
+            update_energy_overlay_blocks,
+            measure_net_energy_rate,
+            draw_energy_overlay,
+            update_energy_overlay_text,
+        )
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,264 @@
+//! A debug overlay that shows the logic groups of the structure the player is on.
+//!
+//! While holding a logic wrench, the overlay draws every wire in its group's color, highlights the
+//! input (spheres) and output (cubes) ports of each group, and shows each group's current signal.
+//! Groups that are off are drawn darker than groups that are on.
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    inventory::{held_item_slot::HeldItemSlot, Inventory},
+    item::Item,
+    logic::{
+        debug::{LogicDebugSignalsEvent, LogicDebugSnapshotEvent, LogicGroupSnapshot, SetLogicDebugTargetEvent, LOGIC_WRENCH_ITEM},
+        Port,
+    },
+    netty::{
+        client::LocalPlayer,
+        sync::{
+            events::client_event::{NettyEventReceived, NettyEventWriter},
+            mapping::NetworkMapping,
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{coordinates::BlockCoordinate, Structure},
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    rendering::MainCamera,
+    ui::{components::show_cursor::no_open_menus, font::DefaultFont},
+};
+
+#[derive(Resource, Debug, Default)]
+struct LogicDebugOverlay {
+    enabled: bool,
+    /// The structure (client entity) the server was last asked to send the logic groups of
+    requested: Option<Entity>,
+    groups: Vec<LogicGroupSnapshot>,
+}
+
+#[derive(Component, Debug)]
+/// Shows the signal of the logic group with this id
+struct LogicSignalLabel(usize);
+
+fn holding_wrench(q_player: &Query<(&Inventory, &HeldItemSlot), With<LocalPlayer>>, items: &Registry<Item>) -> bool {
+    q_player.get_single().is_ok_and(|(inventory, held_slot)| {
+        inventory
+            .itemstack_at(held_slot.slot() as usize)
+            .is_some_and(|is| items.from_numeric_id(is.item_id()).unlocalized_name() == LOGIC_WRENCH_ITEM)
+    })
+}
+
+/// Every group gets its own color, spread out around the color wheel so neighboring groups are easy to tell apart.
+fn group_color(group: &LogicGroupSnapshot) -> Color {
+    let hue = (group.id as f32 * 137.508) % 360.0;
+    let lightness = if group.on() { 0.6 } else { 0.25 };
+
+    Color::hsl(hue, 0.9, lightness)
+}
+
+fn toggle_logic_debug_overlay(
+    inputs: InputChecker,
+    q_player: Query<(&Inventory, &HeldItemSlot), With<LocalPlayer>>,
+    items: Res<Registry<Item>>,
+    mut overlay: ResMut<LogicDebugOverlay>,
+) {
+    if !inputs.check_just_pressed(CosmosInputs::ToggleLogicDebugOverlay) || !holding_wrench(&q_player, &items) {
+        return;
+    }
+
+    overlay.enabled = !overlay.enabled;
+}
+
+fn update_debug_target(
+    q_player: Query<(&Inventory, &HeldItemSlot), With<LocalPlayer>>,
+    q_parent: Query<&Parent, With<LocalPlayer>>,
+    q_structure: Query<(), With<Structure>>,
+    items: Res<Registry<Item>>,
+    network_mapping: Res<NetworkMapping>,
+    mut overlay: ResMut<LogicDebugOverlay>,
+    mut nevw_set_target: NettyEventWriter<SetLogicDebugTargetEvent>,
+) {
+    let target = q_parent
+        .get_single()
+        .ok()
+        .map(|p| p.get())
+        .filter(|&e| q_structure.contains(e))
+        .filter(|_| overlay.enabled && holding_wrench(&q_player, &items));
+
+    if target == overlay.requested {
+        return;
+    }
+
+    overlay.requested = target;
+    overlay.groups.clear();
+
+    nevw_set_target.send(SetLogicDebugTargetEvent {
+        structure: target.and_then(|e| network_mapping.server_from_client(&e)),
+    });
+}
+
+fn receive_snapshots(
+    mut nevr_snapshot: EventReader<NettyEventReceived<LogicDebugSnapshotEvent>>,
+    network_mapping: Res<NetworkMapping>,
+    mut overlay: ResMut<LogicDebugOverlay>,
+) {
+    for ev in nevr_snapshot.read() {
+        let structure = network_mapping.client_from_server(&ev.structure);
+
+        if structure.is_none() || structure != overlay.requested {
+            continue;
+        }
+
+        overlay.groups = ev.groups.clone();
+    }
+}
+
+fn receive_signals(
+    mut nevr_signals: EventReader<NettyEventReceived<LogicDebugSignalsEvent>>,
+    network_mapping: Res<NetworkMapping>,
+    mut overlay: ResMut<LogicDebugOverlay>,
+) {
+    for ev in nevr_signals.read() {
+        let structure = network_mapping.client_from_server(&ev.structure);
+
+        if structure.is_none() || structure != overlay.requested {
+            continue;
+        }
+
+        for group in overlay.groups.iter_mut() {
+            if let Ok(idx) = ev.signals.binary_search_by_key(&group.id, |(id, _)| *id) {
+                group.signal = ev.signals[idx].1;
+            }
+        }
+    }
+}
+
+fn draw_logic_debug_overlay(mut gizmos: Gizmos, overlay: Res<LogicDebugOverlay>, q_structure: Query<(&Structure, &GlobalTransform)>) {
+    let Some((structure, g_trans)) = overlay.requested.and_then(|e| q_structure.get(e).ok()) else {
+        return;
+    };
+
+    let block_pos = |coords: BlockCoordinate| g_trans.transform_point(structure.block_relative_position(coords));
+    let port_pos = |port: &Port| block_pos(port.coords) + g_trans.rotation() * (port.direction.as_vec3() * 0.4);
+
+    for group in overlay.groups.iter() {
+        let color = group_color(group);
+
+        for &coords in group.wires.iter() {
+            gizmos.cuboid(
+                Transform::from_translation(block_pos(coords))
+                    .with_rotation(g_trans.rotation())
+                    .with_scale(Vec3::splat(1.04)),
+                color,
+            );
+        }
+
+        for port in group.inputs.iter() {
+            gizmos.sphere(Isometry3d::from_translation(port_pos(port)), 0.12, color);
+        }
+
+        for port in group.outputs.iter() {
+            gizmos.cuboid(
+                Transform::from_translation(port_pos(port))
+                    .with_rotation(g_trans.rotation())
+                    .with_scale(Vec3::splat(0.24)),
+                color,
+            );
+        }
+    }
+}
+
+/// Where the signal of this group is shown - above its first wire, or its first port if it has no wires.
+fn label_position(group: &LogicGroupSnapshot, structure: &Structure, g_trans: &GlobalTransform) -> Option<Vec3> {
+    let coords = group
+        .wires
+        .first()
+        .copied()
+        .or_else(|| group.outputs.first().or(group.inputs.first()).map(|p| p.coords))?;
+
+    Some(g_trans.transform_point(structure.block_relative_position(coords)) + g_trans.up() * 0.75)
+}
+
+fn update_signal_labels(
+    mut commands: Commands,
+    overlay: Res<LogicDebugOverlay>,
+    q_structure: Query<(&Structure, &GlobalTransform)>,
+    q_cam: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut q_labels: Query<(Entity, &LogicSignalLabel, &mut Text, &mut TextColor, &mut Node, &mut Visibility)>,
+    font: Res<DefaultFont>,
+) {
+    let structure = overlay.requested.and_then(|e| q_structure.get(e).ok());
+    let cam = q_cam.get_single().ok();
+
+    for (ent, label, mut text, mut text_color, mut node, mut visibility) in q_labels.iter_mut() {
+        let Some(group) = structure.and_then(|_| overlay.groups.iter().find(|g| g.id == label.0)) else {
+            commands.entity(ent).insert(NeedsDespawned);
+            continue;
+        };
+        let (Some((structure, g_trans)), Some((cam, cam_trans))) = (structure, cam) else {
+            continue;
+        };
+
+        let screen_pos = label_position(group, structure, g_trans).and_then(|pos| cam.world_to_viewport(cam_trans, pos).ok());
+
+        let Some(screen_pos) = screen_pos else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        *visibility = Visibility::Inherited;
+        node.left = Val::Px(screen_pos.x);
+        node.top = Val::Px(screen_pos.y);
+        text.0 = format!("{}", group.signal);
+        text_color.0 = if group.on() { css::WHITE.into() } else { css::GRAY.into() };
+    }
+
+    if structure.is_none() {
+        return;
+    }
+
+    for group in overlay.groups.iter() {
+        if q_labels.iter().any(|(_, label, ..)| label.0 == group.id) {
+            continue;
+        }
+
+        commands.spawn((
+            Name::new("Logic Signal Label"),
+            LogicSignalLabel(group.id),
+            Text::new(format!("{}", group.signal)),
+            TextFont {
+                font: font.0.clone_weak(),
+                font_size: 16.0,
+                ..Default::default()
+            },
+            TextColor(Color::WHITE),
+            // Positioned on the next frame, once its location on the screen is known
+            Visibility::Hidden,
+            Node {
+                position_type: PositionType::Absolute,
+                ..Default::default()
+            },
+        ));
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<LogicDebugOverlay>().add_systems(
+        Update,
+        (
+            toggle_logic_debug_overlay.run_if(no_open_menus),
+            update_debug_target,
+            receive_snapshots,
+            receive_signals,
+            draw_logic_debug_overlay,
+            update_signal_labels,
+        )
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -3,7 +3,12 @@
 use bevy::prelude::*;
 use bevy_renet2::renet2::RenetClient;
 use cosmos_core::{
-    netty::{client::LocalPlayer, client_reliable_messages::ClientReliableMessages, cosmos_encoder, NettyChannelClient},
+    netty::{
+        client::LocalPlayer,
+        client_reliable_messages::ClientReliableMessages,
+        network_stats::{NettyClientMessages, NettyMessage},
+        NettyChannelClient,
+    },
     state::GameState,
     structure::ship::{pilot::Pilot, Ship},
 };
@@ -14,6 +19,8 @@ use crate::{
 };
 
 pub mod build_mode;
+mod energy_overlay;
+mod logic_debug_overlay;
 
 fn remove_self_from_structure(
     has_parent: Query<(Entity, &Parent), (With<LocalPlayer>, Without<Pilot>)>,
@@ -27,16 +34,15 @@ fn remove_self_from_structure(
         if ship_is_parent.contains(parent.get()) && input_handler.check_just_pressed(CosmosInputs::LeaveShip) {
             commands.entity(entity).remove_parent_in_place();
 
-            renet_client.send_message(
-                NettyChannelClient::Reliable,
-                cosmos_encoder::serialize(&ClientReliableMessages::LeaveShip),
-            );
+            renet_client.send_netty_message(NettyChannelClient::Reliable, NettyMessage::new(&ClientReliableMessages::LeaveShip));
         }
     }
 }
 
 pub(super) fn register(app: &mut App) {
     build_mode::register(app);
+    energy_overlay::register(app);
+    logic_debug_overlay::register(app);
 
     app.add_systems(
         Update,
@@ -0,0 +1,69 @@
+//! Lets the pilot engage & disengage their ship's autopilot
+
+use bevy::prelude::*;
+use cosmos_core::{
+    netty::{client::LocalPlayer, sync::events::client_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    physics::location::Location,
+    state::GameState,
+    structure::ship::{
+        autopilot::{Autopilot, DisengageAutopilotEvent, EngageAutopilotEvent},
+        pilot::Pilot,
+    },
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    ui::{
+        components::show_cursor::no_open_menus,
+        ship_flight::indicators::{FocusedWaypointEntity, Indicating},
+    },
+    universe::map::waypoint::Waypoint,
+};
+
+/// Engages the autopilot towards the focused waypoint (or the map waypoint if nothing is focused),
+/// or disengages it if it's already engaged.
+fn toggle_autopilot(
+    inputs: InputChecker,
+    q_local_pilot: Query<&Pilot, With<LocalPlayer>>,
+    q_autopilot: Query<(), With<Autopilot>>,
+    q_focused: Query<&Indicating, With<FocusedWaypointEntity>>,
+    q_waypoint: Query<&Location, With<Waypoint>>,
+    q_location: Query<&Location>,
+    mut nevw_engage: NettyEventWriter<EngageAutopilotEvent>,
+    mut nevw_disengage: NettyEventWriter<DisengageAutopilotEvent>,
+) {
+    if !inputs.check_just_pressed(CosmosInputs::ToggleAutopilot) {
+        return;
+    }
+
+    let Ok(pilot) = q_local_pilot.get_single() else {
+        return;
+    };
+
+    if q_autopilot.contains(pilot.entity) {
+        nevw_disengage.send(DisengageAutopilotEvent);
+        return;
+    }
+
+    let destination = q_focused
+        .get_single()
+        .ok()
+        .and_then(|indicating| q_location.get(indicating.0).ok())
+        .or_else(|| q_waypoint.get_single().ok());
+
+    let Some(&destination) = destination else {
+        return;
+    };
+
+    nevw_engage.send(EngageAutopilotEvent { destination });
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        toggle_autopilot
+            .run_if(no_open_menus)
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -2,16 +2,13 @@
 
 use bevy::{
     ecs::system::EntityCommands,
-    prelude::{
-        resource_exists, Added, App, BuildChildren, ChildBuild, Commands, Entity, Handle, IntoSystemConfigs, Name, Query, Res, Resource,
-        Transform, Update,
-    },
+    prelude::{Added, App, BuildChildren, ChildBuild, Commands, Entity, IntoSystemConfigs, Name, Query, Res, Transform, Update},
 };
-use bevy_kira_audio::{Audio, AudioControl, AudioInstance, AudioSource};
+use bevy_kira_audio::Audio;
 use bevy_rapier3d::prelude::Velocity;
 use cosmos_core::{
     physics::location::Location,
-    state::GameState,
+    registry::Registry,
     structure::{
         loading::StructureLoadingSet,
         shared::DespawnWithStructure,
@@ -24,8 +21,7 @@ use cosmos_core::{
 };
 
 use crate::{
-    asset::asset_loader::load_assets,
-    audio::{AudioEmission, CosmosAudioEmitter},
+    audio::{sound_effects::SoundEffect, CosmosAudioEmitter},
     structure::{chunk_retreiver::NeedsPopulated, client_structure_builder::ClientStructureBuilder},
 };
 
@@ -50,50 +46,31 @@ impl TShipBuilder for ClientShipBuilder {
 
 fn client_on_add_ship(
     query: Query<Entity, Added<Ship>>,
-    engine_idle_sound: Res<EngineIdleSound>,
+    sound_effects: Res<Registry<SoundEffect>>,
     audio: Res<Audio>,
     mut commands: Commands,
 ) {
+    let idle_sound = sound_effects.from_id("cosmos:engine_idle");
+
     for entity in query.iter() {
-        let playing_sound: Handle<AudioInstance> = audio.play(engine_idle_sound.0.clone()).with_volume(0.0).looped().handle();
+        let mut ecmds = commands.entity(entity);
+        ecmds.insert(NeedsPopulated);
 
-        let idle_emitter = CosmosAudioEmitter::with_emissions(vec![AudioEmission {
-            instance: playing_sound,
-            max_distance: 20.0,
-            peak_volume: 0.15 * 5.0,
-            ..Default::default()
-        }]);
+        let Some(emission) = idle_sound.and_then(|sound| sound.play_looped(&audio, Default::default())) else {
+            continue;
+        };
 
-        commands.entity(entity).insert(NeedsPopulated).with_children(|p| {
+        ecmds.with_children(|p| {
             p.spawn((
                 Name::new("Engine idle sound"),
                 DespawnWithStructure,
                 Transform::from_xyz(0.5, 0.5, 0.5),
-                idle_emitter,
+                CosmosAudioEmitter::with_emissions(vec![emission]),
             ));
         });
     }
 }
 
-#[derive(Resource)]
-struct EngineIdleSound(Handle<AudioSource>);
-
-struct LoadingShipAudio;
-
 pub(super) fn register(app: &mut App) {
-    load_assets::<AudioSource, LoadingShipAudio>(
-        app,
-        GameState::PreLoading,
-        vec!["cosmos/sounds/sfx/engine-idle.ogg"],
-        |mut commands, mut handles| {
-            commands.insert_resource(EngineIdleSound(handles.remove(0).0));
-        },
-    );
-
-    app.add_systems(
-        Update,
-        client_on_add_ship
-            .in_set(StructureLoadingSet::AddStructureComponents)
-            .run_if(resource_exists::<EngineIdleSound>),
-    );
+    app.add_systems(Update, client_on_add_ship.in_set(StructureLoadingSet::AddStructureComponents));
 }
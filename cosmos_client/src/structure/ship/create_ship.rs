@@ -11,7 +11,10 @@ use cosmos_core::{
     inventory::Inventory,
     item::Item,
     netty::{
-        client::LocalPlayer, client_reliable_messages::ClientReliableMessages, cosmos_encoder, system_sets::NetworkingSystemsSet,
+        client::LocalPlayer,
+        client_reliable_messages::ClientReliableMessages,
+        network_stats::{NettyClientMessages, NettyMessage},
+        system_sets::NetworkingSystemsSet,
         NettyChannelClient,
     },
     registry::Registry,
@@ -59,9 +62,9 @@ fn listener(
 fn event_handler(mut event_reader: EventReader<CreateShipEvent>, mut client: ResMut<RenetClient>) {
     for ev in event_reader.read() {
         info!("Got create ship event!");
-        client.send_message(
+        client.send_netty_message(
             NettyChannelClient::Reliable,
-            cosmos_encoder::serialize(&ClientReliableMessages::CreateShip { name: ev.name.clone() }),
+            NettyMessage::new(&ClientReliableMessages::CreateShip { name: ev.name.clone() }),
         );
     }
 }
@@ -0,0 +1,232 @@
+//! Lets the pilot hail the ship or station they have focused
+
+use bevy::{a11y::Focus, color::palettes::css, prelude::*};
+use cosmos_core::{
+    chat::comms::{SendHailEvent, MAX_HAIL_LENGTH},
+    ecs::NeedsDespawned,
+    netty::{
+        client::LocalPlayer,
+        sync::{events::client_event::NettyEventWriter, mapping::NetworkMapping},
+        system_sets::NetworkingSystemsSet,
+    },
+    state::GameState,
+    structure::{
+        ship::{pilot::Pilot, Ship},
+        station::Station,
+        structure_name::StructureName,
+    },
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    lang::Localization,
+    rendering::MainCamera,
+    ui::{
+        components::{
+            button::{register_button, Button, ButtonEvent, ButtonStyles},
+            show_cursor::no_open_menus,
+            text_input::{InputType, InputValue, TextInput},
+            window::GuiWindow,
+        },
+        font::DefaultFont,
+        ship_flight::indicators::{FocusedWaypointEntity, Indicating},
+        OpenMenu, UiSystemSet,
+    },
+};
+
+#[derive(Component, Debug)]
+/// The structure (client entity) being hailed
+struct OpenHailPrompt(Entity);
+
+#[derive(Component, Debug)]
+struct HailMessageInput;
+
+#[derive(Event, Debug)]
+struct SendHailClicked;
+
+impl ButtonEvent for SendHailClicked {
+    fn create_event(_: Entity) -> Self {
+        Self
+    }
+}
+
+fn open_hail_prompt(
+    mut commands: Commands,
+    inputs: InputChecker,
+    q_local_pilot: Query<&Pilot, With<LocalPlayer>>,
+    q_focused: Query<&Indicating, With<FocusedWaypointEntity>>,
+    q_hailable: Query<(), Or<(With<Ship>, With<Station>)>>,
+) {
+    if !inputs.check_just_pressed(CosmosInputs::HailTarget) {
+        return;
+    }
+
+    let Ok(pilot) = q_local_pilot.get_single() else {
+        return;
+    };
+
+    let Some(target) = q_focused
+        .get_single()
+        .ok()
+        .map(|indicating| indicating.0)
+        .filter(|&e| e != pilot.entity && q_hailable.contains(e))
+    else {
+        return;
+    };
+
+    commands.spawn((OpenHailPrompt(target), Name::new("Open Hail Prompt")));
+}
+
+fn populate_hail_prompt(
+    mut commands: Commands,
+    q_added_prompt: Query<(Entity, &OpenHailPrompt), Added<OpenHailPrompt>>,
+    q_structure: Query<(Option<&StructureName>, Has<Station>)>,
+    q_cam: Query<Entity, With<MainCamera>>,
+    font: Res<DefaultFont>,
+    localization: Res<Localization>,
+    mut focus: ResMut<Focus>,
+) {
+    for (ent, prompt) in q_added_prompt.iter() {
+        let Ok(cam) = q_cam.get_single() else {
+            return;
+        };
+
+        let target_name = match q_structure.get(prompt.0) {
+            Ok((Some(name), _)) => name.name().to_owned(),
+            Ok((None, true)) => "Unnamed Station".into(),
+            _ => "Unnamed Ship".into(),
+        };
+
+        let text_style = TextFont {
+            font: font.0.clone_weak(),
+            font_size: 24.0,
+            ..Default::default()
+        };
+
+        let mut ecmds = commands.entity(ent);
+
+        ecmds.insert((
+            TargetCamera(cam),
+            OpenMenu::new(0),
+            BackgroundColor(Srgba::hex("2D2D2D").unwrap().into()),
+            Node {
+                width: Val::Px(600.0),
+                margin: UiRect::all(Val::Auto),
+                ..Default::default()
+            },
+            GuiWindow {
+                title: localization.get("cosmos:window.hail").into(),
+                body_styles: Node {
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(20.0)),
+                    ..Default::default()
+                },
+            },
+        ));
+
+        ecmds.with_children(|p| {
+            p.spawn((
+                Name::new("Hail Target"),
+                Text::new(target_name),
+                text_style.clone(),
+                Node {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..Default::default()
+                },
+            ));
+
+            let input_ent = p
+                .spawn((
+                    HailMessageInput,
+                    text_style.clone(),
+                    TextInput {
+                        input_type: InputType::Text {
+                            max_length: Some(MAX_HAIL_LENGTH),
+                        },
+                        ..Default::default()
+                    },
+                    BorderColor(Srgba::hex("555555").unwrap().into()),
+                    BackgroundColor(Srgba::hex("111111").unwrap().into()),
+                    Node {
+                        border: UiRect::all(Val::Px(2.0)),
+                        width: Val::Percent(100.0),
+                        min_height: Val::Px(45.0),
+                        padding: UiRect::all(Val::Px(4.0)),
+                        ..Default::default()
+                    },
+                ))
+                .id();
+
+            focus.0 = Some(input_ent);
+
+            p.spawn((
+                Name::new("Send Hail Button"),
+                Node {
+                    height: Val::Px(50.0),
+                    margin: UiRect::top(Val::Px(20.0)),
+                    ..Default::default()
+                },
+                Button::<SendHailClicked> {
+                    button_styles: Some(ButtonStyles {
+                        background_color: Srgba::hex("555555").unwrap().into(),
+                        hover_background_color: Srgba::hex("777777").unwrap().into(),
+                        press_background_color: Srgba::hex("333333").unwrap().into(),
+                        foreground_color: css::WHITE.into(),
+                        hover_foreground_color: css::WHITE.into(),
+                        press_foreground_color: css::WHITE.into(),
+                    }),
+                    text: Some(("Send".into(), text_style, Default::default())),
+                    ..Default::default()
+                },
+            ));
+        });
+    }
+}
+
+fn on_send_hail(
+    mut commands: Commands,
+    mut evr_send: EventReader<SendHailClicked>,
+    q_open_prompt: Query<(Entity, &OpenHailPrompt)>,
+    q_input: Query<&InputValue, With<HailMessageInput>>,
+    mut nevw_send_hail: NettyEventWriter<SendHailEvent>,
+    network_mapping: Res<NetworkMapping>,
+) {
+    if evr_send.read().next().is_none() {
+        return;
+    }
+
+    let Ok((ent, prompt)) = q_open_prompt.get_single() else {
+        return;
+    };
+
+    let Ok(input) = q_input.get_single() else {
+        return;
+    };
+
+    let message = input.value().trim();
+
+    if !message.is_empty() {
+        if let Some(target) = network_mapping.server_from_client(&prompt.0) {
+            nevw_send_hail.send(SendHailEvent {
+                target,
+                message: message.to_owned(),
+            });
+        }
+    }
+
+    commands.entity(ent).insert(NeedsDespawned);
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<SendHailClicked>(app);
+
+    app.add_systems(
+        Update,
+        (
+            open_hail_prompt.run_if(no_open_menus).in_set(NetworkingSystemsSet::Between),
+            (populate_hail_prompt, on_send_hail).chain().in_set(UiSystemSet::DoUi),
+        )
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    );
+}
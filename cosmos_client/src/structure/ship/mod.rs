@@ -11,7 +11,10 @@ use bevy_rapier3d::pipeline::CollisionEvent;
 use bevy_renet2::renet2::RenetClient;
 use cosmos_core::{
     netty::{
-        client::LocalPlayer, client_reliable_messages::ClientReliableMessages, cosmos_encoder, system_sets::NetworkingSystemsSet,
+        client::LocalPlayer,
+        client_reliable_messages::ClientReliableMessages,
+        network_stats::{NettyClientMessages, NettyMessage},
+        system_sets::NetworkingSystemsSet,
         NettyChannelClient,
     },
     physics::location::{CosmosBundleSet, Location, LocationPhysicsSet},
@@ -22,8 +25,10 @@ use cosmos_core::{
     },
 };
 
+mod autopilot;
 pub mod client_ship_builder;
 pub mod create_ship;
+mod hail;
 pub mod ship_movement;
 pub mod ui;
 
@@ -85,10 +90,7 @@ fn respond_to_collisions(
         // Otherwise just remove the parent if they hit a different structure
         commands.entity(player_entity).remove_parent_in_place();
 
-        renet_client.send_message(
-            NettyChannelClient::Reliable,
-            cosmos_encoder::serialize(&ClientReliableMessages::LeaveShip),
-        );
+        renet_client.send_netty_message(NettyChannelClient::Reliable, NettyMessage::new(&ClientReliableMessages::LeaveShip));
     }
 }
 
@@ -107,10 +109,7 @@ fn remove_parent_when_too_far(
             if player_loc.distance_sqrd(structure_loc).sqrt() >= CHUNK_DIMENSIONSF * 10.0 {
                 commands.entity(player_entity).remove_parent_in_place();
 
-                renet_client.send_message(
-                    NettyChannelClient::Reliable,
-                    cosmos_encoder::serialize(&ClientReliableMessages::LeaveShip),
-                );
+                renet_client.send_netty_message(NettyChannelClient::Reliable, NettyMessage::new(&ClientReliableMessages::LeaveShip));
             }
         }
     }
@@ -124,6 +123,8 @@ pub enum PlayerParentChangingSet {
 }
 
 pub(super) fn register(app: &mut App) {
+    autopilot::register(app);
+    hail::register(app);
     client_ship_builder::register(app);
     ship_movement::register(app);
     create_ship::register(app);
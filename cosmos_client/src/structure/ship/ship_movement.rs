@@ -8,14 +8,20 @@ use bevy_renet2::renet2::RenetClient;
 use cosmos_core::netty::client::LocalPlayer;
 use cosmos_core::netty::client_reliable_messages::ClientReliableMessages;
 use cosmos_core::netty::client_unreliable_messages::ClientUnreliableMessages;
+use cosmos_core::netty::sync::events::client_event::NettyEventWriter;
 use cosmos_core::netty::system_sets::NetworkingSystemsSet;
-use cosmos_core::netty::{cosmos_encoder, NettyChannelClient};
+use cosmos_core::netty::{
+    network_stats::{NettyClientMessages, NettyMessage},
+    NettyChannelClient,
+};
 use cosmos_core::state::GameState;
 use cosmos_core::structure::shared::build_mode::BuildMode;
+use cosmos_core::structure::ship::flight_assist::{FlightAssistMode, SetFlightAssistModeEvent};
 use cosmos_core::structure::ship::pilot::Pilot;
 use cosmos_core::structure::ship::ship_movement::ShipMovement;
 use cosmos_core::structure::systems::dock_system::Docked;
 
+use crate::camera::camera_mode::CameraMode;
 use crate::input::inputs::{CosmosInputs, InputChecker, InputHandler};
 use crate::rendering::MainCamera;
 use crate::settings::MouseSensitivity;
@@ -35,6 +41,7 @@ fn process_ship_movement(
     primary_query: Query<&Window, With<PrimaryWindow>>,
     cursor_flags: Res<CursorFlags>,
     mouse_sensitivity: Res<MouseSensitivity>,
+    camera_mode: Res<CameraMode>,
 ) {
     let Ok(pilot) = q_local_pilot.get_single() else {
         return;
@@ -44,7 +51,8 @@ fn process_ship_movement(
         return;
     };
 
-    let cursor_delta_position = if cursor_flags.is_cursor_locked() {
+    // While orbiting, the mouse is moving the camera instead of steering the ship
+    let cursor_delta_position = if cursor_flags.is_cursor_locked() && !camera_mode.is_orbiting(&input_handler) {
         *cursor_delta_position
     } else {
         DeltaCursorPosition::default()
@@ -97,9 +105,9 @@ fn process_ship_movement(
     movement.braking = input_handler.check_pressed(CosmosInputs::SlowDown);
 
     if input_handler.check_just_pressed(CosmosInputs::StopPiloting) {
-        client.send_message(
+        client.send_netty_message(
             NettyChannelClient::Reliable,
-            cosmos_encoder::serialize(&ClientReliableMessages::StopPiloting),
+            NettyMessage::new(&ClientReliableMessages::StopPiloting),
         );
     }
 
@@ -144,12 +152,31 @@ fn process_ship_movement(
         ));
     }
 
-    client.send_message(
+    client.send_netty_message(
         NettyChannelClient::Unreliable,
-        cosmos_encoder::serialize(&ClientUnreliableMessages::SetMovement { movement }),
+        NettyMessage::new(&ClientUnreliableMessages::SetMovement { movement }),
     );
 }
 
+fn toggle_flight_assist(
+    input_handler: InputChecker,
+    q_local_pilot: Query<&Pilot, (With<LocalPlayer>, Without<BuildMode>)>,
+    q_flight_assist: Query<&FlightAssistMode>,
+    mut nevw_set_flight_assist: NettyEventWriter<SetFlightAssistModeEvent>,
+) {
+    if !input_handler.check_just_pressed(CosmosInputs::ToggleFlightAssist) {
+        return;
+    }
+
+    let Ok(pilot) = q_local_pilot.get_single() else {
+        return;
+    };
+
+    let current_mode = q_flight_assist.get(pilot.entity).copied().unwrap_or_default();
+
+    nevw_set_flight_assist.send(SetFlightAssistModeEvent(current_mode.next()));
+}
+
 fn reset_cursor(
     local_player_without_pilot: Query<(), (With<LocalPlayer>, Without<Pilot>)>,
     mut crosshair_position: ResMut<CrosshairOffset>,
@@ -172,7 +199,7 @@ pub(super) fn register(app: &mut App) {
 
     app.add_systems(
         Update,
-        (reset_cursor, process_ship_movement)
+        (reset_cursor, process_ship_movement, toggle_flight_assist)
             .after(UiSystemSet::FinishUi)
             .run_if(no_open_menus)
             .in_set(NetworkingSystemsSet::Between)
@@ -0,0 +1,290 @@
+//! The menu pilots use to choose which systems each slot of their flight hotbar activates
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    item::Item,
+    netty::{client::LocalPlayer, sync::events::client_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        ship::pilot::Pilot,
+        systems::{
+            activation_groups::{ActivationGroups, SetActivationGroupEvent, N_ACTIVATION_GROUPS},
+            StructureSystemType, StructureSystems,
+        },
+    },
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    lang::{Lang, Localization},
+    rendering::MainCamera,
+    ui::{
+        components::{
+            button::{register_button, Button, ButtonEvent, ButtonStyles},
+            show_cursor::no_open_menus,
+            window::GuiWindow,
+        },
+        font::DefaultFont,
+        OpenMenu, UiSystemSet,
+    },
+};
+
+#[derive(Component, Debug)]
+struct OpenActivationGroupsMenu;
+
+#[derive(Component, Debug)]
+struct ActivationGroupsContents;
+
+#[derive(Component, Debug)]
+struct GroupToggle {
+    system_type: String,
+    group: u32,
+    in_group: bool,
+}
+
+#[derive(Event, Debug)]
+struct GroupToggleClicked(Entity);
+
+impl ButtonEvent for GroupToggleClicked {
+    fn create_event(entity: Entity) -> Self {
+        Self(entity)
+    }
+}
+
+fn button_styles(in_group: bool) -> ButtonStyles {
+    let (background, hover, press) = if in_group {
+        ("3B7A57", "4E9A6F", "2C5C41")
+    } else {
+        ("555555", "777777", "333333")
+    };
+
+    ButtonStyles {
+        background_color: Srgba::hex(background).unwrap().into(),
+        hover_background_color: Srgba::hex(hover).unwrap().into(),
+        press_background_color: Srgba::hex(press).unwrap().into(),
+        foreground_color: css::WHITE.into(),
+        hover_foreground_color: css::WHITE.into(),
+        press_foreground_color: css::WHITE.into(),
+    }
+}
+
+fn open_activation_groups(
+    mut commands: Commands,
+    inputs: InputChecker,
+    q_open: Query<(), With<OpenActivationGroupsMenu>>,
+    q_pilot: Query<(), (With<LocalPlayer>, With<Pilot>)>,
+) {
+    if !inputs.check_just_pressed(CosmosInputs::OpenActivationGroups) {
+        return;
+    }
+
+    if q_pilot.is_empty() || !q_open.is_empty() {
+        return;
+    }
+
+    commands.spawn((OpenActivationGroupsMenu, Name::new("Open Activation Groups")));
+}
+
+/// The groups only make sense while flying the ship they're for
+fn close_when_not_piloting(
+    mut commands: Commands,
+    q_open: Query<Entity, With<OpenActivationGroupsMenu>>,
+    q_pilot: Query<(), (With<LocalPlayer>, With<Pilot>)>,
+) {
+    if !q_pilot.is_empty() {
+        return;
+    }
+
+    for ent in q_open.iter() {
+        commands.entity(ent).insert(NeedsDespawned);
+    }
+}
+
+fn create_activation_groups_window(
+    mut commands: Commands,
+    q_added: Query<Entity, Added<OpenActivationGroupsMenu>>,
+    q_cam: Query<Entity, With<MainCamera>>,
+    localization: Res<Localization>,
+) {
+    for ent in q_added.iter() {
+        let Ok(cam) = q_cam.get_single() else {
+            return;
+        };
+
+        commands
+            .entity(ent)
+            .insert((
+                TargetCamera(cam),
+                OpenMenu::new(0),
+                BackgroundColor(Srgba::hex("2D2D2D").unwrap().into()),
+                Node {
+                    width: Val::Px(700.0),
+                    margin: UiRect::all(Val::Auto),
+                    ..Default::default()
+                },
+                GuiWindow {
+                    title: localization.get("cosmos:window.activation_groups").into(),
+                    body_styles: Node {
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(20.0)),
+                        ..Default::default()
+                    },
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    Name::new("Activation groups contents"),
+                    ActivationGroupsContents,
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(8.0),
+                        ..Default::default()
+                    },
+                ));
+            });
+    }
+}
+
+fn populate_activation_groups_window(
+    mut commands: Commands,
+    q_contents: Query<Entity, With<ActivationGroupsContents>>,
+    q_added_contents: Query<(), Added<ActivationGroupsContents>>,
+    q_pilot: Query<&Pilot, With<LocalPlayer>>,
+    q_ship: Query<(Ref<StructureSystems>, Option<Ref<ActivationGroups>>)>,
+    system_types: Res<Registry<StructureSystemType>>,
+    items: Res<Registry<Item>>,
+    names: Res<Lang<Item>>,
+    font: Res<DefaultFont>,
+) {
+    let (Ok(contents_ent), Ok(pilot)) = (q_contents.get_single(), q_pilot.get_single()) else {
+        return;
+    };
+
+    let Ok((systems, groups)) = q_ship.get(pilot.entity) else {
+        return;
+    };
+
+    if q_added_contents.is_empty() && !systems.is_changed() && !groups.as_ref().is_some_and(|g| g.is_changed()) {
+        return;
+    }
+
+    // Ships that haven't been configured yet use the default groups
+    let groups = groups.map(|g| (*g).clone()).unwrap_or_else(|| systems.default_activation_groups());
+
+    let text_style = TextFont {
+        font: font.0.clone_weak(),
+        font_size: 20.0,
+        ..Default::default()
+    };
+
+    let mut system_type_names = systems.activatable_system_types().map(|x| x.to_owned()).collect::<Vec<_>>();
+    // A ship could have multiple systems of the same type, but groups work by type
+    system_type_names.sort();
+    system_type_names.dedup();
+
+    let mut ecmds = commands.entity(contents_ent);
+    ecmds.despawn_descendants();
+
+    ecmds.with_children(|p| {
+        if system_type_names.is_empty() {
+            p.spawn((
+                Text::new("This ship has no systems that can be activated"),
+                text_style.clone(),
+                TextColor(css::GRAY.into()),
+            ));
+        }
+
+        for system_type_name in system_type_names {
+            // Systems are named after the item used as their icon
+            let display_name = system_types
+                .from_id(&system_type_name)
+                .map(|t| items.from_numeric_id(t.item_icon_id()).unlocalized_name())
+                .and_then(|item| names.get_name_from_id(item))
+                .unwrap_or(&system_type_name)
+                .to_owned();
+
+            p.spawn((
+                Name::new("System activation groups"),
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    justify_content: JustifyContent::SpaceBetween,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((Text::new(display_name), text_style.clone()));
+
+                p.spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(4.0),
+                    ..Default::default()
+                })
+                .with_children(|p| {
+                    for group in 0..N_ACTIVATION_GROUPS {
+                        let in_group = groups.contains(group, &system_type_name);
+
+                        p.spawn((
+                            Name::new("Activation group toggle"),
+                            GroupToggle {
+                                system_type: system_type_name.clone(),
+                                group: group as u32,
+                                in_group,
+                            },
+                            Node {
+                                width: Val::Px(36.0),
+                                height: Val::Px(36.0),
+                                ..Default::default()
+                            },
+                            Button::<GroupToggleClicked> {
+                                button_styles: Some(button_styles(in_group)),
+                                text: Some((format!("{}", group + 1), text_style.clone(), Default::default())),
+                                ..Default::default()
+                            },
+                        ));
+                    }
+                });
+            });
+        }
+    });
+}
+
+fn on_toggle_clicked(
+    mut evr_clicked: EventReader<GroupToggleClicked>,
+    q_toggle: Query<&GroupToggle>,
+    mut nevw_set_group: NettyEventWriter<SetActivationGroupEvent>,
+) {
+    for ev in evr_clicked.read() {
+        let Ok(toggle) = q_toggle.get(ev.0) else {
+            continue;
+        };
+
+        nevw_set_group.send(SetActivationGroupEvent {
+            system_type: toggle.system_type.clone(),
+            group: toggle.group,
+            in_group: !toggle.in_group,
+        });
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<GroupToggleClicked>(app);
+
+    app.add_systems(
+        Update,
+        (
+            (open_activation_groups.run_if(no_open_menus), close_when_not_piloting).in_set(NetworkingSystemsSet::Between),
+            (
+                create_activation_groups_window,
+                populate_activation_groups_window,
+                on_toggle_clicked,
+            )
+                .chain()
+                .in_set(UiSystemSet::DoUi),
+        )
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    );
+}
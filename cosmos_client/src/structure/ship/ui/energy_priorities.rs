@@ -0,0 +1,247 @@
+//! The menu pilots use to choose which of their ship's systems keep getting energy when it runs low
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    netty::{client::LocalPlayer, sync::events::client_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    state::GameState,
+    structure::{
+        ship::pilot::Pilot,
+        systems::energy_priority::{EnergyBrownout, EnergyConsumer, EnergyPriorities, RaiseEnergyPriorityEvent},
+    },
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    lang::Localization,
+    rendering::MainCamera,
+    ui::{
+        components::{
+            button::{register_button, Button, ButtonEvent},
+            show_cursor::no_open_menus,
+            window::GuiWindow,
+        },
+        font::DefaultFont,
+        OpenMenu, UiSystemSet,
+    },
+};
+
+#[derive(Component, Debug)]
+struct OpenEnergyPrioritiesMenu;
+
+#[derive(Component, Debug)]
+struct EnergyPrioritiesContents;
+
+#[derive(Component, Debug)]
+struct RaiseButton(EnergyConsumer);
+
+#[derive(Event, Debug)]
+struct RaiseClicked(Entity);
+
+impl ButtonEvent for RaiseClicked {
+    fn create_event(entity: Entity) -> Self {
+        Self(entity)
+    }
+}
+
+fn open_energy_priorities(
+    mut commands: Commands,
+    inputs: InputChecker,
+    q_open: Query<(), With<OpenEnergyPrioritiesMenu>>,
+    q_pilot: Query<(), (With<LocalPlayer>, With<Pilot>)>,
+) {
+    if !inputs.check_just_pressed(CosmosInputs::OpenEnergyPriorities) {
+        return;
+    }
+
+    if q_pilot.is_empty() || !q_open.is_empty() {
+        return;
+    }
+
+    commands.spawn((OpenEnergyPrioritiesMenu, Name::new("Open Energy Priorities")));
+}
+
+/// The priorities can only be changed by the ship's pilot
+fn close_when_not_piloting(
+    mut commands: Commands,
+    q_open: Query<Entity, With<OpenEnergyPrioritiesMenu>>,
+    q_pilot: Query<(), (With<LocalPlayer>, With<Pilot>)>,
+) {
+    if !q_pilot.is_empty() {
+        return;
+    }
+
+    for ent in q_open.iter() {
+        commands.entity(ent).insert(NeedsDespawned);
+    }
+}
+
+fn create_energy_priorities_window(
+    mut commands: Commands,
+    q_added: Query<Entity, Added<OpenEnergyPrioritiesMenu>>,
+    q_cam: Query<Entity, With<MainCamera>>,
+    localization: Res<Localization>,
+) {
+    for ent in q_added.iter() {
+        let Ok(cam) = q_cam.get_single() else {
+            return;
+        };
+
+        commands
+            .entity(ent)
+            .insert((
+                TargetCamera(cam),
+                OpenMenu::new(0),
+                BackgroundColor(Srgba::hex("2D2D2D").unwrap().into()),
+                Node {
+                    width: Val::Px(500.0),
+                    margin: UiRect::all(Val::Auto),
+                    ..Default::default()
+                },
+                GuiWindow {
+                    title: localization.get("cosmos:window.energy_priorities").into(),
+                    body_styles: Node {
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(20.0)),
+                        ..Default::default()
+                    },
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    Name::new("Energy priorities contents"),
+                    EnergyPrioritiesContents,
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(8.0),
+                        ..Default::default()
+                    },
+                ));
+            });
+    }
+}
+
+fn populate_energy_priorities_window(
+    mut commands: Commands,
+    q_contents: Query<Entity, With<EnergyPrioritiesContents>>,
+    q_added_contents: Query<(), Added<EnergyPrioritiesContents>>,
+    q_pilot: Query<&Pilot, With<LocalPlayer>>,
+    q_ship: Query<(Option<Ref<EnergyPriorities>>, Option<Ref<EnergyBrownout>>)>,
+    localization: Res<Localization>,
+    font: Res<DefaultFont>,
+) {
+    let (Ok(contents_ent), Ok(pilot)) = (q_contents.get_single(), q_pilot.get_single()) else {
+        return;
+    };
+
+    let Ok((priorities, brownout)) = q_ship.get(pilot.entity) else {
+        return;
+    };
+
+    if q_added_contents.is_empty()
+        && !priorities.as_ref().is_some_and(|p| p.is_changed())
+        && !brownout.as_ref().is_some_and(|b| b.is_changed())
+    {
+        return;
+    }
+
+    // Ships that haven't been configured yet use the default order
+    let priorities = priorities.map(|p| (*p).clone()).unwrap_or_default();
+
+    let text_style = TextFont {
+        font: font.0.clone_weak(),
+        font_size: 20.0,
+        ..Default::default()
+    };
+
+    let mut ecmds = commands.entity(contents_ent);
+    ecmds.despawn_descendants();
+
+    ecmds.with_children(|p| {
+        for (rank, &consumer) in priorities.order().iter().enumerate() {
+            let browned_out = brownout.as_ref().is_some_and(|b| b.contains(consumer));
+
+            p.spawn((
+                Name::new("Energy priority"),
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    justify_content: JustifyContent::SpaceBetween,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    Text::new(format!("{}. {}", rank + 1, localization.get(consumer.unlocalized_name()))),
+                    text_style.clone(),
+                ));
+
+                p.spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(12.0),
+                    ..Default::default()
+                })
+                .with_children(|p| {
+                    let (status, color) = if browned_out {
+                        ("cosmos:energy_priorities.browned_out", css::RED)
+                    } else {
+                        ("cosmos:energy_priorities.powered", css::LIGHT_GREEN)
+                    };
+
+                    p.spawn((Text::new(localization.get(status)), text_style.clone(), TextColor(color.into())));
+
+                    if rank != 0 {
+                        p.spawn((
+                            Name::new("Raise priority button"),
+                            RaiseButton(consumer),
+                            Node {
+                                width: Val::Px(80.0),
+                                height: Val::Px(36.0),
+                                ..Default::default()
+                            },
+                            Button::<RaiseClicked> {
+                                text: Some((
+                                    localization.get("cosmos:energy_priorities.raise").into(),
+                                    text_style.clone(),
+                                    Default::default(),
+                                )),
+                                ..Default::default()
+                            },
+                        ));
+                    }
+                });
+            });
+        }
+    });
+}
+
+fn on_raise_clicked(
+    mut evr_clicked: EventReader<RaiseClicked>,
+    q_button: Query<&RaiseButton>,
+    mut nevw_raise: NettyEventWriter<RaiseEnergyPriorityEvent>,
+) {
+    for ev in evr_clicked.read() {
+        let Ok(button) = q_button.get(ev.0) else {
+            continue;
+        };
+
+        nevw_raise.send(RaiseEnergyPriorityEvent { consumer: button.0 });
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<RaiseClicked>(app);
+
+    app.add_systems(
+        Update,
+        (
+            (open_energy_priorities.run_if(no_open_menus), close_when_not_piloting).in_set(NetworkingSystemsSet::Between),
+            (create_energy_priorities_window, populate_energy_priorities_window, on_raise_clicked)
+                .chain()
+                .in_set(UiSystemSet::DoUi),
+        )
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -2,8 +2,12 @@
 
 use bevy::app::App;
 
+mod activation_groups;
+mod energy_priorities;
 pub mod system_selection;
 
 pub(super) fn register(app: &mut App) {
     system_selection::register(app);
+    activation_groups::register(app);
+    energy_priorities::register(app);
 }
@@ -20,7 +20,7 @@ use cosmos_core::{
     state::GameState,
     structure::{
         ship::pilot::Pilot,
-        systems::{StructureSystem, StructureSystemType, StructureSystems},
+        systems::{activation_groups::ActivationGroups, StructureSystem, StructureSystemType, StructureSystems},
     },
 };
 
@@ -64,10 +64,11 @@ fn add_priority_when_flying(
     }
 }
 
+/// Each hotbar slot shows an activation group, using the icon of the first system in it
 fn sync_ship_systems(
-    q_systems: Query<&StructureSystems>,
+    q_systems: Query<(&StructureSystems, Option<&ActivationGroups>)>,
     q_piloting: Query<&Pilot, With<LocalPlayer>>,
-    q_systems_changed: Query<(), Changed<StructureSystems>>,
+    q_systems_changed: Query<(), Or<(Changed<StructureSystems>, Changed<ActivationGroups>)>>,
     q_priority_changed: Query<(), (Changed<HotbarPriorityQueue>, With<LocalPlayerHotbar>)>,
     q_structure_system: Query<&StructureSystem>,
     structure_system_types: Res<Registry<StructureSystemType>>,
@@ -92,17 +93,18 @@ fn sync_ship_systems(
         return;
     }
 
-    let Ok(ship_systems) = q_systems.get(piloting.entity) else {
+    let Ok((ship_systems, groups)) = q_systems.get(piloting.entity) else {
         return;
     };
 
-    let n_slots = hotbar_contents.n_slots();
-    let mut slot = 0;
+    let n_slots = hotbar_contents.n_slots().min(ship_systems.n_activation_groups(groups));
 
     hotbar_contents.clear_contents(Some(&mut commands));
 
-    for system_ent in ship_systems.all_activatable_systems() {
-        let Ok(system) = q_structure_system.get(system_ent) else {
+    for slot in 0..n_slots {
+        let group_systems = ship_systems.activation_group_systems(slot as u32, groups);
+
+        let Some(system) = group_systems.first().and_then(|&ent| q_structure_system.get(ent).ok()) else {
             continue;
         };
 
@@ -114,19 +116,14 @@ fn sync_ship_systems(
             slot,
             Some(ItemStack::with_quantity(
                 item,
-                1,
+                // Shows how many systems this group activates
+                group_systems.len().min(u16::MAX as usize) as u16,
                 // TODO: Make this hotbar use an actual inventory so this isn't meaningless
                 (Entity::PLACEHOLDER, 0),
                 &mut commands,
                 &has_data,
             )),
         );
-
-        slot += 1;
-
-        if slot == n_slots {
-            break;
-        }
     }
 }
 
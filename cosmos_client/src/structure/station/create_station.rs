@@ -3,7 +3,12 @@
 use bevy::prelude::{in_state, App, Event, EventReader, EventWriter, IntoSystemConfigs, Query, ResMut, Update, With};
 use bevy_renet2::renet2::RenetClient;
 use cosmos_core::{
-    netty::{client::LocalPlayer, client_reliable_messages::ClientReliableMessages, cosmos_encoder, NettyChannelClient},
+    netty::{
+        client::LocalPlayer,
+        client_reliable_messages::ClientReliableMessages,
+        network_stats::{NettyClientMessages, NettyMessage},
+        NettyChannelClient,
+    },
     state::GameState,
     structure::shared::build_mode::BuildMode,
 };
@@ -36,9 +41,9 @@ fn listener(
 
 fn event_handler(mut event_reader: EventReader<CreateStationEvent>, mut client: ResMut<RenetClient>) {
     for ev in event_reader.read() {
-        client.send_message(
+        client.send_netty_message(
             NettyChannelClient::Reliable,
-            cosmos_encoder::serialize(&ClientReliableMessages::CreateStation { name: ev.name.clone() }),
+            NettyMessage::new(&ClientReliableMessages::CreateStation { name: ev.name.clone() }),
         );
     }
 }
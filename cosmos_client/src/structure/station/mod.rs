@@ -4,8 +4,10 @@ use bevy::app::App;
 
 pub mod client_station_builder;
 pub mod create_station;
+pub mod prefab;
 
 pub(super) fn register(app: &mut App) {
     client_station_builder::register(app);
     create_station::register(app);
+    prefab::register(app);
 }
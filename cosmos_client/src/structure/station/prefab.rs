@@ -0,0 +1,229 @@
+//! Lets players in build mode pick a station prefab & attach it to a station connector.
+//!
+//! While a prefab is selected, an outline of where it will go is shown over the connector being looked at.
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    block::{block_face::BlockFace, block_rotation::BlockRotation, Block},
+    ecs::NeedsDespawned,
+    netty::{
+        client::LocalPlayer,
+        sync::{
+            events::client_event::NettyEventWriter,
+            mapping::{Mappable, NetworkMapping},
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        coordinates::{BlockCoordinate, UnboundBlockCoordinate},
+        shared::build_mode::BuildMode,
+        station::{
+            prefab::{rotate_offset, PlaceStationPrefabEvent, StationPrefab, STATION_CONNECTOR_BLOCK},
+            Station,
+        },
+        Structure,
+    },
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    interactions::block_interactions::{process_player_interaction, LookingAt},
+    lang::Localization,
+    ui::{components::show_cursor::no_open_menus, font::DefaultFont, theme::UiTheme},
+};
+
+#[derive(Resource, Debug, Default, Clone, Copy)]
+/// The station prefab the player will place when they click on a station connector in build mode.
+///
+/// While this is `Some`, normal block placing is disabled.
+pub struct SelectedStationPrefab(pub Option<u16>);
+
+#[derive(Component)]
+struct SelectedPrefabText;
+
+fn cycle_prefab(
+    inputs: InputChecker,
+    prefabs: Res<Registry<StationPrefab>>,
+    mut selected: ResMut<SelectedStationPrefab>,
+    q_building: Query<(), (With<LocalPlayer>, With<BuildMode>)>,
+) {
+    if q_building.is_empty() {
+        if selected.0.is_some() {
+            selected.0 = None;
+        }
+        return;
+    }
+
+    if !inputs.check_just_pressed(CosmosInputs::CycleStationPrefab) {
+        return;
+    }
+
+    let ids = prefabs.iter().map(|x| x.id()).collect::<Vec<_>>();
+
+    // Cycles through every prefab, then back to placing normal blocks
+    selected.0 = match selected.0 {
+        None => ids.first().copied(),
+        Some(id) => ids.iter().position(|x| *x == id).and_then(|idx| ids.get(idx + 1)).copied(),
+    };
+}
+
+/// Returns the station connector the player is looking at
+fn looked_at_connector<'a>(
+    looking_at: &LookingAt,
+    q_station: &'a Query<(&Structure, &GlobalTransform), With<Station>>,
+    blocks: &Registry<Block>,
+) -> Option<(BlockCoordinate, &'a Structure, &'a GlobalTransform)> {
+    let block = looking_at.looking_at_block?.block;
+    let (structure, g_trans) = q_station.get(block.structure()).ok()?;
+
+    if structure.block_at(block.coords(), blocks).unlocalized_name() != STATION_CONNECTOR_BLOCK {
+        return None;
+    }
+
+    Some((block.coords(), structure, g_trans))
+}
+
+fn preview_prefab(
+    mut gizmos: Gizmos,
+    selected: Res<SelectedStationPrefab>,
+    prefabs: Res<Registry<StationPrefab>>,
+    blocks: Res<Registry<Block>>,
+    q_looking_at: Query<&LookingAt, With<LocalPlayer>>,
+    q_station: Query<(&Structure, &GlobalTransform), With<Station>>,
+) {
+    let Some(prefab) = selected.0.and_then(|id| prefabs.try_from_numeric_id(id)) else {
+        return;
+    };
+
+    let Ok(looking_at) = q_looking_at.get_single() else {
+        return;
+    };
+
+    let Some((connector, structure, g_trans)) = looked_at_connector(looking_at, &q_station, &blocks) else {
+        return;
+    };
+
+    let color = if prefab.compute_placement(structure, connector, &blocks).is_ok() {
+        css::LIME
+    } else {
+        css::RED
+    };
+
+    // Even if it can't be placed, the outline still shows where it would go
+    let facing = structure.block_rotation(connector).direction_of(BlockFace::Front);
+    let rotation = BlockRotation::face_front(facing);
+    let origin = connector + facing.to_coordinates();
+
+    for region in prefab.regions().iter().filter(|r| r.block != "cosmos:air") {
+        let a = origin + rotate_offset(region.from, rotation);
+        let b = origin + rotate_offset(region.to, rotation);
+
+        let min = UnboundBlockCoordinate::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z));
+        let max = UnboundBlockCoordinate::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z));
+
+        let (Ok(min), Ok(max)) = (BlockCoordinate::try_from(min), BlockCoordinate::try_from(max)) else {
+            continue;
+        };
+
+        let center = (structure.block_relative_position(min) + structure.block_relative_position(max)) / 2.0;
+        let size = Vec3::new((max.x - min.x + 1) as f32, (max.y - min.y + 1) as f32, (max.z - min.z + 1) as f32);
+
+        gizmos.cuboid(
+            Transform::from_translation(g_trans.transform_point(center))
+                .with_rotation(g_trans.rotation())
+                .with_scale(size),
+            color,
+        );
+    }
+}
+
+fn place_prefab(
+    inputs: InputChecker,
+    selected: Res<SelectedStationPrefab>,
+    q_looking_at: Query<&LookingAt, With<LocalPlayer>>,
+    q_station: Query<(&Structure, &GlobalTransform), With<Station>>,
+    blocks: Res<Registry<Block>>,
+    network_mapping: Res<NetworkMapping>,
+    mut nevw_place_prefab: NettyEventWriter<PlaceStationPrefabEvent>,
+) {
+    if !inputs.check_just_pressed(CosmosInputs::PlaceBlock) {
+        return;
+    }
+
+    let Some(prefab_id) = selected.0 else {
+        return;
+    };
+
+    let Ok(looking_at) = q_looking_at.get_single() else {
+        return;
+    };
+
+    if looked_at_connector(looking_at, &q_station, &blocks).is_none() {
+        return;
+    }
+
+    let Some(Ok(connector)) = looking_at.looking_at_block.map(|x| x.block.map_to_server(&network_mapping)) else {
+        return;
+    };
+
+    // The server will tell us if this can't be placed
+    nevw_place_prefab.send(PlaceStationPrefabEvent { connector, prefab_id });
+}
+
+fn update_selected_text(
+    mut commands: Commands,
+    selected: Res<SelectedStationPrefab>,
+    prefabs: Res<Registry<StationPrefab>>,
+    localization: Res<Localization>,
+    default_font: Res<DefaultFont>,
+    theme: Res<UiTheme>,
+    q_text: Query<Entity, With<SelectedPrefabText>>,
+) {
+    for ent in q_text.iter() {
+        commands.entity(ent).insert(NeedsDespawned);
+    }
+
+    let Some(prefab) = selected.0.and_then(|id| prefabs.try_from_numeric_id(id)) else {
+        return;
+    };
+
+    let name = localization.get(prefab.unlocalized_name());
+
+    commands
+        .spawn((
+            Name::new("Selected station prefab text"),
+            SelectedPrefabText,
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(120.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new(localization.format("cosmos:station_prefab_selected", &[&name])),
+                theme.font(default_font.0.clone(), 20.0),
+                TextColor(theme.text),
+            ));
+        });
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<SelectedStationPrefab>().add_systems(
+        Update,
+        (
+            cycle_prefab.run_if(no_open_menus),
+            update_selected_text.run_if(resource_changed::<SelectedStationPrefab>),
+            preview_prefab,
+            place_prefab.run_if(no_open_menus),
+        )
+            .chain()
+            .after(process_player_interaction)
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,67 @@
+//! Client-side cloaking device logic
+//!
+//! Cloaked structures are hidden from everyone except the players aboard them, who see them as mostly see-through instead.
+
+use bevy::prelude::*;
+use cosmos_core::{
+    netty::{client::LocalPlayer, system_sets::NetworkingSystemsSet},
+    state::GameState,
+    structure::{
+        systems::cloaking_system::{is_aboard, Cloaked, CloakingSystem},
+        Structure,
+    },
+};
+
+use super::sync::sync_system;
+
+#[derive(Component)]
+/// A cloaked structure the local player is aboard. Instead of being hidden, it is rendered see-through.
+pub struct SeeThroughCloak;
+
+#[derive(Component)]
+/// A cloaked structure that is hidden from the local player
+struct HiddenByCloak;
+
+fn update_cloaked_visibility(
+    mut commands: Commands,
+    q_local_player: Query<Entity, With<LocalPlayer>>,
+    q_cloaked: Query<(Entity, Has<SeeThroughCloak>, Has<HiddenByCloak>), (With<Cloaked>, With<Structure>)>,
+    q_decloaked: Query<Entity, (Or<(With<SeeThroughCloak>, With<HiddenByCloak>)>, Without<Cloaked>)>,
+    mut q_visibility: Query<&mut Visibility>,
+    q_parent: Query<&Parent>,
+) {
+    for ent in q_decloaked.iter() {
+        commands.entity(ent).remove::<(SeeThroughCloak, HiddenByCloak)>();
+
+        if let Ok(mut visibility) = q_visibility.get_mut(ent) {
+            visibility.set_if_neq(Visibility::Inherited);
+        }
+    }
+
+    let local_player = q_local_player.get_single().ok();
+
+    for (ent, see_through, hidden) in q_cloaked.iter() {
+        let aboard = local_player.is_some_and(|player| is_aboard(player, ent, &q_parent));
+
+        if aboard && !see_through {
+            commands.entity(ent).remove::<HiddenByCloak>().insert(SeeThroughCloak);
+        } else if !aboard && !hidden {
+            commands.entity(ent).remove::<SeeThroughCloak>().insert(HiddenByCloak);
+        }
+
+        if let Ok(mut visibility) = q_visibility.get_mut(ent) {
+            visibility.set_if_neq(if aboard { Visibility::Inherited } else { Visibility::Hidden });
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_system::<CloakingSystem>(app);
+
+    app.add_systems(
+        Update,
+        update_cloaked_visibility
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
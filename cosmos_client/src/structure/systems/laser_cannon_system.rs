@@ -1,17 +1,19 @@
 //! Client-side laser cannon system logic
 
-use bevy::{asset::LoadState, prelude::*};
+use bevy::prelude::*;
 use bevy_kira_audio::prelude::*;
 use cosmos_core::{
-    netty::system_sets::NetworkingSystemsSet,
+    netty::{client::LocalPlayer, system_sets::NetworkingSystemsSet},
     physics::location::{Location, LocationPhysicsSet},
+    projectiles::laser::{LaserCollideEvent, LaserSystemSet},
+    registry::Registry,
     state::GameState,
-    structure::systems::laser_cannon_system::LaserCannonSystem,
+    structure::{ship::pilot::Pilot, systems::laser_cannon_system::LaserCannonSystem},
 };
 
 use crate::{
-    asset::asset_loader::load_assets,
-    audio::{AudioEmission, CosmosAudioEmitter, DespawnOnNoEmissions},
+    audio::{sound_effects::SoundEffect, CosmosAudioEmitter, DespawnOnNoEmissions},
+    camera::camera_shake::CameraShake,
 };
 
 use super::sync::sync_system;
@@ -20,16 +22,17 @@ use super::sync::sync_system;
 /// This event is fired whenever a laser cannon system is fired
 pub struct LaserCannonSystemFiredEvent(pub Entity);
 
-#[derive(Resource)]
-struct LaserCannonFireHandles(Vec<Handle<bevy_kira_audio::prelude::AudioSource>>);
-
 fn apply_shooting_sound(
     query: Query<(&Location, &GlobalTransform)>,
     mut commands: Commands,
     audio: Res<Audio>,
-    audio_handles: Res<LaserCannonFireHandles>,
+    sound_effects: Res<Registry<SoundEffect>>,
     mut event_reader: EventReader<LaserCannonSystemFiredEvent>,
 ) {
+    let Some(laser_sound) = sound_effects.from_id("cosmos:laser_fire") else {
+        return;
+    };
+
     for entity in event_reader.read() {
         let Ok((ship_location, ship_global_transform)) = query.get(entity.0) else {
             continue;
@@ -38,20 +41,12 @@ fn apply_shooting_sound(
         let location = *ship_location;
         let translation = ship_global_transform.translation();
 
-        let idx = rand::random::<usize>() % audio_handles.0.len();
-
-        let handle = audio_handles.0[idx].clone_weak();
-
-        let playing_sound: Handle<AudioInstance> = audio.play(handle.clone_weak()).handle();
+        let Some(emission) = laser_sound.play(&audio) else {
+            continue;
+        };
 
         commands.spawn((
-            CosmosAudioEmitter {
-                emissions: vec![AudioEmission {
-                    instance: playing_sound,
-                    handle,
-                    ..Default::default()
-                }],
-            },
+            CosmosAudioEmitter::with_emissions(vec![emission]),
             DespawnOnNoEmissions,
             location,
             Transform::from_translation(translation),
@@ -59,34 +54,45 @@ fn apply_shooting_sound(
     }
 }
 
-struct LaserCannonLoadingFlag;
+/// How much the camera shakes when the pilot fires their ship's lasers
+const FIRE_TRAUMA: f32 = 0.25;
+/// Lasers this strong hitting the pilot's ship shake the camera as much as it can be
+const MAX_SHAKE_LASER_STRENGTH: f32 = 300.0;
+
+fn shake_pilot_camera(
+    q_pilot: Query<&Pilot, With<LocalPlayer>>,
+    mut evr_fired: EventReader<LaserCannonSystemFiredEvent>,
+    mut evr_laser_collide: EventReader<LaserCollideEvent>,
+    mut shake: ResMut<CameraShake>,
+) {
+    let Ok(pilot) = q_pilot.get_single() else {
+        evr_fired.clear();
+        evr_laser_collide.clear();
+        return;
+    };
+
+    for ev in evr_fired.read() {
+        if ev.0 == pilot.entity {
+            shake.add_trauma(FIRE_TRAUMA);
+        }
+    }
+
+    for ev in evr_laser_collide.read() {
+        if ev.entity_hit() == pilot.entity {
+            shake.add_trauma((ev.laser_strength() / MAX_SHAKE_LASER_STRENGTH).clamp(0.1, 1.0));
+        }
+    }
+}
 
 pub(super) fn register(app: &mut App) {
     sync_system::<LaserCannonSystem>(app);
 
-    load_assets::<bevy_kira_audio::prelude::AudioSource, LaserCannonLoadingFlag>(
-        app,
-        GameState::PreLoading,
-        vec![
-            "cosmos/sounds/sfx/laser-fire-1.ogg",
-            "cosmos/sounds/sfx/laser-fire-2.ogg",
-            "cosmos/sounds/sfx/laser-fire-3.ogg",
-        ],
-        |mut commands, handles| {
-            commands.insert_resource(LaserCannonFireHandles(
-                handles
-                    .into_iter()
-                    .filter(|x| matches!(x.1, LoadState::Loaded))
-                    .map(|x| x.0)
-                    .collect(),
-            ));
-        },
-    );
-
     app.add_event::<LaserCannonSystemFiredEvent>().add_systems(
         Update,
-        apply_shooting_sound
-            .after(LocationPhysicsSet::DoPhysics)
+        (
+            apply_shooting_sound.after(LocationPhysicsSet::DoPhysics),
+            shake_pilot_camera.after(LaserSystemSet::SendHitEvents),
+        )
             .in_set(NetworkingSystemsSet::Between)
             .run_if(in_state(GameState::Playing)),
     );
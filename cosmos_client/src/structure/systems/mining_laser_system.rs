@@ -1,7 +1,6 @@
 //! Client-side laser cannon system logic
 
 use bevy::{
-    asset::LoadState,
     pbr::{NotShadowCaster, NotShadowReceiver},
     prelude::*,
     utils::HashMap,
@@ -15,6 +14,7 @@ use bevy_rapier3d::{
 use cosmos_core::{
     block::block_direction::BlockDirection,
     ecs::NeedsDespawned,
+    registry::Registry,
     state::GameState,
     structure::{
         shared::DespawnWithStructure,
@@ -27,10 +27,7 @@ use cosmos_core::{
     },
 };
 
-use crate::{
-    asset::asset_loader::load_assets,
-    audio::{AudioEmission, CosmosAudioEmitter, DespawnOnNoEmissions},
-};
+use crate::audio::{sound_effects::SoundEffect, CosmosAudioEmitter, DespawnOnNoEmissions};
 
 use super::sync::sync_system;
 
@@ -43,9 +40,6 @@ const BEAM_MAX_RANGE: f32 = 250.0;
 /// This event is fired whenever a laser cannon system is fired
 pub struct LaserCannonSystemFiredEvent(pub Entity);
 
-#[derive(Resource)]
-struct LaserCannonFireHandles(Vec<Handle<bevy_kira_audio::prelude::AudioSource>>);
-
 #[derive(Resource)]
 struct MiningLaserMesh(Handle<Mesh>);
 
@@ -100,7 +94,7 @@ fn apply_mining_effects(
     q_energy_storage_system: Query<&EnergyStorageSystem>,
     mut commands: Commands,
     audio: Res<Audio>,
-    audio_handles: Res<LaserCannonFireHandles>,
+    sound_effects: Res<Registry<SoundEffect>>,
 
     q_structure: Query<(&Structure, &RapierContextEntityLink)>,
     mut materials_cache: ResMut<MiningLaserMaterialCache>,
@@ -135,25 +129,17 @@ fn apply_mining_effects(
             continue;
         }
 
-        let idx = rand::random::<usize>() % audio_handles.0.len();
-
-        let handle = audio_handles.0[idx].clone_weak();
-        let playing_sound: Handle<AudioInstance> = audio.play(handle.clone_weak()).handle();
-
-        commands.entity(structure_entity).with_children(|p| {
-            p.spawn((
-                CosmosAudioEmitter {
-                    emissions: vec![AudioEmission {
-                        instance: playing_sound,
-                        peak_volume: 0.3,
-                        handle,
-                        ..Default::default()
-                    }],
-                },
-                DespawnOnNoEmissions,
-                Transform::from_xyz(0.5, 0.5, 1.0),
-            ));
-        });
+        if let Some(mut emission) = sound_effects.from_id("cosmos:laser_fire").and_then(|sound| sound.play(&audio)) {
+            emission.peak_volume = 0.3;
+
+            commands.entity(structure_entity).with_children(|p| {
+                p.spawn((
+                    CosmosAudioEmitter::with_emissions(vec![emission]),
+                    DespawnOnNoEmissions,
+                    Transform::from_xyz(0.5, 0.5, 1.0),
+                ));
+            });
+        }
 
         let mut active_beams = Vec::with_capacity(mining_laser_system.lines.len());
 
@@ -280,8 +266,6 @@ fn rotate_mining_lasers(time: Res<Time>, mut q_transforms: Query<(&mut Transform
     }
 }
 
-struct LaserCannonLoadingFlag;
-
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 enum LasersSystemSet {
     CreateLasers,
@@ -291,25 +275,6 @@ enum LasersSystemSet {
 pub(super) fn register(app: &mut App) {
     sync_system::<MiningLaserSystem>(app);
 
-    load_assets::<bevy_kira_audio::prelude::AudioSource, LaserCannonLoadingFlag>(
-        app,
-        GameState::PreLoading,
-        vec![
-            "cosmos/sounds/sfx/laser-fire-1.ogg",
-            "cosmos/sounds/sfx/laser-fire-2.ogg",
-            "cosmos/sounds/sfx/laser-fire-3.ogg",
-        ],
-        |mut commands, handles| {
-            commands.insert_resource(LaserCannonFireHandles(
-                handles
-                    .into_iter()
-                    .filter(|x| matches!(x.1, LoadState::Loaded))
-                    .map(|x| x.0)
-                    .collect(),
-            ));
-        },
-    );
-
     app.configure_sets(Update, (LasersSystemSet::CreateLasers, LasersSystemSet::UpdateLasers).chain());
 
     app.add_event::<LaserCannonSystemFiredEvent>()
@@ -1,15 +1,17 @@
 //! Client-side laser cannon system logic
 
-use bevy::{asset::LoadState, prelude::*};
+use bevy::prelude::*;
 use bevy_kira_audio::prelude::*;
 use cosmos_core::{
     ecs::NeedsDespawned,
     netty::{client::LocalPlayer, sync::mapping::NetworkMapping, system_sets::NetworkingSystemsSet},
     physics::location::{Location, LocationPhysicsSet},
+    registry::Registry,
     state::GameState,
     structure::{
         ship::pilot::Pilot,
         systems::{
+            activation_groups::ActivationGroups,
             missile_launcher_system::{MissileLauncherFocus, MissileLauncherPreferredFocus, MissileLauncherSystem},
             StructureSystems,
         },
@@ -18,7 +20,7 @@ use cosmos_core::{
 
 use crate::{
     asset::asset_loader::load_assets,
-    audio::{AudioEmission, CosmosAudioEmitter, DespawnOnNoEmissions},
+    audio::{sound_effects::SoundEffect, CosmosAudioEmitter, DespawnOnNoEmissions},
     ui::ship_flight::indicators::{FocusedWaypointEntity, Indicating},
 };
 
@@ -31,9 +33,6 @@ use super::{
 /// This event is fired whenever a laser cannon system is fired
 pub struct MissileLauncherSystemFiredEvent(pub Entity);
 
-#[derive(Resource)]
-struct MissileLauncherFireHandles(Vec<Handle<bevy_kira_audio::prelude::AudioSource>>);
-
 #[derive(Resource)]
 struct MissileLauncherLockonGraphic(Handle<Image>);
 
@@ -41,9 +40,13 @@ fn apply_shooting_sound(
     query: Query<(&Location, &GlobalTransform)>,
     mut commands: Commands,
     audio: Res<Audio>,
-    audio_handles: Res<MissileLauncherFireHandles>,
+    sound_effects: Res<Registry<SoundEffect>>,
     mut event_reader: EventReader<MissileLauncherSystemFiredEvent>,
 ) {
+    let Some(launch_sound) = sound_effects.from_id("cosmos:missile_launch") else {
+        return;
+    };
+
     for entity in event_reader.read() {
         let Ok((ship_location, ship_global_transform)) = query.get(entity.0) else {
             continue;
@@ -52,20 +55,12 @@ fn apply_shooting_sound(
         let location = *ship_location;
         let translation = ship_global_transform.translation();
 
-        let idx = rand::random::<usize>() % audio_handles.0.len();
-
-        let handle = audio_handles.0[idx].clone_weak();
-
-        let playing_sound: Handle<AudioInstance> = audio.play(handle.clone_weak()).handle();
+        let Some(emission) = launch_sound.play(&audio) else {
+            continue;
+        };
 
         commands.spawn((
-            CosmosAudioEmitter {
-                emissions: vec![AudioEmission {
-                    instance: playing_sound,
-                    handle,
-                    ..Default::default()
-                }],
-            },
+            CosmosAudioEmitter::with_emissions(vec![emission]),
             DespawnOnNoEmissions,
             location,
             Transform::from_translation(translation),
@@ -136,7 +131,7 @@ fn render_lockon_status(
     mut commands: Commands,
     lockon_graphic: Res<MissileLauncherLockonGraphic>,
     q_piloting: Query<(&HoveredSystem, &Pilot), With<LocalPlayer>>,
-    q_systems: Query<&StructureSystems>,
+    q_systems: Query<(&StructureSystems, Option<&ActivationGroups>)>,
     q_missile_focus: Query<&MissileLauncherFocus>,
     q_missile_focus_ui: Query<(Entity, &MissileFocusUi)>,
     mut q_style: Query<(&mut Node, &mut ImageNode)>,
@@ -151,7 +146,7 @@ fn render_lockon_status(
         return;
     };
 
-    let Ok(systems) = q_systems.get(piloting.entity) else {
+    let Ok((systems, groups)) = q_systems.get(piloting.entity) else {
         if let Ok((ent, _)) = focus_ui {
             commands.entity(ent).insert(NeedsDespawned);
         }
@@ -159,8 +154,9 @@ fn render_lockon_status(
     };
 
     let Some(missile_focus) = systems
-        .try_get_activatable_system_from_activatable_index(hovered_system.hovered_system_index)
-        .and_then(|x| q_missile_focus.get(x).ok())
+        .activation_group_systems(hovered_system.hovered_system_index as u32, groups)
+        .into_iter()
+        .find_map(|x| q_missile_focus.get(x).ok())
     else {
         if let Ok((ent, _)) = focus_ui {
             commands.entity(ent).insert(NeedsDespawned);
@@ -335,21 +331,6 @@ fn update_corner_styles(q_style: &mut Query<(&mut Node, &mut ImageNode)>, entity
 pub(super) fn register(app: &mut App) {
     sync_system::<MissileLauncherSystem>(app);
 
-    load_assets::<bevy_kira_audio::prelude::AudioSource, MissileLauncherFireHandles>(
-        app,
-        GameState::PreLoading,
-        vec!["cosmos/sounds/sfx/missile-launch-1.ogg", "cosmos/sounds/sfx/missile-launch-2.ogg"],
-        |mut commands, handles| {
-            commands.insert_resource(MissileLauncherFireHandles(
-                handles
-                    .into_iter()
-                    .filter(|x| matches!(x.1, LoadState::Loaded))
-                    .map(|x| x.0)
-                    .collect(),
-            ));
-        },
-    );
-
     load_assets::<Image, MissileLauncherLockonGraphic>(
         app,
         GameState::PreLoading,
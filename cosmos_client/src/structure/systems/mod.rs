@@ -1,6 +1,7 @@
 //! Client-side ship systems logic
 
 mod camera_system;
+pub mod cloaking_system;
 mod dock_system;
 mod energy_generation_system;
 mod energy_storage_system;
@@ -11,6 +12,7 @@ pub mod player_interactions;
 mod shield_system;
 mod sync;
 pub mod thruster_system;
+mod turret_system;
 
 use bevy::prelude::App;
 
@@ -20,10 +22,12 @@ pub(super) fn register(app: &mut App) {
     shield_system::register(app);
     thruster_system::register(app);
     camera_system::register(app);
+    cloaking_system::register(app);
     laser_cannon_system::register(app);
     mining_laser_system::register(app);
     energy_generation_system::register(app);
     energy_storage_system::register(app);
     missile_launcher_system::register(app);
+    turret_system::register(app);
     sync::register(app);
 }
@@ -9,7 +9,10 @@ use bevy::{
 use bevy_renet2::renet2::RenetClient;
 use cosmos_core::{
     netty::{
-        client::LocalPlayer, client_unreliable_messages::ClientUnreliableMessages, cosmos_encoder, system_sets::NetworkingSystemsSet,
+        client::LocalPlayer,
+        client_unreliable_messages::ClientUnreliableMessages,
+        network_stats::{NettyClientMessages, NettyMessage},
+        system_sets::NetworkingSystemsSet,
         NettyChannelClient,
     },
     state::GameState,
@@ -22,11 +25,11 @@ use crate::{
 };
 
 #[derive(Component, Default, Reflect)]
-/// Contains the structure system's information currently hovered by the player
+/// Contains the activation group currently hovered by the player
 pub struct HoveredSystem {
-    /// The index of the system, relative to the `active_systems` iterator
+    /// The index of the activation group. See [`cosmos_core::structure::systems::activation_groups::ActivationGroups`].
     pub hovered_system_index: usize,
-    /// If the hovered system is active
+    /// If the hovered group is active
     pub active: bool,
 }
 
@@ -47,9 +50,9 @@ fn check_system_in_use(
         ShipActiveSystem::Hovered(hovered_system.hovered_system_index as u32)
     };
 
-    client.send_message(
+    client.send_netty_message(
         NettyChannelClient::Unreliable,
-        cosmos_encoder::serialize(&ClientUnreliableMessages::ShipActiveSystem(active_system)),
+        NettyMessage::new(&ClientUnreliableMessages::ShipActiveSystem(active_system)),
     );
 }
 
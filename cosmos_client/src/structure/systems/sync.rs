@@ -18,8 +18,8 @@ use bevy_renet2::renet2::RenetClient;
 use cosmos_core::{
     block::specific_blocks::gravity_well::GravityWell,
     netty::{
-        cosmos_encoder, server_replication::ReplicationMessage, sync::mapping::NetworkMapping, system_sets::NetworkingSystemsSet,
-        NettyChannelServer,
+        cosmos_encoder, network_stats::NettyClientMessages, server_replication::ReplicationMessage, sync::mapping::NetworkMapping,
+        system_sets::NetworkingSystemsSet, NettyChannelServer,
     },
     physics::location::LocationPhysicsSet,
     registry::{identifiable::Identifiable, Registry},
@@ -72,8 +72,8 @@ fn replication_listen_netty(
     mut commands: Commands,
     q_is_active: Query<(), With<SystemActive>>,
 ) {
-    while let Some(message) = client.receive_message(NettyChannelServer::SystemReplication) {
-        let msg: ReplicationMessage = cosmos_encoder::deserialize(&message).expect("Unable to parse registry sync from server");
+    while let Some(message) = client.receive_netty_message::<ReplicationMessage>(NettyChannelServer::SystemReplication) {
+        let msg: ReplicationMessage = message.expect("Unable to parse registry sync from server");
 
         match msg {
             ReplicationMessage::SystemReplication {
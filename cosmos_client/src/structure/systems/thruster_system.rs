@@ -6,6 +6,7 @@ use bevy::prelude::*;
 use bevy_kira_audio::prelude::*;
 use cosmos_core::{
     netty::system_sets::NetworkingSystemsSet,
+    registry::Registry,
     state::GameState,
     structure::{
         ship::ship_movement::{ShipMovement, ShipMovementSet},
@@ -13,10 +14,7 @@ use cosmos_core::{
     },
 };
 
-use crate::{
-    asset::asset_loader::load_assets,
-    audio::{AudioEmission, AudioSet, BufferedStopAudio, CosmosAudioEmitter},
-};
+use crate::audio::{sound_effects::SoundEffect, AudioSet, BufferedStopAudio, CosmosAudioEmitter};
 
 use super::sync::sync_system;
 
@@ -35,10 +33,14 @@ fn apply_thruster_sound(
     >,
     mut commands: Commands,
     audio: Res<Audio>,
-    audio_handle: Res<ThrusterAudioHandle>,
+    sound_effects: Res<Registry<SoundEffect>>,
     mut audio_instances: ResMut<Assets<AudioInstance>>,
     mut stop_later: ResMut<BufferedStopAudio>,
 ) {
+    let Some(thruster_sound) = sound_effects.from_id("cosmos:thruster_running") else {
+        return;
+    };
+
     for (entity, ship_movement, thruster_sound_instance, audio_emitter) in query.iter_mut() {
         // A hacky way of determining if the thrusters are running
         let thrusters_off =
@@ -53,43 +55,23 @@ fn apply_thruster_sound(
                 }
             }
         } else if !thrusters_off && thruster_sound_instance.is_none() {
-            let playing_sound: Handle<AudioInstance> = audio.play(audio_handle.0.clone_weak()).with_volume(0.0).looped().handle();
-
             let stop_tween = AudioTween::new(Duration::from_millis(400), AudioEasing::Linear);
 
+            let Some(emission) = thruster_sound.play_looped(&audio, stop_tween) else {
+                continue;
+            };
+
             commands.entity(entity).insert((
-                ThrusterSoundInstace(playing_sound.clone_weak()),
-                CosmosAudioEmitter {
-                    emissions: vec![AudioEmission {
-                        instance: playing_sound,
-                        max_distance: 100.0,
-                        peak_volume: 0.3 * 5.0,
-                        stop_tween,
-                        handle: audio_handle.0.clone_weak(),
-                    }],
-                },
+                ThrusterSoundInstace(emission.instance.clone_weak()),
+                CosmosAudioEmitter::with_emissions(vec![emission]),
             ));
         }
     }
 }
 
-#[derive(Resource)]
-struct ThrusterAudioHandle(Handle<bevy_kira_audio::prelude::AudioSource>);
-
-struct ThrusterSoundLoading;
-
 pub(super) fn register(app: &mut App) {
     sync_system::<ThrusterSystem>(app);
 
-    load_assets::<bevy_kira_audio::prelude::AudioSource, ThrusterSoundLoading>(
-        app,
-        GameState::PreLoading,
-        vec!["cosmos/sounds/sfx/thruster-running.ogg"],
-        |mut commands, mut handles| {
-            commands.insert_resource(ThrusterAudioHandle(handles.remove(0).0));
-        },
-    );
-
     app.add_systems(
         Update,
         apply_thruster_sound
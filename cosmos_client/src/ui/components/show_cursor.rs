@@ -6,11 +6,12 @@ use bevy::{
         component::Component,
         query::With,
         schedule::IntoSystemConfigs,
-        system::{Query, ResMut},
+        system::{Query, Res, ResMut},
     },
 };
 
 use crate::{
+    camera::photo_mode::PhotoMode,
     ui::UiSystemSet,
     window::setup::{CursorFlags, CursorFlagsSet},
 };
@@ -31,7 +32,7 @@ fn show_cursor(mut cursor_flags: ResMut<CursorFlags>, q_show_cursor: Query<(), W
     }
 }
 
-/// A system that returns true if there are no open menus.
+/// A system that returns true if there are no open menus, and the player isn't in photo mode.
 ///
 /// This is particularlly useful if you are dealing with realtime inputs
 /// you don't want running with UIs open, such as movement.
@@ -40,8 +41,8 @@ fn show_cursor(mut cursor_flags: ResMut<CursorFlags>, q_show_cursor: Query<(), W
 /// ```rs
 /// app.add_systems(Update, process_movement.run_if(no_open_menus));
 /// ```
-pub fn no_open_menus(q_show_cursor: Query<(), With<ShowCursor>>) -> bool {
-    q_show_cursor.is_empty()
+pub fn no_open_menus(q_show_cursor: Query<(), With<ShowCursor>>, photo_mode: Option<Res<PhotoMode>>) -> bool {
+    q_show_cursor.is_empty() && photo_mode.is_none()
 }
 
 pub(super) fn register(app: &mut App) {
@@ -13,7 +13,9 @@ use cosmos_core::{
 };
 
 use crate::{
+    camera::camera_mode::CameraMode,
     input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    inventory::durability_bar::{durability_bar_bundle, DurabilityBar},
     lang::Lang,
     structure::ship::ui::system_selection::SystemSelectionSet,
 };
@@ -132,8 +134,8 @@ impl<T> Default for PriorityQueue<T> {
 #[derive(Component)]
 /// The hotbar the player can see
 pub struct Hotbar {
-    /// Vec<(slot, slot text, slot item)>
-    slots: Vec<(Entity, Entity, Entity)>,
+    /// Vec<(slot, slot text, slot item, slot durability bar)>
+    slots: Vec<(Entity, Entity, Entity, Entity)>,
     selected_slot: usize,
     prev_slot: usize,
     max_slots: usize,
@@ -177,11 +179,17 @@ fn listen_button_presses(
     mut scroll_evr: EventReader<MouseWheel>,
     mut q_held_item_slot: Query<&mut HeldItemSlot, With<LocalPlayer>>,
     mut hotbar: Query<&mut Hotbar>,
+    camera_mode: Res<CameraMode>,
 ) {
     let Ok(mut hotbar) = hotbar.get_single_mut() else {
         return;
     };
 
+    // Scrolling zooms the orbit camera instead
+    if camera_mode.is_orbiting(&input_handler) {
+        scroll_evr.clear();
+    }
+
     for ev in scroll_evr.read() {
         if ev.y > 0.0 {
             if hotbar.selected_slot == 0 {
@@ -259,6 +267,7 @@ fn listen_for_change_events(
     mut commands: Commands,
     names: Res<Lang<Item>>,
     items: Res<Registry<Item>>,
+    mut q_durability_bar: Query<&mut DurabilityBar>,
 ) {
     let Ok(mut hb) = query_hb.get_single_mut() else {
         return;
@@ -308,6 +317,10 @@ fn listen_for_change_events(
                     text.as_mut().0 = "".into();
                 }
             }
+
+            if let Ok(mut durability_bar) = q_durability_bar.get_mut(hb.slots[hb_slot].3) {
+                durability_bar.data_entity = is.and_then(|is| is.data_entity());
+            }
         }
     }
 }
@@ -368,7 +381,7 @@ fn populate_hotbar(
         return;
     };
 
-    for (item, &(_, _, item_entity)) in hotbar_contents.iter().take(hotbar.slots.len()).zip(hotbar.slots.iter()) {
+    for (item, &(_, _, item_entity, _)) in hotbar_contents.iter().take(hotbar.slots.len()).zip(hotbar.slots.iter()) {
         let Some(item_stack) = item else {
             commands.entity(item_entity).remove::<RenderItem>();
 
@@ -431,6 +444,7 @@ fn add_hotbar(mut commands: Commands, default_font: Res<DefaultFont>, asset_serv
 
                     let mut text_entity = None;
                     let mut item_entity = None;
+                    let mut durability_bar_entity = None;
 
                     slot.with_children(|slot| {
                         item_entity = Some(
@@ -465,6 +479,8 @@ fn add_hotbar(mut commands: Commands, default_font: Res<DefaultFont>, asset_serv
                                     ))
                                     .id(),
                                 );
+
+                                durability_bar_entity = Some(slot.spawn(durability_bar_bundle(None)).id());
                             })
                             .id(),
                         );
@@ -474,6 +490,7 @@ fn add_hotbar(mut commands: Commands, default_font: Res<DefaultFont>, asset_serv
                         slot.id(),
                         text_entity.expect("This should have been set in the closure above"),
                         item_entity.expect("Should have been set above"),
+                        durability_bar_entity.expect("Should have been set above"),
                     ));
                 }
             });
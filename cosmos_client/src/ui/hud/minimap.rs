@@ -0,0 +1,328 @@
+//! A small radar in the corner of the screen that shows the structures & players around you.
+//!
+//! Contacts are projected onto the ship's horizontal plane while piloting, or the plane the player is
+//! facing while on foot, so "up" on the minimap is always forward.
+
+use bevy::prelude::*;
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    entities::player::{spectator::Spectator, Player},
+    netty::{client::LocalPlayer, system_sets::NetworkingSystemsSet},
+    physics::location::Location,
+    state::GameState,
+    structure::{
+        sensors::{detect, Detection, SensorStrength},
+        ship::pilot::Pilot,
+        systems::cloaking_system::{cloaked_ancestor, is_aboard, Cloaked},
+    },
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    rendering::MainCamera,
+    ui::{
+        components::show_cursor::no_open_menus,
+        font::DefaultFont,
+        ship_flight::indicators::{IndicatorSettings, WaypointSet},
+        theme::UiTheme,
+    },
+};
+
+/// The radius (in meters) the minimap covers at each zoom level
+const ZOOM_LEVELS: [f32; 4] = [250.0, 1_000.0, 5_000.0, 20_000.0];
+const DEFAULT_ZOOM_LEVEL: usize = 1;
+/// Width & height of the minimap (px)
+const MINIMAP_SIZE: f32 = 200.0;
+const BLIP_SIZE: f32 = 6.0;
+/// Contacts that are too jammed to identify are shown in this color instead of their own
+const UNKNOWN_BLIP_COLOR: &str = "AAAAAA";
+
+#[derive(Resource, Debug, Clone, Copy)]
+/// The player's minimap preferences
+pub struct Minimap {
+    /// If the minimap should be displayed
+    pub enabled: bool,
+    /// Index into the zoom levels - lower is more zoomed in
+    zoom_level: usize,
+}
+
+impl Default for Minimap {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            zoom_level: DEFAULT_ZOOM_LEVEL,
+        }
+    }
+}
+
+impl Minimap {
+    /// The radius (in meters) the minimap currently covers
+    pub fn radius(&self) -> f32 {
+        ZOOM_LEVELS[self.zoom_level]
+    }
+}
+
+#[derive(Component)]
+struct MinimapRoot;
+
+#[derive(Component)]
+/// Blips are children of this, so they can be positioned relative to the minimap
+struct MinimapArea;
+
+#[derive(Component)]
+struct MinimapRangeText;
+
+#[derive(Component, Debug)]
+/// The blip displayed on the minimap for this entity
+struct HasMinimapBlip(Entity);
+
+#[derive(Component, Debug)]
+/// The entity this blip is displaying
+struct MinimapBlip(Entity);
+
+fn get_range_text(radius: f32) -> String {
+    if radius < 1_000.0 {
+        format!("{}m", radius as i32)
+    } else {
+        format!("{}km", radius / 1_000.0)
+    }
+}
+
+fn control_minimap(inputs: InputChecker, mut minimap: ResMut<Minimap>) {
+    if inputs.check_just_pressed(CosmosInputs::ToggleMinimap) {
+        minimap.enabled = !minimap.enabled;
+    }
+
+    if inputs.check_just_pressed(CosmosInputs::MinimapZoomIn) {
+        minimap.zoom_level = minimap.zoom_level.saturating_sub(1);
+    }
+
+    if inputs.check_just_pressed(CosmosInputs::MinimapZoomOut) {
+        minimap.zoom_level = (minimap.zoom_level + 1).min(ZOOM_LEVELS.len() - 1);
+    }
+}
+
+fn create_minimap(mut commands: Commands, default_font: Res<DefaultFont>, theme: Res<UiTheme>) {
+    commands
+        .spawn((
+            Name::new("Minimap"),
+            MinimapRoot,
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(10.0),
+                top: Val::Px(50.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Name::new("Minimap Area"),
+                MinimapArea,
+                Node {
+                    width: Val::Px(MINIMAP_SIZE),
+                    height: Val::Px(MINIMAP_SIZE),
+                    border: theme.border_rect(),
+                    ..Default::default()
+                },
+                BorderColor(theme.border),
+                BackgroundColor(Srgba::hex("00000099").unwrap().into()),
+            ))
+            .with_children(|p| {
+                // The player/ship is always in the center
+                p.spawn((
+                    Name::new("Minimap Center"),
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(MINIMAP_SIZE / 2.0 - BLIP_SIZE / 2.0),
+                        top: Val::Px(MINIMAP_SIZE / 2.0 - BLIP_SIZE / 2.0),
+                        width: Val::Px(BLIP_SIZE),
+                        height: Val::Px(BLIP_SIZE),
+                        ..Default::default()
+                    },
+                    BackgroundColor(theme.accent),
+                ));
+            });
+
+            p.spawn((
+                Name::new("Minimap Range"),
+                MinimapRangeText,
+                Text::new(""),
+                theme.font(default_font.0.clone(), 14.0),
+                TextColor(theme.text),
+            ));
+        });
+}
+
+fn despawn_minimap(mut commands: Commands, q_root: Query<Entity, With<MinimapRoot>>) {
+    for ent in q_root.iter() {
+        commands.entity(ent).insert(NeedsDespawned);
+    }
+}
+
+fn update_minimap_visibility(minimap: Res<Minimap>, mut q_root: Query<&mut Visibility, With<MinimapRoot>>) {
+    for mut vis in q_root.iter_mut() {
+        vis.set_if_neq(if minimap.enabled {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+fn update_range_text(minimap: Res<Minimap>, mut q_text: Query<&mut Text, With<MinimapRangeText>>) {
+    let range_text = get_range_text(minimap.radius());
+
+    for mut text in q_text.iter_mut() {
+        if text.0 != range_text {
+            text.0 = range_text.clone();
+        }
+    }
+}
+
+fn update_blips(
+    mut commands: Commands,
+    minimap: Res<Minimap>,
+    q_local_player: Query<(Entity, &Location, Option<&Pilot>), With<LocalPlayer>>,
+    q_camera: Query<&GlobalTransform, With<MainCamera>>,
+    q_global_trans: Query<&GlobalTransform>,
+    q_minimap_area: Query<Entity, With<MinimapArea>>,
+    q_contacts: Query<(Entity, &Location, &IndicatorSettings, Option<&HasMinimapBlip>, Option<&Spectator>), Without<LocalPlayer>>,
+    q_is_player: Query<(), With<Player>>,
+    mut q_blips: Query<(&mut Node, &mut BackgroundColor), With<MinimapBlip>>,
+    q_all_blips: Query<(Entity, &MinimapBlip)>,
+    q_sensor_strength: Query<&SensorStrength>,
+    q_cloaked: Query<(), With<Cloaked>>,
+    q_parent: Query<&Parent>,
+) {
+    let remove_blip = |commands: &mut Commands, contact: Entity, blip: Entity| {
+        commands.entity(blip).despawn_recursive();
+        if let Some(mut ecmds) = commands.get_entity(contact) {
+            ecmds.remove::<HasMinimapBlip>();
+        }
+    };
+
+    let (Ok((player_ent, player_loc, pilot)), Ok(minimap_area)) = (q_local_player.get_single(), q_minimap_area.get_single()) else {
+        for (blip_ent, blip) in q_all_blips.iter() {
+            remove_blip(&mut commands, blip.0, blip_ent);
+        }
+        return;
+    };
+
+    // While piloting, the ship's orientation is used. Otherwise, only the direction the player is facing matters.
+    let reference_rotation = match pilot.and_then(|p| q_global_trans.get(p.entity).ok()) {
+        Some(ship_g_trans) => ship_g_trans.rotation(),
+        None => {
+            let Ok(cam_g_trans) = q_camera.get_single() else {
+                return;
+            };
+
+            let player_up = q_global_trans.get(player_ent).map(|x| *x.up()).unwrap_or(Vec3::Y);
+            let forward = cam_g_trans.forward().reject_from_normalized(player_up).normalize_or_zero();
+
+            if forward == Vec3::ZERO {
+                return;
+            }
+
+            Transform::default().looking_to(forward, player_up).rotation
+        }
+    };
+    let inv_rotation = reference_rotation.inverse();
+
+    let own_ship = pilot.map(|p| p.entity);
+    let radius = minimap.radius();
+    // Only ships have sensors - players on foot can't see through jamming
+    let observer_strength = own_ship.and_then(|e| q_sensor_strength.get(e).ok());
+
+    for (contact_ent, contact_loc, settings, has_blip, spectator) in q_contacts.iter() {
+        let hidden_spectator = q_is_player.contains(contact_ent) && spectator.is_some_and(|s| s.invisible);
+
+        let relative = inv_rotation * player_loc.relative_coords_to(contact_loc);
+        // Projected onto the horizontal plane, where -z is forward (up on the minimap)
+        let flat = Vec2::new(relative.x, relative.z);
+
+        // Cloaked ships can only be seen by the players aboard them
+        let cloaked = cloaked_ancestor(contact_ent, &q_cloaked, &q_parent).is_some_and(|c| !is_aboard(player_ent, c, &q_parent));
+
+        let detection = if cloaked {
+            Detection::Undetected
+        } else {
+            detect(
+                relative.length(),
+                settings.max_distance,
+                observer_strength,
+                q_sensor_strength.get(contact_ent).ok(),
+            )
+        };
+
+        if !minimap.enabled
+            || Some(contact_ent) == own_ship
+            || hidden_spectator
+            || flat.length() > radius
+            || detection == Detection::Undetected
+        {
+            if let Some(has_blip) = has_blip {
+                remove_blip(&mut commands, contact_ent, has_blip.0);
+            }
+            continue;
+        }
+
+        let pos = (flat / radius + Vec2::ONE) * (MINIMAP_SIZE / 2.0) - Vec2::splat(BLIP_SIZE / 2.0);
+        let color = match detection {
+            Detection::UnknownBlip => Srgba::hex(UNKNOWN_BLIP_COLOR).unwrap().into(),
+            _ => settings.color.with_alpha(1.0),
+        };
+
+        if let Some(Ok((mut node, mut bg))) = has_blip.map(|x| q_blips.get_mut(x.0)) {
+            node.left = Val::Px(pos.x);
+            node.top = Val::Px(pos.y);
+            bg.0 = color;
+            continue;
+        }
+
+        let blip = commands
+            .spawn((
+                Name::new("Minimap Blip"),
+                MinimapBlip(contact_ent),
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(pos.x),
+                    top: Val::Px(pos.y),
+                    width: Val::Px(BLIP_SIZE),
+                    height: Val::Px(BLIP_SIZE),
+                    ..Default::default()
+                },
+                BackgroundColor(color),
+            ))
+            .set_parent(minimap_area)
+            .id();
+
+        commands.entity(contact_ent).insert(HasMinimapBlip(blip));
+    }
+
+    // Blips for entities that were despawned
+    for (blip_ent, blip) in q_all_blips.iter() {
+        if !q_contacts.contains(blip.0) {
+            commands.entity(blip_ent).despawn_recursive();
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<Minimap>()
+        .add_systems(OnEnter(GameState::Playing), create_minimap)
+        .add_systems(OnExit(GameState::Playing), despawn_minimap)
+        .add_systems(
+            Update,
+            (
+                control_minimap.run_if(no_open_menus),
+                update_minimap_visibility,
+                update_range_text,
+                update_blips.after(WaypointSet::CreateWaypoints),
+            )
+                .chain()
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        );
+}
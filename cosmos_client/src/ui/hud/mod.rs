@@ -17,6 +17,13 @@ use cosmos_core::{economy::Credits, netty::client::LocalPlayer, state::GameState
 
 use super::reactivity::{BindValue, BindValues, ReactableFields};
 
+mod minimap;
+mod needs;
+mod party;
+mod sector_rules;
+mod structure_streaming;
+mod wanted_list;
+
 fn create_credits_node(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -61,6 +68,13 @@ fn create_credits_node(
 }
 
 pub(super) fn register(app: &mut App) {
+    minimap::register(app);
+    needs::register(app);
+    party::register(app);
+    sector_rules::register(app);
+    structure_streaming::register(app);
+    wanted_list::register(app);
+
     app.add_systems(OnEnter(GameState::Playing), create_credits_node)
         .add_systems(Update, create_credits_node.run_if(in_state(GameState::Playing)));
 }
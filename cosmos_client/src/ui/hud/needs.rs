@@ -0,0 +1,185 @@
+//! Shows the local player's health, hunger and any status effects they have
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    entities::{
+        health::Health,
+        player::hunger::Hunger,
+        status_effects::{StatusEffect, StatusEffects},
+    },
+    netty::{client::LocalPlayer, system_sets::NetworkingSystemsSet},
+    state::GameState,
+};
+
+/// How wide the hunger & health bars are when full
+const HUNGER_BAR_WIDTH: f32 = 200.0;
+
+#[derive(Component, Debug)]
+struct HungerBarFill;
+
+#[derive(Component, Debug)]
+struct HealthBarFill;
+
+#[derive(Component, Debug)]
+struct StatusEffectsText;
+
+fn create_needs_display(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    q_local_player: Query<(), (Added<Hunger>, With<LocalPlayer>)>,
+) {
+    if q_local_player.is_empty() {
+        return;
+    }
+
+    let font = asset_server.load("fonts/PixeloidSans.ttf");
+
+    let text_font = TextFont {
+        font_size: 16.0,
+        font,
+        ..Default::default()
+    };
+
+    commands
+        .spawn((
+            Name::new("Needs display"),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(10.0),
+                bottom: Val::Px(10.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..Default::default()
+            },
+        ))
+        .with_children(|p| {
+            p.spawn((Name::new("Status effects"), StatusEffectsText, Text::new(""), text_font.clone()));
+
+            p.spawn((Name::new("Health label"), Text::new("Health"), text_font.clone()));
+
+            p.spawn((
+                Name::new("Health bar"),
+                Node {
+                    width: Val::Px(HUNGER_BAR_WIDTH),
+                    height: Val::Px(12.0),
+                    ..Default::default()
+                },
+                BackgroundColor(Srgba::hex("00000099").unwrap().into()),
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    Name::new("Health bar fill"),
+                    HealthBarFill,
+                    Node {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..Default::default()
+                    },
+                    BackgroundColor(css::RED.into()),
+                ));
+            });
+
+            p.spawn((Name::new("Hunger label"), Text::new("Hunger"), text_font));
+
+            p.spawn((
+                Name::new("Hunger bar"),
+                Node {
+                    width: Val::Px(HUNGER_BAR_WIDTH),
+                    height: Val::Px(12.0),
+                    ..Default::default()
+                },
+                BackgroundColor(Srgba::hex("00000099").unwrap().into()),
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    Name::new("Hunger bar fill"),
+                    HungerBarFill,
+                    Node {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..Default::default()
+                    },
+                    BackgroundColor(css::ORANGE.into()),
+                ));
+            });
+        });
+}
+
+fn update_hunger_bar(
+    q_hunger: Query<&Hunger, (Changed<Hunger>, With<LocalPlayer>)>,
+    mut q_fill: Query<(&mut Node, &mut BackgroundColor), With<HungerBarFill>>,
+) {
+    let Ok(hunger) = q_hunger.get_single() else {
+        return;
+    };
+
+    for (mut node, mut color) in q_fill.iter_mut() {
+        node.width = Val::Percent(hunger.fraction() * 100.0);
+        color.0 = if hunger.is_starving() {
+            css::RED.into()
+        } else {
+            css::ORANGE.into()
+        };
+    }
+}
+
+fn update_health_bar(q_health: Query<&Health, (Changed<Health>, With<LocalPlayer>)>, mut q_fill: Query<&mut Node, With<HealthBarFill>>) {
+    let Ok(health) = q_health.get_single() else {
+        return;
+    };
+
+    for mut node in q_fill.iter_mut() {
+        node.width = Val::Percent(health.current() / health.max() * 100.0);
+    }
+}
+
+fn effect_name(effect: StatusEffect) -> &'static str {
+    match effect {
+        StatusEffect::Speed => "Speed",
+        StatusEffect::OxygenEfficiency => "Oxygen Efficiency",
+    }
+}
+
+fn update_status_effects_text(
+    q_effects: Query<(&StatusEffects, Option<&Hunger>), (Or<(Changed<StatusEffects>, Changed<Hunger>)>, With<LocalPlayer>)>,
+    mut q_text: Query<&mut Text, With<StatusEffectsText>>,
+) {
+    let Ok((effects, hunger)) = q_effects.get_single() else {
+        return;
+    };
+
+    let mut lines = effects
+        .iter()
+        .map(|active| {
+            format!(
+                "{} +{:.0}% ({:.0}s)",
+                effect_name(active.effect),
+                active.strength * 100.0,
+                active.remaining_secs.max(0.0)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if hunger.is_some_and(|h| h.is_starving()) {
+        lines.push("Starving - can't sprint".into());
+    }
+
+    for mut text in q_text.iter_mut() {
+        text.0 = lines.join("\n");
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            create_needs_display,
+            update_hunger_bar,
+            update_health_bar,
+            update_status_effects_text,
+        )
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
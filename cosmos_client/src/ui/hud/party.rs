@@ -0,0 +1,140 @@
+//! Shows the members of the local player's party, and their health & shields
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{netty::system_sets::NetworkingSystemsSet, party::PartyMemberStatus, state::GameState};
+
+use crate::party::{CurrentParty, PartyStatus};
+
+/// How wide the health & shield bars are when full
+const BAR_WIDTH: f32 = 120.0;
+
+#[derive(Component, Debug)]
+struct PartyDisplay;
+
+fn create_party_display(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Party display"),
+        PartyDisplay,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            top: Val::Percent(30.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.0),
+            ..Default::default()
+        },
+    ));
+}
+
+fn bar(p: &mut ChildBuilder, name: &'static str, fraction: f32, color: Srgba) {
+    p.spawn((
+        Name::new(name),
+        Node {
+            width: Val::Px(BAR_WIDTH),
+            height: Val::Px(6.0),
+            ..Default::default()
+        },
+        BackgroundColor(Srgba::hex("00000099").unwrap().into()),
+    ))
+    .with_children(|p| {
+        p.spawn((
+            Node {
+                width: Val::Percent(fraction.clamp(0.0, 1.0) * 100.0),
+                height: Val::Percent(100.0),
+                ..Default::default()
+            },
+            BackgroundColor(color.into()),
+        ));
+    });
+}
+
+fn member_entry(p: &mut ChildBuilder, member: &PartyMemberStatus, is_leader: bool, text_font: &TextFont) {
+    p.spawn((
+        Name::new("Party member"),
+        Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(2.0),
+            ..Default::default()
+        },
+    ))
+    .with_children(|p| {
+        let name = if is_leader {
+            format!("* {}", member.name)
+        } else {
+            member.name.clone()
+        };
+
+        let (label, color) = if member.online {
+            (name, css::WHITE)
+        } else {
+            (format!("{name} (offline)"), css::GREY)
+        };
+
+        p.spawn((Text::new(label), text_font.clone(), TextColor(color.into())));
+
+        if let Some(health) = member.health {
+            bar(p, "Health bar", health.current() / health.max(), css::RED);
+        }
+
+        if let Some((strength, max)) = member.shield.filter(|(_, max)| *max > 0.0) {
+            bar(p, "Shield bar", strength / max, css::AQUA);
+        }
+    });
+}
+
+fn update_party_display(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    current_party: Res<CurrentParty>,
+    party_status: Res<PartyStatus>,
+    q_display: Query<Entity, With<PartyDisplay>>,
+) {
+    if !current_party.is_changed() && !party_status.is_changed() {
+        return;
+    }
+
+    let Ok(display) = q_display.get_single() else {
+        return;
+    };
+
+    commands.entity(display).despawn_descendants();
+
+    let Some(party) = &current_party.0 else {
+        return;
+    };
+
+    let text_font = TextFont {
+        font_size: 16.0,
+        font: asset_server.load("fonts/PixeloidSans.ttf"),
+        ..Default::default()
+    };
+
+    commands.entity(display).with_children(|p| {
+        // Until the server sends how everyone is doing, at least show who's in the party
+        if party_status.0.is_empty() {
+            for name in party.members.iter() {
+                let member = PartyMemberStatus {
+                    name: name.clone(),
+                    online: true,
+                    health: None,
+                    shield: None,
+                };
+
+                member_entry(p, &member, party.is_leader(name), &text_font);
+            }
+        } else {
+            for member in party_status.0.iter() {
+                member_entry(p, member, party.is_leader(&member.name), &text_font);
+            }
+        }
+    });
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Playing), create_party_display).add_systems(
+        Update,
+        update_party_display
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
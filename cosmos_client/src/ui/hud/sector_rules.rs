@@ -0,0 +1,53 @@
+//! Tells the player what the rules are when they cross into a sector with different rules
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    netty::{sync::events::client_event::NettyEventReceived, system_sets::NetworkingSystemsSet},
+    state::GameState,
+    universe::sector_rules::{SectorRules, SectorRulesChangedEvent},
+};
+
+use crate::ui::message::{HudMessage, HudMessages, RichText};
+
+fn describe_rules(rules: &SectorRules) -> HudMessage {
+    let (title, color) = if rules.is_lawless() {
+        ("Entering lawless space", css::ORANGE_RED)
+    } else if rules.pvp {
+        ("Entering policed space", css::YELLOW)
+    } else {
+        ("Entering lawful space", css::LIGHT_GREEN)
+    };
+
+    let yes_no = |allowed: bool| if allowed { "on" } else { "off" };
+
+    HudMessage::new(vec![
+        RichText::new(format!("{title}\n"), color.into()),
+        RichText::new(
+            format!(
+                "PvP: {} | Building: {} | Police: {}",
+                yes_no(rules.pvp),
+                yes_no(rules.block_editing),
+                yes_no(rules.police_response)
+            ),
+            Color::WHITE,
+        ),
+    ])
+}
+
+fn on_sector_rules_changed(
+    mut nevr_sector_rules: EventReader<NettyEventReceived<SectorRulesChangedEvent>>,
+    mut hud_messages: ResMut<HudMessages>,
+) {
+    for ev in nevr_sector_rules.read() {
+        hud_messages.display_message(describe_rules(&ev.rules));
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        on_sector_rules_changed
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,67 @@
+//! Shows how much of the structures that are currently being streamed in from the server have been received
+
+use bevy::prelude::*;
+use cosmos_core::{state::GameState, structure::loading::ChunksNeedLoaded};
+
+use crate::{lang::Localization, structure::chunk_retreiver::StructureStreamingProgress, ui::font::DefaultFont};
+
+#[derive(Component)]
+struct StructureStreamingText;
+
+fn create_streaming_text(mut commands: Commands, default_font: Res<DefaultFont>) {
+    commands.spawn((
+        Name::new("Structure Streaming Text"),
+        StructureStreamingText,
+        Text::new(""),
+        TextFont {
+            font: default_font.0.clone(),
+            font_size: 20.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(120.0),
+            right: Val::Px(10.0),
+            ..Default::default()
+        },
+        Visibility::Hidden,
+    ));
+}
+
+fn update_streaming_text(
+    localization: Res<Localization>,
+    q_streaming: Query<(&StructureStreamingProgress, &ChunksNeedLoaded)>,
+    mut q_text: Query<(&mut Text, &mut Visibility), With<StructureStreamingText>>,
+) {
+    let Ok((mut text, mut visibility)) = q_text.get_single_mut() else {
+        return;
+    };
+
+    let (n_structures, total_chunks, chunks_needed) =
+        q_streaming.iter().fold((0, 0, 0), |(n, total, needed), (progress, chunks_needed)| {
+            (
+                n + 1,
+                total + progress.total_chunks,
+                needed + chunks_needed.amount_needed.min(progress.total_chunks),
+            )
+        });
+
+    if n_structures == 0 || total_chunks == 0 {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    }
+
+    let percent = (1.0 - chunks_needed as f32 / total_chunks as f32) * 100.0;
+
+    text.0 = localization.format("cosmos:hud.streaming_structures", &[&n_structures, &format!("{percent:.0}")]);
+    if *visibility != Visibility::Inherited {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Playing), create_streaming_text)
+        .add_systems(Update, update_streaming_text.run_if(in_state(GameState::Playing)));
+}
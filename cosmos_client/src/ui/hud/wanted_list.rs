@@ -0,0 +1,100 @@
+//! Shows who is wanted, and for how much, while the local player is aboard a station
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    economy::bounty::{WantedListEvent, WantedPlayer},
+    netty::{client::LocalPlayer, sync::events::client_event::NettyEventReceived, system_sets::NetworkingSystemsSet},
+    state::GameState,
+    structure::station::Station,
+};
+
+#[derive(Resource, Debug, Default)]
+/// The latest wanted list sent by the server
+struct WantedList(Vec<WantedPlayer>);
+
+#[derive(Component, Debug)]
+struct WantedListDisplay;
+
+fn receive_wanted_list(mut nevr_wanted_list: EventReader<NettyEventReceived<WantedListEvent>>, mut wanted_list: ResMut<WantedList>) {
+    if let Some(ev) = nevr_wanted_list.read().last() {
+        wanted_list.0 = ev.wanted.clone();
+    }
+}
+
+fn create_wanted_list_display(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Wanted list display"),
+        WantedListDisplay,
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            top: Val::Percent(30.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..Default::default()
+        },
+        BackgroundColor(Srgba::hex("00000099").unwrap().into()),
+        Visibility::Hidden,
+    ));
+}
+
+fn update_wanted_list_display(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    wanted_list: Res<WantedList>,
+    q_local_player: Query<Option<&Parent>, With<LocalPlayer>>,
+    q_station: Query<(), With<Station>>,
+    mut q_display: Query<(Entity, &mut Visibility), With<WantedListDisplay>>,
+) {
+    let Ok((display, mut visibility)) = q_display.get_single_mut() else {
+        return;
+    };
+
+    let aboard_station = q_local_player
+        .get_single()
+        .is_ok_and(|parent| parent.is_some_and(|p| q_station.contains(p.get())));
+
+    let desired = if aboard_station && !wanted_list.0.is_empty() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    visibility.set_if_neq(desired);
+
+    if !wanted_list.is_changed() {
+        return;
+    }
+
+    commands.entity(display).despawn_descendants();
+
+    let text_font = TextFont {
+        font_size: 16.0,
+        font: asset_server.load("fonts/PixeloidSans.ttf"),
+        ..Default::default()
+    };
+
+    commands.entity(display).with_children(|p| {
+        p.spawn((Text::new("Wanted"), text_font.clone(), TextColor(css::ORANGE_RED.into())));
+
+        for wanted in wanted_list.0.iter() {
+            p.spawn((
+                Text::new(format!("{} - ${}", wanted.name, wanted.bounty)),
+                text_font.clone(),
+                TextColor(Color::WHITE),
+            ));
+        }
+    });
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<WantedList>()
+        .add_systems(OnEnter(GameState::Playing), create_wanted_list_display)
+        .add_systems(
+            Update,
+            (receive_wanted_list, update_wanted_list_display)
+                .chain()
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        );
+}
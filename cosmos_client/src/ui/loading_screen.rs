@@ -0,0 +1,137 @@
+//! The screen shown while the game's assets and registries are being loaded, before the main menu.
+
+use bevy::prelude::*;
+use cosmos_core::{loader::LoadingProgress, state::GameState};
+
+use super::font::DefaultFont;
+
+#[derive(Component)]
+struct LoadingScreen;
+
+#[derive(Component)]
+struct LoadingBar;
+
+#[derive(Component)]
+struct LoadingStageText;
+
+fn create_loading_screen(mut commands: Commands, default_font: Res<DefaultFont>) {
+    let cool_blue: Color = Srgba::hex("00FFFF").unwrap().into();
+
+    let text_style_large = TextFont {
+        font_size: 64.0,
+        font: default_font.0.clone(),
+        ..Default::default()
+    };
+    let text_style = TextFont {
+        font_size: 24.0,
+        font: default_font.0.clone(),
+        ..Default::default()
+    };
+
+    let camera = commands.spawn((Name::new("Loading screen camera"), LoadingScreen, Camera2d)).id();
+
+    commands
+        .spawn((
+            Name::new("Loading screen"),
+            LoadingScreen,
+            TargetCamera(camera),
+            BackgroundColor(Color::BLACK),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new("COSMOS"),
+                text_style_large,
+                TextColor(cool_blue),
+                Node {
+                    margin: UiRect::bottom(Val::Px(50.0)),
+                    ..Default::default()
+                },
+            ));
+
+            p.spawn((
+                BorderColor(cool_blue),
+                Node {
+                    width: Val::Px(500.0),
+                    height: Val::Px(30.0),
+                    border: UiRect::all(Val::Px(2.0)),
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..Default::default()
+                },
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    LoadingBar,
+                    BackgroundColor(cool_blue),
+                    Node {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..Default::default()
+                    },
+                ));
+            });
+
+            p.spawn((LoadingStageText, Text::new(""), text_style));
+        });
+}
+
+fn update_loading_screen(
+    state: Res<State<GameState>>,
+    progress: Res<LoadingProgress>,
+    mut q_bar: Query<&mut Node, With<LoadingBar>>,
+    mut q_text: Query<&mut Text, With<LoadingStageText>>,
+) {
+    // Each stage gets an equal chunk of the loading bar
+    let (stage, stage_name) = match state.get() {
+        GameState::PreLoading => (0, "Preparing assets"),
+        GameState::Loading => (1, "Loading assets"),
+        GameState::PostLoading => (2, "Finishing up"),
+        _ => return,
+    };
+
+    let percent = (stage as f32 + progress.fraction()) / 3.0 * 100.0;
+
+    for mut node in q_bar.iter_mut() {
+        node.width = Val::Percent(percent);
+    }
+
+    let status = format!("{stage_name} ({}/{})", progress.finished(), progress.registered());
+    for mut text in q_text.iter_mut() {
+        if text.0 != status {
+            text.0 = status.clone();
+        }
+    }
+}
+
+fn remove_loading_screen(mut commands: Commands, q_loading_screen: Query<Entity, With<LoadingScreen>>) {
+    for ent in q_loading_screen.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            // The font is loaded on startup, which may be after the pre-loading state has been entered.
+            create_loading_screen
+                .run_if(in_state(GameState::PreLoading))
+                .run_if(resource_exists::<DefaultFont>)
+                .run_if(not(any_with_component::<LoadingScreen>)),
+            update_loading_screen.run_if(
+                in_state(GameState::PreLoading)
+                    .or(in_state(GameState::Loading))
+                    .or(in_state(GameState::PostLoading)),
+            ),
+        )
+            .chain(),
+    )
+    .add_systems(OnExit(GameState::PostLoading), remove_loading_screen);
+}
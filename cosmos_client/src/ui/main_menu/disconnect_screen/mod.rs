@@ -1,10 +1,14 @@
 use bevy::{app::App, prelude::*};
 use bevy_renet2::renet2::{DisconnectReason, RenetClient};
 
-use crate::ui::{
-    components::button::{register_button, Button, ButtonEvent, ButtonStyles},
-    font::DefaultFont,
-    settings::SettingsMenuSet,
+use crate::{
+    lang::Localization,
+    ui::{
+        components::button::{register_button, Button, ButtonEvent},
+        font::DefaultFont,
+        settings::SettingsMenuSet,
+        theme::UiTheme,
+    },
 };
 
 use super::{in_main_menu_state, title_screen::TitleScreenSet, MainMenuRootUiNode, MainMenuSubState, MainMenuSystemSet};
@@ -14,19 +18,13 @@ fn create_disconnect_screen(
     q_ui_root: Query<Entity, With<MainMenuRootUiNode>>,
     client: Option<Res<RenetClient>>,
     default_font: Res<DefaultFont>,
+    localization: Res<Localization>,
+    theme: Res<UiTheme>,
 ) {
-    let cool_blue: Color = Srgba::hex("00FFFF").unwrap().into();
+    let accent = theme.accent;
 
-    let text_style = TextFont {
-        font_size: 32.0,
-        font: default_font.0.clone(),
-        ..Default::default()
-    };
-    let text_style_small = TextFont {
-        font_size: 24.0,
-        font: default_font.0.clone(),
-        ..Default::default()
-    };
+    let text_style = theme.font(default_font.0.clone(), 32.0);
+    let text_style_small = theme.font(default_font.0.clone(), 24.0);
 
     let Ok(main_menu_root) = q_ui_root.get_single() else {
         warn!("No main menu UI root.");
@@ -35,7 +33,7 @@ fn create_disconnect_screen(
 
     commands.entity(main_menu_root).with_children(|p| {
         p.spawn((
-            Text::new("Disconnected"),
+            Text::new(localization.get("cosmos:disconnected.title")),
             text_style.clone(),
             Node {
                 margin: UiRect::bottom(Val::Px(20.0)),
@@ -75,9 +73,9 @@ fn create_disconnect_screen(
         ));
 
         p.spawn((
-            BorderColor(cool_blue),
+            BorderColor(accent),
             Node {
-                border: UiRect::all(Val::Px(2.0)),
+                border: theme.border_rect(),
                 width: Val::Px(500.0),
                 height: Val::Px(70.0),
                 align_self: AlignSelf::Center,
@@ -85,13 +83,8 @@ fn create_disconnect_screen(
                 ..Default::default()
             },
             Button::<OkButtonEvent> {
-                button_styles: Some(ButtonStyles {
-                    background_color: Srgba::hex("333333").unwrap().into(),
-                    hover_background_color: Srgba::hex("232323").unwrap().into(),
-                    press_background_color: Srgba::hex("111111").unwrap().into(),
-                    ..Default::default()
-                }),
-                text: Some(("OK".into(), text_style.clone(), Default::default())),
+                button_styles: Some(theme.button_styles()),
+                text: Some((localization.get("cosmos:ok").into(), text_style.clone(), Default::default())),
                 ..Default::default()
             },
         ));
@@ -15,6 +15,7 @@ use super::components::show_cursor::ShowCursor;
 mod disconnect_screen;
 mod menu_panorama;
 mod settings_screen;
+mod singleplayer_screen;
 mod title_screen;
 mod triggers;
 
@@ -47,6 +48,8 @@ pub enum MainMenuSubState {
     Settings,
     /// When the player is disconnected from a server, this will display the latest disconnect message.
     Disconnect,
+    /// Waiting for the singleplayer server to start
+    StartingSingleplayer,
 }
 
 fn despawn_all_main_menu_ents<T: Component>(mut commands: Commands, q_main_menu_entities: Query<Entity, With<T>>) {
@@ -196,6 +199,7 @@ pub(super) fn register(app: &mut App) {
     menu_panorama::register(app);
     title_screen::register(app);
     disconnect_screen::register(app);
+    singleplayer_screen::register(app);
     triggers::register(app);
     settings_screen::register(app);
 
@@ -0,0 +1,147 @@
+use bevy::{app::App, prelude::*};
+
+use crate::{
+    lang::Localization,
+    netty::singleplayer::{cancel_singleplayer, SingleplayerServer},
+    ui::{
+        components::button::{register_button, Button, ButtonEvent},
+        font::DefaultFont,
+        settings::SettingsMenuSet,
+        theme::UiTheme,
+    },
+};
+
+use super::{
+    disconnect_screen::DisconnectMenuSet, in_main_menu_state, title_screen::TitleScreenSet, MainMenuRootUiNode, MainMenuSubState,
+    MainMenuSystemSet,
+};
+
+#[derive(Component)]
+struct SingleplayerStatusText;
+
+fn create_singleplayer_screen(
+    mut commands: Commands,
+    q_ui_root: Query<Entity, With<MainMenuRootUiNode>>,
+    default_font: Res<DefaultFont>,
+    localization: Res<Localization>,
+    theme: Res<UiTheme>,
+) {
+    let accent = theme.accent;
+
+    let text_style = theme.font(default_font.0.clone(), 32.0);
+    let text_style_small = theme.font(default_font.0.clone(), 24.0);
+
+    let Ok(main_menu_root) = q_ui_root.get_single() else {
+        warn!("No main menu UI root.");
+        return;
+    };
+
+    commands.entity(main_menu_root).with_children(|p| {
+        p.spawn((
+            Text::new(localization.get("cosmos:main_menu.singleplayer")),
+            text_style.clone(),
+            Node {
+                margin: UiRect::bottom(Val::Px(20.0)),
+                align_self: AlignSelf::Center,
+                ..Default::default()
+            },
+        ));
+
+        p.spawn((
+            SingleplayerStatusText,
+            Text::new(localization.get("cosmos:singleplayer.starting_world")),
+            text_style_small,
+            Node {
+                margin: UiRect::bottom(Val::Px(50.0)),
+                align_self: AlignSelf::Center,
+                ..Default::default()
+            },
+        ));
+
+        p.spawn((
+            BorderColor(accent),
+            Node {
+                border: theme.border_rect(),
+                width: Val::Px(500.0),
+                height: Val::Px(70.0),
+                align_self: AlignSelf::Center,
+                margin: UiRect::top(Val::Px(20.0)),
+                ..Default::default()
+            },
+            Button::<CancelButtonEvent> {
+                button_styles: Some(theme.button_styles()),
+                text: Some((localization.get("cosmos:cancel").into(), text_style.clone(), Default::default())),
+                ..Default::default()
+            },
+        ));
+    });
+}
+
+fn update_status_text(
+    server: Option<Res<SingleplayerServer>>,
+    localization: Res<Localization>,
+    mut q_text: Query<&mut Text, With<SingleplayerStatusText>>,
+) {
+    let status = localization.get(match server {
+        Some(server) if server.is_shutting_down() => "cosmos:singleplayer.waiting_for_save",
+        Some(_) => "cosmos:singleplayer.starting_world",
+        None => "cosmos:singleplayer.launching_server",
+    });
+
+    for mut text in q_text.iter_mut() {
+        if text.0 != status {
+            text.0 = status.to_owned();
+        }
+    }
+}
+
+fn return_to_title_screen(mut mms: ResMut<MainMenuSubState>) {
+    *mms = MainMenuSubState::TitleScreen;
+}
+
+#[derive(Default, Event, Debug)]
+struct CancelButtonEvent;
+
+impl ButtonEvent for CancelButtonEvent {
+    fn create_event(_: Entity) -> Self {
+        Self
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub(super) enum SingleplayerMenuSet {
+    SingleplayerMenuInteractions,
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<CancelButtonEvent>(app);
+
+    app.configure_sets(
+        Update,
+        SingleplayerMenuSet::SingleplayerMenuInteractions
+            .ambiguous_with(SettingsMenuSet::SettingsMenuInteractions)
+            .ambiguous_with(DisconnectMenuSet::DisconnectMenuInteractions)
+            .ambiguous_with(TitleScreenSet::TitleScreenInteractions),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            create_singleplayer_screen
+                .run_if(in_main_menu_state(MainMenuSubState::StartingSingleplayer))
+                .run_if(resource_exists_and_changed::<MainMenuSubState>)
+                .in_set(MainMenuSystemSet::InitializeMenu),
+            (
+                update_status_text,
+                (cancel_singleplayer, return_to_title_screen)
+                    .chain()
+                    .run_if(on_event::<CancelButtonEvent>),
+            )
+                .chain()
+                .run_if(in_main_menu_state(MainMenuSubState::StartingSingleplayer))
+                .in_set(MainMenuSystemSet::UpdateMenu),
+        )
+            .in_set(SingleplayerMenuSet::SingleplayerMenuInteractions)
+            .chain(),
+    );
+}
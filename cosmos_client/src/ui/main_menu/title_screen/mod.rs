@@ -8,21 +8,23 @@ use cosmos_core::state::GameState;
 use rand::seq::IteratorRandom;
 
 use crate::{
-    netty::connect::HostConfig,
+    lang::Localization,
+    netty::{connect::HostConfig, singleplayer::StartSingleplayer},
     ui::{
         components::{
-            button::{register_button, Button, ButtonEvent, ButtonStyles},
+            button::{register_button, Button, ButtonEvent},
             text_input::{InputType, TextInput},
         },
         font::DefaultFont,
         reactivity::{add_reactable_type, BindValue, BindValues, ReactableFields, ReactableValue},
         settings::SettingsMenuSet,
+        theme::UiTheme,
     },
 };
 
 use super::{
-    super::components::text_input::InputValue, disconnect_screen::DisconnectMenuSet, in_main_menu_state, MainMenuRootUiNode,
-    MainMenuSubState, MainMenuSystemSet,
+    super::components::text_input::InputValue, disconnect_screen::DisconnectMenuSet, in_main_menu_state,
+    singleplayer_screen::SingleplayerMenuSet, MainMenuRootUiNode, MainMenuSubState, MainMenuSystemSet,
 };
 
 #[derive(Debug, Clone, Component, PartialEq, Eq)]
@@ -64,26 +66,20 @@ impl ReactableValue for ErrorMessage {
     }
 }
 
-fn create_main_menu(mut commands: Commands, default_font: Res<DefaultFont>, q_ui_root: Query<Entity, With<MainMenuRootUiNode>>) {
-    let cool_blue = Srgba::hex("00FFFF").unwrap().into();
+fn create_main_menu(
+    mut commands: Commands,
+    default_font: Res<DefaultFont>,
+    localization: Res<Localization>,
+    theme: Res<UiTheme>,
+    q_ui_root: Query<Entity, With<MainMenuRootUiNode>>,
+) {
+    let accent = theme.accent;
 
-    let text_style = TextFont {
-        font_size: 32.0,
-        font: default_font.0.clone(),
-        ..Default::default()
-    };
-    let text_style_small = TextFont {
-        font_size: 24.0,
-        font: default_font.0.clone(),
-        ..Default::default()
-    };
+    let text_style = theme.font(default_font.0.clone(), 32.0);
+    let text_style_small = theme.font(default_font.0.clone(), 24.0);
 
-    let text_blue = TextColor(cool_blue);
-    let text_style_large = TextFont {
-        font_size: 256.0,
-        font: default_font.0.clone(),
-        ..Default::default()
-    };
+    let accent_text = TextColor(accent);
+    let text_style_large = theme.font(default_font.0.clone(), 256.0);
 
     let Ok(main_menu_root) = q_ui_root.get_single() else {
         warn!("No main menu UI root.");
@@ -94,7 +90,7 @@ fn create_main_menu(mut commands: Commands, default_font: Res<DefaultFont>, q_ui
         p.spawn((
             Text::new("COSMOS"),
             text_style_large,
-            text_blue,
+            accent_text,
             Node {
                 margin: UiRect::bottom(Val::Px(50.0)),
                 align_self: AlignSelf::Center,
@@ -103,22 +99,42 @@ fn create_main_menu(mut commands: Commands, default_font: Res<DefaultFont>, q_ui
         ));
 
         p.spawn((
-            BorderColor(cool_blue),
+            BorderColor(accent),
             Node {
-                border: UiRect::all(Val::Px(2.0)),
+                border: theme.border_rect(),
                 width: Val::Px(500.0),
                 height: Val::Px(70.0),
                 align_self: AlignSelf::Center,
                 ..Default::default()
             },
+            Button::<SingleplayerButtonEvent> {
+                button_styles: Some(theme.button_styles()),
+                text: Some((
+                    localization.get("cosmos:main_menu.singleplayer").into(),
+                    text_style.clone(),
+                    Default::default(),
+                )),
+                ..Default::default()
+            },
+        ));
+
+        p.spawn((
+            BorderColor(accent),
+            Node {
+                border: theme.border_rect(),
+                width: Val::Px(500.0),
+                height: Val::Px(70.0),
+                align_self: AlignSelf::Center,
+                margin: UiRect::top(Val::Px(20.0)),
+                ..Default::default()
+            },
             Button::<ConnectButtonEvent> {
-                button_styles: Some(ButtonStyles {
-                    background_color: Srgba::hex("333333").unwrap().into(),
-                    hover_background_color: Srgba::hex("232323").unwrap().into(),
-                    press_background_color: Srgba::hex("111111").unwrap().into(),
-                    ..Default::default()
-                }),
-                text: Some(("Connect".into(), text_style.clone(), Default::default())),
+                button_styles: Some(theme.button_styles()),
+                text: Some((
+                    localization.get("cosmos:main_menu.connect").into(),
+                    text_style.clone(),
+                    Default::default(),
+                )),
                 ..Default::default()
             },
         ));
@@ -152,10 +168,10 @@ fn create_main_menu(mut commands: Commands, default_font: Res<DefaultFont>, q_ui
                 ..Default::default()
             },
             InputValue::new(name),
-            BorderColor(Srgba::hex("555555").unwrap().into()),
-            BackgroundColor(Srgba::hex("111111").unwrap().into()),
+            BorderColor(theme.border),
+            BackgroundColor(theme.input_background),
             Node {
-                border: UiRect::all(Val::Px(2.0)),
+                border: theme.border_rect(),
                 width: Val::Px(500.0),
                 min_height: Val::Px(45.0),
                 align_self: AlignSelf::Center,
@@ -177,10 +193,10 @@ fn create_main_menu(mut commands: Commands, default_font: Res<DefaultFont>, q_ui
                 ..Default::default()
             },
             InputValue::new("localhost"),
-            BorderColor(Srgba::hex("555555").unwrap().into()),
-            BackgroundColor(Srgba::hex("111111").unwrap().into()),
+            BorderColor(theme.border),
+            BackgroundColor(theme.input_background),
             Node {
-                border: UiRect::all(Val::Px(2.0)),
+                border: theme.border_rect(),
                 width: Val::Px(500.0),
                 min_height: Val::Px(45.0),
                 align_self: AlignSelf::Center,
@@ -195,9 +211,9 @@ fn create_main_menu(mut commands: Commands, default_font: Res<DefaultFont>, q_ui
         ));
 
         p.spawn((
-            BorderColor(cool_blue),
+            BorderColor(accent),
             Node {
-                border: UiRect::all(Val::Px(2.0)),
+                border: theme.border_rect(),
                 width: Val::Px(500.0),
                 height: Val::Px(70.0),
                 align_self: AlignSelf::Center,
@@ -205,21 +221,20 @@ fn create_main_menu(mut commands: Commands, default_font: Res<DefaultFont>, q_ui
                 ..Default::default()
             },
             Button::<SettingsButtonEvent> {
-                button_styles: Some(ButtonStyles {
-                    background_color: Srgba::hex("333333").unwrap().into(),
-                    hover_background_color: Srgba::hex("232323").unwrap().into(),
-                    press_background_color: Srgba::hex("111111").unwrap().into(),
-                    ..Default::default()
-                }),
-                text: Some(("Settings".into(), text_style.clone(), Default::default())),
+                button_styles: Some(theme.button_styles()),
+                text: Some((
+                    localization.get("cosmos:main_menu.settings").into(),
+                    text_style.clone(),
+                    Default::default(),
+                )),
                 ..Default::default()
             },
         ));
 
         p.spawn((
-            BorderColor(cool_blue),
+            BorderColor(accent),
             Node {
-                border: UiRect::all(Val::Px(2.0)),
+                border: theme.border_rect(),
                 width: Val::Px(500.0),
                 height: Val::Px(70.0),
                 align_self: AlignSelf::Center,
@@ -227,13 +242,12 @@ fn create_main_menu(mut commands: Commands, default_font: Res<DefaultFont>, q_ui
                 ..Default::default()
             },
             Button::<QuitButtonEvent> {
-                button_styles: Some(ButtonStyles {
-                    background_color: Srgba::hex("333333").unwrap().into(),
-                    hover_background_color: Srgba::hex("232323").unwrap().into(),
-                    press_background_color: Srgba::hex("111111").unwrap().into(),
-                    ..Default::default()
-                }),
-                text: Some(("Quit".into(), text_style.clone(), Default::default())),
+                button_styles: Some(theme.button_styles()),
+                text: Some((
+                    localization.get("cosmos:main_menu.quit").into(),
+                    text_style.clone(),
+                    Default::default(),
+                )),
                 ..Default::default()
             },
         ));
@@ -249,6 +263,15 @@ impl ButtonEvent for ConnectButtonEvent {
     }
 }
 
+#[derive(Default, Event, Debug)]
+struct SingleplayerButtonEvent;
+
+impl ButtonEvent for SingleplayerButtonEvent {
+    fn create_event(_: Entity) -> Self {
+        Self
+    }
+}
+
 #[derive(Default, Event, Debug)]
 struct SettingsButtonEvent;
 
@@ -271,6 +294,35 @@ fn goto_settings(mut mms: ResMut<MainMenuSubState>) {
     *mms = MainMenuSubState::Settings;
 }
 
+/// Returns false (and sets the error message) if the name is invalid, otherwise saves it for next time.
+fn validate_player_name(player_name: &PlayerName, em: &mut ErrorMessage) -> bool {
+    if player_name.0.is_empty() || player_name.0.len() > 32 {
+        em.0 = "Must have a name".to_owned();
+        return false;
+    }
+
+    fs::write("name.env", &player_name.0).unwrap_or_else(|e| {
+        error!("Failed to save name ;(\n{e:?}");
+    });
+
+    true
+}
+
+fn start_singleplayer(mut q_vars: Query<(&PlayerName, &mut ErrorMessage)>, mut mms: ResMut<MainMenuSubState>, mut commands: Commands) {
+    let Ok((player_name, mut em)) = q_vars.get_single_mut() else {
+        return;
+    };
+
+    if !validate_player_name(player_name, &mut em) {
+        return;
+    }
+
+    commands.insert_resource(StartSingleplayer {
+        player_name: player_name.0.clone(),
+    });
+    *mms = MainMenuSubState::StartingSingleplayer;
+}
+
 fn trigger_connection(
     mut q_vars: Query<(&PlayerName, &ConnectionString, &mut ErrorMessage)>,
     mut state: ResMut<NextState<GameState>>,
@@ -318,15 +370,10 @@ fn trigger_connection(
         return;
     }
 
-    if player_name.0.is_empty() || player_name.0.len() > 32 {
-        em.0 = "Must have a name".to_owned();
+    if !validate_player_name(player_name, &mut em) {
         return;
     }
 
-    fs::write("name.env", &player_name.0).unwrap_or_else(|e| {
-        error!("Failed to save name ;(\n{e:?}");
-    });
-
     commands.insert_resource(HostConfig {
         name: player_name.0.clone(),
         host_name: host_name.into(),
@@ -345,6 +392,7 @@ pub(super) enum TitleScreenSet {
 }
 
 pub(super) fn register(app: &mut App) {
+    register_button::<SingleplayerButtonEvent>(app);
     register_button::<ConnectButtonEvent>(app);
     register_button::<SettingsButtonEvent>(app);
     register_button::<QuitButtonEvent>(app);
@@ -356,6 +404,7 @@ pub(super) fn register(app: &mut App) {
         Update,
         TitleScreenSet::TitleScreenInteractions
             .ambiguous_with(DisconnectMenuSet::DisconnectMenuInteractions)
+            .ambiguous_with(SingleplayerMenuSet::SingleplayerMenuInteractions)
             .ambiguous_with(SettingsMenuSet::SettingsMenuInteractions),
     );
 
@@ -366,6 +415,10 @@ pub(super) fn register(app: &mut App) {
                 .run_if(in_main_menu_state(MainMenuSubState::TitleScreen))
                 .run_if(resource_exists_and_changed::<MainMenuSubState>)
                 .in_set(MainMenuSystemSet::InitializeMenu),
+            start_singleplayer
+                .run_if(on_event::<SingleplayerButtonEvent>)
+                .run_if(in_main_menu_state(MainMenuSubState::TitleScreen))
+                .in_set(MainMenuSystemSet::UpdateMenu),
             goto_settings
                 .run_if(on_event::<SettingsButtonEvent>)
                 .run_if(in_main_menu_state(MainMenuSubState::TitleScreen))
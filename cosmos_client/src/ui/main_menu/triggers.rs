@@ -5,6 +5,8 @@ use bevy::{
 use bevy_renet2::renet2::{DisconnectReason, RenetClient};
 use cosmos_core::state::GameState;
 
+use crate::netty::reconnect::is_disconnected_without_reconnect;
+
 use super::MainMenuSubState;
 
 fn switch_to_title(mut commands: Commands, mut state: ResMut<NextState<GameState>>, client: Res<RenetClient>) {
@@ -25,8 +27,14 @@ fn is_client_disconnected(client: Option<Res<RenetClient>>) -> bool {
 pub(super) fn register(app: &mut App) {
     app.add_systems(
         Update,
-        switch_to_title
-            .run_if(in_state(GameState::Playing).or(in_state(GameState::Connecting)))
-            .run_if(is_client_disconnected),
+        (
+            switch_to_title
+                .run_if(in_state(GameState::Connecting))
+                .run_if(is_client_disconnected),
+            // If the connection was lost unexpectedly while playing, the reconnect logic will take over.
+            switch_to_title
+                .run_if(in_state(GameState::Playing))
+                .run_if(is_disconnected_without_reconnect),
+        ),
     );
 }
@@ -17,12 +17,17 @@ pub mod font;
 pub mod hotbar;
 mod hud;
 pub mod item_renderer;
+mod loading_screen;
 pub mod main_menu;
 pub mod message;
+pub mod network_stats_display;
 pub mod pause;
+mod profiling_display;
 pub mod reactivity;
+pub mod scale;
 pub mod settings;
 pub mod ship_flight;
+pub mod theme;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 /// All systems that handle GUI interactions should be in here
@@ -100,7 +105,10 @@ pub(super) fn register(app: &mut App) {
     hotbar::register(app);
     debug_info_display::register(app);
     item_renderer::register(app);
+    loading_screen::register(app);
     message::register(app);
+    network_stats_display::register(app);
+    profiling_display::register(app);
     ship_flight::register(app);
     components::register(app);
     reactivity::register(app);
@@ -109,6 +117,8 @@ pub(super) fn register(app: &mut App) {
     font::register(app);
     pause::register(app);
     settings::register(app);
+    theme::register(app);
+    scale::register(app);
 
     app.configure_sets(Update, (UiSystemSet::PreDoUi, UiSystemSet::DoUi, UiSystemSet::FinishUi).chain());
 
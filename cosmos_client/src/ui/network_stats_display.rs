@@ -0,0 +1,144 @@
+//! Displays a debug panel with information about the client's connection to the server
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_renet2::renet2::RenetClient;
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    netty::network_stats::{ChannelStats, MessageKindStats, NetworkStats},
+    state::GameState,
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    netty::gameplay::sync::interpolation::InterpolationStats,
+};
+
+use super::font::DefaultFont;
+
+/// How many of the busiest channels are displayed in each direction
+const CHANNELS_SHOWN: usize = 5;
+/// How many of the busiest types of message are displayed in each direction
+const MESSAGE_KINDS_SHOWN: usize = 5;
+
+#[derive(Component)]
+struct NetworkStatsDisplay;
+
+#[derive(Component)]
+struct NetworkStatsText;
+
+fn toggle_network_stats(
+    mut commands: Commands,
+    input_handler: InputChecker,
+    q_display: Query<Entity, With<NetworkStatsDisplay>>,
+    default_font: Res<DefaultFont>,
+) {
+    if !input_handler.check_just_pressed(CosmosInputs::ToggleNetworkStats) {
+        return;
+    }
+
+    if let Ok(ent) = q_display.get_single() {
+        commands.entity(ent).insert(NeedsDespawned);
+        return;
+    }
+
+    commands
+        .spawn((
+            Name::new("Network Stats Display"),
+            NetworkStatsDisplay,
+            Node {
+                top: Val::Px(5.0),
+                right: Val::Px(5.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                position_type: PositionType::Absolute,
+                ..Default::default()
+            },
+            BackgroundColor(Srgba::hex("00000099").unwrap().into()),
+        ))
+        .with_children(|p| {
+            p.spawn((
+                NetworkStatsText,
+                Text::new("Gathering network stats..."),
+                TextFont {
+                    font: default_font.0.clone(),
+                    font_size: 16.0,
+                    ..Default::default()
+                },
+            ));
+        });
+}
+
+fn format_channel_stats(direction: &str, name: &str, (messages, bytes): (u64, u64)) -> String {
+    format!("{direction} {name}: {messages}/s ({:.1} KB/s)", bytes as f32 / 1024.0)
+}
+
+fn channel_lines<'a>(direction: &'a str, stats: &'a [ChannelStats]) -> impl Iterator<Item = String> + 'a {
+    stats
+        .iter()
+        .take(CHANNELS_SHOWN)
+        .map(move |stats| format_channel_stats(direction, &stats.channel, (stats.messages, stats.bytes)))
+}
+
+fn message_kind_lines<'a>(direction: &'a str, stats: &'a [MessageKindStats]) -> impl Iterator<Item = String> + 'a {
+    stats
+        .iter()
+        .take(MESSAGE_KINDS_SHOWN)
+        .map(move |stats| format_channel_stats(direction, &stats.kind.to_string(), (stats.messages, stats.bytes)))
+}
+
+fn update_network_stats(
+    client: Res<RenetClient>,
+    network_stats: Res<NetworkStats>,
+    interpolation_stats: Res<InterpolationStats>,
+    mut q_text: Query<&mut Text, With<NetworkStatsText>>,
+) {
+    let Ok(mut text) = q_text.get_single_mut() else {
+        return;
+    };
+
+    let info = client.network_info();
+
+    let mut lines = vec![
+        "Network Stats".to_owned(),
+        format!("RTT: {:.1}ms | Packet Loss: {:.1}%", info.rtt * 1000.0, info.packet_loss * 100.0),
+        format!(
+            "Up: {:.1} KB/s | Down: {:.1} KB/s",
+            info.bytes_sent_per_second / 1024.0,
+            info.bytes_received_per_second / 1024.0
+        ),
+        format!(
+            "Interpolation: {} interpolating, {} extrapolating, {} starved (avg buffer {:.1})",
+            interpolation_stats.interpolating,
+            interpolation_stats.extrapolating,
+            interpolation_stats.starved,
+            interpolation_stats.average_buffer_len
+        ),
+        String::new(),
+        format_channel_stats("Up", "total", network_stats.total_sent()),
+    ];
+
+    lines.extend(channel_lines("Up", network_stats.sent()));
+    lines.push(format_channel_stats("Down", "total", network_stats.total_received()));
+    lines.extend(channel_lines("Down", network_stats.received()));
+    lines.push(String::new());
+    lines.push("By message type".to_owned());
+    lines.extend(message_kind_lines("Up", network_stats.sent_by_kind()));
+    lines.extend(message_kind_lines("Down", network_stats.received_by_kind()));
+
+    text.0 = lines.join("\n");
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            toggle_network_stats,
+            update_network_stats
+                .run_if(resource_exists::<RenetClient>)
+                .run_if(on_timer(Duration::from_millis(500))),
+        )
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -9,16 +9,18 @@ use renet2::RenetClient;
 
 use crate::{
     input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    lang::Localization,
     window::setup::CursorFlagsSet,
 };
 
 use super::{
     components::{
-        button::{register_button, Button, ButtonEvent, ButtonStyles},
+        button::{register_button, Button, ButtonEvent},
         show_cursor::ShowCursor,
     },
     font::DefaultFont,
     settings::{NeedsSettingsAdded, SettingsCancelButtonEvent, SettingsDoneButtonEvent, SettingsMenuSet},
+    theme::UiTheme,
     CloseMethod, OpenMenu, UiSystemSet,
 };
 
@@ -35,6 +37,8 @@ fn toggle_pause_menu(
     q_pause_menu: Query<Entity, With<PauseMenu>>,
     input_handler: InputChecker,
     default_font: Res<DefaultFont>,
+    localization: Res<Localization>,
+    theme: Res<UiTheme>,
 ) {
     if !input_handler.check_just_pressed(CosmosInputs::Pause) {
         return;
@@ -55,22 +59,13 @@ fn toggle_pause_menu(
         return;
     }
 
-    let text_style = TextFont {
-        font_size: 32.0,
-        font: default_font.0.clone(),
-        ..Default::default()
-    };
+    let text_style = theme.font(default_font.0.clone(), 32.0);
 
-    let cool_blue = Srgba::hex("00FFFF").unwrap();
+    let accent = theme.accent;
 
-    let button_styles = Some(ButtonStyles {
-        background_color: Srgba::hex("333333").unwrap().into(),
-        hover_background_color: Srgba::hex("232323").unwrap().into(),
-        press_background_color: Srgba::hex("111111").unwrap().into(),
-        ..Default::default()
-    });
+    let button_styles = Some(theme.button_styles());
     let style = Node {
-        border: UiRect::all(Val::Px(2.0)),
+        border: theme.border_rect(),
         width: Val::Px(500.0),
         height: Val::Px(70.0),
         align_self: AlignSelf::Center,
@@ -105,31 +100,43 @@ fn toggle_pause_menu(
         ))
         .with_children(|p| {
             p.spawn((
-                BorderColor(cool_blue.into()),
+                BorderColor(accent),
                 style.clone(),
                 Button::<ResumeButtonEvent> {
                     button_styles: button_styles.clone(),
-                    text: Some(("RESUME".into(), text_style.clone(), Default::default())),
+                    text: Some((
+                        localization.get("cosmos:pause.resume").into(),
+                        text_style.clone(),
+                        Default::default(),
+                    )),
                     ..Default::default()
                 },
             ));
 
             p.spawn((
-                BorderColor(cool_blue.into()),
+                BorderColor(accent),
                 style.clone(),
                 Button::<SettingsButtonEvent> {
                     button_styles: button_styles.clone(),
-                    text: Some(("SETTINGS".into(), text_style.clone(), Default::default())),
+                    text: Some((
+                        localization.get("cosmos:pause.settings").into(),
+                        text_style.clone(),
+                        Default::default(),
+                    )),
                     ..Default::default()
                 },
             ));
 
             p.spawn((
-                BorderColor(cool_blue.into()),
+                BorderColor(accent),
                 style.clone(),
                 Button::<DisconnectButtonEvent> {
                     button_styles: button_styles.clone(),
-                    text: Some(("DISCONNECT".into(), text_style.clone(), Default::default())),
+                    text: Some((
+                        localization.get("cosmos:pause.disconnect").into(),
+                        text_style.clone(),
+                        Default::default(),
+                    )),
                     ..Default::default()
                 },
             ));
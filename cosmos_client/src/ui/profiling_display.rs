@@ -0,0 +1,175 @@
+//! Displays a debug panel with a breakdown of how long the client's frames and the server's ticks take
+
+use std::time::Duration;
+
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+    time::common_conditions::on_timer,
+};
+use cosmos_core::{
+    debug::profiling::{profile_system_set, RequestServerDiagnosticsEvent, ServerDiagnosticsEvent, SystemSetTimings, FRAME_TIMING},
+    ecs::NeedsDespawned,
+    netty::{
+        sync::events::client_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    state::GameState,
+    structure::Structure,
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    rendering::structure_renderer::StructureRenderingSet,
+};
+
+use super::{font::DefaultFont, UiSystemSet};
+
+#[derive(Component)]
+struct ProfilingDisplay;
+
+#[derive(Component)]
+struct ProfilingText;
+
+#[derive(Resource, Default)]
+/// The most recent diagnostics the server has sent us
+struct LatestServerDiagnostics(Option<ServerDiagnosticsEvent>);
+
+fn toggle_profiler(
+    mut commands: Commands,
+    input_handler: InputChecker,
+    q_display: Query<Entity, With<ProfilingDisplay>>,
+    default_font: Res<DefaultFont>,
+    mut latest: ResMut<LatestServerDiagnostics>,
+    mut nevw_request_diagnostics: NettyEventWriter<RequestServerDiagnosticsEvent>,
+) {
+    if !input_handler.check_just_pressed(CosmosInputs::ToggleProfiler) {
+        return;
+    }
+
+    if let Ok(ent) = q_display.get_single() {
+        commands.entity(ent).insert(NeedsDespawned);
+        nevw_request_diagnostics.send(RequestServerDiagnosticsEvent { enabled: false });
+        return;
+    }
+
+    latest.0 = None;
+    nevw_request_diagnostics.send(RequestServerDiagnosticsEvent { enabled: true });
+
+    commands
+        .spawn((
+            Name::new("Profiling Display"),
+            ProfilingDisplay,
+            Node {
+                top: Val::Px(5.0),
+                left: Val::Px(5.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                position_type: PositionType::Absolute,
+                ..Default::default()
+            },
+            BackgroundColor(Srgba::hex("00000099").unwrap().into()),
+        ))
+        .with_children(|p| {
+            p.spawn((
+                ProfilingText,
+                Text::new("Gathering profiling data..."),
+                TextFont {
+                    font: default_font.0.clone(),
+                    font_size: 16.0,
+                    ..Default::default()
+                },
+            ));
+        });
+}
+
+fn receive_server_diagnostics(
+    mut nevr_diagnostics: EventReader<NettyEventReceived<ServerDiagnosticsEvent>>,
+    mut latest: ResMut<LatestServerDiagnostics>,
+) {
+    if let Some(ev) = nevr_diagnostics.read().last() {
+        latest.0 = Some(ev.clone());
+    }
+}
+
+fn format_ms(duration: Duration) -> String {
+    format!("{:.2}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn update_profiler(
+    diagnostics: Res<DiagnosticsStore>,
+    timings: Res<SystemSetTimings>,
+    latest: Res<LatestServerDiagnostics>,
+    q_entities: Query<()>,
+    q_structures: Query<&Structure>,
+    mut q_text: Query<&mut Text, With<ProfilingText>>,
+) {
+    let Ok(mut text) = q_text.get_single_mut() else {
+        return;
+    };
+
+    let frame_time_ms = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FRAME_TIME)
+        .and_then(|d| d.smoothed())
+        .unwrap_or(0.0);
+    let frame_time = Duration::from_secs_f64(frame_time_ms / 1000.0);
+    let update_time = timings.get(FRAME_TIMING).unwrap_or_default();
+
+    let mut lines = vec![
+        "Client".to_owned(),
+        format!(
+            "Frame: {} ({:.0} FPS)",
+            format_ms(frame_time),
+            1000.0 / frame_time_ms.max(f64::EPSILON)
+        ),
+        format!("  Render/other: {}", format_ms(frame_time.saturating_sub(update_time))),
+    ];
+
+    lines.extend(
+        timings
+            .sorted()
+            .into_iter()
+            .map(|(name, duration)| format!("  {name}: {}", format_ms(duration))),
+    );
+
+    lines.push(format!(
+        "Entities: {} | Chunks: {}",
+        q_entities.iter().count(),
+        q_structures.iter().map(|s| s.chunks().len()).sum::<usize>()
+    ));
+
+    lines.push(String::new());
+    lines.push("Server".to_owned());
+
+    if let Some(server) = &latest.0 {
+        lines.push(format!("Tick: {}", format_ms(server.tick_time)));
+        lines.extend(
+            server
+                .set_timings
+                .iter()
+                .map(|(name, duration)| format!("  {name}: {}", format_ms(*duration))),
+        );
+        lines.push(format!("Entities: {} | Chunks: {}", server.entity_count, server.chunk_count));
+    } else {
+        lines.push("Waiting for server...".to_owned());
+    }
+
+    text.0 = lines.join("\n");
+}
+
+pub(super) fn register(app: &mut App) {
+    profile_system_set(app, Update, "UI", UiSystemSet::DoUi);
+    profile_system_set(app, Update, "Chunk Meshing", StructureRenderingSet::BeginRendering);
+    profile_system_set(app, Update, "Game Logic", NetworkingSystemsSet::Between);
+
+    app.init_resource::<LatestServerDiagnostics>().add_systems(
+        Update,
+        (
+            toggle_profiler,
+            receive_server_diagnostics,
+            update_profiler.run_if(on_timer(Duration::from_millis(500))),
+        )
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
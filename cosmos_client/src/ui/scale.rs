@@ -0,0 +1,52 @@
+//! Applies the player's UI scale setting through bevy's [`UiScale`], without letting the UI grow larger than the window can fit.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::settings::{Setting, SettingsRegistry, SettingsSet};
+
+/// The window size (in unscaled UI pixels) the inventory & hotbar layouts need to not overlap.
+///
+/// Two inventories can be open side by side, each 100px from the edge of the window.
+const MIN_LAYOUT_WIDTH: f32 = 1400.0;
+/// Enough room for an inventory with a few rows above the hotbar
+const MIN_LAYOUT_HEIGHT: f32 = 600.0;
+
+#[derive(Resource, Debug, Clone, Copy)]
+/// The UI scale the player wants. The actual [`UiScale`] may be smaller if the window is too small to fit this.
+pub struct DesiredUiScale(pub f32);
+
+impl Default for DesiredUiScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+fn load_ui_scale(settings: Res<Registry<Setting>>, mut desired: ResMut<DesiredUiScale>) {
+    desired.0 = (settings.i32_or("cosmos:ui_scale", 100) as f32 / 100.0).clamp(0.5, 2.0);
+}
+
+fn apply_ui_scale(desired: Res<DesiredUiScale>, q_window: Query<&Window, With<PrimaryWindow>>, mut ui_scale: ResMut<UiScale>) {
+    let Ok(window) = q_window.get_single() else {
+        return;
+    };
+
+    // Scaling down is always allowed, but scaling up stops once the layouts would no longer fit.
+    let max_scale = (window.width() / MIN_LAYOUT_WIDTH)
+        .min(window.height() / MIN_LAYOUT_HEIGHT)
+        .max(1.0);
+    let scale = desired.0.min(max_scale);
+
+    if ui_scale.0 != scale {
+        ui_scale.0 = scale;
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<DesiredUiScale>().add_systems(
+        Update,
+        (
+            load_ui_scale.in_set(SettingsSet::LoadSettings),
+            apply_ui_scale.after(SettingsSet::LoadSettings),
+        ),
+    );
+}
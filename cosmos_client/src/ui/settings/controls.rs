@@ -0,0 +1,326 @@
+//! The controls section of the settings menu, where players can rebind their inputs.
+//!
+//! Changes are made to a copy of the controls ([`EditingControls`]), which only replaces the real
+//! controls once the settings menu's `Done` button is clicked.
+
+use bevy::prelude::*;
+
+use crate::{
+    input::inputs::{
+        default_input_handler, gamepad_button_display_name, save_controls, CosmosInputHandler, CosmosInputs, GamepadInputs, InputBinding,
+    },
+    lang::Localization,
+    ui::{
+        components::button::{register_button, Button, ButtonEvent, ButtonStyles},
+        font::DefaultFont,
+        theme::UiTheme,
+    },
+};
+
+use super::{SettingsCancelButtonEvent, SettingsDoneButtonEvent, SettingsMenuSet};
+
+#[derive(Component)]
+/// Add this to a UI node inside the settings menu to have the controls section added to it
+pub(super) struct NeedsControlsAdded;
+
+#[derive(Resource, Debug)]
+/// The controls as they are being edited in the settings menu
+struct EditingControls(CosmosInputHandler);
+
+#[derive(Resource, Debug)]
+/// The binding button that is waiting for the player to press something
+struct ListeningForBinding(Entity);
+
+#[derive(Component, Debug)]
+struct ControlBindingButton {
+    input: CosmosInputs,
+    /// If this is the gamepad binding rather than the keyboard/mouse one
+    gamepad: bool,
+}
+
+fn binding_button_styles(theme: &UiTheme, conflicting: bool) -> ButtonStyles {
+    let text_color = if conflicting { theme.negative } else { theme.text };
+
+    ButtonStyles {
+        background_color: theme.input_background,
+        hover_background_color: Srgba::hex("232323").unwrap().into(),
+        press_background_color: Srgba::hex("333333").unwrap().into(),
+        foreground_color: text_color,
+        hover_foreground_color: text_color,
+        press_foreground_color: text_color,
+    }
+}
+
+fn create_controls_section(
+    mut commands: Commands,
+    q_needs_controls: Query<Entity, Added<NeedsControlsAdded>>,
+    input_handler: Res<CosmosInputHandler>,
+    default_font: Res<DefaultFont>,
+    localization: Res<Localization>,
+    theme: Res<UiTheme>,
+) {
+    let Ok(controls_root) = q_needs_controls.get_single() else {
+        return;
+    };
+
+    let text_style = TextFont {
+        font_size: 32.0,
+        font: default_font.0.clone(),
+        ..Default::default()
+    };
+    let text_style_small = TextFont {
+        font_size: 24.0,
+        font: default_font.0.clone(),
+        ..Default::default()
+    };
+
+    commands.insert_resource(EditingControls(input_handler.clone()));
+
+    let mut inputs = input_handler
+        .iter()
+        .map(|(input, _)| (input, input.display_name()))
+        .collect::<Vec<_>>();
+    inputs.sort_by(|(_, a), (_, b)| a.cmp(b));
+
+    commands.entity(controls_root).with_children(|p| {
+        p.spawn((
+            Text::new(localization.get("cosmos:settings.controls")),
+            text_style.clone(),
+            Node {
+                margin: UiRect::bottom(Val::Px(20.0)),
+                align_self: AlignSelf::Center,
+                ..Default::default()
+            },
+        ));
+
+        for (input, display_name) in inputs {
+            p.spawn(Node {
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                column_gap: Val::Px(20.0),
+                margin: UiRect::bottom(Val::Px(20.0)),
+                ..Default::default()
+            })
+            .with_children(|p| {
+                p.spawn((
+                    Text::new(display_name),
+                    text_style.clone(),
+                    Node {
+                        width: Val::Px(500.0),
+                        align_self: AlignSelf::Center,
+                        ..Default::default()
+                    },
+                ));
+
+                for gamepad in [false, true] {
+                    p.spawn((
+                        ControlBindingButton { input, gamepad },
+                        BorderColor(theme.border),
+                        Node {
+                            border: UiRect::all(Val::Px(2.0)),
+                            width: Val::Px(250.0),
+                            height: Val::Px(45.0),
+                            align_self: AlignSelf::Center,
+                            ..Default::default()
+                        },
+                        Button::<ControlBindingClicked> {
+                            button_styles: Some(binding_button_styles(&theme, false)),
+                            text: Some((String::new(), text_style_small.clone(), Default::default())),
+                            ..Default::default()
+                        },
+                    ));
+                }
+            });
+        }
+
+        p.spawn((
+            BorderColor(theme.border),
+            Node {
+                border: UiRect::all(Val::Px(2.0)),
+                width: Val::Px(300.0),
+                height: Val::Px(50.0),
+                align_self: AlignSelf::Center,
+                margin: UiRect::bottom(Val::Px(20.0)),
+                ..Default::default()
+            },
+            Button::<ResetControlsClicked> {
+                button_styles: Some(binding_button_styles(&theme, false)),
+                text: Some((
+                    localization.get("cosmos:settings.reset_controls").into(),
+                    text_style_small,
+                    Default::default(),
+                )),
+                ..Default::default()
+            },
+        ));
+    });
+}
+
+fn on_binding_clicked(mut commands: Commands, mut evr_clicked: EventReader<ControlBindingClicked>) {
+    if let Some(ev) = evr_clicked.read().last() {
+        commands.insert_resource(ListeningForBinding(ev.0));
+    }
+}
+
+fn listen_for_binding(
+    mut commands: Commands,
+    listening: Res<ListeningForBinding>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepad_inputs: Res<GamepadInputs>,
+    q_buttons: Query<&ControlBindingButton>,
+    mut editing: ResMut<EditingControls>,
+) {
+    // The click that started listening shouldn't be used as the binding
+    if listening.is_added() {
+        return;
+    }
+
+    let Ok(button) = q_buttons.get(listening.0) else {
+        commands.remove_resource::<ListeningForBinding>();
+        return;
+    };
+
+    if keys.just_pressed(KeyCode::Escape) {
+        commands.remove_resource::<ListeningForBinding>();
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::Delete) || keys.just_pressed(KeyCode::Backspace) {
+        if button.gamepad {
+            editing.0.set_gamepad_binding(button.input, None);
+        } else {
+            editing.0.set_binding(button.input, None);
+        }
+        commands.remove_resource::<ListeningForBinding>();
+        return;
+    }
+
+    if button.gamepad {
+        let Some(gamepad_button) = gamepad_inputs.buttons().get_just_pressed().next() else {
+            return;
+        };
+
+        editing.0.set_gamepad_binding(button.input, Some(*gamepad_button));
+    } else {
+        let binding = keys
+            .get_just_pressed()
+            .next()
+            .map(|key| InputBinding::Key(*key))
+            .or_else(|| mouse.get_just_pressed().next().map(|button| InputBinding::Mouse(*button)));
+
+        let Some(binding) = binding else {
+            return;
+        };
+
+        editing.0.set_binding(button.input, Some(binding));
+    }
+
+    commands.remove_resource::<ListeningForBinding>();
+}
+
+fn update_binding_buttons(
+    theme: Res<UiTheme>,
+    editing: Res<EditingControls>,
+    listening: Option<Res<ListeningForBinding>>,
+    mut q_buttons: Query<(Entity, &ControlBindingButton, &mut Button<ControlBindingClicked>)>,
+) {
+    for (entity, binding_button, mut button) in q_buttons.iter_mut() {
+        let binding = editing.0.binding_for(binding_button.input);
+
+        let text = if listening.as_ref().is_some_and(|l| l.0 == entity) {
+            "Press any button...".to_owned()
+        } else if binding_button.gamepad {
+            binding.gamepad.map(gamepad_button_display_name).unwrap_or_else(|| "None".into())
+        } else {
+            binding.binding.map(|b| b.display_name()).unwrap_or_else(|| "None".into())
+        };
+
+        let conflicting = editing.0.conflicts_for(binding_button.input).into_iter().any(|other| {
+            let other = editing.0.binding_for(other);
+
+            if binding_button.gamepad {
+                other.gamepad == binding.gamepad
+            } else {
+                other.binding == binding.binding
+            }
+        });
+
+        let styles = binding_button_styles(&theme, conflicting);
+
+        let Some((current_text, _, _)) = &button.text else {
+            continue;
+        };
+
+        if *current_text == text && button.button_styles.as_ref().map(|s| s.foreground_color) == Some(styles.foreground_color) {
+            continue;
+        }
+
+        if let Some((current_text, _, _)) = button.text.as_mut() {
+            *current_text = text;
+        }
+        button.button_styles = Some(styles);
+    }
+}
+
+fn reset_clicked(mut commands: Commands, mut editing: ResMut<EditingControls>) {
+    editing.0 = default_input_handler();
+    commands.remove_resource::<ListeningForBinding>();
+}
+
+fn done_clicked(mut commands: Commands, editing: Res<EditingControls>, mut input_handler: ResMut<CosmosInputHandler>) {
+    if *input_handler != editing.0 {
+        *input_handler = editing.0.clone();
+        save_controls(&input_handler);
+    }
+
+    commands.remove_resource::<EditingControls>();
+    commands.remove_resource::<ListeningForBinding>();
+}
+
+fn cancel_clicked(mut commands: Commands) {
+    commands.remove_resource::<EditingControls>();
+    commands.remove_resource::<ListeningForBinding>();
+}
+
+#[derive(Event, Debug)]
+struct ControlBindingClicked(Entity);
+
+impl ButtonEvent for ControlBindingClicked {
+    fn create_event(e: Entity) -> Self {
+        Self(e)
+    }
+}
+
+#[derive(Event, Debug)]
+struct ResetControlsClicked;
+
+impl ButtonEvent for ResetControlsClicked {
+    fn create_event(_: Entity) -> Self {
+        Self
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    register_button::<ControlBindingClicked>(app);
+    register_button::<ResetControlsClicked>(app);
+
+    app.add_systems(
+        Update,
+        (
+            create_controls_section,
+            (
+                on_binding_clicked.run_if(on_event::<ControlBindingClicked>),
+                listen_for_binding.run_if(resource_exists::<ListeningForBinding>),
+                reset_clicked.run_if(on_event::<ResetControlsClicked>),
+                update_binding_buttons,
+                done_clicked.run_if(on_event::<SettingsDoneButtonEvent>),
+                cancel_clicked.run_if(on_event::<SettingsCancelButtonEvent>),
+            )
+                .chain()
+                .run_if(resource_exists::<EditingControls>),
+        )
+            .chain()
+            .in_set(SettingsMenuSet::SettingsMenuInteractions),
+    );
+}
@@ -4,11 +4,11 @@ use bevy::{prelude::*, utils::hashbrown::HashMap};
 use cosmos_core::registry::{identifiable::Identifiable, Registry};
 
 use crate::{
-    lang::Lang,
+    lang::{Lang, Localization},
     settings::{Setting, SettingCategory, SettingConstraint, SettingData},
     ui::{
         components::{
-            button::{Button, ButtonEvent, ButtonStyles},
+            button::{Button, ButtonEvent},
             text_input::{InputType, InputValue, TextInput},
         },
         reactivity::{BindValue, BindValues, ReactableFields, ReactableValue},
@@ -23,9 +23,12 @@ use super::{
     },
     font::DefaultFont,
     reactivity::add_reactable_type,
+    theme::UiTheme,
     UiSystemSet,
 };
 
+mod controls;
+
 #[derive(Component)]
 /// Add this to a UI NodeBundle when you need a settings screen added to it
 pub struct NeedsSettingsAdded;
@@ -49,11 +52,17 @@ impl ReactableValue for WrittenSetting {
 #[derive(Component)]
 struct SettingsMenu;
 
+#[derive(Component)]
+/// The settings before the menu was opened, so any live changes can be undone if the user cancels.
+struct SettingsBeforeChanges(Vec<(u16, SettingData)>);
+
 fn create_settings_screen(
     mut commands: Commands,
     q_ui_root: Query<Entity, (Without<SettingsMenu>, With<NeedsSettingsAdded>)>,
     settings: Res<Registry<Setting>>,
     lang: Res<Lang<Setting>>,
+    localization: Res<Localization>,
+    theme: Res<UiTheme>,
     mut q_style: Query<&mut Node, With<NeedsSettingsAdded>>,
     default_font: Res<DefaultFont>,
 ) {
@@ -61,36 +70,28 @@ fn create_settings_screen(
         return;
     };
 
-    let cool_blue = Srgba::hex("00FFFF").unwrap().into();
+    let accent = theme.accent;
 
-    let blue_text = TextColor(cool_blue);
-    let text_style_large = TextFont {
-        font_size: 64.0,
-        font: default_font.0.clone(),
-        ..Default::default()
-    };
+    let accent_text = TextColor(accent);
+    let text_style_large = theme.font(default_font.0.clone(), 64.0);
 
-    let text_style = TextFont {
-        font_size: 32.0,
-        font: default_font.0.clone(),
-        ..Default::default()
-    };
-    let text_style_small = TextFont {
-        font_size: 24.0,
-        font: default_font.0.clone(),
-        ..Default::default()
-    };
+    let text_style = theme.font(default_font.0.clone(), 32.0);
+    let text_style_small = theme.font(default_font.0.clone(), 24.0);
 
     q_style
         .get_mut(main_menu_root)
         .expect("Attempted to insert settings menu into non-UI element")
         .flex_direction = FlexDirection::Column;
 
+    let before_changes = SettingsBeforeChanges(settings.iter().map(|s| (s.id(), s.data.clone())).collect());
+
+    commands.entity(main_menu_root).insert(before_changes);
+
     commands.entity(main_menu_root).insert(SettingsMenu).with_children(|p| {
         p.spawn((
-            Text::new("SETTINGS"),
+            Text::new(localization.get("cosmos:settings.title")),
             text_style_large,
-            blue_text,
+            accent_text,
             Node {
                 margin: UiRect::new(Val::Px(0.0), Val::Px(0.0), Val::Px(100.0), Val::Px(70.0)),
                 align_self: AlignSelf::Center,
@@ -124,11 +125,12 @@ fn create_settings_screen(
             categorized_settings.sort_by(|a, b| a.0.cmp(&b.0));
 
             for (category, mut settings) in categorized_settings {
-                let category_display_name = match category {
-                    SettingCategory::Graphics => "Graphics",
-                    SettingCategory::Mouse => "Mouse",
-                    SettingCategory::Audio => "Audio",
-                };
+                let category_display_name = localization.get(match category {
+                    SettingCategory::General => "cosmos:settings.general",
+                    SettingCategory::Graphics => "cosmos:settings.graphics",
+                    SettingCategory::Mouse => "cosmos:settings.mouse",
+                    SettingCategory::Audio => "cosmos:settings.audio",
+                });
 
                 p.spawn((
                     Text::new(category_display_name),
@@ -188,10 +190,10 @@ fn create_settings_screen(
                                         ..Default::default()
                                     },
                                     InputValue::new(input_value),
-                                    BorderColor(Srgba::hex("555555").unwrap().into()),
-                                    BackgroundColor(Srgba::hex("111111").unwrap().into()),
+                                    BorderColor(theme.border),
+                                    BackgroundColor(theme.input_background),
                                     Node {
-                                        border: UiRect::all(Val::Px(2.0)),
+                                        border: theme.border_rect(),
                                         width: Val::Px(150.0),
                                         height: Val::Px(45.0),
                                         align_self: AlignSelf::Center,
@@ -221,8 +223,8 @@ fn create_settings_screen(
                                         Slider {
                                             min: min as i64,
                                             max: max as i64,
-                                            background_color: Srgba::hex("111111").unwrap().into(),
-                                            foreground_color: Srgba::hex("555555").unwrap().into(),
+                                            background_color: theme.input_background,
+                                            foreground_color: theme.border,
                                             ..Default::default()
                                         },
                                         Node {
@@ -245,6 +247,15 @@ fn create_settings_screen(
                     });
                 }
             }
+
+            p.spawn((
+                controls::NeedsControlsAdded,
+                Node {
+                    width: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+            ));
         });
 
         p.spawn(Node {
@@ -256,9 +267,9 @@ fn create_settings_screen(
         })
         .with_children(|p| {
             p.spawn((
-                BorderColor(cool_blue),
+                BorderColor(accent),
                 Node {
-                    border: UiRect::all(Val::Px(2.0)),
+                    border: theme.border_rect(),
                     width: Val::Px(500.0),
                     height: Val::Px(70.0),
                     align_self: AlignSelf::Center,
@@ -266,21 +277,16 @@ fn create_settings_screen(
                     ..Default::default()
                 },
                 Button::<SettingsCancelButtonEvent> {
-                    button_styles: Some(ButtonStyles {
-                        background_color: Srgba::hex("333333").unwrap().into(),
-                        hover_background_color: Srgba::hex("232323").unwrap().into(),
-                        press_background_color: Srgba::hex("111111").unwrap().into(),
-                        ..Default::default()
-                    }),
-                    text: Some(("Cancel".into(), text_style.clone(), Default::default())),
+                    button_styles: Some(theme.button_styles()),
+                    text: Some((localization.get("cosmos:cancel").into(), text_style.clone(), Default::default())),
                     ..Default::default()
                 },
             ));
 
             p.spawn((
-                BorderColor(cool_blue),
+                BorderColor(accent),
                 Node {
-                    border: UiRect::all(Val::Px(2.0)),
+                    border: theme.border_rect(),
                     width: Val::Px(500.0),
                     height: Val::Px(70.0),
                     align_self: AlignSelf::Center,
@@ -288,13 +294,8 @@ fn create_settings_screen(
                     ..Default::default()
                 },
                 Button::<SettingsDoneButtonEvent> {
-                    button_styles: Some(ButtonStyles {
-                        background_color: Srgba::hex("333333").unwrap().into(),
-                        hover_background_color: Srgba::hex("232323").unwrap().into(),
-                        press_background_color: Srgba::hex("111111").unwrap().into(),
-                        ..Default::default()
-                    }),
-                    text: Some(("Done".into(), text_style.clone(), Default::default())),
+                    button_styles: Some(theme.button_styles()),
+                    text: Some((localization.get("cosmos:done").into(), text_style.clone(), Default::default())),
                     ..Default::default()
                 },
             ));
@@ -302,28 +303,70 @@ fn create_settings_screen(
     });
 }
 
+fn parse_written_setting(setting: &Setting, written_setting: &WrittenSetting) -> Option<SettingData> {
+    match setting.data {
+        SettingData::I32(_) => {
+            let Ok(parsed) = written_setting.value.parse::<i32>() else {
+                warn!("Invalid i32 - {}", written_setting.value);
+                return None;
+            };
+
+            Some(SettingData::I32(parsed))
+        }
+        SettingData::String(_) => Some(SettingData::String(written_setting.value.clone())),
+    }
+}
+
 fn done_clicked(mut settings: ResMut<Registry<Setting>>, q_written_settings: Query<&WrittenSetting>) {
     for written_setting in q_written_settings.iter() {
         let setting = settings.from_numeric_id_mut(written_setting.setting_id);
 
-        match setting.data {
-            SettingData::I32(_) => {
-                let Ok(parsed) = written_setting.value.parse::<i32>() else {
-                    warn!("Invalid i32 - {}", written_setting.value);
-                    continue;
-                };
+        let Some(data) = parse_written_setting(setting, written_setting) else {
+            continue;
+        };
 
-                setting.data = SettingData::I32(parsed);
-            }
-            SettingData::String(_) => {
-                setting.data = SettingData::String(written_setting.value.clone());
-            }
-        }
+        setting.data = data;
 
         info!("{setting:?}");
     }
 }
 
+/// Settings with a constraint are changed via sliders, so they are applied as they are dragged to
+/// give immediate feedback (such as seeing the brightness change or chunks unloading).
+///
+/// Free-form text settings are only applied once `Done` is clicked, since they are often invalid mid-typing.
+fn apply_changes_live(mut settings: ResMut<Registry<Setting>>, q_written_settings: Query<&WrittenSetting, Changed<WrittenSetting>>) {
+    for written_setting in q_written_settings.iter() {
+        let setting = settings.from_numeric_id(written_setting.setting_id);
+
+        if setting.constraint.is_none() {
+            continue;
+        }
+
+        let Some(data) = parse_written_setting(setting, written_setting) else {
+            continue;
+        };
+
+        if data == setting.data {
+            continue;
+        }
+
+        settings.from_numeric_id_mut(written_setting.setting_id).data = data;
+    }
+}
+
+fn cancel_clicked(mut settings: ResMut<Registry<Setting>>, q_before_changes: Query<&SettingsBeforeChanges>) {
+    for before_changes in q_before_changes.iter() {
+        for (id, data) in before_changes.0.iter() {
+            let setting = settings.from_numeric_id(*id);
+
+            if setting.data != *data {
+                settings.from_numeric_id_mut(*id).data = data.clone();
+            }
+        }
+    }
+}
+
 #[derive(Event, Debug)]
 /// The cancel button was clicked on the settings menu
 ///
@@ -359,14 +402,20 @@ pub(super) fn register(app: &mut App) {
 
     add_reactable_type::<WrittenSetting>(app);
 
+    controls::register(app);
+
     app.add_systems(
         Update,
         (
             create_settings_screen
                 .in_set(UiSystemSet::DoUi)
                 .before(SettingsMenuSet::SettingsMenuInteractions),
-            done_clicked
-                .run_if(on_event::<SettingsDoneButtonEvent>)
+            (
+                apply_changes_live,
+                done_clicked.run_if(on_event::<SettingsDoneButtonEvent>),
+                cancel_clicked.run_if(on_event::<SettingsCancelButtonEvent>),
+            )
+                .chain()
                 .in_set(SettingsMenuSet::SettingsMenuInteractions),
         ),
     )
@@ -9,8 +9,10 @@ use cosmos_core::{
     structure::{
         asteroid::Asteroid,
         planet::Planet,
+        sensors::{detect, Detection, SensorStrength},
         ship::{pilot::Pilot, Ship},
         station::Station,
+        systems::cloaking_system::{cloaked_ancestor, is_aboard, Cloaked},
     },
 };
 
@@ -37,8 +39,8 @@ pub struct IndicatorSettings {
 }
 
 #[derive(Component, Debug)]
-/// Represents the entity that is the indicator for this entity
-struct HasIndicator(Entity);
+/// Represents the entity that is the indicator for this entity, and how well that entity was detected when it was created
+struct HasIndicator(Entity, Detection);
 
 #[derive(Component, Debug)]
 /// Indicates which entity this waypoint is a waypoint for.
@@ -58,6 +60,9 @@ struct IndicatorTextEntity(Entity);
 /// Waypoint closest to the center of your screen NOT your character/ship
 pub struct ClosestWaypoint(pub Option<Entity>);
 
+/// Contacts that are too jammed to identify are shown in this color instead of their own
+const UNKNOWN_CONTACT_COLOR: &str = "AAAAAA7F";
+
 fn get_distance_text(distance: f32) -> String {
     const METERS_TO_KM: f32 = 1.0 / 1000.0;
     const METERS_TO_MEGA_METERS: f32 = METERS_TO_KM / 1000.0;
@@ -79,7 +84,14 @@ fn create_indicator(
     color: Color,
     indicator_images: &mut IndicatorImages,
     default_font: &DefaultFont,
+    detection: Detection,
 ) {
+    let color = if detection == Detection::UnknownBlip {
+        Srgba::hex(UNKNOWN_CONTACT_COLOR).unwrap().into()
+    } else {
+        color
+    };
+
     let text_color = TextColor(color);
     let text_font = TextFont {
         font: default_font.0.clone(),
@@ -167,7 +179,7 @@ fn create_indicator(
         })
         .id();
 
-    commands.entity(entity).insert(HasIndicator(indicator_entity));
+    commands.entity(entity).insert(HasIndicator(indicator_entity, detection));
 }
 
 #[derive(Resource)]
@@ -186,6 +198,9 @@ fn add_indicators(
     mut indicator_images: ResMut<IndicatorImages>,
     default_font: Res<DefaultFont>,
     q_text_entity_with_focus: Query<&IndicatorTextEntity, With<FocusedWaypointEntity>>,
+    q_sensor_strength: Query<&SensorStrength>,
+    q_cloaked: Query<(), With<Cloaked>>,
+    q_parent: Query<&Parent>,
 ) {
     let despawn_indicator = |(entity, indicator): (Entity, &HasIndicator)| {
         commands.entity(indicator.0).despawn_recursive();
@@ -210,35 +225,57 @@ fn add_indicators(
                 return;
             }
 
-            let max_distance = indicator_settings.max_distance;
-
-            let max_dist_sqrd = max_distance * max_distance;
+            let distance = location.distance_sqrd(player_location).sqrt();
 
-            let distance_sqrd = location.distance_sqrd(player_location);
+            let cloaked = cloaked_ancestor(entity, &q_cloaked, &q_parent).is_some_and(|c| !is_aboard(pilot.entity, c, &q_parent));
 
-            if distance_sqrd <= max_dist_sqrd {
-                if let Some(has_indicator) = has_indicator {
-                    if let Ok(text_entity) = q_text_entity_with_focus.get(has_indicator.0) {
-                        if let Ok(mut text) = text_query.get_mut(text_entity.0) {
-                            text.0 = get_distance_text(distance_sqrd.sqrt());
-                        }
+            let detection = if cloaked {
+                Detection::Undetected
+            } else {
+                detect(
+                    distance,
+                    indicator_settings.max_distance,
+                    q_sensor_strength.get(pilot.entity).ok(),
+                    q_sensor_strength.get(entity).ok(),
+                )
+            };
+
+            // The contact needs a new indicator if how well it's detected changed
+            let has_indicator = match has_indicator {
+                Some(has_indicator) if has_indicator.1 != detection => {
+                    commands.entity(entity).remove::<HasIndicator>();
+                    if let Some(ecmds) = commands.get_entity(has_indicator.0) {
+                        ecmds.despawn_recursive();
                     }
-                } else {
-                    create_indicator(
-                        entity,
-                        &mut commands,
-                        indicator_image.0.clone_weak(),
-                        &mut images,
-                        indicator_settings.color,
-                        &mut indicator_images,
-                        &default_font,
-                    );
+                    None
                 }
-            } else if let Some(has_indicator) = has_indicator {
-                commands.entity(entity).remove::<HasIndicator>();
-                if let Some(ecmds) = commands.get_entity(has_indicator.0) {
-                    ecmds.despawn_recursive();
+                x => x,
+            };
+
+            if detection == Detection::Undetected {
+                return;
+            }
+
+            if let Some(has_indicator) = has_indicator {
+                if let Ok(text_entity) = q_text_entity_with_focus.get(has_indicator.0) {
+                    if let Ok(mut text) = text_query.get_mut(text_entity.0) {
+                        text.0 = match detection {
+                            Detection::Identified => get_distance_text(distance),
+                            _ => "?".into(),
+                        };
+                    }
                 }
+            } else {
+                create_indicator(
+                    entity,
+                    &mut commands,
+                    indicator_image.0.clone_weak(),
+                    &mut images,
+                    indicator_settings.color,
+                    &mut indicator_images,
+                    &default_font,
+                    detection,
+                );
             }
         });
 }
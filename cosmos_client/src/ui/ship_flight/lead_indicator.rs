@@ -0,0 +1,251 @@
+//! Helps pilots hit moving targets with their laser cannons.
+//!
+//! While a group containing laser cannons is selected and a waypoint is focused, this draws two markers:
+//! - The lead indicator, which is where the target will be when a laser fired now reaches it.
+//! - The convergence point, which is where the ship's forward-facing cannons will be firing at that range.
+//!
+//! Lining the convergence point up with the lead indicator means the shots will hit.
+
+use bevy::{color::palettes::css, prelude::*};
+use bevy_rapier3d::prelude::Velocity;
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    netty::{client::LocalPlayer, system_sets::NetworkingSystemsSet},
+    physics::location::LocationPhysicsSet,
+    state::GameState,
+    structure::{
+        ship::pilot::Pilot,
+        systems::{
+            activation_groups::ActivationGroups,
+            laser_cannon_system::{projectile_intercept_time, LaserCannonSystem, LASER_BASE_VELOCITY},
+            StructureSystems,
+        },
+        Structure,
+    },
+};
+
+use crate::{rendering::MainCamera, structure::systems::player_interactions::HoveredSystem};
+
+use super::indicators::{FocusedWaypointEntity, Indicating, IndicatorSettings, WaypointSet};
+
+/// How close (in meters) the shots have to pass to the predicted position for the lead indicator to show the shot as on target
+const ON_TARGET_RADIUS: f32 = 2.0;
+
+const LEAD_INDICATOR_SIZE: f32 = 28.0;
+const CONVERGENCE_POINT_SIZE: f32 = 8.0;
+
+#[derive(Component)]
+struct LeadIndicatorUi {
+    lead: Entity,
+    convergence: Entity,
+}
+
+/// What the markers should display this frame
+struct LeadSolution {
+    lead_point: Vec3,
+    convergence_point: Vec3,
+    on_target: bool,
+}
+
+/// The velocity of this entity in the world, including the velocity of anything it's riding on
+fn world_velocity(entity: Entity, q_velocity: &Query<&Velocity>, q_parent: &Query<&Parent>) -> Vec3 {
+    let mut linvel = q_velocity.get(entity).map(|x| x.linvel).unwrap_or(Vec3::ZERO);
+
+    let mut entity = entity;
+    while let Ok(parent) = q_parent.get(entity) {
+        entity = parent.get();
+        linvel += q_velocity.get(entity).map(|x| x.linvel).unwrap_or(Vec3::ZERO);
+    }
+
+    linvel
+}
+
+fn compute_lead_solution(
+    q_piloting: &Query<(&HoveredSystem, &Pilot), With<LocalPlayer>>,
+    q_ship: &Query<(&Structure, &StructureSystems, &GlobalTransform, Option<&ActivationGroups>)>,
+    q_laser_cannon_system: &Query<&LaserCannonSystem>,
+    q_focused: &Query<&Indicating, With<FocusedWaypointEntity>>,
+    q_target: &Query<(&GlobalTransform, &IndicatorSettings)>,
+    q_velocity: &Query<&Velocity>,
+    q_parent: &Query<&Parent>,
+) -> Option<LeadSolution> {
+    let (hovered_system, pilot) = q_piloting.get_single().ok()?;
+    let (structure, systems, ship_g_trans, groups) = q_ship.get(pilot.entity).ok()?;
+
+    let muzzle_center = systems
+        .activation_group_systems(hovered_system.hovered_system_index as u32, groups)
+        .into_iter()
+        .find_map(|x| q_laser_cannon_system.get(x).ok())?
+        .forward_muzzle_center(structure)?;
+
+    let target_ent = q_focused.get_single().ok()?.0;
+    let (target_g_trans, settings) = q_target.get(target_ent).ok()?;
+
+    let target_rot = Quat::from_affine3(&target_g_trans.affine());
+    let target_pos = target_g_trans.translation() + target_rot * settings.offset;
+
+    let muzzle_pos = ship_g_trans.transform_point(muzzle_center);
+    let forward = (Quat::from_affine3(&ship_g_trans.affine()) * Vec3::NEG_Z).normalize_or_zero();
+
+    let relative_velocity = world_velocity(target_ent, q_velocity, q_parent) - world_velocity(pilot.entity, q_velocity, q_parent);
+    let relative_position = target_pos - muzzle_pos;
+
+    let time_to_hit = projectile_intercept_time(relative_position, relative_velocity, LASER_BASE_VELOCITY)?;
+
+    let lead_offset = relative_position + relative_velocity * time_to_hit;
+    let range = lead_offset.length();
+
+    let convergence_point = muzzle_pos + forward * range;
+    let lead_point = muzzle_pos + lead_offset;
+
+    Some(LeadSolution {
+        lead_point,
+        convergence_point,
+        on_target: lead_point.distance(convergence_point) <= ON_TARGET_RADIUS,
+    })
+}
+
+fn spawn_lead_indicator_ui(commands: &mut Commands) {
+    let mut lead = Entity::PLACEHOLDER;
+    let mut convergence = Entity::PLACEHOLDER;
+
+    let mut ecmds = commands.spawn((
+        Name::new("Lead indicator UI"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..Default::default()
+        },
+    ));
+
+    ecmds.with_children(|p| {
+        lead = p
+            .spawn((
+                Name::new("Lead indicator"),
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(LEAD_INDICATOR_SIZE),
+                    height: Val::Px(LEAD_INDICATOR_SIZE),
+                    margin: UiRect {
+                        left: Val::Px(LEAD_INDICATOR_SIZE / -2.0),
+                        bottom: Val::Px(LEAD_INDICATOR_SIZE / -2.0),
+                        ..Default::default()
+                    },
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..Default::default()
+                },
+                BorderColor(css::ORANGE.into()),
+                BorderRadius::MAX,
+                Visibility::Hidden,
+            ))
+            .id();
+
+        convergence = p
+            .spawn((
+                Name::new("Laser convergence point"),
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(CONVERGENCE_POINT_SIZE),
+                    height: Val::Px(CONVERGENCE_POINT_SIZE),
+                    margin: UiRect {
+                        left: Val::Px(CONVERGENCE_POINT_SIZE / -2.0),
+                        bottom: Val::Px(CONVERGENCE_POINT_SIZE / -2.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                BackgroundColor(Srgba::hex("FFFFFFB0").unwrap().into()),
+                Visibility::Hidden,
+            ))
+            .id();
+    });
+
+    ecmds.insert(LeadIndicatorUi { lead, convergence });
+}
+
+/// Moves a marker to where this point is on the screen, hiding it if it's off screen
+fn position_marker(cam: &Camera, cam_trans: &GlobalTransform, point: Vec3, (node, visibility): (&mut Node, &mut Visibility)) {
+    let on_screen = cam
+        .world_to_ndc(cam_trans, point)
+        .filter(|ndc| ndc.z > 0.0 && ndc.x.abs() <= 1.0 && ndc.y.abs() <= 1.0);
+
+    let Some(ndc) = on_screen else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    *visibility = Visibility::Inherited;
+    node.left = Val::Percent((ndc.x / 2.0 + 0.5) * 100.0);
+    node.bottom = Val::Percent((ndc.y / 2.0 + 0.5) * 100.0);
+}
+
+fn render_lead_indicator(
+    mut commands: Commands,
+    q_piloting: Query<(&HoveredSystem, &Pilot), With<LocalPlayer>>,
+    q_ship: Query<(&Structure, &StructureSystems, &GlobalTransform, Option<&ActivationGroups>)>,
+    q_laser_cannon_system: Query<&LaserCannonSystem>,
+    q_focused: Query<&Indicating, With<FocusedWaypointEntity>>,
+    q_target: Query<(&GlobalTransform, &IndicatorSettings)>,
+    q_velocity: Query<&Velocity>,
+    q_parent: Query<&Parent>,
+    q_cam: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    q_lead_ui: Query<(Entity, &LeadIndicatorUi)>,
+    mut q_marker: Query<(&mut Node, &mut Visibility)>,
+    mut q_border_color: Query<&mut BorderColor>,
+) {
+    let lead_ui = q_lead_ui.get_single();
+
+    let solution = compute_lead_solution(
+        &q_piloting,
+        &q_ship,
+        &q_laser_cannon_system,
+        &q_focused,
+        &q_target,
+        &q_velocity,
+        &q_parent,
+    );
+
+    let Some(solution) = solution else {
+        if let Ok((ent, _)) = lead_ui {
+            commands.entity(ent).insert(NeedsDespawned);
+        }
+        return;
+    };
+
+    let Ok((_, lead_ui)) = lead_ui else {
+        spawn_lead_indicator_ui(&mut commands);
+        return;
+    };
+
+    let Ok((cam, cam_trans)) = q_cam.get_single() else {
+        return;
+    };
+
+    if let Ok((mut node, mut visibility)) = q_marker.get_mut(lead_ui.lead) {
+        position_marker(cam, cam_trans, solution.lead_point, (&mut node, &mut visibility));
+    }
+
+    if let Ok((mut node, mut visibility)) = q_marker.get_mut(lead_ui.convergence) {
+        position_marker(cam, cam_trans, solution.convergence_point, (&mut node, &mut visibility));
+    }
+
+    if let Ok(mut border_color) = q_border_color.get_mut(lead_ui.lead) {
+        let color: Color = if solution.on_target { css::LIME.into() } else { css::ORANGE.into() };
+
+        if border_color.0 != color {
+            border_color.0 = color;
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        render_lead_indicator
+            .after(WaypointSet::FocusWaypoints)
+            .after(LocationPhysicsSet::DoPhysics)
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
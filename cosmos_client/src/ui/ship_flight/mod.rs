@@ -3,9 +3,13 @@
 use bevy::app::App;
 
 pub mod indicators;
+mod lead_indicator;
 mod stats_display;
+mod subsystem_targeting;
 
 pub(super) fn register(app: &mut App) {
     indicators::register(app);
+    lead_indicator::register(app);
     stats_display::register(app);
+    subsystem_targeting::register(app);
 }
@@ -1,7 +1,7 @@
 use bevy::{
     app::{App, Update},
     asset::AssetServer,
-    color::palettes::css,
+    color::{palettes::css, Color},
     core::Name,
     ecs::{
         component::Component,
@@ -18,16 +18,18 @@ use bevy::{
 };
 use bevy_rapier3d::dynamics::Velocity;
 use cosmos_core::{
+    block::multiblock::reactor_fuel::ReactorFuel,
     ecs::NeedsDespawned,
     netty::{client::LocalPlayer, system_sets::NetworkingSystemsSet},
-    physics::location::LocationPhysicsSet,
+    physics::location::{Location, LocationPhysicsSet},
     structure::{
-        ship::pilot::Pilot,
+        heat::StructureHeat,
+        ship::{autopilot::Autopilot, flight_assist::FlightAssistMode, pilot::Pilot},
         systems::{energy_storage_system::EnergyStorageSystem, StructureSystems, StructureSystemsSet},
     },
 };
 
-use crate::entities::player::player_movement::PlayerMovementSet;
+use crate::{entities::player::player_movement::PlayerMovementSet, lang::Localization};
 
 #[derive(Component)]
 struct StatsNodes;
@@ -35,9 +37,21 @@ struct StatsNodes;
 #[derive(Component)]
 struct EnergyText;
 
+#[derive(Component)]
+struct HeatText;
+
+#[derive(Component)]
+struct FuelText;
+
 #[derive(Component)]
 struct SpeedText;
 
+#[derive(Component)]
+struct FlightAssistText;
+
+#[derive(Component)]
+struct AutopilotText;
+
 fn create_nodes(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -60,6 +74,24 @@ fn create_nodes(
             },
         );
 
+        let text_style_heat = (
+            TextColor(css::ORANGE.into()),
+            TextFont {
+                font_size: 32.0,
+                font: font.clone(),
+                ..Default::default()
+            },
+        );
+
+        let text_style_fuel = (
+            TextColor(css::LIGHT_GREEN.into()),
+            TextFont {
+                font_size: 32.0,
+                font: font.clone(),
+                ..Default::default()
+            },
+        );
+
         let text_style_speed = (
             TextColor(css::AQUAMARINE.into()),
             TextFont {
@@ -69,6 +101,15 @@ fn create_nodes(
             },
         );
 
+        let text_style_flight_assist = (
+            TextColor(css::WHITE.into()),
+            TextFont {
+                font_size: 24.0,
+                font: font.clone(),
+                ..Default::default()
+            },
+        );
+
         commands
             .spawn((
                 Name::new("Ship stats ui"),
@@ -85,16 +126,98 @@ fn create_nodes(
             ))
             .with_children(|p| {
                 p.spawn((Name::new("Energy Text"), EnergyText, Text::new(""), text_style_energy));
+                p.spawn((Name::new("Heat Text"), HeatText, Text::new(""), text_style_heat));
+                p.spawn((Name::new("Fuel Text"), FuelText, Text::new(""), text_style_fuel));
                 p.spawn((Name::new("Speed Text"), SpeedText, Text::new(""), text_style_speed));
+                p.spawn((
+                    Name::new("Flight Assist Text"),
+                    FlightAssistText,
+                    Text::new(""),
+                    text_style_flight_assist.clone(),
+                ));
+                p.spawn((Name::new("Autopilot Text"), AutopilotText, Text::new(""), text_style_flight_assist));
             });
     }
 }
 
 fn update_nodes(
+    localization: Res<Localization>,
     piloting: Query<&Pilot, With<LocalPlayer>>,
-    q_piloting: Query<(&Velocity, &StructureSystems)>,
-    mut q_energy_text: Query<&mut Text, (With<EnergyText>, Without<SpeedText>)>,
-    mut q_speed_text: Query<&mut Text, (With<SpeedText>, Without<EnergyText>)>,
+    q_piloting: Query<(
+        &Velocity,
+        &StructureSystems,
+        &Location,
+        Option<&FlightAssistMode>,
+        Option<&Autopilot>,
+        Option<&StructureHeat>,
+        Option<&ReactorFuel>,
+    )>,
+    mut q_energy_text: Query<
+        &mut Text,
+        (
+            With<EnergyText>,
+            Without<HeatText>,
+            Without<SpeedText>,
+            Without<FlightAssistText>,
+            Without<AutopilotText>,
+            Without<FuelText>,
+        ),
+    >,
+    mut q_heat_text: Query<
+        (&mut Text, &mut TextColor),
+        (
+            With<HeatText>,
+            Without<EnergyText>,
+            Without<SpeedText>,
+            Without<FlightAssistText>,
+            Without<AutopilotText>,
+            Without<FuelText>,
+        ),
+    >,
+    mut q_speed_text: Query<
+        &mut Text,
+        (
+            With<SpeedText>,
+            Without<EnergyText>,
+            Without<HeatText>,
+            Without<FlightAssistText>,
+            Without<AutopilotText>,
+            Without<FuelText>,
+        ),
+    >,
+    mut q_flight_assist_text: Query<
+        &mut Text,
+        (
+            With<FlightAssistText>,
+            Without<EnergyText>,
+            Without<HeatText>,
+            Without<SpeedText>,
+            Without<AutopilotText>,
+            Without<FuelText>,
+        ),
+    >,
+    mut q_autopilot_text: Query<
+        &mut Text,
+        (
+            With<AutopilotText>,
+            Without<EnergyText>,
+            Without<HeatText>,
+            Without<SpeedText>,
+            Without<FlightAssistText>,
+            Without<FuelText>,
+        ),
+    >,
+    mut q_fuel_text: Query<
+        (&mut Text, &mut TextColor),
+        (
+            With<FuelText>,
+            Without<EnergyText>,
+            Without<HeatText>,
+            Without<SpeedText>,
+            Without<FlightAssistText>,
+            Without<AutopilotText>,
+        ),
+    >,
 
     q_energy_storage_system: Query<&EnergyStorageSystem>,
 ) {
@@ -102,12 +225,38 @@ fn update_nodes(
         return;
     };
 
-    let Ok((piloting_vel, piloting_systems)) = q_piloting.get(piloting.entity) else {
+    let Ok((piloting_vel, piloting_systems, piloting_loc, flight_assist, autopilot, heat, fuel)) = q_piloting.get(piloting.entity) else {
         return;
     };
 
+    if let Ok(mut text) = q_autopilot_text.get_single_mut() {
+        text.0 = match autopilot {
+            Some(autopilot) => {
+                let to_destination = (autopilot.destination - *piloting_loc).absolute_coords_f32();
+                let closing_speed = piloting_vel.linvel.dot(to_destination.normalize_or_zero());
+
+                if closing_speed > 0.0 {
+                    let eta_secs = (to_destination.length() / closing_speed).round() as u64;
+                    localization.format("cosmos:hud.autopilot_eta", &[&(eta_secs / 60), &format!("{:02}", eta_secs % 60)])
+                } else {
+                    localization.get("cosmos:hud.autopilot_engaged").into()
+                }
+            }
+            None => "".into(),
+        };
+    }
+
+    if let Ok(mut text) = q_flight_assist_text.get_single_mut() {
+        let mode = localization.get(match flight_assist.copied().unwrap_or_default() {
+            FlightAssistMode::FullDampeners => "cosmos:flight_assist.full_dampeners",
+            FlightAssistMode::Decoupled => "cosmos:flight_assist.decoupled",
+            FlightAssistMode::PrecisionDocking => "cosmos:flight_assist.precision_docking",
+        });
+        text.0 = localization.format("cosmos:hud.flight_assist", &[&mode]);
+    }
+
     if let Ok(mut text) = q_speed_text.get_single_mut() {
-        text.0 = format!("Speed: {:.1}m/s", piloting_vel.linvel.length());
+        text.0 = localization.format("cosmos:hud.speed", &[&format!("{:.1}", piloting_vel.linvel.length())]);
     }
 
     if let Ok(mut text) = q_energy_text.get_single_mut() {
@@ -118,7 +267,67 @@ fn update_nodes(
                 0.0
             };
 
-            text.0 = format!("Energy {}%", (percent * 100.0).round());
+            text.0 = localization.format("cosmos:hud.energy", &[&(percent * 100.0).round()]);
+        }
+    }
+
+    if let Ok((mut text, mut color)) = q_heat_text.get_single_mut() {
+        match heat {
+            Some(heat) => {
+                let key = if heat.is_overheating() {
+                    "cosmos:hud.heat_overheating"
+                } else {
+                    "cosmos:hud.heat"
+                };
+                text.0 = localization.format(key, &[&heat.percent()]);
+
+                let new_color: Color = if heat.is_overheating() {
+                    css::RED.into()
+                } else {
+                    css::ORANGE.into()
+                };
+                if color.0 != new_color {
+                    color.0 = new_color;
+                }
+            }
+            None => text.0 = "".into(),
+        }
+    }
+
+    if let Ok((mut text, mut color)) = q_fuel_text.get_single_mut() {
+        match fuel {
+            Some(fuel) => {
+                let time_left = match fuel.seconds_remaining() {
+                    Some(secs) => {
+                        let secs = secs as u64;
+                        format!("{}:{:02}", secs / 60, secs % 60)
+                    }
+                    None => "--:--".into(),
+                };
+                let per_item = fuel.energy_per_item().map(|e| format!("{e:.0}")).unwrap_or_else(|| "-".into());
+
+                let key = if fuel.is_empty() {
+                    "cosmos:hud.fuel_empty"
+                } else if fuel.is_low() {
+                    "cosmos:hud.fuel_low"
+                } else {
+                    "cosmos:hud.fuel"
+                };
+                text.0 = localization.format(
+                    key,
+                    &[&time_left, &fuel.stored_items(), &per_item, &format!("{:.0}", fuel.burn_rate())],
+                );
+
+                let new_color: Color = if fuel.is_empty() || fuel.is_low() {
+                    css::RED.into()
+                } else {
+                    css::LIGHT_GREEN.into()
+                };
+                if color.0 != new_color {
+                    color.0 = new_color;
+                }
+            }
+            None => text.0 = "".into(),
         }
     }
 }
@@ -0,0 +1,100 @@
+//! Lets the pilot cycle which subsystem of their targets their weapons favor, and shows which one is selected
+
+use bevy::{color::palettes::css, prelude::*};
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    netty::{client::LocalPlayer, sync::events::client_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    state::GameState,
+    structure::{
+        ship::pilot::Pilot,
+        systems::subsystem_targeting::{SetTargetedSubsystemEvent, TargetedSubsystem},
+    },
+};
+
+use crate::{
+    input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    lang::Localization,
+    ui::{components::show_cursor::no_open_menus, font::DefaultFont},
+};
+
+#[derive(Component)]
+struct TargetedSubsystemText;
+
+fn cycle_targeted_subsystem(
+    inputs: InputChecker,
+    q_pilot: Query<&Pilot, With<LocalPlayer>>,
+    q_targeted_subsystem: Query<&TargetedSubsystem>,
+    mut nevw_set_subsystem: NettyEventWriter<SetTargetedSubsystemEvent>,
+) {
+    if !inputs.check_just_pressed(CosmosInputs::CycleTargetedSubsystem) {
+        return;
+    }
+
+    let Ok(pilot) = q_pilot.get_single() else {
+        return;
+    };
+
+    let current = q_targeted_subsystem.get(pilot.entity).ok().copied();
+
+    nevw_set_subsystem.send(SetTargetedSubsystemEvent {
+        subsystem: TargetedSubsystem::cycle(current),
+    });
+}
+
+fn display_targeted_subsystem(
+    mut commands: Commands,
+    q_pilot: Query<&Pilot, With<LocalPlayer>>,
+    q_targeted_subsystem: Query<&TargetedSubsystem>,
+    mut q_text: Query<(Entity, &mut Text), With<TargetedSubsystemText>>,
+    localization: Res<Localization>,
+    font: Res<DefaultFont>,
+) {
+    let subsystem = q_pilot
+        .get_single()
+        .ok()
+        .and_then(|pilot| q_targeted_subsystem.get(pilot.entity).ok());
+
+    let Some(subsystem) = subsystem else {
+        for (ent, _) in q_text.iter() {
+            commands.entity(ent).insert(NeedsDespawned);
+        }
+        return;
+    };
+
+    let text = localization.format("cosmos:hud.targeted_subsystem", &[&localization.get(subsystem.unlocalized_name())]);
+
+    if let Ok((_, mut existing)) = q_text.get_single_mut() {
+        if existing.0 != text {
+            existing.0 = text;
+        }
+        return;
+    }
+
+    commands.spawn((
+        Name::new("Targeted subsystem text"),
+        TargetedSubsystemText,
+        Text::new(text),
+        TextColor(css::ORANGE.into()),
+        TextFont {
+            font: font.0.clone_weak(),
+            font_size: 24.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            top: Val::Px(10.0),
+            ..Default::default()
+        },
+    ));
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (cycle_targeted_subsystem.run_if(no_open_menus), display_targeted_subsystem)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
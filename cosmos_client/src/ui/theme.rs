@@ -0,0 +1,163 @@
+//! The colors, borders & font sizes shared by the UI, which the player can swap out for more accessible ones.
+//!
+//! Menus read the [`UiTheme`] when they are created, so a new theme is applied the next time a menu is opened.
+
+use bevy::{color::palettes::css, prelude::*};
+
+use crate::settings::{Setting, SettingsRegistry, SettingsSet};
+
+use super::components::button::ButtonStyles;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// The different themes a player can pick from via the `cosmos:ui_theme` setting
+pub enum UiThemeKind {
+    #[default]
+    /// The normal look of the game
+    Default,
+    /// Brighter text & borders against darker backgrounds, with slightly larger text
+    HighContrast,
+    /// Avoids relying on red vs green to tell things apart
+    Colorblind,
+}
+
+impl UiThemeKind {
+    /// Parses the value of the `cosmos:ui_theme` setting. Unknown values use the default theme.
+    pub fn from_setting(value: &str) -> Self {
+        match value {
+            "high_contrast" => Self::HighContrast,
+            "colorblind" => Self::Colorblind,
+            _ => Self::Default,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+/// The colors, borders & font sizes UI elements should use
+pub struct UiTheme {
+    /// Which theme this is
+    pub kind: UiThemeKind,
+    /// Used for titles & the borders of important buttons
+    pub accent: Color,
+    /// Normal text
+    pub text: Color,
+    /// Borders of less important elements, such as inputs
+    pub border: Color,
+    /// The background of text inputs & similar elements
+    pub input_background: Color,
+    /// Something good or confirming, such as a buy or craft button
+    pub positive: Color,
+    /// Something bad or conflicting, such as an invalid input
+    pub negative: Color,
+    /// How wide borders should be (px)
+    pub border_width: f32,
+    /// Every font size is multiplied by this
+    pub font_scale: f32,
+    button: ButtonStyles,
+}
+
+impl UiTheme {
+    /// Creates the theme for this kind
+    pub fn new(kind: UiThemeKind) -> Self {
+        let default_button = ButtonStyles {
+            background_color: Srgba::hex("333333").unwrap().into(),
+            hover_background_color: Srgba::hex("232323").unwrap().into(),
+            press_background_color: Srgba::hex("111111").unwrap().into(),
+            ..Default::default()
+        };
+
+        match kind {
+            UiThemeKind::Default => Self {
+                kind,
+                accent: Srgba::hex("00FFFF").unwrap().into(),
+                text: css::WHITE.into(),
+                border: Srgba::hex("555555").unwrap().into(),
+                input_background: Srgba::hex("111111").unwrap().into(),
+                positive: css::GREEN.into(),
+                negative: Color::srgb(1.0, 0.2, 0.2),
+                border_width: 2.0,
+                font_scale: 1.0,
+                button: default_button,
+            },
+            UiThemeKind::HighContrast => Self {
+                kind,
+                accent: css::YELLOW.into(),
+                text: css::WHITE.into(),
+                border: css::WHITE.into(),
+                input_background: css::BLACK.into(),
+                positive: css::LIME.into(),
+                negative: css::RED.into(),
+                border_width: 3.0,
+                font_scale: 1.15,
+                button: ButtonStyles {
+                    background_color: css::BLACK.into(),
+                    foreground_color: css::WHITE.into(),
+                    hover_background_color: css::WHITE.into(),
+                    hover_foreground_color: css::BLACK.into(),
+                    press_background_color: css::YELLOW.into(),
+                    press_foreground_color: css::BLACK.into(),
+                },
+            },
+            // Uses the Okabe-Ito palette, which is distinguishable with every common form of colorblindness.
+            UiThemeKind::Colorblind => Self {
+                kind,
+                accent: Srgba::hex("56B4E9").unwrap().into(),
+                text: css::WHITE.into(),
+                border: Srgba::hex("666666").unwrap().into(),
+                input_background: Srgba::hex("111111").unwrap().into(),
+                positive: Srgba::hex("0072B2").unwrap().into(),
+                negative: Srgba::hex("E69F00").unwrap().into(),
+                border_width: 2.0,
+                font_scale: 1.0,
+                button: default_button,
+            },
+        }
+    }
+
+    /// The styles normal buttons should use
+    pub fn button_styles(&self) -> ButtonStyles {
+        self.button.clone()
+    }
+
+    /// The styles buttons that confirm something (such as buying or crafting) should use
+    pub fn positive_button_styles(&self) -> ButtonStyles {
+        ButtonStyles {
+            background_color: self.positive,
+            hover_background_color: self.positive,
+            press_background_color: self.positive,
+            ..self.button_styles()
+        }
+    }
+
+    /// A border of this theme's width on every side
+    pub fn border_rect(&self) -> UiRect {
+        UiRect::all(Val::Px(self.border_width))
+    }
+
+    /// Creates the text font for this size, scaled by this theme's font scale
+    pub fn font(&self, font: Handle<Font>, font_size: f32) -> TextFont {
+        TextFont {
+            font,
+            font_size: font_size * self.font_scale,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for UiTheme {
+    fn default() -> Self {
+        Self::new(UiThemeKind::Default)
+    }
+}
+
+fn load_ui_theme(settings: Res<Registry<Setting>>, mut theme: ResMut<UiTheme>) {
+    let kind = UiThemeKind::from_setting(settings.str_or("cosmos:ui_theme", "default"));
+
+    if theme.kind != kind {
+        *theme = UiTheme::new(kind);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<UiTheme>()
+        .add_systems(Update, load_ui_theme.in_set(SettingsSet::LoadSettings));
+}
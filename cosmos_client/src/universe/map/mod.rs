@@ -5,16 +5,21 @@ use std::f32::consts::PI;
 use bevy::{
     app::Update,
     asset::{AssetServer, Assets},
-    color::{palettes::css, Alpha},
+    color::{palettes::css, Alpha, Color},
     core::Name,
     core_pipeline::bloom::Bloom,
+    gizmos::{
+        config::{GizmoConfigGroup, GizmoConfigStore},
+        gizmos::Gizmos,
+        AppGizmoBuilder,
+    },
     input::mouse::{MouseScrollUnit, MouseWheel},
-    math::{Dir3, Quat, Vec3},
+    math::{Dir3, Isometry3d, Quat, Vec3},
     pbr::{MeshMaterial3d, StandardMaterial},
     prelude::{
-        in_state, AlphaMode, App, BuildChildren, Camera, Camera3d, Capsule3d, Changed, ChildBuild, Commands, Component, Cuboid, Entity,
-        EventReader, IntoSystemConfigs, Mesh, Mesh3d, MouseButton, OnEnter, PerspectiveProjection, Projection, Query, Res, ResMut, Sphere,
-        Text, Transform, Visibility, With, Without,
+        in_state, AlphaMode, App, BuildChildren, Camera, Camera3d, Capsule3d, Changed, ChildBuild, Commands, Component, Cuboid,
+        DespawnRecursiveExt, Entity, EventReader, IntoSystemConfigs, Mesh, Mesh3d, MouseButton, OnEnter, PerspectiveProjection, Projection,
+        Query, Res, ResMut, Sphere, Startup, Text, Transform, Visibility, With, Without,
     },
     reflect::Reflect,
     render::view::RenderLayers,
@@ -26,7 +31,7 @@ use bevy::{
 use cosmos_core::{
     ecs::NeedsDespawned,
     netty::{client::LocalPlayer, sync::events::client_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
-    physics::location::{Location, Sector, SectorUnit},
+    physics::location::{Location, Sector, SectorUnit, SystemCoordinate},
     registry::{identifiable::Identifiable, Registry},
     state::GameState,
     structure::planet::biosphere::Biosphere,
@@ -39,6 +44,7 @@ use waypoint::Waypoint;
 
 use crate::{
     input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    lang::Localization,
     structure::planet::biosphere::BiosphereColor,
     ui::{components::show_cursor::ShowCursor, OpenMenu, UiSystemSet},
     window::setup::DeltaCursorPosition,
@@ -56,6 +62,38 @@ enum GalaxyMapDisplay {
 
 const CAMERA_LAYER: usize = 0b1000;
 
+#[derive(Component, Debug)]
+/// The system the map is currently displaying the contents of
+struct DisplayedSystem {
+    /// The most recently requested system - may not be received yet
+    requested: SystemCoordinate,
+    explored: bool,
+}
+
+#[derive(Component)]
+/// Everything rendered from the received map data - these are replaced whenever a new map is received.
+struct MapDestination;
+
+#[derive(Component)]
+struct PlayerPositionMarker;
+
+#[derive(Component, Debug, Clone, Copy)]
+/// Marks the sector of this entity's [`Location`] on the galaxy map
+pub struct MapMarker {
+    /// The color of the marker
+    pub color: Color,
+}
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+/// Lines drawn on the map (such as the route to your waypoint), which are only visible to the map camera
+struct MapGizmos;
+
+fn configure_map_gizmos(mut config_store: ResMut<GizmoConfigStore>) {
+    let (config, _) = config_store.config_mut::<MapGizmos>();
+    config.render_layers = RenderLayers::from_layers(&[CAMERA_LAYER]);
+    config.line_width = 3.0;
+}
+
 #[derive(Component, Reflect)]
 struct MapCamera {
     sector: Sector,
@@ -127,6 +165,9 @@ struct MapSelectedSectorText;
 #[derive(Component)]
 struct WaypointText;
 
+#[derive(Component)]
+struct MapSystemText;
+
 fn toggle_map(
     asset_server: Res<AssetServer>,
     q_galaxy_map_display: Query<Entity, With<GalaxyMapDisplay>>,
@@ -137,6 +178,7 @@ fn toggle_map(
     mut nevw_system_map: NettyEventWriter<RequestSystemMap>,
     mut nevw_galaxy_map: NettyEventWriter<RequestGalaxyMap>,
     q_open_menus: Query<(), With<OpenMenu>>,
+    localization: Res<Localization>,
 ) {
     if !input_handler.check_just_pressed(CosmosInputs::ToggleMap) {
         return;
@@ -167,6 +209,10 @@ fn toggle_map(
     let map_display = commands
         .spawn((
             GalaxyMapDisplay::Loading,
+            DisplayedSystem {
+                requested: player_system,
+                explored: false,
+            },
             OpenMenu::new(0),
             RenderLayers::from_layers(&[CAMERA_LAYER]),
             Name::new("System map display"),
@@ -227,6 +273,16 @@ fn toggle_map(
                     Text::new(""),
                     big_text.clone(),
                 ));
+                p.spawn((
+                    Name::new("System text"),
+                    MapSystemText,
+                    Node {
+                        align_self: AlignSelf::Center,
+                        ..Default::default()
+                    },
+                    Text::new(""),
+                    small_text.clone(),
+                ));
                 p.spawn((
                     Name::new("Waypoint text"),
                     WaypointText,
@@ -254,7 +310,7 @@ fn toggle_map(
                         width: Val::Percent(100.0),
                         ..Default::default()
                     },
-                    Text::new("WASDEQ to Move"),
+                    Text::new(localization.get("cosmos:map.move_help")),
                     small_text.clone(),
                 ));
 
@@ -263,7 +319,7 @@ fn toggle_map(
                         width: Val::Percent(100.0),
                         ..Default::default()
                     },
-                    Text::new("Scroll to Zoom"),
+                    Text::new(localization.get("cosmos:map.zoom_help")),
                     small_text.clone(),
                 ));
 
@@ -272,7 +328,7 @@ fn toggle_map(
                         width: Val::Percent(100.0),
                         ..Default::default()
                     },
-                    Text::new("R to Reset to Your Sector"),
+                    Text::new(localization.get("cosmos:map.reset_help")),
                     small_text.clone(),
                 ));
 
@@ -281,7 +337,16 @@ fn toggle_map(
                         width: Val::Percent(100.0),
                         ..Default::default()
                     },
-                    Text::new("Enter to Set/Unset Waypoint"),
+                    Text::new(localization.get("cosmos:map.waypoint_help")),
+                    small_text.clone(),
+                ));
+
+                p.spawn((
+                    Node {
+                        width: Val::Percent(100.0),
+                        ..Default::default()
+                    },
+                    Text::new(localization.get("cosmos:map.select_star_help")),
                     small_text.clone(),
                 ));
             });
@@ -290,7 +355,11 @@ fn toggle_map(
     nevw_galaxy_map.send(RequestGalaxyMap);
 }
 
-fn update_waypoint_text(q_waypoint: Query<&Location, With<Waypoint>>, mut q_text: Query<&mut Text, With<WaypointText>>) {
+fn update_waypoint_text(
+    localization: Res<Localization>,
+    q_waypoint: Query<&Location, With<Waypoint>>,
+    mut q_text: Query<&mut Text, With<WaypointText>>,
+) {
     let Ok(mut text) = q_text.get_single_mut() else {
         return;
     };
@@ -299,9 +368,9 @@ fn update_waypoint_text(q_waypoint: Query<&Location, With<Waypoint>>, mut q_text
 
     let waypoint_text = waypoint_loc
         .map(|x| format!("{}, {}, {}", x.sector.x(), x.sector.y(), x.sector.z()))
-        .unwrap_or("<enter to set>".to_owned());
+        .unwrap_or(localization.get("cosmos:map.waypoint_unset").to_owned());
 
-    text.as_mut().0 = format!("Waypoint: {}", waypoint_text);
+    text.as_mut().0 = localization.format("cosmos:map.waypoint", &[&waypoint_text]);
 }
 
 fn update_sector_text(q_cam: Query<&MapCamera, Changed<MapCamera>>, mut q_text: Query<&mut Text, With<MapSelectedSectorText>>) {
@@ -316,15 +385,153 @@ fn update_sector_text(q_cam: Query<&MapCamera, Changed<MapCamera>>, mut q_text:
     text.as_mut().0 = format!("{}, {}, {}", cam.sector.x(), cam.sector.y(), cam.sector.z());
 }
 
+fn update_system_text(
+    localization: Res<Localization>,
+    q_displayed: Query<&DisplayedSystem, Changed<DisplayedSystem>>,
+    mut q_text: Query<&mut Text, With<MapSystemText>>,
+) {
+    let Ok(displayed) = q_displayed.get_single() else {
+        return;
+    };
+
+    let Ok(mut text) = q_text.get_single_mut() else {
+        return;
+    };
+
+    let s = displayed.requested;
+    let coords = format!("{}, {}, {}", s.x(), s.y(), s.z());
+
+    let key = if displayed.explored {
+        "cosmos:map.system"
+    } else {
+        "cosmos:map.system_unexplored"
+    };
+
+    text.as_mut().0 = localization.format(key, &[&coords]);
+}
+
+/// Once the camera moves into a different system, the contents of that system are requested from the server.
+fn request_selected_system(
+    q_cam: Query<&MapCamera, Changed<MapCamera>>,
+    mut q_displayed: Query<&mut DisplayedSystem>,
+    mut nevw_system_map: NettyEventWriter<RequestSystemMap>,
+) {
+    let Ok(cam) = q_cam.get_single() else {
+        return;
+    };
+
+    let Ok(mut displayed) = q_displayed.get_single_mut() else {
+        return;
+    };
+
+    let system = SystemCoordinate::from_sector(cam.sector);
+
+    if displayed.requested != system {
+        displayed.requested = system;
+        nevw_system_map.send(RequestSystemMap { system });
+    }
+}
+
+/// Cycles through the stars in the galaxy, starting with the one closest to the player.
+fn select_next_star(
+    inputs: InputChecker,
+    q_galaxy_map: Query<&GalaxyMapDisplay>,
+    q_player_loc: Query<&Location, With<LocalPlayer>>,
+    mut q_camera: Query<&mut MapCamera>,
+) {
+    if !inputs.check_just_pressed(CosmosInputs::SelectNextStar) {
+        return;
+    }
+
+    let Ok(GalaxyMapDisplay::Map { galaxy_map, .. }) = q_galaxy_map.get_single() else {
+        return;
+    };
+
+    let Ok(player_loc) = q_player_loc.get_single() else {
+        return;
+    };
+
+    let Ok(mut cam) = q_camera.get_single_mut() else {
+        return;
+    };
+
+    let player_sector = sector_to_vec3(player_loc.sector());
+
+    let mut stars = galaxy_map
+        .destinations()
+        // Stars in unexplored systems are sent as unknown destinations
+        .filter(|(_, d)| matches!(d, Destination::Star(_) | Destination::Unknown(_)))
+        .map(|(sector, _)| *sector)
+        .collect::<Vec<_>>();
+
+    stars.sort_by(|a, b| {
+        sector_to_vec3(*a)
+            .distance_squared(player_sector)
+            .total_cmp(&sector_to_vec3(*b).distance_squared(player_sector))
+    });
+
+    let next = stars
+        .iter()
+        .position(|x| *x == cam.sector)
+        .map(|idx| (idx + 1) % stars.len())
+        .unwrap_or(0);
+
+    if let Some(star_sector) = stars.get(next) {
+        cam.sector = *star_sector;
+    }
+}
+
 const SECTOR_SCALE: f32 = 1.0;
 
+fn sector_to_vec3(sector: Sector) -> Vec3 {
+    Vec3::new(sector.x() as f32, sector.y() as f32, sector.z() as f32) * SECTOR_SCALE
+}
+
+fn handle_player_marker(
+    q_player_loc: Query<&Location, With<LocalPlayer>>,
+    mut q_marker: Query<&mut Transform, With<PlayerPositionMarker>>,
+) {
+    let (Ok(player_loc), Ok(mut marker_trans)) = (q_player_loc.get_single(), q_marker.get_single_mut()) else {
+        return;
+    };
+
+    marker_trans.translation = sector_to_vec3(player_loc.sector());
+}
+
+/// Draws the route the player has plotted to their waypoint
+fn draw_waypoint_route(
+    mut gizmos: Gizmos<MapGizmos>,
+    q_player_loc: Query<&Location, With<LocalPlayer>>,
+    q_waypoint: Query<&Location, With<Waypoint>>,
+) {
+    let (Ok(player_loc), Ok(waypoint_loc)) = (q_player_loc.get_single(), q_waypoint.get_single()) else {
+        return;
+    };
+
+    gizmos.line(
+        sector_to_vec3(player_loc.sector()),
+        sector_to_vec3(waypoint_loc.sector()),
+        css::AQUA,
+    );
+}
+
+/// Draws the sectors of everything with a [`MapMarker`], such as party pings & shared waypoints
+fn draw_map_markers(mut gizmos: Gizmos<MapGizmos>, q_markers: Query<(&Location, &MapMarker)>) {
+    for (loc, marker) in q_markers.iter() {
+        gizmos.sphere(
+            Isometry3d::from_translation(sector_to_vec3(loc.sector())),
+            0.2 * SECTOR_SCALE,
+            marker.color,
+        );
+    }
+}
+
 fn position_camera(mut q_camera: Query<(&mut Transform, &mut MapCamera)>) {
     let Ok((mut trans, mut cam)) = q_camera.get_single_mut() else {
         return;
     };
 
-    let s = cam.sector;
-    let vec_sec = Vec3::new(s.x() as f32, s.y() as f32, s.z() as f32) * SECTOR_SCALE;
+    let vec_sec = sector_to_vec3(cam.sector);
     cam.lerp_sector = cam.lerp_sector.lerp(vec_sec, 0.1);
 
     trans.rotation = Quat::from_rotation_y(cam.yaw) * Quat::from_rotation_x(-cam.pitch);
@@ -350,7 +557,7 @@ fn handle_waypoint_sector(
     *text_vis = Visibility::Inherited;
 
     let ws = waypoint_loc.sector();
-    sector_trans.translation = Vec3::new(ws.x() as f32, ws.y() as f32, ws.z() as f32) * SECTOR_SCALE;
+    sector_trans.translation = sector_to_vec3(ws);
 
     let standard_material = materials.get_mut(standard_material).expect("Material missing");
     standard_material.base_color.set_alpha(time.elapsed_secs().sin().abs() * 0.1);
@@ -404,6 +611,8 @@ fn render_galaxy_map(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     q_changed_map: Query<(Entity, &GalaxyMapDisplay), Changed<GalaxyMapDisplay>>,
+    q_destinations: Query<Entity, With<MapDestination>>,
+    q_selected_sector: Query<(), With<SelectedSector>>,
     q_player_loc: Query<&Location, With<LocalPlayer>>,
     mut q_camera: Query<&mut MapCamera>,
     biospheres: Res<Registry<Biosphere>>,
@@ -423,8 +632,17 @@ fn render_galaxy_map(
             return;
         };
 
-        cam.sector = player.sector();
-        cam.lerp_sector = Vec3::new(cam.sector.x() as f32, cam.sector.y() as f32, cam.sector.z() as f32) * SECTOR_SCALE;
+        for ent in q_destinations.iter() {
+            commands.entity(ent).despawn_recursive();
+        }
+
+        // The map is re-rendered every time a new system is viewed, but the camera & markers only need setup once.
+        let first_render = q_selected_sector.is_empty();
+
+        if first_render {
+            cam.sector = player.sector();
+            cam.lerp_sector = sector_to_vec3(cam.sector);
+        }
         // let player_translation = Vec3::new(diff.x() as f32, diff.y() as f32, diff.z() as f32) * SECTOR_SCALE;
         // cam_trans.translation = player_translation + Vec3::new(1.0, 2.0, 2.0) * SECTOR_SCALE;
         // cam.relative_sector = diff + Sector::new(1, 2, 2);
@@ -438,6 +656,23 @@ fn render_galaxy_map(
         };
 
         commands.entity(ent).with_children(|p| {
+            if !first_render {
+                return;
+            }
+
+            p.spawn((
+                Name::new("Player Position"),
+                PlayerPositionMarker,
+                RenderLayers::from_layers(&[CAMERA_LAYER]), // https://github.com/bevyengine/bevy/issues/12461
+                Mesh3d(meshes.add(Sphere::new(0.15))),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: css::AQUA.into(),
+                    unlit: true,
+                    ..Default::default()
+                })),
+                Transform::from_translation(sector_to_vec3(player.sector())),
+            ));
+
             p.spawn((
                 Name::new("Selected Sector"),
                 SelectedSector,
@@ -497,7 +732,9 @@ fn render_galaxy_map(
                     // },
                 ));
             });
+        });
 
+        commands.entity(ent).with_children(|p| {
             for (sector, destination, sector_offset) in system_map
                 .destinations()
                 // stars are already covered by the galaxy map
@@ -505,12 +742,7 @@ fn render_galaxy_map(
                 .map(|(sec, des)| (sec, des, system_map.system.negative_most_sector()))
                 .chain(galaxy_map.destinations().map(|(sec, des)| (sec, des, Sector::ZERO)))
             {
-                let sector = *sector + sector_offset;
-                let transform = Transform::from_xyz(
-                    sector.x() as f32 * SECTOR_SCALE,
-                    sector.y() as f32 * SECTOR_SCALE,
-                    sector.z() as f32 * SECTOR_SCALE,
-                );
+                let transform = Transform::from_translation(sector_to_vec3(*sector + sector_offset));
 
                 let mesh = match destination {
                     Destination::Star(_) => meshes.add(Sphere::new(1.0)),
@@ -558,6 +790,7 @@ fn render_galaxy_map(
                 };
 
                 p.spawn((
+                    MapDestination,
                     RenderLayers::from_layers(&[CAMERA_LAYER]), // https://github.com/bevyengine/bevy/issues/12461
                     transform,
                     Mesh3d(mesh),
@@ -656,10 +889,10 @@ fn handle_map_camera(mut q_map_camera: Query<&mut Camera, With<MapCamera>>, q_ex
 fn receive_map(
     mut nevr_galaxy_map: EventReader<GalaxyMapResponseEvent>,
     mut nevr_system_map: EventReader<SystemMapResponseEvent>,
-    mut q_galaxy_map: Query<&mut GalaxyMapDisplay>,
+    mut q_galaxy_map: Query<(&mut GalaxyMapDisplay, &mut DisplayedSystem)>,
 ) {
     for ev in nevr_galaxy_map.read() {
-        let Ok(mut gmap) = q_galaxy_map.get_single_mut() else {
+        let Ok((mut gmap, _)) = q_galaxy_map.get_single_mut() else {
             return;
         };
 
@@ -681,10 +914,17 @@ fn receive_map(
     }
 
     for ev in nevr_system_map.read() {
-        let Ok(mut gmap) = q_galaxy_map.get_single_mut() else {
+        let Ok((mut gmap, mut displayed)) = q_galaxy_map.get_single_mut() else {
             return;
         };
 
+        if ev.system != displayed.requested {
+            // The player has already moved on to another system
+            continue;
+        }
+
+        displayed.explored = ev.explored;
+
         match gmap.as_ref() {
             GalaxyMapDisplay::WaitingSystem(galaxy_map) => {
                 *gmap = GalaxyMapDisplay::Map {
@@ -722,7 +962,9 @@ fn teleport_at(mut q_player: Query<&mut Location, With<LocalPlayer>>, inputs: In
 pub(super) fn register(app: &mut App) {
     waypoint::register(app);
 
-    app.add_systems(OnEnter(GameState::Playing), create_map_camera)
+    app.init_gizmo_group::<MapGizmos>()
+        .add_systems(Startup, configure_map_gizmos)
+        .add_systems(OnEnter(GameState::Playing), create_map_camera)
         .add_systems(
             Update,
             (
@@ -732,11 +974,17 @@ pub(super) fn register(app: &mut App) {
                     render_galaxy_map,
                     (
                         camera_movement,
+                        select_next_star,
+                        request_selected_system,
                         position_camera,
+                        handle_player_marker,
+                        draw_waypoint_route,
+                        draw_map_markers,
                         handle_selected_sector,
                         handle_waypoint_sector,
                         teleport_at,
                         update_sector_text,
+                        update_system_text,
                         update_waypoint_text,
                     )
                         .chain()
@@ -5,12 +5,19 @@ use bevy::{
     color::palettes::css,
     core::Name,
     math::Vec3,
-    prelude::{App, Commands, Component, Entity, IntoSystemConfigs, Query, With},
+    prelude::{App, Commands, Component, Entity, IntoSystemConfigs, Query, Res, With},
+};
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    entities::player::Player,
+    netty::{client::LocalPlayer, sync::events::client_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    party::{AddSharedWaypointEvent, RemoveSharedWaypointEvent, SendPingEvent},
+    physics::location::Location,
 };
-use cosmos_core::{ecs::NeedsDespawned, netty::system_sets::NetworkingSystemsSet, physics::location::Location};
 
 use crate::{
     input::inputs::{CosmosInputs, InputChecker, InputHandler},
+    party::{CurrentParty, SharedWaypointMarker},
     ui::ship_flight::indicators::IndicatorSettings,
 };
 
@@ -57,6 +64,71 @@ fn create_waypoint(
     }
 }
 
+/// Pings the selected sector for the rest of the player's party
+fn ping_selected_sector(
+    input_checker: InputChecker,
+    current_party: Res<CurrentParty>,
+    q_open_map: Query<&GalaxyMapDisplay>,
+    q_map_cam: Query<&MapCamera>,
+    mut nevw_ping: NettyEventWriter<SendPingEvent>,
+) {
+    if current_party.0.is_none() || q_open_map.iter().next().is_none() {
+        return;
+    }
+
+    if !input_checker.check_just_pressed(CosmosInputs::PingLocation) {
+        return;
+    }
+
+    let Ok(map_cam) = q_map_cam.get_single() else {
+        return;
+    };
+
+    nevw_ping.send(SendPingEvent {
+        location: Location::new(Vec3::ZERO, map_cam.sector),
+    });
+}
+
+/// Lets party leaders share the selected sector with their party, or stop sharing it
+fn toggle_shared_waypoint(
+    input_checker: InputChecker,
+    current_party: Res<CurrentParty>,
+    q_open_map: Query<&GalaxyMapDisplay>,
+    q_map_cam: Query<&MapCamera>,
+    q_local_player: Query<&Player, With<LocalPlayer>>,
+    q_shared_waypoints: Query<(&Location, &SharedWaypointMarker)>,
+    mut nevw_add: NettyEventWriter<AddSharedWaypointEvent>,
+    mut nevw_remove: NettyEventWriter<RemoveSharedWaypointEvent>,
+) {
+    if q_open_map.iter().next().is_none() || !input_checker.check_just_pressed(CosmosInputs::ToggleSharedWaypoint) {
+        return;
+    }
+
+    let (Ok(map_cam), Ok(player)) = (q_map_cam.get_single(), q_local_player.get_single()) else {
+        return;
+    };
+
+    if !current_party.0.as_ref().is_some_and(|x| x.is_leader(player.name())) {
+        return;
+    }
+
+    let existing = q_shared_waypoints
+        .iter()
+        .find(|(loc, _)| loc.sector() == map_cam.sector)
+        .map(|(_, waypoint)| waypoint.id);
+
+    if let Some(id) = existing {
+        nevw_remove.send(RemoveSharedWaypointEvent { id });
+    } else {
+        nevw_add.send(AddSharedWaypointEvent {
+            location: Location::new(Vec3::ZERO, map_cam.sector),
+        });
+    }
+}
+
 pub(super) fn register(app: &mut App) {
-    app.add_systems(Update, create_waypoint.in_set(NetworkingSystemsSet::Between));
+    app.add_systems(
+        Update,
+        (create_waypoint, ping_selected_sector, toggle_shared_waypoint).in_set(NetworkingSystemsSet::Between),
+    );
 }
@@ -2,7 +2,7 @@
 
 use crate::block::{Block, BlockProperty};
 
-use super::ConnectionGroup;
+use super::{block_shape::BlockShape, block_tick::BlockTicking, ConnectionGroup};
 
 /// Used to more easily create blocks
 pub struct BlockBuilder {
@@ -11,8 +11,12 @@ pub struct BlockBuilder {
     density: f32,
     hardness: f32,
     mining_resistance: f32,
+    armor: f32,
+    flammability: f32,
+    ticking: BlockTicking,
     connect_to_groups: Vec<ConnectionGroup>,
     connection_groups: Vec<ConnectionGroup>,
+    shape: BlockShape,
 }
 
 impl BlockBuilder {
@@ -26,8 +30,12 @@ impl BlockBuilder {
             density,
             hardness,
             mining_resistance,
+            armor: 0.0,
+            flammability: 0.0,
+            ticking: BlockTicking::default(),
             connect_to_groups: vec![],
             connection_groups: vec![],
+            shape: BlockShape::default(),
         }
     }
 
@@ -64,9 +72,55 @@ impl BlockBuilder {
         self
     }
 
+    /// Sets how much damage the block's armor absorbs from every hit. Blocks have no armor by default.
+    ///
+    /// See [`Block::damage_after_armor`].
+    pub fn set_armor(mut self, armor: f32) -> Self {
+        self.armor = armor;
+
+        self
+    }
+
+    /// Sets the chance (from 0.0 to 1.0) every second that a fire next to this block spreads to it. Blocks aren't flammable by default.
+    ///
+    /// See [`Block::flammability`].
+    pub fn set_flammability(mut self, flammability: f32) -> Self {
+        self.flammability = flammability.clamp(0.0, 1.0);
+
+        self
+    }
+
+    /// Lets the server pick this block for random ticks, which is good for slow changes like crops growing.
+    ///
+    /// See [`super::block_tick`].
+    pub fn set_random_ticks(mut self) -> Self {
+        self.ticking.random = true;
+
+        self
+    }
+
+    /// Has the server tick every one of these blocks after this many block ticks (at least 1).
+    ///
+    /// See [`super::block_tick`].
+    pub fn set_tick_interval(mut self, interval: u32) -> Self {
+        self.ticking.interval = Some(interval.max(1));
+
+        self
+    }
+
+    /// Sets the shape of the block.
+    ///
+    /// Non-cube shapes should not be given the [`BlockProperty::Full`] property, and still need their own
+    /// model & collider to look and act like that shape.
+    pub fn set_shape(mut self, shape: BlockShape) -> Self {
+        self.shape = shape;
+
+        self
+    }
+
     /// Creates that block
     pub fn create(self) -> Block {
-        Block::new(
+        let mut block = Block::new(
             &self.properties,
             u16::MAX,
             self.unlocalized_name.clone(),
@@ -75,6 +129,13 @@ impl BlockBuilder {
             self.mining_resistance,
             self.connect_to_groups,
             self.connection_groups,
-        )
+        );
+
+        block.shape = self.shape;
+        block.armor = self.armor;
+        block.flammability = self.flammability;
+        block.ticking = self.ticking;
+
+        block
     }
 }
@@ -74,7 +74,7 @@ pub struct BlockPlaceEventData {
     /// The placed block's id
     pub block_id: u16,
     /// The block's rotation
-    pub block_up: BlockRotation,
+    pub block_rotation: BlockRotation,
     /// The inventory slot this block came from
     pub inventory_slot: usize,
     /// The player who placed this block
@@ -432,8 +432,6 @@ fn handle_block_place_events(
         let Ok(mut structure) = query.get_mut(place_event_data.structure_block.structure()) else {
             continue;
         };
-        let mut structure_blocks = vec![(place_event_data.structure_block.coords(), place_event_data.block_up)];
-
         let Some(is) = inv.itemstack_at(place_event_data.inventory_slot) else {
             break;
         };
@@ -446,6 +444,15 @@ fn handle_block_place_events(
 
         let block = blocks.from_numeric_id(block_id);
 
+        // Players can only choose the sub rotation of blocks that are allowed to have one
+        let block_rotation = if block.is_fully_rotatable() || block.should_face_front() {
+            place_event_data.block_rotation
+        } else {
+            BlockRotation::new(place_event_data.block_rotation.face_pointing_pos_y, BlockSubRotation::None)
+        };
+
+        let mut structure_blocks = vec![(place_event_data.structure_block.coords(), block_rotation)];
+
         if let (Some(build_mode), Some(parent)) = (build_mode, parent) {
             structure_blocks = calculate_build_mode_blocks(
                 structure_blocks,
@@ -458,7 +465,7 @@ fn handle_block_place_events(
             );
         }
 
-        for (coords, block_up) in structure_blocks {
+        for (coords, block_rotation) in structure_blocks {
             if structure.has_block_at(coords) && !structure.block_at(coords, &blocks).is_fluid() {
                 continue;
             }
@@ -474,7 +481,7 @@ fn handle_block_place_events(
             }
 
             if creative.is_some() || inv.decrease_quantity_at(place_event_data.inventory_slot, 1, &mut commands) == 0 {
-                structure.set_block_at(coords, block, block_up, &blocks, Some(&mut event_writer));
+                structure.set_block_at(coords, block, block_rotation, &blocks, Some(&mut event_writer));
             } else {
                 break;
             }
@@ -0,0 +1,114 @@
+//! The shapes a block can take up within its 1x1x1 space.
+//!
+//! Shapes are described in the block's unrotated space. A shape's faces are rotated along with the block, so
+//! use [`super::block_rotation::BlockRotation::block_face_pointing`] to find which face points in a direction.
+
+use bevy::reflect::Reflect;
+use serde::{Deserialize, Serialize};
+
+use super::block_face::BlockFace;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+/// The shape of a block, used to know which of its faces fully cover their side of the block.
+pub enum BlockShape {
+    #[default]
+    /// The block's shape is not known, so only its [`super::BlockProperty::Full`] property decides if it covers its faces.
+    Cube,
+    /// The bottom half of a block.
+    Slab,
+    /// A ramp sloping down from the top of its front face to the back of its bottom face.
+    Wedge,
+    /// A cube with its top-back-right corner cut off.
+    ///
+    /// Fits into the inside of a corner where two [`Self::Wedge`]s meet.
+    InnerCorner,
+    /// A pyramid sloping down from the top-front-left corner to the rest of its bottom face.
+    ///
+    /// Fits onto the outside of a corner where two [`Self::Wedge`]s meet.
+    OuterCorner,
+}
+
+impl BlockShape {
+    /// Returns true if this shape completely covers the given (unrotated) face of the block.
+    ///
+    /// For [`Self::Cube`], this is always true - check if the block is full before calling this.
+    pub fn covers_face(&self, face: BlockFace) -> bool {
+        match self {
+            Self::Cube => true,
+            Self::Slab | Self::OuterCorner => face == BlockFace::Bottom,
+            Self::Wedge => matches!(face, BlockFace::Bottom | BlockFace::Front),
+            Self::InnerCorner => matches!(face, BlockFace::Bottom | BlockFace::Front | BlockFace::Left),
+        }
+    }
+
+    /// Returns the corners of this shape, which form a convex hull.
+    ///
+    /// Positions are relative to the block's center, which makes each coordinate either `-0.5`, `0.0` or `0.5`.
+    pub fn vertices(&self) -> &'static [[f32; 3]] {
+        const BOTTOM: [[f32; 3]; 4] = [[-0.5, -0.5, -0.5], [0.5, -0.5, -0.5], [0.5, -0.5, 0.5], [-0.5, -0.5, 0.5]];
+
+        match self {
+            Self::Cube => &[
+                BOTTOM[0],
+                BOTTOM[1],
+                BOTTOM[2],
+                BOTTOM[3],
+                [-0.5, 0.5, -0.5],
+                [0.5, 0.5, -0.5],
+                [0.5, 0.5, 0.5],
+                [-0.5, 0.5, 0.5],
+            ],
+            Self::Slab => &[
+                BOTTOM[0],
+                BOTTOM[1],
+                BOTTOM[2],
+                BOTTOM[3],
+                [-0.5, 0.0, -0.5],
+                [0.5, 0.0, -0.5],
+                [0.5, 0.0, 0.5],
+                [-0.5, 0.0, 0.5],
+            ],
+            Self::Wedge => &[BOTTOM[0], BOTTOM[1], BOTTOM[2], BOTTOM[3], [-0.5, 0.5, -0.5], [0.5, 0.5, -0.5]],
+            Self::InnerCorner => &[
+                BOTTOM[0],
+                BOTTOM[1],
+                BOTTOM[2],
+                BOTTOM[3],
+                [-0.5, 0.5, -0.5],
+                [0.5, 0.5, -0.5],
+                [-0.5, 0.5, 0.5],
+            ],
+            Self::OuterCorner => &[BOTTOM[0], BOTTOM[1], BOTTOM[2], BOTTOM[3], [-0.5, 0.5, -0.5]],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::block_face::ALL_BLOCK_FACES;
+
+    /// A face is covered exactly when all 4 of its corners are part of the shape.
+    #[test]
+    fn covered_faces_match_vertices() {
+        let cube_corners = BlockShape::Cube.vertices();
+
+        for shape in [
+            BlockShape::Cube,
+            BlockShape::Slab,
+            BlockShape::Wedge,
+            BlockShape::InnerCorner,
+            BlockShape::OuterCorner,
+        ] {
+            for face in ALL_BLOCK_FACES {
+                let normal = face.direction().as_vec3();
+                let all_corners_in_shape = cube_corners
+                    .iter()
+                    .filter(|v| v[0] * normal.x + v[1] * normal.y + v[2] * normal.z == 0.5)
+                    .all(|corner| shape.vertices().contains(corner));
+
+                assert_eq!(all_corners_in_shape, shape.covers_face(face), "{shape:?} {face:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! Lets the server tick blocks - either at random, for slow things like crops and corrosion, or at a fixed interval, for
+//! things like fluid sources.
+//!
+//! Block types choose how they're ticked when they're built (see [`super::block_builder::BlockBuilder::set_random_ticks`]
+//! and [`super::block_builder::BlockBuilder::set_tick_interval`]). Any block can also ask for a one-off tick later on with
+//! [`ScheduledBlockTicks::schedule`].
+//!
+//! Ticks are scheduled per chunk, and only chunks near players are ticked. There is also a limit to how many blocks are
+//! ticked each frame, so ticks may arrive a bit late when lots of blocks want them.
+
+use std::collections::BTreeMap;
+
+use bevy::{
+    prelude::{App, Component, Event},
+    reflect::Reflect,
+    utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::structure::{
+    coordinates::{BlockCoordinate, ChunkCoordinate},
+    structure_block::StructureBlock,
+};
+
+/// How many block ticks happen every second
+pub const BLOCK_TICKS_PER_SECOND: u64 = 10;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// How a type of block wants to be ticked. By default, blocks are never ticked.
+pub struct BlockTicking {
+    /// If this block can be picked for random ticks
+    pub random: bool,
+    /// If present, each of these blocks is ticked every this many block ticks
+    pub interval: Option<u32>,
+}
+
+impl BlockTicking {
+    /// Returns true if this block is ever ticked on its own
+    pub fn is_ticked(&self) -> bool {
+        self.random || self.interval.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Why a block was ticked
+pub enum BlockTickKind {
+    /// The block was picked at random from its chunk. See [`BlockTicking::random`].
+    Random,
+    /// The block's [`BlockTicking::interval`] has passed
+    Interval,
+    /// A tick requested through [`ScheduledBlockTicks::schedule`]
+    Scheduled,
+}
+
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+/// Sent by the server whenever a block is ticked
+pub struct BlockTickEvent {
+    /// The block being ticked
+    pub block: StructureBlock,
+    /// The id of the block being ticked, so handlers can check if this tick is for them without a structure lookup
+    pub block_id: u16,
+    /// Why this block is being ticked
+    pub kind: BlockTickKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A tick waiting in a [`ScheduledBlockTicks`]
+pub struct ScheduledTick {
+    /// The block to tick
+    pub coords: BlockCoordinate,
+    /// The block this tick was scheduled for. If the block was replaced since then, the tick is dropped.
+    pub block_id: u16,
+    /// Why this block is being ticked
+    pub kind: BlockTickKind,
+}
+
+#[derive(Component, Debug, Default)]
+/// The ticks waiting to happen on a structure, grouped by chunk so chunks far from players can be skipped.
+///
+/// Scheduled ticks are not saved, and are dropped when their chunk is unloaded.
+pub struct ScheduledBlockTicks {
+    tick: u64,
+    chunks: HashMap<ChunkCoordinate, BTreeMap<u64, Vec<ScheduledTick>>>,
+}
+
+impl ScheduledBlockTicks {
+    /// How many block ticks this structure has been through
+    pub fn current_tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Moves this structure on to its next block tick
+    pub fn advance(&mut self) {
+        self.tick += 1;
+    }
+
+    /// Ticks this block after this many block ticks (at least 1). The tick is dropped if the block changes before then.
+    pub fn schedule(&mut self, coords: BlockCoordinate, block_id: u16, delay: u32) {
+        self.schedule_tick(
+            ScheduledTick {
+                coords,
+                block_id,
+                kind: BlockTickKind::Scheduled,
+            },
+            delay,
+        );
+    }
+
+    /// Adds this tick to its chunk's queue, to happen after this many block ticks (at least 1)
+    pub fn schedule_tick(&mut self, tick: ScheduledTick, delay: u32) {
+        let due = self.tick + delay.max(1) as u64;
+
+        self.chunks
+            .entry(ChunkCoordinate::for_block_coordinate(tick.coords))
+            .or_default()
+            .entry(due)
+            .or_default()
+            .push(tick);
+    }
+
+    /// Every chunk with ticks waiting on it
+    pub fn chunks(&self) -> impl Iterator<Item = ChunkCoordinate> + '_ {
+        self.chunks.keys().copied()
+    }
+
+    /// Takes up to `max` ticks that are due in this chunk. Ticks that don't fit stay queued for next time.
+    pub fn take_due(&mut self, chunk: ChunkCoordinate, max: usize) -> Vec<ScheduledTick> {
+        let Some(queue) = self.chunks.get_mut(&chunk) else {
+            return vec![];
+        };
+
+        let mut due = vec![];
+
+        while due.len() < max {
+            let Some(mut entry) = queue.first_entry() else {
+                break;
+            };
+
+            if *entry.key() > self.tick {
+                break;
+            }
+
+            let ticks = entry.get_mut();
+            let n = ticks.len().min(max - due.len());
+            due.extend(ticks.drain(..n));
+
+            if ticks.is_empty() {
+                entry.remove();
+            }
+        }
+
+        if queue.is_empty() {
+            self.chunks.remove(&chunk);
+        }
+
+        due
+    }
+
+    /// Drops every tick waiting on this chunk
+    pub fn clear_chunk(&mut self, chunk: ChunkCoordinate) {
+        self.chunks.remove(&chunk);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_event::<BlockTickEvent>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn due_ticks_respect_budget() {
+        let mut ticks = ScheduledBlockTicks::default();
+        let chunk = ChunkCoordinate::new(0, 0, 0);
+
+        for x in 0..3 {
+            ticks.schedule(BlockCoordinate::new(x, 0, 0), 1, 1);
+        }
+        ticks.schedule(BlockCoordinate::new(0, 1, 0), 1, 5);
+
+        assert!(ticks.take_due(chunk, 10).is_empty());
+
+        ticks.advance();
+        assert_eq!(ticks.take_due(chunk, 2).len(), 2);
+        // The one that didn't fit the budget is still waiting
+        assert_eq!(ticks.take_due(chunk, 10).len(), 1);
+        assert!(ticks.take_due(chunk, 10).is_empty());
+        assert_eq!(ticks.chunks().count(), 1);
+    }
+}
@@ -1,14 +1,24 @@
 //! Events sent to adjacent blocks on block changes
+//!
+//! [`BlockUpdate`] is sent to every adjacent block. Blocks that only care about their own neighbors should instead
+//! register a [`NeighborChangeListener`] and read [`BlockNeighborChangedEvent`]s, which are only sent to those blocks.
 
-use bevy::prelude::{App, Entity, Event, EventReader, EventWriter, IntoSystemConfigs, Query, Update};
+use bevy::prelude::{App, Entity, Event, EventReader, EventWriter, IntoSystemConfigs, Query, Res, Update};
 
 use crate::{
     ecs::mut_events::{MutEvent, MutEventsCommand},
     events::block_events::BlockChangedEvent,
+    registry::{create_registry, identifiable::Identifiable, Registry},
     structure::{coordinates::BlockCoordinate, structure_block::StructureBlock, Structure},
 };
 
-use super::{block_events::BlockEventsSet, block_face::ALL_BLOCK_FACES};
+use super::{
+    block_direction::{BlockDirection, ALL_BLOCK_DIRECTIONS},
+    block_events::BlockEventsSet,
+    block_face::ALL_BLOCK_FACES,
+    blocks::AIR_BLOCK_ID,
+    Block,
+};
 
 #[derive(Debug, Clone, Copy, Event, PartialEq, Eq)]
 /// This event is sent whenever an adjacent block is changed
@@ -82,7 +92,122 @@ pub fn send_block_updates(
     event_writer.send_batch(block_updates);
 }
 
+#[derive(Debug, Clone)]
+/// Marks a block as wanting [`BlockNeighborChangedEvent`]s whenever a block next to it changes.
+///
+/// Register these in the `post_loading_state`, once every block exists.
+pub struct NeighborChangeListener {
+    id: u16,
+    unlocalized_name: String,
+}
+
+impl Identifiable for NeighborChangeListener {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+impl NeighborChangeListener {
+    /// Makes this block listen for changes to its neighbors
+    pub fn new(block: &Block) -> Self {
+        Self {
+            id: u16::MAX,
+            unlocalized_name: block.unlocalized_name().to_owned(),
+        }
+    }
+}
+
+impl Registry<NeighborChangeListener> {
+    /// Returns true if this block has registered a [`NeighborChangeListener`]
+    pub fn listens(&self, block: &Block) -> bool {
+        self.contains(block.unlocalized_name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Event, PartialEq, Eq)]
+/// Sent to a block with a [`NeighborChangeListener`] whenever one of its adjacent blocks is placed or removed.
+///
+/// Because this is only sent to blocks that asked for it, listeners only need to check [`Self::block_id`] instead of
+/// looking at every [`BlockChangedEvent`].
+pub struct BlockNeighborChangedEvent {
+    /// The block that is listening for changes
+    pub block: StructureBlock,
+    /// The id of the block that is listening - so listeners can check if this event is for them without a structure lookup
+    pub block_id: u16,
+    /// The direction from [`Self::block`] to the neighbor that changed
+    pub direction: BlockDirection,
+    /// The block that was next to this one before
+    pub old_neighbor: u16,
+    /// The block that is next to this one now
+    pub new_neighbor: u16,
+}
+
+impl BlockNeighborChangedEvent {
+    /// The neighbor that changed
+    pub fn neighbor(&self) -> StructureBlock {
+        // This was computed by stepping from the neighbor, so it is always valid.
+        let coords = self
+            .block
+            .coords()
+            .step(self.direction)
+            .expect("Neighbor of a block changed event should always be in bounds");
+
+        StructureBlock::new(coords, self.block.structure())
+    }
+}
+
+/// Sends [`BlockNeighborChangedEvent`]s to the listening neighbors of changed blocks
+fn send_neighbor_changed_events(
+    q_structure: Query<&Structure>,
+    blocks: Res<Registry<Block>>,
+    listeners: Res<Registry<NeighborChangeListener>>,
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    mut evw_neighbor_changed: EventWriter<BlockNeighborChangedEvent>,
+) {
+    for ev in evr_block_changed.read() {
+        let Ok(structure) = q_structure.get(ev.block.structure()) else {
+            continue;
+        };
+
+        for direction in ALL_BLOCK_DIRECTIONS {
+            let Ok(coords) = ev.block.coords().step(direction) else {
+                continue;
+            };
+            if !structure.is_within_blocks(coords) {
+                continue;
+            }
+
+            let block_id = structure.block_id_at(coords);
+            if block_id == AIR_BLOCK_ID || !listeners.listens(blocks.from_numeric_id(block_id)) {
+                continue;
+            }
+
+            evw_neighbor_changed.send(BlockNeighborChangedEvent {
+                block: StructureBlock::new(coords, ev.block.structure()),
+                block_id,
+                direction: direction.inverse(),
+                old_neighbor: ev.old_block,
+                new_neighbor: ev.new_block,
+            });
+        }
+    }
+}
+
 pub(super) fn register(app: &mut App) {
-    app.add_systems(Update, send_block_updates.in_set(BlockEventsSet::SendBlockUpdateEvents))
-        .add_mut_event::<BlockUpdate>();
+    create_registry::<NeighborChangeListener>(app, "cosmos:neighbor_change_listeners");
+
+    app.add_systems(
+        Update,
+        (send_block_updates, send_neighbor_changed_events).in_set(BlockEventsSet::SendBlockUpdateEvents),
+    )
+    .add_mut_event::<BlockUpdate>()
+    .add_event::<BlockNeighborChangedEvent>();
 }
@@ -5,18 +5,34 @@
 //! The only guarenteed block is air ("cosmos:air").
 
 use crate::block::block_builder::BlockBuilder;
+use crate::block::specific_blocks::crop::{CROPS, FARMLAND_BLOCK};
 use crate::loader::{AddLoadingEvent, DoneLoadingEvent, LoadingManager};
 use crate::logic::LogicWireColor;
+use crate::netty::sync::registry::sync_registry_ids;
 use crate::registry::{self, Registry};
 use bevy::prelude::{App, EventWriter, OnEnter, ResMut, States};
 
-use super::{Block, BlockProperty};
+use super::{block_shape::BlockShape, Block, BlockProperty};
 
 pub mod fluid;
 
 /// Air's ID - this block will always exist
 pub const AIR_BLOCK_ID: u16 = 0;
 
+/// How much damage hull blocks (and their shaped variants) absorb from every hit
+const HULL_ARMOR: f32 = 5.0;
+/// How much damage glass blocks absorb from every hit
+const GLASS_ARMOR: f32 = 2.0;
+/// How much damage heavy armor absorbs from every hit. This is enough to stop most lasers outright.
+const HEAVY_ARMOR: f32 = 40.0;
+
+/// How likely leaves & grass are to catch fire from a neighboring fire every second
+const LEAF_FLAMMABILITY: f32 = 0.5;
+/// How likely wood is to catch fire from a neighboring fire every second
+const WOOD_FLAMMABILITY: f32 = 0.2;
+/// How likely furniture, storage & cable insulation are to catch fire from a neighboring fire every second
+const FURNITURE_FLAMMABILITY: f32 = 0.1;
+
 fn add_cosmos_blocks(
     mut blocks: ResMut<Registry<Block>>,
     mut loading: ResMut<LoadingManager>,
@@ -47,18 +63,21 @@ fn add_cosmos_blocks(
     blocks.register(
         BlockBuilder::new("cosmos:cherry_leaf", 0.1, 1.0, 1.0)
             .add_property(BlockProperty::Transparent)
+            .set_flammability(LEAF_FLAMMABILITY)
             .create(),
     );
 
     blocks.register(
         BlockBuilder::new("cosmos:redwood_log", 3.0, 30.0, 7.0)
             .add_property(BlockProperty::Full)
+            .set_flammability(WOOD_FLAMMABILITY)
             .create(),
     );
 
     blocks.register(
         BlockBuilder::new("cosmos:redwood_leaf", 0.1, 1.0, 1.0)
             .add_property(BlockProperty::Transparent)
+            .set_flammability(LEAF_FLAMMABILITY)
             .create(),
     );
 
@@ -75,6 +94,38 @@ fn add_cosmos_blocks(
             .create(),
     );
 
+    blocks.register(
+        BlockBuilder::new("cosmos:energy_relay", 2.0, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:warp_gate_controller", 4.0, 40.0, 20.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FullyRotatable)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:warp_gate_frame", 4.0, 40.0, 20.0)
+            .add_property(BlockProperty::Full)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:chunk_anchor", 4.0, 40.0, 20.0)
+            .add_property(BlockProperty::Full)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:battery_charger", 2.0, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .add_connection_group("cosmos:consumes_power")
+            .create(),
+    );
+
     blocks.register(
         BlockBuilder::new("cosmos:passive_generator", 2.0, 20.0, 5.0)
             .add_property(BlockProperty::Full)
@@ -82,6 +133,14 @@ fn add_cosmos_blocks(
             .create(),
     );
 
+    blocks.register(
+        BlockBuilder::new("cosmos:solar_panel", 2.0, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FullyRotatable)
+            .add_connection_group("cosmos:produces_power")
+            .create(),
+    );
+
     blocks.register(
         BlockBuilder::new("cosmos:laser_cannon", 2.0, 20.0, 5.0)
             .add_property(BlockProperty::Full)
@@ -94,6 +153,7 @@ fn add_cosmos_blocks(
     blocks.register(
         BlockBuilder::new("cosmos:ship_hull_grey", 4.0, 100.0, 10.0)
             .add_property(BlockProperty::Full)
+            .set_armor(HULL_ARMOR)
             .create(),
     );
 
@@ -115,6 +175,7 @@ fn add_cosmos_blocks(
         BlockBuilder::new("cosmos:glass", 4.0, 100.0, 10.0)
             .add_property(BlockProperty::Transparent)
             .add_property(BlockProperty::Full)
+            .set_armor(GLASS_ARMOR)
             .add_connection_group("cosmos:glass")
             .connect_to_group("cosmos:glass")
             .create(),
@@ -152,7 +213,23 @@ fn add_cosmos_blocks(
             .create(),
     );
 
-    blocks.register(BlockBuilder::new("cosmos:short_grass", 0.1, 1.0, 0.0).create());
+    blocks.register(
+        BlockBuilder::new("cosmos:short_grass", 0.1, 1.0, 0.0)
+            .set_flammability(LEAF_FLAMMABILITY)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new(FARMLAND_BLOCK, 3.0, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .create(),
+    );
+
+    for crop in CROPS {
+        for stage in crop.stages {
+            blocks.register(BlockBuilder::new(*stage, 0.1, 1.0, 0.0).create());
+        }
+    }
 
     blocks.register(
         BlockBuilder::new("cosmos:sand", 4.0, 10.0, 5.0)
@@ -163,6 +240,7 @@ fn add_cosmos_blocks(
     blocks.register(
         BlockBuilder::new("cosmos:cactus", 0.8, 10.0, 5.0)
             .add_property(BlockProperty::Full)
+            .set_flammability(WOOD_FLAMMABILITY)
             .create(),
     );
 
@@ -199,10 +277,18 @@ fn add_cosmos_blocks(
         blocks.register(
             BlockBuilder::new(format!("cosmos:ship_hull_{color}"), 4.0, 100.0, 10.0)
                 .add_property(BlockProperty::Full)
+                .set_armor(HULL_ARMOR)
                 .create(),
         );
     }
 
+    blocks.register(
+        BlockBuilder::new("cosmos:heavy_armor", 12.0, 300.0, 20.0)
+            .add_property(BlockProperty::Full)
+            .set_armor(HEAVY_ARMOR)
+            .create(),
+    );
+
     blocks.register(
         BlockBuilder::new("cosmos:reactor_controller", 2.0, 20.0, 5.0)
             .add_property(BlockProperty::Full)
@@ -265,6 +351,7 @@ fn add_cosmos_blocks(
             BlockBuilder::new(format!("cosmos:glass_{color}"), 4.0, 100.0, 10.0)
                 .add_property(BlockProperty::Transparent)
                 .add_property(BlockProperty::Full)
+                .set_armor(GLASS_ARMOR)
                 .connect_to_group("cosmos:glass")
                 .add_connection_group("cosmos:glass")
                 .create(),
@@ -273,6 +360,49 @@ fn add_cosmos_blocks(
 
     blocks.register(
         BlockBuilder::new("cosmos:storage", 2.0, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .set_flammability(FURNITURE_FLAMMABILITY)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:cargo_expander", 2.0, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:targeting_computer", 2.0, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:reactor_coolant", 2.0, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:jammer", 2.0, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:scanner", 2.0, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:cloaking_device", 2.0, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:radiator", 2.0, 20.0, 5.0)
             .add_property(BlockProperty::Full)
             .create(),
     );
@@ -283,6 +413,41 @@ fn add_cosmos_blocks(
             .create(),
     );
 
+    blocks.register(
+        BlockBuilder::new("cosmos:station_connector", 2.0, 20.0, 10.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FaceFront)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:hangar_controller", 2.0, 20.0, 10.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FaceFront)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:shipyard_terminal", 2.0, 20.0, 10.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FaceFront)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:blueprint_projector", 2.0, 20.0, 10.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FaceFront)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:drone_bay", 2.0, 30.0, 10.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FaceFront)
+            .create(),
+    );
+
     blocks.register(
         BlockBuilder::new("cosmos:test_ore", 10.0, 50.0, 12.0)
             .add_property(BlockProperty::Full)
@@ -304,6 +469,13 @@ fn add_cosmos_blocks(
             .create(),
     );
 
+    blocks.register(
+        BlockBuilder::new("cosmos:market_terminal", 2.0, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FullyRotatable)
+            .create(),
+    );
+
     blocks.register(
         BlockBuilder::new("cosmos:camera", 2.0, 20.0, 5.0)
             .add_property(BlockProperty::Full)
@@ -321,6 +493,62 @@ fn add_cosmos_blocks(
         // ramp colliders are super small, so to compensate I give them a high density
         BlockBuilder::new("cosmos:ramp", 40.0, 100.0, 10.0)
             .add_property(BlockProperty::FullyRotatable)
+            .set_shape(BlockShape::Wedge)
+            .set_armor(HULL_ARMOR)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:slab", 4.0, 100.0, 10.0)
+            .add_property(BlockProperty::FullyRotatable)
+            .set_shape(BlockShape::Slab)
+            .set_armor(HULL_ARMOR)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:ladder", 1.0, 10.0, 5.0)
+            .add_property(BlockProperty::FaceFront)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:chair", 2.0, 20.0, 5.0)
+            .add_property(BlockProperty::FullyRotatable)
+            .set_shape(BlockShape::Slab)
+            .set_flammability(FURNITURE_FLAMMABILITY)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:bed", 4.0, 20.0, 5.0)
+            .add_property(BlockProperty::FullyRotatable)
+            .set_shape(BlockShape::Slab)
+            .set_flammability(FURNITURE_FLAMMABILITY)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:cockpit_seat", 4.0, 20.0, 5.0)
+            .add_property(BlockProperty::FullyRotatable)
+            .set_shape(BlockShape::Slab)
+            .set_flammability(FURNITURE_FLAMMABILITY)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:ramp_inner_corner", 4.0, 100.0, 10.0)
+            .add_property(BlockProperty::FullyRotatable)
+            .set_shape(BlockShape::InnerCorner)
+            .set_armor(HULL_ARMOR)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:ramp_outer_corner", 4.0, 100.0, 10.0)
+            .add_property(BlockProperty::FullyRotatable)
+            .set_shape(BlockShape::OuterCorner)
+            .set_armor(HULL_ARMOR)
             .create(),
     );
 
@@ -367,6 +595,7 @@ fn add_cosmos_blocks(
             .connect_to_group("cosmos:produces_power")
             .connect_to_group("cosmos:stores_power")
             .connect_to_group("cosmos:power_cable")
+            .set_flammability(FURNITURE_FLAMMABILITY)
             .create(),
     );
 
@@ -377,6 +606,35 @@ fn add_cosmos_blocks(
             .create(),
     );
 
+    blocks.register(
+        BlockBuilder::new("cosmos:sign", 0.5, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FaceFront)
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:holo_projector", 2.0, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .add_connection_group("cosmos:uses_logic")
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:turret_mount", 2.0, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FullyRotatable)
+            .add_connection_group("cosmos:uses_logic")
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:turret_base", 2.0, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FullyRotatable)
+            .create(),
+    );
+
     blocks.register(
         BlockBuilder::new("cosmos:tank", 2.0, 20.0, 5.0)
             .add_property(BlockProperty::Full)
@@ -393,6 +651,54 @@ fn add_cosmos_blocks(
             .create(),
     );
 
+    blocks.register(
+        BlockBuilder::new("cosmos:button", 0.1, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FullyRotatable)
+            .add_connection_group("cosmos:uses_logic")
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:lever", 0.1, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FullyRotatable)
+            .add_connection_group("cosmos:uses_logic")
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:keypad", 0.1, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FullyRotatable)
+            .add_connection_group("cosmos:uses_logic")
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:timer", 0.1, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FullyRotatable)
+            .add_connection_group("cosmos:uses_logic")
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:clock", 0.1, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FullyRotatable)
+            .add_connection_group("cosmos:uses_logic")
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:heat_sensor", 0.1, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FullyRotatable)
+            .add_connection_group("cosmos:uses_logic")
+            .create(),
+    );
+
     blocks.register(
         BlockBuilder::new("cosmos:and_gate", 0.1, 20.0, 5.0)
             .add_property(BlockProperty::Full)
@@ -425,6 +731,22 @@ fn add_cosmos_blocks(
             .create(),
     );
 
+    blocks.register(
+        BlockBuilder::new("cosmos:logic_splitter", 0.1, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FullyRotatable)
+            .add_connection_group("cosmos:uses_logic")
+            .create(),
+    );
+
+    blocks.register(
+        BlockBuilder::new("cosmos:logic_filter", 0.1, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FullyRotatable)
+            .add_connection_group("cosmos:uses_logic")
+            .create(),
+    );
+
     let logic_wire_colors_array = [
         "grey",
         "black",
@@ -480,6 +802,14 @@ fn add_cosmos_blocks(
             .create(),
     );
 
+    blocks.register(
+        BlockBuilder::new("cosmos:airlock_controller", 0.1, 20.0, 5.0)
+            .add_property(BlockProperty::Full)
+            .add_property(BlockProperty::FullyRotatable)
+            .add_connection_group("cosmos:uses_logic")
+            .create(),
+    );
+
     blocks.register(
         BlockBuilder::new("cosmos:basic_fabricator", 2.0, 20.0, 5.0)
             .add_property(BlockProperty::Full)
@@ -551,6 +881,7 @@ fn add_air_block(
 
 pub(super) fn register<T: States>(app: &mut App, pre_loading_state: T, loading_state: T, post_loading_state: T) {
     registry::create_registry::<Block>(app, "cosmos:blocks");
+    sync_registry_ids::<Block>(app);
     fluid::register(app, post_loading_state);
 
     app.add_systems(OnEnter(pre_loading_state), add_air_block);
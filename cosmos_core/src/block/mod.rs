@@ -11,16 +11,21 @@ use serde::{Deserialize, Serialize};
 use crate::registry::identifiable::Identifiable;
 
 use block_face::BlockFace;
+use block_shape::BlockShape;
+use block_tick::BlockTicking;
 
 pub mod block_builder;
 pub mod block_direction;
 pub mod block_events;
 pub mod block_face;
 pub mod block_rotation;
+pub mod block_shape;
+pub mod block_tick;
 pub mod block_update;
 pub mod blocks;
 pub mod data;
 pub mod multiblock;
+pub mod paint;
 pub mod specific_blocks;
 
 #[derive(Reflect, Debug, Eq, PartialEq, Clone, Copy, Hash)]
@@ -64,6 +69,9 @@ impl BlockProperty {
     }
 }
 
+/// The fraction of a hit's damage that always gets through a block's armor, no matter how much armor it has
+pub const MIN_DAMAGE_THROUGH_ARMOR: f32 = 0.25;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Reflect, Default)]
 /// A block is the smallest unit used on a structure.
 ///
@@ -78,9 +86,17 @@ pub struct Block {
     ///
     /// This is (for now) how long it takes 1 mining beam to mine this block in seconds
     mining_resistance: f32,
+    /// How much of every hit this block shrugs off. See [`Block::damage_after_armor`].
+    armor: f32,
+    /// The chance every second that a fire next to this block spreads to it. See [`Block::flammability`].
+    flammability: f32,
+    /// How the server ticks this block. See [`Block::ticking`].
+    ticking: BlockTicking,
 
     connect_to_groups: Vec<ConnectionGroup>,
     connection_groups: Vec<ConnectionGroup>,
+
+    shape: BlockShape,
 }
 
 impl Identifiable for Block {
@@ -120,8 +136,12 @@ impl Block {
             density,
             hardness,
             mining_resistance,
+            armor: 0.0,
+            flammability: 0.0,
+            ticking: BlockTicking::default(),
             connect_to_groups,
             connection_groups,
+            shape: BlockShape::default(),
         }
     }
 
@@ -148,6 +168,23 @@ impl Block {
         self.property_flags & BlockProperty::Full.id() != 0
     }
 
+    /// Returns the shape this block takes up within its space
+    #[inline(always)]
+    pub fn shape(&self) -> BlockShape {
+        self.shape
+    }
+
+    /// Returns true if this block takes up the entirety of the given (unrotated) face.
+    ///
+    /// Used to know if the face of the block next to this one can be culled. Note that transparent blocks can
+    /// still cover a face.
+    pub fn covers_face(&self, face: BlockFace) -> bool {
+        match self.shape {
+            BlockShape::Cube => self.is_full(),
+            shape => shape.covers_face(face),
+        }
+    }
+
     /// Returns true if this block takes up no space
     #[inline(always)]
     pub fn is_empty(&self) -> bool {
@@ -194,6 +231,37 @@ impl Block {
         self.mining_resistance
     }
 
+    /// How much damage this block's armor absorbs from every hit it takes.
+    ///
+    /// Most blocks have no armor, while hull blocks are armored enough to stop weak weapons from punching through them.
+    #[inline(always)]
+    pub fn armor(&self) -> f32 {
+        self.armor
+    }
+
+    /// How much of this damage actually gets through this block's armor.
+    ///
+    /// Armor absorbs a flat amount of every hit, but at least [`MIN_DAMAGE_THROUGH_ARMOR`] of the damage always gets through.
+    pub fn damage_after_armor(&self, damage: f32) -> f32 {
+        (damage - self.armor).max(damage * MIN_DAMAGE_THROUGH_ARMOR)
+    }
+
+    /// The chance (from 0.0 to 1.0) every second that a fire on a neighboring block spreads to this block.
+    ///
+    /// Blocks that aren't flammable (0.0) can still be set on fire by destroyed power blocks, but they burn out on their own.
+    #[inline(always)]
+    pub fn flammability(&self) -> f32 {
+        self.flammability
+    }
+
+    /// How the server ticks this block. Most blocks are never ticked.
+    ///
+    /// See [`block_tick`] for more details.
+    #[inline(always)]
+    pub fn ticking(&self) -> BlockTicking {
+        self.ticking
+    }
+
     /// If the block's [`Self::mining_resistance`] is `f32::INFINITY` this will be false
     #[inline(always)]
     pub fn can_be_mined(&self) -> bool {
@@ -205,6 +273,14 @@ impl Block {
     pub fn is_fluid(&self) -> bool {
         self.property_flags & BlockProperty::Fluid.id() != 0
     }
+
+    #[inline(always)]
+    /// Returns true if this block can have a [`paint::PaintColor`] applied to it.
+    ///
+    /// Only solid, full blocks can be painted.
+    pub fn can_be_painted(&self) -> bool {
+        self.is_full() && !self.is_fluid() && !self.is_transparent()
+    }
 }
 
 impl PartialEq for Block {
@@ -266,8 +342,10 @@ pub(super) fn register<T: States + Clone + Copy>(
     block_events::register(app);
     multiblock::register(app, post_loading_state, playing_state);
     block_update::register(app);
+    block_tick::register(app);
     specific_blocks::register(app, post_loading_state);
     data::register(app);
+    paint::register(app);
 
     app.register_type::<BlockFace>();
 }
@@ -0,0 +1,334 @@
+//! A framework for machines made up of multiple blocks placed in a specific pattern (such as a 3x3x3 warp gate).
+//!
+//! To declare a new multiblock machine, register a [`MultiblockPattern`] into the [`Registry<MultiblockPattern>`].
+//! Whenever the controller block of that pattern has all the pattern's blocks around it, the multiblock is formed
+//! and a [`MultiblockFormedEvent`] is sent. Changing any block in the multiblock deforms it again, which sends a
+//! [`MultiblockDeformedEvent`].
+
+use bevy::prelude::{
+    in_state, Added, App, Commands, Component, Deref, DerefMut, Entity, Event, IntoSystemConfigs, Query, States, Update, Vec3, Without,
+};
+
+use crate::{
+    block::{block_rotation::BlockRotation, Block},
+    inventory::Inventory,
+    registry::{create_registry, identifiable::Identifiable, Registry},
+    structure::{
+        coordinates::{BlockCoordinate, UnboundBlockCoordinate, UnboundCoordinateType},
+        loading::StructureLoadingSet,
+        systems::energy_storage_system::EnergyStorageBlocks,
+        Structure,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Why a multiblock could not be formed
+pub enum InvalidMultiblock {
+    /// Part of the multiblock would be outside of the structure
+    OutOfBounds,
+    /// The block at these coordinates is not the block the pattern needs there
+    WrongBlock(BlockCoordinate),
+}
+
+#[derive(Debug, Clone)]
+/// The blocks that make up a multiblock machine, and where they have to be placed relative to its controller.
+pub struct MultiblockPattern {
+    id: u16,
+    unlocalized_name: String,
+
+    controller: u16,
+    /// Offsets are relative to the controller while it is unrotated, and do not include the controller
+    blocks: Vec<(UnboundBlockCoordinate, u16)>,
+}
+
+impl Identifiable for MultiblockPattern {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+impl MultiblockPattern {
+    /// Creates a pattern from a layout of characters.
+    ///
+    /// * `layers` Each horizontal layer of the multiblock, from the bottom to the top. Each layer is made of rows from the
+    ///   controller's front (-z) to its back (+z), and each character in a row goes from the controller's left (-x) to its right (+x).
+    /// * `key` What block each character represents. Characters not in the key (such as spaces) can be any block.
+    /// * `controller` The block that controls this multiblock. This must be in the key, and used exactly once in the layout.
+    ///
+    /// Returns `None` if any of the blocks in the key do not exist.
+    ///
+    /// For example, a 3x3x3 machine with the controller in the center of its front face:
+    /// ```ignore
+    /// MultiblockPattern::new(
+    ///     "cosmos:example",
+    ///     &[
+    ///         &["###", "###", "###"],
+    ///         &["#C#", "#.#", "###"],
+    ///         &["###", "###", "###"],
+    ///     ],
+    ///     &[('#', "cosmos:ship_hull_grey"), ('.', "cosmos:air"), ('C', "cosmos:example_controller")],
+    ///     "cosmos:example_controller",
+    ///     &blocks,
+    /// )
+    /// ```
+    pub fn new(
+        unlocalized_name: impl Into<String>,
+        layers: &[&[&str]],
+        key: &[(char, &str)],
+        controller: &str,
+        blocks: &Registry<Block>,
+    ) -> Option<Self> {
+        let unlocalized_name = unlocalized_name.into();
+        let controller_id = blocks.from_id(controller)?.id();
+
+        let mut key_ids = Vec::with_capacity(key.len());
+        for &(c, name) in key {
+            key_ids.push((c, blocks.from_id(name)?.id()));
+        }
+
+        let mut controller_offset = None;
+        let mut pattern_blocks = vec![];
+
+        for (y, layer) in layers.iter().enumerate() {
+            for (z, row) in layer.iter().enumerate() {
+                for (x, c) in row.chars().enumerate() {
+                    let Some(&(_, block_id)) = key_ids.iter().find(|(key_char, _)| *key_char == c) else {
+                        continue;
+                    };
+
+                    let offset =
+                        UnboundBlockCoordinate::new(x as UnboundCoordinateType, y as UnboundCoordinateType, z as UnboundCoordinateType);
+
+                    if block_id == controller_id {
+                        assert!(
+                            controller_offset.is_none(),
+                            "Multiblock pattern {unlocalized_name} can only have one controller."
+                        );
+                        controller_offset = Some(offset);
+                    } else {
+                        pattern_blocks.push((offset, block_id));
+                    }
+                }
+            }
+        }
+
+        let controller_offset = controller_offset.unwrap_or_else(|| panic!("Multiblock pattern {unlocalized_name} has no controller."));
+
+        Some(Self {
+            id: 0,
+            unlocalized_name,
+            controller: controller_id,
+            blocks: pattern_blocks
+                .into_iter()
+                .map(|(offset, block_id)| (offset - controller_offset, block_id))
+                .collect(),
+        })
+    }
+
+    /// The block id of this multiblock's controller
+    pub fn controller(&self) -> u16 {
+        self.controller
+    }
+
+    /// Returns true if this block is part of this pattern (including the controller)
+    pub fn uses_block(&self, block_id: u16) -> bool {
+        self.controller == block_id || self.blocks.iter().any(|&(_, id)| id == block_id)
+    }
+
+    /// The furthest any block in this pattern is from its controller along any axis
+    pub fn max_extent(&self) -> UnboundCoordinateType {
+        self.blocks
+            .iter()
+            .map(|(offset, _)| offset.x.abs().max(offset.y.abs()).max(offset.z.abs()))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Checks if every block in this pattern is placed around the controller at these coordinates.
+    ///
+    /// The pattern is rotated to match the controller's rotation. Returns the coordinates of every block in the
+    /// multiblock (including the controller) if it is valid.
+    pub fn check(&self, structure: &Structure, controller_coords: BlockCoordinate) -> Result<Vec<BlockCoordinate>, InvalidMultiblock> {
+        if structure.block_id_at(controller_coords) != self.controller {
+            return Err(InvalidMultiblock::WrongBlock(controller_coords));
+        }
+
+        let rotation = structure.block_rotation(controller_coords);
+
+        let mut members = Vec::with_capacity(self.blocks.len() + 1);
+        members.push(controller_coords);
+
+        for &(offset, block_id) in self.blocks.iter() {
+            let coords = BlockCoordinate::try_from(UnboundBlockCoordinate::from(controller_coords) + rotate_offset(offset, rotation))
+                .ok()
+                .filter(|&c| structure.is_within_blocks(c))
+                .ok_or(InvalidMultiblock::OutOfBounds)?;
+
+            if structure.block_id_at(coords) != block_id {
+                return Err(InvalidMultiblock::WrongBlock(coords));
+            }
+
+            members.push(coords);
+        }
+
+        Ok(members)
+    }
+}
+
+/// Rotates an offset from the controller's unrotated space into the structure's space
+fn rotate_offset(offset: UnboundBlockCoordinate, rotation: BlockRotation) -> UnboundBlockCoordinate {
+    let rotated = rotation.as_quat() * Vec3::new(offset.x as f32, offset.y as f32, offset.z as f32);
+
+    UnboundBlockCoordinate::new(
+        rotated.x.round() as UnboundCoordinateType,
+        rotated.y.round() as UnboundCoordinateType,
+        rotated.z.round() as UnboundCoordinateType,
+    )
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A multiblock that has all of its blocks placed correctly
+pub struct FormedMultiblock {
+    pattern: u16,
+    members: Vec<BlockCoordinate>,
+}
+
+impl FormedMultiblock {
+    /// Creates a formed multiblock. The first member must be the controller.
+    pub fn new(pattern: &MultiblockPattern, members: Vec<BlockCoordinate>) -> Self {
+        assert!(!members.is_empty(), "A multiblock must contain its controller.");
+
+        Self {
+            pattern: pattern.id(),
+            members,
+        }
+    }
+
+    /// The id of this multiblock's [`MultiblockPattern`]
+    pub fn pattern_id(&self) -> u16 {
+        self.pattern
+    }
+
+    /// Where this multiblock's controller is
+    pub fn controller(&self) -> BlockCoordinate {
+        self.members[0]
+    }
+
+    /// Every block that is a part of this multiblock, including its controller
+    pub fn members(&self) -> &[BlockCoordinate] {
+        &self.members
+    }
+
+    /// Returns true if this block is part of this multiblock
+    pub fn contains(&self, coords: BlockCoordinate) -> bool {
+        self.members.contains(&coords)
+    }
+
+    /// Iterates over the inventories of every block in this multiblock, so the controller can treat them as one.
+    pub fn inventories<'a>(&'a self, structure: &'a Structure, q_inventory: &'a Query<&Inventory>) -> impl Iterator<Item = &'a Inventory> {
+        self.members
+            .iter()
+            .flat_map(move |&coords| structure.query_block_data(coords, q_inventory))
+    }
+
+    /// The total quantity of this item across every inventory in this multiblock
+    pub fn total_quantity_of_item(&self, item_id: u16, structure: &Structure, q_inventory: &Query<&Inventory>) -> u64 {
+        self.inventories(structure, q_inventory)
+            .map(|inventory| inventory.total_quantity_of_item(item_id))
+            .sum()
+    }
+
+    /// How much energy the energy storage blocks that are part of this multiblock can hold combined
+    pub fn energy_capacity(&self, structure: &Structure, blocks: &Registry<Block>, energy_storage_blocks: &EnergyStorageBlocks) -> f32 {
+        self.members
+            .iter()
+            .flat_map(|&coords| energy_storage_blocks.get(structure.block_at(coords, blocks)))
+            .map(|prop| prop.capacity)
+            .sum()
+    }
+}
+
+#[derive(Debug, Component, Default, Deref, DerefMut, Clone)]
+/// Every multiblock that is currently formed on a structure
+pub struct Multiblocks(Vec<FormedMultiblock>);
+
+impl Multiblocks {
+    /// Returns the multiblock controlled by the block at these coordinates, if there is one
+    pub fn controlled_by(&self, controller: BlockCoordinate) -> Option<&FormedMultiblock> {
+        self.0.iter().find(|multiblock| multiblock.controller() == controller)
+    }
+}
+
+#[derive(Debug, Event, Clone, Copy)]
+/// Sent whenever a multiblock has had all its blocks placed correctly
+pub struct MultiblockFormedEvent {
+    /// The structure the multiblock is on
+    pub structure: Entity,
+    /// The coordinates of the multiblock's controller
+    pub controller: BlockCoordinate,
+    /// The id of the multiblock's [`MultiblockPattern`]
+    pub pattern: u16,
+}
+
+#[derive(Debug, Event, Clone, Copy)]
+/// Sent whenever a block that was part of a formed multiblock is changed, which makes it no longer formed
+pub struct MultiblockDeformedEvent {
+    /// The structure the multiblock was on
+    pub structure: Entity,
+    /// The coordinates of the multiblock's controller
+    pub controller: BlockCoordinate,
+    /// The id of the multiblock's [`MultiblockPattern`]
+    pub pattern: u16,
+}
+
+fn add_multiblocks_to_structure(mut commands: Commands, query: Query<Entity, (Added<Structure>, Without<Multiblocks>)>) {
+    for ent in query.iter() {
+        commands.entity(ent).insert(Multiblocks::default());
+    }
+}
+
+pub(super) fn register<T: States>(app: &mut App, playing_state: T) {
+    create_registry::<MultiblockPattern>(app, "cosmos:multiblock_patterns");
+
+    app.add_event::<MultiblockFormedEvent>()
+        .add_event::<MultiblockDeformedEvent>()
+        .add_systems(
+            Update,
+            add_multiblocks_to_structure
+                .in_set(StructureLoadingSet::AddStructureComponents)
+                .run_if(in_state(playing_state)),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::{block_face::BlockFace, block_rotation::BlockSubRotation};
+
+    #[test]
+    fn unrotated_offsets_are_unchanged() {
+        let offset = UnboundBlockCoordinate::new(1, -2, 3);
+
+        assert_eq!(rotate_offset(offset, BlockRotation::IDENTITY), offset);
+    }
+
+    #[test]
+    fn offsets_follow_controller_rotation() {
+        // The block above the controller should be wherever the controller's top face is pointing
+        let rotation = BlockRotation::new(BlockFace::Right, BlockSubRotation::None);
+        let above = UnboundBlockCoordinate::new(0, 1, 0);
+
+        assert_eq!(
+            rotate_offset(above, rotation),
+            rotation.direction_of(BlockFace::Top).to_coordinates()
+        );
+    }
+}
@@ -2,13 +2,12 @@
 
 use bevy::prelude::{App, States};
 
+pub mod machine;
 pub mod reactor;
-
-// enum Multiblock {
-//     Blueprint { layout: Vec<String>, key: HashMap<String, String> },
-//     Multiblock { size: BlockCoordinate, blocks: Vec<u16> },
-// }
+pub mod reactor_fuel;
 
 pub(super) fn register<T: States>(app: &mut App, post_loading_state: T, playing_state: T) {
+    machine::register(app, playing_state.clone());
+    reactor_fuel::register(app, post_loading_state.clone());
     reactor::register(app, post_loading_state, playing_state);
 }
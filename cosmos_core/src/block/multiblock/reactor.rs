@@ -26,6 +26,8 @@ use crate::{
     },
 };
 
+use super::reactor_fuel::ReactorFuel;
+
 #[derive(Debug, Clone, Copy, Reflect, Serialize, Deserialize, PartialEq, Eq)]
 /// The inclusive bounds of a reactor, including its casing
 pub struct ReactorBounds {
@@ -64,6 +66,11 @@ impl Reactor {
         self.power_per_second += amount;
     }
 
+    /// The power this reactor generates every second
+    pub fn power_per_second(&self) -> f32 {
+        self.power_per_second
+    }
+
     /// Returns the block where the controller for this reactor is
     pub fn controller_block(&self) -> BlockCoordinate {
         self.controller
@@ -196,13 +203,18 @@ fn on_modify_reactor(
 }
 
 // TODO: move this to server
+/// Reactors that have run out of fuel don't generate any power
 fn generate_power(
-    reactors: Query<(&Reactors, Entity)>,
+    reactors: Query<(&Reactors, Entity, Option<&ReactorFuel>)>,
     structure: Query<&StructureSystems>,
     mut energy_storage_system_query: Query<&mut EnergyStorageSystem>,
     time: Res<Time>,
 ) {
-    for (reactors, structure_entity) in reactors.iter() {
+    for (reactors, structure_entity, fuel) in reactors.iter() {
+        if fuel.is_some_and(|fuel| fuel.is_empty()) {
+            continue;
+        }
+
         let Ok(systems) = structure.get(structure_entity) else {
             continue;
         };
@@ -0,0 +1,184 @@
+//! Servers can make reactors burn fuel items to generate power.
+//!
+//! This is off by default, in which case reactors generate power for free. When it is on, every structure with a
+//! reactor gets a [`ReactorFuel`], and its reactors burn the fuel items in the structure's storage. Once the fuel
+//! runs out, the reactors stop generating power until they're refueled.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    item::Item,
+    netty::sync::{sync_component, IdentifiableComponent, SyncType, SyncableComponent},
+    registry::{create_registry, identifiable::Identifiable, Registry},
+};
+
+/// Once a structure has less than this many seconds of fuel left, its pilot is warned
+pub const LOW_FUEL_SECS: f32 = 120.0;
+
+#[derive(Debug, Clone)]
+/// An item that reactors can burn to generate power
+pub struct ReactorFuelItem {
+    energy_per_item: f32,
+
+    id: u16,
+    unlocalized_name: String,
+}
+
+impl Identifiable for ReactorFuelItem {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+impl ReactorFuelItem {
+    /// Makes this item burnable in reactors
+    pub fn new(item: &Item, energy_per_item: f32) -> Self {
+        Self {
+            energy_per_item,
+            id: 0,
+            unlocalized_name: item.unlocalized_name().to_owned(),
+        }
+    }
+
+    /// How much energy reactors get out of one of these items
+    pub fn energy_per_item(&self) -> f32 {
+        self.energy_per_item
+    }
+}
+
+impl Registry<ReactorFuelItem> {
+    /// Gets the fuel entry for this item, if reactors can burn it
+    pub fn for_item(&self, item: &Item) -> Option<&ReactorFuelItem> {
+        self.from_id(item.unlocalized_name())
+    }
+}
+
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
+/// How much fuel a structure's reactors have left to burn.
+///
+/// This is only updated on the server, and is synced to the clients whenever the whole seconds of fuel left changes.
+pub struct ReactorFuel {
+    burning: f32,
+    stored: f32,
+    stored_items: u32,
+    burn_rate: f32,
+}
+
+impl ReactorFuel {
+    /// The energy left in the fuel item that is currently being burnt
+    pub fn burning(&self) -> f32 {
+        self.burning
+    }
+
+    /// Adds the energy of a fuel item that was just taken out of storage to be burnt
+    pub fn add_burning(&mut self, energy: f32) {
+        self.burning += energy;
+    }
+
+    /// Burns up to `energy` of the fuel currently being burnt, returning how much was actually burnt
+    pub fn burn(&mut self, energy: f32) -> f32 {
+        let burnt = energy.min(self.burning).max(0.0);
+        self.burning -= burnt;
+        burnt
+    }
+
+    /// Sets what is left in the structure's storage, and how fast its reactors are burning through it
+    pub fn set_supply(&mut self, stored_energy: f32, stored_items: u32, burn_rate: f32) {
+        self.stored = stored_energy;
+        self.stored_items = stored_items;
+        self.burn_rate = burn_rate;
+    }
+
+    /// The total energy left in all the fuel, including the item currently being burnt
+    pub fn energy_remaining(&self) -> f32 {
+        self.burning + self.stored
+    }
+
+    /// How many fuel items are left in storage
+    pub fn stored_items(&self) -> u32 {
+        self.stored_items
+    }
+
+    /// How much energy the reactors burn every second
+    pub fn burn_rate(&self) -> f32 {
+        self.burn_rate
+    }
+
+    /// The average energy each stored fuel item is worth, or `None` if there is no fuel in storage
+    pub fn energy_per_item(&self) -> Option<f32> {
+        (self.stored_items != 0).then(|| self.stored / self.stored_items as f32)
+    }
+
+    /// How many seconds the fuel will last, or `None` if the reactors aren't burning any
+    pub fn seconds_remaining(&self) -> Option<f32> {
+        (self.burn_rate > 0.0).then(|| self.energy_remaining() / self.burn_rate)
+    }
+
+    /// Returns true if the reactors have no fuel left
+    pub fn is_empty(&self) -> bool {
+        self.energy_remaining() <= 0.0
+    }
+
+    /// Returns true if the reactors will run out of fuel within [`LOW_FUEL_SECS`]
+    pub fn is_low(&self) -> bool {
+        self.seconds_remaining().is_some_and(|secs| secs < LOW_FUEL_SECS)
+    }
+}
+
+impl IdentifiableComponent for ReactorFuel {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:reactor_fuel"
+    }
+}
+
+impl SyncableComponent for ReactorFuel {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+}
+
+fn register_fuel_items(items: Res<Registry<Item>>, mut registry: ResMut<Registry<ReactorFuelItem>>) {
+    if let Some(uranium) = items.from_id("cosmos:uranium") {
+        registry.register(ReactorFuelItem::new(uranium, 120_000.0));
+    }
+
+    if let Some(energite) = items.from_id("cosmos:energite_crystal") {
+        registry.register(ReactorFuelItem::new(energite, 30_000.0));
+    }
+}
+
+pub(super) fn register<T: States>(app: &mut App, post_loading_state: T) {
+    create_registry::<ReactorFuelItem>(app, "cosmos:reactor_fuel_items");
+    sync_component::<ReactorFuel>(app);
+
+    app.add_systems(OnEnter(post_loading_state), register_fuel_items)
+        .register_type::<ReactorFuel>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_low_then_empty() {
+        let mut fuel = ReactorFuel::default();
+        fuel.add_burning(1000.0);
+        fuel.set_supply(0.0, 0, 10.0);
+
+        assert_eq!(fuel.seconds_remaining(), Some(100.0));
+        assert!(fuel.is_low());
+
+        assert_eq!(fuel.burn(400.0), 400.0);
+        assert_eq!(fuel.burn(1000.0), 600.0);
+        assert!(fuel.is_empty());
+    }
+}
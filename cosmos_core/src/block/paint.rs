@@ -0,0 +1,105 @@
+//! Blocks on a structure can be painted to tint their color.
+//!
+//! The paint color is stored in the unused bits of a block's [`crate::structure::chunk::BlockInfo`], so only
+//! a small palette of colors is available.
+
+use bevy::{color::Srgba, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::sync::events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+    structure::structure_block::StructureBlock,
+};
+
+/// The item used to paint blocks
+pub const PAINT_TOOL_ITEM: &str = "cosmos:paint_tool";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+/// The colors a block can be painted.
+///
+/// This must never have more than 8 variants, since it is stored in 3 bits of the block's info.
+pub enum PaintColor {
+    #[default]
+    /// The block is unpainted and uses its normal texture
+    None,
+    /// Red
+    Red,
+    /// Orange
+    Orange,
+    /// Yellow
+    Yellow,
+    /// Green
+    Green,
+    /// Blue
+    Blue,
+    /// Purple
+    Purple,
+    /// Black
+    Black,
+}
+
+impl PaintColor {
+    /// Every paint color, ordered by their index
+    pub const ALL: [Self; 8] = [
+        Self::None,
+        Self::Red,
+        Self::Orange,
+        Self::Yellow,
+        Self::Green,
+        Self::Blue,
+        Self::Purple,
+        Self::Black,
+    ];
+
+    /// The index this is stored as in the block info
+    pub fn index(&self) -> u8 {
+        *self as u8
+    }
+
+    /// Gets the paint color from its index. Any invalid index is treated as [`Self::None`].
+    pub fn from_index(index: u8) -> Self {
+        Self::ALL.get(index as usize).copied().unwrap_or_default()
+    }
+
+    /// The color the block's texture is multiplied by, or `None` if it isn't painted
+    pub fn color(&self) -> Option<Srgba> {
+        match self {
+            Self::None => None,
+            Self::Red => Some(Srgba::rgb(0.9, 0.15, 0.15)),
+            Self::Orange => Some(Srgba::rgb(0.95, 0.5, 0.1)),
+            Self::Yellow => Some(Srgba::rgb(0.95, 0.85, 0.2)),
+            Self::Green => Some(Srgba::rgb(0.2, 0.75, 0.25)),
+            Self::Blue => Some(Srgba::rgb(0.2, 0.4, 0.9)),
+            Self::Purple => Some(Srgba::rgb(0.6, 0.25, 0.85)),
+            Self::Black => Some(Srgba::rgb(0.15, 0.15, 0.15)),
+        }
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to request painting a block.
+///
+/// The server will ignore this if the player isn't holding the paint tool, is too far away, or the
+/// block cannot be painted.
+pub struct PaintBlockEvent {
+    /// The block to paint
+    pub block: StructureBlock,
+    /// The color to paint it - [`PaintColor::None`] removes the paint
+    pub color: PaintColor,
+}
+
+impl IdentifiableEvent for PaintBlockEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:paint_block"
+    }
+}
+
+impl NettyEvent for PaintBlockEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.register_type::<PaintColor>().add_netty_event::<PaintBlockEvent>();
+}
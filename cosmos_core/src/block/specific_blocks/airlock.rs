@@ -0,0 +1,236 @@
+//! Airlock controllers link an inner & outer door together, and make sure both are never open at the same time.
+//!
+//! Cycling an airlock closes the open door, waits [`AIRLOCK_CYCLE_TICKS`], then opens the other door. An airlock can be
+//! cycled from its menu, by interacting with either of its doors, or by sending a logic signal into the back of the controller.
+//! While the airlock is cycling, the controller lights up and outputs a logic signal on its sides.
+
+use bevy::{
+    app::{App, Update},
+    prelude::{Component, Event, EventReader, EventWriter, IntoSystemConfigs, OnEnter, Query, Res, ResMut, States},
+    reflect::Reflect,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::Block,
+    logic::{
+        default_logic_block_output, logic_driver::LogicDriver, BlockLogicData, LogicBlock, LogicConnection, LogicOutputEvent,
+        LogicSystemSet, PortType, QueueLogicInputEvent, LOGIC_TICKS_PER_SECOND,
+    },
+    netty::sync::{
+        events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+        sync_component, IdentifiableComponent, SyncType, SyncableComponent,
+    },
+    registry::{identifiable::Identifiable, Registry},
+    structure::{coordinates::BlockCoordinate, structure_block::StructureBlock, Structure},
+};
+
+/// The unlocalized name of the airlock controller block
+pub const AIRLOCK_CONTROLLER_BLOCK: &str = "cosmos:airlock_controller";
+
+/// How far away (in blocks, along each axis) a door can be from the controller and still be linked to it
+pub const AIRLOCK_DOOR_RANGE: u64 = 8;
+
+/// How many logic ticks an airlock waits between closing one door and opening the other
+pub const AIRLOCK_CYCLE_TICKS: u64 = LOGIC_TICKS_PER_SECOND * 3;
+
+/// Returns true if a door at these coordinates is close enough to the controller to be linked to it
+pub fn within_airlock_range(controller: BlockCoordinate, door: BlockCoordinate) -> bool {
+    let diff = door - controller;
+
+    diff.x.unsigned_abs() <= AIRLOCK_DOOR_RANGE
+        && diff.y.unsigned_abs() <= AIRLOCK_DOOR_RANGE
+        && diff.z.unsigned_abs() <= AIRLOCK_DOOR_RANGE
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+/// One of the two doors of an airlock
+pub enum AirlockSide {
+    /// The door leading into the structure
+    Inner,
+    /// The door leading out of the structure
+    Outer,
+}
+
+impl AirlockSide {
+    /// The door on the other side of the airlock
+    pub fn opposite(&self) -> Self {
+        match self {
+            Self::Inner => Self::Outer,
+            Self::Outer => Self::Inner,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Reflect)]
+/// What an airlock's doors are currently doing
+pub enum AirlockState {
+    #[default]
+    /// Both doors are closed
+    Sealed,
+    /// Only this door is open
+    Open(AirlockSide),
+    /// Both doors are closed, and this door will open on the logic tick given (see [`crate::logic::LogicTicks`])
+    Cycling {
+        /// The door that will be opened
+        to: AirlockSide,
+        /// The logic tick the door will be opened on
+        finishes_at: u64,
+    },
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Reflect)]
+/// The doors an airlock controller cycles between. This is stored as block data on the controller.
+///
+/// Each door is stored as the coordinates of any one of its blocks.
+pub struct Airlock {
+    /// The door leading into the structure
+    pub inner_door: Option<BlockCoordinate>,
+    /// The door leading out of the structure
+    pub outer_door: Option<BlockCoordinate>,
+    /// What the doors are currently doing
+    pub state: AirlockState,
+    /// If the controller was receiving a logic signal last logic tick. Airlocks only cycle when a signal is first received.
+    pub powered: bool,
+}
+
+impl Airlock {
+    /// The door linked to this side of the airlock, if there is one
+    pub fn door(&self, side: AirlockSide) -> Option<BlockCoordinate> {
+        match side {
+            AirlockSide::Inner => self.inner_door,
+            AirlockSide::Outer => self.outer_door,
+        }
+    }
+
+    /// Links a door to this side of the airlock, or unlinks it if `None` is given
+    pub fn set_door(&mut self, side: AirlockSide, door: Option<BlockCoordinate>) {
+        match side {
+            AirlockSide::Inner => self.inner_door = door,
+            AirlockSide::Outer => self.outer_door = door,
+        }
+    }
+
+    /// Returns true if the airlock is between opening its doors
+    pub fn is_cycling(&self) -> bool {
+        matches!(self.state, AirlockState::Cycling { .. })
+    }
+}
+
+impl IdentifiableComponent for Airlock {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:airlock"
+    }
+}
+
+impl SyncableComponent for Airlock {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+
+    fn validate(&self) -> bool {
+        self.inner_door.is_none() || self.inner_door != self.outer_door
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the server to the client to instruct them to open the menu of this airlock controller.
+pub struct OpenAirlockEvent(pub StructureBlock);
+
+impl IdentifiableEvent for OpenAirlockEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:open_airlock"
+    }
+}
+
+impl NettyEvent for OpenAirlockEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to the server to link a door to one side of an airlock, or to unlink it.
+pub struct SetAirlockDoorEvent {
+    /// The airlock controller
+    pub controller: StructureBlock,
+    /// The side of the airlock being changed
+    pub side: AirlockSide,
+    /// Any block of the door to link, or `None` to unlink this side's door
+    pub door: Option<BlockCoordinate>,
+}
+
+impl IdentifiableEvent for SetAirlockDoorEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:set_airlock_door"
+    }
+}
+
+impl NettyEvent for SetAirlockDoorEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to the server to cycle an airlock from its menu.
+pub struct CycleAirlockEvent(pub StructureBlock);
+
+impl IdentifiableEvent for CycleAirlockEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:cycle_airlock"
+    }
+}
+
+impl NettyEvent for CycleAirlockEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+fn register_logic_connections(blocks: Res<Registry<Block>>, mut registry: ResMut<Registry<LogicBlock>>) {
+    if let Some(controller) = blocks.from_id(AIRLOCK_CONTROLLER_BLOCK) {
+        let output = Some(LogicConnection::Port(PortType::Output));
+        let input = Some(LogicConnection::Port(PortType::Input));
+        // The front is the status light, and the back cycles the airlock.
+        registry.register(LogicBlock::new(controller, [output, output, output, output, None, input]));
+    }
+}
+
+fn airlock_output_event_listener(
+    evr_logic_output: EventReader<LogicOutputEvent>,
+    evw_queue_logic_input: EventWriter<QueueLogicInputEvent>,
+    logic_blocks: Res<Registry<LogicBlock>>,
+    blocks: Res<Registry<Block>>,
+    q_logic_driver: Query<&mut LogicDriver>,
+    q_structure: Query<&mut Structure>,
+    q_logic_data: Query<&BlockLogicData>,
+) {
+    default_logic_block_output(
+        AIRLOCK_CONTROLLER_BLOCK,
+        evr_logic_output,
+        evw_queue_logic_input,
+        &logic_blocks,
+        &blocks,
+        q_logic_driver,
+        q_structure,
+        q_logic_data,
+    );
+}
+
+pub(super) fn register<T: States>(app: &mut App, post_loading_state: T) {
+    sync_component::<Airlock>(app);
+
+    app.register_type::<Airlock>()
+        .add_netty_event::<OpenAirlockEvent>()
+        .add_netty_event::<SetAirlockDoorEvent>()
+        .add_netty_event::<CycleAirlockEvent>();
+
+    app.add_systems(OnEnter(post_loading_state), register_logic_connections)
+        .add_systems(
+            Update,
+            airlock_output_event_listener
+                .in_set(LogicSystemSet::Produce)
+                .ambiguous_with(LogicSystemSet::Produce),
+        );
+}
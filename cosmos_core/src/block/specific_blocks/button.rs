@@ -0,0 +1,60 @@
+//! Logic behavior for "Button", a block that outputs a short pulse on every face but its front when pressed.
+//!
+//! How long the pulse lasts is set by the server.
+
+use bevy::{
+    app::{App, Update},
+    prelude::{EventReader, EventWriter, IntoSystemConfigs, OnEnter, Query, Res, ResMut, States},
+};
+
+use crate::{
+    block::Block,
+    logic::{
+        default_logic_block_output, logic_driver::LogicDriver, BlockLogicData, LogicBlock, LogicConnection, LogicOutputEvent,
+        LogicSystemSet, PortType, QueueLogicInputEvent,
+    },
+    registry::{identifiable::Identifiable, Registry},
+    structure::Structure,
+};
+
+/// The unlocalized name of the button block
+pub const BUTTON_BLOCK: &str = "cosmos:button";
+
+fn register_logic_connections(blocks: Res<Registry<Block>>, mut registry: ResMut<Registry<LogicBlock>>) {
+    if let Some(button) = blocks.from_id(BUTTON_BLOCK) {
+        let output = Some(LogicConnection::Port(PortType::Output));
+        // The front is the face that gets pressed.
+        registry.register(LogicBlock::new(button, [output, output, output, output, None, output]));
+    }
+}
+
+fn button_output_event_listener(
+    evr_logic_output: EventReader<LogicOutputEvent>,
+    evw_queue_logic_input: EventWriter<QueueLogicInputEvent>,
+    logic_blocks: Res<Registry<LogicBlock>>,
+    blocks: Res<Registry<Block>>,
+    q_logic_driver: Query<&mut LogicDriver>,
+    q_structure: Query<&mut Structure>,
+    q_logic_data: Query<&BlockLogicData>,
+) {
+    default_logic_block_output(
+        BUTTON_BLOCK,
+        evr_logic_output,
+        evw_queue_logic_input,
+        &logic_blocks,
+        &blocks,
+        q_logic_driver,
+        q_structure,
+        q_logic_data,
+    );
+}
+
+pub(super) fn register<T: States>(app: &mut App, post_loading_state: T) {
+    app.add_systems(OnEnter(post_loading_state), register_logic_connections)
+        .add_systems(
+            Update,
+            button_output_event_listener
+                .in_set(LogicSystemSet::Produce)
+                .ambiguous_with(LogicSystemSet::Produce),
+        );
+}
@@ -0,0 +1,163 @@
+//! Logic behavior for "Clock", a block that outputs on every face but its front based on the day/night cycle of the nearest planet.
+//!
+//! Away from planets, the sun never sets.
+
+use bevy::{
+    app::{App, Update},
+    prelude::{Component, Event, EventReader, EventWriter, IntoSystemConfigs, OnEnter, Query, Res, ResMut, States},
+    reflect::Reflect,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::Block,
+    logic::{
+        default_logic_block_output, logic_driver::LogicDriver, BlockLogicData, LogicBlock, LogicConnection, LogicOutputEvent,
+        LogicSystemSet, PortType, QueueLogicInputEvent,
+    },
+    netty::sync::{
+        events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+        sync_component, IdentifiableComponent, SyncType, SyncableComponent,
+    },
+    registry::{identifiable::Identifiable, Registry},
+    structure::{structure_block::StructureBlock, Structure},
+};
+
+/// The unlocalized name of the clock block
+pub const CLOCK_BLOCK: &str = "cosmos:clock";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// What a clock block outputs
+pub enum ClockMode {
+    #[default]
+    /// Outputs 1 while the sun is above the horizon, and 0 otherwise
+    Day,
+    /// Outputs 1 while the sun is below the horizon, and 0 otherwise
+    Night,
+    /// Outputs the angle of the sun above the horizon, in degrees (-90 to 90)
+    SunElevation,
+}
+
+impl ClockMode {
+    /// Every mode, in the order they should be displayed
+    pub const ALL: [ClockMode; 3] = [ClockMode::Day, ClockMode::Night, ClockMode::SunElevation];
+
+    /// The signal a clock in this mode outputs when the sun is this many degrees above the horizon
+    pub fn signal(&self, sun_elevation_degrees: f32) -> i32 {
+        match self {
+            Self::Day => (sun_elevation_degrees > 0.0) as i32,
+            Self::Night => (sun_elevation_degrees <= 0.0) as i32,
+            Self::SunElevation => sun_elevation_degrees.round() as i32,
+        }
+    }
+
+    /// The unlocalized name of this mode, used for translations
+    pub fn unlocalized_name(&self) -> &'static str {
+        match self {
+            Self::Day => "cosmos:clock_mode.day",
+            Self::Night => "cosmos:clock_mode.night",
+            Self::SunElevation => "cosmos:clock_mode.sun_elevation",
+        }
+    }
+}
+
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// What a clock block outputs. This is stored as block data on the clock.
+pub struct ClockSettings {
+    /// What this clock outputs
+    pub mode: ClockMode,
+}
+
+impl IdentifiableComponent for ClockSettings {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:clock_settings"
+    }
+}
+
+impl SyncableComponent for ClockSettings {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the server to the client to instruct them to open the settings of this clock block.
+pub struct OpenClockSettingsEvent(pub StructureBlock);
+
+impl IdentifiableEvent for OpenClockSettingsEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:open_clock_settings"
+    }
+}
+
+impl NettyEvent for OpenClockSettingsEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to the server to change what a clock outputs.
+pub struct SetClockSettingsEvent {
+    /// The clock block
+    pub block: StructureBlock,
+    /// The new settings
+    pub settings: ClockSettings,
+}
+
+impl IdentifiableEvent for SetClockSettingsEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:set_clock_settings"
+    }
+}
+
+impl NettyEvent for SetClockSettingsEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+fn register_logic_connections(blocks: Res<Registry<Block>>, mut registry: ResMut<Registry<LogicBlock>>) {
+    if let Some(clock) = blocks.from_id(CLOCK_BLOCK) {
+        let output = Some(LogicConnection::Port(PortType::Output));
+        // The front is the face with the display.
+        registry.register(LogicBlock::new(clock, [output, output, output, output, None, output]));
+    }
+}
+
+fn clock_output_event_listener(
+    evr_logic_output: EventReader<LogicOutputEvent>,
+    evw_queue_logic_input: EventWriter<QueueLogicInputEvent>,
+    logic_blocks: Res<Registry<LogicBlock>>,
+    blocks: Res<Registry<Block>>,
+    q_logic_driver: Query<&mut LogicDriver>,
+    q_structure: Query<&mut Structure>,
+    q_logic_data: Query<&BlockLogicData>,
+) {
+    default_logic_block_output(
+        CLOCK_BLOCK,
+        evr_logic_output,
+        evw_queue_logic_input,
+        &logic_blocks,
+        &blocks,
+        q_logic_driver,
+        q_structure,
+        q_logic_data,
+    );
+}
+
+pub(super) fn register<T: States>(app: &mut App, post_loading_state: T) {
+    sync_component::<ClockSettings>(app);
+
+    app.register_type::<ClockSettings>()
+        .add_netty_event::<OpenClockSettingsEvent>()
+        .add_netty_event::<SetClockSettingsEvent>();
+
+    app.add_systems(OnEnter(post_loading_state), register_logic_connections)
+        .add_systems(
+            Update,
+            clock_output_event_listener
+                .in_set(LogicSystemSet::Produce)
+                .ambiguous_with(LogicSystemSet::Produce),
+        );
+}
@@ -0,0 +1,104 @@
+//! Shared logic for farming - tilled soil and the crops that grow on it.
+//!
+//! Every crop is made up of a block per growth stage. Crops that can still grow store their
+//! [`CropGrowth`] as block data, and are swapped out for the next stage's block once they're fully grown.
+
+use bevy::{
+    app::App,
+    prelude::{Component, Reflect},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::IdentifiableComponent;
+
+/// The unlocalized name of the block dirt & grass turn into when they are tilled
+pub const FARMLAND_BLOCK: &str = "cosmos:farmland";
+
+/// The unlocalized name of the item used to till dirt & grass into farmland
+pub const HOE_ITEM: &str = "cosmos:hoe";
+
+/// Blocks that can be tilled into farmland
+pub const TILLABLE_BLOCKS: [&str; 2] = ["cosmos:dirt", "cosmos:grass"];
+
+/// How long (in seconds) each stage of a crop takes to grow in full sunlight
+pub const SECONDS_PER_GROWTH_STAGE: f32 = 120.0;
+
+#[derive(Debug)]
+/// A plant that can be grown on farmland
+pub struct Crop {
+    /// The item planted on farmland to grow this crop
+    pub seed_item: &'static str,
+    /// The block for every stage of this crop's growth, from freshly planted to ready to harvest
+    pub stages: &'static [&'static str],
+    /// The item this crop yields when it's harvested
+    pub produce_item: &'static str,
+    /// The most of [`Self::produce_item`] a single harvest can yield. At least one is always given.
+    pub max_produce: u16,
+    /// The most seeds a single harvest can give back (on top of the one used to replant it)
+    pub max_seeds: u16,
+}
+
+impl Crop {
+    /// The block this crop is planted as
+    pub fn planted_block(&self) -> &'static str {
+        self.stages[0]
+    }
+
+    /// The block this crop is as once it's ready to harvest
+    pub fn grown_block(&self) -> &'static str {
+        self.stages[self.stages.len() - 1]
+    }
+}
+
+/// Every crop that can be grown
+pub const CROPS: &[Crop] = &[
+    Crop {
+        seed_item: "cosmos:wheat_seeds",
+        stages: &[
+            "cosmos:wheat_crop_0",
+            "cosmos:wheat_crop_1",
+            "cosmos:wheat_crop_2",
+            "cosmos:wheat_crop_3",
+        ],
+        produce_item: "cosmos:wheat",
+        max_produce: 3,
+        max_seeds: 2,
+    },
+    Crop {
+        // Potatoes are planted directly
+        seed_item: "cosmos:potato",
+        stages: &["cosmos:potato_crop_0", "cosmos:potato_crop_1", "cosmos:potato_crop_2"],
+        produce_item: "cosmos:potato",
+        max_produce: 4,
+        max_seeds: 0,
+    },
+];
+
+/// Finds the crop this block is a stage of, along with which stage it is
+pub fn crop_from_block(unlocalized_name: &str) -> Option<(&'static Crop, usize)> {
+    CROPS
+        .iter()
+        .find_map(|crop| crop.stages.iter().position(|s| *s == unlocalized_name).map(|stage| (crop, stage)))
+}
+
+/// Finds the crop that is planted using this item
+pub fn crop_from_seed(unlocalized_name: &str) -> Option<&'static Crop> {
+    CROPS.iter().find(|crop| crop.seed_item == unlocalized_name)
+}
+
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
+/// How far a crop is through its current growth stage. This is stored as block data on crops that can still grow.
+pub struct CropGrowth {
+    /// How many seconds of full sunlight this crop has had in its current stage
+    pub progress: f32,
+}
+
+impl IdentifiableComponent for CropGrowth {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:crop_growth"
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.register_type::<CropGrowth>();
+}
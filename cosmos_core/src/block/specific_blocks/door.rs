@@ -0,0 +1,67 @@
+//! Doors are made of any number of door blocks. Every door block touching another one opens & closes with it.
+
+use bevy::utils::HashSet;
+
+use crate::{
+    block::{block_direction::ALL_BLOCK_DIRECTIONS, Block},
+    registry::{identifiable::Identifiable, Registry},
+    structure::{coordinates::BlockCoordinate, Structure},
+};
+
+/// The unlocalized name of a closed door block
+pub const DOOR_BLOCK: &str = "cosmos:door";
+/// The unlocalized name of an open door block
+pub const DOOR_OPEN_BLOCK: &str = "cosmos:door_open";
+
+/// Returns `Some(true)` if there is an open door here, `Some(false)` if there is a closed door here, or `None` if there is no door here.
+pub fn door_open_at(structure: &Structure, coords: BlockCoordinate, blocks: &Registry<Block>) -> Option<bool> {
+    if !structure.is_within_blocks(coords) {
+        return None;
+    }
+
+    match structure.block_at(coords, blocks).unlocalized_name() {
+        DOOR_BLOCK => Some(false),
+        DOOR_OPEN_BLOCK => Some(true),
+        _ => None,
+    }
+}
+
+/// Returns every door block that is part of the same door as the block at these coordinates.
+///
+/// This is empty if there is no door at these coordinates.
+pub fn connected_door_blocks(structure: &Structure, start: BlockCoordinate, blocks: &Registry<Block>) -> HashSet<BlockCoordinate> {
+    let mut door_blocks = HashSet::new();
+
+    let (Some(door), Some(door_open)) = (blocks.from_id(DOOR_BLOCK), blocks.from_id(DOOR_OPEN_BLOCK)) else {
+        return door_blocks;
+    };
+
+    let is_door = |coords: BlockCoordinate| {
+        structure.is_within_blocks(coords) && {
+            let id = structure.block_id_at(coords);
+            id == door.id() || id == door_open.id()
+        }
+    };
+
+    if !is_door(start) {
+        return door_blocks;
+    }
+
+    let mut todo = vec![start];
+    door_blocks.insert(start);
+
+    while let Some(coords) = todo.pop() {
+        for dir in ALL_BLOCK_DIRECTIONS {
+            let Ok(neighbor) = BlockCoordinate::try_from(dir.to_coordinates() + coords) else {
+                continue;
+            };
+
+            if !door_blocks.contains(&neighbor) && is_door(neighbor) {
+                door_blocks.insert(neighbor);
+                todo.push(neighbor);
+            }
+        }
+    }
+
+    door_blocks
+}
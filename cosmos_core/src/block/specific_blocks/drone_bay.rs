@@ -0,0 +1,28 @@
+//! Drone bays launch builder drones that weld ghost blocks & repair damaged blocks on their structure.
+//!
+//! Drones take the materials they need from the structure's storage blocks, and fly back into their bay once they're
+//! done.
+
+use bevy::prelude::*;
+
+use crate::{block::Block, registry::Registry, structure::block_counts::TrackedBlockPositions};
+
+/// The unlocalized name of the drone bay block
+pub const DRONE_BAY_BLOCK: &str = "cosmos:drone_bay";
+
+/// The most drones a single bay can have out at once
+pub const MAX_DRONES_PER_BAY: usize = 2;
+
+/// How far (in blocks) from their bay drones will go to work
+pub const DRONE_BAY_RANGE: f32 = 64.0;
+
+/// Drone bays are found through their positions rather than iterating over every block of a structure
+fn track_drone_bay_positions(blocks: Res<Registry<Block>>, mut tracked: ResMut<TrackedBlockPositions>) {
+    if let Some(drone_bay) = blocks.from_id(DRONE_BAY_BLOCK) {
+        tracked.track(drone_bay);
+    }
+}
+
+pub(super) fn register<T: States>(app: &mut App, post_loading_state: T) {
+    app.add_systems(OnEnter(post_loading_state), track_drone_bay_positions);
+}
@@ -0,0 +1,108 @@
+//! Energy relays wirelessly share energy between the structures they are placed on.
+//!
+//! Two relays that are linked together will move energy from whichever structure has the fuller
+//! energy storage to the emptier one, as long as they stay within [`ENERGY_RELAY_RANGE`] of each other.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::Block,
+    netty::sync::{
+        events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+        sync_component, IdentifiableComponent, SyncType, SyncableComponent,
+    },
+    physics::location::Location,
+    registry::Registry,
+    structure::{block_counts::TrackedBlockPositions, structure_block::StructureBlock},
+};
+
+/// The unlocalized name of the energy relay block
+pub const ENERGY_RELAY_BLOCK: &str = "cosmos:energy_relay";
+
+/// How far apart (in meters) two relays can be and still share energy
+pub const ENERGY_RELAY_RANGE: f32 = 200.0;
+
+/// The most energy a single link can move per second
+pub const ENERGY_RELAY_TRANSFER_RATE: f32 = 1000.0;
+
+/// Returns true if relays at these locations are close enough to share energy
+pub fn within_relay_range(a: &Location, b: &Location) -> bool {
+    a.distance_sqrd(b) <= ENERGY_RELAY_RANGE * ENERGY_RELAY_RANGE
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// The relay this relay is linked to. This is stored as block data on both relays of a link.
+pub struct EnergyRelayLink(pub StructureBlock);
+
+impl IdentifiableComponent for EnergyRelayLink {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:energy_relay_link"
+    }
+}
+
+impl SyncableComponent for EnergyRelayLink {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+
+    #[cfg(feature = "client")]
+    fn convert_entities_server_to_client(self, mapping: &crate::netty::sync::mapping::NetworkMapping) -> Option<Self> {
+        use crate::netty::sync::mapping::Mappable;
+
+        self.0.map(mapping).ok().map(Self)
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the server to the client to instruct them to open the link menu of this relay.
+pub struct OpenEnergyRelayEvent(pub StructureBlock);
+
+impl IdentifiableEvent for OpenEnergyRelayEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:open_energy_relay"
+    }
+}
+
+impl NettyEvent for OpenEnergyRelayEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to the server to link a relay to another one, or to unlink it.
+pub struct SetEnergyRelayLinkEvent {
+    /// The relay being changed
+    pub relay: StructureBlock,
+    /// The relay to link to, or `None` to remove the relay's current link
+    pub target: Option<StructureBlock>,
+}
+
+impl IdentifiableEvent for SetEnergyRelayLinkEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:set_energy_relay_link"
+    }
+}
+
+impl NettyEvent for SetEnergyRelayLinkEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+/// Relays need to be able to find the other relays around them
+fn track_relay_positions(blocks: Res<Registry<Block>>, mut tracked: ResMut<TrackedBlockPositions>) {
+    if let Some(relay) = blocks.from_id(ENERGY_RELAY_BLOCK) {
+        tracked.track(relay);
+    }
+}
+
+pub(super) fn register<T: States>(app: &mut App, post_loading_state: T) {
+    sync_component::<EnergyRelayLink>(app);
+
+    app.add_systems(OnEnter(post_loading_state), track_relay_positions)
+        .register_type::<EnergyRelayLink>()
+        .add_netty_event::<OpenEnergyRelayEvent>()
+        .add_netty_event::<SetEnergyRelayLinkEvent>();
+}
@@ -22,7 +22,9 @@ use bevy_rapier3d::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    block::specific_blocks::ladder::Climbing,
     netty::{sync::IdentifiableComponent, system_sets::NetworkingSystemsSet},
+    physics::structure_mass::StructureMass,
     structure::coordinates::BlockCoordinate,
 };
 
@@ -45,7 +47,7 @@ impl IdentifiableComponent for GravityWell {
 
 fn do_gravity_well(
     time: Res<Time>,
-    mut q_grav_well: Query<(&GravityWell, &ReadMassProperties, &mut ExternalImpulse)>,
+    mut q_grav_well: Query<(&GravityWell, &ReadMassProperties, &mut ExternalImpulse), Without<Climbing>>,
     q_global_trans: Query<&GlobalTransform>,
 ) {
     for (under_gravity_well, read_mass_props, mut ext_impulse) in q_grav_well.iter_mut() {
@@ -60,10 +62,14 @@ fn do_gravity_well(
 
 // TODO: This is a hack because the physics engine is kinda buggy. Check back on this in the
 // future.
-fn update_mass_props(mut commands: Commands, q_ent: Query<Entity, With<ReadMassProperties>>) {
-    for e in q_ent.iter() {
+fn update_mass_props(mut commands: Commands, q_ent: Query<(Entity, Option<&StructureMass>), With<ReadMassProperties>>) {
+    for (e, structure_mass) in q_ent.iter() {
         // Recalculates the [`ReadMassProperties`], which can be unreliable
-        commands.entity(e).insert(AdditionalMassProperties::Mass(0.0));
+        let additional_mass = structure_mass
+            .map(|x| AdditionalMassProperties::MassProperties(x.mass_properties()))
+            .unwrap_or(AdditionalMassProperties::Mass(0.0));
+
+        commands.entity(e).insert(additional_mass);
     }
 }
 
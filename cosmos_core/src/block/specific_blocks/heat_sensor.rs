@@ -0,0 +1,70 @@
+//! Logic behavior for "Heat Sensor", a block that outputs how hot its structure is on every face but its front.
+//!
+//! The signal is the structure's heat as a percentage of what it can hold, so anything at or above 100 means it is overheating.
+
+use bevy::{
+    app::{App, Update},
+    prelude::{EventReader, EventWriter, IntoSystemConfigs, OnEnter, Query, Res, ResMut, States},
+};
+
+use crate::{
+    block::Block,
+    logic::{
+        default_logic_block_output, logic_driver::LogicDriver, BlockLogicData, LogicBlock, LogicConnection, LogicOutputEvent,
+        LogicSystemSet, PortType, QueueLogicInputEvent,
+    },
+    registry::{identifiable::Identifiable, Registry},
+    structure::{block_counts::TrackedBlockPositions, Structure},
+};
+
+/// The unlocalized name of the heat sensor block
+pub const HEAT_SENSOR_BLOCK: &str = "cosmos:heat_sensor";
+
+fn register_logic_connections(blocks: Res<Registry<Block>>, mut registry: ResMut<Registry<LogicBlock>>) {
+    if let Some(heat_sensor) = blocks.from_id(HEAT_SENSOR_BLOCK) {
+        let output = Some(LogicConnection::Port(PortType::Output));
+        // The front is the face with the display.
+        registry.register(LogicBlock::new(heat_sensor, [output, output, output, output, None, output]));
+    }
+}
+
+/// Heat sensors are updated whenever their structure's heat changes, so every sensor needs to be easy to find
+fn track_heat_sensor_positions(blocks: Res<Registry<Block>>, mut tracked: ResMut<TrackedBlockPositions>) {
+    if let Some(heat_sensor) = blocks.from_id(HEAT_SENSOR_BLOCK) {
+        tracked.track(heat_sensor);
+    }
+}
+
+fn heat_sensor_output_event_listener(
+    evr_logic_output: EventReader<LogicOutputEvent>,
+    evw_queue_logic_input: EventWriter<QueueLogicInputEvent>,
+    logic_blocks: Res<Registry<LogicBlock>>,
+    blocks: Res<Registry<Block>>,
+    q_logic_driver: Query<&mut LogicDriver>,
+    q_structure: Query<&mut Structure>,
+    q_logic_data: Query<&BlockLogicData>,
+) {
+    default_logic_block_output(
+        HEAT_SENSOR_BLOCK,
+        evr_logic_output,
+        evw_queue_logic_input,
+        &logic_blocks,
+        &blocks,
+        q_logic_driver,
+        q_structure,
+        q_logic_data,
+    );
+}
+
+pub(super) fn register<T: States>(app: &mut App, post_loading_state: T) {
+    app.add_systems(
+        OnEnter(post_loading_state),
+        (register_logic_connections, track_heat_sensor_positions),
+    )
+    .add_systems(
+        Update,
+        heat_sensor_output_event_listener
+            .in_set(LogicSystemSet::Produce)
+            .ambiguous_with(LogicSystemSet::Produce),
+    );
+}
@@ -0,0 +1,81 @@
+//! Shared logic for the holographic projector, which displays a hologram above itself.
+
+use bevy::{
+    app::App,
+    prelude::{Component, OnEnter, Res, ResMut, States},
+    reflect::Reflect,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::Block,
+    logic::{LogicBlock, LogicConnection, PortType},
+    netty::sync::{sync_component, IdentifiableComponent, SyncableComponent},
+    registry::{identifiable::Identifiable, Registry},
+};
+
+/// The unlocalized name of the holographic projector block
+pub const HOLO_PROJECTOR_BLOCK: &str = "cosmos:holo_projector";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// What a holographic projector is displaying
+pub enum HoloProjectorMode {
+    #[default]
+    /// The energy and shield levels of the structure the projector is on
+    ShipStats,
+    /// A miniature version of the structure the projector is on
+    Miniature,
+}
+
+impl HoloProjectorMode {
+    /// The mode the projector should switch to when it is interacted with
+    pub fn next(&self) -> Self {
+        match self {
+            Self::ShipStats => Self::Miniature,
+            Self::Miniature => Self::ShipStats,
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// The block data of a holographic projector
+pub struct HoloProjector {
+    /// What the projector is displaying
+    pub mode: HoloProjectorMode,
+    /// If the hologram is being displayed. A logic signal turning on will toggle this.
+    pub enabled: bool,
+}
+
+impl Default for HoloProjector {
+    fn default() -> Self {
+        Self {
+            mode: Default::default(),
+            enabled: true,
+        }
+    }
+}
+
+impl IdentifiableComponent for HoloProjector {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:holo_projector"
+    }
+}
+
+impl SyncableComponent for HoloProjector {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+}
+
+fn register_logic_ports(blocks: Res<Registry<Block>>, mut registry: ResMut<Registry<LogicBlock>>) {
+    if let Some(holo_projector) = blocks.from_id(HOLO_PROJECTOR_BLOCK) {
+        registry.register(LogicBlock::new(holo_projector, [Some(LogicConnection::Port(PortType::Input)); 6]));
+    }
+}
+
+pub(super) fn register<T: States>(app: &mut App, post_loading_state: T) {
+    sync_component::<HoloProjector>(app);
+
+    app.add_systems(OnEnter(post_loading_state), register_logic_ports)
+        .register_type::<HoloProjector>();
+}
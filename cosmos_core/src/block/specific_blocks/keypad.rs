@@ -0,0 +1,125 @@
+//! Logic behavior for "Keypad", a block that outputs a short pulse on every face but its front when the correct code is entered.
+//!
+//! Codes are only known by the server, so they are never sent to clients.
+
+use bevy::{
+    app::{App, Update},
+    prelude::{Event, EventReader, EventWriter, IntoSystemConfigs, OnEnter, Query, Res, ResMut, States},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::Block,
+    logic::{
+        default_logic_block_output, logic_driver::LogicDriver, BlockLogicData, LogicBlock, LogicConnection, LogicOutputEvent,
+        LogicSystemSet, PortType, QueueLogicInputEvent,
+    },
+    netty::sync::events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+    registry::{identifiable::Identifiable, Registry},
+    structure::{structure_block::StructureBlock, Structure},
+};
+
+/// The unlocalized name of the keypad block
+pub const KEYPAD_BLOCK: &str = "cosmos:keypad";
+
+/// The maximum number of digits a keypad code can have
+pub const MAX_KEYPAD_CODE_LENGTH: usize = 8;
+
+/// Returns true if this can be used as the code of a keypad
+pub fn is_valid_keypad_code(code: &str) -> bool {
+    !code.is_empty() && code.len() <= MAX_KEYPAD_CODE_LENGTH && code.chars().all(|c| c.is_ascii_digit())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// What the code the player is asked for will be used for
+pub enum KeypadPurpose {
+    /// Try the code, sending a pulse if it's correct
+    Enter,
+    /// Change the keypad's code
+    SetCode,
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the server to ask the player to type a code into a keypad
+pub struct OpenKeypadEvent {
+    /// The keypad block
+    pub block: StructureBlock,
+    /// What the entered code will be used for
+    pub purpose: KeypadPurpose,
+}
+
+impl IdentifiableEvent for OpenKeypadEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:open_keypad"
+    }
+}
+
+impl NettyEvent for OpenKeypadEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+/// Sent by the client with the code they typed into a [`OpenKeypadEvent`] prompt
+pub struct EnterKeypadCodeEvent {
+    /// The keypad block
+    pub block: StructureBlock,
+    /// The code the player entered
+    pub code: String,
+    /// What the entered code will be used for
+    pub purpose: KeypadPurpose,
+}
+
+impl IdentifiableEvent for EnterKeypadCodeEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:enter_keypad_code"
+    }
+}
+
+impl NettyEvent for EnterKeypadCodeEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+fn register_logic_connections(blocks: Res<Registry<Block>>, mut registry: ResMut<Registry<LogicBlock>>) {
+    if let Some(keypad) = blocks.from_id(KEYPAD_BLOCK) {
+        let output = Some(LogicConnection::Port(PortType::Output));
+        // The front is the face with the keys.
+        registry.register(LogicBlock::new(keypad, [output, output, output, output, None, output]));
+    }
+}
+
+fn keypad_output_event_listener(
+    evr_logic_output: EventReader<LogicOutputEvent>,
+    evw_queue_logic_input: EventWriter<QueueLogicInputEvent>,
+    logic_blocks: Res<Registry<LogicBlock>>,
+    blocks: Res<Registry<Block>>,
+    q_logic_driver: Query<&mut LogicDriver>,
+    q_structure: Query<&mut Structure>,
+    q_logic_data: Query<&BlockLogicData>,
+) {
+    default_logic_block_output(
+        KEYPAD_BLOCK,
+        evr_logic_output,
+        evw_queue_logic_input,
+        &logic_blocks,
+        &blocks,
+        q_logic_driver,
+        q_structure,
+        q_logic_data,
+    );
+}
+
+pub(super) fn register<T: States>(app: &mut App, post_loading_state: T) {
+    app.add_netty_event::<OpenKeypadEvent>().add_netty_event::<EnterKeypadCodeEvent>();
+
+    app.add_systems(OnEnter(post_loading_state), register_logic_connections)
+        .add_systems(
+            Update,
+            keypad_output_event_listener
+                .in_set(LogicSystemSet::Produce)
+                .ambiguous_with(LogicSystemSet::Produce),
+        );
+}
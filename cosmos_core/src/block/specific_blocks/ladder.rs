@@ -0,0 +1,53 @@
+//! Ladders let players climb up & down the insides of structures while under gravity.
+//!
+//! Whether or not a player is climbing is decided by their client, since it's the one moving them. Climbing players
+//! are not pulled by gravity, so they can hold still on a ladder.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::Block,
+    netty::sync::{sync_component, ClientAuthority, IdentifiableComponent, SyncType, SyncableComponent},
+    prelude::Structure,
+    registry::Registry,
+};
+
+/// The unlocalized name of the ladder block
+pub const LADDER_BLOCK: &str = "cosmos:ladder";
+
+/// How fast (in m/s) players climb ladders
+pub const CLIMB_SPEED: f32 = 3.0;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// Put on a player that is holding onto a ladder. Gravity does not affect them while this is present.
+pub struct Climbing;
+
+impl IdentifiableComponent for Climbing {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:climbing"
+    }
+}
+
+impl SyncableComponent for Climbing {
+    fn get_sync_type() -> SyncType {
+        SyncType::ClientAuthoritative(ClientAuthority::Themselves)
+    }
+}
+
+/// Returns true if there is a ladder at this point in the world on the given structure
+pub fn is_ladder_at(point: Vec3, structure: &Structure, structure_g_trans: &GlobalTransform, blocks: &Registry<Block>) -> bool {
+    let local = structure_g_trans.affine().inverse().transform_point3(point);
+
+    let Ok(coords) = structure.relative_coords_to_local_coords_checked(local.x, local.y, local.z) else {
+        return false;
+    };
+
+    structure.block_at(coords, blocks).unlocalized_name() == LADDER_BLOCK
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<Climbing>(app);
+
+    app.register_type::<Climbing>();
+}
@@ -0,0 +1,135 @@
+//! Logic behavior for "Logic Filter", a block with a back signal input, a left threshold input, and a front output.
+//! Outputs the signal if it is greater than the threshold. Otherwise, outputs 0.
+
+use std::{cell::RefCell, rc::Rc};
+
+use bevy::{
+    app::{App, Update},
+    prelude::{EventReader, EventWriter, IntoSystemConfigs, OnEnter, Query, Res, ResMut, States},
+};
+
+use crate::{
+    block::{Block, BlockFace},
+    events::block_events::BlockDataSystemParams,
+    logic::{
+        default_logic_block_output, logic_driver::LogicDriver, BlockLogicData, LogicBlock, LogicConnection, LogicInputEvent,
+        LogicOutputEvent, LogicSystemSet, PortType, QueueLogicInputEvent,
+    },
+    registry::{identifiable::Identifiable, Registry},
+    structure::Structure,
+};
+
+/// The unlocalized name of the logic filter block
+pub const LOGIC_FILTER_BLOCK: &str = "cosmos:logic_filter";
+
+/// The signal a logic filter outputs for these inputs
+pub fn filter_signal(signal: i32, threshold: i32) -> i32 {
+    if signal > threshold {
+        signal
+    } else {
+        0
+    }
+}
+
+fn register_logic_connections(blocks: Res<Registry<Block>>, mut registry: ResMut<Registry<LogicBlock>>) {
+    if let Some(logic_filter) = blocks.from_id(LOGIC_FILTER_BLOCK) {
+        registry.register(LogicBlock::new(
+            logic_filter,
+            [
+                None,
+                Some(LogicConnection::Port(PortType::Input)),
+                None,
+                None,
+                Some(LogicConnection::Port(PortType::Output)),
+                Some(LogicConnection::Port(PortType::Input)),
+            ],
+        ));
+    }
+}
+
+fn logic_filter_input_event_listener(
+    mut evr_logic_input: EventReader<LogicInputEvent>,
+    blocks: Res<Registry<Block>>,
+    q_logic_driver: Query<&LogicDriver>,
+    q_structure: Query<&Structure>,
+    mut q_logic_data: Query<&mut BlockLogicData>,
+    bs_params: BlockDataSystemParams,
+) {
+    let bs_params = Rc::new(RefCell::new(bs_params));
+    for ev in evr_logic_input.read() {
+        let Ok(structure) = q_structure.get(ev.block.structure()) else {
+            continue;
+        };
+        if structure.block_at(ev.block.coords(), &blocks).unlocalized_name() != LOGIC_FILTER_BLOCK {
+            continue;
+        }
+        let Ok(logic_driver) = q_logic_driver.get(ev.block.structure()) else {
+            continue;
+        };
+        let Some(mut logic_data) = structure.query_block_data_mut(ev.block.coords(), &mut q_logic_data, bs_params.clone()) else {
+            continue;
+        };
+
+        let coords = ev.block.coords();
+        let rotation = structure.block_rotation(ev.block.coords());
+        let signal = logic_driver.read_input(coords, rotation.direction_of(BlockFace::Back));
+        let threshold = logic_driver.read_input(coords, rotation.direction_of(BlockFace::Left));
+        let new_state = BlockLogicData(filter_signal(signal, threshold));
+
+        if **logic_data != new_state {
+            // Don't trigger unneccesary change detection.
+            **logic_data = new_state;
+        }
+    }
+}
+
+fn logic_filter_output_event_listener(
+    evr_logic_output: EventReader<LogicOutputEvent>,
+    evw_queue_logic_input: EventWriter<QueueLogicInputEvent>,
+    logic_blocks: Res<Registry<LogicBlock>>,
+    blocks: Res<Registry<Block>>,
+    q_logic_driver: Query<&mut LogicDriver>,
+    q_structure: Query<&mut Structure>,
+    q_logic_data: Query<&BlockLogicData>,
+) {
+    default_logic_block_output(
+        LOGIC_FILTER_BLOCK,
+        evr_logic_output,
+        evw_queue_logic_input,
+        &logic_blocks,
+        &blocks,
+        q_logic_driver,
+        q_structure,
+        q_logic_data,
+    );
+}
+
+pub(super) fn register<T: States>(app: &mut App, post_loading_state: T) {
+    app.add_systems(OnEnter(post_loading_state), register_logic_connections)
+        .add_systems(
+            Update,
+            logic_filter_input_event_listener
+                .in_set(LogicSystemSet::Consume)
+                .ambiguous_with(LogicSystemSet::Consume),
+        )
+        .add_systems(
+            Update,
+            logic_filter_output_event_listener
+                .in_set(LogicSystemSet::Produce)
+                .ambiguous_with(LogicSystemSet::Produce),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_passes_signals_above_threshold() {
+        assert_eq!(filter_signal(5, 3), 5);
+        assert_eq!(filter_signal(3, 3), 0);
+        assert_eq!(filter_signal(1, 3), 0);
+        // A missing threshold passes any positive signal
+        assert_eq!(filter_signal(1, 0), 1);
+    }
+}
@@ -0,0 +1,183 @@
+//! Logic behavior for "Logic Splitter", a block with a back signal input, a left control input, and front and right outputs.
+//! Sends the signal out its front while the control is zero or missing, and out its right while the control is non-zero.
+//! The unused output is 0.
+//!
+//! The control is sampled while consuming, alongside the signal, so the route can't change partway through producing.
+
+use std::{cell::RefCell, rc::Rc};
+
+use bevy::{
+    app::{App, Update},
+    prelude::{Component, EventReader, EventWriter, IntoSystemConfigs, OnEnter, Query, Res, ResMut, States, With},
+    reflect::Reflect,
+};
+
+use crate::{
+    block::{data::BlockData, Block, BlockFace},
+    events::block_events::BlockDataSystemParams,
+    logic::{
+        logic_driver::LogicDriver, BlockLogicData, LogicBlock, LogicConnection, LogicInputEvent, LogicOutputEvent, LogicSystemSet, Port,
+        PortType, QueueLogicInputEvent,
+    },
+    registry::{identifiable::Identifiable, Registry},
+    structure::Structure,
+};
+
+/// The unlocalized name of the logic splitter block
+pub const LOGIC_SPLITTER_BLOCK: &str = "cosmos:logic_splitter";
+
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+/// Which output a logic splitter is currently sending its signal to. This is stored as block data on the splitter.
+pub struct LogicSplitterRoute {
+    /// True if the signal goes out the right face, false if it goes out the front
+    pub right: bool,
+}
+
+impl LogicSplitterRoute {
+    /// The signals this splitter outputs out its front and right faces, in that order
+    pub fn outputs(&self, signal: i32) -> (i32, i32) {
+        if self.right {
+            (0, signal)
+        } else {
+            (signal, 0)
+        }
+    }
+}
+
+fn register_logic_connections(blocks: Res<Registry<Block>>, mut registry: ResMut<Registry<LogicBlock>>) {
+    if let Some(logic_splitter) = blocks.from_id(LOGIC_SPLITTER_BLOCK) {
+        registry.register(LogicBlock::new(
+            logic_splitter,
+            [
+                Some(LogicConnection::Port(PortType::Output)),
+                Some(LogicConnection::Port(PortType::Input)),
+                None,
+                None,
+                Some(LogicConnection::Port(PortType::Output)),
+                Some(LogicConnection::Port(PortType::Input)),
+            ],
+        ));
+    }
+}
+
+fn logic_splitter_input_event_listener(
+    mut evr_logic_input: EventReader<LogicInputEvent>,
+    blocks: Res<Registry<Block>>,
+    q_logic_driver: Query<&LogicDriver>,
+    mut q_structure: Query<&mut Structure>,
+    mut q_logic_data: Query<&mut BlockLogicData>,
+    mut q_route: Query<&mut LogicSplitterRoute>,
+    q_has_route: Query<(), With<LogicSplitterRoute>>,
+    mut q_block_data: Query<&mut BlockData>,
+    bs_params: BlockDataSystemParams,
+) {
+    let bs_params = Rc::new(RefCell::new(bs_params));
+    for ev in evr_logic_input.read() {
+        let Ok(mut structure) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+        if structure.block_at(ev.block.coords(), &blocks).unlocalized_name() != LOGIC_SPLITTER_BLOCK {
+            continue;
+        }
+        let Ok(logic_driver) = q_logic_driver.get(ev.block.structure()) else {
+            continue;
+        };
+
+        let coords = ev.block.coords();
+        let rotation = structure.block_rotation(ev.block.coords());
+        let signal = logic_driver.read_input(coords, rotation.direction_of(BlockFace::Back));
+        let new_route = LogicSplitterRoute {
+            right: logic_driver.read_input(coords, rotation.direction_of(BlockFace::Left)) != 0,
+        };
+
+        let has_route = structure.block_data(coords).is_some_and(|ent| q_has_route.contains(ent));
+        let route_changed = if has_route {
+            let Some(mut route) = structure.query_block_data_mut(coords, &mut q_route, bs_params.clone()) else {
+                continue;
+            };
+
+            let changed = **route != new_route;
+            if changed {
+                **route = new_route;
+            }
+            changed
+        } else {
+            structure.insert_block_data(coords, new_route, &mut bs_params.borrow_mut(), &mut q_block_data, &q_has_route);
+            new_route != LogicSplitterRoute::default()
+        };
+
+        let Some(mut logic_data) = structure.query_block_data_mut(coords, &mut q_logic_data, bs_params.clone()) else {
+            continue;
+        };
+
+        let new_state = BlockLogicData(signal);
+
+        // The outputs also need updating when only the route changes.
+        if **logic_data != new_state || route_changed {
+            **logic_data = new_state;
+        }
+    }
+}
+
+fn logic_splitter_output_event_listener(
+    mut evr_logic_output: EventReader<LogicOutputEvent>,
+    mut evw_queue_logic_input: EventWriter<QueueLogicInputEvent>,
+    blocks: Res<Registry<Block>>,
+    mut q_logic_driver: Query<&mut LogicDriver>,
+    q_structure: Query<&Structure>,
+    q_logic_data: Query<&BlockLogicData>,
+    q_route: Query<&LogicSplitterRoute>,
+) {
+    for ev in evr_logic_output.read() {
+        let Ok(structure) = q_structure.get(ev.block.structure()) else {
+            continue;
+        };
+        if structure.block_at(ev.block.coords(), &blocks).unlocalized_name() != LOGIC_SPLITTER_BLOCK {
+            continue;
+        }
+        let Ok(mut logic_driver) = q_logic_driver.get_mut(ev.block.structure()) else {
+            continue;
+        };
+        let Some(&BlockLogicData(signal)) = structure.query_block_data(ev.block.coords(), &q_logic_data) else {
+            continue;
+        };
+
+        // Splitters that haven't consumed anything yet send to their front.
+        let route = structure.query_block_data(ev.block.coords(), &q_route).copied().unwrap_or_default();
+        let (front, right) = route.outputs(signal);
+
+        let rotation = structure.block_rotation(ev.block.coords());
+        for (face, signal) in [(BlockFace::Front, front), (BlockFace::Right, right)] {
+            let port = Port::new(ev.block.coords(), rotation.direction_of(face));
+            logic_driver.update_producer(port, signal, &mut evw_queue_logic_input, ev.block.structure());
+        }
+    }
+}
+
+pub(super) fn register<T: States>(app: &mut App, post_loading_state: T) {
+    app.register_type::<LogicSplitterRoute>()
+        .add_systems(OnEnter(post_loading_state), register_logic_connections)
+        .add_systems(
+            Update,
+            logic_splitter_input_event_listener
+                .in_set(LogicSystemSet::Consume)
+                .ambiguous_with(LogicSystemSet::Consume),
+        )
+        .add_systems(
+            Update,
+            logic_splitter_output_event_listener
+                .in_set(LogicSystemSet::Produce)
+                .ambiguous_with(LogicSystemSet::Produce),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_one_output_gets_the_signal() {
+        assert_eq!(LogicSplitterRoute { right: false }.outputs(7), (7, 0));
+        assert_eq!(LogicSplitterRoute { right: true }.outputs(7), (0, 7));
+    }
+}
@@ -4,30 +4,71 @@ use bevy::{app::App, prelude::States};
 
 use crate::{logic::LogicBlock, registry::Registry};
 
+pub mod airlock;
 pub mod and_gate;
+pub mod button;
+pub mod clock;
 pub mod colored_logic_wires;
+pub mod crop;
+pub mod door;
+pub mod drone_bay;
+pub mod energy_relay;
 pub mod gravity_well;
+pub mod heat_sensor;
+pub mod holo_projector;
+pub mod keypad;
+pub mod ladder;
 mod laser_cannon;
+pub mod lever;
 pub mod logic_bus;
+pub mod logic_filter;
 pub mod logic_indicator;
 pub mod logic_on;
+pub mod logic_splitter;
 mod missile_launcher;
 pub mod not_gate;
 pub mod or_gate;
+pub mod seat;
+pub mod sign;
+pub mod solar_panel;
+pub mod storage_lock;
+pub mod timer;
+pub mod turret_mount;
+pub mod warp_gate;
 pub mod xor_gate;
 
 pub(super) fn register<T: States + Clone + Copy>(app: &mut App, post_loading_state: T) {
     gravity_well::register(app);
+    sign::register(app);
+    seat::register(app);
+    ladder::register(app);
+    crop::register(app);
+    energy_relay::register(app, post_loading_state);
+    solar_panel::register(app, post_loading_state);
+    storage_lock::register(app);
+    holo_projector::register(app, post_loading_state);
+    turret_mount::register(app, post_loading_state);
     logic_bus::register(app, post_loading_state);
     logic_on::register(app, post_loading_state);
+    button::register(app, post_loading_state);
+    lever::register(app, post_loading_state);
+    keypad::register(app, post_loading_state);
+    timer::register(app, post_loading_state);
+    airlock::register(app, post_loading_state);
+    clock::register(app, post_loading_state);
+    heat_sensor::register(app, post_loading_state);
     logic_indicator::register(app, post_loading_state);
     and_gate::register(app, post_loading_state);
     or_gate::register(app, post_loading_state);
     not_gate::register(app, post_loading_state);
     xor_gate::register(app, post_loading_state);
+    logic_splitter::register(app, post_loading_state);
+    logic_filter::register(app, post_loading_state);
     colored_logic_wires::register(app, post_loading_state);
     laser_cannon::register(app, post_loading_state);
     missile_launcher::register(app, post_loading_state);
+    warp_gate::register(app, post_loading_state);
+    drone_bay::register(app, post_loading_state);
 
     // TODO: Move this all to server, then add them to LogicSystemRegistrySet::RegisterLogicBlocks.
     app.allow_ambiguous_resource::<Registry<LogicBlock>>();
@@ -0,0 +1,157 @@
+//! Blocks players can sit (or lie) on - chairs & beds.
+//!
+//! Interacting with one of these seats the player on it, which attaches them to the structure and stops them
+//! from walking around until they stand back up. Cockpit seats are also seats, but sitting in one pilots the ship
+//! instead, so they are handled with the rest of the piloting logic.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{RigidBody, Sensor, Velocity};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::{
+        sync::{
+            events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+            sync_component, IdentifiableComponent, SyncableComponent,
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    physics::location::LocationPhysicsSet,
+    prelude::{BlockCoordinate, Structure},
+};
+
+/// The unlocalized name of the chair block
+pub const CHAIR_BLOCK: &str = "cosmos:chair";
+/// The unlocalized name of the bed block
+pub const BED_BLOCK: &str = "cosmos:bed";
+/// The unlocalized name of the cockpit seat block, which must be used to pilot ships that have one
+pub const COCKPIT_SEAT_BLOCK: &str = "cosmos:cockpit_seat";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+/// What a player is doing on the seat they're on
+pub enum SeatKind {
+    /// Sitting upright
+    Chair,
+    /// Lying down
+    Bed,
+}
+
+impl SeatKind {
+    /// Gets the kind of seat this block is, if it is one.
+    ///
+    /// Cockpit seats are not included, since sitting in them pilots the ship instead.
+    pub fn from_block(unlocalized_name: &str) -> Option<Self> {
+        match unlocalized_name {
+            CHAIR_BLOCK => Some(Self::Chair),
+            BED_BLOCK => Some(Self::Bed),
+            _ => None,
+        }
+    }
+
+    /// Where the center of the player is relative to the seat block, before the block's rotation is applied
+    pub fn player_offset(&self) -> Vec3 {
+        match self {
+            Self::Chair => Vec3::new(0.0, 0.4, 0.0),
+            Self::Bed => Vec3::new(0.0, 0.2, 0.0),
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
+/// Put on a player that is sitting on a seat. While this is present, the player is a child of the structure and cannot walk around.
+pub struct Seated {
+    /// The structure the seat is on
+    pub structure: Entity,
+    /// Where the seat is on that structure
+    pub block: BlockCoordinate,
+    /// What kind of seat this is
+    pub kind: SeatKind,
+}
+
+impl IdentifiableComponent for Seated {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:seated"
+    }
+}
+
+impl SyncableComponent for Seated {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+
+    #[cfg(feature = "client")]
+    fn convert_entities_server_to_client(mut self, mapping: &crate::netty::sync::mapping::NetworkMapping) -> Option<Self> {
+        self.structure = mapping.client_from_server(&self.structure)?;
+        Some(self)
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client when the player wants to get up from their seat
+pub struct StandUpEvent;
+
+impl IdentifiableEvent for StandUpEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:stand_up"
+    }
+}
+
+impl NettyEvent for StandUpEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+fn on_sit_down(mut commands: Commands, mut q_seated: Query<(Entity, &Seated, Option<&Parent>, &mut Velocity), Added<Seated>>) {
+    for (ent, seated, parent, mut velocity) in q_seated.iter_mut() {
+        let mut ecmds = commands.entity(ent);
+
+        ecmds.insert((RigidBody::Fixed, Sensor));
+
+        if parent.map(|p| p.get()) != Some(seated.structure) {
+            ecmds.set_parent_in_place(seated.structure);
+        }
+
+        *velocity = Velocity::zero();
+    }
+}
+
+/// Keeps seated players on their seat, even as the structure moves around
+fn keep_players_on_seats(mut q_seated: Query<(&Seated, &mut Transform)>, q_structure: Query<&Structure>) {
+    for (seated, mut transform) in q_seated.iter_mut() {
+        let Ok(structure) = q_structure.get(seated.structure) else {
+            continue;
+        };
+
+        let rotation = structure.block_rotation(seated.block).as_quat();
+
+        transform.translation = structure.block_relative_position(seated.block) + rotation * seated.kind.player_offset();
+    }
+}
+
+fn on_stand_up(mut commands: Commands, mut removed: RemovedComponents<Seated>, mut q_transform: Query<&mut Transform>) {
+    for ent in removed.read() {
+        let Ok(mut transform) = q_transform.get_mut(ent) else {
+            continue;
+        };
+
+        // Moves the player above the seat so they don't get stuck in it
+        transform.translation.y += 1.0;
+
+        commands.entity(ent).insert(RigidBody::Dynamic).remove::<Sensor>();
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<Seated>(app);
+
+    app.add_netty_event::<StandUpEvent>()
+        .add_systems(
+            Update,
+            (on_sit_down, keep_players_on_seats, on_stand_up)
+                .chain()
+                .after(LocationPhysicsSet::DoPhysics)
+                .in_set(NetworkingSystemsSet::Between),
+        )
+        .register_type::<Seated>();
+}
@@ -0,0 +1,106 @@
+//! Shared logic for the sign block, which displays a short piece of text on its front face.
+
+use bevy::{
+    app::App,
+    prelude::{Component, Deref, Event},
+    reflect::Reflect,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::sync::{
+        events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+        sync_component, IdentifiableComponent, SyncableComponent,
+    },
+    structure::structure_block::StructureBlock,
+};
+
+/// The unlocalized name of the sign block
+pub const SIGN_BLOCK: &str = "cosmos:sign";
+
+/// The maximum number of characters a sign can display
+pub const MAX_SIGN_TEXT_LENGTH: usize = 64;
+
+#[derive(Component, Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, Reflect, Deref)]
+/// The text a sign block is displaying. This is stored as block data on the sign.
+pub struct SignText(String);
+
+impl SignText {
+    /// Creates new sign text.
+    ///
+    /// Any text beyond [`MAX_SIGN_TEXT_LENGTH`] characters is cut off, and control characters
+    /// (other than newlines) are removed.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self(sanitize_sign_text(&text.into()))
+    }
+
+    /// The text this sign is displaying
+    pub fn text(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Removes any characters a sign cannot display and limits it to [`MAX_SIGN_TEXT_LENGTH`] characters
+pub fn sanitize_sign_text(text: &str) -> String {
+    text.chars()
+        .filter(|c| *c == '\n' || !c.is_control())
+        .take(MAX_SIGN_TEXT_LENGTH)
+        .collect()
+}
+
+impl IdentifiableComponent for SignText {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:sign_text"
+    }
+}
+
+impl SyncableComponent for SignText {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the server to the client to instruct them to open the sign editor for this block.
+pub struct OpenSignEditorEvent(pub StructureBlock);
+
+impl IdentifiableEvent for OpenSignEditorEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:open_sign_editor"
+    }
+}
+
+impl NettyEvent for OpenSignEditorEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+/// Sent by the client to the server to change the text a sign displays.
+pub struct SetSignTextEvent {
+    /// The sign block being edited
+    pub block: StructureBlock,
+    /// The text the sign should display. This will be sanitized by the server.
+    pub text: String,
+}
+
+impl IdentifiableEvent for SetSignTextEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:set_sign_text"
+    }
+}
+
+impl NettyEvent for SetSignTextEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<SignText>(app);
+
+    app.register_type::<SignText>()
+        .add_netty_event::<OpenSignEditorEvent>()
+        .add_netty_event::<SetSignTextEvent>();
+}
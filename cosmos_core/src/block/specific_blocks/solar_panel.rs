@@ -0,0 +1,25 @@
+//! Solar panels generate energy from the light of the nearest star.
+//!
+//! A panel's top face collects the light, so it produces the most energy while facing its star directly
+//! with nothing in the way.
+
+use bevy::prelude::*;
+
+use crate::{block::Block, registry::Registry, structure::block_counts::TrackedBlockPositions};
+
+/// The unlocalized name of the solar panel block
+pub const SOLAR_PANEL_BLOCK: &str = "cosmos:solar_panel";
+
+/// How much energy per second a panel generates while facing a sun-like star directly
+pub const SOLAR_PANEL_MAX_OUTPUT: f32 = 20.0;
+
+/// Solar panels need to know where each panel is to figure out which way it faces and what is blocking it
+fn track_solar_panel_positions(blocks: Res<Registry<Block>>, mut tracked: ResMut<TrackedBlockPositions>) {
+    if let Some(solar_panel) = blocks.from_id(SOLAR_PANEL_BLOCK) {
+        tracked.track(solar_panel);
+    }
+}
+
+pub(super) fn register<T: States>(app: &mut App, post_loading_state: T) {
+    app.add_systems(OnEnter(post_loading_state), track_solar_panel_positions);
+}
@@ -0,0 +1,128 @@
+//! Shared logic for locking storage blocks.
+//!
+//! Storage can be locked with a keycard (anyone carrying that same keycard can open it) or with a code lock
+//! (anyone who knows the code can open it). Locks themselves are only known by the server, so codes are never sent to clients.
+
+use bevy::{
+    app::App,
+    prelude::{Component, Event, Reflect},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::sync::{
+        events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+        sync_component, IdentifiableComponent, SyncableComponent,
+    },
+    structure::structure_block::StructureBlock,
+};
+
+/// Keycards that can be used to lock storage. A keycard lock can only be opened by the keycard it was locked with.
+pub const KEYCARD_ITEMS: [&str; 3] = ["cosmos:keycard_red", "cosmos:keycard_green", "cosmos:keycard_blue"];
+
+/// The item used to put a code lock on storage
+pub const CODE_LOCK_ITEM: &str = "cosmos:code_lock";
+
+/// The item used to try and open locked storage without the key or code
+pub const LOCKPICK_ITEM: &str = "cosmos:lockpick";
+
+/// The maximum number of digits a lock code can have
+pub const MAX_LOCK_CODE_LENGTH: usize = 8;
+
+/// Returns true if this can be used as the code of a code lock
+pub fn is_valid_lock_code(code: &str) -> bool {
+    !code.is_empty() && code.len() <= MAX_LOCK_CODE_LENGTH && code.chars().all(|c| c.is_ascii_digit())
+}
+
+#[derive(Component, Debug, Reflect, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// The key a specific keycard opens locks with. This is stored on the keycard's itemstack data entity.
+///
+/// Every keycard is given its own random key, so two keycards of the same color don't open each other's locks.
+pub struct KeycardId(u64);
+
+impl KeycardId {
+    /// Creates a keycard id from its raw value
+    pub fn new(id: u64) -> Self {
+        Self(id)
+    }
+
+    /// The raw value of this id
+    pub fn id(&self) -> u64 {
+        self.0
+    }
+}
+
+impl IdentifiableComponent for KeycardId {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:keycard_id"
+    }
+}
+
+impl SyncableComponent for KeycardId {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// What the code the player is asked for will be used for
+pub enum LockCodePurpose {
+    /// Open storage that has a code lock
+    Unlock,
+    /// Put a new code lock on storage
+    SetCode,
+    /// Take the code lock off of storage
+    RemoveLock,
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the server to ask the player to enter the code for a code lock
+pub struct OpenLockCodePromptEvent {
+    /// The storage block the lock is on
+    pub block: StructureBlock,
+    /// What the entered code will be used for
+    pub purpose: LockCodePurpose,
+}
+
+impl IdentifiableEvent for OpenLockCodePromptEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:open_lock_code_prompt"
+    }
+}
+
+impl NettyEvent for OpenLockCodePromptEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+/// Sent by the client with the code they entered into a [`OpenLockCodePromptEvent`] prompt
+pub struct EnterLockCodeEvent {
+    /// The storage block the lock is on
+    pub block: StructureBlock,
+    /// The code the player entered
+    pub code: String,
+    /// What the entered code will be used for
+    pub purpose: LockCodePurpose,
+}
+
+impl IdentifiableEvent for EnterLockCodeEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:enter_lock_code"
+    }
+}
+
+impl NettyEvent for EnterLockCodeEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<KeycardId>(app);
+
+    app.register_type::<KeycardId>()
+        .add_netty_event::<OpenLockCodePromptEvent>()
+        .add_netty_event::<EnterLockCodeEvent>();
+}
@@ -0,0 +1,158 @@
+//! Logic behavior for "Timer", a block that outputs a pulse on every face but its front at a set interval.
+//!
+//! Every timer is based on the total number of logic ticks, so timers with the same interval pulse together.
+
+use bevy::{
+    app::{App, Update},
+    prelude::{Component, Event, EventReader, EventWriter, IntoSystemConfigs, OnEnter, Query, Res, ResMut, States},
+    reflect::Reflect,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::Block,
+    logic::{
+        default_logic_block_output, logic_driver::LogicDriver, BlockLogicData, LogicBlock, LogicConnection, LogicOutputEvent,
+        LogicSystemSet, PortType, QueueLogicInputEvent, LOGIC_TICKS_PER_SECOND,
+    },
+    netty::sync::{
+        events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+        sync_component, IdentifiableComponent, SyncType, SyncableComponent,
+    },
+    registry::{identifiable::Identifiable, Registry},
+    structure::{structure_block::StructureBlock, Structure},
+};
+
+/// The unlocalized name of the timer block
+pub const TIMER_BLOCK: &str = "cosmos:timer";
+
+/// The longest interval a timer can have (in logic ticks) - one hour
+pub const MAX_TIMER_INTERVAL: u32 = LOGIC_TICKS_PER_SECOND as u32 * 60 * 60;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// How often a timer block pulses. This is stored as block data on the timer.
+pub struct TimerSettings {
+    /// How many logic ticks there are between the start of each pulse
+    pub interval: u32,
+    /// How many logic ticks each pulse lasts. Must be less than the interval.
+    pub pulse_length: u32,
+}
+
+impl Default for TimerSettings {
+    fn default() -> Self {
+        Self {
+            interval: LOGIC_TICKS_PER_SECOND as u32,
+            pulse_length: 2,
+        }
+    }
+}
+
+impl TimerSettings {
+    /// Returns true if the timer can actually turn both on and off with these settings
+    pub fn is_valid(&self) -> bool {
+        self.pulse_length > 0 && self.pulse_length < self.interval && self.interval <= MAX_TIMER_INTERVAL
+    }
+
+    /// Returns true if the timer should be outputting a signal on this logic tick (see [`crate::logic::LogicTicks`])
+    pub fn on_at(&self, logic_tick: u64) -> bool {
+        self.interval != 0 && logic_tick % (self.interval as u64) < self.pulse_length as u64
+    }
+}
+
+impl IdentifiableComponent for TimerSettings {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:timer_settings"
+    }
+}
+
+impl SyncableComponent for TimerSettings {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+
+    fn validate(&self) -> bool {
+        self.is_valid()
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the server to the client to instruct them to open the settings of this timer block.
+pub struct OpenTimerSettingsEvent(pub StructureBlock);
+
+impl IdentifiableEvent for OpenTimerSettingsEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:open_timer_settings"
+    }
+}
+
+impl NettyEvent for OpenTimerSettingsEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to the server to change how often a timer pulses.
+pub struct SetTimerSettingsEvent {
+    /// The timer block
+    pub block: StructureBlock,
+    /// The new settings
+    pub settings: TimerSettings,
+}
+
+impl IdentifiableEvent for SetTimerSettingsEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:set_timer_settings"
+    }
+}
+
+impl NettyEvent for SetTimerSettingsEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+fn register_logic_connections(blocks: Res<Registry<Block>>, mut registry: ResMut<Registry<LogicBlock>>) {
+    if let Some(timer) = blocks.from_id(TIMER_BLOCK) {
+        let output = Some(LogicConnection::Port(PortType::Output));
+        // The front is the face with the display.
+        registry.register(LogicBlock::new(timer, [output, output, output, output, None, output]));
+    }
+}
+
+fn timer_output_event_listener(
+    evr_logic_output: EventReader<LogicOutputEvent>,
+    evw_queue_logic_input: EventWriter<QueueLogicInputEvent>,
+    logic_blocks: Res<Registry<LogicBlock>>,
+    blocks: Res<Registry<Block>>,
+    q_logic_driver: Query<&mut LogicDriver>,
+    q_structure: Query<&mut Structure>,
+    q_logic_data: Query<&BlockLogicData>,
+) {
+    default_logic_block_output(
+        TIMER_BLOCK,
+        evr_logic_output,
+        evw_queue_logic_input,
+        &logic_blocks,
+        &blocks,
+        q_logic_driver,
+        q_structure,
+        q_logic_data,
+    );
+}
+
+pub(super) fn register<T: States>(app: &mut App, post_loading_state: T) {
+    sync_component::<TimerSettings>(app);
+
+    app.register_type::<TimerSettings>()
+        .add_netty_event::<OpenTimerSettingsEvent>()
+        .add_netty_event::<SetTimerSettingsEvent>();
+
+    app.add_systems(OnEnter(post_loading_state), register_logic_connections)
+        .add_systems(
+            Update,
+            timer_output_event_listener
+                .in_set(LogicSystemSet::Produce)
+                .ambiguous_with(LogicSystemSet::Produce),
+        );
+}
@@ -0,0 +1,135 @@
+//! Warp gates are multiblock rings built on stations that send ships flying through them to another warp gate.
+//!
+//! A gate is made of [`WARP_GATE_FRAME`] blocks around a 3x3 opening, with a [`WARP_GATE_CONTROLLER`] in the middle of
+//! its bottom edge. Once a gate has a [`WarpGateDestination`], it charges up while a ship is nearby and sends the next
+//! ship that flies through its opening to the destination, using energy from the gate's station.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::{block_face::BlockFace, block_rotation::BlockRotation, multiblock::machine::MultiblockPattern, Block},
+    netty::sync::{
+        events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+        sync_component, IdentifiableComponent, SyncType, SyncableComponent,
+    },
+    physics::location::Location,
+    registry::Registry,
+    structure::structure_block::StructureBlock,
+};
+
+/// The unlocalized name of the block that controls a warp gate
+pub const WARP_GATE_CONTROLLER: &str = "cosmos:warp_gate_controller";
+/// The unlocalized name of the blocks that make up the ring of a warp gate
+pub const WARP_GATE_FRAME: &str = "cosmos:warp_gate_frame";
+/// The unlocalized name of the warp gate's [`MultiblockPattern`]
+pub const WARP_GATE_PATTERN: &str = "cosmos:warp_gate";
+
+/// How long (in seconds) a gate has to charge before it can send a ship
+pub const WARP_GATE_CHARGE_TIME: f32 = 5.0;
+/// How long (in seconds) a gate has to wait after sending a ship before it can charge again
+pub const WARP_GATE_COOLDOWN: f32 = 30.0;
+/// How close (in meters) a ship has to be to the center of a gate for it to start charging
+pub const WARP_GATE_CHARGE_RANGE: f32 = 50.0;
+/// How much energy it takes to send every kilogram of a ship through a gate
+pub const WARP_GATE_ENERGY_PER_KG: f32 = 50.0;
+
+/// How far (in blocks) the center of a gate's opening is from its controller, in the direction of the controller's top face
+const GATE_CENTER_DISTANCE: f32 = 2.0;
+/// Half the width of a gate's opening
+pub const WARP_GATE_OPENING_HALF_WIDTH: f32 = 1.5;
+
+/// Returns where the center of a gate's opening is relative to its controller, in the structure's space
+pub fn gate_center_offset(controller_rotation: BlockRotation) -> Vec3 {
+    controller_rotation.direction_of(BlockFace::Top).as_vec3() * GATE_CENTER_DISTANCE
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
+/// Where a warp gate sends ships. This is stored as block data on the gate's controller.
+///
+/// Destinations are always gates on stations, which never move, so this stores where the gate is rather than the
+/// gate itself. This lets gates send ships to gates in sectors that aren't loaded.
+pub struct WarpGateDestination {
+    /// The center of the destination gate's opening
+    pub location: Location,
+    /// The rotation of the destination gate's controller in the world. Ships leave through the gate's front.
+    pub rotation: Quat,
+}
+
+impl IdentifiableComponent for WarpGateDestination {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:warp_gate_destination"
+    }
+}
+
+impl SyncableComponent for WarpGateDestination {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the server to every client whenever a gate starts or stops charging
+pub struct WarpGateChargeEvent {
+    /// The controller of the gate
+    pub gate: StructureBlock,
+    /// If the gate started charging (true) or stopped before it could send a ship (false)
+    pub charging: bool,
+}
+
+impl IdentifiableEvent for WarpGateChargeEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:warp_gate_charge"
+    }
+}
+
+impl NettyEvent for WarpGateChargeEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the server to every client whenever a ship is sent through a gate
+pub struct WarpGateJumpEvent {
+    /// The ship that was sent
+    pub ship: Entity,
+    /// The controller of the gate the ship went through
+    pub gate: StructureBlock,
+    /// Where the ship left through
+    pub destination: WarpGateDestination,
+}
+
+impl IdentifiableEvent for WarpGateJumpEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:warp_gate_jump"
+    }
+}
+
+impl NettyEvent for WarpGateJumpEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+fn register_warp_gate_pattern(blocks: Res<Registry<Block>>, mut patterns: ResMut<Registry<MultiblockPattern>>) {
+    // A 5x5 ring standing up from the controller, with the controller's front facing out of the ring
+    if let Some(pattern) = MultiblockPattern::new(
+        WARP_GATE_PATTERN,
+        &[&["##C##"], &["#...#"], &["#...#"], &["#...#"], &["#####"]],
+        &[('#', WARP_GATE_FRAME), ('.', "cosmos:air"), ('C', WARP_GATE_CONTROLLER)],
+        WARP_GATE_CONTROLLER,
+        &blocks,
+    ) {
+        patterns.register(pattern);
+    }
+}
+
+pub(super) fn register<T: States>(app: &mut App, post_loading_state: T) {
+    sync_component::<WarpGateDestination>(app);
+
+    app.add_systems(OnEnter(post_loading_state), register_warp_gate_pattern)
+        .register_type::<WarpGateDestination>()
+        .add_netty_event::<WarpGateChargeEvent>()
+        .add_netty_event::<WarpGateJumpEvent>();
+}
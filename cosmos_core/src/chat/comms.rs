@@ -0,0 +1,36 @@
+//! Hailing lets pilots send short messages to the players aboard another ship or station.
+
+use bevy::prelude::{App, Entity, Event};
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl};
+
+/// The longest message that can be sent in a hail
+pub const MAX_HAIL_LENGTH: usize = 200;
+
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+/// Sent by a pilot to hail another structure.
+///
+/// The message is shown in the chat of every player aboard the target.
+pub struct SendHailEvent {
+    /// The server's entity of the structure being hailed
+    pub target: Entity,
+    /// The message to send
+    pub message: String,
+}
+
+impl IdentifiableEvent for SendHailEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:send_hail"
+    }
+}
+
+impl NettyEvent for SendHailEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_netty_event::<SendHailEvent>();
+}
@@ -4,11 +4,15 @@ use crate::netty::sync::events::netty_event::{IdentifiableEvent, NettyEvent, Syn
 use bevy::prelude::{App, Entity, Event};
 use serde::{Deserialize, Serialize};
 
+pub mod comms;
+
 #[derive(Event, Debug, Serialize, Deserialize)]
-/// Sent from client to server to send a chat message to everyone
+/// Sent from client to server to send a chat message
 pub enum ClientSendChatMessageEvent {
     /// This message should be sent to everyone
     Global(String),
+    /// This message should only be sent to the members of the sender's party
+    Party(String),
 }
 
 impl IdentifiableEvent for ClientSendChatMessageEvent {
@@ -47,4 +51,6 @@ impl NettyEvent for ServerSendChatMessageEvent {
 pub(super) fn register(app: &mut App) {
     app.add_netty_event::<ClientSendChatMessageEvent>();
     app.add_netty_event::<ServerSendChatMessageEvent>();
+
+    comms::register(app);
 }
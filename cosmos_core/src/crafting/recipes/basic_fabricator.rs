@@ -62,6 +62,26 @@ impl BasicFabricatorRecipe {
         Self { output, inputs }
     }
 
+    /// Converts the ids of every item in this recipe, or returns None if `remap` returns None for any of them.
+    ///
+    /// This is only meant for converting between the server's & client's ids for the same items.
+    pub fn remap_item_ids(&self, remap: impl Fn(u16) -> Option<u16>) -> Option<Self> {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|input| match input.item {
+                RecipeItem::Item(id) => remap(id).map(|id| FabricatorItemInput::new(RecipeItem::Item(id), input.quantity)),
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let output = FabricatorItemOutput {
+            item: remap(self.output.item)?,
+            quantity: self.output.quantity,
+        };
+
+        Some(Self { inputs, output })
+    }
+
     /// Computes the maximum amount of items this recipe can prodce, with the given items.
     ///
     /// The `items` iterator can contain items unrelated to the recipe.
@@ -115,6 +135,13 @@ impl BasicFabricatorRecipes {
     pub fn iter(&self) -> impl Iterator<Item = &'_ BasicFabricatorRecipe> {
         self.0.iter()
     }
+
+    /// Converts the ids of every item in these recipes. Recipes using any item `remap` returns None for are removed.
+    ///
+    /// This is only meant for converting between the server's & client's ids for the same items.
+    pub fn remap_item_ids(&mut self, remap: impl Fn(u16) -> Option<u16>) {
+        self.0 = self.0.iter().flat_map(|recipe| recipe.remap_item_ids(&remap)).collect();
+    }
 }
 
 #[derive(Event, Serialize, Deserialize, Debug)]
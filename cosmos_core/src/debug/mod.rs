@@ -10,6 +10,8 @@ use renet2::{RenetClient, RenetServer};
 
 use crate::structure::Structure;
 
+pub mod profiling;
+
 /// FIXME: bevy should not have any ambiguities, but it takes time to clean these up,
 /// so we're juste ignoring those for now.
 ///
@@ -53,6 +55,8 @@ fn configure_ambiguity_detection(sub_app: &mut SubApp) {
 // }
 
 pub(super) fn register(app: &mut App) {
+    profiling::register(app);
+
     let sub_app = app.main_mut();
     configure_ambiguity_detection(sub_app);
     let sub_app = app.sub_app_mut(RenderApp);
@@ -0,0 +1,139 @@
+//! Measures how long different parts of each frame (tick on the server) take.
+//!
+//! Use [`profile_system_set`] to time a system set. The results are stored in the [`SystemSetTimings`] resource.
+
+use std::time::{Duration, Instant};
+
+use bevy::{
+    app::{App, First, Last},
+    ecs::schedule::ScheduleLabel,
+    prelude::{Event, IntoSystemConfigs, ResMut, Resource, SystemSet},
+    utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl};
+
+/// The name of the timing that represents the entire frame (tick on the server), excluding rendering.
+pub const FRAME_TIMING: &str = "Frame";
+
+/// How much a new sample affects the average. Smaller values smooth out spikes more.
+const SMOOTHING: f32 = 0.1;
+
+#[derive(Resource, Debug, Default)]
+/// The average time (smoothed over several frames) each profiled system set takes to run
+pub struct SystemSetTimings {
+    started: HashMap<&'static str, Instant>,
+    averages: HashMap<&'static str, Duration>,
+}
+
+impl SystemSetTimings {
+    fn start(&mut self, name: &'static str) {
+        self.started.insert(name, Instant::now());
+    }
+
+    fn end(&mut self, name: &'static str) {
+        let Some(started) = self.started.remove(name) else {
+            return;
+        };
+
+        let elapsed = started.elapsed();
+
+        let average = self
+            .averages
+            .get(name)
+            .map(|avg| avg.mul_f32(1.0 - SMOOTHING) + elapsed.mul_f32(SMOOTHING))
+            .unwrap_or(elapsed);
+
+        self.averages.insert(name, average);
+    }
+
+    /// Gets the average time the timing with this name takes.
+    pub fn get(&self, name: &str) -> Option<Duration> {
+        self.averages.get(name).copied()
+    }
+
+    /// Every timing (except for [`FRAME_TIMING`]), sorted from longest to shortest
+    pub fn sorted(&self) -> Vec<(&'static str, Duration)> {
+        let mut timings = self
+            .averages
+            .iter()
+            .filter(|(name, _)| **name != FRAME_TIMING)
+            .map(|(name, duration)| (*name, *duration))
+            .collect::<Vec<_>>();
+
+        timings.sort_by_key(|(_, duration)| std::cmp::Reverse(*duration));
+
+        timings
+    }
+}
+
+/// Records how long this system set takes to run in the [`SystemSetTimings`] resource, under this name.
+///
+/// Any systems that run in parallel with this set will also affect its time, so treat these as estimates.
+pub fn profile_system_set(app: &mut App, schedule: impl ScheduleLabel + Clone, name: &'static str, set: impl SystemSet + Clone) {
+    app.add_systems(
+        schedule.clone(),
+        (move |mut timings: ResMut<SystemSetTimings>| timings.start(name))
+            .before(set.clone())
+            .ambiguous_with_all(),
+    )
+    .add_systems(
+        schedule,
+        (move |mut timings: ResMut<SystemSetTimings>| timings.end(name))
+            .after(set)
+            .ambiguous_with_all(),
+    );
+}
+
+#[derive(Event, Debug, Clone, Serialize, Deserialize, Default)]
+/// Sent by the server to clients that have requested it with [`RequestServerDiagnosticsEvent`]
+pub struct ServerDiagnosticsEvent {
+    /// The average duration of a server tick
+    pub tick_time: Duration,
+    /// The average duration of every profiled system set on the server, sorted from longest to shortest
+    pub set_timings: Vec<(String, Duration)>,
+    /// The number of entities on the server
+    pub entity_count: u32,
+    /// The number of chunks loaded on the server
+    pub chunk_count: u32,
+}
+
+impl IdentifiableEvent for ServerDiagnosticsEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:server_diagnostics"
+    }
+}
+
+impl NettyEvent for ServerDiagnosticsEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to start (or stop) receiving [`ServerDiagnosticsEvent`]s
+pub struct RequestServerDiagnosticsEvent {
+    /// If the client wants to receive diagnostics
+    pub enabled: bool,
+}
+
+impl IdentifiableEvent for RequestServerDiagnosticsEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:request_server_diagnostics"
+    }
+}
+
+impl NettyEvent for RequestServerDiagnosticsEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<SystemSetTimings>()
+        .add_systems(First, |mut timings: ResMut<SystemSetTimings>| timings.start(FRAME_TIMING))
+        .add_systems(Last, |mut timings: ResMut<SystemSetTimings>| timings.end(FRAME_TIMING))
+        .add_netty_event::<ServerDiagnosticsEvent>()
+        .add_netty_event::<RequestServerDiagnosticsEvent>();
+}
@@ -0,0 +1,40 @@
+//! Bounties are placed on players who destroy ships in policed space, and can be claimed by destroying their ships.
+//!
+//! Stations show the wanted list to anyone aboard them.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A player with a bounty on them
+pub struct WantedPlayer {
+    /// The wanted player's name
+    pub name: String,
+    /// How many credits are awarded for destroying one of their ships
+    pub bounty: u64,
+}
+
+#[derive(Event, Debug, Clone, Default, Serialize, Deserialize)]
+/// Sent by the server with every wanted player whenever a player joins or the bounties change
+pub struct WantedListEvent {
+    /// Every player with a bounty on them, highest bounty first
+    pub wanted: Vec<WantedPlayer>,
+}
+
+impl IdentifiableEvent for WantedListEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:wanted_list"
+    }
+}
+
+impl NettyEvent for WantedListEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_netty_event::<WantedListEvent>();
+}
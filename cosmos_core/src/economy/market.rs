@@ -0,0 +1,192 @@
+//! Market terminals let players post buy & sell orders that every player on the server can see.
+//!
+//! Orders are matched by the server. Whatever a player is owed from their orders is handed over the next time they use
+//! any market terminal - credits go to the player and items go into the cargo of that terminal's station.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::sync::events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+    structure::structure_block::StructureBlock,
+};
+
+/// The unlocalized name of the market terminal block
+pub const MARKET_TERMINAL_BLOCK: &str = "cosmos:market_terminal";
+
+/// The most items a single order can be for
+pub const MAX_MARKET_ORDER_QUANTITY: u32 = 100_000;
+
+/// The most orders a single player can have open at once
+pub const MAX_OPEN_MARKET_ORDERS: usize = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// If an order is offering to buy or sell items
+pub enum MarketOrderKind {
+    /// The owner wants to buy items, and has paid for them up front
+    Buy,
+    /// The owner wants to sell items, and has handed them over up front
+    Sell,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// An offer to buy or sell items that hasn't been completely filled yet
+pub struct MarketOrder {
+    /// Unique across every order ever posted
+    pub id: u64,
+    /// The name of the player who posted this order
+    pub owner: String,
+    /// If this is buying or selling
+    pub kind: MarketOrderKind,
+    /// The unlocalized name of the item being traded
+    pub item: String,
+    /// How many items are left to be bought or sold
+    pub quantity: u32,
+    /// How many credits each item is bought or sold for
+    pub price_per_item: u64,
+}
+
+impl MarketOrder {
+    /// Returns true if an order of this kind & price for the same item would trade with this one.
+    ///
+    /// Sell orders trade with buy orders that pay at least as much, and buy orders trade with sell orders that ask
+    /// for at most as much.
+    pub fn matches(&self, kind: MarketOrderKind, price_per_item: u64) -> bool {
+        match (self.kind, kind) {
+            (MarketOrderKind::Sell, MarketOrderKind::Buy) => self.price_per_item <= price_per_item,
+            (MarketOrderKind::Buy, MarketOrderKind::Sell) => self.price_per_item >= price_per_item,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the server to open the market menu of a terminal
+pub struct OpenMarketEvent {
+    /// The market terminal block
+    pub terminal: StructureBlock,
+}
+
+impl IdentifiableEvent for OpenMarketEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:open_market"
+    }
+}
+
+impl NettyEvent for OpenMarketEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Default, Serialize, Deserialize)]
+/// Sent by the server with every open order whenever a player opens the market
+pub struct MarketOrdersEvent {
+    /// Every order that hasn't been filled or cancelled
+    pub orders: Vec<MarketOrder>,
+}
+
+impl IdentifiableEvent for MarketOrdersEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:market_orders"
+    }
+}
+
+impl NettyEvent for MarketOrdersEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Default, Serialize, Deserialize)]
+/// Sent by the server to every player whenever orders are posted, filled, or cancelled.
+///
+/// Apply this to the orders of the last [`MarketOrdersEvent`] to get the current orders.
+pub struct MarketOrdersChangedEvent {
+    /// Orders that were posted or partially filled, which replace any existing order with the same [`MarketOrder::id`]
+    pub changed: Vec<MarketOrder>,
+    /// The [`MarketOrder::id`]s of orders that were completely filled or cancelled
+    pub removed: Vec<u64>,
+}
+
+impl MarketOrdersChangedEvent {
+    /// Applies these changes to a list of orders
+    pub fn apply(&self, orders: &mut Vec<MarketOrder>) {
+        orders.retain(|o| !self.removed.contains(&o.id));
+
+        for order in self.changed.iter() {
+            match orders.iter_mut().find(|o| o.id == order.id) {
+                Some(existing) => *existing = order.clone(),
+                None => orders.push(order.clone()),
+            }
+        }
+    }
+}
+
+impl IdentifiableEvent for MarketOrdersChangedEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:market_orders_changed"
+    }
+}
+
+impl NettyEvent for MarketOrdersChangedEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to post a new order from a market terminal
+pub struct PostMarketOrderEvent {
+    /// The market terminal being used
+    pub terminal: StructureBlock,
+    /// If this is buying or selling
+    pub kind: MarketOrderKind,
+    /// The numeric id of the item being traded
+    pub item_id: u16,
+    /// How many items to buy or sell
+    pub quantity: u32,
+    /// How many credits each item is bought or sold for
+    pub price_per_item: u64,
+}
+
+impl IdentifiableEvent for PostMarketOrderEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:post_market_order"
+    }
+}
+
+impl NettyEvent for PostMarketOrderEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to cancel one of their orders from a market terminal
+pub struct CancelMarketOrderEvent {
+    /// The market terminal being used
+    pub terminal: StructureBlock,
+    /// The [`MarketOrder::id`] of the order to cancel
+    pub order_id: u64,
+}
+
+impl IdentifiableEvent for CancelMarketOrderEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:cancel_market_order"
+    }
+}
+
+impl NettyEvent for CancelMarketOrderEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_netty_event::<OpenMarketEvent>()
+        .add_netty_event::<MarketOrdersEvent>()
+        .add_netty_event::<MarketOrdersChangedEvent>()
+        .add_netty_event::<PostMarketOrderEvent>()
+        .add_netty_event::<CancelMarketOrderEvent>();
+}
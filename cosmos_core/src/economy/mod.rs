@@ -7,6 +7,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::netty::sync::{sync_component, IdentifiableComponent, SyncableComponent};
 
+pub mod bounty;
+pub mod market;
+
 #[derive(Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, Reflect, Default)]
 /// Represents a quantity of money. If attached to an entity, this is how much money that entity has
 pub struct Credits(u64);
@@ -71,4 +74,7 @@ pub(super) fn register(app: &mut App) {
     sync_component::<Credits>(app);
 
     app.register_type::<Credits>();
+
+    bounty::register(app);
+    market::register(app);
 }
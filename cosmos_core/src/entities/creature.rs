@@ -0,0 +1,154 @@
+//! Creatures are simple mobs that roam the surfaces of planets.
+//!
+//! Every kind of creature is described by a [`CreatureType`], which the server loads and syncs to clients so they
+//! know how to draw them.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, LockedAxes, ReadMassProperties, RigidBody};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::sync::{registry::sync_registry, sync_component, IdentifiableComponent, SyncableComponent},
+    registry::{create_registry, identifiable::Identifiable, Registry},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// How a creature reacts to players
+pub enum CreatureBehavior {
+    /// Wanders around, and only runs away once it's been hurt
+    Passive,
+    /// Runs away from any player that gets too close
+    Skittish,
+    /// Chases down & attacks any player that gets too close
+    Hostile,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// An item a creature may drop when it dies
+pub struct CreatureDrop {
+    /// The unlocalized name of the item dropped
+    pub item: String,
+    /// The fewest of this item that will be dropped
+    pub min: u16,
+    /// The most of this item that will be dropped
+    pub max: u16,
+    /// The chance (0.0 to 1.0) this item is dropped at all
+    pub chance: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Everything that makes one kind of creature different from another
+pub struct CreatureProperties {
+    /// How this creature reacts to players
+    pub behavior: CreatureBehavior,
+    /// How much damage this creature can take before dying
+    pub max_health: f32,
+    /// How fast this creature moves, in blocks per second
+    pub speed: f32,
+    /// How much damage each of this creature's attacks deals
+    pub attack_damage: f32,
+    /// Half the width, height & length of this creature's body
+    pub half_size: Vec3,
+    /// The color of this creature's body, as RGB from 0.0 to 1.0
+    pub color: [f32; 3],
+    /// The unlocalized names of the biospheres this creature can spawn on
+    pub biospheres: Vec<String>,
+    /// The unlocalized names of the blocks this creature can spawn on top of
+    pub spawn_blocks: Vec<String>,
+    /// How likely this creature is to be picked to spawn compared to other creatures that can spawn in the same spot
+    pub spawn_weight: u32,
+    /// The most of this creature that will spawn together at once
+    pub max_group_size: u32,
+    /// The items this creature may drop when it dies
+    pub drops: Vec<CreatureDrop>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A kind of creature that can spawn on planets
+pub struct CreatureType {
+    id: u16,
+    unlocalized_name: String,
+    properties: CreatureProperties,
+}
+
+impl CreatureType {
+    /// Creates a new creature type with these properties
+    pub fn new(unlocalized_name: impl Into<String>, properties: CreatureProperties) -> Self {
+        Self {
+            id: 0,
+            unlocalized_name: unlocalized_name.into(),
+            properties,
+        }
+    }
+
+    /// Everything that makes this creature different from other creatures
+    pub fn properties(&self) -> &CreatureProperties {
+        &self.properties
+    }
+}
+
+impl Identifiable for CreatureType {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// A creature roaming around a planet
+pub struct Creature {
+    /// The numeric id of this creature's [`CreatureType`]
+    pub creature_type: u16,
+}
+
+impl IdentifiableComponent for Creature {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:creature"
+    }
+}
+
+impl SyncableComponent for Creature {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+}
+
+fn on_add_creature(
+    mut commands: Commands,
+    q_added_creature: Query<(Entity, &Creature), Added<Creature>>,
+    creature_types: Res<Registry<CreatureType>>,
+) {
+    for (ent, creature) in q_added_creature.iter() {
+        let Some(creature_type) = creature_types.try_from_numeric_id(creature.creature_type) else {
+            error!("Creature {ent:?} has an unknown creature type ({}).", creature.creature_type);
+            continue;
+        };
+
+        let half_size = creature_type.properties().half_size;
+
+        commands.entity(ent).insert((
+            Name::new(format!("Creature ({})", creature_type.unlocalized_name())),
+            RigidBody::Dynamic,
+            Collider::cuboid(half_size.x, half_size.y, half_size.z),
+            // Creatures are kept upright by their AI, not by physics
+            LockedAxes::ROTATION_LOCKED,
+            ReadMassProperties::default(),
+        ));
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    create_registry::<CreatureType>(app, "cosmos:creature_types");
+    sync_registry::<CreatureType>(app);
+
+    sync_component::<Creature>(app);
+
+    app.add_systems(Update, on_add_creature).register_type::<Creature>();
+}
@@ -0,0 +1,53 @@
+//! Drones are small flying robots launched from drone bays that build & repair their structure.
+//!
+//! Drones are always children of the structure they work on, and are entirely controlled by the server.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::sync::{sync_component, IdentifiableComponent, SyncType, SyncableComponent},
+    structure::coordinates::BlockCoordinate,
+};
+
+/// The radius of a drone's body
+pub const DRONE_RADIUS: f32 = 0.25;
+
+/// How fast drones fly, in blocks per second
+pub const DRONE_SPEED: f32 = 5.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// What a drone is currently doing
+pub enum DroneActivity {
+    /// Flying to a job, or back to its bay
+    Flying,
+    /// Welding or repairing the block at these coordinates
+    Working(BlockCoordinate),
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// A drone working on the structure it is a child of
+pub struct Drone {
+    /// The drone bay this drone was launched from
+    pub bay: BlockCoordinate,
+    /// What this drone is currently doing
+    pub activity: DroneActivity,
+}
+
+impl IdentifiableComponent for Drone {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:drone"
+    }
+}
+
+impl SyncableComponent for Drone {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<Drone>(app);
+
+    app.register_type::<Drone>();
+}
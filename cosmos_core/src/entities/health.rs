@@ -0,0 +1,69 @@
+//! Health for entities that can be hurt & killed, such as creatures
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::{sync_component, IdentifiableComponent, SyncableComponent};
+
+#[derive(Component, Clone, Copy, PartialEq, Debug, Serialize, Deserialize, Reflect)]
+/// How much damage an entity can take before it dies
+pub struct Health {
+    current: f32,
+    max: f32,
+}
+
+impl Health {
+    /// Creates a new health that starts full
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    /// The amount of health left
+    pub fn current(&self) -> f32 {
+        self.current
+    }
+
+    /// The most health this can have
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+
+    /// Reduces the health by this amount, without going below 0.
+    ///
+    /// Returns true if this damage killed it.
+    pub fn take_damage(&mut self, amount: f32) -> bool {
+        let was_alive = self.is_alive();
+
+        self.current = (self.current - amount).max(0.0);
+
+        was_alive && !self.is_alive()
+    }
+
+    /// Increases the health by this amount, without going above the max
+    pub fn heal(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+
+    /// If there is any health left
+    pub fn is_alive(&self) -> bool {
+        self.current > 0.0
+    }
+}
+
+impl IdentifiableComponent for Health {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:health"
+    }
+}
+
+impl SyncableComponent for Health {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<Health>(app);
+
+    app.register_type::<Health>();
+}
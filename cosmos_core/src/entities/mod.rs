@@ -4,8 +4,18 @@
 
 use bevy::prelude::App;
 
+pub mod creature;
+pub mod drone;
+pub mod health;
+pub mod npc;
 pub mod player;
+pub mod status_effects;
 
 pub(super) fn register(app: &mut App) {
+    creature::register(app);
+    drone::register(app);
+    health::register(app);
+    npc::register(app);
     player::register(app);
+    status_effects::register(app);
 }
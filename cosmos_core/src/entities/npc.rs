@@ -0,0 +1,130 @@
+//! NPCs are characters that stand around stations and can be talked to.
+//!
+//! What an NPC says is decided by the server, which walks the player through the NPC's dialogue tree one line at a
+//! time. Clients only ever see the line they're currently on.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, RigidBody};
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::{
+    events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+    sync_component, IdentifiableComponent, SyncableComponent,
+};
+
+/// Half the height of the cylinder part of an NPC's body
+pub const NPC_HALF_HEIGHT: f32 = 0.5;
+/// The radius of an NPC's body
+pub const NPC_RADIUS: f32 = 0.3;
+
+#[derive(Component, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// A character players can talk to
+pub struct Npc {
+    /// The name shown to players talking to this NPC
+    pub name: String,
+    /// The unlocalized name of the dialogue tree used when talking to this NPC
+    pub dialogue: String,
+}
+
+impl IdentifiableComponent for Npc {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:npc"
+    }
+}
+
+impl SyncableComponent for Npc {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// One thing an NPC says, and what the player can say back
+pub struct DialogueLine {
+    /// What the NPC says
+    pub text: String,
+    /// The responses the player can choose from. If this is empty, the player can only leave.
+    pub options: Vec<String>,
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client when the player interacts with an NPC
+pub struct InteractNpcEvent {
+    /// The NPC being talked to
+    pub npc: Entity,
+}
+
+impl IdentifiableEvent for InteractNpcEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:interact_npc"
+    }
+}
+
+impl NettyEvent for InteractNpcEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+/// Sent by the server to show the player what an NPC says
+pub struct NpcDialogueEvent {
+    /// The NPC being talked to
+    pub npc: Entity,
+    /// The line to show, or `None` if the conversation is over
+    pub line: Option<DialogueLine>,
+}
+
+impl IdentifiableEvent for NpcDialogueEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:npc_dialogue"
+    }
+}
+
+impl NettyEvent for NpcDialogueEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client when the player picks one of the options of the line they're on
+pub struct ChooseDialogueOptionEvent {
+    /// The NPC being talked to
+    pub npc: Entity,
+    /// The index of the chosen option in [`DialogueLine::options`]
+    pub option: usize,
+}
+
+impl IdentifiableEvent for ChooseDialogueOptionEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:choose_dialogue_option"
+    }
+}
+
+impl NettyEvent for ChooseDialogueOptionEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+fn on_add_npc(mut commands: Commands, q_added_npc: Query<(Entity, &Npc), Added<Npc>>) {
+    for (ent, npc) in q_added_npc.iter() {
+        commands.entity(ent).insert((
+            Name::new(format!("NPC ({})", npc.name)),
+            // NPCs only ever stand on stations, which never move
+            RigidBody::Fixed,
+            Collider::capsule_y(NPC_HALF_HEIGHT, NPC_RADIUS),
+        ));
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<Npc>(app);
+
+    app.add_systems(Update, on_add_npc)
+        .register_type::<Npc>()
+        .add_netty_event::<InteractNpcEvent>()
+        .add_netty_event::<NpcDialogueEvent>()
+        .add_netty_event::<ChooseDialogueOptionEvent>();
+}
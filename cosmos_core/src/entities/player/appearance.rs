@@ -0,0 +1,122 @@
+//! How a player looks - their suit's colors, pattern and helmet.
+//!
+//! Players pick their appearance in the character screen, which sends a [`SetAppearanceEvent`] to the server. The server
+//! then updates that player's [`PlayerAppearance`], which is synced to everyone so they all see the same thing.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::{
+    events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+    sync_component, IdentifiableComponent, SyncableComponent,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+/// The pattern the secondary color of a suit is drawn in
+pub enum SuitPattern {
+    #[default]
+    /// The suit is entirely the primary color
+    Solid,
+    /// A band of the secondary color around the chest
+    Striped,
+    /// The legs are the secondary color
+    TwoTone,
+}
+
+impl SuitPattern {
+    /// Every pattern, in the order the character screen cycles through them
+    pub const ALL: [SuitPattern; 3] = [Self::Solid, Self::Striped, Self::TwoTone];
+
+    /// The pattern that comes after this one, wrapping back around to the first
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|x| *x == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+/// The style of helmet a player wears
+pub enum HelmetStyle {
+    #[default]
+    /// A helmet in the suit's primary color with a dark visor
+    Standard,
+    /// A helmet with a wide, reflective visor in the secondary color
+    Visor,
+    /// A clear glass dome
+    Dome,
+}
+
+impl HelmetStyle {
+    /// Every helmet style, in the order the character screen cycles through them
+    pub const ALL: [HelmetStyle; 3] = [Self::Standard, Self::Visor, Self::Dome];
+
+    /// The helmet style that comes after this one, wrapping back around to the first
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|x| *x == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// How a player looks. Colors are stored as srgb bytes.
+pub struct PlayerAppearance {
+    /// The main color of the suit
+    pub primary_color: [u8; 3],
+    /// The color used by the suit's pattern & helmet details
+    pub secondary_color: [u8; 3],
+    /// How the secondary color is laid out on the suit
+    pub pattern: SuitPattern,
+    /// The helmet worn
+    pub helmet: HelmetStyle,
+}
+
+impl Default for PlayerAppearance {
+    fn default() -> Self {
+        Self {
+            primary_color: [0, 255, 0],
+            secondary_color: [255, 255, 255],
+            pattern: SuitPattern::Solid,
+            helmet: HelmetStyle::Standard,
+        }
+    }
+}
+
+impl IdentifiableComponent for PlayerAppearance {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:player_appearance"
+    }
+}
+
+impl SyncableComponent for PlayerAppearance {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client when the player saves their appearance in the character screen
+pub struct SetAppearanceEvent {
+    /// How the player wants to look
+    pub appearance: PlayerAppearance,
+}
+
+impl IdentifiableEvent for SetAppearanceEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:set_appearance"
+    }
+}
+
+impl NettyEvent for SetAppearanceEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<PlayerAppearance>(app);
+
+    app.add_netty_event::<SetAppearanceEvent>()
+        .register_type::<SuitPattern>()
+        .register_type::<HelmetStyle>()
+        .register_type::<PlayerAppearance>();
+}
@@ -0,0 +1,35 @@
+//! Players are hurt by hazards such as fires (see [`crate::structure::hazards`]) and hostile creatures.
+//! Once a player's [`crate::entities::health::Health`] runs out, they respawn at full health.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::sync::events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+    physics::location::Location,
+};
+
+/// How much health a player has when they are unharmed
+pub const PLAYER_MAX_HEALTH: f32 = 100.0;
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the server to a player that died, to move them to where they respawn.
+///
+/// The client is in charge of its player's position, so this is how dead players are moved.
+pub struct PlayerRespawnEvent(pub Location);
+
+impl IdentifiableEvent for PlayerRespawnEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:player_respawn"
+    }
+}
+
+impl NettyEvent for PlayerRespawnEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_netty_event::<PlayerRespawnEvent>();
+}
@@ -0,0 +1,79 @@
+//! How hungry a player is. Hunger slowly runs out over time, and is restored by eating.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::{sync_component, IdentifiableComponent, SyncableComponent};
+
+/// The most food a player can have eaten
+pub const MAX_HUNGER: f32 = 100.0;
+
+#[derive(Component, Clone, Copy, PartialEq, Debug, Serialize, Deserialize, Reflect)]
+/// How much food a player has left. Once this hits 0, the player is starving and can no longer sprint.
+pub struct Hunger {
+    current: f32,
+    max: f32,
+}
+
+impl Default for Hunger {
+    fn default() -> Self {
+        Self {
+            current: MAX_HUNGER,
+            max: MAX_HUNGER,
+        }
+    }
+}
+
+impl Hunger {
+    /// The amount of food left
+    pub fn current(&self) -> f32 {
+        self.current
+    }
+
+    /// The most food this can have
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+
+    /// How full this is, from 0.0 (starving) to 1.0 (full)
+    pub fn fraction(&self) -> f32 {
+        if self.max <= 0.0 {
+            0.0
+        } else {
+            self.current / self.max
+        }
+    }
+
+    /// Uses up this much food, without going below 0
+    pub fn deplete(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(0.0);
+    }
+
+    /// Restores this much food, without going above the max
+    pub fn eat(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+
+    /// If there is no food left
+    pub fn is_starving(&self) -> bool {
+        self.current <= 0.0
+    }
+}
+
+impl IdentifiableComponent for Hunger {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:hunger"
+    }
+}
+
+impl SyncableComponent for Hunger {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<Hunger>(app);
+
+    app.register_type::<Hunger>();
+}
@@ -1,7 +1,11 @@
 //! Represents a player
 
+pub mod appearance;
 pub mod creative;
+pub mod death;
+pub mod hunger;
 pub mod render_distance;
+pub mod spectator;
 
 use bevy::prelude::{App, Component};
 use bevy_renet2::renet2::ClientId;
@@ -50,5 +54,9 @@ impl SyncableComponent for Player {
 pub(super) fn register(app: &mut App) {
     sync_component::<Player>(app);
 
+    appearance::register(app);
     creative::register(app);
+    death::register(app);
+    hunger::register(app);
+    spectator::register(app);
 }
@@ -0,0 +1,80 @@
+//! Spectator mode lets a player (that the server has allowed to) leave their body behind and fly
+//! around freely, passing through structures.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::ColliderDisabled;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::{
+        sync::{
+            events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+            sync_component, IdentifiableComponent, SyncableComponent,
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    physics::location::Location,
+};
+
+#[derive(Component, Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+/// Signifies a player is spectating.
+///
+/// Spectators fly around without gravity and do not collide with anything.
+pub struct Spectator {
+    /// If this spectator should not be shown to other players
+    pub invisible: bool,
+}
+
+impl IdentifiableComponent for Spectator {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:spectator"
+    }
+}
+
+impl SyncableComponent for Spectator {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the server to move the local player somewhere else.
+///
+/// The client is in charge of its player's position, so this is how spectators are teleported
+/// around & returned to their body.
+pub struct SpectatorTeleportEvent(pub Location);
+
+impl IdentifiableEvent for SpectatorTeleportEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:spectator_teleport"
+    }
+}
+
+impl NettyEvent for SpectatorTeleportEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+fn on_add_spectator(mut commands: Commands, q_added: Query<Entity, Added<Spectator>>) {
+    for ent in q_added.iter() {
+        commands.entity(ent).insert(ColliderDisabled);
+    }
+}
+
+fn on_remove_spectator(mut commands: Commands, mut removed: RemovedComponents<Spectator>) {
+    for ent in removed.read() {
+        if let Some(mut ecmds) = commands.get_entity(ent) {
+            ecmds.remove::<ColliderDisabled>();
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<Spectator>(app);
+
+    app.add_netty_event::<SpectatorTeleportEvent>().add_systems(
+        Update,
+        (on_add_spectator, on_remove_spectator).in_set(NetworkingSystemsSet::Between),
+    );
+}
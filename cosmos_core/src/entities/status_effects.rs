@@ -0,0 +1,94 @@
+//! Temporary buffs an entity can have, such as those given by consuming certain items
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::{sync_component, IdentifiableComponent, SyncableComponent};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Reflect)]
+/// A temporary buff
+pub enum StatusEffect {
+    /// Increases how fast the entity moves.
+    ///
+    /// A strength of 0.5 makes the entity 50% faster.
+    Speed,
+    /// Reduces how much oxygen the entity uses.
+    ///
+    /// A strength of 0.5 makes the entity use 50% less oxygen.
+    OxygenEfficiency,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize, Reflect)]
+/// A status effect that is currently applied
+pub struct ActiveStatusEffect {
+    /// The effect that is applied
+    pub effect: StatusEffect,
+    /// How strong the effect is. What this means depends on the effect.
+    pub strength: f32,
+    /// How many seconds until this effect wears off
+    pub remaining_secs: f32,
+}
+
+#[derive(Component, Clone, Default, PartialEq, Debug, Serialize, Deserialize, Reflect)]
+/// Every status effect currently applied to this entity
+pub struct StatusEffects(Vec<ActiveStatusEffect>);
+
+impl StatusEffects {
+    /// Applies this effect.
+    ///
+    /// If this effect is already applied, the strongest strength and the longest duration of the two are kept.
+    pub fn apply(&mut self, effect: StatusEffect, strength: f32, duration_secs: f32) {
+        if let Some(active) = self.0.iter_mut().find(|a| a.effect == effect) {
+            active.strength = active.strength.max(strength);
+            active.remaining_secs = active.remaining_secs.max(duration_secs);
+        } else {
+            self.0.push(ActiveStatusEffect {
+                effect,
+                strength,
+                remaining_secs: duration_secs,
+            });
+        }
+    }
+
+    /// Counts down every effect's duration by this many seconds, and removes any that have worn off
+    pub fn tick(&mut self, delta_secs: f32) {
+        for active in self.0.iter_mut() {
+            active.remaining_secs -= delta_secs;
+        }
+
+        self.0.retain(|a| a.remaining_secs > 0.0);
+    }
+
+    /// How strong this effect is, or 0.0 if it isn't applied
+    pub fn strength(&self, effect: StatusEffect) -> f32 {
+        self.0.iter().find(|a| a.effect == effect).map(|a| a.strength).unwrap_or(0.0)
+    }
+
+    /// Iterates over every effect currently applied
+    pub fn iter(&self) -> impl Iterator<Item = &ActiveStatusEffect> {
+        self.0.iter()
+    }
+
+    /// Returns true if there are no effects applied
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl IdentifiableComponent for StatusEffects {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:status_effects"
+    }
+}
+
+impl SyncableComponent for StatusEffects {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<StatusEffects>(app);
+
+    app.register_type::<StatusEffects>();
+}
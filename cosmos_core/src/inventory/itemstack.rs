@@ -341,6 +341,13 @@ impl ItemStack {
         self.item_id
     }
 
+    /// Changes the id of the item this is a stack of, keeping its quantity & data.
+    ///
+    /// This is only meant for converting between the server's & client's ids for the same item.
+    pub fn remap_item_id(&mut self, item_id: u16) {
+        self.item_id = item_id;
+    }
+
     #[inline]
     /// Gets the quantity
     pub fn quantity(&self) -> u16 {
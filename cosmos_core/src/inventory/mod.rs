@@ -97,6 +97,15 @@ impl SyncableComponent for Inventory {
 
         Some(self)
     }
+
+    #[cfg(feature = "client")]
+    fn convert_item_ids_server_to_client(mut self, item_ids: &crate::netty::sync::registry::client::RegistryIdMap<Item>) -> Option<Self> {
+        if !item_ids.is_identity() {
+            self.remap_item_ids(|id| item_ids.to_client(id));
+        }
+
+        Some(self)
+    }
 }
 
 type InventorySlot = usize;
@@ -172,6 +181,33 @@ impl Inventory {
         self.items.iter().any(|x| x.is_some())
     }
 
+    /// Changes the number of slots this inventory has.
+    ///
+    /// When shrinking, items in the removed slots are moved into empty slots that are being kept. If there
+    /// isn't room for all of them, the inventory will only shrink as far as it can without losing any items.
+    pub fn resize(&mut self, n_slots: usize, commands: &mut Commands) {
+        if n_slots >= self.items.len() {
+            self.items.resize(n_slots, None);
+            return;
+        }
+
+        for slot in n_slots..self.items.len() {
+            if self.items[slot].is_none() {
+                continue;
+            }
+
+            let Some(empty_slot) = (0..n_slots).find(|&s| self.items[s].is_none()) else {
+                break;
+            };
+
+            let is = self.items[slot].take().expect("Checked to be some above");
+            self.set_items_at(empty_slot, is, commands);
+        }
+
+        let used_slots = self.items.iter().rposition(|x| x.is_some()).map(|x| x + 1).unwrap_or(0);
+        self.items.truncate(n_slots.max(used_slots));
+    }
+
     /// Swaps the contents of two inventory slots in the same inventory.
     ///
     /// Returns Ok if both slots were within the bounds of the inventory, Err if either was not
@@ -439,6 +475,22 @@ impl Inventory {
         (qty, new_slot)
     }
 
+    /// Converts the item id of every [`ItemStack`] in this inventory. Any itemstacks `remap` returns `None` for are removed.
+    ///
+    /// This is only meant for converting between the server's & client's ids for the same items.
+    pub fn remap_item_ids(&mut self, remap: impl Fn(u16) -> Option<u16>) {
+        for slot in self.items.iter_mut() {
+            let Some(is) = slot else {
+                continue;
+            };
+
+            match remap(is.item_id()) {
+                Some(item_id) => is.remap_item_id(item_id),
+                None => *slot = None,
+            }
+        }
+    }
+
     /// Returns the ItemStack at that slot
     pub fn itemstack_at(&self, slot: usize) -> Option<&ItemStack> {
         self.items[slot].as_ref()
@@ -0,0 +1,106 @@
+//! Batteries are items that store energy.
+//!
+//! How much energy a specific battery holds is stored as [`BatteryCharge`] on that itemstack's data entity.
+//! Batteries are charged in a battery charger, and can power handheld tools or jump-start a structure's energy storage.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::{sync_component, IdentifiableComponent, SyncableComponent};
+
+/// The unlocalized name of the battery item
+pub const BATTERY_ITEM: &str = "cosmos:battery";
+/// The unlocalized name of the block that charges batteries
+pub const BATTERY_CHARGER_BLOCK: &str = "cosmos:battery_charger";
+
+/// How much energy a battery can hold
+pub const BATTERY_CAPACITY: f32 = 10_000.0;
+
+/// How much energy a mining tool takes from a battery in its user's inventory to mine a block without wearing down
+pub const TOOL_ENERGY_PER_BLOCK: f32 = 50.0;
+
+#[derive(Component, Debug, Reflect, Clone, Copy, Serialize, Deserialize, PartialEq)]
+/// How much energy a battery is holding
+pub struct BatteryCharge {
+    energy: f32,
+    capacity: f32,
+}
+
+impl BatteryCharge {
+    /// Creates a battery with no energy in it
+    pub fn empty(capacity: f32) -> Self {
+        Self { energy: 0.0, capacity }
+    }
+
+    /// How much energy is stored
+    pub fn energy(&self) -> f32 {
+        self.energy
+    }
+
+    /// The most energy this can store
+    pub fn capacity(&self) -> f32 {
+        self.capacity
+    }
+
+    /// How much more energy this can store before it's full
+    pub fn missing_energy(&self) -> f32 {
+        self.capacity - self.energy
+    }
+
+    /// The fraction of the capacity that is filled, from 0.0 to 1.0
+    pub fn fraction(&self) -> f32 {
+        if self.capacity <= 0.0 {
+            return 0.0;
+        }
+
+        self.energy / self.capacity
+    }
+
+    /// Adds up to `amount` energy to this battery.
+    ///
+    /// Returns how much energy was actually added.
+    pub fn charge(&mut self, amount: f32) -> f32 {
+        let added = amount.clamp(0.0, self.missing_energy());
+        self.energy += added;
+
+        added
+    }
+
+    /// Takes up to `amount` energy out of this battery.
+    ///
+    /// Returns how much energy was actually taken.
+    pub fn drain(&mut self, amount: f32) -> f32 {
+        let taken = amount.clamp(0.0, self.energy);
+        self.energy -= taken;
+
+        taken
+    }
+
+    /// Returns true if there is no energy stored
+    pub fn is_empty(&self) -> bool {
+        self.energy <= 0.0
+    }
+
+    /// Returns true if no more energy can be stored
+    pub fn is_full(&self) -> bool {
+        self.energy >= self.capacity
+    }
+}
+
+impl IdentifiableComponent for BatteryCharge {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:battery_charge"
+    }
+}
+
+impl SyncableComponent for BatteryCharge {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<BatteryCharge>(app);
+
+    app.register_type::<BatteryCharge>();
+}
@@ -0,0 +1,76 @@
+//! Cargo pods hold cargo jettisoned from a ship's storage.
+//!
+//! A pod keeps everything that was in the storage it came from in its own [`crate::inventory::Inventory`]. Piloted
+//! ships pull in nearby pods with their tractor beam and move the pod's items into their storage.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, ReadMassProperties, RigidBody};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::{
+        sync::{
+            events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+            sync_component, IdentifiableComponent, SyncType, SyncableComponent,
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    structure::structure_block::StructureBlock,
+};
+
+/// Half the width, height & length of a cargo pod
+pub const CARGO_POD_HALF_SIZE: f32 = 0.5;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// A container floating in space, holding jettisoned cargo
+pub struct CargoPod;
+
+impl IdentifiableComponent for CargoPod {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:cargo_pod"
+    }
+}
+
+impl SyncableComponent for CargoPod {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to jettison everything in a ship's storage block as a cargo pod
+pub struct JettisonCargoEvent {
+    /// The storage block to empty
+    pub storage: StructureBlock,
+}
+
+impl IdentifiableEvent for JettisonCargoEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:jettison_cargo"
+    }
+}
+
+impl NettyEvent for JettisonCargoEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+fn on_add_cargo_pod(mut commands: Commands, q_added: Query<Entity, Added<CargoPod>>) {
+    for ent in q_added.iter() {
+        commands.entity(ent).insert((
+            RigidBody::Dynamic,
+            Collider::cuboid(CARGO_POD_HALF_SIZE, CARGO_POD_HALF_SIZE, CARGO_POD_HALF_SIZE),
+            ReadMassProperties::default(),
+            Name::new("Cargo Pod"),
+        ));
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<CargoPod>(app);
+
+    app.add_systems(Update, on_add_cargo_pod.in_set(NetworkingSystemsSet::Between))
+        .register_type::<CargoPod>()
+        .add_netty_event::<JettisonCargoEvent>();
+}
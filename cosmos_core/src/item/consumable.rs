@@ -0,0 +1,115 @@
+//! Items that can be consumed (eaten/drunk) by players to restore hunger and apply status effects.
+//!
+//! Each [`Consumable`] shares its unlocalized name with the item it describes.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entities::status_effects::StatusEffect,
+    netty::sync::events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+    registry::{create_registry, identifiable::Identifiable, Registry},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// A status effect that is applied when something is consumed
+pub struct ConsumableEffect {
+    /// The effect to apply
+    pub effect: StatusEffect,
+    /// How strong the effect is (see [`StatusEffect`])
+    pub strength: f32,
+    /// How long (in seconds) the effect lasts
+    pub duration_secs: f32,
+}
+
+#[derive(Debug, Clone)]
+/// Describes what happens when an item is consumed
+pub struct Consumable {
+    unlocalized_name: String,
+    id: u16,
+    hunger_restored: f32,
+    effects: Vec<ConsumableEffect>,
+}
+
+impl Consumable {
+    /// Creates a consumable for the item with this unlocalized name
+    pub fn new(item_unlocalized_name: impl Into<String>, hunger_restored: f32, effects: Vec<ConsumableEffect>) -> Self {
+        Self {
+            unlocalized_name: item_unlocalized_name.into(),
+            id: 0,
+            hunger_restored,
+            effects,
+        }
+    }
+
+    /// How much hunger is restored when this is consumed
+    pub fn hunger_restored(&self) -> f32 {
+        self.hunger_restored
+    }
+
+    /// The status effects applied when this is consumed
+    pub fn effects(&self) -> &[ConsumableEffect] {
+        &self.effects
+    }
+}
+
+impl Identifiable for Consumable {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to consume one of the item they are currently holding
+pub struct ConsumeHeldItemEvent;
+
+impl IdentifiableEvent for ConsumeHeldItemEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:consume_held_item"
+    }
+}
+
+impl NettyEvent for ConsumeHeldItemEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+fn register_consumables(mut consumables: ResMut<Registry<Consumable>>) {
+    consumables.register(Consumable::new("cosmos:wheat", 4.0, vec![]));
+    consumables.register(Consumable::new("cosmos:potato", 12.0, vec![]));
+    consumables.register(Consumable::new("cosmos:raw_meat", 20.0, vec![]));
+    consumables.register(Consumable::new(
+        "cosmos:energy_drink",
+        5.0,
+        vec![ConsumableEffect {
+            effect: StatusEffect::Speed,
+            strength: 0.3,
+            duration_secs: 60.0,
+        }],
+    ));
+    consumables.register(Consumable::new(
+        "cosmos:oxygen_tablet",
+        0.0,
+        vec![ConsumableEffect {
+            effect: StatusEffect::OxygenEfficiency,
+            strength: 0.5,
+            duration_secs: 120.0,
+        }],
+    ));
+}
+
+pub(super) fn register<T: States>(app: &mut App, loading_state: T) {
+    create_registry::<Consumable>(app, "cosmos:consumables");
+
+    app.add_systems(OnEnter(loading_state), register_consumables)
+        .add_netty_event::<ConsumeHeldItemEvent>();
+}
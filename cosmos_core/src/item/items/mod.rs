@@ -1,10 +1,23 @@
 //! Loads all the items for cosmos & adds the item registry.
 
+use crate::block::paint::PAINT_TOOL_ITEM;
+use crate::block::specific_blocks::crop::{CROPS, HOE_ITEM};
+use crate::block::specific_blocks::storage_lock::{CODE_LOCK_ITEM, KEYCARD_ITEMS, LOCKPICK_ITEM};
 use crate::loader::{AddLoadingEvent, DoneLoadingEvent, LoadingManager};
+use crate::logic::debug::LOGIC_WRENCH_ITEM;
+use crate::netty::sync::registry::sync_registry_ids;
 use crate::registry::{self, Registry};
+use crate::structure::hazards::FIRE_EXTINGUISHER_ITEM;
+use crate::structure::welding::WELDING_TOOL_ITEM;
 use bevy::prelude::*;
 
-use super::{Item, DEFAULT_MAX_STACK_SIZE};
+use super::{
+    battery::BATTERY_ITEM,
+    scanner::SCANNER_ITEM,
+    tool::{ENERGITE_DRILL_ITEM, GRAVITRON_DRILL_ITEM, IRON_DRILL_ITEM},
+    upgrade_module::UpgradeModuleKind,
+    Item, DEFAULT_MAX_STACK_SIZE,
+};
 
 fn add_cosmos_items(
     mut items: ResMut<Registry<Item>>,
@@ -28,11 +41,54 @@ fn add_cosmos_items(
     items.register(Item::new("cosmos:gravitron_crystal", DEFAULT_MAX_STACK_SIZE));
     items.register(Item::new("cosmos:energite_crystal", DEFAULT_MAX_STACK_SIZE));
 
+    items.register(Item::new("cosmos:scrap", DEFAULT_MAX_STACK_SIZE));
+
+    items.register(Item::new("cosmos:hide", DEFAULT_MAX_STACK_SIZE));
+    items.register(Item::new("cosmos:raw_meat", DEFAULT_MAX_STACK_SIZE));
+    items.register(Item::new("cosmos:chitin", DEFAULT_MAX_STACK_SIZE));
+
+    items.register(Item::new("cosmos:energy_drink", DEFAULT_MAX_STACK_SIZE));
+    items.register(Item::new("cosmos:oxygen_tablet", DEFAULT_MAX_STACK_SIZE));
+
+    items.register(Item::new(IRON_DRILL_ITEM, 1));
+    items.register(Item::new(ENERGITE_DRILL_ITEM, 1));
+    items.register(Item::new(GRAVITRON_DRILL_ITEM, 1));
+
+    for module in UpgradeModuleKind::ALL {
+        items.register(Item::new(module.item_unlocalized_name(), DEFAULT_MAX_STACK_SIZE));
+    }
+
+    items.register(Item::new(HOE_ITEM, 1));
+
+    items.register(Item::new(BATTERY_ITEM, 1));
+    for crop in CROPS {
+        if !items.contains(crop.seed_item) {
+            items.register(Item::new(crop.seed_item, DEFAULT_MAX_STACK_SIZE));
+        }
+        if !items.contains(crop.produce_item) {
+            items.register(Item::new(crop.produce_item, DEFAULT_MAX_STACK_SIZE));
+        }
+    }
+
+    items.register(Item::new(PAINT_TOOL_ITEM, 1));
+
+    for keycard in KEYCARD_ITEMS {
+        items.register(Item::new(keycard, 1));
+    }
+    items.register(Item::new(CODE_LOCK_ITEM, DEFAULT_MAX_STACK_SIZE));
+    items.register(Item::new(LOCKPICK_ITEM, DEFAULT_MAX_STACK_SIZE));
+
+    items.register(Item::new(LOGIC_WRENCH_ITEM, 1));
+    items.register(Item::new(WELDING_TOOL_ITEM, 1));
+    items.register(Item::new(FIRE_EXTINGUISHER_ITEM, 1));
+    items.register(Item::new(SCANNER_ITEM, 1));
+
     loading.finish_loading(id, &mut end_writer);
 }
 
 pub(super) fn register<T: States>(app: &mut App, loading_state: T) {
     registry::create_registry::<Item>(app, "cosmos:items");
+    sync_registry_ids::<Item>(app);
 
     app.add_systems(OnEnter(loading_state), add_cosmos_items);
 }
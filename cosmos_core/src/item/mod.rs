@@ -1,7 +1,13 @@
 //! Items are something that represent something that can be stored in inventories.
 
+pub mod battery;
+pub mod cargo_pod;
+pub mod consumable;
 pub mod items;
 pub mod physical_item;
+pub mod scanner;
+pub mod tool;
+pub mod upgrade_module;
 
 use bevy::{prelude::App, prelude::States};
 
@@ -52,5 +58,11 @@ impl Item {
 
 pub(super) fn register<T: States>(app: &mut App, loading_state: T) {
     items::register(app, loading_state);
+    consumable::register(app, loading_state);
+    tool::register(app, loading_state);
+    upgrade_module::register(app);
+    battery::register(app);
     physical_item::register(app);
+    cargo_pod::register(app);
+    scanner::register(app);
 }
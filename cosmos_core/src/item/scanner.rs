@@ -0,0 +1,77 @@
+//! A handheld scanner that finds ore blocks through walls.
+//!
+//! Using the scanner asks the server to scan any planets & asteroids near the player. The server replies with a
+//! [`ScanResultEvent`] for every structure it found ore in, which the client highlights for a few seconds.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::sync::events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+    structure::coordinates::BlockCoordinate,
+};
+
+/// The item used to scan for ores
+pub const SCANNER_ITEM: &str = "cosmos:handheld_scanner";
+
+/// How far (in blocks) from the player the scanner will find ores
+pub const SCAN_RANGE: i64 = 32;
+/// How long (in seconds) a player has to wait between scans
+pub const SCAN_COOLDOWN_SECS: f32 = 10.0;
+/// How long (in seconds) the ores found by a scan stay highlighted
+pub const SCAN_HIGHLIGHT_SECS: f32 = 8.0;
+/// The most ores a single scan will find - the closest ones are kept
+pub const MAX_SCAN_RESULTS: usize = 256;
+
+/// The blocks the scanner looks for
+pub const ORE_BLOCKS: [&str; 8] = [
+    "cosmos:test_ore",
+    "cosmos:iron_ore",
+    "cosmos:copper_ore",
+    "cosmos:lead_ore",
+    "cosmos:uranium_ore",
+    "cosmos:sulfur_ore",
+    "cosmos:gravitron_crystal_ore",
+    "cosmos:energite_crystal_ore",
+];
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to scan with the [`SCANNER_ITEM`] they are currently holding
+pub struct UseScannerEvent;
+
+impl IdentifiableEvent for UseScannerEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:use_scanner"
+    }
+}
+
+impl NettyEvent for UseScannerEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+/// Sent to a player that scanned, once for every structure ore was found in
+pub struct ScanResultEvent {
+    /// The server's entity for the structure these ores are in
+    pub structure: Entity,
+    /// The coordinates of every ore block found
+    pub ores: Vec<BlockCoordinate>,
+}
+
+impl IdentifiableEvent for ScanResultEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:scan_result"
+    }
+}
+
+impl NettyEvent for ScanResultEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_netty_event::<UseScannerEvent>().add_netty_event::<ScanResultEvent>();
+}
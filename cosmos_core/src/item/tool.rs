@@ -0,0 +1,177 @@
+//! Tools are items that wear out as they are used, and may make mining blocks faster.
+//!
+//! Each [`Tool`] shares its unlocalized name with the item it describes. How worn out a specific
+//! tool is gets stored as [`ItemDurability`] on that itemstack's data entity.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::specific_blocks::crop::HOE_ITEM,
+    netty::sync::{sync_component, IdentifiableComponent, SyncableComponent},
+    registry::{create_registry, identifiable::Identifiable, Registry},
+    structure::hazards::FIRE_EXTINGUISHER_ITEM,
+};
+
+/// How much hardness a player can mine through every second without a tool
+pub const BASE_MINING_SPEED: f32 = 40.0;
+
+/// A drill made of iron
+pub const IRON_DRILL_ITEM: &str = "cosmos:iron_drill";
+/// A drill made of energite
+pub const ENERGITE_DRILL_ITEM: &str = "cosmos:energite_drill";
+/// A drill made of gravitron
+pub const GRAVITRON_DRILL_ITEM: &str = "cosmos:gravitron_drill";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+/// How good a tool is at mining blocks
+pub enum ToolTier {
+    /// Iron tools
+    Basic,
+    /// Energite tools
+    Advanced,
+    /// Gravitron tools
+    Exotic,
+}
+
+impl ToolTier {
+    /// How many times faster a tool of this tier mines blocks than bare hands
+    pub fn mining_speed_multiplier(&self) -> f32 {
+        match self {
+            Self::Basic => 2.0,
+            Self::Advanced => 4.0,
+            Self::Exotic => 8.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// An item that wears out as it is used
+pub struct Tool {
+    unlocalized_name: String,
+    id: u16,
+    tier: Option<ToolTier>,
+    max_durability: u32,
+}
+
+impl Tool {
+    /// Creates a tool for the item with this unlocalized name.
+    ///
+    /// `tier` should be `None` if this tool doesn't help mine blocks.
+    pub fn new(item_unlocalized_name: impl Into<String>, tier: Option<ToolTier>, max_durability: u32) -> Self {
+        Self {
+            unlocalized_name: item_unlocalized_name.into(),
+            id: 0,
+            tier,
+            max_durability,
+        }
+    }
+
+    /// The tier of this tool, or `None` if it doesn't help mine blocks
+    pub fn tier(&self) -> Option<ToolTier> {
+        self.tier
+    }
+
+    /// How many times this tool can be used before it breaks
+    pub fn max_durability(&self) -> u32 {
+        self.max_durability
+    }
+}
+
+impl Identifiable for Tool {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+/// Computes how many seconds it takes to mine a block of this hardness using this tool.
+///
+/// Pass `None` for the tool if the block is being mined by hand (or with an item that isn't a tool).
+pub fn mining_time_secs(hardness: f32, tool: Option<&Tool>) -> f32 {
+    let multiplier = tool.and_then(|t| t.tier()).map(|t| t.mining_speed_multiplier()).unwrap_or(1.0);
+
+    hardness.max(0.0) / (BASE_MINING_SPEED * multiplier)
+}
+
+#[derive(Component, Debug, Reflect, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+/// How many more uses an item has before it breaks
+pub struct ItemDurability {
+    current: u32,
+    max: u32,
+}
+
+impl ItemDurability {
+    /// Creates a brand new (undamaged) durability
+    pub fn new(max: u32) -> Self {
+        Self { current: max, max }
+    }
+
+    /// How many uses are left
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+
+    /// How many uses this had when it was brand new
+    pub fn max(&self) -> u32 {
+        self.max
+    }
+
+    /// The fraction of uses left, from 0.0 to 1.0
+    pub fn fraction(&self) -> f32 {
+        if self.max == 0 {
+            return 0.0;
+        }
+
+        self.current as f32 / self.max as f32
+    }
+
+    /// Uses this up by the given amount.
+    ///
+    /// Returns true if this is now broken.
+    pub fn damage(&mut self, amount: u32) -> bool {
+        self.current = self.current.saturating_sub(amount);
+
+        self.is_broken()
+    }
+
+    /// Returns true if there are no uses left
+    pub fn is_broken(&self) -> bool {
+        self.current == 0
+    }
+}
+
+impl IdentifiableComponent for ItemDurability {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:item_durability"
+    }
+}
+
+impl SyncableComponent for ItemDurability {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+}
+
+fn register_tools(mut tools: ResMut<Registry<Tool>>) {
+    tools.register(Tool::new(HOE_ITEM, None, 250));
+    tools.register(Tool::new(IRON_DRILL_ITEM, Some(ToolTier::Basic), 500));
+    tools.register(Tool::new(ENERGITE_DRILL_ITEM, Some(ToolTier::Advanced), 1_000));
+    tools.register(Tool::new(GRAVITRON_DRILL_ITEM, Some(ToolTier::Exotic), 2_000));
+    tools.register(Tool::new(FIRE_EXTINGUISHER_ITEM, None, 100));
+}
+
+pub(super) fn register<T: States>(app: &mut App, loading_state: T) {
+    create_registry::<Tool>(app, "cosmos:tools");
+    sync_component::<ItemDurability>(app);
+
+    app.add_systems(OnEnter(loading_state), register_tools)
+        .register_type::<ItemDurability>();
+}
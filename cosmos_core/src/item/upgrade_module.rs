@@ -0,0 +1,239 @@
+//! Upgrade modules can be installed into mining tools and ship weapon blocks to improve them.
+//!
+//! Modules installed into a tool are stored as [`InstalledModules`] item data, and modules installed into a
+//! weapon block are stored as [`InstalledModules`] block data.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::sync::{
+        events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+        sync_component, IdentifiableComponent, SyncableComponent,
+    },
+    structure::{
+        structure_block::StructureBlock,
+        systems::line_system::{Line, LineProperty},
+        Structure,
+    },
+};
+
+/// The most modules that can be installed into a single tool or block
+pub const MAX_INSTALLED_MODULES: usize = 3;
+
+/// Ship weapon blocks that can have modules installed into them
+pub const UPGRADEABLE_WEAPON_BLOCKS: [&str; 2] = ["cosmos:laser_cannon", "cosmos:missile_launcher"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+/// The different kinds of upgrade modules
+pub enum UpgradeModuleKind {
+    /// Weapons fire more often
+    FireRate,
+    /// Weapons use less energy per shot
+    EnergyEfficiency,
+    /// Tools have a chance to mine an extra item
+    MiningYield,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// What kind of thing a module is being installed into
+pub enum UpgradeableKind {
+    /// A mining tool
+    Tool,
+    /// A ship weapon block
+    ShipWeapon,
+}
+
+impl UpgradeModuleKind {
+    /// Every kind of module
+    pub const ALL: [Self; 3] = [Self::FireRate, Self::EnergyEfficiency, Self::MiningYield];
+
+    /// The unlocalized name of the item for this module
+    pub fn item_unlocalized_name(&self) -> &'static str {
+        match self {
+            Self::FireRate => "cosmos:fire_rate_module",
+            Self::EnergyEfficiency => "cosmos:energy_efficiency_module",
+            Self::MiningYield => "cosmos:mining_yield_module",
+        }
+    }
+
+    /// Gets the module this item is for, if it is one
+    pub fn from_item_unlocalized_name(unlocalized_name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.item_unlocalized_name() == unlocalized_name)
+    }
+
+    /// How much of a bonus each installed module of this kind gives.
+    ///
+    /// - [`Self::FireRate`]: 0.25 means weapons fire 25% more often
+    /// - [`Self::EnergyEfficiency`]: 0.2 means weapons use 20% less energy
+    /// - [`Self::MiningYield`]: 0.25 means a 25% chance of an extra item
+    pub fn bonus_per_module(&self) -> f32 {
+        match self {
+            Self::FireRate => 0.25,
+            Self::EnergyEfficiency => 0.2,
+            Self::MiningYield => 0.25,
+        }
+    }
+
+    /// Returns true if this module does anything when installed into this
+    pub fn can_upgrade(&self, upgradeable: UpgradeableKind) -> bool {
+        match self {
+            Self::FireRate | Self::EnergyEfficiency => upgradeable == UpgradeableKind::ShipWeapon,
+            Self::MiningYield => upgradeable == UpgradeableKind::Tool,
+        }
+    }
+}
+
+#[derive(Component, Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// The upgrade modules installed into a tool or block
+pub struct InstalledModules(Vec<UpgradeModuleKind>);
+
+impl InstalledModules {
+    /// Installs this module.
+    ///
+    /// Returns false if there is no room for another module.
+    pub fn install(&mut self, module: UpgradeModuleKind) -> bool {
+        if self.is_full() {
+            return false;
+        }
+
+        self.0.push(module);
+
+        true
+    }
+
+    /// Uninstalls the module at this index, returning it if there was one
+    pub fn uninstall(&mut self, index: usize) -> Option<UpgradeModuleKind> {
+        if index >= self.0.len() {
+            return None;
+        }
+
+        Some(self.0.remove(index))
+    }
+
+    /// Returns true if no more modules can be installed
+    pub fn is_full(&self) -> bool {
+        self.0.len() >= MAX_INSTALLED_MODULES
+    }
+
+    /// Iterates over every installed module
+    pub fn iter(&self) -> impl Iterator<Item = &UpgradeModuleKind> {
+        self.0.iter()
+    }
+
+    /// The total bonus all installed modules of this kind give (see [`UpgradeModuleKind::bonus_per_module`])
+    pub fn bonus(&self, kind: UpgradeModuleKind) -> f32 {
+        self.0.iter().filter(|k| **k == kind).count() as f32 * kind.bonus_per_module()
+    }
+}
+
+impl IdentifiableComponent for InstalledModules {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:installed_modules"
+    }
+}
+
+impl SyncableComponent for InstalledModules {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+}
+
+/// The bonus a line of weapon blocks gets from the modules installed into its blocks.
+///
+/// Each block contributes its share of the line, so a line only gets the full bonus if every block in it has
+/// the same modules installed.
+pub fn line_module_bonus<T: LineProperty>(
+    line: &Line<T>,
+    structure: &Structure,
+    kind: UpgradeModuleKind,
+    q_installed_modules: &Query<&InstalledModules>,
+) -> f32 {
+    if line.len == 0 {
+        return 0.0;
+    }
+
+    let total = line
+        .iter_blocks()
+        .filter_map(|coords| structure.query_block_data(coords, q_installed_modules))
+        .map(|modules| modules.bonus(kind))
+        .sum::<f32>();
+
+    total / line.len as f32
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Something that upgrade modules can be installed into
+pub enum UpgradeTarget {
+    /// A ship weapon block
+    Block(StructureBlock),
+    /// The tool the player is currently holding
+    HeldItem,
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the server to the client to instruct them to open the upgrade modules menu for this.
+pub struct OpenUpgradeModulesEvent(pub UpgradeTarget);
+
+impl IdentifiableEvent for OpenUpgradeModulesEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:open_upgrade_modules"
+    }
+}
+
+impl NettyEvent for OpenUpgradeModulesEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to install the module in this slot of their inventory into the target
+pub struct InstallUpgradeModuleEvent {
+    /// What the module is being installed into
+    pub target: UpgradeTarget,
+    /// The inventory slot of the module item
+    pub inventory_slot: u32,
+}
+
+impl IdentifiableEvent for InstallUpgradeModuleEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:install_upgrade_module"
+    }
+}
+
+impl NettyEvent for InstallUpgradeModuleEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to take the module at this index out of the target and put it in their inventory
+pub struct UninstallUpgradeModuleEvent {
+    /// What the module is being removed from
+    pub target: UpgradeTarget,
+    /// The index of the module in its [`InstalledModules`]
+    pub module_index: u32,
+}
+
+impl IdentifiableEvent for UninstallUpgradeModuleEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:uninstall_upgrade_module"
+    }
+}
+
+impl NettyEvent for UninstallUpgradeModuleEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<InstalledModules>(app);
+
+    app.add_netty_event::<OpenUpgradeModulesEvent>()
+        .add_netty_event::<InstallUpgradeModuleEvent>()
+        .add_netty_event::<UninstallUpgradeModuleEvent>()
+        .register_type::<InstalledModules>();
+}
@@ -17,7 +17,9 @@ pub mod inventory;
 pub mod item;
 pub mod loader;
 pub mod logic;
+pub mod modding;
 pub mod netty;
+pub mod party;
 pub mod persistence;
 pub mod physics;
 pub mod plugin;
@@ -32,6 +32,36 @@ impl LoadingManager {
     }
 }
 
+#[derive(Resource, Default, Debug, Clone, Copy)]
+/// How much of the current loading stage (pre-loading, loading, or post-loading) has finished.
+///
+/// This is reset each time the loading stage changes.
+pub struct LoadingProgress {
+    registered: usize,
+    finished: usize,
+}
+
+impl LoadingProgress {
+    /// The number of things that have started loading this stage
+    pub fn registered(&self) -> usize {
+        self.registered
+    }
+
+    /// The number of things that have finished loading this stage
+    pub fn finished(&self) -> usize {
+        self.finished
+    }
+
+    /// How much of this stage has finished loading [0.0, 1.0]
+    pub fn fraction(&self) -> f32 {
+        if self.registered == 0 {
+            0.0
+        } else {
+            self.finished as f32 / self.registered as f32
+        }
+    }
+}
+
 #[derive(Resource)]
 struct LoadingStatus<T: States + Clone + Copy> {
     loaders: HashSet<usize>,
@@ -61,15 +91,18 @@ fn monitor_loading<T: States + Clone + Copy + FreelyMutableState>(
     mut event_done_reader: EventReader<DoneLoadingEvent>,
     mut event_start_reader: EventReader<AddLoadingEvent>,
     mut loading_status: ResMut<LoadingStatus<T>>,
+    mut progress: ResMut<LoadingProgress>,
     state: Res<State<T>>,
     mut state_changer: ResMut<NextState<T>>,
 ) {
     for ev in event_start_reader.read() {
         loading_status.loaders.insert(ev.loading_id);
+        progress.registered += 1;
     }
 
     for ev in event_done_reader.read() {
         loading_status.done_loading(ev.loading_id);
+        progress.finished += 1;
     }
 
     if loading_status.done {
@@ -89,6 +122,7 @@ fn monitor_loading<T: States + Clone + Copy + FreelyMutableState>(
         }
 
         loading_status.done = false;
+        *progress = LoadingProgress::default();
     }
 }
 
@@ -140,6 +174,7 @@ pub(super) fn register<T: States + Clone + Copy + FreelyMutableState>(
         )
         .insert_resource(LoadingStatus::new(pre_loading_state, loading_state, post_loading_state, done_state))
         .insert_resource(LoadingManager::default())
+        .init_resource::<LoadingProgress>()
         .allow_ambiguous_resource::<Events<DoneLoadingEvent>>()
         .allow_ambiguous_resource::<Events<AddLoadingEvent>>()
         .allow_ambiguous_resource::<LoadingManager>();
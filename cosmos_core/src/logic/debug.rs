@@ -0,0 +1,112 @@
+//! Shared data for the logic debug overlay.
+//!
+//! While holding a [`LOGIC_WRENCH_ITEM`], a player can ask the server to send them the state of a
+//! structure's logic groups, which the client then draws on top of the structure's wires and ports.
+
+use bevy::prelude::{App, Entity, Event};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::sync::events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+    structure::coordinates::BlockCoordinate,
+};
+
+use super::Port;
+
+/// The item that must be held to see the logic debug overlay
+pub const LOGIC_WRENCH_ITEM: &str = "cosmos:logic_wrench";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// The current state of a single logic group
+pub struct LogicGroupSnapshot {
+    /// The ID of this group within its structure's logic graph. Only unique per structure.
+    pub id: usize,
+    /// The wire color of this group, or [`None`] if it has no wires
+    pub wire_color_id: Option<u16>,
+    /// The sum of every signal being produced into this group
+    pub signal: i32,
+    /// Every wire block that is part of this group
+    pub wires: Vec<BlockCoordinate>,
+    /// The input ports that read from this group
+    pub inputs: Vec<Port>,
+    /// The output ports that write to this group
+    pub outputs: Vec<Port>,
+}
+
+impl LogicGroupSnapshot {
+    /// Any non-zero signal is considered "on"
+    pub fn on(&self) -> bool {
+        self.signal != 0
+    }
+}
+
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+/// Sent by the client to start or stop receiving [`LogicDebugSnapshotEvent`]s.
+///
+/// The server will only send snapshots while the player is holding a [`LOGIC_WRENCH_ITEM`].
+pub struct SetLogicDebugTargetEvent {
+    /// The server's entity for the structure to debug, or [`None`] to stop debugging
+    pub structure: Option<Entity>,
+}
+
+impl IdentifiableEvent for SetLogicDebugTargetEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:set_logic_debug_target"
+    }
+}
+
+impl NettyEvent for SetLogicDebugTargetEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+/// Sent to players debugging a structure's logic when they start, and whenever its logic blocks change
+pub struct LogicDebugSnapshotEvent {
+    /// The server's entity for the structure these groups are in
+    pub structure: Entity,
+    /// Every logic group in this structure
+    pub groups: Vec<LogicGroupSnapshot>,
+}
+
+impl IdentifiableEvent for LogicDebugSnapshotEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:logic_debug_snapshot"
+    }
+}
+
+impl NettyEvent for LogicDebugSnapshotEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+/// Sent to players debugging a structure's logic whenever the signals of its groups change, but the groups themselves don't.
+///
+/// These signals replace those of the last [`LogicDebugSnapshotEvent`].
+pub struct LogicDebugSignalsEvent {
+    /// The server's entity for the structure these groups are in
+    pub structure: Entity,
+    /// The signal of every logic group in this structure, as `(group id, signal)` sorted by group id
+    pub signals: Vec<(usize, i32)>,
+}
+
+impl IdentifiableEvent for LogicDebugSignalsEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:logic_debug_signals"
+    }
+}
+
+impl NettyEvent for LogicDebugSignalsEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_netty_event::<SetLogicDebugTargetEvent>()
+        .add_netty_event::<LogicDebugSnapshotEvent>()
+        .add_netty_event::<LogicDebugSignalsEvent>();
+}
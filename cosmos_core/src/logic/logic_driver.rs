@@ -14,7 +14,10 @@ use crate::{
     structure::{coordinates::BlockCoordinate, structure_block::StructureBlock, Structure},
 };
 
-use super::{logic_graph::LogicGraph, LogicBlock, LogicWireColor, Port, PortType, QueueLogicInputEvent, QueueLogicOutputEvent, WireType};
+use super::{
+    debug::LogicGroupSnapshot, logic_graph::LogicGraph, LogicBlock, LogicWireColor, Port, PortType, QueueLogicInputEvent,
+    QueueLogicOutputEvent, WireType,
+};
 
 #[derive(Debug, Default, Reflect, Component)]
 /// The public interface for accessing and mutating an [`Entity`]'s [`LogicGraph`].
@@ -22,6 +25,8 @@ use super::{logic_graph::LogicGraph, LogicBlock, LogicWireColor, Port, PortType,
 /// Any functionality needed for specific logic blocks (for example, wires and logic gates) should use this struct and never directly access the [`LogicGraph`].
 pub struct LogicDriver {
     logic_graph: LogicGraph,
+    /// Goes up every time a logic block is added or removed
+    layout_version: u64,
 }
 
 impl LogicDriver {
@@ -99,6 +104,8 @@ impl LogicDriver {
         evw_queue_logic_output: &mut EventWriter<QueueLogicOutputEvent>,
         evw_queue_logic_input: &mut EventWriter<QueueLogicInputEvent>,
     ) {
+        self.layout_version += 1;
+
         // Adding input faces as consumers to their connected group, or a new group if there is no connected group.
         for input_face in logic_block.input_faces() {
             self.port_placed(
@@ -187,6 +194,8 @@ impl LogicDriver {
         evw_queue_logic_output: &mut EventWriter<QueueLogicOutputEvent>,
         evw_queue_logic_input: &mut EventWriter<QueueLogicInputEvent>,
     ) {
+        self.layout_version += 1;
+
         // Removing input ports from their groups.
         for input_face in logic_block.input_faces() {
             self.logic_graph.remove_port(
@@ -281,4 +290,74 @@ impl LogicDriver {
     ) {
         self.logic_graph.update_producer(port, signal, evw_queue_logic_input, entity);
     }
+
+    /// Changes every time a logic block is added or removed, which is the only time the wires & ports of each
+    /// logic group can change. Signals can change without this changing.
+    pub fn layout_version(&self) -> u64 {
+        self.layout_version
+    }
+
+    /// The current signal of every logic group, as `(group id, signal)` sorted by group id
+    pub fn debug_signals(&self) -> Vec<(usize, i32)> {
+        let mut signals = self
+            .logic_graph
+            .iter_groups()
+            .map(|(id, group)| (id, group.signal()))
+            .collect::<Vec<_>>();
+        signals.sort_unstable_by_key(|(id, _)| *id);
+        signals
+    }
+
+    /// Captures the current state of every logic group, including which wire blocks belong to each one.
+    ///
+    /// This searches the whole structure, so should only be used for debugging. The result only needs to be
+    /// recomputed when [`Self::layout_version`] changes - use [`Self::debug_signals`] to update the signals.
+    pub fn debug_snapshot(
+        &self,
+        structure: &Structure,
+        blocks: &Registry<Block>,
+        logic_blocks: &Registry<LogicBlock>,
+        logic_wire_colors: &Registry<LogicWireColor>,
+    ) -> Vec<LogicGroupSnapshot> {
+        let mut snapshots: HashMap<usize, LogicGroupSnapshot> = self
+            .logic_graph
+            .iter_groups()
+            .map(|(id, group)| {
+                (
+                    id,
+                    LogicGroupSnapshot {
+                        id,
+                        wire_color_id: group.wire_color_id,
+                        signal: group.signal(),
+                        wires: vec![],
+                        inputs: group.consumers.iter().copied().collect(),
+                        outputs: group.producers.keys().copied().collect(),
+                    },
+                )
+            })
+            .collect();
+
+        let no_events = HashMap::new();
+
+        for coords in structure.all_blocks_iter(false) {
+            let Some(logic_block) = logic_blocks.for_block(structure.block_at(coords, blocks)) else {
+                continue;
+            };
+
+            for wire_color_id in logic_block.wire_face_colors(logic_wire_colors) {
+                let Some(group_id) =
+                    self.logic_graph
+                        .find_wire_group(coords, wire_color_id, logic_block, structure, &no_events, blocks, logic_blocks)
+                else {
+                    continue;
+                };
+
+                if let Some(snapshot) = snapshots.get_mut(&group_id) {
+                    snapshot.wires.push(coords);
+                }
+            }
+        }
+
+        snapshots.into_values().collect()
+    }
 }
@@ -123,6 +123,11 @@ impl LogicGraph {
         self.groups.get(&group_id).expect("Logic group with requested ID should exist.")
     }
 
+    /// Iterates over every group in this graph along with its ID.
+    pub fn iter_groups(&self) -> impl Iterator<Item = (usize, &LogicGroup)> {
+        self.groups.iter().map(|(&id, group)| (id, group))
+    }
+
     /// Public convenience method to get the [`LogicGroup`] ID, then the [`LogicGroup`] instance itself.
     /// Returns None if the given [`Port`] and [`PortType`] are not in this logic graph.
     pub fn group_of(&self, port: &Port, port_type: PortType) -> Option<&LogicGroup> {
@@ -298,7 +303,8 @@ impl LogicGraph {
         None
     }
 
-    pub fn get_wire_group(
+    /// Finds the group the wire of this color at these coordinates is a part of, or [`None`] if it isn't part of any group.
+    pub fn find_wire_group(
         &self,
         coords: BlockCoordinate,
         wire_color_id: u16,
@@ -307,7 +313,7 @@ impl LogicGraph {
         events_by_coords: &HashMap<BlockCoordinate, BlockChangedEvent>,
         blocks: &Registry<Block>,
         logic_blocks: &Registry<LogicBlock>,
-    ) -> usize {
+    ) -> Option<usize> {
         self.groups
             .iter()
             .find_map(|(&id, group)| {
@@ -317,7 +323,7 @@ impl LogicGraph {
                     None
                 }
             })
-            .unwrap_or_else(|| {
+            .or_else(|| {
                 self.group_dfs_all_faces(
                     logic_block,
                     wire_color_id,
@@ -328,15 +334,36 @@ impl LogicGraph {
                     blocks,
                     logic_blocks,
                 )
-                .unwrap_or_else(|| {
-                    panic!(
-                        "Logic block with wire connections (color {}) is not part of any logic group.",
-                        wire_color_id
-                    )
-                })
             })
     }
 
+    pub fn get_wire_group(
+        &self,
+        coords: BlockCoordinate,
+        wire_color_id: u16,
+        logic_block: &LogicBlock,
+        structure: &Structure,
+        events_by_coords: &HashMap<BlockCoordinate, BlockChangedEvent>,
+        blocks: &Registry<Block>,
+        logic_blocks: &Registry<LogicBlock>,
+    ) -> usize {
+        self.find_wire_group(
+            coords,
+            wire_color_id,
+            logic_block,
+            structure,
+            events_by_coords,
+            blocks,
+            logic_blocks,
+        )
+        .unwrap_or_else(|| {
+            panic!(
+                "Logic block with wire connections (color {}) is not part of any logic group.",
+                wire_color_id
+            )
+        })
+    }
+
     pub fn set_group_recent_wire(&mut self, group_id: usize, wire_color_id: u16, coords: BlockCoordinate) {
         let group = self
             .groups
@@ -32,6 +32,7 @@ use crate::{
 
 use bevy::prelude::IntoSystemSetConfigs;
 
+pub mod debug;
 pub mod logic_driver;
 pub mod logic_graph;
 
@@ -204,7 +205,7 @@ impl LogicBlock {
     }
 }
 
-#[derive(Debug, Default, Reflect, Hash, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, Default, Reflect, Hash, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 /// Represents an input or output connection on the face of a logic block.
 pub struct Port {
     /// The coordinates of the logic block.
@@ -438,6 +439,23 @@ fn queue_logic_producers(
     }
 }
 
+#[derive(Resource, Debug, Default, Clone, Copy)]
+/// How many logic ticks have happened since the game started.
+///
+/// Blocks that change their output on a schedule (such as timers) should base it on this, so they all stay in step.
+pub struct LogicTicks(u64);
+
+impl LogicTicks {
+    /// The number of logic ticks that have happened since the game started
+    pub fn count(&self) -> u64 {
+        self.0
+    }
+}
+
+fn count_logic_ticks(mut ticks: ResMut<LogicTicks>) {
+    ticks.0 += 1;
+}
+
 fn send_queued_logic_events(
     mut outputs: ResMut<LogicOutputEventQueue>,
     mut inputs: ResMut<LogicInputEventQueue>,
@@ -522,8 +540,10 @@ pub const LOGIC_TICKS_PER_SECOND: u64 = 20;
 pub(super) fn register<T: States>(app: &mut App, playing_state: T) {
     create_registry::<LogicBlock>(app, "cosmos:logic_blocks");
     create_registry::<LogicWireColor>(app, "cosmos:logic_wire_colors");
+    debug::register(app);
     app.init_resource::<LogicOutputEventQueue>();
     app.init_resource::<LogicInputEventQueue>();
+    app.init_resource::<LogicTicks>();
 
     app.configure_sets(
         Update,
@@ -554,7 +574,9 @@ pub(super) fn register<T: States>(app: &mut App, playing_state: T) {
             logic_block_changed_event_listener.in_set(LogicSystemSet::EditLogicGraph),
             queue_logic_producers.in_set(LogicSystemSet::QueueProducers),
             queue_logic_consumers.in_set(LogicSystemSet::QueueConsumers),
-            send_queued_logic_events.in_set(LogicSystemSet::SendQueues),
+            (count_logic_ticks, send_queued_logic_events)
+                .chain()
+                .in_set(LogicSystemSet::SendQueues),
             listen_for_changed_logic_data.in_set(LogicSystemSet::BlockLogicDataUpdate),
         )
             .run_if(in_state(playing_state)),
@@ -0,0 +1,206 @@
+//! The functions mods can call, which are all in the `cosmos` wasm import module.
+//!
+//! Mods only ever see numbers & strings they pass in themselves, so they can't break the game's state directly.
+//! Anything they register is validated before being added to the game.
+
+use bevy::log::{error, info};
+use wasmi::{Caller, Linker};
+
+use crate::logic::{LogicConnection, PortType, WireType};
+
+/// A block a mod wants to add
+pub(super) struct ModBlock {
+    pub unlocalized_name: String,
+    pub density: f32,
+    pub hardness: f32,
+    pub mining_resistance: f32,
+}
+
+/// An item a mod wants to add
+pub(super) struct ModItem {
+    pub unlocalized_name: String,
+    pub max_stack_size: u16,
+}
+
+/// A logic block a mod wants to add. The mod computes its output via `cosmos_logic_compute`.
+pub(super) struct ModLogicBlock {
+    pub unlocalized_name: String,
+    pub connections: [Option<LogicConnection>; 6],
+}
+
+/// Everything a mod has registered through the host api
+pub(super) struct ModState {
+    pub mod_name: String,
+    pub blocks: Vec<ModBlock>,
+    pub items: Vec<ModItem>,
+    pub basic_fabricator_recipes: Vec<String>,
+    /// Index is the handle given to the mod
+    pub block_changed_subscriptions: Vec<String>,
+    /// Index is the handle given to the mod
+    pub logic_blocks: Vec<ModLogicBlock>,
+}
+
+impl ModState {
+    pub fn new(mod_name: String) -> Self {
+        Self {
+            mod_name,
+            blocks: vec![],
+            items: vec![],
+            basic_fabricator_recipes: vec![],
+            block_changed_subscriptions: vec![],
+            logic_blocks: vec![],
+        }
+    }
+}
+
+/// Nothing a mod passes to the host should come close to this, so anything longer is treated as invalid
+const MAX_STRING_LEN: usize = 64 * 1024;
+
+fn read_string(caller: &Caller<'_, ModState>, ptr: i32, len: i32) -> Option<String> {
+    let (Ok(ptr), Ok(len)) = (usize::try_from(ptr), usize::try_from(len)) else {
+        return None;
+    };
+
+    if len > MAX_STRING_LEN {
+        return None;
+    }
+
+    let memory = caller.get_export("memory")?.into_memory()?;
+
+    // Checked before allocating, so a mod can't make the host allocate more than it has memory for
+    if ptr.checked_add(len)? > memory.data_size(caller) {
+        return None;
+    }
+
+    let mut buf = vec![0; len];
+    memory.read(caller, ptr, &mut buf).ok()?;
+
+    String::from_utf8(buf).ok()
+}
+
+/// Mods must namespace everything they add (`modname:thing`) so they can't collide with the base game or each other.
+fn read_unlocalized_name(caller: &Caller<'_, ModState>, ptr: i32, len: i32) -> Option<String> {
+    let Some(name) = read_string(caller, ptr, len) else {
+        error!("[{}] Passed an invalid string to the host api.", caller.data().mod_name);
+        return None;
+    };
+
+    match name.split_once(':') {
+        Some((namespace, id)) if !namespace.is_empty() && !id.is_empty() && namespace != "cosmos" => Some(name),
+        _ => {
+            error!(
+                "[{}] Invalid unlocalized name \"{name}\" - it must be in the form \"modname:name\", and cannot use the cosmos namespace.",
+                caller.data().mod_name
+            );
+            None
+        }
+    }
+}
+
+fn logic_connection(code: i32) -> Option<LogicConnection> {
+    match code {
+        1 => Some(LogicConnection::Port(PortType::Input)),
+        2 => Some(LogicConnection::Port(PortType::Output)),
+        3 => Some(LogicConnection::Wire(WireType::Bus)),
+        _ => None,
+    }
+}
+
+/// Adds every host function to the linker
+pub(super) fn link(linker: &mut Linker<ModState>) -> Result<(), wasmi::Error> {
+    linker.func_wrap("cosmos", "log", |caller: Caller<'_, ModState>, ptr: i32, len: i32| {
+        if let Some(message) = read_string(&caller, ptr, len) {
+            info!("[{}] {message}", caller.data().mod_name);
+        }
+    })?;
+
+    linker.func_wrap(
+        "cosmos",
+        "register_block",
+        |mut caller: Caller<'_, ModState>, ptr: i32, len: i32, density: f32, hardness: f32, mining_resistance: f32| {
+            let Some(unlocalized_name) = read_unlocalized_name(&caller, ptr, len) else {
+                return;
+            };
+
+            caller.data_mut().blocks.push(ModBlock {
+                unlocalized_name,
+                density: density.max(0.0),
+                hardness: hardness.max(0.0),
+                mining_resistance: mining_resistance.max(0.0),
+            });
+        },
+    )?;
+
+    linker.func_wrap(
+        "cosmos",
+        "register_item",
+        |mut caller: Caller<'_, ModState>, ptr: i32, len: i32, max_stack_size: i32| {
+            let Some(unlocalized_name) = read_unlocalized_name(&caller, ptr, len) else {
+                return;
+            };
+
+            caller.data_mut().items.push(ModItem {
+                unlocalized_name,
+                max_stack_size: max_stack_size.clamp(1, u16::MAX as i32) as u16,
+            });
+        },
+    )?;
+
+    linker.func_wrap(
+        "cosmos",
+        "register_basic_fabricator_recipe",
+        |mut caller: Caller<'_, ModState>, ptr: i32, len: i32| {
+            let Some(recipe_json) = read_string(&caller, ptr, len) else {
+                error!("[{}] Passed an invalid recipe string to the host api.", caller.data().mod_name);
+                return;
+            };
+
+            caller.data_mut().basic_fabricator_recipes.push(recipe_json);
+        },
+    )?;
+
+    linker.func_wrap(
+        "cosmos",
+        "subscribe_block_changed",
+        |mut caller: Caller<'_, ModState>, ptr: i32, len: i32| -> i32 {
+            let Some(block_name) = read_string(&caller, ptr, len) else {
+                error!("[{}] Passed an invalid block name to the host api.", caller.data().mod_name);
+                return -1;
+            };
+
+            let subscriptions = &mut caller.data_mut().block_changed_subscriptions;
+            subscriptions.push(block_name);
+            subscriptions.len() as i32 - 1
+        },
+    )?;
+
+    linker.func_wrap(
+        "cosmos",
+        "register_logic_block",
+        |mut caller: Caller<'_, ModState>,
+         ptr: i32,
+         len: i32,
+         right: i32,
+         left: i32,
+         top: i32,
+         bottom: i32,
+         front: i32,
+         back: i32|
+         -> i32 {
+            let Some(unlocalized_name) = read_unlocalized_name(&caller, ptr, len) else {
+                return -1;
+            };
+
+            let connections = [right, left, top, bottom, front, back].map(logic_connection);
+
+            let logic_blocks = &mut caller.data_mut().logic_blocks;
+            logic_blocks.push(ModLogicBlock {
+                unlocalized_name,
+                connections,
+            });
+            logic_blocks.len() as i32 - 1
+        },
+    )?;
+
+    Ok(())
+}
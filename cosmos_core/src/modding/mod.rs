@@ -0,0 +1,445 @@
+//! Loads WebAssembly mods from the [`MODS_DIRECTORY`] on both the client & server.
+//!
+//! Mods are sandboxed - they can only interact with the game through the functions in the `cosmos` import module.
+//! From their optional `cosmos_init` export, mods can:
+//!
+//! - `log(ptr, len)` - Log a message
+//! - `register_block(ptr, len, density, hardness, mining_resistance)` - Add a block
+//! - `register_item(ptr, len, max_stack_size)` - Add an item
+//! - `register_basic_fabricator_recipe(ptr, len)` - Add a basic fabricator recipe, in the same json format as the recipe files
+//! - `subscribe_block_changed(ptr, len) -> handle` - Have `cosmos_on_block_changed(handle, structure, x, y, z, placed)` called whenever
+//!   this block is placed or removed
+//! - `register_logic_block(ptr, len, right, left, top, bottom, front, back) -> handle` - Turns a block into a logic block.
+//!   Each face is `0` (nothing), `1` (input), `2` (output), or `3` (bus wire). `cosmos_logic_compute(handle, right, left, top, bottom, front, back) -> i32`
+//!   is called with the face's inputs whenever they change, and its result is sent to every output face.
+//!
+//! Every string is passed as a pointer + length into the mod's exported `memory`, and must be utf-8.
+//! Everything a mod adds must be namespaced with the mod's name (`modname:thing`).
+//!
+//! Every call into a mod is given a limited amount of fuel (roughly one unit per instruction), so a mod that loops
+//! forever can't hang the game. A mod that runs out of fuel is treated as broken and is never called again.
+
+use std::{cell::RefCell, fs, path::Path, rc::Rc, sync::Mutex};
+
+use bevy::{
+    log::{error, info, warn},
+    prelude::*,
+    utils::HashMap,
+};
+use host_api::ModState;
+use wasmi::{core::TrapCode, Config, Engine, Instance, Linker, Module, Store, WasmParams, WasmResults};
+
+use crate::{
+    block::{block_builder::BlockBuilder, block_events::BlockEventsSet, Block},
+    events::block_events::{BlockChangedEvent, BlockDataSystemParams},
+    item::Item,
+    loader::{AddLoadingEvent, DoneLoadingEvent, LoadingManager},
+    logic::{
+        logic_driver::LogicDriver, BlockLogicData, LogicBlock, LogicInputEvent, LogicOutputEvent, LogicSystemSet, Port,
+        QueueLogicInputEvent,
+    },
+    registry::{identifiable::Identifiable, Registry},
+    structure::Structure,
+};
+
+mod host_api;
+
+/// The directory every `.wasm` mod is loaded from
+pub const MODS_DIRECTORY: &str = "mods";
+
+/// How much fuel a mod has to start up (including `cosmos_init`)
+const INIT_FUEL: u64 = 100_000_000;
+/// How much fuel each call into a mod after it has started has
+const CALL_FUEL: u64 = 1_000_000;
+
+struct LoadedMod {
+    store: Store<ModState>,
+    instance: Instance,
+    /// Set once this mod runs out of fuel
+    disabled: bool,
+}
+
+impl LoadedMod {
+    fn name(&self) -> &str {
+        &self.store.data().mod_name
+    }
+
+    /// Calls this mod's exported function with [`CALL_FUEL`] fuel.
+    ///
+    /// Returns `None` if the mod doesn't export this function or has been disabled. If the mod runs out of fuel, it is disabled.
+    fn call<Params: WasmParams, Results: WasmResults>(&mut self, name: &str, params: Params) -> Option<Result<Results, wasmi::Error>> {
+        if self.disabled {
+            return None;
+        }
+
+        let func = self.instance.get_typed_func::<Params, Results>(&self.store, name).ok()?;
+
+        if let Err(e) = self.store.set_fuel(CALL_FUEL) {
+            return Some(Err(e));
+        }
+
+        let result = func.call(&mut self.store, params);
+
+        if result.as_ref().is_err_and(|e| e.as_trap_code() == Some(TrapCode::OutOfFuel)) {
+            error!("[{}] Ran out of fuel in {name} - this mod will no longer be run.", self.name());
+            self.disabled = true;
+        }
+
+        Some(result)
+    }
+}
+
+#[derive(Resource, Default)]
+/// Every mod that was successfully loaded
+pub struct LoadedMods(Mutex<Vec<LoadedMod>>);
+
+impl LoadedMods {
+    /// The names of every loaded mod
+    pub fn names(&self) -> Vec<String> {
+        self.0.lock().unwrap().iter().map(|m| m.name().to_owned()).collect()
+    }
+
+    /// Every basic fabricator recipe (as json) registered by a mod, paired with that mod's name
+    pub fn basic_fabricator_recipes(&self) -> Vec<(String, String)> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|m| {
+                m.store
+                    .data()
+                    .basic_fabricator_recipes
+                    .iter()
+                    .map(|recipe| (m.name().to_owned(), recipe.clone()))
+            })
+            .collect()
+    }
+}
+
+#[derive(Resource, Default)]
+/// Block id -> (mod index, handle) of each mod that subscribed to that block changing
+struct ModBlockChangedSubscriptions(HashMap<u16, Vec<(usize, i32)>>);
+
+#[derive(Resource, Default)]
+/// Block id -> (mod index, handle) of the mod that computes this logic block's output
+struct ModLogicBlocks(HashMap<u16, (usize, i32)>);
+
+fn load_mod(engine: &Engine, path: &Path) -> Result<LoadedMod, wasmi::Error> {
+    let mod_name = path.file_stem().and_then(|x| x.to_str()).unwrap_or("unknown").to_owned();
+
+    let bytes = fs::read(path).map_err(|e| wasmi::Error::new(format!("Unable to read mod file - {e}")))?;
+    let module = Module::new(engine, &bytes)?;
+
+    let mut store = Store::new(engine, ModState::new(mod_name));
+    store.set_fuel(INIT_FUEL)?;
+
+    let mut linker = Linker::<ModState>::new(engine);
+    host_api::link(&mut linker)?;
+
+    let instance = linker.instantiate(&mut store, &module)?.start(&mut store)?;
+
+    if let Ok(init) = instance.get_typed_func::<(), ()>(&store, "cosmos_init") {
+        init.call(&mut store, ())?;
+    }
+
+    Ok(LoadedMod {
+        store,
+        instance,
+        disabled: false,
+    })
+}
+
+fn load_mods(mut commands: Commands) {
+    let mut loaded = vec![];
+
+    let Ok(entries) = fs::read_dir(MODS_DIRECTORY) else {
+        commands.insert_resource(LoadedMods::default());
+        return;
+    };
+
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config);
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|x| x.to_str()) != Some("wasm") {
+            continue;
+        }
+
+        match load_mod(&engine, &path) {
+            Ok(loaded_mod) => {
+                info!("Loaded mod {}", loaded_mod.name());
+                loaded.push(loaded_mod);
+            }
+            Err(e) => error!("Unable to load mod {path:?} - {e}"),
+        }
+    }
+
+    commands.insert_resource(LoadedMods(Mutex::new(loaded)));
+}
+
+fn register_mod_blocks(
+    mods: Res<LoadedMods>,
+    mut blocks: ResMut<Registry<Block>>,
+    mut loading: ResMut<LoadingManager>,
+    mut end_writer: EventWriter<DoneLoadingEvent>,
+    mut start_writer: EventWriter<AddLoadingEvent>,
+) {
+    let id = loading.register_loader(&mut start_writer);
+
+    for loaded_mod in mods.0.lock().unwrap().iter() {
+        for block in loaded_mod.store.data().blocks.iter() {
+            if blocks.contains(&block.unlocalized_name) {
+                warn!(
+                    "[{}] Block {} already exists - skipping.",
+                    loaded_mod.name(),
+                    block.unlocalized_name
+                );
+                continue;
+            }
+
+            blocks.register(BlockBuilder::new(&block.unlocalized_name, block.density, block.hardness, block.mining_resistance).create());
+        }
+    }
+
+    loading.finish_loading(id, &mut end_writer);
+}
+
+fn register_mod_items(
+    mods: Res<LoadedMods>,
+    mut items: ResMut<Registry<Item>>,
+    mut loading: ResMut<LoadingManager>,
+    mut end_writer: EventWriter<DoneLoadingEvent>,
+    mut start_writer: EventWriter<AddLoadingEvent>,
+) {
+    let id = loading.register_loader(&mut start_writer);
+
+    for loaded_mod in mods.0.lock().unwrap().iter() {
+        for item in loaded_mod.store.data().items.iter() {
+            if items.contains(&item.unlocalized_name) {
+                warn!("[{}] Item {} already exists - skipping.", loaded_mod.name(), item.unlocalized_name);
+                continue;
+            }
+
+            items.register(Item::new(&item.unlocalized_name, item.max_stack_size));
+        }
+    }
+
+    loading.finish_loading(id, &mut end_writer);
+}
+
+fn register_mod_hooks(
+    mods: Res<LoadedMods>,
+    blocks: Res<Registry<Block>>,
+    mut logic_blocks: ResMut<Registry<LogicBlock>>,
+    mut commands: Commands,
+) {
+    let mut subscriptions = ModBlockChangedSubscriptions::default();
+    let mut mod_logic_blocks = ModLogicBlocks::default();
+
+    for (mod_idx, loaded_mod) in mods.0.lock().unwrap().iter().enumerate() {
+        let state = loaded_mod.store.data();
+
+        for (handle, block_name) in state.block_changed_subscriptions.iter().enumerate() {
+            let Some(block) = blocks.from_id(block_name) else {
+                warn!("[{}] Subscribed to unknown block {block_name}.", loaded_mod.name());
+                continue;
+            };
+
+            subscriptions.0.entry(block.id()).or_default().push((mod_idx, handle as i32));
+        }
+
+        for (handle, logic_block) in state.logic_blocks.iter().enumerate() {
+            let Some(block) = blocks.from_id(&logic_block.unlocalized_name) else {
+                warn!(
+                    "[{}] Registered logic for unknown block {}.",
+                    loaded_mod.name(),
+                    logic_block.unlocalized_name
+                );
+                continue;
+            };
+
+            if logic_blocks.contains(block.unlocalized_name()) {
+                warn!(
+                    "[{}] {} is already a logic block - skipping.",
+                    loaded_mod.name(),
+                    block.unlocalized_name()
+                );
+                continue;
+            }
+
+            logic_blocks.register(LogicBlock::new(block, logic_block.connections));
+            mod_logic_blocks.0.insert(block.id(), (mod_idx, handle as i32));
+        }
+    }
+
+    commands.insert_resource(subscriptions);
+    commands.insert_resource(mod_logic_blocks);
+}
+
+fn on_block_changed(
+    mods: Res<LoadedMods>,
+    subscriptions: Res<ModBlockChangedSubscriptions>,
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+) {
+    if subscriptions.0.is_empty() {
+        return;
+    }
+
+    let mut mods = mods.0.lock().unwrap();
+
+    for ev in evr_block_changed.read() {
+        if ev.old_block == ev.new_block {
+            continue;
+        }
+
+        let changes = [(ev.old_block, false), (ev.new_block, true)];
+
+        for (block_id, placed) in changes {
+            let Some(subscribers) = subscriptions.0.get(&block_id) else {
+                continue;
+            };
+
+            let coords = ev.block.coords();
+
+            for &(mod_idx, handle) in subscribers {
+                let loaded_mod = &mut mods[mod_idx];
+
+                if let Some(Err(e)) = loaded_mod.call::<_, ()>(
+                    "cosmos_on_block_changed",
+                    (
+                        handle,
+                        ev.block.structure().to_bits() as i64,
+                        coords.x as i64,
+                        coords.y as i64,
+                        coords.z as i64,
+                        placed as i32,
+                    ),
+                ) {
+                    error!("[{}] Error in cosmos_on_block_changed - {e}", loaded_mod.name());
+                }
+            }
+        }
+    }
+}
+
+fn mod_logic_input_event_listener(
+    mods: Res<LoadedMods>,
+    mod_logic_blocks: Res<ModLogicBlocks>,
+    logic_blocks: Res<Registry<LogicBlock>>,
+    blocks: Res<Registry<Block>>,
+    mut evr_logic_input: EventReader<LogicInputEvent>,
+    q_logic_driver: Query<&LogicDriver>,
+    q_structure: Query<&Structure>,
+    mut q_logic_data: Query<&mut BlockLogicData>,
+    bs_params: BlockDataSystemParams,
+) {
+    if mod_logic_blocks.0.is_empty() {
+        return;
+    }
+
+    let mut mods = mods.0.lock().unwrap();
+    let bs_params = Rc::new(RefCell::new(bs_params));
+
+    for ev in evr_logic_input.read() {
+        let Ok(structure) = q_structure.get(ev.block.structure()) else {
+            continue;
+        };
+        let coords = ev.block.coords();
+        let block = structure.block_at(coords, &blocks);
+        let Some(&(mod_idx, handle)) = mod_logic_blocks.0.get(&block.id()) else {
+            continue;
+        };
+        let Some(logic_block) = logic_blocks.for_block(block) else {
+            continue;
+        };
+        let Ok(logic_driver) = q_logic_driver.get(ev.block.structure()) else {
+            continue;
+        };
+        let Some(mut logic_data) = structure.query_block_data_mut(coords, &mut q_logic_data, bs_params.clone()) else {
+            continue;
+        };
+
+        let rotation = structure.block_rotation(coords);
+        let mut inputs = [0; 6];
+        for face in logic_block.input_faces() {
+            inputs[face.index()] = logic_driver.read_input(coords, rotation.direction_of(face));
+        }
+
+        let loaded_mod = &mut mods[mod_idx];
+
+        let [right, left, top, bottom, front, back] = inputs;
+        let new_state = match loaded_mod.call::<_, i32>("cosmos_logic_compute", (handle, right, left, top, bottom, front, back)) {
+            Some(Ok(signal)) => BlockLogicData(signal),
+            Some(Err(e)) => {
+                error!("[{}] Error in cosmos_logic_compute - {e}", loaded_mod.name());
+                continue;
+            }
+            None => continue,
+        };
+
+        if **logic_data != new_state {
+            // Don't trigger unneccesary change detection.
+            **logic_data = new_state;
+        }
+    }
+}
+
+fn mod_logic_output_event_listener(
+    mod_logic_blocks: Res<ModLogicBlocks>,
+    logic_blocks: Res<Registry<LogicBlock>>,
+    blocks: Res<Registry<Block>>,
+    mut evr_logic_output: EventReader<LogicOutputEvent>,
+    mut evw_queue_logic_input: EventWriter<QueueLogicInputEvent>,
+    mut q_logic_driver: Query<&mut LogicDriver>,
+    q_structure: Query<&Structure>,
+    q_logic_data: Query<&BlockLogicData>,
+) {
+    for ev in evr_logic_output.read() {
+        let Ok(structure) = q_structure.get(ev.block.structure()) else {
+            continue;
+        };
+        let coords = ev.block.coords();
+        let block = structure.block_at(coords, &blocks);
+        if !mod_logic_blocks.0.contains_key(&block.id()) {
+            continue;
+        }
+        let Some(logic_block) = logic_blocks.for_block(block) else {
+            continue;
+        };
+        let Ok(mut logic_driver) = q_logic_driver.get_mut(ev.block.structure()) else {
+            continue;
+        };
+        let Some(&BlockLogicData(signal)) = structure.query_block_data(coords, &q_logic_data) else {
+            continue;
+        };
+
+        let rotation = structure.block_rotation(coords);
+        for face in logic_block.output_faces() {
+            let port = Port::new(coords, rotation.direction_of(face));
+            logic_driver.update_producer(port, signal, &mut evw_queue_logic_input, ev.block.structure());
+        }
+    }
+}
+
+pub(super) fn register<T: States>(app: &mut App, pre_loading_state: T, loading_state: T, post_loading_state: T) {
+    app.init_resource::<ModBlockChangedSubscriptions>()
+        .init_resource::<ModLogicBlocks>()
+        .add_systems(OnEnter(pre_loading_state), load_mods)
+        .add_systems(OnEnter(loading_state), (register_mod_blocks, register_mod_items))
+        .add_systems(OnEnter(post_loading_state), register_mod_hooks)
+        .add_systems(Update, on_block_changed.in_set(BlockEventsSet::ProcessEvents))
+        .add_systems(
+            Update,
+            mod_logic_input_event_listener
+                .in_set(LogicSystemSet::Consume)
+                .ambiguous_with(LogicSystemSet::Consume),
+        )
+        .add_systems(
+            Update,
+            mod_logic_output_event_listener
+                .in_set(LogicSystemSet::Produce)
+                .ambiguous_with(LogicSystemSet::Produce),
+        );
+}
@@ -44,7 +44,7 @@ pub enum ClientReliableMessages {
         block: StructureBlock,
         /// This is passed along with `inventory_slot` to verify that the client + server are still in sync
         block_id: u16,
-        /// The block's top face
+        /// The block's full rotation, including its sub-rotation
         block_rotation: BlockRotation,
         /// The inventory slot the block came from
         inventory_slot: u32,
@@ -27,3 +27,62 @@ pub fn deserialize<T: DeserializeOwned>(raw: &[u8]) -> Result<T, Box<bincode::Er
 
     res
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// How data serialized via [`serialize_compressed`] should be compressed.
+///
+/// The compression used is stored alongside the data, so [`deserialize_compressed`] does not need to be told which one was used.
+pub enum Compression {
+    /// The data is left uncompressed
+    None,
+    #[default]
+    /// Very fast, but doesn't compress as well as [`Compression::Zstd`]. This is what [`serialize`] uses.
+    Lz4,
+    /// Slower than [`Compression::Lz4`], but results in much smaller data. Great for large payloads like chunks.
+    ///
+    /// The level ranges from 1 to 22 - higher levels are smaller but take longer to compress.
+    Zstd(i32),
+}
+
+impl Compression {
+    fn tag(&self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Lz4 => 1,
+            Self::Zstd(_) => 2,
+        }
+    }
+}
+
+/// Serializes the data and compresses it using the given [`Compression`].
+///
+/// This is not compatible with [`deserialize`] - use [`deserialize_compressed`] instead.
+pub fn serialize_compressed<T: Serialize>(x: &T, compression: Compression) -> Vec<u8> {
+    let data = bincode::serialize(x).expect("Error serializing data!");
+
+    let mut compressed = vec![compression.tag()];
+
+    match compression {
+        Compression::None => compressed.extend(data),
+        Compression::Lz4 => compressed.extend(lz4_flex::compress_prepend_size(data.as_slice())),
+        Compression::Zstd(level) => compressed.extend(zstd::bulk::compress(data.as_slice(), level).expect("Error compressing data!")),
+    }
+
+    compressed
+}
+
+/// Deserializes data created by [`serialize_compressed`], regardless of the [`Compression`] used.
+pub fn deserialize_compressed<T: DeserializeOwned>(raw: &[u8]) -> Result<T, Box<bincode::ErrorKind>> {
+    let Some((&tag, data)) = raw.split_first() else {
+        return Err(Box::new(bincode::ErrorKind::Custom("Missing compression tag".into())));
+    };
+
+    let decompressed = match tag {
+        0 => data.to_vec(),
+        1 => lz4_flex::decompress_size_prepended(data).map_err(|_| Box::new(bincode::ErrorKind::Custom("Unable to decompress".into())))?,
+        2 => zstd::stream::decode_all(data).map_err(|_| Box::new(bincode::ErrorKind::Custom("Unable to decompress".into())))?,
+        _ => return Err(Box::new(bincode::ErrorKind::Custom(format!("Unknown compression tag {tag}")))),
+    };
+
+    bincode::deserialize::<T>(&decompressed)
+}
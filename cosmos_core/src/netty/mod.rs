@@ -7,6 +7,7 @@ pub mod client_reliable_messages;
 pub mod client_unreliable_messages;
 pub mod cosmos_encoder;
 pub mod netty_rigidbody;
+pub mod network_stats;
 #[cfg(feature = "server")]
 pub mod server;
 pub mod server_laser_cannon_system_messages;
@@ -14,6 +15,7 @@ pub mod server_registry;
 pub mod server_reliable_messages;
 pub mod server_replication;
 pub mod server_unreliable_messages;
+pub mod singleplayer;
 pub mod sync;
 pub mod system_sets;
 pub mod world_tick;
@@ -33,6 +35,7 @@ use sync::registry::RegistrySyncInit;
 #[derive(Component)]
 pub struct NoSendEntity;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 /// Network channels that the server sends to clients
 pub enum NettyChannelServer {
     /// These are reliably sent, so they are guarenteed to reach their destination.
@@ -61,6 +64,7 @@ pub enum NettyChannelServer {
     NettyEvent,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 /// Network channels that clients send to the server
 pub enum NettyChannelClient {
     /// These are reliably sent, so they are guarenteed to reach their destination.
@@ -274,5 +278,7 @@ pub fn get_local_ipaddress() -> String {
 pub(super) fn register<T: States + Clone + Copy + FreelyMutableState>(app: &mut App, registry_syncing: RegistrySyncInit<T>) {
     sync::register(app, registry_syncing);
     world_tick::register(app);
+    network_stats::register(app);
+    singleplayer::register(app);
     system_sets::register(app);
 }
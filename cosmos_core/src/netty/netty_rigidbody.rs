@@ -1,12 +1,14 @@
 //! Represents a Location, Velocity, & Rotation that is easy to send over packets.
 
+use std::f32::consts::SQRT_2;
+
 use bevy::prelude::{Entity, Quat, Transform, Vec3};
 use bevy_rapier3d::prelude::Velocity;
 use serde::{Deserialize, Serialize};
 
-use crate::physics::location::Location;
+use crate::physics::location::{Location, Sector};
 
-#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
 /// There are two ways of sending your position - relative to something or absolute.
 pub enum NettyRigidBodyLocation {
     /// Absolute is just the entity's location, relative to nothing
@@ -55,3 +57,313 @@ impl NettyRigidBody {
         self.body_vel.unwrap_or_default()
     }
 }
+
+/// Linear velocities are stored in increments of this many m/s when quantized.
+///
+/// This gives a range of roughly +/- 655 m/s.
+const LINVEL_PRECISION: f32 = 0.02;
+/// Angular velocities are stored in increments of this many rad/s when quantized.
+///
+/// This gives a range of roughly +/- 32 rad/s.
+const ANGVEL_PRECISION: f32 = 0.001;
+
+/// Positions are stored in increments of this many meters when quantized.
+///
+/// This gives a range of roughly +/- 2,000 km, which is far more than a sector's local coordinates need.
+const LOCATION_PRECISION: f32 = 0.001;
+
+/// The number of bits used to store each of the three smallest quaternion components
+const QUAT_COMPONENT_BITS: u32 = 10;
+const QUAT_COMPONENT_MAX: f32 = ((1 << QUAT_COMPONENT_BITS) - 1) as f32;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+/// A rotation packed into 32 bits using the "smallest three" method.
+///
+/// The largest component of the quaternion is dropped (and recalculated on unpacking), and the
+/// remaining three are stored using 10 bits each. The top two bits store which component was dropped.
+pub struct QuantizedQuat(u32);
+
+impl From<Quat> for QuantizedQuat {
+    fn from(quat: Quat) -> Self {
+        let quat = quat.normalize();
+        let mut components = quat.to_array();
+
+        let (largest_idx, _) = components.iter().enumerate().fold(
+            (0, f32::MIN),
+            |(max_i, max), (i, c)| if c.abs() > max { (i, c.abs()) } else { (max_i, max) },
+        );
+
+        // q and -q represent the same rotation, so we can always make the largest component positive.
+        if components[largest_idx] < 0.0 {
+            components.iter_mut().for_each(|c| *c = -*c);
+        }
+
+        let mut packed = (largest_idx as u32) << (QUAT_COMPONENT_BITS * 3);
+        let mut shift = QUAT_COMPONENT_BITS * 2;
+
+        for (_, c) in components.iter().enumerate().filter(|(i, _)| *i != largest_idx) {
+            // The smallest three components are always within [-1/sqrt(2), 1/sqrt(2)]
+            let normalized = ((c * SQRT_2 + 1.0) * 0.5).clamp(0.0, 1.0);
+            packed |= ((normalized * QUAT_COMPONENT_MAX).round() as u32) << shift;
+            shift = shift.saturating_sub(QUAT_COMPONENT_BITS);
+        }
+
+        Self(packed)
+    }
+}
+
+impl From<QuantizedQuat> for Quat {
+    fn from(value: QuantizedQuat) -> Self {
+        let largest_idx = (value.0 >> (QUAT_COMPONENT_BITS * 3)) as usize;
+
+        let mut components = [0.0; 4];
+        let mut shift = QUAT_COMPONENT_BITS * 2;
+        let mut sum_sqrd = 0.0;
+
+        for (i, c) in components.iter_mut().enumerate() {
+            if i == largest_idx {
+                continue;
+            }
+
+            let bits = (value.0 >> shift) & ((1 << QUAT_COMPONENT_BITS) - 1);
+            *c = ((bits as f32 / QUAT_COMPONENT_MAX) * 2.0 - 1.0) / SQRT_2;
+            sum_sqrd += *c * *c;
+            shift = shift.saturating_sub(QUAT_COMPONENT_BITS);
+        }
+
+        components[largest_idx] = (1.0 - sum_sqrd).max(0.0).sqrt();
+
+        Quat::from_array(components).normalize()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+/// A [`Velocity`] stored as fixed-point integers to save bandwidth
+pub struct QuantizedVelocity {
+    linvel: [i16; 3],
+    angvel: [i16; 3],
+}
+
+fn quantize_vec3(v: Vec3, precision: f32) -> [i16; 3] {
+    v.to_array()
+        .map(|c| (c / precision).round().clamp(i16::MIN as f32, i16::MAX as f32) as i16)
+}
+
+fn dequantize_vec3(v: [i16; 3], precision: f32) -> Vec3 {
+    Vec3::from_array(v.map(|c| c as f32 * precision))
+}
+
+fn quantize_position(v: Vec3) -> [i32; 3] {
+    // `as` saturates, so positions outside of the representable range are clamped
+    v.to_array().map(|c| (c / LOCATION_PRECISION).round() as i32)
+}
+
+fn dequantize_position(v: [i32; 3]) -> Vec3 {
+    Vec3::from_array(v.map(|c| c as f32 * LOCATION_PRECISION))
+}
+
+impl From<Velocity> for QuantizedVelocity {
+    fn from(value: Velocity) -> Self {
+        Self {
+            linvel: quantize_vec3(value.linvel, LINVEL_PRECISION),
+            angvel: quantize_vec3(value.angvel, ANGVEL_PRECISION),
+        }
+    }
+}
+
+impl From<QuantizedVelocity> for Velocity {
+    fn from(value: QuantizedVelocity) -> Self {
+        Velocity {
+            linvel: dequantize_vec3(value.linvel, LINVEL_PRECISION),
+            angvel: dequantize_vec3(value.angvel, ANGVEL_PRECISION),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+/// A [`NettyRigidBodyLocation`] with its position stored as fixed-point integers.
+///
+/// Absolute locations are stored as their sector plus their quantized local coordinates, and relative
+/// locations only store their quantized offset from the entity they are relative to.
+pub enum QuantizedBodyLocation {
+    /// The sector and the quantized local coordinates within it
+    Absolute(Sector, [i32; 3]),
+    /// The quantized offset from the entity provided
+    Relative([i32; 3], Entity),
+}
+
+impl From<NettyRigidBodyLocation> for QuantizedBodyLocation {
+    fn from(value: NettyRigidBodyLocation) -> Self {
+        match value {
+            NettyRigidBodyLocation::Absolute(location) => Self::Absolute(location.sector(), quantize_position(location.local)),
+            NettyRigidBodyLocation::Relative(offset, entity) => Self::Relative(quantize_position(offset), entity),
+        }
+    }
+}
+
+impl From<QuantizedBodyLocation> for NettyRigidBodyLocation {
+    fn from(value: QuantizedBodyLocation) -> Self {
+        match value {
+            QuantizedBodyLocation::Absolute(sector, local) => Self::Absolute(Location::new(dequantize_position(local), sector)),
+            QuantizedBodyLocation::Relative(offset, entity) => Self::Relative(dequantize_position(offset), entity),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+/// A [`NettyRigidBody`] with its location, rotation and velocity quantized.
+///
+/// Because every field is quantized, two bodies that compare equal would look identical to the client,
+/// so there is no need to send a body again until it no longer equals what was last sent.
+pub struct QuantizedRigidBody {
+    body_vel: Option<QuantizedVelocity>,
+    location: QuantizedBodyLocation,
+    rotation: QuantizedQuat,
+}
+
+impl QuantizedRigidBody {
+    /// Creates a delta containing only the fields of this body that differ from the `baseline`.
+    pub fn delta_from(&self, baseline: &Self) -> QuantizedRigidBodyDelta {
+        QuantizedRigidBodyDelta {
+            body_vel: (self.body_vel != baseline.body_vel).then_some(self.body_vel),
+            location: (self.location != baseline.location).then_some(self.location),
+            rotation: (self.rotation != baseline.rotation).then_some(self.rotation),
+        }
+    }
+
+    /// Applies a delta created by [`Self::delta_from`] to this body, which must be the baseline the delta was created from.
+    pub fn apply_delta(&self, delta: &QuantizedRigidBodyDelta) -> Self {
+        Self {
+            body_vel: delta.body_vel.unwrap_or(self.body_vel),
+            location: delta.location.unwrap_or(self.location),
+            rotation: delta.rotation.unwrap_or(self.rotation),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+/// The fields of a [`QuantizedRigidBody`] that differ from a baseline body the client already has.
+///
+/// Fields that are `None` are the same as the baseline.
+pub struct QuantizedRigidBodyDelta {
+    /// `Some(None)` means the body no longer has a velocity
+    body_vel: Option<Option<QuantizedVelocity>>,
+    location: Option<QuantizedBodyLocation>,
+    rotation: Option<QuantizedQuat>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+/// A body as it is sent over the unreliable channel
+pub enum ReplicatedBody {
+    /// The entire body.
+    ///
+    /// The client should keep this as the baseline for any deltas it receives for this entity afterwards.
+    Full(QuantizedRigidBody),
+    /// Only the fields that changed since the full body was sent.
+    ///
+    /// If the client does not have the full body sent at the `baseline` tick (it was lost or arrived out of order),
+    /// this cannot be applied and should be ignored. The server will send the full body again on its next snapshot.
+    Delta {
+        /// The tick the full body this is relative to was sent at
+        baseline: u64,
+        /// The fields that changed
+        delta: QuantizedRigidBodyDelta,
+    },
+}
+
+impl From<NettyRigidBody> for QuantizedRigidBody {
+    fn from(value: NettyRigidBody) -> Self {
+        Self {
+            body_vel: value.body_vel.map(|v| v.into()),
+            location: value.location.into(),
+            rotation: value.rotation.into(),
+        }
+    }
+}
+
+impl From<QuantizedRigidBody> for NettyRigidBody {
+    fn from(value: QuantizedRigidBody) -> Self {
+        Self {
+            body_vel: value.body_vel.map(|v| v.into()),
+            location: value.location.into(),
+            rotation: value.rotation.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{Entity, Quat, Vec3};
+    use bevy_rapier3d::prelude::Velocity;
+
+    use crate::physics::location::{Location, Sector};
+
+    use super::{NettyRigidBody, NettyRigidBodyLocation, QuantizedQuat, QuantizedRigidBody, QuantizedVelocity};
+
+    #[test]
+    fn quat_round_trip() {
+        let rotations = [
+            Quat::IDENTITY,
+            Quat::from_rotation_x(1.0),
+            Quat::from_rotation_y(-2.5),
+            Quat::from_euler(bevy::math::EulerRot::XYZ, 0.3, -1.2, 2.9),
+            Quat::from_xyzw(-0.5, -0.5, -0.5, -0.5),
+        ];
+
+        for rot in rotations {
+            let unpacked = Quat::from(QuantizedQuat::from(rot));
+
+            // q and -q are the same rotation
+            assert!(rot.dot(unpacked).abs() > 0.9999, "{rot} != {unpacked}");
+        }
+    }
+
+    #[test]
+    fn velocity_round_trip() {
+        let vel = Velocity {
+            linvel: Vec3::new(100.0, -35.27, 0.01),
+            angvel: Vec3::new(0.5, -3.1415, 0.0),
+        };
+
+        let unpacked = Velocity::from(QuantizedVelocity::from(vel));
+
+        assert!(unpacked.linvel.distance(vel.linvel) < 0.02);
+        assert!(unpacked.angvel.distance(vel.angvel) < 0.001);
+    }
+
+    #[test]
+    fn location_round_trip() {
+        let location = Location::new(Vec3::new(9_999.123, -4_321.5, 0.0004), Sector::new(3, -7, 1_000));
+        let body = NettyRigidBody::new(None, Quat::IDENTITY, NettyRigidBodyLocation::Absolute(location));
+
+        let NettyRigidBodyLocation::Absolute(unpacked) = NettyRigidBody::from(QuantizedRigidBody::from(body)).location else {
+            panic!("Absolute location became relative");
+        };
+
+        assert_eq!(unpacked.sector(), location.sector());
+        assert!(unpacked.local.distance(location.local) < 0.002);
+    }
+
+    #[test]
+    fn delta_only_contains_changes() {
+        let parent = Entity::from_raw(5);
+        let baseline = QuantizedRigidBody::from(NettyRigidBody::new(
+            Some(Velocity::linear(Vec3::X)),
+            Quat::IDENTITY,
+            NettyRigidBodyLocation::Relative(Vec3::new(1.0, 2.0, 3.0), parent),
+        ));
+        let moved = QuantizedRigidBody::from(NettyRigidBody::new(
+            Some(Velocity::linear(Vec3::X)),
+            Quat::IDENTITY,
+            NettyRigidBodyLocation::Relative(Vec3::new(1.5, 2.0, 3.0), parent),
+        ));
+
+        let delta = moved.delta_from(&baseline);
+
+        assert!(delta.body_vel.is_none());
+        assert!(delta.rotation.is_none());
+        assert!(delta.location.is_some());
+        assert_eq!(baseline.apply_delta(&delta), moved);
+        assert_eq!(moved.apply_delta(&moved.delta_from(&moved)), moved);
+    }
+}
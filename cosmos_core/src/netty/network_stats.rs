@@ -0,0 +1,580 @@
+//! Keeps track of how many messages are being sent + received on each channel & of each type, and how many bytes they take up.
+//!
+//! Messages should be sent + received through [`NettyServerMessages`] and [`NettyClientMessages`] rather than
+//! directly through the [`RenetServer`]/[`RenetClient`], so they are recorded here. These raw counts are then
+//! periodically sampled into the [`NetworkStats`] resource, which contains per-second rates that are useful for
+//! finding what is hogging bandwidth.
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        LazyLock, Mutex,
+    },
+    time::Duration,
+};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_renet2::renet2::{Bytes, ClientId, RenetClient, RenetServer};
+use serde::{
+    de::DeserializeOwned,
+    ser::{self, Impossible},
+    Serialize, Serializer,
+};
+
+use super::{cosmos_encoder, NettyChannelClient, NettyChannelServer};
+
+/// How often the [`NetworkStats`] resource is updated
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Every channel the server sends messages on
+const SERVER_CHANNELS: [NettyChannelServer; 11] = [
+    NettyChannelServer::Reliable,
+    NettyChannelServer::Unreliable,
+    NettyChannelServer::StructureSystems,
+    NettyChannelServer::Asteroid,
+    NettyChannelServer::DeltaLod,
+    NettyChannelServer::Inventory,
+    NettyChannelServer::SystemReplication,
+    NettyChannelServer::Registry,
+    NettyChannelServer::Shop,
+    NettyChannelServer::ComponentReplication,
+    NettyChannelServer::NettyEvent,
+];
+
+/// Every channel clients send messages on
+const CLIENT_CHANNELS: [NettyChannelClient; 7] = [
+    NettyChannelClient::Reliable,
+    NettyChannelClient::Unreliable,
+    NettyChannelClient::Inventory,
+    NettyChannelClient::Shop,
+    NettyChannelClient::ComponentReplication,
+    NettyChannelClient::NettyEvent,
+    NettyChannelClient::Registry,
+];
+
+/// Channel ids at or above this aren't recorded
+const MAX_CHANNELS: usize = 16;
+
+struct ChannelCounter {
+    messages: AtomicU64,
+    bytes: AtomicU64,
+}
+
+impl ChannelCounter {
+    const fn new() -> Self {
+        Self {
+            messages: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+        }
+    }
+
+    fn take(&self) -> (u64, u64) {
+        (self.messages.swap(0, Ordering::Relaxed), self.bytes.swap(0, Ordering::Relaxed))
+    }
+}
+
+/// Messages on [`NettyChannelServer`] channels - sent by the server & received by the client
+static SERVER_CHANNEL_COUNTERS: [ChannelCounter; MAX_CHANNELS] = [const { ChannelCounter::new() }; MAX_CHANNELS];
+/// Messages on [`NettyChannelClient`] channels - sent by the client & received by the server
+static CLIENT_CHANNEL_COUNTERS: [ChannelCounter; MAX_CHANNELS] = [const { ChannelCounter::new() }; MAX_CHANNELS];
+
+type KindCounters = LazyLock<Mutex<HashMap<MessageKind, (u64, u64)>>>;
+
+/// Messages sent by the server & received by the client, by type
+static SERVER_KIND_COUNTERS: KindCounters = LazyLock::new(Default::default);
+/// Messages sent by the client & received by the server, by type
+static CLIENT_KIND_COUNTERS: KindCounters = LazyLock::new(Default::default);
+
+fn record(
+    counters: &[ChannelCounter; MAX_CHANNELS],
+    kind_counters: &KindCounters,
+    channel: u8,
+    kind: MessageKind,
+    messages: usize,
+    bytes: usize,
+) {
+    let (messages, bytes) = (messages as u64, (messages * bytes) as u64);
+
+    if let Ok(mut kind_counters) = kind_counters.lock() {
+        let counter = kind_counters.entry(kind).or_default();
+        counter.0 += messages;
+        counter.1 += bytes;
+    }
+
+    let Some(counter) = counters.get(channel as usize) else {
+        return;
+    };
+
+    counter.messages.fetch_add(messages, Ordering::Relaxed);
+    counter.bytes.fetch_add(bytes, Ordering::Relaxed);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// What type of message was sent - the enum + variant name for enums, or just the type's name otherwise.
+pub struct MessageKind {
+    name: &'static str,
+    variant: Option<&'static str>,
+}
+
+impl MessageKind {
+    /// Finds the kind of this message, without fully serializing it
+    pub fn of<T: Serialize>(message: &T) -> Self {
+        match message.serialize(MessageKindSerializer) {
+            Err(FoundMessageKind(Some(kind))) => kind,
+            _ => Self::from_type::<T>(),
+        }
+    }
+
+    fn from_type<T>() -> Self {
+        let name = std::any::type_name::<T>();
+        // Strip the module path, but leave any generics alone
+        let path = name.split('<').next().unwrap_or(name);
+        let short_start = path.rfind("::").map(|i| i + 2).unwrap_or(0);
+
+        Self {
+            name: &name[short_start..],
+            variant: None,
+        }
+    }
+}
+
+impl Display for MessageKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.variant {
+            Some(variant) => write!(f, "{}::{variant}", self.name),
+            None => f.write_str(self.name),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Returned as an error by the [`MessageKindSerializer`] to stop serializing as soon as the kind is known
+struct FoundMessageKind(Option<MessageKind>);
+
+impl Display for FoundMessageKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl std::error::Error for FoundMessageKind {}
+
+impl ser::Error for FoundMessageKind {
+    fn custom<T: Display>(_: T) -> Self {
+        Self(None)
+    }
+}
+
+/// Serializing an enum starts with its name + variant, so this only has to look at the first thing serialized
+/// to find a message's [`MessageKind`].
+struct MessageKindSerializer;
+
+impl MessageKindSerializer {
+    fn found<T>(name: &'static str, variant: Option<&'static str>) -> Result<T, FoundMessageKind> {
+        Err(FoundMessageKind(Some(MessageKind { name, variant })))
+    }
+}
+
+/// Anything that isn't a named type falls back to [`MessageKind::from_type`]
+macro_rules! unnamed {
+    ($($method: ident($($arg: ty),*)),* $(,)?) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<Self::Ok, Self::Error> {
+                Err(FoundMessageKind(None))
+            }
+        )*
+    };
+}
+
+impl Serializer for MessageKindSerializer {
+    type Ok = ();
+    type Error = FoundMessageKind;
+    type SerializeSeq = Impossible<(), FoundMessageKind>;
+    type SerializeTuple = Impossible<(), FoundMessageKind>;
+    type SerializeTupleStruct = Impossible<(), FoundMessageKind>;
+    type SerializeTupleVariant = Impossible<(), FoundMessageKind>;
+    type SerializeMap = Impossible<(), FoundMessageKind>;
+    type SerializeStruct = Impossible<(), FoundMessageKind>;
+    type SerializeStructVariant = Impossible<(), FoundMessageKind>;
+
+    unnamed!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_none(),
+        serialize_unit(),
+    );
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _: &T) -> Result<Self::Ok, Self::Error> {
+        Err(FoundMessageKind(None))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Self::found(name, None)
+    }
+
+    fn serialize_unit_variant(self, name: &'static str, _: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+        Self::found(name, Some(variant))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, name: &'static str, _: &T) -> Result<Self::Ok, Self::Error> {
+        Self::found(name, None)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Self::found(name, Some(variant))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(FoundMessageKind(None))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(FoundMessageKind(None))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, _: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Self::found(name, None)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Self::found(name, Some(variant))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(FoundMessageKind(None))
+    }
+
+    fn serialize_struct(self, name: &'static str, _: usize) -> Result<Self::SerializeStruct, Self::Error> {
+        Self::found(name, None)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Self::found(name, Some(variant))
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A message that has been serialized with [`cosmos_encoder::serialize`], ready to be sent.
+///
+/// This keeps track of the [`MessageKind`] so the [`NetworkStats`] can tell which messages take up the most bandwidth.
+/// Cloning this is cheap, so serialize a message once if it's going to be sent to multiple clients.
+pub struct NettyMessage {
+    kind: MessageKind,
+    bytes: Bytes,
+}
+
+impl NettyMessage {
+    /// Serializes this message so it can be sent
+    pub fn new<T: Serialize>(message: &T) -> Self {
+        Self {
+            kind: MessageKind::of(message),
+            bytes: cosmos_encoder::serialize(message).into(),
+        }
+    }
+
+    /// The type of message this is
+    pub fn kind(&self) -> MessageKind {
+        self.kind
+    }
+}
+
+/// Deserializes a received message, recording it by its [`MessageKind`]
+fn deserialize_received<T: Serialize + DeserializeOwned>(
+    counters: &[ChannelCounter; MAX_CHANNELS],
+    kind_counters: &KindCounters,
+    channel: u8,
+    message: Bytes,
+) -> Result<T, Box<bincode::ErrorKind>> {
+    let deserialized = cosmos_encoder::deserialize::<T>(&message);
+
+    let kind = match &deserialized {
+        Ok(deserialized) => MessageKind::of(deserialized),
+        Err(_) => MessageKind::from_type::<T>(),
+    };
+
+    record(counters, kind_counters, channel, kind, 1, message.len());
+
+    deserialized
+}
+
+/// Sends + receives messages through the [`RenetServer`], recording them in the [`NetworkStats`]
+pub trait NettyServerMessages {
+    /// Sends this message to a single client
+    fn send_netty_message(&mut self, client_id: ClientId, channel: NettyChannelServer, message: NettyMessage);
+    /// Sends this message to every client
+    fn broadcast_netty_message(&mut self, channel: NettyChannelServer, message: NettyMessage);
+    /// Sends this message to every client except this one
+    fn broadcast_netty_message_except(&mut self, except_id: ClientId, channel: NettyChannelServer, message: NettyMessage);
+    /// Receives + deserializes the next message this client sent on this channel, if there is one
+    fn receive_netty_message<T: Serialize + DeserializeOwned>(
+        &mut self,
+        client_id: ClientId,
+        channel: NettyChannelClient,
+    ) -> Option<Result<T, Box<bincode::ErrorKind>>>;
+}
+
+impl NettyServerMessages for RenetServer {
+    fn send_netty_message(&mut self, client_id: ClientId, channel: NettyChannelServer, message: NettyMessage) {
+        let channel = u8::from(channel);
+
+        record(
+            &SERVER_CHANNEL_COUNTERS,
+            &SERVER_KIND_COUNTERS,
+            channel,
+            message.kind,
+            1,
+            message.bytes.len(),
+        );
+        self.send_message(client_id, channel, message.bytes);
+    }
+
+    fn broadcast_netty_message(&mut self, channel: NettyChannelServer, message: NettyMessage) {
+        let channel = u8::from(channel);
+
+        record(
+            &SERVER_CHANNEL_COUNTERS,
+            &SERVER_KIND_COUNTERS,
+            channel,
+            message.kind,
+            self.clients_id().len(),
+            message.bytes.len(),
+        );
+        self.broadcast_message(channel, message.bytes);
+    }
+
+    fn broadcast_netty_message_except(&mut self, except_id: ClientId, channel: NettyChannelServer, message: NettyMessage) {
+        let channel = u8::from(channel);
+
+        let recipients = self.clients_id().iter().filter(|&&id| id != except_id).count();
+        record(
+            &SERVER_CHANNEL_COUNTERS,
+            &SERVER_KIND_COUNTERS,
+            channel,
+            message.kind,
+            recipients,
+            message.bytes.len(),
+        );
+        self.broadcast_message_except(except_id, channel, message.bytes);
+    }
+
+    fn receive_netty_message<T: Serialize + DeserializeOwned>(
+        &mut self,
+        client_id: ClientId,
+        channel: NettyChannelClient,
+    ) -> Option<Result<T, Box<bincode::ErrorKind>>> {
+        let channel = u8::from(channel);
+        let message = self.receive_message(client_id, channel)?;
+
+        Some(deserialize_received(
+            &CLIENT_CHANNEL_COUNTERS,
+            &CLIENT_KIND_COUNTERS,
+            channel,
+            message,
+        ))
+    }
+}
+
+/// Sends + receives messages through the [`RenetClient`], recording them in the [`NetworkStats`]
+pub trait NettyClientMessages {
+    /// Sends this message to the server
+    fn send_netty_message(&mut self, channel: NettyChannelClient, message: NettyMessage);
+    /// Receives + deserializes the next message the server sent on this channel, if there is one
+    fn receive_netty_message<T: Serialize + DeserializeOwned>(
+        &mut self,
+        channel: NettyChannelServer,
+    ) -> Option<Result<T, Box<bincode::ErrorKind>>>;
+}
+
+impl NettyClientMessages for RenetClient {
+    fn send_netty_message(&mut self, channel: NettyChannelClient, message: NettyMessage) {
+        let channel = u8::from(channel);
+
+        record(
+            &CLIENT_CHANNEL_COUNTERS,
+            &CLIENT_KIND_COUNTERS,
+            channel,
+            message.kind,
+            1,
+            message.bytes.len(),
+        );
+        self.send_message(channel, message.bytes);
+    }
+
+    fn receive_netty_message<T: Serialize + DeserializeOwned>(
+        &mut self,
+        channel: NettyChannelServer,
+    ) -> Option<Result<T, Box<bincode::ErrorKind>>> {
+        let channel = u8::from(channel);
+        let message = self.receive_message(channel)?;
+
+        Some(deserialize_received(
+            &SERVER_CHANNEL_COUNTERS,
+            &SERVER_KIND_COUNTERS,
+            channel,
+            message,
+        ))
+    }
+}
+
+#[derive(Debug, Clone)]
+/// The number of messages + bytes on a single channel
+pub struct ChannelStats {
+    /// The name of the channel
+    pub channel: String,
+    /// Number of messages sent or received on this channel
+    pub messages: u64,
+    /// Number of bytes (after compression) sent or received on this channel
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone)]
+/// The number of messages + bytes of a single [`MessageKind`]
+pub struct MessageKindStats {
+    /// The type of message
+    pub kind: MessageKind,
+    /// Number of messages of this type sent or received
+    pub messages: u64,
+    /// Number of bytes (after compression) of this type sent or received
+    pub bytes: u64,
+}
+
+#[derive(Resource, Debug, Default)]
+/// Per-second stats of every channel & type of message sent + received, updated every second.
+///
+/// A broadcast message counts once for every client it is sent to.
+pub struct NetworkStats {
+    sent: Vec<ChannelStats>,
+    received: Vec<ChannelStats>,
+    sent_by_kind: Vec<MessageKindStats>,
+    received_by_kind: Vec<MessageKindStats>,
+}
+
+impl NetworkStats {
+    /// Every channel messages were sent on in the last sample, sorted from most to least bytes
+    pub fn sent(&self) -> &[ChannelStats] {
+        &self.sent
+    }
+
+    /// Every channel messages were received on in the last sample, sorted from most to least bytes
+    pub fn received(&self) -> &[ChannelStats] {
+        &self.received
+    }
+
+    /// Every type of message sent in the last sample, sorted from most to least bytes
+    pub fn sent_by_kind(&self) -> &[MessageKindStats] {
+        &self.sent_by_kind
+    }
+
+    /// Every type of message received in the last sample, sorted from most to least bytes
+    pub fn received_by_kind(&self) -> &[MessageKindStats] {
+        &self.received_by_kind
+    }
+
+    /// The total messages + bytes sent in the last sample
+    pub fn total_sent(&self) -> (u64, u64) {
+        Self::total(&self.sent)
+    }
+
+    /// The total messages + bytes received in the last sample
+    pub fn total_received(&self) -> (u64, u64) {
+        Self::total(&self.received)
+    }
+
+    fn total(stats: &[ChannelStats]) -> (u64, u64) {
+        stats
+            .iter()
+            .fold((0, 0), |(messages, bytes), stats| (messages + stats.messages, bytes + stats.bytes))
+    }
+}
+
+fn take_channel_stats<C: Copy + Into<u8> + std::fmt::Debug>(
+    channels: &[C],
+    counters: &[ChannelCounter; MAX_CHANNELS],
+) -> Vec<ChannelStats> {
+    let mut stats = channels
+        .iter()
+        .filter_map(|&channel| {
+            let (messages, bytes) = counters.get(channel.into() as usize)?.take();
+
+            (messages != 0).then(|| ChannelStats {
+                channel: format!("{channel:?}"),
+                messages,
+                bytes,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    stats.sort_by_key(|stats| std::cmp::Reverse(stats.bytes));
+
+    stats
+}
+
+fn take_kind_stats(kind_counters: &KindCounters) -> Vec<MessageKindStats> {
+    let Ok(mut kind_counters) = kind_counters.lock() else {
+        return vec![];
+    };
+
+    let mut stats = kind_counters
+        .drain()
+        .map(|(kind, (messages, bytes))| MessageKindStats { kind, messages, bytes })
+        .collect::<Vec<_>>();
+
+    stats.sort_by_key(|stats| std::cmp::Reverse(stats.bytes));
+
+    stats
+}
+
+fn sample_network_stats(mut network_stats: ResMut<NetworkStats>, server: Option<Res<RenetServer>>) {
+    let server_channels = take_channel_stats(&SERVER_CHANNELS, &SERVER_CHANNEL_COUNTERS);
+    let client_channels = take_channel_stats(&CLIENT_CHANNELS, &CLIENT_CHANNEL_COUNTERS);
+    let server_kinds = take_kind_stats(&SERVER_KIND_COUNTERS);
+    let client_kinds = take_kind_stats(&CLIENT_KIND_COUNTERS);
+
+    let (sent, received, sent_by_kind, received_by_kind) = if server.is_some() {
+        (server_channels, client_channels, server_kinds, client_kinds)
+    } else {
+        (client_channels, server_channels, client_kinds, server_kinds)
+    };
+
+    network_stats.sent = sent;
+    network_stats.received = received;
+    network_stats.sent_by_kind = sent_by_kind;
+    network_stats.received_by_kind = received_by_kind;
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<NetworkStats>()
+        .add_systems(Update, sample_network_stats.run_if(on_timer(SAMPLE_INTERVAL)));
+}
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Serialize, Deserialize)]
 /// Used to sync registries from server -> client
 ///
-/// For this to work, both the client and server must call their own versions of `sync_registry<T>` for the registry type.
+/// For this to work, both the client and server must call their own versions of `sync_registry<T>` (or `sync_registry_ids<T>`) for the registry type.
 pub enum RegistrySyncing {
     /// The # of registries the client must received before starting the game
     RegistryCount(u64),
@@ -16,4 +16,13 @@ pub enum RegistrySyncing {
         /// The unlocalized name of this registry
         registry_name: String,
     },
+    /// The server's numeric ids for a registry the client creates itself, such as blocks & items.
+    ///
+    /// The client uses this to convert between its own ids and the server's.
+    IdMapping {
+        /// The unlocalized name of the registry
+        registry_name: String,
+        /// The unlocalized name of every entry in the server's registry, where the index is that entry's numeric id on the server
+        unlocalized_names: Vec<String>,
+    },
 }
@@ -26,6 +26,19 @@ use crate::{
 
 use super::{netty_rigidbody::NettyRigidBody, sync::ComponentEntityIdentifier};
 
+#[derive(Debug, Serialize, Deserialize)]
+/// A single chunk sent as part of a [`ServerReliableMessages::ChunkBatch`]
+pub struct SerializedChunk {
+    /// The chunk serialized via [`crate::netty::cosmos_encoder::serialize_compressed`].
+    ///
+    /// Deserialize this with [`crate::netty::cosmos_encoder::deserialize_compressed`].
+    pub serialized_chunk: Vec<u8>,
+    /// The chunk's block data in serialized form
+    pub serialized_block_data: Option<SerializedChunkBlockData>,
+    /// The chunk's block entities that need to be requested from the server
+    pub block_entities: HashMap<(u16, ChunkBlockCoordinate), Entity>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Component)]
 /// The data for a singular block changed.
 pub struct BlockChanged {
@@ -91,16 +104,15 @@ pub enum ServerReliableMessages {
         /// The server's version of the entity.
         entity: ComponentEntityIdentifier,
     },
-    /// This represents the data for a serialized chunk.
-    ChunkData {
-        /// The structure this chunk belongs to.
+    /// A group of serialized chunks that all belong to the same structure.
+    ///
+    /// Chunks are batched together so that nearby chunks (which tend to be very similar) are compressed together,
+    /// and to reduce the overhead of sending many small messages when a large structure is streamed in.
+    ChunkBatch {
+        /// The structure these chunks belong to.
         structure_entity: Entity,
-        /// The serialized version of the chunk.
-        serialized_chunk: Vec<u8>,
-        /// The chunk's block data in serialized form
-        serialized_block_data: Option<SerializedChunkBlockData>,
-        /// The chunk's block entities that need to be requested from the server
-        block_entities: HashMap<(u16, ChunkBlockCoordinate), Entity>,
+        /// The chunks in this batch
+        chunks: Vec<SerializedChunk>,
     },
     /// This represents the data for an empty chunk.
     EmptyChunk {
@@ -8,15 +8,19 @@ use serde::{Deserialize, Serialize};
 
 use crate::structure::ship::ship_movement::ShipMovement;
 
-use super::netty_rigidbody::NettyRigidBody;
+use super::netty_rigidbody::ReplicatedBody;
 
 #[derive(Debug, Serialize, Deserialize, Component)]
 /// Movement & position data of entities
 pub enum ServerUnreliableMessages {
     /// Contains position information of entities relevant to the player that receives it
+    ///
+    /// Entities whose bodies haven't changed since the last time they were sent to this player
+    /// may be omitted, so the absence of an entity does not mean it was despawned. Bodies that have
+    /// changed are usually sent as a delta against the last full body sent for that entity.
     BulkBodies {
         /// All the entities with their corresponding rigidbody
-        bodies: Vec<(Entity, NettyRigidBody)>,
+        bodies: Vec<(Entity, ReplicatedBody)>,
         /// The server tick this was sent at
         time_stamp: u64,
     },
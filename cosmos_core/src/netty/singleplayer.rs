@@ -0,0 +1,40 @@
+//! Shared information for singleplayer, where the client launches and manages its own server.
+
+use bevy::prelude::{App, Event};
+use serde::{Deserialize, Serialize};
+
+use super::sync::events::netty_event::{IdentifiableEvent, NettyEvent, SyncedEventImpl};
+
+/// The server will print this line to stdout once it is ready to accept connections when running
+/// in singleplayer mode.
+///
+/// The client watches the server's output for this to know when it can connect.
+pub const SINGLEPLAYER_READY_MARKER: &str = "[cosmos] singleplayer server ready";
+
+/// The command line flag that tells the server it is being run as a singleplayer server
+pub const SINGLEPLAYER_ARG: &str = "--singleplayer";
+
+#[derive(Serialize, Deserialize, Event, Debug, Clone, Copy)]
+/// Sent by the client to pause or unpause the world.
+///
+/// This is ignored unless the server is running in singleplayer mode.
+pub struct SetSingleplayerPaused {
+    /// If the world should be paused
+    pub paused: bool,
+}
+
+impl IdentifiableEvent for SetSingleplayerPaused {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:set_singleplayer_paused"
+    }
+}
+
+impl NettyEvent for SetSingleplayerPaused {
+    fn event_receiver() -> super::sync::events::netty_event::EventReceiver {
+        super::sync::events::netty_event::EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_netty_event::<SetSingleplayerPaused>();
+}
@@ -1,6 +1,7 @@
 //! Exposes [`ClientReceiveComponents::ClientReceiveComponents`] - this is to remove ambiguity
 
 use super::mapping::{NetworkMapping, ServerEntity};
+use super::registry::client::RegistryIdMap;
 use super::{
     ClientAuthority, ComponentEntityIdentifier, ComponentReplicationMessage, ComponentSyncingSet, GotComponentToRemoveEvent,
     ReplicatedComponentData, SyncType, SyncableComponent, SyncedComponentId,
@@ -10,11 +11,15 @@ use crate::ecs::NeedsDespawned;
 use crate::events::block_events::BlockDataChangedEvent;
 use crate::inventory::itemstack::ItemStackData;
 use crate::inventory::Inventory;
+use crate::item::Item;
 use crate::netty::client::{LocalPlayer, NeedsLoadedFromServer};
 use crate::netty::client_reliable_messages::ClientReliableMessages;
 use crate::netty::sync::GotComponentToSyncEvent;
 use crate::netty::system_sets::NetworkingSystemsSet;
-use crate::netty::{cosmos_encoder, NettyChannelClient};
+use crate::netty::{
+    network_stats::{NettyClientMessages, NettyMessage},
+    NettyChannelClient,
+};
 use crate::netty::{NettyChannelServer, NoSendEntity};
 use crate::registry::{identifiable::Identifiable, Registry};
 use crate::structure::ship::pilot::Pilot;
@@ -46,6 +51,7 @@ fn client_deserialize_component<T: SyncableComponent>(
     mut ev_reader: EventReader<GotComponentToSyncEvent>,
     mut commands: Commands,
     mapping: Res<NetworkMapping>,
+    item_ids: Res<RegistryIdMap<Item>>,
     q_t: Query<&T>,
 ) {
     for ev in ev_reader.read() {
@@ -65,6 +71,11 @@ fn client_deserialize_component<T: SyncableComponent>(
                 continue;
             };
 
+            let Some(mapped) = mapped.convert_item_ids_server_to_client(&item_ids) else {
+                warn!("Couldn't convert item ids for {}!", T::get_component_unlocalized_name());
+                continue;
+            };
+
             component = mapped;
 
             if matches!(T::get_sync_type(), SyncType::BothAuthoritative(_)) {
@@ -173,9 +184,9 @@ fn client_send_components<T: SyncableComponent>(
         .collect::<Vec<ReplicatedComponentData>>();
 
     if !data_to_sync.is_empty() {
-        client.send_message(
+        client.send_netty_message(
             NettyChannelClient::ComponentReplication,
-            cosmos_encoder::serialize(&ComponentReplicationMessage::ComponentReplication {
+            NettyMessage::new(&ComponentReplicationMessage::ComponentReplication {
                 component_id: id.id(),
                 replicated: data_to_sync,
             }),
@@ -239,9 +250,9 @@ fn client_send_removed_components<T: SyncableComponent>(
             }
         }
 
-        client.send_message(
+        client.send_netty_message(
             NettyChannelClient::ComponentReplication,
-            cosmos_encoder::serialize(&ComponentReplicationMessage::RemovedComponent {
+            NettyMessage::new(&ComponentReplicationMessage::RemovedComponent {
                 component_id: id.id(),
                 entity_identifier,
             }),
@@ -324,9 +335,9 @@ fn client_receive_components(
         .is_some()
     });
 
-    while let Some(message) = client.receive_message(NettyChannelServer::ComponentReplication) {
-        let msg: ComponentReplicationMessage = cosmos_encoder::deserialize(&message).unwrap_or_else(|e| {
-            panic!("Failed to parse component replication message from server! Bytes:\n{message:?}\nError: {e:?}");
+    while let Some(message) = client.receive_netty_message::<ComponentReplicationMessage>(NettyChannelServer::ComponentReplication) {
+        let msg: ComponentReplicationMessage = message.unwrap_or_else(|e| {
+            panic!("Failed to parse component replication message from server!\nError: {e:?}");
         });
 
         match msg {
@@ -490,9 +501,9 @@ fn get_entity_identifier_info(
                 if !q_block_data.contains(x) {
                     error!("Component got for block data but had no block data component - requesting entity. (Client: {x:?})");
 
-                    client.send_message(
+                    client.send_netty_message(
                         NettyChannelClient::Reliable,
-                        cosmos_encoder::serialize(&ClientReliableMessages::RequestEntityData {
+                        NettyMessage::new(&ClientReliableMessages::RequestEntityData {
                             entity: server_data_entity,
                         }),
                     );
@@ -650,6 +661,7 @@ pub(super) fn sync_component_client<T: SyncableComponent>(app: &mut App) {
                     .chain()
                     .run_if(resource_exists::<NetworkMapping>)
                     .run_if(resource_exists::<Registry<SyncedComponentId>>)
+                    .run_if(resource_exists::<RegistryIdMap<Item>>)
                     .in_set(ComponentSyncingSet::ReceiveComponents),
             );
         }
@@ -667,6 +679,7 @@ pub(super) fn sync_component_client<T: SyncableComponent>(app: &mut App) {
                     .chain()
                     .run_if(resource_exists::<NetworkMapping>)
                     .run_if(resource_exists::<Registry<SyncedComponentId>>)
+                    .run_if(resource_exists::<RegistryIdMap<Item>>)
                     .in_set(ComponentSyncingSet::ReceiveComponents),
             );
         }
@@ -11,7 +11,11 @@ use renet2::RenetClient;
 
 use crate::{netty::NettyChannelServer, registry::Registry};
 use crate::{
-    netty::{cosmos_encoder, system_sets::NetworkingSystemsSet, NettyChannelClient},
+    netty::{
+        network_stats::{NettyClientMessages, NettyMessage},
+        system_sets::NetworkingSystemsSet,
+        NettyChannelClient,
+    },
     registry::identifiable::Identifiable,
 };
 
@@ -87,9 +91,9 @@ fn send_events<T: NettyEvent>(
 
         let serialized = bincode::serialize(&ev.0).unwrap();
 
-        client.send_message(
+        client.send_netty_message(
             NettyChannelClient::NettyEvent,
-            cosmos_encoder::serialize(&NettyEventMessage::SendNettyEvent {
+            NettyMessage::new(&NettyEventMessage::SendNettyEvent {
                 component_id: registered_event.id(),
                 raw_data: serialized,
             }),
@@ -98,14 +102,11 @@ fn send_events<T: NettyEvent>(
 }
 
 fn receive_events(mut client: ResMut<RenetClient>, mut evw_got_event: EventWriter<GotNetworkEvent>) {
-    while let Some(message) = client.receive_message(NettyChannelServer::NettyEvent) {
-        let Some(msg) = cosmos_encoder::deserialize::<NettyEventMessage>(&message)
-            .map(Some)
-            .unwrap_or_else(|e| {
-                error!("Failed to parse netty event message from server!\nBytes: {message:?}\nError: {e:?}");
-                None
-            })
-        else {
+    while let Some(message) = client.receive_netty_message::<NettyEventMessage>(NettyChannelServer::NettyEvent) {
+        let Some(msg) = message.map(Some).unwrap_or_else(|e| {
+            error!("Failed to parse netty event message from server!\nError: {e:?}");
+            None
+        }) else {
             error!("Error deserializing message into `NettyEventMessage`");
             continue;
         };
@@ -10,7 +10,11 @@ use bevy::{
 use renet2::{ClientId, RenetServer};
 
 use crate::{
-    netty::{cosmos_encoder, system_sets::NetworkingSystemsSet, NettyChannelClient, NettyChannelServer},
+    netty::{
+        network_stats::{NettyMessage, NettyServerMessages},
+        system_sets::NetworkingSystemsSet,
+        NettyChannelClient, NettyChannelServer,
+    },
     registry::identifiable::Identifiable,
 };
 use crate::{registry::Registry, state::GameState};
@@ -102,8 +106,8 @@ impl<E: NettyEvent> NettyEventWriter<'_, E> {
 
 fn receive_event(mut server: ResMut<RenetServer>, mut evw_got_event: EventWriter<GotNetworkEvent>) {
     for client_id in server.clients_id().into_iter() {
-        while let Some(message) = server.receive_message(client_id, NettyChannelClient::NettyEvent) {
-            let msg: NettyEventMessage = cosmos_encoder::deserialize(&message).unwrap_or_else(|e| {
+        while let Some(message) = server.receive_netty_message::<NettyEventMessage>(client_id, NettyChannelClient::NettyEvent) {
+            let msg: NettyEventMessage = message.unwrap_or_else(|e| {
                 panic!("Failed to parse component replication message from client ({client_id})!\nError: {e:?}");
             });
 
@@ -160,18 +164,18 @@ fn send_events<T: NettyEvent>(
         let serialized = bincode::serialize(&ev.event).unwrap();
 
         if let Some(client_id) = ev.client_id {
-            server.send_message(
+            server.send_netty_message(
                 client_id,
                 NettyChannelServer::NettyEvent,
-                cosmos_encoder::serialize(&NettyEventMessage::SendNettyEvent {
+                NettyMessage::new(&NettyEventMessage::SendNettyEvent {
                     component_id: registered_event.id(),
                     raw_data: serialized,
                 }),
             );
         } else {
-            server.broadcast_message(
+            server.broadcast_netty_message(
                 NettyChannelServer::NettyEvent,
-                cosmos_encoder::serialize(&NettyEventMessage::SendNettyEvent {
+                NettyMessage::new(&NettyEventMessage::SendNettyEvent {
                     component_id: registered_event.id(),
                     raw_data: serialized,
                 }),
@@ -197,6 +197,14 @@ pub trait SyncableComponent: Serialize + DeserializeOwned + Clone + std::fmt::De
     fn convert_entities_client_to_server(&self, _mapping: &self::mapping::NetworkMapping) -> Option<Self> {
         Some(self.clone())
     }
+
+    #[cfg(feature = "client")]
+    /// Converts any of the server's item ids this contains to the client's ids for those items.
+    ///
+    /// Return None if this fails.
+    fn convert_item_ids_server_to_client(self, _item_ids: &self::registry::client::RegistryIdMap<crate::item::Item>) -> Option<Self> {
+        Some(self)
+    }
 }
 
 #[derive(Event, Debug)]
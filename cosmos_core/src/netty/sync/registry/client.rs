@@ -1,7 +1,13 @@
 //! Handles client-side registry syncing logic
 
 use crate::{
-    netty::{cosmos_encoder, server_registry::RegistrySyncing, system_sets::NetworkingSystemsSet, NettyChannelClient, NettyChannelServer},
+    netty::{
+        cosmos_encoder,
+        network_stats::{NettyClientMessages, NettyMessage},
+        server_registry::RegistrySyncing,
+        system_sets::NetworkingSystemsSet,
+        NettyChannelClient, NettyChannelServer,
+    },
     registry::{identifiable::Identifiable, Registry},
 };
 use bevy::{
@@ -9,9 +15,9 @@ use bevy::{
     ecs::{
         event::{Event, EventReader, EventWriter},
         schedule::{common_conditions::resource_exists, IntoSystemConfigs},
-        system::{Res, ResMut, Resource},
+        system::{Commands, Res, ResMut, Resource},
     },
-    log::{error, info},
+    log::{error, info, warn},
     prelude::{IntoSystemSetConfigs, States, SystemSet},
     reflect::erased_serde::Serialize,
     state::{
@@ -21,6 +27,7 @@ use bevy::{
 };
 use bevy_renet2::renet2::RenetClient;
 use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
 
 use crate::ecs::add_multi_statebound_resource;
 
@@ -30,9 +37,81 @@ struct ReceivedRegistryEvent {
     registry_name: String,
 }
 
+#[derive(Event)]
+struct ReceivedIdMappingEvent {
+    unlocalized_names: Vec<String>,
+    registry_name: String,
+}
+
 #[derive(Debug, Default, Resource)]
 struct RegistriesLeftToSync(Option<i64>);
 
+#[derive(Resource, Debug)]
+/// Converts between the server's & client's numeric ids for a registry synced via [`super::sync_registry_ids`].
+///
+/// Anything sent to or received from the server that contains a numeric id from this registry must be converted
+/// using this, since the server & client may have registered things in a different order.
+pub struct RegistryIdMap<T: Identifiable> {
+    server_to_client: Vec<Option<u16>>,
+    client_to_server: Vec<Option<u16>>,
+    identity: bool,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Identifiable + Send + Sync> RegistryIdMap<T> {
+    /// Creates the mapping between the server's ids (where each index of `server_unlocalized_names` is that entry's server id)
+    /// and this registry's ids.
+    ///
+    /// Any entries that only exist on one side are left unmapped.
+    pub fn new(registry: &Registry<T>, server_unlocalized_names: &[String]) -> Self {
+        let server_to_client = server_unlocalized_names
+            .iter()
+            .map(|name| {
+                let client_id = registry.from_id(name).map(|x| x.id());
+                if client_id.is_none() {
+                    error!("The server has {name} in {}, but the client does not!", registry.name());
+                }
+                client_id
+            })
+            .collect::<Vec<Option<u16>>>();
+
+        let mut client_to_server = vec![None; registry.iter().len()];
+        for (server_id, client_id) in server_to_client.iter().enumerate() {
+            if let Some(client_id) = client_id {
+                client_to_server[*client_id as usize] = Some(server_id as u16);
+            }
+        }
+
+        let identity = server_to_client.len() == client_to_server.len()
+            && server_to_client
+                .iter()
+                .enumerate()
+                .all(|(server_id, &client_id)| client_id == Some(server_id as u16));
+
+        Self {
+            server_to_client,
+            client_to_server,
+            identity,
+            _phantom: Default::default(),
+        }
+    }
+
+    /// Converts the server's id to the client's id, or returns None if the client has no entry for it
+    pub fn to_client(&self, server_id: u16) -> Option<u16> {
+        self.server_to_client.get(server_id as usize).copied().flatten()
+    }
+
+    /// Converts the client's id to the server's id, or returns None if the server has no entry for it
+    pub fn to_server(&self, client_id: u16) -> Option<u16> {
+        self.client_to_server.get(client_id as usize).copied().flatten()
+    }
+
+    /// Returns true if the server & client ids are all the same, meaning no conversion is needed
+    pub fn is_identity(&self) -> bool {
+        self.identity
+    }
+}
+
 fn sync<T: Identifiable + Serialize + DeserializeOwned + std::fmt::Debug>(
     mut registry: ResMut<Registry<T>>,
     mut ev_reader: EventReader<ReceivedRegistryEvent>,
@@ -58,6 +137,36 @@ fn sync<T: Identifiable + Serialize + DeserializeOwned + std::fmt::Debug>(
     }
 }
 
+fn sync_ids<T: Identifiable + Send + Sync + 'static>(
+    registry: Res<Registry<T>>,
+    mut ev_reader: EventReader<ReceivedIdMappingEvent>,
+    mut left_to_sync: ResMut<RegistriesLeftToSync>,
+    mut commands: Commands,
+) {
+    for ev in ev_reader.read() {
+        if ev.registry_name != registry.name() {
+            continue;
+        }
+
+        let new_amt = left_to_sync.0.unwrap_or(0) - 1;
+
+        left_to_sync.0 = Some(new_amt);
+
+        info!("Got id mapping from server: {}! Need {} more.", ev.registry_name, new_amt);
+
+        let id_map = RegistryIdMap::new(&registry, &ev.unlocalized_names);
+
+        if !id_map.is_identity() {
+            warn!(
+                "The server's {} ids do not match the client's - they will be converted.",
+                ev.registry_name
+            );
+        }
+
+        commands.insert_resource(id_map);
+    }
+}
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 enum LoadingRegistriesSet {
     LoadRegistriesFromServer,
@@ -68,6 +177,17 @@ enum TransitionStateSet {
     TransitionState,
 }
 
+/// Call this function on the client-side to receive the server's ids for this registry
+pub(super) fn sync_registry_ids<T: Identifiable + Send + Sync + 'static>(app: &mut App) {
+    app.add_systems(
+        Update,
+        sync_ids::<T>
+            .before(TransitionStateSet::TransitionState)
+            .in_set(LoadingRegistriesSet::LoadRegistriesFromServer)
+            .ambiguous_with(LoadingRegistriesSet::LoadRegistriesFromServer),
+    );
+}
+
 /// Call this function on the client-side to signal that this registry should be synced with the server
 pub(super) fn sync_registry<T: Identifiable + Serialize + DeserializeOwned + std::fmt::Debug>(app: &mut App) {
     app.add_systems(
@@ -82,10 +202,11 @@ pub(super) fn sync_registry<T: Identifiable + Serialize + DeserializeOwned + std
 fn registry_listen_netty(
     mut client: ResMut<RenetClient>,
     mut ev_writer: EventWriter<ReceivedRegistryEvent>,
+    mut evw_id_mapping: EventWriter<ReceivedIdMappingEvent>,
     mut registry_count: ResMut<RegistriesLeftToSync>,
 ) {
-    while let Some(message) = client.receive_message(NettyChannelServer::Registry) {
-        let msg: RegistrySyncing = cosmos_encoder::deserialize(&message).expect("Unable to parse registry sync from server");
+    while let Some(message) = client.receive_netty_message::<RegistrySyncing>(NettyChannelServer::Registry) {
+        let msg: RegistrySyncing = message.expect("Unable to parse registry sync from server");
 
         match msg {
             RegistrySyncing::RegistryCount(count) => {
@@ -98,6 +219,15 @@ fn registry_listen_netty(
                     registry_name,
                 });
             }
+            RegistrySyncing::IdMapping {
+                registry_name,
+                unlocalized_names,
+            } => {
+                evw_id_mapping.send(ReceivedIdMappingEvent {
+                    unlocalized_names,
+                    registry_name,
+                });
+            }
         }
     }
 }
@@ -123,9 +253,9 @@ pub(super) fn register<T: States + FreelyMutableState + Clone + Copy>(
             if loading_registries.0.is_some_and(|x| x == 0) {
                 info!("Got all registries from server - loading world!");
                 state_changer.set(loading_world_state);
-                client.send_message(
+                client.send_netty_message(
                     NettyChannelClient::Registry,
-                    cosmos_encoder::serialize(&crate::netty::client_registry::RegistrySyncing::FinishedReceivingRegistries),
+                    NettyMessage::new(&crate::netty::client_registry::RegistrySyncing::FinishedReceivingRegistries),
                 )
             }
         };
@@ -140,7 +270,8 @@ pub(super) fn register<T: States + FreelyMutableState + Clone + Copy>(
             .chain()
             .run_if(in_state(loading_data_state)),
     )
-    .add_event::<ReceivedRegistryEvent>();
+    .add_event::<ReceivedRegistryEvent>()
+    .add_event::<ReceivedIdMappingEvent>();
 
     add_multi_statebound_resource::<RegistriesLeftToSync, T>(app, connecting_state, loading_data_state);
 }
@@ -7,7 +7,7 @@ use serde::{de::DeserializeOwned, Serialize};
 use crate::registry::identifiable::Identifiable;
 
 #[cfg(feature = "client")]
-mod client;
+pub mod client;
 #[cfg(feature = "server")]
 pub mod server;
 
@@ -21,6 +21,18 @@ pub fn sync_registry<T: Identifiable + Serialize + DeserializeOwned + std::fmt::
     client::sync_registry::<T>(app);
 }
 
+/// Ensures the client knows the server's numeric id for every entry in this registry when it connects.
+///
+/// Use this instead of [`sync_registry`] for registries that both the server & client create themselves (such as blocks & items),
+/// since their numeric ids may not match if they registered things in a different order. The client will have a
+/// [`client::RegistryIdMap<T>`] resource to convert between the two.
+pub fn sync_registry_ids<T: Identifiable + Send + Sync + 'static>(app: &mut App) {
+    #[cfg(feature = "server")]
+    server::sync_registry_ids::<T>(app);
+    #[cfg(feature = "client")]
+    client::sync_registry_ids::<T>(app);
+}
+
 #[derive(Clone, Copy)]
 /// Used to setup the registry syncing systems
 pub enum RegistrySyncInit<T: States + Clone + Copy> {
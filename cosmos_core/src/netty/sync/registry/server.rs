@@ -2,7 +2,13 @@
 
 use crate::{
     entities::player::Player,
-    netty::{cosmos_encoder, server_registry::RegistrySyncing, system_sets::NetworkingSystemsSet, NettyChannelServer},
+    netty::{
+        cosmos_encoder,
+        network_stats::{NettyMessage, NettyServerMessages},
+        server_registry::RegistrySyncing,
+        system_sets::NetworkingSystemsSet,
+        NettyChannelServer,
+    },
     registry::{identifiable::Identifiable, Registry},
 };
 use bevy::{
@@ -43,10 +49,10 @@ fn sync<'a, T: Identifiable + Serialize + Deserialize<'a>>(
             continue;
         };
 
-        server.send_message(
+        server.send_netty_message(
             player.id(),
             NettyChannelServer::Registry,
-            cosmos_encoder::serialize(&RegistrySyncing::Registry {
+            NettyMessage::new(&RegistrySyncing::Registry {
                 serialized: cosmos_encoder::serialize(registry.as_ref()),
                 registry_name: registry.name().into(),
             }),
@@ -54,6 +60,29 @@ fn sync<'a, T: Identifiable + Serialize + Deserialize<'a>>(
     }
 }
 
+fn sync_ids<T: Identifiable + Send + Sync + 'static>(
+    q_player: Query<&Player>,
+    mut server: ResMut<RenetServer>,
+    mut ev_reader: EventReader<SyncRegistriesEvent>,
+    registry: Res<Registry<T>>,
+) {
+    for ev in ev_reader.read() {
+        let Ok(player) = q_player.get(ev.player_entity) else {
+            warn!("Missing player entity from player join event!");
+            continue;
+        };
+
+        server.send_netty_message(
+            player.id(),
+            NettyChannelServer::Registry,
+            NettyMessage::new(&RegistrySyncing::IdMapping {
+                registry_name: registry.name().into(),
+                unlocalized_names: registry.iter().map(|x| x.unlocalized_name().to_owned()).collect(),
+            }),
+        );
+    }
+}
+
 fn incr_registries_to_sync(mut n_registries: ResMut<NumRegistriesToSync>) {
     n_registries.0 += 1;
 }
@@ -72,10 +101,10 @@ fn send_number_of_registries(
 
         info!("Sending {n_registries:?}");
 
-        server.send_message(
+        server.send_netty_message(
             player.id(),
             NettyChannelServer::Registry,
-            cosmos_encoder::serialize(&RegistrySyncing::RegistryCount(n_registries.0)),
+            NettyMessage::new(&RegistrySyncing::RegistryCount(n_registries.0)),
         );
     }
 }
@@ -91,6 +120,12 @@ pub(super) fn sync_registry<'a, T: Identifiable + Serialize + Deserialize<'a>>(a
         .add_systems(Update, sync::<T>.after(send_number_of_registries));
 }
 
+/// Call this function on the server-side to signal that this registry's ids should be sent to the client
+pub(super) fn sync_registry_ids<T: Identifiable + Send + Sync + 'static>(app: &mut App) {
+    app.add_systems(Startup, incr_registries_to_sync.in_set(IncrementSet::Increment))
+        .add_systems(Update, sync_ids::<T>.after(send_number_of_registries));
+}
+
 #[allow(unused)] // LSP assumes this function is never used, even though it's just feature flagged
 pub(super) fn register<T: States>(app: &mut App, playing_state: T) {
     app.add_event::<SyncRegistriesEvent>();
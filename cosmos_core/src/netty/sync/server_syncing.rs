@@ -9,7 +9,10 @@ use crate::inventory::itemstack::ItemStackData;
 use crate::netty::server::ServerLobby;
 use crate::netty::sync::{GotComponentToRemoveEvent, GotComponentToSyncEvent};
 use crate::netty::system_sets::NetworkingSystemsSet;
-use crate::netty::{cosmos_encoder, NettyChannelClient, NettyChannelServer, NoSendEntity};
+use crate::netty::{
+    network_stats::{NettyMessage, NettyServerMessages},
+    NettyChannelClient, NettyChannelServer, NoSendEntity,
+};
 use crate::persistence::LoadingDistance;
 use crate::physics::location::{CosmosBundleSet, Location};
 use crate::registry::{identifiable::Identifiable, Registry};
@@ -247,10 +250,10 @@ fn server_send_component<T: SyncableComponent>(
             })
             .collect::<Vec<ReplicatedComponentData>>();
 
-        server.send_message(
+        server.send_netty_message(
             player.id(),
             NettyChannelServer::ComponentReplication,
-            cosmos_encoder::serialize(&ComponentReplicationMessage::ComponentReplication {
+            NettyMessage::new(&ComponentReplicationMessage::ComponentReplication {
                 component_id: id.id(),
                 replicated: replicated_data,
             }),
@@ -299,9 +302,9 @@ fn server_sync_removed_components<T: SyncableComponent>(
             ComponentEntityIdentifier::Entity(removed_ent)
         };
 
-        server.broadcast_message(
+        server.broadcast_netty_message(
             NettyChannelServer::ComponentReplication,
-            cosmos_encoder::serialize(&ComponentReplicationMessage::RemovedComponent {
+            NettyMessage::new(&ComponentReplicationMessage::RemovedComponent {
                 component_id: id.id(),
                 entity_identifier,
             }),
@@ -368,10 +371,10 @@ fn on_request_component<T: SyncableComponent>(
             return;
         };
 
-        server.send_message(
+        server.send_netty_message(
             client_id,
             NettyChannelServer::ComponentReplication,
-            cosmos_encoder::serialize(&ComponentReplicationMessage::ComponentReplication {
+            NettyMessage::new(&ComponentReplicationMessage::ComponentReplication {
                 component_id: id.id(),
                 replicated: replicated_component,
             }),
@@ -386,8 +389,10 @@ fn server_receive_components(
     q_structure_systems: Query<&StructureSystems>,
 ) {
     for client_id in server.clients_id().into_iter() {
-        while let Some(message) = server.receive_message(client_id, NettyChannelClient::ComponentReplication) {
-            let Ok(msg) = cosmos_encoder::deserialize::<ComponentReplicationMessage>(&message) else {
+        while let Some(message) =
+            server.receive_netty_message::<ComponentReplicationMessage>(client_id, NettyChannelClient::ComponentReplication)
+        {
+            let Ok(msg) = message else {
                 warn!("Bad deserialization");
                 continue;
             };
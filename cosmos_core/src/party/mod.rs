@@ -0,0 +1,223 @@
+//! Parties are small groups of players playing together.
+//!
+//! Members of a party can ping locations for each other, see the waypoints their party leader has shared, chat
+//! privately, and see how each other are doing on their HUD. Parties are managed by the server, which sends each player
+//! their party whenever it changes.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entities::health::Health,
+    netty::sync::events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+    physics::location::Location,
+};
+
+/// The most players that can be in a single party
+pub const MAX_PARTY_SIZE: usize = 8;
+
+/// The most waypoints a party leader can share at once
+pub const MAX_SHARED_WAYPOINTS: usize = 16;
+
+/// How long (in seconds) pings stay visible for
+pub const PING_DURATION: f32 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// A waypoint the party leader has shared with the rest of their party
+pub struct SharedWaypoint {
+    /// Unique within this party
+    pub id: u32,
+    /// Where this waypoint is
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// A group of players playing together
+pub struct Party {
+    /// The name of the player who leads this party
+    pub leader: String,
+    /// The names of every player in this party, including the leader
+    pub members: Vec<String>,
+    /// The waypoints the leader has shared
+    pub waypoints: Vec<SharedWaypoint>,
+}
+
+impl Party {
+    /// Creates a party with only its leader in it
+    pub fn new(leader: impl Into<String>) -> Self {
+        let leader = leader.into();
+
+        Self {
+            members: vec![leader.clone()],
+            leader,
+            waypoints: vec![],
+        }
+    }
+
+    /// Returns true if the player with this name is in this party
+    pub fn is_member(&self, player_name: &str) -> bool {
+        self.members.iter().any(|x| x == player_name)
+    }
+
+    /// Returns true if the player with this name leads this party
+    pub fn is_leader(&self, player_name: &str) -> bool {
+        self.leader == player_name
+    }
+}
+
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+/// Sent by the server to a player whenever their party changes
+pub struct PartyUpdatedEvent {
+    /// The player's party, or `None` if they aren't in one
+    pub party: Option<Party>,
+}
+
+impl IdentifiableEvent for PartyUpdatedEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:party_updated"
+    }
+}
+
+impl NettyEvent for PartyUpdatedEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// How a party member is doing
+pub struct PartyMemberStatus {
+    /// The member's name
+    pub name: String,
+    /// If this member is currently playing
+    pub online: bool,
+    /// The member's health, if they have any
+    pub health: Option<Health>,
+    /// The combined (strength, max strength) of the shields of the ship this member is piloting, if they're piloting one
+    pub shield: Option<(f32, f32)>,
+}
+
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+/// Regularly sent by the server to every party member with how the members of their party are doing
+pub struct PartyStatusEvent {
+    /// Every member of the party, in the same order as [`Party::members`]
+    pub members: Vec<PartyMemberStatus>,
+}
+
+impl IdentifiableEvent for PartyStatusEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:party_status"
+    }
+}
+
+impl NettyEvent for PartyStatusEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the server to move the player to another member of their party.
+///
+/// The client is in charge of its player's position, so this is how admins are teleported to party members.
+pub struct PartyTeleportEvent(pub Location);
+
+impl IdentifiableEvent for PartyTeleportEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:party_teleport"
+    }
+}
+
+impl NettyEvent for PartyTeleportEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to ping a location for the rest of their party
+pub struct SendPingEvent {
+    /// Where to ping
+    pub location: Location,
+}
+
+impl IdentifiableEvent for SendPingEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:send_ping"
+    }
+}
+
+impl NettyEvent for SendPingEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+/// Sent by the server to every member of a party when one of them pings a location
+pub struct PartyPingEvent {
+    /// The name of the player who sent the ping
+    pub player: String,
+    /// Where they pinged
+    pub location: Location,
+}
+
+impl IdentifiableEvent for PartyPingEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:party_ping"
+    }
+}
+
+impl NettyEvent for PartyPingEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client when a party leader shares a new waypoint
+pub struct AddSharedWaypointEvent {
+    /// Where the waypoint is
+    pub location: Location,
+}
+
+impl IdentifiableEvent for AddSharedWaypointEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:add_shared_waypoint"
+    }
+}
+
+impl NettyEvent for AddSharedWaypointEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client when a party leader stops sharing a waypoint
+pub struct RemoveSharedWaypointEvent {
+    /// The [`SharedWaypoint::id`] of the waypoint to remove
+    pub id: u32,
+}
+
+impl IdentifiableEvent for RemoveSharedWaypointEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:remove_shared_waypoint"
+    }
+}
+
+impl NettyEvent for RemoveSharedWaypointEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_netty_event::<PartyUpdatedEvent>()
+        .add_netty_event::<PartyStatusEvent>()
+        .add_netty_event::<PartyTeleportEvent>()
+        .add_netty_event::<SendPingEvent>()
+        .add_netty_event::<PartyPingEvent>()
+        .add_netty_event::<AddSharedWaypointEvent>()
+        .add_netty_event::<RemoveSharedWaypointEvent>();
+}
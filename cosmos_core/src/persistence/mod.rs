@@ -6,15 +6,20 @@ use bevy::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::physics::location::{Location, SECTOR_DIMENSIONS};
+use crate::{
+    netty::sync::{sync_component, IdentifiableComponent, SyncType, SyncableComponent},
+    physics::location::{Location, SECTOR_DIMENSIONS},
+};
 
 /// The default loading distance for structures
 pub const LOAD_DISTANCE: f32 = SECTOR_DIMENSIONS * 8.0;
 
-#[derive(Component, Debug, Reflect, Clone, Copy, Serialize, Deserialize)]
+#[derive(Component, Debug, Reflect, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 /// Use this to have a custom distance for something to be unloaded.
 ///
 /// This distance is in # of sectors. The default is 10.
+///
+/// The server sends this to clients, so they unload things at the same distance the server does.
 pub struct LoadingDistance {
     load_distance: u32,
     unload_distance: u32,
@@ -67,7 +72,27 @@ impl LoadingDistance {
     pub fn should_load(&self, loc_a: &Location, loc_b: &Location) -> bool {
         loc_a.is_within_reasonable_range(loc_b) && loc_a.relative_coords_to(loc_b).abs().max_element() < self.load_block_distance()
     }
-    // TODO: Make function for should unload
+
+    /// Returns true if these two locations are far enough apart that this should be unloaded
+    pub fn should_unload(&self, loc_a: &Location, loc_b: &Location) -> bool {
+        !loc_a.is_within_reasonable_range(loc_b) || loc_a.relative_coords_to(loc_b).abs().max_element() > self.unload_block_distance()
+    }
+}
+
+impl IdentifiableComponent for LoadingDistance {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:loading_distance"
+    }
+}
+
+impl SyncableComponent for LoadingDistance {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+
+    fn validate(&self) -> bool {
+        self.load_distance <= self.unload_distance
+    }
 }
 
 #[derive(Component, Debug, Reflect, Default, Clone, Copy)]
@@ -75,5 +100,7 @@ impl LoadingDistance {
 pub struct Blueprintable;
 
 pub(super) fn register(app: &mut App) {
+    sync_component::<LoadingDistance>(app);
+
     app.register_type::<LoadingDistance>().register_type::<Blueprintable>();
 }
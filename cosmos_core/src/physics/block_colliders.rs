@@ -10,7 +10,11 @@ use bevy::{
 use bevy_rapier3d::prelude::Collider;
 
 use crate::{
-    block::Block,
+    block::{
+        block_shape::BlockShape,
+        specific_blocks::{crop::CROPS, ladder::LADDER_BLOCK},
+        Block,
+    },
     registry::{create_registry, identifiable::Identifiable, Registry},
 };
 
@@ -123,6 +127,20 @@ fn register_custom_colliders(blocks: Res<Registry<Block>>, mut registry: ResMut<
         ));
     }
 
+    for crop in CROPS {
+        for stage in crop.stages.iter().filter(|s| blocks.contains(s)) {
+            registry.register(BlockCollider::new(
+                BlockColliderType::Custom(vec![CustomCollider {
+                    collider: Collider::cuboid(0.5, 0.2, 0.5),
+                    mode: BlockColliderMode::SensorCollider,
+                    rotation: Quat::IDENTITY,
+                    offset: Vec3::new(0.0, -(0.5 - 0.2), 0.0),
+                }]),
+                *stage,
+            ));
+        }
+    }
+
     if blocks.contains("cosmos:ramp") {
         registry.register(BlockCollider::new(
             BlockColliderType::Custom(vec![
@@ -166,6 +184,19 @@ fn register_custom_colliders(blocks: Res<Registry<Block>>, mut registry: ResMut<
         ));
     }
 
+    if blocks.contains(LADDER_BLOCK) {
+        registry.register(BlockCollider::new(
+            BlockColliderType::Custom(vec![CustomCollider {
+                // Only the rails against the back of the block are solid, so players can stand in the ladder to climb it
+                collider: Collider::cuboid(0.4, 0.5, 0.05),
+                mode: BlockColliderMode::NormalCollider,
+                rotation: Quat::IDENTITY,
+                offset: Vec3::new(0.0, 0.0, 0.45),
+            }]),
+            LADDER_BLOCK,
+        ));
+    }
+
     if blocks.contains("cosmos:power_cable") {
         registry.register(BlockCollider::new(
             BlockColliderType::Connected(Box::new(ConnectedCollider {
@@ -259,11 +290,30 @@ fn register_custom_colliders(blocks: Res<Registry<Block>>, mut registry: ResMut<
     }
 }
 
+/// Creates a collider that matches a non-cube [`BlockShape`]
+fn shape_collider(shape: BlockShape) -> Option<BlockColliderType> {
+    let points = shape.vertices().iter().map(|&v| Vec3::from(v)).collect::<Vec<_>>();
+
+    Collider::convex_hull(&points).map(|collider| {
+        BlockColliderType::Custom(vec![CustomCollider {
+            rotation: Quat::IDENTITY,
+            collider,
+            mode: BlockColliderMode::NormalCollider,
+            offset: Vec3::ZERO,
+        }])
+    })
+}
+
 fn register_all_colliders(blocks: Res<Registry<Block>>, mut registry: ResMut<Registry<BlockCollider>>) {
     for block in blocks.iter() {
         if registry.from_id(block.unlocalized_name()).is_none() {
+            let collider = match block.shape() {
+                BlockShape::Cube => None,
+                shape => shape_collider(shape),
+            };
+
             registry.register(BlockCollider::new(
-                BlockColliderType::Full(BlockColliderMode::NormalCollider),
+                collider.unwrap_or(BlockColliderType::Full(BlockColliderMode::NormalCollider)),
                 block.unlocalized_name(),
             ));
         }
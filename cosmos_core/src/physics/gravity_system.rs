@@ -3,13 +3,19 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::{ExternalImpulse, ReadMassProperties, RigidBody, RigidBodyDisabled};
 
-use crate::{netty::system_sets::NetworkingSystemsSet, structure::planet::Planet};
+use crate::{
+    block::specific_blocks::ladder::Climbing, entities::player::spectator::Spectator, netty::system_sets::NetworkingSystemsSet,
+    structure::planet::Planet,
+};
 
 use super::location::{Location, LocationPhysicsSet};
 
 fn gravity_system(
     emitters: Query<(Entity, &GravityEmitter, &GlobalTransform, &Location)>,
-    mut receiver: Query<(Entity, &Location, &ReadMassProperties, &RigidBody, Option<&mut ExternalImpulse>), Without<RigidBodyDisabled>>,
+    mut receiver: Query<
+        (Entity, &Location, &ReadMassProperties, &RigidBody, Option<&mut ExternalImpulse>),
+        (Without<RigidBodyDisabled>, Without<Spectator>, Without<Climbing>),
+    >,
     time: Res<Time>,
     mut commands: Commands,
 ) {
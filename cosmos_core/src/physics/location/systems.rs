@@ -11,9 +11,12 @@ use bevy::utils::HashSet;
 #[cfg(feature = "server")]
 use bevy_rapier3d::{
     plugin::{RapierConfiguration, RapierContextEntityLink},
-    prelude::RapierContextSimulation,
+    prelude::{RapierContextSimulation, RigidBody},
 };
 
+#[cfg(feature = "server")]
+use crate::structure::Structure;
+
 use crate::{
     netty::system_sets::NetworkingSystemsSet,
     physics::player_world::{PlayerWorld, WorldWithin},
@@ -42,6 +45,17 @@ use crate::netty::client::LocalPlayer;
 /// point for a [`PlayerWorld`].
 pub struct Anchor;
 
+#[cfg(feature = "server")]
+#[derive(Component, Debug)]
+/// An [`Anchor`] that was automatically added to a structure that was too far away from every other [`Anchor`].
+///
+/// This gives distant clusters of entities their own physics world centered on them, rather than simulating them
+/// far from the origin of whatever world happens to be closest. Once this comes near another [`Anchor`], it stops
+/// being one so the two clusters can share a world again.
+///
+/// The server steps every world in parallel. Clusters are grouped only by distance - not by how much is in them.
+pub struct ClusterAnchor;
+
 fn loc_from_trans(
     entity: Entity,
     q_trans: &Query<&Transform>,
@@ -188,6 +202,83 @@ fn create_physics_world(commands: &mut Commands) -> RapierContextEntityLink {
     RapierContextEntityLink(rw)
 }
 
+/// Cluster anchors only give up their world once they are this close to another anchor, so they don't
+/// constantly switch between being and not being an anchor at the edge of [`WORLD_SWITCH_DISTANCE`].
+#[cfg(feature = "server")]
+const CLUSTER_MERGE_DISTANCE_SQRD: f32 = WORLD_SWITCH_DISTANCE_SQRD / 4.0;
+
+/// Turns dynamic structures that are too far from every [`Anchor`] into [`ClusterAnchor`]s, each with their own world.
+///
+/// Only structures can start a cluster, so stray projectiles don't each get their own world.
+#[cfg(feature = "server")]
+fn anchor_distant_clusters(
+    q_candidates: Query<(Entity, &Location, &RigidBody), (With<Structure>, Without<Anchor>, Without<Parent>)>,
+    q_anchors: Query<&Location, With<Anchor>>,
+    mut commands: Commands,
+) {
+    let mut new_anchors: Vec<Location> = vec![];
+
+    for (entity, location, rb) in q_candidates.iter() {
+        if *rb != RigidBody::Dynamic {
+            continue;
+        }
+
+        let near_anchor = q_anchors
+            .iter()
+            .chain(new_anchors.iter())
+            .any(|anchor_loc| anchor_loc.distance_sqrd(location) < WORLD_SWITCH_DISTANCE_SQRD);
+
+        if near_anchor {
+            continue;
+        }
+
+        new_anchors.push(*location);
+
+        let link = create_physics_world(&mut commands);
+
+        info!("Creating new physics world for distant cluster!");
+        let world_entity = commands
+            .spawn((Name::new("Cluster World"), PlayerWorld { player: entity }, *location, link))
+            .id();
+
+        commands
+            .entity(entity)
+            .insert((Anchor, ClusterAnchor, WorldWithin(world_entity), link));
+    }
+}
+
+/// Removes [`ClusterAnchor`]s that have come close to another [`Anchor`], since they will already share its world.
+///
+/// This runs after [`move_anchors_between_worlds`], so anchors this close are always in the same world.
+#[cfg(feature = "server")]
+fn merge_nearby_clusters(
+    q_cluster_anchors: Query<(Entity, &Location, &WorldWithin), With<ClusterAnchor>>,
+    q_anchors: Query<(Entity, &Location), With<Anchor>>,
+    mut q_worlds: Query<&mut PlayerWorld>,
+    mut commands: Commands,
+) {
+    let mut merged = HashSet::new();
+
+    for (entity, location, world_within) in q_cluster_anchors.iter() {
+        let Some((other_anchor, _)) = q_anchors.iter().find(|&(other, other_loc)| {
+            other != entity && !merged.contains(&other) && other_loc.distance_sqrd(location) < CLUSTER_MERGE_DISTANCE_SQRD
+        }) else {
+            continue;
+        };
+
+        merged.insert(entity);
+
+        // The world needs to stay centered around an anchor
+        if let Ok(mut world) = q_worlds.get_mut(world_within.0) {
+            if world.player == entity {
+                world.player = other_anchor;
+            }
+        }
+
+        commands.entity(entity).remove::<(Anchor, ClusterAnchor)>();
+    }
+}
+
 #[cfg(feature = "server")]
 fn move_anchors_between_worlds(
     q_anchors: Query<(Entity, &Location), (With<WorldWithin>, With<Anchor>)>,
@@ -435,7 +526,14 @@ pub(super) fn register(app: &mut App) {
             apply_set_position,
             reposition_worlds_around_anchors,
             #[cfg(feature = "server")]
-            (move_anchors_between_worlds, move_non_anchors_between_worlds, remove_empty_worlds).chain(),
+            (
+                anchor_distant_clusters,
+                move_anchors_between_worlds,
+                merge_nearby_clusters,
+                move_non_anchors_between_worlds,
+                remove_empty_worlds,
+            )
+                .chain(),
             sync_transforms_and_locations,
         )
             .chain()
@@ -8,10 +8,12 @@ pub mod gravity_system;
 pub mod location;
 pub mod player_world;
 mod stop_near_unloaded_chunks;
+pub mod structure_mass;
 pub mod structure_physics;
 
 pub(super) fn register<T: States + Copy>(app: &mut App, post_loading_state: T) {
     structure_physics::register(app);
+    structure_mass::register(app);
     gravity_system::register(app);
     location::register(app);
     player_world::register(app);
@@ -0,0 +1,251 @@
+//! Computes the mass, center of mass and inertia tensor of full structures from their blocks.
+//!
+//! This is kept up to date as blocks are placed & removed, and is given to rapier as the structure's
+//! mass properties. This means heavier and longer structures accelerate and turn slower than small ones.
+
+use bevy::{
+    math::{DMat3, DVec3},
+    prelude::*,
+};
+use bevy_rapier3d::{
+    dynamics::{AdditionalMassProperties, MassProperties},
+    rapier::{
+        na::{Matrix3, Point3},
+        prelude::MassProperties as RapierMassProperties,
+    },
+};
+
+use crate::{
+    block::{block_events::BlockEventsSet, Block},
+    events::block_events::BlockChangedEvent,
+    netty::system_sets::NetworkingSystemsSet,
+    registry::Registry,
+    structure::{events::StructureLoadedEvent, loading::StructureLoadingSet, systems::StructureSystemsSet, Structure},
+};
+
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Reflect)]
+/// The mass of a full structure and how it's spread out over its blocks.
+///
+/// Each block is treated as a 1x1x1 cube with a mass equal to its density. Everything here is
+/// relative to the structure's center, in the structure's local space.
+///
+/// This is stored as sums over every block (rather than the final values) so that blocks can be added & removed
+/// without having to iterate over the entire structure.
+pub struct StructureMass {
+    mass: f64,
+    /// Sum of `mass * position`
+    first_moment: DVec3,
+    /// Sum of `mass * (x^2, y^2, z^2)`
+    second_moment_diagonal: DVec3,
+    /// Sum of `mass * (xy, xz, yz)`
+    second_moment_products: DVec3,
+}
+
+impl StructureMass {
+    /// Adds a block with this mass at this position relative to the structure's center
+    pub fn block_added(&mut self, mass: f32, position: Vec3) {
+        self.add(mass as f64, position.as_dvec3());
+    }
+
+    /// Removes a block with this mass at this position relative to the structure's center
+    pub fn block_removed(&mut self, mass: f32, position: Vec3) {
+        self.add(-(mass as f64), position.as_dvec3());
+    }
+
+    fn add(&mut self, mass: f64, p: DVec3) {
+        if mass == 0.0 {
+            return;
+        }
+
+        self.mass += mass;
+        self.first_moment += mass * p;
+        self.second_moment_diagonal += mass * p * p;
+        self.second_moment_products += mass * DVec3::new(p.x * p.y, p.x * p.z, p.y * p.z);
+
+        // Prevents floating point errors from leaving a tiny amount of mass behind once every block is removed
+        if self.mass <= f64::EPSILON {
+            *self = Self::default();
+        }
+    }
+
+    /// The total mass of the structure
+    pub fn mass(&self) -> f32 {
+        self.mass as f32
+    }
+
+    /// The center of mass, relative to the structure's center
+    pub fn center_of_mass(&self) -> Vec3 {
+        self.center_of_mass_f64().as_vec3()
+    }
+
+    fn center_of_mass_f64(&self) -> DVec3 {
+        if self.mass <= 0.0 {
+            return DVec3::ZERO;
+        }
+
+        self.first_moment / self.mass
+    }
+
+    /// The inertia tensor about the center of mass, in the structure's local space
+    pub fn inertia_tensor(&self) -> Mat3 {
+        self.inertia_tensor_f64().as_mat3()
+    }
+
+    fn inertia_tensor_f64(&self) -> DMat3 {
+        if self.mass <= 0.0 {
+            return DMat3::ZERO;
+        }
+
+        let d = self.second_moment_diagonal;
+        let p = self.second_moment_products;
+
+        // Sum of m * r * r^T for every block
+        let second_moment = DMat3::from_cols(DVec3::new(d.x, p.x, p.y), DVec3::new(p.x, d.y, p.z), DVec3::new(p.y, p.z, d.z));
+
+        // Each block is a unit cube, which has an inertia of m * (1^2 + 1^2) / 12 = m / 6 about its own center
+        let about_origin = DMat3::from_diagonal(DVec3::splat(d.x + d.y + d.z + self.mass / 6.0)) - second_moment;
+
+        // Parallel axis theorem to move it from the structure's center to the center of mass
+        let com = self.center_of_mass_f64();
+        let com_outer = DMat3::from_cols(com * com.x, com * com.y, com * com.z);
+
+        about_origin - (DMat3::from_diagonal(DVec3::splat(com.length_squared())) - com_outer) * self.mass
+    }
+
+    /// How hard it is to rotate the structure around this axis (in the structure's local space).
+    ///
+    /// Returns 0.0 if the structure has no mass.
+    pub fn angular_inertia_about(&self, local_axis: Vec3) -> f32 {
+        let axis = local_axis.normalize_or_zero();
+
+        axis.dot(self.inertia_tensor() * axis)
+    }
+
+    /// Converts this into the mass properties rapier uses
+    pub fn mass_properties(&self) -> MassProperties {
+        if self.mass <= 0.0 {
+            return MassProperties::default();
+        }
+
+        let com = self.center_of_mass();
+        let inertia = Matrix3::from_column_slice(&self.inertia_tensor().to_cols_array());
+
+        MassProperties::from_rapier(RapierMassProperties::with_inertia_matrix(
+            Point3::new(com.x, com.y, com.z),
+            self.mass(),
+            inertia,
+        ))
+    }
+}
+
+fn compute_mass_on_load(
+    mut commands: Commands,
+    mut evr_structure_loaded: EventReader<StructureLoadedEvent>,
+    q_structure: Query<&Structure>,
+    blocks: Res<Registry<Block>>,
+) {
+    for ev in evr_structure_loaded.read() {
+        let Ok(structure) = q_structure.get(ev.structure_entity) else {
+            continue;
+        };
+
+        if !matches!(structure, Structure::Full(_)) {
+            continue;
+        }
+
+        let mut mass = StructureMass::default();
+        for coords in structure.all_blocks_iter(false) {
+            mass.block_added(
+                structure.block_at(coords, &blocks).density(),
+                structure.block_relative_position(coords),
+            );
+        }
+
+        if let Some(mut ecmds) = commands.get_entity(ev.structure_entity) {
+            ecmds.insert(mass);
+        }
+    }
+}
+
+fn update_mass(
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    mut q_structure: Query<(&Structure, &mut StructureMass)>,
+    blocks: Res<Registry<Block>>,
+) {
+    for ev in evr_block_changed.read() {
+        if ev.old_block == ev.new_block {
+            continue;
+        }
+
+        let Ok((structure, mut mass)) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        let position = structure.block_relative_position(ev.block.coords());
+
+        mass.block_removed(blocks.from_numeric_id(ev.old_block).density(), position);
+        mass.block_added(blocks.from_numeric_id(ev.new_block).density(), position);
+    }
+}
+
+fn apply_mass_properties(mut commands: Commands, q_changed_mass: Query<(Entity, &StructureMass), Changed<StructureMass>>) {
+    for (ent, mass) in q_changed_mass.iter() {
+        commands
+            .entity(ent)
+            .insert(AdditionalMassProperties::MassProperties(mass.mass_properties()));
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            compute_mass_on_load
+                .in_set(StructureLoadingSet::StructureLoaded)
+                .before(StructureSystemsSet::InitSystems),
+            (update_mass, apply_mass_properties)
+                .chain()
+                .in_set(BlockEventsSet::ProcessEvents)
+                .in_set(NetworkingSystemsSet::Between),
+        ),
+    )
+    .register_type::<StructureMass>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longer_structures_are_harder_to_turn() {
+        let mut short = StructureMass::default();
+        let mut long = StructureMass::default();
+
+        for z in -2..=2 {
+            short.block_added(1.0, Vec3::new(0.0, 0.0, z as f32));
+        }
+        for z in -10..=10 {
+            long.block_added(1.0, Vec3::new(0.0, 0.0, z as f32));
+        }
+
+        // Spinning around its length is just as easy for both
+        assert!((short.angular_inertia_about(Vec3::Z) / short.mass() - long.angular_inertia_about(Vec3::Z) / long.mass()).abs() < 0.001);
+        assert!(long.angular_inertia_about(Vec3::Y) / long.mass() > short.angular_inertia_about(Vec3::Y) / short.mass());
+    }
+
+    #[test]
+    fn removing_blocks_undoes_adding_them() {
+        let mut mass = StructureMass::default();
+
+        mass.block_added(2.0, Vec3::new(4.0, 0.0, 0.0));
+        mass.block_added(2.0, Vec3::new(-4.0, 0.0, 0.0));
+        mass.block_added(6.0, Vec3::new(0.0, 0.0, 3.0));
+
+        assert!((mass.center_of_mass() - Vec3::new(0.0, 0.0, 1.8)).length() < 0.001);
+
+        mass.block_removed(6.0, Vec3::new(0.0, 0.0, 3.0));
+
+        assert_eq!(mass.center_of_mass(), Vec3::ZERO);
+        assert!((mass.angular_inertia_about(Vec3::Y) - (4.0 * 16.0 + 4.0 / 6.0)).abs() < 0.001);
+    }
+}
@@ -416,6 +416,12 @@ fn generate_chunk_collider(
         &mut mass,
     );
 
+    // Full structures get their mass from their [`super::structure_mass::StructureMass`] instead, which accounts for
+    // where each block's mass actually is.
+    if matches!(structure, Structure::Full(_)) {
+        mass = 0.0;
+    }
+
     let mut all_colliders = Vec::with_capacity(3);
 
     if !colliders.is_empty() {
@@ -2,7 +2,8 @@
 
 use crate::netty::sync::registry::RegistrySyncInit;
 use crate::{
-    block, chat, crafting, debug, economy, ecs, entities, fluid, inventory, logic, netty, persistence, projectiles, shop, universe, utils,
+    block, chat, crafting, debug, economy, ecs, entities, fluid, inventory, logic, modding, netty, party, persistence, projectiles, shop,
+    universe, utils,
 };
 use crate::{blockitems, structure};
 use crate::{events, loader};
@@ -118,8 +119,10 @@ impl<T: States + Clone + Copy + FreelyMutableState> Plugin for CosmosCorePlugin<
         debug::register(app);
         utils::register(app);
         chat::register(app);
+        party::register(app);
         entities::register(app);
         crafting::register(app);
+        modding::register(app, self.pre_loading_state, self.loading_state, self.post_loading_state);
     }
 }
 
@@ -45,6 +45,7 @@ pub const LASER_LIVE_TIME: Duration = Duration::from_secs(5);
 pub struct LaserCollideEvent {
     entity_hit: Entity,
     local_position_hit: Vec3,
+    local_direction: Vec3,
     laser_strength: f32,
     causer: Option<Causer>,
 }
@@ -67,6 +68,11 @@ impl LaserCollideEvent {
         self.local_position_hit
     }
 
+    /// The direction this laser was travelling relative to the entity it hit's rotation. This is normalized.
+    pub fn local_direction(&self) -> Vec3 {
+        self.local_direction
+    }
+
     /// Returns the entity that caused this laser to be fired
     pub fn causer(&self) -> Option<Causer> {
         self.causer
@@ -227,25 +233,25 @@ fn send_laser_hit_events(
 
                 if let Ok(parent) = chunk_parent_query.get(entity) {
                     if let Ok(transform) = transform_query.get(parent.get()) {
-                        let lph = Quat::from_affine3(&transform.affine())
-                            .inverse()
-                            .mul_vec3(pos - transform.translation());
+                        let inv_rot = Quat::from_affine3(&transform.affine()).inverse();
+                        let lph = inv_rot.mul_vec3(pos - transform.translation());
 
                         event_writer.send(LaserCollideEvent {
                             entity_hit: entity,
                             local_position_hit: lph,
+                            local_direction: inv_rot.mul_vec3(ray_direction),
                             laser_strength: laser.strength,
                             causer: causer.copied(),
                         });
                     }
                 } else if let Ok(transform) = transform_query.get(entity) {
-                    let lph = Quat::from_affine3(&transform.affine())
-                        .inverse()
-                        .mul_vec3(pos - transform.translation());
+                    let inv_rot = Quat::from_affine3(&transform.affine()).inverse();
+                    let lph = inv_rot.mul_vec3(pos - transform.translation());
 
                     event_writer.send(LaserCollideEvent {
                         entity_hit: entity,
                         local_position_hit: lph,
+                        local_direction: inv_rot.mul_vec3(ray_direction),
                         laser_strength: laser.strength,
                         causer: causer.copied(),
                     });
@@ -42,6 +42,23 @@ pub struct Shop {
 }
 
 impl Shop {
+    /// Converts the id of every item in this shop. Entries `remap` returns None for are removed.
+    ///
+    /// This is only meant for converting between the server's & client's ids for the same items.
+    pub fn remap_item_ids(&mut self, remap: impl Fn(u16) -> Option<u16>) {
+        self.contents.retain_mut(|entry| {
+            let (ShopEntry::Selling { item_id, .. } | ShopEntry::Buying { item_id, .. }) = entry;
+
+            match remap(*item_id) {
+                Some(id) => {
+                    *item_id = id;
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+
     /// Buys an item from this shop, or returns an error if the purchase was unsuccessful
     pub fn buy(&mut self, item_id: u16, quantity: u32, credits: &mut Credits) -> Result<(), ShopPurchaseError> {
         for entry in self.contents.iter_mut() {
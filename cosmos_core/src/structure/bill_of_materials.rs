@@ -0,0 +1,103 @@
+//! Works out which items are needed to build a structure
+
+use bevy::utils::HashMap;
+
+use crate::{block::Block, blockitems::BlockItems, registry::Registry};
+
+use super::Structure;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Every item (and how many of it) needed to build something
+pub struct BillOfMaterials {
+    items: HashMap<u16, u32>,
+    /// Blocks that have no item, so can't be built
+    unobtainable_blocks: u32,
+}
+
+impl BillOfMaterials {
+    /// Computes the items needed to place every block in this structure
+    pub fn from_structure(structure: &Structure, blocks: &Registry<Block>, block_items: &BlockItems) -> Self {
+        let mut bill = Self::default();
+
+        for coords in structure.all_blocks_iter(false) {
+            match block_items.item_from_block(structure.block_at(coords, blocks)) {
+                Some(item_id) => bill.add(item_id, 1),
+                None => bill.unobtainable_blocks += 1,
+            }
+        }
+
+        bill
+    }
+
+    /// Adds this many of this item to what is needed
+    pub fn add(&mut self, item_id: u16, quantity: u32) {
+        if quantity != 0 {
+            *self.items.entry(item_id).or_default() += quantity;
+        }
+    }
+
+    /// How many of this item are needed
+    pub fn quantity(&self, item_id: u16) -> u32 {
+        self.items.get(&item_id).copied().unwrap_or(0)
+    }
+
+    /// Iterates over every item needed, along with how many of it are needed
+    pub fn iter(&self) -> impl Iterator<Item = (u16, u32)> + '_ {
+        self.items.iter().map(|(&id, &quantity)| (id, quantity))
+    }
+
+    /// The total number of items needed
+    pub fn total_items(&self) -> u32 {
+        self.items.values().sum()
+    }
+
+    /// How many blocks have no item, and will be left out when this is built
+    pub fn unobtainable_blocks(&self) -> u32 {
+        self.unobtainable_blocks
+    }
+
+    /// Returns true if no items are needed
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// What is still needed once the items already available are used.
+    ///
+    /// `available` returns how many of an item (by id) are available.
+    pub fn missing(&self, available: impl Fn(u16) -> u32) -> Self {
+        let mut missing = Self {
+            unobtainable_blocks: self.unobtainable_blocks,
+            ..Default::default()
+        };
+
+        for (item_id, quantity) in self.iter() {
+            missing.add(item_id, quantity.saturating_sub(available(item_id)));
+        }
+
+        missing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_only_counts_shortfall() {
+        let mut bill = BillOfMaterials::default();
+        bill.add(1, 10);
+        bill.add(2, 5);
+        bill.add(1, 2);
+
+        assert_eq!(bill.quantity(1), 12);
+        assert_eq!(bill.total_items(), 17);
+
+        let missing = bill.missing(|id| if id == 1 { 20 } else { 3 });
+
+        assert_eq!(missing.quantity(1), 0);
+        assert_eq!(missing.quantity(2), 2);
+        assert_eq!(missing.iter().count(), 1);
+
+        assert!(bill.missing(|_| u32::MAX).is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+//! Keeps track of how many of each block a structure has, and where certain blocks are.
+//!
+//! Structure systems can use this instead of iterating over every block in a structure
+//! whenever they need to know about the blocks they care about.
+
+use bevy::{
+    prelude::{App, EventReader, IntoSystemConfigs, Query, Res, Resource, Update},
+    utils::{HashMap, HashSet},
+};
+
+use crate::{
+    block::{block_events::BlockEventsSet, blocks::AIR_BLOCK_ID, Block},
+    events::block_events::BlockChangedEvent,
+    netty::system_sets::NetworkingSystemsSet,
+    registry::identifiable::Identifiable,
+};
+
+use super::{
+    coordinates::BlockCoordinate, events::StructureLoadedEvent, loading::StructureLoadingSet, systems::StructureSystemsSet, Structure,
+};
+
+#[derive(Resource, Debug, Default)]
+/// The blocks whose positions are stored in every structure's [`BlockCounts`].
+///
+/// Blocks should be added to this in or before [`crate::state::GameState::PostLoading`], since
+/// positions are only recorded for blocks that were tracked when the structure was loaded.
+pub struct TrackedBlockPositions(HashSet<u16>);
+
+impl TrackedBlockPositions {
+    /// Every structure will now keep track of where this block is
+    pub fn track(&mut self, block: &Block) {
+        self.0.insert(block.id());
+    }
+
+    /// Returns true if the positions of this block are being tracked
+    pub fn is_tracked(&self, block_id: u16) -> bool {
+        self.0.contains(&block_id)
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+/// How many of each block a structure has, and where its [`TrackedBlockPositions`] blocks are.
+///
+/// This is kept up to date from [`BlockChangedEvent`]s once the structure is loaded, and is updated
+/// before [`StructureSystemsSet::UpdateSystemsBlocks`].
+pub struct BlockCounts {
+    counts: HashMap<u16, u32>,
+    positions: HashMap<u16, HashSet<BlockCoordinate>>,
+}
+
+impl BlockCounts {
+    /// The number of this block in the structure
+    pub fn count(&self, block_id: u16) -> u32 {
+        self.counts.get(&block_id).copied().unwrap_or(0)
+    }
+
+    /// The total number of blocks in this structure, not including air
+    pub fn total(&self) -> u32 {
+        self.counts.values().sum()
+    }
+
+    /// Every block id present in this structure paired with how many of them there are. Does not include air.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, u32)> + '_ {
+        self.counts.iter().map(|(&id, &count)| (id, count))
+    }
+
+    /// Where every block of this type is in the structure.
+    ///
+    /// This will be empty if the block is not in [`TrackedBlockPositions`].
+    pub fn positions(&self, block_id: u16) -> impl Iterator<Item = BlockCoordinate> + '_ {
+        self.positions
+            .get(&block_id)
+            .into_iter()
+            .flat_map(|positions| positions.iter().copied())
+    }
+
+    fn block_added(&mut self, block_id: u16, coords: BlockCoordinate, tracked: &TrackedBlockPositions) {
+        if block_id == AIR_BLOCK_ID {
+            return;
+        }
+
+        *self.counts.entry(block_id).or_default() += 1;
+
+        if tracked.is_tracked(block_id) {
+            self.positions.entry(block_id).or_default().insert(coords);
+        }
+    }
+
+    fn block_removed(&mut self, block_id: u16, coords: BlockCoordinate) {
+        if let Some(count) = self.counts.get_mut(&block_id) {
+            *count = count.saturating_sub(1);
+
+            if *count == 0 {
+                self.counts.remove(&block_id);
+            }
+        }
+
+        if let Some(positions) = self.positions.get_mut(&block_id) {
+            positions.remove(&coords);
+
+            if positions.is_empty() {
+                self.positions.remove(&block_id);
+            }
+        }
+    }
+}
+
+fn count_blocks_on_load(
+    mut evr_structure_loaded: EventReader<StructureLoadedEvent>,
+    mut q_structure: Query<&mut Structure>,
+    tracked: Res<TrackedBlockPositions>,
+) {
+    for ev in evr_structure_loaded.read() {
+        let Ok(mut structure) = q_structure.get_mut(ev.structure_entity) else {
+            continue;
+        };
+
+        if !matches!(structure.as_ref(), Structure::Full(_)) {
+            continue;
+        }
+
+        let mut counts = BlockCounts::default();
+        for coords in structure.all_blocks_iter(false) {
+            counts.block_added(structure.block_id_at(coords), coords, &tracked);
+        }
+
+        if let Structure::Full(fs) = structure.as_mut() {
+            *fs.block_counts_mut() = counts;
+        }
+    }
+}
+
+fn update_block_counts(
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    mut q_structure: Query<&mut Structure>,
+    tracked: Res<TrackedBlockPositions>,
+) {
+    for ev in evr_block_changed.read() {
+        if ev.old_block == ev.new_block {
+            continue;
+        }
+
+        let Ok(mut structure) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        let Structure::Full(fs) = structure.as_mut() else {
+            continue;
+        };
+
+        // Blocks set before the structure is loaded will be counted once it finishes loading
+        if !fs.is_loaded() {
+            continue;
+        }
+
+        let coords = ev.block.coords();
+        let counts = fs.block_counts_mut();
+        counts.block_removed(ev.old_block, coords);
+        counts.block_added(ev.new_block, coords, &tracked);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<TrackedBlockPositions>().add_systems(
+        Update,
+        (
+            count_blocks_on_load
+                .in_set(StructureLoadingSet::StructureLoaded)
+                .before(StructureSystemsSet::InitSystems),
+            update_block_counts
+                .in_set(BlockEventsSet::ProcessEvents)
+                .before(StructureSystemsSet::UpdateSystemsBlocks)
+                .in_set(NetworkingSystemsSet::Between),
+        ),
+    );
+}
@@ -112,6 +112,17 @@ impl BlockStorage {
         coords.flatten(CHUNK_DIMENSIONS, CHUNK_DIMENSIONS)
     }
 
+    /// Replaces every block id in this storage with the result of `remap`.
+    ///
+    /// This is used to convert block ids from one [`Registry<Block>`] to another, such as the server's to the client's.
+    pub fn remap_block_ids(&mut self, remap: impl Fn(u16) -> u16) {
+        for block in self.blocks.iter_mut() {
+            *block = remap(*block);
+        }
+
+        self.non_air_blocks = self.blocks.iter().filter(|&&b| b != AIR_BLOCK_ID).count() as u32;
+    }
+
     /// Sets every block within this to be this block + rotation
     pub fn fill(&mut self, block: &Block, block_rotation: BlockRotation) {
         for z in 0..self.length {
@@ -149,9 +160,10 @@ impl BlockStorer for BlockStorage {
 
         let index = Self::flatten(coords);
 
-        self.block_info[index].set_rotation(block_rotation);
-
         if self.blocks[index] != id {
+            // Things like paint shouldn't carry over to a different block
+            self.block_info[index] = BlockInfo::default();
+
             if self.blocks[index] == AIR_BLOCK_ID {
                 self.non_air_blocks += 1;
             } else if id == AIR_BLOCK_ID {
@@ -160,6 +172,8 @@ impl BlockStorer for BlockStorage {
 
             self.blocks[index] = id;
         }
+
+        self.block_info[index].set_rotation(block_rotation);
     }
 
     fn block_at(&self, coords: ChunkBlockCoordinate) -> u16 {
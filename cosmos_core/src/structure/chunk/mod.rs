@@ -15,6 +15,7 @@ use bevy::utils::HashMap;
 use serde::{Deserialize, Serialize};
 
 use crate::block::data::{BlockData, BlockDataIdentifier};
+use crate::block::paint::PaintColor;
 use crate::block::{block_face::BlockFace, block_rotation::BlockRotation, block_rotation::BlockSubRotation, Block};
 use crate::ecs::NeedsDespawned;
 use crate::events::block_events::{BlockDataChangedEvent, BlockDataSystemParams};
@@ -169,6 +170,13 @@ impl Chunk {
         }
     }
 
+    /// Replaces every block id in this chunk with the result of `remap`.
+    ///
+    /// This is used to convert block ids from one [`Registry<Block>`] to another, such as the server's to the client's.
+    pub fn remap_block_ids(&mut self, remap: impl Fn(u16) -> u16) {
+        self.block_storage.remap_block_ids(remap);
+    }
+
     #[inline]
     /// The position of this chunk in the structure.
     pub fn chunk_coordinates(&self) -> ChunkCoordinate {
@@ -538,9 +546,8 @@ impl Chunk {
 }
 
 #[derive(Debug, Default, Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
-/// This represents the information for a block. The first 3 rightmost bits are reserved for rotation data.
-///
-/// All other bits can be used for anything else
+/// This represents the information for a block. The first 5 rightmost bits are reserved for rotation data,
+/// and the 3 leftmost bits store the block's [`PaintColor`].
 pub struct BlockInfo(pub u8);
 
 impl BlockInfo {
@@ -562,6 +569,17 @@ impl BlockInfo {
     pub fn set_rotation(&mut self, rotation: BlockRotation) {
         self.0 = self.0 & !0b11111 | (rotation.face_pointing_pos_y.index() as u8 | (rotation.sub_rotation.index() << 3) as u8);
     }
+
+    #[inline]
+    /// Gets the color this block is painted
+    pub fn get_paint_color(&self) -> PaintColor {
+        PaintColor::from_index(self.0 >> 5)
+    }
+
+    /// Sets the color this block is painted
+    pub fn set_paint_color(&mut self, paint_color: PaintColor) {
+        self.0 = self.0 & 0b11111 | (paint_color.index() << 5);
+    }
 }
 
 /// This entity represents a chunk stored within the structure
@@ -0,0 +1,199 @@
+//! Incremental construction of a blueprint on an existing ship or station.
+//!
+//! A blueprint projector projects a ship blueprint as "ghost" blocks in front of it. Players then turn each ghost into a
+//! real block, either by placing the matching block there or by welding it with a [`WELDING_TOOL_ITEM`], which uses the
+//! block's item from their inventory. Only the block a ghost shows can be placed where it is.
+//!
+//! [`WELDING_TOOL_ITEM`]: super::welding::WELDING_TOOL_ITEM
+
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::block_rotation::BlockRotation,
+    netty::sync::{
+        events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+        sync_component, IdentifiableComponent, SyncType, SyncableComponent,
+    },
+    structure::{coordinates::BlockCoordinate, structure_block::StructureBlock},
+};
+
+/// The block that projects blueprints
+pub const BLUEPRINT_PROJECTOR_BLOCK: &str = "cosmos:blueprint_projector";
+
+/// How far (in blocks) a player can be from a ghost block to weld it
+pub const MAX_GHOST_WELD_DISTANCE: f32 = 8.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// A block that hasn't been built yet
+pub struct GhostBlock {
+    /// Index into the [`ConstructionSite`]'s palette
+    palette_index: u16,
+    /// The rotation the block will be built with
+    pub rotation: BlockRotation,
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize, Reflect)]
+/// A blueprint that is being built on this structure
+pub struct ConstructionSite {
+    /// The name of the blueprint being built
+    blueprint: String,
+    /// The blueprint projector this was projected from
+    projector: BlockCoordinate,
+    /// Unlocalized names of every block the ghosts are. Names are used instead of ids so this can be saved.
+    palette: Vec<String>,
+    ghosts: HashMap<BlockCoordinate, GhostBlock>,
+    /// How many ghosts this site started with
+    total_blocks: u32,
+}
+
+impl ConstructionSite {
+    /// Creates a construction site for this blueprint, projected from the projector at these coordinates
+    pub fn new(blueprint: impl Into<String>, projector: BlockCoordinate) -> Self {
+        Self {
+            blueprint: blueprint.into(),
+            projector,
+            palette: vec![],
+            ghosts: Default::default(),
+            total_blocks: 0,
+        }
+    }
+
+    /// Adds a ghost of this block (by unlocalized name) that still needs to be built
+    pub fn add_ghost(&mut self, coords: BlockCoordinate, block: &str, rotation: BlockRotation) {
+        let palette_index = match self.palette.iter().position(|x| x == block) {
+            Some(idx) => idx,
+            None => {
+                self.palette.push(block.to_owned());
+                self.palette.len() - 1
+            }
+        } as u16;
+
+        if self.ghosts.insert(coords, GhostBlock { palette_index, rotation }).is_none() {
+            self.total_blocks += 1;
+        }
+    }
+
+    /// Returns the unlocalized name of the block the ghost at these coordinates is, along with its rotation
+    pub fn ghost_at(&self, coords: BlockCoordinate) -> Option<(&str, BlockRotation)> {
+        self.ghosts
+            .get(&coords)
+            .map(|ghost| (self.palette[ghost.palette_index as usize].as_str(), ghost.rotation))
+    }
+
+    /// Marks the ghost at these coordinates as built.
+    ///
+    /// Returns false if there was no ghost there.
+    pub fn build_ghost(&mut self, coords: BlockCoordinate) -> bool {
+        self.ghosts.remove(&coords).is_some()
+    }
+
+    /// Iterates over every ghost that still needs to be built, along with its block's unlocalized name & rotation
+    pub fn ghosts(&self) -> impl Iterator<Item = (BlockCoordinate, &str, BlockRotation)> + '_ {
+        self.ghosts
+            .iter()
+            .map(|(&coords, ghost)| (coords, self.palette[ghost.palette_index as usize].as_str(), ghost.rotation))
+    }
+
+    /// The name of the blueprint being built
+    pub fn blueprint(&self) -> &str {
+        &self.blueprint
+    }
+
+    /// The blueprint projector this was projected from
+    pub fn projector(&self) -> BlockCoordinate {
+        self.projector
+    }
+
+    /// How many blocks this site needed when it was projected
+    pub fn total_blocks(&self) -> u32 {
+        self.total_blocks
+    }
+
+    /// How many blocks have been built so far
+    pub fn built_blocks(&self) -> u32 {
+        self.total_blocks - self.ghosts.len() as u32
+    }
+
+    /// How much of this site has been built, from 0.0 to 1.0
+    pub fn progress(&self) -> f32 {
+        if self.total_blocks == 0 {
+            1.0
+        } else {
+            self.built_blocks() as f32 / self.total_blocks as f32
+        }
+    }
+
+    /// Returns true once every ghost has been built
+    pub fn is_complete(&self) -> bool {
+        self.ghosts.is_empty()
+    }
+}
+
+impl IdentifiableComponent for ConstructionSite {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:construction_site"
+    }
+}
+
+impl SyncableComponent for ConstructionSite {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to weld the ghost block at this block into a real block, using the welding tool they are holding.
+///
+/// The block's item is taken from the player's inventory.
+pub struct WeldGhostEvent {
+    /// Where the ghost is
+    pub block: StructureBlock,
+}
+
+impl IdentifiableEvent for WeldGhostEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:weld_ghost"
+    }
+}
+
+impl NettyEvent for WeldGhostEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<ConstructionSite>(app);
+
+    app.register_type::<ConstructionSite>().add_netty_event::<WeldGhostEvent>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn building_ghosts_tracks_progress() {
+        let mut site = ConstructionSite::new("test", BlockCoordinate::new(0, 0, 0));
+
+        site.add_ghost(BlockCoordinate::new(1, 0, 0), "cosmos:ship_hull_grey", BlockRotation::default());
+        site.add_ghost(BlockCoordinate::new(2, 0, 0), "cosmos:ship_hull_grey", BlockRotation::default());
+        site.add_ghost(BlockCoordinate::new(3, 0, 0), "cosmos:glass", BlockRotation::default());
+
+        assert_eq!(site.total_blocks(), 3);
+        assert_eq!(site.ghost_at(BlockCoordinate::new(3, 0, 0)).map(|x| x.0), Some("cosmos:glass"));
+
+        assert!(site.build_ghost(BlockCoordinate::new(1, 0, 0)));
+        assert!(!site.build_ghost(BlockCoordinate::new(1, 0, 0)));
+
+        assert_eq!(site.built_blocks(), 1);
+        assert!(!site.is_complete());
+
+        site.build_ghost(BlockCoordinate::new(2, 0, 0));
+        site.build_ghost(BlockCoordinate::new(3, 0, 0));
+
+        assert!(site.is_complete());
+        assert_eq!(site.progress(), 1.0);
+    }
+}
@@ -18,6 +18,7 @@ use crate::{
 
 use super::{
     base_structure::BaseStructure,
+    block_counts::BlockCounts,
     block_storage::BlockStorer,
     chunk::{BlockInfo, Chunk},
     coordinates::{BlockCoordinate, ChunkBlockCoordinate, ChunkCoordinate, CoordinateType},
@@ -35,6 +36,9 @@ pub struct FullStructure {
     block_bounds: Option<(BlockCoordinate, BlockCoordinate)>,
     #[serde(skip)]
     loaded: bool,
+    #[serde(skip)]
+    #[reflect(ignore)]
+    block_counts: BlockCounts,
 }
 
 impl Deref for FullStructure {
@@ -60,6 +64,7 @@ impl FullStructure {
             base_structure: BaseStructure::new(dimensions),
             block_bounds: None,
             loaded: false,
+            block_counts: BlockCounts::default(),
         }
     }
 
@@ -220,6 +225,15 @@ impl FullStructure {
         self.loaded
     }
 
+    /// How many of each block this structure has. This will be empty until the structure is loaded.
+    pub fn block_counts(&self) -> &BlockCounts {
+        &self.block_counts
+    }
+
+    pub(super) fn block_counts_mut(&mut self) -> &mut BlockCounts {
+        &mut self.block_counts
+    }
+
     /// Returns the chunk's state
     pub fn get_chunk_state(&self, coords: ChunkCoordinate) -> ChunkState {
         if !self.is_within_chunks(coords) {
@@ -0,0 +1,169 @@
+//! Fires & electrical arcs that hurt players and blocks over time.
+//!
+//! Destroying a reactor or power conduit sets the blocks around it on fire, and destroying any power block leaves it
+//! arcing for a while. Fires slowly damage the block they are on, spread to nearby flammable blocks (see
+//! [`crate::block::Block::flammability`]), and hurt any player standing too close. A fire goes out once its block is
+//! destroyed, once its block is vented to space, or when it is sprayed with a [`FIRE_EXTINGUISHER_ITEM`].
+
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::sync::{sync_component, IdentifiableComponent, SyncType, SyncableComponent},
+    structure::coordinates::BlockCoordinate,
+};
+
+/// The item used to put out fires
+pub const FIRE_EXTINGUISHER_ITEM: &str = "cosmos:fire_extinguisher";
+
+/// Destroying one of these blocks sets the blocks around it on fire
+pub const FIRE_STARTING_BLOCKS: [&str; 5] = [
+    "cosmos:reactor_controller",
+    "cosmos:reactor_cell",
+    "cosmos:power_cable",
+    "cosmos:energy_cell",
+    "cosmos:passive_generator",
+];
+
+/// Destroying one of these blocks leaves electrical arcs where it used to be
+pub const ARCING_BLOCKS: [&str; 7] = [
+    "cosmos:reactor_controller",
+    "cosmos:reactor_cell",
+    "cosmos:power_cable",
+    "cosmos:energy_cell",
+    "cosmos:energy_relay",
+    "cosmos:passive_generator",
+    "cosmos:battery_charger",
+];
+
+/// A fire on a block that isn't flammable burns out after this many seconds
+pub const FIRE_BURNOUT_SECS: f32 = 10.0;
+/// How many seconds a destroyed power block keeps arcing for
+pub const ARC_DURATION_SECS: f32 = 20.0;
+
+#[derive(Component, Debug, Default, Clone, PartialEq, Serialize, Deserialize, Reflect)]
+/// Every fire & electrical arc on a structure.
+///
+/// Only structures that currently have hazards have this. This is only marked as changed (and synced) when a hazard is
+/// added or removed, not when they are ticked.
+pub struct StructureHazards {
+    /// Every block on fire, and how many seconds it has been burning for
+    fires: HashMap<BlockCoordinate, f32>,
+    /// Every spot that is arcing, and how many seconds it will keep arcing for
+    arcs: HashMap<BlockCoordinate, f32>,
+}
+
+impl StructureHazards {
+    /// Sets the block at these coordinates on fire.
+    ///
+    /// Returns false if it was already on fire.
+    pub fn ignite(&mut self, coords: BlockCoordinate) -> bool {
+        if self.fires.contains_key(&coords) {
+            return false;
+        }
+
+        self.fires.insert(coords, 0.0);
+        true
+    }
+
+    /// Puts out the fire on the block at these coordinates.
+    ///
+    /// Returns false if it wasn't on fire.
+    pub fn extinguish(&mut self, coords: BlockCoordinate) -> bool {
+        self.fires.remove(&coords).is_some()
+    }
+
+    /// Returns true if the block at these coordinates is on fire
+    pub fn is_burning(&self, coords: BlockCoordinate) -> bool {
+        self.fires.contains_key(&coords)
+    }
+
+    /// Iterates over every block on fire, along with how many seconds it has been burning for
+    pub fn fires(&self) -> impl Iterator<Item = (BlockCoordinate, f32)> + '_ {
+        self.fires.iter().map(|(&coords, &secs)| (coords, secs))
+    }
+
+    /// Starts (or restarts) an electrical arc at these coordinates that lasts for this many seconds
+    pub fn start_arc(&mut self, coords: BlockCoordinate, duration_secs: f32) {
+        let remaining = self.arcs.entry(coords).or_default();
+        *remaining = remaining.max(duration_secs);
+    }
+
+    /// Iterates over every spot that is arcing, along with how many seconds it will keep arcing for
+    pub fn arcs(&self) -> impl Iterator<Item = (BlockCoordinate, f32)> + '_ {
+        self.arcs.iter().map(|(&coords, &secs)| (coords, secs))
+    }
+
+    /// Advances every fire & arc by this many seconds, and removes any arcs that have run out.
+    ///
+    /// Returns true if any arcs were removed.
+    pub fn tick(&mut self, delta_secs: f32) -> bool {
+        for secs in self.fires.values_mut() {
+            *secs += delta_secs;
+        }
+
+        for secs in self.arcs.values_mut() {
+            *secs -= delta_secs;
+        }
+
+        let n_arcs = self.arcs.len();
+        self.arcs.retain(|_, secs| *secs > 0.0);
+
+        n_arcs != self.arcs.len()
+    }
+
+    /// Returns true if there are no fires or arcs
+    pub fn is_empty(&self) -> bool {
+        self.fires.is_empty() && self.arcs.is_empty()
+    }
+}
+
+impl IdentifiableComponent for StructureHazards {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:structure_hazards"
+    }
+}
+
+impl SyncableComponent for StructureHazards {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<StructureHazards>(app);
+
+    app.register_type::<StructureHazards>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arcs_run_out() {
+        let mut hazards = StructureHazards::default();
+        let coords = BlockCoordinate::new(1, 2, 3);
+
+        hazards.start_arc(coords, 2.0);
+
+        assert!(!hazards.tick(1.0));
+        assert!(hazards.tick(1.0));
+        assert!(hazards.is_empty());
+    }
+
+    #[test]
+    fn fires_burn_until_extinguished() {
+        let mut hazards = StructureHazards::default();
+        let coords = BlockCoordinate::new(1, 2, 3);
+
+        assert!(hazards.ignite(coords));
+        assert!(!hazards.ignite(coords));
+
+        hazards.tick(FIRE_BURNOUT_SECS);
+        assert_eq!(hazards.fires().collect::<Vec<_>>(), vec![(coords, FIRE_BURNOUT_SECS)]);
+
+        assert!(hazards.extinguish(coords));
+        assert!(hazards.is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+//! Weapons, reactors and thrusters heat a structure up as they're used, and radiators get rid of that heat.
+//!
+//! A structure that gets too hot throttles its weapons and thrusters, and past its heat capacity it starts damaging its
+//! own blocks.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::{sync_component, IdentifiableComponent, SyncType, SyncableComponent};
+
+/// The unlocalized name of the radiator block
+pub const RADIATOR_BLOCK: &str = "cosmos:radiator";
+
+/// How much heat each block of a structure can hold before it overheats
+pub const HEAT_CAPACITY_PER_BLOCK: f32 = 20.0;
+/// How much heat each block of a structure gets rid of every second, even without radiators
+pub const PASSIVE_DISSIPATION_PER_BLOCK: f32 = 0.25;
+/// How much heat each radiator gets rid of every second
+pub const RADIATOR_DISSIPATION: f32 = 50.0;
+
+/// Once a structure's heat passes this fraction of its capacity, its systems start being throttled
+const THROTTLE_START: f32 = 0.75;
+/// Systems never get throttled below this fraction of their normal output
+const MIN_THROTTLE: f32 = 0.25;
+/// Heat stops building up past this multiple of the structure's capacity
+const MAX_HEAT_MULTIPLIER: f32 = 1.5;
+
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
+/// How hot a structure is, and how well it handles heat.
+///
+/// This is only updated on the server, and is synced to the clients whenever the heat percentage changes.
+pub struct StructureHeat {
+    heat: f32,
+    capacity: f32,
+    dissipation: f32,
+}
+
+impl StructureHeat {
+    /// Computes the heat limits of a structure with this many blocks & radiators. The structure starts with no heat.
+    pub fn from_block_counts(total_blocks: u32, radiators: u32) -> Self {
+        let mut heat = Self::default();
+        heat.set_limits(total_blocks, radiators);
+        heat
+    }
+
+    /// Recomputes how much heat this can hold and get rid of, keeping its current heat
+    pub fn set_limits(&mut self, total_blocks: u32, radiators: u32) {
+        self.capacity = total_blocks as f32 * HEAT_CAPACITY_PER_BLOCK;
+        self.dissipation = total_blocks as f32 * PASSIVE_DISSIPATION_PER_BLOCK + radiators as f32 * RADIATOR_DISSIPATION;
+    }
+
+    /// The amount of heat currently stored
+    pub fn heat(&self) -> f32 {
+        self.heat
+    }
+
+    /// The amount of heat this can store before overheating
+    pub fn capacity(&self) -> f32 {
+        self.capacity
+    }
+
+    /// How much heat this gets rid of every second
+    pub fn dissipation(&self) -> f32 {
+        self.dissipation
+    }
+
+    /// The current heat as a fraction of the capacity. `1.0` or above means this is overheating.
+    pub fn fraction(&self) -> f32 {
+        if self.capacity <= 0.0 {
+            return 0.0;
+        }
+
+        self.heat / self.capacity
+    }
+
+    /// The current heat as a whole percentage of the capacity
+    pub fn percent(&self) -> i32 {
+        (self.fraction() * 100.0).round() as i32
+    }
+
+    /// Returns true if this has more heat than it can hold
+    pub fn is_overheating(&self) -> bool {
+        self.capacity > 0.0 && self.heat >= self.capacity
+    }
+
+    /// Systems that generate heat should multiply their output by this.
+    ///
+    /// This is `1.0` until the heat passes 75% of the capacity, then drops to 25% as the structure overheats.
+    pub fn throttle(&self) -> f32 {
+        let over = ((self.fraction() - THROTTLE_START) / (1.0 - THROTTLE_START)).clamp(0.0, 1.0);
+
+        1.0 - over * (1.0 - MIN_THROTTLE)
+    }
+
+    /// Adds heat, up to a bit past the capacity
+    pub fn add_heat(&mut self, amount: f32) {
+        self.heat = (self.heat + amount).clamp(0.0, self.capacity * MAX_HEAT_MULTIPLIER);
+    }
+
+    /// Gets rid of `delta_secs` worth of heat
+    pub fn dissipate(&mut self, delta_secs: f32) {
+        self.heat = (self.heat - self.dissipation * delta_secs).max(0.0);
+    }
+}
+
+impl IdentifiableComponent for StructureHeat {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:structure_heat"
+    }
+}
+
+impl SyncableComponent for StructureHeat {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<StructureHeat>(app);
+
+    app.register_type::<StructureHeat>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttle_starts_once_hot() {
+        let mut heat = StructureHeat::from_block_counts(10, 0);
+
+        heat.add_heat(heat.capacity() * 0.5);
+        assert_eq!(heat.throttle(), 1.0);
+
+        heat.add_heat(heat.capacity() * 0.375);
+        assert!((heat.throttle() - (1.0 + MIN_THROTTLE) / 2.0).abs() < 0.001);
+
+        heat.add_heat(heat.capacity());
+        assert!(heat.is_overheating());
+        assert_eq!(heat.throttle(), MIN_THROTTLE);
+    }
+
+    #[test]
+    fn radiators_dissipate_heat() {
+        let mut heat = StructureHeat::from_block_counts(10, 1);
+
+        heat.add_heat(RADIATOR_DISSIPATION);
+        heat.dissipate(1.0);
+
+        assert_eq!(heat.heat(), 0.0);
+    }
+}
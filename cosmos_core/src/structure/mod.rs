@@ -19,19 +19,26 @@ use query::MutBlockData;
 
 pub mod asteroid;
 pub mod base_structure;
+pub mod bill_of_materials;
+pub mod block_counts;
 pub mod block_health;
 pub mod block_storage;
 pub mod chunk;
+pub mod construction;
 pub mod coordinates;
 pub mod dynamic_structure;
 pub mod events;
 pub mod full_structure;
+pub mod hazards;
+pub mod heat;
 pub mod loading;
 pub mod lod;
 pub mod lod_chunk;
+pub mod ownership;
 pub mod planet;
 pub mod prelude;
 pub mod query;
+pub mod sensors;
 pub mod shared;
 pub mod shields;
 pub mod ship;
@@ -39,7 +46,9 @@ pub mod station;
 pub mod structure_block;
 pub mod structure_builder;
 pub mod structure_iterator;
+pub mod structure_name;
 pub mod systems;
+pub mod welding;
 
 use crate::block::data::persistence::ChunkLoadBlockDataEvent;
 use crate::block::data::BlockData;
@@ -54,6 +63,7 @@ use bevy::prelude::{BuildChildren, Commands, Component, Entity, EventReader, Eve
 use serde::{Deserialize, Serialize};
 
 use self::base_structure::RaycastIter;
+use self::block_counts::BlockCounts;
 use self::block_health::events::{BlockDestroyedEvent, BlockTakeDamageEvent};
 use self::block_storage::BlockStorer;
 use self::chunk::netty::SerializedChunkBlockData;
@@ -307,6 +317,17 @@ impl Structure {
         }
     }
 
+    /// How many of each block this structure has, and where its [`block_counts::TrackedBlockPositions`] blocks are.
+    ///
+    /// This is only kept for structures that have all their chunks loaded at once, so this will be `None` for
+    /// [`Structure::Dynamic`] structures (planets).
+    pub fn block_counts(&self) -> Option<&BlockCounts> {
+        match self {
+            Self::Full(fs) => Some(fs.block_counts()),
+            Self::Dynamic(_) => None,
+        }
+    }
+
     /// Removes the block at the given coordinates
     ///
     /// * `event_writer` If this is None, no event will be generated.
@@ -959,7 +980,15 @@ pub(super) fn register(app: &mut App) {
     systems::register(app);
     shields::register(app);
     block_health::register(app);
+    block_counts::register(app);
+    sensors::register(app);
+    heat::register(app);
+    hazards::register(app);
     structure_block::register(app);
+    ownership::register(app);
+    structure_name::register(app);
+    welding::register(app);
+    construction::register(app);
 
     use StructureTypeSet as S;
 
@@ -0,0 +1,89 @@
+//! Structures can be owned by a player, who decides who else is allowed to use them.
+//!
+//! Structures without a [`StructureOwnership`] can be used by anyone.
+
+use bevy::{
+    prelude::{App, Component},
+    reflect::Reflect,
+    utils::HashSet,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::{sync_component, IdentifiableComponent, SyncableComponent};
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Reflect)]
+/// Who owns this structure, and who else they have allowed to use it.
+///
+/// Only players with access can edit blocks, pilot, or open inventories on this structure.
+pub struct StructureOwnership {
+    owner: String,
+    access_list: HashSet<String>,
+}
+
+impl StructureOwnership {
+    /// Creates a structure owned by the player with this name
+    pub fn new(owner: impl Into<String>) -> Self {
+        Self {
+            owner: owner.into(),
+            access_list: Default::default(),
+        }
+    }
+
+    /// The name of the player that owns this structure
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    /// Returns true if this player owns this structure
+    pub fn is_owner(&self, player_name: &str) -> bool {
+        self.owner == player_name
+    }
+
+    /// Returns true if this player owns this structure or has been given access to it
+    pub fn has_access(&self, player_name: &str) -> bool {
+        self.is_owner(player_name) || self.access_list.contains(player_name)
+    }
+
+    /// Lets this player use this structure.
+    ///
+    /// Returns false if they already had access.
+    pub fn grant_access(&mut self, player_name: impl Into<String>) -> bool {
+        let player_name = player_name.into();
+
+        if self.is_owner(&player_name) {
+            return false;
+        }
+
+        self.access_list.insert(player_name)
+    }
+
+    /// Stops this player from using this structure. The owner cannot have their access revoked.
+    ///
+    /// Returns false if they didn't have access to begin with.
+    pub fn revoke_access(&mut self, player_name: &str) -> bool {
+        self.access_list.remove(player_name)
+    }
+
+    /// Every player (other than the owner) allowed to use this structure
+    pub fn access_list(&self) -> impl Iterator<Item = &str> {
+        self.access_list.iter().map(|x| x.as_str())
+    }
+}
+
+impl IdentifiableComponent for StructureOwnership {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:structure_ownership"
+    }
+}
+
+impl SyncableComponent for StructureOwnership {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<StructureOwnership>(app);
+
+    app.register_type::<StructureOwnership>();
+}
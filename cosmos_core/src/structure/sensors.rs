@@ -0,0 +1,137 @@
+//! Jammers make a structure harder to detect, while scanners make it easier for a structure to detect others.
+//!
+//! Every contact has a base range it can be detected from. A jammed contact can only be identified from a fraction of that range,
+//! and past that it shows up as an unknown blip until it's out of sensor range entirely. Scanners on the observing ship cancel
+//! out jamming.
+
+use bevy::prelude::*;
+
+use crate::{
+    block::{block_events::BlockEventsSet, Block},
+    netty::system_sets::NetworkingSystemsSet,
+    registry::{identifiable::Identifiable, Registry},
+};
+
+use super::Structure;
+
+/// The unlocalized name of the jammer block
+pub const JAMMER_BLOCK: &str = "cosmos:jammer";
+/// The unlocalized name of the scanner block
+pub const SCANNER_BLOCK: &str = "cosmos:scanner";
+
+/// How much each point of uncountered jamming shrinks the range a contact can be identified from.
+///
+/// The range is divided by `1 + net_jamming * JAMMING_RANGE_FACTOR`.
+const JAMMING_RANGE_FACTOR: f32 = 0.5;
+/// Contacts that are too jammed to identify still show up as an unknown blip within this many times their identification range.
+const UNKNOWN_BLIP_RANGE_MULTIPLIER: f32 = 2.0;
+
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Reflect)]
+/// How strong a structure's jammers and scanners are.
+///
+/// Stacking more of the same block has diminishing returns.
+///
+/// This is kept up to date as blocks are placed and removed, and is updated in [`BlockEventsSet::PostProcessEvents`].
+pub struct SensorStrength {
+    jamming: f32,
+    scanning: f32,
+}
+
+impl SensorStrength {
+    /// Computes the sensor strength of a structure with this many jammers and scanners
+    pub fn from_block_counts(jammers: u32, scanners: u32) -> Self {
+        Self {
+            jamming: (jammers as f32).sqrt(),
+            scanning: (scanners as f32).sqrt(),
+        }
+    }
+
+    /// How much this structure reduces the range others can detect it from
+    pub fn jamming(&self) -> f32 {
+        self.jamming
+    }
+
+    /// How much jamming this structure can see through
+    pub fn scanning(&self) -> f32 {
+        self.scanning
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How much an observer knows about a contact
+pub enum Detection {
+    /// The contact is close enough to be seen clearly
+    Identified,
+    /// The observer knows something is there, but not what it is
+    UnknownBlip,
+    /// The contact doesn't show up at all
+    Undetected,
+}
+
+/// Multiply a contact's base detection range by this to get the range it can be identified from.
+///
+/// Returns `1.0` if the observer's scanners fully counter the contact's jammers.
+pub fn detection_range_multiplier(observer: Option<&SensorStrength>, contact: Option<&SensorStrength>) -> f32 {
+    let jamming = contact.map(|x| x.jamming).unwrap_or(0.0);
+    let scanning = observer.map(|x| x.scanning).unwrap_or(0.0);
+
+    let net_jamming = (jamming - scanning).max(0.0);
+
+    1.0 / (1.0 + net_jamming * JAMMING_RANGE_FACTOR)
+}
+
+/// Determines what an observer can make out of a contact `distance` meters away.
+///
+/// * `base_range` - The furthest this contact can ever be detected from, if it isn't jammed
+pub fn detect(distance: f32, base_range: f32, observer: Option<&SensorStrength>, contact: Option<&SensorStrength>) -> Detection {
+    let identify_range = base_range * detection_range_multiplier(observer, contact);
+
+    if distance <= identify_range {
+        Detection::Identified
+    } else if distance <= (identify_range * UNKNOWN_BLIP_RANGE_MULTIPLIER).min(base_range) {
+        Detection::UnknownBlip
+    } else {
+        Detection::Undetected
+    }
+}
+
+fn compute_sensor_strength(
+    mut commands: Commands,
+    blocks: Res<Registry<Block>>,
+    mut q_structures: Query<(Entity, &Structure, Option<&mut SensorStrength>), Changed<Structure>>,
+) {
+    let count_of = |structure: &Structure, unlocalized_name: &str| {
+        blocks
+            .from_id(unlocalized_name)
+            .and_then(|block| structure.block_counts().map(|counts| counts.count(block.id())))
+            .unwrap_or(0)
+    };
+
+    for (ent, structure, sensor_strength) in q_structures.iter_mut() {
+        // Only full structures (ships, stations, asteroids) keep track of their block counts
+        if structure.block_counts().is_none() {
+            continue;
+        }
+
+        let new_strength = SensorStrength::from_block_counts(count_of(structure, JAMMER_BLOCK), count_of(structure, SCANNER_BLOCK));
+
+        match sensor_strength {
+            Some(mut sensor_strength) => {
+                sensor_strength.set_if_neq(new_strength);
+            }
+            None => {
+                commands.entity(ent).insert(new_strength);
+            }
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        compute_sensor_strength
+            .in_set(BlockEventsSet::PostProcessEvents)
+            .in_set(NetworkingSystemsSet::Between),
+    )
+    .register_type::<SensorStrength>();
+}
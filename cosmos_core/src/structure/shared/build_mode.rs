@@ -15,7 +15,14 @@ use bevy_rapier3d::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{block::block_events::BlockEventsSet, netty::system_sets::NetworkingSystemsSet, structure::coordinates::CoordinateType};
+use crate::{
+    block::{block_direction::BlockDirection, block_events::BlockEventsSet, block_face::BlockFace, block_rotation::BlockRotation},
+    netty::{
+        sync::events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+        system_sets::NetworkingSystemsSet,
+    },
+    structure::coordinates::{CoordinateType, UnboundBlockCoordinate},
+};
 
 type BuildModeSymmetries = (Option<CoordinateType>, Option<CoordinateType>, Option<CoordinateType>);
 
@@ -27,7 +34,7 @@ pub struct BuildMode {
     symmetries: BuildModeSymmetries,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 /// Represents the X/Y/Z symmetry axis
 pub enum BuildAxis {
     /// X axis
@@ -67,6 +74,62 @@ impl BuildMode {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// A way every block of a structure can be moved around at once
+pub enum StructureTransformation {
+    /// Rotates the structure 90 degrees counter-clockwise around this axis (following the right-hand rule)
+    Rotate(BuildAxis),
+    /// Mirrors the structure across this axis
+    Mirror(BuildAxis),
+}
+
+impl StructureTransformation {
+    /// Transforms an offset from the block the structure is being rotated/mirrored around
+    pub fn apply_to_offset(&self, offset: UnboundBlockCoordinate) -> UnboundBlockCoordinate {
+        let UnboundBlockCoordinate { x, y, z } = offset;
+
+        match self {
+            Self::Rotate(BuildAxis::X) => UnboundBlockCoordinate::new(x, -z, y),
+            Self::Rotate(BuildAxis::Y) => UnboundBlockCoordinate::new(z, y, -x),
+            Self::Rotate(BuildAxis::Z) => UnboundBlockCoordinate::new(-y, x, z),
+            Self::Mirror(BuildAxis::X) => UnboundBlockCoordinate::new(-x, y, z),
+            Self::Mirror(BuildAxis::Y) => UnboundBlockCoordinate::new(x, -y, z),
+            Self::Mirror(BuildAxis::Z) => UnboundBlockCoordinate::new(x, y, -z),
+        }
+    }
+
+    /// Transforms the direction something is facing
+    pub fn apply_to_direction(&self, direction: BlockDirection) -> BlockDirection {
+        BlockDirection::from_coordinates(self.apply_to_offset(direction.to_coordinates()))
+    }
+
+    /// Transforms a block's rotation.
+    ///
+    /// Blocks themselves can't be mirrored, so mirrored blocks just have their top & front faces mirrored.
+    pub fn apply_to_rotation(&self, rotation: BlockRotation) -> BlockRotation {
+        BlockRotation::from_face_directions(
+            self.apply_to_direction(rotation.direction_of(BlockFace::Top)),
+            self.apply_to_direction(rotation.direction_of(BlockFace::Front)),
+        )
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by a player in build mode to rotate or mirror the structure they are building
+pub struct TransformStructureEvent(pub StructureTransformation);
+
+impl IdentifiableEvent for TransformStructureEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:transform_structure"
+    }
+}
+
+impl NettyEvent for TransformStructureEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
 #[derive(Event)]
 /// This event is sent when a player is entering build mode
 pub struct EnterBuildModeEvent {
@@ -176,5 +239,42 @@ pub(super) fn register(app: &mut App) {
     )
     .add_event::<EnterBuildModeEvent>()
     .add_event::<ExitBuildModeEvent>()
+    .add_netty_event::<TransformStructureEvent>()
     .register_type::<BuildMode>();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn four_rotations_are_identity() {
+        let offset = UnboundBlockCoordinate::new(1, -2, 3);
+
+        for axis in [BuildAxis::X, BuildAxis::Y, BuildAxis::Z] {
+            let rotation = StructureTransformation::Rotate(axis);
+            let rotated = (0..4).fold(offset, |o, _| rotation.apply_to_offset(o));
+            assert_eq!(rotated, offset);
+            assert_ne!(rotation.apply_to_offset(offset), offset);
+        }
+    }
+
+    #[test]
+    fn rotations_match_quaternions() {
+        use bevy::math::{Quat, Vec3};
+        use std::f32::consts::FRAC_PI_2;
+
+        let offset = UnboundBlockCoordinate::new(1, -2, 3);
+        let vec = Vec3::new(1.0, -2.0, 3.0);
+
+        for (axis, quat) in [
+            (BuildAxis::X, Quat::from_rotation_x(FRAC_PI_2)),
+            (BuildAxis::Y, Quat::from_rotation_y(FRAC_PI_2)),
+            (BuildAxis::Z, Quat::from_rotation_z(FRAC_PI_2)),
+        ] {
+            let rotated = StructureTransformation::Rotate(axis).apply_to_offset(offset);
+            let expected = (quat * vec).round();
+            assert_eq!(Vec3::new(rotated.x as f32, rotated.y as f32, rotated.z as f32), expected);
+        }
+    }
+}
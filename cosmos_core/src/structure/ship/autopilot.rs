@@ -0,0 +1,92 @@
+//! An autopilot flies a ship to a destination on its own
+
+use bevy::{
+    prelude::{App, Component, Event},
+    reflect::Reflect,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::sync::{
+        events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+        sync_component, IdentifiableComponent, SyncableComponent,
+    },
+    physics::location::Location,
+};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
+/// If a ship has this component, it is being flown to its destination by the autopilot.
+///
+/// The autopilot will disengage (this component will be removed) once the ship arrives, if it
+/// detects something in its path, or if the pilot tries to move the ship themselves.
+pub struct Autopilot {
+    /// Where the ship is trying to go
+    pub destination: Location,
+}
+
+impl Autopilot {
+    /// The autopilot will not accelerate the ship past this speed (m/s)
+    pub const CRUISE_SPEED: f32 = 150.0;
+    /// Once a ship is within this many blocks of its destination and has stopped, the autopilot will disengage
+    pub const ARRIVAL_DISTANCE: f32 = 50.0;
+
+    /// Creates an autopilot that will fly towards this destination
+    pub fn new(destination: Location) -> Self {
+        Self { destination }
+    }
+}
+
+impl IdentifiableComponent for Autopilot {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:autopilot"
+    }
+}
+
+impl SyncableComponent for Autopilot {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to engage the autopilot of the ship they are piloting
+pub struct EngageAutopilotEvent {
+    /// Where the ship should fly to
+    pub destination: Location,
+}
+
+impl IdentifiableEvent for EngageAutopilotEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:engage_autopilot"
+    }
+}
+
+impl NettyEvent for EngageAutopilotEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to disengage the autopilot of the ship they are piloting
+pub struct DisengageAutopilotEvent;
+
+impl IdentifiableEvent for DisengageAutopilotEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:disengage_autopilot"
+    }
+}
+
+impl NettyEvent for DisengageAutopilotEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<Autopilot>(app);
+
+    app.register_type::<Autopilot>()
+        .add_netty_event::<EngageAutopilotEvent>()
+        .add_netty_event::<DisengageAutopilotEvent>();
+}
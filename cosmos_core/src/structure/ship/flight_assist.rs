@@ -0,0 +1,89 @@
+//! Flight assist modes change how a ship's thrusters respond to its pilot
+
+use std::fmt::Display;
+
+use bevy::{
+    prelude::{App, Component, Event},
+    reflect::Reflect,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::{
+    events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+    sync_component, IdentifiableComponent, SyncableComponent,
+};
+
+#[derive(Component, Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// How the ship's thrusters assist the pilot.
+///
+/// Ships without this component should be treated as [`FlightAssistMode::FullDampeners`].
+pub enum FlightAssistMode {
+    #[default]
+    /// The ship stops rotating as soon as the pilot stops turning it
+    FullDampeners,
+    /// Newtonian flight - both the ship's linear and angular momentum are preserved until the pilot counters them
+    Decoupled,
+    /// The ship's max speed is greatly reduced and it automatically brakes when there is no movement input
+    PrecisionDocking,
+}
+
+impl FlightAssistMode {
+    /// The max speed (m/s) a ship in precision docking mode can go
+    pub const PRECISION_DOCKING_MAX_SPEED: f32 = 20.0;
+    /// The percent of thrust a ship in precision docking mode will use
+    pub const PRECISION_DOCKING_THRUST_MULTIPLIER: f32 = 0.25;
+
+    /// The mode the pilot switches to when they toggle flight assist
+    pub fn next(&self) -> Self {
+        match self {
+            Self::FullDampeners => Self::Decoupled,
+            Self::Decoupled => Self::PrecisionDocking,
+            Self::PrecisionDocking => Self::FullDampeners,
+        }
+    }
+}
+
+impl Display for FlightAssistMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::FullDampeners => "Dampeners",
+            Self::Decoupled => "Decoupled",
+            Self::PrecisionDocking => "Precision Docking",
+        })
+    }
+}
+
+impl IdentifiableComponent for FlightAssistMode {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:flight_assist_mode"
+    }
+}
+
+impl SyncableComponent for FlightAssistMode {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to change the flight assist mode of the ship they are piloting
+pub struct SetFlightAssistModeEvent(pub FlightAssistMode);
+
+impl IdentifiableEvent for SetFlightAssistModeEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:set_flight_assist_mode"
+    }
+}
+
+impl NettyEvent for SetFlightAssistModeEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<FlightAssistMode>(app);
+
+    app.register_type::<FlightAssistMode>()
+        .add_netty_event::<SetFlightAssistModeEvent>();
+}
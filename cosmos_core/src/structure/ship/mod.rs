@@ -9,8 +9,11 @@ use bevy::reflect::Reflect;
 use super::coordinates::BlockCoordinate;
 use super::Structure;
 
+pub mod autopilot;
+pub mod flight_assist;
 pub mod pilot;
 pub mod ship_builder;
+pub mod ship_modifiers;
 pub mod ship_movement;
 
 #[derive(Component, Debug, Reflect, Clone, Copy)]
@@ -26,8 +29,11 @@ impl Ship {
 }
 
 pub(super) fn register(app: &mut App) {
+    autopilot::register(app);
+    flight_assist::register(app);
     pilot::register(app);
     ship_movement::register(app);
+    ship_modifiers::register(app);
     ship_builder::register(app);
 
     app.register_type::<Ship>();
@@ -0,0 +1,147 @@
+//! Passive modules are blocks that don't do anything on their own, but improve the systems of the ship they're placed on.
+//!
+//! Rather than every system counting these blocks itself, their effects are gathered into the ship's [`ShipModifiers`],
+//! which systems should read instead of using hardcoded values.
+
+use bevy::prelude::*;
+
+use crate::{
+    block::{block_events::BlockEventsSet, Block},
+    netty::system_sets::NetworkingSystemsSet,
+    registry::{identifiable::Identifiable, Registry},
+    structure::Structure,
+};
+
+use super::Ship;
+
+/// How many extra slots each cargo expander gives every storage block on its ship
+pub const SLOTS_PER_CARGO_EXPANDER: usize = 9;
+/// Cargo expanders past this amount do nothing
+pub const MAX_CARGO_EXPANDERS: u32 = 5;
+
+/// Each targeting computer multiplies how long missiles take to lock on by this
+const TARGETING_COMPUTER_FOCUS_FACTOR: f32 = 0.9;
+/// Missiles will never lock on faster than this fraction of their normal time
+const MIN_FOCUS_TIME_MULTIPLIER: f32 = 0.25;
+
+/// Each reactor coolant multiplies how much energy weapons use by this
+const REACTOR_COOLANT_ENERGY_FACTOR: f32 = 0.95;
+/// Weapons will never use less than this fraction of their normal energy
+const MIN_WEAPON_ENERGY_MULTIPLIER: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The different kinds of passive module blocks
+pub enum PassiveModule {
+    /// Gives every storage block on the ship more slots
+    CargoExpander,
+    /// Lets missile launchers lock on to targets faster
+    TargetingComputer,
+    /// Makes weapons use less energy
+    ReactorCoolant,
+}
+
+impl PassiveModule {
+    /// Every kind of passive module
+    pub const ALL: [Self; 3] = [Self::CargoExpander, Self::TargetingComputer, Self::ReactorCoolant];
+
+    /// The unlocalized name of the block for this module
+    pub fn block_unlocalized_name(&self) -> &'static str {
+        match self {
+            Self::CargoExpander => "cosmos:cargo_expander",
+            Self::TargetingComputer => "cosmos:targeting_computer",
+            Self::ReactorCoolant => "cosmos:reactor_coolant",
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+/// The combined effects of every passive module on a ship.
+///
+/// This is kept up to date as blocks are placed and removed, and is updated in [`BlockEventsSet::PostProcessEvents`].
+pub struct ShipModifiers {
+    storage_slot_bonus: usize,
+    missile_focus_time_multiplier: f32,
+    weapon_energy_multiplier: f32,
+}
+
+impl Default for ShipModifiers {
+    fn default() -> Self {
+        Self {
+            storage_slot_bonus: 0,
+            missile_focus_time_multiplier: 1.0,
+            weapon_energy_multiplier: 1.0,
+        }
+    }
+}
+
+impl ShipModifiers {
+    /// Computes the modifiers a ship with this many of each passive module would have
+    pub fn from_module_counts(count: impl Fn(PassiveModule) -> u32) -> Self {
+        let cargo_expanders = count(PassiveModule::CargoExpander).min(MAX_CARGO_EXPANDERS);
+        let targeting_computers = count(PassiveModule::TargetingComputer);
+        let reactor_coolants = count(PassiveModule::ReactorCoolant);
+
+        Self {
+            storage_slot_bonus: cargo_expanders as usize * SLOTS_PER_CARGO_EXPANDER,
+            missile_focus_time_multiplier: TARGETING_COMPUTER_FOCUS_FACTOR
+                .powi(targeting_computers.min(i32::MAX as u32) as i32)
+                .max(MIN_FOCUS_TIME_MULTIPLIER),
+            weapon_energy_multiplier: REACTOR_COOLANT_ENERGY_FACTOR
+                .powi(reactor_coolants.min(i32::MAX as u32) as i32)
+                .max(MIN_WEAPON_ENERGY_MULTIPLIER),
+        }
+    }
+
+    /// How many extra slots every storage block on this ship should have
+    pub fn storage_slot_bonus(&self) -> usize {
+        self.storage_slot_bonus
+    }
+
+    /// Multiply how long missile launchers take to lock on by this
+    pub fn missile_focus_time_multiplier(&self) -> f32 {
+        self.missile_focus_time_multiplier
+    }
+
+    /// Multiply how much energy a weapon uses per shot by this
+    pub fn weapon_energy_multiplier(&self) -> f32 {
+        self.weapon_energy_multiplier
+    }
+}
+
+fn compute_ship_modifiers(
+    mut commands: Commands,
+    blocks: Res<Registry<Block>>,
+    mut q_ships: Query<(Entity, &Structure, Option<&mut ShipModifiers>), (With<Ship>, Changed<Structure>)>,
+) {
+    for (ent, structure, modifiers) in q_ships.iter_mut() {
+        let Some(block_counts) = structure.block_counts() else {
+            continue;
+        };
+
+        let new_modifiers = ShipModifiers::from_module_counts(|module| {
+            blocks
+                .from_id(module.block_unlocalized_name())
+                .map(|block| block_counts.count(block.id()))
+                .unwrap_or(0)
+        });
+
+        match modifiers {
+            Some(mut modifiers) => {
+                modifiers.set_if_neq(new_modifiers);
+            }
+            None => {
+                commands.entity(ent).insert(new_modifiers);
+            }
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        compute_ship_modifiers
+            .in_set(BlockEventsSet::PostProcessEvents)
+            .in_set(NetworkingSystemsSet::Between),
+    )
+    .register_type::<ShipModifiers>();
+}
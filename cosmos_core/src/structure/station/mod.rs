@@ -4,6 +4,7 @@
 
 use bevy::{app::App, ecs::component::Component, reflect::Reflect};
 
+pub mod prefab;
 pub mod station_builder;
 
 #[derive(Component, Debug, Reflect, Clone, Copy)]
@@ -14,4 +15,5 @@ pub(super) fn register(app: &mut App) {
     app.register_type::<Station>();
 
     station_builder::register(app);
+    prefab::register(app);
 }
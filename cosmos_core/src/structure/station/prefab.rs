@@ -0,0 +1,214 @@
+//! Station prefabs are large premade modules (such as habitat rings or hangars) that players can attach to
+//! station connector blocks on their station.
+//!
+//! A prefab is made up of regions of blocks. The prefab's origin `(0, 0, 0)` is the block directly in front of
+//! the connector it is being attached to, and the prefab extends out in the -Z direction (the way the connector's
+//! front faces). Later regions overwrite earlier ones, and `cosmos:air` regions are used to carve out hollow
+//! spaces (which must also be empty to place the prefab).
+
+use bevy::{
+    math::Vec3,
+    prelude::{App, Event},
+    utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::{block_face::BlockFace, block_rotation::BlockRotation, blocks::AIR_BLOCK_ID, Block},
+    netty::sync::{
+        events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+        registry::sync_registry,
+    },
+    registry::{create_registry, identifiable::Identifiable, Registry},
+    structure::{
+        coordinates::{BlockCoordinate, UnboundBlockCoordinate},
+        structure_block::StructureBlock,
+        Structure,
+    },
+};
+
+/// The block prefabs are attached to
+pub const STATION_CONNECTOR_BLOCK: &str = "cosmos:station_connector";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A cuboid of a single type of block within a prefab
+pub struct PrefabRegion {
+    /// The unlocalized name of the block this region is filled with
+    pub block: String,
+    /// One corner of this region (inclusive), relative to the prefab's origin
+    pub from: UnboundBlockCoordinate,
+    /// The opposite corner of this region (inclusive), relative to the prefab's origin
+    pub to: UnboundBlockCoordinate,
+}
+
+impl PrefabRegion {
+    /// Iterates over every coordinate in this region
+    pub fn iter_coords(&self) -> impl Iterator<Item = UnboundBlockCoordinate> + '_ {
+        let (min_x, max_x) = (self.from.x.min(self.to.x), self.from.x.max(self.to.x));
+        let (min_y, max_y) = (self.from.y.min(self.to.y), self.from.y.max(self.to.y));
+        let (min_z, max_z) = (self.from.z.min(self.to.z), self.from.z.max(self.to.z));
+
+        (min_z..=max_z)
+            .flat_map(move |z| (min_y..=max_y).flat_map(move |y| (min_x..=max_x).map(move |x| UnboundBlockCoordinate::new(x, y, z))))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A premade station module that can be attached to a station connector
+pub struct StationPrefab {
+    id: u16,
+    unlocalized_name: String,
+    regions: Vec<PrefabRegion>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Why a prefab cannot be placed
+pub enum PrefabPlacementError {
+    /// Prefabs can only be attached to station connector blocks
+    NotAConnector,
+    /// Part of the prefab would be outside the structure
+    OutOfBounds,
+    /// Part of the prefab would overlap existing blocks
+    Obstructed,
+    /// The prefab contains a block that doesn't exist
+    UnknownBlock,
+}
+
+/// Where every block of a prefab will go once it's attached to a connector
+pub struct PrefabPlacement<'a> {
+    /// Every block the prefab will place - air is not included
+    pub blocks: Vec<(BlockCoordinate, &'a Block)>,
+    /// The rotation given to every block placed
+    pub rotation: BlockRotation,
+}
+
+impl StationPrefab {
+    /// Creates a new prefab made up of these regions. Later regions overwrite earlier ones.
+    pub fn new(unlocalized_name: impl Into<String>, regions: Vec<PrefabRegion>) -> Self {
+        Self {
+            id: 0,
+            unlocalized_name: unlocalized_name.into(),
+            regions,
+        }
+    }
+
+    /// The regions of blocks that make up this prefab
+    pub fn regions(&self) -> &[PrefabRegion] {
+        &self.regions
+    }
+
+    /// Every block in this prefab (including air) relative to the prefab's origin, with later regions taking priority.
+    pub fn blocks(&self) -> HashMap<UnboundBlockCoordinate, &str> {
+        let mut blocks = HashMap::new();
+
+        for region in self.regions.iter() {
+            for coords in region.iter_coords() {
+                blocks.insert(coords, region.block.as_str());
+            }
+        }
+
+        blocks
+    }
+
+    /// Computes where this prefab's blocks would go if it was attached to this connector, and makes sure there is room for them.
+    pub fn compute_placement<'a>(
+        &self,
+        structure: &Structure,
+        connector: BlockCoordinate,
+        blocks: &'a Registry<Block>,
+    ) -> Result<PrefabPlacement<'a>, PrefabPlacementError> {
+        if !structure.is_within_blocks(connector) || structure.block_at(connector, blocks).unlocalized_name() != STATION_CONNECTOR_BLOCK {
+            return Err(PrefabPlacementError::NotAConnector);
+        }
+
+        let facing = structure.block_rotation(connector).direction_of(BlockFace::Front);
+        let rotation = BlockRotation::face_front(facing);
+        let origin = connector + facing.to_coordinates();
+
+        let mut placement = PrefabPlacement { blocks: vec![], rotation };
+
+        for (offset, block_name) in self.blocks() {
+            let coords =
+                BlockCoordinate::try_from(origin + rotate_offset(offset, rotation)).map_err(|_| PrefabPlacementError::OutOfBounds)?;
+
+            if !structure.is_within_blocks(coords) {
+                return Err(PrefabPlacementError::OutOfBounds);
+            }
+
+            if structure.block_id_at(coords) != AIR_BLOCK_ID {
+                return Err(PrefabPlacementError::Obstructed);
+            }
+
+            let block = blocks.from_id(block_name).ok_or(PrefabPlacementError::UnknownBlock)?;
+
+            if block.id() != AIR_BLOCK_ID {
+                placement.blocks.push((coords, block));
+            }
+        }
+
+        Ok(placement)
+    }
+
+    /// How many of each block (by unlocalized name) are needed to build this prefab. Air is not included.
+    pub fn block_counts(&self) -> HashMap<&str, usize> {
+        let mut counts = HashMap::new();
+
+        for block in self.blocks().into_values().filter(|x| *x != "cosmos:air") {
+            *counts.entry(block).or_default() += 1;
+        }
+
+        counts
+    }
+}
+
+/// Rotates an offset (relative to a prefab's origin) so the prefab extends in the direction the rotation's front faces.
+pub fn rotate_offset(offset: UnboundBlockCoordinate, rotation: BlockRotation) -> UnboundBlockCoordinate {
+    let rotated = (rotation.as_quat() * Vec3::new(offset.x as f32, offset.y as f32, offset.z as f32)).round();
+
+    UnboundBlockCoordinate::new(rotated.x as _, rotated.y as _, rotated.z as _)
+}
+
+impl Identifiable for StationPrefab {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to attach a prefab to a station connector.
+///
+/// The server will ignore this if the player isn't building on that station, there isn't room for the prefab,
+/// or the player doesn't have the blocks needed to build it.
+pub struct PlaceStationPrefabEvent {
+    /// The station connector block the prefab is being attached to
+    pub connector: StructureBlock,
+    /// The [`StationPrefab`]'s id
+    pub prefab_id: u16,
+}
+
+impl IdentifiableEvent for PlaceStationPrefabEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:place_station_prefab"
+    }
+}
+
+impl NettyEvent for PlaceStationPrefabEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    create_registry::<StationPrefab>(app, "cosmos:station_prefabs");
+    sync_registry::<StationPrefab>(app);
+
+    app.add_netty_event::<PlaceStationPrefabEvent>();
+}
@@ -0,0 +1,75 @@
+//! Structures can be given a custom name by the players using them.
+//!
+//! Structures without a [`StructureName`] are displayed using a generic name (such as "Ship").
+
+use bevy::{
+    prelude::{App, Changed, Commands, Component, Entity, IntoSystemConfigs, Name, Query, Update},
+    reflect::Reflect,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::netty::{
+    sync::{sync_component, IdentifiableComponent, SyncableComponent},
+    system_sets::NetworkingSystemsSet,
+};
+
+/// The longest name a structure can be given
+pub const MAX_STRUCTURE_NAME_LENGTH: usize = 32;
+
+/// Returns true if this can be used as the name of a structure.
+///
+/// Names cannot be empty, be longer than [`MAX_STRUCTURE_NAME_LENGTH`], or contain control characters.
+pub fn is_valid_structure_name(name: &str) -> bool {
+    !name.trim().is_empty() && name.chars().count() <= MAX_STRUCTURE_NAME_LENGTH && !name.chars().any(|c| c.is_control())
+}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Reflect)]
+/// The name players have given this structure
+pub struct StructureName(String);
+
+impl StructureName {
+    /// Creates a new structure name.
+    ///
+    /// Returns `None` if the name isn't valid (see [`is_valid_structure_name`]).
+    pub fn new(name: impl Into<String>) -> Option<Self> {
+        let name = name.into();
+        let name = name.trim();
+
+        is_valid_structure_name(name).then(|| Self(name.to_owned()))
+    }
+
+    /// The name of this structure
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl IdentifiableComponent for StructureName {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:structure_name"
+    }
+}
+
+impl SyncableComponent for StructureName {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+
+    fn validate(&self) -> bool {
+        is_valid_structure_name(&self.0)
+    }
+}
+
+/// Keeps the entity's [`Name`] the same as its [`StructureName`] to make debugging easier
+fn update_entity_names(mut commands: Commands, q_named: Query<(Entity, &StructureName), Changed<StructureName>>) {
+    for (entity, structure_name) in q_named.iter() {
+        commands.entity(entity).insert(Name::new(structure_name.name().to_owned()));
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<StructureName>(app);
+
+    app.register_type::<StructureName>()
+        .add_systems(Update, update_entity_names.in_set(NetworkingSystemsSet::Between));
+}
@@ -0,0 +1,100 @@
+//! Activation groups let pilots activate several of their ship's systems at once from a single hotbar slot.
+//!
+//! Groups store systems by the unlocalized name of their [`super::StructureSystemType`], since
+//! [`super::StructureSystemId`]s are regenerated every time a structure is loaded.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::{
+    events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+    sync_component, IdentifiableComponent, SyncType, SyncableComponent,
+};
+
+/// How many activation groups a ship has. There is one per hotbar slot.
+pub const N_ACTIVATION_GROUPS: usize = 9;
+
+#[derive(Component, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// The systems the pilot activates from each slot of their hotbar.
+///
+/// Ships without this component have each activatable system in its own group, in the order of
+/// [`super::StructureSystems::all_activatable_systems`].
+pub struct ActivationGroups {
+    groups: Vec<Vec<String>>,
+}
+
+impl Default for ActivationGroups {
+    fn default() -> Self {
+        Self {
+            groups: vec![vec![]; N_ACTIVATION_GROUPS],
+        }
+    }
+}
+
+impl ActivationGroups {
+    /// The unlocalized names of the system types in this group
+    pub fn group(&self, group: usize) -> &[String] {
+        self.groups.get(group).map(|x| x.as_slice()).unwrap_or_default()
+    }
+
+    /// Returns true if systems of this type are activated by this group
+    pub fn contains(&self, group: usize, system_type: &str) -> bool {
+        self.group(group).iter().any(|x| x == system_type)
+    }
+
+    /// Adds or removes this system type from the group. Does nothing if the group doesn't exist.
+    pub fn set_in_group(&mut self, group: usize, system_type: &str, in_group: bool) {
+        let Some(group) = self.groups.get_mut(group) else {
+            return;
+        };
+
+        let present = group.iter().any(|x| x == system_type);
+
+        if in_group && !present {
+            group.push(system_type.to_owned());
+        } else if !in_group && present {
+            group.retain(|x| x != system_type);
+        }
+    }
+}
+
+impl IdentifiableComponent for ActivationGroups {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:activation_groups"
+    }
+}
+
+impl SyncableComponent for ActivationGroups {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+}
+
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+/// Sent by the client to add or remove a system from an activation group of the ship they are piloting
+pub struct SetActivationGroupEvent {
+    /// The unlocalized name of the system type
+    pub system_type: String,
+    /// The group being changed
+    pub group: u32,
+    /// If the system should be activated by this group
+    pub in_group: bool,
+}
+
+impl IdentifiableEvent for SetActivationGroupEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:set_activation_group"
+    }
+}
+
+impl NettyEvent for SetActivationGroupEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<ActivationGroups>(app);
+
+    app.register_type::<ActivationGroups>().add_netty_event::<SetActivationGroupEvent>();
+}
@@ -0,0 +1,134 @@
+//! Cloaking devices hide a ship from sensors and make it nearly invisible while active.
+//!
+//! Staying cloaked drains a lot of energy, and firing any weapons will force the ship to decloak.
+//! After decloaking, the cloak has to cool down before it can be used again.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::{sync_component, IdentifiableComponent, SyncType, SyncableComponent};
+
+use super::{sync::SyncableSystem, StructureSystemImpl};
+
+/// The unlocalized name of the cloaking device block
+pub const CLOAKING_DEVICE_BLOCK: &str = "cosmos:cloaking_device";
+
+/// How many blocks of a structure a single cloaking device can hide.
+///
+/// Structures without enough cloaking devices for their size cannot cloak.
+pub const BLOCKS_PER_CLOAKING_DEVICE: u32 = 200;
+/// How much energy is drained per block of the structure every second while cloaked
+pub const CLOAK_ENERGY_PER_BLOCK_PER_SECOND: f32 = 2.0;
+/// How long (in seconds) after decloaking until the cloak can be activated again
+pub const CLOAK_COOLDOWN_SECONDS: f32 = 10.0;
+
+#[derive(Component, Default, Reflect, Serialize, Deserialize, Debug)]
+/// Represents all the cloaking devices on a structure
+pub struct CloakingSystem {
+    devices: u32,
+    cooldown_remaining: f32,
+}
+
+impl SyncableSystem for CloakingSystem {}
+
+impl StructureSystemImpl for CloakingSystem {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:cloaking_system"
+    }
+}
+
+impl CloakingSystem {
+    /// Call this whenever a cloaking device is added to the system
+    pub fn block_added(&mut self) {
+        self.devices += 1;
+    }
+
+    /// Call this whenever a cloaking device is removed from the system
+    pub fn block_removed(&mut self) {
+        self.devices = self.devices.saturating_sub(1);
+    }
+
+    /// The number of cloaking devices in this system
+    pub fn devices(&self) -> u32 {
+        self.devices
+    }
+
+    /// Returns true if there are enough cloaking devices to hide a structure with this many blocks
+    pub fn can_hide(&self, structure_blocks: u32) -> bool {
+        self.devices != 0 && self.devices * BLOCKS_PER_CLOAKING_DEVICE >= structure_blocks
+    }
+
+    /// How many seconds until the cloak can be activated again
+    pub fn cooldown_remaining(&self) -> f32 {
+        self.cooldown_remaining
+    }
+
+    /// Returns true if the cloak is still cooling down from being deactivated
+    pub fn is_cooling_down(&self) -> bool {
+        self.cooldown_remaining > 0.0
+    }
+
+    /// Starts the cooldown - call this whenever the structure decloaks
+    pub fn start_cooldown(&mut self) {
+        self.cooldown_remaining = CLOAK_COOLDOWN_SECONDS;
+    }
+
+    /// Counts down the cooldown by this many seconds
+    pub fn tick_cooldown(&mut self, delta_secs: f32) {
+        self.cooldown_remaining = (self.cooldown_remaining - delta_secs).max(0.0);
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// A structure with this component is currently cloaked.
+///
+/// Cloaked structures don't show up on sensors, and are only sent to players that are aboard them.
+pub struct Cloaked;
+
+impl IdentifiableComponent for Cloaked {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:cloaked"
+    }
+}
+
+impl SyncableComponent for Cloaked {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+}
+
+/// Returns the cloaked structure this entity is a part of (or riding on), if there is one.
+pub fn cloaked_ancestor(entity: Entity, q_cloaked: &Query<(), With<Cloaked>>, q_parent: &Query<&Parent>) -> Option<Entity> {
+    let mut entity = entity;
+
+    loop {
+        if q_cloaked.contains(entity) {
+            return Some(entity);
+        }
+
+        entity = q_parent.get(entity).ok()?.get();
+    }
+}
+
+/// Returns true if this entity is inside of or a part of the given structure
+pub fn is_aboard(entity: Entity, structure_entity: Entity, q_parent: &Query<&Parent>) -> bool {
+    let mut entity = entity;
+
+    loop {
+        if entity == structure_entity {
+            return true;
+        }
+
+        let Ok(parent) = q_parent.get(entity) else {
+            return false;
+        };
+
+        entity = parent.get();
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<Cloaked>(app);
+
+    app.register_type::<CloakingSystem>().register_type::<Cloaked>();
+}
@@ -13,6 +13,8 @@ use super::{sync::SyncableSystem, StructureSystemImpl};
 /// This will eventually be removed
 pub struct EnergyGenerationSystem {
     generation_rate: f32,
+    #[serde(default)]
+    solar_generation_rate: f32,
 }
 
 impl StructureSystemImpl for EnergyGenerationSystem {
@@ -46,6 +48,11 @@ impl EnergyGenerationBlocks {
     pub fn get(&self, block: &Block) -> Option<&EnergyGenerationProperty> {
         self.blocks.get(&block.id())
     }
+
+    /// Iterates over every block id that has a property
+    pub fn iter(&self) -> impl Iterator<Item = (u16, &EnergyGenerationProperty)> {
+        self.blocks.iter().map(|(&id, prop)| (id, prop))
+    }
 }
 
 impl EnergyGenerationSystem {
@@ -59,9 +66,20 @@ impl EnergyGenerationSystem {
         self.generation_rate -= prop.generation_rate;
     }
 
-    /// How much energy is generated per second
+    /// How much energy is generated per second, including solar panels
     pub fn energy_generation_rate(&self) -> f32 {
-        self.generation_rate
+        self.generation_rate + self.solar_generation_rate
+    }
+
+    /// How much energy the solar panels on this structure generate per second
+    pub fn solar_generation_rate(&self) -> f32 {
+        self.solar_generation_rate
+    }
+
+    /// Solar panel output changes with the position of the star, so it is tracked separately from
+    /// the fixed output of other generators.
+    pub fn set_solar_generation_rate(&mut self, rate: f32) {
+        self.solar_generation_rate = rate;
     }
 }
 
@@ -0,0 +1,203 @@
+//! When a structure uses energy faster than it makes it, its systems brown out in order of priority.
+//!
+//! Every consumer keeps a reserve of energy for the consumers ranked above it. Once a structure's stored energy falls
+//! below a consumer's reserve, that consumer is browned out and stops drawing energy until the structure recovers.
+//! The highest priority consumer has no reserve, so it keeps running until the structure is completely drained.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::{
+    events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+    sync_component, IdentifiableComponent, SyncType, SyncableComponent,
+};
+
+/// Each step down the priority list reserves this fraction of the structure's energy capacity for the consumers above it
+pub const BROWNOUT_STEP: f32 = 0.1;
+
+/// A browned out consumer comes back once the structure has this fraction of its capacity more than its reserve
+const BROWNOUT_RECOVERY: f32 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+/// The kinds of systems that compete for a structure's energy
+pub enum EnergyConsumer {
+    /// Recharging shields
+    Shields,
+    /// Laser cannons and missile launchers
+    Weapons,
+    /// Moving the structure
+    Thrusters,
+    /// Everything else - mining lasers, cloaking, battery chargers
+    Utility,
+}
+
+impl EnergyConsumer {
+    /// Every consumer, in the default priority order
+    pub const ALL: [EnergyConsumer; 4] = [Self::Shields, Self::Weapons, Self::Thrusters, Self::Utility];
+
+    /// The unlocalized name of this consumer, used for its display name
+    pub fn unlocalized_name(&self) -> &'static str {
+        match self {
+            Self::Shields => "cosmos:energy_consumer.shields",
+            Self::Weapons => "cosmos:energy_consumer.weapons",
+            Self::Thrusters => "cosmos:energy_consumer.thrusters",
+            Self::Utility => "cosmos:energy_consumer.utility",
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// The order a structure's consumers get energy in, highest priority first.
+///
+/// Structures without this component use [`EnergyConsumer::ALL`]'s order.
+pub struct EnergyPriorities(Vec<EnergyConsumer>);
+
+impl Default for EnergyPriorities {
+    fn default() -> Self {
+        Self(EnergyConsumer::ALL.to_vec())
+    }
+}
+
+impl EnergyPriorities {
+    /// Every consumer, highest priority first
+    pub fn order(&self) -> &[EnergyConsumer] {
+        &self.0
+    }
+
+    /// Where this consumer is in the priority list, `0` being the highest
+    pub fn rank(&self, consumer: EnergyConsumer) -> usize {
+        self.0.iter().position(|&c| c == consumer).unwrap_or(self.0.len())
+    }
+
+    /// Swaps this consumer with the one above it. Does nothing if it's already the highest priority.
+    pub fn raise(&mut self, consumer: EnergyConsumer) {
+        let rank = self.rank(consumer);
+
+        if rank != 0 && rank < self.0.len() {
+            self.0.swap(rank, rank - 1);
+        }
+    }
+
+    /// Works out which consumers should be browned out once the structure has this fraction of its energy capacity
+    /// stored. Consumers that are already browned out need a bit more energy to come back, so they don't flicker.
+    pub fn browned_out(&self, energy_fraction: f32, previous: &EnergyBrownout) -> EnergyBrownout {
+        EnergyBrownout(
+            self.0
+                .iter()
+                .enumerate()
+                .filter(|&(rank, consumer)| {
+                    let mut reserve = rank as f32 * BROWNOUT_STEP;
+                    if previous.contains(*consumer) {
+                        reserve += BROWNOUT_RECOVERY;
+                    }
+
+                    rank != 0 && energy_fraction < reserve
+                })
+                .map(|(_, &consumer)| consumer)
+                .collect(),
+        )
+    }
+}
+
+impl IdentifiableComponent for EnergyPriorities {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:energy_priorities"
+    }
+}
+
+impl SyncableComponent for EnergyPriorities {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+}
+
+#[derive(Component, Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// The consumers of a structure that currently aren't getting any energy
+pub struct EnergyBrownout(Vec<EnergyConsumer>);
+
+impl EnergyBrownout {
+    /// Returns true if this consumer is browned out
+    pub fn contains(&self, consumer: EnergyConsumer) -> bool {
+        self.0.contains(&consumer)
+    }
+
+    /// Every browned out consumer
+    pub fn iter(&self) -> impl Iterator<Item = &EnergyConsumer> {
+        self.0.iter()
+    }
+}
+
+impl IdentifiableComponent for EnergyBrownout {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:energy_brownout"
+    }
+}
+
+impl SyncableComponent for EnergyBrownout {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to move a consumer up one spot in the energy priorities of the ship they are piloting
+pub struct RaiseEnergyPriorityEvent {
+    /// The consumer to raise
+    pub consumer: EnergyConsumer,
+}
+
+impl IdentifiableEvent for RaiseEnergyPriorityEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:raise_energy_priority"
+    }
+}
+
+impl NettyEvent for RaiseEnergyPriorityEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<EnergyPriorities>(app);
+    sync_component::<EnergyBrownout>(app);
+
+    app.register_type::<EnergyPriorities>()
+        .register_type::<EnergyBrownout>()
+        .add_netty_event::<RaiseEnergyPriorityEvent>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lowest_priority_browns_out_first() {
+        let priorities = EnergyPriorities::default();
+
+        let brownout = priorities.browned_out(0.25, &EnergyBrownout::default());
+        assert_eq!(brownout.0, vec![EnergyConsumer::Utility]);
+
+        let brownout = priorities.browned_out(0.0, &brownout);
+        assert_eq!(
+            brownout.0,
+            vec![EnergyConsumer::Weapons, EnergyConsumer::Thrusters, EnergyConsumer::Utility]
+        );
+
+        // Needs to pass the reserve by a bit to recover
+        let brownout = priorities.browned_out(0.32, &brownout);
+        assert_eq!(brownout.0, vec![EnergyConsumer::Utility]);
+    }
+
+    #[test]
+    fn raising_swaps_with_the_one_above() {
+        let mut priorities = EnergyPriorities::default();
+
+        priorities.raise(EnergyConsumer::Thrusters);
+        assert_eq!(priorities.rank(EnergyConsumer::Thrusters), 1);
+        assert_eq!(priorities.rank(EnergyConsumer::Weapons), 2);
+
+        priorities.raise(EnergyConsumer::Shields);
+        assert_eq!(priorities.rank(EnergyConsumer::Shields), 0);
+    }
+}
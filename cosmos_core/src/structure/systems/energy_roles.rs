@@ -0,0 +1,74 @@
+//! Which blocks generate, store, and consume energy.
+//!
+//! The server fills this registry from the properties of its structure systems, which is then sent to
+//! the client so it can show players how energy flows through their structures.
+
+use bevy::prelude::App;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::Block,
+    netty::sync::registry::sync_registry,
+    registry::{create_registry, identifiable::Identifiable},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// What a block does with energy. A block can have more than one role (ex: ship cores).
+pub struct EnergyRoles {
+    /// This block adds energy to the structure
+    pub generator: bool,
+    /// This block increases how much energy the structure can hold
+    pub storage: bool,
+    /// This block uses energy from the structure
+    pub consumer: bool,
+}
+
+impl EnergyRoles {
+    /// Returns true if this block does nothing with energy
+    pub fn is_empty(&self) -> bool {
+        !self.generator && !self.storage && !self.consumer
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The [`EnergyRoles`] of a block. Look this up using the block's unlocalized name.
+pub struct BlockEnergyRoles {
+    id: u16,
+    unlocalized_name: String,
+    roles: EnergyRoles,
+}
+
+impl BlockEnergyRoles {
+    /// Gives this block these energy roles
+    pub fn new(block: &Block, roles: EnergyRoles) -> Self {
+        Self {
+            id: 0,
+            unlocalized_name: block.unlocalized_name().to_owned(),
+            roles,
+        }
+    }
+
+    /// What this block does with energy
+    pub fn roles(&self) -> EnergyRoles {
+        self.roles
+    }
+}
+
+impl Identifiable for BlockEnergyRoles {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    create_registry::<BlockEnergyRoles>(app, "cosmos:block_energy_roles");
+    sync_registry::<BlockEnergyRoles>(app);
+}
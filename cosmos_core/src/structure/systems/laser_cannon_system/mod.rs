@@ -5,7 +5,10 @@ use std::time::Duration;
 use bevy::{prelude::*, reflect::Reflect, utils::HashMap};
 use serde::{Deserialize, Serialize};
 
-use crate::prelude::BlockCoordinate;
+use crate::{
+    block::block_direction::BlockDirection,
+    prelude::{BlockCoordinate, Structure},
+};
 
 use super::{
     line_system::{LineProperty, LinePropertyCalculator, LineSystem},
@@ -20,6 +23,68 @@ pub type LaserCannonSystem = LineSystem<LaserCannonProperty, LaserCannonCalculat
 
 impl SyncableSystem for LaserCannonSystem {}
 
+/// How fast a laser will travel (m/s) ignoring the speed of its shooter.
+pub const LASER_BASE_VELOCITY: f32 = 200.0;
+
+impl LaserCannonSystem {
+    /// The average position (relative to the structure) that this structure's forward-facing cannons fire from.
+    ///
+    /// Lasers travel in a straight line from their muzzle, so forward-facing shots are centered around this point
+    /// at any range in front of the structure.
+    ///
+    /// Returns `None` if no cannons face forward.
+    pub fn forward_muzzle_center(&self, structure: &Structure) -> Option<Vec3> {
+        let (sum, count) = self
+            .lines
+            .iter()
+            .filter(|line| line.direction == BlockDirection::NegZ)
+            .fold((Vec3::ZERO, 0), |(sum, count), line| {
+                (sum + structure.block_relative_position(line.start), count + 1)
+            });
+
+        (count != 0).then(|| sum / count as f32)
+    }
+}
+
+/// Computes how long a projectile fired at `projectile_speed` would take to hit a target moving in a straight line.
+///
+/// * `relative_position` - The target's position relative to where the projectile is fired from
+/// * `relative_velocity` - The target's velocity minus the shooter's velocity. Lasers inherit their shooter's velocity,
+///   so only the difference matters.
+///
+/// Returns `None` if the projectile can never catch up to the target.
+pub fn projectile_intercept_time(relative_position: Vec3, relative_velocity: Vec3, projectile_speed: f32) -> Option<f32> {
+    // Solves |relative_position + relative_velocity * t| = projectile_speed * t for the smallest positive t
+    let a = relative_velocity.length_squared() - projectile_speed * projectile_speed;
+    let b = 2.0 * relative_position.dot(relative_velocity);
+    let c = relative_position.length_squared();
+
+    if a.abs() < f32::EPSILON {
+        // The target moves as fast as the projectile, so this is linear
+        if b >= 0.0 {
+            return None;
+        }
+
+        return Some(-c / b);
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let sqrt_disc = discriminant.sqrt();
+    let t1 = (-b - sqrt_disc) / (2.0 * a);
+    let t2 = (-b + sqrt_disc) / (2.0 * a);
+
+    match (t1 >= 0.0, t2 >= 0.0) {
+        (true, true) => Some(t1.min(t2)),
+        (true, false) => Some(t1),
+        (false, true) => Some(t2),
+        (false, false) => None,
+    }
+}
+
 #[derive(Default, Reflect, Clone, Copy, Debug, Serialize, Deserialize)]
 /// Every block that is a laser cannon should have this property
 pub struct LaserCannonProperty {
@@ -91,3 +156,35 @@ pub(super) fn register(app: &mut App) {
                 .after(StructureSystemsSet::InitSystems),
         );
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Vec3;
+
+    use super::projectile_intercept_time;
+
+    #[test]
+    fn intercept_stationary_target() {
+        let t = projectile_intercept_time(Vec3::new(0.0, 0.0, -100.0), Vec3::ZERO, 50.0);
+
+        assert_eq!(t, Some(2.0));
+    }
+
+    #[test]
+    fn intercept_crossing_target() {
+        let position = Vec3::new(0.0, 0.0, -400.0);
+        let velocity = Vec3::new(30.0, 0.0, 0.0);
+
+        let t = projectile_intercept_time(position, velocity, 50.0).expect("Should be able to hit this target");
+
+        // The projectile and target should be at the same spot after t seconds
+        assert!(((position + velocity * t).length() - 50.0 * t).abs() < 0.01);
+    }
+
+    #[test]
+    fn cannot_catch_fleeing_target() {
+        let t = projectile_intercept_time(Vec3::new(0.0, 0.0, -100.0), Vec3::new(0.0, 0.0, -80.0), 50.0);
+
+        assert_eq!(t, None);
+    }
+}
@@ -153,6 +153,19 @@ impl<T: LineProperty> Line<T> {
         )
     }
 
+    /// Iterates over the coordinates of every block in this line, starting at [`Self::start`]
+    pub fn iter_blocks(&self) -> impl Iterator<Item = BlockCoordinate> + '_ {
+        let (dx, dy, dz) = self.direction.to_i32_tuple();
+
+        (0..self.len as i32).map(move |i| {
+            BlockCoordinate::new(
+                (self.start.x as i32 + i * dx) as CoordinateType,
+                (self.start.y as i32 + i * dy) as CoordinateType,
+                (self.start.z as i32 + i * dz) as CoordinateType,
+            )
+        })
+    }
+
     /// Checks if this line is *individually* active.
     /// A structure system can be wholly active, or it can have individual lines active (usually through logic).
     ///
@@ -23,17 +23,25 @@ use crate::{
 
 use super::{loading::StructureLoadingSet, shared::MeltingDown, ship::Ship, Structure};
 
+use activation_groups::{ActivationGroups, N_ACTIVATION_GROUPS};
+
+pub mod activation_groups;
 pub mod camera_system;
+pub mod cloaking_system;
 pub mod dock_system;
 pub mod energy_generation_system;
+pub mod energy_priority;
+pub mod energy_roles;
 pub mod energy_storage_system;
 pub mod laser_cannon_system;
 pub mod line_system;
 pub mod mining_laser_system;
 pub mod missile_launcher_system;
 pub mod shield_system;
+pub mod subsystem_targeting;
 pub mod sync;
 pub mod thruster_system;
+pub mod turret_system;
 
 #[derive(Component)]
 #[component(storage = "SparseSet")]
@@ -52,16 +60,16 @@ pub mod thruster_system;
 pub struct SystemActive;
 
 #[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, Reflect)]
-/// Sets the system the player has selected
+/// Sets the activation group the player has selected. See [`ActivationGroups`].
 pub enum ShipActiveSystem {
-    /// No system hovered/active
+    /// No group hovered/active
     #[default]
     None,
-    /// A system is being hovered by the user, but is not being activated.
+    /// A group is being hovered by the user, but is not being activated.
     ///
     /// (Usefor for missile that need time to focus before being used)
     Hovered(u32),
-    /// The user is actively firing the system
+    /// The user is actively firing the systems in this group
     Active(u32),
 }
 
@@ -167,10 +175,14 @@ pub struct StructureSystems {
     activatable_systems: Vec<StructureSystemId>,
     /// The system ids
     ids: HashMap<StructureSystemId, Entity>,
-    /// More than just one system can be active at a time, but the pilot can only personally activate one system at a time
+    /// The unlocalized name of each system's [`StructureSystemType`]
+    type_names: HashMap<StructureSystemId, String>,
+    /// More than just one system can be active at a time, but the pilot can only personally activate one group at a time
     /// Perhaps make this a component on the pilot entity in the future?
     /// Currently this limits a ship to one pilot, the above would fix this issue, but this is a future concern.
     active_system: ShipActiveSystem,
+    /// The systems the pilot is currently activating
+    active_entities: Vec<Entity>,
     entity: Entity,
 }
 
@@ -241,6 +253,7 @@ impl StructureSystems {
 
     fn insert_system(&mut self, system_id: StructureSystemId, system_type: &StructureSystemType, entity: Entity) {
         self.ids.insert(system_id, entity);
+        self.type_names.insert(system_id, system_type.unlocalized_name().to_owned());
         self.systems.push(system_id);
         // This ensures the client + server have the same order, which is important.
         // Making this up to user preference would be pointless, since they only should be able
@@ -259,75 +272,97 @@ impl StructureSystems {
         self.ids.get(&system_id).copied()
     }
 
-    /// Activates the passed in selected system, and deactivates the system that was previously selected
+    /// How many activation groups the pilot can choose from
+    pub fn n_activation_groups(&self, groups: Option<&ActivationGroups>) -> usize {
+        match groups {
+            Some(_) => N_ACTIVATION_GROUPS,
+            None => self.activatable_systems.len(),
+        }
+    }
+
+    /// The activatable systems the pilot activates when using this activation group.
     ///
-    /// The passed in system index must be based off the [`Self::all_activatable_systems`] iterator.
-    pub fn set_active_system(&mut self, active: ShipActiveSystem, commands: &mut Commands) {
-        if active == self.active_system {
-            return;
+    /// Ships without [`ActivationGroups`] have each activatable system in its own group.
+    pub fn activation_group_systems(&self, group: u32, groups: Option<&ActivationGroups>) -> Vec<Entity> {
+        match groups {
+            Some(groups) => self
+                .activatable_systems
+                .iter()
+                .filter(|id| {
+                    self.type_names
+                        .get(id)
+                        .is_some_and(|type_name| groups.contains(group as usize, type_name))
+                })
+                .map(|id| *self.ids.get(id).expect("Invalid state - system id has no entity mapping"))
+                .collect(),
+            None => self
+                .try_get_activatable_system_from_activatable_index(group as usize)
+                .into_iter()
+                .collect(),
         }
+    }
 
-        if let ShipActiveSystem::Active(active_system) = self.active_system {
-            if (active_system as usize) < self.activatable_systems.len() {
-                let ent = self
-                    .ids
-                    .get(&self.activatable_systems[active_system as usize])
-                    .expect("Invalid state - system id has no entity mapping");
+    /// The unlocalized name of each activatable system's type, in the order of [`Self::all_activatable_systems`]
+    pub fn activatable_system_types(&self) -> impl Iterator<Item = &str> {
+        self.activatable_systems
+            .iter()
+            .map(|id| self.type_names.get(id).map(|x| x.as_str()).unwrap_or_default())
+    }
 
-                commands.entity(*ent).remove::<SystemActive>();
-            }
+    /// The activation groups a ship without [`ActivationGroups`] uses, with each activatable system in its own group
+    pub fn default_activation_groups(&self) -> ActivationGroups {
+        let mut groups = ActivationGroups::default();
+
+        for (group, system_type) in self.activatable_system_types().enumerate().take(N_ACTIVATION_GROUPS) {
+            groups.set_in_group(group, system_type, true);
         }
 
-        match active {
-            ShipActiveSystem::Active(active_system) => {
-                if (active_system as usize) < self.activatable_systems.len() {
-                    let ent = self
-                        .ids
-                        .get(&self.activatable_systems[active_system as usize])
-                        .expect("Invalid state - system id has no entity mapping");
+        groups
+    }
+
+    /// Activates every system in the selected activation group, and deactivates the systems that were previously active.
+    ///
+    /// This should be called again whenever the ship's [`ActivationGroups`] change.
+    pub fn set_active_system(&mut self, active: ShipActiveSystem, groups: Option<&ActivationGroups>, commands: &mut Commands) {
+        let n_groups = self.n_activation_groups(groups);
 
-                    commands.entity(*ent).insert(SystemActive);
+        let active = match active {
+            ShipActiveSystem::Active(group) | ShipActiveSystem::Hovered(group) if group as usize >= n_groups => ShipActiveSystem::None,
+            active => active,
+        };
 
-                    self.active_system = active;
-                } else {
-                    self.active_system = ShipActiveSystem::None;
-                }
-            }
-            ShipActiveSystem::Hovered(hovered_system) => {
-                if (hovered_system as usize) < self.activatable_systems.len() {
-                    self.active_system = active;
-                } else {
-                    self.active_system = ShipActiveSystem::None;
-                }
-            }
-            ShipActiveSystem::None => self.active_system = ShipActiveSystem::None,
+        let new_active_entities = match active {
+            ShipActiveSystem::Active(group) => self.activation_group_systems(group, groups),
+            _ => vec![],
+        };
+
+        if active == self.active_system && new_active_entities == self.active_entities {
+            return;
         }
-    }
 
-    /// Returns the active system entity, if there is one.
-    pub fn active_system(&self) -> Option<Entity> {
-        match self.active_system {
-            ShipActiveSystem::Active(active_system_idx) => Some(
-                *self
-                    .ids
-                    .get(&self.activatable_systems[active_system_idx as usize])
-                    .expect("Invalid state - system id has no entity mapping"),
-            ),
-            _ => None,
+        for ent in self.active_entities.iter().filter(|e| !new_active_entities.contains(e)) {
+            commands.entity(*ent).remove::<SystemActive>();
+        }
+
+        for ent in new_active_entities.iter().filter(|e| !self.active_entities.contains(e)) {
+            commands.entity(*ent).insert(SystemActive);
         }
+
+        self.active_system = active;
+        self.active_entities = new_active_entities;
+    }
+
+    /// Returns the systems the pilot is currently activating
+    pub fn active_systems(&self) -> &[Entity] {
+        &self.active_entities
     }
 
-    /// Returns the hovered system entity, if there is one.
+    /// Returns the activation group the pilot has selected, if there is one.
     ///
-    /// If this system is active, it would still also count as hovered.
-    pub fn hovered_system(&self) -> Option<Entity> {
+    /// If this group is active, it would still also count as hovered.
+    pub fn hovered_group(&self) -> Option<u32> {
         match self.active_system {
-            ShipActiveSystem::Active(active_system_idx) | ShipActiveSystem::Hovered(active_system_idx) => Some(
-                *self
-                    .ids
-                    .get(&self.activatable_systems[active_system_idx as usize])
-                    .expect("Invalid state - system id has no entity mapping"),
-            ),
+            ShipActiveSystem::Active(group) | ShipActiveSystem::Hovered(group) => Some(group),
             ShipActiveSystem::None => None,
         }
     }
@@ -439,7 +474,9 @@ fn add_structure(mut commands: Commands, query: Query<Entity, (Added<Structure>,
             activatable_systems: Vec::new(),
             entity,
             active_system: ShipActiveSystem::None,
+            active_entities: Vec::new(),
             ids: Default::default(),
+            type_names: Default::default(),
         });
     }
 }
@@ -525,14 +562,20 @@ pub(super) fn register(app: &mut App) {
     .register_type::<StructureSystem>()
     .register_type::<StructureSystems>();
 
+    activation_groups::register(app);
+    subsystem_targeting::register(app);
     line_system::register(app);
     shield_system::register(app);
     camera_system::register(app);
     energy_storage_system::register(app);
     energy_generation_system::register(app);
+    energy_roles::register(app);
+    energy_priority::register(app);
     thruster_system::register(app);
     missile_launcher_system::register(app);
     laser_cannon_system::register(app);
     mining_laser_system::register(app);
     dock_system::register(app);
+    cloaking_system::register(app);
+    turret_system::register(app);
 }
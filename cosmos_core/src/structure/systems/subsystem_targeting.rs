@@ -0,0 +1,87 @@
+//! Pilots can target a specific subsystem of the structures they shoot at, which makes their lasers favor
+//! blocks belonging to that subsystem.
+//!
+//! This lets pilots disable a ship (ex: knock out its engines) instead of only being able to destroy it.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::netty::sync::{
+    events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+    sync_component, IdentifiableComponent, SyncType, SyncableComponent,
+};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+/// The subsystem this ship's weapons are aimed at.
+///
+/// If a ship doesn't have this component, its weapons hit wherever they land.
+pub enum TargetedSubsystem {
+    /// Thrusters
+    Engines,
+    /// Laser cannons & missile launchers
+    Weapons,
+    /// Anything that generates or stores energy
+    Power,
+}
+
+impl TargetedSubsystem {
+    /// Every subsystem, in the order they are cycled through
+    pub const ALL: [Self; 3] = [Self::Engines, Self::Weapons, Self::Power];
+
+    /// The subsystem after this one when cycling. Cycling past the last subsystem goes back to targeting nothing.
+    pub fn cycle(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(Self::Engines),
+            Some(Self::Engines) => Some(Self::Weapons),
+            Some(Self::Weapons) => Some(Self::Power),
+            Some(Self::Power) => None,
+        }
+    }
+
+    /// The lang key used to display this subsystem
+    pub fn unlocalized_name(&self) -> &'static str {
+        match self {
+            Self::Engines => "cosmos:subsystem.engines",
+            Self::Weapons => "cosmos:subsystem.weapons",
+            Self::Power => "cosmos:subsystem.power",
+        }
+    }
+}
+
+impl IdentifiableComponent for TargetedSubsystem {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:targeted_subsystem"
+    }
+}
+
+impl SyncableComponent for TargetedSubsystem {
+    fn get_sync_type() -> SyncType {
+        SyncType::ServerAuthoritative
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to change which subsystem the ship they are piloting is targeting.
+pub struct SetTargetedSubsystemEvent {
+    /// The subsystem to target, or `None` to stop targeting subsystems
+    pub subsystem: Option<TargetedSubsystem>,
+}
+
+impl IdentifiableEvent for SetTargetedSubsystemEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:set_targeted_subsystem"
+    }
+}
+
+impl NettyEvent for SetTargetedSubsystemEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<TargetedSubsystem>(app);
+
+    app.register_type::<TargetedSubsystem>()
+        .add_netty_event::<SetTargetedSubsystemEvent>();
+}
@@ -35,6 +35,11 @@ impl ThrusterBlocks {
     pub fn get(&self, block: &Block) -> Option<&ThrusterProperty> {
         self.blocks.get(&block.id())
     }
+
+    /// Iterates over every block id that has a property
+    pub fn iter(&self) -> impl Iterator<Item = (u16, &ThrusterProperty)> {
+        self.blocks.iter().map(|(&id, prop)| (id, prop))
+    }
 }
 
 #[derive(Component, Default, Reflect, Serialize, Deserialize, Debug)]
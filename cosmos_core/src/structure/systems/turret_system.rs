@@ -0,0 +1,98 @@
+//! Turrets are ships that are mounted on top of a turret mount block of another structure.
+//!
+//! A turret rotates independently of the structure it is mounted to, and fires its own weapons
+//! while its mount block receives a logic signal.
+
+use bevy::{
+    ecs::entity::Entity,
+    prelude::{App, Component},
+    reflect::Reflect,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::sync::{sync_component, IdentifiableComponent, SyncableComponent},
+    structure::coordinates::BlockCoordinate,
+};
+
+use super::{sync::SyncableSystem, StructureSystemImpl};
+
+/// The block placed on the parent structure that turrets are mounted to
+pub const TURRET_MOUNT_BLOCK: &str = "cosmos:turret_mount";
+/// The block on the turret that sits on top of a turret mount
+pub const TURRET_BASE_BLOCK: &str = "cosmos:turret_base";
+
+#[derive(Component, Default, Reflect, Serialize, Deserialize, Debug)]
+/// Keeps track of all the turret base blocks on a structure
+pub struct TurretSystem {
+    base_blocks: Vec<BlockCoordinate>,
+}
+
+impl TurretSystem {
+    /// Call this whenever a block is added to the system
+    pub fn block_added(&mut self, location: BlockCoordinate) {
+        self.base_blocks.push(location)
+    }
+
+    /// Call this whenever a block is removed from the system
+    pub fn block_removed(&mut self, location: BlockCoordinate) {
+        let Some((idx, _)) = self.base_blocks.iter().enumerate().find(|(_, &x)| x == location) else {
+            return;
+        };
+
+        self.base_blocks.remove(idx);
+    }
+
+    /// Returns all the turret base locations
+    pub fn block_locations(&self) -> &[BlockCoordinate] {
+        self.base_blocks.as_slice()
+    }
+}
+
+impl SyncableSystem for TurretSystem {}
+
+impl StructureSystemImpl for TurretSystem {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:turret_system"
+    }
+}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone, PartialEq, Reflect)]
+/// If a structure is mounted as a turret on another structure, it will have this component
+pub struct Turret {
+    /// The structure this turret is mounted on
+    pub mounted_to: Entity,
+    /// The turret mount block on the structure this is mounted to
+    pub mount_block: BlockCoordinate,
+    /// The turret base block on this structure
+    pub base_block: BlockCoordinate,
+}
+
+impl IdentifiableComponent for Turret {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:turret"
+    }
+}
+
+impl SyncableComponent for Turret {
+    fn get_sync_type() -> crate::netty::sync::SyncType {
+        crate::netty::sync::SyncType::ServerAuthoritative
+    }
+
+    #[cfg(feature = "client")]
+    fn needs_entity_conversion() -> bool {
+        true
+    }
+
+    #[cfg(feature = "client")]
+    fn convert_entities_server_to_client(mut self, mapping: &crate::netty::sync::mapping::NetworkMapping) -> Option<Self> {
+        self.mounted_to = mapping.client_from_server(&self.mounted_to)?;
+        Some(self)
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    sync_component::<Turret>(app);
+
+    app.register_type::<TurretSystem>().register_type::<Turret>();
+}
@@ -0,0 +1,44 @@
+//! Welding merges a smaller ship (a "module") into a larger ship or station it is touching.
+//!
+//! While holding a [`WELDING_TOOL_ITEM`], a player selects a block on the module and then a block on the structure
+//! to weld it to. The module's blocks, along with their block data (inventories, logic, etc.), are then moved onto the
+//! larger structure and the module is removed.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::sync::events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+    structure::structure_block::StructureBlock,
+};
+
+/// The item used to weld structures together
+pub const WELDING_TOOL_ITEM: &str = "cosmos:welding_tool";
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the client to request welding the `module`'s structure onto the `target`'s structure.
+///
+/// The server will ignore this if the player isn't holding the welding tool, the structures aren't
+/// touching & lined up with each other, or the module doesn't fit on the target.
+pub struct WeldStructuresEvent {
+    /// A block on the structure that will be merged into the target
+    pub module: StructureBlock,
+    /// A block on the structure the module will be merged into
+    pub target: StructureBlock,
+}
+
+impl IdentifiableEvent for WeldStructuresEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:weld_structures"
+    }
+}
+
+impl NettyEvent for WeldStructuresEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Server
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_netty_event::<WeldStructuresEvent>();
+}
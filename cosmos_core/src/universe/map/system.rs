@@ -197,6 +197,10 @@ pub struct SystemMapResponseEvent {
     pub system: SystemCoordinate,
     /// The map data
     pub map: SystemMap,
+    /// If the player has been to this system before.
+    ///
+    /// If not, the map will be empty.
+    pub explored: bool,
 }
 
 impl IdentifiableEvent for SystemMapResponseEvent {
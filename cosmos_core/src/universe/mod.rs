@@ -3,9 +3,11 @@
 use bevy::prelude::App;
 
 pub mod map;
+pub mod sector_rules;
 pub mod star;
 
 pub(super) fn register(app: &mut App) {
     star::register(app);
     map::register(app);
+    sector_rules::register(app);
 }
@@ -0,0 +1,82 @@
+//! Every sector has rules that decide what players are allowed to do there.
+//!
+//! Sectors with shops are lawful, the sectors around them are policed, and the rest of space is lawless.
+
+use bevy::{prelude::*, reflect::Reflect};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    netty::sync::events::netty_event::{EventReceiver, IdentifiableEvent, NettyEvent, SyncedEventImpl},
+    physics::location::Sector,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+/// What players are allowed to do within a sector
+pub struct SectorRules {
+    /// If player-owned structures can damage other player-owned structures here
+    pub pvp: bool,
+    /// If players (that aren't admins) can place and break blocks here
+    pub block_editing: bool,
+    /// If crimes here are punished. Destroying a ship puts a bounty on the player responsible, and pirates stay away.
+    pub police_response: bool,
+}
+
+impl SectorRules {
+    /// The rules of lawless space - anything goes, and nobody will come to help.
+    pub const LAWLESS: Self = Self {
+        pvp: true,
+        block_editing: true,
+        police_response: false,
+    };
+
+    /// The rules of lawful space, such as around shops.
+    pub const LAWFUL: Self = Self {
+        pvp: false,
+        block_editing: true,
+        police_response: true,
+    };
+
+    /// The rules of policed space, such as the sectors surrounding shops. Players can fight here, but anyone who
+    /// destroys a ship gets a bounty put on them.
+    pub const POLICED: Self = Self {
+        pvp: true,
+        block_editing: true,
+        police_response: true,
+    };
+
+    /// Returns true if these are the rules of lawless space
+    pub fn is_lawless(&self) -> bool {
+        self.pvp && !self.police_response
+    }
+}
+
+impl Default for SectorRules {
+    fn default() -> Self {
+        Self::LAWLESS
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Sent by the server to a player when they enter a sector with different rules than the one they were in.
+pub struct SectorRulesChangedEvent {
+    /// The sector the player is now in
+    pub sector: Sector,
+    /// The rules of that sector
+    pub rules: SectorRules,
+}
+
+impl IdentifiableEvent for SectorRulesChangedEvent {
+    fn unlocalized_name() -> &'static str {
+        "cosmos:sector_rules_changed"
+    }
+}
+
+impl NettyEvent for SectorRulesChangedEvent {
+    fn event_receiver() -> EventReceiver {
+        EventReceiver::Client
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.register_type::<SectorRules>().add_netty_event::<SectorRulesChangedEvent>();
+}
@@ -17,7 +17,7 @@ use crate::persistence::{
     SerializedData,
 };
 
-mod pirate;
+pub(crate) mod pirate;
 
 #[derive(Component)]
 /// This entity is controlled by NPCs
@@ -1,6 +1,6 @@
 use bevy::{
     app::{App, Update},
-    core::Name,
+    core::{FrameCount, Name},
     ecs::{
         component::Component,
         entity::Entity,
@@ -29,7 +29,11 @@ use cosmos_core::{
             ship_movement::{ShipMovement, ShipMovementSet},
             Ship,
         },
-        systems::{laser_cannon_system::LaserCannonSystem, StructureSystems, SystemActive},
+        systems::{
+            cloaking_system::{cloaked_ancestor, Cloaked},
+            laser_cannon_system::{LaserCannonSystem, LASER_BASE_VELOCITY},
+            StructureSystems, SystemActive,
+        },
         StructureTypeSet,
     },
 };
@@ -41,8 +45,7 @@ use crate::{
         saving::{SavingSystemSet, SAVING_SCHEDULE},
         SerializedData,
     },
-    structure::systems::laser_cannon_system::LASER_BASE_VELOCITY,
-    universe::spawners::pirate::Pirate,
+    universe::{sector_load::SectorLoad, spawners::pirate::Pirate},
 };
 
 use super::AiControlled;
@@ -85,7 +88,10 @@ fn handle_pirate_movement(
     q_parent: Query<&Parent>,
     q_velocity: Query<&Velocity>,
     q_targets: Query<(Entity, &Location, &Velocity, Has<MeltingDown>), (Without<Pirate>, With<PirateTarget>)>,
+    q_cloaked: Query<(), With<Cloaked>>,
     time: Res<Time>,
+    sector_load: Res<SectorLoad>,
+    frame: Res<FrameCount>,
 ) {
     for (
         pirate_ent,
@@ -98,9 +104,16 @@ fn handle_pirate_movement(
         pirate_g_transform,
     ) in q_pirates.iter_mut()
     {
+        // Pirates in overloaded sectors keep doing what they were last doing until their next update
+        if !sector_load.should_run_low_priority(pirate_loc.sector(), pirate_ent, &frame) {
+            continue;
+        }
+
         let Some((target_ent, target_loc, target_vel, _)) = q_targets
             .iter()
             .filter(|x| x.1.is_within_reasonable_range(pirate_loc))
+            // pirates can't see cloaked ships or anyone on them
+            .filter(|x| cloaked_ancestor(x.0, &q_cloaked, &q_parent).is_none())
             // add a large penalty for something that's melting down so they prioritize non-melting down things
             .min_by_key(|(_, this_loc, _, melting_down)| {
                 // Makes it only target melting down targets if they're the only one nearby
@@ -3,7 +3,7 @@ use bevy_renet2::renet2::RenetServer;
 use cosmos_core::{
     events::block_events::{BlockChangedEvent, BlockDataChangedEvent},
     netty::{
-        cosmos_encoder,
+        network_stats::{NettyMessage, NettyServerMessages},
         server_reliable_messages::{BlockChanged, BlocksChangedPacket, ServerReliableMessages},
         system_sets::NetworkingSystemsSet,
         NettyChannelServer,
@@ -49,9 +49,9 @@ fn handle_block_changed_event(
     }
 
     for (entity, v) in map {
-        server.broadcast_message(
+        server.broadcast_netty_message(
             NettyChannelServer::Reliable,
-            cosmos_encoder::serialize(&ServerReliableMessages::BlockChange {
+            NettyMessage::new(&ServerReliableMessages::BlockChange {
                 structure_entity: entity,
                 blocks_changed_packet: BlocksChangedPacket(v),
             }),
@@ -0,0 +1,238 @@
+//! Hands out [`BlockTickEvent`]s to blocks in loaded chunks near players.
+//!
+//! Every block tick, each nearby chunk has a few random blocks picked for random ticks, and any interval or scheduled
+//! ticks that are due are sent. Only so many ticks are sent each frame - the rest stay queued until the next block tick.
+//!
+//! Every chunk of a structure with a [`ChunkAnchor`] is ticked, even without players nearby.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use cosmos_core::{
+    block::{
+        block_events::BlockEventsSet,
+        block_tick::{BlockTickEvent, BlockTickKind, ScheduledBlockTicks, ScheduledTick, BLOCK_TICKS_PER_SECOND},
+        Block,
+    },
+    entities::player::Player,
+    events::block_events::BlockChangedEvent,
+    netty::system_sets::NetworkingSystemsSet,
+    physics::location::Location,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        block_storage::BlockStorer,
+        chunk::CHUNK_DIMENSIONS,
+        coordinates::{BlockCoordinate, ChunkBlockCoordinate, ChunkCoordinate},
+        events::StructureLoadedEvent,
+        structure_block::StructureBlock,
+        ChunkInitEvent, Structure,
+    },
+};
+use rand::Rng;
+
+use crate::structure::anchor::ChunkAnchor;
+
+/// How many blocks in each chunk are picked for random ticks every block tick
+const RANDOM_TICKS_PER_CHUNK: usize = 3;
+
+/// The most blocks that can be ticked in one block tick, across every structure
+const MAX_BLOCK_TICKS: usize = 4096;
+
+/// Only chunks with their center this close to a player are ticked
+const BLOCK_TICK_DISTANCE: f32 = 256.0;
+
+fn add_scheduled_block_ticks(mut commands: Commands, q_structures: Query<Entity, (With<Structure>, Without<ScheduledBlockTicks>)>) {
+    for ent in q_structures.iter() {
+        commands.entity(ent).insert(ScheduledBlockTicks::default());
+    }
+}
+
+fn schedule_interval_tick(ticks: &mut ScheduledBlockTicks, coords: BlockCoordinate, block: &Block) {
+    let Some(interval) = block.ticking().interval else {
+        return;
+    };
+
+    ticks.schedule_tick(
+        ScheduledTick {
+            coords,
+            block_id: block.id(),
+            kind: BlockTickKind::Interval,
+        },
+        interval,
+    );
+}
+
+fn schedule_placed_blocks(
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    blocks: Res<Registry<Block>>,
+    mut q_ticks: Query<&mut ScheduledBlockTicks>,
+) {
+    for ev in evr_block_changed.read() {
+        let block = blocks.from_numeric_id(ev.new_block);
+        if block.ticking().interval.is_none() {
+            continue;
+        }
+
+        let Ok(mut ticks) = q_ticks.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        schedule_interval_tick(&mut ticks, ev.block.coords(), block);
+    }
+}
+
+/// Blocks that were loaded instead of placed don't send [`BlockChangedEvent`]s, so they are found here instead
+fn schedule_loaded_blocks(
+    mut evr_chunk_init: EventReader<ChunkInitEvent>,
+    mut evr_structure_loaded: EventReader<StructureLoadedEvent>,
+    blocks: Res<Registry<Block>>,
+    mut q_structure: Query<(&Structure, &mut ScheduledBlockTicks)>,
+) {
+    // Almost every chunk is loaded without any interval blocks, so don't bother scanning when there can't be any
+    if !blocks.iter().any(|block| block.ticking().interval.is_some()) {
+        evr_chunk_init.clear();
+        evr_structure_loaded.clear();
+        return;
+    }
+
+    for ev in evr_chunk_init.read() {
+        let Ok((structure, mut ticks)) = q_structure.get_mut(ev.structure_entity) else {
+            continue;
+        };
+
+        // Anything left over from the last time this chunk was loaded is stale now
+        ticks.clear_chunk(ev.coords);
+
+        for coords in structure.block_iter_for_chunk(ev.coords, false) {
+            schedule_interval_tick(&mut ticks, coords, structure.block_at(coords, &blocks));
+        }
+    }
+
+    for ev in evr_structure_loaded.read() {
+        let Ok((structure, mut ticks)) = q_structure.get_mut(ev.structure_entity) else {
+            continue;
+        };
+
+        *ticks = ScheduledBlockTicks::default();
+
+        for coords in structure.all_blocks_iter(false) {
+            schedule_interval_tick(&mut ticks, coords, structure.block_at(coords, &blocks));
+        }
+    }
+}
+
+fn near_player(
+    chunk: ChunkCoordinate,
+    structure: &Structure,
+    g_trans: &GlobalTransform,
+    location: &Location,
+    players: &[Location],
+) -> bool {
+    let chunk_location = structure.block_world_location(chunk.middle_structure_block(), g_trans, location);
+
+    players
+        .iter()
+        .any(|player| player.distance_sqrd(&chunk_location) < BLOCK_TICK_DISTANCE * BLOCK_TICK_DISTANCE)
+}
+
+fn tick_blocks(
+    blocks: Res<Registry<Block>>,
+    q_players: Query<&Location, With<Player>>,
+    mut q_structures: Query<(
+        Entity,
+        &Structure,
+        &Location,
+        &GlobalTransform,
+        &mut ScheduledBlockTicks,
+        Has<ChunkAnchor>,
+    )>,
+    mut evw_block_tick: EventWriter<BlockTickEvent>,
+) {
+    let players = q_players.iter().copied().collect::<Vec<_>>();
+    let any_random = blocks.iter().any(|block| block.ticking().random);
+
+    let mut rng = rand::thread_rng();
+    let mut budget = MAX_BLOCK_TICKS;
+
+    for (structure_ent, structure, location, g_trans, mut ticks, anchored) in q_structures.iter_mut() {
+        ticks.advance();
+
+        if budget == 0 {
+            // Out of budget - due ticks stay queued and are sent late instead.
+            continue;
+        }
+
+        if any_random {
+            for chunk in structure.chunks().values() {
+                let chunk_coords = chunk.chunk_coordinates();
+                if !anchored && !near_player(chunk_coords, structure, g_trans, location, &players) {
+                    continue;
+                }
+
+                for _ in 0..RANDOM_TICKS_PER_CHUNK {
+                    let chunk_block = ChunkBlockCoordinate::new(
+                        rng.gen_range(0..CHUNK_DIMENSIONS),
+                        rng.gen_range(0..CHUNK_DIMENSIONS),
+                        rng.gen_range(0..CHUNK_DIMENSIONS),
+                    )
+                    .expect("Random coordinates are within the chunk");
+
+                    let block_id = chunk.block_at(chunk_block);
+                    if budget == 0 || !blocks.from_numeric_id(block_id).ticking().random {
+                        continue;
+                    }
+
+                    budget -= 1;
+                    evw_block_tick.send(BlockTickEvent {
+                        block: StructureBlock::new(chunk_coords.first_structure_block() + chunk_block, structure_ent),
+                        block_id,
+                        kind: BlockTickKind::Random,
+                    });
+                }
+            }
+        }
+
+        let queued_chunks = ticks
+            .chunks()
+            .filter(|&chunk| {
+                structure.chunk_at(chunk).is_some() && (anchored || near_player(chunk, structure, g_trans, location, &players))
+            })
+            .collect::<Vec<_>>();
+
+        for chunk in queued_chunks {
+            for tick in ticks.take_due(chunk, budget) {
+                if !structure.is_within_blocks(tick.coords) || structure.block_id_at(tick.coords) != tick.block_id {
+                    continue;
+                }
+
+                budget -= 1;
+                evw_block_tick.send(BlockTickEvent {
+                    block: StructureBlock::new(tick.coords, structure_ent),
+                    block_id: tick.block_id,
+                    kind: tick.kind,
+                });
+
+                if tick.kind == BlockTickKind::Interval {
+                    schedule_interval_tick(&mut ticks, tick.coords, blocks.from_numeric_id(tick.block_id));
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            tick_blocks
+                .run_if(on_timer(Duration::from_millis(1000 / BLOCK_TICKS_PER_SECOND)))
+                .in_set(BlockEventsSet::SendEventsForThisFrame),
+            (add_scheduled_block_ticks, schedule_placed_blocks, schedule_loaded_blocks)
+                .chain()
+                .in_set(BlockEventsSet::PostProcessEvents),
+        )
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,196 @@
+//! Battery chargers hold a single battery, and charge it using the energy of the structure they're placed on
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use cosmos_core::{
+    block::{block_events::BlockEventsSet, data::BlockData, Block},
+    events::block_events::{BlockChangedEvent, BlockDataSystemParams},
+    inventory::Inventory,
+    item::battery::{BatteryCharge, BATTERY_CHARGER_BLOCK},
+    netty::system_sets::NetworkingSystemsSet,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        structure_block::StructureBlock,
+        systems::{
+            energy_priority::{EnergyBrownout, EnergyConsumer},
+            energy_storage_system::EnergyStorageSystem,
+            StructureSystems, StructureSystemsSet,
+        },
+        Structure,
+    },
+};
+
+use crate::{
+    fluid::interact_fluid::FluidInteractionSet,
+    persistence::loading::{LoadingBlueprintSystemSet, NeedsBlueprintLoaded, LOADING_SCHEDULE},
+};
+
+/// How often chargers move energy into their batteries.
+///
+/// Charging in steps instead of every frame keeps the battery's charge from being synced every frame.
+const CHARGE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How much energy per second a charger can put into a battery
+const CHARGE_RATE: f32 = 500.0;
+
+#[derive(Event, Debug)]
+/// Sent whenever a charger needs its inventory created
+struct PopulateChargerInventoryEvent {
+    block: StructureBlock,
+}
+
+fn on_add_charger(
+    mut q_structure: Query<&mut Structure>,
+    blocks: Res<Registry<Block>>,
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    mut evw_populate: EventWriter<PopulateChargerInventoryEvent>,
+    mut q_block_data: Query<&mut BlockData>,
+    mut params: BlockDataSystemParams,
+    q_has_data: Query<(), With<Inventory>>,
+) {
+    let Some(charger) = blocks.from_id(BATTERY_CHARGER_BLOCK) else {
+        return;
+    };
+
+    for ev in evr_block_changed.read() {
+        if ev.new_block == ev.old_block {
+            continue;
+        }
+
+        if ev.old_block == charger.id() {
+            if let Ok(mut structure) = q_structure.get_mut(ev.block.structure()) {
+                structure.remove_block_data::<Inventory>(ev.block.coords(), &mut params, &mut q_block_data, &q_has_data);
+            }
+        }
+
+        if ev.new_block == charger.id() {
+            evw_populate.send(PopulateChargerInventoryEvent { block: ev.block });
+        }
+    }
+}
+
+fn on_load_blueprint_charger(
+    q_needs_blueprint_loaded: Query<(Entity, &Structure), With<NeedsBlueprintLoaded>>,
+    blocks: Res<Registry<Block>>,
+    mut evw_populate: EventWriter<PopulateChargerInventoryEvent>,
+) {
+    let Some(charger) = blocks.from_id(BATTERY_CHARGER_BLOCK) else {
+        return;
+    };
+
+    for (structure_entity, structure) in q_needs_blueprint_loaded.iter() {
+        for coords in structure.all_blocks_iter(false) {
+            if structure.block_id_at(coords) == charger.id() {
+                evw_populate.send(PopulateChargerInventoryEvent {
+                    block: StructureBlock::new(coords, structure_entity),
+                });
+            }
+        }
+    }
+}
+
+fn populate_charger_inventory(
+    mut q_structure: Query<&mut Structure>,
+    mut q_block_data: Query<&mut BlockData>,
+    q_has_inventory: Query<(), With<Inventory>>,
+    mut params: BlockDataSystemParams,
+    mut evr_populate: EventReader<PopulateChargerInventoryEvent>,
+) {
+    for ev in evr_populate.read() {
+        let Ok(mut structure) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        structure.insert_block_data_with_entity(
+            ev.block.coords(),
+            |e| Inventory::new("Battery Charger", 1, None, e),
+            &mut params,
+            &mut q_block_data,
+            &q_has_inventory,
+        );
+    }
+}
+
+/// Moves energy from each charger's structure into the battery it's holding
+fn charge_batteries(
+    blocks: Res<Registry<Block>>,
+    q_chargers: Query<(&BlockData, &Inventory)>,
+    q_systems: Query<&StructureSystems>,
+    mut q_energy_storage: Query<&mut EnergyStorageSystem>,
+    mut q_charge: Query<&mut BatteryCharge>,
+    q_brownout: Query<&EnergyBrownout>,
+) {
+    let Some(charger) = blocks.from_id(BATTERY_CHARGER_BLOCK) else {
+        return;
+    };
+
+    for (block_data, inventory) in q_chargers.iter() {
+        if block_data.identifier.block_id != charger.id() {
+            continue;
+        }
+
+        let Some(mut charge) = inventory
+            .itemstack_at(0)
+            .and_then(|is| is.data_entity())
+            .and_then(|e| q_charge.get_mut(e).ok())
+        else {
+            continue;
+        };
+
+        if charge.is_full() {
+            continue;
+        }
+
+        let structure = block_data.identifier.block.structure();
+
+        if q_brownout.get(structure).is_ok_and(|b| b.contains(EnergyConsumer::Utility)) {
+            continue;
+        }
+
+        let Ok(systems) = q_systems.get(structure) else {
+            continue;
+        };
+
+        let Ok(mut storage) = systems.query_mut(&mut q_energy_storage) else {
+            continue;
+        };
+
+        let amount = (CHARGE_RATE * CHARGE_INTERVAL.as_secs_f32())
+            .min(charge.missing_energy())
+            .min(storage.get_energy());
+
+        if amount <= 0.0 {
+            continue;
+        }
+
+        storage.decrease_energy(amount);
+        charge.charge(amount);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            (
+                on_add_charger
+                    .in_set(BlockEventsSet::ProcessEvents)
+                    .ambiguous_with(FluidInteractionSet::InteractWithFluidBlocks),
+                populate_charger_inventory.in_set(BlockEventsSet::SendEventsForNextFrame),
+            )
+                .chain(),
+            charge_batteries
+                .run_if(on_timer(CHARGE_INTERVAL))
+                .in_set(StructureSystemsSet::UpdateSystems)
+                .run_if(in_state(GameState::Playing)),
+        )
+            .in_set(NetworkingSystemsSet::Between),
+    )
+    .add_systems(
+        LOADING_SCHEDULE,
+        on_load_blueprint_charger.in_set(LoadingBlueprintSystemSet::DoneLoadingBlueprints),
+    )
+    .add_event::<PopulateChargerInventoryEvent>();
+}
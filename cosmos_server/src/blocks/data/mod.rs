@@ -1,9 +1,11 @@
 use bevy::app::App;
 
 mod basic_fabricator;
+mod battery_charger;
 mod storage;
 
 pub(super) fn register(app: &mut App) {
     storage::register(app);
     basic_fabricator::register(app);
+    battery_charger::register(app);
 }
@@ -5,11 +5,12 @@ use bevy::{
     ecs::{
         entity::Entity,
         event::{EventReader, EventWriter},
-        query::With,
+        query::{Changed, With},
         schedule::IntoSystemConfigs,
-        system::{Query, Res},
+        system::{Commands, Query, Res},
     },
-    prelude::Event,
+    prelude::{DetectChanges, Event},
+    utils::HashSet,
 };
 use cosmos_core::{
     block::{block_events::BlockEventsSet, data::BlockData, Block},
@@ -17,7 +18,7 @@ use cosmos_core::{
     inventory::Inventory,
     netty::system_sets::NetworkingSystemsSet,
     registry::{identifiable::Identifiable, Registry},
-    structure::{structure_block::StructureBlock, Structure},
+    structure::{ship::ship_modifiers::ShipModifiers, structure_block::StructureBlock, Structure},
 };
 
 use crate::{
@@ -25,6 +26,9 @@ use crate::{
     persistence::loading::{LoadingBlueprintSystemSet, NeedsBlueprintLoaded, LOADING_SCHEDULE},
 };
 
+/// How many slots a storage block has before any [`ShipModifiers`] are applied
+const STORAGE_BASE_SLOTS: usize = 9 * 5;
+
 #[derive(Event, Debug)]
 /// Sent whenever an entity needs an inventory populated.
 ///
@@ -101,17 +105,20 @@ fn populate_inventory(
     q_has_inventory: Query<(), With<Inventory>>,
     mut params: BlockDataSystemParams,
     mut ev_reader: EventReader<PopulateBlockInventoryEvent>,
+    q_ship_modifiers: Query<&ShipModifiers>,
 ) {
     for ev in ev_reader.read() {
         let coords = ev.block.coords();
 
+        let n_slots = storage_slots(q_ship_modifiers.get(ev.block.structure()).ok());
+
         let Ok(mut structure) = q_structure.get_mut(ev.block.structure()) else {
             continue;
         };
 
         structure.insert_block_data_with_entity(
             coords,
-            |e| Inventory::new("Storage", 9 * 5, None, e),
+            |e| Inventory::new("Storage", n_slots, None, e),
             &mut params,
             &mut q_block_data,
             &q_has_inventory,
@@ -119,6 +126,42 @@ fn populate_inventory(
     }
 }
 
+fn storage_slots(ship_modifiers: Option<&ShipModifiers>) -> usize {
+    STORAGE_BASE_SLOTS + ship_modifiers.map(|m| m.storage_slot_bonus()).unwrap_or(0)
+}
+
+/// Cargo expanders change how many slots every storage block on their ship has, so whenever a ship's
+/// [`ShipModifiers`] change (or a storage inventory is created/loaded) the storage inventories are resized to match.
+fn resize_storage_inventories(
+    mut commands: Commands,
+    blocks: Res<Registry<Block>>,
+    q_changed_modifiers: Query<Entity, Changed<ShipModifiers>>,
+    q_ship_modifiers: Query<&ShipModifiers>,
+    mut q_storage: Query<(&BlockData, &mut Inventory)>,
+) {
+    let Some(storage_block) = blocks.from_id("cosmos:storage") else {
+        return;
+    };
+
+    let changed_structures = q_changed_modifiers.iter().collect::<HashSet<Entity>>();
+
+    for (block_data, mut inventory) in q_storage.iter_mut() {
+        if block_data.identifier.block_id != storage_block.id() {
+            continue;
+        }
+
+        let structure = block_data.identifier.block.structure();
+        if !inventory.is_added() && !changed_structures.contains(&structure) {
+            continue;
+        }
+
+        let n_slots = storage_slots(q_ship_modifiers.get(structure).ok());
+        if inventory.len() != n_slots {
+            inventory.resize(n_slots, &mut commands);
+        }
+    }
+}
+
 pub(super) fn register(app: &mut App) {
     app.add_systems(
         Update,
@@ -127,6 +170,7 @@ pub(super) fn register(app: &mut App) {
                 .in_set(BlockEventsSet::ProcessEvents)
                 .ambiguous_with(FluidInteractionSet::InteractWithFluidBlocks),
             populate_inventory.in_set(BlockEventsSet::SendEventsForNextFrame),
+            resize_storage_inventories.in_set(BlockEventsSet::SendEventsForNextFrame),
         )
             .chain()
             .in_set(NetworkingSystemsSet::Between),
@@ -0,0 +1,431 @@
+//! Airlock controllers cycle between their inner & outer doors, never letting both be open at once.
+//!
+//! Interacting with a controller opens its menu, where its doors can be linked & the airlock can be cycled.
+
+use bevy::prelude::*;
+use bevy_renet2::renet2::ClientId;
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        block_face::BlockFace,
+        data::BlockData,
+        specific_blocks::{
+            airlock::{
+                within_airlock_range, Airlock, AirlockSide, AirlockState, CycleAirlockEvent, OpenAirlockEvent, SetAirlockDoorEvent,
+                AIRLOCK_CONTROLLER_BLOCK, AIRLOCK_CYCLE_TICKS,
+            },
+            door::{connected_door_blocks, door_open_at},
+        },
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    events::block_events::{BlockChangedEvent, BlockDataChangedEvent, BlockDataSystemParams},
+    logic::{logic_driver::LogicDriver, BlockLogicData, LogicInputEvent, LogicSystemSet, LogicTicks},
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::{Structure, StructureBlock},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+};
+
+use crate::{
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+    structure::ownership::{notify_no_permission, StructurePermissions},
+};
+
+use super::door::set_door_open;
+
+/// Players can change airlocks from a little further than they can reach to account for latency
+const MAX_AIRLOCK_EDIT_DISTANCE: f32 = 12.0;
+
+impl DefaultPersistentComponent for Airlock {}
+
+#[derive(Event, Debug, Clone, Copy)]
+/// Starts cycling an airlock, unless it is already cycling
+pub(super) struct StartAirlockCycleEvent {
+    /// The airlock controller
+    pub controller: StructureBlock,
+    /// The door to open. If this is `None` or this door is already open, the other door is opened instead.
+    pub to: Option<AirlockSide>,
+}
+
+/// Finds the airlock this door belongs to, along with which side of the airlock it's on
+pub(super) fn airlock_for_door(
+    door: StructureBlock,
+    structure: &Structure,
+    q_airlocks: &Query<(&BlockData, &Airlock)>,
+    blocks: &Registry<Block>,
+) -> Option<(StructureBlock, AirlockSide)> {
+    let door_blocks = connected_door_blocks(structure, door.coords(), blocks);
+
+    q_airlocks
+        .iter()
+        .filter(|(block_data, _)| block_data.identifier.block.structure() == door.structure())
+        .find_map(|(block_data, airlock)| {
+            [AirlockSide::Inner, AirlockSide::Outer]
+                .into_iter()
+                .find(|&side| airlock.door(side).is_some_and(|c| door_blocks.contains(&c)))
+                .map(|side| (block_data.identifier.block, side))
+        })
+}
+
+fn is_airlock_controller(s_block: StructureBlock, structure: &Structure, blocks: &Registry<Block>) -> bool {
+    structure.is_within_blocks(s_block.coords())
+        && structure.block_at(s_block.coords(), blocks).unlocalized_name() == AIRLOCK_CONTROLLER_BLOCK
+}
+
+fn on_place_airlock(
+    mut evr_changed_block: EventReader<BlockChangedEvent>,
+    mut q_structure: Query<&mut Structure>,
+    q_has_data: Query<(), With<Airlock>>,
+    mut q_block_data: Query<&mut BlockData>,
+    mut bs_params: BlockDataSystemParams,
+    blocks: Res<Registry<Block>>,
+) {
+    let Some(controller) = blocks.from_id(AIRLOCK_CONTROLLER_BLOCK) else {
+        return;
+    };
+
+    for ev in evr_changed_block.read() {
+        if ev.new_block != controller.id() {
+            continue;
+        }
+
+        let Ok(mut structure) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        structure.insert_block_data(
+            ev.block.coords(),
+            Airlock::default(),
+            &mut bs_params,
+            &mut q_block_data,
+            &q_has_data,
+        );
+    }
+}
+
+fn on_interact_airlock(
+    mut evr_block_interact: EventReader<BlockInteractEvent>,
+    q_structure: Query<&Structure>,
+    q_player: Query<&Player>,
+    blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    mut nevw_open_airlock: NettyEventWriter<OpenAirlockEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for ev in evr_block_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok(structure) = q_structure.get(s_block.structure()) else {
+            continue;
+        };
+
+        if !is_airlock_controller(s_block, structure, &blocks) {
+            continue;
+        }
+
+        let Ok(player) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        if !permissions.can_use(ev.interactor, s_block.structure()) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        nevw_open_airlock.send(OpenAirlockEvent(s_block), player.id());
+    }
+}
+
+/// Makes sure the player sending an airlock event is allowed to change this airlock
+fn can_edit_airlock(
+    player_ent: Entity,
+    client_id: ClientId,
+    controller: StructureBlock,
+    player_g_trans: &GlobalTransform,
+    (structure, structure_g_trans): (&Structure, &GlobalTransform),
+    blocks: &Registry<Block>,
+    permissions: &StructurePermissions,
+    nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>,
+) -> bool {
+    if !is_airlock_controller(controller, structure, blocks) {
+        warn!("Player {player_ent:?} tried to change an airlock where there is none.");
+        return false;
+    }
+
+    let block_position = structure_g_trans.transform_point(structure.block_relative_position(controller.coords()));
+    if block_position.distance_squared(player_g_trans.translation()) > MAX_AIRLOCK_EDIT_DISTANCE * MAX_AIRLOCK_EDIT_DISTANCE {
+        warn!("Player {player_ent:?} tried to change an airlock that is too far away.");
+        return false;
+    }
+
+    if !permissions.can_use(player_ent, controller.structure()) {
+        notify_no_permission(nevw_chat, client_id);
+        return false;
+    }
+
+    true
+}
+
+fn on_set_airlock_door(
+    mut nevr_set_door: EventReader<NettyEventReceived<SetAirlockDoorEvent>>,
+    lobby: Res<ServerLobby>,
+    q_player: Query<&GlobalTransform, With<Player>>,
+    mut q_structure: Query<(&mut Structure, &GlobalTransform)>,
+    mut q_airlock: Query<&mut Airlock>,
+    blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut evw_block_changed: EventWriter<BlockChangedEvent>,
+) {
+    for ev in nevr_set_door.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let (Ok(player_g_trans), Ok((mut structure, structure_g_trans))) =
+            (q_player.get(player_ent), q_structure.get_mut(ev.controller.structure()))
+        else {
+            continue;
+        };
+
+        if !can_edit_airlock(
+            player_ent,
+            ev.client_id,
+            ev.controller,
+            player_g_trans,
+            (&structure, structure_g_trans),
+            &blocks,
+            &permissions,
+            &mut nevw_chat,
+        ) {
+            continue;
+        }
+
+        let Some(mut airlock) = structure.block_data(ev.controller.coords()).and_then(|e| q_airlock.get_mut(e).ok()) else {
+            continue;
+        };
+
+        if let Some(door) = ev.door {
+            if door_open_at(&structure, door, &blocks).is_none() || !within_airlock_range(ev.controller.coords(), door) {
+                warn!("Player {player_ent:?} tried to link an airlock to an invalid door.");
+                continue;
+            }
+
+            // Both sides being the same door would let it be opened from either side
+            let other_door = airlock.door(ev.side.opposite());
+            if other_door.is_some_and(|other| connected_door_blocks(&structure, door, &blocks).contains(&other)) {
+                continue;
+            }
+        }
+
+        airlock.set_door(ev.side, ev.door);
+
+        // Start from a known state, so the new door can't be open alongside the other one
+        airlock.state = AirlockState::Sealed;
+        for door in [airlock.inner_door, airlock.outer_door].into_iter().flatten() {
+            set_door_open(&mut structure, door, false, &blocks, &mut evw_block_changed);
+        }
+    }
+}
+
+fn on_cycle_airlock(
+    mut nevr_cycle: EventReader<NettyEventReceived<CycleAirlockEvent>>,
+    lobby: Res<ServerLobby>,
+    q_player: Query<&GlobalTransform, With<Player>>,
+    q_structure: Query<(&Structure, &GlobalTransform)>,
+    blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut evw_start_cycle: EventWriter<StartAirlockCycleEvent>,
+) {
+    for ev in nevr_cycle.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let (Ok(player_g_trans), Ok(structure)) = (q_player.get(player_ent), q_structure.get(ev.0.structure())) else {
+            continue;
+        };
+
+        if can_edit_airlock(
+            player_ent,
+            ev.client_id,
+            ev.0,
+            player_g_trans,
+            structure,
+            &blocks,
+            &permissions,
+            &mut nevw_chat,
+        ) {
+            evw_start_cycle.send(StartAirlockCycleEvent {
+                controller: ev.0,
+                to: None,
+            });
+        }
+    }
+}
+
+/// Airlocks cycle when their controller starts receiving a logic signal on its back
+fn airlock_input_event_listener(
+    mut evr_logic_input: EventReader<LogicInputEvent>,
+    blocks: Res<Registry<Block>>,
+    q_logic_driver: Query<&LogicDriver>,
+    q_structure: Query<&Structure>,
+    mut q_airlock: Query<&mut Airlock>,
+    mut evw_start_cycle: EventWriter<StartAirlockCycleEvent>,
+) {
+    for ev in evr_logic_input.read() {
+        let Ok(structure) = q_structure.get(ev.block.structure()) else {
+            continue;
+        };
+        if !is_airlock_controller(ev.block, structure, &blocks) {
+            continue;
+        }
+        let Ok(logic_driver) = q_logic_driver.get(ev.block.structure()) else {
+            continue;
+        };
+        let Some(mut airlock) = structure.block_data(ev.block.coords()).and_then(|e| q_airlock.get_mut(e).ok()) else {
+            continue;
+        };
+
+        let rotation = structure.block_rotation(ev.block.coords());
+        let powered = logic_driver.read_input(ev.block.coords(), rotation.direction_of(BlockFace::Back)) != 0;
+
+        if powered && !airlock.powered {
+            evw_start_cycle.send(StartAirlockCycleEvent {
+                controller: ev.block,
+                to: None,
+            });
+        }
+
+        if airlock.powered != powered {
+            airlock.powered = powered;
+        }
+    }
+}
+
+/// Closes the open door of each airlock that should start cycling
+fn start_airlock_cycles(
+    mut evr_start_cycle: EventReader<StartAirlockCycleEvent>,
+    mut q_structure: Query<&mut Structure>,
+    mut q_airlock: Query<&mut Airlock>,
+    blocks: Res<Registry<Block>>,
+    logic_ticks: Res<LogicTicks>,
+    mut evw_block_changed: EventWriter<BlockChangedEvent>,
+) {
+    for ev in evr_start_cycle.read() {
+        let Ok(mut structure) = q_structure.get_mut(ev.controller.structure()) else {
+            continue;
+        };
+
+        let Some(mut airlock) = structure.block_data(ev.controller.coords()).and_then(|e| q_airlock.get_mut(e).ok()) else {
+            continue;
+        };
+
+        if airlock.is_cycling() {
+            continue;
+        }
+
+        let to = match (ev.to, airlock.state) {
+            (Some(side), state) if state != AirlockState::Open(side) => side,
+            (_, AirlockState::Open(side)) => side.opposite(),
+            _ => AirlockSide::Inner,
+        };
+
+        for door in [airlock.inner_door, airlock.outer_door].into_iter().flatten() {
+            set_door_open(&mut structure, door, false, &blocks, &mut evw_block_changed);
+        }
+
+        airlock.state = AirlockState::Cycling {
+            to,
+            finishes_at: logic_ticks.count() + AIRLOCK_CYCLE_TICKS,
+        };
+    }
+}
+
+/// Opens the door of each airlock that is done cycling
+fn finish_airlock_cycles(
+    mut q_airlocks: Query<(&BlockData, &mut Airlock)>,
+    mut q_structure: Query<&mut Structure>,
+    blocks: Res<Registry<Block>>,
+    logic_ticks: Res<LogicTicks>,
+    mut evw_block_changed: EventWriter<BlockChangedEvent>,
+) {
+    let now = logic_ticks.count();
+
+    for (block_data, mut airlock) in q_airlocks.iter_mut() {
+        let AirlockState::Cycling { to, finishes_at } = airlock.state else {
+            continue;
+        };
+
+        // Logic ticks start over when the server restarts, so airlocks saved mid-cycle may be waiting on a tick far in the future
+        if now < finishes_at && finishes_at - now <= AIRLOCK_CYCLE_TICKS {
+            continue;
+        }
+
+        airlock.state = AirlockState::Open(to);
+
+        let (Some(door), Ok(mut structure)) = (airlock.door(to), q_structure.get_mut(block_data.identifier.block.structure())) else {
+            continue;
+        };
+
+        set_door_open(&mut structure, door, true, &blocks, &mut evw_block_changed);
+    }
+}
+
+/// Controllers light up & output a logic signal while their airlock is cycling
+fn update_airlock_lights(
+    mut q_airlocks: Query<(Entity, &BlockData, &Airlock, &mut BlockLogicData), Changed<Airlock>>,
+    mut evw_block_data_changed: EventWriter<BlockDataChangedEvent>,
+) {
+    for (ent, block_data, airlock, mut logic_data) in q_airlocks.iter_mut() {
+        let cycling = airlock.is_cycling();
+
+        if logic_data.on() == cycling {
+            continue;
+        }
+
+        *logic_data = BlockLogicData(cycling as i32);
+
+        evw_block_data_changed.send(BlockDataChangedEvent {
+            block_data_entity: Some(ent),
+            block: block_data.identifier.block,
+        });
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<Airlock>(app);
+
+    app.add_systems(
+        Update,
+        (
+            on_place_airlock.in_set(BlockEventsSet::SendEventsForThisFrame),
+            (on_interact_airlock, on_set_airlock_door, on_cycle_airlock)
+                .chain()
+                .in_set(BlockEventsSet::ProcessEvents),
+            start_airlock_cycles.in_set(BlockEventsSet::SendEventsForNextFrame),
+        )
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    )
+    .add_systems(
+        Update,
+        (airlock_input_event_listener, finish_airlock_cycles, update_airlock_lights)
+            .chain()
+            .in_set(LogicSystemSet::Consume)
+            .ambiguous_with(LogicSystemSet::Consume),
+    )
+    .add_event::<StartAirlockCycleEvent>();
+}
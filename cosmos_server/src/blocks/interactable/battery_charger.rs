@@ -0,0 +1,87 @@
+//! Interacting with a battery charger opens its inventory, so a battery can be put in or taken out
+
+use bevy::prelude::*;
+use bevy_renet2::renet2::RenetServer;
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        data::BlockDataIdentifier,
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    inventory::netty::{InventoryIdentifier, ServerInventoryMessages},
+    item::battery::BATTERY_CHARGER_BLOCK,
+    netty::{
+        network_stats::{NettyMessage, NettyServerMessages},
+        sync::events::server_event::NettyEventWriter,
+        system_sets::NetworkingSystemsSet,
+        NettyChannelServer,
+    },
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::Structure,
+};
+
+use crate::structure::ownership::{notify_no_permission, StructurePermissions};
+
+fn on_interact_charger(
+    mut evr_block_interact: EventReader<BlockInteractEvent>,
+    q_structure: Query<&Structure>,
+    q_player: Query<&Player>,
+    blocks: Res<Registry<Block>>,
+    mut server: ResMut<RenetServer>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    let Some(charger) = blocks.from_id(BATTERY_CHARGER_BLOCK) else {
+        return;
+    };
+
+    for ev in evr_block_interact.read() {
+        // Crouch-interacting while holding a battery is used to jump-start structures
+        if ev.alternate {
+            continue;
+        }
+
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok(structure) = q_structure.get(s_block.structure()) else {
+            continue;
+        };
+
+        let block_id = s_block.block_id(structure);
+        if block_id != charger.id() {
+            continue;
+        }
+
+        let Ok(player) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        if !permissions.can_use(ev.interactor, s_block.structure()) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        server.send_netty_message(
+            player.id(),
+            NettyChannelServer::Inventory,
+            NettyMessage::new(&ServerInventoryMessages::OpenInventory {
+                owner: InventoryIdentifier::BlockData(BlockDataIdentifier { block: s_block, block_id }),
+            }),
+        );
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        on_interact_charger
+            .in_set(NetworkingSystemsSet::Between)
+            .in_set(BlockEventsSet::ProcessEvents)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,234 @@
+//! Pressing a button sends a logic pulse. Alternate interacting with a button changes how long its pulse lasts.
+
+use std::{cell::RefCell, rc::Rc};
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        data::BlockData,
+        specific_blocks::{button::BUTTON_BLOCK, keypad::KEYPAD_BLOCK},
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    events::block_events::{BlockChangedEvent, BlockDataSystemParams},
+    logic::{BlockLogicData, LogicSystemSet, LOGIC_TICKS_PER_SECOND},
+    netty::{
+        sync::{events::server_event::NettyEventWriter, IdentifiableComponent},
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::{Structure, StructureBlock},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+};
+use renet2::ClientId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+    structure::ownership::{notify_no_permission, StructurePermissions},
+};
+
+/// The pulse lengths (in logic ticks) a button cycles through
+const BUTTON_PULSE_LENGTHS: [u32; 6] = [2, 5, 10, 20, 40, 100];
+
+/// How long a newly placed button's pulse lasts (in logic ticks)
+const DEFAULT_BUTTON_PULSE_LENGTH: u32 = 10;
+
+/// How long a keypad's pulse lasts (in logic ticks) after the correct code is entered
+const KEYPAD_PULSE_LENGTH: u32 = 20;
+
+#[derive(Component, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Block data for blocks that output a signal for a set number of logic ticks, then turn off
+pub(super) struct LogicPulse {
+    /// How many logic ticks the pulse lasts
+    length: u32,
+    /// How many logic ticks are left in the current pulse
+    #[serde(skip)]
+    remaining: u32,
+}
+
+impl LogicPulse {
+    fn new(length: u32) -> Self {
+        Self { length, remaining: 0 }
+    }
+
+    /// Restarts the pulse. The block's logic data must be set to on separately.
+    pub(super) fn start(&mut self) {
+        self.remaining = self.length;
+    }
+}
+
+impl IdentifiableComponent for LogicPulse {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:logic_pulse"
+    }
+}
+
+impl DefaultPersistentComponent for LogicPulse {}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+fn on_place_pulse_block(
+    mut evr_changed_block: EventReader<BlockChangedEvent>,
+    mut q_structure: Query<&mut Structure>,
+    q_has_data: Query<(), With<LogicPulse>>,
+    mut q_block_data: Query<&mut BlockData>,
+    mut bs_params: BlockDataSystemParams,
+    blocks: Res<Registry<Block>>,
+) {
+    for ev in evr_changed_block.read() {
+        let length = match blocks.from_numeric_id(ev.new_block).unlocalized_name() {
+            BUTTON_BLOCK => DEFAULT_BUTTON_PULSE_LENGTH,
+            KEYPAD_BLOCK => KEYPAD_PULSE_LENGTH,
+            _ => continue,
+        };
+
+        let Ok(mut structure) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        structure.insert_block_data(
+            ev.block.coords(),
+            LogicPulse::new(length),
+            &mut bs_params,
+            &mut q_block_data,
+            &q_has_data,
+        );
+    }
+}
+
+fn on_interact_button(
+    mut evr_block_interact: EventReader<BlockInteractEvent>,
+    q_structure: Query<&Structure>,
+    q_player: Query<&Player>,
+    mut q_pulse: Query<&mut LogicPulse>,
+    mut q_logic_data: Query<&mut BlockLogicData>,
+    blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    bs_params: BlockDataSystemParams,
+) {
+    let bs_params = Rc::new(RefCell::new(bs_params));
+
+    for ev in evr_block_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok(structure) = q_structure.get(s_block.structure()) else {
+            continue;
+        };
+
+        if structure.block_at(s_block.coords(), &blocks).unlocalized_name() != BUTTON_BLOCK {
+            continue;
+        }
+
+        let Ok(player) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        if !permissions.can_use(ev.interactor, s_block.structure()) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        let Some(mut pulse) = structure.query_block_data_mut(s_block.coords(), &mut q_pulse, bs_params.clone()) else {
+            continue;
+        };
+
+        if ev.alternate {
+            let next = BUTTON_PULSE_LENGTHS
+                .iter()
+                .copied()
+                .find(|&l| l > pulse.length)
+                .unwrap_or(BUTTON_PULSE_LENGTHS[0]);
+
+            pulse.length = next;
+
+            reply(
+                &mut nevw_chat,
+                player.id(),
+                format!(
+                    "Button pulse set to {next} ticks ({:.2}s).",
+                    next as f32 / LOGIC_TICKS_PER_SECOND as f32
+                ),
+            );
+            continue;
+        }
+
+        pulse.start();
+        drop(pulse);
+
+        if let Some(mut logic_data) = structure.query_block_data_mut(s_block.coords(), &mut q_logic_data, bs_params.clone()) {
+            if !logic_data.on() {
+                **logic_data = BlockLogicData(1);
+            }
+        }
+    }
+}
+
+/// Turns off pulses once they've lasted long enough
+fn tick_logic_pulses(
+    q_structure: Query<&Structure>,
+    mut q_pulse: Query<(&BlockData, &mut LogicPulse)>,
+    mut q_logic_data: Query<&mut BlockLogicData>,
+    bs_params: BlockDataSystemParams,
+) {
+    let bs_params = Rc::new(RefCell::new(bs_params));
+
+    let mut finished: Vec<StructureBlock> = vec![];
+
+    for (block_data, mut pulse) in q_pulse.iter_mut() {
+        if pulse.remaining == 0 {
+            continue;
+        }
+
+        pulse.remaining -= 1;
+
+        if pulse.remaining == 0 {
+            finished.push(block_data.identifier.block);
+        }
+    }
+
+    for block in finished {
+        let Ok(structure) = q_structure.get(block.structure()) else {
+            continue;
+        };
+
+        if let Some(mut logic_data) = structure.query_block_data_mut(block.coords(), &mut q_logic_data, bs_params.clone()) {
+            if logic_data.on() {
+                **logic_data = BlockLogicData(0);
+            }
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<LogicPulse>(app);
+
+    app.add_systems(
+        Update,
+        (
+            on_place_pulse_block.in_set(BlockEventsSet::SendEventsForThisFrame),
+            on_interact_button.in_set(BlockEventsSet::ProcessEvents),
+        )
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    )
+    .add_systems(
+        Update,
+        tick_logic_pulses
+            .in_set(LogicSystemSet::Consume)
+            .ambiguous_with(LogicSystemSet::Consume),
+    );
+}
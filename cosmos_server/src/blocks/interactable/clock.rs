@@ -0,0 +1,218 @@
+//! Clocks output based on where the sun is in the sky of the nearest planet. Interacting with a clock opens its settings.
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        data::BlockData,
+        specific_blocks::clock::{ClockSettings, OpenClockSettingsEvent, SetClockSettingsEvent, CLOCK_BLOCK},
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    events::block_events::{BlockChangedEvent, BlockDataChangedEvent, BlockDataSystemParams},
+    logic::{BlockLogicData, LogicSystemSet},
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    physics::location::Location,
+    prelude::{Planet, Structure},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    universe::star::Star,
+};
+
+use crate::{
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+    structure::ownership::{notify_no_permission, StructurePermissions},
+    universe::star::sun_elevation_degrees,
+};
+
+/// Players can change clocks from a little further than they can reach to account for latency
+const MAX_CLOCK_EDIT_DISTANCE: f32 = 12.0;
+
+impl DefaultPersistentComponent for ClockSettings {}
+
+fn on_place_clock(
+    mut evr_changed_block: EventReader<BlockChangedEvent>,
+    mut q_structure: Query<&mut Structure>,
+    q_has_data: Query<(), With<ClockSettings>>,
+    mut q_block_data: Query<&mut BlockData>,
+    mut bs_params: BlockDataSystemParams,
+    blocks: Res<Registry<Block>>,
+) {
+    let Some(clock) = blocks.from_id(CLOCK_BLOCK) else {
+        return;
+    };
+
+    for ev in evr_changed_block.read() {
+        if ev.new_block != clock.id() {
+            continue;
+        }
+
+        let Ok(mut structure) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        structure.insert_block_data(
+            ev.block.coords(),
+            ClockSettings::default(),
+            &mut bs_params,
+            &mut q_block_data,
+            &q_has_data,
+        );
+    }
+}
+
+fn on_interact_clock(
+    mut evr_block_interact: EventReader<BlockInteractEvent>,
+    q_structure: Query<&Structure>,
+    q_player: Query<&Player>,
+    blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    mut nevw_open_settings: NettyEventWriter<OpenClockSettingsEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for ev in evr_block_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok(structure) = q_structure.get(s_block.structure()) else {
+            continue;
+        };
+
+        if structure.block_at(s_block.coords(), &blocks).unlocalized_name() != CLOCK_BLOCK {
+            continue;
+        }
+
+        let Ok(player) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        if !permissions.can_use(ev.interactor, s_block.structure()) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        nevw_open_settings.send(OpenClockSettingsEvent(s_block), player.id());
+    }
+}
+
+fn on_set_clock_settings(
+    mut nevr_set_settings: EventReader<NettyEventReceived<SetClockSettingsEvent>>,
+    lobby: Res<ServerLobby>,
+    q_player: Query<&GlobalTransform, With<Player>>,
+    mut q_structure: Query<(&mut Structure, &GlobalTransform)>,
+    blocks: Res<Registry<Block>>,
+    mut q_block_data: Query<&mut BlockData>,
+    q_has_settings: Query<(), With<ClockSettings>>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut bs_params: BlockDataSystemParams,
+) {
+    for ev in nevr_set_settings.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok(player_g_trans) = q_player.get(player_ent) else {
+            continue;
+        };
+
+        let Ok((mut structure, structure_g_trans)) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        let coords = ev.block.coords();
+
+        if !structure.is_within_blocks(coords) || structure.block_at(coords, &blocks).unlocalized_name() != CLOCK_BLOCK {
+            warn!("Player {player_ent:?} tried to change a clock where there is none.");
+            continue;
+        }
+
+        let block_position = structure_g_trans.transform_point(structure.block_relative_position(coords));
+        if block_position.distance_squared(player_g_trans.translation()) > MAX_CLOCK_EDIT_DISTANCE * MAX_CLOCK_EDIT_DISTANCE {
+            warn!("Player {player_ent:?} tried to change a clock that is too far away.");
+            continue;
+        }
+
+        if !permissions.can_use(player_ent, ev.block.structure()) {
+            notify_no_permission(&mut nevw_chat, ev.client_id);
+            continue;
+        }
+
+        structure.insert_block_data(coords, ev.settings, &mut bs_params, &mut q_block_data, &q_has_settings);
+    }
+}
+
+/// Updates the output of every clock based on where the sun is
+fn update_clocks(
+    mut q_clocks: Query<(Entity, &BlockData, &ClockSettings, &mut BlockLogicData)>,
+    q_structure: Query<(&Structure, &GlobalTransform, &Location)>,
+    q_planets: Query<(Entity, &Location, &GlobalTransform), With<Planet>>,
+    q_stars: Query<&Location, With<Star>>,
+    mut evw_block_data_changed: EventWriter<BlockDataChangedEvent>,
+) {
+    for (ent, block_data, settings, mut logic_data) in q_clocks.iter_mut() {
+        let block = block_data.identifier.block;
+
+        let Ok((structure, g_trans, location)) = q_structure.get(block.structure()) else {
+            continue;
+        };
+
+        let planet = match q_planets.get(block.structure()) {
+            Ok((_, planet_loc, planet_g_trans)) => Some((planet_loc, planet_g_trans)),
+            Err(_) => q_planets
+                .iter()
+                .filter(|(_, planet_loc, _)| planet_loc.is_within_reasonable_range(location))
+                .min_by(|(_, a, _), (_, b, _)| a.distance_sqrd(location).total_cmp(&b.distance_sqrd(location)))
+                .map(|(_, planet_loc, planet_g_trans)| (planet_loc, planet_g_trans)),
+        };
+
+        let star = planet.and_then(|(planet_loc, _)| {
+            q_stars
+                .iter()
+                .min_by(|a, b| a.distance_sqrd(planet_loc).total_cmp(&b.distance_sqrd(planet_loc)))
+        });
+
+        let position = g_trans.transform_point(structure.block_relative_position(block.coords()));
+        let signal = settings.mode.signal(sun_elevation_degrees(position, planet, star));
+
+        if logic_data.0 == signal {
+            continue;
+        }
+
+        *logic_data = BlockLogicData(signal);
+
+        evw_block_data_changed.send(BlockDataChangedEvent {
+            block_data_entity: Some(ent),
+            block,
+        });
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<ClockSettings>(app);
+
+    app.add_systems(
+        Update,
+        (
+            on_place_clock.in_set(BlockEventsSet::SendEventsForThisFrame),
+            (on_interact_clock, on_set_clock_settings)
+                .chain()
+                .in_set(BlockEventsSet::ProcessEvents),
+        )
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    )
+    .add_systems(
+        Update,
+        update_clocks
+            .in_set(LogicSystemSet::Consume)
+            .ambiguous_with(LogicSystemSet::Consume),
+    );
+}
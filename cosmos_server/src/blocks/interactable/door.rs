@@ -1,25 +1,56 @@
-use bevy::{prelude::*, utils::hashbrown::HashSet};
+use bevy::prelude::*;
 use cosmos_core::{
     block::{
-        block_direction::ALL_BLOCK_DIRECTIONS,
         block_events::{BlockEventsSet, BlockInteractEvent},
+        data::BlockData,
+        specific_blocks::{
+            airlock::Airlock,
+            door::{connected_door_blocks, door_open_at, DOOR_BLOCK, DOOR_OPEN_BLOCK},
+        },
         Block,
     },
     events::block_events::BlockChangedEvent,
     netty::system_sets::NetworkingSystemsSet,
     prelude::{BlockCoordinate, Structure, StructureBlock},
-    registry::{identifiable::Identifiable, Registry},
+    registry::Registry,
     state::GameState,
 };
 
+use super::airlock::{airlock_for_door, StartAirlockCycleEvent};
+
 #[derive(Debug, Event)]
 struct ToggleDoorEvent(StructureBlock);
 
+/// Opens or closes every block of the door at these coordinates. Does nothing if there is no door here.
+pub(super) fn set_door_open(
+    structure: &mut Structure,
+    coords: BlockCoordinate,
+    open: bool,
+    blocks: &Registry<Block>,
+    evw_block_changed: &mut EventWriter<BlockChangedEvent>,
+) {
+    let Some(block) = blocks.from_id(if open { DOOR_OPEN_BLOCK } else { DOOR_BLOCK }) else {
+        return;
+    };
+
+    for coords in connected_door_blocks(structure, coords, blocks) {
+        if door_open_at(structure, coords, blocks) == Some(open) {
+            continue;
+        }
+
+        let block_info = structure.block_info_at(coords);
+
+        structure.set_block_and_info_at(coords, block, block_info, blocks, Some(&mut *evw_block_changed));
+    }
+}
+
 fn handle_door_block_event(
     mut interact_events: EventReader<BlockInteractEvent>,
     q_structure: Query<&Structure>,
+    q_airlocks: Query<(&BlockData, &Airlock)>,
     blocks: Res<Registry<Block>>,
     mut ev_writer: EventWriter<ToggleDoorEvent>,
+    mut evw_start_airlock_cycle: EventWriter<StartAirlockCycleEvent>,
 ) {
     for ev in interact_events.read() {
         let Some(s_block) = ev.block else {
@@ -31,10 +62,17 @@ fn handle_door_block_event(
             continue;
         };
 
-        let block = structure.block_at(s_block.coords(), &blocks);
-        let un = block.unlocalized_name();
-        if un != "cosmos:door" && un != "cosmos:door_open" {
-            return;
+        if door_open_at(structure, s_block.coords(), &blocks).is_none() {
+            continue;
+        }
+
+        // Airlock doors can only be opened by cycling their airlock, so both are never open at once
+        if let Some((controller, side)) = airlock_for_door(s_block, structure, &q_airlocks, &blocks) {
+            evw_start_airlock_cycle.send(StartAirlockCycleEvent {
+                controller,
+                to: Some(side),
+            });
+            continue;
         }
 
         ev_writer.send(ToggleDoorEvent(s_block));
@@ -53,53 +91,11 @@ fn toggle_doors(
             continue;
         };
 
-        let Some(door) = blocks.from_id("cosmos:door") else {
-            return;
-        };
-        let Some(door_open) = blocks.from_id("cosmos:door_open") else {
-            return;
+        let Some(open) = door_open_at(&structure, ev.0.coords(), &blocks) else {
+            continue;
         };
 
-        let door_id = door.id();
-        let door_open_id = door_open.id();
-
-        let open = structure.block_id_at(ev.0.coords()) == door_open_id;
-        let block = if open { door } else { door_open };
-
-        let mut todo = HashSet::new();
-        todo.insert(ev.0.coords());
-
-        let mut done = HashSet::new();
-        while !todo.is_empty() {
-            let mut new_todo = HashSet::new();
-
-            for c in &todo {
-                done.insert(*c);
-            }
-
-            for coord in todo {
-                let block_id_here = structure.block_id_at(coord);
-                if block_id_here != door_id && block_id_here != door_open_id {
-                    continue;
-                }
-
-                let block_info = structure.block_info_at(coord);
-
-                structure.set_block_and_info_at(coord, block, block_info, &blocks, Some(&mut evw_block_changed));
-
-                done.insert(coord);
-
-                for dir in ALL_BLOCK_DIRECTIONS {
-                    if let Ok(coord) = BlockCoordinate::try_from(dir.to_coordinates() + coord) {
-                        if !done.contains(&coord) {
-                            new_todo.insert(coord);
-                        }
-                    }
-                }
-            }
-
-            todo = new_todo;
-        }
+        set_door_open(&mut structure, ev.0.coords(), !open, &blocks, &mut evw_block_changed);
     }
 }
 
@@ -0,0 +1,309 @@
+//! Energy relays share energy between the structures they are on. Interacting with a relay opens its link menu.
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        data::BlockData,
+        specific_blocks::energy_relay::{
+            within_relay_range, EnergyRelayLink, OpenEnergyRelayEvent, SetEnergyRelayLinkEvent, ENERGY_RELAY_BLOCK,
+            ENERGY_RELAY_TRANSFER_RATE,
+        },
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    events::block_events::BlockDataSystemParams,
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    physics::location::Location,
+    prelude::{Structure, StructureBlock},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        coordinates::BlockCoordinate,
+        systems::{energy_storage_system::EnergyStorageSystem, StructureSystems, StructureSystemsSet},
+    },
+    utils::ownership::MaybeOwned,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    persistence::{
+        make_persistent::{make_persistent, EntityIdManager, PersistentComponent},
+        EntityId,
+    },
+    structure::ownership::{notify_no_permission, StructurePermissions},
+};
+
+/// Players can change relays from a little further than they can reach to account for latency
+const MAX_RELAY_EDIT_DISTANCE: f32 = 12.0;
+
+/// The serialized version of an [`EnergyRelayLink`].
+///
+/// Only public because the trait requires it to be public. Don't use this.
+#[derive(Serialize, Deserialize)]
+pub struct SerializedEnergyRelayLink {
+    /// The structure the linked relay is on
+    structure_entity_id: EntityId,
+    /// The linked relay's block
+    coords: BlockCoordinate,
+}
+
+impl PersistentComponent for EnergyRelayLink {
+    type SaveType = SerializedEnergyRelayLink;
+
+    fn convert_to_save_type<'a>(&'a self, q_entity_ids: &Query<&EntityId>) -> Option<MaybeOwned<'a, SerializedEnergyRelayLink>> {
+        q_entity_ids
+            .get(self.0.structure())
+            .map(|x| {
+                MaybeOwned::Owned(Box::new(SerializedEnergyRelayLink {
+                    structure_entity_id: x.clone(),
+                    coords: self.0.coords(),
+                }))
+            })
+            .ok()
+    }
+
+    fn convert_from_save_type(e_id_type: Self::SaveType, entity_id_manager: &EntityIdManager) -> Option<Self> {
+        entity_id_manager
+            .entity_from_entity_id(&e_id_type.structure_entity_id)
+            .map(|e| Self(StructureBlock::new(e_id_type.coords, e)))
+    }
+}
+
+fn is_relay(s_block: StructureBlock, structure: &Structure, blocks: &Registry<Block>) -> bool {
+    structure.is_within_blocks(s_block.coords()) && structure.block_at(s_block.coords(), blocks).unlocalized_name() == ENERGY_RELAY_BLOCK
+}
+
+fn on_interact_relay(
+    mut evr_block_interact: EventReader<BlockInteractEvent>,
+    q_structure: Query<&Structure>,
+    q_player: Query<&Player>,
+    blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    mut nevw_open_relay: NettyEventWriter<OpenEnergyRelayEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for ev in evr_block_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok(structure) = q_structure.get(s_block.structure()) else {
+            continue;
+        };
+
+        if !is_relay(s_block, structure, &blocks) {
+            continue;
+        }
+
+        let Ok(player) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        if !permissions.can_use(ev.interactor, s_block.structure()) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        nevw_open_relay.send(OpenEnergyRelayEvent(s_block), player.id());
+    }
+}
+
+/// Removes this relay's link, along with the link back to it from the relay it was linked to
+fn unlink(
+    relay: StructureBlock,
+    q_structure: &mut Query<(&mut Structure, &Location, &GlobalTransform)>,
+    q_link: &Query<&EnergyRelayLink>,
+    q_has_link: &Query<(), With<EnergyRelayLink>>,
+    q_block_data: &mut Query<&mut BlockData>,
+    bs_params: &mut BlockDataSystemParams,
+) {
+    let Ok((mut structure, _, _)) = q_structure.get_mut(relay.structure()) else {
+        return;
+    };
+
+    let Some(partner) = structure.query_block_data(relay.coords(), q_link).map(|x| x.0) else {
+        return;
+    };
+
+    structure.remove_block_data::<EnergyRelayLink>(relay.coords(), bs_params, q_block_data, q_has_link);
+
+    let Ok((mut partner_structure, _, _)) = q_structure.get_mut(partner.structure()) else {
+        return;
+    };
+
+    if partner_structure.query_block_data(partner.coords(), q_link).map(|x| x.0) == Some(relay) {
+        partner_structure.remove_block_data::<EnergyRelayLink>(partner.coords(), bs_params, q_block_data, q_has_link);
+    }
+}
+
+fn on_set_relay_link(
+    mut nevr_set_link: EventReader<NettyEventReceived<SetEnergyRelayLinkEvent>>,
+    lobby: Res<ServerLobby>,
+    q_player: Query<&Location, With<Player>>,
+    mut q_structure: Query<(&mut Structure, &Location, &GlobalTransform)>,
+    blocks: Res<Registry<Block>>,
+    mut q_block_data: Query<&mut BlockData>,
+    q_link: Query<&EnergyRelayLink>,
+    q_has_link: Query<(), With<EnergyRelayLink>>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut bs_params: BlockDataSystemParams,
+) {
+    for ev in nevr_set_link.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok(player_loc) = q_player.get(player_ent) else {
+            continue;
+        };
+
+        let Ok((structure, structure_loc, structure_g_trans)) = q_structure.get(ev.relay.structure()) else {
+            continue;
+        };
+
+        if !is_relay(ev.relay, structure, &blocks) {
+            warn!("Player {player_ent:?} tried to change an energy relay where there is none.");
+            continue;
+        }
+
+        let relay_loc = structure.block_world_location(ev.relay.coords(), structure_g_trans, structure_loc);
+        if relay_loc.distance_sqrd(player_loc) > MAX_RELAY_EDIT_DISTANCE * MAX_RELAY_EDIT_DISTANCE {
+            warn!("Player {player_ent:?} tried to change an energy relay that is too far away.");
+            continue;
+        }
+
+        if !permissions.can_use(player_ent, ev.relay.structure()) {
+            notify_no_permission(&mut nevw_chat, ev.client_id);
+            continue;
+        }
+
+        let Some(target) = ev.target else {
+            unlink(ev.relay, &mut q_structure, &q_link, &q_has_link, &mut q_block_data, &mut bs_params);
+            continue;
+        };
+
+        // Relays on the same structure would just be moving energy back into the storage it came from
+        if target.structure() == ev.relay.structure() {
+            continue;
+        }
+
+        let Ok((target_structure, target_structure_loc, target_g_trans)) = q_structure.get(target.structure()) else {
+            continue;
+        };
+
+        if !is_relay(target, target_structure, &blocks) {
+            warn!("Player {player_ent:?} tried to link an energy relay to a block that isn't one.");
+            continue;
+        }
+
+        let target_loc = target_structure.block_world_location(target.coords(), target_g_trans, target_structure_loc);
+        if !within_relay_range(&relay_loc, &target_loc) {
+            continue;
+        }
+
+        // Linking to another structure's relay takes its energy, so they need to be allowed to use that structure too
+        if !permissions.can_use(player_ent, target.structure()) {
+            notify_no_permission(&mut nevw_chat, ev.client_id);
+            continue;
+        }
+
+        unlink(ev.relay, &mut q_structure, &q_link, &q_has_link, &mut q_block_data, &mut bs_params);
+        unlink(target, &mut q_structure, &q_link, &q_has_link, &mut q_block_data, &mut bs_params);
+
+        for (relay, partner) in [(ev.relay, target), (target, ev.relay)] {
+            if let Ok((mut structure, _, _)) = q_structure.get_mut(relay.structure()) {
+                structure.insert_block_data(
+                    relay.coords(),
+                    EnergyRelayLink(partner),
+                    &mut bs_params,
+                    &mut q_block_data,
+                    &q_has_link,
+                );
+            }
+        }
+    }
+}
+
+/// Energy flows from the structure with the fuller energy storage to the emptier one.
+///
+/// Each link is stored on both of its relays, so only the relay on the fuller structure moves any energy.
+fn transfer_relay_energy(
+    q_links: Query<(&BlockData, &EnergyRelayLink)>,
+    q_link: Query<&EnergyRelayLink>,
+    q_structure: Query<(&Structure, &Location, &GlobalTransform, &StructureSystems)>,
+    mut q_energy_storage: Query<&mut EnergyStorageSystem>,
+    time: Res<Time>,
+) {
+    for (block_data, link) in q_links.iter() {
+        let relay = block_data.identifier.block;
+        let partner = link.0;
+
+        let (Ok((structure, loc, g_trans, systems)), Ok((partner_structure, partner_loc, partner_g_trans, partner_systems))) =
+            (q_structure.get(relay.structure()), q_structure.get(partner.structure()))
+        else {
+            continue;
+        };
+
+        // The partner relay was broken or relinked somewhere else
+        if partner_structure.query_block_data(partner.coords(), &q_link).map(|x| x.0) != Some(relay) {
+            continue;
+        }
+
+        let relay_loc = structure.block_world_location(relay.coords(), g_trans, loc);
+        let partner_relay_loc = partner_structure.block_world_location(partner.coords(), partner_g_trans, partner_loc);
+
+        if !within_relay_range(&relay_loc, &partner_relay_loc) {
+            continue;
+        }
+
+        let (Ok(storage), Ok(partner_storage)) = (systems.query(&q_energy_storage), partner_systems.query(&q_energy_storage)) else {
+            continue;
+        };
+
+        let (energy, capacity) = (storage.get_energy(), storage.get_capacity());
+        let (partner_energy, partner_capacity) = (partner_storage.get_energy(), partner_storage.get_capacity());
+
+        if capacity <= 0.0 || partner_capacity <= 0.0 || energy / capacity <= partner_energy / partner_capacity {
+            continue;
+        }
+
+        // The amount that would make both storages equally full
+        let balancing_amount = (energy * partner_capacity - partner_energy * capacity) / (capacity + partner_capacity);
+        let amount = balancing_amount
+            .min(ENERGY_RELAY_TRANSFER_RATE * time.delta_secs())
+            .min(partner_capacity - partner_energy);
+
+        if amount <= 0.0 {
+            continue;
+        }
+
+        if let Ok(mut storage) = systems.query_mut(&mut q_energy_storage) {
+            storage.decrease_energy(amount);
+        }
+        if let Ok(mut partner_storage) = partner_systems.query_mut(&mut q_energy_storage) {
+            partner_storage.increase_energy(amount);
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<EnergyRelayLink>(app);
+
+    app.add_systems(
+        Update,
+        (
+            (on_interact_relay, on_set_relay_link).chain().in_set(BlockEventsSet::ProcessEvents),
+            transfer_relay_energy.in_set(StructureSystemsSet::UpdateSystems),
+        )
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
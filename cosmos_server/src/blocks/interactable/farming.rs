@@ -0,0 +1,430 @@
+//! Farming - tilling dirt into farmland, planting seeds on it, growing crops and harvesting them.
+//!
+//! Crops only grow quickly while the sun is up. Crops that aren't on a planet are always in sunlight.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        block_update::BlockUpdate,
+        data::BlockData,
+        specific_blocks::crop::{
+            crop_from_block, crop_from_seed, Crop, CropGrowth, FARMLAND_BLOCK, HOE_ITEM, SECONDS_PER_GROWTH_STAGE, TILLABLE_BLOCKS,
+        },
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    ecs::mut_events::MutEvent,
+    entities::player::Player,
+    events::block_events::{BlockChangedEvent, BlockDataSystemParams},
+    inventory::{held_item_slot::HeldItemSlot, itemstack::ItemShouldHaveData, Inventory},
+    item::{physical_item::PhysicalItem, tool::ItemDurability, Item},
+    netty::{sync::events::server_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    persistence::LoadingDistance,
+    physics::location::Location,
+    prelude::{Planet, Structure},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{coordinates::BlockCoordinate, structure_block::StructureBlock},
+    universe::star::Star,
+};
+use rand::Rng;
+
+use crate::{
+    items::durability::wear_item_at,
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+    structure::ownership::{notify_no_permission, StructurePermissions},
+    universe::star::sun_elevation_degrees,
+};
+
+/// How often (in seconds) crops grow
+const GROWTH_TICK_SECS: f32 = 1.0;
+/// How fast crops grow at night compared to during the day
+const NIGHT_GROWTH_MULTIPLIER: f32 = 0.2;
+
+impl DefaultPersistentComponent for CropGrowth {}
+
+#[derive(Resource, Debug)]
+struct CropGrowthTimer(Timer);
+
+impl Default for CropGrowthTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(GROWTH_TICK_SECS, TimerMode::Repeating))
+    }
+}
+
+#[derive(Event, Debug)]
+enum FarmingAction {
+    /// Turns this block into farmland
+    Till(StructureBlock),
+    /// Plants this crop on top of this farmland
+    Plant { crop: &'static Crop, farmland: StructureBlock },
+    /// Advances this crop to its next growth stage
+    Grow(StructureBlock),
+    /// Drops this crop's produce and replants it
+    Harvest(StructureBlock),
+}
+
+/// The block directly above this one, based on the way the block is facing
+fn block_above(structure: &Structure, block: StructureBlock) -> Option<BlockCoordinate> {
+    let up = block.block_up(structure).face_pointing_pos_y.direction().to_coordinates();
+
+    BlockCoordinate::try_from(up + block.coords())
+        .ok()
+        .filter(|c| structure.is_within_blocks(*c))
+}
+
+/// The block directly below this one, based on the way the block is facing
+fn block_below(structure: &Structure, block: StructureBlock) -> Option<BlockCoordinate> {
+    let down = block.block_up(structure).face_pointing_pos_y.inverse().direction().to_coordinates();
+
+    BlockCoordinate::try_from(down + block.coords())
+        .ok()
+        .filter(|c| structure.is_within_blocks(*c))
+}
+
+fn on_interact_farming(
+    mut evr_block_interact: EventReader<BlockInteractEvent>,
+    q_structure: Query<&Structure>,
+    mut q_player: Query<(&Player, &HeldItemSlot, &mut Inventory)>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut evw_farming_action: EventWriter<FarmingAction>,
+    mut q_durability: Query<&mut ItemDurability>,
+    mut commands: Commands,
+) {
+    let Some(air) = blocks.from_id("cosmos:air") else {
+        return;
+    };
+
+    for ev in evr_block_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok(structure) = q_structure.get(s_block.structure()) else {
+            continue;
+        };
+
+        let Ok((player, held_item, mut inventory)) = q_player.get_mut(ev.interactor) else {
+            continue;
+        };
+
+        let block = structure.block_at(s_block.coords(), &blocks);
+        let held_slot = held_item.slot() as usize;
+        let held_item = inventory
+            .itemstack_at(held_slot)
+            .and_then(|is| items.try_from_numeric_id(is.item_id()));
+
+        let action = if let Some((crop, stage)) = crop_from_block(block.unlocalized_name()) {
+            if stage + 1 != crop.stages.len() {
+                continue;
+            }
+
+            FarmingAction::Harvest(s_block)
+        } else if TILLABLE_BLOCKS.contains(&block.unlocalized_name()) {
+            if held_item.is_none_or(|item| item.unlocalized_name() != HOE_ITEM) {
+                continue;
+            }
+
+            FarmingAction::Till(s_block)
+        } else if block.unlocalized_name() == FARMLAND_BLOCK {
+            let Some(crop) = held_item.and_then(|item| crop_from_seed(item.unlocalized_name())) else {
+                continue;
+            };
+
+            // Crops need room to grow
+            if block_above(structure, s_block).is_none_or(|above| structure.block_at(above, &blocks) != air) {
+                continue;
+            }
+
+            FarmingAction::Plant { crop, farmland: s_block }
+        } else {
+            continue;
+        };
+
+        if !permissions.can_use(ev.interactor, s_block.structure()) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        match action {
+            FarmingAction::Plant { .. } => {
+                inventory.decrease_quantity_at(held_slot, 1, &mut commands);
+            }
+            FarmingAction::Till(_) => {
+                wear_item_at(&mut inventory, held_slot, 1, &mut q_durability, &mut commands);
+            }
+            _ => {}
+        }
+
+        evw_farming_action.send(action);
+    }
+}
+
+/// Spawns a physical item for this harvest at the given location
+fn drop_produce(commands: &mut Commands, item: &Item, quantity: u16, location: Location, rotation: Quat, needs_data: &ItemShouldHaveData) {
+    let mut rng = rand::thread_rng();
+
+    let dropped_item_entity = commands
+        .spawn((
+            PhysicalItem,
+            location,
+            LoadingDistance::new(1, 2),
+            Transform::from_rotation(rotation),
+            Velocity {
+                linvel: Vec3::new(rng.gen_range(-0.5..0.5), rng.gen_range(-0.5..0.5), rng.gen_range(-0.5..0.5)) * 2.0,
+                angvel: Vec3::ZERO,
+            },
+        ))
+        .id();
+
+    let mut physical_item_inventory = Inventory::new("", 1, None, dropped_item_entity);
+    physical_item_inventory.insert_item(item, quantity.min(item.max_stack_size()), commands, needs_data);
+    commands.entity(dropped_item_entity).insert(physical_item_inventory);
+}
+
+fn apply_farming_actions(
+    mut commands: Commands,
+    mut evr_farming_action: EventReader<FarmingAction>,
+    mut q_structure: Query<(&mut Structure, &Location, &GlobalTransform)>,
+    mut evw_block_changed: EventWriter<BlockChangedEvent>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    needs_data: Res<ItemShouldHaveData>,
+) {
+    let Some(air) = blocks.from_id("cosmos:air") else {
+        return;
+    };
+    let Some(farmland) = blocks.from_id(FARMLAND_BLOCK) else {
+        return;
+    };
+
+    let mut rng = rand::thread_rng();
+
+    for ev in evr_farming_action.read() {
+        let s_block = match ev {
+            FarmingAction::Till(b) | FarmingAction::Grow(b) | FarmingAction::Harvest(b) => *b,
+            FarmingAction::Plant { farmland, .. } => *farmland,
+        };
+
+        let Ok((mut structure, location, g_trans)) = q_structure.get_mut(s_block.structure()) else {
+            continue;
+        };
+
+        let coords = s_block.coords();
+        // The block could have changed since this action was requested, so make sure it's still valid
+        let block = structure.block_at(coords, &blocks);
+
+        match ev {
+            FarmingAction::Till(_) => {
+                if !TILLABLE_BLOCKS.contains(&block.unlocalized_name()) {
+                    continue;
+                }
+
+                let rotation = structure.block_rotation(coords);
+                structure.set_block_at(coords, farmland, rotation, &blocks, Some(&mut evw_block_changed));
+            }
+            FarmingAction::Plant { crop, .. } => {
+                if block != farmland {
+                    continue;
+                }
+
+                let Some(above) = block_above(&structure, s_block) else {
+                    continue;
+                };
+
+                if structure.block_at(above, &blocks) != air {
+                    continue;
+                }
+
+                let Some(planted) = blocks.from_id(crop.planted_block()) else {
+                    continue;
+                };
+
+                let rotation = structure.block_rotation(coords);
+                structure.set_block_at(above, planted, rotation, &blocks, Some(&mut evw_block_changed));
+            }
+            FarmingAction::Grow(_) => {
+                let Some((crop, stage)) = crop_from_block(block.unlocalized_name()) else {
+                    continue;
+                };
+
+                let Some(next_stage) = crop.stages.get(stage + 1).and_then(|s| blocks.from_id(s)) else {
+                    continue;
+                };
+
+                let rotation = structure.block_rotation(coords);
+                structure.set_block_at(coords, next_stage, rotation, &blocks, Some(&mut evw_block_changed));
+            }
+            FarmingAction::Harvest(_) => {
+                let Some((crop, stage)) = crop_from_block(block.unlocalized_name()) else {
+                    continue;
+                };
+
+                if stage + 1 != crop.stages.len() {
+                    continue;
+                }
+
+                let Some(planted) = blocks.from_id(crop.planted_block()) else {
+                    continue;
+                };
+
+                let rotation = structure.block_rotation(coords);
+                structure.set_block_at(coords, planted, rotation, &blocks, Some(&mut evw_block_changed));
+
+                let structure_rot = g_trans.to_scale_rotation_translation().1;
+                let drop_location = *location + structure_rot * structure.block_relative_position(coords);
+
+                if let Some(produce) = items.from_id(crop.produce_item) {
+                    let quantity = rng.gen_range(1..=crop.max_produce.max(1));
+                    drop_produce(&mut commands, produce, quantity, drop_location, structure_rot, &needs_data);
+                }
+
+                let seeds = rng.gen_range(0..=crop.max_seeds);
+                if seeds != 0 {
+                    if let Some(seed) = items.from_id(crop.seed_item) {
+                        drop_produce(&mut commands, seed, seeds, drop_location, structure_rot, &needs_data);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn on_place_crop(
+    mut evr_changed_block: EventReader<BlockChangedEvent>,
+    mut q_structure: Query<&mut Structure>,
+    q_has_data: Query<(), With<CropGrowth>>,
+    mut q_block_data: Query<&mut BlockData>,
+    mut bs_params: BlockDataSystemParams,
+    blocks: Res<Registry<Block>>,
+) {
+    for ev in evr_changed_block.read() {
+        let Some(new_block) = blocks.try_from_numeric_id(ev.new_block) else {
+            continue;
+        };
+
+        // Fully grown crops have nothing left to track
+        if crop_from_block(new_block.unlocalized_name()).is_none_or(|(crop, stage)| stage + 1 == crop.stages.len()) {
+            continue;
+        }
+
+        let Ok(mut structure) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        structure.insert_block_data(
+            ev.block.coords(),
+            CropGrowth::default(),
+            &mut bs_params,
+            &mut q_block_data,
+            &q_has_data,
+        );
+    }
+}
+
+fn grow_crops(
+    time: Res<Time>,
+    mut timer: ResMut<CropGrowthTimer>,
+    mut q_crops: Query<(&BlockData, &mut CropGrowth)>,
+    q_structure: Query<(&Structure, &GlobalTransform)>,
+    q_planets: Query<(&Location, &GlobalTransform), With<Planet>>,
+    q_stars: Query<&Location, With<Star>>,
+    mut evw_farming_action: EventWriter<FarmingAction>,
+) {
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() {
+        return;
+    }
+
+    for (block_data, mut growth) in q_crops.iter_mut() {
+        let block = block_data.identifier.block;
+
+        let Ok((structure, g_trans)) = q_structure.get(block.structure()) else {
+            continue;
+        };
+
+        let planet = q_planets.get(block.structure()).ok();
+        let star = planet.and_then(|(planet_loc, _)| {
+            q_stars
+                .iter()
+                .min_by(|a, b| a.distance_sqrd(planet_loc).total_cmp(&b.distance_sqrd(planet_loc)))
+        });
+
+        let position = g_trans.transform_point(structure.block_relative_position(block.coords()));
+        let light = if sun_elevation_degrees(position, planet, star) > 0.0 {
+            1.0
+        } else {
+            NIGHT_GROWTH_MULTIPLIER
+        };
+
+        growth.progress += GROWTH_TICK_SECS * light;
+
+        if growth.progress >= SECONDS_PER_GROWTH_STAGE {
+            // The new stage's block will get fresh growth data once it's placed
+            growth.progress = 0.0;
+            evw_farming_action.send(FarmingAction::Grow(block));
+        }
+    }
+}
+
+/// Crops can only stay planted on farmland
+fn monitor_crops_updated(
+    mut q_structure: Query<&mut Structure>,
+    blocks: Res<Registry<Block>>,
+    mut evr_block_update: EventReader<MutEvent<BlockUpdate>>,
+    mut evw_block_changed: EventWriter<BlockChangedEvent>,
+) {
+    for ev in evr_block_update.read() {
+        let ev = ev.read();
+
+        if ev.cancelled() {
+            continue;
+        }
+
+        let Ok(mut structure) = q_structure.get_mut(ev.structure_entity()) else {
+            continue;
+        };
+
+        let block = ev.block().block(&structure, &blocks);
+
+        if crop_from_block(block.unlocalized_name()).is_none() {
+            continue;
+        }
+
+        let on_farmland = block_below(&structure, ev.block())
+            .is_some_and(|below| structure.block_at(below, &blocks).unlocalized_name() == FARMLAND_BLOCK);
+
+        if !on_farmland {
+            structure.remove_block_at(ev.block().coords(), &blocks, Some(&mut evw_block_changed));
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<CropGrowth>(app);
+
+    app.add_event::<FarmingAction>().init_resource::<CropGrowthTimer>().add_systems(
+        Update,
+        (
+            on_place_crop.in_set(BlockEventsSet::SendEventsForThisFrame),
+            monitor_crops_updated
+                .in_set(BlockEventsSet::SendEventsForNextFrame)
+                .ambiguous_with(BlockEventsSet::SendEventsForNextFrame),
+            (
+                (
+                    on_interact_farming.in_set(BlockEventsSet::ProcessEvents),
+                    grow_crops.in_set(BlockEventsSet::PostProcessEvents),
+                ),
+                apply_farming_actions.in_set(BlockEventsSet::SendEventsForNextFrame),
+            )
+                .chain(),
+        )
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
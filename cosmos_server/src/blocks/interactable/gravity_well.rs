@@ -22,8 +22,11 @@ use cosmos_core::{
         Block,
     },
     netty::{
-        cosmos_encoder, server_replication::ReplicationMessage, sync::server_entity_syncing::RequestedEntityEvent,
-        system_sets::NetworkingSystemsSet, NettyChannelServer,
+        network_stats::{NettyMessage, NettyServerMessages},
+        server_replication::ReplicationMessage,
+        sync::server_entity_syncing::RequestedEntityEvent,
+        system_sets::NetworkingSystemsSet,
+        NettyChannelServer,
     },
     prelude::BlockCoordinate,
     registry::{identifiable::Identifiable, Registry},
@@ -106,9 +109,9 @@ fn sync_gravity_well(
     mut removed_components: RemovedComponents<GravityWell>,
 ) {
     for (entity, under_grav_well) in &q_grav_well {
-        server.broadcast_message(
+        server.broadcast_netty_message(
             NettyChannelServer::SystemReplication,
-            cosmos_encoder::serialize(&ReplicationMessage::GravityWell {
+            NettyMessage::new(&ReplicationMessage::GravityWell {
                 gravity_well: Some(*under_grav_well),
                 entity,
             }),
@@ -116,9 +119,9 @@ fn sync_gravity_well(
     }
 
     for entity in removed_components.read() {
-        server.broadcast_message(
+        server.broadcast_netty_message(
             NettyChannelServer::SystemReplication,
-            cosmos_encoder::serialize(&ReplicationMessage::GravityWell {
+            NettyMessage::new(&ReplicationMessage::GravityWell {
                 gravity_well: None,
                 entity,
             }),
@@ -149,10 +152,10 @@ fn on_request_under_grav(
             continue;
         };
 
-        server.send_message(
+        server.send_netty_message(
             ev.client_id,
             NettyChannelServer::SystemReplication,
-            cosmos_encoder::serialize(&ReplicationMessage::GravityWell {
+            NettyMessage::new(&ReplicationMessage::GravityWell {
                 gravity_well: Some(*grav_well),
                 entity: ev.entity,
             }),
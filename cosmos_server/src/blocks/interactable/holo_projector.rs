@@ -0,0 +1,153 @@
+use std::{cell::RefCell, rc::Rc};
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        data::BlockData,
+        specific_blocks::holo_projector::{HoloProjector, HOLO_PROJECTOR_BLOCK},
+        Block,
+    },
+    events::block_events::{BlockChangedEvent, BlockDataSystemParams},
+    logic::{logic_driver::LogicDriver, BlockLogicData, LogicInputEvent, LogicSystemSet},
+    netty::system_sets::NetworkingSystemsSet,
+    prelude::Structure,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+};
+
+use crate::persistence::make_persistent::{make_persistent, DefaultPersistentComponent};
+
+impl DefaultPersistentComponent for HoloProjector {}
+
+fn on_place_holo_projector(
+    mut evr_changed_block: EventReader<BlockChangedEvent>,
+    mut q_structure: Query<&mut Structure>,
+    q_has_data: Query<(), With<HoloProjector>>,
+    mut q_block_data: Query<&mut BlockData>,
+    mut bs_params: BlockDataSystemParams,
+    blocks: Res<Registry<Block>>,
+) {
+    let Some(holo_projector) = blocks.from_id(HOLO_PROJECTOR_BLOCK) else {
+        return;
+    };
+
+    for ev in evr_changed_block.read() {
+        if ev.new_block != holo_projector.id() {
+            continue;
+        }
+
+        let Ok(mut structure) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        structure.insert_block_data(
+            ev.block.coords(),
+            HoloProjector::default(),
+            &mut bs_params,
+            &mut q_block_data,
+            &q_has_data,
+        );
+    }
+}
+
+/// Interacting with a projector cycles through what it displays
+fn on_interact_holo_projector(
+    mut evr_block_interact: EventReader<BlockInteractEvent>,
+    q_structure: Query<&Structure>,
+    mut q_projector: Query<&mut HoloProjector>,
+    blocks: Res<Registry<Block>>,
+    bs_params: BlockDataSystemParams,
+) {
+    let bs_params = Rc::new(RefCell::new(bs_params));
+
+    for ev in evr_block_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok(structure) = q_structure.get(s_block.structure()) else {
+            continue;
+        };
+
+        if structure.block_at(s_block.coords(), &blocks).unlocalized_name() != HOLO_PROJECTOR_BLOCK {
+            continue;
+        }
+
+        let Some(mut projector) = structure.query_block_data_mut(s_block.coords(), &mut q_projector, bs_params.clone()) else {
+            continue;
+        };
+
+        projector.mode = projector.mode.next();
+    }
+}
+
+/// A logic signal turning on toggles the hologram
+fn holo_projector_input_event_listener(
+    mut evr_logic_input: EventReader<LogicInputEvent>,
+    blocks: Res<Registry<Block>>,
+    q_logic_driver: Query<&LogicDriver>,
+    q_structure: Query<&Structure>,
+    mut q_logic_data: Query<&mut BlockLogicData>,
+    mut q_projector: Query<&mut HoloProjector>,
+    bs_params: BlockDataSystemParams,
+) {
+    let bs_params = Rc::new(RefCell::new(bs_params));
+
+    for ev in evr_logic_input.read() {
+        let Ok(structure) = q_structure.get(ev.block.structure()) else {
+            continue;
+        };
+        if structure.block_at(ev.block.coords(), &blocks).unlocalized_name() != HOLO_PROJECTOR_BLOCK {
+            continue;
+        }
+        let Ok(logic_driver) = q_logic_driver.get(ev.block.structure()) else {
+            continue;
+        };
+        let Some(mut logic_data) = structure.query_block_data_mut(ev.block.coords(), &mut q_logic_data, bs_params.clone()) else {
+            continue;
+        };
+
+        let new_state = BlockLogicData(
+            logic_driver
+                .read_all_inputs(ev.block.coords(), structure.block_rotation(ev.block.coords()))
+                .iter()
+                .any(|signal| *signal != 0) as i32,
+        );
+
+        if **logic_data == new_state {
+            continue;
+        }
+
+        let turned_on = new_state.on();
+        **logic_data = new_state;
+
+        if !turned_on {
+            continue;
+        }
+
+        if let Some(mut projector) = structure.query_block_data_mut(ev.block.coords(), &mut q_projector, bs_params.clone()) {
+            projector.enabled = !projector.enabled;
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<HoloProjector>(app);
+
+    app.add_systems(
+        Update,
+        (
+            on_place_holo_projector.in_set(BlockEventsSet::SendEventsForThisFrame),
+            on_interact_holo_projector.in_set(BlockEventsSet::ProcessEvents),
+        )
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    )
+    .add_systems(
+        Update,
+        holo_projector_input_event_listener
+            .in_set(LogicSystemSet::Consume)
+            .ambiguous_with(LogicSystemSet::Consume),
+    );
+}
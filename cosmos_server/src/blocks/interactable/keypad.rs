@@ -0,0 +1,333 @@
+//! Keypads send a logic pulse when the correct code is typed into them.
+//!
+//! Alternate interacting with a keypad lets players who can use the structure change its code.
+//!
+//! Entering too many wrong codes into a keypad locks that player out of that keypad for a while, so codes can't be
+//! guessed by trying every one of them.
+
+use std::{cell::RefCell, rc::Rc};
+
+use bevy::{prelude::*, utils::HashMap};
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        data::BlockData,
+        specific_blocks::keypad::{is_valid_keypad_code, EnterKeypadCodeEvent, KeypadPurpose, OpenKeypadEvent, KEYPAD_BLOCK},
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    events::block_events::BlockDataSystemParams,
+    logic::BlockLogicData,
+    netty::{
+        server::ServerLobby,
+        sync::{
+            events::server_event::{NettyEventReceived, NettyEventWriter},
+            IdentifiableComponent,
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::{BlockCoordinate, Structure},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+};
+use renet2::ClientId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+    structure::ownership::{notify_no_permission, StructurePermissions},
+};
+
+use super::button::LogicPulse;
+
+/// Players can type into keypads from a little further than they can reach to account for latency
+const MAX_KEYPAD_DISTANCE: f32 = 12.0;
+
+/// How many wrong codes a player can enter into a keypad before they're locked out of it
+const MAX_WRONG_CODES: u32 = 3;
+/// How long (in seconds) a player is locked out of a keypad for after entering too many wrong codes
+const WRONG_CODE_LOCKOUT_SECS: f64 = 30.0;
+/// How long (in seconds) a wrong code is remembered for
+const WRONG_CODE_MEMORY_SECS: f64 = 60.0;
+
+#[derive(Component, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// The code that must be entered into a keypad for it to send a pulse. This is never sent to clients.
+struct KeypadCode(String);
+
+impl IdentifiableComponent for KeypadCode {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:keypad_code"
+    }
+}
+
+impl DefaultPersistentComponent for KeypadCode {}
+
+#[derive(Debug, Default, Clone, Copy)]
+/// The wrong codes one player has recently entered into one keypad
+struct WrongCodes {
+    count: u32,
+    last_wrong: f64,
+    locked_out_until: f64,
+}
+
+impl WrongCodes {
+    /// How many seconds are left until the player can use this keypad again, if they're locked out
+    fn lockout_remaining(&self, now: f64) -> Option<f64> {
+        (self.locked_out_until > now).then_some(self.locked_out_until - now)
+    }
+
+    /// Records a wrong code, locking the player out once they've entered too many
+    fn record_wrong_code(&mut self, now: f64) {
+        self.count += 1;
+        self.last_wrong = now;
+
+        if self.count >= MAX_WRONG_CODES {
+            self.count = 0;
+            self.locked_out_until = now + WRONG_CODE_LOCKOUT_SECS;
+        }
+    }
+
+    /// If this no longer affects the player, and can be forgotten
+    fn expired(&self, now: f64) -> bool {
+        self.lockout_remaining(now).is_none() && now - self.last_wrong > WRONG_CODE_MEMORY_SECS
+    }
+}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+fn on_interact_keypad(
+    mut evr_block_interact: EventReader<BlockInteractEvent>,
+    q_structure: Query<&Structure>,
+    q_player: Query<&Player>,
+    q_code: Query<(), With<KeypadCode>>,
+    blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    mut nevw_open_keypad: NettyEventWriter<OpenKeypadEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for ev in evr_block_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok(structure) = q_structure.get(s_block.structure()) else {
+            continue;
+        };
+
+        if structure.block_at(s_block.coords(), &blocks).unlocalized_name() != KEYPAD_BLOCK {
+            continue;
+        }
+
+        let Ok(player) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        let purpose = if ev.alternate {
+            if !permissions.can_use(ev.interactor, s_block.structure()) {
+                notify_no_permission(&mut nevw_chat, player.id());
+                continue;
+            }
+
+            KeypadPurpose::SetCode
+        } else {
+            if structure.query_block_data(s_block.coords(), &q_code).is_none() {
+                reply(&mut nevw_chat, player.id(), "This keypad doesn't have a code yet.");
+                continue;
+            }
+
+            KeypadPurpose::Enter
+        };
+
+        nevw_open_keypad.send(OpenKeypadEvent { block: s_block, purpose }, player.id());
+    }
+}
+
+/// Returns the player who sent this code if they're close enough to the keypad
+fn keypad_user(
+    ev: &EnterKeypadCodeEvent,
+    client_id: ClientId,
+    lobby: &ServerLobby,
+    q_player: &Query<&GlobalTransform, With<Player>>,
+    structure: &Structure,
+    structure_g_trans: &GlobalTransform,
+    blocks: &Registry<Block>,
+) -> Option<Entity> {
+    let Some(player_ent) = lobby.player_from_id(client_id) else {
+        warn!("Bad player - cid: {client_id}");
+        return None;
+    };
+
+    let player_g_trans = q_player.get(player_ent).ok()?;
+
+    let coords = ev.block.coords();
+
+    if !structure.is_within_blocks(coords) || structure.block_at(coords, blocks).unlocalized_name() != KEYPAD_BLOCK {
+        warn!("Player {player_ent:?} tried to use a keypad where there is none.");
+        return None;
+    }
+
+    let block_position = structure_g_trans.transform_point(structure.block_relative_position(coords));
+    if block_position.distance_squared(player_g_trans.translation()) > MAX_KEYPAD_DISTANCE * MAX_KEYPAD_DISTANCE {
+        warn!("Player {player_ent:?} tried to use a keypad that is too far away.");
+        return None;
+    }
+
+    Some(player_ent)
+}
+
+fn on_enter_keypad_code(
+    mut nevr_enter_code: EventReader<NettyEventReceived<EnterKeypadCodeEvent>>,
+    lobby: Res<ServerLobby>,
+    q_player: Query<&GlobalTransform, With<Player>>,
+    q_structure: Query<(&Structure, &GlobalTransform)>,
+    q_code: Query<&KeypadCode>,
+    mut q_pulse: Query<&mut LogicPulse>,
+    mut q_logic_data: Query<&mut BlockLogicData>,
+    blocks: Res<Registry<Block>>,
+    time: Res<Time>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    bs_params: BlockDataSystemParams,
+    mut wrong_codes: Local<HashMap<(ClientId, Entity, BlockCoordinate), WrongCodes>>,
+) {
+    if nevr_enter_code.is_empty() {
+        return;
+    }
+
+    let now = time.elapsed_secs_f64();
+    wrong_codes.retain(|_, wrong| !wrong.expired(now));
+
+    let bs_params = Rc::new(RefCell::new(bs_params));
+
+    for ev in nevr_enter_code.read() {
+        if ev.purpose != KeypadPurpose::Enter {
+            continue;
+        }
+
+        let Ok((structure, structure_g_trans)) = q_structure.get(ev.block.structure()) else {
+            continue;
+        };
+
+        if keypad_user(ev, ev.client_id, &lobby, &q_player, structure, structure_g_trans, &blocks).is_none() {
+            continue;
+        }
+
+        let coords = ev.block.coords();
+        let key = (ev.client_id, ev.block.structure(), coords);
+
+        if let Some(remaining) = wrong_codes.get(&key).and_then(|wrong| wrong.lockout_remaining(now)) {
+            reply(
+                &mut nevw_chat,
+                ev.client_id,
+                format!("Too many incorrect codes. Try again in {} seconds.", remaining.ceil()),
+            );
+            continue;
+        }
+
+        if structure.query_block_data(coords, &q_code).is_none_or(|code| code.0 != ev.code) {
+            wrong_codes.entry(key).or_default().record_wrong_code(now);
+            reply(&mut nevw_chat, ev.client_id, "Incorrect code.");
+            continue;
+        }
+
+        wrong_codes.remove(&key);
+
+        if let Some(mut pulse) = structure.query_block_data_mut(coords, &mut q_pulse, bs_params.clone()) {
+            pulse.start();
+        }
+
+        if let Some(mut logic_data) = structure.query_block_data_mut(coords, &mut q_logic_data, bs_params.clone()) {
+            if !logic_data.on() {
+                **logic_data = BlockLogicData(1);
+            }
+        }
+    }
+}
+
+fn on_set_keypad_code(
+    mut nevr_enter_code: EventReader<NettyEventReceived<EnterKeypadCodeEvent>>,
+    lobby: Res<ServerLobby>,
+    q_player: Query<&GlobalTransform, With<Player>>,
+    mut q_structure: Query<(&mut Structure, &GlobalTransform)>,
+    q_has_code: Query<(), With<KeypadCode>>,
+    mut q_block_data: Query<&mut BlockData>,
+    blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut bs_params: BlockDataSystemParams,
+) {
+    for ev in nevr_enter_code.read() {
+        if ev.purpose != KeypadPurpose::SetCode {
+            continue;
+        }
+
+        let Ok((mut structure, structure_g_trans)) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        let Some(player_ent) = keypad_user(ev, ev.client_id, &lobby, &q_player, &structure, structure_g_trans, &blocks) else {
+            continue;
+        };
+
+        if !permissions.can_use(player_ent, ev.block.structure()) {
+            notify_no_permission(&mut nevw_chat, ev.client_id);
+            continue;
+        }
+
+        if !is_valid_keypad_code(&ev.code) {
+            reply(&mut nevw_chat, ev.client_id, "Keypad codes must be made of digits.");
+            continue;
+        }
+
+        structure.insert_block_data(
+            ev.block.coords(),
+            KeypadCode(ev.code.clone()),
+            &mut bs_params,
+            &mut q_block_data,
+            &q_has_code,
+        );
+
+        reply(&mut nevw_chat, ev.client_id, "Keypad code set.");
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<KeypadCode>(app);
+
+    app.add_systems(
+        Update,
+        (on_interact_keypad, on_enter_keypad_code, on_set_keypad_code)
+            .chain()
+            .in_set(BlockEventsSet::ProcessEvents)
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrong_codes_lock_out_keypad() {
+        let mut wrong = WrongCodes::default();
+
+        for _ in 0..MAX_WRONG_CODES - 1 {
+            wrong.record_wrong_code(0.0);
+        }
+        assert_eq!(wrong.lockout_remaining(0.0), None);
+
+        wrong.record_wrong_code(0.0);
+        assert_eq!(wrong.lockout_remaining(10.0), Some(WRONG_CODE_LOCKOUT_SECS - 10.0));
+        assert!(!wrong.expired(WRONG_CODE_LOCKOUT_SECS));
+        assert!(wrong.expired(WRONG_CODE_LOCKOUT_SECS + WRONG_CODE_MEMORY_SECS + 1.0));
+    }
+}
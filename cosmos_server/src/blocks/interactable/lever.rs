@@ -0,0 +1,74 @@
+//! Using a lever toggles its logic output on and off
+
+use std::{cell::RefCell, rc::Rc};
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        specific_blocks::lever::LEVER_BLOCK,
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    events::block_events::BlockDataSystemParams,
+    logic::BlockLogicData,
+    netty::{sync::events::server_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    prelude::Structure,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+};
+
+use crate::structure::ownership::{notify_no_permission, StructurePermissions};
+
+fn on_interact_lever(
+    mut evr_block_interact: EventReader<BlockInteractEvent>,
+    q_structure: Query<&Structure>,
+    q_player: Query<&Player>,
+    mut q_logic_data: Query<&mut BlockLogicData>,
+    blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    bs_params: BlockDataSystemParams,
+) {
+    let bs_params = Rc::new(RefCell::new(bs_params));
+
+    for ev in evr_block_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok(structure) = q_structure.get(s_block.structure()) else {
+            continue;
+        };
+
+        if structure.block_at(s_block.coords(), &blocks).unlocalized_name() != LEVER_BLOCK {
+            continue;
+        }
+
+        let Ok(player) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        if !permissions.can_use(ev.interactor, s_block.structure()) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        let Some(mut logic_data) = structure.query_block_data_mut(s_block.coords(), &mut q_logic_data, bs_params.clone()) else {
+            continue;
+        };
+
+        **logic_data = BlockLogicData(!logic_data.on() as i32);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        on_interact_lever
+            .in_set(BlockEventsSet::ProcessEvents)
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
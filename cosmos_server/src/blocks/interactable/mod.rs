@@ -2,14 +2,40 @@
 
 use bevy::prelude::App;
 
+mod airlock;
+mod battery_charger;
+mod button;
+mod clock;
 mod door;
+mod energy_relay;
+mod farming;
 mod gravity_well;
+mod holo_projector;
+mod keypad;
+mod lever;
+pub mod seat;
 mod ship_core;
+mod sign;
 mod storage;
+pub mod storage_lock;
+mod timer;
 
 pub(super) fn register(app: &mut App) {
     ship_core::register(app);
     storage::register(app);
+    storage_lock::register(app);
     gravity_well::register(app);
     door::register(app);
+    airlock::register(app);
+    sign::register(app);
+    holo_projector::register(app);
+    button::register(app);
+    lever::register(app);
+    keypad::register(app);
+    timer::register(app);
+    clock::register(app);
+    farming::register(app);
+    energy_relay::register(app);
+    battery_charger::register(app);
+    seat::register(app);
 }
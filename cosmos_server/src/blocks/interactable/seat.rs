@@ -0,0 +1,210 @@
+//! Seats players on chairs & beds, and binds the respawn point of players who sleep in a bed
+
+use bevy::prelude::*;
+use bevy_renet2::renet2::ClientId;
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        specific_blocks::seat::{SeatKind, Seated, StandUpEvent},
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    events::block_events::BlockChangedEvent,
+    netty::{
+        server::ServerLobby,
+        sync::{
+            events::server_event::{NettyEventReceived, NettyEventWriter},
+            IdentifiableComponent,
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::{BlockCoordinate, Structure},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{shared::build_mode::BuildMode, ship::pilot::Pilot},
+    utils::ownership::MaybeOwned,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    persistence::{
+        make_persistent::{make_persistent, EntityIdManager, PersistentComponent},
+        EntityId,
+    },
+    structure::ownership::{notify_no_permission, StructurePermissions},
+};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+/// The bed a player last slept in, which is where they respawn
+pub struct RespawnPoint {
+    /// The structure the bed is on
+    pub structure: Entity,
+    /// Where the bed is on that structure
+    pub block: BlockCoordinate,
+}
+
+impl IdentifiableComponent for RespawnPoint {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:respawn_point"
+    }
+}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+fn on_interact_seat(
+    mut commands: Commands,
+    mut evr_interact: EventReader<BlockInteractEvent>,
+    q_structure: Query<&Structure>,
+    q_player: Query<(&Player, Option<&RespawnPoint>), (Without<Pilot>, Without<BuildMode>, Without<Seated>)>,
+    q_seated: Query<&Seated>,
+    blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for ev in evr_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok(structure) = q_structure.get(s_block.structure()) else {
+            continue;
+        };
+
+        let Some(kind) = SeatKind::from_block(structure.block_at(s_block.coords(), &blocks).unlocalized_name()) else {
+            continue;
+        };
+
+        let Ok((player, respawn_point)) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        if !permissions.can_use(ev.interactor, s_block.structure()) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        if q_seated
+            .iter()
+            .any(|seated| seated.structure == s_block.structure() && seated.block == s_block.coords())
+        {
+            reply(&mut nevw_chat, player.id(), "Someone is already using this.");
+            continue;
+        }
+
+        let mut ecmds = commands.entity(ev.interactor);
+
+        ecmds.insert(Seated {
+            structure: s_block.structure(),
+            block: s_block.coords(),
+            kind,
+        });
+
+        if kind == SeatKind::Bed {
+            let bed = RespawnPoint {
+                structure: s_block.structure(),
+                block: s_block.coords(),
+            };
+
+            if respawn_point != Some(&bed) {
+                ecmds.insert(bed);
+                reply(&mut nevw_chat, player.id(), "You will now respawn at this bed.");
+            }
+        }
+    }
+}
+
+fn on_stand_up(
+    mut commands: Commands,
+    mut nevr_stand_up: EventReader<NettyEventReceived<StandUpEvent>>,
+    lobby: Res<ServerLobby>,
+    q_seated: Query<(), With<Seated>>,
+) {
+    for ev in nevr_stand_up.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        if q_seated.contains(player_ent) {
+            commands.entity(player_ent).remove::<Seated>();
+        }
+    }
+}
+
+/// Stands players up if their seat is broken or the structure it was on is gone
+fn remove_invalid_seats(
+    mut commands: Commands,
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    q_seated: Query<(Entity, &Seated)>,
+    q_structure: Query<(), With<Structure>>,
+) {
+    let changed = evr_block_changed.read().map(|ev| ev.block).collect::<Vec<_>>();
+
+    for (ent, seated) in q_seated.iter() {
+        let seat_changed = changed
+            .iter()
+            .any(|block| block.structure() == seated.structure && block.coords() == seated.block);
+
+        if seat_changed || !q_structure.contains(seated.structure) {
+            commands.entity(ent).remove::<Seated>();
+        }
+    }
+}
+
+/// The serialized version of a respawn point.
+///
+/// Only public because the trait requires it to be public. Don't use this.
+#[derive(Serialize, Deserialize)]
+pub struct SerializedRespawnPoint {
+    structure_entity_id: EntityId,
+    block: BlockCoordinate,
+}
+
+impl PersistentComponent for RespawnPoint {
+    type SaveType = SerializedRespawnPoint;
+
+    fn convert_to_save_type<'a>(&'a self, q_entity_ids: &Query<&EntityId>) -> Option<MaybeOwned<'a, SerializedRespawnPoint>> {
+        q_entity_ids
+            .get(self.structure)
+            .map(|x| {
+                MaybeOwned::Owned(Box::new(SerializedRespawnPoint {
+                    structure_entity_id: x.clone(),
+                    block: self.block,
+                }))
+            })
+            .ok()
+    }
+
+    fn convert_from_save_type(save_type: Self::SaveType, entity_id_manager: &EntityIdManager) -> Option<Self> {
+        entity_id_manager
+            .entity_from_entity_id(&save_type.structure_entity_id)
+            .map(|structure| Self {
+                structure,
+                block: save_type.block,
+            })
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<RespawnPoint>(app);
+
+    app.add_systems(
+        Update,
+        (
+            (on_interact_seat, on_stand_up).in_set(BlockEventsSet::ProcessEvents),
+            remove_invalid_seats.after(BlockEventsSet::PostProcessEvents),
+        )
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -1,11 +1,15 @@
 use bevy::prelude::{in_state, App, EventReader, EventWriter, IntoSystemConfigs, Query, Res, Update, With};
+use bevy_renet2::renet2::ClientId;
 use cosmos_core::{
     block::{
         block_events::{BlockEventsSet, BlockInteractEvent},
+        specific_blocks::seat::{Seated, COCKPIT_SEAT_BLOCK},
         Block,
     },
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
     events::structure::change_pilot_event::ChangePilotEvent,
-    netty::system_sets::NetworkingSystemsSet,
+    netty::{sync::events::server_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
     registry::{identifiable::Identifiable, Registry},
     state::GameState,
     structure::{
@@ -14,12 +18,28 @@ use cosmos_core::{
     },
 };
 
+use crate::structure::ownership::{notify_no_permission, StructurePermissions};
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
 fn handle_block_event(
     mut interact_events: EventReader<BlockInteractEvent>,
     mut change_pilot_event: EventWriter<ChangePilotEvent>,
     s_query: Query<&Structure, With<Ship>>,
     pilot_query: Query<&Pilot>,
     blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    q_player: Query<&Player>,
+    q_seated: Query<(), With<Seated>>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
 ) {
     for ev in interact_events.read() {
         let Some(s_block) = ev.block else {
@@ -30,13 +50,34 @@ fn handle_block_event(
             continue;
         };
 
-        let Some(block) = blocks.from_id("cosmos:ship_core") else {
+        let (Some(ship_core), Some(cockpit_seat)) = (blocks.from_id("cosmos:ship_core"), blocks.from_id(COCKPIT_SEAT_BLOCK)) else {
             continue;
         };
 
         let block_id = s_block.block_id(structure);
 
-        if block_id != block.id() {
+        if block_id != ship_core.id() && block_id != cockpit_seat.id() {
+            continue;
+        }
+
+        // Players sitting down have to stand up before they can pilot
+        if q_seated.contains(ev.interactor) {
+            continue;
+        }
+
+        // Ships with a cockpit seat can only be flown from one, but ships without any are still flown from their core
+        let has_cockpit = structure.block_counts().is_some_and(|counts| counts.count(cockpit_seat.id()) != 0);
+        if block_id == ship_core.id() && has_cockpit {
+            if let Ok(player) = q_player.get(ev.interactor) {
+                reply(&mut nevw_chat, player.id(), "This ship is piloted from its cockpit seat.");
+            }
+            continue;
+        }
+
+        if !permissions.can_use(ev.interactor, s_block.structure()) {
+            if let Ok(player) = q_player.get(ev.interactor) {
+                notify_no_permission(&mut nevw_chat, player.id());
+            }
             continue;
         }
 
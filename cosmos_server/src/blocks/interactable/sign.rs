@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        data::BlockData,
+        specific_blocks::sign::{OpenSignEditorEvent, SetSignTextEvent, SignText, SIGN_BLOCK},
+        Block,
+    },
+    entities::player::Player,
+    events::block_events::BlockDataSystemParams,
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::Structure,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+};
+
+use crate::persistence::make_persistent::{make_persistent, DefaultPersistentComponent};
+
+/// Players can edit signs from a little further than they can reach to account for latency
+const MAX_SIGN_EDIT_DISTANCE: f32 = 12.0;
+
+impl DefaultPersistentComponent for SignText {}
+
+fn handle_sign_interaction(
+    mut evr_block_interact: EventReader<BlockInteractEvent>,
+    mut nevw_open_sign_editor: NettyEventWriter<OpenSignEditorEvent>,
+    q_player: Query<&Player>,
+    q_structure: Query<&Structure>,
+    blocks: Res<Registry<Block>>,
+) {
+    for ev in evr_block_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok(structure) = q_structure.get(s_block.structure()) else {
+            continue;
+        };
+
+        if structure.block_at(s_block.coords(), &blocks).unlocalized_name() != SIGN_BLOCK {
+            continue;
+        }
+
+        let Ok(player) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        nevw_open_sign_editor.send(OpenSignEditorEvent(s_block), player.id());
+    }
+}
+
+fn on_set_sign_text(
+    mut nevr_set_sign_text: EventReader<NettyEventReceived<SetSignTextEvent>>,
+    lobby: Res<ServerLobby>,
+    q_player: Query<&GlobalTransform, With<Player>>,
+    mut q_structure: Query<(&mut Structure, &GlobalTransform)>,
+    blocks: Res<Registry<Block>>,
+    mut q_block_data: Query<&mut BlockData>,
+    q_has_sign_text: Query<(), With<SignText>>,
+    mut bs_params: BlockDataSystemParams,
+) {
+    for ev in nevr_set_sign_text.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok(player_g_trans) = q_player.get(player_ent) else {
+            continue;
+        };
+
+        let Ok((mut structure, structure_g_trans)) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        let coords = ev.block.coords();
+
+        if !structure.is_within_blocks(coords) || structure.block_at(coords, &blocks).unlocalized_name() != SIGN_BLOCK {
+            warn!("Player {player_ent:?} tried to edit a sign where there is none.");
+            continue;
+        }
+
+        let block_position = structure_g_trans.transform_point(structure.block_relative_position(coords));
+        if block_position.distance_squared(player_g_trans.translation()) > MAX_SIGN_EDIT_DISTANCE * MAX_SIGN_EDIT_DISTANCE {
+            warn!("Player {player_ent:?} tried to edit a sign that is too far away.");
+            continue;
+        }
+
+        let sign_text = SignText::new(ev.text.as_str());
+
+        if sign_text.is_empty() {
+            structure.remove_block_data(coords, &mut bs_params, &mut q_block_data, &q_has_sign_text);
+        } else {
+            structure.insert_block_data(coords, sign_text, &mut bs_params, &mut q_block_data, &q_has_sign_text);
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<SignText>(app);
+
+    app.add_systems(
+        Update,
+        (handle_sign_interaction, on_set_sign_text)
+            .in_set(NetworkingSystemsSet::Between)
+            .in_set(BlockEventsSet::ProcessEvents)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
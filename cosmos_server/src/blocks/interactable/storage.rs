@@ -1,6 +1,6 @@
 use bevy::{
     ecs::system::ResMut,
-    prelude::{in_state, App, EventReader, IntoSystemConfigs, Query, Res, Update},
+    prelude::{in_state, App, EventReader, IntoSystemConfigs, Query, Res, Update, With},
 };
 use bevy_renet2::renet2::RenetServer;
 use cosmos_core::{
@@ -9,27 +9,46 @@ use cosmos_core::{
         data::BlockDataIdentifier,
         Block,
     },
+    chat::ServerSendChatMessageEvent,
     entities::player::Player,
-    inventory::netty::{InventoryIdentifier, ServerInventoryMessages},
-    netty::{cosmos_encoder, system_sets::NetworkingSystemsSet, NettyChannelServer},
+    inventory::{
+        held_item_slot::HeldItemSlot,
+        netty::{InventoryIdentifier, ServerInventoryMessages},
+        Inventory,
+    },
+    item::Item,
+    netty::{
+        network_stats::{NettyMessage, NettyServerMessages},
+        sync::events::server_event::NettyEventWriter,
+        system_sets::NetworkingSystemsSet,
+        NettyChannelServer,
+    },
     registry::{identifiable::Identifiable, Registry},
     state::GameState,
     structure::Structure,
 };
 
+use crate::structure::ownership::{notify_no_permission, StructurePermissions};
+
+use super::storage_lock::{held_item, is_lock_item, StorageLock};
+
 fn handle_block_event(
     mut interact_events: EventReader<BlockInteractEvent>,
     s_query: Query<&Structure>,
     blocks: Res<Registry<Block>>,
-    q_player: Query<&Player>,
+    q_player: Query<(&Player, &Inventory, &HeldItemSlot)>,
+    q_lock: Query<(), With<StorageLock>>,
+    items: Res<Registry<Item>>,
     mut server: ResMut<RenetServer>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
 ) {
     for ev in interact_events.read() {
         let Some(s_block) = ev.block else {
             continue;
         };
 
-        let Ok(player) = q_player.get(ev.interactor) else {
+        let Ok((player, inventory, held_slot)) = q_player.get(ev.interactor) else {
             continue;
         };
 
@@ -44,10 +63,22 @@ fn handle_block_event(
         let block_id = s_block.block_id(structure);
 
         if block_id == block.id() {
-            server.send_message(
+            // Locked storage & locking/unlocking storage is handled in `storage_lock.rs`
+            if structure.query_block_data(s_block.coords(), &q_lock).is_some()
+                || (ev.alternate && held_item(inventory, held_slot, &items).is_some_and(is_lock_item))
+            {
+                continue;
+            }
+
+            if !permissions.can_use(ev.interactor, s_block.structure()) {
+                notify_no_permission(&mut nevw_chat, player.id());
+                continue;
+            }
+
+            server.send_netty_message(
                 player.id(),
                 NettyChannelServer::Inventory,
-                cosmos_encoder::serialize(&ServerInventoryMessages::OpenInventory {
+                NettyMessage::new(&ServerInventoryMessages::OpenInventory {
                     owner: InventoryIdentifier::BlockData(BlockDataIdentifier { block: s_block, block_id }),
                 }),
             );
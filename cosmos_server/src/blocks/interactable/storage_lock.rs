@@ -0,0 +1,704 @@
+//! Server-side handling of locked storage.
+//!
+//! - Crouch + interact with storage while holding a keycard to lock it with that keycard
+//! - Crouch + interact with storage while holding a code lock to lock it with a code
+//! - Crouch + interact with locked storage while holding its key/a code lock to take the lock off
+//! - Crouch + interact with locked storage while holding a lockpick to try and break in
+//!
+//! Every keycard has its own [`KeycardId`], and a keycard lock only opens for the keycard it was locked with.
+//! Entering too many wrong codes locks the player out of entering codes for a while.
+//!
+//! What happens when a lock is picked or locked storage is broken is configured in `./config/cosmos/locks.json`.
+
+use std::{fs, time::Duration};
+
+use bevy::{ecs::system::SystemParam, prelude::*, time::common_conditions::on_timer};
+use bevy_renet2::renet2::{ClientId, RenetServer};
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        data::{BlockData, BlockDataIdentifier},
+        specific_blocks::storage_lock::{
+            is_valid_lock_code, EnterLockCodeEvent, KeycardId, LockCodePurpose, OpenLockCodePromptEvent, CODE_LOCK_ITEM, KEYCARD_ITEMS,
+            LOCKPICK_ITEM,
+        },
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    events::block_events::BlockDataSystemParams,
+    inventory::{
+        held_item_slot::HeldItemSlot,
+        itemstack::{ItemShouldHaveData, ItemStackData, ItemStackNeedsDataCreated, ItemStackSystemSet},
+        netty::{InventoryIdentifier, ServerInventoryMessages},
+        Inventory,
+    },
+    item::Item,
+    netty::{
+        network_stats::{NettyMessage, NettyServerMessages},
+        server::ServerLobby,
+        sync::{
+            events::server_event::{NettyEventReceived, NettyEventWriter},
+            IdentifiableComponent,
+        },
+        system_sets::NetworkingSystemsSet,
+        NettyChannelServer,
+    },
+    prelude::{Structure, StructureBlock},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::ownership::StructureOwnership,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entities::player::admin::Admin,
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+    structure::ownership::{notify_no_permission, StructurePermissions},
+};
+
+/// The block that can be locked
+pub const LOCKABLE_STORAGE_BLOCK: &str = "cosmos:storage";
+
+/// Players can enter codes from a little further than they can reach to account for latency
+const MAX_LOCK_CODE_DISTANCE: f32 = 12.0;
+
+const LOCK_SETTINGS_PATH: &str = "./config/cosmos/locks.json";
+
+/// The most locked storage a player is remembered to have opened. Past this, the oldest are forgotten.
+const MAX_UNLOCKED_STORAGE: usize = 64;
+
+/// How many wrong codes a player can enter before they're locked out of entering codes
+const MAX_WRONG_CODES: u32 = 3;
+/// How long (in seconds) a player is locked out of entering codes for after entering too many wrong ones
+const WRONG_CODE_LOCKOUT_SECS: f64 = 30.0;
+
+#[derive(Component, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A lock on a storage block. This is stored as block data & is never sent to clients.
+pub enum StorageLock {
+    /// Can be opened by anyone carrying the keycard with this id
+    Keycard {
+        /// The keycard item (its unlocalized name), used to tell players which color of keycard they need
+        item: String,
+        /// The id of the specific keycard that opens this
+        key: KeycardId,
+    },
+    /// Can be opened by anyone who knows this code
+    Code(String),
+}
+
+impl IdentifiableComponent for StorageLock {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:storage_lock"
+    }
+}
+
+impl DefaultPersistentComponent for StorageLock {}
+impl DefaultPersistentComponent for KeycardId {}
+
+#[derive(Component, Debug, Default)]
+/// Locked storage this player has opened (with its key, code, or a lockpick), oldest first.
+///
+/// The lock is stored alongside the block so that replacing a lock requires the player to unlock it again.
+/// Only the last [`MAX_UNLOCKED_STORAGE`] are remembered.
+pub struct UnlockedStorage(Vec<(StructureBlock, StorageLock)>);
+
+impl UnlockedStorage {
+    fn contains(&self, block: StructureBlock, lock: &StorageLock) -> bool {
+        self.0.iter().any(|(b, l)| *b == block && l == lock)
+    }
+
+    fn insert(&mut self, block: StructureBlock, lock: StorageLock) {
+        self.0.retain(|(b, _)| *b != block);
+        self.0.push((block, lock));
+
+        if self.0.len() > MAX_UNLOCKED_STORAGE {
+            let n_forgotten = self.0.len() - MAX_UNLOCKED_STORAGE;
+            self.0.drain(..n_forgotten);
+        }
+    }
+}
+
+#[derive(Component, Debug, Default)]
+/// How many wrong lock codes this player has entered in a row, and when they can enter codes again if they're locked out
+struct WrongLockCodes {
+    count: u32,
+    locked_out_until: f64,
+}
+
+impl WrongLockCodes {
+    /// How many seconds are left until this player can enter codes again, if they're locked out
+    fn lockout_remaining(&self, now: f64) -> Option<f64> {
+        (self.locked_out_until > now).then_some(self.locked_out_until - now)
+    }
+
+    /// Records a wrong code, locking the player out once they've entered too many
+    fn record_wrong_code(&mut self, now: f64) {
+        self.count += 1;
+
+        if self.count >= MAX_WRONG_CODES {
+            self.count = 0;
+            self.locked_out_until = now + WRONG_CODE_LOCKOUT_SECS;
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+/// Server configuration for what happens when locks are picked or locked storage is broken
+pub struct LockSettings {
+    /// The chance (0.0 to 1.0) that a lockpick will open a lock
+    pub lockpick_chance: f32,
+    /// If a successfully picked lock is destroyed, leaving the storage unlocked for everyone
+    pub lockpick_destroys_lock: bool,
+    /// If a lockpick is used up when it fails to open a lock
+    pub consume_lockpick_on_failure: bool,
+    /// If the owner of the structure is told when someone fails to pick one of their locks
+    pub alert_owner_on_failed_lockpick: bool,
+    /// If the contents of locked storage are destroyed instead of dropped when the storage is broken
+    pub destroy_contents_on_break: bool,
+}
+
+impl Default for LockSettings {
+    fn default() -> Self {
+        Self {
+            lockpick_chance: 0.25,
+            lockpick_destroys_lock: false,
+            consume_lockpick_on_failure: true,
+            alert_owner_on_failed_lockpick: true,
+            destroy_contents_on_break: true,
+        }
+    }
+}
+
+#[derive(SystemParam)]
+/// Checks if players are allowed to open locked storage
+pub struct StorageLocks<'w, 's> {
+    q_structure: Query<'w, 's, &'static Structure>,
+    q_lock: Query<'w, 's, &'static StorageLock>,
+    q_players: Query<'w, 's, (Option<&'static UnlockedStorage>, Has<Admin>)>,
+}
+
+impl StorageLocks<'_, '_> {
+    /// Returns true if this entity can open the storage at this block.
+    ///
+    /// Storage without a lock can be opened by anyone. Locked storage can only be opened by
+    /// admins and players that have already unlocked it.
+    pub fn can_open(&self, entity: Entity, block: StructureBlock) -> bool {
+        let Ok(structure) = self.q_structure.get(block.structure()) else {
+            return true;
+        };
+
+        let Some(lock) = structure.query_block_data(block.coords(), &self.q_lock) else {
+            return true;
+        };
+
+        let Ok((unlocked, admin)) = self.q_players.get(entity) else {
+            return true;
+        };
+
+        admin || unlocked.is_some_and(|u| u.contains(block, lock))
+    }
+}
+
+/// Returns the item the player is holding, if any
+pub(super) fn held_item<'a>(inventory: &Inventory, held_slot: &HeldItemSlot, items: &'a Registry<Item>) -> Option<&'a Item> {
+    inventory
+        .itemstack_at(held_slot.slot() as usize)
+        .map(|is| items.from_numeric_id(is.item_id()))
+}
+
+/// Returns true if this item is used to lock, unlock, or pick storage (instead of just opening it)
+pub(super) fn is_lock_item(item: &Item) -> bool {
+    let name = item.unlocalized_name();
+    KEYCARD_ITEMS.contains(&name) || name == CODE_LOCK_ITEM || name == LOCKPICK_ITEM
+}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+fn open_storage(server: &mut RenetServer, client_id: ClientId, block: StructureBlock, block_id: u16) {
+    server.send_netty_message(
+        client_id,
+        NettyChannelServer::Inventory,
+        NettyMessage::new(&ServerInventoryMessages::OpenInventory {
+            owner: InventoryIdentifier::BlockData(BlockDataIdentifier { block, block_id }),
+        }),
+    );
+}
+
+fn mark_unlocked(
+    commands: &mut Commands,
+    player_ent: Entity,
+    unlocked: Option<Mut<UnlockedStorage>>,
+    block: StructureBlock,
+    lock: StorageLock,
+) {
+    if let Some(mut unlocked) = unlocked {
+        unlocked.insert(block, lock);
+    } else {
+        commands.entity(player_ent).insert(UnlockedStorage(vec![(block, lock)]));
+    }
+}
+
+fn record_wrong_code(commands: &mut Commands, player_ent: Entity, wrong_codes: Option<Mut<WrongLockCodes>>, now: f64) {
+    if let Some(mut wrong_codes) = wrong_codes {
+        wrong_codes.record_wrong_code(now);
+    } else {
+        let mut wrong_codes = WrongLockCodes::default();
+        wrong_codes.record_wrong_code(now);
+        commands.entity(player_ent).insert(wrong_codes);
+    }
+}
+
+/// Returns true if any keycard in this inventory has this id
+fn has_keycard(inventory: &Inventory, key: KeycardId, q_keycard_id: &Query<&KeycardId>) -> bool {
+    inventory
+        .iter()
+        .flatten()
+        .flat_map(|is| is.data_entity())
+        .any(|data_ent| q_keycard_id.get(data_ent).is_ok_and(|id| *id == key))
+}
+
+fn register_keycard_items(items: Res<Registry<Item>>, mut needs_data: ResMut<ItemShouldHaveData>) {
+    for keycard in KEYCARD_ITEMS {
+        if let Some(keycard) = items.from_id(keycard) {
+            needs_data.add_item(keycard);
+        }
+    }
+}
+
+fn add_keycard_ids(
+    q_needs_data: Query<(Entity, &ItemStackData), (Without<KeycardId>, With<ItemStackNeedsDataCreated>)>,
+    mut commands: Commands,
+    items: Res<Registry<Item>>,
+) {
+    for (ent, is_data) in q_needs_data.iter() {
+        if !KEYCARD_ITEMS.contains(&items.from_numeric_id(is_data.item_id).unlocalized_name()) {
+            continue;
+        }
+
+        commands.entity(ent).insert(KeycardId::new(rand::random()));
+    }
+}
+
+/// Forgets unlocked storage on structures that no longer exist
+fn forget_missing_unlocked_storage(mut q_unlocked: Query<&mut UnlockedStorage>, q_structure: Query<(), With<Structure>>) {
+    for mut unlocked in q_unlocked.iter_mut() {
+        if unlocked.0.iter().any(|(b, _)| !q_structure.contains(b.structure())) {
+            unlocked.0.retain(|(b, _)| q_structure.contains(b.structure()));
+        }
+    }
+}
+
+fn keycard_color(keycard: &str) -> &str {
+    keycard.strip_prefix("cosmos:keycard_").unwrap_or(keycard)
+}
+
+fn handle_storage_lock_interaction(
+    mut evr_block_interact: EventReader<BlockInteractEvent>,
+    mut q_structure: Query<&mut Structure>,
+    mut q_player: Query<(&Player, &mut Inventory, &HeldItemSlot, Has<Admin>, Option<&mut UnlockedStorage>)>,
+    q_players: Query<&Player>,
+    q_ownership: Query<&StructureOwnership>,
+    q_lock: Query<&StorageLock>,
+    q_has_lock: Query<(), With<StorageLock>>,
+    q_keycard_id: Query<&KeycardId>,
+    mut q_block_data: Query<&mut BlockData>,
+    mut bs_params: BlockDataSystemParams,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    settings: Res<LockSettings>,
+    permissions: StructurePermissions,
+    mut server: ResMut<RenetServer>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut nevw_open_prompt: NettyEventWriter<OpenLockCodePromptEvent>,
+) {
+    for ev in evr_block_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok(mut structure) = q_structure.get_mut(s_block.structure()) else {
+            continue;
+        };
+
+        if structure.block_at(s_block.coords(), &blocks).unlocalized_name() != LOCKABLE_STORAGE_BLOCK {
+            continue;
+        }
+
+        let Ok((player, mut inventory, held_slot, admin, unlocked)) = q_player.get_mut(ev.interactor) else {
+            continue;
+        };
+
+        let held = held_item(&inventory, held_slot, &items);
+        let lock = structure.query_block_data(s_block.coords(), &q_lock).cloned();
+
+        // Storage without a lock is opened like normal in `storage.rs`
+        let lock_action = ev.alternate && held.is_some_and(is_lock_item);
+        if lock.is_none() && !lock_action {
+            continue;
+        }
+
+        if !permissions.can_use(ev.interactor, s_block.structure()) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        let block_id = s_block.block_id(&structure);
+
+        if !lock_action {
+            let Some(lock) = lock else {
+                continue;
+            };
+
+            if admin || unlocked.as_ref().is_some_and(|u| u.contains(s_block, &lock)) {
+                open_storage(&mut server, player.id(), s_block, block_id);
+                continue;
+            }
+
+            match &lock {
+                StorageLock::Keycard { item, key } => {
+                    if has_keycard(&inventory, *key, &q_keycard_id) {
+                        mark_unlocked(&mut bs_params.commands, ev.interactor, unlocked, s_block, lock.clone());
+                        open_storage(&mut server, player.id(), s_block, block_id);
+                    } else {
+                        reply(
+                            &mut nevw_chat,
+                            player.id(),
+                            format!(
+                                "This storage is locked. It needs the {} keycard it was locked with.",
+                                keycard_color(item)
+                            ),
+                        );
+                    }
+                }
+                StorageLock::Code(_) => {
+                    nevw_open_prompt.send(
+                        OpenLockCodePromptEvent {
+                            block: s_block,
+                            purpose: LockCodePurpose::Unlock,
+                        },
+                        player.id(),
+                    );
+                }
+            }
+
+            continue;
+        }
+
+        let Some(held) = held else {
+            continue;
+        };
+        let held_name = held.unlocalized_name();
+        let held_keycard_id = inventory.query_itemstack_data(held_slot.slot() as usize, &q_keycard_id).copied();
+
+        match lock {
+            None if KEYCARD_ITEMS.contains(&held_name) => {
+                // Keycards made before they had ids are given one the first time they're used
+                let key = held_keycard_id.unwrap_or_else(|| {
+                    let key = KeycardId::new(rand::random());
+                    inventory.insert_itemstack_data(held_slot.slot() as usize, key, &mut bs_params.commands);
+                    key
+                });
+
+                structure.insert_block_data(
+                    s_block.coords(),
+                    StorageLock::Keycard {
+                        item: held_name.to_owned(),
+                        key,
+                    },
+                    &mut bs_params,
+                    &mut q_block_data,
+                    &q_has_lock,
+                );
+                reply(
+                    &mut nevw_chat,
+                    player.id(),
+                    format!("Locked this storage with the {} keycard.", keycard_color(held_name)),
+                );
+            }
+            None if held_name == CODE_LOCK_ITEM => {
+                nevw_open_prompt.send(
+                    OpenLockCodePromptEvent {
+                        block: s_block,
+                        purpose: LockCodePurpose::SetCode,
+                    },
+                    player.id(),
+                );
+            }
+            None => {
+                reply(&mut nevw_chat, player.id(), "This storage isn't locked.");
+            }
+            Some(StorageLock::Keycard { key, .. }) if held_keycard_id == Some(key) || (admin && KEYCARD_ITEMS.contains(&held_name)) => {
+                structure.remove_block_data(s_block.coords(), &mut bs_params, &mut q_block_data, &q_has_lock);
+                reply(&mut nevw_chat, player.id(), "Removed the lock from this storage.");
+            }
+            Some(StorageLock::Code(_)) if held_name == CODE_LOCK_ITEM => {
+                nevw_open_prompt.send(
+                    OpenLockCodePromptEvent {
+                        block: s_block,
+                        purpose: LockCodePurpose::RemoveLock,
+                    },
+                    player.id(),
+                );
+            }
+            Some(lock) if held_name == LOCKPICK_ITEM => {
+                if unlocked.as_ref().is_some_and(|u| u.contains(s_block, &lock)) {
+                    open_storage(&mut server, player.id(), s_block, block_id);
+                    continue;
+                }
+
+                if rand::random::<f32>() < settings.lockpick_chance {
+                    if settings.lockpick_destroys_lock {
+                        structure.remove_block_data(s_block.coords(), &mut bs_params, &mut q_block_data, &q_has_lock);
+                        reply(&mut nevw_chat, player.id(), "You picked the lock, breaking it.");
+                    } else {
+                        mark_unlocked(&mut bs_params.commands, ev.interactor, unlocked, s_block, lock);
+                        reply(&mut nevw_chat, player.id(), "You picked the lock.");
+                    }
+
+                    open_storage(&mut server, player.id(), s_block, block_id);
+                    continue;
+                }
+
+                if settings.consume_lockpick_on_failure {
+                    inventory.decrease_quantity_at(held_slot.slot() as usize, 1, &mut bs_params.commands);
+                    reply(&mut nevw_chat, player.id(), "Your lockpick broke.");
+                } else {
+                    reply(&mut nevw_chat, player.id(), "You failed to pick the lock.");
+                }
+
+                if settings.alert_owner_on_failed_lockpick {
+                    let owner = q_ownership
+                        .get(s_block.structure())
+                        .ok()
+                        .and_then(|ownership| q_players.iter().find(|p| ownership.is_owner(p.name())));
+
+                    if let Some(owner) = owner.filter(|owner| owner.id() != player.id()) {
+                        reply(
+                            &mut nevw_chat,
+                            owner.id(),
+                            format!("{} tried to pick one of your locks.", player.name()),
+                        );
+                    }
+                }
+            }
+            Some(_) => {
+                reply(&mut nevw_chat, player.id(), "That can't be used on this lock.");
+            }
+        }
+    }
+}
+
+fn on_enter_lock_code(
+    mut nevr_enter_lock_code: EventReader<NettyEventReceived<EnterLockCodeEvent>>,
+    lobby: Res<ServerLobby>,
+    mut q_player: Query<
+        (
+            &GlobalTransform,
+            &mut Inventory,
+            Has<Admin>,
+            Option<&mut UnlockedStorage>,
+            Option<&mut WrongLockCodes>,
+        ),
+        With<Player>,
+    >,
+    mut q_structure: Query<(&mut Structure, &GlobalTransform)>,
+    q_lock: Query<&StorageLock>,
+    q_has_lock: Query<(), With<StorageLock>>,
+    mut q_block_data: Query<&mut BlockData>,
+    mut bs_params: BlockDataSystemParams,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    needs_data: Res<ItemShouldHaveData>,
+    permissions: StructurePermissions,
+    time: Res<Time>,
+    mut server: ResMut<RenetServer>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    let now = time.elapsed_secs_f64();
+
+    let Some(code_lock_item) = items.from_id(CODE_LOCK_ITEM) else {
+        return;
+    };
+
+    for ev in nevr_enter_lock_code.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok((player_g_trans, mut inventory, admin, unlocked, mut wrong_codes)) = q_player.get_mut(player_ent) else {
+            continue;
+        };
+
+        let Ok((mut structure, structure_g_trans)) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        let coords = ev.block.coords();
+
+        if !structure.is_within_blocks(coords) || structure.block_at(coords, &blocks).unlocalized_name() != LOCKABLE_STORAGE_BLOCK {
+            warn!("Player {player_ent:?} tried to enter a lock code where there is no storage.");
+            continue;
+        }
+
+        let block_position = structure_g_trans.transform_point(structure.block_relative_position(coords));
+        if block_position.distance_squared(player_g_trans.translation()) > MAX_LOCK_CODE_DISTANCE * MAX_LOCK_CODE_DISTANCE {
+            warn!("Player {player_ent:?} tried to enter a lock code for storage that is too far away.");
+            continue;
+        }
+
+        if !permissions.can_use(player_ent, ev.block.structure()) {
+            notify_no_permission(&mut nevw_chat, ev.client_id);
+            continue;
+        }
+
+        if ev.purpose != LockCodePurpose::SetCode {
+            if let Some(remaining) = wrong_codes.as_ref().and_then(|w| w.lockout_remaining(now)) {
+                reply(
+                    &mut nevw_chat,
+                    ev.client_id,
+                    format!("Too many incorrect codes. Try again in {} seconds.", remaining.ceil()),
+                );
+                continue;
+            }
+        }
+
+        let lock = structure.query_block_data(coords, &q_lock).cloned();
+
+        match (ev.purpose, lock) {
+            (LockCodePurpose::Unlock, Some(StorageLock::Code(code))) => {
+                if code != ev.code {
+                    record_wrong_code(&mut bs_params.commands, player_ent, wrong_codes, now);
+                    reply(&mut nevw_chat, ev.client_id, "Incorrect code.");
+                    continue;
+                }
+
+                if let Some(wrong_codes) = wrong_codes.as_mut() {
+                    wrong_codes.count = 0;
+                }
+
+                let block_id = ev.block.block_id(&structure);
+                mark_unlocked(&mut bs_params.commands, player_ent, unlocked, ev.block, StorageLock::Code(code));
+                open_storage(&mut server, ev.client_id, ev.block, block_id);
+            }
+            (LockCodePurpose::SetCode, None) => {
+                if !is_valid_lock_code(&ev.code) {
+                    reply(&mut nevw_chat, ev.client_id, "Lock codes must be made up of 1 to 8 digits.");
+                    continue;
+                }
+
+                if !inventory.can_take_item(code_lock_item, 1) {
+                    continue;
+                }
+
+                inventory.take_and_remove_item(code_lock_item, 1, &mut bs_params.commands);
+                structure.insert_block_data(
+                    coords,
+                    StorageLock::Code(ev.code.clone()),
+                    &mut bs_params,
+                    &mut q_block_data,
+                    &q_has_lock,
+                );
+                reply(&mut nevw_chat, ev.client_id, "Locked this storage.");
+            }
+            (LockCodePurpose::RemoveLock, Some(StorageLock::Code(code))) => {
+                if !admin && code != ev.code {
+                    record_wrong_code(&mut bs_params.commands, player_ent, wrong_codes, now);
+                    reply(&mut nevw_chat, ev.client_id, "Incorrect code.");
+                    continue;
+                }
+
+                if let Some(wrong_codes) = wrong_codes.as_mut() {
+                    wrong_codes.count = 0;
+                }
+
+                if !inventory.can_insert(code_lock_item, 1) {
+                    reply(
+                        &mut nevw_chat,
+                        ev.client_id,
+                        "You don't have room in your inventory for the code lock.",
+                    );
+                    continue;
+                }
+
+                inventory.insert_item(code_lock_item, 1, &mut bs_params.commands, &needs_data);
+                structure.remove_block_data(coords, &mut bs_params, &mut q_block_data, &q_has_lock);
+                reply(&mut nevw_chat, ev.client_id, "Removed the lock from this storage.");
+            }
+            _ => {
+                // The lock was changed since the prompt was opened
+                continue;
+            }
+        }
+    }
+}
+
+fn load_lock_settings(mut commands: Commands) {
+    let settings = match fs::read_to_string(LOCK_SETTINGS_PATH) {
+        Ok(json) => serde_json::from_str::<LockSettings>(&json).unwrap_or_else(|e| {
+            error!("Invalid lock settings in {LOCK_SETTINGS_PATH} - using defaults.\n{e:?}");
+            LockSettings::default()
+        }),
+        Err(_) => {
+            let settings = LockSettings::default();
+
+            let json = serde_json::to_string_pretty(&settings).expect("Lock settings are always valid json");
+            if let Err(e) = fs::create_dir_all("./config/cosmos").and_then(|_| fs::write(LOCK_SETTINGS_PATH, json)) {
+                error!("Unable to write default lock settings to {LOCK_SETTINGS_PATH}.\n{e:?}");
+            }
+
+            settings
+        }
+    };
+
+    commands.insert_resource(settings);
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<StorageLock>(app);
+    make_persistent::<KeycardId>(app);
+
+    app.init_resource::<LockSettings>()
+        .add_systems(OnEnter(GameState::PostLoading), (load_lock_settings, register_keycard_items))
+        .add_systems(
+            Update,
+            (
+                add_keycard_ids.in_set(ItemStackSystemSet::FillDataEntity),
+                (handle_storage_lock_interaction, on_enter_lock_code)
+                    .chain()
+                    .in_set(NetworkingSystemsSet::Between)
+                    .in_set(BlockEventsSet::ProcessEvents),
+                forget_missing_unlocked_storage.run_if(on_timer(Duration::from_secs(10))),
+            )
+                .run_if(in_state(GameState::Playing)),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrong_codes_lock_out_entry() {
+        let mut wrong_codes = WrongLockCodes::default();
+
+        for _ in 0..MAX_WRONG_CODES - 1 {
+            wrong_codes.record_wrong_code(0.0);
+        }
+        assert_eq!(wrong_codes.lockout_remaining(0.0), None);
+
+        wrong_codes.record_wrong_code(0.0);
+        assert_eq!(wrong_codes.lockout_remaining(1.0), Some(WRONG_CODE_LOCKOUT_SECS - 1.0));
+        assert_eq!(wrong_codes.lockout_remaining(WRONG_CODE_LOCKOUT_SECS), None);
+    }
+}
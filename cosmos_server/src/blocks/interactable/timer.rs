@@ -0,0 +1,196 @@
+//! Timers pulse on a set interval. Interacting with a timer opens its settings.
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        data::BlockData,
+        specific_blocks::timer::{OpenTimerSettingsEvent, SetTimerSettingsEvent, TimerSettings, TIMER_BLOCK},
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    events::block_events::{BlockChangedEvent, BlockDataChangedEvent, BlockDataSystemParams},
+    logic::{BlockLogicData, LogicSystemSet, LogicTicks},
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::Structure,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+};
+
+use crate::{
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+    structure::ownership::{notify_no_permission, StructurePermissions},
+};
+
+/// Players can change timers from a little further than they can reach to account for latency
+const MAX_TIMER_EDIT_DISTANCE: f32 = 12.0;
+
+impl DefaultPersistentComponent for TimerSettings {}
+
+fn on_place_timer(
+    mut evr_changed_block: EventReader<BlockChangedEvent>,
+    mut q_structure: Query<&mut Structure>,
+    q_has_data: Query<(), With<TimerSettings>>,
+    mut q_block_data: Query<&mut BlockData>,
+    mut bs_params: BlockDataSystemParams,
+    blocks: Res<Registry<Block>>,
+) {
+    let Some(timer) = blocks.from_id(TIMER_BLOCK) else {
+        return;
+    };
+
+    for ev in evr_changed_block.read() {
+        if ev.new_block != timer.id() {
+            continue;
+        }
+
+        let Ok(mut structure) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        structure.insert_block_data(
+            ev.block.coords(),
+            TimerSettings::default(),
+            &mut bs_params,
+            &mut q_block_data,
+            &q_has_data,
+        );
+    }
+}
+
+fn on_interact_timer(
+    mut evr_block_interact: EventReader<BlockInteractEvent>,
+    q_structure: Query<&Structure>,
+    q_player: Query<&Player>,
+    blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    mut nevw_open_settings: NettyEventWriter<OpenTimerSettingsEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for ev in evr_block_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok(structure) = q_structure.get(s_block.structure()) else {
+            continue;
+        };
+
+        if structure.block_at(s_block.coords(), &blocks).unlocalized_name() != TIMER_BLOCK {
+            continue;
+        }
+
+        let Ok(player) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        if !permissions.can_use(ev.interactor, s_block.structure()) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        nevw_open_settings.send(OpenTimerSettingsEvent(s_block), player.id());
+    }
+}
+
+fn on_set_timer_settings(
+    mut nevr_set_settings: EventReader<NettyEventReceived<SetTimerSettingsEvent>>,
+    lobby: Res<ServerLobby>,
+    q_player: Query<&GlobalTransform, With<Player>>,
+    mut q_structure: Query<(&mut Structure, &GlobalTransform)>,
+    blocks: Res<Registry<Block>>,
+    mut q_block_data: Query<&mut BlockData>,
+    q_has_settings: Query<(), With<TimerSettings>>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut bs_params: BlockDataSystemParams,
+) {
+    for ev in nevr_set_settings.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok(player_g_trans) = q_player.get(player_ent) else {
+            continue;
+        };
+
+        let Ok((mut structure, structure_g_trans)) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        let coords = ev.block.coords();
+
+        if !structure.is_within_blocks(coords) || structure.block_at(coords, &blocks).unlocalized_name() != TIMER_BLOCK {
+            warn!("Player {player_ent:?} tried to change a timer where there is none.");
+            continue;
+        }
+
+        let block_position = structure_g_trans.transform_point(structure.block_relative_position(coords));
+        if block_position.distance_squared(player_g_trans.translation()) > MAX_TIMER_EDIT_DISTANCE * MAX_TIMER_EDIT_DISTANCE {
+            warn!("Player {player_ent:?} tried to change a timer that is too far away.");
+            continue;
+        }
+
+        if !permissions.can_use(player_ent, ev.block.structure()) {
+            notify_no_permission(&mut nevw_chat, ev.client_id);
+            continue;
+        }
+
+        if !ev.settings.is_valid() {
+            warn!("Player {player_ent:?} sent invalid timer settings - {:?}", ev.settings);
+            continue;
+        }
+
+        structure.insert_block_data(coords, ev.settings, &mut bs_params, &mut q_block_data, &q_has_settings);
+    }
+}
+
+/// Turns every timer on or off based on how many logic ticks have happened
+fn update_timers(
+    logic_ticks: Res<LogicTicks>,
+    mut q_timers: Query<(Entity, &BlockData, &TimerSettings, &mut BlockLogicData)>,
+    mut evw_block_data_changed: EventWriter<BlockDataChangedEvent>,
+) {
+    for (ent, block_data, settings, mut logic_data) in q_timers.iter_mut() {
+        let on = settings.on_at(logic_ticks.count());
+
+        if logic_data.on() == on {
+            continue;
+        }
+
+        *logic_data = BlockLogicData(on as i32);
+
+        evw_block_data_changed.send(BlockDataChangedEvent {
+            block_data_entity: Some(ent),
+            block: block_data.identifier.block,
+        });
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<TimerSettings>(app);
+
+    app.add_systems(
+        Update,
+        (
+            on_place_timer.in_set(BlockEventsSet::SendEventsForThisFrame),
+            (on_interact_timer, on_set_timer_settings)
+                .chain()
+                .in_set(BlockEventsSet::ProcessEvents),
+        )
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    )
+    .add_systems(
+        Update,
+        update_timers
+            .in_set(LogicSystemSet::Consume)
+            .ambiguous_with(LogicSystemSet::Consume),
+    );
+}
@@ -3,15 +3,19 @@
 use bevy::prelude::App;
 
 mod block_events;
+mod block_tick;
 mod data;
 pub mod interactable;
 pub mod multiblock;
+mod paint;
 mod updates;
 
 pub(super) fn register(app: &mut App) {
     interactable::register(app);
     block_events::register(app);
+    block_tick::register(app);
     multiblock::register(app);
     updates::register(app);
     data::register(app);
+    paint::register(app);
 }
@@ -0,0 +1,151 @@
+//! Forms and deforms the multiblock machines described by [`MultiblockPattern`]s as their blocks change
+
+use bevy::prelude::{in_state, App, Entity, EventReader, EventWriter, IntoSystemConfigs, Query, Res, Update};
+use cosmos_core::{
+    block::{
+        block_events::BlockEventsSet,
+        multiblock::machine::{FormedMultiblock, MultiblockDeformedEvent, MultiblockFormedEvent, MultiblockPattern, Multiblocks},
+    },
+    events::block_events::BlockChangedEvent,
+    netty::system_sets::NetworkingSystemsSet,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        coordinates::{BlockCoordinate, UnboundBlockCoordinate},
+        events::StructureLoadedEvent,
+        Structure,
+    },
+};
+
+/// Tries to form every pattern controlled by the block at these coordinates, if it isn't already formed
+fn try_form_multiblocks(
+    structure_entity: Entity,
+    structure: &Structure,
+    controller_coords: BlockCoordinate,
+    multiblocks: &mut Multiblocks,
+    patterns: &Registry<MultiblockPattern>,
+    evw_formed: &mut EventWriter<MultiblockFormedEvent>,
+) {
+    if multiblocks.controlled_by(controller_coords).is_some() {
+        return;
+    }
+
+    let controller_id = structure.block_id_at(controller_coords);
+
+    for pattern in patterns.iter().filter(|p| p.controller() == controller_id) {
+        let Ok(members) = pattern.check(structure, controller_coords) else {
+            continue;
+        };
+
+        multiblocks.push(FormedMultiblock::new(pattern, members));
+
+        evw_formed.send(MultiblockFormedEvent {
+            structure: structure_entity,
+            controller: controller_coords,
+            pattern: pattern.id(),
+        });
+
+        return;
+    }
+}
+
+fn on_change_multiblock_blocks(
+    mut q_structure: Query<(&Structure, &mut Multiblocks)>,
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    patterns: Res<Registry<MultiblockPattern>>,
+    mut evw_formed: EventWriter<MultiblockFormedEvent>,
+    mut evw_deformed: EventWriter<MultiblockDeformedEvent>,
+) {
+    for ev in evr_block_changed.read() {
+        let Ok((structure, mut multiblocks)) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        let coords = ev.block.coords();
+
+        multiblocks.retain(|multiblock| {
+            if !multiblock.contains(coords) {
+                return true;
+            }
+
+            evw_deformed.send(MultiblockDeformedEvent {
+                structure: ev.block.structure(),
+                controller: multiblock.controller(),
+                pattern: multiblock.pattern_id(),
+            });
+
+            false
+        });
+
+        // Only blocks that are part of a pattern can complete one
+        let relevant_patterns = patterns.iter().filter(|p| p.uses_block(ev.new_block)).collect::<Vec<_>>();
+        if relevant_patterns.is_empty() {
+            continue;
+        }
+
+        let max_extent = relevant_patterns.iter().map(|p| p.max_extent()).max().unwrap_or(0);
+        let ub_coords = UnboundBlockCoordinate::from(coords);
+
+        for dz in -max_extent..=max_extent {
+            for dy in -max_extent..=max_extent {
+                for dx in -max_extent..=max_extent {
+                    let Ok(check_coords) = BlockCoordinate::try_from(ub_coords + UnboundBlockCoordinate::new(dx, dy, dz)) else {
+                        continue;
+                    };
+
+                    if !structure.is_within_blocks(check_coords) {
+                        continue;
+                    }
+
+                    let block_id = structure.block_id_at(check_coords);
+                    if !relevant_patterns.iter().any(|p| p.controller() == block_id) {
+                        continue;
+                    }
+
+                    try_form_multiblocks(
+                        ev.block.structure(),
+                        structure,
+                        check_coords,
+                        &mut multiblocks,
+                        &patterns,
+                        &mut evw_formed,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Multiblocks aren't saved, so they are re-formed whenever a structure is loaded
+fn form_multiblocks_on_load(
+    mut q_structure: Query<(&Structure, &mut Multiblocks)>,
+    mut evr_loaded: EventReader<StructureLoadedEvent>,
+    patterns: Res<Registry<MultiblockPattern>>,
+    mut evw_formed: EventWriter<MultiblockFormedEvent>,
+) {
+    for ev in evr_loaded.read() {
+        let Ok((structure, mut multiblocks)) = q_structure.get_mut(ev.structure_entity) else {
+            continue;
+        };
+
+        for coords in structure.all_blocks_iter(false) {
+            let block_id = structure.block_id_at(coords);
+
+            if patterns.iter().any(|p| p.controller() == block_id) {
+                try_form_multiblocks(ev.structure_entity, structure, coords, &mut multiblocks, &patterns, &mut evw_formed);
+            }
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            on_change_multiblock_blocks.in_set(BlockEventsSet::PostProcessEvents),
+            form_multiblocks_on_load,
+        )
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
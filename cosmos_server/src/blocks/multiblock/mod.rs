@@ -2,10 +2,16 @@
 
 use bevy::prelude::App;
 
+pub mod machine;
 pub mod reactor;
+pub mod reactor_fuel;
 pub mod reactor_persistence;
+pub mod warp_gate;
 
 pub(super) fn register(app: &mut App) {
+    machine::register(app);
     reactor::register(app);
+    reactor_fuel::register(app);
     reactor_persistence::register(app);
+    warp_gate::register(app);
 }
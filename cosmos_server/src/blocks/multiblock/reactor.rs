@@ -14,7 +14,12 @@ use cosmos_core::{
         Block,
     },
     entities::player::Player,
-    netty::{cosmos_encoder, server_reliable_messages::ServerReliableMessages, system_sets::NetworkingSystemsSet, NettyChannelServer},
+    netty::{
+        network_stats::{NettyMessage, NettyServerMessages},
+        server_reliable_messages::ServerReliableMessages,
+        system_sets::NetworkingSystemsSet,
+        NettyChannelServer,
+    },
     registry::{identifiable::Identifiable, Registry},
     state::GameState,
     structure::{
@@ -416,10 +421,10 @@ fn on_interact_reactor(
                         let Ok(player) = player_query.get(ev.interactor) else {
                             continue;
                         };
-                        server.send_message(
+                        server.send_netty_message(
                             player.id(),
                             NettyChannelServer::Reliable,
-                            cosmos_encoder::serialize(&ServerReliableMessages::InvalidReactor {
+                            NettyMessage::new(&ServerReliableMessages::InvalidReactor {
                                 reason: "The reactor is missing required casing.".into(),
                             }),
                         );
@@ -428,10 +433,10 @@ fn on_interact_reactor(
                         let Ok(player) = player_query.get(ev.interactor) else {
                             continue;
                         };
-                        server.send_message(
+                        server.send_netty_message(
                             player.id(),
                             NettyChannelServer::Reliable,
-                            cosmos_encoder::serialize(&ServerReliableMessages::InvalidReactor {
+                            NettyMessage::new(&ServerReliableMessages::InvalidReactor {
                                 reason: "The reactor can only have 1 controller.".into(),
                             }),
                         );
@@ -446,10 +451,10 @@ fn on_interact_reactor(
                 let Ok(player) = player_query.get(ev.interactor) else {
                     continue;
                 };
-                server.send_message(
+                server.send_netty_message(
                     player.id(),
                     NettyChannelServer::Reliable,
-                    cosmos_encoder::serialize(&ServerReliableMessages::InvalidReactor {
+                    NettyMessage::new(&ServerReliableMessages::InvalidReactor {
                         reason: "Invalid bounds for the reactor - maximum of 11x11x11.".into(),
                     }),
                 );
@@ -0,0 +1,139 @@
+//! Makes reactors burn fuel from their structure's storage when the server is started with `--reactor-fuel`.
+//!
+//! Otherwise, reactors keep generating power for free.
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::{
+        data::BlockData,
+        multiblock::{
+            reactor::Reactors,
+            reactor_fuel::{ReactorFuel, ReactorFuelItem},
+        },
+        Block,
+    },
+    inventory::Inventory,
+    item::Item,
+    netty::system_sets::NetworkingSystemsSet,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::systems::StructureSystemsSet,
+};
+
+use crate::settings::ServerSettings;
+
+const STORAGE_BLOCK: &str = "cosmos:storage";
+
+fn reactor_fuel_enabled(settings: Res<ServerSettings>) -> bool {
+    settings.reactor_fuel
+}
+
+fn add_reactor_fuel(mut commands: Commands, q_reactors: Query<(Entity, &Reactors), (Changed<Reactors>, Without<ReactorFuel>)>) {
+    for (ent, reactors) in q_reactors.iter() {
+        if !reactors.is_empty() {
+            commands.entity(ent).insert(ReactorFuel::default());
+        }
+    }
+}
+
+/// Takes one fuel item out of any of these storages, returning the energy it's worth
+fn take_fuel_item<'a>(
+    storages: impl Iterator<Item = Mut<'a, Inventory>>,
+    fuel_items: &Registry<ReactorFuelItem>,
+    items: &Registry<Item>,
+    commands: &mut Commands,
+) -> Option<f32> {
+    for mut inventory in storages {
+        for fuel in fuel_items.iter() {
+            let Some(item) = items.from_id(fuel.unlocalized_name()) else {
+                continue;
+            };
+
+            if inventory.quantity_of(item) == 0 {
+                continue;
+            }
+
+            let (remaining, _) = inventory.take_and_remove_item(item, 1, commands);
+            if remaining == 0 {
+                return Some(fuel.energy_per_item());
+            }
+        }
+    }
+
+    None
+}
+
+/// Fuel is burnt every frame, so it is only marked as changed (and synced) when the whole seconds of fuel left changes
+fn burn_reactor_fuel(
+    mut commands: Commands,
+    time: Res<Time>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    fuel_items: Res<Registry<ReactorFuelItem>>,
+    mut q_reactors: Query<(Entity, &Reactors, &mut ReactorFuel)>,
+    mut q_storage: Query<(&BlockData, &mut Inventory)>,
+) {
+    let Some(storage_block) = blocks.from_id(STORAGE_BLOCK) else {
+        return;
+    };
+
+    for (structure_ent, reactors, mut fuel) in q_reactors.iter_mut() {
+        let mut storages = q_storage
+            .iter_mut()
+            .filter(|(block_data, _)| {
+                block_data.identifier.block.structure() == structure_ent && block_data.identifier.block_id == storage_block.id()
+            })
+            .map(|(_, inventory)| inventory)
+            .collect::<Vec<_>>();
+
+        let burn_rate = reactors.iter().map(|reactor| reactor.power_per_second()).sum::<f32>();
+        let needed = burn_rate * time.delta_secs();
+
+        let old_fuel = *fuel;
+        let new_fuel = fuel.bypass_change_detection();
+
+        while new_fuel.burning() < needed {
+            let Some(energy) = take_fuel_item(storages.iter_mut().map(|x| x.reborrow()), &fuel_items, &items, &mut commands) else {
+                break;
+            };
+
+            new_fuel.add_burning(energy);
+        }
+
+        new_fuel.burn(needed);
+
+        let (stored_energy, stored_items) = storages
+            .iter()
+            .flat_map(|inventory| inventory.iter().flatten())
+            .filter_map(|is| {
+                fuel_items
+                    .for_item(items.from_numeric_id(is.item_id()))
+                    .map(|fuel| (fuel.energy_per_item() * is.quantity() as f32, is.quantity() as u32))
+            })
+            .fold((0.0, 0), |(energy, count), (e, c)| (energy + e, count + c));
+
+        new_fuel.set_supply(stored_energy, stored_items, burn_rate);
+
+        let whole_secs = |fuel: &ReactorFuel| fuel.seconds_remaining().map(|secs| secs as u32);
+
+        if whole_secs(&old_fuel) != whole_secs(&fuel)
+            || old_fuel.is_empty() != fuel.is_empty()
+            || old_fuel.stored_items() != fuel.stored_items()
+            || old_fuel.burn_rate() != fuel.burn_rate()
+        {
+            fuel.set_changed();
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (add_reactor_fuel, burn_reactor_fuel)
+            .chain()
+            .before(StructureSystemsSet::UpdateSystemsBlocks)
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing))
+            .run_if(reactor_fuel_enabled),
+    );
+}
@@ -0,0 +1,297 @@
+//! Pairs warp gates together, charges them while ships are nearby, and sends ships through them.
+//!
+//! Interacting with a finished gate remembers it as a destination. Interacting with another gate after that makes
+//! that gate send ships to the remembered one. Links only go one way, so gates have to be linked to each other
+//! for ships to be able to come back.
+
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{ReadMassProperties, Velocity};
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        data::BlockData,
+        multiblock::machine::{MultiblockPattern, Multiblocks},
+        specific_blocks::warp_gate::{
+            gate_center_offset, WarpGateChargeEvent, WarpGateDestination, WarpGateJumpEvent, WARP_GATE_CHARGE_RANGE, WARP_GATE_CHARGE_TIME,
+            WARP_GATE_CONTROLLER, WARP_GATE_COOLDOWN, WARP_GATE_ENERGY_PER_KG, WARP_GATE_OPENING_HALF_WIDTH, WARP_GATE_PATTERN,
+        },
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    events::block_events::BlockDataSystemParams,
+    netty::{sync::events::server_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    physics::location::{Location, SetPosition},
+    prelude::{Structure, StructureBlock},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        coordinates::BlockCoordinate,
+        ship::Ship,
+        station::Station,
+        systems::{energy_storage_system::EnergyStorageSystem, StructureSystems, StructureSystemsSet},
+    },
+};
+
+use crate::{
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+    structure::ownership::{notify_no_permission, StructurePermissions},
+};
+
+/// How much space is left between a ship and the destination gate when it arrives
+const EXIT_PADDING: f32 = 10.0;
+
+impl DefaultPersistentComponent for WarpGateDestination {}
+
+#[derive(Component, Debug, Clone, Copy)]
+/// The gate a player has chosen to link other gates to
+struct SelectedWarpGate {
+    gate: StructureBlock,
+    destination: WarpGateDestination,
+}
+
+#[derive(Component, Debug, Default, Clone, Copy)]
+/// Placed on the block data of linked gates to keep track of how charged they are
+struct WarpGateCharge {
+    /// Seconds spent charging so far
+    charge: f32,
+    /// Seconds left before the gate can charge again
+    cooldown: f32,
+}
+
+/// Where ships going to this gate should arrive
+fn gate_destination(structure: &Structure, coords: BlockCoordinate, loc: &Location, g_trans: &GlobalTransform) -> WarpGateDestination {
+    let block_rotation = structure.block_rotation(coords);
+    let structure_rotation = g_trans.compute_transform().rotation;
+
+    WarpGateDestination {
+        location: structure.block_world_location(coords, g_trans, loc) + structure_rotation * gate_center_offset(block_rotation),
+        rotation: structure_rotation * block_rotation.as_quat(),
+    }
+}
+
+fn is_formed_gate(multiblocks: &Multiblocks, coords: BlockCoordinate, patterns: &Registry<MultiblockPattern>) -> bool {
+    let Some(pattern) = patterns.from_id(WARP_GATE_PATTERN) else {
+        return false;
+    };
+
+    multiblocks
+        .controlled_by(coords)
+        .is_some_and(|multiblock| multiblock.pattern_id() == pattern.id())
+}
+
+fn on_interact_gate(
+    mut commands: Commands,
+    mut evr_block_interact: EventReader<BlockInteractEvent>,
+    mut q_structure: Query<(&mut Structure, &Location, &GlobalTransform, &Multiblocks), With<Station>>,
+    q_non_station: Query<&Structure, Without<Station>>,
+    q_player: Query<(&Player, Option<&SelectedWarpGate>)>,
+    blocks: Res<Registry<Block>>,
+    patterns: Res<Registry<MultiblockPattern>>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut q_block_data: Query<&mut BlockData>,
+    q_has_destination: Query<(), With<WarpGateDestination>>,
+    mut bs_params: BlockDataSystemParams,
+) {
+    for ev in evr_block_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok((player, selected)) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        let reply = |nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, message: &str| {
+            nevw_chat.send(
+                ServerSendChatMessageEvent {
+                    sender: None,
+                    message: message.into(),
+                },
+                player.id(),
+            );
+        };
+
+        let Ok((mut structure, loc, g_trans, multiblocks)) = q_structure.get_mut(s_block.structure()) else {
+            if q_non_station
+                .get(s_block.structure())
+                .is_ok_and(|structure| structure.block_at(s_block.coords(), &blocks).unlocalized_name() == WARP_GATE_CONTROLLER)
+            {
+                reply(&mut nevw_chat, "Warp gates can only be built on stations.");
+            }
+            continue;
+        };
+
+        if structure.block_at(s_block.coords(), &blocks).unlocalized_name() != WARP_GATE_CONTROLLER {
+            continue;
+        }
+
+        if !permissions.can_use(ev.interactor, s_block.structure()) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        if !is_formed_gate(multiblocks, s_block.coords(), &patterns) {
+            reply(
+                &mut nevw_chat,
+                "This warp gate isn't finished. It needs a 5x5 ring of warp gate frames around an empty 3x3 opening.",
+            );
+            continue;
+        }
+
+        match selected {
+            Some(selected) if selected.gate == s_block => {
+                commands.entity(ev.interactor).remove::<SelectedWarpGate>();
+                reply(&mut nevw_chat, "Forgot this warp gate.");
+            }
+            Some(selected) => {
+                structure.insert_block_data(
+                    s_block.coords(),
+                    selected.destination,
+                    &mut bs_params,
+                    &mut q_block_data,
+                    &q_has_destination,
+                );
+
+                commands.entity(ev.interactor).remove::<SelectedWarpGate>();
+                reply(&mut nevw_chat, "This warp gate will now send ships to the remembered warp gate.");
+            }
+            None => {
+                commands.entity(ev.interactor).insert(SelectedWarpGate {
+                    gate: s_block,
+                    destination: gate_destination(&structure, s_block.coords(), loc, g_trans),
+                });
+                reply(
+                    &mut nevw_chat,
+                    "Remembered this warp gate. Interact with another warp gate to make it send ships here.",
+                );
+            }
+        }
+    }
+}
+
+fn add_charge_to_gates(mut commands: Commands, q_gates: Query<Entity, (With<WarpGateDestination>, Without<WarpGateCharge>)>) {
+    for ent in q_gates.iter() {
+        commands.entity(ent).insert(WarpGateCharge::default());
+    }
+}
+
+/// Gates charge up while a ship is nearby, then send the next ship that flies through their opening.
+fn charge_and_use_gates(
+    mut commands: Commands,
+    mut q_gates: Query<(&BlockData, &WarpGateDestination, &mut WarpGateCharge)>,
+    q_structure: Query<(&Structure, &Location, &GlobalTransform, &StructureSystems, &Multiblocks), With<Station>>,
+    mut q_ships: Query<
+        (
+            Entity,
+            &Structure,
+            &mut Location,
+            &mut Transform,
+            &mut Velocity,
+            &ReadMassProperties,
+        ),
+        (With<Ship>, Without<Station>),
+    >,
+    mut q_energy_storage: Query<&mut EnergyStorageSystem>,
+    patterns: Res<Registry<MultiblockPattern>>,
+    mut nevw_charge: NettyEventWriter<WarpGateChargeEvent>,
+    mut nevw_jump: NettyEventWriter<WarpGateJumpEvent>,
+    time: Res<Time>,
+) {
+    for (block_data, destination, mut charge) in q_gates.iter_mut() {
+        charge.cooldown = (charge.cooldown - time.delta_secs()).max(0.0);
+
+        let gate = block_data.identifier.block;
+
+        let Ok((structure, loc, g_trans, systems, multiblocks)) = q_structure.get(gate.structure()) else {
+            continue;
+        };
+
+        let gate_here = gate_destination(structure, gate.coords(), loc, g_trans);
+
+        let can_charge = charge.cooldown == 0.0
+            && is_formed_gate(multiblocks, gate.coords(), &patterns)
+            && q_ships
+                .iter()
+                .any(|(_, _, ship_loc, ..)| ship_loc.distance_sqrd(&gate_here.location) <= WARP_GATE_CHARGE_RANGE * WARP_GATE_CHARGE_RANGE);
+
+        if !can_charge {
+            if charge.charge != 0.0 {
+                charge.charge = 0.0;
+                nevw_charge.broadcast(WarpGateChargeEvent { gate, charging: false });
+            }
+            continue;
+        }
+
+        if charge.charge < WARP_GATE_CHARGE_TIME {
+            if charge.charge == 0.0 {
+                nevw_charge.broadcast(WarpGateChargeEvent { gate, charging: true });
+            }
+            charge.charge += time.delta_secs();
+            continue;
+        }
+
+        for (ship_ent, ship_structure, mut ship_loc, mut ship_trans, mut velocity, mass) in q_ships.iter_mut() {
+            let relative = gate_here.rotation.inverse() * gate_here.location.relative_coords_to(&ship_loc);
+            if relative.abs().max_element() > WARP_GATE_OPENING_HALF_WIDTH {
+                continue;
+            }
+
+            let Ok(mut energy_storage) = systems.query_mut(&mut q_energy_storage) else {
+                break;
+            };
+
+            let cost = mass.get().mass * WARP_GATE_ENERGY_PER_KG;
+            if energy_storage.get_energy() < cost {
+                continue;
+            }
+            energy_storage.decrease_energy(cost);
+
+            // Ships keep their speed & rotation relative to the gates, and always leave through the front of the destination.
+            let entered_through_front = velocity.linvel.dot(gate_here.rotation * Vec3::NEG_Z) < 0.0;
+            let flip = if entered_through_front {
+                Quat::from_rotation_y(PI)
+            } else {
+                Quat::IDENTITY
+            };
+            let delta_rotation = destination.rotation * flip * gate_here.rotation.inverse();
+
+            let exit_distance = ship_structure.block_dimensions().max_element() as f32 / 2.0 + EXIT_PADDING;
+            *ship_loc = destination.location + destination.rotation * Vec3::NEG_Z * exit_distance;
+            commands.entity(ship_ent).insert(SetPosition::Transform);
+
+            ship_trans.rotation = (delta_rotation * ship_trans.rotation).normalize();
+            velocity.linvel = delta_rotation * velocity.linvel;
+            velocity.angvel = delta_rotation * velocity.angvel;
+
+            nevw_jump.broadcast(WarpGateJumpEvent {
+                ship: ship_ent,
+                gate,
+                destination: *destination,
+            });
+
+            charge.charge = 0.0;
+            charge.cooldown = WARP_GATE_COOLDOWN;
+            break;
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<WarpGateDestination>(app);
+
+    app.add_systems(
+        Update,
+        (
+            on_interact_gate.in_set(BlockEventsSet::ProcessEvents),
+            (add_charge_to_gates, charge_and_use_gates)
+                .chain()
+                .in_set(StructureSystemsSet::UpdateSystems),
+        )
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,102 @@
+//! Validates and applies requests from clients to paint blocks
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::{
+        block_events::BlockEventsSet,
+        paint::{PaintBlockEvent, PAINT_TOOL_ITEM},
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    events::block_events::BlockDataChangedEvent,
+    inventory::{held_item_slot::HeldItemSlot, Inventory},
+    item::Item,
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::Structure,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+};
+
+use crate::structure::ownership::{notify_no_permission, StructurePermissions};
+
+/// Players can reach a little further than the client's raycast to account for latency
+const MAX_PAINT_DISTANCE: f32 = 12.0;
+
+fn on_paint_block(
+    mut nevr_paint_block: EventReader<NettyEventReceived<PaintBlockEvent>>,
+    lobby: Res<ServerLobby>,
+    q_player: Query<(&GlobalTransform, &HeldItemSlot, &Inventory)>,
+    mut q_structure: Query<(&mut Structure, &GlobalTransform)>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    mut evw_block_data_changed: EventWriter<BlockDataChangedEvent>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    let Some(paint_tool) = items.from_id(PAINT_TOOL_ITEM) else {
+        return;
+    };
+
+    for ev in nevr_paint_block.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok((player_g_trans, held_item, inventory)) = q_player.get(player_ent) else {
+            continue;
+        };
+
+        if inventory
+            .itemstack_at(held_item.slot() as usize)
+            .is_none_or(|is| is.item_id() != paint_tool.id())
+        {
+            warn!("Player {player_ent:?} tried to paint without holding the paint tool.");
+            continue;
+        }
+
+        if !permissions.can_use(player_ent, ev.block.structure()) {
+            notify_no_permission(&mut nevw_chat, ev.client_id);
+            continue;
+        }
+
+        let Ok((mut structure, structure_g_trans)) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        let coords = ev.block.coords();
+
+        if !structure.is_within_blocks(coords) || !structure.block_at(coords, &blocks).can_be_painted() {
+            continue;
+        }
+
+        let block_position = structure_g_trans.transform_point(structure.block_relative_position(coords));
+        if block_position.distance_squared(player_g_trans.translation()) > MAX_PAINT_DISTANCE * MAX_PAINT_DISTANCE {
+            warn!("Player {player_ent:?} tried to paint a block that is too far away.");
+            continue;
+        }
+
+        let mut block_info = structure.block_info_at(coords);
+        if block_info.get_paint_color() == ev.color {
+            continue;
+        }
+
+        block_info.set_paint_color(ev.color);
+        // This will sync the new block info to all the clients
+        structure.set_block_info_at(coords, block_info, &mut evw_block_data_changed);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        on_paint_block
+            .in_set(NetworkingSystemsSet::Between)
+            .in_set(BlockEventsSet::ProcessEvents)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
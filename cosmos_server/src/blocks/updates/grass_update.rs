@@ -1,57 +1,70 @@
-use bevy::prelude::{in_state, App, EventReader, EventWriter, IntoSystemConfigs, Query, Res, Update};
+use bevy::prelude::{in_state, App, EventReader, EventWriter, IntoSystemConfigs, OnEnter, Query, Res, ResMut, Update};
 
 use cosmos_core::{
-    block::{block_events::BlockEventsSet, block_update::BlockUpdate, Block},
-    ecs::mut_events::MutEvent,
+    block::{
+        block_events::BlockEventsSet,
+        block_update::{BlockNeighborChangedEvent, NeighborChangeListener},
+        Block,
+    },
     events::block_events::BlockChangedEvent,
     netty::system_sets::NetworkingSystemsSet,
     registry::{identifiable::Identifiable, Registry},
     state::GameState,
-    structure::{coordinates::BlockCoordinate, Structure},
+    structure::Structure,
 };
 
+const SHORT_GRASS_BLOCK: &str = "cosmos:short_grass";
+
+fn register_neighbor_listeners(blocks: Res<Registry<Block>>, mut listeners: ResMut<Registry<NeighborChangeListener>>) {
+    if let Some(short_grass) = blocks.from_id(SHORT_GRASS_BLOCK) {
+        listeners.register(NeighborChangeListener::new(short_grass));
+    }
+}
+
+/// Short grass breaks once the block it's growing on is no longer full
 fn monitor_grass_updated(
     mut structure_query: Query<&mut Structure>,
     blocks: Res<Registry<Block>>,
-    mut event_reader: EventReader<MutEvent<BlockUpdate>>,
+    mut evr_neighbor_changed: EventReader<BlockNeighborChangedEvent>,
     mut event_writer: EventWriter<BlockChangedEvent>,
 ) {
-    for ev in event_reader.read() {
-        let ev = ev.read();
+    let Some(short_grass) = blocks.from_id(SHORT_GRASS_BLOCK) else {
+        return;
+    };
 
-        if ev.cancelled() {
+    for ev in evr_neighbor_changed.read() {
+        if ev.block_id != short_grass.id() {
             continue;
         }
 
-        let Ok(mut structure) = structure_query.get_mut(ev.structure_entity()) else {
+        let Ok(mut structure) = structure_query.get_mut(ev.block.structure()) else {
             continue;
         };
 
-        let block = ev.block().block(&structure, &blocks);
-
-        if block.unlocalized_name() == "cosmos:short_grass" {
-            let block_up = ev.block().block_up(&structure);
-            let down_coord = block_up.face_pointing_pos_y.inverse().direction().to_coordinates() + ev.block().coords();
+        // The event could be from before this grass was broken by another neighbor this frame
+        if structure.block_id_at(ev.block.coords()) != short_grass.id() {
+            continue;
+        }
 
-            let Ok(down_coord) = BlockCoordinate::try_from(down_coord) else {
-                structure.remove_block_at(ev.block().coords(), &blocks, Some(&mut event_writer));
-                continue;
-            };
+        let down = ev.block.block_up(&structure).face_pointing_pos_y.inverse().direction();
+        if ev.direction != down {
+            continue;
+        }
 
-            if !structure.block_at(down_coord, &blocks).is_full() {
-                structure.remove_block_at(ev.block().coords(), &blocks, Some(&mut event_writer));
-            }
+        if !structure.block_at(ev.neighbor().coords(), &blocks).is_full() {
+            structure.remove_block_at(ev.block.coords(), &blocks, Some(&mut event_writer));
         }
     }
 }
 
 pub(super) fn register(app: &mut App) {
-    app.add_systems(
-        Update,
-        monitor_grass_updated
-            .in_set(BlockEventsSet::SendEventsForNextFrame)
-            .ambiguous_with(BlockEventsSet::SendEventsForNextFrame) // Order of blocks being updated doesn't matter
-            .in_set(NetworkingSystemsSet::Between)
-            .run_if(in_state(GameState::Playing)),
-    );
+    app.add_systems(OnEnter(GameState::PostLoading), register_neighbor_listeners)
+        .add_systems(
+            Update,
+            monitor_grass_updated
+                .in_set(BlockEventsSet::SendEventsForNextFrame)
+                .ambiguous_with(BlockEventsSet::SendEventsForNextFrame) // Order of blocks being updated doesn't matter
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        );
 }
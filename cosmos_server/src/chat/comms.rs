@@ -0,0 +1,252 @@
+//! Delivers hails between structures & keeps a log of every player's communications.
+//!
+//! Players can view their recent communications with the `/comms [count]` chat command.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use cosmos_core::{
+    chat::{
+        comms::{SendHailEvent, MAX_HAIL_LENGTH},
+        ServerSendChatMessageEvent,
+    },
+    entities::player::Player,
+    netty::{
+        server::ServerLobby,
+        sync::{
+            events::server_event::{NettyEventReceived, NettyEventWriter},
+            IdentifiableComponent,
+        },
+        system_sets::NetworkingSystemsSet,
+    },
+    physics::location::Location,
+    state::GameState,
+    structure::{
+        ship::{pilot::Pilot, Ship},
+        station::Station,
+        structure_name::StructureName,
+    },
+};
+use renet2::ClientId;
+use serde::{Deserialize, Serialize};
+
+use crate::persistence::make_persistent::{make_persistent, DefaultPersistentComponent};
+
+use super::{ChatCommandSet, ChatCommands, PlayerChatCommandEvent};
+
+/// Structures further away than this cannot be hailed
+const MAX_HAIL_DISTANCE: f32 = 50_000.0;
+
+/// Older entries are removed once a player's log has this many entries
+const MAX_COMMS_LOG_ENTRIES: usize = 50;
+
+/// How many entries `/comms` shows if no count is given
+const DEFAULT_COMMS_SHOWN: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A hail that was sent or received
+pub struct CommsEntry {
+    /// The name of the structure that sent this hail
+    pub from: String,
+    /// The name of the player that sent this hail
+    pub sender: String,
+    /// The name of the structure that was hailed
+    pub to: String,
+    /// What was said
+    pub message: String,
+}
+
+impl CommsEntry {
+    fn display(&self) -> String {
+        format!("[Hail] {} ({}) -> {}: {}", self.from, self.sender, self.to, self.message)
+    }
+}
+
+#[derive(Component, Debug, Clone, Default, Serialize, Deserialize)]
+/// Every hail this player has recently sent or received, oldest first
+pub struct CommsLog(VecDeque<CommsEntry>);
+
+impl CommsLog {
+    /// Adds an entry to this log, removing the oldest one if the log is full
+    pub fn push(&mut self, entry: CommsEntry) {
+        if self.0.len() >= MAX_COMMS_LOG_ENTRIES {
+            self.0.pop_front();
+        }
+
+        self.0.push_back(entry);
+    }
+
+    /// Iterates over this log from oldest to newest
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &CommsEntry> + ExactSizeIterator {
+        self.0.iter()
+    }
+}
+
+impl IdentifiableComponent for CommsLog {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:comms_log"
+    }
+}
+
+impl DefaultPersistentComponent for CommsLog {}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+fn structure_display_name(name: Option<&StructureName>, is_station: bool) -> String {
+    match name {
+        Some(name) => name.name().to_owned(),
+        None if is_station => "Unnamed Station".into(),
+        None => "Unnamed Ship".into(),
+    }
+}
+
+fn log_comms(commands: &mut Commands, player_entity: Entity, log: Option<Mut<CommsLog>>, entry: CommsEntry) {
+    if let Some(mut log) = log {
+        log.push(entry);
+    } else {
+        let mut log = CommsLog::default();
+        log.push(entry);
+        commands.entity(player_entity).insert(log);
+    }
+}
+
+fn on_hail(
+    mut commands: Commands,
+    mut nevr_hail: EventReader<NettyEventReceived<SendHailEvent>>,
+    lobby: Res<ServerLobby>,
+    mut q_players: Query<(Entity, &Player, Option<&Parent>, Option<&Pilot>, Option<&mut CommsLog>)>,
+    q_structures: Query<(&Location, Option<&StructureName>, Has<Station>), Or<(With<Ship>, With<Station>)>>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for ev in nevr_hail.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok((_, player, _, pilot, _)) = q_players.get(player_ent) else {
+            continue;
+        };
+
+        let sender_name = player.name().to_owned();
+
+        let Some(from_entity) = pilot.map(|x| x.entity) else {
+            reply(&mut nevw_chat, ev.client_id, "You must be piloting a ship to send a hail.");
+            continue;
+        };
+
+        if from_entity == ev.target {
+            continue;
+        }
+
+        let message = ev.message.trim();
+        if message.is_empty() || message.chars().count() > MAX_HAIL_LENGTH || message.chars().any(|c| c.is_control()) {
+            warn!("Player {player_ent:?} sent an invalid hail.");
+            continue;
+        }
+
+        let (Ok((from_loc, from_name, from_station)), Ok((to_loc, to_name, to_station))) =
+            (q_structures.get(from_entity), q_structures.get(ev.target))
+        else {
+            continue;
+        };
+
+        let to = structure_display_name(to_name, to_station);
+
+        if from_loc.distance_sqrd(to_loc) > MAX_HAIL_DISTANCE * MAX_HAIL_DISTANCE {
+            reply(&mut nevw_chat, ev.client_id, format!("{to} is too far away to hail."));
+            continue;
+        }
+
+        let entry = CommsEntry {
+            from: structure_display_name(from_name, from_station),
+            sender: sender_name,
+            to,
+            message: message.to_owned(),
+        };
+
+        let mut delivered = false;
+
+        for (entity, player, parent, pilot, log) in q_players.iter_mut() {
+            let aboard_target = pilot.is_some_and(|p| p.entity == ev.target) || parent.is_some_and(|p| p.get() == ev.target);
+            let is_sender = entity == player_ent;
+
+            if !aboard_target && !is_sender {
+                continue;
+            }
+
+            delivered |= aboard_target;
+
+            reply(&mut nevw_chat, player.id(), entry.display());
+            log_comms(&mut commands, entity, log, entry.clone());
+        }
+
+        if !delivered {
+            reply(
+                &mut nevw_chat,
+                ev.client_id,
+                format!("Nobody aboard {} answered your hail.", entry.to),
+            );
+        }
+    }
+}
+
+fn register_chat_commands(mut chat_commands: ResMut<ChatCommands>) {
+    chat_commands.add("comms");
+}
+
+fn on_comms_command(
+    mut evr_command: EventReader<PlayerChatCommandEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    q_log: Query<&CommsLog>,
+) {
+    for ev in evr_command.read() {
+        if ev.name != "comms" {
+            continue;
+        }
+
+        let count = match ev.args.as_slice() {
+            [] => DEFAULT_COMMS_SHOWN,
+            [count] => match count.parse::<usize>() {
+                Ok(count) => count,
+                Err(_) => {
+                    reply(&mut nevw_chat, ev.client_id, "Usage: /comms [count]");
+                    continue;
+                }
+            },
+            _ => {
+                reply(&mut nevw_chat, ev.client_id, "Usage: /comms [count]");
+                continue;
+            }
+        };
+
+        let Some(log) = q_log.get(ev.player_entity).ok().filter(|log| log.iter().len() != 0) else {
+            reply(&mut nevw_chat, ev.client_id, "You haven't sent or received any hails.");
+            continue;
+        };
+
+        let skip = log.iter().len().saturating_sub(count);
+        for entry in log.iter().skip(skip) {
+            reply(&mut nevw_chat, ev.client_id, entry.display());
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<CommsLog>(app);
+
+    app.add_systems(Startup, register_chat_commands).add_systems(
+        Update,
+        (on_hail, on_comms_command.after(ChatCommandSet::SendCommandEvents))
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -3,7 +3,8 @@
 use bevy::{
     app::Update,
     log::info,
-    prelude::{in_state, App, EventReader, IntoSystemConfigs, Query, Res},
+    prelude::{in_state, App, Entity, Event, EventReader, EventWriter, IntoSystemConfigs, Query, Res, Resource, SystemSet},
+    utils::HashSet,
 };
 use cosmos_core::{
     chat::{ClientSendChatMessageEvent, ServerSendChatMessageEvent},
@@ -15,10 +16,56 @@ use cosmos_core::{
     },
     state::GameState,
 };
+use renet2::ClientId;
+
+use crate::party::Parties;
+
+pub mod comms;
+
+#[derive(Event, Debug)]
+/// Sent when a player sends a chat message starting with `/` that matches a command in [`ChatCommands`].
+pub struct PlayerChatCommandEvent {
+    /// The player that used this command
+    pub player_entity: Entity,
+    /// The client id of the player that used this command
+    pub client_id: ClientId,
+    /// The name of the command, without the leading `/`
+    pub name: String,
+    /// The args split around spaces
+    pub args: Vec<String>,
+}
+
+#[derive(Resource, Debug, Default)]
+/// Every command players can use from the chat (ex: `/spectate`).
+///
+/// Chat messages starting with `/` are never shown to other players.
+pub struct ChatCommands(HashSet<String>);
+
+impl ChatCommands {
+    /// Allows players to use this command. The name should not include the leading `/`.
+    pub fn add(&mut self, name: impl Into<String>) {
+        self.0.insert(name.into());
+    }
+
+    /// Returns true if a command with this name exists
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.contains(name)
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+/// Chat messages are turned into [`PlayerChatCommandEvent`]s
+pub enum ChatCommandSet {
+    /// Chat messages are turned into [`PlayerChatCommandEvent`]s
+    SendCommandEvents,
+}
 
 fn receive_messages(
     mut nevw_send_chat_msg: NettyEventWriter<ServerSendChatMessageEvent>,
     mut nevr_chat_msg: EventReader<NettyEventReceived<ClientSendChatMessageEvent>>,
+    mut evw_chat_command: EventWriter<PlayerChatCommandEvent>,
+    chat_commands: Res<ChatCommands>,
+    parties: Res<Parties>,
     clients: Res<ServerLobby>,
     q_player: Query<&Player>,
 ) {
@@ -32,6 +79,33 @@ fn receive_messages(
 
         match &ev.event {
             ClientSendChatMessageEvent::Global(msg) => {
+                if let Some(command) = msg.strip_prefix('/') {
+                    let mut split = command.split(' ').filter(|x| !x.is_empty());
+                    let name = split.next().unwrap_or_default().to_lowercase();
+
+                    if !chat_commands.contains(&name) {
+                        nevw_send_chat_msg.send(
+                            ServerSendChatMessageEvent {
+                                sender: None,
+                                message: format!("Unknown command /{name}"),
+                            },
+                            ev.client_id,
+                        );
+                        continue;
+                    }
+
+                    info!("{} used command: {msg}", player.name());
+
+                    evw_chat_command.send(PlayerChatCommandEvent {
+                        player_entity: player_ent,
+                        client_id: ev.client_id,
+                        name,
+                        args: split.map(|x| x.to_owned()).collect(),
+                    });
+
+                    continue;
+                }
+
                 let message = format!("{}> {}", player.name(), msg);
 
                 info!("{message}");
@@ -41,15 +115,46 @@ fn receive_messages(
                     message,
                 });
             }
+            ClientSendChatMessageEvent::Party(msg) => {
+                let Some(party) = parties.party_of(player.name()) else {
+                    nevw_send_chat_msg.send(
+                        ServerSendChatMessageEvent {
+                            sender: None,
+                            message: "You aren't in a party.".into(),
+                        },
+                        ev.client_id,
+                    );
+                    continue;
+                };
+
+                let message = format!("[Party] {}> {}", player.name(), msg);
+
+                info!("{message}");
+
+                for member in q_player.iter().filter(|x| party.is_member(x.name())) {
+                    nevw_send_chat_msg.send(
+                        ServerSendChatMessageEvent {
+                            sender: Some(player_ent),
+                            message: message.clone(),
+                        },
+                        member.id(),
+                    );
+                }
+            }
         }
     }
 }
 
 pub(super) fn register(app: &mut App) {
-    app.add_systems(
-        Update,
-        receive_messages
-            .in_set(NetworkingSystemsSet::Between)
-            .run_if(in_state(GameState::Playing)),
-    );
+    comms::register(app);
+
+    app.configure_sets(Update, ChatCommandSet::SendCommandEvents.in_set(NetworkingSystemsSet::Between))
+        .init_resource::<ChatCommands>()
+        .add_event::<PlayerChatCommandEvent>()
+        .add_systems(
+            Update,
+            receive_messages
+                .in_set(ChatCommandSet::SendCommandEvents)
+                .run_if(in_state(GameState::Playing)),
+        );
 }
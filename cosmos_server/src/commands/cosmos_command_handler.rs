@@ -9,18 +9,27 @@ use bevy::{
     app::Update,
     ecs::schedule::IntoSystemConfigs,
     log::warn,
-    prelude::{App, Commands, Entity, EventReader, Name, Quat, Query, Res, ResMut, Startup, Vec3, With},
+    prelude::{App, Commands, Entity, EventReader, Has, Name, Quat, Query, Res, ResMut, Startup, Vec3, With},
 };
 use cosmos_core::{
     ecs::NeedsDespawned,
+    entities::player::{spectator::SpectatorTeleportEvent, Player},
+    netty::sync::events::server_event::NettyEventWriter,
     persistence::Blueprintable,
     physics::location::{Location, Sector, SectorUnit},
 };
 use thiserror::Error;
 
-use crate::persistence::{
-    loading::{LoadingSystemSet, NeedsBlueprintLoaded},
-    saving::NeedsBlueprinted,
+use crate::{
+    entities::player::{
+        admin::Admin,
+        spectator::{stop_spectating, CanSpectate, SpectatorBody},
+    },
+    persistence::{
+        loading::{LoadingSystemSet, NeedsBlueprintLoaded},
+        saving::NeedsBlueprinted,
+    },
+    universe::{safe_zone::SafeZone, sector_load::SectorLoad},
 };
 
 use super::{CosmosCommandInfo, CosmosCommandSent, CosmosCommands};
@@ -69,6 +78,32 @@ fn register_commands(mut commands: ResMut<CosmosCommands>) {
         usage: "despawn [entity_id]".into(),
         description: "Despawns the given entity.".into(),
     });
+
+    commands.add_command_info(CosmosCommandInfo {
+        name: "spectator".into(),
+        usage: "spectator [player_name]".into(),
+        description: "Gives/takes away a player's permission to spectate.".into(),
+    });
+
+    commands.add_command_info(CosmosCommandInfo {
+        name: "admin".into(),
+        usage: "admin [player_name]".into(),
+        description: "Makes/stops a player being an admin. Admins can use any structure, even ones they don't have access to.".into(),
+    });
+
+    commands.add_command_info(CosmosCommandInfo {
+        name: "safezone".into(),
+        usage: "safezone {entity_id} {radius|off}".into(),
+        description: "Makes the area within the radius of this entity a safe zone, where weapons cannot be fired and blocks cannot be damaged. Use 'off' to remove it. With no arguments, lists every safe zone."
+            .into(),
+    });
+
+    commands.add_command_info(CosmosCommandInfo {
+        name: "sectorload".into(),
+        usage: "sectorload {amount}".into(),
+        description: "Lists the busiest sectors, how many entities they have, roughly how much of each tick they take, and if they are being throttled. Shows 10 sectors unless an amount is given."
+            .into(),
+    });
 }
 
 fn display_help(command_name: Option<&str>, commands: &CosmosCommands) {
@@ -101,6 +136,10 @@ fn cosmos_command_listener(
     cosmos_commands: Res<CosmosCommands>,
 
     all_blueprintable_entities: Query<(Entity, &Name, &Location), With<Blueprintable>>,
+    q_players: Query<(Entity, &Player, Has<CanSpectate>, Has<Admin>, Option<&SpectatorBody>)>,
+    q_safe_zones: Query<(Entity, &Name, &SafeZone)>,
+    sector_load: Res<SectorLoad>,
+    mut nevw_teleport: NettyEventWriter<SpectatorTeleportEvent>,
 ) {
     for ev in command_events.read() {
         match ev.name.as_str() {
@@ -140,6 +179,107 @@ fn cosmos_command_listener(
                     println!("This must be the entity's ID (positive whole number)");
                 }
             }
+            "spectator" => {
+                if ev.args.len() != 1 {
+                    display_help(Some("spectator"), &cosmos_commands);
+                    continue;
+                }
+
+                let Some((entity, player, can_spectate, _, body)) =
+                    q_players.iter().find(|(_, player, _, _, _)| player.name() == ev.args[0])
+                else {
+                    println!("No player named {} is online.", ev.args[0]);
+                    continue;
+                };
+
+                if can_spectate {
+                    stop_spectating(&mut commands, &mut nevw_teleport, entity, player.id(), body);
+                    commands.entity(entity).remove::<CanSpectate>();
+                    println!("{} can no longer spectate.", player.name());
+                } else {
+                    commands.entity(entity).insert(CanSpectate);
+                    println!("{} can now spectate.", player.name());
+                }
+            }
+            "admin" => {
+                if ev.args.len() != 1 {
+                    display_help(Some("admin"), &cosmos_commands);
+                    continue;
+                }
+
+                let Some((entity, player, _, admin, _)) = q_players.iter().find(|(_, player, _, _, _)| player.name() == ev.args[0]) else {
+                    println!("No player named {} is online.", ev.args[0]);
+                    continue;
+                };
+
+                if admin {
+                    commands.entity(entity).remove::<Admin>();
+                    println!("{} is no longer an admin.", player.name());
+                } else {
+                    commands.entity(entity).insert(Admin);
+                    println!("{} is now an admin.", player.name());
+                }
+            }
+            "safezone" => {
+                if ev.args.is_empty() {
+                    println!("All safe zones (shop stations are always safe zones): ");
+                    println!("Name\tRadius\tId");
+                    for (entity, name, safe_zone) in q_safe_zones.iter() {
+                        println!("{name}\t{}\t{}", safe_zone.radius, entity.to_bits());
+                    }
+                    println!("======================================");
+                    continue;
+                }
+
+                if ev.args.len() != 2 {
+                    display_help(Some("safezone"), &cosmos_commands);
+                    continue;
+                }
+
+                let Some(entity) = ev.args[0].parse::<u64>().ok().and_then(|index| Entity::try_from_bits(index).ok()) else {
+                    println!("The first argument must be the entity's id (positive whole number)");
+                    continue;
+                };
+
+                if !all_blueprintable_entities.contains(entity) {
+                    println!("Entity not found");
+                    continue;
+                }
+
+                if ev.args[1] == "off" {
+                    commands.entity(entity).remove::<SafeZone>();
+                    println!("Removed the safe zone around entity {}.", ev.args[0]);
+                    continue;
+                }
+
+                let Some(radius) = ev.args[1].parse::<f32>().ok().filter(|r| r.is_finite() && *r > 0.0) else {
+                    println!("The radius must be a positive number");
+                    continue;
+                };
+
+                commands.entity(entity).insert(SafeZone { radius });
+                println!("Entity {} is now the center of a safe zone with a radius of {radius}.", ev.args[0]);
+            }
+            "sectorload" => {
+                let amount = match ev.args.first().map(|x| x.parse::<usize>()) {
+                    None => 10,
+                    Some(Ok(amount)) => amount,
+                    Some(Err(_)) => {
+                        display_help(Some("sectorload"), &cosmos_commands);
+                        continue;
+                    }
+                };
+
+                let mut sectors = sector_load.iter().collect::<Vec<_>>();
+                sectors.sort_by(|(_, a), (_, b)| b.entities.cmp(&a.entities));
+
+                println!("Average tick time: {:.2}ms", sector_load.average_tick_ms());
+                println!("Sector		Entities	Tick (ms)	Throttled");
+                for (sector, info) in sectors.into_iter().take(amount) {
+                    println!("{sector}	{}		{:.2}		{}", info.entities, info.estimated_tick_ms, info.throttled);
+                }
+                println!("======================================");
+            }
             "load" => {
                 if ev.args.len() < 2 || ev.args.len() > 8 {
                     display_help(Some("load"), &cosmos_commands);
@@ -15,6 +15,7 @@ use cosmos_core::{
         RecipeItem,
     },
     item::Item,
+    modding::LoadedMods,
     netty::{sync::events::server_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
     registry::{identifiable::Identifiable, Registry},
     state::GameState,
@@ -43,12 +44,42 @@ pub struct RawBasicFabricatorRecipe {
     output: RawFabricatorOutput,
 }
 
-fn load_recipes(items: Res<Registry<Item>>, mut commands: Commands) {
+/// Converts the raw recipe into one the game can use. `source` is only used in error messages.
+fn parse_recipe(recipe: RawBasicFabricatorRecipe, items: &Registry<Item>, source: &str) -> Option<BasicFabricatorRecipe> {
+    let output = items.from_id(&recipe.output.item).map(|x| (x, recipe.output.quantity));
+
+    let Some((output_item, output_quantity)) = output else {
+        error!("Unable to find item with id matching {:?} in {source}", recipe.output.item);
+        return None;
+    };
+
+    let mut inputs = vec![];
+
+    for input in recipe.inputs {
+        let input_data = match &input.item {
+            RawRecipeItem::Item(item_name) => items.from_id(item_name).map(|x| (x, input.quantity)),
+        };
+
+        let Some((item, quantity)) = input_data else {
+            error!("Unable to find item with id matching {:?} in {source}", input.item);
+            return None;
+        };
+
+        inputs.push(FabricatorItemInput::new(RecipeItem::Item(item.id()), quantity));
+    }
+
+    Some(BasicFabricatorRecipe::new(
+        FabricatorItemOutput::new(output_item, output_quantity),
+        inputs,
+    ))
+}
+
+fn load_recipes(items: Res<Registry<Item>>, mods: Res<LoadedMods>, mut commands: Commands) {
     info!("Loading basic fabricator recipes!");
 
     let mut recipes = BasicFabricatorRecipes::default();
 
-    'recipe_lookup: for entry in WalkDir::new("assets/cosmos/recipes/basic_fabricator").max_depth(1) {
+    for entry in WalkDir::new("assets/cosmos/recipes/basic_fabricator").max_depth(1) {
         let Ok(entry) = entry else {
             continue;
         };
@@ -63,32 +94,24 @@ fn load_recipes(items: Res<Registry<Item>>, mut commands: Commands) {
         let recipe = serde_json::from_slice::<RawBasicFabricatorRecipe>(&recipe_json)
             .unwrap_or_else(|e| panic!("Invalid recipe json {path:?}\n{e:?}"));
 
-        let output = items.from_id(&recipe.output.item).map(|x| (x, recipe.output.quantity));
+        if let Some(recipe) = parse_recipe(recipe, &items, &format!("file {path:?}")) {
+            recipes.add_recipe(recipe);
+        }
+    }
 
-        let Some((output_item, output_quantity)) = output else {
-            error!("Unable to find item with id matching {:?} in file {path:?}", recipe.output.item);
-            continue;
+    // Unlike the base game's recipes, a broken mod recipe shouldn't crash the server.
+    for (mod_name, recipe_json) in mods.basic_fabricator_recipes() {
+        let recipe = match serde_json::from_str::<RawBasicFabricatorRecipe>(&recipe_json) {
+            Ok(recipe) => recipe,
+            Err(e) => {
+                error!("Invalid recipe json from mod {mod_name}\n{e:?}");
+                continue;
+            }
         };
 
-        let mut inputs = vec![];
-
-        for input in recipe.inputs {
-            let input_data = match &input.item {
-                RawRecipeItem::Item(item_name) => items.from_id(item_name).map(|x| (x, input.quantity)),
-            };
-
-            let Some((item, quantity)) = input_data else {
-                error!("Unable to find item with id matching {:?} in file {path:?}", input.item);
-                continue 'recipe_lookup;
-            };
-
-            inputs.push(FabricatorItemInput::new(RecipeItem::Item(item.id()), quantity));
+        if let Some(recipe) = parse_recipe(recipe, &items, &format!("mod {mod_name}")) {
+            recipes.add_recipe(recipe);
         }
-
-        recipes.add_recipe(BasicFabricatorRecipe::new(
-            FabricatorItemOutput::new(output_item, output_quantity),
-            inputs,
-        ));
     }
 
     commands.insert_resource(recipes);
@@ -0,0 +1,354 @@
+//! Bounties are placed on players who destroy ships in policed space.
+//!
+//! A bounty is paid for by the player it's on - the credits are taken from them when they destroy the ship, and held
+//! until someone claims the bounty. This means claiming a bounty never creates credits out of nothing.
+//!
+//! Any other player can claim a bounty by destroying a ship owned or piloted by the wanted player, even if the wanted
+//! player is offline. Every player is sent the wanted list, which is shown to them while they're aboard a station.
+//!
+//! Bounties are stored in `world/bounties.json`.
+
+use std::fs;
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::block_events::BlockEventsSet,
+    chat::ServerSendChatMessageEvent,
+    economy::{
+        bounty::{WantedListEvent, WantedPlayer},
+        Credits,
+    },
+    entities::player::Player,
+    netty::{sync::events::server_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    physics::location::Location,
+    state::GameState,
+    structure::{
+        block_health::events::BlockTakeDamageEvent, ownership::StructureOwnership, shared::MeltingDown, ship::pilot::Pilot, ship::Ship,
+        station::Station,
+    },
+};
+use renet2::ClientId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    chat::{ChatCommandSet, ChatCommands, PlayerChatCommandEvent},
+    universe::{sector_rules::SectorRulesLookup, spawners::pirate::Pirate},
+};
+
+const BOUNTIES_PATH: &str = "world/bounties.json";
+
+/// How many credits a player is fined (and added to their bounty) for every ship they destroy in policed space
+const BOUNTY_PER_SHIP_DESTROYED: u64 = 5_000;
+
+/// How long (in seconds) after a player last damaged a ship they are still blamed for destroying it
+const ATTACK_MEMORY_SECS: f64 = 30.0;
+
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+/// Every player with a bounty on them, and the credits held for each bounty
+struct Bounties {
+    wanted: Vec<WantedPlayer>,
+}
+
+impl Bounties {
+    /// Takes up to [`BOUNTY_PER_SHIP_DESTROYED`] from this player's credits and adds it to their bounty.
+    ///
+    /// Returns the amount taken and their new bounty.
+    fn fine(&mut self, name: &str, credits: &mut Credits) -> (u64, u64) {
+        let fine = credits.amount().min(BOUNTY_PER_SHIP_DESTROYED);
+        credits.decrease(fine);
+
+        let bounty = match self.wanted.iter_mut().find(|w| w.name == name) {
+            Some(wanted) => {
+                wanted.bounty = wanted.bounty.saturating_add(fine);
+                wanted.bounty
+            }
+            None => {
+                if fine != 0 {
+                    self.wanted.push(WantedPlayer {
+                        name: name.to_owned(),
+                        bounty: fine,
+                    });
+                }
+                fine
+            }
+        };
+
+        (fine, bounty)
+    }
+
+    /// Removes the bounty on this player, returning the credits held for it
+    fn claim(&mut self, name: &str) -> Option<u64> {
+        let idx = self.wanted.iter().position(|w| w.name == name)?;
+
+        Some(self.wanted.remove(idx).bounty)
+    }
+
+    fn wanted_list_event(&self) -> WantedListEvent {
+        let mut wanted = self.wanted.clone();
+        wanted.sort_by(|a, b| b.bounty.cmp(&a.bounty));
+
+        WantedListEvent { wanted }
+    }
+
+    fn save(&self) {
+        let json = serde_json::to_string_pretty(self).expect("Bounties are always valid json");
+
+        if let Err(e) = fs::write(BOUNTIES_PATH, json) {
+            error!("Unable to save bounties to {BOUNTIES_PATH}.\n{e:?}");
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Reflect)]
+/// The last player to damage this ship
+struct LastAttacker {
+    player_name: String,
+    time: f64,
+}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+fn record_attackers(
+    mut commands: Commands,
+    mut evr_block_take_damage: EventReader<BlockTakeDamageEvent>,
+    q_pilot: Query<&Pilot>,
+    q_ownership: Query<&StructureOwnership>,
+    q_player: Query<&Player>,
+    q_ship: Query<(), With<Ship>>,
+    time: Res<Time>,
+) {
+    for ev in evr_block_take_damage.read() {
+        let Some(causer) = ev.causer else {
+            continue;
+        };
+
+        if causer == ev.structure_entity || !q_ship.contains(ev.structure_entity) {
+            continue;
+        }
+
+        // Whoever is flying the attacking ship is to blame, otherwise whoever owns it.
+        let player_name = q_pilot
+            .get(causer)
+            .ok()
+            .and_then(|pilot| q_player.get(pilot.entity).ok())
+            .map(|player| player.name().to_owned())
+            .or_else(|| q_ownership.get(causer).ok().map(|ownership| ownership.owner().to_owned()));
+
+        let Some(player_name) = player_name else {
+            continue;
+        };
+
+        commands.entity(ev.structure_entity).insert(LastAttacker {
+            player_name,
+            time: time.elapsed_secs_f64(),
+        });
+    }
+}
+
+/// The name of the player whose bounty is claimed by destroying this ship, if there is one.
+///
+/// The pilot's bounty is claimed first, then the owner's - who doesn't have to be online.
+fn wanted_player(
+    pilot: Option<&Pilot>,
+    ownership: Option<&StructureOwnership>,
+    bounties: &Bounties,
+    q_players: &Query<(Entity, &Player, &mut Credits)>,
+) -> Option<String> {
+    let is_wanted = |name: &str| bounties.wanted.iter().any(|w| w.name == name);
+
+    pilot
+        .and_then(|p| q_players.get(p.entity).ok())
+        .map(|(_, player, _)| player.name().to_owned())
+        .filter(|name| is_wanted(name))
+        .or_else(|| ownership.map(|o| o.owner().to_owned()).filter(|name| is_wanted(name)))
+}
+
+fn on_ship_destroyed(
+    q_destroyed: Query<
+        (&Location, &LastAttacker, Option<&Pilot>, Option<&StructureOwnership>, Has<Pirate>),
+        (Added<MeltingDown>, With<Ship>),
+    >,
+    mut q_players: Query<(Entity, &Player, &mut Credits)>,
+    mut bounties: ResMut<Bounties>,
+    sector_rules: SectorRulesLookup,
+    time: Res<Time>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut nevw_wanted_list: NettyEventWriter<WantedListEvent>,
+) {
+    let mut changed = false;
+
+    for (location, last_attacker, pilot, ownership, is_pirate) in q_destroyed.iter() {
+        if time.elapsed_secs_f64() - last_attacker.time > ATTACK_MEMORY_SECS {
+            continue;
+        }
+
+        let Some((attacker_ent, attacker_id)) = q_players
+            .iter()
+            .find(|(_, player, _)| player.name() == last_attacker.player_name)
+            .map(|(e, player, _)| (e, player.id()))
+        else {
+            continue;
+        };
+
+        if let Some(wanted_name) = wanted_player(pilot, ownership, &bounties, &q_players).filter(|name| *name != last_attacker.player_name)
+        {
+            let Some(amount) = bounties.claim(&wanted_name) else {
+                continue;
+            };
+            changed = true;
+
+            if let Ok((_, _, mut credits)) = q_players.get_mut(attacker_ent) {
+                credits.increase(amount);
+            }
+
+            nevw_chat.broadcast(ServerSendChatMessageEvent {
+                sender: None,
+                message: format!("{} claimed the {amount} credit bounty on {wanted_name}.", last_attacker.player_name),
+            });
+
+            continue;
+        }
+
+        // Players can scrap their own ships without being fined for it
+        let own_ship = ownership.is_some_and(|o| o.is_owner(&last_attacker.player_name));
+
+        if is_pirate || own_ship || !sector_rules.rules_at(location.sector()).police_response {
+            continue;
+        }
+
+        let Ok((_, _, mut attacker_credits)) = q_players.get_mut(attacker_ent) else {
+            continue;
+        };
+
+        let (fine, bounty) = bounties.fine(&last_attacker.player_name, &mut attacker_credits);
+        changed = true;
+
+        reply(
+            &mut nevw_chat,
+            attacker_id,
+            format!("You destroyed a ship in policed space and were fined {fine} credits. There is now a {bounty} credit bounty on you."),
+        );
+    }
+
+    if changed {
+        bounties.save();
+        nevw_wanted_list.broadcast(bounties.wanted_list_event());
+    }
+}
+
+fn send_wanted_list_on_join(
+    q_joined: Query<&Player, Added<Player>>,
+    bounties: Res<Bounties>,
+    mut nevw_wanted_list: NettyEventWriter<WantedListEvent>,
+) {
+    for player in q_joined.iter() {
+        nevw_wanted_list.send(bounties.wanted_list_event(), player.id());
+    }
+}
+
+fn register_chat_commands(mut chat_commands: ResMut<ChatCommands>) {
+    chat_commands.add("wanted");
+}
+
+fn on_wanted_command(
+    mut evr_command: EventReader<PlayerChatCommandEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    q_parent: Query<&Parent, With<Player>>,
+    q_station: Query<(), With<Station>>,
+    bounties: Res<Bounties>,
+) {
+    for ev in evr_command.read() {
+        if ev.name != "wanted" {
+            continue;
+        }
+
+        if !q_parent.get(ev.player_entity).is_ok_and(|p| q_station.contains(p.get())) {
+            reply(&mut nevw_chat, ev.client_id, "The wanted list can only be viewed from a station.");
+            continue;
+        }
+
+        let wanted = bounties.wanted_list_event().wanted;
+
+        if wanted.is_empty() {
+            reply(&mut nevw_chat, ev.client_id, "Nobody is wanted right now.");
+            continue;
+        }
+
+        let list = wanted
+            .into_iter()
+            .map(|w| format!("{} - {} credits", w.name, w.bounty))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        reply(&mut nevw_chat, ev.client_id, format!("Wanted:\n{list}"));
+    }
+}
+
+fn load_bounties(mut commands: Commands) {
+    let bounties = fs::read_to_string(BOUNTIES_PATH)
+        .ok()
+        .map(|json| {
+            serde_json::from_str::<Bounties>(&json).unwrap_or_else(|e| {
+                error!("Invalid bounties in {BOUNTIES_PATH} - ignoring them.\n{e:?}");
+                Bounties::default()
+            })
+        })
+        .unwrap_or_default();
+
+    commands.insert_resource(bounties);
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<Bounties>()
+        .add_systems(OnEnter(GameState::PostLoading), load_bounties)
+        .add_systems(Startup, register_chat_commands)
+        .add_systems(
+            Update,
+            record_attackers
+                .in_set(BlockEventsSet::ProcessEvents)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            Update,
+            (
+                on_ship_destroyed.after(record_attackers),
+                send_wanted_list_on_join,
+                on_wanted_command.after(ChatCommandSet::SendCommandEvents),
+            )
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .register_type::<LastAttacker>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounty_is_paid_for_by_the_wanted_player() {
+        let mut bounties = Bounties::default();
+        let mut credits = Credits::new(BOUNTY_PER_SHIP_DESTROYED + 100);
+
+        assert_eq!(
+            bounties.fine("a", &mut credits),
+            (BOUNTY_PER_SHIP_DESTROYED, BOUNTY_PER_SHIP_DESTROYED)
+        );
+        assert_eq!(credits.amount(), 100);
+
+        // Can only be fined what they have
+        assert_eq!(bounties.fine("a", &mut credits), (100, BOUNTY_PER_SHIP_DESTROYED + 100));
+        assert_eq!(credits.amount(), 0);
+
+        assert_eq!(bounties.claim("a"), Some(BOUNTY_PER_SHIP_DESTROYED + 100));
+        assert_eq!(bounties.claim("a"), None);
+    }
+}
@@ -0,0 +1,818 @@
+//! Runs the server-wide market that players trade on through market terminals.
+//!
+//! Everything an order could need is taken when it's posted - buy orders take the credits for every item they're
+//! for, and sell orders take the items from the player's inventory. Whenever a new order is posted, it's filled as
+//! much as possible by the existing orders offering the best price, at the price of those existing orders. Whatever
+//! isn't filled stays on the market until someone else fills it or its owner cancels it.
+//!
+//! Everything players are owed from their orders (and from cancelling them) is kept until they next use a market
+//! terminal. Credits are given to the player, and items are put into the storage blocks of the terminal's station.
+//! Anything that doesn't fit is kept for next time.
+//!
+//! Items with data (such as damaged tools or keycards) can't be sold, since their data can't be kept on the market. Only
+//! stacks without any data are taken for sell orders.
+//!
+//! Orders and what players are owed are stored in `world/market.json`, so trades can happen while players are offline.
+//! Changes are saved every few seconds rather than after every trade.
+
+use std::{fs, time::Duration};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        data::BlockData,
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    economy::{
+        market::{
+            CancelMarketOrderEvent, MarketOrder, MarketOrderKind, MarketOrdersChangedEvent, MarketOrdersEvent, OpenMarketEvent,
+            PostMarketOrderEvent, MARKET_TERMINAL_BLOCK, MAX_MARKET_ORDER_QUANTITY, MAX_OPEN_MARKET_ORDERS,
+        },
+        Credits,
+    },
+    entities::player::Player,
+    inventory::{itemstack::ItemShouldHaveData, Inventory},
+    item::Item,
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::{Structure, StructureBlock},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::station::Station,
+};
+use renet2::ClientId;
+use serde::{Deserialize, Serialize};
+
+use crate::structure::ownership::{notify_no_permission, StructurePermissions};
+
+const MARKET_PATH: &str = "world/market.json";
+
+/// The block bought items are delivered to
+const STORAGE_BLOCK: &str = "cosmos:storage";
+
+/// Players can use market terminals from a little further than they can reach to account for latency
+const MAX_MARKET_DISTANCE: f32 = 12.0;
+
+/// How often the market is saved, if it has changed
+const SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// Everything a player is owed from their orders that they haven't collected yet
+struct MarketClaim {
+    owner: String,
+    credits: u64,
+    /// (unlocalized item name, quantity)
+    items: Vec<(String, u32)>,
+}
+
+impl MarketClaim {
+    fn add_items(&mut self, item: &str, quantity: u32) {
+        if let Some((_, q)) = self.items.iter_mut().find(|(x, _)| x == item) {
+            *q = q.saturating_add(quantity);
+        } else {
+            self.items.push((item.to_owned(), quantity));
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.credits == 0 && self.items.is_empty()
+    }
+}
+
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+/// Every open order, and everything players are owed from them
+struct Market {
+    next_order_id: u64,
+    orders: Vec<MarketOrder>,
+    claims: Vec<MarketClaim>,
+    /// The ids of orders that have changed since they were last sent to players
+    #[serde(skip)]
+    changed_orders: Vec<u64>,
+    /// If this has changed since it was last saved
+    #[serde(skip)]
+    needs_saved: bool,
+}
+
+impl Market {
+    fn claim_mut(&mut self, owner: &str) -> &mut MarketClaim {
+        self.needs_saved = true;
+
+        let idx = match self.claims.iter().position(|x| x.owner == owner) {
+            Some(idx) => idx,
+            None => {
+                self.claims.push(MarketClaim {
+                    owner: owner.to_owned(),
+                    ..Default::default()
+                });
+                self.claims.len() - 1
+            }
+        };
+
+        &mut self.claims[idx]
+    }
+
+    /// Fills as much of this order as possible, then puts whatever is left of it on the market.
+    ///
+    /// The order's items or credits must have already been taken from its owner. Its id is ignored and replaced
+    /// with a new one.
+    ///
+    /// Returns the owners of every existing order that was traded with.
+    fn post(&mut self, mut order: MarketOrder) -> Vec<String> {
+        order.id = self.next_order_id;
+        self.next_order_id += 1;
+        self.needs_saved = true;
+
+        let mut traded_with = vec![];
+
+        while order.quantity != 0 {
+            // Best price first, then oldest first
+            let Some(idx) = self
+                .orders
+                .iter()
+                .enumerate()
+                .filter(|(_, o)| o.item == order.item && o.owner != order.owner && o.matches(order.kind, order.price_per_item))
+                .min_by_key(|(_, o)| match order.kind {
+                    MarketOrderKind::Buy => (o.price_per_item, o.id),
+                    MarketOrderKind::Sell => (u64::MAX - o.price_per_item, o.id),
+                })
+                .map(|(idx, _)| idx)
+            else {
+                break;
+            };
+
+            let resting = &mut self.orders[idx];
+            let quantity = resting.quantity.min(order.quantity);
+            let price = resting.price_per_item;
+
+            resting.quantity -= quantity;
+            order.quantity -= quantity;
+
+            let resting_owner = resting.owner.clone();
+            let resting_id = resting.id;
+            if resting.quantity == 0 {
+                self.orders.remove(idx);
+            }
+            self.mark_changed(resting_id);
+
+            let (buyer, seller) = match order.kind {
+                MarketOrderKind::Buy => (&order.owner, &resting_owner),
+                MarketOrderKind::Sell => (&resting_owner, &order.owner),
+            };
+
+            self.claim_mut(buyer).add_items(&order.item, quantity);
+
+            let earned = quantity as u64 * price;
+            let claim = self.claim_mut(seller);
+            claim.credits = claim.credits.saturating_add(earned);
+
+            // The buyer paid their own price up front, so gets back the difference if the seller asked for less
+            if order.kind == MarketOrderKind::Buy {
+                let refund = quantity as u64 * (order.price_per_item - price);
+                let claim = self.claim_mut(&order.owner);
+                claim.credits = claim.credits.saturating_add(refund);
+            }
+
+            if !traded_with.contains(&resting_owner) {
+                traded_with.push(resting_owner);
+            }
+        }
+
+        if order.quantity != 0 {
+            self.mark_changed(order.id);
+            self.orders.push(order);
+        }
+
+        traded_with
+    }
+
+    /// Removes this order, giving back whatever its owner paid for the part of it that wasn't filled.
+    ///
+    /// Returns false if this player has no order with this id.
+    fn cancel(&mut self, owner: &str, order_id: u64) -> bool {
+        let Some(idx) = self.orders.iter().position(|o| o.id == order_id && o.owner == owner) else {
+            return false;
+        };
+
+        let order = self.orders.remove(idx);
+        self.mark_changed(order.id);
+        let claim = self.claim_mut(owner);
+
+        match order.kind {
+            MarketOrderKind::Buy => claim.credits = claim.credits.saturating_add(order.quantity as u64 * order.price_per_item),
+            MarketOrderKind::Sell => claim.add_items(&order.item, order.quantity),
+        }
+
+        true
+    }
+
+    fn take_claim(&mut self, owner: &str) -> Option<MarketClaim> {
+        let idx = self.claims.iter().position(|x| x.owner == owner)?;
+        self.needs_saved = true;
+
+        Some(self.claims.remove(idx))
+    }
+
+    fn open_orders_of(&self, owner: &str) -> usize {
+        self.orders.iter().filter(|o| o.owner == owner).count()
+    }
+
+    fn mark_changed(&mut self, order_id: u64) {
+        if !self.changed_orders.contains(&order_id) {
+            self.changed_orders.push(order_id);
+        }
+    }
+
+    fn orders_event(&self) -> MarketOrdersEvent {
+        MarketOrdersEvent {
+            orders: self.orders.clone(),
+        }
+    }
+
+    /// Every order that has changed since this was last called, or `None` if nothing has
+    fn take_changes(&mut self) -> Option<MarketOrdersChangedEvent> {
+        if self.changed_orders.is_empty() {
+            return None;
+        }
+
+        let mut changes = MarketOrdersChangedEvent::default();
+
+        for id in std::mem::take(&mut self.changed_orders) {
+            match self.orders.iter().find(|o| o.id == id) {
+                Some(order) => changes.changed.push(order.clone()),
+                None => changes.removed.push(id),
+            }
+        }
+
+        Some(changes)
+    }
+
+    fn save(&self) {
+        let json = serde_json::to_string_pretty(self).expect("The market is always valid json");
+
+        if let Err(e) = fs::write(MARKET_PATH, json) {
+            error!("Unable to save the market to {MARKET_PATH}.\n{e:?}");
+        }
+    }
+}
+
+#[derive(Event, Debug)]
+/// Gives this player everything they are owed from the market, putting items into this station's storage
+struct DeliverMarketClaimEvent {
+    player: Entity,
+    station: Entity,
+}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+fn on_interact_market_terminal(
+    mut evr_block_interact: EventReader<BlockInteractEvent>,
+    q_structure: Query<(&Structure, Has<Station>)>,
+    q_player: Query<&Player>,
+    blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    market: Res<Market>,
+    mut nevw_open_market: NettyEventWriter<OpenMarketEvent>,
+    mut nevw_orders: NettyEventWriter<MarketOrdersEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut evw_deliver: EventWriter<DeliverMarketClaimEvent>,
+) {
+    for ev in evr_block_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok((structure, is_station)) = q_structure.get(s_block.structure()) else {
+            continue;
+        };
+
+        if structure.block_at(s_block.coords(), &blocks).unlocalized_name() != MARKET_TERMINAL_BLOCK {
+            continue;
+        }
+
+        let Ok(player) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        if !is_station {
+            reply(&mut nevw_chat, player.id(), "Market terminals only work on stations.");
+            continue;
+        }
+
+        if !permissions.can_use(ev.interactor, s_block.structure()) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        evw_deliver.send(DeliverMarketClaimEvent {
+            player: ev.interactor,
+            station: s_block.structure(),
+        });
+
+        nevw_open_market.send(OpenMarketEvent { terminal: s_block }, player.id());
+        nevw_orders.send(market.orders_event(), player.id());
+    }
+}
+
+/// Returns the player who sent this if they're close enough to a market terminal on a station they can use
+fn market_user(
+    terminal: StructureBlock,
+    client_id: ClientId,
+    lobby: &ServerLobby,
+    q_player_trans: &Query<&GlobalTransform, With<Player>>,
+    q_structure: &Query<(&Structure, &GlobalTransform), With<Station>>,
+    blocks: &Registry<Block>,
+    permissions: &StructurePermissions,
+    nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>,
+) -> Option<Entity> {
+    let Some(player_ent) = lobby.player_from_id(client_id) else {
+        warn!("Bad player - cid: {client_id}");
+        return None;
+    };
+
+    let player_g_trans = q_player_trans.get(player_ent).ok()?;
+    let (structure, structure_g_trans) = q_structure.get(terminal.structure()).ok()?;
+
+    let coords = terminal.coords();
+
+    if !structure.is_within_blocks(coords) || structure.block_at(coords, blocks).unlocalized_name() != MARKET_TERMINAL_BLOCK {
+        warn!("Player {player_ent:?} tried to use a market terminal where there is none.");
+        return None;
+    }
+
+    let block_position = structure_g_trans.transform_point(structure.block_relative_position(coords));
+    if block_position.distance_squared(player_g_trans.translation()) > MAX_MARKET_DISTANCE * MAX_MARKET_DISTANCE {
+        warn!("Player {player_ent:?} tried to use a market terminal that is too far away.");
+        return None;
+    }
+
+    if !permissions.can_use(player_ent, terminal.structure()) {
+        notify_no_permission(nevw_chat, client_id);
+        return None;
+    }
+
+    Some(player_ent)
+}
+
+/// How many of this item are in stacks without any data, which are the only ones that can be sold
+fn sellable_quantity(inventory: &Inventory, item: &Item) -> usize {
+    inventory
+        .iter()
+        .flatten()
+        .filter(|is| is.item_id() == item.id() && is.data_entity().is_none())
+        .map(|is| is.quantity() as usize)
+        .sum()
+}
+
+/// Takes this many of this item from stacks without any data. There must be enough (see [`sellable_quantity`]).
+fn take_sellable(inventory: &mut Inventory, item: &Item, mut quantity: usize, commands: &mut Commands) {
+    for slot in 0..inventory.len() {
+        if quantity == 0 {
+            break;
+        }
+
+        let Some(is) = inventory.itemstack_at(slot) else {
+            continue;
+        };
+
+        if is.item_id() != item.id() || is.data_entity().is_some() {
+            continue;
+        }
+
+        let amount = (is.quantity() as usize).min(quantity) as u16;
+        inventory.decrease_quantity_at(slot, amount, commands);
+        quantity -= amount as usize;
+    }
+}
+
+/// Tells the owners of these orders who are online that they have something to collect
+fn notify_traded_with(owners: &[String], q_players: &Query<&Player>, nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>) {
+    for player in q_players.iter().filter(|p| owners.iter().any(|o| o == p.name())) {
+        reply(
+            nevw_chat,
+            player.id(),
+            "One of your market orders was filled. Use any market terminal to collect what you're owed.",
+        );
+    }
+}
+
+fn on_post_market_order(
+    mut commands: Commands,
+    mut nevr_post: EventReader<NettyEventReceived<PostMarketOrderEvent>>,
+    lobby: Res<ServerLobby>,
+    q_player_trans: Query<&GlobalTransform, With<Player>>,
+    mut q_player: Query<(&Player, &mut Credits, &mut Inventory), Without<BlockData>>,
+    q_players: Query<&Player>,
+    q_structure: Query<(&Structure, &GlobalTransform), With<Station>>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    permissions: StructurePermissions,
+    mut market: ResMut<Market>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut evw_deliver: EventWriter<DeliverMarketClaimEvent>,
+) {
+    for ev in nevr_post.read() {
+        let Some(player_ent) = market_user(
+            ev.terminal,
+            ev.client_id,
+            &lobby,
+            &q_player_trans,
+            &q_structure,
+            &blocks,
+            &permissions,
+            &mut nevw_chat,
+        ) else {
+            continue;
+        };
+
+        let Ok((player, mut credits, mut inventory)) = q_player.get_mut(player_ent) else {
+            continue;
+        };
+
+        let Some(item) = items.try_from_numeric_id(ev.item_id) else {
+            warn!(
+                "Player {player_ent:?} tried to post a market order for an item that doesn't exist ({}).",
+                ev.item_id
+            );
+            continue;
+        };
+
+        if ev.quantity == 0 || ev.quantity > MAX_MARKET_ORDER_QUANTITY {
+            reply(
+                &mut nevw_chat,
+                ev.client_id,
+                format!("Orders must be for between 1 and {MAX_MARKET_ORDER_QUANTITY} items."),
+            );
+            continue;
+        }
+
+        let Some(total_price) = (ev.quantity as u64).checked_mul(ev.price_per_item).filter(|&x| x != 0) else {
+            reply(&mut nevw_chat, ev.client_id, "That price isn't allowed.");
+            continue;
+        };
+
+        if market.open_orders_of(player.name()) >= MAX_OPEN_MARKET_ORDERS {
+            reply(
+                &mut nevw_chat,
+                ev.client_id,
+                format!("You can only have {MAX_OPEN_MARKET_ORDERS} open orders at once. Cancel one to post another."),
+            );
+            continue;
+        }
+
+        match ev.kind {
+            MarketOrderKind::Buy => {
+                if !credits.decrease(total_price) {
+                    reply(
+                        &mut nevw_chat,
+                        ev.client_id,
+                        format!("This order costs {total_price} credits, but you only have {}.", credits.amount()),
+                    );
+                    continue;
+                }
+            }
+            MarketOrderKind::Sell => {
+                if sellable_quantity(&inventory, item) < ev.quantity as usize {
+                    let message = if inventory.can_take_item(item, ev.quantity as usize) {
+                        "Items with data (such as damaged tools or keycards) can't be sold on the market."
+                    } else {
+                        "You don't have enough of that item to sell."
+                    };
+
+                    reply(&mut nevw_chat, ev.client_id, message);
+                    continue;
+                }
+
+                take_sellable(&mut inventory, item, ev.quantity as usize, &mut commands);
+            }
+        }
+
+        let traded_with = market.post(MarketOrder {
+            id: 0,
+            owner: player.name().to_owned(),
+            kind: ev.kind,
+            item: item.unlocalized_name().to_owned(),
+            quantity: ev.quantity,
+            price_per_item: ev.price_per_item,
+        });
+
+        if traded_with.is_empty() {
+            reply(&mut nevw_chat, ev.client_id, "Order posted.");
+        } else {
+            reply(&mut nevw_chat, ev.client_id, "Order posted and filled by existing orders.");
+            notify_traded_with(&traded_with, &q_players, &mut nevw_chat);
+        }
+
+        evw_deliver.send(DeliverMarketClaimEvent {
+            player: player_ent,
+            station: ev.terminal.structure(),
+        });
+    }
+}
+
+fn on_cancel_market_order(
+    mut nevr_cancel: EventReader<NettyEventReceived<CancelMarketOrderEvent>>,
+    lobby: Res<ServerLobby>,
+    q_player_trans: Query<&GlobalTransform, With<Player>>,
+    q_player: Query<&Player>,
+    q_structure: Query<(&Structure, &GlobalTransform), With<Station>>,
+    blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    mut market: ResMut<Market>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut evw_deliver: EventWriter<DeliverMarketClaimEvent>,
+) {
+    for ev in nevr_cancel.read() {
+        let Some(player_ent) = market_user(
+            ev.terminal,
+            ev.client_id,
+            &lobby,
+            &q_player_trans,
+            &q_structure,
+            &blocks,
+            &permissions,
+            &mut nevw_chat,
+        ) else {
+            continue;
+        };
+
+        let Ok(player) = q_player.get(player_ent) else {
+            continue;
+        };
+
+        if !market.cancel(player.name(), ev.order_id) {
+            reply(&mut nevw_chat, ev.client_id, "That order has already been filled or cancelled.");
+            continue;
+        }
+
+        reply(&mut nevw_chat, ev.client_id, "Order cancelled.");
+
+        evw_deliver.send(DeliverMarketClaimEvent {
+            player: player_ent,
+            station: ev.terminal.structure(),
+        });
+    }
+}
+
+fn deliver_market_claims(
+    mut commands: Commands,
+    mut evr_deliver: EventReader<DeliverMarketClaimEvent>,
+    mut q_player: Query<(&Player, &mut Credits)>,
+    mut q_storage: Query<(&BlockData, &mut Inventory)>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    needs_data: Res<ItemShouldHaveData>,
+    mut market: ResMut<Market>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    let Some(storage_block) = blocks.from_id(STORAGE_BLOCK) else {
+        return;
+    };
+
+    for ev in evr_deliver.read() {
+        let Ok((player, mut credits)) = q_player.get_mut(ev.player) else {
+            continue;
+        };
+
+        let Some(mut claim) = market.take_claim(player.name()) else {
+            continue;
+        };
+
+        let delivered_credits = claim.credits;
+        credits.increase(claim.credits);
+        claim.credits = 0;
+
+        let mut storages = q_storage
+            .iter_mut()
+            .filter(|(block_data, _)| {
+                block_data.identifier.block.structure() == ev.station && block_data.identifier.block_id == storage_block.id()
+            })
+            .map(|(_, inventory)| inventory)
+            .collect::<Vec<_>>();
+
+        let mut delivered_items = 0;
+
+        for (item_name, quantity) in claim.items.iter_mut() {
+            // Items that no longer exist are kept in case they come back
+            let Some(item) = items.from_id(item_name) else {
+                continue;
+            };
+
+            for inventory in storages.iter_mut() {
+                while *quantity != 0 {
+                    let batch = (*quantity).min(u16::MAX as u32) as u16;
+                    let (leftover, _) = inventory.insert_item(item, batch, &mut commands, &needs_data);
+
+                    let inserted = (batch - leftover) as u32;
+                    *quantity -= inserted;
+                    delivered_items += inserted;
+
+                    if leftover != 0 {
+                        break;
+                    }
+                }
+
+                if *quantity == 0 {
+                    break;
+                }
+            }
+        }
+
+        claim.items.retain(|(_, quantity)| *quantity != 0);
+
+        let items_left = !claim.items.is_empty();
+        if !claim.is_empty() {
+            market.claims.push(claim);
+        }
+
+        if delivered_credits != 0 {
+            reply(
+                &mut nevw_chat,
+                player.id(),
+                format!("Collected {delivered_credits} credits from the market."),
+            );
+        }
+
+        if delivered_items != 0 {
+            reply(
+                &mut nevw_chat,
+                player.id(),
+                format!("Delivered {delivered_items} items from the market to this station's storage."),
+            );
+        }
+
+        if items_left {
+            reply(
+                &mut nevw_chat,
+                player.id(),
+                "There wasn't enough storage on this station for all your market items. The rest will be delivered next time.",
+            );
+        }
+    }
+}
+
+fn broadcast_order_changes(mut market: ResMut<Market>, mut nevw_changed: NettyEventWriter<MarketOrdersChangedEvent>) {
+    if let Some(changes) = market.take_changes() {
+        nevw_changed.broadcast(changes);
+    }
+}
+
+fn save_market(mut market: ResMut<Market>) {
+    if market.needs_saved {
+        market.save();
+        market.needs_saved = false;
+    }
+}
+
+fn save_market_on_exit(mut evr_app_exit: EventReader<AppExit>, market: ResMut<Market>) {
+    if evr_app_exit.read().next().is_some() {
+        save_market(market);
+    }
+}
+
+fn load_market(mut commands: Commands) {
+    let market = fs::read_to_string(MARKET_PATH)
+        .ok()
+        .map(|json| {
+            serde_json::from_str::<Market>(&json).unwrap_or_else(|e| {
+                error!("Invalid market in {MARKET_PATH} - ignoring it.\n{e:?}");
+                Market::default()
+            })
+        })
+        .unwrap_or_default();
+
+    commands.insert_resource(market);
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<Market>()
+        .add_event::<DeliverMarketClaimEvent>()
+        .add_systems(OnEnter(GameState::PostLoading), load_market)
+        .add_systems(
+            Update,
+            (
+                on_interact_market_terminal,
+                on_post_market_order,
+                on_cancel_market_order,
+                deliver_market_claims,
+                broadcast_order_changes,
+                save_market.run_if(on_timer(SAVE_INTERVAL)),
+            )
+                .chain()
+                .in_set(BlockEventsSet::ProcessEvents)
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(Last, save_market_on_exit);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(owner: &str, kind: MarketOrderKind, quantity: u32, price_per_item: u64) -> MarketOrder {
+        MarketOrder {
+            id: 0,
+            owner: owner.into(),
+            kind,
+            item: "cosmos:iron_bar".into(),
+            quantity,
+            price_per_item,
+        }
+    }
+
+    fn claim(market: &Market, owner: &str) -> MarketClaim {
+        market.claims.iter().find(|x| x.owner == owner).cloned().unwrap_or_default()
+    }
+
+    #[test]
+    fn buy_fills_cheapest_sell_first() {
+        let mut market = Market::default();
+
+        market.post(order("a", MarketOrderKind::Sell, 10, 20));
+        market.post(order("b", MarketOrderKind::Sell, 10, 10));
+
+        let traded_with = market.post(order("c", MarketOrderKind::Buy, 15, 25));
+
+        assert_eq!(traded_with, vec!["b".to_owned(), "a".to_owned()]);
+        assert_eq!(market.orders.len(), 1);
+        assert_eq!(market.orders[0].owner, "a");
+        assert_eq!(market.orders[0].quantity, 5);
+
+        assert_eq!(claim(&market, "b").credits, 100);
+        assert_eq!(claim(&market, "a").credits, 100);
+
+        // Paid 15 * 25 up front, but only spent 10 * 10 + 5 * 20
+        let buyer = claim(&market, "c");
+        assert_eq!(buyer.credits, 15 * 25 - 200);
+        assert_eq!(buyer.items, vec![("cosmos:iron_bar".to_owned(), 15)]);
+    }
+
+    #[test]
+    fn sell_fills_at_buy_price() {
+        let mut market = Market::default();
+
+        market.post(order("a", MarketOrderKind::Buy, 5, 30));
+        market.post(order("b", MarketOrderKind::Buy, 5, 50));
+        market.post(order("c", MarketOrderKind::Sell, 8, 40));
+
+        // Only the buy order paying at least 40 can be filled
+        assert_eq!(claim(&market, "c").credits, 5 * 50);
+        assert_eq!(claim(&market, "b").items, vec![("cosmos:iron_bar".to_owned(), 5)]);
+        assert!(claim(&market, "a").is_empty());
+
+        let remaining_sell = market.orders.iter().find(|o| o.owner == "c").expect("Sell order should remain");
+        assert_eq!(remaining_sell.quantity, 3);
+    }
+
+    #[test]
+    fn cancel_refunds_unfilled_part() {
+        let mut market = Market::default();
+
+        market.post(order("a", MarketOrderKind::Buy, 10, 5));
+        market.post(order("b", MarketOrderKind::Sell, 4, 5));
+        market.post(order("b", MarketOrderKind::Sell, 3, 100));
+
+        let buy_id = market.orders.iter().find(|o| o.owner == "a").unwrap().id;
+        let sell_id = market.orders.iter().find(|o| o.owner == "b").unwrap().id;
+
+        assert!(!market.cancel("b", buy_id));
+        assert!(market.cancel("a", buy_id));
+        assert!(market.cancel("b", sell_id));
+        assert!(market.orders.is_empty());
+
+        assert_eq!(claim(&market, "a").credits, 6 * 5);
+        assert_eq!(claim(&market, "b").items, vec![("cosmos:iron_bar".to_owned(), 3)]);
+    }
+
+    #[test]
+    fn changes_include_filled_and_new_orders() {
+        let mut market = Market::default();
+
+        market.post(order("a", MarketOrderKind::Sell, 5, 10));
+        market.post(order("b", MarketOrderKind::Sell, 5, 20));
+        market.take_changes();
+
+        market.post(order("c", MarketOrderKind::Buy, 7, 20));
+
+        let changes = market.take_changes().expect("Orders changed");
+        assert_eq!(changes.removed, vec![0]);
+        assert_eq!(changes.changed.len(), 1);
+        assert_eq!(changes.changed[0].id, 1);
+        assert_eq!(changes.changed[0].quantity, 3);
+
+        assert!(market.take_changes().is_none());
+    }
+}
@@ -3,8 +3,14 @@ use cosmos_core::economy::Credits;
 
 use crate::persistence::make_persistent::{make_persistent, DefaultPersistentComponent};
 
+pub mod bounty;
+mod market;
+
 impl DefaultPersistentComponent for Credits {}
 
 pub(super) fn register(app: &mut App) {
     make_persistent::<Credits>(app);
+
+    bounty::register(app);
+    market::register(app);
 }
@@ -0,0 +1,225 @@
+//! Simple wander/flee/attack AI that drives creatures around
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use cosmos_core::{
+    entities::{
+        creature::{Creature, CreatureBehavior, CreatureType},
+        health::Health,
+        player::{spectator::Spectator, Player},
+    },
+    netty::system_sets::NetworkingSystemsSet,
+    physics::location::Location,
+    registry::Registry,
+    state::GameState,
+    structure::{planet::Planet, Structure},
+};
+use rand::Rng;
+
+use crate::universe::safe_zone::SpawnProtection;
+
+use super::planet_up;
+
+/// Creatures notice players within this distance
+const SENSE_RANGE: f32 = 32.0;
+/// Skittish creatures run away from players within this distance
+const FLEE_RANGE: f32 = 16.0;
+/// How far past the edge of its body a creature can reach to attack
+const ATTACK_REACH: f32 = 1.5;
+/// Seconds between each of a creature's attacks
+const ATTACK_COOLDOWN_SECS: f32 = 1.0;
+/// How much faster a creature moves while running away
+const FLEE_SPEED_MULTIPLIER: f32 = 1.5;
+/// How fast a creature jumps to get over blocks in its way
+const JUMP_SPEED: f32 = 6.0;
+/// How long (in seconds) a creature has to be stuck before it tries to jump
+const STUCK_JUMP_SECS: f32 = 0.4;
+/// How long (in seconds) a passive creature stays scared after being hurt
+pub(super) const HURT_FLEE_SECS: f32 = 8.0;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum AiState {
+    #[default]
+    Idle,
+    /// Walking in this world-space direction
+    Wandering(Vec3),
+    /// Running away from this entity
+    Fleeing(Entity),
+    /// Chasing & attacking this entity
+    Attacking(Entity),
+}
+
+#[derive(Component, Debug, Default)]
+/// What a creature is currently doing
+pub(super) struct CreatureAi {
+    state: AiState,
+    /// Seconds until the creature picks something new to do while it isn't fleeing or attacking
+    next_decision_secs: f32,
+    attack_cooldown_secs: f32,
+    /// How long the creature has been unable to move where it wants to
+    stuck_secs: f32,
+    /// How long the creature will stay scared from being hurt
+    pub(super) hurt_secs: f32,
+}
+
+#[derive(Event, Debug)]
+/// Sent whenever a creature attacks something
+pub struct CreatureAttackEvent {
+    /// The creature attacking
+    pub creature: Entity,
+    /// What the creature attacked
+    pub target: Entity,
+    /// How much damage the attack does
+    pub damage: f32,
+}
+
+fn decide_creature_actions(
+    time: Res<Time>,
+    mut q_creatures: Query<(&Creature, &Location, &mut CreatureAi)>,
+    q_players: Query<(Entity, &Location), (With<Player>, Without<Spectator>)>,
+    creature_types: Res<Registry<CreatureType>>,
+) {
+    let delta = time.delta_secs();
+    let mut rng = rand::thread_rng();
+
+    for (creature, location, mut ai) in q_creatures.iter_mut() {
+        let Some(creature_type) = creature_types.try_from_numeric_id(creature.creature_type) else {
+            continue;
+        };
+
+        ai.next_decision_secs -= delta;
+        ai.attack_cooldown_secs -= delta;
+        ai.hurt_secs -= delta;
+
+        let nearest_player = q_players
+            .iter()
+            .map(|(ent, loc)| (ent, loc.distance_sqrd(location)))
+            .filter(|(_, dist_sqrd)| *dist_sqrd < SENSE_RANGE * SENSE_RANGE)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+
+        let reaction = nearest_player.and_then(|(player, dist_sqrd)| match creature_type.properties().behavior {
+            CreatureBehavior::Hostile => Some(AiState::Attacking(player)),
+            CreatureBehavior::Skittish if dist_sqrd < FLEE_RANGE * FLEE_RANGE || ai.hurt_secs > 0.0 => Some(AiState::Fleeing(player)),
+            CreatureBehavior::Passive if ai.hurt_secs > 0.0 => Some(AiState::Fleeing(player)),
+            _ => None,
+        });
+
+        if let Some(reaction) = reaction {
+            ai.state = reaction;
+            continue;
+        }
+
+        if matches!(ai.state, AiState::Fleeing(_) | AiState::Attacking(_)) || ai.next_decision_secs <= 0.0 {
+            ai.next_decision_secs = rng.gen_range(2.0..6.0);
+
+            ai.state = if rng.gen_bool(0.4) {
+                AiState::Idle
+            } else {
+                AiState::Wandering(Vec3::new(
+                    rng.gen_range(-1.0..1.0),
+                    rng.gen_range(-1.0..1.0),
+                    rng.gen_range(-1.0..1.0),
+                ))
+            };
+        }
+    }
+}
+
+fn move_creatures(
+    time: Res<Time>,
+    mut q_creatures: Query<(Entity, &Creature, &Location, &mut Transform, &mut Velocity, &mut CreatureAi)>,
+    q_locations: Query<&Location>,
+    q_planets: Query<(Entity, &Location, &GlobalTransform, &Structure), With<Planet>>,
+    creature_types: Res<Registry<CreatureType>>,
+    mut evw_attack: EventWriter<CreatureAttackEvent>,
+) {
+    let delta = time.delta_secs();
+
+    for (ent, creature, location, mut transform, mut velocity, mut ai) in q_creatures.iter_mut() {
+        let Some(creature_type) = creature_types.try_from_numeric_id(creature.creature_type) else {
+            continue;
+        };
+
+        // Creatures that aren't on a planet just drift around
+        let Some((_, up)) = planet_up(location, &q_planets, creature_type.properties().half_size.max_element() * 4.0) else {
+            continue;
+        };
+
+        let props = creature_type.properties();
+
+        let (direction, speed) = match ai.state {
+            AiState::Idle => (Vec3::ZERO, 0.0),
+            AiState::Wandering(direction) => (direction, props.speed),
+            AiState::Fleeing(from) => match q_locations.get(from) {
+                Ok(from_loc) => (-location.relative_coords_to(from_loc), props.speed * FLEE_SPEED_MULTIPLIER),
+                Err(_) => (Vec3::ZERO, 0.0),
+            },
+            AiState::Attacking(target) => match q_locations.get(target) {
+                Ok(target_loc) => {
+                    let to_target = location.relative_coords_to(target_loc);
+
+                    if to_target.length() <= props.half_size.max_element() + ATTACK_REACH {
+                        if ai.attack_cooldown_secs <= 0.0 {
+                            ai.attack_cooldown_secs = ATTACK_COOLDOWN_SECS;
+
+                            evw_attack.send(CreatureAttackEvent {
+                                creature: ent,
+                                target,
+                                damage: props.attack_damage,
+                            });
+                        }
+
+                        (to_target, 0.0)
+                    } else {
+                        (to_target, props.speed)
+                    }
+                }
+                Err(_) => (Vec3::ZERO, 0.0),
+            },
+        };
+
+        // Creatures can only walk along the ground
+        let direction = (direction - up * direction.dot(up)).normalize_or_zero();
+
+        let vertical = up * velocity.linvel.dot(up);
+        let horizontal = velocity.linvel - vertical;
+
+        let mut new_vertical = vertical;
+
+        if speed > 0.0 && horizontal.length() < speed * 0.25 {
+            ai.stuck_secs += delta;
+
+            // Hop over whatever is in the way, as long as we're on the ground
+            if ai.stuck_secs >= STUCK_JUMP_SECS && vertical.length() < 0.5 {
+                new_vertical = up * JUMP_SPEED;
+                ai.stuck_secs = 0.0;
+            }
+        } else {
+            ai.stuck_secs = 0.0;
+        }
+
+        velocity.linvel = direction * speed + new_vertical;
+
+        if direction != Vec3::ZERO {
+            transform.look_to(direction, up);
+        }
+    }
+}
+
+fn apply_creature_attacks(mut evr_attack: EventReader<CreatureAttackEvent>, mut q_health: Query<&mut Health, Without<SpawnProtection>>) {
+    for ev in evr_attack.read() {
+        if let Ok(mut health) = q_health.get_mut(ev.target) {
+            health.take_damage(ev.damage);
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_event::<CreatureAttackEvent>().add_systems(
+        Update,
+        (decide_creature_actions, move_creatures, apply_creature_attacks)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,102 @@
+//! Hurting & killing creatures, and the items they drop when they die
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    entities::{
+        creature::{Creature, CreatureType},
+        health::Health,
+    },
+    inventory::{itemstack::ItemShouldHaveData, Inventory},
+    item::{physical_item::PhysicalItem, Item},
+    netty::system_sets::NetworkingSystemsSet,
+    persistence::LoadingDistance,
+    physics::location::Location,
+    projectiles::laser::{LaserCollideEvent, LaserSystemSet},
+    registry::Registry,
+    state::GameState,
+};
+use rand::Rng;
+
+use super::ai::{CreatureAi, HURT_FLEE_SECS};
+
+fn damage_creatures_from_lasers(mut evr_laser_hit: EventReader<LaserCollideEvent>, mut q_creature: Query<(&mut Health, &mut CreatureAi)>) {
+    for ev in evr_laser_hit.read() {
+        let Ok((mut health, mut ai)) = q_creature.get_mut(ev.entity_hit()) else {
+            continue;
+        };
+
+        health.take_damage(ev.laser_strength());
+        ai.hurt_secs = HURT_FLEE_SECS;
+    }
+}
+
+fn kill_creatures(
+    mut commands: Commands,
+    q_creatures: Query<(Entity, &Creature, &Health, &Location, &Velocity), (Changed<Health>, Without<NeedsDespawned>)>,
+    creature_types: Res<Registry<CreatureType>>,
+    items: Res<Registry<Item>>,
+    needs_data: Res<ItemShouldHaveData>,
+) {
+    let mut rng = rand::thread_rng();
+
+    for (ent, creature, health, location, velocity) in q_creatures.iter() {
+        if health.is_alive() {
+            continue;
+        }
+
+        commands.entity(ent).insert(NeedsDespawned);
+
+        let Some(creature_type) = creature_types.try_from_numeric_id(creature.creature_type) else {
+            continue;
+        };
+
+        for drop in creature_type.properties().drops.iter() {
+            if !rng.gen_bool(drop.chance.clamp(0.0, 1.0) as f64) {
+                continue;
+            }
+
+            let Some(item) = items.from_id(&drop.item) else {
+                continue;
+            };
+
+            let quantity = rng.gen_range(drop.min..=drop.max.max(drop.min));
+            if quantity == 0 {
+                continue;
+            }
+
+            let dropped_item_entity = commands
+                .spawn((
+                    PhysicalItem,
+                    *location,
+                    LoadingDistance::new(1, 2),
+                    Transform::default(),
+                    Velocity {
+                        linvel: velocity.linvel
+                            + Vec3::new(
+                                rand::random::<f32>() - 0.5,
+                                rand::random::<f32>() - 0.5,
+                                rand::random::<f32>() - 0.5,
+                            ) * 2.0,
+                        angvel: Vec3::ZERO,
+                    },
+                ))
+                .id();
+
+            let mut physical_item_inventory = Inventory::new("", 1, None, dropped_item_entity);
+            physical_item_inventory.insert_item(item, quantity.min(item.max_stack_size()), &mut commands, &needs_data);
+            commands.entity(dropped_item_entity).insert(physical_item_inventory);
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (damage_creatures_from_lasers.after(LaserSystemSet::SendHitEvents), kill_creatures)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,102 @@
+//! Server-side logic for the creatures that roam the surfaces of planets.
+//!
+//! Creature types are loaded from `assets/cosmos/creatures`, and are registered as `cosmos:<file name>`.
+//! Creatures are spawned around players walking on planets, and are never saved - they're despawned once no
+//! players are nearby.
+
+use std::{ffi::OsStr, fs};
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::Block,
+    entities::creature::{Creature, CreatureProperties, CreatureType},
+    item::Item,
+    physics::location::Location,
+    registry::Registry,
+    state::GameState,
+    structure::{planet::Planet, Structure},
+};
+use walkdir::WalkDir;
+
+use crate::persistence::{
+    saving::{NeedsSaved, SavingSystemSet, SAVING_SCHEDULE},
+    SerializedData,
+};
+
+mod ai;
+mod damage;
+mod spawning;
+
+fn load_creature_types(mut creature_types: ResMut<Registry<CreatureType>>, blocks: Res<Registry<Block>>, items: Res<Registry<Item>>) {
+    for entry in WalkDir::new("assets/cosmos/creatures").max_depth(1) {
+        let Ok(entry) = entry else {
+            continue;
+        };
+
+        let path = entry.path();
+        if path.is_dir() || path.extension().and_then(OsStr::to_str) != Some("json") {
+            continue;
+        }
+
+        let Some(name) = path.file_stem().and_then(OsStr::to_str) else {
+            continue;
+        };
+
+        let creature_json = fs::read(path).unwrap_or_else(|e| panic!("Unable to read creature file {path:?}\n{e:?}"));
+
+        let properties = serde_json::from_slice::<CreatureProperties>(&creature_json)
+            .unwrap_or_else(|e| panic!("Invalid creature json {path:?}\n{e:?}"));
+
+        if let Some(block) = properties.spawn_blocks.iter().find(|b| !blocks.contains(b)) {
+            error!("Unable to find block with id matching {block:?} in creature {path:?}");
+            continue;
+        }
+
+        if let Some(drop) = properties.drops.iter().find(|d| !items.contains(&d.item)) {
+            error!("Unable to find item with id matching {:?} in creature {path:?}", drop.item);
+            continue;
+        }
+
+        creature_types.register(CreatureType::new(format!("cosmos:{name}"), properties));
+    }
+
+    info!("Loaded {} creature types", creature_types.iter().count());
+}
+
+/// Finds the planet this location is on (or just above), and the world-space direction pointing away from that
+/// planet's surface.
+///
+/// `margin` is how far above the surface of the planet still counts as being on it.
+fn planet_up(
+    location: &Location,
+    q_planets: &Query<(Entity, &Location, &GlobalTransform, &Structure), With<Planet>>,
+    margin: f32,
+) -> Option<(Entity, Vec3)> {
+    q_planets.iter().find_map(|(ent, planet_loc, g_trans, structure)| {
+        let rotation = g_trans.compute_transform().rotation;
+        let relative = rotation.inverse() * planet_loc.relative_coords_to(location);
+
+        let half_size = structure.block_dimensions().x as f32 / 2.0;
+        if relative.abs().max_element() > half_size + margin {
+            return None;
+        }
+
+        Some((ent, rotation * Planet::planet_face_relative(relative).direction().as_vec3()))
+    })
+}
+
+// Creatures are respawned around players instead of being saved
+fn on_save_creature(mut query: Query<&mut SerializedData, (With<NeedsSaved>, With<Creature>)>) {
+    for mut sd in query.iter_mut() {
+        sd.set_should_save(false);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    spawning::register(app);
+    ai::register(app);
+    damage::register(app);
+
+    app.add_systems(OnEnter(GameState::PostLoading), load_creature_types)
+        .add_systems(SAVING_SCHEDULE, on_save_creature.in_set(SavingSystemSet::DoSaving));
+}
@@ -0,0 +1,223 @@
+//! Spawns creatures around players that are on planets, and despawns them once no players are nearby
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use cosmos_core::{
+    block::Block,
+    ecs::NeedsDespawned,
+    entities::{
+        creature::{Creature, CreatureType},
+        health::Health,
+        player::{spectator::Spectator, Player},
+    },
+    netty::system_sets::NetworkingSystemsSet,
+    persistence::LoadingDistance,
+    physics::location::Location,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        planet::{biosphere::BiosphereMarker, Planet},
+        Structure,
+    },
+};
+use rand::Rng;
+
+use super::{ai::CreatureAi, planet_up};
+
+/// How often (in seconds) creatures try to spawn around each player
+const SPAWN_INTERVAL_SECS: f32 = 4.0;
+/// No more creatures will spawn around a player once there are this many near them
+const MAX_CREATURES_PER_PLAYER: usize = 10;
+/// Creatures within this distance of a player count towards [`MAX_CREATURES_PER_PLAYER`]
+const CREATURE_CAP_RADIUS: f32 = 64.0;
+/// Creatures will spawn at least this far from a player, so players don't see them pop into existence
+const MIN_SPAWN_DISTANCE: f32 = 24.0;
+/// Creatures will spawn at most this far from a player
+const MAX_SPAWN_DISTANCE: f32 = 48.0;
+/// How far above & below a spawn point the surface is searched for
+const SURFACE_SEARCH_DISTANCE: f32 = 32.0;
+/// Creatures farther than this from every player are despawned
+const DESPAWN_DISTANCE: f32 = 128.0;
+
+#[derive(Resource, Debug)]
+struct CreatureSpawnTimer(Timer);
+
+impl Default for CreatureSpawnTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(SPAWN_INTERVAL_SECS, TimerMode::Repeating))
+    }
+}
+
+/// Picks a random creature type that can spawn on this block, weighted by each creature's spawn weight
+fn pick_creature_type<'a>(
+    creature_types: &'a Registry<CreatureType>,
+    biosphere: &str,
+    ground: &Block,
+    rng: &mut impl Rng,
+) -> Option<&'a CreatureType> {
+    let candidates = creature_types
+        .iter()
+        .filter(|c| {
+            let props = c.properties();
+            props.biospheres.iter().any(|b| b == biosphere) && props.spawn_blocks.iter().any(|b| b == ground.unlocalized_name())
+        })
+        .collect::<Vec<_>>();
+
+    let total_weight: u32 = candidates.iter().map(|c| c.properties().spawn_weight).sum();
+    if total_weight == 0 {
+        return None;
+    }
+
+    let mut pick = rng.gen_range(0..total_weight);
+
+    candidates.into_iter().find(|c| {
+        let weight = c.properties().spawn_weight;
+        if pick < weight {
+            true
+        } else {
+            pick -= weight;
+            false
+        }
+    })
+}
+
+/// Searches down from above this point for the first solid block with air above it.
+///
+/// Returns the ground block and the planet-relative position just above it.
+fn find_ground<'a>(structure: &Structure, start: Vec3, up: Vec3, blocks: &'a Registry<Block>) -> Option<(&'a Block, Vec3)> {
+    let air = blocks.from_id("cosmos:air")?;
+
+    let mut previous_was_air = false;
+
+    for i in 0..(SURFACE_SEARCH_DISTANCE * 2.0) as i32 {
+        let pos = start - up * i as f32;
+
+        let Ok(coords) = structure.relative_coords_to_local_coords_checked(pos.x, pos.y, pos.z) else {
+            previous_was_air = false;
+            continue;
+        };
+
+        let block = structure.block_at(coords, blocks);
+
+        if block == air {
+            previous_was_air = true;
+            continue;
+        }
+
+        if !previous_was_air || block.is_fluid() {
+            return None;
+        }
+
+        return Some((block, structure.block_relative_position(coords) + up));
+    }
+
+    None
+}
+
+fn spawn_creatures(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut timer: ResMut<CreatureSpawnTimer>,
+    q_players: Query<&Location, (With<Player>, Without<Spectator>)>,
+    q_planets: Query<(Entity, &Location, &GlobalTransform, &Structure), With<Planet>>,
+    q_biosphere: Query<&BiosphereMarker>,
+    q_creatures: Query<&Location, With<Creature>>,
+    creature_types: Res<Registry<CreatureType>>,
+    blocks: Res<Registry<Block>>,
+) {
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() {
+        return;
+    }
+
+    let mut rng = rand::thread_rng();
+
+    for player_loc in q_players.iter() {
+        if q_creatures
+            .iter()
+            .filter(|loc| loc.distance_sqrd(player_loc) < CREATURE_CAP_RADIUS * CREATURE_CAP_RADIUS)
+            .count()
+            >= MAX_CREATURES_PER_PLAYER
+        {
+            continue;
+        }
+
+        let Some((planet_ent, up)) = planet_up(player_loc, &q_planets, SURFACE_SEARCH_DISTANCE) else {
+            continue;
+        };
+
+        let Ok((_, planet_loc, planet_g_trans, structure)) = q_planets.get(planet_ent) else {
+            continue;
+        };
+
+        let Ok(biosphere) = q_biosphere.get(planet_ent) else {
+            continue;
+        };
+
+        let planet_rot = planet_g_trans.compute_transform().rotation;
+        let local_up = planet_rot.inverse() * up;
+        let player_relative = planet_rot.inverse() * planet_loc.relative_coords_to(player_loc);
+
+        let (tangent_a, tangent_b) = local_up.any_orthonormal_pair();
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let distance = rng.gen_range(MIN_SPAWN_DISTANCE..MAX_SPAWN_DISTANCE);
+        let offset = (tangent_a * angle.cos() + tangent_b * angle.sin()) * distance;
+
+        let search_start = player_relative + offset + local_up * SURFACE_SEARCH_DISTANCE;
+
+        let Some((ground, spawn_relative)) = find_ground(structure, search_start, local_up, &blocks) else {
+            continue;
+        };
+
+        let Some(creature_type) = pick_creature_type(&creature_types, biosphere.biosphere_name(), ground, &mut rng) else {
+            continue;
+        };
+
+        let props = creature_type.properties();
+        let group_size = rng.gen_range(1..=props.max_group_size.max(1));
+
+        for _ in 0..group_size {
+            let jitter = (tangent_a * rng.gen_range(-2.0..2.0)) + (tangent_b * rng.gen_range(-2.0..2.0));
+            let relative = spawn_relative + jitter + local_up * props.half_size.y;
+
+            commands.spawn((
+                Creature {
+                    creature_type: creature_type.id(),
+                },
+                Health::new(props.max_health),
+                CreatureAi::default(),
+                *planet_loc + planet_rot * relative,
+                Transform::from_rotation(Quat::from_rotation_arc(Vec3::Y, up)),
+                Velocity::default(),
+                LoadingDistance::new(1, 2),
+            ));
+        }
+    }
+}
+
+fn despawn_distant_creatures(
+    mut commands: Commands,
+    timer: Res<CreatureSpawnTimer>,
+    q_players: Query<&Location, With<Player>>,
+    q_creatures: Query<(Entity, &Location), (With<Creature>, Without<NeedsDespawned>)>,
+) {
+    if !timer.0.just_finished() {
+        return;
+    }
+
+    for (ent, loc) in q_creatures.iter() {
+        if !q_players.iter().any(|p| p.distance_sqrd(loc) < DESPAWN_DISTANCE * DESPAWN_DISTANCE) {
+            commands.entity(ent).insert(NeedsDespawned);
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<CreatureSpawnTimer>().add_systems(
+        Update,
+        (spawn_creatures, despawn_distant_creatures)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
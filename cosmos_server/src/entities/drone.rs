@@ -0,0 +1,522 @@
+//! Server-side logic for the drones launched from drone bays.
+//!
+//! Every second, each drone bay with room for another drone looks for a job within [`DRONE_BAY_RANGE`] of it: either a
+//! ghost block of the structure's [`ConstructionSite`] or a damaged block, whose item is in the structure's storage.
+//! The drone flies to its job, takes one of the block's items from storage to weld or repair it, then flies back
+//! into its bay.
+//!
+//! Drones are never saved. They only take materials once they've finished their job, so nothing is lost if one is
+//! unloaded mid-flight.
+
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::{
+    prelude::*,
+    time::common_conditions::on_timer,
+    utils::{HashMap, HashSet},
+};
+use bevy_renet2::renet2::RenetServer;
+use cosmos_core::{
+    block::{
+        block_direction::{BlockDirection, ALL_BLOCK_DIRECTIONS},
+        block_events::BlockEventsSet,
+        block_face::BlockFace,
+        data::BlockData,
+        specific_blocks::drone_bay::{DRONE_BAY_BLOCK, DRONE_BAY_RANGE, MAX_DRONES_PER_BAY},
+        Block,
+    },
+    blockitems::BlockItems,
+    ecs::NeedsDespawned,
+    entities::drone::{Drone, DroneActivity, DRONE_SPEED},
+    events::block_events::BlockChangedEvent,
+    inventory::Inventory,
+    item::Item,
+    netty::{
+        network_stats::{NettyMessage, NettyServerMessages},
+        server_reliable_messages::{BlockHealthUpdate, ServerReliableMessages},
+        system_sets::NetworkingSystemsSet,
+        NettyChannelServer,
+    },
+    persistence::LoadingDistance,
+    physics::location::SetPosition,
+    prelude::{BlockCoordinate, StructureBlock},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{block_health::events::BlockTakeDamageEvent, construction::ConstructionSite, Structure},
+};
+
+const STORAGE_BLOCK: &str = "cosmos:storage";
+
+/// How long (in seconds) it takes a drone to weld or repair a block
+const DRONE_WORK_SECS: f32 = 2.0;
+
+/// How far (in blocks) past the edge of a structure drones fly to get around it
+const HULL_CLEARANCE: f32 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DroneJob {
+    /// Weld the ghost block at these coordinates
+    Build(BlockCoordinate),
+    /// Repair the damaged block at these coordinates
+    Repair(BlockCoordinate),
+}
+
+impl DroneJob {
+    fn coords(&self) -> BlockCoordinate {
+        match self {
+            Self::Build(coords) | Self::Repair(coords) => *coords,
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+struct DroneAi {
+    /// The job this drone is doing, or `None` if it's going back to its bay
+    job: Option<DroneJob>,
+    /// Structure-relative points this drone still has to fly through, in order
+    path: VecDeque<Vec3>,
+    /// How long this drone has been working on its job
+    work_secs: f32,
+}
+
+#[derive(Component, Debug, Default)]
+/// Blocks on this structure that have been damaged since it was loaded, and may need repairs
+struct DamagedBlocks(HashSet<BlockCoordinate>);
+
+fn track_damaged_blocks(
+    mut commands: Commands,
+    mut evr_block_damaged: EventReader<BlockTakeDamageEvent>,
+    mut q_damaged: Query<&mut DamagedBlocks>,
+) {
+    let mut newly_damaged = HashMap::<Entity, HashSet<BlockCoordinate>>::default();
+
+    for ev in evr_block_damaged.read() {
+        // Destroyed blocks can't be repaired
+        if ev.new_health <= 0.0 {
+            continue;
+        }
+
+        match q_damaged.get_mut(ev.structure_entity) {
+            Ok(mut damaged) => {
+                damaged.0.insert(ev.block.coords());
+            }
+            Err(_) => {
+                newly_damaged.entry(ev.structure_entity).or_default().insert(ev.block.coords());
+            }
+        }
+    }
+
+    for (structure_ent, damaged) in newly_damaged {
+        if let Some(mut ecmds) = commands.get_entity(structure_ent) {
+            ecmds.insert(DamagedBlocks(damaged));
+        }
+    }
+}
+
+/// Returns the block here if it is damaged
+fn damaged_block<'a>(structure: &Structure, coords: BlockCoordinate, blocks: &'a Registry<Block>) -> Option<&'a Block> {
+    let block = structure.block_at(coords, blocks);
+
+    (!block.is_empty() && structure.get_block_health(coords, blocks) < block.hardness()).then_some(block)
+}
+
+/// Finds a side of this block a drone can hover next to while working on it
+fn open_side(structure: &Structure, coords: BlockCoordinate, blocks: &Registry<Block>) -> Option<BlockDirection> {
+    ALL_BLOCK_DIRECTIONS.into_iter().find(|direction| {
+        BlockCoordinate::try_from(coords + direction.to_coordinates())
+            .ok()
+            .filter(|&neighbor| structure.is_within_blocks(neighbor))
+            // Anything past the edge of the structure is open space
+            .is_none_or(|neighbor| structure.block_at(neighbor, blocks).is_empty())
+    })
+}
+
+/// Returns true if nothing is in the way of flying between these structure-relative points
+fn segment_clear(structure: &Structure, from: Vec3, to: Vec3, blocks: &Registry<Block>) -> bool {
+    let delta = to - from;
+    let length = delta.length();
+
+    length < f32::EPSILON
+        || structure
+            .raycast_iter(from, delta / length, length, false)
+            .all(|coords| structure.block_at(coords, blocks).is_fluid())
+}
+
+/// Plans a path between these structure-relative points that doesn't go through any blocks.
+///
+/// If the straight line between them is blocked, the drone flies out past the edge of the structure in the `outward`
+/// direction, across to the destination, then back in.
+fn plan_path(structure: &Structure, from: Vec3, to: Vec3, outward: BlockDirection, blocks: &Registry<Block>) -> Vec<Vec3> {
+    if segment_clear(structure, from, to, blocks) {
+        return vec![to];
+    }
+
+    let dims = structure.block_dimensions();
+    let edges = Vec3::new(dims.x as f32, dims.y as f32, dims.z as f32) / 2.0 + HULL_CLEARANCE;
+    let outward = outward.as_vec3();
+
+    // Replaces the outward axis of this point with the structure's edge on that side
+    let lift = |point: Vec3| point - point * outward.abs() + outward * edges;
+
+    vec![lift(from), lift(to), to]
+}
+
+/// Finds the closest job to this bay that no other drone has taken, along with the side of the block to work from
+fn find_job(
+    structure: &Structure,
+    bay: BlockCoordinate,
+    site: Option<&ConstructionSite>,
+    damaged: Option<&DamagedBlocks>,
+    taken: &HashSet<BlockCoordinate>,
+    has_item: impl Fn(&Block) -> bool,
+    blocks: &Registry<Block>,
+) -> Option<(DroneJob, BlockDirection)> {
+    let ghosts = site.into_iter().flat_map(|site| site.ghosts()).filter_map(|(coords, block, _)| {
+        structure
+            .block_at(coords, blocks)
+            .is_empty()
+            .then(|| blocks.from_id(block))
+            .flatten()
+            .map(|block| (DroneJob::Build(coords), block))
+    });
+
+    let repairs = damaged
+        .into_iter()
+        .flat_map(|damaged| damaged.0.iter())
+        .filter_map(|&coords| damaged_block(structure, coords, blocks).map(|block| (DroneJob::Repair(coords), block)));
+
+    let bay_pos = structure.block_relative_position(bay);
+
+    ghosts
+        .chain(repairs)
+        .filter(|(job, _)| !taken.contains(&job.coords()))
+        .map(|(job, block)| (job, block, structure.block_relative_position(job.coords()).distance(bay_pos)))
+        .filter(|(_, block, distance)| *distance <= DRONE_BAY_RANGE && has_item(block))
+        .filter_map(|(job, _, distance)| open_side(structure, job.coords(), blocks).map(|side| (job, side, distance)))
+        .min_by(|a, b| a.2.total_cmp(&b.2))
+        .map(|(job, side, _)| (job, side))
+}
+
+fn structure_storages<'a>(
+    q_storage: &'a mut Query<(&BlockData, &mut Inventory)>,
+    structure: Entity,
+    storage_block: &'a Block,
+) -> impl Iterator<Item = Mut<'a, Inventory>> {
+    q_storage
+        .iter_mut()
+        .filter(move |(block_data, _)| {
+            block_data.identifier.block.structure() == structure && block_data.identifier.block_id == storage_block.id()
+        })
+        .map(|(_, inventory)| inventory)
+}
+
+/// Takes one of this block's items out of the structure's storage, returning false if there aren't any
+fn take_block_item(
+    commands: &mut Commands,
+    q_storage: &mut Query<(&BlockData, &mut Inventory)>,
+    structure: Entity,
+    storage_block: &Block,
+    block: &Block,
+    items: &Registry<Item>,
+    block_items: &BlockItems,
+) -> bool {
+    let Some(item) = block_items.item_from_block(block).map(|id| items.from_numeric_id(id)) else {
+        return false;
+    };
+
+    let Some(mut inventory) = structure_storages(q_storage, structure, storage_block).find(|inventory| inventory.quantity_of(item) != 0)
+    else {
+        return false;
+    };
+
+    let (remaining, _) = inventory.take_and_remove_item(item, 1, commands);
+
+    remaining == 0
+}
+
+fn launch_drones(
+    mut commands: Commands,
+    q_structures: Query<(Entity, &Structure, Option<&ConstructionSite>, Option<&DamagedBlocks>)>,
+    q_drones: Query<(&Drone, &DroneAi, &Parent)>,
+    mut q_storage: Query<(&BlockData, &mut Inventory)>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    block_items: Res<BlockItems>,
+) {
+    let (Some(drone_bay), Some(storage_block)) = (blocks.from_id(DRONE_BAY_BLOCK), blocks.from_id(STORAGE_BLOCK)) else {
+        return;
+    };
+
+    for (structure_ent, structure, site, damaged) in q_structures.iter() {
+        if site.is_none() && damaged.is_none_or(|damaged| damaged.0.is_empty()) {
+            continue;
+        }
+
+        let Some(counts) = structure.block_counts() else {
+            continue;
+        };
+
+        let mut bays = counts.positions(drone_bay.id()).peekable();
+        if bays.peek().is_none() {
+            continue;
+        }
+
+        let drones = q_drones
+            .iter()
+            .filter(|(_, _, parent)| parent.get() == structure_ent)
+            .map(|(drone, ai, _)| (drone.bay, ai.job))
+            .collect::<Vec<_>>();
+
+        let mut taken = drones
+            .iter()
+            .filter_map(|(_, job)| job.map(|job| job.coords()))
+            .collect::<HashSet<_>>();
+
+        let storages = structure_storages(&mut q_storage, structure_ent, storage_block).collect::<Vec<_>>();
+        let has_item = |block: &Block| {
+            block_items
+                .item_from_block(block)
+                .map(|id| items.from_numeric_id(id))
+                .is_some_and(|item| storages.iter().any(|inventory| inventory.quantity_of(item) != 0))
+        };
+
+        for bay in bays {
+            if drones.iter().filter(|(drone_bay, _)| *drone_bay == bay).count() >= MAX_DRONES_PER_BAY {
+                continue;
+            }
+
+            let front = structure.block_rotation(bay).direction_of(BlockFace::Front);
+            // Drones can't get out of a bay that's blocked
+            if BlockCoordinate::try_from(bay + front.to_coordinates())
+                .ok()
+                .filter(|&c| structure.is_within_blocks(c))
+                .is_some_and(|c| !structure.block_at(c, &blocks).is_empty())
+            {
+                continue;
+            }
+
+            let Some((job, side)) = find_job(structure, bay, site, damaged, &taken, &has_item, &blocks) else {
+                continue;
+            };
+
+            taken.insert(job.coords());
+
+            let bay_pos = structure.block_relative_position(bay);
+            let exit_pos = bay_pos + front.as_vec3();
+            let work_pos = structure.block_relative_position(job.coords()) + side.as_vec3();
+
+            let mut path = VecDeque::from([exit_pos]);
+            path.extend(plan_path(structure, exit_pos, work_pos, side, &blocks));
+
+            commands.entity(structure_ent).with_children(|p| {
+                p.spawn((
+                    Name::new("Drone"),
+                    Drone {
+                        bay,
+                        activity: DroneActivity::Flying,
+                    },
+                    DroneAi {
+                        job: Some(job),
+                        path,
+                        work_secs: 0.0,
+                    },
+                    Transform::from_translation(bay_pos),
+                    SetPosition::Location,
+                    LoadingDistance::new(1, 2),
+                ));
+            });
+        }
+    }
+}
+
+fn fly_drones(time: Res<Time>, mut q_drones: Query<(&mut Transform, &mut DroneAi)>) {
+    let delta = time.delta_secs();
+
+    for (mut transform, mut ai) in q_drones.iter_mut() {
+        let mut travel = DRONE_SPEED * delta;
+
+        while let Some(&next) = ai.path.front() {
+            let to_next = next - transform.translation;
+            let distance = to_next.length();
+
+            if distance > travel {
+                transform.translation += to_next / distance * travel;
+                transform.look_to(to_next, Vec3::Y);
+                break;
+            }
+
+            transform.translation = next;
+            travel -= distance;
+            ai.path.pop_front();
+        }
+    }
+}
+
+fn do_drone_jobs(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_drones: Query<(Entity, &Parent, &Transform, &mut Drone, &mut DroneAi)>,
+    mut q_structure: Query<(&mut Structure, Option<&ConstructionSite>, Option<&mut DamagedBlocks>)>,
+    mut q_storage: Query<(&BlockData, &mut Inventory)>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    block_items: Res<BlockItems>,
+    mut evw_block_changed: EventWriter<BlockChangedEvent>,
+    mut server: ResMut<RenetServer>,
+) {
+    let Some(storage_block) = blocks.from_id(STORAGE_BLOCK) else {
+        return;
+    };
+
+    let mut health_changes = vec![];
+
+    for (drone_ent, parent, transform, mut drone, mut ai) in q_drones.iter_mut() {
+        if !ai.path.is_empty() {
+            continue;
+        }
+
+        let structure_ent = parent.get();
+
+        let Some(job) = ai.job else {
+            // The drone is back in its bay
+            commands.entity(drone_ent).insert(NeedsDespawned);
+            continue;
+        };
+
+        let Ok((mut structure, site, damaged)) = q_structure.get_mut(structure_ent) else {
+            continue;
+        };
+
+        let coords = job.coords();
+
+        if drone.activity != DroneActivity::Working(coords) {
+            drone.activity = DroneActivity::Working(coords);
+        }
+
+        ai.work_secs += time.delta_secs();
+        if ai.work_secs < DRONE_WORK_SECS {
+            continue;
+        }
+
+        match job {
+            DroneJob::Build(coords) => {
+                let ghost = site
+                    .and_then(|site| site.ghost_at(coords))
+                    .filter(|_| structure.block_at(coords, &blocks).is_empty())
+                    .and_then(|(block, rotation)| blocks.from_id(block).map(|block| (block, rotation)));
+
+                if let Some((block, rotation)) = ghost {
+                    if take_block_item(
+                        &mut commands,
+                        &mut q_storage,
+                        structure_ent,
+                        storage_block,
+                        block,
+                        &items,
+                        &block_items,
+                    ) {
+                        structure.set_block_at(coords, block, rotation, &blocks, Some(&mut evw_block_changed));
+                    }
+                }
+            }
+            DroneJob::Repair(coords) => {
+                let repaired = match damaged_block(&structure, coords, &blocks) {
+                    Some(block) => {
+                        let hardness = block.hardness();
+
+                        if take_block_item(
+                            &mut commands,
+                            &mut q_storage,
+                            structure_ent,
+                            storage_block,
+                            block,
+                            &items,
+                            &block_items,
+                        ) {
+                            structure.set_block_health(coords, hardness, &blocks);
+
+                            health_changes.push(BlockHealthUpdate {
+                                structure_entity: structure_ent,
+                                block: StructureBlock::new(coords, structure_ent),
+                                new_health: hardness,
+                                causer: None,
+                            });
+
+                            true
+                        } else {
+                            false
+                        }
+                    }
+                    // It was already repaired or destroyed
+                    None => true,
+                };
+
+                if repaired {
+                    if let Some(mut damaged) = damaged {
+                        damaged.0.remove(&coords);
+                    }
+                }
+            }
+        }
+
+        // Head back to the bay, even if there's more work to do. The bay will send it back out if there is.
+        let bay_pos = structure.block_relative_position(drone.bay);
+        let front = structure.block_rotation(drone.bay).direction_of(BlockFace::Front);
+        let exit_pos = bay_pos + front.as_vec3();
+
+        ai.job = None;
+        ai.work_secs = 0.0;
+        ai.path = plan_path(&structure, transform.translation, exit_pos, front, &blocks).into();
+        ai.path.push_back(bay_pos);
+
+        drone.activity = DroneActivity::Flying;
+    }
+
+    if !health_changes.is_empty() {
+        server.broadcast_netty_message(
+            NettyChannelServer::Reliable,
+            NettyMessage::new(&ServerReliableMessages::BlockHealthChange { changes: health_changes }),
+        );
+    }
+}
+
+/// Drones whose bay was removed have nowhere to go back to
+fn despawn_orphaned_drones(
+    mut commands: Commands,
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    q_drones: Query<(Entity, &Drone, &Parent)>,
+    blocks: Res<Registry<Block>>,
+) {
+    let Some(drone_bay) = blocks.from_id(DRONE_BAY_BLOCK) else {
+        return;
+    };
+
+    for ev in evr_block_changed.read() {
+        if ev.old_block != drone_bay.id() || ev.new_block == drone_bay.id() {
+            continue;
+        }
+
+        for (drone_ent, _, _) in q_drones
+            .iter()
+            .filter(|(_, drone, parent)| parent.get() == ev.block.structure() && drone.bay == ev.block.coords())
+        {
+            commands.entity(drone_ent).insert(NeedsDespawned);
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            track_damaged_blocks,
+            launch_drones.run_if(on_timer(Duration::from_secs(1))),
+            fly_drones,
+            do_drone_jobs.in_set(BlockEventsSet::ChangeBlocks),
+            despawn_orphaned_drones.in_set(BlockEventsSet::PostProcessEvents),
+        )
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
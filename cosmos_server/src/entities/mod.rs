@@ -2,8 +2,14 @@
 
 use bevy::prelude::App;
 
+pub mod creature;
+pub mod drone;
+pub mod npc;
 pub mod player;
 
 pub(super) fn register(app: &mut App) {
+    creature::register(app);
+    drone::register(app);
+    npc::register(app);
     player::register(app);
 }
@@ -0,0 +1,165 @@
+//! The dialogue trees NPCs use, which are loaded from `assets/cosmos/dialogue` and registered as `cosmos:<file name>`.
+//!
+//! A dialogue file has the name of the node conversations start at, and every node in the tree:
+//!
+//! ```json
+//! {
+//!   "start": "greeting",
+//!   "nodes": {
+//!     "greeting": {
+//!       "text": "Welcome aboard.",
+//!       "options": [
+//!         { "text": "What do you sell?", "action": "OpenShop" },
+//!         { "text": "Tell me about this place.", "action": { "Goto": "history" } },
+//!         { "text": "Goodbye.", "action": "End" }
+//!       ]
+//!     },
+//!     "history": { "text": "It was built long ago." }
+//!   }
+//! }
+//! ```
+//!
+//! Nodes without any options let the player leave the conversation.
+
+use std::{ffi::OsStr, fs};
+
+use bevy::{prelude::*, utils::HashMap};
+use cosmos_core::{
+    registry::{create_registry, identifiable::Identifiable, Registry},
+    state::GameState,
+};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// What happens when the player picks a dialogue option
+pub enum DialogueAction {
+    /// Moves to the node with this name
+    Goto(String),
+    /// Ends the conversation
+    End,
+    /// Ends the conversation and opens the NPC's shop
+    OpenShop,
+    /// Offers the player the mission with this unlocalized name, then ends the conversation
+    Mission(String),
+    /// Gives the player the lore entry with this unlocalized name, then ends the conversation
+    Lore(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Something the player can say back to an NPC
+pub struct DialogueOption {
+    /// What the player says
+    pub text: String,
+    /// What happens when the player says it
+    pub action: DialogueAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// One thing an NPC says
+pub struct DialogueNode {
+    /// What the NPC says
+    pub text: String,
+    #[serde(default)]
+    /// What the player can say back
+    pub options: Vec<DialogueOption>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DialogueFile {
+    start: String,
+    nodes: HashMap<String, DialogueNode>,
+}
+
+#[derive(Debug, Clone)]
+/// Everything an NPC can say, and how the player gets from one line to the next
+pub struct DialogueTree {
+    id: u16,
+    unlocalized_name: String,
+    start: String,
+    nodes: HashMap<String, DialogueNode>,
+}
+
+impl DialogueTree {
+    /// The name of the node conversations start at
+    pub fn start(&self) -> &str {
+        &self.start
+    }
+
+    /// Gets the node with this name
+    pub fn node(&self, name: &str) -> Option<&DialogueNode> {
+        self.nodes.get(name)
+    }
+
+    /// The names of nodes that are referred to but don't exist
+    fn missing_nodes(&self) -> Vec<&str> {
+        let gotos = self.nodes.values().flat_map(|n| n.options.iter()).filter_map(|o| match &o.action {
+            DialogueAction::Goto(node) => Some(node.as_str()),
+            _ => None,
+        });
+
+        std::iter::once(self.start.as_str())
+            .chain(gotos)
+            .filter(|name| !self.nodes.contains_key(*name))
+            .collect()
+    }
+}
+
+impl Identifiable for DialogueTree {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+fn load_dialogue_trees(mut dialogue_trees: ResMut<Registry<DialogueTree>>) {
+    for entry in WalkDir::new("assets/cosmos/dialogue").max_depth(1) {
+        let Ok(entry) = entry else {
+            continue;
+        };
+
+        let path = entry.path();
+        if path.is_dir() || path.extension().and_then(OsStr::to_str) != Some("json") {
+            continue;
+        }
+
+        let Some(name) = path.file_stem().and_then(OsStr::to_str) else {
+            continue;
+        };
+
+        let dialogue_json = fs::read(path).unwrap_or_else(|e| panic!("Unable to read dialogue file {path:?}\n{e:?}"));
+
+        let file =
+            serde_json::from_slice::<DialogueFile>(&dialogue_json).unwrap_or_else(|e| panic!("Invalid dialogue json {path:?}\n{e:?}"));
+
+        let tree = DialogueTree {
+            id: 0,
+            unlocalized_name: format!("cosmos:{name}"),
+            start: file.start,
+            nodes: file.nodes,
+        };
+
+        let missing = tree.missing_nodes();
+        if !missing.is_empty() {
+            error!("Dialogue {path:?} refers to nodes that don't exist: {missing:?}");
+            continue;
+        }
+
+        dialogue_trees.register(tree);
+    }
+
+    info!("Loaded {} dialogue trees", dialogue_trees.iter().count());
+}
+
+pub(super) fn register(app: &mut App) {
+    create_registry::<DialogueTree>(app, "cosmos:dialogue_trees");
+
+    app.add_systems(OnEnter(GameState::PostLoading), load_dialogue_trees);
+}
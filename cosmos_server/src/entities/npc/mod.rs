@@ -0,0 +1,294 @@
+//! Server-side logic for NPCs, and walking players through their dialogue.
+//!
+//! Admins place NPCs where they're standing with the `/npc [dialogue] [name]` chat command. Options that do more
+//! than move the conversation along send a [`NpcDialogueActionEvent`], which is how shops, missions & lore entries
+//! hook into dialogue.
+
+use bevy::prelude::*;
+use bevy_renet2::renet2::RenetServer;
+use cosmos_core::{
+    chat::ServerSendChatMessageEvent,
+    entities::{
+        npc::{ChooseDialogueOptionEvent, DialogueLine, InteractNpcEvent, Npc, NpcDialogueEvent},
+        player::Player,
+    },
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    persistence::LoadingDistance,
+    physics::location::Location,
+    prelude::StructureBlock,
+    registry::Registry,
+    state::GameState,
+    structure::coordinates::BlockCoordinate,
+};
+use dialogue::{DialogueAction, DialogueNode, DialogueTree};
+use renet2::ClientId;
+
+use crate::{
+    chat::{ChatCommandSet, ChatCommands, PlayerChatCommandEvent},
+    entities::player::admin::Admin,
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+    shop::{prices::DefaultShopEntries, send_open_shop},
+};
+
+pub mod dialogue;
+
+/// Players can talk to NPCs from a little further than they can reach to account for latency
+const MAX_TALK_DISTANCE: f32 = 12.0;
+
+impl DefaultPersistentComponent for Npc {}
+
+#[derive(Component, Debug)]
+/// The NPC this player is talking to, and the node of its dialogue they're on
+struct InDialogue {
+    npc: Entity,
+    node: String,
+}
+
+#[derive(Event, Debug, Clone)]
+/// Sent whenever a player picks a dialogue option that does more than move the conversation along
+pub struct NpcDialogueActionEvent {
+    /// The player who picked the option
+    pub player: Entity,
+    /// The NPC they were talking to
+    pub npc: Entity,
+    /// What the option does. This is never [`DialogueAction::Goto`] or [`DialogueAction::End`].
+    pub action: DialogueAction,
+}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+fn dialogue_line(node: &DialogueNode) -> DialogueLine {
+    DialogueLine {
+        text: node.text.clone(),
+        options: node.options.iter().map(|o| o.text.clone()).collect(),
+    }
+}
+
+fn register_chat_commands(mut chat_commands: ResMut<ChatCommands>) {
+    chat_commands.add("npc");
+}
+
+fn on_npc_command(
+    mut commands: Commands,
+    mut evr_command: EventReader<PlayerChatCommandEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    q_player: Query<(&Location, &GlobalTransform, Has<Admin>), With<Player>>,
+    dialogue_trees: Res<Registry<DialogueTree>>,
+) {
+    for ev in evr_command.read() {
+        if ev.name != "npc" {
+            continue;
+        }
+
+        let Ok((location, g_trans, is_admin)) = q_player.get(ev.player_entity) else {
+            continue;
+        };
+
+        if !is_admin {
+            reply(&mut nevw_chat, ev.client_id, "Only admins can place NPCs.");
+            continue;
+        }
+
+        if ev.args.len() < 2 {
+            reply(&mut nevw_chat, ev.client_id, "Usage: /npc [dialogue] [name]");
+            continue;
+        }
+
+        let dialogue = if ev.args[0].contains(':') {
+            ev.args[0].clone()
+        } else {
+            format!("cosmos:{}", ev.args[0])
+        };
+
+        if dialogue_trees.from_id(&dialogue).is_none() {
+            reply(&mut nevw_chat, ev.client_id, format!("There is no dialogue named {dialogue}."));
+            continue;
+        }
+
+        commands.spawn((
+            Npc {
+                name: ev.args[1..].join(" "),
+                dialogue,
+            },
+            *location,
+            Transform::from_rotation(g_trans.compute_transform().rotation),
+            LoadingDistance::new(1, 2),
+        ));
+    }
+}
+
+/// Returns true if this player is close enough to talk to this NPC
+fn within_talking_distance(player_ent: Entity, npc_ent: Entity, q_location: &Query<&Location>) -> bool {
+    let (Ok(player_loc), Ok(npc_loc)) = (q_location.get(player_ent), q_location.get(npc_ent)) else {
+        return false;
+    };
+
+    player_loc.distance_sqrd(npc_loc) <= MAX_TALK_DISTANCE * MAX_TALK_DISTANCE
+}
+
+fn on_interact_npc(
+    mut commands: Commands,
+    mut nevr_interact: EventReader<NettyEventReceived<InteractNpcEvent>>,
+    lobby: Res<ServerLobby>,
+    q_location: Query<&Location>,
+    q_npc: Query<&Npc>,
+    dialogue_trees: Res<Registry<DialogueTree>>,
+    mut nevw_dialogue: NettyEventWriter<NpcDialogueEvent>,
+) {
+    for ev in nevr_interact.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            continue;
+        };
+
+        let Ok(npc) = q_npc.get(ev.npc) else {
+            continue;
+        };
+
+        if !within_talking_distance(player_ent, ev.npc, &q_location) {
+            warn!("Player {player_ent:?} tried to talk to an NPC that is too far away.");
+            continue;
+        }
+
+        let Some(tree) = dialogue_trees.from_id(&npc.dialogue) else {
+            warn!("NPC {:?} uses dialogue {} which doesn't exist.", ev.npc, npc.dialogue);
+            continue;
+        };
+
+        let Some(node) = tree.node(tree.start()) else {
+            continue;
+        };
+
+        commands.entity(player_ent).insert(InDialogue {
+            npc: ev.npc,
+            node: tree.start().to_owned(),
+        });
+
+        nevw_dialogue.send(
+            NpcDialogueEvent {
+                npc: ev.npc,
+                line: Some(dialogue_line(node)),
+            },
+            ev.client_id,
+        );
+    }
+}
+
+fn on_choose_dialogue_option(
+    mut commands: Commands,
+    mut nevr_choose: EventReader<NettyEventReceived<ChooseDialogueOptionEvent>>,
+    lobby: Res<ServerLobby>,
+    q_location: Query<&Location>,
+    q_npc: Query<&Npc>,
+    mut q_in_dialogue: Query<&mut InDialogue>,
+    dialogue_trees: Res<Registry<DialogueTree>>,
+    mut nevw_dialogue: NettyEventWriter<NpcDialogueEvent>,
+    mut evw_action: EventWriter<NpcDialogueActionEvent>,
+) {
+    for ev in nevr_choose.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            continue;
+        };
+
+        let Ok(mut in_dialogue) = q_in_dialogue.get_mut(player_ent) else {
+            continue;
+        };
+
+        if in_dialogue.npc != ev.npc || !within_talking_distance(player_ent, ev.npc, &q_location) {
+            continue;
+        }
+
+        let Some(option) = q_npc
+            .get(ev.npc)
+            .ok()
+            .and_then(|npc| dialogue_trees.from_id(&npc.dialogue))
+            .and_then(|tree| tree.node(&in_dialogue.node).map(|node| (tree, node)))
+            .and_then(|(tree, node)| node.options.get(ev.option).map(|option| (tree, option)))
+        else {
+            continue;
+        };
+
+        let (tree, option) = option;
+
+        if let DialogueAction::Goto(next) = &option.action {
+            if let Some(node) = tree.node(next) {
+                in_dialogue.node = next.clone();
+
+                nevw_dialogue.send(
+                    NpcDialogueEvent {
+                        npc: ev.npc,
+                        line: Some(dialogue_line(node)),
+                    },
+                    ev.client_id,
+                );
+
+                continue;
+            }
+        }
+
+        commands.entity(player_ent).remove::<InDialogue>();
+        nevw_dialogue.send(NpcDialogueEvent { npc: ev.npc, line: None }, ev.client_id);
+
+        if !matches!(option.action, DialogueAction::Goto(_) | DialogueAction::End) {
+            evw_action.send(NpcDialogueActionEvent {
+                player: player_ent,
+                npc: ev.npc,
+                action: option.action.clone(),
+            });
+        }
+    }
+}
+
+fn open_vendor_shops(
+    mut evr_action: EventReader<NpcDialogueActionEvent>,
+    mut server: ResMut<RenetServer>,
+    q_player: Query<&Player>,
+    default_shop_entries: Res<DefaultShopEntries>,
+) {
+    for ev in evr_action.read() {
+        if ev.action != DialogueAction::OpenShop {
+            continue;
+        }
+
+        let Ok(player) = q_player.get(ev.player) else {
+            continue;
+        };
+
+        // Vendors aren't blocks, so the vendor stands in for the shop's structure
+        send_open_shop(
+            &mut server,
+            player.id(),
+            StructureBlock::new(BlockCoordinate::default(), ev.npc),
+            &default_shop_entries,
+        );
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    dialogue::register(app);
+
+    make_persistent::<Npc>(app);
+
+    app.add_event::<NpcDialogueActionEvent>()
+        .add_systems(Startup, register_chat_commands)
+        .add_systems(
+            Update,
+            (
+                on_npc_command.after(ChatCommandSet::SendCommandEvents),
+                (on_interact_npc, on_choose_dialogue_option, open_vendor_shops).chain(),
+            )
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        );
+}
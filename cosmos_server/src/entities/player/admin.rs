@@ -0,0 +1,25 @@
+//! Server admins can bypass the restrictions normal players have.
+
+use bevy::prelude::*;
+use cosmos_core::netty::sync::IdentifiableComponent;
+use serde::{Deserialize, Serialize};
+
+use crate::persistence::make_persistent::{make_persistent, DefaultPersistentComponent};
+
+#[derive(Component, Debug, Clone, Copy, Serialize, Deserialize)]
+/// This player is a server admin, and can use any structure regardless of who owns it.
+///
+/// This is given/taken away via the `admin [player_name]` console command.
+pub struct Admin;
+
+impl IdentifiableComponent for Admin {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:admin"
+    }
+}
+
+impl DefaultPersistentComponent for Admin {}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<Admin>(app);
+}
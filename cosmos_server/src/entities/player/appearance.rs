@@ -0,0 +1,54 @@
+//! Saves how players look & applies the changes they make in their character screen
+
+use bevy::prelude::*;
+use cosmos_core::{
+    entities::player::{
+        appearance::{PlayerAppearance, SetAppearanceEvent},
+        Player,
+    },
+    netty::{server::ServerLobby, sync::events::server_event::NettyEventReceived, system_sets::NetworkingSystemsSet},
+    state::GameState,
+};
+
+use crate::persistence::make_persistent::{make_persistent, DefaultPersistentComponent};
+
+impl DefaultPersistentComponent for PlayerAppearance {}
+
+/// Players that were created or saved before appearances existed get the default look
+fn add_appearance_to_players(mut commands: Commands, q_players: Query<Entity, (Added<Player>, Without<PlayerAppearance>)>) {
+    for ent in q_players.iter() {
+        commands.entity(ent).insert(PlayerAppearance::default());
+    }
+}
+
+fn on_set_appearance(
+    mut nevr_set_appearance: EventReader<NettyEventReceived<SetAppearanceEvent>>,
+    lobby: Res<ServerLobby>,
+    mut q_appearance: Query<&mut PlayerAppearance, With<Player>>,
+) {
+    for ev in nevr_set_appearance.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok(mut appearance) = q_appearance.get_mut(player_ent) else {
+            continue;
+        };
+
+        // Avoids resyncing the player if nothing changed
+        appearance.set_if_neq(ev.appearance);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<PlayerAppearance>(app);
+
+    app.add_systems(
+        Update,
+        (add_appearance_to_players, on_set_appearance)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
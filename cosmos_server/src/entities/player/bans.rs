@@ -0,0 +1,102 @@
+//! Players that are banned from joining the server.
+//!
+//! Players choose their own names, so banning a player also bans the address they were connected from (if they were
+//! online when banned). Anyone connecting with a banned name or from a banned address is disconnected.
+//!
+//! Bans are stored in `./config/cosmos/banned_players.json`.
+
+use std::{fs, net::IpAddr};
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use cosmos_core::state::GameState;
+use serde::{Deserialize, Serialize};
+
+const BANNED_PLAYERS_PATH: &str = "./config/cosmos/banned_players.json";
+
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+/// Every player that isn't allowed to join the server
+pub struct BannedPlayers {
+    names: HashSet<String>,
+    /// Banned addresses, and the name of the player they were banned with
+    #[serde(default)]
+    addresses: HashMap<IpAddr, String>,
+}
+
+impl BannedPlayers {
+    /// Returns true if a player with this name, or connecting from this address, is banned
+    pub fn is_banned(&self, name: &str, address: Option<IpAddr>) -> bool {
+        self.names.contains(name) || address.is_some_and(|address| self.addresses.contains_key(&address))
+    }
+
+    /// Bans this player (and the address they're connected from, if they're online) & saves the ban list.
+    ///
+    /// Returns false if they were already banned.
+    pub fn ban(&mut self, name: impl Into<String>, address: Option<IpAddr>) -> bool {
+        let name = name.into();
+
+        let address_banned = address.is_some_and(|address| self.addresses.insert(address, name.clone()).is_none());
+        let banned = self.names.insert(name) || address_banned;
+
+        if banned {
+            self.save();
+        }
+
+        banned
+    }
+
+    /// Unbans this player and every address they were banned with & saves the ban list. Returns false if they weren't banned.
+    pub fn unban(&mut self, name: &str) -> bool {
+        let n_addresses = self.addresses.len();
+        self.addresses.retain(|_, banned_name| banned_name != name);
+
+        let unbanned = self.names.remove(name) || self.addresses.len() != n_addresses;
+
+        if unbanned {
+            self.save();
+        }
+
+        unbanned
+    }
+
+    /// The names of every banned player
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|x| x.as_str())
+    }
+
+    fn save(&self) {
+        let json = serde_json::to_string_pretty(self).expect("Banned players are always valid json");
+
+        if let Err(e) = fs::create_dir_all("./config/cosmos").and_then(|_| fs::write(BANNED_PLAYERS_PATH, json)) {
+            error!("Unable to save banned players to {BANNED_PLAYERS_PATH}.\n{e:?}");
+        }
+    }
+}
+
+fn load_banned_players(mut commands: Commands) {
+    let Ok(json) = fs::read_to_string(BANNED_PLAYERS_PATH) else {
+        return;
+    };
+
+    let banned = serde_json::from_str::<BannedPlayers>(&json).or_else(|e| {
+        // Older ban lists are only a list of names
+        serde_json::from_str::<HashSet<String>>(&json)
+            .map(|names| BannedPlayers {
+                names,
+                addresses: Default::default(),
+            })
+            .map_err(|_| e)
+    });
+
+    match banned {
+        Ok(banned) => commands.insert_resource(banned),
+        Err(e) => error!("Invalid banned players in {BANNED_PLAYERS_PATH} - nobody will be banned.\n{e:?}"),
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<BannedPlayers>()
+        .add_systems(OnEnter(GameState::PostLoading), load_banned_players);
+}
@@ -0,0 +1,127 @@
+//! Gives players health, and respawns them once it runs out.
+//!
+//! Players respawn at the bed they last slept in, or where new players spawn if they don't have one.
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::{
+        specific_blocks::seat::{Seated, BED_BLOCK},
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    entities::{
+        health::Health,
+        player::{
+            death::{PlayerRespawnEvent, PLAYER_MAX_HEALTH},
+            Player,
+        },
+    },
+    events::structure::change_pilot_event::ChangePilotEvent,
+    netty::{sync::events::server_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    physics::location::Location,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        shared::build_mode::{BuildMode, ExitBuildModeEvent},
+        ship::pilot::Pilot,
+        Structure,
+    },
+};
+
+use crate::{
+    blocks::interactable::seat::RespawnPoint,
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+    universe::{generation::UniverseSystems, safe_zone::SpawnProtection},
+};
+
+use super::spawn_player::find_new_player_location;
+
+impl DefaultPersistentComponent for Health {}
+
+/// Players that were created or saved before they had health start out unharmed
+fn add_health_to_players(mut commands: Commands, q_players: Query<Entity, (Added<Player>, Without<Health>)>) {
+    for ent in q_players.iter() {
+        commands.entity(ent).insert(Health::new(PLAYER_MAX_HEALTH));
+    }
+}
+
+/// Finds where the bed this respawn point is for is, if it still exists
+fn bed_location(
+    respawn_point: &RespawnPoint,
+    q_structure: &Query<(&Structure, &Location, &GlobalTransform)>,
+    blocks: &Registry<Block>,
+) -> Option<Location> {
+    let (structure, location, g_trans) = q_structure.get(respawn_point.structure).ok()?;
+
+    if !structure.is_within_blocks(respawn_point.block) || structure.block_at(respawn_point.block, blocks).unlocalized_name() != BED_BLOCK {
+        return None;
+    }
+
+    // Respawn on top of the bed, not inside of it
+    let offset = structure.block_relative_position(respawn_point.block) + Vec3::Y;
+
+    Some(*location + g_trans.compute_transform().rotation * offset)
+}
+
+fn respawn_dead_players(
+    mut commands: Commands,
+    mut q_players: Query<(Entity, &Player, &mut Health, Option<&Pilot>, Has<BuildMode>, Option<&RespawnPoint>), Changed<Health>>,
+    q_structure: Query<(&Structure, &Location, &GlobalTransform)>,
+    blocks: Res<Registry<Block>>,
+    universe_systems: Res<UniverseSystems>,
+    mut evw_change_pilot: EventWriter<ChangePilotEvent>,
+    mut evw_exit_build_mode: EventWriter<ExitBuildModeEvent>,
+    mut nevw_respawn: NettyEventWriter<PlayerRespawnEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for (ent, player, mut health, pilot, in_build_mode, respawn_point) in q_players.iter_mut() {
+        if health.is_alive() {
+            continue;
+        }
+
+        info!("{} died.", player.name());
+
+        if let Some(pilot) = pilot {
+            evw_change_pilot.send(ChangePilotEvent {
+                structure_entity: pilot.entity,
+                pilot_entity: None,
+            });
+        }
+
+        if in_build_mode {
+            evw_exit_build_mode.send(ExitBuildModeEvent { player_entity: ent });
+        }
+
+        health.heal(health.max());
+
+        let respawn_location = respawn_point
+            .and_then(|respawn_point| bed_location(respawn_point, &q_structure, &blocks))
+            .unwrap_or_else(|| find_new_player_location(&universe_systems));
+
+        commands
+            .entity(ent)
+            .remove::<Seated>()
+            .remove_parent_in_place()
+            .insert(SpawnProtection::default());
+        nevw_respawn.send(PlayerRespawnEvent(respawn_location), player.id());
+        nevw_chat.send(
+            ServerSendChatMessageEvent {
+                sender: None,
+                message: "You died.".into(),
+            },
+            player.id(),
+        );
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<Health>(app);
+
+    app.add_systems(
+        Update,
+        (add_health_to_players, respawn_dead_players)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
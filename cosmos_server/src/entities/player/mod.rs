@@ -7,9 +7,15 @@ use serde::{Deserialize, Serialize};
 
 use crate::persistence::make_persistent::{make_persistent, DefaultPersistentComponent};
 
+pub mod admin;
+mod appearance;
+pub mod bans;
+mod death;
 mod kits;
+mod needs;
 pub mod persistence;
 mod spawn_player;
+pub mod spectator;
 
 #[derive(Component, Debug, Serialize, Deserialize)]
 /// The server doesn't have a camera, so this is used to track where the player is looking
@@ -29,4 +35,10 @@ impl DefaultPersistentComponent for PlayerLooking {}
 pub(super) fn register(app: &mut App) {
     make_persistent::<PlayerLooking>(app);
     persistence::register(app);
+    admin::register(app);
+    appearance::register(app);
+    bans::register(app);
+    death::register(app);
+    needs::register(app);
+    spectator::register(app);
 }
@@ -0,0 +1,137 @@
+//! Player hunger, eating food & the status effects consumables give
+
+use bevy::prelude::*;
+use cosmos_core::{
+    entities::{
+        player::{
+            creative::Creative,
+            hunger::{Hunger, MAX_HUNGER},
+            Player,
+        },
+        status_effects::StatusEffects,
+    },
+    inventory::{held_item_slot::HeldItemSlot, Inventory},
+    item::{
+        consumable::{Consumable, ConsumeHeldItemEvent},
+        Item,
+    },
+    netty::{server::ServerLobby, sync::events::server_event::NettyEventReceived, system_sets::NetworkingSystemsSet},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+};
+
+use crate::persistence::make_persistent::{make_persistent, DefaultPersistentComponent};
+
+/// How often (in seconds) hunger & status effects are updated
+const NEEDS_TICK_SECS: f32 = 1.0;
+/// How much hunger a player uses up every second - a full stomach lasts 40 minutes
+const HUNGER_PER_SEC: f32 = MAX_HUNGER / (40.0 * 60.0);
+
+impl DefaultPersistentComponent for Hunger {}
+impl DefaultPersistentComponent for StatusEffects {}
+
+#[derive(Resource, Debug)]
+struct NeedsTimer(Timer);
+
+impl Default for NeedsTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(NEEDS_TICK_SECS, TimerMode::Repeating))
+    }
+}
+
+/// Players that were created or saved before hunger existed start out full
+fn add_needs_to_players(mut commands: Commands, q_players: Query<(Entity, Has<Hunger>, Has<StatusEffects>), Added<Player>>) {
+    for (ent, has_hunger, has_effects) in q_players.iter() {
+        let mut ecmds = commands.entity(ent);
+
+        if !has_hunger {
+            ecmds.insert(Hunger::default());
+        }
+        if !has_effects {
+            ecmds.insert(StatusEffects::default());
+        }
+    }
+}
+
+fn tick_needs(
+    time: Res<Time>,
+    mut timer: ResMut<NeedsTimer>,
+    mut q_hunger: Query<&mut Hunger, Without<Creative>>,
+    mut q_effects: Query<&mut StatusEffects>,
+) {
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() {
+        return;
+    }
+
+    for mut hunger in q_hunger.iter_mut() {
+        if !hunger.is_starving() {
+            hunger.deplete(HUNGER_PER_SEC * NEEDS_TICK_SECS);
+        }
+    }
+
+    for mut effects in q_effects.iter_mut() {
+        // Avoids syncing players that have no effects every tick
+        if !effects.is_empty() {
+            effects.tick(NEEDS_TICK_SECS);
+        }
+    }
+}
+
+fn on_consume_held_item(
+    mut nevr_consume: EventReader<NettyEventReceived<ConsumeHeldItemEvent>>,
+    lobby: Res<ServerLobby>,
+    mut q_player: Query<(&HeldItemSlot, &mut Inventory, &mut Hunger, &mut StatusEffects)>,
+    items: Res<Registry<Item>>,
+    consumables: Res<Registry<Consumable>>,
+    mut commands: Commands,
+) {
+    for ev in nevr_consume.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok((held_item, mut inventory, mut hunger, mut effects)) = q_player.get_mut(player_ent) else {
+            continue;
+        };
+
+        let slot = held_item.slot() as usize;
+
+        let Some(consumable) = inventory
+            .itemstack_at(slot)
+            .and_then(|is| items.try_from_numeric_id(is.item_id()))
+            .and_then(|item| consumables.from_id(item.unlocalized_name()))
+        else {
+            continue;
+        };
+
+        // No point in wasting food that does nothing
+        if consumable.effects().is_empty() && hunger.current() >= hunger.max() {
+            continue;
+        }
+
+        inventory.decrease_quantity_at(slot, 1, &mut commands);
+
+        if consumable.hunger_restored() > 0.0 {
+            hunger.eat(consumable.hunger_restored());
+        }
+
+        for effect in consumable.effects() {
+            effects.apply(effect.effect, effect.strength, effect.duration_secs);
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<Hunger>(app);
+    make_persistent::<StatusEffects>(app);
+
+    app.init_resource::<NeedsTimer>().add_systems(
+        Update,
+        (add_needs_to_players, tick_needs, on_consume_held_item)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -13,8 +13,8 @@ use cosmos_core::{
     inventory::{itemstack::ItemShouldHaveData, Inventory},
     item::Item,
     netty::{
-        cosmos_encoder,
         netty_rigidbody::{NettyRigidBody, NettyRigidBodyLocation},
+        network_stats::{NettyMessage, NettyServerMessages},
         server::ServerLobby,
         server_reliable_messages::ServerReliableMessages,
         sync::{registry::server::SyncRegistriesEvent, ComponentSyncingSet},
@@ -32,16 +32,16 @@ use renet2::{ClientId, RenetServer};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    entities::player::spawn_player::find_new_player_location,
+    entities::player::{spawn_player::find_new_player_location, spectator::SpectatorBody},
     netty::server_events::PlayerConnectedEvent,
     persistence::{
         loading::{LoadingSystemSet, NeedsLoaded, LOADING_SCHEDULE},
-        saving::{calculate_sfi, NeedsSaved, SavingSystemSet, SAVING_SCHEDULE},
+        saving::{calculate_sfi, write_file_atomically, NeedsSaved, SavingSystemSet, SAVING_SCHEDULE},
         EntityId, SaveFileIdentifier, SerializedData,
     },
     physics::assign_player_world,
     settings::ServerSettings,
-    universe::generation::UniverseSystems,
+    universe::{generation::UniverseSystems, safe_zone::SpawnProtection},
 };
 
 use super::PlayerLooking;
@@ -77,10 +77,12 @@ const PLAYER_LINK_PATH: &str = "world/players";
 fn save_player_link(
     q_parent: Query<&Parent>,
     q_entity_id: Query<&EntityId>,
-    q_player_needs_saved: Query<(Entity, &EntityId, &Player, &Location), With<NeedsSaved>>,
+    q_player_needs_saved: Query<(Entity, &EntityId, &Player, &Location, Option<&SpectatorBody>), With<NeedsSaved>>,
     q_serialized_data: Query<(&SerializedData, &EntityId, Option<&LoadingDistance>)>,
 ) {
-    for (entity, e_id, player, loc) in q_player_needs_saved.iter() {
+    for (entity, e_id, player, loc, spectator_body) in q_player_needs_saved.iter() {
+        let loc = spectator_body.map(|body| &body.0).unwrap_or(loc);
+
         info!("Saving player {player:?} ({entity:?}) @ {loc}");
         let _ = fs::create_dir_all(PLAYER_LINK_PATH);
 
@@ -96,7 +98,7 @@ fn save_player_link(
         let json_data = serde_json::to_string(&player_identifier).expect("Failed to create json");
 
         let player_file_name = generate_player_file_id(player.name());
-        fs::write(format!("{PLAYER_LINK_PATH}/{player_file_name}"), json_data).expect("Failed to save player!!!");
+        write_file_atomically(&format!("{PLAYER_LINK_PATH}/{player_file_name}"), json_data).expect("Failed to save player!!!");
     }
 }
 
@@ -234,6 +236,7 @@ fn create_new_player(
                 inventory,
                 credits,
                 PlayerLooking { rotation: Quat::IDENTITY },
+                SpawnProtection::default(),
             ))
             .remove::<LoadPlayer>();
 
@@ -283,7 +286,7 @@ fn finish_loading_player(
         let netty_body = NettyRigidBody::new(Some(*velocity), Quat::IDENTITY, NettyRigidBodyLocation::Absolute(*location));
 
         info!("Sending player create message!");
-        let msg = cosmos_encoder::serialize(&ServerReliableMessages::PlayerCreate {
+        let msg = NettyMessage::new(&ServerReliableMessages::PlayerCreate {
             entity: player_entity,
             parent: maybe_parent.map(|x| x.get()),
             id: load_player.id(),
@@ -292,15 +295,15 @@ fn finish_loading_player(
             render_distance: None,
         });
 
-        server.send_message(
+        server.send_netty_message(
             load_player.id(),
             NettyChannelServer::Reliable,
-            cosmos_encoder::serialize(&ServerReliableMessages::MOTD {
+            NettyMessage::new(&ServerReliableMessages::MOTD {
                 motd: "Welcome to the server!".into(),
             }),
         );
 
-        server.broadcast_message(NettyChannelServer::Reliable, msg);
+        server.broadcast_netty_message(NettyChannelServer::Reliable, msg);
 
         evw_player_join.send(PlayerConnectedEvent {
             player_entity,
@@ -0,0 +1,240 @@
+//! Lets players the server has given permission to spectate.
+//!
+//! Players control this via chat commands:
+//! - `/spectate` - Starts/stops spectating. Stopping returns the player to where they started.
+//! - `/invisible` - Hides/shows the spectator to other players
+//! - `/tp [player or structure name]` - Teleports the spectator to that player or structure
+//!
+//! Spectators are saved where their body is, and are still spectating when they next join.
+
+use bevy::prelude::*;
+use cosmos_core::{
+    chat::ServerSendChatMessageEvent,
+    entities::player::{
+        spectator::{Spectator, SpectatorTeleportEvent},
+        Player,
+    },
+    events::structure::change_pilot_event::ChangePilotEvent,
+    netty::{
+        sync::{events::server_event::NettyEventWriter, IdentifiableComponent},
+        system_sets::NetworkingSystemsSet,
+    },
+    physics::location::Location,
+    state::GameState,
+    structure::{
+        shared::build_mode::{BuildMode, ExitBuildModeEvent},
+        ship::pilot::Pilot,
+        Structure,
+    },
+};
+use renet2::ClientId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    chat::{ChatCommandSet, ChatCommands, PlayerChatCommandEvent},
+    persistence::{
+        make_persistent::{make_persistent, DefaultPersistentComponent},
+        saving::{NeedsSaved, SavingSystemSet, SAVING_SCHEDULE},
+        SerializedData,
+    },
+};
+
+#[derive(Component, Debug, Clone, Copy, Serialize, Deserialize)]
+/// The server has allowed this player to spectate.
+///
+/// This is given/taken away via the `spectator [player_name]` console command.
+pub struct CanSpectate;
+
+impl IdentifiableComponent for CanSpectate {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:can_spectate"
+    }
+}
+
+impl DefaultPersistentComponent for CanSpectate {}
+
+impl DefaultPersistentComponent for Spectator {}
+
+#[derive(Component, Debug, Clone, Copy, Serialize, Deserialize)]
+/// Where the spectator's body is - they will be returned here once they stop spectating
+pub struct SpectatorBody(pub Location);
+
+impl IdentifiableComponent for SpectatorBody {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:spectator_body"
+    }
+}
+
+impl DefaultPersistentComponent for SpectatorBody {}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+/// Returns this spectator to their body, and stops them from spectating
+pub(crate) fn stop_spectating(
+    commands: &mut Commands,
+    nevw_teleport: &mut NettyEventWriter<SpectatorTeleportEvent>,
+    player_entity: Entity,
+    client_id: ClientId,
+    body: Option<&SpectatorBody>,
+) {
+    if let Some(body) = body {
+        nevw_teleport.send(SpectatorTeleportEvent(body.0), client_id);
+    }
+
+    commands.entity(player_entity).remove::<(Spectator, SpectatorBody)>();
+}
+
+fn register_chat_commands(mut chat_commands: ResMut<ChatCommands>) {
+    chat_commands.add("spectate");
+    chat_commands.add("invisible");
+    chat_commands.add("tp");
+}
+
+fn on_spectate_commands(
+    mut commands: Commands,
+    mut evr_command: EventReader<PlayerChatCommandEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut nevw_teleport: NettyEventWriter<SpectatorTeleportEvent>,
+    mut evw_change_pilot: EventWriter<ChangePilotEvent>,
+    mut evw_exit_build_mode: EventWriter<ExitBuildModeEvent>,
+    mut q_spectators: Query<
+        (
+            &Location,
+            Option<&mut Spectator>,
+            Option<&SpectatorBody>,
+            Option<&Pilot>,
+            Has<BuildMode>,
+        ),
+        With<CanSpectate>,
+    >,
+    q_players: Query<(&Player, &Location)>,
+    q_structures: Query<(&Name, &Location), With<Structure>>,
+) {
+    for ev in evr_command.read() {
+        if !matches!(ev.name.as_str(), "spectate" | "invisible" | "tp") {
+            continue;
+        }
+
+        let Ok((location, spectator, body, pilot, in_build_mode)) = q_spectators.get_mut(ev.player_entity) else {
+            reply(&mut nevw_chat, ev.client_id, "You do not have permission to spectate.");
+            continue;
+        };
+
+        match ev.name.as_str() {
+            "spectate" => {
+                if spectator.is_some() {
+                    stop_spectating(&mut commands, &mut nevw_teleport, ev.player_entity, ev.client_id, body);
+                    reply(&mut nevw_chat, ev.client_id, "Stopped spectating.");
+                    continue;
+                }
+
+                if let Some(pilot) = pilot {
+                    evw_change_pilot.send(ChangePilotEvent {
+                        structure_entity: pilot.entity,
+                        pilot_entity: None,
+                    });
+                }
+
+                if in_build_mode {
+                    evw_exit_build_mode.send(ExitBuildModeEvent {
+                        player_entity: ev.player_entity,
+                    });
+                }
+
+                commands
+                    .entity(ev.player_entity)
+                    .remove_parent_in_place()
+                    .insert((Spectator::default(), SpectatorBody(*location)));
+
+                reply(
+                    &mut nevw_chat,
+                    ev.client_id,
+                    "Now spectating. Use /spectate again to return to your body.",
+                );
+            }
+            "invisible" => {
+                let Some(mut spectator) = spectator else {
+                    reply(&mut nevw_chat, ev.client_id, "You must be spectating to do this.");
+                    continue;
+                };
+
+                spectator.invisible = !spectator.invisible;
+
+                let message = if spectator.invisible {
+                    "Other players can no longer see you."
+                } else {
+                    "Other players can now see you."
+                };
+                reply(&mut nevw_chat, ev.client_id, message);
+            }
+            "tp" => {
+                if spectator.is_none() {
+                    reply(&mut nevw_chat, ev.client_id, "You must be spectating to do this.");
+                    continue;
+                }
+
+                if ev.args.is_empty() {
+                    reply(&mut nevw_chat, ev.client_id, "Usage: /tp [player or structure name]");
+                    continue;
+                }
+
+                let target_name = ev.args.join(" ");
+
+                let target = q_players
+                    .iter()
+                    .find(|(player, _)| player.name().eq_ignore_ascii_case(&target_name))
+                    .map(|(_, loc)| *loc)
+                    .or_else(|| {
+                        q_structures
+                            .iter()
+                            .find(|(name, _)| name.as_str().eq_ignore_ascii_case(&target_name))
+                            .map(|(_, loc)| *loc)
+                    });
+
+                let Some(target) = target else {
+                    reply(&mut nevw_chat, ev.client_id, format!("No player or structure named {target_name}."));
+                    continue;
+                };
+
+                nevw_teleport.send(SpectatorTeleportEvent(target), ev.client_id);
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// The spectator's free-cam position isn't where they actually are, so they're saved at their body instead
+fn save_spectators_at_body(mut q_spectators: Query<(&mut SerializedData, &SpectatorBody), With<NeedsSaved>>) {
+    for (mut data, body) in q_spectators.iter_mut() {
+        data.set_location(&body.0);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<CanSpectate>(app);
+    make_persistent::<Spectator>(app);
+    make_persistent::<SpectatorBody>(app);
+
+    app.add_systems(Startup, register_chat_commands)
+        .add_systems(
+            Update,
+            on_spectate_commands
+                .after(ChatCommandSet::SendCommandEvents)
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            SAVING_SCHEDULE,
+            save_spectators_at_body
+                .after(SavingSystemSet::DoSaving)
+                .before(SavingSystemSet::CreateEntityIds),
+        );
+}
@@ -15,8 +15,11 @@ use renet2::transport::{NativeSocket, ServerSetupConfig};
 use crate::netty::network_helpers::{ClientTicks, NetworkTick};
 
 /// Sets up the server & makes it ready to be connected to
-pub fn init(app: &mut App, port: u16) {
-    let public_addr = format!("0.0.0.0:{port}").parse().unwrap();
+///
+/// If `local_only` is true, only connections from this machine will be accepted.
+pub fn init(app: &mut App, port: u16, local_only: bool) {
+    let ip = if local_only { "127.0.0.1" } else { "0.0.0.0" };
+    let public_addr = format!("{ip}:{port}").parse().unwrap();
     let socket = NativeSocket::new(UdpSocket::bind(public_addr).unwrap()).unwrap();
 
     let current_time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap();
@@ -10,6 +10,8 @@ use bevy::prelude::*;
 use cosmos_core::netty::cosmos_encoder;
 use serde::{Deserialize, Serialize};
 
+use crate::settings::ServerSettings;
+
 #[derive(Debug, Resource, Deref, Serialize, Deserialize, Clone, Copy)]
 /// This sets the seed the server uses to generate the universe
 pub struct ServerSeed(u64);
@@ -28,15 +30,22 @@ impl ServerSeed {
     /// Computes a "random" number at the given x, y, z coordinates.
     ///
     /// This randomness is based off a hash of the coordinates with this seed.
+    ///
+    /// Prefer using a [`crate::rng::SubSeed`] for the specific thing you're generating.
     pub fn chaos_hash(&self, x: f64, y: f64, z: f64) -> i64 {
-        let wrapping_seed = Wrapping(self.0 as i64);
+        chaos_hash(self.0, x, y, z)
+    }
+}
 
-        let mut h =
-            wrapping_seed + (Wrapping((x * 374761393.0) as i64) + Wrapping((y * 668265263.0) as i64) + Wrapping((z * 1610612741.0) as i64)); //all constants are prime
+/// Computes a "random" number at the given x, y, z coordinates, based off a hash of the coordinates with this seed.
+pub(crate) fn chaos_hash(seed: u64, x: f64, y: f64, z: f64) -> i64 {
+    let wrapping_seed = Wrapping(seed as i64);
 
-        h = (h ^ (h >> 13)) * Wrapping(1274126177);
-        (h ^ Wrapping(h.0 >> 16)).0
-    }
+    let mut h =
+        wrapping_seed + (Wrapping((x * 374761393.0) as i64) + Wrapping((y * 668265263.0) as i64) + Wrapping((z * 1610612741.0) as i64)); //all constants are prime
+
+    h = (h ^ (h >> 13)) * Wrapping(1274126177);
+    (h ^ Wrapping(h.0 >> 16)).0
 }
 
 #[derive(Resource, Debug, Clone, Deref, DerefMut, Default)]
@@ -66,10 +75,22 @@ impl ReadOnlyNoise {
 }
 
 pub(super) fn register(app: &mut App) {
+    let configured_seed = app.world().get_resource::<ServerSettings>().and_then(|x| x.seed);
+
     let server_seed = if let Ok(seed) = fs::read("./world/seed.dat") {
-        cosmos_encoder::deserialize::<ServerSeed>(&seed).expect("Unable to understand './world/seed.dat' seed file. Is it corrupted?")
+        let seed =
+            cosmos_encoder::deserialize::<ServerSeed>(&seed).expect("Unable to understand './world/seed.dat' seed file. Is it corrupted?");
+
+        if configured_seed.is_some_and(|configured| configured != seed.as_u64()) {
+            warn!(
+                "A seed was given, but this world was already generated with the seed {} - the world's seed will be used.",
+                seed.as_u64()
+            );
+        }
+
+        seed
     } else {
-        let seed = ServerSeed(rand::random());
+        let seed = ServerSeed(configured_seed.unwrap_or_else(rand::random));
 
         fs::create_dir("./world/").expect("Error creating world directory!");
         fs::write("./world/seed.dat", cosmos_encoder::serialize(&seed)).expect("Error writing file './world/seed.dat'");
@@ -77,6 +98,8 @@ pub(super) fn register(app: &mut App) {
         seed
     };
 
+    info!("Using world seed {}", server_seed.as_u64());
+
     let noise = Noise(noise::OpenSimplex::new(server_seed.as_u32()));
     let read_noise = ReadOnlyNoise(Arc::new(RwLock::new(noise.clone())));
 
@@ -1,7 +1,7 @@
 use bevy::{
     app::Update,
     math::{Quat, Vec3},
-    prelude::{App, Commands, Entity, EventReader, GlobalTransform, IntoSystemConfigs, Query, Transform},
+    prelude::{App, Commands, Entity, EventReader, GlobalTransform, IntoSystemConfigs, Query, Res, Transform, With},
 };
 use bevy_rapier3d::prelude::Velocity;
 use cosmos_core::{
@@ -15,19 +15,28 @@ use cosmos_core::{
     structure::block_health::events::BlockDestroyedEvent,
 };
 
-use crate::structure::block_health::BlockHealthSet;
+use crate::{
+    blocks::interactable::storage_lock::{LockSettings, StorageLock},
+    structure::block_health::BlockHealthSet,
+};
 
 fn process_event(
     structure_entity: Entity,
     block: StructureBlock,
     q_inventory: &Query<&Inventory>,
     q_structure: &Query<(&Location, &GlobalTransform, &Structure, &Velocity)>,
+    q_locked: &Query<(), With<StorageLock>>,
+    lock_settings: &LockSettings,
     commands: &mut Commands,
 ) {
     let Ok((location, g_trans, structure, velocity)) = q_structure.get(structure_entity) else {
         return;
     };
 
+    if lock_settings.destroy_contents_on_break && structure.query_block_data(block.coords(), q_locked).is_some() {
+        return;
+    }
+
     let Some(inventory_here) = structure.query_block_data(block.coords(), q_inventory) else {
         return;
     };
@@ -65,22 +74,42 @@ fn process_event(
 fn monitor_block_detroy(
     q_inventory: Query<&Inventory>,
     q_structure: Query<(&Location, &GlobalTransform, &Structure, &Velocity)>,
+    q_locked: Query<(), With<StorageLock>>,
+    lock_settings: Res<LockSettings>,
     mut evr_block_destroy: EventReader<BlockDestroyedEvent>,
     mut commands: Commands,
 ) {
     for (block, structure_entity) in evr_block_destroy.read().map(|x| (x.block, x.structure_entity)) {
-        process_event(structure_entity, block, &q_inventory, &q_structure, &mut commands);
+        process_event(
+            structure_entity,
+            block,
+            &q_inventory,
+            &q_structure,
+            &q_locked,
+            &lock_settings,
+            &mut commands,
+        );
     }
 }
 
 fn monitor_block_breaks(
     q_inventory: Query<&Inventory>,
     q_structure: Query<(&Location, &GlobalTransform, &Structure, &Velocity)>,
+    q_locked: Query<(), With<StorageLock>>,
+    lock_settings: Res<LockSettings>,
     mut evr_block_break: EventReader<BlockBreakEvent>,
     mut commands: Commands,
 ) {
     for (block, structure_entity) in evr_block_break.read().map(|x| (x.block, x.block.structure())) {
-        process_event(structure_entity, block, &q_inventory, &q_structure, &mut commands);
+        process_event(
+            structure_entity,
+            block,
+            &q_inventory,
+            &q_structure,
+            &q_locked,
+            &lock_settings,
+            &mut commands,
+        );
     }
 }
 
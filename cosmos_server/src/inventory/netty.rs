@@ -1,7 +1,7 @@
 //! Syncs player inventories
 
 use bevy::{
-    ecs::world::Mut,
+    ecs::{system::SystemParam, world::Mut},
     log::warn,
     math::{Quat, Vec3},
     prelude::{
@@ -18,14 +18,20 @@ use cosmos_core::{
         HeldItemStack, Inventory,
     },
     item::physical_item::PhysicalItem,
-    netty::{cosmos_encoder, server::ServerLobby, NettyChannelClient, NettyChannelServer},
+    netty::{
+        network_stats::{NettyMessage, NettyServerMessages},
+        server::ServerLobby,
+        NettyChannelClient, NettyChannelServer,
+    },
     persistence::LoadingDistance,
     physics::location::Location,
     state::GameState,
-    structure::Structure,
+    structure::{structure_block::StructureBlock, Structure},
 };
 
-use crate::entities::player::PlayerLooking;
+use crate::{
+    blocks::interactable::storage_lock::StorageLocks, entities::player::PlayerLooking, structure::ownership::StructurePermissions,
+};
 
 fn sync_held_items(
     query: Query<(&Player, &HeldItemStack), Changed<HeldItemStack>>,
@@ -34,10 +40,10 @@ fn sync_held_items(
     mut server: ResMut<RenetServer>,
 ) {
     for (player, held_itemstack) in query.iter() {
-        server.send_message(
+        server.send_netty_message(
             player.id(),
             NettyChannelServer::Inventory,
-            cosmos_encoder::serialize(&ServerInventoryMessages::HeldItemstack {
+            NettyMessage::new(&ServerInventoryMessages::HeldItemstack {
                 itemstack: Some(held_itemstack.clone()),
             }),
         );
@@ -45,23 +51,43 @@ fn sync_held_items(
 
     for removed_held_item in removed_held_itemstacks.read() {
         if let Ok(player) = player_query.get(removed_held_item) {
-            server.send_message(
+            server.send_netty_message(
                 player.id(),
                 NettyChannelServer::Inventory,
-                cosmos_encoder::serialize(&ServerInventoryMessages::HeldItemstack { itemstack: None }),
+                NettyMessage::new(&ServerInventoryMessages::HeldItemstack { itemstack: None }),
             );
         }
     }
 }
 
+#[derive(SystemParam)]
+struct InventoryAccess<'w, 's> {
+    permissions: StructurePermissions<'w, 's>,
+    locks: StorageLocks<'w, 's>,
+}
+
+impl InventoryAccess<'_, '_> {
+    /// Players need access to the structure & to have unlocked the storage (if it's locked) to use a block's inventory
+    fn can_access(&self, player: Entity, block: StructureBlock) -> bool {
+        self.permissions.can_use(player, block.structure()) && self.locks.can_open(player, block)
+    }
+}
+
 fn get_inventory_mut<'a>(
     identifier: InventoryIdentifier,
     q_inventory: &'a mut Query<&mut Inventory>,
     q_structure: &'a Query<&Structure>,
+    player: Entity,
+    access: &InventoryAccess,
 ) -> Option<Mut<'a, Inventory>> {
     match identifier {
         InventoryIdentifier::Entity(entity) => q_inventory.get_mut(entity).ok(),
         InventoryIdentifier::BlockData(block_data) => {
+            if !access.can_access(player, block_data.block) {
+                warn!("Player {player:?} tried to access an inventory they don't have access to.");
+                return None;
+            }
+
             let Ok(structure) = q_structure.get(block_data.block.structure()) else {
                 warn!("Missing structure entity for {:?}", block_data.block.structure());
                 return None;
@@ -85,12 +111,19 @@ fn get_many_inventories_mut<'a, const N: usize>(
     identifiers: [InventoryIdentifier; N],
     q_inventory: &'a mut Query<&mut Inventory>,
     q_structure: &'a Query<&Structure>,
+    player: Entity,
+    access: &InventoryAccess,
 ) -> Option<[Mut<'a, Inventory>; N]> {
     let ents = identifiers
         .into_iter()
         .map(|x| match x {
             InventoryIdentifier::Entity(entity) => Some(entity),
             InventoryIdentifier::BlockData(block_data) => {
+                if !access.can_access(player, block_data.block) {
+                    warn!("Player {player:?} tried to access an inventory they don't have access to.");
+                    return None;
+                }
+
                 let structure = q_structure.get(block_data.block.structure()).ok()?;
 
                 structure.block_data(block_data.block.coords())
@@ -111,15 +144,15 @@ fn listen_for_inventory_messages(
     mut server: ResMut<RenetServer>,
     q_player: Query<(&Location, &GlobalTransform, &PlayerLooking, &Velocity)>,
     lobby: Res<ServerLobby>,
+    access: InventoryAccess,
 ) {
     for client_id in server.clients_id().into_iter() {
-        while let Some(message) = server.receive_message(client_id, NettyChannelClient::Inventory) {
+        while let Some(message) = server.receive_netty_message::<ClientInventoryMessages>(client_id, NettyChannelClient::Inventory) {
             let Some(client_entity) = lobby.player_from_id(client_id) else {
                 continue;
             };
 
-            let msg: ClientInventoryMessages =
-                cosmos_encoder::deserialize(&message).expect("Failed to deserialize server inventory message!");
+            let msg: ClientInventoryMessages = message.expect("Failed to deserialize server inventory message!");
 
             match msg {
                 ClientInventoryMessages::SwapSlots {
@@ -129,13 +162,14 @@ fn listen_for_inventory_messages(
                     inventory_b,
                 } => {
                     if inventory_a == inventory_b {
-                        if let Some(mut inventory) = get_inventory_mut(inventory_a, &mut q_inventory, &q_structure) {
+                        if let Some(mut inventory) = get_inventory_mut(inventory_a, &mut q_inventory, &q_structure, client_entity, &access)
+                        {
                             inventory
                                 .self_swap_slots(slot_a as usize, slot_b as usize, &mut commands)
                                 .unwrap_or_else(|_| panic!("Got bad inventory slots from player! {}, {}", slot_a, slot_b));
                         }
                     } else if let Some([mut inventory_a, mut inventory_b]) =
-                        get_many_inventories_mut([inventory_a, inventory_b], &mut q_inventory, &q_structure)
+                        get_many_inventories_mut([inventory_a, inventory_b], &mut q_inventory, &q_structure, client_entity, &access)
                     {
                         inventory_a
                             .swap_slots(slot_a as usize, &mut inventory_b, slot_b as usize, &mut commands)
@@ -149,14 +183,20 @@ fn listen_for_inventory_messages(
                     to_inventory,
                 } => {
                     if from_inventory == to_inventory {
-                        if let Some(mut inventory) = get_inventory_mut(from_inventory, &mut q_inventory, &q_structure) {
+                        if let Some(mut inventory) =
+                            get_inventory_mut(from_inventory, &mut q_inventory, &q_structure, client_entity, &access)
+                        {
                             inventory
                                 .auto_move(from_slot as usize, quantity, &mut commands)
                                 .unwrap_or_else(|_| panic!("Got bad inventory slot from player! {}", from_slot));
                         }
-                    } else if let Some([mut from_inventory, mut to_inventory]) =
-                        get_many_inventories_mut([from_inventory, to_inventory], &mut q_inventory, &q_structure)
-                    {
+                    } else if let Some([mut from_inventory, mut to_inventory]) = get_many_inventories_mut(
+                        [from_inventory, to_inventory],
+                        &mut q_inventory,
+                        &q_structure,
+                        client_entity,
+                        &access,
+                    ) {
                         let from_slot = from_slot as usize;
                         if let Some(mut is) = from_inventory.remove_itemstack_at(from_slot) {
                             let (leftover, _) = to_inventory.insert_itemstack(&is, &mut commands);
@@ -183,14 +223,20 @@ fn listen_for_inventory_messages(
                     to_slot,
                 } => {
                     if from_inventory == to_inventory {
-                        if let Some(mut inventory) = get_inventory_mut(from_inventory, &mut q_inventory, &q_structure) {
+                        if let Some(mut inventory) =
+                            get_inventory_mut(from_inventory, &mut q_inventory, &q_structure, client_entity, &access)
+                        {
                             inventory
                                 .self_move_itemstack(from_slot as usize, to_slot as usize, quantity, &mut commands)
                                 .unwrap_or_else(|_| panic!("Got bad inventory slots from player! {}, {}", from_slot, to_slot));
                         }
-                    } else if let Some([mut inventory_a, mut inventory_b]) =
-                        get_many_inventories_mut([from_inventory, to_inventory], &mut q_inventory, &q_structure)
-                    {
+                    } else if let Some([mut inventory_a, mut inventory_b]) = get_many_inventories_mut(
+                        [from_inventory, to_inventory],
+                        &mut q_inventory,
+                        &q_structure,
+                        client_entity,
+                        &access,
+                    ) {
                         inventory_a
                             .move_itemstack(from_slot as usize, &mut inventory_b, to_slot as usize, quantity, &mut commands)
                             .unwrap_or_else(|_| panic!("Got bad inventory slots from player! {}, {}", from_slot, to_slot));
@@ -205,10 +251,10 @@ fn listen_for_inventory_messages(
 
                     // Check if already holding - if so you can't pick up more stuff
                     if let Ok(is) = held_item_query.get(client_entity) {
-                        server.send_message(
+                        server.send_netty_message(
                             client_id,
                             NettyChannelServer::Inventory,
-                            cosmos_encoder::serialize(&ServerInventoryMessages::HeldItemstack {
+                            NettyMessage::new(&ServerInventoryMessages::HeldItemstack {
                                 itemstack: Some(is.clone()),
                             }),
                         );
@@ -217,7 +263,8 @@ fn listen_for_inventory_messages(
 
                     // TODO: Check if has access to inventory
 
-                    if let Some(mut inventory) = get_inventory_mut(inventory_holder, &mut q_inventory, &q_structure) {
+                    if let Some(mut inventory) = get_inventory_mut(inventory_holder, &mut q_inventory, &q_structure, client_entity, &access)
+                    {
                         if let Some(is) = inventory.mut_itemstack_at(slot) {
                             let quantity = quantity.min(is.quantity());
 
@@ -244,17 +291,18 @@ fn listen_for_inventory_messages(
 
                     let Ok(mut held_is) = held_item_query.get_mut(client_entity) else {
                         // Perhaps the client needs updated
-                        server.send_message(
+                        server.send_netty_message(
                             client_id,
                             NettyChannelServer::Inventory,
-                            cosmos_encoder::serialize(&ServerInventoryMessages::HeldItemstack { itemstack: None }),
+                            NettyMessage::new(&ServerInventoryMessages::HeldItemstack { itemstack: None }),
                         );
                         continue;
                     };
 
                     // TODO: Check if has access to inventory
 
-                    if let Some(mut inventory) = get_inventory_mut(inventory_holder, &mut q_inventory, &q_structure) {
+                    if let Some(mut inventory) = get_inventory_mut(inventory_holder, &mut q_inventory, &q_structure, client_entity, &access)
+                    {
                         let quantity = quantity.min(held_is.quantity()); // make sure we don't deposit more than we have
                         let mut moving_is = held_is.clone();
                         moving_is.set_quantity(quantity);
@@ -276,17 +324,18 @@ fn listen_for_inventory_messages(
 
                     let Ok(mut held_item_stack) = held_item_query.get_mut(client_entity) else {
                         // Perhaps the client needs updated
-                        server.send_message(
+                        server.send_netty_message(
                             client_id,
                             NettyChannelServer::Inventory,
-                            cosmos_encoder::serialize(&ServerInventoryMessages::HeldItemstack { itemstack: None }),
+                            NettyMessage::new(&ServerInventoryMessages::HeldItemstack { itemstack: None }),
                         );
                         continue;
                     };
 
                     // TODO: Check if has access to inventory
 
-                    if let Some(mut inventory) = get_inventory_mut(inventory_holder, &mut q_inventory, &q_structure) {
+                    if let Some(mut inventory) = get_inventory_mut(inventory_holder, &mut q_inventory, &q_structure, client_entity, &access)
+                    {
                         let itemstack_here = inventory.remove_itemstack_at(slot);
 
                         let leftover = inventory.insert_itemstack_at(slot, &held_item_stack, &mut commands);
@@ -310,7 +359,8 @@ fn listen_for_inventory_messages(
                     slot,
                     inventory_holder,
                 } => {
-                    let Some(mut inventory) = get_inventory_mut(inventory_holder, &mut q_inventory, &q_structure) else {
+                    let Some(mut inventory) = get_inventory_mut(inventory_holder, &mut q_inventory, &q_structure, client_entity, &access)
+                    else {
                         continue;
                     };
 
@@ -358,10 +408,10 @@ fn listen_for_inventory_messages(
                 ClientInventoryMessages::ThrowHeldItemstack { quantity } => {
                     let Ok(mut held_item_stack) = held_item_query.get_mut(client_entity) else {
                         // Perhaps the client needs updated
-                        server.send_message(
+                        server.send_netty_message(
                             client_id,
                             NettyChannelServer::Inventory,
-                            cosmos_encoder::serialize(&ServerInventoryMessages::HeldItemstack { itemstack: None }),
+                            NettyMessage::new(&ServerInventoryMessages::HeldItemstack { itemstack: None }),
                         );
                         continue;
                     };
@@ -414,17 +464,18 @@ fn listen_for_inventory_messages(
                 } => {
                     let Ok(mut held_item_stack) = held_item_query.get_mut(client_entity) else {
                         // Perhaps the client needs updated
-                        server.send_message(
+                        server.send_netty_message(
                             client_id,
                             NettyChannelServer::Inventory,
-                            cosmos_encoder::serialize(&ServerInventoryMessages::HeldItemstack { itemstack: None }),
+                            NettyMessage::new(&ServerInventoryMessages::HeldItemstack { itemstack: None }),
                         );
                         continue;
                     };
 
                     let quantity = held_item_stack.quantity().min(quantity);
 
-                    if let Some(mut inventory) = get_inventory_mut(inventory_holder, &mut q_inventory, &q_structure) {
+                    if let Some(mut inventory) = get_inventory_mut(inventory_holder, &mut q_inventory, &q_structure, client_entity, &access)
+                    {
                         let unused_leftover = held_item_stack.quantity() - quantity;
                         let mut is = held_item_stack.clone();
                         is.set_quantity(unused_leftover);
@@ -0,0 +1,146 @@
+//! Gives batteries their charge, and lets players use them to jump-start a structure's energy storage
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::block_events::{BlockEventsSet, BlockInteractEvent},
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    inventory::{
+        held_item_slot::HeldItemSlot,
+        itemstack::{ItemShouldHaveData, ItemStackData, ItemStackNeedsDataCreated, ItemStackSystemSet},
+        Inventory,
+    },
+    item::{
+        battery::{BatteryCharge, BATTERY_CAPACITY, BATTERY_ITEM},
+        Item,
+    },
+    netty::{sync::events::server_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::systems::{energy_storage_system::EnergyStorageSystem, StructureSystems},
+};
+
+use crate::{
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+    structure::ownership::{notify_no_permission, StructurePermissions},
+};
+
+impl DefaultPersistentComponent for BatteryCharge {}
+
+/// Takes `amount` energy from the first battery in this inventory that has enough of it.
+///
+/// Returns true if the energy was taken. No energy is taken if no single battery has enough.
+pub fn draw_from_batteries(inventory: &Inventory, amount: f32, q_charge: &mut Query<&mut BatteryCharge>) -> bool {
+    for data_ent in inventory.iter().flatten().flat_map(|is| is.data_entity()) {
+        let Ok(mut charge) = q_charge.get_mut(data_ent) else {
+            continue;
+        };
+
+        if charge.energy() >= amount {
+            charge.drain(amount);
+            return true;
+        }
+    }
+
+    false
+}
+
+fn register_battery_item(items: Res<Registry<Item>>, mut needs_data: ResMut<ItemShouldHaveData>) {
+    if let Some(battery) = items.from_id(BATTERY_ITEM) {
+        needs_data.add_item(battery);
+    }
+}
+
+fn add_battery_charge(
+    q_needs_data: Query<(Entity, &ItemStackData), (Without<BatteryCharge>, With<ItemStackNeedsDataCreated>)>,
+    mut commands: Commands,
+    items: Res<Registry<Item>>,
+) {
+    let Some(battery) = items.from_id(BATTERY_ITEM) else {
+        return;
+    };
+
+    for (ent, is_data) in q_needs_data.iter() {
+        if is_data.item_id != battery.id() {
+            continue;
+        }
+
+        commands.entity(ent).insert(BatteryCharge::empty(BATTERY_CAPACITY));
+    }
+}
+
+/// Crouch-interacting with a structure while holding a battery empties the battery into that structure's energy storage.
+///
+/// This is how derelicts with no power can be brought back to life.
+fn jump_start_structure(
+    mut evr_block_interact: EventReader<BlockInteractEvent>,
+    q_player: Query<(&Player, &Inventory, &HeldItemSlot)>,
+    mut q_charge: Query<&mut BatteryCharge>,
+    q_systems: Query<&StructureSystems>,
+    mut q_energy_storage: Query<&mut EnergyStorageSystem>,
+    items: Res<Registry<Item>>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for ev in evr_block_interact.read() {
+        if !ev.alternate {
+            continue;
+        }
+
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok((player, inventory, held_slot)) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        let Some(held_is) = inventory.itemstack_at(held_slot.slot() as usize) else {
+            continue;
+        };
+
+        if items.from_numeric_id(held_is.item_id()).unlocalized_name() != BATTERY_ITEM {
+            continue;
+        }
+
+        let Some(mut charge) = held_is.data_entity().and_then(|e| q_charge.get_mut(e).ok()) else {
+            continue;
+        };
+
+        let Ok(systems) = q_systems.get(s_block.structure()) else {
+            continue;
+        };
+
+        let Ok(mut storage) = systems.query_mut(&mut q_energy_storage) else {
+            continue;
+        };
+
+        let amount = charge.energy().min(storage.get_capacity() - storage.get_energy());
+        if amount <= 0.0 {
+            continue;
+        }
+
+        if !permissions.can_use(ev.interactor, s_block.structure()) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        charge.drain(amount);
+        storage.increase_energy(amount);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<BatteryCharge>(app);
+
+    app.add_systems(OnEnter(GameState::PostLoading), register_battery_item).add_systems(
+        Update,
+        (
+            add_battery_charge.in_set(ItemStackSystemSet::FillDataEntity),
+            jump_start_structure
+                .in_set(BlockEventsSet::ProcessEvents)
+                .run_if(in_state(GameState::Playing)),
+        )
+            .in_set(NetworkingSystemsSet::Between),
+    );
+}
@@ -0,0 +1,248 @@
+//! Jettisoning cargo from ships as cargo pods, and collecting those pods again.
+//!
+//! Jettisoned pods drift away from the back of their ship. Any other ship being piloted pulls pods within
+//! [`TRACTOR_RANGE`] of it in with its tractor beam, and moves whatever it can fit into its storage once the pod is
+//! close enough. Pods are saved with their contents, but break apart after [`CARGO_POD_LIFETIME`].
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use cosmos_core::{
+    block::{block_events::BlockEventsSet, data::BlockData, Block},
+    ecs::NeedsDespawned,
+    inventory::Inventory,
+    item::cargo_pod::{CargoPod, JettisonCargoEvent},
+    netty::{
+        server::ServerLobby,
+        sync::{events::server_event::NettyEventReceived, IdentifiableComponent},
+        system_sets::NetworkingSystemsSet,
+    },
+    persistence::LoadingDistance,
+    physics::location::{Location, SetPosition},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        ship::{pilot::Pilot, Ship},
+        Structure,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    blocks::interactable::storage_lock::StorageLocks,
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+    structure::ownership::StructurePermissions,
+};
+
+const STORAGE_BLOCK: &str = "cosmos:storage";
+
+/// How long a cargo pod floats around before breaking apart
+const CARGO_POD_LIFETIME: Duration = Duration::from_mins(30);
+
+/// How far (in blocks) from a piloted ship cargo pods are pulled in by its tractor beam
+const TRACTOR_RANGE: f32 = 60.0;
+
+/// How fast (in blocks per second) pods are pulled towards a ship
+const TRACTOR_SPEED: f32 = 8.0;
+
+/// How far (in blocks) past the edge of a ship pods are collected
+const COLLECT_DISTANCE: f32 = 3.0;
+
+/// How fast (in blocks per second) pods are pushed away from the ship that jettisoned them
+const JETTISON_SPEED: f32 = 4.0;
+
+#[derive(Default, Component, Debug, Reflect, Serialize, Deserialize, Clone, Copy, PartialEq)]
+/// The time (in seconds) since this cargo pod was jettisoned
+struct CargoPodAge(f32);
+
+impl IdentifiableComponent for CargoPodAge {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:cargo_pod_age"
+    }
+}
+
+impl DefaultPersistentComponent for CargoPodAge {}
+
+impl DefaultPersistentComponent for CargoPod {}
+
+#[derive(Component, Debug)]
+/// The ship this pod was jettisoned from, which won't pull it back in
+struct JettisonedFrom(Entity);
+
+fn on_jettison_cargo(
+    mut commands: Commands,
+    mut nevr_jettison: EventReader<NettyEventReceived<JettisonCargoEvent>>,
+    lobby: Res<ServerLobby>,
+    q_ship: Query<(&Structure, &Location, &GlobalTransform, &Velocity), With<Ship>>,
+    mut q_inventory: Query<&mut Inventory, With<BlockData>>,
+    blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    locks: StorageLocks,
+) {
+    for ev in nevr_jettison.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let ship_ent = ev.storage.structure();
+
+        if !permissions.can_use(player_ent, ship_ent) || !locks.can_open(player_ent, ev.storage) {
+            warn!("Player {player_ent:?} tried to jettison cargo they don't have access to.");
+            continue;
+        }
+
+        let Ok((structure, location, g_trans, velocity)) = q_ship.get(ship_ent) else {
+            continue;
+        };
+
+        let coords = ev.storage.coords();
+        if structure.block_at(coords, &blocks).unlocalized_name() != STORAGE_BLOCK {
+            continue;
+        }
+
+        let Some(mut storage) = structure.block_data(coords).and_then(|ent| q_inventory.get_mut(ent).ok()) else {
+            continue;
+        };
+
+        let cargo = (0..storage.len())
+            .filter_map(|slot| storage.remove_itemstack_at(slot))
+            .collect::<Vec<_>>();
+
+        if cargo.is_empty() {
+            continue;
+        }
+
+        // Pods come out of the back of the ship, behind the storage they came from
+        let rotation = g_trans.compute_transform().rotation;
+        let block_pos = structure.block_relative_position(coords);
+        let behind_ship = Vec3::new(
+            block_pos.x,
+            block_pos.y,
+            structure.block_dimensions().z as f32 / 2.0 + COLLECT_DISTANCE,
+        );
+
+        let pod_ent = commands
+            .spawn((
+                CargoPod,
+                CargoPodAge::default(),
+                JettisonedFrom(ship_ent),
+                *location + rotation * behind_ship,
+                LoadingDistance::new(1, 2),
+                Transform::from_rotation(rotation),
+                SetPosition::Transform,
+                Velocity {
+                    linvel: velocity.linvel + rotation * Vec3::Z * JETTISON_SPEED,
+                    angvel: Vec3::ZERO,
+                },
+            ))
+            .id();
+
+        let mut pod_inventory = Inventory::new("Cargo Pod", cargo.len(), None, pod_ent);
+        for (slot, is) in cargo.into_iter().enumerate() {
+            pod_inventory.set_itemstack_at(slot, Some(is), &mut commands);
+        }
+
+        commands.entity(pod_ent).insert(pod_inventory);
+    }
+}
+
+fn age_cargo_pods(mut commands: Commands, time: Res<Time>, mut q_pods: Query<(Entity, &mut CargoPodAge)>) {
+    for (ent, mut age) in q_pods.iter_mut() {
+        age.0 += time.delta_secs();
+
+        if age.0 > CARGO_POD_LIFETIME.as_secs_f32() {
+            commands.entity(ent).insert(NeedsDespawned);
+        }
+    }
+}
+
+/// Piloted ships pull in nearby pods, then move their cargo into storage once they're close enough
+fn tractor_cargo_pods(
+    mut commands: Commands,
+    mut q_pods: Query<(Entity, &Location, &mut Velocity, &mut Inventory, Option<&JettisonedFrom>), With<CargoPod>>,
+    q_ships: Query<(Entity, &Location, &Structure, &Velocity), (With<Ship>, With<Pilot>, Without<CargoPod>)>,
+    mut q_storage: Query<(&BlockData, &mut Inventory), Without<CargoPod>>,
+    blocks: Res<Registry<Block>>,
+) {
+    let Some(storage_block) = blocks.from_id(STORAGE_BLOCK) else {
+        return;
+    };
+
+    for (pod_ent, pod_loc, mut pod_velocity, mut pod_inventory, jettisoned_from) in q_pods.iter_mut() {
+        let closest = q_ships
+            .iter()
+            .filter(|(ship_ent, ship_loc, _, _)| {
+                jettisoned_from.is_none_or(|from| from.0 != *ship_ent)
+                    && ship_loc.is_within_reasonable_range(pod_loc)
+                    && ship_loc.distance_sqrd(pod_loc) <= TRACTOR_RANGE * TRACTOR_RANGE
+            })
+            .min_by(|a, b| a.1.distance_sqrd(pod_loc).total_cmp(&b.1.distance_sqrd(pod_loc)));
+
+        let Some((ship_ent, ship_loc, structure, ship_velocity)) = closest else {
+            continue;
+        };
+
+        let dims = structure.block_dimensions();
+        let ship_radius = dims.x.max(dims.y).max(dims.z) as f32 / 2.0;
+
+        let to_ship = pod_loc.relative_coords_to(ship_loc);
+
+        if to_ship.length() > ship_radius + COLLECT_DISTANCE {
+            pod_velocity.linvel = ship_velocity.linvel + to_ship.normalize_or_zero() * TRACTOR_SPEED;
+            continue;
+        }
+
+        let mut storages = q_storage
+            .iter_mut()
+            .filter(|(block_data, _)| {
+                block_data.identifier.block.structure() == ship_ent && block_data.identifier.block_id == storage_block.id()
+            })
+            .map(|(_, inventory)| inventory)
+            .collect::<Vec<_>>();
+
+        for slot in 0..pod_inventory.len() {
+            let Some(is) = pod_inventory.itemstack_at(slot).cloned() else {
+                continue;
+            };
+
+            let mut left_over = is.quantity();
+            for storage in storages.iter_mut() {
+                let mut remaining = is.clone();
+                remaining.set_quantity(left_over);
+
+                (left_over, _) = storage.insert_itemstack(&remaining, &mut commands);
+
+                if left_over == 0 {
+                    break;
+                }
+            }
+
+            pod_inventory.decrease_quantity_at(slot, is.quantity() - left_over, &mut commands);
+        }
+
+        if pod_inventory.iter().all(|is| is.is_none()) {
+            commands.entity(pod_ent).insert(NeedsDespawned);
+        } else {
+            // The ship is full, so stop pulling the pod in
+            pod_velocity.linvel = ship_velocity.linvel;
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<CargoPod>(app);
+    make_persistent::<CargoPodAge>(app);
+
+    app.add_systems(
+        Update,
+        (
+            on_jettison_cargo.in_set(BlockEventsSet::ProcessEvents),
+            (age_cargo_pods, tractor_cargo_pods).chain(),
+        )
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    )
+    .register_type::<CargoPodAge>();
+}
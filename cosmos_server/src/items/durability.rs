@@ -0,0 +1,133 @@
+//! Wears out tools as they are used, breaking them once they run out of durability
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::block_events::{BlockBreakEvent, BlockEventsSet},
+    entities::player::creative::Creative,
+    inventory::{
+        held_item_slot::HeldItemSlot,
+        itemstack::{ItemShouldHaveData, ItemStackData, ItemStackNeedsDataCreated, ItemStackSystemSet},
+        Inventory,
+    },
+    item::{
+        battery::{BatteryCharge, TOOL_ENERGY_PER_BLOCK},
+        tool::{ItemDurability, Tool},
+        Item,
+    },
+    netty::system_sets::NetworkingSystemsSet,
+    prelude::Structure,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+};
+
+use crate::persistence::make_persistent::{make_persistent, DefaultPersistentComponent};
+
+use super::battery::draw_from_batteries;
+
+impl DefaultPersistentComponent for ItemDurability {}
+
+/// Uses up `amount` durability of the item in this inventory slot.
+///
+/// If the item runs out of durability, it breaks and is removed from the inventory.
+/// Items without [`ItemDurability`] are left alone.
+pub fn wear_item_at(
+    inventory: &mut Inventory,
+    slot: usize,
+    amount: u32,
+    q_durability: &mut Query<&mut ItemDurability>,
+    commands: &mut Commands,
+) {
+    let Some(mut durability) = inventory
+        .itemstack_at(slot)
+        .and_then(|is| is.data_entity())
+        .and_then(|data_ent| q_durability.get_mut(data_ent).ok())
+    else {
+        return;
+    };
+
+    if durability.damage(amount) {
+        inventory.decrease_quantity_at(slot, 1, commands);
+    }
+}
+
+fn register_tool_items(items: Res<Registry<Item>>, tools: Res<Registry<Tool>>, mut needs_data: ResMut<ItemShouldHaveData>) {
+    for tool in tools.iter() {
+        if let Some(item) = items.from_id(tool.unlocalized_name()) {
+            needs_data.add_item(item);
+        }
+    }
+}
+
+fn add_item_durability(
+    q_needs_data: Query<(Entity, &ItemStackData), (Without<ItemDurability>, With<ItemStackNeedsDataCreated>)>,
+    mut commands: Commands,
+    items: Res<Registry<Item>>,
+    tools: Res<Registry<Tool>>,
+) {
+    for (ent, is_data) in q_needs_data.iter() {
+        let item = items.from_numeric_id(is_data.item_id);
+
+        let Some(tool) = tools.from_id(item.unlocalized_name()) else {
+            continue;
+        };
+
+        commands.entity(ent).insert(ItemDurability::new(tool.max_durability()));
+    }
+}
+
+pub(super) fn wear_tools_on_block_break(
+    mut evr_block_break: EventReader<BlockBreakEvent>,
+    mut q_player: Query<(&HeldItemSlot, &mut Inventory), Without<Creative>>,
+    q_structure: Query<&Structure>,
+    mut q_durability: Query<&mut ItemDurability>,
+    mut q_charge: Query<&mut BatteryCharge>,
+    items: Res<Registry<Item>>,
+    tools: Res<Registry<Tool>>,
+    mut commands: Commands,
+) {
+    for ev in evr_block_break.read() {
+        let Ok((held_item, mut inventory)) = q_player.get_mut(ev.breaker) else {
+            continue;
+        };
+
+        // The block is still present here, since blocks are removed in `BlockEventsSet::ChangeBlocks`
+        if !q_structure
+            .get(ev.block.structure())
+            .is_ok_and(|structure| structure.has_block_at(ev.block.coords()))
+        {
+            continue;
+        }
+
+        let slot = held_item.slot() as usize;
+
+        // Only tools that help mine blocks wear out from mining
+        let is_mining_tool = inventory
+            .itemstack_at(slot)
+            .and_then(|is| tools.from_id(items.from_numeric_id(is.item_id()).unlocalized_name()))
+            .is_some_and(|tool| tool.tier().is_some());
+
+        if !is_mining_tool {
+            continue;
+        }
+
+        // Powered tools don't wear down
+        if draw_from_batteries(&inventory, TOOL_ENERGY_PER_BLOCK, &mut q_charge) {
+            continue;
+        }
+
+        wear_item_at(&mut inventory, slot, 1, &mut q_durability, &mut commands);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<ItemDurability>(app);
+
+    app.add_systems(OnEnter(GameState::PostLoading), register_tool_items).add_systems(
+        Update,
+        (
+            add_item_durability.in_set(ItemStackSystemSet::FillDataEntity),
+            wear_tools_on_block_break.in_set(BlockEventsSet::PreProcessEvents),
+        )
+            .in_set(NetworkingSystemsSet::Between),
+    );
+}
@@ -14,12 +14,19 @@ use cosmos_core::{
     inventory::Inventory,
     item::physical_item::PhysicalItem,
     netty::{sync::IdentifiableComponent, system_sets::NetworkingSystemsSet},
+    persistence::LoadingDistance,
     physics::location::Location,
 };
 use serde::{Deserialize, Serialize};
 
 use crate::persistence::make_persistent::{make_persistent, DefaultPersistentComponent};
 
+pub mod battery;
+mod cargo_pod;
+pub mod durability;
+mod scanner;
+mod upgrade_modules;
+
 #[derive(Default, Component, Debug, Reflect, Serialize, Deserialize, Clone, Copy, PartialEq)]
 /// The time (in seconds) since this physcal item was created.
 struct TimeSinceSpawn(pub f32);
@@ -55,6 +62,14 @@ fn advance_time_since_spawn(
     }
 }
 
+/// Dropped items are saved when they're unloaded, but only things with a [`LoadingDistance`] are ever unloaded.
+/// This makes sure items from anywhere get one, rather than staying loaded forever.
+fn add_loading_distance(mut commands: Commands, q_needs_loading_distance: Query<Entity, (With<PhysicalItem>, Without<LoadingDistance>)>) {
+    for ent in q_needs_loading_distance.iter() {
+        commands.entity(ent).insert(LoadingDistance::new(1, 2));
+    }
+}
+
 fn pickup_near_item(
     mut commands: Commands,
     mut q_physical_items: Query<(Entity, &Location, &mut Inventory, &TimeSinceSpawn), With<PhysicalItem>>,
@@ -91,12 +106,18 @@ fn pickup_near_item(
 }
 
 pub(super) fn register(app: &mut App) {
+    durability::register(app);
+    battery::register(app);
+    cargo_pod::register(app);
+    scanner::register(app);
+    upgrade_modules::register(app);
+
     make_persistent::<TimeSinceSpawn>(app);
     make_persistent::<PhysicalItem>(app);
 
     app.add_systems(
         Update,
-        (pickup_near_item, advance_time_since_spawn)
+        (add_loading_distance, pickup_near_item, advance_time_since_spawn)
             .chain()
             .in_set(NetworkingSystemsSet::Between),
     )
@@ -0,0 +1,142 @@
+//! Scans the planets & asteroids around players that use a handheld scanner for ore
+
+use bevy::{prelude::*, utils::HashMap};
+use cosmos_core::{
+    block::Block,
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    inventory::{held_item_slot::HeldItemSlot, Inventory},
+    item::{
+        scanner::{ScanResultEvent, UseScannerEvent, MAX_SCAN_RESULTS, ORE_BLOCKS, SCANNER_ITEM, SCAN_COOLDOWN_SECS, SCAN_RANGE},
+        Item,
+    },
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::{BlockCoordinate, Structure},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{asteroid::Asteroid, planet::Planet},
+};
+
+#[derive(Component, Debug)]
+/// When (in seconds since the server started) this player last scanned
+struct LastScan(f32);
+
+fn holding_scanner(inventory: &Inventory, held_slot: &HeldItemSlot, items: &Registry<Item>) -> bool {
+    inventory
+        .itemstack_at(held_slot.slot() as usize)
+        .is_some_and(|is| items.from_numeric_id(is.item_id()).unlocalized_name() == SCANNER_ITEM)
+}
+
+/// Every ore within [`SCAN_RANGE`] of this point (relative to the structure), along with its squared distance from the point
+fn find_ores(structure: &Structure, relative_pos: Vec3, ore_ids: &[u16]) -> Vec<(f32, BlockCoordinate)> {
+    let center = structure.relative_coords_to_local_coords(relative_pos.x, relative_pos.y, relative_pos.z);
+    let dims = structure.block_dimensions();
+
+    let range = |center: i64, dim: u64| (center - SCAN_RANGE).max(0)..=(center + SCAN_RANGE).min(dim as i64 - 1);
+
+    let mut ores = vec![];
+
+    for z in range(center.z, dims.z) {
+        for y in range(center.y, dims.y) {
+            for x in range(center.x, dims.x) {
+                let coords = BlockCoordinate::new(x as u64, y as u64, z as u64);
+
+                if ore_ids.contains(&structure.block_id_at(coords)) {
+                    let dist_sqrd = structure.block_relative_position(coords).distance_squared(relative_pos);
+                    ores.push((dist_sqrd, coords));
+                }
+            }
+        }
+    }
+
+    ores
+}
+
+fn on_use_scanner(
+    mut commands: Commands,
+    mut nevr_use_scanner: EventReader<NettyEventReceived<UseScannerEvent>>,
+    lobby: Res<ServerLobby>,
+    time: Res<Time>,
+    q_player: Query<(&Inventory, &HeldItemSlot, &GlobalTransform, Option<&LastScan>), With<Player>>,
+    q_structures: Query<(Entity, &Structure, &GlobalTransform), Or<(With<Planet>, With<Asteroid>)>>,
+    items: Res<Registry<Item>>,
+    blocks: Res<Registry<Block>>,
+    mut nevw_scan_result: NettyEventWriter<ScanResultEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for ev in nevr_use_scanner.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok((inventory, held_slot, player_g_trans, last_scan)) = q_player.get(player_ent) else {
+            continue;
+        };
+
+        if !holding_scanner(inventory, held_slot, &items) {
+            continue;
+        }
+
+        let now = time.elapsed_secs();
+        if let Some(last_scan) = last_scan {
+            let remaining = SCAN_COOLDOWN_SECS - (now - last_scan.0);
+
+            if remaining > 0.0 {
+                nevw_chat.send(
+                    ServerSendChatMessageEvent {
+                        sender: None,
+                        message: format!("Scanner is recharging ({}s)", remaining.ceil()),
+                    },
+                    ev.client_id,
+                );
+                continue;
+            }
+        }
+
+        commands.entity(player_ent).insert(LastScan(now));
+
+        let ore_ids = ORE_BLOCKS
+            .iter()
+            .filter_map(|ore| blocks.from_id(ore).map(|b| b.id()))
+            .collect::<Vec<_>>();
+
+        let player_pos = player_g_trans.translation();
+
+        let mut ores = q_structures
+            .iter()
+            .flat_map(|(ent, structure, g_trans)| {
+                let relative_pos = g_trans.affine().inverse().transform_point3(player_pos);
+
+                find_ores(structure, relative_pos, &ore_ids)
+                    .into_iter()
+                    .map(move |(dist_sqrd, coords)| (dist_sqrd, ent, coords))
+            })
+            .collect::<Vec<_>>();
+
+        ores.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+        ores.truncate(MAX_SCAN_RESULTS);
+
+        let mut by_structure = HashMap::<Entity, Vec<BlockCoordinate>>::default();
+        for (_, ent, coords) in ores {
+            by_structure.entry(ent).or_default().push(coords);
+        }
+
+        for (structure, ores) in by_structure {
+            nevw_scan_result.send(ScanResultEvent { structure, ores }, ev.client_id);
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        on_use_scanner
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
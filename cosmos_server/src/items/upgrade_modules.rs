@@ -0,0 +1,370 @@
+//! Installing upgrade modules into tools & ship weapon blocks, and the effects modules have on tools
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::{
+        block_events::{BlockBreakEvent, BlockEventsSet, BlockInteractEvent},
+        data::BlockData,
+        Block,
+    },
+    blockitems::BlockItems,
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    events::block_events::BlockDataSystemParams,
+    inventory::{
+        held_item_slot::HeldItemSlot,
+        itemstack::{ItemShouldHaveData, ItemStackSystemSet},
+        Inventory,
+    },
+    item::{
+        tool::Tool,
+        upgrade_module::{
+            InstallUpgradeModuleEvent, InstalledModules, OpenUpgradeModulesEvent, UninstallUpgradeModuleEvent, UpgradeModuleKind,
+            UpgradeTarget, UpgradeableKind, UPGRADEABLE_WEAPON_BLOCKS,
+        },
+        Item,
+    },
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::Structure,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::structure_block::StructureBlock,
+};
+
+use crate::{
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+    structure::ownership::{notify_no_permission, StructurePermissions},
+};
+
+use super::durability::wear_tools_on_block_break;
+
+/// Players can change modules from a little further than they can reach to account for latency
+const MAX_MODULE_EDIT_DISTANCE: f32 = 12.0;
+
+impl DefaultPersistentComponent for InstalledModules {}
+
+fn is_upgradeable_weapon(structure: &Structure, s_block: StructureBlock, blocks: &Registry<Block>) -> bool {
+    structure.is_within_blocks(s_block.coords())
+        && UPGRADEABLE_WEAPON_BLOCKS.contains(&structure.block_at(s_block.coords(), blocks).unlocalized_name())
+}
+
+/// Returns true if the held item is a tool that can mine blocks
+fn is_upgradeable_tool(inventory: &Inventory, held_item: &HeldItemSlot, items: &Registry<Item>, tools: &Registry<Tool>) -> bool {
+    inventory
+        .itemstack_at(held_item.slot() as usize)
+        .and_then(|is| tools.from_id(items.from_numeric_id(is.item_id()).unlocalized_name()))
+        .is_some_and(|tool| tool.tier().is_some())
+}
+
+fn on_interact_weapon_block(
+    mut evr_block_interact: EventReader<BlockInteractEvent>,
+    q_structure: Query<&Structure>,
+    q_player: Query<&Player>,
+    blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    mut nevw_open_modules: NettyEventWriter<OpenUpgradeModulesEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for ev in evr_block_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok(structure) = q_structure.get(s_block.structure()) else {
+            continue;
+        };
+
+        if !is_upgradeable_weapon(structure, s_block, &blocks) {
+            continue;
+        }
+
+        let Ok(player) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        if !permissions.can_use(ev.interactor, s_block.structure()) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        nevw_open_modules.send(OpenUpgradeModulesEvent(UpgradeTarget::Block(s_block)), player.id());
+    }
+}
+
+/// Makes sure this player is allowed to change the modules of this block
+fn can_edit_block_modules(
+    player_ent: Entity,
+    player_g_trans: &GlobalTransform,
+    s_block: StructureBlock,
+    structure: &Structure,
+    structure_g_trans: &GlobalTransform,
+    blocks: &Registry<Block>,
+) -> bool {
+    if !is_upgradeable_weapon(structure, s_block, blocks) {
+        warn!("Player {player_ent:?} tried to change the modules of a block that can't have any.");
+        return false;
+    }
+
+    let block_position = structure_g_trans.transform_point(structure.block_relative_position(s_block.coords()));
+    if block_position.distance_squared(player_g_trans.translation()) > MAX_MODULE_EDIT_DISTANCE * MAX_MODULE_EDIT_DISTANCE {
+        warn!("Player {player_ent:?} tried to change the modules of a block that is too far away.");
+        return false;
+    }
+
+    true
+}
+
+fn on_install_module(
+    mut nevr_install: EventReader<NettyEventReceived<InstallUpgradeModuleEvent>>,
+    lobby: Res<ServerLobby>,
+    mut q_player: Query<(&GlobalTransform, &HeldItemSlot, &mut Inventory), With<Player>>,
+    mut q_structure: Query<(&mut Structure, &GlobalTransform)>,
+    q_installed_modules: Query<&InstalledModules>,
+    q_has_modules: Query<(), With<InstalledModules>>,
+    mut q_block_data: Query<&mut BlockData>,
+    mut bs_params: BlockDataSystemParams,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    tools: Res<Registry<Tool>>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for ev in nevr_install.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok((player_g_trans, held_item, mut inventory)) = q_player.get_mut(player_ent) else {
+            continue;
+        };
+
+        let slot = ev.inventory_slot as usize;
+        if slot >= inventory.len() {
+            continue;
+        }
+
+        let Some(module) = inventory
+            .itemstack_at(slot)
+            .and_then(|is| UpgradeModuleKind::from_item_unlocalized_name(items.from_numeric_id(is.item_id()).unlocalized_name()))
+        else {
+            continue;
+        };
+
+        match ev.target {
+            UpgradeTarget::Block(s_block) => {
+                let Ok((mut structure, structure_g_trans)) = q_structure.get_mut(s_block.structure()) else {
+                    continue;
+                };
+
+                if !module.can_upgrade(UpgradeableKind::ShipWeapon)
+                    || !can_edit_block_modules(player_ent, player_g_trans, s_block, &structure, structure_g_trans, &blocks)
+                {
+                    continue;
+                }
+
+                if !permissions.can_use(player_ent, s_block.structure()) {
+                    notify_no_permission(&mut nevw_chat, ev.client_id);
+                    continue;
+                }
+
+                let mut modules = structure
+                    .query_block_data(s_block.coords(), &q_installed_modules)
+                    .cloned()
+                    .unwrap_or_default();
+
+                if !modules.install(module) {
+                    continue;
+                }
+
+                inventory.decrease_quantity_at(slot, 1, &mut bs_params.commands);
+
+                structure.insert_block_data(s_block.coords(), modules, &mut bs_params, &mut q_block_data, &q_has_modules);
+            }
+            UpgradeTarget::HeldItem => {
+                if !module.can_upgrade(UpgradeableKind::Tool) || !is_upgradeable_tool(&inventory, held_item, &items, &tools) {
+                    continue;
+                }
+
+                let Some(data_ent) = inventory.itemstack_at(held_item.slot() as usize).and_then(|is| is.data_entity()) else {
+                    continue;
+                };
+
+                let mut modules = q_installed_modules.get(data_ent).cloned().unwrap_or_default();
+
+                if !modules.install(module) {
+                    continue;
+                }
+
+                inventory.decrease_quantity_at(slot, 1, &mut bs_params.commands);
+
+                bs_params.commands.entity(data_ent).insert(modules);
+            }
+        }
+    }
+}
+
+fn on_uninstall_module(
+    mut nevr_uninstall: EventReader<NettyEventReceived<UninstallUpgradeModuleEvent>>,
+    lobby: Res<ServerLobby>,
+    mut q_player: Query<(&GlobalTransform, &HeldItemSlot, &mut Inventory), With<Player>>,
+    mut q_structure: Query<(&mut Structure, &GlobalTransform)>,
+    q_installed_modules: Query<&InstalledModules>,
+    q_has_modules: Query<(), With<InstalledModules>>,
+    mut q_block_data: Query<&mut BlockData>,
+    mut bs_params: BlockDataSystemParams,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    needs_data: Res<ItemShouldHaveData>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for ev in nevr_uninstall.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok((player_g_trans, held_item, mut inventory)) = q_player.get_mut(player_ent) else {
+            continue;
+        };
+
+        let index = ev.module_index as usize;
+
+        match ev.target {
+            UpgradeTarget::Block(s_block) => {
+                let Ok((mut structure, structure_g_trans)) = q_structure.get_mut(s_block.structure()) else {
+                    continue;
+                };
+
+                if !can_edit_block_modules(player_ent, player_g_trans, s_block, &structure, structure_g_trans, &blocks) {
+                    continue;
+                }
+
+                if !permissions.can_use(player_ent, s_block.structure()) {
+                    notify_no_permission(&mut nevw_chat, ev.client_id);
+                    continue;
+                }
+
+                let Some(mut modules) = structure.query_block_data(s_block.coords(), &q_installed_modules).cloned() else {
+                    continue;
+                };
+
+                let Some(module) = modules.uninstall(index) else {
+                    continue;
+                };
+
+                let Some(item) = items.from_id(module.item_unlocalized_name()) else {
+                    continue;
+                };
+
+                if inventory.insert_item(item, 1, &mut bs_params.commands, &needs_data).0 != 0 {
+                    continue;
+                }
+
+                structure.insert_block_data(s_block.coords(), modules, &mut bs_params, &mut q_block_data, &q_has_modules);
+            }
+            UpgradeTarget::HeldItem => {
+                let Some(data_ent) = inventory.itemstack_at(held_item.slot() as usize).and_then(|is| is.data_entity()) else {
+                    continue;
+                };
+
+                let Ok(mut modules) = q_installed_modules.get(data_ent).cloned() else {
+                    continue;
+                };
+
+                let Some(module) = modules.uninstall(index) else {
+                    continue;
+                };
+
+                let Some(item) = items.from_id(module.item_unlocalized_name()) else {
+                    continue;
+                };
+
+                if inventory.insert_item(item, 1, &mut bs_params.commands, &needs_data).0 != 0 {
+                    continue;
+                }
+
+                bs_params.commands.entity(data_ent).insert(modules);
+            }
+        }
+    }
+}
+
+/// Gives back the modules of broken weapon blocks, and rolls for extra items when mining with a tool that has
+/// mining yield modules.
+fn on_block_break(
+    mut evr_block_break: EventReader<BlockBreakEvent>,
+    mut q_player: Query<(&HeldItemSlot, &mut Inventory), With<Player>>,
+    q_structure: Query<&Structure>,
+    q_installed_modules: Query<&InstalledModules>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    block_items: Res<BlockItems>,
+    needs_data: Res<ItemShouldHaveData>,
+    mut commands: Commands,
+) {
+    for ev in evr_block_break.read() {
+        let Ok((held_item, mut inventory)) = q_player.get_mut(ev.breaker) else {
+            continue;
+        };
+
+        let Ok(structure) = q_structure.get(ev.block.structure()) else {
+            continue;
+        };
+
+        let coords = ev.block.coords();
+
+        // The block is still present here, since blocks are removed in `BlockEventsSet::ChangeBlocks`
+        if !structure.has_block_at(coords) {
+            continue;
+        }
+
+        if let Some(modules) = structure.query_block_data(coords, &q_installed_modules) {
+            for module in modules.iter() {
+                if let Some(item) = items.from_id(module.item_unlocalized_name()) {
+                    inventory.insert_item(item, 1, &mut commands, &needs_data);
+                }
+            }
+        }
+
+        let mining_yield = inventory
+            .itemstack_at(held_item.slot() as usize)
+            .and_then(|is| is.data_entity())
+            .and_then(|data_ent| q_installed_modules.get(data_ent).ok())
+            .map(|modules| modules.bonus(UpgradeModuleKind::MiningYield))
+            .unwrap_or(0.0);
+
+        if mining_yield <= 0.0 || rand::random::<f32>() >= mining_yield {
+            continue;
+        }
+
+        let block = structure.block_at(coords, &blocks);
+        if let Some(item_id) = block_items.item_from_block(block) {
+            inventory.insert_item(items.from_numeric_id(item_id), 1, &mut commands, &needs_data);
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<InstalledModules>(app);
+
+    app.add_systems(
+        Update,
+        (
+            on_block_break
+                .in_set(BlockEventsSet::PreProcessEvents)
+                .before(wear_tools_on_block_break),
+            (on_interact_weapon_block, on_install_module, on_uninstall_module)
+                .chain()
+                .in_set(ItemStackSystemSet::CreateDataEntity)
+                .in_set(BlockEventsSet::ProcessEvents),
+        )
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
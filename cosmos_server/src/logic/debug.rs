@@ -0,0 +1,186 @@
+//! Sends the state of a structure's logic groups to players debugging it with a logic wrench
+//!
+//! Finding the wires of every logic group means searching the whole structure, so this is only done when the
+//! structure's logic blocks change (see [`LogicDriver::layout_version`]) and is shared between every player debugging it.
+//! Otherwise, players are only sent the signals of each group, and only when they change.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer, utils::HashMap};
+use cosmos_core::{
+    block::Block,
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    inventory::{held_item_slot::HeldItemSlot, Inventory},
+    item::Item,
+    logic::{
+        debug::{LogicDebugSignalsEvent, LogicDebugSnapshotEvent, LogicGroupSnapshot, SetLogicDebugTargetEvent, LOGIC_WRENCH_ITEM},
+        logic_driver::LogicDriver,
+        LogicBlock, LogicWireColor,
+    },
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::Structure,
+};
+
+use crate::structure::ownership::{notify_no_permission, StructurePermissions};
+
+/// How often players debugging a structure are sent any changes to its logic groups
+const SNAPSHOT_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Component, Debug)]
+/// The structure this player is viewing the logic groups of
+struct DebuggingLogic {
+    structure: Entity,
+    /// The [`LogicDriver::layout_version`] of the last full snapshot this player was sent
+    sent_layout_version: Option<u64>,
+    sent_signals: Vec<(usize, i32)>,
+}
+
+impl DebuggingLogic {
+    fn new(structure: Entity) -> Self {
+        Self {
+            structure,
+            sent_layout_version: None,
+            sent_signals: vec![],
+        }
+    }
+}
+
+#[derive(Default)]
+/// The last snapshot taken of each structure being debugged, and the [`LogicDriver::layout_version`] it was taken at
+struct SnapshotCache(HashMap<Entity, (u64, Vec<LogicGroupSnapshot>)>);
+
+fn holding_wrench(inventory: &Inventory, held_slot: &HeldItemSlot, items: &Registry<Item>) -> bool {
+    inventory
+        .itemstack_at(held_slot.slot() as usize)
+        .is_some_and(|is| items.from_numeric_id(is.item_id()).unlocalized_name() == LOGIC_WRENCH_ITEM)
+}
+
+fn on_set_debug_target(
+    mut commands: Commands,
+    mut nevr_set_target: EventReader<NettyEventReceived<SetLogicDebugTargetEvent>>,
+    lobby: Res<ServerLobby>,
+    q_player: Query<(&Inventory, &HeldItemSlot), With<Player>>,
+    q_logic_driver: Query<(), With<LogicDriver>>,
+    items: Res<Registry<Item>>,
+    permissions: StructurePermissions,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for ev in nevr_set_target.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Some(structure) = ev.structure else {
+            commands.entity(player_ent).remove::<DebuggingLogic>();
+            continue;
+        };
+
+        let Ok((inventory, held_slot)) = q_player.get(player_ent) else {
+            continue;
+        };
+
+        if !holding_wrench(inventory, held_slot, &items) || !q_logic_driver.contains(structure) {
+            continue;
+        }
+
+        if !permissions.can_use(player_ent, structure) {
+            notify_no_permission(&mut nevw_chat, ev.client_id);
+            continue;
+        }
+
+        commands.entity(player_ent).insert(DebuggingLogic::new(structure));
+    }
+}
+
+fn send_logic_snapshots(
+    mut commands: Commands,
+    mut q_debugging: Query<(Entity, &Player, &mut DebuggingLogic, &Inventory, &HeldItemSlot)>,
+    q_structure: Query<(&Structure, &LogicDriver)>,
+    items: Res<Registry<Item>>,
+    blocks: Res<Registry<Block>>,
+    logic_blocks: Res<Registry<LogicBlock>>,
+    logic_wire_colors: Res<Registry<LogicWireColor>>,
+    mut nevw_snapshot: NettyEventWriter<LogicDebugSnapshotEvent>,
+    mut nevw_signals: NettyEventWriter<LogicDebugSignalsEvent>,
+    mut cache: Local<SnapshotCache>,
+) {
+    // Forget structures nobody is debugging anymore
+    cache
+        .0
+        .retain(|structure, _| q_debugging.iter().any(|(_, _, debugging, _, _)| debugging.structure == *structure));
+
+    for (player_ent, player, mut debugging, inventory, held_slot) in q_debugging.iter_mut() {
+        let Ok((structure, logic_driver)) = q_structure.get(debugging.structure) else {
+            commands.entity(player_ent).remove::<DebuggingLogic>();
+            continue;
+        };
+
+        if !holding_wrench(inventory, held_slot, &items) {
+            commands.entity(player_ent).remove::<DebuggingLogic>();
+            continue;
+        }
+
+        let layout_version = logic_driver.layout_version();
+        let signals = logic_driver.debug_signals();
+
+        if debugging.sent_layout_version != Some(layout_version) {
+            let (cached_version, groups) = cache.0.entry(debugging.structure).or_insert_with(|| {
+                (
+                    layout_version,
+                    logic_driver.debug_snapshot(structure, &blocks, &logic_blocks, &logic_wire_colors),
+                )
+            });
+
+            if *cached_version != layout_version {
+                *groups = logic_driver.debug_snapshot(structure, &blocks, &logic_blocks, &logic_wire_colors);
+                *cached_version = layout_version;
+            }
+
+            let mut groups = groups.clone();
+            for group in groups.iter_mut() {
+                if let Ok(idx) = signals.binary_search_by_key(&group.id, |(id, _)| *id) {
+                    group.signal = signals[idx].1;
+                }
+            }
+
+            nevw_snapshot.send(
+                LogicDebugSnapshotEvent {
+                    structure: debugging.structure,
+                    groups,
+                },
+                player.id(),
+            );
+
+            debugging.sent_layout_version = Some(layout_version);
+            debugging.sent_signals = signals;
+        } else if debugging.sent_signals != signals {
+            nevw_signals.send(
+                LogicDebugSignalsEvent {
+                    structure: debugging.structure,
+                    signals: signals.clone(),
+                },
+                player.id(),
+            );
+
+            debugging.sent_signals = signals;
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (on_set_debug_target, send_logic_snapshots.run_if(on_timer(SNAPSHOT_INTERVAL)))
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -7,6 +7,8 @@ use bevy::{
 };
 use cosmos_core::state::GameState;
 
+mod debug;
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 /// Logic blocks should be registered here and can be ambiguous with this set
 pub enum LogicSystemRegistrySet {
@@ -19,4 +21,6 @@ pub(super) fn register(app: &mut App) {
         OnEnter(GameState::PostLoading),
         LogicSystemRegistrySet::RegisterLogicBlocks.ambiguous_with(LogicSystemRegistrySet::RegisterLogicBlocks),
     );
+
+    debug::register(app);
 }
@@ -0,0 +1,158 @@
+//! Loot tables decide what items are found in containers that weren't filled by a player, such as those in derelicts.
+//!
+//! Loot tables are loaded from `assets/cosmos/loot_tables`, and are registered as `cosmos:<file name>`.
+
+use std::{ffi::OsStr, fs};
+
+use bevy::prelude::*;
+use cosmos_core::{
+    item::Item,
+    registry::{create_registry, identifiable::Identifiable, Registry},
+    state::GameState,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+/// Anything that needs loot tables to be loaded should run after [`LootSet::LoadLootTables`]
+pub enum LootSet {
+    /// Loot tables are loaded from their json files
+    LoadLootTables,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RawLootEntry {
+    item: String,
+    /// How likely this entry is to be picked compared to the other entries in the table
+    weight: u32,
+    min: u16,
+    max: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RawLootTable {
+    /// The fewest entries that will be picked from this table
+    min_rolls: u32,
+    /// The most entries that will be picked from this table
+    max_rolls: u32,
+    entries: Vec<RawLootEntry>,
+}
+
+#[derive(Debug, Clone)]
+struct LootEntry {
+    item: u16,
+    weight: u32,
+    min: u16,
+    max: u16,
+}
+
+#[derive(Debug, Clone)]
+/// A weighted list of items that can be randomly picked from to fill containers
+pub struct LootTable {
+    id: u16,
+    unlocalized_name: String,
+    min_rolls: u32,
+    max_rolls: u32,
+    entries: Vec<LootEntry>,
+}
+
+impl LootTable {
+    /// Randomly picks items from this table.
+    ///
+    /// Returns the item ids paired with how many of them were picked. The same item may appear more than once.
+    pub fn roll(&self, rng: &mut impl Rng) -> Vec<(u16, u16)> {
+        let total_weight: u32 = self.entries.iter().map(|e| e.weight).sum();
+
+        if total_weight == 0 {
+            return vec![];
+        }
+
+        let n_rolls = rng.gen_range(self.min_rolls..=self.max_rolls.max(self.min_rolls));
+
+        (0..n_rolls)
+            .filter_map(|_| {
+                let mut pick = rng.gen_range(0..total_weight);
+
+                let entry = self.entries.iter().find(|e| {
+                    if pick < e.weight {
+                        true
+                    } else {
+                        pick -= e.weight;
+                        false
+                    }
+                })?;
+
+                Some((entry.item, rng.gen_range(entry.min..=entry.max.max(entry.min))))
+            })
+            .collect()
+    }
+}
+
+impl Identifiable for LootTable {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+fn load_loot_tables(mut loot_tables: ResMut<Registry<LootTable>>, items: Res<Registry<Item>>) {
+    for entry in WalkDir::new("assets/cosmos/loot_tables").max_depth(1) {
+        let Ok(entry) = entry else {
+            continue;
+        };
+
+        let path = entry.path();
+        if path.is_dir() || path.extension().and_then(OsStr::to_str) != Some("json") {
+            continue;
+        }
+
+        let Some(name) = path.file_stem().and_then(OsStr::to_str) else {
+            continue;
+        };
+
+        let table_json = fs::read(path).unwrap_or_else(|e| panic!("Unable to read loot table file {path:?}\n{e:?}"));
+
+        let table =
+            serde_json::from_slice::<RawLootTable>(&table_json).unwrap_or_else(|e| panic!("Invalid loot table json {path:?}\n{e:?}"));
+
+        let mut entries = vec![];
+
+        for raw_entry in table.entries {
+            let Some(item) = items.from_id(&raw_entry.item) else {
+                error!("Unable to find item with id matching {:?} in loot table {path:?}", raw_entry.item);
+                continue;
+            };
+
+            entries.push(LootEntry {
+                item: item.id(),
+                weight: raw_entry.weight,
+                min: raw_entry.min,
+                max: raw_entry.max,
+            });
+        }
+
+        loot_tables.register(LootTable {
+            id: 0,
+            unlocalized_name: format!("cosmos:{name}"),
+            min_rolls: table.min_rolls,
+            max_rolls: table.max_rolls,
+            entries,
+        });
+    }
+
+    info!("Loaded {} loot tables", loot_tables.iter().count());
+}
+
+pub(super) fn register(app: &mut App) {
+    create_registry::<LootTable>(app, "cosmos:loot_tables");
+
+    app.add_systems(OnEnter(GameState::PostLoading), load_loot_tables.in_set(LootSet::LoadLootTables));
+}
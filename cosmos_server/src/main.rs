@@ -40,14 +40,18 @@ pub mod init;
 pub mod inventory;
 pub mod items;
 pub mod logic;
+pub mod loot;
 pub mod netty;
+pub mod party;
 pub mod persistence;
 pub mod physics;
 pub mod plugin;
 pub mod projectiles;
+mod rcon;
 pub mod rng;
 pub mod settings;
 pub mod shop;
+pub mod singleplayer;
 pub mod structure;
 pub mod universe;
 
@@ -63,6 +67,7 @@ fn main() {
     let server_settings = read_server_settings();
 
     let port = server_settings.port.unwrap_or(1337);
+    let local_only = server_settings.singleplayer;
 
     let mut app = App::new();
 
@@ -101,22 +106,25 @@ fn main() {
                 playing_state: GameState::Playing,
             },
         ))
+        // Inserted before the server plugin so it can read these settings while being built
+        .insert_resource(server_settings)
         .add_plugins(
             RapierPhysicsPlugin::<CosmosPhysicsFilter>::default()
                 // .in_schedule(FixedUpdate)
-                .with_custom_initialization(RapierContextInitialization::NoAutomaticRapierContext),
+                .with_custom_initialization(RapierContextInitialization::NoAutomaticRapierContext)
+                // Physics worlds are stepped in parallel instead - see `physics::parallel_stepping`
+                .with_default_system_setup(false),
         )
         .add_plugins((
             RenetServerPlugin,
             NetcodeServerPlugin,
-            ServerPlugin { port },
+            ServerPlugin { port, local_only },
             // Used for diagnostics
             SystemInformationDiagnosticsPlugin,
             EntityCountDiagnosticsPlugin,
             FrameTimeDiagnosticsPlugin,
             // PerfUiPlugin,
-        ))
-        .insert_resource(server_settings);
+        ));
 
     if cfg!(feature = "print-schedule") {
         println!(
@@ -0,0 +1,86 @@
+//! Profiles the server's tick & sends it to clients that want to see it in their profiling overlay
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer, utils::HashSet};
+use bevy_rapier3d::plugin::PhysicsSet;
+use bevy_renet2::renet2::ClientId;
+use cosmos_core::{
+    block::block_events::BlockEventsSet,
+    debug::profiling::{profile_system_set, RequestServerDiagnosticsEvent, ServerDiagnosticsEvent, SystemSetTimings, FRAME_TIMING},
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    physics::location::LocationPhysicsSet,
+    structure::{systems::StructureSystemsSet, Structure},
+};
+
+/// How often diagnostics are sent to the clients that requested them
+const SEND_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Resource, Default, Debug)]
+/// The clients that want to receive [`ServerDiagnosticsEvent`]s
+struct DiagnosticsSubscribers(HashSet<ClientId>);
+
+fn on_request_diagnostics(
+    mut nevr_request: EventReader<NettyEventReceived<RequestServerDiagnosticsEvent>>,
+    mut subscribers: ResMut<DiagnosticsSubscribers>,
+) {
+    for ev in nevr_request.read() {
+        if ev.enabled {
+            subscribers.0.insert(ev.client_id);
+        } else {
+            subscribers.0.remove(&ev.client_id);
+        }
+    }
+}
+
+fn send_diagnostics(
+    mut subscribers: ResMut<DiagnosticsSubscribers>,
+    lobby: Res<ServerLobby>,
+    timings: Res<SystemSetTimings>,
+    q_entities: Query<()>,
+    q_structures: Query<&Structure>,
+    mut nevw_diagnostics: NettyEventWriter<ServerDiagnosticsEvent>,
+) {
+    // Clients that have disconnected no longer want diagnostics
+    subscribers.0.retain(|client_id| lobby.player_from_id(*client_id).is_some());
+
+    if subscribers.0.is_empty() {
+        return;
+    }
+
+    let diagnostics = ServerDiagnosticsEvent {
+        tick_time: timings.get(FRAME_TIMING).unwrap_or_default(),
+        set_timings: timings
+            .sorted()
+            .into_iter()
+            .map(|(name, duration)| (name.to_owned(), duration))
+            .collect(),
+        entity_count: q_entities.iter().count() as u32,
+        chunk_count: q_structures.iter().map(|s| s.chunks().len() as u32).sum(),
+    };
+
+    for &client_id in subscribers.0.iter() {
+        nevw_diagnostics.send(diagnostics.clone(), client_id);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    profile_system_set(app, Update, "Receive Messages", NetworkingSystemsSet::ReceiveMessages);
+    profile_system_set(app, Update, "Game Logic", NetworkingSystemsSet::Between);
+    profile_system_set(app, Update, "Block Events", BlockEventsSet::ProcessEvents);
+    profile_system_set(app, Update, "Structure Systems", StructureSystemsSet::UpdateSystems);
+    profile_system_set(app, Update, "Location Physics", LocationPhysicsSet::DoPhysics);
+    profile_system_set(app, Update, "Sync Components", NetworkingSystemsSet::SyncComponents);
+    profile_system_set(app, PostUpdate, "Rapier Physics", PhysicsSet::StepSimulation);
+
+    app.init_resource::<DiagnosticsSubscribers>().add_systems(
+        Update,
+        (on_request_diagnostics, send_diagnostics.run_if(on_timer(SEND_INTERVAL)))
+            .chain()
+            .in_set(NetworkingSystemsSet::Between),
+    );
+}
@@ -2,13 +2,17 @@
 
 use bevy::prelude::App;
 
+mod diagnostics;
 pub mod network_helpers;
+pub mod network_stats;
 pub mod server_events;
 pub mod server_listener;
 pub mod sync;
 
 pub(super) fn register(app: &mut App) {
+    diagnostics::register(app);
     sync::register(app);
     server_events::register(app);
     server_listener::register(app);
+    network_stats::register(app);
 }
@@ -0,0 +1,136 @@
+//! Periodically logs the aggregate network stats of the server
+
+use std::{collections::HashMap, time::Duration};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_renet2::renet2::RenetServer;
+use cosmos_core::netty::network_stats::{MessageKind, NetworkStats};
+
+/// How often the network stats are logged
+const LOG_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How many of the busiest channels & types of message are logged in each direction
+const CHANNELS_LOGGED: usize = 5;
+
+#[derive(Default, Debug)]
+struct Totals {
+    by_channel: HashMap<String, (u64, u64)>,
+    by_kind: HashMap<MessageKind, (u64, u64)>,
+}
+
+impl Totals {
+    fn busiest<K: Clone>(map: &HashMap<K, (u64, u64)>) -> Vec<(K, (u64, u64))> {
+        let mut busiest = map.iter().map(|(k, v)| (k.clone(), *v)).collect::<Vec<_>>();
+        busiest.sort_by_key(|(_, (_, bytes))| std::cmp::Reverse(*bytes));
+        busiest.truncate(CHANNELS_LOGGED);
+        busiest
+    }
+
+    fn total(&self) -> (u64, u64) {
+        self.by_channel
+            .values()
+            .fold((0, 0), |(messages, bytes), (m, b)| (messages + m, bytes + b))
+    }
+}
+
+#[derive(Resource, Default, Debug)]
+/// The [`NetworkStats`] only hold the last second, so they are added up here between each log
+struct NetworkStatsSinceLastLog {
+    sent: Totals,
+    received: Totals,
+}
+
+fn add_up_network_stats(network_stats: Res<NetworkStats>, mut since_last_log: ResMut<NetworkStatsSinceLastLog>) {
+    let since_last_log = &mut *since_last_log;
+
+    for (stats, totals) in [
+        (network_stats.sent(), &mut since_last_log.sent),
+        (network_stats.received(), &mut since_last_log.received),
+    ] {
+        for stats in stats {
+            let total = totals.by_channel.entry(stats.channel.clone()).or_default();
+            total.0 += stats.messages;
+            total.1 += stats.bytes;
+        }
+    }
+
+    for (stats, totals) in [
+        (network_stats.sent_by_kind(), &mut since_last_log.sent),
+        (network_stats.received_by_kind(), &mut since_last_log.received),
+    ] {
+        for stats in stats {
+            let total = totals.by_kind.entry(stats.kind).or_default();
+            total.0 += stats.messages;
+            total.1 += stats.bytes;
+        }
+    }
+}
+
+fn log_totals(direction: &str, totals: &Totals) {
+    let (messages, bytes) = totals.total();
+    info!(
+        "{direction} over the last {}s: {messages} messages ({:.1} KB)",
+        LOG_INTERVAL.as_secs(),
+        bytes as f32 / 1024.0
+    );
+
+    for (channel, (messages, bytes)) in Totals::busiest(&totals.by_channel) {
+        info!("  {direction} on {channel}: {messages} messages ({:.1} KB)", bytes as f32 / 1024.0);
+    }
+
+    for (kind, (messages, bytes)) in Totals::busiest(&totals.by_kind) {
+        info!("  {direction} {kind}: {messages} messages ({:.1} KB)", bytes as f32 / 1024.0);
+    }
+}
+
+fn log_network_stats(server: Res<RenetServer>, mut since_last_log: ResMut<NetworkStatsSinceLastLog>) {
+    let since_last_log = std::mem::take(&mut *since_last_log);
+
+    let clients = server.clients_id();
+
+    if clients.is_empty() {
+        return;
+    }
+
+    let (mut total_sent, mut total_received) = (0.0, 0.0);
+
+    for client_id in clients.iter().copied() {
+        let Ok(info) = server.network_info(client_id) else {
+            continue;
+        };
+
+        total_sent += info.bytes_sent_per_second;
+        total_received += info.bytes_received_per_second;
+
+        info!(
+            "Client {client_id} (current): RTT {:.1}ms | Loss {:.1}% | Up {:.1} KB/s | Down {:.1} KB/s",
+            info.rtt * 1000.0,
+            info.packet_loss * 100.0,
+            info.bytes_received_per_second / 1024.0,
+            info.bytes_sent_per_second / 1024.0,
+        );
+    }
+
+    info!(
+        "Current network totals for {} client(s): Sent {:.1} KB/s | Received {:.1} KB/s",
+        clients.len(),
+        total_sent / 1024.0,
+        total_received / 1024.0
+    );
+
+    log_totals("Sent", &since_last_log.sent);
+    log_totals("Received", &since_last_log.received);
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<NetworkStatsSinceLastLog>().add_systems(
+        Update,
+        (
+            add_up_network_stats.run_if(resource_changed::<NetworkStats>),
+            log_network_stats
+                .run_if(resource_exists::<RenetServer>)
+                .run_if(on_timer(LOG_INTERVAL)),
+        )
+            .chain(),
+    );
+}
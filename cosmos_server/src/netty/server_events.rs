@@ -6,9 +6,13 @@ use bevy_renet2::renet2::{ClientId, RenetServer, ServerEvent};
 use cosmos_core::ecs::NeedsDespawned;
 use cosmos_core::netty::server::ServerLobby;
 use cosmos_core::netty::server_reliable_messages::ServerReliableMessages;
-use cosmos_core::netty::{cosmos_encoder, NettyChannelServer};
+use cosmos_core::netty::{
+    network_stats::{NettyMessage, NettyServerMessages},
+    NettyChannelServer,
+};
 use renet2_visualizer::RenetServerVisualizer;
 
+use crate::entities::player::bans::BannedPlayers;
 use crate::entities::player::persistence::LoadPlayer;
 use crate::netty::network_helpers::ClientTicks;
 use crate::persistence::saving::NeedsSaved;
@@ -30,6 +34,7 @@ pub(super) fn handle_server_events(
     mut lobby: ResMut<ServerLobby>,
     mut client_ticks: ResMut<ClientTicks>,
     mut visualizer: ResMut<RenetServerVisualizer<200>>,
+    banned_players: Res<BannedPlayers>,
 ) {
     for event in server_events.read() {
         match event {
@@ -47,6 +52,14 @@ pub(super) fn handle_server_events(
                     continue;
                 };
 
+                let address = transport.client_addr(client_id).map(|(_, address)| address.ip());
+
+                if banned_players.is_banned(&name, address) {
+                    info!("{name} is banned - disconnecting them.");
+                    server.disconnect(client_id);
+                    continue;
+                }
+
                 commands.spawn(LoadPlayer { name, client_id });
             }
             ServerEvent::ClientDisconnected { client_id, reason } => {
@@ -58,9 +71,9 @@ pub(super) fn handle_server_events(
                     commands.entity(player_entity).insert((NeedsSaved, NeedsDespawned));
                 }
 
-                let message = cosmos_encoder::serialize(&ServerReliableMessages::PlayerRemove { id: *client_id });
+                let message = NettyMessage::new(&ServerReliableMessages::PlayerRemove { id: *client_id });
 
-                server.broadcast_message(NettyChannelServer::Reliable, message);
+                server.broadcast_netty_message(NettyChannelServer::Reliable, message);
             }
         }
     }
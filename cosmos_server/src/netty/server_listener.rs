@@ -7,23 +7,28 @@ use bevy::utils::HashMap;
 use bevy_rapier3d::prelude::Velocity;
 use bevy_renet2::renet2::{ClientId, RenetServer};
 use cosmos_core::block::block_events::{BlockBreakEvent, BlockInteractEvent, BlockPlaceEvent, BlockPlaceEventData};
+use cosmos_core::chat::ServerSendChatMessageEvent;
 use cosmos_core::ecs::mut_events::MutEvent;
 use cosmos_core::inventory::itemstack::ItemStackSystemSet;
 use cosmos_core::inventory::Inventory;
 use cosmos_core::item::Item;
 use cosmos_core::netty::netty_rigidbody::NettyRigidBodyLocation;
 use cosmos_core::netty::server::ServerLobby;
+use cosmos_core::netty::sync::events::server_event::NettyEventWriter;
 use cosmos_core::netty::sync::server_entity_syncing::RequestedEntityEvent;
 use cosmos_core::netty::system_sets::NetworkingSystemsSet;
-use cosmos_core::netty::{cosmos_encoder, NettyChannelClient, NettyChannelServer};
+use cosmos_core::netty::{
+    network_stats::{NettyMessage, NettyServerMessages},
+    NettyChannelClient, NettyChannelServer,
+};
 use cosmos_core::physics::location::{Location, SetPosition};
 use cosmos_core::registry::Registry;
 use cosmos_core::state::GameState;
 use cosmos_core::structure::loading::ChunksNeedLoaded;
 use cosmos_core::structure::shared::build_mode::{BuildMode, ExitBuildModeEvent};
-use cosmos_core::structure::systems::StructureSystems;
+use cosmos_core::structure::systems::{activation_groups::ActivationGroups, StructureSystems};
 use cosmos_core::{
-    entities::player::Player,
+    entities::player::{spectator::Spectator, Player},
     events::structure::change_pilot_event::ChangePilotEvent,
     netty::{
         client_reliable_messages::ClientReliableMessages, client_unreliable_messages::ClientUnreliableMessages,
@@ -33,13 +38,38 @@ use cosmos_core::{
 };
 
 use crate::entities::player::PlayerLooking;
+use crate::structure::ownership::{notify_no_permission, StructurePermissions};
 use crate::structure::planet::chunk::ChunkNeedsSent;
 use crate::structure::planet::generation::planet_generator::RequestChunkEvent;
 use crate::structure::ship::events::{CreateShipEvent, ShipSetMovementEvent};
 use crate::structure::station::events::CreateStationEvent;
+use crate::universe::sector_rules::BlockEditRules;
 
 use super::server_events::handle_server_events;
 
+/// Returns true if the rules of the sector this structure is in let this player edit its blocks
+fn can_edit_blocks_here(
+    block_edit_rules: &BlockEditRules,
+    player_entity: Entity,
+    structure_entity: Entity,
+    q_location: &Query<&Location, Without<Player>>,
+) -> bool {
+    q_location
+        .get(structure_entity)
+        .map(|loc| block_edit_rules.can_edit_blocks(player_entity, loc.sector()))
+        .unwrap_or(true)
+}
+
+fn notify_building_not_allowed(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: "Building is not allowed in this sector.".into(),
+        },
+        client_id,
+    );
+}
+
 #[derive(Resource, Default)]
 struct SendAllChunks(HashMap<Entity, Vec<ClientId>>);
 
@@ -62,7 +92,7 @@ fn server_listen_messages(
         mut requested_entities_writer,
         mut request_chunk_event_writer,
     ): (
-        Query<&mut StructureSystems>,
+        Query<(&mut StructureSystems, Option<&ActivationGroups>)>,
         EventWriter<BlockBreakEvent>,
         EventWriter<MutEvent<BlockPlaceEvent>>,
         EventWriter<BlockInteractEvent>,
@@ -79,13 +109,20 @@ fn server_listen_messages(
     player_parent_location: Query<&Location, Without<Player>>,
     mut q_player: Query<(&GlobalTransform, &mut Transform, &mut Location, &mut PlayerLooking, &mut Velocity), With<Player>>,
     mut build_mode: Query<&mut BuildMode>,
+    (permissions, block_edit_rules, q_player_names, mut nevw_chat, q_spectator): (
+        StructurePermissions,
+        BlockEditRules,
+        Query<&Player>,
+        NettyEventWriter<ServerSendChatMessageEvent>,
+        Query<(), With<Spectator>>,
+    ),
 
     mut send_all_chunks: ResMut<SendAllChunks>,
 ) {
     for client_id in server.clients_id().into_iter() {
-        while let Some(message) = server.receive_message(client_id, NettyChannelClient::Unreliable) {
+        while let Some(message) = server.receive_netty_message::<ClientUnreliableMessages>(client_id, NettyChannelClient::Unreliable) {
             if let Some(player_entity) = lobby.player_from_id(client_id) {
-                let Ok(command) = cosmos_encoder::deserialize::<ClientUnreliableMessages>(&message) else {
+                let Ok(command) = message else {
                     warn!("UNABLE TO DESERIALIZE CLIENT MESSAGE!");
                     break;
                 };
@@ -118,8 +155,8 @@ fn server_listen_messages(
                     }
                     ClientUnreliableMessages::ShipActiveSystem(active_system) => {
                         if let Ok(pilot) = pilot_query.get(player_entity) {
-                            if let Ok(mut systems) = systems_query.get_mut(pilot.entity) {
-                                systems.set_active_system(active_system, &mut commands);
+                            if let Ok((mut systems, groups)) = systems_query.get_mut(pilot.entity) {
+                                systems.set_active_system(active_system, groups, &mut commands);
                             }
                         }
                     }
@@ -127,8 +164,8 @@ fn server_listen_messages(
             }
         }
 
-        while let Some(message) = server.receive_message(client_id, NettyChannelClient::Reliable) {
-            let Ok(command) = cosmos_encoder::deserialize::<ClientReliableMessages>(&message) else {
+        while let Some(message) = server.receive_netty_message::<ClientReliableMessages>(client_id, NettyChannelClient::Reliable) {
+            let Ok(command) = message else {
                 warn!("UNABLE TO DESERIALIZE CLIENT MESSAGE!");
                 break;
             };
@@ -158,6 +195,21 @@ fn server_listen_messages(
                 }
                 ClientReliableMessages::BreakBlock { block } => {
                     if let Some(player_entity) = lobby.player_from_id(client_id) {
+                        // Spectators can't touch anything
+                        if q_spectator.contains(player_entity) {
+                            continue;
+                        }
+
+                        if !permissions.can_use(player_entity, block.structure()) {
+                            notify_no_permission(&mut nevw_chat, client_id);
+                            continue;
+                        }
+
+                        if !can_edit_blocks_here(&block_edit_rules, player_entity, block.structure(), &player_parent_location) {
+                            notify_building_not_allowed(&mut nevw_chat, client_id);
+                            continue;
+                        }
+
                         break_block_event.send(BlockBreakEvent {
                             breaker: player_entity,
                             block,
@@ -167,15 +219,29 @@ fn server_listen_messages(
                 ClientReliableMessages::PlaceBlock {
                     block,
                     block_id,
-                    block_rotation: block_up,
+                    block_rotation,
                     inventory_slot,
                 } => {
                     if let Some(player_entity) = lobby.player_from_id(client_id) {
+                        if q_spectator.contains(player_entity) {
+                            continue;
+                        }
+
+                        if !permissions.can_use(player_entity, block.structure()) {
+                            notify_no_permission(&mut nevw_chat, client_id);
+                            continue;
+                        }
+
+                        if !can_edit_blocks_here(&block_edit_rules, player_entity, block.structure(), &player_parent_location) {
+                            notify_building_not_allowed(&mut nevw_chat, client_id);
+                            continue;
+                        }
+
                         place_block_event.send(
                             BlockPlaceEvent::Event(BlockPlaceEventData {
                                 structure_block: block,
                                 block_id,
-                                block_up,
+                                block_rotation,
                                 inventory_slot: inventory_slot as usize,
                                 placer: player_entity,
                             })
@@ -188,10 +254,18 @@ fn server_listen_messages(
                     block_including_fluids,
                     alternate,
                 } => {
+                    let Some(player_entity) = lobby.player_from_id(client_id) else {
+                        continue;
+                    };
+
+                    if q_spectator.contains(player_entity) {
+                        continue;
+                    }
+
                     block_interact_event.send(BlockInteractEvent {
                         block,
                         block_including_fluids,
-                        interactor: lobby.player_from_id(client_id).unwrap(),
+                        interactor: player_entity,
                         alternate,
                     });
                 }
@@ -224,7 +298,11 @@ fn server_listen_messages(
 
                         info!("Creating ship {name}");
 
-                        create_ship_event_writer.send(CreateShipEvent { ship_location, rotation });
+                        create_ship_event_writer.send(CreateShipEvent {
+                            ship_location,
+                            rotation,
+                            owner: q_player_names.get(client).ok().map(|x| x.name().to_owned()),
+                        });
                     } else {
                         warn!("Invalid player entity - {client:?}");
                     }
@@ -261,6 +339,7 @@ fn server_listen_messages(
                         create_station_event_writer.send(CreateStationEvent {
                             station_location,
                             rotation,
+                            owner: q_player_names.get(client).ok().map(|x| x.name().to_owned()),
                         });
                     }
                 }
@@ -270,10 +349,10 @@ fn server_listen_messages(
                         _ => None,
                     };
 
-                    server.send_message(
+                    server.send_netty_message(
                         client_id,
                         NettyChannelServer::Reliable,
-                        cosmos_encoder::serialize(&ServerReliableMessages::PilotChange {
+                        NettyMessage::new(&ServerReliableMessages::PilotChange {
                             structure_entity: ship_entity,
                             pilot_entity: pilot,
                         }),
@@ -315,10 +394,10 @@ fn server_listen_messages(
                             //     player_loc.last_transform_loc = Some(player_trans.translation);
                             // }
 
-                            server.broadcast_message_except(
+                            server.broadcast_netty_message_except(
                                 client_id,
                                 NettyChannelServer::Reliable,
-                                cosmos_encoder::serialize(&ServerReliableMessages::PlayerLeaveShip { player_entity }),
+                                NettyMessage::new(&ServerReliableMessages::PlayerLeaveShip { player_entity }),
                             );
                         }
                     }
@@ -363,7 +442,7 @@ fn send_all_chunks(
             return true;
         }
 
-        let message = cosmos_encoder::serialize(&ServerReliableMessages::NumberOfChunks {
+        let message = NettyMessage::new(&ServerReliableMessages::NumberOfChunks {
             entity: structure_entity,
             chunks_needed: ChunksNeedLoaded {
                 amount_needed: structure.chunks().len(),
@@ -371,7 +450,7 @@ fn send_all_chunks(
         });
 
         for &client_id in client_ids.iter() {
-            server.send_message(client_id, NettyChannelServer::Reliable, message.clone());
+            server.send_netty_message(client_id, NettyChannelServer::Reliable, message.clone());
         }
 
         info!("Sending chunks for {structure_entity:?}!");
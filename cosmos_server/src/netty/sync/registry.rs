@@ -6,7 +6,7 @@ use bevy::{
     prelude::{in_state, App, Event, EventWriter, IntoSystemConfigs, ResMut},
 };
 use cosmos_core::{
-    netty::{client_registry::RegistrySyncing, cosmos_encoder, system_sets::NetworkingSystemsSet, NettyChannelClient},
+    netty::{client_registry::RegistrySyncing, network_stats::NettyServerMessages, system_sets::NetworkingSystemsSet, NettyChannelClient},
     state::GameState,
 };
 use renet2::{ClientId, RenetServer};
@@ -23,8 +23,8 @@ fn listen_for_done_syncing(
     mut evw_finished_receiving_registries: EventWriter<ClientFinishedReceivingRegistriesEvent>,
 ) {
     for client_id in server.clients_id().into_iter() {
-        while let Some(message) = server.receive_message(client_id, NettyChannelClient::Registry) {
-            let Ok(msg) = cosmos_encoder::deserialize::<RegistrySyncing>(&message) else {
+        while let Some(message) = server.receive_netty_message::<RegistrySyncing>(client_id, NettyChannelClient::Registry) {
+            let Ok(msg) = message else {
                 warn!("Bad deserialization");
                 continue;
             };
@@ -1,16 +1,16 @@
 //! Handles the syncing of entity's rigidbodies + velocities
 
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 use bevy_rapier3d::prelude::Velocity;
 use bevy_renet2::renet2::RenetServer;
 use cosmos_core::{
     block::data::BlockData,
     ecs::{despawn_needed, NeedsDespawned},
-    entities::player::{render_distance::RenderDistance, Player},
+    entities::player::{spectator::Spectator, Player},
     inventory::itemstack::ItemStackData,
     netty::{
-        cosmos_encoder,
-        netty_rigidbody::{NettyRigidBody, NettyRigidBodyLocation},
+        netty_rigidbody::{NettyRigidBody, NettyRigidBodyLocation, QuantizedRigidBody, ReplicatedBody},
+        network_stats::{NettyMessage, NettyServerMessages},
         server_reliable_messages::ServerReliableMessages,
         server_unreliable_messages::ServerUnreliableMessages,
         sync::{server_entity_syncing::RequestedEntityEvent, ComponentEntityIdentifier},
@@ -19,7 +19,10 @@ use cosmos_core::{
     },
     persistence::LoadingDistance,
     physics::location::{Location, LocationPhysicsSet},
-    structure::systems::StructureSystem,
+    structure::systems::{
+        cloaking_system::{cloaked_ancestor, is_aboard, Cloaked},
+        StructureSystem,
+    },
 };
 
 use crate::netty::network_helpers::NetworkTick;
@@ -30,54 +33,86 @@ use crate::netty::network_helpers::NetworkTick;
 /// This only works if the entity is despawned via the `NeedsDespawned` component.
 pub struct DontNotifyClientOfDespawn;
 
-/// Sends bodies to players only if it's within their render distance.
-fn send_bodies(
-    players: &Query<(&Player, &RenderDistance, &Location)>,
-    bodies: &[(Entity, NettyRigidBody, Location, LoadingDistance)],
-    server: &mut RenetServer,
-    tick: &NetworkTick,
-) {
-    for (player, _, loc) in players.iter() {
-        let players_bodies: Vec<(Entity, NettyRigidBody)> = bodies
-            .iter()
-            .filter(|(_, _, location, loading_distance)| loading_distance.should_load(loc, location))
-            .map(|(ent, net_rb, _, _)| (*ent, *net_rb))
-            .collect();
+/// The packet size can only be so big, so limit syncing to this many bodies per packet
+const MAX_BODIES_PER_PACKET: usize = 20;
 
-        if !players_bodies.is_empty() {
-            let sync_message = ServerUnreliableMessages::BulkBodies {
-                time_stamp: tick.0,
-                bodies: players_bodies,
-            };
+#[derive(Resource, Debug, Clone, Copy)]
+/// Controls how entity bodies are replicated to players
+pub struct BodyReplicationSettings {
+    /// Entities further than this from a player will only be sent to them as part of a full snapshot.
+    ///
+    /// Entities outside of a player's [`LoadingDistance`] are never sent.
+    pub interest_radius: f32,
+    /// Every `full_snapshot_interval` ticks, every relevant body is sent in full to every player, even if it hasn't changed.
+    ///
+    /// Bodies are sent over an unreliable channel, and changes between snapshots are sent as deltas against the
+    /// last full body. This makes sure that clients eventually recover from any updates (or full bodies) that were lost.
+    pub full_snapshot_interval: u64,
+}
 
-            let message = cosmos_encoder::serialize(&sync_message);
-            server.send_message(player.id(), NettyChannelServer::Unreliable, message.clone());
+impl Default for BodyReplicationSettings {
+    fn default() -> Self {
+        Self {
+            interest_radius: 5_000.0,
+            full_snapshot_interval: 30,
         }
     }
 }
 
+#[derive(Component, Default, Debug)]
+/// Keeps track of the body states sent to this player for each entity.
+struct ReplicatedBodies {
+    /// The last full body sent for each entity, and the tick it was sent on.
+    ///
+    /// Changes are sent as deltas against this.
+    baselines: HashMap<Entity, (u64, QuantizedRigidBody)>,
+    /// The last body sent for each entity, used to only send bodies that have changed since then.
+    last_sent: HashMap<Entity, QuantizedRigidBody>,
+}
+
+fn add_replicated_bodies(mut commands: Commands, q_players: Query<Entity, Added<Player>>) {
+    for ent in q_players.iter() {
+        commands.entity(ent).insert(ReplicatedBodies::default());
+    }
+}
+
+fn send_bodies(server: &mut RenetServer, player: &Player, bodies: Vec<(Entity, ReplicatedBody)>, tick: &NetworkTick) {
+    let sync_message = ServerUnreliableMessages::BulkBodies {
+        time_stamp: tick.0,
+        bodies,
+    };
+
+    let message = NettyMessage::new(&sync_message);
+    server.send_netty_message(player.id(), NettyChannelServer::Unreliable, message);
+}
+
+/// Sends bodies to players only if it's within their render distance.
+///
+/// Between full snapshots, only bodies that are within the player's interest radius and that have changed
+/// since they were last sent to that player are sent, and only the fields that changed since the last full
+/// body are included.
+///
+/// Cloaked structures (and anything on them) are only sent to players that are aboard them, and invisible spectators
+/// are only sent to themselves.
 fn server_sync_bodies(
     mut server: ResMut<RenetServer>,
     mut tick: ResMut<NetworkTick>,
+    settings: Res<BodyReplicationSettings>,
     location_query: Query<&Location>,
     entities: Query<(Entity, &Transform, &Location, Option<&Velocity>, &LoadingDistance, Option<&Parent>), Without<NoSendEntity>>,
-    players: Query<(&Player, &RenderDistance, &Location)>,
-    // Often children will not have locations or loading distances, but still need to by synced
-    // q_children_need_synced: Query<
-    //     (Entity, Option<&Velocity>, &Transform, &Parent),
-    //     (Without<LoadingDistance>, Without<NoSendEntity>, Without<Location>),
-    // >,
-    // q_loading_distance: Query<(&Location, &LoadingDistance)>,
-    // q_parent: Query<&Parent>,
+    mut players: Query<(Entity, &Player, &Location, &mut ReplicatedBodies)>,
+    q_cloaked: Query<(), With<Cloaked>>,
+    q_parent: Query<&Parent>,
+    q_spectator: Query<&Spectator>,
 ) {
     tick.0 += 1;
 
-    let mut bodies = Vec::with_capacity(20);
+    let full_snapshot = tick.0 % settings.full_snapshot_interval.max(1) == 0;
 
-    for (entity, transform, location, velocity, unload_distance, parent) in entities.iter() {
-        bodies.push((
-            entity,
-            NettyRigidBody::new(
+    let bodies = entities
+        .iter()
+        .map(|(entity, transform, location, velocity, unload_distance, parent)| {
+            let body = NettyRigidBody::new(
                 velocity.copied(),
                 transform.rotation,
                 match parent.map(|p| p.get()) {
@@ -87,42 +122,83 @@ fn server_sync_bodies(
                     ),
                     None => NettyRigidBodyLocation::Absolute(*location),
                 },
-            ),
-            *location,
-            *unload_distance,
-        ));
-
-        // The packet size can only be so big, so limit syncing to 20 per packet
-        if bodies.len() >= 20 {
-            send_bodies(&players, &bodies, &mut server, &tick);
-            bodies.clear();
+            );
+
+            let cloaked_by = cloaked_ancestor(entity, &q_cloaked, &q_parent);
+            let invisible = q_spectator.get(entity).is_ok_and(|spectator| spectator.invisible);
+
+            (
+                entity,
+                QuantizedRigidBody::from(body),
+                *location,
+                *unload_distance,
+                cloaked_by,
+                invisible,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let interest_radius_sqrd = settings.interest_radius * settings.interest_radius;
+
+    for (player_ent, player, player_loc, mut replicated) in players.iter_mut() {
+        if full_snapshot {
+            // This also cleans up any entities that no longer exist or are no longer relevant
+            replicated.baselines.clear();
+            replicated.last_sent.clear();
+        }
+
+        let mut players_bodies = Vec::with_capacity(MAX_BODIES_PER_PACKET);
+
+        for (entity, body, location, loading_distance, cloaked_by, invisible) in bodies.iter() {
+            if !loading_distance.should_load(player_loc, location) {
+                continue;
+            }
+
+            if *invisible && *entity != player_ent {
+                continue;
+            }
+
+            if cloaked_by.is_some_and(|cloaked| !is_aboard(player_ent, cloaked, &q_parent)) {
+                continue;
+            }
+
+            if !full_snapshot {
+                if player_loc.distance_sqrd(location) > interest_radius_sqrd {
+                    continue;
+                }
+
+                if replicated.last_sent.get(entity) == Some(body) {
+                    continue;
+                }
+            }
+
+            let replicated_body = match replicated.baselines.get(entity) {
+                Some((baseline_tick, baseline)) => ReplicatedBody::Delta {
+                    baseline: *baseline_tick,
+                    delta: body.delta_from(baseline),
+                },
+                None => {
+                    replicated.baselines.insert(*entity, (tick.0, *body));
+                    ReplicatedBody::Full(*body)
+                }
+            };
+
+            replicated.last_sent.insert(*entity, *body);
+            players_bodies.push((*entity, replicated_body));
+
+            if players_bodies.len() >= MAX_BODIES_PER_PACKET {
+                send_bodies(
+                    &mut server,
+                    player,
+                    std::mem::replace(&mut players_bodies, Vec::with_capacity(MAX_BODIES_PER_PACKET)),
+                    &tick,
+                );
+            }
         }
-    }
 
-    // for (ent, velocity, transform, parent) in q_children_need_synced.iter() {
-    //     let mut info = None;
-
-    //     let mut cur_ent = parent.get();
-    //     while info.is_none() {
-    //         if let Ok((loc, load_dist)) = q_loading_distance.get(cur_ent) {
-    //             info = Some((*loc, *load_dist));
-    //         } else {
-    //             if let Ok(next_ent) = q_parent.get(cur_ent) {
-    //                 cur_ent = next_ent.get();
-    //             } else {
-    //                 break;
-    //             }
-    //         }
-    //     }
-
-    //     let Some((parent_loc, parent_loading_distance)) = info else {
-    //         continue;
-    //     };
-
-    // }
-
-    if !bodies.is_empty() {
-        send_bodies(&players, &bodies, &mut server, &tick);
+        if !players_bodies.is_empty() {
+            send_bodies(&mut server, player, players_bodies, &tick);
+        }
     }
 }
 
@@ -133,10 +209,10 @@ fn notify_client_of_successful_entity_request(
 ) {
     for ev in event_reader.read() {
         if commands.get_entity(ev.entity).is_some() {
-            server.send_message(
+            server.send_netty_message(
                 ev.client_id,
                 NettyChannelServer::Reliable,
-                cosmos_encoder::serialize(&ServerReliableMessages::RequestedEntityReceived(ev.entity)),
+                NettyMessage::new(&ServerReliableMessages::RequestedEntityReceived(ev.entity)),
             );
         }
     }
@@ -174,21 +250,24 @@ fn notify_despawned_entities(
 
         info!("Notifying of entity despawn -- {entity_identifier:?}");
 
-        server.broadcast_message(
+        server.broadcast_netty_message(
             NettyChannelServer::Reliable,
-            cosmos_encoder::serialize(&ServerReliableMessages::EntityDespawn { entity: entity_identifier }),
+            NettyMessage::new(&ServerReliableMessages::EntityDespawn { entity: entity_identifier }),
         );
     }
 }
 
 pub(super) fn register(app: &mut App) {
-    app.add_systems(
-        Update,
-        (
-            notify_client_of_successful_entity_request,
-            server_sync_bodies.after(LocationPhysicsSet::DoPhysics),
+    app.init_resource::<BodyReplicationSettings>()
+        .add_systems(
+            Update,
+            (
+                notify_client_of_successful_entity_request,
+                (add_replicated_bodies, server_sync_bodies)
+                    .chain()
+                    .after(LocationPhysicsSet::DoPhysics),
+            )
+                .in_set(NetworkingSystemsSet::SyncComponents),
         )
-            .in_set(NetworkingSystemsSet::SyncComponents),
-    )
-    .add_systems(First, notify_despawned_entities.before(despawn_needed));
+        .add_systems(First, notify_despawned_entities.before(despawn_needed));
 }
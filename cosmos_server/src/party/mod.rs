@@ -0,0 +1,435 @@
+//! Server-side party logic.
+//!
+//! Players form parties from the chat:
+//! - `/party invite [player]` invites a player to your party, creating it if you aren't in one yet
+//! - `/party accept` joins the party you were last invited to
+//! - `/party leave` leaves your party. If the leader leaves, the next member to have joined leads the party instead.
+//! - `/party kick [player]` removes a player from your party, if you lead it
+//! - `/party tp [player]` teleports you to a member of your party. Only admins can do this.
+//! - `/party` lists the members of your party
+//!
+//! Parties are stored in `world/parties.json`, so they (and their shared waypoints) survive restarts.
+
+use std::fs;
+
+use bevy::{prelude::*, utils::HashMap};
+use cosmos_core::{
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    netty::{sync::events::server_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    party::{Party, PartyTeleportEvent, PartyUpdatedEvent, SharedWaypoint, MAX_PARTY_SIZE, MAX_SHARED_WAYPOINTS},
+    physics::location::Location,
+    state::GameState,
+    structure::{shared::build_mode::BuildMode, ship::pilot::Pilot},
+};
+use renet2::ClientId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    chat::{ChatCommandSet, ChatCommands, PlayerChatCommandEvent},
+    entities::player::admin::Admin,
+};
+
+mod ping;
+mod status;
+mod waypoint;
+
+const PARTIES_PATH: &str = "world/parties.json";
+
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+/// Every party on the server
+pub struct Parties {
+    parties: Vec<Party>,
+    next_waypoint_id: u32,
+}
+
+impl Parties {
+    /// The party the player with this name is in
+    pub fn party_of(&self, player_name: &str) -> Option<&Party> {
+        self.parties.iter().find(|x| x.is_member(player_name))
+    }
+
+    fn party_of_mut(&mut self, player_name: &str) -> Option<&mut Party> {
+        self.parties.iter_mut().find(|x| x.is_member(player_name))
+    }
+
+    /// Adds the player to the leader's party, creating it if the leader isn't in one.
+    ///
+    /// Returns false if the player is already in a party or the leader's party is full.
+    fn join(&mut self, leader: &str, player_name: &str) -> bool {
+        if self.party_of(player_name).is_some() {
+            return false;
+        }
+
+        if self.party_of(leader).is_none() {
+            self.parties.push(Party::new(leader));
+        }
+
+        let Some(party) = self.party_of_mut(leader) else {
+            return false;
+        };
+
+        if party.members.len() >= MAX_PARTY_SIZE {
+            return false;
+        }
+
+        party.members.push(player_name.to_owned());
+        true
+    }
+
+    /// Removes the player from their party, disbanding it if only one member would be left.
+    ///
+    /// Returns false if they weren't in a party.
+    fn leave(&mut self, player_name: &str) -> bool {
+        let Some(idx) = self.parties.iter().position(|x| x.is_member(player_name)) else {
+            return false;
+        };
+
+        let party = &mut self.parties[idx];
+        party.members.retain(|x| x != player_name);
+
+        if party.members.len() <= 1 {
+            self.parties.remove(idx);
+        } else if party.leader == player_name {
+            party.leader = party.members[0].clone();
+        }
+
+        true
+    }
+
+    /// Shares a waypoint with this leader's party. Returns false if they don't lead a party or have shared too many.
+    fn add_waypoint(&mut self, leader: &str, location: Location) -> bool {
+        let id = self.next_waypoint_id;
+
+        let Some(party) = self.party_of_mut(leader).filter(|x| x.is_leader(leader)) else {
+            return false;
+        };
+
+        if party.waypoints.len() >= MAX_SHARED_WAYPOINTS {
+            return false;
+        }
+
+        party.waypoints.push(SharedWaypoint { id, location });
+        self.next_waypoint_id = self.next_waypoint_id.wrapping_add(1);
+
+        true
+    }
+
+    /// Stops sharing a waypoint with this leader's party. Returns false if there was nothing to remove.
+    fn remove_waypoint(&mut self, leader: &str, id: u32) -> bool {
+        let Some(party) = self.party_of_mut(leader).filter(|x| x.is_leader(leader)) else {
+            return false;
+        };
+
+        let n_waypoints = party.waypoints.len();
+        party.waypoints.retain(|x| x.id != id);
+
+        party.waypoints.len() != n_waypoints
+    }
+
+    fn save(&self) {
+        let json = serde_json::to_string_pretty(self).expect("Parties are always valid json");
+
+        if let Err(e) = fs::write(PARTIES_PATH, json) {
+            error!("Unable to save parties to {PARTIES_PATH}.\n{e:?}");
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+/// The party each player was last invited to, as (invited player's name -> name of who invited them)
+struct PartyInvites(HashMap<String, String>);
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+fn register_chat_commands(mut chat_commands: ResMut<ChatCommands>) {
+    chat_commands.add("party");
+}
+
+fn on_party_command(
+    mut commands: Commands,
+    mut evr_command: EventReader<PlayerChatCommandEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut nevw_teleport: NettyEventWriter<PartyTeleportEvent>,
+    q_player: Query<&Player>,
+    q_player_location: Query<(&Player, &Location)>,
+    q_teleporter: Query<(Has<Admin>, Has<Pilot>, Has<BuildMode>)>,
+    mut parties: ResMut<Parties>,
+    mut invites: ResMut<PartyInvites>,
+) {
+    for ev in evr_command.read() {
+        if ev.name != "party" {
+            continue;
+        }
+
+        let Ok(player) = q_player.get(ev.player_entity) else {
+            continue;
+        };
+
+        let name = player.name();
+
+        match ev.args.first().map(|x| x.as_str()) {
+            None => {
+                let message = match parties.party_of(name) {
+                    Some(party) => format!("Party (led by {}): {}", party.leader, party.members.join(", ")),
+                    None => "You aren't in a party. Invite someone with /party invite [player].".to_owned(),
+                };
+
+                reply(&mut nevw_chat, ev.client_id, message);
+            }
+            Some("invite") => {
+                let Some(invited_name) = ev.args.get(1) else {
+                    reply(&mut nevw_chat, ev.client_id, "Usage: /party invite [player]");
+                    continue;
+                };
+
+                let Some(invited) = q_player.iter().find(|x| x.name() == invited_name) else {
+                    reply(&mut nevw_chat, ev.client_id, format!("{invited_name} isn't online."));
+                    continue;
+                };
+
+                if invited.name() == name {
+                    reply(&mut nevw_chat, ev.client_id, "You can't invite yourself.");
+                    continue;
+                }
+
+                if parties.party_of(name).is_some_and(|x| !x.is_leader(name)) {
+                    reply(&mut nevw_chat, ev.client_id, "Only the party leader can invite players.");
+                    continue;
+                }
+
+                if parties.party_of(invited.name()).is_some() {
+                    reply(&mut nevw_chat, ev.client_id, format!("{invited_name} is already in a party."));
+                    continue;
+                }
+
+                invites.0.insert(invited.name().to_owned(), name.to_owned());
+
+                reply(&mut nevw_chat, ev.client_id, format!("Invited {invited_name} to your party."));
+                reply(
+                    &mut nevw_chat,
+                    invited.id(),
+                    format!("{name} invited you to their party. Type /party accept to join."),
+                );
+            }
+            Some("accept") => {
+                let Some(leader) = invites.0.remove(name) else {
+                    reply(&mut nevw_chat, ev.client_id, "You haven't been invited to a party.");
+                    continue;
+                };
+
+                if parties.party_of(&leader).is_some_and(|x| !x.is_leader(&leader)) {
+                    reply(&mut nevw_chat, ev.client_id, format!("{leader} no longer leads their party."));
+                    continue;
+                }
+
+                if !parties.join(&leader, name) {
+                    reply(
+                        &mut nevw_chat,
+                        ev.client_id,
+                        "Unable to join - either you're already in a party or theirs is full.",
+                    );
+                    continue;
+                }
+
+                reply(&mut nevw_chat, ev.client_id, format!("You joined {leader}'s party."));
+            }
+            Some("leave") => {
+                if !parties.leave(name) {
+                    reply(&mut nevw_chat, ev.client_id, "You aren't in a party.");
+                    continue;
+                }
+
+                reply(&mut nevw_chat, ev.client_id, "You left your party.");
+            }
+            Some("kick") => {
+                let Some(kicked_name) = ev.args.get(1) else {
+                    reply(&mut nevw_chat, ev.client_id, "Usage: /party kick [player]");
+                    continue;
+                };
+
+                let Some(party) = parties.party_of(name) else {
+                    reply(&mut nevw_chat, ev.client_id, "You aren't in a party.");
+                    continue;
+                };
+
+                if !party.is_leader(name) {
+                    reply(&mut nevw_chat, ev.client_id, "Only the party leader can kick players.");
+                    continue;
+                }
+
+                if kicked_name == name || !party.is_member(kicked_name) {
+                    reply(&mut nevw_chat, ev.client_id, format!("{kicked_name} isn't in your party."));
+                    continue;
+                }
+
+                parties.leave(kicked_name);
+
+                reply(&mut nevw_chat, ev.client_id, format!("Kicked {kicked_name} from your party."));
+                if let Some(kicked) = q_player.iter().find(|x| x.name() == kicked_name) {
+                    reply(&mut nevw_chat, kicked.id(), format!("{name} kicked you from their party."));
+                }
+            }
+            Some("tp") => {
+                let Some(target_name) = ev.args.get(1) else {
+                    reply(&mut nevw_chat, ev.client_id, "Usage: /party tp [player]");
+                    continue;
+                };
+
+                let Ok((is_admin, is_piloting, in_build_mode)) = q_teleporter.get(ev.player_entity) else {
+                    continue;
+                };
+
+                if !is_admin {
+                    reply(&mut nevw_chat, ev.client_id, "Only admins can teleport to party members.");
+                    continue;
+                }
+
+                if is_piloting || in_build_mode {
+                    reply(&mut nevw_chat, ev.client_id, "You can't teleport while piloting or building.");
+                    continue;
+                }
+
+                if !parties.party_of(name).is_some_and(|x| x.is_member(target_name)) {
+                    reply(&mut nevw_chat, ev.client_id, format!("{target_name} isn't in your party."));
+                    continue;
+                }
+
+                let Some((_, target_loc)) = q_player_location.iter().find(|(x, _)| x.name() == target_name) else {
+                    reply(&mut nevw_chat, ev.client_id, format!("{target_name} isn't online."));
+                    continue;
+                };
+
+                commands.entity(ev.player_entity).remove_parent_in_place();
+                nevw_teleport.send(PartyTeleportEvent(*target_loc), ev.client_id);
+            }
+            Some(_) => {
+                reply(&mut nevw_chat, ev.client_id, "Usage: /party [invite|accept|leave|kick|tp]");
+            }
+        }
+    }
+}
+
+/// Sends every player their party whenever any party changes, and newly joined players their party.
+fn sync_parties(
+    parties: Res<Parties>,
+    q_players: Query<&Player>,
+    q_new_players: Query<&Player, Added<Player>>,
+    mut nevw_party: NettyEventWriter<PartyUpdatedEvent>,
+) {
+    if parties.is_changed() {
+        parties.save();
+
+        for player in q_players.iter() {
+            nevw_party.send(
+                PartyUpdatedEvent {
+                    party: parties.party_of(player.name()).cloned(),
+                },
+                player.id(),
+            );
+        }
+
+        return;
+    }
+
+    for player in q_new_players.iter() {
+        nevw_party.send(
+            PartyUpdatedEvent {
+                party: parties.party_of(player.name()).cloned(),
+            },
+            player.id(),
+        );
+    }
+}
+
+fn load_parties(mut commands: Commands) {
+    let parties = fs::read_to_string(PARTIES_PATH)
+        .ok()
+        .map(|json| {
+            serde_json::from_str::<Parties>(&json).unwrap_or_else(|e| {
+                error!("Invalid parties in {PARTIES_PATH} - ignoring them.\n{e:?}");
+                Parties::default()
+            })
+        })
+        .unwrap_or_default();
+
+    commands.insert_resource(parties);
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+/// Parties are changed, then sent to their members
+enum PartySet {
+    /// Parties are changed by players
+    ChangeParties,
+    /// Players are sent their parties if they changed
+    SyncParties,
+}
+
+pub(super) fn register(app: &mut App) {
+    ping::register(app);
+    waypoint::register(app);
+
+    app.configure_sets(
+        Update,
+        (PartySet::ChangeParties, PartySet::SyncParties)
+            .chain()
+            .in_set(NetworkingSystemsSet::Between),
+    )
+    .init_resource::<Parties>()
+    .init_resource::<PartyInvites>()
+    .add_systems(Startup, register_chat_commands)
+    .add_systems(OnEnter(GameState::PostLoading), load_parties)
+    .add_systems(
+        Update,
+        (
+            on_party_command
+                .after(ChatCommandSet::SendCommandEvents)
+                .in_set(PartySet::ChangeParties),
+            sync_parties.in_set(PartySet::SyncParties),
+        )
+            .run_if(in_state(GameState::Playing)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leader_leaving_passes_leadership_on() {
+        let mut parties = Parties::default();
+
+        assert!(parties.join("a", "b"));
+        assert!(parties.join("a", "c"));
+        assert!(!parties.join("c", "b"));
+
+        assert!(parties.leave("a"));
+
+        let party = parties.party_of("b").expect("b is still in a party");
+        assert_eq!(party.leader, "b");
+        assert_eq!(party.members, vec!["b".to_owned(), "c".to_owned()]);
+        assert!(parties.party_of("a").is_none());
+    }
+
+    #[test]
+    fn party_disbands_with_one_member_left() {
+        let mut parties = Parties::default();
+
+        assert!(parties.join("a", "b"));
+        assert!(parties.add_waypoint("a", Location::default()));
+        assert!(!parties.add_waypoint("b", Location::default()));
+
+        assert!(parties.leave("b"));
+
+        assert!(parties.party_of("a").is_none());
+        assert!(!parties.leave("a"));
+    }
+}
@@ -0,0 +1,69 @@
+//! Relays pings to the rest of the pinging player's party
+
+use bevy::prelude::*;
+use cosmos_core::{
+    entities::player::Player,
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+    },
+    party::{PartyPingEvent, SendPingEvent},
+    state::GameState,
+};
+
+use super::{Parties, PartySet};
+
+/// How long (in seconds) a player has to wait between pings, so they can't flood their party with them
+const PING_COOLDOWN: f32 = 1.0;
+
+#[derive(Component, Debug)]
+/// When this player last pinged, in seconds since the server started
+struct LastPing(f32);
+
+fn on_send_ping(
+    mut commands: Commands,
+    mut nevr_ping: EventReader<NettyEventReceived<SendPingEvent>>,
+    lobby: Res<ServerLobby>,
+    time: Res<Time>,
+    parties: Res<Parties>,
+    q_players: Query<(&Player, Option<&LastPing>)>,
+    mut nevw_ping: NettyEventWriter<PartyPingEvent>,
+) {
+    let now = time.elapsed_secs();
+
+    for ev in nevr_ping.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            continue;
+        };
+
+        let Ok((player, last_ping)) = q_players.get(player_ent) else {
+            continue;
+        };
+
+        if last_ping.is_some_and(|x| now - x.0 < PING_COOLDOWN) {
+            continue;
+        }
+
+        let Some(party) = parties.party_of(player.name()) else {
+            continue;
+        };
+
+        commands.entity(player_ent).insert(LastPing(now));
+
+        let ping = PartyPingEvent {
+            player: player.name().to_owned(),
+            location: ev.location,
+        };
+
+        for (member, _) in q_players.iter().filter(|(x, _)| party.is_member(x.name())) {
+            nevw_ping.send(ping.clone(), member.id());
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        on_send_ping.in_set(PartySet::ChangeParties).run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,82 @@
+//! Regularly tells party members how the rest of their party is doing
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer, utils::HashMap};
+use cosmos_core::{
+    entities::{health::Health, player::Player},
+    netty::sync::events::server_event::NettyEventWriter,
+    party::{PartyMemberStatus, PartyStatusEvent},
+    state::GameState,
+    structure::{shields::Shield, ship::pilot::Pilot},
+};
+
+use super::{Parties, PartySet};
+
+/// How often party members are sent the status of their party
+const STATUS_INTERVAL: Duration = Duration::from_millis(500);
+
+fn send_party_status(
+    parties: Res<Parties>,
+    q_players: Query<(&Player, Option<&Health>, Option<&Pilot>)>,
+    q_shields: Query<(&Shield, &Parent)>,
+    mut nevw_status: NettyEventWriter<PartyStatusEvent>,
+) {
+    if parties.parties.is_empty() {
+        return;
+    }
+
+    let online = q_players
+        .iter()
+        .map(|(player, health, pilot)| (player.name(), (player.id(), health, pilot)))
+        .collect::<HashMap<_, _>>();
+
+    for party in parties.parties.iter() {
+        let members = party
+            .members
+            .iter()
+            .map(|name| {
+                let Some((_, health, pilot)) = online.get(name.as_str()) else {
+                    return PartyMemberStatus {
+                        name: name.clone(),
+                        online: false,
+                        health: None,
+                        shield: None,
+                    };
+                };
+
+                let shield = pilot.map(|pilot| {
+                    q_shields
+                        .iter()
+                        .filter(|(_, parent)| parent.get() == pilot.entity)
+                        .fold((0.0, 0.0), |(strength, max), (shield, _)| {
+                            (strength + shield.strength, max + shield.max_strength)
+                        })
+                });
+
+                PartyMemberStatus {
+                    name: name.clone(),
+                    online: true,
+                    health: health.copied(),
+                    shield,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for name in party.members.iter() {
+            if let Some((client_id, _, _)) = online.get(name.as_str()) {
+                nevw_status.send(PartyStatusEvent { members: members.clone() }, *client_id);
+            }
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        send_party_status
+            .run_if(on_timer(STATUS_INTERVAL))
+            .in_set(PartySet::SyncParties)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,53 @@
+//! Lets party leaders share waypoints with their party
+
+use bevy::prelude::*;
+use cosmos_core::{
+    entities::player::Player,
+    netty::{server::ServerLobby, sync::events::server_event::NettyEventReceived},
+    party::{AddSharedWaypointEvent, RemoveSharedWaypointEvent},
+    state::GameState,
+};
+
+use super::{Parties, PartySet};
+
+fn on_add_shared_waypoint(
+    mut nevr_add: EventReader<NettyEventReceived<AddSharedWaypointEvent>>,
+    lobby: Res<ServerLobby>,
+    q_player: Query<&Player>,
+    mut parties: ResMut<Parties>,
+) {
+    for ev in nevr_add.read() {
+        let Some(player) = lobby.player_from_id(ev.client_id).and_then(|x| q_player.get(x).ok()) else {
+            continue;
+        };
+
+        if !parties.add_waypoint(player.name(), ev.location) {
+            warn!("{} tried to share a waypoint they couldn't.", player.name());
+        }
+    }
+}
+
+fn on_remove_shared_waypoint(
+    mut nevr_remove: EventReader<NettyEventReceived<RemoveSharedWaypointEvent>>,
+    lobby: Res<ServerLobby>,
+    q_player: Query<&Player>,
+    mut parties: ResMut<Parties>,
+) {
+    for ev in nevr_remove.read() {
+        let Some(player) = lobby.player_from_id(ev.client_id).and_then(|x| q_player.get(x).ok()) else {
+            continue;
+        };
+
+        parties.remove_waypoint(player.name(), ev.id);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (on_add_shared_waypoint, on_remove_shared_waypoint)
+            .chain()
+            .in_set(PartySet::ChangeParties)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -13,7 +13,7 @@ use bevy::{
     ecs::schedule::{IntoSystemConfigs, IntoSystemSetConfigs, SystemSet},
     hierarchy::BuildChildren,
     log::{error, warn},
-    prelude::{App, Commands, Component, Entity, Quat, Query, Transform, Update, With, Without},
+    prelude::{App, Commands, Component, Entity, Quat, Query, ResMut, Transform, Update, With, Without},
     reflect::Reflect,
 };
 use bevy_rapier3d::prelude::Velocity;
@@ -23,7 +23,7 @@ use cosmos_core::{
     structure::loading::StructureLoadingSet,
 };
 
-use super::{EntityId, SaveFileIdentifier, SaveFileIdentifierType, SerializedData};
+use super::{saving::WritingSaveFiles, EntityId, SaveFileIdentifier, SaveFileIdentifierType, SerializedData};
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 /// Put anything related to loading entities in from serialized data into this set
@@ -65,10 +65,12 @@ pub struct NeedsBlueprintLoaded {
 fn check_needs_loaded(
     q_entity_ids: Query<(Entity, &EntityId)>,
     q_sfis: Query<(Entity, &SaveFileIdentifier), (Without<SerializedData>, With<NeedsLoaded>)>,
+    mut writing: ResMut<WritingSaveFiles>,
     mut commands: Commands,
 ) {
     for (ent, nl) in q_sfis.iter() {
         let path = nl.get_save_file_path();
+        writing.finish_writing(&path);
         let Ok(data) = fs::read(&path) else {
             warn!("Error reading file at '{path}'. Is it there?");
             commands.entity(ent).insert(NeedsDespawned);
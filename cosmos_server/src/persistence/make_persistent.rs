@@ -7,7 +7,7 @@ use bevy::{
         event::EventReader,
         query::With,
         schedule::IntoSystemConfigs,
-        system::{Commands, Query, SystemParam},
+        system::{Commands, Query, Res, SystemParam},
     },
     hierarchy::Parent,
     log::{error, warn},
@@ -25,6 +25,7 @@ use crate::structure::persistence::{chunk::BlockDataSavingSet, BlockDataNeedsSav
 
 use super::{
     loading::{LoadingSystemSet, NeedsLoaded, LOADING_SCHEDULE},
+    migrations::{version_data_id, ComponentMigrations},
     saving::{NeedsSaved, SavingSystemSet, SAVING_SCHEDULE},
     EntityId, SerializedData,
 };
@@ -43,6 +44,10 @@ fn save_component<T: PersistentComponent>(
         };
 
         serialized_data.serialize_data(T::get_component_unlocalized_name(), save_type.as_ref());
+        // Data without a version is treated as version 0, so there's no need to store it
+        if T::SCHEMA_VERSION != 0 {
+            serialized_data.serialize_data(version_data_id(T::get_component_unlocalized_name()), &T::SCHEMA_VERSION);
+        }
     });
 }
 
@@ -64,11 +69,12 @@ fn save_component_block_data<T: PersistentComponent>(
             return;
         };
 
-        serialized_block_data.serialize_data(
-            ChunkBlockCoordinate::for_block_coordinate(block_data.identifier.block.coords()),
-            T::get_component_unlocalized_name(),
-            save_type.as_ref(),
-        );
+        let coords = ChunkBlockCoordinate::for_block_coordinate(block_data.identifier.block.coords());
+
+        serialized_block_data.serialize_data(coords, T::get_component_unlocalized_name(), save_type.as_ref());
+        if T::SCHEMA_VERSION != 0 {
+            serialized_block_data.serialize_data(coords, version_data_id(T::get_component_unlocalized_name()), &T::SCHEMA_VERSION);
+        }
     });
 }
 
@@ -76,10 +82,19 @@ fn load_component<T: PersistentComponent>(
     mut commands: Commands,
     q_needs_loaded: Query<(Entity, &SerializedData), With<NeedsLoaded>>,
     entity_id_manager: EntityIdManager,
+    migrations: Res<ComponentMigrations>,
 ) {
     q_needs_loaded.iter().for_each(|(entity, serialized_data)| {
-        let Some(component_save_data) = serialized_data.deserialize_data::<T::SaveType>(T::get_component_unlocalized_name()) else {
-            return;
+        let component_save_data = match migrations.read_component::<T>(|id| serialized_data.read_data(id)) {
+            Ok(Some(data)) => data,
+            Ok(None) => return,
+            Err(e) => {
+                error!(
+                    "Unable to load component {} for entity {entity:?} ({e}). This component will not be loaded.",
+                    T::get_component_unlocalized_name()
+                );
+                return;
+            }
         };
 
         let Some(mut component) = T::convert_from_save_type(component_save_data, &entity_id_manager) else {
@@ -103,6 +118,7 @@ fn load_component_from_block_data<T: PersistentComponent>(
     mut commands: Commands,
     q_has_component: Query<(), With<T>>,
     entity_id_manager: EntityIdManager,
+    migrations: Res<ComponentMigrations>,
 ) {
     for ev in ev_reader.read() {
         let Ok(mut structure) = q_structure.get_mut(ev.structure_entity) else {
@@ -112,8 +128,17 @@ fn load_component_from_block_data<T: PersistentComponent>(
 
         let first = ev.chunk.first_structure_block();
         for (data_coord, serialized) in ev.data.iter() {
-            let Some(component_save_data) = serialized.deserialize_data::<T::SaveType>(T::get_component_unlocalized_name()) else {
-                return;
+            let component_save_data = match migrations.read_component::<T>(|id| serialized.read_data(id)) {
+                Ok(Some(data)) => data,
+                Ok(None) => continue,
+                Err(e) => {
+                    error!(
+                        "Unable to load component {} for block data @ {data_coord} in structure {:?} ({e}). This component will not be loaded.",
+                        T::get_component_unlocalized_name(),
+                        ev.structure_entity
+                    );
+                    continue;
+                }
             };
 
             let Some(mut data) = T::convert_from_save_type(component_save_data, &entity_id_manager) else {
@@ -167,6 +192,12 @@ pub trait PersistentComponent: IdentifiableComponent + Sized {
     /// runtime.
     type SaveType: Serialize + DeserializeOwned;
 
+    /// The version of [`Self::SaveType`]'s saved format.
+    ///
+    /// Bump this whenever the saved format changes, and register a migration from the previous
+    /// version via [`super::migrations::register_migration`] so existing saves can still be loaded.
+    const SCHEMA_VERSION: u32 = 0;
+
     /// Initializes this component before adding it to this entity
     ///
     /// Mostly used to clear out any junk data that got saved
@@ -187,6 +218,9 @@ pub trait PersistentComponent: IdentifiableComponent + Sized {
 
 /// This component will be saved & loaded when the entity it is a part of is saved/unloaded.
 pub trait DefaultPersistentComponent: PersistentComponent<SaveType = Self> {
+    /// The version of this component's saved format. See [`PersistentComponent::SCHEMA_VERSION`].
+    const SCHEMA_VERSION: u32 = 0;
+
     /// Initializes this component before adding it to this entity
     ///
     /// Mostly used to clear out any junk data that got saved
@@ -199,6 +233,8 @@ where
 {
     type SaveType = Self;
 
+    const SCHEMA_VERSION: u32 = <T as DefaultPersistentComponent>::SCHEMA_VERSION;
+
     fn initialize(&mut self, self_entity: Entity, commands: &mut Commands) {
         DefaultPersistentComponent::initialize(self, self_entity, commands);
     }
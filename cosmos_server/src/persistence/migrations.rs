@@ -0,0 +1,220 @@
+//! Upgrades components saved by older versions of the game to their current format.
+//!
+//! Every [`PersistentComponent`] has a [`PersistentComponent::SCHEMA_VERSION`]. That version is saved next
+//! to the component's data, and data saved before versions existed is treated as version 0.
+//!
+//! Whenever you change the saved format of a component, bump its `SCHEMA_VERSION` and register a migration
+//! from the previous version using [`register_migration`]. When an older save is loaded, every migration between
+//! the saved version and the current version is run in order before the data is deserialized.
+//!
+//! Data that cannot be migrated is skipped (and logged) instead of crashing the server or being loaded
+//! as garbage.
+
+use std::fmt::Display;
+
+use bevy::{prelude::*, utils::HashMap};
+use cosmos_core::netty::cosmos_encoder;
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::make_persistent::PersistentComponent;
+
+type MigrationFn = Box<dyn Fn(&[u8]) -> Option<Vec<u8>> + Send + Sync>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Why a saved component could not be loaded
+pub enum MigrationError {
+    /// The data was saved by a newer version of the game than this one
+    NewerVersion {
+        /// The version the data was saved with
+        saved: u32,
+        /// The newest version this game knows about
+        current: u32,
+    },
+    /// There is no migration registered from this version
+    MissingMigration {
+        /// The version that has no migration
+        from: u32,
+    },
+    /// The migration from this version was unable to convert the data
+    MigrationFailed {
+        /// The version that was being migrated from
+        from: u32,
+    },
+    /// The data (after being migrated) is not valid for the current version
+    InvalidData,
+}
+
+impl Display for MigrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NewerVersion { saved, current } => write!(f, "saved with version {saved}, but the newest known version is {current}"),
+            Self::MissingMigration { from } => write!(f, "no migration registered from version {from}"),
+            Self::MigrationFailed { from } => write!(f, "migration from version {from} failed"),
+            Self::InvalidData => f.write_str("the data is invalid"),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+/// Every migration registered for every persistent component.
+///
+/// Use [`register_migration`] or [`register_raw_migration`] to add migrations.
+pub struct ComponentMigrations {
+    /// Component unlocalized name -> (version migrated from -> migration to the next version)
+    migrations: HashMap<String, HashMap<u32, MigrationFn>>,
+}
+
+impl ComponentMigrations {
+    fn add(&mut self, component_name: &str, from_version: u32, migration: MigrationFn) {
+        let old = self
+            .migrations
+            .entry(component_name.to_owned())
+            .or_default()
+            .insert(from_version, migration);
+
+        if old.is_some() {
+            warn!("Replacing existing migration for {component_name} from version {from_version}.");
+        }
+    }
+
+    /// Runs every migration needed to bring this data from `saved_version` to `current_version`.
+    pub fn upgrade(&self, component_name: &str, saved_version: u32, current_version: u32, data: &[u8]) -> Result<Vec<u8>, MigrationError> {
+        if saved_version > current_version {
+            return Err(MigrationError::NewerVersion {
+                saved: saved_version,
+                current: current_version,
+            });
+        }
+
+        let migrations = self.migrations.get(component_name);
+
+        let mut data = data.to_vec();
+        for from in saved_version..current_version {
+            let migration = migrations
+                .and_then(|x| x.get(&from))
+                .ok_or(MigrationError::MissingMigration { from })?;
+
+            data = migration(&data).ok_or(MigrationError::MigrationFailed { from })?;
+        }
+
+        Ok(data)
+    }
+
+    /// Reads the saved data for this component, migrating it to the current version if needed.
+    ///
+    /// `read_data` should return the raw bytes saved under the given data id.
+    ///
+    /// Returns `Ok(None)` if this component was not saved.
+    pub fn read_component<'a, T: PersistentComponent>(
+        &self,
+        read_data: impl Fn(&str) -> Option<&'a Vec<u8>>,
+    ) -> Result<Option<T::SaveType>, MigrationError> {
+        let name = T::get_component_unlocalized_name();
+
+        let Some(data) = read_data(name) else {
+            return Ok(None);
+        };
+
+        let saved_version = match read_data(&version_data_id(name)) {
+            Some(version) => cosmos_encoder::deserialize::<u32>(version).map_err(|_| MigrationError::InvalidData)?,
+            None => 0,
+        };
+
+        if saved_version == T::SCHEMA_VERSION {
+            return cosmos_encoder::deserialize(data).map(Some).map_err(|_| MigrationError::InvalidData);
+        }
+
+        let upgraded = self.upgrade(name, saved_version, T::SCHEMA_VERSION, data)?;
+
+        cosmos_encoder::deserialize(&upgraded)
+            .map(Some)
+            .map_err(|_| MigrationError::InvalidData)
+    }
+}
+
+/// The data id a component's schema version is saved under
+pub fn version_data_id(component_name: &str) -> String {
+    format!("{component_name}#version")
+}
+
+/// Registers a migration that converts this component's saved data from `from_version` to `from_version + 1`.
+///
+/// `Old` is the format the data was saved in at `from_version`, and `New` is the format at `from_version + 1`.
+/// These are normally copies of the component's old save types kept around just for migrating.
+pub fn register_migration<T: PersistentComponent, Old: DeserializeOwned, New: Serialize>(
+    app: &mut App,
+    from_version: u32,
+    migrate: impl Fn(Old) -> New + Send + Sync + 'static,
+) {
+    register_raw_migration::<T>(app, from_version, move |data| {
+        let old = cosmos_encoder::deserialize::<Old>(data).ok()?;
+
+        Some(cosmos_encoder::serialize(&migrate(old)))
+    });
+}
+
+/// Registers a migration that converts this component's raw saved bytes from `from_version` to `from_version + 1`.
+///
+/// Return `None` if the data cannot be migrated. Prefer [`register_migration`] unless you need to work with the bytes directly.
+pub fn register_raw_migration<T: PersistentComponent>(
+    app: &mut App,
+    from_version: u32,
+    migrate: impl Fn(&[u8]) -> Option<Vec<u8>> + Send + Sync + 'static,
+) {
+    assert!(
+        from_version < T::SCHEMA_VERSION,
+        "Migration for {} from version {from_version} would never be used - its current version is {}.",
+        T::get_component_unlocalized_name(),
+        T::SCHEMA_VERSION
+    );
+
+    app.init_resource::<ComponentMigrations>();
+    app.world_mut()
+        .resource_mut::<ComponentMigrations>()
+        .add(T::get_component_unlocalized_name(), from_version, Box::new(migrate));
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<ComponentMigrations>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrations_run_in_order() {
+        let mut migrations = ComponentMigrations::default();
+
+        migrations.add(
+            "test",
+            0,
+            Box::new(|data| {
+                let old = cosmos_encoder::deserialize::<u8>(data).ok()?;
+                Some(cosmos_encoder::serialize(&(old as u32 * 10)))
+            }),
+        );
+        migrations.add(
+            "test",
+            1,
+            Box::new(|data| {
+                let old = cosmos_encoder::deserialize::<u32>(data).ok()?;
+                Some(cosmos_encoder::serialize(&(old, true)))
+            }),
+        );
+
+        let data = cosmos_encoder::serialize(&4u8);
+
+        let upgraded = migrations.upgrade("test", 0, 2, &data).expect("Both migrations exist");
+        assert_eq!(cosmos_encoder::deserialize::<(u32, bool)>(&upgraded).unwrap(), (40, true));
+
+        assert_eq!(
+            migrations.upgrade("test", 0, 3, &data),
+            Err(MigrationError::MissingMigration { from: 2 })
+        );
+        assert_eq!(
+            migrations.upgrade("test", 3, 2, &data),
+            Err(MigrationError::NewerVersion { saved: 3, current: 2 })
+        );
+    }
+}
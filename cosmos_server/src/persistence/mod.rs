@@ -23,6 +23,7 @@ pub mod autosave;
 pub mod backup;
 pub mod loading;
 pub mod make_persistent;
+pub mod migrations;
 pub mod player_loading;
 pub mod saving;
 
@@ -317,6 +318,7 @@ pub(super) fn register(app: &mut App) {
     player_loading::register(app);
     autosave::register(app);
     backup::register(app);
+    migrations::register(app);
 
     app.register_type::<EntityId>().register_type::<SerializedData>();
 }
@@ -8,10 +8,13 @@ use std::{
 
 use bevy::{
     log::warn,
-    prelude::{App, Commands, Component, DespawnRecursiveExt, Entity, IntoSystemConfigs, Name, Query, ResMut, Update, With, Without},
+    prelude::{
+        App, Commands, Component, DespawnRecursiveExt, Entity, Has, IntoSystemConfigs, Name, Query, Res, ResMut, Update, With, Without,
+    },
     state::condition::in_state,
     tasks::{AsyncComputeTaskPool, Task},
     time::common_conditions::on_timer,
+    utils::HashSet,
 };
 use cosmos_core::{
     ecs::NeedsDespawned,
@@ -24,22 +27,29 @@ use cosmos_core::{
 use futures_lite::future;
 use walkdir::WalkDir;
 
+use crate::structure::anchor::{AnchoredSectors, ChunkAnchor};
+
 use super::{loading::NeedsLoaded, saving::NeedsSaved, EntityId, SaveFileIdentifier, SectorsCache};
 
 fn unload_far(
     query: Query<&Location, With<Player>>,
-    others: Query<(&Location, Entity, &LoadingDistance), (Without<Player>, Without<NeedsDespawned>)>,
+    others: Query<(&Location, Entity, &LoadingDistance, Has<ChunkAnchor>), (Without<Player>, Without<NeedsDespawned>)>,
+    anchored_sectors: Res<AnchoredSectors>,
     mut commands: Commands,
 ) {
-    for (loc, ent, ul_distance) in others.iter() {
-        let ul_distance = ul_distance.unload_block_distance();
+    let anchored_sectors = anchored_sectors.iter().copied().collect::<HashSet<Sector>>();
 
-        if let Some(min_dist) = query.iter().map(|l| l.relative_coords_to(loc).abs().max_element()).reduce(f32::min) {
-            if min_dist <= ul_distance {
-                continue;
-            }
+    for (loc, ent, ul_distance, anchored) in others.iter() {
+        // Anchors keep themselves and everything else in their sector loaded
+        if anchored || anchored_sectors.contains(&loc.sector()) {
+            continue;
+        }
+
+        if !query.iter().all(|p_loc| ul_distance.should_unload(p_loc, loc)) {
+            continue;
         }
 
+        // Saved with its sector, so it's loaded back in once a player comes near it again
         commands.entity(ent).insert((NeedsSaved, NeedsDespawned));
     }
 }
@@ -80,6 +90,7 @@ fn monitor_loading_task(
 /// Performance hot spot
 fn load_near(
     q_player_locations: Query<&Location, With<Player>>,
+    anchored_sectors: Res<AnchoredSectors>,
     loaded_entities: Query<&EntityId>,
     // This is modified below, despite it being cloned. Use ResMut to make purpose clear
     sectors_cache: ResMut<SectorsCache>,
@@ -92,14 +103,19 @@ fn load_near(
         return;
     }
 
-    if q_player_locations.is_empty() {
-        // Don't bother if there are no players
+    if q_player_locations.is_empty() && anchored_sectors.is_empty() {
+        // Don't bother if there are no players or anchors
         return;
     }
 
     let thread_pool = AsyncComputeTaskPool::get();
 
-    let sectors = q_player_locations.iter().map(|l| l.sector()).collect::<Vec<Sector>>();
+    // Players load everything around them, while anchors only load their own sector
+    let sectors = q_player_locations
+        .iter()
+        .map(|l| (l.sector(), SEARCH_RANGE))
+        .chain(anchored_sectors.iter().map(|&sector| (sector, 0)))
+        .collect::<Vec<(Sector, SectorUnit)>>();
 
     // Shallow clone - we are only cloning the Arc<Mutex<...>> not the ...
     let mut sectors_cache = sectors_cache.clone();
@@ -110,10 +126,10 @@ fn load_near(
     let task = thread_pool.spawn(async move {
         let mut to_load = vec![];
 
-        for sector in sectors {
-            for dz in -SEARCH_RANGE..=SEARCH_RANGE {
-                for dy in -SEARCH_RANGE..=SEARCH_RANGE {
-                    for dx in -SEARCH_RANGE..SEARCH_RANGE {
+        for (sector, search_range) in sectors {
+            for dz in -search_range..=search_range {
+                for dy in -search_range..=search_range {
+                    for dx in -search_range..=search_range {
                         let sector = Sector::new(dx + sector.x(), dy + sector.y(), dz + sector.z());
                         let max_delta = dz.abs().max(dy.abs()).max(dx.abs()) as u32;
 
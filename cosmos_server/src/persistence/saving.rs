@@ -6,14 +6,21 @@
 //! loaded. From there, you can add any components necessary to the entity to fully load it in.
 //!
 //! See [`saving::default_save`] for an example.
+//!
+//! Save files are written in the background, so they may not be on the disk yet once `done_saving` has run. Anything
+//! reading a save file should call [`WritingSaveFiles::finish_writing`] with its path first.
 
 use bevy::{
     core::Name,
     ecs::schedule::{IntoSystemSetConfigs, SystemSet},
     hierarchy::Parent,
     log::{error, info, warn},
-    prelude::{App, Commands, Component, Entity, First, IntoSystemConfigs, Or, Query, ResMut, Transform, With, Without},
+    prelude::{
+        App, AppExit, Commands, Component, Entity, EventReader, First, IntoSystemConfigs, Last, Or, ParamSet, Query, ResMut, Resource,
+        Transform, With, Without,
+    },
     reflect::Reflect,
+    tasks::{AsyncComputeTaskPool, Task},
 };
 use bevy_rapier3d::prelude::Velocity;
 use cosmos_core::{
@@ -23,6 +30,7 @@ use cosmos_core::{
     persistence::LoadingDistance,
     physics::location::Location,
 };
+use futures_lite::future;
 use std::{
     fs,
     io::{self, ErrorKind},
@@ -104,7 +112,7 @@ fn save_blueprint(data: &SerializedData, needs_blueprinted: &NeedsBlueprinted) -
         }
     }
 
-    if let Err(e) = fs::create_dir(format!("blueprints/{}", needs_blueprinted.subdir_name)) {
+    if let Err(e) = fs::create_dir_all(format!("blueprints/{}", needs_blueprinted.subdir_name)) {
         match e.kind() {
             ErrorKind::AlreadyExists => {}
             _ => return Err(e),
@@ -149,29 +157,61 @@ fn create_entity_ids(mut commands: Commands, q_without_id: Query<(Entity, &Seria
     }
 }
 
+struct SaveFileWrite {
+    path: String,
+    task: Task<()>,
+}
+
+#[derive(Resource, Default)]
+/// Serializing large structures takes a while, so save files are serialized & written in the [`AsyncComputeTaskPool`]
+/// rather than freezing the server.
+pub(crate) struct WritingSaveFiles(Vec<SaveFileWrite>);
+
+impl WritingSaveFiles {
+    /// Waits for any file still being written to this path.
+    ///
+    /// Call this before reading, deleting, or writing a save file so the file on disk is never older than
+    /// (or overwritten by) a write that was still in progress.
+    pub(crate) fn finish_writing(&mut self, path: &str) {
+        for write in self.0.iter_mut().filter(|w| w.path == path) {
+            future::block_on(&mut write.task);
+        }
+
+        self.0.retain(|w| w.path != path);
+    }
+}
+
 /// Make sure any systems that serialize data for saving are run before this
 fn done_saving(
-    q_needs_saved: Query<
+    mut q_saving: ParamSet<(
         (
-            Entity,
-            Option<&Name>,
-            &SerializedData,
-            &EntityId,
-            Option<&LoadingDistance>,
-            Option<&SaveFileIdentifier>,
-            Option<&Player>,
+            Query<
+                (
+                    Entity,
+                    Option<&Name>,
+                    &SerializedData,
+                    &EntityId,
+                    Option<&LoadingDistance>,
+                    Option<&SaveFileIdentifier>,
+                    Option<&Player>,
+                ),
+                With<NeedsSaved>,
+            >,
+            Query<(&SerializedData, &EntityId, Option<&LoadingDistance>)>,
         ),
-        With<NeedsSaved>,
-    >,
+        Query<&mut SerializedData, With<NeedsSaved>>,
+    )>,
     q_parent: Query<&Parent>,
     q_entity_id: Query<&EntityId>,
-    q_serialized_data: Query<(&SerializedData, &EntityId, Option<&LoadingDistance>)>,
     dead_saves_query: Query<&SaveFileIdentifier, (With<NeedsDespawned>, Without<NeedsSaved>)>,
     mut sectors_cache: ResMut<SectorsCache>,
+    mut writing: ResMut<WritingSaveFiles>,
     mut commands: Commands,
 ) {
     for dead_save in dead_saves_query.iter() {
         let path = dead_save.get_save_file_path();
+        writing.finish_writing(&path);
+
         if fs::exists(&path).unwrap_or(false) {
             fs::remove_file(path).expect("Error deleting old save file!");
 
@@ -181,6 +221,10 @@ fn done_saving(
         }
     }
 
+    let mut to_write = vec![];
+
+    let (q_needs_saved, q_serialized_data) = q_saving.p0();
+
     for (entity, name, sd, entity_id, loading_distance, mut save_file_identifier, player) in q_needs_saved.iter() {
         commands.entity(entity).remove::<NeedsSaved>().remove::<SerializedData>();
 
@@ -221,21 +265,16 @@ fn done_saving(
         };
 
         let path = save_file_identifier.get_save_file_path();
-        if fs::exists(&path).unwrap_or(false) {
-            if fs::remove_file(&path).is_err() {
-                warn!("Error deleting old save file at {path}!");
-            }
+        writing.finish_writing(&path);
 
+        // The old file is replaced once the new one is fully written, so it is never missing while being saved
+        if fs::exists(&path).unwrap_or(false) {
             if let SaveFileIdentifierType::Base(entity_id, Some(sector), load_distance) = &save_file_identifier.identifier_type {
                 sectors_cache.remove(entity_id, *sector, *load_distance);
             }
         }
 
-        let serialized: Vec<u8> = cosmos_encoder::serialize(&sd);
-
-        if let Err(e) = write_file(save_file_identifier, &serialized) {
-            error!("Unable to save {entity:?}\n{e}");
-        }
+        to_write.push((entity, save_file_identifier.clone()));
 
         if let Some(player) = player {
             info!("Saving player data for {player:?} to disk.")
@@ -247,6 +286,56 @@ fn done_saving(
             }
         }
     }
+
+    if to_write.is_empty() {
+        return;
+    }
+
+    let thread_pool = AsyncComputeTaskPool::get();
+    let mut q_serialized_data = q_saving.p1();
+
+    for (entity, save_file_identifier) in to_write {
+        let Ok(mut sd) = q_serialized_data.get_mut(entity) else {
+            continue;
+        };
+
+        // The component is being removed anyway, so the task can own the data instead of copying it
+        let sd = std::mem::take(&mut *sd);
+
+        let task = thread_pool.spawn(async move {
+            let serialized: Vec<u8> = cosmos_encoder::serialize(&sd);
+
+            if let Err(e) = write_file(&save_file_identifier, &serialized) {
+                error!("Unable to save {entity:?}\n{e}");
+            }
+        });
+
+        writing.0.push(SaveFileWrite {
+            path: save_file_identifier.get_save_file_path(),
+            task,
+        });
+    }
+}
+
+fn poll_save_file_writes(mut writing: ResMut<WritingSaveFiles>) {
+    writing
+        .0
+        .retain_mut(|write| future::block_on(future::poll_once(&mut write.task)).is_none());
+}
+
+/// Anything still being written would be lost if the server shut down before it finished
+fn finish_writing_on_exit(mut evr_app_exit: EventReader<AppExit>, mut writing: ResMut<WritingSaveFiles>) {
+    if evr_app_exit.read().next().is_none() {
+        return;
+    }
+
+    if !writing.0.is_empty() {
+        info!("Waiting for save files to finish writing before shutting down.");
+    }
+
+    for write in writing.0.drain(..) {
+        future::block_on(write.task);
+    }
 }
 
 /// This is in a bad spot, and should be moved.
@@ -289,9 +378,17 @@ fn write_file(save_identifier: &SaveFileIdentifier, serialized: &[u8]) -> io::Re
 
     fs::create_dir_all(directory)?;
 
-    fs::write(&path, serialized)?;
+    write_file_atomically(&path, serialized)
+}
 
-    Ok(())
+/// Writes the data to a temporary file next to this path, then moves it into place.
+///
+/// Anything reading this path will either see the old file or the new one - never a partially written one.
+pub(crate) fn write_file_atomically(path: &str, data: impl AsRef<[u8]>) -> io::Result<()> {
+    let temp_path = format!("{path}.tmp");
+
+    fs::write(&temp_path, data)?;
+    fs::rename(&temp_path, path)
 }
 
 fn default_save(
@@ -346,9 +443,11 @@ pub(super) fn register(app: &mut App) {
             check_needs_saved.in_set(SavingSystemSet::BeginSaving),
             default_save.in_set(SavingSystemSet::DoSaving),
             create_entity_ids.in_set(SavingSystemSet::CreateEntityIds),
-            done_saving.in_set(SavingSystemSet::DoneSaving),
+            (done_saving, poll_save_file_writes).chain().in_set(SavingSystemSet::DoneSaving),
         ),
-    );
+    )
+    .add_systems(Last, finish_writing_on_exit)
+    .init_resource::<WritingSaveFiles>();
 
     app.configure_sets(
         SAVING_SCHEDULE,
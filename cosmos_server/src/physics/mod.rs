@@ -16,6 +16,7 @@ use cosmos_core::{
 };
 
 mod collider_disabling;
+mod parallel_stepping;
 
 const WORLD_SWITCH_DISTANCE: f32 = SECTOR_DIMENSIONS / 2.0;
 
@@ -67,4 +68,5 @@ pub fn assign_player_world(
 
 pub(super) fn register(app: &mut App) {
     collider_disabling::register(app);
+    parallel_stepping::register(app);
 }
@@ -0,0 +1,93 @@
+//! Steps every physics world at the same time, rather than one after another.
+//!
+//! Every anchor (player or distant cluster) has its own physics world, and nothing in one world can affect another.
+//! This means each world can be stepped on its own thread, so one busy sector doesn't slow down physics for everyone.
+//!
+//! Worlds are handed to the [`ComputeTaskPool`] from most to least bodies, so the slowest worlds start first and the
+//! smaller ones fill in the gaps around them.
+
+use bevy::{ecs::system::StaticSystemParam, prelude::*, tasks::ComputeTaskPool, transform::TransformSystem};
+use bevy_rapier3d::{
+    pipeline::BevyPhysicsHooksAdapter,
+    plugin::{
+        context::{
+            RapierContextColliders, RapierContextJoints, RapierContextSimulation, RapierQueryPipeline, RapierRigidBodySet,
+            SimulationToRenderTime,
+        },
+        systems::send_bevy_events,
+        PhysicsSet, RapierConfiguration, RapierPhysicsPlugin, TimestepMode,
+    },
+};
+use cosmos_core::physics::collision_handling::CosmosPhysicsFilter;
+
+fn step_worlds_in_parallel(
+    mut q_worlds: Query<(
+        &mut RapierContextSimulation,
+        &mut RapierContextColliders,
+        &mut RapierContextJoints,
+        &mut RapierRigidBodySet,
+        &mut RapierQueryPipeline,
+        &RapierConfiguration,
+        &mut SimulationToRenderTime,
+    )>,
+    timestep_mode: Res<TimestepMode>,
+    hooks: StaticSystemParam<CosmosPhysicsFilter>,
+    time: Res<Time>,
+) {
+    let hooks = BevyPhysicsHooksAdapter::new(hooks.into_inner());
+    let timestep_mode = *timestep_mode;
+    let time = &*time;
+    let hooks = &hooks;
+
+    let mut worlds = q_worlds.iter_mut().collect::<Vec<_>>();
+    worlds.sort_by_key(|(_, _, _, bodies, _, _, _)| std::cmp::Reverse(bodies.bodies.len()));
+
+    ComputeTaskPool::get().scope(|scope| {
+        for (mut simulation, mut colliders, mut joints, mut bodies, mut query_pipeline, config, mut sim_to_render_time) in worlds {
+            scope.spawn(async move {
+                if config.physics_pipeline_active {
+                    // Collision events are stored on each world & sent once every world is done
+                    simulation.step_simulation(
+                        &mut colliders,
+                        &mut joints,
+                        &mut bodies,
+                        config.gravity,
+                        timestep_mode,
+                        true,
+                        hooks,
+                        time,
+                        &mut sim_to_render_time,
+                        None,
+                    );
+                } else {
+                    bodies.propagate_modified_body_positions_to_colliders(&mut colliders);
+                }
+
+                if config.query_pipeline_active {
+                    query_pipeline.update_query_pipeline(&colliders);
+                }
+            });
+        }
+    });
+}
+
+pub(super) fn register(app: &mut App) {
+    // The physics plugin is added without its default systems (see `main.rs`), so everything but the stepping
+    // is added back here as rapier would normally set it up.
+    app.configure_sets(
+        PostUpdate,
+        (PhysicsSet::SyncBackend, PhysicsSet::StepSimulation, PhysicsSet::Writeback)
+            .chain()
+            .before(TransformSystem::TransformPropagate),
+    )
+    .add_systems(
+        PostUpdate,
+        (
+            RapierPhysicsPlugin::<CosmosPhysicsFilter>::get_systems(PhysicsSet::SyncBackend).in_set(PhysicsSet::SyncBackend),
+            (step_worlds_in_parallel, send_bevy_events)
+                .chain()
+                .in_set(PhysicsSet::StepSimulation),
+            RapierPhysicsPlugin::<CosmosPhysicsFilter>::get_systems(PhysicsSet::Writeback).in_set(PhysicsSet::Writeback),
+        ),
+    );
+}
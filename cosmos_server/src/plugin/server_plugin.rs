@@ -5,7 +5,8 @@ use bevy::{log::info, prelude::Plugin};
 use crate::{
     ai, blocks, chat, commands, crafting, debug, economy, entities, fluid,
     init::{self, init_server},
-    inventory, items, logic, netty, persistence, physics, projectiles, shop, structure, universe, utility_runs,
+    inventory, items, logic, loot, netty, party, persistence, physics, projectiles, rcon, shop, singleplayer, structure, universe,
+    utility_runs,
 };
 
 /// The server's plugin
@@ -14,12 +15,14 @@ use crate::{
 pub struct ServerPlugin {
     /// The port this server will be run on
     pub port: u16,
+    /// If true, the server will only accept connections from this machine
+    pub local_only: bool,
 }
 
 impl Plugin for ServerPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         info!("Setting up server");
-        init_server::init(app, self.port);
+        init_server::init(app, self.port, self.local_only);
         commands::register(app);
         init::register(app);
         netty::register(app);
@@ -37,11 +40,15 @@ impl Plugin for ServerPlugin {
         utility_runs::register(app);
         fluid::register(app);
         logic::register(app);
+        loot::register(app);
         debug::register(app);
         chat::register(app);
         crafting::register(app);
         entities::register(app);
         economy::register(app);
+        party::register(app);
+        singleplayer::register(app);
+        rcon::register(app);
 
         info!("Done setting up server!");
     }
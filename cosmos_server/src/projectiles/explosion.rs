@@ -41,6 +41,7 @@ use cosmos_core::{
 use crate::{
     netty::sync::sync_bodies::DontNotifyClientOfDespawn,
     structure::{block_health::BlockHealthSet, shared::MeltingDownSet, systems::shield_system::ShieldSet},
+    universe::{safe_zone::SafeZones, sector_rules::CombatRules},
 };
 
 /// 1 unit of explosion power = this amount of health. Bigger this number is, the more damage explosives will do.
@@ -85,6 +86,8 @@ fn respond_to_explosion(
     mut ev_writer_explosion_hit: EventWriter<ExplosionHitEvent>,
 
     q_shield: Query<&Shield>,
+    safe_zones: SafeZones,
+    combat_rules: CombatRules,
 ) {
     for (ent, &explosion_loc, world_within, physics_world, &explosion, causer) in q_explosions.iter() {
         commands.entity(ent).insert((NeedsDespawned, DontNotifyClientOfDespawn));
@@ -137,6 +140,11 @@ fn respond_to_explosion(
 
                 continue;
             };
+
+            if safe_zones.in_safe_zone(structure_loc) || !combat_rules.can_damage(causer.map(|x| x.0), hit, structure_loc) {
+                continue;
+            }
+
             let explosion_relative_position =
                 structure_g_trans.affine().inverse().matrix3 * (explosion_loc - *structure_loc).absolute_coords_f32();
 
@@ -183,10 +191,14 @@ fn respond_to_explosion(
                     )
                     .is_none()
                 {
+                    let damage = structure
+                        .block_at(block, &blocks_registry)
+                        .damage_after_armor(explosion_power * HEALTH_PER_EXPLOSION_POWER);
+
                     structure.block_take_damage(
                         block,
                         &blocks_registry,
-                        explosion_power * HEALTH_PER_EXPLOSION_POWER,
+                        damage,
                         Some((&mut evw_block_take_damage, &mut evw_block_destroyed)),
                         causer.map(|x| x.0),
                     );
@@ -217,7 +229,9 @@ fn calculate_block_explosion_power(
         .raycast_iter(explosion_relative_position, distance.normalize_or_zero(), distance.length(), false)
         .filter(|&intercepting_block| intercepting_block != this_block)
     {
-        remaining_explosion_power -= structure.get_block_health(intercepting_block, blocks_registry) / HEALTH_PER_EXPLOSION_POWER;
+        // Armored blocks soak up more of the blast than their health alone would
+        let armor = structure.block_at(intercepting_block, blocks_registry).armor();
+        remaining_explosion_power -= (structure.get_block_health(intercepting_block, blocks_registry) + armor) / HEALTH_PER_EXPLOSION_POWER;
 
         let block_pos = structure.block_relative_position(intercepting_block);
         // exponential decay is intended
@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use cosmos_core::{
     block::Block,
     netty::system_sets::NetworkingSystemsSet,
+    physics::location::Location,
     projectiles::{
         causer::Causer,
         laser::{Laser, LaserCollideEvent, LaserSystemSet},
@@ -10,6 +11,7 @@ use cosmos_core::{
     state::GameState,
     structure::{
         block_health::events::{BlockDestroyedEvent, BlockTakeDamageEvent},
+        systems::subsystem_targeting::TargetedSubsystem,
         Structure,
     },
 };
@@ -19,55 +21,123 @@ use crate::{
         saving::{NeedsSaved, SavingSystemSet, SAVING_SCHEDULE},
         SerializedData,
     },
-    structure::{block_health::BlockHealthSet, systems::shield_system::ShieldSet},
+    structure::{
+        block_health::BlockHealthSet,
+        systems::{shield_system::ShieldSet, subsystem_targeting::SubsystemBlocks},
+    },
+    universe::{safe_zone::SafeZones, sector_rules::CombatRules},
 };
 
+/// How many blocks deep a laser can punch through a structure before it runs out of energy, no matter how strong it is
+const MAX_LASER_PENETRATION: f32 = 8.0;
+
 /// Called when the laser hits a structure at a given position
+///
+/// The laser damages the block it hit, and any damage left over after destroying that block carries on into the blocks
+/// behind it (up to [`MAX_LASER_PENETRATION`] blocks deep). Every block's armor absorbs some of the damage passing through it,
+/// so weak blocks barely slow a laser down while heavy armor stops it entirely.
+///
+/// If the laser's shooter is targeting a subsystem, the damage goes to the closest block of that subsystem near
+/// where the laser hit instead, and does not penetrate any further.
 fn on_laser_hit_structure(
     structure: &mut Structure,
     local_position_hit: Vec3,
+    local_direction: Vec3,
     blocks: &Registry<Block>,
     block_take_damage_event_writer: &mut EventWriter<BlockTakeDamageEvent>,
     block_destroy_event_writer: &mut EventWriter<BlockDestroyedEvent>,
     strength: f32,
     causer: Option<&Causer>,
+    targeting: Option<(TargetedSubsystem, &SubsystemBlocks)>,
 ) {
-    if let Ok(coords) = structure.relative_coords_to_local_coords_checked(local_position_hit.x, local_position_hit.y, local_position_hit.z)
+    let Ok(coords) = structure.relative_coords_to_local_coords_checked(local_position_hit.x, local_position_hit.y, local_position_hit.z)
+    else {
+        warn!("Bad laser hit spot that isn't actually on structure ;(");
+        return;
+    };
+
+    if let Some(coords) =
+        targeting.and_then(|(subsystem, subsystem_blocks)| subsystem_blocks.closest_subsystem_block(structure, coords, subsystem))
     {
+        let damage = structure.block_at(coords, blocks).damage_after_armor(strength);
+
         structure.block_take_damage(
             coords,
             blocks,
-            strength,
+            damage,
             Some((block_take_damage_event_writer, block_destroy_event_writer)),
             causer.map(|x| x.0),
         );
-    } else {
-        warn!("Bad laser hit spot that isn't actually on structure ;(");
+
+        return;
+    }
+
+    let mut path = vec![coords];
+    for coords in structure.raycast_iter(local_position_hit, local_direction, MAX_LASER_PENETRATION, false) {
+        if !path.contains(&coords) {
+            path.push(coords);
+        }
+    }
+
+    let mut damage = strength;
+
+    for coords in path {
+        let health = structure.get_block_health(coords, blocks);
+        let dealt = structure.block_at(coords, blocks).damage_after_armor(damage);
+
+        structure.block_take_damage(
+            coords,
+            blocks,
+            dealt,
+            Some((block_take_damage_event_writer, block_destroy_event_writer)),
+            causer.map(|x| x.0),
+        );
+
+        // Only the damage that was left over after destroying this block carries on to the next one
+        damage = dealt - health;
+        if damage <= 0.0 {
+            break;
+        }
     }
 }
 
 fn respond_laser_hit_event(
     mut reader: EventReader<LaserCollideEvent>,
     parent_query: Query<&Parent>,
-    mut structure_query: Query<&mut Structure>,
+    mut structure_query: Query<(&mut Structure, &Location)>,
     blocks: Res<Registry<Block>>,
     mut block_take_damage_event_writer: EventWriter<BlockTakeDamageEvent>,
     mut block_destroy_event_writer: EventWriter<BlockDestroyedEvent>,
+    safe_zones: SafeZones,
+    combat_rules: CombatRules,
+    q_targeted_subsystem: Query<&TargetedSubsystem>,
+    subsystem_blocks: Res<SubsystemBlocks>,
 ) {
     for ev in reader.read() {
         let entity_hit = ev.entity_hit();
         if let Ok(parent) = parent_query.get(entity_hit) {
-            if let Ok(mut structure) = structure_query.get_mut(parent.get()) {
+            if let Ok((mut structure, location)) = structure_query.get_mut(parent.get()) {
+                if safe_zones.in_safe_zone(location) || !combat_rules.can_damage(ev.causer().map(|x| x.0), parent.get(), location) {
+                    continue;
+                }
+
                 let local_position_hit = ev.local_position_hit();
 
+                let targeting = ev
+                    .causer()
+                    .and_then(|causer| q_targeted_subsystem.get(causer.0).ok())
+                    .map(|subsystem| (*subsystem, subsystem_blocks.as_ref()));
+
                 on_laser_hit_structure(
                     &mut structure,
                     local_position_hit,
+                    ev.local_direction(),
                     &blocks,
                     &mut block_take_damage_event_writer,
                     &mut block_destroy_event_writer,
                     ev.laser_strength(),
                     ev.causer().as_ref(),
+                    targeting,
                 );
             }
         }
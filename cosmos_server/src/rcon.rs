@@ -0,0 +1,401 @@
+//! An optional remote admin interface, so servers can be managed without access to the server's console.
+//!
+//! This is enabled by passing `--rcon-port [port]` and setting the `COSMOS_RCON_TOKEN` environment variable. Only local
+//! connections are accepted unless a different address is given with `--rcon-address [address]`.
+//!
+//! The protocol is plain text over TCP, one command per line:
+//! - The first line sent must be `auth [token]`. If the token is wrong, or it isn't sent within a few seconds, the
+//!   connection is closed.
+//! - After that, every line is a command (see `help`).
+//! - Every response is ended by a line containing only `.`
+
+use std::{
+    env,
+    io::{ErrorKind, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    time::{Duration, Instant},
+};
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_renet2::renet2::{transport::NetcodeServerTransport, RenetServer};
+use cosmos_core::{
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    netty::{sync::events::server_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    physics::location::Location,
+    state::GameState,
+};
+
+use crate::{
+    entities::player::bans::BannedPlayers, persistence::autosave::SaveEverything, settings::ServerSettings,
+    universe::sector_load::SectorLoad,
+};
+
+/// The environment variable the rcon token is read from
+const RCON_TOKEN_ENV: &str = "COSMOS_RCON_TOKEN";
+/// No more than this many admins can be connected at once
+const MAX_CONNECTIONS: usize = 8;
+/// No more than this many connections can be waiting to authenticate at once
+const MAX_PENDING_CONNECTIONS: usize = 8;
+/// Connections that haven't authenticated within this long are closed
+const AUTH_TIMEOUT: Duration = Duration::from_secs(5);
+/// Connections sending lines longer than this are closed
+const MAX_LINE_LENGTH: usize = 4096;
+/// Connections that stop reading their responses are closed once this much is waiting to be sent to them
+const MAX_PENDING_OUTPUT: usize = 1024 * 1024;
+
+const HELP: &str = "help - Lists every command
+players - Lists every online player, their client id, and sector
+kick [player_name] {reason} - Disconnects a player
+ban [player_name] - Bans a player and disconnects them. If they're online, their address is banned too
+unban [player_name] - Unbans a player
+bans - Lists every banned player
+save - Saves the world
+broadcast [message] - Sends a message to every player
+sectors {amount} - Lists the busiest sectors. Shows 10 unless an amount is given.";
+
+struct RconConnection {
+    stream: TcpStream,
+    address: SocketAddr,
+    opened_at: Instant,
+    buffer: Vec<u8>,
+    /// Responses that haven't been written to the stream yet
+    output: Vec<u8>,
+    authenticated: bool,
+    closed: bool,
+}
+
+impl RconConnection {
+    /// Queues this response to be sent. It's written to the stream by [`Self::flush`].
+    fn send(&mut self, response: &str) {
+        self.output.extend_from_slice(response.trim_end().as_bytes());
+        self.output.extend_from_slice(b"\n.\n");
+
+        if self.output.len() > MAX_PENDING_OUTPUT {
+            self.closed = true;
+        }
+    }
+
+    /// Writes as much of the queued responses as the stream will take without blocking
+    fn flush(&mut self) {
+        while !self.output.is_empty() {
+            match self.stream.write(&self.output) {
+                Ok(0) => {
+                    self.closed = true;
+                    break;
+                }
+                Ok(n) => {
+                    self.output.drain(..n);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => {
+                    self.closed = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Reads every complete line this connection has sent.
+    ///
+    /// The connection is closed as soon as it sends a line longer than [`MAX_LINE_LENGTH`].
+    fn read_lines(&mut self) -> Vec<String> {
+        let mut buf = [0; 1024];
+        let mut lines = vec![];
+
+        while !self.closed {
+            match self.stream.read(&mut buf) {
+                Ok(0) => self.closed = true,
+                Ok(n) => {
+                    self.buffer.extend_from_slice(&buf[..n]);
+
+                    while let Some(newline) = self.buffer.iter().position(|&c| c == b'\n') {
+                        let line = self.buffer.drain(..=newline).collect::<Vec<u8>>();
+                        lines.push(String::from_utf8_lossy(&line).trim().to_owned());
+                    }
+
+                    if self.buffer.len() > MAX_LINE_LENGTH {
+                        self.closed = true;
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => self.closed = true,
+            }
+        }
+
+        lines
+    }
+}
+
+#[derive(Resource)]
+/// Only exists if the rcon interface is enabled
+struct RconServer {
+    listener: TcpListener,
+    token: String,
+    connections: HashMap<u64, RconConnection>,
+    next_id: u64,
+}
+
+/// Compares every byte so the time this takes doesn't reveal how much of the token was right
+fn tokens_match(given: &str, token: &str) -> bool {
+    given.len() == token.len() && given.bytes().zip(token.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+impl RconServer {
+    fn n_connections(&self, authenticated: bool) -> usize {
+        self.connections.values().filter(|c| c.authenticated == authenticated).count()
+    }
+
+    fn accept_connections(&mut self) {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, address)) => {
+                    if self.n_connections(false) >= MAX_PENDING_CONNECTIONS || stream.set_nonblocking(true).is_err() {
+                        continue;
+                    }
+
+                    info!("Rcon connection opened from {address}");
+
+                    self.connections.insert(
+                        self.next_id,
+                        RconConnection {
+                            stream,
+                            address,
+                            opened_at: Instant::now(),
+                            buffer: vec![],
+                            output: vec![],
+                            authenticated: false,
+                            closed: false,
+                        },
+                    );
+                    self.next_id += 1;
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    warn!("Error accepting rcon connection: {e:?}");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Returns every command sent by authenticated connections, along with the connection that sent it
+    fn read_commands(&mut self) -> Vec<(u64, String)> {
+        let mut commands = vec![];
+        let mut n_authenticated = self.n_connections(true);
+
+        for (&id, connection) in self.connections.iter_mut() {
+            if !connection.authenticated && connection.opened_at.elapsed() > AUTH_TIMEOUT {
+                warn!("Rcon connection from {} didn't authenticate in time.", connection.address);
+                connection.send("error: authentication timed out");
+                connection.closed = true;
+                continue;
+            }
+
+            for line in connection.read_lines() {
+                if line.is_empty() || connection.closed {
+                    continue;
+                }
+
+                if connection.authenticated {
+                    commands.push((id, line));
+                    continue;
+                }
+
+                let given_token = line.strip_prefix("auth ").unwrap_or_default();
+
+                if !tokens_match(given_token, &self.token) {
+                    warn!("Rcon connection from {} failed to authenticate.", connection.address);
+                    connection.send("error: invalid token");
+                    connection.closed = true;
+                } else if n_authenticated >= MAX_CONNECTIONS {
+                    connection.send("error: too many rcon connections");
+                    connection.closed = true;
+                } else {
+                    n_authenticated += 1;
+                    connection.authenticated = true;
+                    connection.send("ok");
+                }
+            }
+        }
+
+        commands
+    }
+
+    fn respond(&mut self, connection_id: u64, response: &str) {
+        if let Some(connection) = self.connections.get_mut(&connection_id) {
+            connection.send(response);
+        }
+    }
+
+    /// Sends every queued response, then removes every closed connection
+    fn flush_connections(&mut self) {
+        for connection in self.connections.values_mut() {
+            connection.flush();
+        }
+
+        self.connections.retain(|_, connection| {
+            if connection.closed {
+                info!("Rcon connection from {} closed", connection.address);
+            }
+
+            !connection.closed
+        });
+    }
+}
+
+fn start_rcon_server(mut commands: Commands, settings: Res<ServerSettings>) {
+    let Some(port) = settings.rcon_port else {
+        return;
+    };
+
+    let token = env::var(RCON_TOKEN_ENV).unwrap_or_default();
+    if token.trim().is_empty() {
+        error!("An rcon port was given, but the {RCON_TOKEN_ENV} environment variable is not set - rcon will not be enabled.");
+        return;
+    }
+
+    let address = settings.rcon_address;
+
+    let listener = match TcpListener::bind((address, port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Unable to start rcon on {address}:{port}.\n{e:?}");
+            return;
+        }
+    };
+
+    if let Err(e) = listener.set_nonblocking(true) {
+        error!("Unable to start rcon on port {port}.\n{e:?}");
+        return;
+    }
+
+    info!("Rcon listening on {address}:{port}");
+
+    commands.insert_resource(RconServer {
+        listener,
+        token: token.trim().to_owned(),
+        connections: Default::default(),
+        next_id: 0,
+    });
+}
+
+fn handle_rcon_commands(
+    mut rcon: ResMut<RconServer>,
+    q_players: Query<(&Player, &Location)>,
+    mut server: ResMut<RenetServer>,
+    transport: Res<NetcodeServerTransport>,
+    mut banned_players: ResMut<BannedPlayers>,
+    mut evw_save_everything: EventWriter<SaveEverything>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    sector_load: Res<SectorLoad>,
+) {
+    rcon.accept_connections();
+
+    for (connection_id, line) in rcon.read_commands() {
+        let mut split = line.split(' ').filter(|x| !x.is_empty());
+        let name = split.next().unwrap_or_default().to_lowercase();
+        let args = split.collect::<Vec<&str>>();
+
+        let find_player = |name: &str| q_players.iter().find(|(player, _)| player.name() == name).map(|(player, _)| player);
+
+        let response = match (name.as_str(), args.as_slice()) {
+            ("help", _) => HELP.to_owned(),
+            ("players", []) => {
+                let mut response = format!("{} player(s) online", q_players.iter().count());
+                for (player, location) in q_players.iter() {
+                    response.push_str(&format!("\n{}\t{}\t{}", player.name(), player.id(), location.sector()));
+                }
+                response
+            }
+            ("kick", [player_name, reason @ ..]) => match find_player(player_name) {
+                Some(player) => {
+                    server.disconnect(player.id());
+                    if reason.is_empty() {
+                        info!("Kicked {player_name} via rcon.");
+                    } else {
+                        info!("Kicked {player_name} via rcon: {}", reason.join(" "));
+                    }
+                    format!("Kicked {player_name}")
+                }
+                None => format!("error: no player named {player_name} is online"),
+            },
+            ("ban", [player_name]) => {
+                let player = find_player(player_name);
+                let address = player
+                    .and_then(|player| transport.client_addr(player.id()))
+                    .map(|(_, address)| address.ip());
+
+                if let Some(player) = player {
+                    server.disconnect(player.id());
+                }
+
+                if banned_players.ban(*player_name, address) {
+                    info!("Banned {player_name} via rcon.");
+                    format!("Banned {player_name}")
+                } else {
+                    format!("{player_name} is already banned")
+                }
+            }
+            ("unban", [player_name]) => {
+                if banned_players.unban(player_name) {
+                    info!("Unbanned {player_name} via rcon.");
+                    format!("Unbanned {player_name}")
+                } else {
+                    format!("{player_name} is not banned")
+                }
+            }
+            ("bans", []) => {
+                let mut response = "Banned players:".to_owned();
+                for name in banned_players.iter() {
+                    response.push_str(&format!("\n{name}"));
+                }
+                response
+            }
+            ("save", []) => {
+                evw_save_everything.send_default();
+                "Saving the world".to_owned()
+            }
+            ("broadcast", [_, ..]) => {
+                let message = args.join(" ");
+                info!("Broadcasting via rcon: {message}");
+                nevw_chat.broadcast(ServerSendChatMessageEvent { sender: None, message });
+                "Sent".to_owned()
+            }
+            ("sectors", [] | [_]) => match args.first().map(|x| x.parse::<usize>()).unwrap_or(Ok(10)) {
+                Ok(amount) => {
+                    let mut sectors = sector_load.iter().collect::<Vec<_>>();
+                    sectors.sort_by(|(_, a), (_, b)| b.entities.cmp(&a.entities));
+
+                    let mut response = format!("Average tick time: {:.2}ms", sector_load.average_tick_ms());
+                    response.push_str("\nSector\tEntities\tTick (ms)\tThrottled");
+                    for (sector, info) in sectors.into_iter().take(amount) {
+                        response.push_str(&format!(
+                            "\n{sector}\t{}\t{:.2}\t{}",
+                            info.entities, info.estimated_tick_ms, info.throttled
+                        ));
+                    }
+                    response
+                }
+                Err(_) => "error: the amount must be a positive whole number".to_owned(),
+            },
+            _ => format!("error: invalid command or arguments '{line}' - use 'help' to see every command"),
+        };
+
+        rcon.respond(connection_id, &response);
+    }
+
+    rcon.flush_connections();
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Playing), start_rcon_server).add_systems(
+        Update,
+        handle_rcon_commands
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(resource_exists::<RconServer>)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
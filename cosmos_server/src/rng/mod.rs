@@ -1,10 +1,113 @@
 //! Contains useful features for randomly generated numbers
+//!
+//! # Sub-seeds
+//!
+//! Different parts of world generation should not share the same random numbers, or features would line up
+//! with each other in obvious ways. Instead, each one derives its own [`SubSeed`] from the [`ServerSeed`]:
+//!
+//! ```text
+//! universe seed
+//! ├── system seed  = derive(universe seed, "cosmos:system", system coordinate)
+//! └── planet seed  = derive(universe seed, "cosmos:planet", planet's sector)
+//!     └── feature seed = derive(planet seed, feature's unlocalized name)
+//! ```
+//!
+//! `derive` hashes the label's UTF-8 bytes with 64-bit FNV-1a, and mixes each coordinate in (as little-endian
+//! bytes) the same way. The result is then combined with the parent seed and passed through the SplitMix64
+//! finalizer. This only uses integer math with explicit byte orders, so the same universe seed generates the
+//! same world on every run and every platform.
+//!
+//! Changing any of this will change the worlds that existing seeds generate, so don't.
+//!
+//! [`get_rng_for_sector`] and the GPU terrain noise predate sub-seeds, and still use the universe seed directly
+//! so that worlds created before sub-seeds existed keep generating the same way.
 
-use cosmos_core::physics::location::Sector;
+use cosmos_core::physics::location::{Sector, SystemCoordinate};
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 
-use crate::init::init_world::ServerSeed;
+use crate::init::init_world::{chaos_hash, ServerSeed};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+
+    hash
+}
+
+/// The SplitMix64 finalizer - spreads every bit of the input over the entire output
+fn split_mix_64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Derives a new seed from this parent seed, label, and coordinates. See the module docs for the exact scheme.
+pub fn derive_seed(parent: u64, label: &str, coordinates: &[i64]) -> u64 {
+    let mut hash = fnv1a(FNV_OFFSET_BASIS, label.as_bytes());
+    for coordinate in coordinates {
+        hash = fnv1a(hash, &coordinate.to_le_bytes());
+    }
+
+    split_mix_64(parent ^ split_mix_64(hash))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A seed derived from the [`ServerSeed`] for one specific part of world generation.
+///
+/// See the module docs for how these are created.
+pub struct SubSeed(u64);
+
+impl SubSeed {
+    /// Derives a seed for something within whatever this seed is for (for instance, a feature on a planet).
+    pub fn derive(&self, label: &str) -> Self {
+        Self(derive_seed(self.0, label, &[]))
+    }
+
+    /// Gets the u64 representation of this seed
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+
+    /// Creates a random number generator from this seed
+    pub fn rng(&self) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(self.0)
+    }
+
+    /// Computes a "random" number at the given x, y, z coordinates.
+    ///
+    /// This randomness is based off a hash of the coordinates with this seed.
+    pub fn chaos_hash(&self, x: f64, y: f64, z: f64) -> i64 {
+        chaos_hash(self.0, x, y, z)
+    }
+}
+
+impl ServerSeed {
+    /// The seed for everything generated as part of this star system
+    pub fn system_seed(&self, system: SystemCoordinate) -> SubSeed {
+        SubSeed(derive_seed(self.as_u64(), "cosmos:system", &[system.x(), system.y(), system.z()]))
+    }
+
+    /// The seed for the planet in this sector. Anything generated on a planet should derive its own seed from this one.
+    pub fn planet_seed(&self, planet_sector: Sector) -> SubSeed {
+        SubSeed(derive_seed(
+            self.as_u64(),
+            "cosmos:planet",
+            &[planet_sector.x(), planet_sector.y(), planet_sector.z()],
+        ))
+    }
+
+    /// Shorthand for `self.planet_seed(planet_sector).derive(feature)`
+    pub fn planet_feature_seed(&self, planet_sector: Sector, feature: &str) -> SubSeed {
+        self.planet_seed(planet_sector).derive(feature)
+    }
+}
 
 /// Generates a seed given the sector & the server's base seed.
 ///
@@ -34,3 +137,24 @@ pub fn get_seed_for_sector_u32(server_seed: &ServerSeed, sector: &Sector) -> u32
 pub fn get_rng_for_sector(server_seed: &ServerSeed, sector: &Sector) -> ChaCha8Rng {
     ChaCha8Rng::seed_from_u64(get_seed_for_sector_u64(server_seed, sector))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_seeds_never_change() {
+        // If this fails, every existing seed will now generate a different world.
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(derive_seed(12345, "cosmos:planet", &[1, -2, 3]), 0x20fa_4beb_e083_c104);
+
+        assert_ne!(
+            derive_seed(1, "cosmos:planet", &[0, 0, 1]),
+            derive_seed(1, "cosmos:planet", &[0, 1, 0])
+        );
+        assert_ne!(
+            derive_seed(1, "cosmos:planet", &[0, 0, 0]),
+            derive_seed(1, "cosmos:system", &[0, 0, 0])
+        );
+    }
+}
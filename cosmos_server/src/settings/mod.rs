@@ -1,5 +1,7 @@
 //! Settings for the server
 
+use std::net::IpAddr;
+
 use bevy::ecs::system::Resource;
 use clap::{arg, Parser};
 
@@ -26,6 +28,41 @@ pub struct Args {
     /// If all players should be in creative mode
     #[arg(long, default_value_t = false)]
     creative: bool,
+
+    /// If this server was launched by a client to play singleplayer.
+    ///
+    /// Singleplayer servers only accept local connections, can be paused by the player, and shut
+    /// themselves down once the player leaves.
+    #[arg(long, default_value_t = false)]
+    singleplayer: bool,
+
+    /// Port the remote admin (RCON) interface should listen on. The interface is disabled if this isn't set.
+    ///
+    /// The token clients must authenticate with is read from the `COSMOS_RCON_TOKEN` environment variable.
+    #[arg(long)]
+    rcon_port: Option<u16>,
+
+    /// The address the remote admin (RCON) interface should listen on.
+    ///
+    /// Only local connections are accepted by default. Use `0.0.0.0` to accept connections from anywhere.
+    #[arg(long, default_value = "127.0.0.1")]
+    rcon_address: IpAddr,
+
+    /// The seed used to generate a new world. Random if not set.
+    ///
+    /// This is ignored if the world has already been generated, since its seed is saved with it.
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// If reactors should burn fuel items from their structure's storage to generate power.
+    ///
+    /// Without this, reactors generate power for free.
+    #[arg(long, default_value_t = false)]
+    reactor_fuel: bool,
+
+    /// How many structures each player can keep loaded with chunk anchors at once. Admins have no limit.
+    #[arg(long, default_value_t = 2)]
+    max_anchors_per_player: u32,
 }
 
 #[derive(Resource)]
@@ -41,6 +78,18 @@ pub struct ServerSettings {
     pub spawn_planets: bool,
     /// If all players should be in creative mode
     pub creative: bool,
+    /// If this server was launched by a client to play singleplayer
+    pub singleplayer: bool,
+    /// The port the remote admin interface listens on, if it is enabled
+    pub rcon_port: Option<u16>,
+    /// The address the remote admin interface listens on
+    pub rcon_address: IpAddr,
+    /// The seed a newly created world should use, if one was given
+    pub seed: Option<u64>,
+    /// If reactors need fuel to generate power
+    pub reactor_fuel: bool,
+    /// How many structures each player can anchor at once
+    pub max_anchors_per_player: u32,
 }
 
 /// Reads the server settings passed in from the command line
@@ -53,5 +102,11 @@ pub(super) fn read_server_settings() -> ServerSettings {
         spawn_planets: !args.no_planets,
         spawn_asteroids: !args.no_asteroids,
         creative: args.creative,
+        singleplayer: args.singleplayer,
+        rcon_port: args.rcon_port,
+        rcon_address: args.rcon_address,
+        seed: args.seed,
+        reactor_fuel: args.reactor_fuel,
+        max_anchors_per_player: args.max_anchors_per_player,
     }
 }
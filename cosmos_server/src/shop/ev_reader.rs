@@ -9,13 +9,18 @@ use cosmos_core::{
         Inventory,
     },
     item::Item,
-    netty::{cosmos_encoder, server::ServerLobby, system_sets::NetworkingSystemsSet, NettyChannelClient, NettyChannelServer},
+    netty::{
+        network_stats::{NettyMessage, NettyServerMessages},
+        server::ServerLobby,
+        system_sets::NetworkingSystemsSet,
+        NettyChannelClient, NettyChannelServer,
+    },
     registry::{identifiable::Identifiable, Registry},
     shop::{
         netty::{ClientShopMessages, ServerShopMessages, ShopPurchaseError, ShopSellError},
         Shop,
     },
-    structure::{coordinates::BlockCoordinate, Structure},
+    structure::{coordinates::BlockCoordinate, structure_block::StructureBlock, Structure},
 };
 
 use super::prices::DefaultShopEntries;
@@ -29,6 +34,26 @@ fn generate_fake_shop(default: &DefaultShopEntries) -> Shop {
     }
 }
 
+/// Opens the shop at this block for this player
+pub(crate) fn send_open_shop(
+    server: &mut RenetServer,
+    client_id: ClientId,
+    s_block: StructureBlock,
+    default_shop_entries: &DefaultShopEntries,
+) {
+    let fake_shop_data = generate_fake_shop(default_shop_entries);
+
+    server.send_netty_message(
+        client_id,
+        NettyChannelServer::Shop,
+        NettyMessage::new(&ServerShopMessages::OpenShop {
+            shop_block: s_block.coords(),
+            structure_entity: s_block.structure(),
+            shop_data: fake_shop_data,
+        }),
+    );
+}
+
 fn on_interact_with_shop(
     mut server: ResMut<RenetServer>,
     q_structure: Query<&Structure>,
@@ -53,17 +78,7 @@ fn on_interact_with_shop(
         let block = s_block.block(structure, &blocks);
 
         if block.unlocalized_name() == "cosmos:shop" {
-            let fake_shop_data = generate_fake_shop(&default_shop_entries);
-
-            server.send_message(
-                player.id(),
-                NettyChannelServer::Shop,
-                cosmos_encoder::serialize(&ServerShopMessages::OpenShop {
-                    shop_block: s_block.coords(),
-                    structure_entity: s_block.structure(),
-                    shop_data: fake_shop_data,
-                }),
-            );
+            send_open_shop(&mut server, player.id(), s_block, &default_shop_entries);
         }
     }
 }
@@ -137,10 +152,10 @@ fn listen_sell_events(
         };
 
         if !inventory.can_take_item(item, quantity as usize) {
-            server.send_message(
+            server.send_netty_message(
                 client_id,
                 NettyChannelServer::Shop,
-                cosmos_encoder::serialize(&ServerShopMessages::SellResult {
+                NettyMessage::new(&ServerShopMessages::SellResult {
                     shop_block,
                     structure_entity,
                     details: Err(ShopSellError::NotEnoughItems),
@@ -153,10 +168,10 @@ fn listen_sell_events(
             continue;
         };
 
-        server.send_message(
+        server.send_netty_message(
             client_id,
             NettyChannelServer::Shop,
-            cosmos_encoder::serialize(&ServerShopMessages::SellResult {
+            NettyMessage::new(&ServerShopMessages::SellResult {
                 shop_block,
                 structure_entity,
                 details: if let Err(error) = shop.sell(item_id, quantity, &mut credits) {
@@ -207,10 +222,10 @@ fn listen_buy_events(
         };
 
         if !inventory.can_insert(item, quantity as u16) {
-            server.send_message(
+            server.send_netty_message(
                 client_id,
                 NettyChannelServer::Shop,
-                cosmos_encoder::serialize(&ServerShopMessages::PurchaseResult {
+                NettyMessage::new(&ServerShopMessages::PurchaseResult {
                     shop_block,
                     structure_entity,
                     details: Err(ShopPurchaseError::NotEnoughInventorySpace),
@@ -225,10 +240,10 @@ fn listen_buy_events(
 
         match shop.buy(item_id, quantity, &mut credits) {
             Ok(_) => {
-                server.send_message(
+                server.send_netty_message(
                     client_id,
                     NettyChannelServer::Shop,
-                    cosmos_encoder::serialize(&ServerShopMessages::PurchaseResult {
+                    NettyMessage::new(&ServerShopMessages::PurchaseResult {
                         shop_block,
                         structure_entity,
                         details: Ok(shop.clone()),
@@ -238,10 +253,10 @@ fn listen_buy_events(
                 inventory.insert_item(item, quantity as u16, &mut commands, &has_data);
             }
             Err(msg) => {
-                server.send_message(
+                server.send_netty_message(
                     client_id,
                     NettyChannelServer::Shop,
-                    cosmos_encoder::serialize(&ServerShopMessages::PurchaseResult {
+                    NettyMessage::new(&ServerShopMessages::PurchaseResult {
                         shop_block,
                         structure_entity,
                         details: Err(msg),
@@ -258,8 +273,8 @@ fn listen_client_shop_messages(
     mut server: ResMut<RenetServer>,
 ) {
     for client_id in server.clients_id() {
-        while let Some(message) = server.receive_message(client_id, NettyChannelClient::Shop) {
-            let Ok(msg) = cosmos_encoder::deserialize::<ClientShopMessages>(&message) else {
+        while let Some(message) = server.receive_netty_message::<ClientShopMessages>(client_id, NettyChannelClient::Shop) {
+            let Ok(msg) = message else {
                 error!("Bad shop message from {client_id}");
                 continue;
             };
@@ -6,6 +6,8 @@ mod ev_reader;
 mod generate_shop;
 pub mod prices;
 
+pub(crate) use ev_reader::send_open_shop;
+
 pub(super) fn register(app: &mut App) {
     ev_reader::register(app);
     generate_shop::register(app);
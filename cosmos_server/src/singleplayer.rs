@@ -0,0 +1,188 @@
+//! Behavior specific to a server that was launched by a client for singleplayer.
+//!
+//! A singleplayer server:
+//! - Tells the client when it's ready to be connected to (see [`SINGLEPLAYER_READY_MARKER`])
+//! - Can be paused by the player via [`SetSingleplayerPaused`], which pauses [`Time<Virtual>`] (and with it everything
+//!   that runs off of the game's time) as well as physics
+//! - Saves + shuts itself down once the player leaves, or if nobody ever connects
+
+use bevy::{app::AppExit, prelude::*};
+use bevy_rapier3d::plugin::RapierConfiguration;
+use bevy_renet2::renet2::{transport::NetcodeServerTransport, RenetServer};
+use cosmos_core::{
+    entities::player::Player,
+    netty::{
+        singleplayer::{SetSingleplayerPaused, SINGLEPLAYER_READY_MARKER},
+        sync::events::server_event::NettyEventReceived,
+        system_sets::NetworkingSystemsSet,
+    },
+    state::GameState,
+};
+
+use crate::{persistence::autosave::SaveEverything, settings::ServerSettings};
+
+/// How long to wait after the player leaves before shutting down, to give the world time to save.
+const SHUTDOWN_DELAY_SECS: f32 = 5.0;
+/// If no player has connected within this many seconds, the client that launched us is probably gone.
+const NO_PLAYER_TIMEOUT_SECS: f32 = 120.0;
+
+#[derive(Resource, Debug)]
+/// If this resource exists, the player has paused the singleplayer world.
+pub struct SingleplayerPaused;
+
+#[derive(Resource, Debug, Default)]
+struct SingleplayerLifecycle {
+    /// If a player has ever joined this server
+    had_player: bool,
+    /// Seconds the server has gone without a player
+    empty_for: f32,
+    /// If the world has been told to save in preparation for shutting down
+    saved: bool,
+}
+
+fn is_singleplayer(settings: Res<ServerSettings>) -> bool {
+    settings.singleplayer
+}
+
+fn announce_ready() {
+    info!("Singleplayer server is ready for connections.");
+    // This has to go straight to stdout, since the client parses it to know when to connect.
+    println!("{SINGLEPLAYER_READY_MARKER}");
+}
+
+fn set_physics_active(q_rapier_config: &mut Query<&mut RapierConfiguration>, active: bool) {
+    for mut config in q_rapier_config.iter_mut() {
+        config.physics_pipeline_active = active;
+    }
+}
+
+fn set_time_paused(time: &mut Time<Virtual>, paused: bool) {
+    if paused {
+        time.pause();
+    } else {
+        time.unpause();
+    }
+}
+
+fn on_pause_request(
+    mut commands: Commands,
+    mut nevr_set_paused: EventReader<NettyEventReceived<SetSingleplayerPaused>>,
+    paused: Option<Res<SingleplayerPaused>>,
+    mut q_rapier_config: Query<&mut RapierConfiguration>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    let Some(ev) = nevr_set_paused.read().last() else {
+        return;
+    };
+
+    if ev.paused == paused.is_some() {
+        return;
+    }
+
+    if ev.paused {
+        info!("Pausing singleplayer world.");
+        commands.insert_resource(SingleplayerPaused);
+    } else {
+        info!("Unpausing singleplayer world.");
+        commands.remove_resource::<SingleplayerPaused>();
+    }
+
+    set_physics_active(&mut q_rapier_config, !ev.paused);
+    set_time_paused(&mut time, ev.paused);
+}
+
+/// Renet is updated with the game's time, which doesn't move while paused.
+///
+/// Without this, the connection would stop sending keep-alives and time out while the player sits in the pause menu.
+fn keep_connection_alive_while_paused(
+    real_time: Res<Time<Real>>,
+    mut server: ResMut<RenetServer>,
+    mut transport: ResMut<NetcodeServerTransport>,
+) {
+    let delta = real_time.delta();
+
+    server.update(delta);
+    if let Err(e) = transport.update(delta, &mut server) {
+        error!("Error updating the network while paused: {e:?}");
+    }
+}
+
+/// Physics worlds are created as players move around, so make sure any new ones respect the pause.
+fn pause_new_physics_worlds(mut q_rapier_config: Query<&mut RapierConfiguration, Added<RapierConfiguration>>) {
+    for mut config in q_rapier_config.iter_mut() {
+        config.physics_pipeline_active = false;
+    }
+}
+
+fn unpause_when_empty(
+    mut commands: Commands,
+    q_players: Query<(), With<Player>>,
+    mut q_rapier_config: Query<&mut RapierConfiguration>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if !q_players.is_empty() {
+        return;
+    }
+
+    commands.remove_resource::<SingleplayerPaused>();
+    set_physics_active(&mut q_rapier_config, true);
+    set_time_paused(&mut time, false);
+}
+
+fn shutdown_when_abandoned(
+    time: Res<Time>,
+    mut lifecycle: ResMut<SingleplayerLifecycle>,
+    q_players: Query<(), With<Player>>,
+    mut evw_save_everything: EventWriter<SaveEverything>,
+    mut evw_app_exit: EventWriter<AppExit>,
+) {
+    if !q_players.is_empty() {
+        lifecycle.had_player = true;
+        lifecycle.empty_for = 0.0;
+        lifecycle.saved = false;
+        return;
+    }
+
+    lifecycle.empty_for += time.delta_secs();
+
+    if lifecycle.had_player {
+        if !lifecycle.saved {
+            info!("Player left the singleplayer world - saving before shutting down.");
+            lifecycle.saved = true;
+            evw_save_everything.send_default();
+        }
+
+        if lifecycle.empty_for >= SHUTDOWN_DELAY_SECS {
+            info!("Shutting down singleplayer server.");
+            evw_app_exit.send(AppExit::Success);
+        }
+    } else if lifecycle.empty_for >= NO_PLAYER_TIMEOUT_SECS {
+        warn!("No player connected to the singleplayer server within {NO_PLAYER_TIMEOUT_SECS}s - shutting down.");
+        evw_app_exit.send(AppExit::Success);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Playing), announce_ready.run_if(is_singleplayer))
+        .add_systems(
+            PreUpdate,
+            keep_connection_alive_while_paused
+                .run_if(resource_exists::<SingleplayerPaused>)
+                .run_if(resource_exists::<RenetServer>)
+                .run_if(resource_exists::<NetcodeServerTransport>),
+        )
+        .add_systems(
+            Update,
+            (
+                on_pause_request,
+                pause_new_physics_worlds.run_if(resource_exists::<SingleplayerPaused>),
+                unpause_when_empty.run_if(resource_exists::<SingleplayerPaused>),
+                shutdown_when_abandoned,
+            )
+                .chain()
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing))
+                .run_if(is_singleplayer),
+        )
+        .init_resource::<SingleplayerLifecycle>();
+}
@@ -0,0 +1,292 @@
+//! Chunk anchors keep their structure, and everything else in its sector, loaded while no players are around.
+//!
+//! This lets factories and farms keep running while their owners are away. Each player can only anchor so many
+//! structures at once (see `--max-anchors-per-player`), though admins have no limit. Extra anchor blocks on a structure
+//! that's already anchored don't count towards this.
+//!
+//! The anchored sectors are saved, so they are loaded again when the server starts up.
+
+use std::fs;
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockPlaceEvent},
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    ecs::mut_events::MutEvent,
+    entities::player::Player,
+    events::block_events::BlockChangedEvent,
+    netty::{
+        sync::{events::server_event::NettyEventWriter, IdentifiableComponent},
+        system_sets::NetworkingSystemsSet,
+    },
+    physics::location::{Location, Sector},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{ownership::StructureOwnership, Structure},
+};
+use renet2::ClientId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entities::player::admin::Admin,
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+    settings::ServerSettings,
+};
+
+/// The unlocalized name of the chunk anchor block
+pub const ANCHOR_BLOCK: &str = "cosmos:chunk_anchor";
+
+const ANCHORED_SECTORS_PATH: &str = "world/anchored_sectors.json";
+
+/// The anchored sectors from the last time the server ran are kept loaded for this long after starting up, giving
+/// their anchors time to load back in.
+const STARTUP_GRACE_SECS: f32 = 30.0;
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize, Reflect)]
+/// A structure with at least one chunk anchor on it, which will not be unloaded when players leave.
+pub struct ChunkAnchor {
+    owner: String,
+    blocks: u32,
+}
+
+impl ChunkAnchor {
+    /// The name of the player this anchor counts against
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+}
+
+impl IdentifiableComponent for ChunkAnchor {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:chunk_anchor"
+    }
+}
+
+impl DefaultPersistentComponent for ChunkAnchor {}
+
+#[derive(Resource, Debug, Default)]
+/// Every sector that is being kept loaded by a chunk anchor
+pub struct AnchoredSectors {
+    loaded: HashSet<Sector>,
+    from_last_run: HashSet<Sector>,
+}
+
+impl AnchoredSectors {
+    /// Every anchored sector
+    pub fn iter(&self) -> impl Iterator<Item = &Sector> {
+        self.loaded.union(&self.from_last_run)
+    }
+
+    /// Returns true if there are no anchored sectors
+    pub fn is_empty(&self) -> bool {
+        self.loaded.is_empty() && self.from_last_run.is_empty()
+    }
+
+    fn save(&self) {
+        let json = serde_json::to_string_pretty(&self.loaded).expect("Sectors are always valid json");
+
+        if let Err(e) = fs::write(ANCHORED_SECTORS_PATH, json) {
+            error!("Unable to save anchored sectors to {ANCHORED_SECTORS_PATH}.\n{e:?}");
+        }
+    }
+}
+
+#[derive(Resource, Debug)]
+struct StartupGraceTimer(Timer);
+
+#[derive(Resource, Debug, Default)]
+/// Who placed the anchor blocks that were placed this frame, so the structures they anchor can count against them
+struct PendingAnchorOwners(Vec<(Entity, String)>);
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+fn load_anchored_sectors(mut commands: Commands) {
+    let from_last_run = fs::read_to_string(ANCHORED_SECTORS_PATH)
+        .ok()
+        .map(|json| {
+            serde_json::from_str::<HashSet<Sector>>(&json).unwrap_or_else(|e| {
+                error!("Invalid anchored sectors in {ANCHORED_SECTORS_PATH} - ignoring them.\n{e:?}");
+                HashSet::default()
+            })
+        })
+        .unwrap_or_default();
+
+    commands.insert_resource(AnchoredSectors {
+        loaded: HashSet::default(),
+        from_last_run,
+    });
+    commands.insert_resource(StartupGraceTimer(Timer::from_seconds(STARTUP_GRACE_SECS, TimerMode::Once)));
+}
+
+/// Stops players from anchoring more structures than they are allowed to
+fn limit_anchor_placements(
+    mut evr_place: EventReader<MutEvent<BlockPlaceEvent>>,
+    blocks: Res<Registry<Block>>,
+    settings: Res<ServerSettings>,
+    q_player: Query<(&Player, Has<Admin>)>,
+    q_anchors: Query<&ChunkAnchor>,
+    mut pending_owners: ResMut<PendingAnchorOwners>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    let Some(anchor_block) = blocks.from_id(ANCHOR_BLOCK) else {
+        return;
+    };
+
+    for ev in evr_place.read() {
+        let BlockPlaceEvent::Event(data) = *ev.read() else {
+            continue;
+        };
+
+        if data.block_id != anchor_block.id() {
+            continue;
+        }
+
+        let structure_ent = data.structure_block.structure();
+        if q_anchors.contains(structure_ent) || pending_owners.0.iter().any(|(ent, _)| *ent == structure_ent) {
+            // Already anchored, so this doesn't count towards any limits
+            continue;
+        }
+
+        let Ok((player, admin)) = q_player.get(data.placer) else {
+            continue;
+        };
+
+        let anchored = q_anchors.iter().filter(|anchor| anchor.owner() == player.name()).count()
+            + pending_owners.0.iter().filter(|(_, owner)| owner == player.name()).count();
+
+        if !admin && anchored >= settings.max_anchors_per_player as usize {
+            *ev.write() = BlockPlaceEvent::Cancelled;
+            reply(
+                &mut nevw_chat,
+                player.id(),
+                format!(
+                    "You can only anchor {} structures at once. Remove an anchor from another structure first.",
+                    settings.max_anchors_per_player
+                ),
+            );
+            continue;
+        }
+
+        pending_owners.0.push((structure_ent, player.name().to_owned()));
+    }
+}
+
+fn track_anchor_blocks(
+    mut commands: Commands,
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    blocks: Res<Registry<Block>>,
+    mut pending_owners: ResMut<PendingAnchorOwners>,
+    mut q_anchors: Query<&mut ChunkAnchor>,
+    q_ownership: Query<&StructureOwnership>,
+) {
+    let Some(anchor_block) = blocks.from_id(ANCHOR_BLOCK) else {
+        return;
+    };
+
+    // Several anchors may be placed on the same structure in one frame, before its new ChunkAnchor is inserted
+    let mut new_anchors = HashMap::<Entity, ChunkAnchor>::default();
+
+    for ev in evr_block_changed.read() {
+        let structure_ent = ev.block.structure();
+
+        if ev.old_block == anchor_block.id() {
+            if let Ok(mut anchor) = q_anchors.get_mut(structure_ent) {
+                anchor.blocks = anchor.blocks.saturating_sub(1);
+                if anchor.blocks == 0 {
+                    commands.entity(structure_ent).remove::<ChunkAnchor>();
+                }
+            } else if let Some(anchor) = new_anchors.get_mut(&structure_ent) {
+                anchor.blocks = anchor.blocks.saturating_sub(1);
+            }
+        }
+
+        if ev.new_block != anchor_block.id() {
+            continue;
+        }
+
+        if let Ok(mut anchor) = q_anchors.get_mut(structure_ent) {
+            anchor.blocks += 1;
+            continue;
+        }
+
+        new_anchors
+            .entry(structure_ent)
+            .or_insert_with(|| {
+                // Anchors that weren't placed by a player (such as ones built from blueprints) count against the structure's owner
+                let owner = pending_owners
+                    .0
+                    .iter()
+                    .find(|(ent, _)| *ent == structure_ent)
+                    .map(|(_, owner)| owner.clone())
+                    .or_else(|| q_ownership.get(structure_ent).ok().map(|o| o.owner().to_owned()))
+                    .unwrap_or_default();
+
+                ChunkAnchor { owner, blocks: 0 }
+            })
+            .blocks += 1;
+    }
+
+    for (structure_ent, anchor) in new_anchors {
+        if anchor.blocks != 0 {
+            commands.entity(structure_ent).insert(anchor);
+        }
+    }
+
+    pending_owners.0.clear();
+}
+
+fn update_anchored_sectors(
+    time: Res<Time>,
+    mut grace: ResMut<StartupGraceTimer>,
+    mut anchored_sectors: ResMut<AnchoredSectors>,
+    q_anchors: Query<&Location, (With<ChunkAnchor>, With<Structure>)>,
+) {
+    grace.0.tick(time.delta());
+
+    let loaded = q_anchors.iter().map(|loc| loc.sector()).collect::<HashSet<_>>();
+
+    if grace.0.just_finished() {
+        anchored_sectors.from_last_run.clear();
+    }
+
+    if loaded != anchored_sectors.loaded {
+        anchored_sectors.loaded = loaded;
+
+        // Don't forget the last run's anchors before they've had a chance to load back in
+        if grace.0.finished() {
+            anchored_sectors.save();
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<ChunkAnchor>(app);
+
+    app.register_type::<ChunkAnchor>()
+        .init_resource::<PendingAnchorOwners>()
+        .add_systems(OnEnter(GameState::PostLoading), load_anchored_sectors)
+        .add_systems(
+            Update,
+            (
+                limit_anchor_placements.in_set(BlockEventsSet::PreProcessEvents),
+                track_anchor_blocks.in_set(BlockEventsSet::PostProcessEvents),
+                update_anchored_sectors,
+            )
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        );
+}
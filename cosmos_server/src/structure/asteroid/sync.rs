@@ -3,8 +3,8 @@ use bevy_rapier3d::prelude::Velocity;
 use bevy_renet2::renet2::RenetServer;
 use cosmos_core::{
     netty::{
-        cosmos_encoder,
         netty_rigidbody::{NettyRigidBody, NettyRigidBodyLocation},
+        network_stats::{NettyMessage, NettyServerMessages},
         sync::server_entity_syncing::RequestedEntityEvent,
         system_sets::NetworkingSystemsSet,
         NettyChannelServer,
@@ -23,10 +23,10 @@ fn on_request_asteroid(
 ) {
     for ev in event_reader.read() {
         if let Ok((structure, transform, location, velocity, asteroid)) = query.get(ev.entity) {
-            server.send_message(
+            server.send_netty_message(
                 ev.client_id,
                 NettyChannelServer::Asteroid,
-                cosmos_encoder::serialize(&AsteroidServerMessages::Asteroid {
+                NettyMessage::new(&AsteroidServerMessages::Asteroid {
                     body: NettyRigidBody::new(Some(*velocity), transform.rotation, NettyRigidBodyLocation::Absolute(*location)),
                     entity: ev.entity,
                     dimensions: structure.chunk_dimensions(),
@@ -8,7 +8,7 @@ use cosmos_core::{
     block::{block_events::BlockEventsSet, Block},
     events::block_events::BlockChangedEvent,
     netty::{
-        cosmos_encoder,
+        network_stats::{NettyMessage, NettyServerMessages},
         server_reliable_messages::{BlockHealthUpdate, ServerReliableMessages},
         NettyChannelServer,
     },
@@ -21,6 +21,8 @@ use cosmos_core::{
     },
 };
 
+mod scrap;
+
 use super::{planet::biosphere::biosphere_generation::BiosphereGenerationSet, shared::MeltingDownSet};
 
 fn monitor_block_destroyed(
@@ -48,9 +50,9 @@ fn monitor_block_health_changed(mut server: ResMut<RenetServer>, mut event_reade
         .collect::<Vec<BlockHealthUpdate>>();
 
     if !changes.is_empty() {
-        server.broadcast_message(
+        server.broadcast_netty_message(
             NettyChannelServer::Reliable,
-            cosmos_encoder::serialize(&ServerReliableMessages::BlockHealthChange { changes }),
+            NettyMessage::new(&ServerReliableMessages::BlockHealthChange { changes }),
         );
     }
 }
@@ -87,4 +89,6 @@ pub(super) fn register(app: &mut App) {
             .chain()
             .run_if(in_state(GameState::Playing)),
     );
+
+    scrap::register(app);
 }
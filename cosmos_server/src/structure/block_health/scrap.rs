@@ -0,0 +1,103 @@
+//! Blocks destroyed by weapons & explosions will sometimes drop scrap that can be salvaged
+
+use bevy::{
+    prelude::{App, Commands, EventReader, GlobalTransform, IntoSystemConfigs, Query, Res, Transform, Update, Vec3},
+    utils::HashMap,
+};
+use bevy_rapier3d::prelude::Velocity;
+use cosmos_core::{
+    inventory::{itemstack::ItemShouldHaveData, Inventory},
+    item::{physical_item::PhysicalItem, Item},
+    netty::system_sets::NetworkingSystemsSet,
+    persistence::LoadingDistance,
+    physics::location::Location,
+    prelude::Structure,
+    registry::Registry,
+    structure::block_health::events::BlockDestroyedEvent,
+};
+
+use super::BlockHealthSet;
+
+/// The unlocalized name of the scrap item
+const SCRAP_ITEM: &str = "cosmos:scrap";
+/// The chance any destroyed block will drop a piece of scrap
+const SCRAP_DROP_CHANCE: f32 = 0.1;
+/// Scrap from many blocks destroyed in the same frame is combined into stacks of at most this many physical items per structure,
+/// so mass destruction doesn't flood the world with item entities.
+const MAX_SCRAP_ITEMS_PER_STRUCTURE: usize = 4;
+
+fn drop_scrap(
+    mut evr_block_destroyed: EventReader<BlockDestroyedEvent>,
+    q_structure: Query<(&Location, &GlobalTransform, &Structure, &Velocity)>,
+    items: Res<Registry<Item>>,
+    needs_data: Res<ItemShouldHaveData>,
+    mut commands: Commands,
+) {
+    let Some(scrap) = items.from_id(SCRAP_ITEM) else {
+        return;
+    };
+
+    // structure -> (where to drop the scrap, quantity)
+    let mut scrap_drops: HashMap<_, Vec<(_, u16)>> = HashMap::default();
+
+    for ev in evr_block_destroyed.read() {
+        if rand::random::<f32>() >= SCRAP_DROP_CHANCE {
+            continue;
+        }
+
+        let drops = scrap_drops.entry(ev.structure_entity).or_default();
+
+        if drops.len() < MAX_SCRAP_ITEMS_PER_STRUCTURE {
+            drops.push((ev.block.coords(), 1));
+        } else {
+            // Adds this to an existing drop instead of making a new one
+            let idx = rand::random::<usize>() % drops.len();
+            drops[idx].1 += 1;
+        }
+    }
+
+    for (structure_entity, drops) in scrap_drops {
+        let Ok((location, g_trans, structure, velocity)) = q_structure.get(structure_entity) else {
+            continue;
+        };
+
+        let structure_rot = g_trans.to_scale_rotation_translation().1;
+
+        for (coords, quantity) in drops {
+            let item_spawn = *location + structure_rot * structure.block_relative_position(coords);
+
+            let dropped_item_entity = commands
+                .spawn((
+                    PhysicalItem,
+                    item_spawn,
+                    LoadingDistance::new(1, 2),
+                    Transform::from_rotation(structure_rot),
+                    Velocity {
+                        linvel: velocity.linvel
+                            + Vec3::new(
+                                rand::random::<f32>() - 0.5,
+                                rand::random::<f32>() - 0.5,
+                                rand::random::<f32>() - 0.5,
+                            ) * 4.0,
+                        angvel: Vec3::ZERO,
+                    },
+                ))
+                .id();
+
+            let mut physical_item_inventory = Inventory::new("", 1, None, dropped_item_entity);
+            physical_item_inventory.insert_item(scrap, quantity.min(scrap.max_stack_size()), &mut commands, &needs_data);
+            commands.entity(dropped_item_entity).insert(physical_item_inventory);
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        // We want to target blocks after events are sent, but before blocks are removed.
+        drop_scrap
+            .in_set(NetworkingSystemsSet::Between)
+            .after(BlockHealthSet::SendHealthChanges)
+            .before(BlockHealthSet::ProcessHealthChanges),
+    );
+}
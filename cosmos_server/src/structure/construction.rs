@@ -0,0 +1,426 @@
+//! Projects blueprints onto structures as ghost blocks, and turns those ghosts into real blocks as they are built.
+//!
+//! Players pick a ship blueprint with the `/project [blueprint]` chat command, then interact with a blueprint projector
+//! to project it in front of the projector. Interacting with the projector again shows how much has been built, and
+//! breaking it removes the projection.
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent, BlockPlaceEvent},
+        block_face::BlockFace,
+        Block,
+    },
+    blockitems::BlockItems,
+    chat::ServerSendChatMessageEvent,
+    ecs::mut_events::MutEvent,
+    entities::player::{creative::Creative, Player},
+    events::block_events::BlockChangedEvent,
+    inventory::{held_item_slot::HeldItemSlot, Inventory},
+    item::Item,
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::{BlockCoordinate, UnboundBlockCoordinate},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        construction::{ConstructionSite, WeldGhostEvent, BLUEPRINT_PROJECTOR_BLOCK, MAX_GHOST_WELD_DISTANCE},
+        welding::WELDING_TOOL_ITEM,
+        Structure,
+    },
+};
+use renet2::ClientId;
+
+use crate::{
+    chat::{ChatCommandSet, ChatCommands, PlayerChatCommandEvent},
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+    structure::{
+        ownership::{notify_no_permission, StructurePermissions},
+        persistence::read_ship_blueprint,
+    },
+};
+
+/// Core blocks are part of every blueprint, but a structure can only have the one it was created with
+const CORE_BLOCKS: [&str; 2] = ["cosmos:ship_core", "cosmos:station_core"];
+
+impl DefaultPersistentComponent for ConstructionSite {}
+
+#[derive(Component, Debug)]
+/// The ship blueprint this player will project with the next blueprint projector they use
+struct ProjectingBlueprint(String);
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+/// Lays out the blueprint's blocks in front of the projector, centered on it.
+///
+/// Fails if the blueprint doesn't fit within the structure or something other than the blueprint's blocks is in the way.
+/// Blocks that are already built are left out.
+fn project_blueprint(
+    blueprint_name: &str,
+    blueprint: &Structure,
+    structure: &Structure,
+    projector: BlockCoordinate,
+    blocks: &Registry<Block>,
+    block_items: &BlockItems,
+) -> Result<ConstructionSite, &'static str> {
+    let blueprint_blocks = blueprint
+        .all_blocks_iter(false)
+        .map(|coords| (coords, blueprint.block_at(coords, blocks), blueprint.block_rotation(coords)))
+        .filter(|(_, block, _)| !CORE_BLOCKS.contains(&block.unlocalized_name()) && block_items.item_from_block(block).is_some())
+        .collect::<Vec<_>>();
+
+    let Some(first) = blueprint_blocks.first().map(|x| UnboundBlockCoordinate::from(x.0)) else {
+        return Err("That blueprint has nothing to build.");
+    };
+
+    let (min, max) = blueprint_blocks.iter().fold((first, first), |(min, max), (coords, _, _)| {
+        let c = UnboundBlockCoordinate::from(*coords);
+        (
+            UnboundBlockCoordinate::new(min.x.min(c.x), min.y.min(c.y), min.z.min(c.z)),
+            UnboundBlockCoordinate::new(max.x.max(c.x), max.y.max(c.y), max.z.max(c.z)),
+        )
+    });
+
+    let facing = structure.block_rotation(projector).direction_of(BlockFace::Front).to_coordinates();
+    let projector = UnboundBlockCoordinate::from(projector);
+
+    // The blueprint starts one block in front of the projector, and is centered on it along the other axes
+    let axis_offset = |facing: i64, projector: i64, min: i64, max: i64| match facing.signum() {
+        1 => projector + 1 - min,
+        -1 => projector - 1 - max,
+        _ => projector - (min + max) / 2,
+    };
+
+    let offset = UnboundBlockCoordinate::new(
+        axis_offset(facing.x, projector.x, min.x, max.x),
+        axis_offset(facing.y, projector.y, min.y, max.y),
+        axis_offset(facing.z, projector.z, min.z, max.z),
+    );
+
+    let mut site = ConstructionSite::new(
+        blueprint_name,
+        BlockCoordinate::try_from(projector).expect("Projector is in the structure"),
+    );
+
+    for (coords, block, rotation) in blueprint_blocks {
+        let Some(coords) = BlockCoordinate::try_from(coords + offset)
+            .ok()
+            .filter(|&c| structure.is_within_blocks(c))
+        else {
+            return Err("That blueprint doesn't fit in front of this projector.");
+        };
+
+        let existing = structure.block_at(coords, blocks);
+
+        if existing.id() == block.id() {
+            continue;
+        }
+
+        if !existing.is_empty() && !existing.is_fluid() {
+            return Err("Something is in the way of that blueprint.");
+        }
+
+        site.add_ghost(coords, block.unlocalized_name(), rotation);
+    }
+
+    if site.is_complete() {
+        return Err("That blueprint has already been built here.");
+    }
+
+    Ok(site)
+}
+
+fn register_chat_commands(mut chat_commands: ResMut<ChatCommands>) {
+    chat_commands.add("project");
+}
+
+fn on_project_command(
+    mut commands: Commands,
+    mut evr_command: EventReader<PlayerChatCommandEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for ev in evr_command.read() {
+        if ev.name != "project" {
+            continue;
+        }
+
+        let [blueprint] = ev.args.as_slice() else {
+            commands.entity(ev.player_entity).remove::<ProjectingBlueprint>();
+            reply(
+                &mut nevw_chat,
+                ev.client_id,
+                "Usage: /project [ship blueprint] - then use a blueprint projector to project it.",
+            );
+            continue;
+        };
+
+        if read_ship_blueprint(blueprint).is_none() {
+            reply(
+                &mut nevw_chat,
+                ev.client_id,
+                format!("There is no ship blueprint named {blueprint}."),
+            );
+            continue;
+        }
+
+        reply(
+            &mut nevw_chat,
+            ev.client_id,
+            format!("Use a blueprint projector to project {blueprint}."),
+        );
+        commands.entity(ev.player_entity).insert(ProjectingBlueprint(blueprint.clone()));
+    }
+}
+
+fn on_interact_with_projector(
+    mut commands: Commands,
+    mut evr_interact: EventReader<BlockInteractEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    blocks: Res<Registry<Block>>,
+    block_items: Res<BlockItems>,
+    q_structure: Query<(&Structure, Option<&ConstructionSite>)>,
+    q_player: Query<(&Player, Option<&ProjectingBlueprint>)>,
+    permissions: StructurePermissions,
+) {
+    for ev in evr_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok((structure, site)) = q_structure.get(s_block.structure()) else {
+            continue;
+        };
+
+        if !matches!(structure, Structure::Full(_))
+            || structure.block_at(s_block.coords(), &blocks).unlocalized_name() != BLUEPRINT_PROJECTOR_BLOCK
+        {
+            continue;
+        }
+
+        let Ok((player, projecting)) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        if !permissions.can_use(ev.interactor, s_block.structure()) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        if let Some(site) = site {
+            reply(
+                &mut nevw_chat,
+                player.id(),
+                format!(
+                    "Building {}: {}/{} blocks ({:.0}%). Break the projector it was projected from to stop building it.",
+                    site.blueprint(),
+                    site.built_blocks(),
+                    site.total_blocks(),
+                    site.progress() * 100.0
+                ),
+            );
+            continue;
+        }
+
+        let Some(projecting) = projecting else {
+            reply(
+                &mut nevw_chat,
+                player.id(),
+                "Pick a ship blueprint to project with /project [ship blueprint], then use this projector again.",
+            );
+            continue;
+        };
+
+        let Some(blueprint) = read_ship_blueprint(&projecting.0) else {
+            reply(
+                &mut nevw_chat,
+                player.id(),
+                format!("There is no ship blueprint named {}.", projecting.0),
+            );
+            continue;
+        };
+
+        match project_blueprint(&projecting.0, &blueprint, structure, s_block.coords(), &blocks, &block_items) {
+            Ok(site) => {
+                reply(
+                    &mut nevw_chat,
+                    player.id(),
+                    format!("Projected {} - {} blocks to build.", site.blueprint(), site.total_blocks()),
+                );
+
+                commands.entity(s_block.structure()).insert(site);
+                commands.entity(ev.interactor).remove::<ProjectingBlueprint>();
+            }
+            Err(reason) => reply(&mut nevw_chat, player.id(), reason),
+        }
+    }
+}
+
+/// Only the block a ghost shows can be placed where that ghost is
+fn reject_mismatched_placements(
+    mut evr_place: EventReader<MutEvent<BlockPlaceEvent>>,
+    q_site: Query<&ConstructionSite>,
+    blocks: Res<Registry<Block>>,
+) {
+    for ev in evr_place.read() {
+        let BlockPlaceEvent::Event(data) = *ev.read() else {
+            continue;
+        };
+
+        let Some((ghost, _)) = q_site
+            .get(data.structure_block.structure())
+            .ok()
+            .and_then(|site| site.ghost_at(data.structure_block.coords()))
+        else {
+            continue;
+        };
+
+        if blocks.from_numeric_id(data.block_id).unlocalized_name() != ghost {
+            *ev.write() = BlockPlaceEvent::Cancelled;
+        }
+    }
+}
+
+fn on_weld_ghost(
+    mut commands: Commands,
+    mut nevr_weld_ghost: EventReader<NettyEventReceived<WeldGhostEvent>>,
+    lobby: Res<ServerLobby>,
+    mut q_player: Query<(&GlobalTransform, &HeldItemSlot, &mut Inventory, Has<Creative>)>,
+    mut q_structure: Query<(&mut Structure, &GlobalTransform, &ConstructionSite)>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    block_items: Res<BlockItems>,
+    permissions: StructurePermissions,
+    mut evw_block_changed: EventWriter<BlockChangedEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    let Some(welding_tool) = items.from_id(WELDING_TOOL_ITEM) else {
+        return;
+    };
+
+    for ev in nevr_weld_ghost.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok((player_g_trans, held_item, mut inventory, creative)) = q_player.get_mut(player_ent) else {
+            continue;
+        };
+
+        if inventory
+            .itemstack_at(held_item.slot() as usize)
+            .is_none_or(|is| is.item_id() != welding_tool.id())
+        {
+            continue;
+        }
+
+        let Ok((mut structure, g_trans, site)) = q_structure.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        let coords = ev.block.coords();
+
+        let Some((ghost, rotation)) = site.ghost_at(coords) else {
+            continue;
+        };
+
+        if !permissions.can_use(player_ent, ev.block.structure()) {
+            notify_no_permission(&mut nevw_chat, ev.client_id);
+            continue;
+        }
+
+        let ghost_pos = g_trans.transform_point(structure.block_relative_position(coords));
+        if ghost_pos.distance_squared(player_g_trans.translation()) > MAX_GHOST_WELD_DISTANCE * MAX_GHOST_WELD_DISTANCE {
+            warn!("Player {player_ent:?} tried to weld a ghost block that is too far away.");
+            continue;
+        }
+
+        let existing = structure.block_at(coords, &blocks);
+        if !existing.is_empty() && !existing.is_fluid() {
+            continue;
+        }
+
+        let Some(block) = blocks.from_id(ghost) else {
+            continue;
+        };
+
+        let Some(item) = block_items.item_from_block(block).map(|id| items.from_numeric_id(id)) else {
+            continue;
+        };
+
+        if !creative {
+            if inventory.quantity_of(item) == 0 {
+                reply(
+                    &mut nevw_chat,
+                    ev.client_id,
+                    format!("You need {} to weld this.", item.unlocalized_name()),
+                );
+                continue;
+            }
+
+            let _ = inventory.take_and_remove_item(item, 1, &mut commands);
+        }
+
+        structure.set_block_at(coords, block, rotation, &blocks, Some(&mut evw_block_changed));
+    }
+}
+
+/// Ghosts are built once their block is placed where they are, whether by welding or normal placement
+fn build_ghosts(
+    mut commands: Commands,
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    mut q_site: Query<&mut ConstructionSite>,
+    blocks: Res<Registry<Block>>,
+) {
+    for ev in evr_block_changed.read() {
+        let Ok(mut site) = q_site.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        let new_block = blocks.from_numeric_id(ev.new_block).unlocalized_name();
+
+        // Breaking the projector stops the construction
+        if ev.block.coords() == site.projector() && new_block != BLUEPRINT_PROJECTOR_BLOCK {
+            commands.entity(ev.block.structure()).remove::<ConstructionSite>();
+            continue;
+        }
+
+        if site.ghost_at(ev.block.coords()).is_none_or(|(ghost, _)| ghost != new_block) {
+            continue;
+        }
+
+        site.build_ghost(ev.block.coords());
+
+        if site.is_complete() {
+            info!("Finished building blueprint {} on {:?}.", site.blueprint(), ev.block.structure());
+            commands.entity(ev.block.structure()).remove::<ConstructionSite>();
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<ConstructionSite>(app);
+
+    app.add_systems(Startup, register_chat_commands).add_systems(
+        Update,
+        (
+            on_project_command.after(ChatCommandSet::SendCommandEvents),
+            reject_mismatched_placements.in_set(BlockEventsSet::PreProcessEvents),
+            on_weld_ghost.in_set(BlockEventsSet::ChangeBlocks),
+            (on_interact_with_projector, build_ghosts).in_set(BlockEventsSet::ProcessEvents),
+        )
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
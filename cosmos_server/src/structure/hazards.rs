@@ -0,0 +1,298 @@
+//! Starts fires & electrical arcs when power blocks are destroyed, spreads & puts out fires, and hurts the players near them.
+//!
+//! There is no atmosphere simulation, so a fire counts as vented once one of its block's faces has a clear line of air to the
+//! edge of the structure.
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use cosmos_core::{
+    block::{
+        block_direction::ALL_BLOCK_DIRECTIONS,
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        blocks::AIR_BLOCK_ID,
+        Block,
+    },
+    entities::{
+        health::Health,
+        player::{creative::Creative, Player},
+    },
+    events::block_events::BlockChangedEvent,
+    inventory::{held_item_slot::HeldItemSlot, Inventory},
+    item::{tool::ItemDurability, Item},
+    netty::system_sets::NetworkingSystemsSet,
+    prelude::{BlockCoordinate, Structure},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        block_health::events::{BlockDestroyedEvent, BlockTakeDamageEvent},
+        hazards::{StructureHazards, ARCING_BLOCKS, ARC_DURATION_SECS, FIRE_BURNOUT_SECS, FIRE_EXTINGUISHER_ITEM, FIRE_STARTING_BLOCKS},
+    },
+};
+
+use crate::{items::durability::wear_item_at, universe::safe_zone::SpawnProtection};
+
+use super::block_health::BlockHealthSet;
+
+/// How often (in seconds) fires burn, spread & hurt players
+const HAZARD_TICK_SECS: f32 = 1.0;
+/// How much damage a fire does to the block it is on every tick
+const FIRE_BLOCK_DAMAGE: f32 = 4.0;
+/// How close (in blocks) a player has to be to a fire to be burnt by it
+const FIRE_PLAYER_RADIUS: f32 = 1.5;
+/// How much damage a player standing near any fire takes every tick
+const FIRE_PLAYER_DAMAGE: f32 = 5.0;
+/// How close (in blocks) a player has to be to an arc to be shocked by it
+const ARC_PLAYER_RADIUS: f32 = 2.5;
+/// How much damage a player standing near any arc takes every tick
+const ARC_PLAYER_DAMAGE: f32 = 8.0;
+/// Fires within this many blocks of the block sprayed by a fire extinguisher are put out
+const EXTINGUISHER_RADIUS: i64 = 2;
+
+#[derive(Resource, Debug)]
+struct HazardTimer(Timer);
+
+impl Default for HazardTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(HAZARD_TICK_SECS, TimerMode::Repeating))
+    }
+}
+
+/// Every existing block next to these coordinates
+fn neighbors(structure: &Structure, coords: BlockCoordinate) -> impl Iterator<Item = BlockCoordinate> + '_ {
+    ALL_BLOCK_DIRECTIONS
+        .into_iter()
+        .filter_map(move |dir| BlockCoordinate::try_from(dir.to_coordinates() + coords).ok())
+        .filter(|&neighbor| structure.is_within_blocks(neighbor) && structure.block_id_at(neighbor) != AIR_BLOCK_ID)
+}
+
+/// Returns true if any face of this block has nothing but air between it and the edge of the structure
+fn is_vented(structure: &Structure, coords: BlockCoordinate) -> bool {
+    ALL_BLOCK_DIRECTIONS.into_iter().any(|dir| {
+        let mut at = coords;
+
+        loop {
+            let Ok(next) = BlockCoordinate::try_from(dir.to_coordinates() + at) else {
+                return true;
+            };
+
+            if !structure.is_within_blocks(next) {
+                return true;
+            }
+
+            if structure.block_id_at(next) != AIR_BLOCK_ID {
+                return false;
+            }
+
+            at = next;
+        }
+    })
+}
+
+/// Destroyed reactors & conduits set the blocks around them on fire, and destroyed power blocks keep arcing for a while
+fn start_hazards_from_destroyed_blocks(
+    mut commands: Commands,
+    mut evr_destroyed: EventReader<BlockDestroyedEvent>,
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    mut q_structure: Query<(&Structure, Option<&mut StructureHazards>)>,
+    blocks: Res<Registry<Block>>,
+) {
+    // Only blocks that were destroyed cause hazards, not ones that were mined or removed in other ways
+    let destroyed = evr_destroyed.read().map(|ev| ev.block).collect::<HashSet<_>>();
+
+    if destroyed.is_empty() {
+        evr_block_changed.clear();
+        return;
+    }
+
+    let mut new_hazards = HashMap::<Entity, StructureHazards>::default();
+
+    for ev in evr_block_changed.read() {
+        if ev.new_block != AIR_BLOCK_ID || !destroyed.contains(&ev.block) {
+            continue;
+        }
+
+        let old_block = blocks.from_numeric_id(ev.old_block).unlocalized_name();
+        let starts_fire = FIRE_STARTING_BLOCKS.contains(&old_block);
+        let arcs = ARCING_BLOCKS.contains(&old_block);
+
+        if !starts_fire && !arcs {
+            continue;
+        }
+
+        let Ok((structure, hazards)) = q_structure.get(ev.block.structure()) else {
+            continue;
+        };
+
+        // Planets are too big to check if fires have been vented, and don't have any power blocks anyway
+        if !matches!(structure, Structure::Full(_)) {
+            continue;
+        }
+
+        let new_hazards = new_hazards
+            .entry(ev.block.structure())
+            .or_insert_with(|| hazards.cloned().unwrap_or_default());
+
+        if starts_fire {
+            for neighbor in neighbors(structure, ev.block.coords()) {
+                new_hazards.ignite(neighbor);
+            }
+        }
+
+        if arcs {
+            new_hazards.start_arc(ev.block.coords(), ARC_DURATION_SECS);
+        }
+    }
+
+    for (ent, new_hazards) in new_hazards {
+        match q_structure.get_mut(ent) {
+            Ok((_, Some(mut hazards))) => *hazards = new_hazards,
+            _ => {
+                commands.entity(ent).insert(new_hazards);
+            }
+        }
+    }
+}
+
+fn tick_hazards(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut timer: ResMut<HazardTimer>,
+    blocks: Res<Registry<Block>>,
+    mut q_structure: Query<(Entity, &mut Structure, &mut StructureHazards, &GlobalTransform)>,
+    mut q_players: Query<(&GlobalTransform, &mut Health), (With<Player>, Without<Creative>, Without<SpawnProtection>)>,
+    mut evw_take_damage: EventWriter<BlockTakeDamageEvent>,
+    mut evw_destroyed: EventWriter<BlockDestroyedEvent>,
+) {
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() {
+        return;
+    }
+
+    for (ent, mut structure, mut hazards, g_trans) in q_structure.iter_mut() {
+        // Ticking only changes timers, so it shouldn't cause the hazards to be synced
+        let mut changed = hazards.bypass_change_detection().tick(HAZARD_TICK_SECS);
+
+        let fires = hazards.fires().collect::<Vec<_>>();
+
+        for (coords, burning_secs) in fires {
+            let block = structure.block_at(coords, &blocks);
+
+            let burnt_out = block.flammability() <= 0.0 && burning_secs >= FIRE_BURNOUT_SECS;
+            if block.is_empty() || burnt_out || is_vented(&structure, coords) {
+                changed |= hazards.bypass_change_detection().extinguish(coords);
+                continue;
+            }
+
+            let spread_to = neighbors(&structure, coords)
+                .filter(|&neighbor| rand::random::<f32>() < structure.block_at(neighbor, &blocks).flammability())
+                .collect::<Vec<_>>();
+
+            for neighbor in spread_to {
+                changed |= hazards.bypass_change_detection().ignite(neighbor);
+            }
+
+            structure.block_take_damage(
+                coords,
+                &blocks,
+                FIRE_BLOCK_DAMAGE,
+                Some((&mut evw_take_damage, &mut evw_destroyed)),
+                None,
+            );
+        }
+
+        let hazard_position = |coords: BlockCoordinate| g_trans.transform_point(structure.block_relative_position(coords));
+
+        let fire_positions = hazards.fires().map(|(coords, _)| hazard_position(coords)).collect::<Vec<_>>();
+        let arc_positions = hazards.arcs().map(|(coords, _)| hazard_position(coords)).collect::<Vec<_>>();
+
+        for (player_g_trans, mut health) in q_players.iter_mut() {
+            let player_pos = player_g_trans.translation();
+            let near = |positions: &[Vec3], radius: f32| positions.iter().any(|pos| pos.distance_squared(player_pos) <= radius * radius);
+
+            let mut damage = 0.0;
+            if near(&fire_positions, FIRE_PLAYER_RADIUS) {
+                damage += FIRE_PLAYER_DAMAGE;
+            }
+            if near(&arc_positions, ARC_PLAYER_RADIUS) {
+                damage += ARC_PLAYER_DAMAGE;
+            }
+
+            if damage > 0.0 {
+                health.take_damage(damage);
+            }
+        }
+
+        if hazards.is_empty() {
+            commands.entity(ent).remove::<StructureHazards>();
+        } else if changed {
+            hazards.set_changed();
+        }
+    }
+}
+
+fn extinguish_fires(
+    mut commands: Commands,
+    mut evr_interact: EventReader<BlockInteractEvent>,
+    mut q_hazards: Query<&mut StructureHazards>,
+    mut q_player: Query<(&HeldItemSlot, &mut Inventory)>,
+    mut q_durability: Query<&mut ItemDurability>,
+    items: Res<Registry<Item>>,
+) {
+    let Some(extinguisher) = items.from_id(FIRE_EXTINGUISHER_ITEM) else {
+        return;
+    };
+
+    for ev in evr_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let Ok(mut hazards) = q_hazards.get_mut(s_block.structure()) else {
+            continue;
+        };
+
+        let Ok((held_item, mut inventory)) = q_player.get_mut(ev.interactor) else {
+            continue;
+        };
+
+        let held_slot = held_item.slot() as usize;
+        if inventory.itemstack_at(held_slot).is_none_or(|is| is.item_id() != extinguisher.id()) {
+            continue;
+        }
+
+        let sprayed = s_block.coords();
+        let put_out = hazards
+            .fires()
+            .map(|(coords, _)| coords)
+            .filter(|&coords| {
+                let diff = coords - sprayed;
+                diff.x.abs() <= EXTINGUISHER_RADIUS && diff.y.abs() <= EXTINGUISHER_RADIUS && diff.z.abs() <= EXTINGUISHER_RADIUS
+            })
+            .collect::<Vec<_>>();
+
+        if put_out.is_empty() {
+            continue;
+        }
+
+        for coords in put_out {
+            hazards.extinguish(coords);
+        }
+
+        wear_item_at(&mut inventory, held_slot, 1, &mut q_durability, &mut commands);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<HazardTimer>().add_systems(
+        Update,
+        (
+            extinguish_fires.in_set(BlockEventsSet::ProcessEvents),
+            tick_hazards.in_set(BlockHealthSet::SendHealthChanges),
+            start_hazards_from_destroyed_blocks.after(BlockHealthSet::ProcessHealthChanges),
+        )
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
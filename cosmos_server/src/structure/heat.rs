@@ -0,0 +1,218 @@
+//! Heats structures up as their reactors, thrusters and weapons are used, cools them down with their radiators, and
+//! damages structures that overheat.
+
+use bevy::{prelude::*, utils::HashMap};
+use cosmos_core::{
+    block::{block_events::BlockEventsSet, specific_blocks::heat_sensor::HEAT_SENSOR_BLOCK, Block},
+    events::block_events::BlockDataChangedEvent,
+    logic::{BlockLogicData, LogicSystemSet},
+    netty::system_sets::NetworkingSystemsSet,
+    prelude::BlockCoordinate,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        block_health::events::{BlockDestroyedEvent, BlockTakeDamageEvent},
+        heat::{StructureHeat, RADIATOR_BLOCK},
+        structure_block::StructureBlock,
+        systems::{energy_generation_system::EnergyGenerationSystem, StructureSystems, StructureSystemsSet},
+        Structure,
+    },
+};
+
+use super::systems::{subsystem_targeting::SubsystemBlocks, thruster_system::ThrusterSystemSet};
+
+/// How much heat is generated per unit of energy a reactor produces
+pub const REACTOR_HEAT_PER_ENERGY: f32 = 0.02;
+/// How much heat is generated per unit of energy thrusters consume
+pub const THRUSTER_HEAT_PER_ENERGY: f32 = 0.05;
+/// How much heat is generated per unit of energy spent firing weapons
+pub const WEAPON_HEAT_PER_ENERGY: f32 = 0.25;
+
+/// On average, an overheating structure damages one of its blocks this often (in seconds)
+const OVERHEAT_DAMAGE_INTERVAL: f32 = 1.0;
+/// How much damage overheating does to a block
+const OVERHEAT_DAMAGE: f32 = 20.0;
+/// How many random spots are checked for a heat-generating block to damage
+const OVERHEAT_DAMAGE_ATTEMPTS: usize = 32;
+
+#[derive(Event, Debug, Clone, Copy)]
+/// Send this to heat up a structure
+pub struct GenerateHeatEvent {
+    /// The structure that is heating up
+    pub structure_entity: Entity,
+    /// How much heat to add
+    pub heat: f32,
+}
+
+fn update_heat_limits(
+    mut commands: Commands,
+    blocks: Res<Registry<Block>>,
+    mut q_structures: Query<(Entity, &Structure, Option<&mut StructureHeat>), (Changed<Structure>, With<StructureSystems>)>,
+) {
+    let radiator_id = blocks.from_id(RADIATOR_BLOCK).map(|x| x.id());
+
+    for (ent, structure, heat) in q_structures.iter_mut() {
+        // Only full structures keep track of their block counts
+        let Some(counts) = structure.block_counts() else {
+            continue;
+        };
+
+        let total_blocks = counts.total();
+        let radiators = radiator_id.map(|id| counts.count(id)).unwrap_or(0);
+
+        match heat {
+            Some(mut heat) => {
+                let mut new_heat = *heat;
+                new_heat.set_limits(total_blocks, radiators);
+                heat.set_if_neq(new_heat);
+            }
+            None => {
+                commands
+                    .entity(ent)
+                    .insert(StructureHeat::from_block_counts(total_blocks, radiators));
+            }
+        }
+    }
+}
+
+/// Heat changes every frame, so it is only marked as changed (and synced) when its percentage changes
+fn update_heat(
+    time: Res<Time>,
+    mut evr_generate_heat: EventReader<GenerateHeatEvent>,
+    mut q_heat: Query<(Entity, &mut StructureHeat, &StructureSystems)>,
+    q_energy_generation_system: Query<&EnergyGenerationSystem>,
+) {
+    let mut generated = HashMap::<Entity, f32>::default();
+
+    for ev in evr_generate_heat.read() {
+        *generated.entry(ev.structure_entity).or_default() += ev.heat;
+    }
+
+    let delta = time.delta_secs();
+
+    for (ent, mut heat, systems) in q_heat.iter_mut() {
+        // Solar panels don't heat up the structure, only reactors do
+        let reactor_heat = systems
+            .query(&q_energy_generation_system)
+            .map(|gen| (gen.energy_generation_rate() - gen.solar_generation_rate()) * REACTOR_HEAT_PER_ENERGY * delta)
+            .unwrap_or(0.0);
+
+        let old_percent = heat.percent();
+
+        let new_heat = heat.bypass_change_detection();
+        new_heat.add_heat(generated.get(&ent).copied().unwrap_or(0.0) + reactor_heat);
+        new_heat.dissipate(delta);
+
+        if heat.percent() != old_percent {
+            heat.set_changed();
+        }
+    }
+}
+
+/// Finds a random block that generates heat on this structure
+fn random_heat_generating_block(structure: &Structure, subsystem_blocks: &SubsystemBlocks) -> Option<BlockCoordinate> {
+    let dims = structure.block_dimensions();
+
+    (0..OVERHEAT_DAMAGE_ATTEMPTS)
+        .map(|_| {
+            BlockCoordinate::new(
+                rand::random::<u64>() % dims.x.max(1),
+                rand::random::<u64>() % dims.y.max(1),
+                rand::random::<u64>() % dims.z.max(1),
+            )
+        })
+        .find(|&coords| subsystem_blocks.subsystem(structure.block_id_at(coords)).is_some())
+}
+
+fn damage_overheating_structures(
+    time: Res<Time>,
+    blocks: Res<Registry<Block>>,
+    subsystem_blocks: Res<SubsystemBlocks>,
+    mut q_structure: Query<(&mut Structure, &StructureHeat)>,
+    mut evw_take_damage: EventWriter<BlockTakeDamageEvent>,
+    mut evw_destroyed: EventWriter<BlockDestroyedEvent>,
+) {
+    let chance = time.delta_secs() / OVERHEAT_DAMAGE_INTERVAL;
+
+    for (mut structure, heat) in q_structure.iter_mut() {
+        if !heat.is_overheating() || rand::random::<f32>() >= chance {
+            continue;
+        }
+
+        let Some(coords) = random_heat_generating_block(&structure, &subsystem_blocks) else {
+            continue;
+        };
+
+        structure.block_take_damage(
+            coords,
+            &blocks,
+            OVERHEAT_DAMAGE,
+            Some((&mut evw_take_damage, &mut evw_destroyed)),
+            None,
+        );
+    }
+}
+
+/// Heat sensors output their structure's heat percentage
+fn update_heat_sensors(
+    blocks: Res<Registry<Block>>,
+    q_structure: Query<(Entity, &Structure, &StructureHeat), Or<(Changed<StructureHeat>, Changed<Structure>)>>,
+    mut q_logic_data: Query<&mut BlockLogicData>,
+    mut evw_block_data_changed: EventWriter<BlockDataChangedEvent>,
+) {
+    let Some(heat_sensor) = blocks.from_id(HEAT_SENSOR_BLOCK) else {
+        return;
+    };
+
+    for (structure_ent, structure, heat) in q_structure.iter() {
+        let Some(counts) = structure.block_counts() else {
+            continue;
+        };
+
+        let signal = heat.percent();
+
+        for coords in counts.positions(heat_sensor.id()) {
+            let Some(data_ent) = structure.block_data(coords) else {
+                continue;
+            };
+
+            let Ok(mut logic_data) = q_logic_data.get_mut(data_ent) else {
+                continue;
+            };
+
+            if logic_data.0 == signal {
+                continue;
+            }
+
+            *logic_data = BlockLogicData(signal);
+
+            evw_block_data_changed.send(BlockDataChangedEvent {
+                block_data_entity: Some(data_ent),
+                block: StructureBlock::new(coords, structure_ent),
+            });
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_event::<GenerateHeatEvent>()
+        .add_systems(
+            Update,
+            (
+                update_heat_limits.in_set(BlockEventsSet::PostProcessEvents),
+                (update_heat, damage_overheating_structures)
+                    .chain()
+                    .in_set(StructureSystemsSet::UpdateSystems)
+                    .after(ThrusterSystemSet::ApplyThrusters),
+            )
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            Update,
+            update_heat_sensors
+                .in_set(LogicSystemSet::Consume)
+                .ambiguous_with(LogicSystemSet::Consume)
+                .run_if(in_state(GameState::Playing)),
+        );
+}
@@ -2,24 +2,38 @@
 
 use bevy::prelude::App;
 
+pub mod anchor;
 pub mod asteroid;
 pub mod block_health;
+pub mod construction;
+pub mod hazards;
+pub mod heat;
+pub mod ownership;
 pub mod persistence;
 pub mod planet;
 pub mod server_structure_builder;
 pub mod shared;
 pub mod ship;
 pub mod station;
+pub mod structure_name;
 pub mod systems;
+pub mod welding;
 
 pub(super) fn register(app: &mut App) {
     ship::register(app);
     systems::register(app);
     planet::register(app);
     block_health::register(app);
+    heat::register(app);
+    hazards::register(app);
     asteroid::register(app);
 
     persistence::register(app);
     shared::register(app);
     station::register(app);
+    ownership::register(app);
+    structure_name::register(app);
+    welding::register(app);
+    construction::register(app);
+    anchor::register(app);
 }
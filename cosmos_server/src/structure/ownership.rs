@@ -0,0 +1,197 @@
+//! Handles who is allowed to use owned structures.
+//!
+//! Players manage the structure they're on via chat commands:
+//! - `/claim` - Takes ownership of an unowned structure
+//! - `/trust [player_name]` - Lets another player use your structure
+//! - `/untrust [player_name]` - Stops another player from using your structure
+//! - `/owner` - Shows who owns the structure & who has access to it
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use cosmos_core::{
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    netty::{sync::events::server_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    state::GameState,
+    structure::{ownership::StructureOwnership, ship::pilot::Pilot, Structure},
+};
+use renet2::ClientId;
+
+use crate::{
+    chat::{ChatCommandSet, ChatCommands, PlayerChatCommandEvent},
+    entities::player::admin::Admin,
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+};
+
+impl DefaultPersistentComponent for StructureOwnership {}
+
+#[derive(SystemParam)]
+/// Checks if players are allowed to use structures.
+///
+/// A player can use a structure if nobody owns it, they have access to it, or they are an [`Admin`].
+pub struct StructurePermissions<'w, 's> {
+    q_ownership: Query<'w, 's, &'static StructureOwnership>,
+    q_players: Query<'w, 's, (&'static Player, Has<Admin>)>,
+}
+
+impl StructurePermissions<'_, '_> {
+    /// Returns true if this entity is allowed to edit blocks, pilot, and open inventories on this structure.
+    ///
+    /// Entities that aren't players (such as ships' mining lasers) are always allowed.
+    pub fn can_use(&self, entity: Entity, structure_entity: Entity) -> bool {
+        let Ok(ownership) = self.q_ownership.get(structure_entity) else {
+            return true;
+        };
+
+        let Ok((player, admin)) = self.q_players.get(entity) else {
+            return true;
+        };
+
+        admin || ownership.has_access(player.name())
+    }
+}
+
+/// Tells the player they aren't allowed to do what they just tried to do
+pub fn notify_no_permission(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId) {
+    reply(nevw_chat, client_id, "You don't have permission to use this structure.");
+}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+fn register_chat_commands(mut chat_commands: ResMut<ChatCommands>) {
+    chat_commands.add("claim");
+    chat_commands.add("trust");
+    chat_commands.add("untrust");
+    chat_commands.add("owner");
+}
+
+fn on_ownership_commands(
+    mut commands: Commands,
+    mut evr_command: EventReader<PlayerChatCommandEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    q_players: Query<(&Player, Option<&Parent>, Option<&Pilot>, Has<Admin>)>,
+    mut q_structures: Query<Option<&mut StructureOwnership>, With<Structure>>,
+) {
+    for ev in evr_command.read() {
+        if !matches!(ev.name.as_str(), "claim" | "trust" | "untrust" | "owner") {
+            continue;
+        }
+
+        let Ok((player, parent, pilot, admin)) = q_players.get(ev.player_entity) else {
+            continue;
+        };
+
+        // The structure the player is piloting or standing on
+        let Some(structure_entity) = pilot
+            .map(|x| x.entity)
+            .into_iter()
+            .chain(parent.map(|x| x.get()))
+            .find(|e| q_structures.contains(*e))
+        else {
+            reply(&mut nevw_chat, ev.client_id, "You must be on a structure to use this command.");
+            continue;
+        };
+
+        let Ok(ownership) = q_structures.get_mut(structure_entity) else {
+            continue;
+        };
+
+        match ev.name.as_str() {
+            "claim" => {
+                if let Some(ownership) = ownership {
+                    reply(
+                        &mut nevw_chat,
+                        ev.client_id,
+                        format!("This structure is owned by {}.", ownership.owner()),
+                    );
+                    continue;
+                }
+
+                commands.entity(structure_entity).insert(StructureOwnership::new(player.name()));
+                reply(&mut nevw_chat, ev.client_id, "You now own this structure.");
+            }
+            "owner" => {
+                let Some(ownership) = ownership else {
+                    reply(
+                        &mut nevw_chat,
+                        ev.client_id,
+                        "Nobody owns this structure. Use /claim to take ownership of it.",
+                    );
+                    continue;
+                };
+
+                let access_list = ownership.access_list().collect::<Vec<_>>();
+
+                let message = if access_list.is_empty() {
+                    format!("This structure is owned by {}.", ownership.owner())
+                } else {
+                    format!(
+                        "This structure is owned by {}. Trusted players: {}",
+                        ownership.owner(),
+                        access_list.join(", ")
+                    )
+                };
+
+                reply(&mut nevw_chat, ev.client_id, message);
+            }
+            "trust" | "untrust" => {
+                let [target] = ev.args.as_slice() else {
+                    reply(&mut nevw_chat, ev.client_id, format!("Usage: /{} [player_name]", ev.name));
+                    continue;
+                };
+
+                let Some(mut ownership) = ownership else {
+                    reply(
+                        &mut nevw_chat,
+                        ev.client_id,
+                        "Nobody owns this structure. Use /claim to take ownership of it.",
+                    );
+                    continue;
+                };
+
+                if !admin && !ownership.is_owner(player.name()) {
+                    reply(
+                        &mut nevw_chat,
+                        ev.client_id,
+                        "Only the owner of this structure can change who has access to it.",
+                    );
+                    continue;
+                }
+
+                let message = if ev.name == "trust" {
+                    if ownership.grant_access(target.as_str()) {
+                        format!("{target} can now use this structure.")
+                    } else {
+                        format!("{target} can already use this structure.")
+                    }
+                } else if ownership.revoke_access(target) {
+                    format!("{target} can no longer use this structure.")
+                } else {
+                    format!("{target} didn't have access to this structure.")
+                };
+
+                reply(&mut nevw_chat, ev.client_id, message);
+            }
+            _ => unreachable!("Checked above"),
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<StructureOwnership>(app);
+
+    app.add_systems(Startup, register_chat_commands).add_systems(
+        Update,
+        on_ownership_commands
+            .after(ChatCommandSet::SendCommandEvents)
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -1,5 +1,7 @@
 //! Saving/reading from disk block data
 
+use std::fs;
+
 use bevy::{
     app::App,
     ecs::{component::Component, system::Commands},
@@ -15,6 +17,9 @@ use crate::persistence::{saving::NeedsSaved, SerializedData};
 
 pub mod chunk;
 
+/// The blueprint subdirectory ship blueprints are saved to by the `blueprint` command
+const SHIP_BLUEPRINT_SUBDIR: &str = "ship";
+
 #[derive(Component, Debug, Clone, Copy)]
 /// Signifies that this block's data needs saved
 pub(crate) struct BlockDataNeedsSaved;
@@ -49,3 +54,36 @@ pub(crate) fn save_structure(structure: &Structure, s_data: &mut SerializedData,
 pub(super) fn register(app: &mut App) {
     chunk::register(app);
 }
+
+/// The path to the ship blueprint with this name
+pub(crate) fn ship_blueprint_path(blueprint: &str) -> String {
+    format!("blueprints/{SHIP_BLUEPRINT_SUBDIR}/{blueprint}.bp")
+}
+
+/// Blueprint names that come from players can't be allowed to point outside of the blueprint directory
+pub(crate) fn is_valid_blueprint_name(blueprint: &str) -> bool {
+    !blueprint.is_empty() && blueprint.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Reads the structure of the ship blueprint with this name without spawning it, or [`None`] if there is no such ship blueprint
+pub(crate) fn read_ship_blueprint(blueprint: &str) -> Option<Structure> {
+    if !is_valid_blueprint_name(blueprint) {
+        return None;
+    }
+
+    read_ship_blueprint_at(&ship_blueprint_path(blueprint))
+}
+
+/// Reads the structure of the ship blueprint at this path without spawning it, or [`None`] if there is no ship blueprint there.
+///
+/// This path must never come from a player.
+pub(crate) fn read_ship_blueprint_at(path: &str) -> Option<Structure> {
+    let data = fs::read(path).ok()?;
+    let s_data = cosmos_encoder::deserialize::<SerializedData>(&data).ok()?;
+
+    if !s_data.deserialize_data::<bool>("cosmos:is_ship").unwrap_or(false) {
+        return None;
+    }
+
+    s_data.deserialize_data::<Structure>("cosmos:structure")
+}
@@ -21,6 +21,7 @@ use cosmos_core::{
 
 use crate::{
     init::init_world::{Noise, ServerSeed},
+    rng::SubSeed,
     structure::planet::biosphere::biosphere_generation::BiosphereGenerationSet,
 };
 
@@ -48,7 +49,17 @@ fn desert_generate_chunk_features(
                 continue;
             };
 
-            generate_chunk_features(&mut ev_writer, ev.chunk, &mut structure, location, &blocks, &noise_generator, &seed);
+            let cactus_seed = seed.planet_feature_seed(location.sector(), "cosmos:cactus");
+
+            generate_chunk_features(
+                &mut ev_writer,
+                ev.chunk,
+                &mut structure,
+                location,
+                &blocks,
+                &noise_generator,
+                &cactus_seed,
+            );
         }
     }
 }
@@ -60,7 +71,7 @@ fn generate_chunk_features(
     location: &Location,
     blocks: &Registry<Block>,
     _noise_generator: &Noise,
-    seed: &ServerSeed,
+    seed: &SubSeed,
 ) {
     let Structure::Dynamic(planet) = structure else {
         panic!("A planet must be dynamic!");
@@ -389,6 +389,12 @@ pub enum BiosphereGenerationSet {
     GenerateChunks,
     /// Called after the [`BiosphereGenerationSet::GenerateChunks`] set. This should be used for things like trees.
     GenerateChunkFeatures,
+    /// Multi-chunk features that have had every chunk they touch generated are found here.
+    ///
+    /// See [`super::multi_chunk_features`].
+    FindPlaceableMultiChunkFeatures,
+    /// Features that span multiple chunks should be placed here when a [`super::multi_chunk_features::PlaceMultiChunkFeatureEvent`] is received.
+    GenerateMultiChunkFeatures,
 }
 
 pub(super) fn register(app: &mut App) {
@@ -399,6 +405,8 @@ pub(super) fn register(app: &mut App) {
             BiosphereGenerationSet::GpuInteraction,
             BiosphereGenerationSet::GenerateChunks,
             BiosphereGenerationSet::GenerateChunkFeatures,
+            BiosphereGenerationSet::FindPlaceableMultiChunkFeatures,
+            BiosphereGenerationSet::GenerateMultiChunkFeatures,
         )
             .before(StructureLoadingSet::CreateChunkEntities)
             .before(BlockEventsSet::PreProcessEvents)
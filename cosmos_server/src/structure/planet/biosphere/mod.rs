@@ -16,7 +16,12 @@ use bevy::{
 use bevy_renet2::renet2::RenetServer;
 use biome::RegisterBiomesSet;
 use cosmos_core::{
-    netty::{cosmos_encoder, server_reliable_messages::ServerReliableMessages, system_sets::NetworkingSystemsSet, NettyChannelServer},
+    netty::{
+        network_stats::{NettyMessage, NettyServerMessages},
+        server_reliable_messages::ServerReliableMessages,
+        system_sets::NetworkingSystemsSet,
+        NettyChannelServer,
+    },
     physics::location::Location,
     registry::{identifiable::Identifiable, Registry},
     state::GameState,
@@ -54,6 +59,8 @@ pub mod generation_tools;
 pub mod grass_biosphere;
 pub mod ice_biosphere;
 pub mod molten_biosphere;
+pub mod multi_chunk_features;
+pub mod outposts;
 pub mod shader_assembler;
 
 /// This component is only used to mark a planet as a specific biosphere.
@@ -300,10 +307,10 @@ fn on_connect(
     shaders: Res<CachedShaders>,
 ) {
     for ev in ev_reader.read() {
-        server.send_message(
+        server.send_netty_message(
             ev.client_id,
             NettyChannelServer::Reliable,
-            cosmos_encoder::serialize(&ServerReliableMessages::TerrainGenerationShaders {
+            NettyMessage::new(&ServerReliableMessages::TerrainGenerationShaders {
                 shaders: shaders.0.clone(),
                 permutation_table: permutation_table.clone(),
             }),
@@ -348,4 +355,6 @@ pub(super) fn register(app: &mut App) {
     molten_biosphere::register(app);
     ice_biosphere::register(app);
     shader_assembler::register(app);
+    multi_chunk_features::register(app);
+    outposts::register(app);
 }
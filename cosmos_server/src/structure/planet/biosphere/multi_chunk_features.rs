@@ -0,0 +1,148 @@
+//! Some features (such as outposts) are too large to fit in the chunk that decided to generate them.
+//!
+//! Instead of placing their blocks right away, these features are queued with every chunk they will touch. Once all
+//! of those chunks have been generated, a [`PlaceMultiChunkFeatureEvent`] is sent so the feature can be placed
+//! across all of them at once.
+//!
+//! Placing these features is expensive, so planets in overloaded sectors (see [`SectorLoad`]) hold onto them until
+//! their sector is no longer overloaded.
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use cosmos_core::{
+    block::block_face::BlockFace,
+    physics::location::Location,
+    structure::{
+        coordinates::{BlockCoordinate, ChunkCoordinate},
+        ChunkState, Structure,
+    },
+};
+
+use crate::universe::sector_load::SectorLoad;
+
+use super::{biome::GenerateChunkFeaturesEvent, biosphere_generation::BiosphereGenerationSet};
+
+#[derive(Debug, Clone)]
+/// A feature that spans multiple chunks
+pub struct MultiChunkFeature {
+    /// The unlocalized name of the feature being placed, used by whatever placed it to know what it is
+    pub feature: String,
+    /// The block this feature is placed relative to
+    pub origin: BlockCoordinate,
+    /// The planet face this feature is on
+    pub block_up: BlockFace,
+    /// Every chunk this feature will place blocks in
+    pub chunks: HashSet<ChunkCoordinate>,
+}
+
+#[derive(Event, Debug)]
+/// Send this during [`BiosphereGenerationSet::GenerateChunkFeatures`] to queue a feature that will be placed once
+/// every chunk it touches has been generated.
+pub struct QueueMultiChunkFeatureEvent {
+    /// The planet this feature is on
+    pub structure_entity: Entity,
+    /// The feature to place
+    pub feature: MultiChunkFeature,
+}
+
+#[derive(Event, Debug)]
+/// Sent during [`BiosphereGenerationSet::GenerateMultiChunkFeatures`] once every chunk a queued feature touches has
+/// been generated. The feature should be placed when this is received.
+pub struct PlaceMultiChunkFeatureEvent {
+    /// The planet this feature is on
+    pub structure_entity: Entity,
+    /// The feature to place
+    pub feature: MultiChunkFeature,
+}
+
+#[derive(Component, Debug, Default)]
+/// Features on this planet that are still waiting for some of their chunks to be generated
+struct PendingMultiChunkFeatures(Vec<MultiChunkFeature>);
+
+#[derive(Component, Debug)]
+/// This planet had features ready to place while its sector was overloaded
+struct DeferredMultiChunkFeatures;
+
+/// Features are only checked when they are queued, when a chunk on their planet is generated, or when their
+/// planet's sector stops being overloaded, since those are the only times they could become placeable.
+fn find_placeable_features(
+    mut commands: Commands,
+    mut evr_queue: EventReader<QueueMultiChunkFeatureEvent>,
+    mut evr_generated: EventReader<GenerateChunkFeaturesEvent>,
+    mut evw_place: EventWriter<PlaceMultiChunkFeatureEvent>,
+    mut q_planet: Query<(&Structure, &Location, Option<&mut PendingMultiChunkFeatures>)>,
+    q_deferred: Query<(Entity, &Location), With<DeferredMultiChunkFeatures>>,
+    sector_load: Res<SectorLoad>,
+) {
+    let mut queued: HashMap<Entity, Vec<MultiChunkFeature>> = HashMap::default();
+
+    for ev in evr_queue.read() {
+        queued.entry(ev.structure_entity).or_default().push(ev.feature.clone());
+    }
+
+    let planets = evr_generated
+        .read()
+        .map(|ev| ev.structure_entity)
+        .chain(queued.keys().copied())
+        .chain(
+            q_deferred
+                .iter()
+                .filter(|(_, location)| !sector_load.is_throttled(location.sector()))
+                .map(|(entity, _)| entity),
+        )
+        .collect::<HashSet<Entity>>();
+
+    for structure_entity in planets {
+        let Ok((structure, location, pending)) = q_planet.get_mut(structure_entity) else {
+            continue;
+        };
+
+        let newly_queued = queued.remove(&structure_entity).unwrap_or_default();
+
+        let throttled = sector_load.is_throttled(location.sector());
+        if !throttled && q_deferred.contains(structure_entity) {
+            commands.entity(structure_entity).remove::<DeferredMultiChunkFeatures>();
+        }
+
+        if pending.is_none() && newly_queued.is_empty() {
+            continue;
+        }
+
+        if throttled {
+            commands.entity(structure_entity).insert(DeferredMultiChunkFeatures);
+        }
+
+        let features = match &pending {
+            Some(pending) => pending.0.iter().cloned().chain(newly_queued).collect::<Vec<_>>(),
+            None => newly_queued,
+        };
+
+        let (ready, waiting): (Vec<_>, Vec<_>) = features
+            .into_iter()
+            .partition(|feature| !throttled && feature.chunks.iter().all(|&c| structure.get_chunk_state(c) == ChunkState::Loaded));
+
+        match pending {
+            Some(mut pending) => pending.0 = waiting,
+            None => {
+                if !waiting.is_empty() {
+                    commands.entity(structure_entity).insert(PendingMultiChunkFeatures(waiting));
+                }
+            }
+        }
+
+        for feature in ready {
+            evw_place.send(PlaceMultiChunkFeatureEvent { structure_entity, feature });
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_event::<QueueMultiChunkFeatureEvent>()
+        .add_event::<PlaceMultiChunkFeatureEvent>()
+        .add_systems(
+            Update,
+            find_placeable_features.in_set(BiosphereGenerationSet::FindPlaceableMultiChunkFeatures),
+        );
+}
@@ -0,0 +1,385 @@
+//! Small settlements that are generated on planet surfaces, such as trading posts with shops in them.
+//!
+//! Outposts are loaded from `assets/cosmos/outposts`, and are registered as `cosmos:<file name>`. Each outpost lists
+//! the biomes it can generate in and how rare it is. Its origin `(0, 0, 0)` is the first block above the ground,
+//! and +Y points away from the planet. The ground beneath an outpost is filled in with its foundation block so it
+//! doesn't float over dips in the terrain, and `cosmos:air` regions can be used to flatten any hills in the way.
+//!
+//! Outposts are usually larger than a single chunk, so they are placed as multi-chunk features once all the chunks
+//! they touch have been generated.
+
+use std::{ffi::OsStr, fs};
+
+use bevy::{prelude::*, utils::HashSet};
+use cosmos_core::{
+    block::{block_face::BlockFace, Block},
+    events::block_events::BlockChangedEvent,
+    physics::location::Location,
+    registry::{create_registry, identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        chunk::CHUNK_DIMENSIONS,
+        coordinates::{BlockCoordinate, ChunkCoordinate, CoordinateType, UnboundBlockCoordinate, UnboundCoordinateType},
+        planet::{generation::biome::Biome, ChunkFaces, Planet},
+        rotate,
+        station::prefab::PrefabRegion,
+        Structure,
+    },
+};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::init::init_world::ServerSeed;
+
+use super::{
+    biome::GenerateChunkFeaturesEvent,
+    biosphere_generation::BiosphereGenerationSet,
+    multi_chunk_features::{MultiChunkFeature, PlaceMultiChunkFeatureEvent, QueueMultiChunkFeatureEvent},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RawOutpost {
+    /// The unlocalized names of the biomes this outpost can generate in
+    biomes: Vec<String>,
+    /// On average, one in this many chunks on the surface of a valid biome will have this outpost
+    rarity: u64,
+    /// The block used to fill in the ground beneath this outpost
+    foundation: String,
+    /// How far down the foundation can go before giving up on reaching the ground
+    foundation_depth: UnboundCoordinateType,
+    regions: Vec<PrefabRegion>,
+}
+
+#[derive(Debug, Clone)]
+/// A premade settlement that can generate on the surface of planets
+pub struct Outpost {
+    id: u16,
+    unlocalized_name: String,
+    biomes: Vec<String>,
+    rarity: u64,
+    foundation: String,
+    foundation_depth: UnboundCoordinateType,
+    regions: Vec<PrefabRegion>,
+}
+
+impl Identifiable for Outpost {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+impl Outpost {
+    /// The (min, max) corners of everything this outpost will touch, including its foundation
+    fn bounds(&self) -> (UnboundBlockCoordinate, UnboundBlockCoordinate) {
+        let mut min = UnboundBlockCoordinate::new(0, -self.foundation_depth, 0);
+        let mut max = UnboundBlockCoordinate::new(0, 0, 0);
+
+        for region in self.regions.iter() {
+            for corner in [region.from, region.to] {
+                min = UnboundBlockCoordinate::new(min.x.min(corner.x), min.y.min(corner.y), min.z.min(corner.z));
+                max = UnboundBlockCoordinate::new(max.x.max(corner.x), max.y.max(corner.y), max.z.max(corner.z));
+            }
+        }
+
+        (min, max)
+    }
+}
+
+fn load_outposts(mut outposts: ResMut<Registry<Outpost>>, blocks: Res<Registry<Block>>) {
+    for entry in WalkDir::new("assets/cosmos/outposts").max_depth(1) {
+        let Ok(entry) = entry else {
+            continue;
+        };
+
+        let path = entry.path();
+        if path.is_dir() || path.extension().and_then(OsStr::to_str) != Some("json") {
+            continue;
+        }
+
+        let Some(name) = path.file_stem().and_then(OsStr::to_str) else {
+            continue;
+        };
+
+        let outpost_json = fs::read(path).unwrap_or_else(|e| panic!("Unable to read outpost file {path:?}\n{e:?}"));
+
+        let outpost =
+            serde_json::from_slice::<RawOutpost>(&outpost_json).unwrap_or_else(|e| panic!("Invalid outpost json {path:?}\n{e:?}"));
+
+        if let Some(block) = outpost
+            .regions
+            .iter()
+            .map(|r| &r.block)
+            .chain(std::iter::once(&outpost.foundation))
+            .find(|b| !blocks.contains(b))
+        {
+            error!("Unable to find block with id matching {block:?} in outpost {path:?}");
+            continue;
+        }
+
+        outposts.register(Outpost {
+            id: 0,
+            unlocalized_name: format!("cosmos:{name}"),
+            biomes: outpost.biomes,
+            rarity: outpost.rarity.max(1),
+            foundation: outpost.foundation,
+            foundation_depth: outpost.foundation_depth.max(0),
+            regions: outpost.regions,
+        });
+    }
+
+    info!("Loaded {} outposts", outposts.iter().count());
+}
+
+/// Decides which chunks will have outposts, and queues them to be placed once all the chunks they touch are generated.
+fn flag_outposts(
+    mut evr_generate_features: EventReader<GenerateChunkFeaturesEvent>,
+    mut evw_queue_feature: EventWriter<QueueMultiChunkFeatureEvent>,
+    q_structure: Query<(&Location, &Structure)>,
+    outposts: Res<Registry<Outpost>>,
+    biomes: Res<Registry<Biome>>,
+    blocks: Res<Registry<Block>>,
+    seed: Res<ServerSeed>,
+) {
+    for ev in evr_generate_features.read() {
+        let Ok((location, structure)) = q_structure.get(ev.structure_entity) else {
+            continue;
+        };
+
+        let Structure::Dynamic(planet) = structure else {
+            continue;
+        };
+
+        let first_block_coords = ev.chunk.first_structure_block();
+        let s_dimension = planet.block_dimensions();
+        let s_dims = structure.block_dimensions();
+
+        // Outposts would look strange bent around the edge of a planet
+        let ChunkFaces::Face(block_up) = Planet::chunk_planet_faces(first_block_coords, s_dimension) else {
+            continue;
+        };
+
+        let abs_coords = location.absolute_coords_f64();
+        let (sx, sy, sz) = (
+            abs_coords.x + first_block_coords.x as f64,
+            abs_coords.y + first_block_coords.y as f64,
+            abs_coords.z + first_block_coords.z as f64,
+        );
+
+        for outpost in outposts.iter() {
+            if !outpost
+                .biomes
+                .iter()
+                .filter_map(|b| biomes.from_id(b))
+                .any(|b| ev.included_biomes.contains(&b.id()))
+            {
+                continue;
+            }
+
+            let outpost_seed = seed.planet_feature_seed(location.sector(), outpost.unlocalized_name());
+
+            if outpost_seed.chaos_hash(sx, sy, sz).unsigned_abs() % outpost.rarity != 0 {
+                continue;
+            }
+
+            let x = outpost_seed.chaos_hash(sx + 391.0, sy + 17.0, sz - 557.0).unsigned_abs() % CHUNK_DIMENSIONS;
+            let z = outpost_seed.chaos_hash(sx - 823.0, sy + 211.0, sz + 97.0).unsigned_abs() % CHUNK_DIMENSIONS;
+
+            let Some(origin) = find_surface(structure, first_block_coords, x, z, block_up, s_dims, &blocks) else {
+                continue;
+            };
+
+            let Some(chunks) = outpost_chunks(outpost, origin, block_up, s_dims, s_dimension) else {
+                continue;
+            };
+
+            evw_queue_feature.send(QueueMultiChunkFeatureEvent {
+                structure_entity: ev.structure_entity,
+                feature: MultiChunkFeature {
+                    feature: outpost.unlocalized_name().to_owned(),
+                    origin,
+                    block_up,
+                    chunks,
+                },
+            });
+
+            // Only one outpost per chunk, otherwise they would generate inside each other
+            break;
+        }
+    }
+}
+
+/// Finds the first air block above solid ground in this column of the chunk.
+///
+/// Returns `None` if the ground isn't within this chunk, or if the ground is covered by a fluid.
+fn find_surface(
+    structure: &Structure,
+    first_block_coords: BlockCoordinate,
+    x: CoordinateType,
+    z: CoordinateType,
+    block_up: BlockFace,
+    s_dims: BlockCoordinate,
+    blocks: &Registry<Block>,
+) -> Option<BlockCoordinate> {
+    let coords: BlockCoordinate = match block_up {
+        BlockFace::Back | BlockFace::Front => (first_block_coords.x + x, first_block_coords.y + z, first_block_coords.z),
+        BlockFace::Top | BlockFace::Bottom => (first_block_coords.x + x, first_block_coords.y, first_block_coords.z + z),
+        BlockFace::Right | BlockFace::Left => (first_block_coords.x, first_block_coords.y + x, first_block_coords.z + z),
+    }
+    .into();
+
+    let air = blocks.from_id("cosmos:air")?;
+
+    let mut height = CHUNK_DIMENSIONS as UnboundCoordinateType - 1;
+    while height >= 0
+        && rotate(coords, UnboundBlockCoordinate::new(0, height, 0), s_dims, block_up)
+            .map(|rotated| structure.block_at(rotated, blocks) == air)
+            .unwrap_or(false)
+    {
+        height -= 1;
+    }
+
+    // Either the whole column is air or the ground continues into the chunk above, so the surface isn't in this chunk.
+    if height < 0 || height == CHUNK_DIMENSIONS as UnboundCoordinateType - 1 {
+        return None;
+    }
+
+    let ground = rotate(coords, UnboundBlockCoordinate::new(0, height, 0), s_dims, block_up).ok()?;
+    if structure.block_at(ground, blocks).is_fluid() {
+        return None;
+    }
+
+    rotate(coords, UnboundBlockCoordinate::new(0, height + 1, 0), s_dims, block_up).ok()
+}
+
+/// Every chunk this outpost will touch if placed at this origin.
+///
+/// Returns `None` if the outpost would go past the edge of the planet or wrap around onto another face.
+fn outpost_chunks(
+    outpost: &Outpost,
+    origin: BlockCoordinate,
+    block_up: BlockFace,
+    s_dims: BlockCoordinate,
+    s_dimension: CoordinateType,
+) -> Option<HashSet<ChunkCoordinate>> {
+    let (min, max) = outpost.bounds();
+
+    let mut min_chunk = ChunkCoordinate::new(CoordinateType::MAX, CoordinateType::MAX, CoordinateType::MAX);
+    let mut max_chunk = ChunkCoordinate::new(0, 0, 0);
+
+    for dz in [min.z, max.z] {
+        for dy in [min.y, max.y] {
+            for dx in [min.x, max.x] {
+                let corner = rotate(origin, UnboundBlockCoordinate::new(dx, dy, dz), s_dims, block_up).ok()?;
+                let chunk = ChunkCoordinate::for_block_coordinate(corner);
+
+                min_chunk = ChunkCoordinate::new(min_chunk.x.min(chunk.x), min_chunk.y.min(chunk.y), min_chunk.z.min(chunk.z));
+                max_chunk = ChunkCoordinate::new(max_chunk.x.max(chunk.x), max_chunk.y.max(chunk.y), max_chunk.z.max(chunk.z));
+            }
+        }
+    }
+
+    let mut chunks = HashSet::new();
+
+    for z in min_chunk.z..=max_chunk.z {
+        for y in min_chunk.y..=max_chunk.y {
+            for x in min_chunk.x..=max_chunk.x {
+                let chunk = ChunkCoordinate::new(x, y, z);
+
+                if !matches!(Planet::chunk_planet_faces(chunk.first_structure_block(), s_dimension), ChunkFaces::Face(face) if face == block_up)
+                {
+                    return None;
+                }
+
+                chunks.insert(chunk);
+            }
+        }
+    }
+
+    Some(chunks)
+}
+
+fn place_outposts(
+    mut evr_place_feature: EventReader<PlaceMultiChunkFeatureEvent>,
+    mut evw_block_changed: EventWriter<BlockChangedEvent>,
+    mut q_structure: Query<&mut Structure>,
+    outposts: Res<Registry<Outpost>>,
+    blocks: Res<Registry<Block>>,
+) {
+    for ev in evr_place_feature.read() {
+        let Some(outpost) = outposts.from_id(&ev.feature.feature) else {
+            continue;
+        };
+
+        let Ok(mut structure) = q_structure.get_mut(ev.structure_entity) else {
+            continue;
+        };
+
+        let MultiChunkFeature { origin, block_up, .. } = ev.feature;
+        let s_dims = structure.block_dimensions();
+
+        let (Some(air), Some(foundation)) = (blocks.from_id("cosmos:air"), blocks.from_id(&outpost.foundation)) else {
+            continue;
+        };
+
+        let (min, max) = outpost.bounds();
+
+        for dz in min.z..=max.z {
+            for dx in min.x..=max.x {
+                for dy in (-outpost.foundation_depth..0).rev() {
+                    let Ok(coords) = rotate(origin, UnboundBlockCoordinate::new(dx, dy, dz), s_dims, block_up) else {
+                        break;
+                    };
+
+                    let existing = structure.block_at(coords, &blocks);
+                    if existing != air && !existing.is_fluid() {
+                        break;
+                    }
+
+                    structure.set_block_at(coords, foundation, block_up.into(), &blocks, Some(&mut evw_block_changed));
+                }
+            }
+        }
+
+        for region in outpost.regions.iter() {
+            let Some(block) = blocks.from_id(&region.block) else {
+                continue;
+            };
+
+            for delta in region.iter_coords() {
+                if let Ok(coords) = rotate(origin, delta, s_dims, block_up) {
+                    structure.set_block_at(coords, block, block_up.into(), &blocks, Some(&mut evw_block_changed));
+                }
+            }
+        }
+
+        info!(
+            "Generated outpost {} at {origin} on planet {:?}.",
+            outpost.unlocalized_name(),
+            ev.structure_entity
+        );
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    create_registry::<Outpost>(app, "cosmos:outposts");
+
+    app.add_systems(OnEnter(GameState::PostLoading), load_outposts).add_systems(
+        Update,
+        (
+            flag_outposts
+                .in_set(BiosphereGenerationSet::GenerateChunkFeatures)
+                .ambiguous_with(BiosphereGenerationSet::GenerateChunkFeatures),
+            place_outposts
+                .in_set(BiosphereGenerationSet::GenerateMultiChunkFeatures)
+                .ambiguous_with(BiosphereGenerationSet::GenerateMultiChunkFeatures),
+        ),
+    );
+}
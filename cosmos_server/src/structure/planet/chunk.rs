@@ -9,16 +9,30 @@ use bevy::{
         system::{Commands, ResMut},
     },
     log::warn,
-    prelude::{App, Component, IntoSystemConfigs, Query, With},
+    prelude::{App, Component, Event, EventReader, EventWriter, IntoSystemConfigs, Query, Resource, With},
+    tasks::{AsyncComputeTaskPool, Task},
+    utils::{HashMap, HashSet},
 };
 use bevy_renet2::renet2::{ClientId, RenetServer};
 use cosmos_core::{
-    netty::{cosmos_encoder, server_reliable_messages::ServerReliableMessages, system_sets::NetworkingSystemsSet, NettyChannelServer},
+    events::block_events::BlockChangedEvent,
+    netty::{
+        cosmos_encoder::{self, Compression},
+        network_stats::{NettyMessage, NettyServerMessages},
+        server_reliable_messages::{SerializedChunk, ServerReliableMessages},
+        system_sets::NetworkingSystemsSet,
+        NettyChannelServer,
+    },
     structure::{
-        chunk::{netty::SerializedBlockData, Chunk, ChunkEntity},
+        chunk::{
+            netty::{SerializedBlockData, SerializedChunkBlockData},
+            Chunk, ChunkEntity,
+        },
+        coordinates::{ChunkBlockCoordinate, ChunkCoordinate},
         Structure,
     },
 };
+use futures_lite::future;
 
 use crate::{
     persistence::{
@@ -53,7 +67,7 @@ pub enum SerializeChunkBlockDataSet {
     BeginSerialization,
     /// Populate the `SerializedChunkBlockData` component with block data players need to know about
     Serialize,
-    /// Sends the serialized block data + chunks to players
+    /// Serializes the chunks in the background, and sends them to players once they're done
     SendChunks,
 }
 
@@ -84,12 +98,97 @@ fn begin_serialization(
     }
 }
 
-fn send_chunks(
+/// Chunks will be grouped into batches until the batch's size exceeds this many bytes.
+///
+/// This keeps each message well within the reliable channel's budget.
+const CHUNK_BATCH_BYTE_BUDGET: usize = 64 * 1024;
+
+/// Chunks are large & mostly made of similar blocks, so the slower but better compression is worth it.
+const CHUNK_COMPRESSION: Compression = Compression::Zstd(3);
+
+/// Everything needed to serialize a chunk off the main thread
+struct ChunkToSerialize {
+    chunk: Chunk,
+    serialized_block_data: Option<SerializedChunkBlockData>,
+    block_entities: HashMap<(u16, ChunkBlockCoordinate), Entity>,
+}
+
+struct ChunkSerializationTask {
+    structure_entity: Entity,
+    client_ids: Vec<ClientId>,
+    /// Chunks that had blocks changed while they were being serialized. These are out of date, and must be sent again.
+    changed_chunks: HashSet<ChunkCoordinate>,
+    chunks: HashSet<ChunkCoordinate>,
+    task: Task<Vec<NettyMessage>>,
+}
+
+#[derive(Resource, Default)]
+/// Chunks are serialized & compressed in the [`AsyncComputeTaskPool`], since doing this for large structures
+/// would otherwise freeze the server.
+struct SerializingChunks(Vec<ChunkSerializationTask>);
+
+#[derive(Event, Debug)]
+/// Sent once a batch of chunks has finished being serialized, and is ready to be sent to the clients.
+pub struct ChunksSerializedEvent {
+    /// The structure these chunks are a part of
+    pub structure_entity: Entity,
+    /// The clients that requested these chunks
+    pub client_ids: Vec<ClientId>,
+    /// Every serialized [`ServerReliableMessages::ChunkBatch`] message that needs to be sent
+    pub messages: Vec<NettyMessage>,
+}
+
+fn serialize_chunk_batches(structure_entity: Entity, mut chunks: Vec<ChunkToSerialize>) -> Vec<NettyMessage> {
+    // Keeps the order chunks are sent in consistent
+    chunks.sort_by_key(|c| {
+        let coords = c.chunk.chunk_coordinates();
+        (coords.y, coords.z, coords.x)
+    });
+
+    let mut messages = vec![];
+    let mut batch = vec![];
+    let mut batch_size = 0;
+
+    for chunk in chunks {
+        let serialized_chunk = SerializedChunk {
+            serialized_chunk: cosmos_encoder::serialize_compressed(&chunk.chunk, CHUNK_COMPRESSION),
+            serialized_block_data: chunk.serialized_block_data,
+            block_entities: chunk.block_entities,
+        };
+
+        let chunk_size = serialized_chunk.serialized_chunk.len();
+
+        if !batch.is_empty() && batch_size + chunk_size > CHUNK_BATCH_BYTE_BUDGET {
+            messages.push(NettyMessage::new(&ServerReliableMessages::ChunkBatch {
+                structure_entity,
+                chunks: std::mem::take(&mut batch),
+            }));
+            batch_size = 0;
+        }
+
+        batch_size += chunk_size;
+        batch.push(serialized_chunk);
+    }
+
+    if !batch.is_empty() {
+        messages.push(NettyMessage::new(&ServerReliableMessages::ChunkBatch {
+            structure_entity,
+            chunks: batch,
+        }));
+    }
+
+    messages
+}
+
+fn begin_serializing_chunks(
     mut commands: Commands,
     mut q_chunks_need_serialized: Query<(Entity, &ChunkNeedsSent, Option<&mut SerializedBlockData>, &ChunkEntity)>,
     q_structure: Query<&Structure>,
-    mut server: ResMut<RenetServer>,
+    mut serializing: ResMut<SerializingChunks>,
 ) {
+    // Chunks going to the same clients for the same structure are batched together
+    let mut batches: HashMap<(Entity, Vec<ClientId>), Vec<ChunkToSerialize>> = HashMap::default();
+
     for (ent, needs_sent, serialized_chunk_block_data, chunk_ent) in q_chunks_need_serialized.iter_mut() {
         commands.entity(ent).remove::<ChunkNeedsSent>().insert(Name::new("Chunk Entity"));
 
@@ -100,28 +199,96 @@ fn send_chunks(
 
         let chunk = structure.chunk_from_entity(&ent).expect("Chunk missing entity despite having one");
 
-        let message = cosmos_encoder::serialize(&ServerReliableMessages::ChunkData {
-            structure_entity: chunk_ent.structure_entity,
-            serialized_chunk: cosmos_encoder::serialize(chunk),
-            serialized_block_data: serialized_chunk_block_data.map(|mut x| x.take_save_data()),
-            block_entities: chunk.all_block_data_entities().clone(),
+        chunk.all_block_data_entities().iter().for_each(|(_, &block_data_ent)| {
+            commands.entity(block_data_ent).remove::<BlockDataNeedsSaved>();
         });
 
-        structure
-            .chunk_at(chunk_ent.chunk_location)
-            .expect("Chunk must still be loaded")
-            .all_block_data_entities()
-            .iter()
-            .for_each(|(_, &block_data_ent)| {
-                commands.entity(block_data_ent).remove::<BlockDataNeedsSaved>();
+        let mut client_ids = needs_sent.client_ids.clone();
+        client_ids.sort();
+        client_ids.dedup();
+
+        batches
+            .entry((chunk_ent.structure_entity, client_ids))
+            .or_default()
+            .push(ChunkToSerialize {
+                chunk: chunk.clone(),
+                serialized_block_data: serialized_chunk_block_data.map(|mut x| x.take_save_data()),
+                block_entities: chunk.all_block_data_entities().clone(),
             });
+    }
+
+    let thread_pool = AsyncComputeTaskPool::get();
+
+    for ((structure_entity, client_ids), chunks) in batches {
+        let chunk_coords = chunks.iter().map(|c| c.chunk.chunk_coordinates()).collect();
+
+        let task = thread_pool.spawn(async move { serialize_chunk_batches(structure_entity, chunks) });
 
-        // Avoids 1 unnecessary clone
-        for client_id in needs_sent.client_ids.iter().skip(1).copied() {
-            server.send_message(client_id, NettyChannelServer::Reliable, message.clone());
+        serializing.0.push(ChunkSerializationTask {
+            structure_entity,
+            client_ids,
+            changed_chunks: Default::default(),
+            chunks: chunk_coords,
+            task,
+        });
+    }
+}
+
+fn track_chunks_changed_while_serializing(
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    mut serializing: ResMut<SerializingChunks>,
+) {
+    for ev in evr_block_changed.read() {
+        let coords = ev.block.chunk_coords();
+
+        for task in serializing
+            .0
+            .iter_mut()
+            .filter(|t| t.structure_entity == ev.block.structure() && t.chunks.contains(&coords))
+        {
+            task.changed_chunks.insert(coords);
         }
-        if let Some(client_id) = needs_sent.client_ids.first().copied() {
-            server.send_message(client_id, NettyChannelServer::Reliable, message);
+    }
+}
+
+fn poll_serializing_chunks(
+    mut serializing: ResMut<SerializingChunks>,
+    q_structure: Query<&Structure>,
+    mut evw_chunks_serialized: EventWriter<ChunksSerializedEvent>,
+    mut commands: Commands,
+) {
+    serializing.0.retain_mut(|serializing| {
+        let Some(messages) = future::block_on(future::poll_once(&mut serializing.task)) else {
+            return true;
+        };
+
+        evw_chunks_serialized.send(ChunksSerializedEvent {
+            structure_entity: serializing.structure_entity,
+            client_ids: serializing.client_ids.clone(),
+            messages,
+        });
+
+        // The clients got old versions of these chunks, so the updated ones need to be sent after them
+        if let Ok(structure) = q_structure.get(serializing.structure_entity) {
+            for &coords in serializing.changed_chunks.iter() {
+                if let Some(chunk_ent) = structure.chunk_entity(coords) {
+                    commands.entity(chunk_ent).insert(ChunkNeedsSent {
+                        client_ids: serializing.client_ids.clone(),
+                    });
+                }
+            }
+        }
+
+        false
+    });
+}
+
+fn send_serialized_chunks(mut evr_chunks_serialized: EventReader<ChunksSerializedEvent>, mut server: ResMut<RenetServer>) {
+    for ev in evr_chunks_serialized.read() {
+        for message in ev.messages.iter() {
+            for &client_id in ev.client_ids.iter() {
+                server.send_netty_message(client_id, NettyChannelServer::Reliable, message.clone());
+            }
         }
     }
 }
@@ -144,7 +311,16 @@ pub(super) fn register(app: &mut App) {
         Update,
         (
             begin_serialization.in_set(SerializeChunkBlockDataSet::BeginSerialization),
-            send_chunks.in_set(SerializeChunkBlockDataSet::SendChunks),
+            (
+                begin_serializing_chunks,
+                track_chunks_changed_while_serializing,
+                poll_serializing_chunks,
+                send_serialized_chunks,
+            )
+                .chain()
+                .in_set(SerializeChunkBlockDataSet::SendChunks),
         ),
-    );
+    )
+    .init_resource::<SerializingChunks>()
+    .add_event::<ChunksSerializedEvent>();
 }
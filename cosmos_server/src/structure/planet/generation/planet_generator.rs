@@ -10,8 +10,10 @@ use cosmos_core::{
     ecs::NeedsDespawned,
     entities::player::Player,
     netty::{
-        cosmos_encoder, server_reliable_messages::ServerReliableMessages, system_sets::NetworkingSystemsSet, NettyChannelServer,
-        NoSendEntity,
+        network_stats::{NettyMessage, NettyServerMessages},
+        server_reliable_messages::ServerReliableMessages,
+        system_sets::NetworkingSystemsSet,
+        NettyChannelServer, NoSendEntity,
     },
     physics::location::Location,
     state::GameState,
@@ -155,7 +157,7 @@ fn get_requested_chunk(
                             for client_id in client_ids {
                                 serialized.push((
                                     client_id,
-                                    cosmos_encoder::serialize(&ServerReliableMessages::EmptyChunk {
+                                    NettyMessage::new(&ServerReliableMessages::EmptyChunk {
                                         structure_entity,
                                         coords: chunk_coords,
                                     }),
@@ -185,7 +187,7 @@ fn get_requested_chunk(
     }
 
     for (client_id, serialized) in serialized {
-        server.send_message(client_id, NettyChannelServer::Reliable, serialized);
+        server.send_netty_message(client_id, NettyChannelServer::Reliable, serialized);
     }
 
     for (structure_entity, chunk_coords, client_ids) in todo {
@@ -21,7 +21,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::persistence::{
     loading::{LoadingSystemSet, NeedsLoaded},
-    saving::{NeedsSaved, SavingSystemSet, SAVING_SCHEDULE},
+    saving::{NeedsSaved, SavingSystemSet, WritingSaveFiles, SAVING_SCHEDULE},
     EntityId, SaveFileIdentifier, SerializedData,
 };
 
@@ -95,6 +95,7 @@ fn add_entity_id_to_new_structures(created: Query<Entity, (Added<Structure>, Wit
 fn populate_chunks(
     q_chunk_needs_populated: Query<(Entity, &ChunkNeedsPopulated)>,
     q_structure: Query<(&EntityId, Option<&SaveFileIdentifier>, &Location, &RapierContextEntityLink)>,
+    mut writing: ResMut<WritingSaveFiles>,
     mut commands: Commands,
 ) {
     for (entity, needs) in q_chunk_needs_populated.iter() {
@@ -115,7 +116,10 @@ fn populate_chunks(
             )
         };
 
-        if let Ok(chunk) = fs::read(svi.get_save_file_path()) {
+        let path = svi.get_save_file_path();
+        writing.finish_writing(&path);
+
+        if let Ok(chunk) = fs::read(&path) {
             if chunk.is_empty() {
                 // This can happen if the file is currently being saved, just try again next frame or whenever it's available
                 continue;
@@ -2,8 +2,11 @@ use bevy::prelude::*;
 use bevy_renet2::renet2::RenetServer;
 use cosmos_core::{
     netty::{
-        cosmos_encoder, server_reliable_messages::ServerReliableMessages, sync::server_entity_syncing::RequestedEntityEvent,
-        system_sets::NetworkingSystemsSet, NettyChannelServer,
+        network_stats::{NettyMessage, NettyServerMessages},
+        server_reliable_messages::ServerReliableMessages,
+        sync::server_entity_syncing::RequestedEntityEvent,
+        system_sets::NetworkingSystemsSet,
+        NettyChannelServer,
     },
     physics::location::Location,
     structure::{
@@ -23,10 +26,10 @@ fn on_request_planet(
                 panic!("Planet must be dynamic!");
             };
 
-            server.send_message(
+            server.send_netty_message(
                 ev.client_id,
                 NettyChannelServer::Reliable,
-                cosmos_encoder::serialize(&ServerReliableMessages::Planet {
+                NettyMessage::new(&ServerReliableMessages::Planet {
                     entity: ev.entity,
                     dimensions: dynamic_planet.chunk_dimensions(),
                     planet: *planet,
@@ -5,7 +5,12 @@ use bevy_renet2::renet2::RenetServer;
 use cosmos_core::{
     block::{block_events::BlockInteractEvent, Block},
     entities::player::Player,
-    netty::{cosmos_encoder, server_reliable_messages::ServerReliableMessages, system_sets::NetworkingSystemsSet, NettyChannelServer},
+    netty::{
+        network_stats::{NettyMessage, NettyServerMessages},
+        server_reliable_messages::ServerReliableMessages,
+        system_sets::NetworkingSystemsSet,
+        NettyChannelServer,
+    },
     registry::{identifiable::Identifiable, Registry},
     state::GameState,
     structure::{
@@ -42,9 +47,9 @@ fn interact_with_block(
 
 fn sync_enter_build_mode(mut server: ResMut<RenetServer>, mut event_reader: EventReader<EnterBuildModeEvent>) {
     for ev in event_reader.read() {
-        server.broadcast_message(
+        server.broadcast_netty_message(
             NettyChannelServer::Reliable,
-            cosmos_encoder::serialize(&ServerReliableMessages::PlayerEnterBuildMode {
+            NettyMessage::new(&ServerReliableMessages::PlayerEnterBuildMode {
                 player_entity: ev.player_entity,
                 structure_entity: ev.structure_entity,
             }),
@@ -54,9 +59,9 @@ fn sync_enter_build_mode(mut server: ResMut<RenetServer>, mut event_reader: Even
 
 fn sync_exit_build_mode(mut server: ResMut<RenetServer>, mut event_reader: EventReader<ExitBuildModeEvent>) {
     for ev in event_reader.read() {
-        server.broadcast_message(
+        server.broadcast_netty_message(
             NettyChannelServer::Reliable,
-            cosmos_encoder::serialize(&ServerReliableMessages::PlayerExitBuildMode {
+            NettyMessage::new(&ServerReliableMessages::PlayerExitBuildMode {
                 player_entity: ev.player_entity,
             }),
         );
@@ -65,10 +70,10 @@ fn sync_exit_build_mode(mut server: ResMut<RenetServer>, mut event_reader: Event
 
 fn sync_build_mode(changed_build_modes: Query<(&Player, &BuildMode), Changed<BuildMode>>, mut server: ResMut<RenetServer>) {
     for (player, build_mode) in changed_build_modes.iter() {
-        server.send_message(
+        server.send_netty_message(
             player.id(),
             NettyChannelServer::Reliable,
-            cosmos_encoder::serialize(&ServerReliableMessages::UpdateBuildMode { build_mode: *build_mode }),
+            NettyMessage::new(&ServerReliableMessages::UpdateBuildMode { build_mode: *build_mode }),
         );
     }
 }
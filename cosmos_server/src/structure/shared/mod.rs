@@ -15,6 +15,9 @@ use cosmos_core::{
 
 pub mod build_mode;
 pub mod melt_down;
+pub mod move_block_data;
+pub mod structure_integrity;
+pub mod transform_structure;
 
 fn on_melting_down(
     mut commands: Commands,
@@ -74,4 +77,7 @@ pub(super) fn register(app: &mut App) {
 
     build_mode::register(app);
     melt_down::register(app);
+    move_block_data::register(app);
+    structure_integrity::register(app);
+    transform_structure::register(app);
 }
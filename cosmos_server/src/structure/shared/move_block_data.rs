@@ -0,0 +1,113 @@
+//! Moves existing block data entities onto blocks that were just placed.
+//!
+//! When blocks are moved around (such as when welding or rotating structures), the new blocks are placed normally so
+//! every system sees the block changes. That also makes those systems create brand new block data for them (an
+//! empty inventory, etc.), so the original block data has to be put in place after that has happened.
+
+use bevy::{core::FrameCount, prelude::*};
+use cosmos_core::{
+    block::{
+        block_events::BlockEventsSet,
+        data::{BlockData, BlockDataIdentifier},
+    },
+    events::block_events::BlockDataChangedEvent,
+    netty::system_sets::NetworkingSystemsSet,
+    prelude::{ChunkCoordinate, Structure, StructureBlock},
+    state::GameState,
+};
+
+/// Block data created for new blocks exists by the end of the next frame, so the moved data has to wait until after that
+const FRAMES_BEFORE_MOVING_DATA: u32 = 2;
+
+struct PendingDataMove {
+    data_entity: Entity,
+    block: StructureBlock,
+    block_id: u16,
+    frame: u32,
+}
+
+#[derive(Resource, Default)]
+/// Block data that is waiting to be put onto the blocks it was moved to
+pub(crate) struct PendingBlockDataMoves(Vec<PendingDataMove>);
+
+impl PendingBlockDataMoves {
+    /// Moves this block data entity onto the block at `block` once that block's new data has been created.
+    ///
+    /// The block data entity should already have been removed from its old block via `set_block_data_entity`,
+    /// and unparented if its old chunk may be despawned.
+    pub(crate) fn queue(&mut self, data_entity: Entity, block: StructureBlock, block_id: u16, frame: &FrameCount) {
+        self.0.push(PendingDataMove {
+            data_entity,
+            block,
+            block_id,
+            frame: frame.0 + FRAMES_BEFORE_MOVING_DATA,
+        });
+    }
+}
+
+/// Replaces the block data created for the new blocks with the moved block data
+fn move_block_data(
+    mut commands: Commands,
+    frame: Res<FrameCount>,
+    mut pending: ResMut<PendingBlockDataMoves>,
+    mut q_structure: Query<&mut Structure>,
+    mut q_block_data: Query<&mut BlockData>,
+    mut evw_block_data_changed: EventWriter<BlockDataChangedEvent>,
+) {
+    pending.0.retain(|data_move| {
+        if data_move.frame > frame.0 {
+            return true;
+        }
+
+        let coords = data_move.block.coords();
+
+        let Ok(mut structure) = q_structure.get_mut(data_move.block.structure()) else {
+            commands.entity(data_move.data_entity).despawn_recursive();
+            return false;
+        };
+
+        if structure.block_id_at(coords) != data_move.block_id {
+            // The block was changed before its data could be moved, so the data no longer has a block
+            commands.entity(data_move.data_entity).despawn_recursive();
+            return false;
+        }
+
+        let Some(chunk_entity) = structure.chunk_entity(ChunkCoordinate::for_block_coordinate(coords)) else {
+            // The chunk the block was placed in hasn't been given an entity yet
+            return true;
+        };
+
+        if let Some(created_data) = structure.block_data(coords) {
+            if created_data != data_move.data_entity {
+                commands.entity(created_data).despawn_recursive();
+            }
+        }
+
+        structure.set_block_data_entity(coords, Some(data_move.data_entity));
+        commands.entity(data_move.data_entity).set_parent(chunk_entity);
+
+        if let Ok(mut block_data) = q_block_data.get_mut(data_move.data_entity) {
+            block_data.identifier = BlockDataIdentifier {
+                block: data_move.block,
+                block_id: data_move.block_id,
+            };
+        }
+
+        evw_block_data_changed.send(BlockDataChangedEvent {
+            block_data_entity: Some(data_move.data_entity),
+            block: data_move.block,
+        });
+
+        false
+    });
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<PendingBlockDataMoves>().add_systems(
+        Update,
+        move_block_data
+            .in_set(BlockEventsSet::PostProcessEvents)
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,397 @@
+//! Splits sections of ships & stations that are no longer connected to their core into their own ships.
+//!
+//! Whenever blocks are removed, the blocks that were next to them are flood filled from at the same time, one
+//! block per fill at a time. Fills that run into each other are merged. Once every fill but one has run out of
+//! blocks, the finished fills are exactly the sections that may have been cut off - so only the smaller side of
+//! a cut is ever searched, and a single destroyed block in a huge ship rarely searches more than a few blocks.
+
+use std::collections::VecDeque;
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_rapier3d::prelude::Velocity;
+use cosmos_core::{
+    block::{
+        block_direction::ALL_BLOCK_DIRECTIONS, block_events::BlockEventsSet, block_rotation::BlockRotation, blocks::AIR_BLOCK_ID, Block,
+    },
+    events::block_events::BlockChangedEvent,
+    netty::system_sets::NetworkingSystemsSet,
+    physics::location::Location,
+    prelude::{BlockCoordinate, ChunkCoordinate},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        chunk::CHUNK_DIMENSIONS,
+        coordinates::CoordinateType,
+        events::StructureLoadedEvent,
+        full_structure::FullStructure,
+        shared::MeltingDown,
+        ship::{ship_builder::TShipBuilder, Ship},
+        station::Station,
+        structure_iterator::ChunkIteratorResult,
+        ChunkInitEvent, Structure,
+    },
+};
+
+use crate::structure::ship::server_ship_builder::ServerShipBuilder;
+
+/// Sections connected to one of these blocks are never split off
+const CORE_BLOCKS: [&str; 2] = ["cosmos:ship_core", "cosmos:station_core"];
+
+struct Fill {
+    frontier: VecDeque<BlockCoordinate>,
+    blocks: Vec<BlockCoordinate>,
+}
+
+/// Finds every section that is no longer connected to the `core` after the `removed` blocks were removed.
+///
+/// If there is no core, the section that is still being searched once all the others are done is kept.
+fn find_disconnected_sections(
+    removed: &[BlockCoordinate],
+    core: Option<BlockCoordinate>,
+    has_block: impl Fn(BlockCoordinate) -> bool,
+) -> Vec<Vec<BlockCoordinate>> {
+    let has_block = &has_block;
+    let neighbors = move |coords: BlockCoordinate| {
+        ALL_BLOCK_DIRECTIONS
+            .into_iter()
+            .filter_map(move |dir| coords.step(dir).ok())
+            .filter(move |&c| has_block(c))
+    };
+
+    // Which fill first reached each block. Fills that have been merged point to the fill they were merged into.
+    let mut owner = HashMap::<BlockCoordinate, usize>::default();
+    let mut merged_into = vec![];
+    let mut fills = vec![];
+
+    for seed in removed.iter().flat_map(|&c| neighbors(c)) {
+        if owner.contains_key(&seed) {
+            continue;
+        }
+
+        owner.insert(seed, fills.len());
+        merged_into.push(fills.len());
+        fills.push(Some(Fill {
+            frontier: VecDeque::from([seed]),
+            blocks: vec![seed],
+        }));
+    }
+
+    if fills.len() <= 1 {
+        return vec![];
+    }
+
+    fn find(merged_into: &[usize], mut id: usize) -> usize {
+        while merged_into[id] != id {
+            id = merged_into[id];
+        }
+        id
+    }
+
+    let step = |id: usize, fills: &mut Vec<Option<Fill>>, owner: &mut HashMap<BlockCoordinate, usize>, merged_into: &mut Vec<usize>| {
+        let Some(coords) = fills[id].as_mut().and_then(|f| f.frontier.pop_front()) else {
+            return;
+        };
+
+        for neighbor in neighbors(coords) {
+            match owner.get(&neighbor).copied() {
+                None => {
+                    owner.insert(neighbor, id);
+                    let fill = fills[id].as_mut().expect("Only active fills are stepped");
+                    fill.frontier.push_back(neighbor);
+                    fill.blocks.push(neighbor);
+                }
+                Some(other) => {
+                    let other = find(merged_into, other);
+                    if other == id {
+                        continue;
+                    }
+
+                    let other_fill = fills[other].take().expect("Root fills always exist");
+                    let fill = fills[id].as_mut().expect("Only active fills are stepped");
+                    fill.frontier.extend(other_fill.frontier);
+                    fill.blocks.extend(other_fill.blocks);
+                    merged_into[other] = id;
+                }
+            }
+        }
+    };
+
+    let is_searching = |fill: &Option<Fill>| fill.as_ref().is_some_and(|f| !f.frontier.is_empty());
+
+    loop {
+        let searching = (0..fills.len()).filter(|&id| is_searching(&fills[id])).collect::<Vec<_>>();
+
+        if searching.len() <= 1 {
+            break;
+        }
+
+        for id in searching {
+            // This fill may have been merged into another one this round
+            if is_searching(&fills[id]) {
+                step(id, &mut fills, &mut owner, &mut merged_into);
+            }
+        }
+    }
+
+    let core_fill = core.and_then(|core| owner.get(&core)).map(|&id| find(&merged_into, id));
+    let still_searching = (0..fills.len()).find(|&id| is_searching(&fills[id]));
+
+    let keep = match (core_fill, still_searching) {
+        (Some(core_fill), Some(still_searching)) if core_fill != still_searching => {
+            // The section with the core was cut off from something bigger, so that bigger section has to be found entirely
+            while is_searching(&fills[still_searching]) {
+                step(still_searching, &mut fills, &mut owner, &mut merged_into);
+            }
+            Some(core_fill)
+        }
+        (Some(core_fill), _) => Some(core_fill),
+        // The core is somewhere in the section that wasn't fully searched
+        (None, Some(still_searching)) => Some(still_searching),
+        // Every section was searched & none of them had the core, so they're all disconnected from it
+        (None, None) if core.is_some() => None,
+        (None, None) => (0..fills.len())
+            .filter(|&id| fills[id].is_some())
+            .max_by_key(|&id| fills[id].as_ref().map(|f| f.blocks.len())),
+    };
+
+    fills
+        .into_iter()
+        .enumerate()
+        .filter(|(id, _)| Some(*id) != keep)
+        .filter_map(|(_, fill)| fill.map(|f| f.blocks))
+        .collect()
+}
+
+struct SplitBlock {
+    coords: BlockCoordinate,
+    block: u16,
+    rotation: BlockRotation,
+    health: f32,
+}
+
+#[derive(Resource, Default)]
+/// Blocks removed from ships & stations this frame
+struct RemovedBlocks(HashMap<Entity, Vec<BlockCoordinate>>);
+
+fn collect_removed_blocks(mut evr_block_changed: EventReader<BlockChangedEvent>, mut removed: ResMut<RemovedBlocks>) {
+    for ev in evr_block_changed.read() {
+        if ev.new_block == AIR_BLOCK_ID && ev.old_block != AIR_BLOCK_ID {
+            removed.0.entry(ev.block.structure()).or_default().push(ev.block.coords());
+        }
+    }
+}
+
+fn split_disconnected_sections(
+    mut commands: Commands,
+    mut removed: ResMut<RemovedBlocks>,
+    mut q_structure: Query<
+        (&mut Structure, &Location, &GlobalTransform, &Velocity),
+        (Or<(With<Ship>, With<Station>)>, Without<MeltingDown>),
+    >,
+    blocks: Res<Registry<Block>>,
+    mut evw_block_changed: EventWriter<BlockChangedEvent>,
+    mut evw_chunk_init: EventWriter<ChunkInitEvent>,
+    mut evw_structure_loaded: EventWriter<StructureLoadedEvent>,
+) {
+    let core_ids = CORE_BLOCKS
+        .iter()
+        .flat_map(|name| blocks.from_id(name))
+        .map(|b| b.id())
+        .collect::<HashSet<_>>();
+
+    for (structure_ent, removed) in std::mem::take(&mut removed.0) {
+        let Ok((mut structure, location, g_trans, velocity)) = q_structure.get_mut(structure_ent) else {
+            continue;
+        };
+
+        let Some(counts) = structure.block_counts() else {
+            continue;
+        };
+
+        let core = core_ids.iter().find_map(|&id| counts.positions(id).next());
+
+        let sections = find_disconnected_sections(&removed, core, |c| structure.is_within_blocks(c) && structure.has_block_at(c));
+
+        let rotation = g_trans.to_scale_rotation_translation().1;
+
+        for section in sections {
+            info!(
+                "{} blocks were disconnected from structure {structure_ent:?} - splitting them off.",
+                section.len()
+            );
+
+            let split_blocks = section
+                .iter()
+                .map(|&coords| SplitBlock {
+                    coords,
+                    block: structure.block_id_at(coords),
+                    rotation: structure.block_rotation(coords),
+                    health: structure.get_block_health(coords, &blocks),
+                })
+                .collect::<Vec<_>>();
+
+            let reference_position = structure.block_relative_position(section[0]);
+
+            for &coords in section.iter() {
+                structure.remove_block_at(coords, &blocks, Some(&mut evw_block_changed));
+            }
+
+            spawn_section(
+                &mut commands,
+                &split_blocks,
+                reference_position,
+                location,
+                rotation,
+                velocity,
+                &blocks,
+                &mut evw_chunk_init,
+                &mut evw_structure_loaded,
+            );
+        }
+    }
+}
+
+/// Creates a new ship out of these blocks, in the same place they were on the structure they were split from
+fn spawn_section(
+    commands: &mut Commands,
+    split_blocks: &[SplitBlock],
+    reference_position: Vec3,
+    location: &Location,
+    rotation: Quat,
+    velocity: &Velocity,
+    blocks: &Registry<Block>,
+    evw_chunk_init: &mut EventWriter<ChunkInitEvent>,
+    evw_structure_loaded: &mut EventWriter<StructureLoadedEvent>,
+) {
+    let min = split_blocks.iter().fold(BlockCoordinate::splat(CoordinateType::MAX), |min, b| {
+        BlockCoordinate::new(min.x.min(b.coords.x), min.y.min(b.coords.y), min.z.min(b.coords.z))
+    });
+    let max = split_blocks.iter().fold(BlockCoordinate::splat(0), |max, b| {
+        BlockCoordinate::new(max.x.max(b.coords.x), max.y.max(b.coords.y), max.z.max(b.coords.z))
+    });
+
+    let chunks = |extent: CoordinateType| (extent + 1).div_ceil(CHUNK_DIMENSIONS);
+
+    let mut structure = Structure::Full(FullStructure::new(ChunkCoordinate::new(
+        chunks(max.x - min.x),
+        chunks(max.y - min.y),
+        chunks(max.z - min.z),
+    )));
+
+    let local = |coords: BlockCoordinate| BlockCoordinate::new(coords.x - min.x, coords.y - min.y, coords.z - min.z);
+
+    // Offset from the old structure's center to the new structure's center, in the old structure's local space
+    let offset = reference_position - structure.block_relative_position(local(split_blocks[0].coords));
+
+    let mut entity_cmds = commands.spawn_empty();
+
+    let new_velocity = Velocity {
+        linvel: velocity.linvel + velocity.angvel.cross(rotation * offset),
+        angvel: velocity.angvel,
+    };
+
+    ServerShipBuilder::default().insert_ship(&mut entity_cmds, *location + rotation * offset, new_velocity, &mut structure);
+
+    if let Structure::Full(full) = &mut structure {
+        full.set_loaded();
+    }
+
+    for split_block in split_blocks {
+        let coords = local(split_block.coords);
+        structure.set_block_at(
+            coords,
+            blocks.from_numeric_id(split_block.block),
+            split_block.rotation,
+            blocks,
+            None,
+        );
+        structure.set_block_health(coords, split_block.health, blocks);
+    }
+
+    let entity = entity_cmds.id();
+
+    for res in structure.all_chunks_iter(false) {
+        // This will always be true because include_empty is false
+        if let ChunkIteratorResult::FilledChunk {
+            position: coords,
+            chunk: _,
+        } = res
+        {
+            evw_chunk_init.send(ChunkInitEvent {
+                structure_entity: entity,
+                coords,
+                serialized_block_data: None,
+            });
+        }
+    }
+
+    entity_cmds.insert((structure, Transform::from_rotation(rotation)));
+
+    evw_structure_loaded.send(StructureLoadedEvent { structure_entity: entity });
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<RemovedBlocks>().add_systems(
+        Update,
+        (collect_removed_blocks, split_disconnected_sections)
+            .chain()
+            .in_set(BlockEventsSet::PostProcessEvents)
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(from: u64, to: u64) -> HashSet<BlockCoordinate> {
+        (from..=to).map(|x| BlockCoordinate::new(x, 0, 0)).collect()
+    }
+
+    #[test]
+    fn cut_off_section_is_found() {
+        // Core at 0, block 5 removed
+        let mut solid = line(0, 4);
+        solid.extend(line(6, 8));
+
+        let sections = find_disconnected_sections(&[BlockCoordinate::new(5, 0, 0)], Some(BlockCoordinate::new(0, 0, 0)), |c| {
+            solid.contains(&c)
+        });
+
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].iter().copied().collect::<HashSet<_>>(), line(6, 8));
+    }
+
+    #[test]
+    fn core_side_is_kept_even_if_smaller() {
+        let mut solid = line(0, 1);
+        solid.extend(line(3, 20));
+
+        let sections = find_disconnected_sections(&[BlockCoordinate::new(2, 0, 0)], Some(BlockCoordinate::new(0, 0, 0)), |c| {
+            solid.contains(&c)
+        });
+
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].iter().copied().collect::<HashSet<_>>(), line(3, 20));
+    }
+
+    #[test]
+    fn still_connected_structures_arent_split() {
+        // A ring - removing one block leaves it connected
+        let solid = [(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (1, 2), (0, 2), (0, 1)]
+            .into_iter()
+            .map(|(x, y)| BlockCoordinate::new(x, y, 0))
+            .filter(|&c| c != BlockCoordinate::new(1, 0, 0))
+            .collect::<HashSet<_>>();
+
+        let sections = find_disconnected_sections(&[BlockCoordinate::new(1, 0, 0)], Some(BlockCoordinate::new(1, 2, 0)), |c| {
+            solid.contains(&c)
+        });
+
+        assert!(sections.is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+//! Rotates & mirrors entire ships for players in build mode.
+//!
+//! Ships are rotated/mirrored around their core, so the core never moves. Every other block is moved to its new
+//! position with its rotation transformed to match, and its block data is moved along with it.
+
+use bevy::{core::FrameCount, prelude::*, utils::HashMap};
+use cosmos_core::{
+    block::{block_events::BlockEventsSet, blocks::AIR_BLOCK_ID, Block},
+    chat::ServerSendChatMessageEvent,
+    events::block_events::{BlockChangedEvent, BlockDataChangedEvent},
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    prelude::{BlockCoordinate, Ship, Structure, StructureBlock},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        chunk::BlockInfo,
+        shared::{
+            build_mode::{BuildMode, StructureTransformation, TransformStructureEvent},
+            MeltingDown,
+        },
+    },
+};
+use renet2::ClientId;
+
+use crate::structure::ownership::{notify_no_permission, StructurePermissions};
+
+use super::move_block_data::PendingBlockDataMoves;
+
+/// Removing or changing these blocks would cause the ship to melt down, so they can't be moved
+const CORE_BLOCKS: [&str; 2] = ["cosmos:ship_core", "cosmos:station_core"];
+
+struct TransformedBlock {
+    block_id: u16,
+    info: BlockInfo,
+    health: f32,
+    data_entity: Option<Entity>,
+}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+/// Works out where every block will end up, or why the structure can't be transformed
+fn plan_transformation(
+    structure: &Structure,
+    pivot: BlockCoordinate,
+    transformation: StructureTransformation,
+    core_ids: &[u16],
+    blocks: &Registry<Block>,
+) -> Result<HashMap<BlockCoordinate, TransformedBlock>, &'static str> {
+    let mut transformed = HashMap::default();
+
+    for coords in structure.all_blocks_iter(false) {
+        let block_id = structure.block_id_at(coords);
+
+        if core_ids.contains(&block_id) && coords != pivot {
+            return Err("The ship's core must be in its center to rotate or mirror it.");
+        }
+
+        let new_coords = BlockCoordinate::try_from(pivot + transformation.apply_to_offset(coords - pivot))
+            .ok()
+            .filter(|&c| structure.is_within_blocks(c))
+            .ok_or("There isn't enough room to rotate or mirror this ship.")?;
+
+        let mut info = structure.block_info_at(coords);
+        info.set_rotation(transformation.apply_to_rotation(info.get_rotation()));
+
+        transformed.insert(
+            new_coords,
+            TransformedBlock {
+                block_id,
+                info,
+                health: structure.get_block_health(coords, blocks),
+                data_entity: structure.block_data(coords),
+            },
+        );
+    }
+
+    Ok(transformed)
+}
+
+fn on_transform_structure(
+    mut commands: Commands,
+    mut nevr_transform: EventReader<NettyEventReceived<TransformStructureEvent>>,
+    lobby: Res<ServerLobby>,
+    q_player: Query<&Parent, With<BuildMode>>,
+    mut q_structure: Query<&mut Structure, (With<Ship>, Without<MeltingDown>)>,
+    blocks: Res<Registry<Block>>,
+    permissions: StructurePermissions,
+    frame: Res<FrameCount>,
+    mut pending_data_moves: ResMut<PendingBlockDataMoves>,
+    mut evw_block_changed: EventWriter<BlockChangedEvent>,
+    mut evw_block_data_changed: EventWriter<BlockDataChangedEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    let core_ids = CORE_BLOCKS
+        .iter()
+        .flat_map(|name| blocks.from_id(name))
+        .map(|b| b.id())
+        .collect::<Vec<_>>();
+
+    for ev in nevr_transform.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok(structure_ent) = q_player.get(player_ent).map(|p| p.get()) else {
+            continue;
+        };
+
+        if !permissions.can_use(player_ent, structure_ent) {
+            notify_no_permission(&mut nevw_chat, ev.client_id);
+            continue;
+        }
+
+        let Ok(mut structure) = q_structure.get_mut(structure_ent) else {
+            continue;
+        };
+
+        if !matches!(*structure, Structure::Full(_)) {
+            continue;
+        }
+
+        let pivot = Ship::ship_core_block_coords(&structure);
+
+        let transformed = match plan_transformation(&structure, pivot, ev.0, &core_ids, &blocks) {
+            Ok(transformed) => transformed,
+            Err(reason) => {
+                reply(&mut nevw_chat, ev.client_id, reason);
+                continue;
+            }
+        };
+
+        let old_coords = structure.all_blocks_iter(false).collect::<Vec<_>>();
+
+        // The data is put back on the blocks once they have been moved
+        for &coords in old_coords.iter() {
+            if let Some(data_entity) = structure.block_data(coords) {
+                structure.set_block_data_entity(coords, None);
+                commands.entity(data_entity).remove_parent();
+            }
+        }
+
+        let air = blocks.from_numeric_id(AIR_BLOCK_ID);
+
+        // Blocks that are being replaced are changed directly to their new block, so each spot only gets one change event
+        for coords in old_coords {
+            if !transformed.contains_key(&coords) {
+                structure.set_block_and_info_at(coords, air, BlockInfo::default(), &blocks, Some(&mut evw_block_changed));
+            }
+        }
+
+        for (&coords, block) in transformed.iter() {
+            if coords == pivot && core_ids.contains(&block.block_id) {
+                // Changing the core block would make the ship melt down, so only its rotation is changed
+                structure.set_block_info_at(coords, block.info, &mut evw_block_data_changed);
+                continue;
+            }
+
+            structure.set_block_and_info_at(
+                coords,
+                blocks.from_numeric_id(block.block_id),
+                block.info,
+                &blocks,
+                Some(&mut evw_block_changed),
+            );
+            structure.set_block_health(coords, block.health, &blocks);
+        }
+
+        for (coords, block) in transformed {
+            if let Some(data_entity) = block.data_entity {
+                pending_data_moves.queue(data_entity, StructureBlock::new(coords, structure_ent), block.block_id, &frame);
+            }
+        }
+
+        info!("Player {player_ent:?} transformed structure {structure_ent:?} ({:?}).", ev.0);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        on_transform_structure
+            .in_set(NetworkingSystemsSet::Between)
+            .in_set(BlockEventsSet::ChangeBlocks)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,236 @@
+//! Flies ships that have their autopilot engaged to their destination
+
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_rapier3d::{
+    dynamics::{ReadMassProperties, Velocity},
+    geometry::{CollisionGroups, Group},
+    pipeline::QueryFilter,
+    plugin::{RapierContextEntityLink, ReadRapierContext},
+};
+use cosmos_core::{
+    netty::{server::ServerLobby, sync::events::server_event::NettyEventReceived, system_sets::NetworkingSystemsSet},
+    physics::{location::Location, structure_physics::ChunkPhysicsPart},
+    state::GameState,
+    structure::{
+        shields::SHIELD_COLLISION_GROUP,
+        ship::{
+            autopilot::{Autopilot, DisengageAutopilotEvent, EngageAutopilotEvent},
+            flight_assist::FlightAssistMode,
+            pilot::Pilot,
+            ship_movement::{ShipMovement, ShipMovementSet},
+            Ship,
+        },
+        systems::{dock_system::Docked, thruster_system::ThrusterSystem, StructureSystems},
+        StructureTypeSet,
+    },
+};
+
+use crate::structure::systems::thruster_system::{ThrusterSystemSet, ANGULAR_VELOCITY_PER_TORQUE, MAX_BRAKE_DELTA_PER_THRUST};
+
+use super::events::ShipSetMovementEvent;
+
+/// How fast the autopilot will turn the ship (radians/sec)
+const TURN_SPEED: f32 = PI / 2.0;
+/// How quickly the autopilot closes the angle between the ship and its destination - higher turns sharper but may overshoot
+const TURN_GAIN: f32 = 2.0;
+/// The ship must be facing within this angle (radians) of its destination before the autopilot will accelerate
+const MAX_ACCELERATION_ANGLE: f32 = PI / 18.0;
+/// If the ship is moving sideways faster than this (m/s), the autopilot will brake to correct its course
+const MAX_DRIFT_SPEED: f32 = 5.0;
+/// The ship is considered stopped once it's moving slower than this (m/s)
+const ARRIVED_SPEED: f32 = 1.0;
+/// Brakes this much earlier than needed, since braking force depends on the ship's energy
+const STOPPING_DISTANCE_MARGIN: f32 = 1.5;
+/// The autopilot disengages if the ship would hit something within this many seconds
+const COLLISION_LOOKAHEAD_SECS: f32 = 3.0;
+
+fn on_engage_autopilot(
+    mut nevr_engage: EventReader<NettyEventReceived<EngageAutopilotEvent>>,
+    mut nevr_disengage: EventReader<NettyEventReceived<DisengageAutopilotEvent>>,
+    lobby: Res<ServerLobby>,
+    q_pilot: Query<&Pilot>,
+    q_ship: Query<(), (With<Ship>, Without<Docked>)>,
+    mut commands: Commands,
+) {
+    for ev in nevr_engage.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok(pilot) = q_pilot.get(player_ent) else {
+            continue;
+        };
+
+        if !q_ship.contains(pilot.entity) {
+            continue;
+        }
+
+        commands.entity(pilot.entity).insert(Autopilot::new(ev.destination));
+    }
+
+    for ev in nevr_disengage.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok(pilot) = q_pilot.get(player_ent) else {
+            continue;
+        };
+
+        commands.entity(pilot.entity).remove::<Autopilot>();
+    }
+}
+
+/// The pilot taking control of the ship or leaving it will disengage the autopilot
+fn disengage_on_pilot_input(
+    mut evr_set_movement: EventReader<ShipSetMovementEvent>,
+    q_autopilot: Query<(), With<Autopilot>>,
+    q_no_pilot: Query<Entity, (With<Autopilot>, Or<(Without<Pilot>, With<Docked>)>)>,
+    mut commands: Commands,
+) {
+    for ev in evr_set_movement.read() {
+        if !q_autopilot.contains(ev.ship) {
+            continue;
+        }
+
+        if ev.movement.movement != Vec3::ZERO || ev.movement.braking {
+            commands.entity(ev.ship).remove::<Autopilot>();
+        }
+    }
+
+    for ent in q_no_pilot.iter() {
+        commands.entity(ent).remove::<Autopilot>();
+    }
+}
+
+fn fly_autopilot(
+    time: Res<Time>,
+    context_access: ReadRapierContext,
+    mut q_autopilot: Query<
+        (
+            Entity,
+            &Autopilot,
+            &Location,
+            &Transform,
+            &Velocity,
+            &ReadMassProperties,
+            &StructureSystems,
+            &mut ShipMovement,
+            &RapierContextEntityLink,
+            Option<&FlightAssistMode>,
+        ),
+        (With<Pilot>, Without<Docked>),
+    >,
+    q_thruster_system: Query<&ThrusterSystem>,
+    q_chunk_entity: Query<&ChunkPhysicsPart>,
+    mut commands: Commands,
+) {
+    for (ship_entity, autopilot, location, transform, velocity, mass, systems, mut movement, pw, flight_assist) in q_autopilot.iter_mut() {
+        let to_destination = (autopilot.destination - *location).absolute_coords_f32();
+        let distance = to_destination.length();
+        let direction = to_destination.normalize_or_zero();
+
+        let speed = velocity.linvel.length();
+        let closing_speed = velocity.linvel.dot(direction);
+
+        movement.torque = Vec3::ZERO;
+        movement.movement = Vec3::ZERO;
+
+        if distance <= Autopilot::ARRIVAL_DISTANCE && speed <= ARRIVED_SPEED {
+            info!("Ship {ship_entity:?} arrived at its autopilot destination.");
+            movement.braking = true;
+            commands.entity(ship_entity).remove::<Autopilot>();
+            continue;
+        }
+
+        if speed > ARRIVED_SPEED {
+            let context = context_access.get(*pw);
+
+            let hit = context.cast_ray(
+                transform.translation,
+                velocity.linvel / speed,
+                (speed * COLLISION_LOOKAHEAD_SECS).min(distance),
+                false,
+                QueryFilter::new()
+                    .groups(CollisionGroups::new(
+                        Group::ALL & !SHIELD_COLLISION_GROUP,
+                        Group::ALL & !SHIELD_COLLISION_GROUP,
+                    ))
+                    .predicate(&|e| {
+                        let Ok(ce) = q_chunk_entity.get(e) else {
+                            return false;
+                        };
+
+                        ce.structure_entity != ship_entity
+                    }),
+            );
+
+            if hit.is_some() {
+                info!("Ship {ship_entity:?} autopilot disengaged due to collision risk.");
+                movement.braking = true;
+                commands.entity(ship_entity).remove::<Autopilot>();
+                continue;
+            }
+        }
+
+        // Turns the same way the pilot would, so the thrusters (and the ship's mass) decide how fast it actually turns
+        if direction != Vec3::ZERO {
+            let (axis, angle) = Quat::from_rotation_arc(*transform.forward(), direction).to_axis_angle();
+            let wanted_angvel = axis * (angle * TURN_GAIN).min(TURN_SPEED);
+
+            let world_torque = if flight_assist.copied().unwrap_or_default() == FlightAssistMode::Decoupled {
+                // Torque only changes how fast a decoupled ship is spinning, so it has to counter the current spin
+                (wanted_angvel - velocity.angvel) / (ANGULAR_VELOCITY_PER_TORQUE * time.delta_secs().max(f32::EPSILON))
+            } else {
+                wanted_angvel / ANGULAR_VELOCITY_PER_TORQUE
+            };
+
+            movement.torque = transform.rotation.inverse() * world_torque;
+        }
+
+        let thrust = systems.query(&q_thruster_system).map(|ts| ts.thrust_total()).unwrap_or(0.0);
+        let brake_deceleration = if mass.get().mass > 0.0 {
+            MAX_BRAKE_DELTA_PER_THRUST * thrust / mass.get().mass
+        } else {
+            0.0
+        };
+
+        let stopping_distance = if brake_deceleration > 0.0 {
+            closing_speed.max(0.0).powi(2) / (2.0 * brake_deceleration)
+        } else {
+            f32::INFINITY
+        };
+
+        let drift_speed = (velocity.linvel - direction * closing_speed).length();
+        let remaining_distance = distance - Autopilot::ARRIVAL_DISTANCE / 2.0;
+
+        let should_brake = drift_speed > MAX_DRIFT_SPEED
+            || (closing_speed < 0.0 && speed > ARRIVED_SPEED)
+            || remaining_distance <= stopping_distance * STOPPING_DISTANCE_MARGIN;
+
+        movement.braking = should_brake;
+
+        let facing_destination = transform.forward().angle_between(direction) <= MAX_ACCELERATION_ANGLE;
+
+        if !should_brake && facing_destination && closing_speed < Autopilot::CRUISE_SPEED {
+            movement.movement = Vec3::Z;
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (on_engage_autopilot, disengage_on_pilot_input, fly_autopilot)
+            .chain()
+            .after(ShipMovementSet::RemoveShipMovement)
+            .before(ThrusterSystemSet::ApplyThrusters)
+            .in_set(StructureTypeSet::Ship)
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -1,7 +1,11 @@
 use bevy::prelude::{in_state, App, Entity, Event, EventReader, IntoSystemConfigs, ResMut, Update};
 use bevy_renet2::renet2::RenetServer;
 use cosmos_core::{
-    netty::{cosmos_encoder, server_reliable_messages::ServerReliableMessages, NettyChannelServer},
+    netty::{
+        network_stats::{NettyMessage, NettyServerMessages},
+        server_reliable_messages::ServerReliableMessages,
+        NettyChannelServer,
+    },
     state::GameState,
 };
 
@@ -16,9 +20,9 @@ pub struct ClientChangePilotEvent {
 
 fn event_listener(mut event_reader: EventReader<ClientChangePilotEvent>, mut server: ResMut<RenetServer>) {
     for ev in event_reader.read() {
-        server.broadcast_message(
+        server.broadcast_netty_message(
             NettyChannelServer::Reliable,
-            cosmos_encoder::serialize(&ServerReliableMessages::PilotChange {
+            NettyMessage::new(&ServerReliableMessages::PilotChange {
                 structure_entity: ev.structure_entity,
                 pilot_entity: ev.pilot_entity,
             }),
@@ -12,8 +12,11 @@ use cosmos_core::{
     block::block_events::BlockEventsSet,
     events::structure::change_pilot_event::ChangePilotEvent,
     netty::{
-        cosmos_encoder, server_reliable_messages::ServerReliableMessages, server_unreliable_messages::ServerUnreliableMessages,
-        system_sets::NetworkingSystemsSet, NettyChannelServer,
+        network_stats::{NettyMessage, NettyServerMessages},
+        server_reliable_messages::ServerReliableMessages,
+        server_unreliable_messages::ServerUnreliableMessages,
+        system_sets::NetworkingSystemsSet,
+        NettyChannelServer,
     },
     physics::location::Location,
     state::GameState,
@@ -21,6 +24,7 @@ use cosmos_core::{
         coordinates::ChunkCoordinate,
         full_structure::FullStructure,
         loading::StructureLoadingSet,
+        ownership::StructureOwnership,
         ship::{ship_builder::TShipBuilder, ship_movement::ShipMovement},
         Structure, StructureTypeSet,
     },
@@ -48,9 +52,9 @@ fn monitor_set_movement_events(
         if let Ok(mut current_movement) = query.get_mut(ev.ship) {
             *current_movement = ev.movement;
 
-            server.broadcast_message(
+            server.broadcast_netty_message(
                 NettyChannelServer::Unreliable,
-                cosmos_encoder::serialize(&ServerUnreliableMessages::SetMovement {
+                NettyMessage::new(&ServerUnreliableMessages::SetMovement {
                     movement: ev.movement,
                     ship_entity: ev.ship,
                 }),
@@ -61,9 +65,9 @@ fn monitor_set_movement_events(
 
 fn monitor_pilot_changes(mut event_reader: EventReader<ChangePilotEvent>, mut server: ResMut<RenetServer>) {
     for ev in event_reader.read() {
-        server.broadcast_message(
+        server.broadcast_netty_message(
             NettyChannelServer::Reliable,
-            cosmos_encoder::serialize(&ServerReliableMessages::PilotChange {
+            NettyMessage::new(&ServerReliableMessages::PilotChange {
                 structure_entity: ev.structure_entity,
                 pilot_entity: ev.pilot_entity,
             }),
@@ -78,6 +82,8 @@ pub struct CreateShipEvent {
     pub ship_location: Location,
     /// The rotation of the ship
     pub rotation: Quat,
+    /// The name of the player that will own this ship
+    pub owner: Option<String>,
 }
 
 pub(crate) fn create_ship_event_reader(mut event_reader: EventReader<CreateShipEvent>, mut commands: Commands) {
@@ -95,6 +101,10 @@ pub(crate) fn create_ship_event_reader(mut event_reader: EventReader<CreateShipE
         entity
             .insert(structure)
             .insert((ShipNeedsCreated, Transform::from_rotation(ev.rotation)));
+
+        if let Some(owner) = &ev.owner {
+            entity.insert(StructureOwnership::new(owner));
+        }
     }
 }
 
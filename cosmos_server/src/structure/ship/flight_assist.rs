@@ -0,0 +1,53 @@
+//! Handles pilots changing their ship's flight assist mode
+
+use bevy::prelude::*;
+use cosmos_core::{
+    netty::{server::ServerLobby, sync::events::server_event::NettyEventReceived, system_sets::NetworkingSystemsSet},
+    state::GameState,
+    structure::ship::{
+        flight_assist::{FlightAssistMode, SetFlightAssistModeEvent},
+        pilot::Pilot,
+        Ship,
+    },
+};
+
+use crate::persistence::make_persistent::{make_persistent, DefaultPersistentComponent};
+
+impl DefaultPersistentComponent for FlightAssistMode {}
+
+fn on_set_flight_assist_mode(
+    mut nevr_set_mode: EventReader<NettyEventReceived<SetFlightAssistModeEvent>>,
+    lobby: Res<ServerLobby>,
+    q_pilot: Query<&Pilot>,
+    q_ship: Query<(), With<Ship>>,
+    mut commands: Commands,
+) {
+    for ev in nevr_set_mode.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        // Only the pilot of a ship can change how it flies
+        let Ok(pilot) = q_pilot.get(player_ent) else {
+            continue;
+        };
+
+        if !q_ship.contains(pilot.entity) {
+            continue;
+        }
+
+        commands.entity(pilot.entity).insert(ev.event.0);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<FlightAssistMode>(app);
+
+    app.add_systems(
+        Update,
+        on_set_flight_assist_mode
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
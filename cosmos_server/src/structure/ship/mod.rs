@@ -2,17 +2,21 @@
 
 use bevy::prelude::App;
 
+mod autopilot;
 mod change_pilot_event_listener;
 pub mod events;
+mod flight_assist;
 pub mod loading;
 mod persistence;
 pub mod server_ship_builder;
 mod sync;
 
 pub(super) fn register(app: &mut App) {
+    autopilot::register(app);
     change_pilot_event_listener::register(app);
     loading::register(app);
     persistence::register(app);
     sync::register(app);
     events::register(app);
+    flight_assist::register(app);
 }
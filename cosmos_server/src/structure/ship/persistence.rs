@@ -24,7 +24,10 @@ use super::server_ship_builder::ServerShipBuilder;
 
 fn on_blueprint_ship(mut query: Query<(&mut SerializedData, &Structure, &mut NeedsBlueprinted), With<Ship>>, mut commands: Commands) {
     for (mut s_data, structure, mut blueprint) in query.iter_mut() {
-        blueprint.subdir_name = "ship".into();
+        // Some ships (such as ones stored in hangars) are blueprinted somewhere else
+        if blueprint.subdir_name.is_empty() {
+            blueprint.subdir_name = "ship".into();
+        }
 
         save_structure(structure, &mut s_data, &mut commands);
         s_data.serialize_data("cosmos:is_ship", &true);
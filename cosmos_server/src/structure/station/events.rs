@@ -5,7 +5,7 @@ use cosmos_core::{
     physics::location::Location,
     state::GameState,
     structure::{
-        coordinates::ChunkCoordinate, full_structure::FullStructure, loading::StructureLoadingSet,
+        coordinates::ChunkCoordinate, full_structure::FullStructure, loading::StructureLoadingSet, ownership::StructureOwnership,
         station::station_builder::TStationBuilder, Structure,
     },
 };
@@ -19,6 +19,8 @@ pub struct CreateStationEvent {
     pub station_location: Location,
     /// The rotation of the station
     pub rotation: Quat,
+    /// The name of the player that will own this station
+    pub owner: Option<String>,
 }
 
 pub(crate) fn create_station_event_reader(mut event_reader: EventReader<CreateStationEvent>, mut commands: Commands) {
@@ -34,6 +36,10 @@ pub(crate) fn create_station_event_reader(mut event_reader: EventReader<CreateSt
         entity
             .insert(structure)
             .insert((StationNeedsCreated, Transform::from_rotation(ev.rotation)));
+
+        if let Some(owner) = &ev.owner {
+            entity.insert(StructureOwnership::new(owner));
+        }
     }
 }
 
@@ -0,0 +1,393 @@
+//! Hangar controllers let stations act as garages for small ships.
+//!
+//! Interacting with a hangar controller while a ship is docked to the station near it will "store" that ship -
+//! the ship is saved as a blueprint and its entities are despawned. Interacting with it again re-spawns the
+//! last ship you stored there in front of the controller. Only the player who stored a ship can take it back out.
+
+use std::fs;
+
+use bevy::prelude::*;
+use bevy_renet2::renet2::ClientId;
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        block_face::BlockFace,
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    ecs::NeedsDespawned,
+    entities::player::Player,
+    events::block_events::BlockChangedEvent,
+    netty::{
+        sync::{events::server_event::NettyEventWriter, IdentifiableComponent},
+        system_sets::NetworkingSystemsSet,
+    },
+    physics::location::Location,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        coordinates::BlockCoordinate,
+        ship::{pilot::Pilot, Ship},
+        station::Station,
+        systems::dock_system::Docked,
+        Structure,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    persistence::{
+        loading::{LoadingBlueprintSystemSet, NeedsBlueprintLoaded, LOADING_SCHEDULE},
+        make_persistent::{make_persistent, DefaultPersistentComponent},
+        saving::{BlueprintingSystemSet, NeedsBlueprinted, SavingSystemSet, SAVING_SCHEDULE},
+        EntityId,
+    },
+    structure::ownership::{notify_no_permission, StructurePermissions},
+};
+
+const HANGAR_CONTROLLER_BLOCK: &str = "cosmos:hangar_controller";
+
+/// The blueprint subdirectory stored ships are saved to
+const HANGAR_SUBDIR: &str = "hangar";
+
+/// How close (in blocks) a docked ship has to be to the controller to be stored by it
+const HANGAR_RANGE: f32 = 100.0;
+
+/// The most ships a single hangar controller can hold
+const MAX_SHIPS_PER_HANGAR: usize = 4;
+
+/// How far in front of the controller ships are launched
+const LAUNCH_DISTANCE: f32 = 30.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredShip {
+    /// The hangar controller this ship is stored in
+    controller: BlockCoordinate,
+    /// The name of this ship's blueprint file in the hangar subdirectory
+    blueprint_name: String,
+    /// The name of the player who stored this ship
+    owner: String,
+}
+
+impl StoredShip {
+    fn blueprint_path(&self) -> String {
+        hangar_blueprint_path(&self.blueprint_name)
+    }
+}
+
+fn hangar_blueprint_path(blueprint_name: &str) -> String {
+    format!("blueprints/{HANGAR_SUBDIR}/{blueprint_name}.bp")
+}
+
+#[derive(Component, Debug, Default, Serialize, Deserialize)]
+/// Every ship stored in the hangar controllers of this station
+struct HangarStorage(Vec<StoredShip>);
+
+impl HangarStorage {
+    fn count_in(&self, controller: BlockCoordinate) -> usize {
+        self.0.iter().filter(|x| x.controller == controller).count()
+    }
+
+    /// Removes the last ship this player stored in this controller
+    fn take_latest(&mut self, controller: BlockCoordinate, owner: &str) -> Option<StoredShip> {
+        let idx = self.0.iter().rposition(|x| x.controller == controller && x.owner == owner)?;
+
+        Some(self.0.remove(idx))
+    }
+
+    /// Removes every ship stored in this controller
+    fn take_all(&mut self, controller: BlockCoordinate) -> Vec<StoredShip> {
+        let (taken, kept) = std::mem::take(&mut self.0).into_iter().partition(|x| x.controller == controller);
+        self.0 = kept;
+
+        taken
+    }
+}
+
+impl IdentifiableComponent for HangarStorage {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:hangar_storage"
+    }
+}
+
+impl DefaultPersistentComponent for HangarStorage {}
+
+#[derive(Component, Debug)]
+/// Placed on a ship that is being blueprinted so it can be stored in a hangar
+struct StoringInHangar {
+    station: Entity,
+    stored: StoredShip,
+    player_id: ClientId,
+}
+
+#[derive(Component, Debug)]
+/// Placed on a ship being loaded out of a hangar, so its blueprint can be cleaned up once it has loaded
+struct RetrievedFromHangar {
+    path: String,
+}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: &str) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+/// Computes the location & facing direction of this hangar controller
+fn controller_location(
+    structure: &Structure,
+    coords: BlockCoordinate,
+    station_loc: &Location,
+    g_trans: &GlobalTransform,
+) -> (Location, Vec3) {
+    let rotation = g_trans.rotation();
+    let facing = structure.block_rotation(coords).direction_of(BlockFace::Front).as_vec3();
+
+    (
+        *station_loc + rotation * structure.block_relative_position(coords),
+        rotation * facing,
+    )
+}
+
+fn launch_ship(commands: &mut Commands, stored: &StoredShip, spawn_at: Location, rotation: Quat) {
+    let path = stored.blueprint_path();
+
+    commands.spawn((
+        spawn_at,
+        NeedsBlueprintLoaded {
+            spawn_at,
+            rotation,
+            path: path.clone(),
+        },
+        RetrievedFromHangar { path },
+    ));
+}
+
+fn on_interact_with_hangar(
+    mut commands: Commands,
+    mut evr_interact: EventReader<BlockInteractEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    blocks: Res<Registry<Block>>,
+    mut q_station: Query<(&Structure, &Location, &GlobalTransform, Option<&mut HangarStorage>), With<Station>>,
+    q_player: Query<&Player>,
+    q_docked_ships: Query<(Entity, &Docked, &Location), (With<Ship>, Without<StoringInHangar>)>,
+    q_docked: Query<&Docked>,
+    q_pilot: Query<(), With<Pilot>>,
+    q_player_parents: Query<&Parent, With<Player>>,
+    permissions: StructurePermissions,
+) {
+    for ev in evr_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let station = s_block.structure();
+
+        let Ok((structure, station_loc, g_trans, storage)) = q_station.get_mut(station) else {
+            continue;
+        };
+
+        if structure.block_at(s_block.coords(), &blocks).unlocalized_name() != HANGAR_CONTROLLER_BLOCK {
+            continue;
+        }
+
+        let Ok(player) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        if !permissions.can_use(ev.interactor, station) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        let (controller_loc, facing) = controller_location(structure, s_block.coords(), station_loc, g_trans);
+
+        let ship_to_store = q_docked_ships
+            .iter()
+            .filter(|(_, docked, _)| docked.to == station)
+            .map(|(ent, _, loc)| (ent, loc.distance_sqrd(&controller_loc)))
+            .filter(|(_, dist)| *dist <= HANGAR_RANGE * HANGAR_RANGE)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(ent, _)| ent);
+
+        if let Some(ship) = ship_to_store {
+            if !permissions.can_use(ev.interactor, ship) {
+                notify_no_permission(&mut nevw_chat, player.id());
+                continue;
+            }
+
+            if q_pilot.contains(ship) {
+                reply(&mut nevw_chat, player.id(), "Someone is piloting that ship.");
+                continue;
+            }
+
+            // Players are children of the ship they're on, so they would be despawned along with it
+            if q_player_parents.iter().any(|p| p.get() == ship) {
+                reply(
+                    &mut nevw_chat,
+                    player.id(),
+                    "Everyone must leave that ship before it can be stored.",
+                );
+                continue;
+            }
+
+            if q_docked.iter().any(|d| d.to == ship) {
+                reply(&mut nevw_chat, player.id(), "Undock everything from that ship before storing it.");
+                continue;
+            }
+
+            if storage.as_ref().map(|x| x.count_in(s_block.coords())).unwrap_or(0) >= MAX_SHIPS_PER_HANGAR {
+                reply(&mut nevw_chat, player.id(), "This hangar is full.");
+                continue;
+            }
+
+            if storage.is_none() {
+                commands.entity(station).insert(HangarStorage::default());
+            }
+
+            let blueprint_name = EntityId::generate().as_str().to_owned();
+
+            commands.entity(ship).insert((
+                NeedsBlueprinted {
+                    blueprint_name: blueprint_name.clone(),
+                    subdir_name: HANGAR_SUBDIR.into(),
+                },
+                StoringInHangar {
+                    station,
+                    player_id: player.id(),
+                    stored: StoredShip {
+                        controller: s_block.coords(),
+                        blueprint_name,
+                        owner: player.name().to_owned(),
+                    },
+                },
+            ));
+
+            continue;
+        }
+
+        let Some(stored) = storage.and_then(|mut x| x.take_latest(s_block.coords(), player.name())) else {
+            reply(
+                &mut nevw_chat,
+                player.id(),
+                "Dock a ship to this station to store it here. You have no ships stored in this hangar.",
+            );
+            continue;
+        };
+
+        launch_ship(
+            &mut commands,
+            &stored,
+            controller_loc + facing * LAUNCH_DISTANCE,
+            g_trans.rotation(),
+        );
+
+        reply(&mut nevw_chat, player.id(), "Launching your ship from the hangar.");
+    }
+}
+
+/// Once a ship's blueprint has been written, the ship is recorded in its hangar & removed from the world.
+///
+/// The ship is only despawned if its blueprint was actually saved, so a failed save never loses a ship.
+fn finish_storing_ships(
+    mut commands: Commands,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    q_storing: Query<(Entity, &StoringInHangar), Without<NeedsBlueprinted>>,
+    mut q_storage: Query<&mut HangarStorage>,
+) {
+    for (ship, storing) in q_storing.iter() {
+        commands.entity(ship).remove::<StoringInHangar>();
+
+        let path = storing.stored.blueprint_path();
+
+        if !fs::exists(&path).unwrap_or(false) {
+            error!("Failed to store ship {ship:?} in hangar - blueprint was not saved to {path}.");
+            reply(&mut nevw_chat, storing.player_id, "Unable to store this ship.");
+            continue;
+        }
+
+        let Ok(mut storage) = q_storage.get_mut(storing.station) else {
+            // The station is gone, so there's nowhere to put this ship. Leave it in the world.
+            if let Err(e) = fs::remove_file(&path) {
+                warn!("Unable to remove unused hangar blueprint {path}\n{e}");
+            }
+            continue;
+        };
+
+        storage.0.push(storing.stored.clone());
+        commands.entity(ship).insert(NeedsDespawned);
+
+        reply(&mut nevw_chat, storing.player_id, "Ship stored in hangar.");
+    }
+}
+
+fn cleanup_retrieved_blueprints(mut commands: Commands, q_retrieved: Query<(Entity, &RetrievedFromHangar), Without<NeedsBlueprintLoaded>>) {
+    for (ent, retrieved) in q_retrieved.iter() {
+        commands.entity(ent).remove::<RetrievedFromHangar>();
+
+        if let Err(e) = fs::remove_file(&retrieved.path) {
+            warn!("Unable to remove hangar blueprint {}\n{e}", retrieved.path);
+        }
+    }
+}
+
+/// If a hangar controller is broken, every ship stored in it is launched so they aren't lost.
+fn launch_ships_from_broken_hangars(
+    mut commands: Commands,
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    blocks: Res<Registry<Block>>,
+    mut q_station: Query<(&Location, &GlobalTransform, &Structure, &mut HangarStorage)>,
+) {
+    for ev in evr_block_changed.read() {
+        if blocks.from_numeric_id(ev.old_block).unlocalized_name() != HANGAR_CONTROLLER_BLOCK
+            || blocks.from_numeric_id(ev.new_block).unlocalized_name() == HANGAR_CONTROLLER_BLOCK
+        {
+            continue;
+        }
+
+        let Ok((station_loc, g_trans, structure, mut storage)) = q_station.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        let coords = ev.block.coords();
+        let rotation = g_trans.rotation();
+        let facing = rotation * ev.old_block_rotation().direction_of(BlockFace::Front).as_vec3();
+        let controller_loc = *station_loc + rotation * structure.block_relative_position(coords);
+
+        for (i, stored) in storage.take_all(coords).iter().enumerate() {
+            launch_ship(
+                &mut commands,
+                stored,
+                controller_loc + facing * LAUNCH_DISTANCE * (i + 1) as f32,
+                rotation,
+            );
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<HangarStorage>(app);
+
+    app.add_systems(
+        Update,
+        (
+            on_interact_with_hangar.in_set(BlockEventsSet::ProcessEvents),
+            launch_ships_from_broken_hangars.in_set(BlockEventsSet::PostProcessEvents),
+        )
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    )
+    .add_systems(
+        SAVING_SCHEDULE,
+        finish_storing_ships
+            .after(BlueprintingSystemSet::DoneBlueprinting)
+            .before(SavingSystemSet::BeginSaving),
+    )
+    .add_systems(
+        LOADING_SCHEDULE,
+        cleanup_retrieved_blueprints.after(LoadingBlueprintSystemSet::DoneLoadingBlueprints),
+    );
+}
@@ -0,0 +1,355 @@
+//! Ship insurance lets players pay to have a copy of their ship made, which they get back if that ship is destroyed.
+//!
+//! While aboard a ship they own, players can run the `/insure` chat command. This charges them a fee based on
+//! the size of their ship and takes a blueprint snapshot of it. If the insured ship is ever destroyed, the policy
+//! can be redeemed at any station's shipyard terminal (see [`super::shipyard`]) to build a fresh copy of the ship.
+//! Blueprints never contain block data, so the copy comes back without any of its cargo.
+//!
+//! Insurance only covers what the ship still had when it was destroyed - if only half of the insured blocks were
+//! left, half of the materials for the copy are covered and the rest must be supplied at the shipyard. This stops
+//! players from stripping an insured ship of its blocks and then getting the whole ship back for free.
+//!
+//! Policies are stored in `world/insurance.json`, so they can be claimed even if the owner was offline when their
+//! ship was destroyed.
+
+use std::fs;
+
+use bevy::prelude::*;
+use bevy_renet2::renet2::ClientId;
+use cosmos_core::{
+    chat::ServerSendChatMessageEvent,
+    economy::Credits,
+    entities::player::Player,
+    netty::{
+        sync::{events::server_event::NettyEventWriter, IdentifiableComponent},
+        system_sets::NetworkingSystemsSet,
+    },
+    state::GameState,
+    structure::{ownership::StructureOwnership, shared::MeltingDown, ship::Ship, Structure},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    chat::{ChatCommandSet, ChatCommands, PlayerChatCommandEvent},
+    persistence::{
+        make_persistent::{make_persistent, DefaultPersistentComponent},
+        saving::{BlueprintingSystemSet, NeedsBlueprinted, SavingSystemSet, SAVING_SCHEDULE},
+        EntityId,
+    },
+};
+
+const INSURANCE_POLICIES_PATH: &str = "world/insurance.json";
+
+/// The blueprint subdirectory insured ships are saved to
+const INSURANCE_SUBDIR: &str = "insurance";
+
+/// How many credits insuring a ship costs per block it has
+const FEE_PER_BLOCK: u64 = 10;
+
+/// The least insuring any ship will cost
+const MIN_FEE: u64 = 1_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A snapshot of a ship that its owner can get back if the ship is destroyed
+pub(super) struct InsurancePolicy {
+    /// The name of this policy's blueprint file in the insurance subdirectory
+    id: String,
+    /// The name of the player who took out this policy
+    owner: String,
+    /// If the insured ship has been destroyed, meaning this policy can be redeemed
+    destroyed: bool,
+    /// How many credits were paid for this policy
+    #[serde(default)]
+    fee: u64,
+    /// How many blocks the ship had when it was insured
+    #[serde(default)]
+    blocks: u64,
+    /// How many blocks the ship had left when it was destroyed
+    #[serde(default)]
+    blocks_remaining: Option<u64>,
+}
+
+impl InsurancePolicy {
+    /// The path to the blueprint of the insured ship
+    pub(super) fn blueprint_path(&self) -> String {
+        format!("blueprints/{INSURANCE_SUBDIR}/{}.bp", self.id)
+    }
+
+    /// The unique id of this policy
+    pub(super) fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// How many credits were paid for this policy
+    pub(super) fn fee(&self) -> u64 {
+        self.fee
+    }
+
+    /// The fraction (0 to 1) of the ship's materials this policy pays for, based on how much of the ship was left
+    /// when it was destroyed
+    pub(super) fn coverage(&self) -> f32 {
+        match (self.blocks, self.blocks_remaining) {
+            (0, _) | (_, None) => 1.0,
+            (blocks, Some(remaining)) => (remaining as f32 / blocks as f32).min(1.0),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+/// Every insurance policy that hasn't been redeemed yet
+pub(super) struct InsurancePolicies(Vec<InsurancePolicy>);
+
+impl InsurancePolicies {
+    /// The first policy of this player whose ship has been destroyed
+    pub(super) fn claim(&self, owner: &str) -> Option<&InsurancePolicy> {
+        self.0.iter().find(|x| x.destroyed && x.owner == owner)
+    }
+
+    /// Removes & returns the policy with this id
+    pub(super) fn remove(&mut self, policy_id: &str) -> Option<InsurancePolicy> {
+        let idx = self.0.iter().position(|x| x.id == policy_id)?;
+
+        let policy = self.0.remove(idx);
+        self.save();
+
+        Some(policy)
+    }
+
+    fn save(&self) {
+        let json = serde_json::to_string_pretty(self).expect("Insurance policies are always valid json");
+
+        if let Err(e) = fs::write(INSURANCE_POLICIES_PATH, json) {
+            error!("Unable to save insurance policies to {INSURANCE_POLICIES_PATH}.\n{e:?}");
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize, Reflect)]
+/// This ship is insured by the policy with this id
+struct Insured {
+    policy_id: String,
+}
+
+impl IdentifiableComponent for Insured {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:insured"
+    }
+}
+
+impl DefaultPersistentComponent for Insured {}
+
+#[derive(Component, Debug)]
+/// Placed on a ship that is being blueprinted so its insurance policy can be written once the blueprint is saved
+struct InsuringShip {
+    policy_id: String,
+    owner: String,
+    player_id: ClientId,
+    fee: u64,
+    blocks: u64,
+}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+fn block_count(structure: &Structure) -> u64 {
+    structure.all_blocks_iter(false).count() as u64
+}
+
+fn insurance_fee(blocks: u64) -> u64 {
+    blocks.saturating_mul(FEE_PER_BLOCK).max(MIN_FEE)
+}
+
+fn register_chat_commands(mut chat_commands: ResMut<ChatCommands>) {
+    chat_commands.add("insure");
+}
+
+fn on_insure_command(
+    mut commands: Commands,
+    mut evr_command: EventReader<PlayerChatCommandEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut q_player: Query<(&Player, &Parent, &mut Credits)>,
+    q_ship: Query<(&Structure, Option<&StructureOwnership>, Has<Insured>, Has<InsuringShip>), With<Ship>>,
+) {
+    for ev in evr_command.read() {
+        if ev.name != "insure" {
+            continue;
+        }
+
+        let Ok((player, parent, mut credits)) = q_player.get_mut(ev.player_entity) else {
+            continue;
+        };
+
+        let ship = parent.get();
+
+        let Ok((structure, ownership, insured, insuring)) = q_ship.get(ship) else {
+            reply(&mut nevw_chat, ev.client_id, "You must be aboard a ship to insure it.");
+            continue;
+        };
+
+        if !ownership.is_some_and(|o| o.is_owner(player.name())) {
+            reply(&mut nevw_chat, ev.client_id, "You can only insure ships you own.");
+            continue;
+        }
+
+        if insured || insuring {
+            reply(&mut nevw_chat, ev.client_id, "This ship is already insured.");
+            continue;
+        }
+
+        let blocks = block_count(structure);
+        let fee = insurance_fee(blocks);
+
+        if !credits.decrease(fee) {
+            reply(
+                &mut nevw_chat,
+                ev.client_id,
+                format!("Insuring this ship costs {fee} credits, but you only have {}.", credits.amount()),
+            );
+            continue;
+        }
+
+        let policy_id = EntityId::generate().as_str().to_owned();
+
+        commands.entity(ship).insert((
+            NeedsBlueprinted {
+                blueprint_name: policy_id.clone(),
+                subdir_name: INSURANCE_SUBDIR.into(),
+            },
+            InsuringShip {
+                policy_id,
+                owner: player.name().to_owned(),
+                player_id: player.id(),
+                fee,
+                blocks,
+            },
+        ));
+    }
+}
+
+/// Once a ship's blueprint has been written, its policy is recorded.
+///
+/// If the blueprint failed to save, the player is refunded instead.
+fn finish_insuring_ships(
+    mut commands: Commands,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    q_insuring: Query<(Entity, &InsuringShip), Without<NeedsBlueprinted>>,
+    mut q_player: Query<(&Player, &mut Credits)>,
+    mut policies: ResMut<InsurancePolicies>,
+) {
+    for (ship, insuring) in q_insuring.iter() {
+        commands.entity(ship).remove::<InsuringShip>();
+
+        let policy = InsurancePolicy {
+            id: insuring.policy_id.clone(),
+            owner: insuring.owner.clone(),
+            destroyed: false,
+            fee: insuring.fee,
+            blocks: insuring.blocks,
+            blocks_remaining: None,
+        };
+
+        let path = policy.blueprint_path();
+
+        if !fs::exists(&path).unwrap_or(false) {
+            error!("Failed to insure ship {ship:?} - blueprint was not saved to {path}.");
+
+            if let Some((_, mut credits)) = q_player.iter_mut().find(|(p, _)| p.name() == insuring.owner) {
+                credits.increase(insuring.fee);
+            }
+
+            reply(
+                &mut nevw_chat,
+                insuring.player_id,
+                "Unable to insure this ship. You have been refunded.",
+            );
+            continue;
+        }
+
+        commands.entity(ship).insert(Insured {
+            policy_id: policy.id.clone(),
+        });
+
+        policies.0.push(policy);
+        policies.save();
+
+        reply(
+            &mut nevw_chat,
+            insuring.player_id,
+            format!(
+                "Ship insured for {} credits. If it is destroyed, use any station's shipyard terminal to get a new one.",
+                insuring.fee
+            ),
+        );
+    }
+}
+
+fn on_insured_ship_destroyed(
+    q_destroyed: Query<(&Insured, &Structure), (Added<MeltingDown>, With<Ship>)>,
+    q_players: Query<&Player>,
+    mut policies: ResMut<InsurancePolicies>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for (insured, structure) in q_destroyed.iter() {
+        let Some(policy) = policies.0.iter_mut().find(|x| x.id == insured.policy_id) else {
+            continue;
+        };
+
+        policy.destroyed = true;
+        policy.blocks_remaining = Some(block_count(structure));
+
+        if let Some(owner) = q_players.iter().find(|p| p.name() == policy.owner) {
+            reply(
+                &mut nevw_chat,
+                owner.id(),
+                "Your insured ship was destroyed. Visit any station's shipyard terminal to claim a replacement.",
+            );
+        }
+
+        policies.save();
+    }
+}
+
+fn load_insurance_policies(mut commands: Commands) {
+    let policies = fs::read_to_string(INSURANCE_POLICIES_PATH)
+        .ok()
+        .map(|json| {
+            serde_json::from_str::<InsurancePolicies>(&json).unwrap_or_else(|e| {
+                error!("Invalid insurance policies in {INSURANCE_POLICIES_PATH} - ignoring them.\n{e:?}");
+                InsurancePolicies::default()
+            })
+        })
+        .unwrap_or_default();
+
+    commands.insert_resource(policies);
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<Insured>(app);
+
+    app.init_resource::<InsurancePolicies>()
+        .add_systems(Startup, register_chat_commands)
+        .add_systems(OnEnter(GameState::PostLoading), load_insurance_policies)
+        .add_systems(
+            Update,
+            (
+                on_insure_command.after(ChatCommandSet::SendCommandEvents),
+                on_insured_ship_destroyed,
+            )
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            SAVING_SCHEDULE,
+            finish_insuring_ships
+                .after(BlueprintingSystemSet::DoneBlueprinting)
+                .before(SavingSystemSet::BeginSaving),
+        )
+        .register_type::<Insured>();
+}
@@ -3,9 +3,14 @@
 use bevy::prelude::App;
 
 pub mod events;
+mod hangar;
+mod insurance;
 pub mod loading;
 mod persistence;
+mod prefab;
+mod refuel;
 pub mod server_station_builder;
+mod shipyard;
 mod sync;
 
 pub(super) fn register(app: &mut App) {
@@ -13,4 +18,9 @@ pub(super) fn register(app: &mut App) {
     loading::register(app);
     sync::register(app);
     persistence::register(app);
+    prefab::register(app);
+    hangar::register(app);
+    insurance::register(app);
+    shipyard::register(app);
+    refuel::register(app);
 }
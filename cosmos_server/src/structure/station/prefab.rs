@@ -0,0 +1,185 @@
+//! Loads the station prefabs & handles players attaching them to their stations
+
+use std::{ffi::OsStr, fs};
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::{block_events::BlockEventsSet, Block},
+    blockitems::BlockItems,
+    chat::ServerSendChatMessageEvent,
+    entities::player::{creative::Creative, Player},
+    events::block_events::BlockChangedEvent,
+    inventory::Inventory,
+    item::Item,
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    registry::Registry,
+    state::GameState,
+    structure::{
+        shared::build_mode::BuildMode,
+        station::{
+            prefab::{PlaceStationPrefabEvent, PrefabPlacementError, PrefabRegion, StationPrefab},
+            Station,
+        },
+        Structure,
+    },
+};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::structure::ownership::{notify_no_permission, StructurePermissions};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RawStationPrefab {
+    regions: Vec<PrefabRegion>,
+}
+
+fn load_prefabs(mut prefabs: ResMut<Registry<StationPrefab>>, blocks: Res<Registry<Block>>) {
+    for entry in WalkDir::new("assets/cosmos/station_prefabs").max_depth(1) {
+        let Ok(entry) = entry else {
+            continue;
+        };
+
+        let path = entry.path();
+        if path.is_dir() || path.extension().and_then(OsStr::to_str) != Some("json") {
+            continue;
+        }
+
+        let Some(name) = path.file_stem().and_then(OsStr::to_str) else {
+            continue;
+        };
+
+        let prefab_json = fs::read(path).unwrap_or_else(|e| panic!("Unable to read station prefab file {path:?}\n{e:?}"));
+
+        let prefab = serde_json::from_slice::<RawStationPrefab>(&prefab_json)
+            .unwrap_or_else(|e| panic!("Invalid station prefab json {path:?}\n{e:?}"));
+
+        if let Some(region) = prefab.regions.iter().find(|r| !blocks.contains(&r.block)) {
+            error!(
+                "Unable to find block with id matching {:?} in station prefab {path:?}",
+                region.block
+            );
+            continue;
+        }
+
+        prefabs.register(StationPrefab::new(format!("cosmos:{name}"), prefab.regions));
+    }
+
+    info!("Loaded {} station prefabs", prefabs.iter().count());
+}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, player: &Player, message: &str) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        player.id(),
+    );
+}
+
+fn on_place_prefab(
+    mut commands: Commands,
+    mut nevr_place_prefab: EventReader<NettyEventReceived<PlaceStationPrefabEvent>>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut evw_block_changed: EventWriter<BlockChangedEvent>,
+    lobby: Res<ServerLobby>,
+    mut q_player: Query<(&Player, &Parent, &mut Inventory, Has<Creative>), With<BuildMode>>,
+    mut q_station: Query<&mut Structure, With<Station>>,
+    prefabs: Res<Registry<StationPrefab>>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    block_items: Res<BlockItems>,
+    permissions: StructurePermissions,
+) {
+    for ev in nevr_place_prefab.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok((player, parent, mut inventory, creative)) = q_player.get_mut(player_ent) else {
+            warn!("Player {player_ent:?} tried to place a station prefab while not in build mode.");
+            continue;
+        };
+
+        let station_entity = ev.connector.structure();
+
+        if parent.get() != station_entity {
+            warn!("Player {player_ent:?} tried to place a station prefab on a structure they are not building on.");
+            continue;
+        }
+
+        if !permissions.can_use(player_ent, station_entity) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        let Ok(mut structure) = q_station.get_mut(station_entity) else {
+            continue;
+        };
+
+        let Some(prefab) = prefabs.try_from_numeric_id(ev.prefab_id) else {
+            warn!("Player {player_ent:?} tried to place a station prefab that doesn't exist.");
+            continue;
+        };
+
+        let placement = match prefab.compute_placement(&structure, ev.connector.coords(), &blocks) {
+            Ok(placement) => placement,
+            Err(e) => {
+                let message = match e {
+                    PrefabPlacementError::NotAConnector => "Station modules must be attached to a station connector.",
+                    PrefabPlacementError::OutOfBounds => "This module would stick out past the edge of the station.",
+                    PrefabPlacementError::Obstructed => "There are blocks in the way of this module.",
+                    PrefabPlacementError::UnknownBlock => "This module is missing blocks.",
+                };
+
+                reply(&mut nevw_chat, player, message);
+                continue;
+            }
+        };
+
+        if !creative {
+            let mut cost = vec![];
+
+            for (block_name, quantity) in prefab.block_counts() {
+                let item = blocks
+                    .from_id(block_name)
+                    .and_then(|block| block_items.item_from_block(block))
+                    .map(|item_id| items.from_numeric_id(item_id));
+
+                let Some(item) = item else {
+                    continue;
+                };
+
+                cost.push((item, quantity));
+            }
+
+            if !cost.iter().all(|(item, quantity)| inventory.can_take_item(item, *quantity)) {
+                reply(&mut nevw_chat, player, "You don't have the blocks needed to build this module.");
+                continue;
+            }
+
+            for (item, quantity) in cost {
+                inventory.take_and_remove_item(item, quantity, &mut commands);
+            }
+        }
+
+        for (coords, block) in placement.blocks {
+            structure.set_block_at(coords, block, placement.rotation, &blocks, Some(&mut evw_block_changed));
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(OnEnter(GameState::PostLoading), load_prefabs).add_systems(
+        Update,
+        on_place_prefab
+            .in_set(NetworkingSystemsSet::Between)
+            .in_set(BlockEventsSet::ChangeBlocks)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,168 @@
+//! Stations sell reactor fuel to the ships docked to them.
+//!
+//! While aboard a ship docked to a station, players can run `/refuel [amount]` to buy uranium for their reactors. The
+//! fuel goes straight into the ship's storage, and players are only charged for what fit. This is only available on
+//! servers where reactors need fuel.
+
+use bevy::prelude::*;
+use bevy_renet2::renet2::ClientId;
+use cosmos_core::{
+    block::{data::BlockData, Block},
+    chat::ServerSendChatMessageEvent,
+    economy::Credits,
+    inventory::{
+        itemstack::{ItemShouldHaveData, ItemStackSystemSet},
+        Inventory,
+    },
+    item::Item,
+    netty::{sync::events::server_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{ship::Ship, station::Station, systems::dock_system::Docked},
+};
+
+use crate::{
+    chat::{ChatCommandSet, ChatCommands, PlayerChatCommandEvent},
+    settings::ServerSettings,
+};
+
+const STORAGE_BLOCK: &str = "cosmos:storage";
+
+/// The fuel stations sell
+const FUEL_ITEM: &str = "cosmos:uranium";
+
+/// How many credits each fuel item costs
+const FUEL_PRICE: u64 = 25;
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+fn register_chat_commands(mut chat_commands: ResMut<ChatCommands>) {
+    chat_commands.add("refuel");
+}
+
+fn on_refuel_command(
+    mut commands: Commands,
+    mut evr_command: EventReader<PlayerChatCommandEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    settings: Res<ServerSettings>,
+    mut q_player: Query<(&Parent, &mut Credits)>,
+    q_docked_ship: Query<&Docked, With<Ship>>,
+    q_station: Query<(), With<Station>>,
+    mut q_storage: Query<(&BlockData, &mut Inventory)>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    needs_data: Res<ItemShouldHaveData>,
+) {
+    for ev in evr_command.read() {
+        if ev.name != "refuel" {
+            continue;
+        }
+
+        if !settings.reactor_fuel {
+            reply(&mut nevw_chat, ev.client_id, "Reactors don't need fuel on this server.");
+            continue;
+        }
+
+        let (Some(storage_block), Some(fuel)) = (blocks.from_id(STORAGE_BLOCK), items.from_id(FUEL_ITEM)) else {
+            continue;
+        };
+
+        let Ok((parent, mut credits)) = q_player.get_mut(ev.player_entity) else {
+            continue;
+        };
+
+        let ship = parent.get();
+
+        if !q_docked_ship.get(ship).is_ok_and(|docked| q_station.contains(docked.to)) {
+            reply(
+                &mut nevw_chat,
+                ev.client_id,
+                "You must be aboard a ship docked to a station to refuel.",
+            );
+            continue;
+        }
+
+        let requested = match ev.args.as_slice() {
+            [] => fuel.max_stack_size() as u64,
+            [amount] => match amount.parse::<u64>() {
+                Ok(amount) if amount > 0 => amount,
+                _ => {
+                    reply(&mut nevw_chat, ev.client_id, "Usage: /refuel [amount]");
+                    continue;
+                }
+            },
+            _ => {
+                reply(&mut nevw_chat, ev.client_id, "Usage: /refuel [amount]");
+                continue;
+            }
+        };
+
+        let affordable = requested.min(credits.amount() / FUEL_PRICE);
+        if affordable == 0 {
+            reply(
+                &mut nevw_chat,
+                ev.client_id,
+                format!("Fuel costs {FUEL_PRICE} credits each, but you only have {}.", credits.amount()),
+            );
+            continue;
+        }
+
+        let mut left_over = affordable;
+        for (_, mut inventory) in q_storage.iter_mut().filter(|(block_data, _)| {
+            block_data.identifier.block.structure() == ship && block_data.identifier.block_id == storage_block.id()
+        }) {
+            // Insert a stack at a time, since quantities are stored as u16s
+            while left_over != 0 {
+                let amount = left_over.min(fuel.max_stack_size() as u64) as u16;
+                let (not_inserted, _) = inventory.insert_item(fuel, amount, &mut commands, &needs_data);
+
+                left_over -= (amount - not_inserted) as u64;
+
+                if not_inserted != 0 {
+                    break;
+                }
+            }
+
+            if left_over == 0 {
+                break;
+            }
+        }
+
+        let bought = affordable - left_over;
+        if bought == 0 {
+            reply(&mut nevw_chat, ev.client_id, "Your ship has no room in its storage for fuel.");
+            continue;
+        }
+
+        let cost = bought * FUEL_PRICE;
+        credits.decrease(cost);
+
+        reply(
+            &mut nevw_chat,
+            ev.client_id,
+            format!(
+                "Bought {bought} fuel for {cost} credits. You have {} credits left.",
+                credits.amount()
+            ),
+        );
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(Startup, register_chat_commands).add_systems(
+        Update,
+        on_refuel_command
+            .after(ChatCommandSet::SendCommandEvents)
+            .in_set(ItemStackSystemSet::CreateDataEntity)
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
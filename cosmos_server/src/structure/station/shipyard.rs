@@ -0,0 +1,665 @@
+//! Shipyard terminals let stations build ships from blueprints, using the materials in the station's storage.
+//!
+//! Players pick a ship blueprint with the `/requisition [blueprint]` chat command, then interact with a shipyard
+//! terminal to order it there. Every interaction with the terminal reserves whatever materials the order still needs
+//! from the station's storage blocks and lists what is missing. Once every material has been reserved, the ship is
+//! built in front of the terminal.
+//!
+//! Reserved materials are kept by the terminal, and are put back into the station's storage if the terminal is broken.
+//!
+//! Players with an insurance claim (see [`super::insurance`]) who haven't picked a blueprint get their insured ship
+//! ordered instead. The insurance pays for part (or all) of its materials up front, and those are never put back into
+//! storage.
+
+use std::fs;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_renet2::renet2::ClientId;
+use cosmos_core::{
+    block::{
+        block_events::{BlockEventsSet, BlockInteractEvent},
+        block_face::BlockFace,
+        data::BlockData,
+        Block,
+    },
+    blockitems::BlockItems,
+    chat::ServerSendChatMessageEvent,
+    economy::Credits,
+    entities::player::Player,
+    events::block_events::BlockChangedEvent,
+    inventory::{itemstack::ItemShouldHaveData, Inventory},
+    item::Item,
+    netty::{
+        sync::{events::server_event::NettyEventWriter, IdentifiableComponent},
+        system_sets::NetworkingSystemsSet,
+    },
+    physics::location::Location,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        bill_of_materials::BillOfMaterials, coordinates::BlockCoordinate, ownership::StructureOwnership, station::Station, Structure,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    chat::{ChatCommandSet, ChatCommands, PlayerChatCommandEvent},
+    persistence::{
+        loading::{LoadingBlueprintSystemSet, NeedsBlueprintLoaded, LOADING_SCHEDULE},
+        make_persistent::{make_persistent, DefaultPersistentComponent},
+    },
+    structure::{
+        ownership::{notify_no_permission, StructurePermissions},
+        persistence::{read_ship_blueprint, read_ship_blueprint_at, ship_blueprint_path},
+    },
+};
+
+use super::insurance::InsurancePolicies;
+
+const SHIPYARD_TERMINAL_BLOCK: &str = "cosmos:shipyard_terminal";
+const STORAGE_BLOCK: &str = "cosmos:storage";
+
+/// How far in front of the terminal ships are built
+const BUILD_DISTANCE: f32 = 30.0;
+
+/// The most missing materials listed at once, so the chat isn't flooded
+const MAX_MISSING_LISTED: usize = 8;
+
+#[derive(Component, Debug)]
+/// The ship blueprint this player will order at the next shipyard terminal they use
+struct SelectedBlueprint(String);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ShipyardOrder {
+    /// The shipyard terminal this order was placed at
+    terminal: BlockCoordinate,
+    /// The name of the ship blueprint being built
+    blueprint: String,
+    /// Every material (by unlocalized name) taken from storage or paid for by insurance for this order so far
+    reserved: HashMap<String, u32>,
+    /// The path to the insured ship's blueprint, if this order is redeeming an insurance claim
+    #[serde(default)]
+    insured_blueprint_path: Option<String>,
+    /// The part of [`Self::reserved`] that was paid for by insurance, which is never put back into storage
+    #[serde(default)]
+    insured: HashMap<String, u32>,
+}
+
+impl ShipyardOrder {
+    fn blueprint_path(&self) -> String {
+        self.insured_blueprint_path
+            .clone()
+            .unwrap_or_else(|| ship_blueprint_path(&self.blueprint))
+    }
+
+    /// Computes the materials needed to build this order's ship, or [`None`] if its blueprint doesn't exist
+    fn bill_of_materials(&self, blocks: &Registry<Block>, block_items: &BlockItems) -> Option<BillOfMaterials> {
+        let structure = match &self.insured_blueprint_path {
+            Some(path) => read_ship_blueprint_at(path),
+            None => read_ship_blueprint(&self.blueprint),
+        };
+
+        structure.map(|structure| BillOfMaterials::from_structure(&structure, blocks, block_items))
+    }
+
+    /// The materials taken from storage for this order, which are put back if the order is cancelled
+    fn refundable(&self) -> HashMap<String, u32> {
+        self.reserved
+            .iter()
+            .map(|(item, &quantity)| (item.clone(), quantity - self.insured.get(item).copied().unwrap_or(0).min(quantity)))
+            .filter(|(_, quantity)| *quantity != 0)
+            .collect()
+    }
+}
+
+#[derive(Component, Debug)]
+/// Placed on a ship being built from an insurance blueprint, so the blueprint can be removed once it has loaded
+struct BuiltFromInsurance {
+    path: String,
+}
+
+#[derive(Component, Debug, Default, Serialize, Deserialize)]
+/// Every order placed at the shipyard terminals of this station
+struct ShipyardOrders(Vec<ShipyardOrder>);
+
+impl IdentifiableComponent for ShipyardOrders {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:shipyard_orders"
+    }
+}
+
+impl DefaultPersistentComponent for ShipyardOrders {}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+/// Computes the materials needed to build this ship blueprint, or [`None`] if it doesn't exist
+fn blueprint_bill_of_materials(blueprint: &str, blocks: &Registry<Block>, block_items: &BlockItems) -> Option<BillOfMaterials> {
+    read_ship_blueprint(blueprint).map(|structure| BillOfMaterials::from_structure(&structure, blocks, block_items))
+}
+
+/// Turns this player's insurance claim into an order, with the insurance paying for its share of the materials.
+///
+/// If the claim's blueprint is missing, the claim is refunded instead. Returns [`None`] if there is no order to place.
+fn redeem_insurance_claim(
+    player_ent: Entity,
+    player: &Player,
+    terminal: BlockCoordinate,
+    insurance_policies: &mut InsurancePolicies,
+    q_credits: &mut Query<&mut Credits>,
+    blocks: &Registry<Block>,
+    items: &Registry<Item>,
+    block_items: &BlockItems,
+    nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>,
+) -> Option<ShipyardOrder> {
+    let Some(policy) = insurance_policies.claim(player.name()) else {
+        reply(
+            nevw_chat,
+            player.id(),
+            "Pick a ship blueprint to build with /requisition [ship blueprint], then use this terminal again.",
+        );
+        return None;
+    };
+
+    let path = policy.blueprint_path();
+    let coverage = policy.coverage();
+
+    let Some(structure) = read_ship_blueprint_at(&path) else {
+        error!("The blueprint of an insured ship is missing ({path}) - refunding its policy.");
+
+        let policy_id = policy.id().to_owned();
+        let policy = insurance_policies.remove(&policy_id).expect("Policy was found above");
+
+        if let Ok(mut credits) = q_credits.get_mut(player_ent) {
+            credits.increase(policy.fee());
+        }
+
+        reply(
+            nevw_chat,
+            player.id(),
+            format!(
+                "Your insured ship's blueprint was lost, so you have been refunded the {} credits you paid for it.",
+                policy.fee()
+            ),
+        );
+        return None;
+    };
+
+    let policy_id = policy.id().to_owned();
+    insurance_policies.remove(&policy_id);
+
+    let bill = BillOfMaterials::from_structure(&structure, blocks, block_items);
+
+    let insured = bill
+        .iter()
+        .map(|(item_id, quantity)| {
+            (
+                items.from_numeric_id(item_id).unlocalized_name().to_owned(),
+                (quantity as f32 * coverage) as u32,
+            )
+        })
+        .filter(|(_, quantity)| *quantity != 0)
+        .collect::<HashMap<_, _>>();
+
+    reply(
+        nevw_chat,
+        player.id(),
+        format!(
+            "Redeeming your insurance claim. Your ship had {:.0}% of its blocks left when it was destroyed, so that much of its materials are covered.",
+            coverage * 100.0
+        ),
+    );
+
+    Some(ShipyardOrder {
+        terminal,
+        blueprint: "your insured ship".into(),
+        reserved: insured.clone(),
+        insured_blueprint_path: Some(path),
+        insured,
+    })
+}
+
+/// Lists the materials in this bill, for sending to a player
+fn describe_materials(bill: &BillOfMaterials, items: &Registry<Item>) -> String {
+    let mut materials = bill
+        .iter()
+        .map(|(item_id, quantity)| (items.from_numeric_id(item_id).unlocalized_name(), quantity))
+        .collect::<Vec<_>>();
+
+    // Show the materials that are needed the most first
+    materials.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+    let mut description = materials
+        .iter()
+        .take(MAX_MISSING_LISTED)
+        .map(|(name, quantity)| format!("{quantity}x {name}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if materials.len() > MAX_MISSING_LISTED {
+        description.push_str(&format!(" and {} more", materials.len() - MAX_MISSING_LISTED));
+    }
+
+    description
+}
+
+fn register_chat_commands(mut chat_commands: ResMut<ChatCommands>) {
+    chat_commands.add("requisition");
+}
+
+fn on_requisition_command(
+    mut commands: Commands,
+    mut evr_command: EventReader<PlayerChatCommandEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    block_items: Res<BlockItems>,
+) {
+    for ev in evr_command.read() {
+        if ev.name != "requisition" {
+            continue;
+        }
+
+        let [blueprint] = ev.args.as_slice() else {
+            commands.entity(ev.player_entity).remove::<SelectedBlueprint>();
+            reply(
+                &mut nevw_chat,
+                ev.client_id,
+                "Usage: /requisition [ship blueprint] - then use a shipyard terminal to order it.",
+            );
+            continue;
+        };
+
+        let Some(bill) = blueprint_bill_of_materials(blueprint, &blocks, &block_items) else {
+            reply(
+                &mut nevw_chat,
+                ev.client_id,
+                format!("There is no ship blueprint named {blueprint}."),
+            );
+            continue;
+        };
+
+        reply(
+            &mut nevw_chat,
+            ev.client_id,
+            format!(
+                "{blueprint} needs {} materials: {}. Use a shipyard terminal to order it.",
+                bill.total_items(),
+                describe_materials(&bill, &items)
+            ),
+        );
+
+        if bill.unobtainable_blocks() != 0 {
+            reply(
+                &mut nevw_chat,
+                ev.client_id,
+                format!(
+                    "{} of its blocks have no item, so they don't need any materials.",
+                    bill.unobtainable_blocks()
+                ),
+            );
+        }
+
+        commands.entity(ev.player_entity).insert(SelectedBlueprint(blueprint.clone()));
+    }
+}
+
+/// The materials this order needs that haven't been reserved yet
+fn still_needed(order: &ShipyardOrder, bill: &BillOfMaterials, items: &Registry<Item>) -> BillOfMaterials {
+    bill.missing(|item_id| {
+        order
+            .reserved
+            .get(items.from_numeric_id(item_id).unlocalized_name())
+            .copied()
+            .unwrap_or(0)
+    })
+}
+
+/// Takes as much of what this order still needs as possible out of these storage inventories
+fn reserve_materials(
+    commands: &mut Commands,
+    order: &mut ShipyardOrder,
+    bill: &BillOfMaterials,
+    storages: &mut [Mut<Inventory>],
+    items: &Registry<Item>,
+) {
+    for (item_id, mut needed) in still_needed(order, bill, items).iter() {
+        let item = items.from_numeric_id(item_id);
+
+        for inventory in storages.iter_mut() {
+            let taking = (inventory.quantity_of(item) as u32).min(needed);
+            if taking == 0 {
+                continue;
+            }
+
+            // Never takes more than the inventory has, so every stack taken is removed
+            let _ = inventory.take_and_remove_item(item, taking as usize, commands);
+
+            *order.reserved.entry(item.unlocalized_name().to_owned()).or_default() += taking;
+            needed -= taking;
+
+            if needed == 0 {
+                break;
+            }
+        }
+    }
+}
+
+/// Puts these reserved materials back into these storage inventories, returning how many couldn't fit
+fn return_materials(
+    commands: &mut Commands,
+    reserved: HashMap<String, u32>,
+    storages: &mut [Mut<Inventory>],
+    items: &Registry<Item>,
+    needs_data: &ItemShouldHaveData,
+) -> u32 {
+    let mut lost = 0;
+
+    for (item_name, mut quantity) in reserved {
+        let Some(item) = items.from_id(&item_name) else {
+            continue;
+        };
+
+        for inventory in storages.iter_mut() {
+            while quantity != 0 {
+                let batch = quantity.min(u16::MAX as u32) as u16;
+                let (leftover, _) = inventory.insert_item(item, batch, commands, needs_data);
+
+                quantity -= (batch - leftover) as u32;
+
+                if leftover != 0 {
+                    break;
+                }
+            }
+
+            if quantity == 0 {
+                break;
+            }
+        }
+
+        lost += quantity;
+    }
+
+    lost
+}
+
+fn station_storages<'a>(
+    q_storage: &'a mut Query<(&BlockData, &mut Inventory)>,
+    station: Entity,
+    storage_block: &Block,
+) -> Vec<Mut<'a, Inventory>> {
+    q_storage
+        .iter_mut()
+        .filter(|(block_data, _)| {
+            block_data.identifier.block.structure() == station && block_data.identifier.block_id == storage_block.id()
+        })
+        .map(|(_, inventory)| inventory)
+        .collect()
+}
+
+fn on_interact_with_shipyard(
+    mut commands: Commands,
+    mut evr_interact: EventReader<BlockInteractEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    block_items: Res<BlockItems>,
+    needs_data: Res<ItemShouldHaveData>,
+    mut q_station: Query<(&Structure, &Location, &GlobalTransform, Option<&mut ShipyardOrders>), With<Station>>,
+    q_player: Query<(&Player, Option<&SelectedBlueprint>)>,
+    mut q_credits: Query<&mut Credits>,
+    mut q_storage: Query<(&BlockData, &mut Inventory)>,
+    permissions: StructurePermissions,
+    mut insurance_policies: ResMut<InsurancePolicies>,
+) {
+    let Some(storage_block) = blocks.from_id(STORAGE_BLOCK) else {
+        return;
+    };
+
+    for ev in evr_interact.read() {
+        let Some(s_block) = ev.block else {
+            continue;
+        };
+
+        let station = s_block.structure();
+
+        let Ok((structure, station_loc, g_trans, orders)) = q_station.get_mut(station) else {
+            continue;
+        };
+
+        if structure.block_at(s_block.coords(), &blocks).unlocalized_name() != SHIPYARD_TERMINAL_BLOCK {
+            continue;
+        }
+
+        let Ok((player, selected)) = q_player.get(ev.interactor) else {
+            continue;
+        };
+
+        if !permissions.can_use(ev.interactor, station) {
+            notify_no_permission(&mut nevw_chat, player.id());
+            continue;
+        }
+
+        let terminal = s_block.coords();
+
+        let mut order = match orders.as_ref().and_then(|o| o.0.iter().position(|x| x.terminal == terminal)) {
+            Some(idx) => orders.as_ref().expect("Checked above").0[idx].clone(),
+            None => match selected {
+                Some(selected) => {
+                    commands.entity(ev.interactor).remove::<SelectedBlueprint>();
+
+                    ShipyardOrder {
+                        terminal,
+                        blueprint: selected.0.clone(),
+                        reserved: Default::default(),
+                        insured_blueprint_path: None,
+                        insured: Default::default(),
+                    }
+                }
+                None => {
+                    let Some(order) = redeem_insurance_claim(
+                        ev.interactor,
+                        player,
+                        terminal,
+                        &mut insurance_policies,
+                        &mut q_credits,
+                        &blocks,
+                        &items,
+                        &block_items,
+                        &mut nevw_chat,
+                    ) else {
+                        continue;
+                    };
+
+                    order
+                }
+            },
+        };
+
+        let mut storages = station_storages(&mut q_storage, station, storage_block);
+
+        let Some(bill) = order.bill_of_materials(&blocks, &block_items) else {
+            let lost = return_materials(&mut commands, order.refundable(), &mut storages, &items, &needs_data);
+            if lost != 0 {
+                warn!("{lost} items reserved by a shipyard terminal were lost because its storage was full.");
+            }
+
+            if let Some(mut orders) = orders {
+                orders.0.retain(|x| x.terminal != terminal);
+            }
+
+            reply(
+                &mut nevw_chat,
+                player.id(),
+                format!("The blueprint {} no longer exists, so its order was cancelled.", order.blueprint),
+            );
+            continue;
+        };
+
+        reserve_materials(&mut commands, &mut order, &bill, &mut storages, &items);
+
+        let missing = still_needed(&order, &bill, &items);
+
+        let built = finish_order(
+            &mut commands,
+            &mut nevw_chat,
+            player,
+            &order,
+            &missing,
+            structure,
+            station_loc,
+            g_trans,
+            &items,
+        );
+
+        match orders {
+            Some(mut orders) => {
+                orders.0.retain(|x| x.terminal != terminal);
+
+                if !built {
+                    orders.0.push(order);
+                }
+            }
+            None => {
+                if !built {
+                    commands.entity(station).insert(ShipyardOrders(vec![order]));
+                }
+            }
+        }
+    }
+}
+
+/// Builds the ship if nothing is missing, otherwise tells the player what's still needed.
+///
+/// Returns true if the ship is being built.
+fn finish_order(
+    commands: &mut Commands,
+    nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>,
+    player: &Player,
+    order: &ShipyardOrder,
+    missing: &BillOfMaterials,
+    structure: &Structure,
+    station_loc: &Location,
+    g_trans: &GlobalTransform,
+    items: &Registry<Item>,
+) -> bool {
+    if !missing.is_empty() {
+        reply(
+            nevw_chat,
+            player.id(),
+            format!(
+                "Still missing {} materials for {}: {}. Add them to this station's storage and use this terminal again.",
+                missing.total_items(),
+                order.blueprint,
+                describe_materials(missing, items)
+            ),
+        );
+
+        return false;
+    }
+
+    let rotation = g_trans.rotation();
+    let facing = rotation * structure.block_rotation(order.terminal).direction_of(BlockFace::Front).as_vec3();
+    let terminal_loc = *station_loc + rotation * structure.block_relative_position(order.terminal);
+    let spawn_at = terminal_loc + facing * BUILD_DISTANCE;
+
+    let path = order.blueprint_path();
+
+    let mut ship = commands.spawn((
+        spawn_at,
+        NeedsBlueprintLoaded {
+            spawn_at,
+            rotation,
+            path: path.clone(),
+        },
+        // Blueprints don't store who owns them
+        StructureOwnership::new(player.name()),
+    ));
+
+    if order.insured_blueprint_path.is_some() {
+        ship.insert(BuiltFromInsurance { path });
+    }
+
+    reply(nevw_chat, player.id(), format!("Construction of {} has begun.", order.blueprint));
+
+    true
+}
+
+/// If a shipyard terminal is broken, the materials reserved by its order are put back into the station's storage
+fn refund_broken_terminals(
+    mut commands: Commands,
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    needs_data: Res<ItemShouldHaveData>,
+    mut q_orders: Query<&mut ShipyardOrders>,
+    mut q_storage: Query<(&BlockData, &mut Inventory)>,
+) {
+    let Some(storage_block) = blocks.from_id(STORAGE_BLOCK) else {
+        return;
+    };
+
+    for ev in evr_block_changed.read() {
+        if blocks.from_numeric_id(ev.old_block).unlocalized_name() != SHIPYARD_TERMINAL_BLOCK
+            || blocks.from_numeric_id(ev.new_block).unlocalized_name() == SHIPYARD_TERMINAL_BLOCK
+        {
+            continue;
+        }
+
+        let station = ev.block.structure();
+
+        let Ok(mut orders) = q_orders.get_mut(station) else {
+            continue;
+        };
+
+        let Some(idx) = orders.0.iter().position(|x| x.terminal == ev.block.coords()) else {
+            continue;
+        };
+
+        let order = orders.0.remove(idx);
+
+        let mut storages = station_storages(&mut q_storage, station, storage_block);
+        let lost = return_materials(&mut commands, order.refundable(), &mut storages, &items, &needs_data);
+
+        if lost != 0 {
+            warn!("{lost} items reserved by a broken shipyard terminal were lost because its station's storage was full.");
+        }
+    }
+}
+
+fn cleanup_insurance_blueprints(mut commands: Commands, q_built: Query<(Entity, &BuiltFromInsurance), Without<NeedsBlueprintLoaded>>) {
+    for (ent, built) in q_built.iter() {
+        commands.entity(ent).remove::<BuiltFromInsurance>();
+
+        if let Err(e) = fs::remove_file(&built.path) {
+            warn!("Unable to remove insurance blueprint {}\n{e}", built.path);
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<ShipyardOrders>(app);
+
+    app.add_systems(Startup, register_chat_commands)
+        .add_systems(
+            Update,
+            (
+                on_requisition_command.after(ChatCommandSet::SendCommandEvents),
+                on_interact_with_shipyard.in_set(BlockEventsSet::ProcessEvents),
+                refund_broken_terminals.in_set(BlockEventsSet::PostProcessEvents),
+            )
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            LOADING_SCHEDULE,
+            cleanup_insurance_blueprints.after(LoadingBlueprintSystemSet::DoneLoadingBlueprints),
+        );
+}
@@ -5,6 +5,7 @@ use cosmos_core::{
     netty::{
         cosmos_encoder,
         netty_rigidbody::{NettyRigidBody, NettyRigidBodyLocation},
+        network_stats::{NettyMessage, NettyServerMessages},
         server_reliable_messages::ServerReliableMessages,
         sync::server_entity_syncing::RequestedEntityEvent,
         system_sets::NetworkingSystemsSet,
@@ -23,7 +24,7 @@ fn on_request_station(
 ) {
     for ev in event_reader.read() {
         if let Ok((structure, transform, location, velocity)) = query.get(ev.entity) {
-            // server.send_message(
+            // server.send_netty_message(
             //     ev.client_id,
             //     NettyChannelServer::Reliable,
             //     cosmos_encoder::serialize(&ServerReliableMessages::NumberOfChunks {
@@ -34,10 +35,10 @@ fn on_request_station(
             //     }),
             // );
 
-            server.send_message(
+            server.send_netty_message(
                 ev.client_id,
                 NettyChannelServer::Reliable,
-                cosmos_encoder::serialize(&ServerReliableMessages::Station {
+                NettyMessage::new(&ServerReliableMessages::Station {
                     entity: ev.entity,
                     body: NettyRigidBody::new(Some(*velocity), transform.rotation, NettyRigidBodyLocation::Absolute(*location)),
                     dimensions: structure.chunk_dimensions(),
@@ -0,0 +1,85 @@
+//! Lets pilots rename their ship via the `/rename [name]` chat command
+
+use bevy::prelude::*;
+use cosmos_core::{
+    chat::ServerSendChatMessageEvent,
+    netty::{sync::events::server_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    state::GameState,
+    structure::{
+        ship::{pilot::Pilot, Ship},
+        structure_name::{StructureName, MAX_STRUCTURE_NAME_LENGTH},
+    },
+};
+use renet2::ClientId;
+
+use crate::{
+    chat::{ChatCommandSet, ChatCommands, PlayerChatCommandEvent},
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+};
+
+use super::ownership::{notify_no_permission, StructurePermissions};
+
+impl DefaultPersistentComponent for StructureName {}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+fn register_chat_commands(mut chat_commands: ResMut<ChatCommands>) {
+    chat_commands.add("rename");
+}
+
+fn on_rename_command(
+    mut commands: Commands,
+    mut evr_command: EventReader<PlayerChatCommandEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    q_pilot: Query<&Pilot>,
+    q_ship: Query<(), With<Ship>>,
+    permissions: StructurePermissions,
+) {
+    for ev in evr_command.read() {
+        if ev.name != "rename" {
+            continue;
+        }
+
+        let Some(ship_entity) = q_pilot.get(ev.player_entity).ok().map(|x| x.entity).filter(|e| q_ship.contains(*e)) else {
+            reply(&mut nevw_chat, ev.client_id, "You must be piloting a ship to rename it.");
+            continue;
+        };
+
+        if !permissions.can_use(ev.player_entity, ship_entity) {
+            notify_no_permission(&mut nevw_chat, ev.client_id);
+            continue;
+        }
+
+        let Some(name) = StructureName::new(ev.args.join(" ")) else {
+            reply(
+                &mut nevw_chat,
+                ev.client_id,
+                format!("Usage: /rename [name] - names can be up to {MAX_STRUCTURE_NAME_LENGTH} characters long."),
+            );
+            continue;
+        };
+
+        reply(&mut nevw_chat, ev.client_id, format!("Your ship is now named {}.", name.name()));
+        commands.entity(ship_entity).insert(name);
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<StructureName>(app);
+
+    app.add_systems(Startup, register_chat_commands).add_systems(
+        Update,
+        on_rename_command
+            .after(ChatCommandSet::SendCommandEvents)
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -0,0 +1,75 @@
+//! Lets pilots change which systems each activation group of their ship activates
+
+use bevy::prelude::*;
+use cosmos_core::{
+    netty::{server::ServerLobby, sync::events::server_event::NettyEventReceived, system_sets::NetworkingSystemsSet},
+    state::GameState,
+    structure::{
+        ship::pilot::Pilot,
+        systems::{
+            activation_groups::{ActivationGroups, SetActivationGroupEvent, N_ACTIVATION_GROUPS},
+            StructureSystems,
+        },
+    },
+};
+
+use crate::persistence::make_persistent::{make_persistent, DefaultPersistentComponent};
+
+impl DefaultPersistentComponent for ActivationGroups {}
+
+fn on_set_activation_group(
+    mut commands: Commands,
+    mut nevr_set_group: EventReader<NettyEventReceived<SetActivationGroupEvent>>,
+    lobby: Res<ServerLobby>,
+    q_pilot: Query<&Pilot>,
+    mut q_ship: Query<(&StructureSystems, Option<&mut ActivationGroups>)>,
+) {
+    for ev in nevr_set_group.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        // Only the pilot gets to decide what their hotbar does
+        let Ok(pilot) = q_pilot.get(player_ent) else {
+            continue;
+        };
+
+        let Ok((systems, groups)) = q_ship.get_mut(pilot.entity) else {
+            continue;
+        };
+
+        if ev.group as usize >= N_ACTIVATION_GROUPS {
+            continue;
+        }
+
+        if !systems.activatable_system_types().any(|x| x == ev.system_type) {
+            warn!("Player {player_ent:?} tried to add a system their ship doesn't have to an activation group.");
+            continue;
+        }
+
+        match groups {
+            Some(mut groups) => {
+                groups.set_in_group(ev.group as usize, &ev.system_type, ev.in_group);
+            }
+            None => {
+                // Keep the groups the pilot was already using, and change them from there
+                let mut groups = systems.default_activation_groups();
+                groups.set_in_group(ev.group as usize, &ev.system_type, ev.in_group);
+
+                commands.entity(pilot.entity).insert(groups);
+            }
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<ActivationGroups>(app);
+
+    app.add_systems(
+        Update,
+        on_set_activation_group
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
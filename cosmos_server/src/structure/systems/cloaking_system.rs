@@ -0,0 +1,155 @@
+//! Server-side cloaking device logic
+
+use bevy::{prelude::*, utils::HashSet};
+use cosmos_core::{
+    block::{block_events::BlockEventsSet, Block},
+    events::block_events::BlockChangedEvent,
+    netty::system_sets::NetworkingSystemsSet,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        events::StructureLoadedEvent,
+        systems::{
+            cloaking_system::{Cloaked, CloakingSystem, CLOAKING_DEVICE_BLOCK, CLOAK_ENERGY_PER_BLOCK_PER_SECOND},
+            energy_priority::{EnergyBrownout, EnergyConsumer},
+            energy_storage_system::EnergyStorageSystem,
+            StructureSystem, StructureSystemType, StructureSystems, StructureSystemsSet, SystemActive,
+        },
+        Structure,
+    },
+};
+
+use super::sync::register_structure_system;
+
+#[derive(Event, Debug, Clone, Copy)]
+/// Sent whenever a structure fires any of its weapons.
+///
+/// Cloaked structures are forced to decloak when they fire.
+pub struct WeaponsFiredEvent {
+    /// The structure that fired
+    pub structure_entity: Entity,
+}
+
+fn cloaking_block_update_system(
+    mut event: EventReader<BlockChangedEvent>,
+    blocks: Res<Registry<Block>>,
+    mut system_query: Query<&mut CloakingSystem>,
+    q_systems: Query<&StructureSystems>,
+) {
+    for ev in event.read() {
+        let Ok(systems) = q_systems.get(ev.block.structure()) else {
+            continue;
+        };
+
+        let Ok(mut system) = systems.query_mut(&mut system_query) else {
+            continue;
+        };
+
+        if blocks.from_numeric_id(ev.old_block).unlocalized_name() == CLOAKING_DEVICE_BLOCK {
+            system.block_removed();
+        }
+
+        if blocks.from_numeric_id(ev.new_block).unlocalized_name() == CLOAKING_DEVICE_BLOCK {
+            system.block_added();
+        }
+    }
+}
+
+fn cloaking_structure_loaded_event_processor(
+    mut event_reader: EventReader<StructureLoadedEvent>,
+    mut structure_query: Query<(&Structure, &mut StructureSystems)>,
+    blocks: Res<Registry<Block>>,
+    mut commands: Commands,
+    registry: Res<Registry<StructureSystemType>>,
+) {
+    for ev in event_reader.read() {
+        if let Ok((structure, mut systems)) = structure_query.get_mut(ev.structure_entity) {
+            let mut system = CloakingSystem::default();
+
+            for block in structure.all_blocks_iter(false) {
+                if structure.block_at(block, &blocks).unlocalized_name() == CLOAKING_DEVICE_BLOCK {
+                    system.block_added();
+                }
+            }
+
+            systems.add_system(&mut commands, system, &registry);
+        }
+    }
+}
+
+fn update_cloaks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut evr_weapons_fired: EventReader<WeaponsFiredEvent>,
+    mut q_cloaking_system: Query<(&mut CloakingSystem, &StructureSystem, Option<Ref<SystemActive>>)>,
+    q_structure: Query<(&Structure, &StructureSystems, Has<Cloaked>, Option<&EnergyBrownout>)>,
+    mut q_energy_storage_system: Query<&mut EnergyStorageSystem>,
+) {
+    let fired = evr_weapons_fired.read().map(|ev| ev.structure_entity).collect::<HashSet<_>>();
+
+    let delta = time.delta_secs();
+
+    for (mut cloaking_system, system, system_active) in q_cloaking_system.iter_mut() {
+        let structure_entity = system.structure_entity();
+
+        let Ok((structure, systems, cloaked, brownout)) = q_structure.get(structure_entity) else {
+            continue;
+        };
+
+        if cloaking_system.is_cooling_down() {
+            cloaking_system.tick_cooldown(delta);
+        }
+
+        let toggled = system_active.is_some_and(|x| x.is_added());
+        let browned_out = brownout.is_some_and(|b| b.contains(EnergyConsumer::Utility));
+        let n_blocks = structure.block_counts().map(|counts| counts.total()).unwrap_or(0);
+
+        let Ok(mut energy_storage_system) = systems.query_mut(&mut q_energy_storage_system) else {
+            continue;
+        };
+
+        if cloaked {
+            let energy_needed = n_blocks as f32 * CLOAK_ENERGY_PER_BLOCK_PER_SECOND * delta;
+
+            let decloak = toggled
+                || fired.contains(&structure_entity)
+                || !cloaking_system.can_hide(n_blocks)
+                || browned_out
+                || energy_storage_system.decrease_energy(energy_needed) != 0.0;
+
+            if decloak {
+                commands.entity(structure_entity).remove::<Cloaked>();
+                cloaking_system.start_cooldown();
+            }
+        } else if toggled
+            && !cloaking_system.is_cooling_down()
+            && cloaking_system.can_hide(n_blocks)
+            && !browned_out
+            && energy_storage_system.get_energy() > 0.0
+        {
+            commands.entity(structure_entity).insert(Cloaked);
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_event::<WeaponsFiredEvent>()
+        .add_systems(
+            Update,
+            (
+                cloaking_structure_loaded_event_processor
+                    .in_set(StructureSystemsSet::InitSystems)
+                    .ambiguous_with(StructureSystemsSet::InitSystems),
+                cloaking_block_update_system
+                    .in_set(BlockEventsSet::ProcessEvents)
+                    .in_set(StructureSystemsSet::UpdateSystemsBlocks),
+                update_cloaks
+                    .in_set(StructureSystemsSet::UpdateSystems)
+                    .in_set(NetworkingSystemsSet::Between),
+            )
+                .run_if(in_state(GameState::Playing)),
+        )
+        .register_type::<CloakingSystem>();
+
+    register_structure_system::<CloakingSystem>(app, true, CLOAKING_DEVICE_BLOCK);
+}
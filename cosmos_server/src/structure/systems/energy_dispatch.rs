@@ -0,0 +1,105 @@
+//! Decides which energy consumers of each structure are browned out before the structure's systems use any energy,
+//! and lets pilots reorder their ship's energy priorities.
+
+use bevy::prelude::*;
+use cosmos_core::{
+    netty::{server::ServerLobby, sync::events::server_event::NettyEventReceived, system_sets::NetworkingSystemsSet},
+    state::GameState,
+    structure::{
+        ship::pilot::Pilot,
+        systems::{
+            energy_priority::{EnergyBrownout, EnergyPriorities, RaiseEnergyPriorityEvent},
+            energy_storage_system::EnergyStorageSystem,
+            StructureSystems, StructureSystemsSet,
+        },
+    },
+};
+
+use crate::persistence::make_persistent::{make_persistent, DefaultPersistentComponent};
+
+impl DefaultPersistentComponent for EnergyPriorities {}
+
+/// Systems that use energy skip their consumer while it's in the structure's [`EnergyBrownout`]
+fn dispatch_energy(
+    mut commands: Commands,
+    mut q_structures: Query<(Entity, &StructureSystems, Option<&EnergyPriorities>, Option<&mut EnergyBrownout>)>,
+    q_energy_storage: Query<&EnergyStorageSystem>,
+) {
+    for (ent, systems, priorities, brownout) in q_structures.iter_mut() {
+        let Ok(storage) = systems.query(&q_energy_storage) else {
+            continue;
+        };
+
+        let fraction = if storage.get_capacity() > 0.0 {
+            storage.get_energy() / storage.get_capacity()
+        } else {
+            0.0
+        };
+
+        let default_priorities = EnergyPriorities::default();
+        let priorities = priorities.unwrap_or(&default_priorities);
+
+        match brownout {
+            Some(mut brownout) => {
+                let new_brownout = priorities.browned_out(fraction, &brownout);
+                brownout.set_if_neq(new_brownout);
+            }
+            None => {
+                commands
+                    .entity(ent)
+                    .insert(priorities.browned_out(fraction, &EnergyBrownout::default()));
+            }
+        }
+    }
+}
+
+fn on_raise_energy_priority(
+    mut commands: Commands,
+    mut nevr_raise: EventReader<NettyEventReceived<RaiseEnergyPriorityEvent>>,
+    lobby: Res<ServerLobby>,
+    q_pilot: Query<&Pilot>,
+    mut q_priorities: Query<Option<&mut EnergyPriorities>, With<StructureSystems>>,
+) {
+    for ev in nevr_raise.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        // Only the pilot gets to decide where their ship's energy goes
+        let Ok(pilot) = q_pilot.get(player_ent) else {
+            continue;
+        };
+
+        let Ok(priorities) = q_priorities.get_mut(pilot.entity) else {
+            continue;
+        };
+
+        match priorities {
+            Some(mut priorities) => priorities.raise(ev.consumer),
+            None => {
+                let mut priorities = EnergyPriorities::default();
+                priorities.raise(ev.consumer);
+
+                commands.entity(pilot.entity).insert(priorities);
+            }
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<EnergyPriorities>(app);
+
+    app.add_systems(
+        Update,
+        (
+            on_raise_energy_priority,
+            dispatch_energy
+                .after(StructureSystemsSet::UpdateSystemsBlocks)
+                .before(StructureSystemsSet::UpdateSystems),
+        )
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
@@ -71,7 +71,6 @@ fn update_energy(
 fn structure_loaded_event(
     mut event_reader: EventReader<StructureLoadedEvent>,
     mut structure_query: Query<(&Structure, &mut StructureSystems)>,
-    blocks: Res<Registry<Block>>,
     mut commands: Commands,
     energy_generation_blocks: Res<EnergyGenerationBlocks>,
     registry: Res<Registry<StructureSystemType>>,
@@ -80,9 +79,11 @@ fn structure_loaded_event(
         if let Ok((structure, mut systems)) = structure_query.get_mut(ev.structure_entity) {
             let mut system = EnergyGenerationSystem::default();
 
-            for block in structure.all_blocks_iter(false) {
-                if let Some(prop) = energy_generation_blocks.get(structure.block_at(block, &blocks)) {
-                    system.block_added(prop);
+            if let Some(counts) = structure.block_counts() {
+                for (id, prop) in energy_generation_blocks.iter() {
+                    for _ in 0..counts.count(id) {
+                        system.block_added(prop);
+                    }
                 }
             }
 
@@ -0,0 +1,56 @@
+//! Fills out the energy roles of blocks based on what the structure systems do with them
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::{specific_blocks::solar_panel::SOLAR_PANEL_BLOCK, Block},
+    item::battery::BATTERY_CHARGER_BLOCK,
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::systems::{
+        energy_generation_system::EnergyGenerationBlocks,
+        energy_roles::{BlockEnergyRoles, EnergyRoles},
+        energy_storage_system::EnergyStorageBlocks,
+        laser_cannon_system::LaserCannonProperty,
+        line_system::LineBlocks,
+        mining_laser_system::MiningLaserProperty,
+        missile_launcher_system::MissileLauncherProperty,
+        shield_system::{ShieldGeneratorBlocks, ShieldProjectorBlocks},
+        thruster_system::ThrusterBlocks,
+    },
+};
+
+/// The systems fill out their block properties in [`GameState::PostLoading`], so this is done once they're all ready.
+fn register_energy_roles(
+    blocks: Res<Registry<Block>>,
+    generation_blocks: Res<EnergyGenerationBlocks>,
+    storage_blocks: Res<EnergyStorageBlocks>,
+    thruster_blocks: Res<ThrusterBlocks>,
+    laser_cannon_blocks: Res<LineBlocks<LaserCannonProperty>>,
+    mining_laser_blocks: Res<LineBlocks<MiningLaserProperty>>,
+    missile_launcher_blocks: Res<LineBlocks<MissileLauncherProperty>>,
+    shield_generator_blocks: Res<ShieldGeneratorBlocks>,
+    shield_projector_blocks: Res<ShieldProjectorBlocks>,
+    mut energy_roles: ResMut<Registry<BlockEnergyRoles>>,
+) {
+    for block in blocks.iter() {
+        let roles = EnergyRoles {
+            generator: generation_blocks.get(block).is_some() || block.unlocalized_name() == SOLAR_PANEL_BLOCK,
+            storage: storage_blocks.get(block).is_some(),
+            consumer: thruster_blocks.get(block).is_some()
+                || laser_cannon_blocks.get(block).is_some()
+                || mining_laser_blocks.get(block).is_some()
+                || missile_launcher_blocks.get(block).is_some()
+                || shield_generator_blocks.0.contains_key(&block.id())
+                || shield_projector_blocks.0.contains_key(&block.id())
+                || block.unlocalized_name() == BATTERY_CHARGER_BLOCK,
+        };
+
+        if !roles.is_empty() {
+            energy_roles.register(BlockEnergyRoles::new(block, roles));
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Playing), register_energy_roles);
+}
@@ -7,9 +7,12 @@ use bevy_rapier3d::{plugin::RapierContextEntityLink, prelude::Velocity};
 use bevy_renet2::renet2::RenetServer;
 use cosmos_core::{
     block::{block_events::BlockEventsSet, Block},
+    item::upgrade_module::{line_module_bonus, InstalledModules, UpgradeModuleKind},
     logic::{logic_driver::LogicDriver, LogicInputEvent, LogicSystemSet},
     netty::{
-        cosmos_encoder, server_laser_cannon_system_messages::ServerStructureSystemMessages, system_sets::NetworkingSystemsSet,
+        network_stats::{NettyMessage, NettyServerMessages},
+        server_laser_cannon_system_messages::ServerStructureSystemMessages,
+        system_sets::NetworkingSystemsSet,
         NettyChannelServer,
     },
     physics::location::Location,
@@ -17,9 +20,14 @@ use cosmos_core::{
     registry::{identifiable::Identifiable, Registry},
     state::GameState,
     structure::{
+        heat::StructureHeat,
+        ship::ship_modifiers::ShipModifiers,
         systems::{
+            energy_priority::{EnergyBrownout, EnergyConsumer},
             energy_storage_system::EnergyStorageSystem,
-            laser_cannon_system::{LaserCannonCalculator, LaserCannonProperty, LaserCannonSystem, LineSystemCooldown, SystemCooldown},
+            laser_cannon_system::{
+                LaserCannonCalculator, LaserCannonProperty, LaserCannonSystem, LineSystemCooldown, SystemCooldown, LASER_BASE_VELOCITY,
+            },
             line_system::LineBlocks,
             StructureSystem, StructureSystems, StructureSystemsSet, SystemActive,
         },
@@ -27,7 +35,12 @@ use cosmos_core::{
     },
 };
 
-use super::{line_system::add_line_system, sync::register_structure_system, thruster_system};
+use crate::{
+    structure::heat::{GenerateHeatEvent, WEAPON_HEAT_PER_ENERGY},
+    universe::safe_zone::SafeZones,
+};
+
+use super::{cloaking_system::WeaponsFiredEvent, line_system::add_line_system, sync::register_structure_system, thruster_system};
 
 fn on_add_laser(mut commands: Commands, query: Query<Entity, Added<LaserCannonSystem>>) {
     for ent in query.iter() {
@@ -41,9 +54,6 @@ fn register_laser_blocks(blocks: Res<Registry<Block>>, mut cannon: ResMut<LineBl
     }
 }
 
-/// How fast a laser will travel (m/s) ignoring the speed of its shooter.
-pub const LASER_BASE_VELOCITY: f32 = 200.0;
-
 fn update_system(
     mut query: Query<(&LaserCannonSystem, &StructureSystem, &mut LineSystemCooldown, Has<SystemActive>)>,
     mut es_query: Query<&mut EnergyStorageSystem>,
@@ -59,6 +69,13 @@ fn update_system(
     time: Res<Time>,
     mut commands: Commands,
     mut server: ResMut<RenetServer>,
+    safe_zones: SafeZones,
+    q_installed_modules: Query<&InstalledModules>,
+    q_ship_modifiers: Query<&ShipModifiers>,
+    mut evw_weapons_fired: EventWriter<WeaponsFiredEvent>,
+    mut evw_generate_heat: EventWriter<GenerateHeatEvent>,
+    q_heat: Query<&StructureHeat>,
+    q_brownout: Query<&EnergyBrownout>,
 ) {
     for (cannon_system, system, mut cooldown, system_active) in query.iter_mut() {
         let Ok((ship_entity, systems, structure, location, global_transform, ship_velocity, physics_world)) =
@@ -66,13 +83,29 @@ fn update_system(
         else {
             continue;
         };
+        if safe_zones.in_safe_zone(location) {
+            continue;
+        }
+        // Weapons don't get any energy while they're browned out
+        if q_brownout.get(ship_entity).is_ok_and(|b| b.contains(EnergyConsumer::Weapons)) {
+            continue;
+        }
         let Ok(mut energy_storage_system) = systems.query_mut(&mut es_query) else {
             continue;
         };
 
+        let energy_multiplier = q_ship_modifiers
+            .get(ship_entity)
+            .map(|m| m.weapon_energy_multiplier())
+            .unwrap_or(1.0);
+
         let sec = time.elapsed_secs();
 
         let mut any_fired = false;
+        let mut energy_used = 0.0;
+
+        // Overheating structures fire slower
+        let throttle = q_heat.get(ship_entity).map(|x| x.throttle()).unwrap_or(1.0);
 
         let default_cooldown = SystemCooldown {
             cooldown_time: Duration::from_millis(1000),
@@ -84,17 +117,23 @@ fn update_system(
         for line in cannon_system.lines.iter() {
             let cooldown = cooldown.lines.entry(line.start).or_insert(default_cooldown);
 
-            if sec - cooldown.last_use_time < cooldown.cooldown_time.as_secs_f32() {
+            let fire_rate = line_module_bonus(line, structure, UpgradeModuleKind::FireRate, &q_installed_modules);
+
+            if sec - cooldown.last_use_time < cooldown.cooldown_time.as_secs_f32() / ((1.0 + fire_rate) * throttle) {
                 continue;
             }
 
-            if !((system_active || line.active()) && energy_storage_system.get_energy() >= line.property.energy_per_shot) {
+            let energy_efficiency = line_module_bonus(line, structure, UpgradeModuleKind::EnergyEfficiency, &q_installed_modules);
+            let energy_per_shot = line.property.energy_per_shot * (1.0 - energy_efficiency).max(0.0) * energy_multiplier;
+
+            if !((system_active || line.active()) && energy_storage_system.get_energy() >= energy_per_shot) {
                 continue;
             }
 
             cooldown.last_use_time = sec;
             any_fired = true;
-            energy_storage_system.decrease_energy(line.property.energy_per_shot);
+            energy_storage_system.decrease_energy(energy_per_shot);
+            energy_used += energy_per_shot;
 
             let location = structure.block_world_location(line.start, global_transform, location);
 
@@ -120,9 +159,9 @@ fn update_system(
 
             let color = line.color;
 
-            server.broadcast_message(
+            server.broadcast_netty_message(
                 NettyChannelServer::StructureSystems,
-                cosmos_encoder::serialize(&ServerStructureSystemMessages::CreateLaser {
+                NettyMessage::new(&ServerStructureSystemMessages::CreateLaser {
                     color,
                     location,
                     laser_velocity,
@@ -135,9 +174,17 @@ fn update_system(
         }
 
         if any_fired {
-            server.broadcast_message(
+            evw_weapons_fired.send(WeaponsFiredEvent {
+                structure_entity: ship_entity,
+            });
+            evw_generate_heat.send(GenerateHeatEvent {
+                structure_entity: ship_entity,
+                heat: energy_used * WEAPON_HEAT_PER_ENERGY,
+            });
+
+            server.broadcast_netty_message(
                 NettyChannelServer::StructureSystems,
-                cosmos_encoder::serialize(&ServerStructureSystemMessages::LaserCannonSystemFired { ship_entity }),
+                NettyMessage::new(&ServerStructureSystemMessages::LaserCannonSystemFired { ship_entity }),
             );
         }
     }
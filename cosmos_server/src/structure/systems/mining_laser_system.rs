@@ -21,6 +21,7 @@ use cosmos_core::{
         ship::Ship,
         structure_block::StructureBlock,
         systems::{
+            energy_priority::{EnergyBrownout, EnergyConsumer},
             energy_storage_system::EnergyStorageSystem,
             line_system::LineBlocks,
             mining_laser_system::{MiningLaserProperty, MiningLaserPropertyCalculator, MiningLaserSystem},
@@ -118,6 +119,7 @@ fn update_mining_beams(
     q_is_system_active: Query<(), With<SystemActive>>,
     rapier_context_access: ReadRapierContext,
     q_parent: Query<&Parent>,
+    q_brownout: Query<&EnergyBrownout>,
     time: Res<Time>,
 ) {
     #[derive(Debug)]
@@ -152,7 +154,11 @@ fn update_mining_beams(
             continue;
         };
 
-        if energy_storage_system.decrease_energy(beam.property.energy_per_second * delta_time) != 0.0 {
+        let browned_out = q_brownout
+            .get(beam.structure_entity)
+            .is_ok_and(|b| b.contains(EnergyConsumer::Utility));
+
+        if browned_out || energy_storage_system.decrease_energy(beam.property.energy_per_second * delta_time) != 0.0 {
             commands.entity(entity).insert(NeedsDespawned);
             continue;
         }
@@ -279,10 +285,18 @@ fn on_activate_system(
     mut query: Query<(Entity, &MiningLaserSystem, &StructureSystem), Added<SystemActive>>,
     mut es_query: Query<&mut EnergyStorageSystem>,
     systems: Query<(Entity, &StructureSystems, &Structure, &RapierContextEntityLink)>,
+    q_brownout: Query<&EnergyBrownout>,
     time: Res<Time>,
     mut commands: Commands,
 ) {
     for (system_entity, mining_system, system) in query.iter_mut() {
+        if q_brownout
+            .get(system.structure_entity())
+            .is_ok_and(|b| b.contains(EnergyConsumer::Utility))
+        {
+            continue;
+        }
+
         if let Ok((ship_entity, systems, structure, physics_world)) = systems.get(system.structure_entity()) {
             if let Ok(mut energy_storage_system) = systems.query_mut(&mut es_query) {
                 let sec = time.delta_secs();
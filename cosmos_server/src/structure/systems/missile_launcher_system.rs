@@ -11,9 +11,12 @@ use bevy_renet2::renet2::RenetServer;
 use cosmos_core::{
     block::Block,
     entities::player::Player,
+    item::upgrade_module::{line_module_bonus, InstalledModules, UpgradeModuleKind},
     logic::{logic_driver::LogicDriver, LogicInputEvent, LogicSystemSet},
     netty::{
-        cosmos_encoder, server_laser_cannon_system_messages::ServerStructureSystemMessages, system_sets::NetworkingSystemsSet,
+        network_stats::{NettyMessage, NettyServerMessages},
+        server_laser_cannon_system_messages::ServerStructureSystemMessages,
+        system_sets::NetworkingSystemsSet,
         NettyChannelServer,
     },
     persistence::LoadingDistance,
@@ -25,7 +28,10 @@ use cosmos_core::{
     registry::{identifiable::Identifiable, Registry},
     state::GameState,
     structure::{
+        heat::StructureHeat,
+        ship::ship_modifiers::ShipModifiers,
         systems::{
+            energy_priority::{EnergyBrownout, EnergyConsumer},
             energy_storage_system::EnergyStorageSystem,
             laser_cannon_system::{LineSystemCooldown, SystemCooldown},
             line_system::LineBlocks,
@@ -39,9 +45,13 @@ use cosmos_core::{
     },
 };
 
-use crate::projectiles::missile::MissileTargetting;
+use crate::{
+    projectiles::missile::MissileTargetting,
+    structure::heat::{GenerateHeatEvent, WEAPON_HEAT_PER_ENERGY},
+    universe::safe_zone::SafeZones,
+};
 
-use super::{line_system::add_line_system, sync::register_structure_system};
+use super::{cloaking_system::WeaponsFiredEvent, line_system::add_line_system, sync::register_structure_system};
 
 fn on_add_missile_launcher(mut commands: Commands, query: Query<Entity, Added<MissileLauncherSystem>>) {
     for ent in query.iter() {
@@ -66,7 +76,7 @@ pub const MISSILE_LIFETIME: Duration = Duration::from_secs(20);
 /// The missile's life time may be +/- this number
 pub const MISSILE_LIFETIME_FUDGE: Duration = Duration::from_secs(1);
 
-/// How long the missile system must focus on a target before it's locked on
+/// How long the missile system must focus on a target before it's locked on, before any [`ShipModifiers`] are applied
 pub const MISSILE_FOCUS_TIME: Duration = Duration::from_secs(5);
 
 const MAX_MISSILE_FOCUS_DISTANCE: f32 = 2000.0;
@@ -82,16 +92,19 @@ fn add_missile_targettable(q_added_targettable: Query<Entity, Or<(Added<Structur
 
 fn missile_lockon(
     mut q_missile_systems: Query<(&StructureSystem, &mut MissileLauncherFocus, &MissileLauncherPreferredFocus)>,
-    q_structure: Query<(&Location, &GlobalTransform)>,
+    q_structure: Query<(&Location, &GlobalTransform, Option<&ShipModifiers>)>,
     q_targettable: Query<(Entity, &Location), With<MissileTargettable>>,
     time: Res<Time>,
 ) {
     for (structure_system, mut missile_launmcher_focus, preferred_focus) in q_missile_systems.iter_mut() {
         // Verify system is hovered
-        let Ok((structure_location, g_trans)) = q_structure.get(structure_system.structure_entity()) else {
+        let Ok((structure_location, g_trans, ship_modifiers)) = q_structure.get(structure_system.structure_entity()) else {
             continue;
         };
 
+        // Targeting computers let missiles lock on faster
+        let focus_time = MISSILE_FOCUS_TIME.mul_f32(ship_modifiers.map(|m| m.missile_focus_time_multiplier()).unwrap_or(1.0));
+
         // TODO: Make this dependent on direction the player is looking (because of camera blocks)
         let targetting_forward = g_trans.forward();
 
@@ -136,13 +149,13 @@ fn missile_lockon(
                 complete_duration: _,
             } => {
                 if *focusing_server_entity != best_target {
-                    missile_launmcher_focus.change_focus(best_target, MISSILE_FOCUS_TIME);
+                    missile_launmcher_focus.change_focus(best_target, focus_time);
                 } else {
                     *focused_duration += Duration::from_secs_f32(time.delta_secs());
                 }
             }
             MissileLauncherFocus::NotFocusing => {
-                missile_launmcher_focus.change_focus(best_target, MISSILE_FOCUS_TIME);
+                missile_launmcher_focus.change_focus(best_target, focus_time);
             }
         }
     }
@@ -187,19 +200,42 @@ fn update_missile_system(
     time: Res<Time>,
     mut commands: Commands,
     mut server: ResMut<RenetServer>,
+    safe_zones: SafeZones,
+    q_installed_modules: Query<&InstalledModules>,
+    q_ship_modifiers: Query<&ShipModifiers>,
+    mut evw_weapons_fired: EventWriter<WeaponsFiredEvent>,
+    mut evw_generate_heat: EventWriter<GenerateHeatEvent>,
+    q_heat: Query<&StructureHeat>,
+    q_brownout: Query<&EnergyBrownout>,
 ) {
     for (missile_launcher_system, focus, system, mut cooldown, system_active) in query.iter_mut() {
         let Ok((ship_entity, systems, structure, location, global_transform, ship_velocity)) = systems.get(system.structure_entity())
         else {
             continue;
         };
+        if safe_zones.in_safe_zone(location) {
+            continue;
+        }
+        // Weapons don't get any energy while they're browned out
+        if q_brownout.get(ship_entity).is_ok_and(|b| b.contains(EnergyConsumer::Weapons)) {
+            continue;
+        }
         let Ok(mut energy_storage_system) = systems.query_mut(&mut es_query) else {
             continue;
         };
 
+        let energy_multiplier = q_ship_modifiers
+            .get(ship_entity)
+            .map(|m| m.weapon_energy_multiplier())
+            .unwrap_or(1.0);
+
         let sec = time.elapsed_secs();
 
         let mut any_fired = false;
+        let mut energy_used = 0.0;
+
+        // Overheating structures fire slower
+        let throttle = q_heat.get(ship_entity).map(|x| x.throttle()).unwrap_or(1.0);
 
         let default_cooldown = SystemCooldown {
             cooldown_time: Duration::from_secs(5),
@@ -211,17 +247,23 @@ fn update_missile_system(
         for line in missile_launcher_system.lines.iter() {
             let cooldown = cooldown.lines.entry(line.start).or_insert(default_cooldown);
 
-            if sec - cooldown.last_use_time <= cooldown.cooldown_time.as_secs_f32() {
+            let fire_rate = line_module_bonus(line, structure, UpgradeModuleKind::FireRate, &q_installed_modules);
+
+            if sec - cooldown.last_use_time <= cooldown.cooldown_time.as_secs_f32() / ((1.0 + fire_rate) * throttle) {
                 continue;
             }
 
-            if !((system_active || line.active()) && energy_storage_system.get_energy() >= line.property.energy_per_shot) {
+            let energy_efficiency = line_module_bonus(line, structure, UpgradeModuleKind::EnergyEfficiency, &q_installed_modules);
+            let energy_per_shot = line.property.energy_per_shot * (1.0 - energy_efficiency).max(0.0) * energy_multiplier;
+
+            if !((system_active || line.active()) && energy_storage_system.get_energy() >= energy_per_shot) {
                 continue;
             }
 
             cooldown.last_use_time = sec;
             any_fired = true;
-            energy_storage_system.decrease_energy(line.property.energy_per_shot);
+            energy_storage_system.decrease_energy(energy_per_shot);
+            energy_used += energy_per_shot;
 
             let location = structure.block_world_location(line.start, global_transform, location);
 
@@ -268,9 +310,17 @@ fn update_missile_system(
         }
 
         if any_fired {
-            server.broadcast_message(
+            evw_weapons_fired.send(WeaponsFiredEvent {
+                structure_entity: ship_entity,
+            });
+            evw_generate_heat.send(GenerateHeatEvent {
+                structure_entity: ship_entity,
+                heat: energy_used * WEAPON_HEAT_PER_ENERGY,
+            });
+
+            server.broadcast_netty_message(
                 NettyChannelServer::StructureSystems,
-                cosmos_encoder::serialize(&ServerStructureSystemMessages::MissileLauncherSystemFired { ship_entity }),
+                NettyMessage::new(&ServerStructureSystemMessages::MissileLauncherSystemFired { ship_entity }),
             );
         }
     }
@@ -3,17 +3,24 @@
 use bevy::prelude::App;
 use cosmos_core::{block::block_rotation::BlockRotation, prelude::BlockCoordinate};
 
+mod activation_groups;
 mod camera_system;
+pub mod cloaking_system;
 mod dock_system;
+mod energy_dispatch;
 mod energy_generation_system;
+mod energy_roles;
 mod energy_storage_system;
 pub mod laser_cannon_system;
 mod line_system;
 mod mining_laser_system;
 pub mod missile_launcher_system;
 pub mod shield_system;
+mod solar_panel_system;
+pub(crate) mod subsystem_targeting;
 pub(crate) mod sync;
-mod thruster_system;
+pub(crate) mod thruster_system;
+mod turret_system;
 
 /// A system that is created by the addition and removal of blocks
 pub trait BlockStructureSystem<T> {
@@ -24,14 +31,21 @@ pub trait BlockStructureSystem<T> {
 }
 
 pub(super) fn register(app: &mut App) {
+    activation_groups::register(app);
+    subsystem_targeting::register(app);
     dock_system::register(app);
     line_system::register(app);
     camera_system::register(app);
+    cloaking_system::register(app);
     shield_system::register(app);
     laser_cannon_system::register(app);
     thruster_system::register(app);
     energy_generation_system::register(app);
+    solar_panel_system::register(app);
+    energy_roles::register(app);
+    energy_dispatch::register(app);
     mining_laser_system::register(app);
     energy_storage_system::register(app);
     missile_launcher_system::register(app);
+    turret_system::register(app);
 }
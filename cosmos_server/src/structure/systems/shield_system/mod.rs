@@ -29,7 +29,9 @@ use cosmos_core::{
     ecs::NeedsDespawned,
     events::block_events::BlockChangedEvent,
     netty::{
-        cosmos_encoder, server_laser_cannon_system_messages::ServerStructureSystemMessages, system_sets::NetworkingSystemsSet,
+        network_stats::{NettyMessage, NettyServerMessages},
+        server_laser_cannon_system_messages::ServerStructureSystemMessages,
+        system_sets::NetworkingSystemsSet,
         NettyChannelServer,
     },
     persistence::LoadingDistance,
@@ -42,6 +44,7 @@ use cosmos_core::{
         events::StructureLoadedEvent,
         shields::Shield,
         systems::{
+            energy_priority::{EnergyBrownout, EnergyConsumer},
             energy_storage_system::EnergyStorageSystem,
             shield_system::{ShieldGeneratorBlocks, ShieldGeneratorProperty, ShieldProjectorBlocks, ShieldProjectorProperty, ShieldSystem},
             StructureSystem, StructureSystemType, StructureSystems, StructureSystemsSet,
@@ -157,9 +160,9 @@ fn structure_loaded_event(
 
 fn send_shield_hits(mut ev_reader: EventReader<ShieldHitEvent>, mut server: ResMut<RenetServer>) {
     for ev in ev_reader.read() {
-        server.broadcast_message(
+        server.broadcast_netty_message(
             NettyChannelServer::StructureSystems,
-            cosmos_encoder::serialize(&ServerStructureSystemMessages::ShieldHit {
+            NettyMessage::new(&ServerStructureSystemMessages::ShieldHit {
                 shield_entity: ev.shield_entity,
                 relative_location: ev.relative_position,
             }),
@@ -266,6 +269,7 @@ fn power_shields(
     mut q_storage_system: Query<&mut EnergyStorageSystem>,
     q_systems: Query<&StructureSystems>,
     mut q_shields: Query<(Entity, &mut Shield, &Parent, Option<&mut ShieldDowntime>)>,
+    q_brownout: Query<&EnergyBrownout>,
     time: Res<Time>,
 ) {
     for (ent, mut shield, parent, shield_downtime) in &mut q_shields {
@@ -287,6 +291,10 @@ fn power_shields(
             let optimal_power_usage = strength_missing / shield.power_efficiency;
             let power_usage = optimal_power_usage.min(shield.power_per_second * time.delta_secs());
 
+            if q_brownout.get(parent.get()).is_ok_and(|b| b.contains(EnergyConsumer::Shields)) {
+                continue;
+            }
+
             let Ok(systems) = q_systems.get(parent.get()) else {
                 warn!("Shield's parent isn't a structure?");
                 continue;
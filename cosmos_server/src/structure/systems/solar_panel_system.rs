@@ -0,0 +1,181 @@
+//! Solar panels generate energy based on how much light they get from the nearest star.
+//!
+//! The light a structure receives is sampled every [`SAMPLE_INTERVAL`], and each panel contributes based on
+//! how directly its top face points at the star and whether any other blocks are in the way.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use cosmos_core::{
+    block::{
+        block_face::BlockFace,
+        specific_blocks::solar_panel::{SOLAR_PANEL_BLOCK, SOLAR_PANEL_MAX_OUTPUT},
+        Block,
+    },
+    netty::system_sets::NetworkingSystemsSet,
+    physics::location::{Location, SYSTEM_DIMENSIONS},
+    prelude::{Planet, Structure},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        coordinates::BlockCoordinate,
+        systems::{energy_generation_system::EnergyGenerationSystem, StructureSystems, StructureSystemsSet},
+    },
+    universe::star::Star,
+};
+
+use crate::universe::star::sun_elevation_degrees;
+
+/// How often the light each structure gets is recalculated
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The temperature of our sun, in kelvin. Stars this hot give panels their normal output.
+const SUN_TEMPERATURE: f32 = 5772.0;
+/// Stars dimmer than this are treated as this bright
+const MIN_TEMPERATURE_FACTOR: f32 = 0.25;
+/// Stars brighter than this are treated as this bright
+const MAX_TEMPERATURE_FACTOR: f32 = 2.0;
+
+/// Even at the edge of a system, panels still get this fraction of their star's light
+const MIN_DISTANCE_FACTOR: f32 = 0.1;
+
+/// The light a structure is receiving from its nearest star
+struct StarExposure {
+    /// The direction to the star, relative to the structure's rotation
+    local_direction: Vec3,
+    /// How strong the light is. 1.0 is a sun-like star at the center of its system
+    intensity: f32,
+}
+
+/// How much brighter or dimmer this star is compared to our sun
+fn temperature_factor(star: &Star) -> f32 {
+    (star.temperature() / SUN_TEMPERATURE).clamp(MIN_TEMPERATURE_FACTOR, MAX_TEMPERATURE_FACTOR)
+}
+
+/// Light fades the further from the star you get, down to [`MIN_DISTANCE_FACTOR`] at the edge of the system
+fn distance_factor(distance: f32) -> f32 {
+    (1.0 - distance / (SYSTEM_DIMENSIONS / 2.0)).clamp(MIN_DISTANCE_FACTOR, 1.0)
+}
+
+/// Returns the light a structure at this location gets, or `None` if it is getting none at all
+fn sample_star_exposure(
+    location: &Location,
+    g_trans: &GlobalTransform,
+    planet: Option<(&Location, &GlobalTransform)>,
+    q_stars: &Query<(&Location, &Star)>,
+) -> Option<StarExposure> {
+    let (star_loc, star) = q_stars
+        .iter()
+        .min_by(|(a, _), (b, _)| a.distance_sqrd(location).total_cmp(&b.distance_sqrd(location)))?;
+
+    // It's night time - the planet is in the way
+    if sun_elevation_degrees(g_trans.translation(), planet, Some(star_loc)) <= 0.0 {
+        return None;
+    }
+
+    let to_star = Vec3::from(*star_loc - *location);
+
+    Some(StarExposure {
+        local_direction: g_trans.rotation().inverse() * to_star.normalize_or_zero(),
+        intensity: temperature_factor(star) * distance_factor(to_star.length()),
+    })
+}
+
+/// Returns how much of the star's light reaches this panel, from 0.0 to 1.0
+fn panel_exposure(coords: BlockCoordinate, structure: &Structure, exposure: &StarExposure, blocks: &Registry<Block>) -> f32 {
+    let facing = structure.block_rotation(coords).direction_of(BlockFace::Top).as_vec3();
+
+    let alignment = facing.dot(exposure.local_direction);
+    if alignment <= 0.0 {
+        return 0.0;
+    }
+
+    // The ray stops once it leaves the structure, so the length only has to be long enough to get there
+    let max_length = Vec3::new(
+        structure.block_dimensions().x as f32,
+        structure.block_dimensions().y as f32,
+        structure.block_dimensions().z as f32,
+    )
+    .length();
+
+    let occluded = structure
+        .raycast_iter(
+            structure.block_relative_position(coords),
+            exposure.local_direction,
+            max_length,
+            false,
+        )
+        .filter(|&hit| hit != coords)
+        .any(|hit| !structure.block_at(hit, blocks).is_transparent());
+
+    if occluded {
+        0.0
+    } else {
+        alignment
+    }
+}
+
+/// Samples where the star is for every structure with solar panels, and updates how much energy they generate
+fn update_solar_generation(
+    blocks: Res<Registry<Block>>,
+    q_structures: Query<(Entity, &Structure, &Location, &GlobalTransform, &StructureSystems)>,
+    q_planets: Query<(&Location, &GlobalTransform), With<Planet>>,
+    q_stars: Query<(&Location, &Star)>,
+    mut q_generation: Query<&mut EnergyGenerationSystem>,
+) {
+    let Some(solar_panel) = blocks.from_id(SOLAR_PANEL_BLOCK) else {
+        return;
+    };
+
+    for (ent, structure, location, g_trans, systems) in q_structures.iter() {
+        let Ok(mut generation) = systems.query_mut(&mut q_generation) else {
+            continue;
+        };
+
+        let mut panels = structure
+            .block_counts()
+            .map(|counts| counts.positions(solar_panel.id()))
+            .into_iter()
+            .flatten()
+            .peekable();
+
+        let rate = if panels.peek().is_none() {
+            0.0
+        } else {
+            let planet = match q_planets.get(ent) {
+                Ok(planet) => Some(planet),
+                Err(_) => q_planets
+                    .iter()
+                    .filter(|(planet_loc, _)| planet_loc.is_within_reasonable_range(location))
+                    .min_by(|(a, _), (b, _)| a.distance_sqrd(location).total_cmp(&b.distance_sqrd(location))),
+            };
+
+            match sample_star_exposure(location, g_trans, planet, &q_stars) {
+                Some(exposure) => {
+                    panels
+                        .map(|coords| panel_exposure(coords, structure, &exposure, &blocks))
+                        .sum::<f32>()
+                        * SOLAR_PANEL_MAX_OUTPUT
+                        * exposure.intensity
+                }
+                None => 0.0,
+            }
+        };
+
+        // Avoid triggering change detection (and syncing the system) when nothing changed
+        if generation.solar_generation_rate() != rate {
+            generation.set_solar_generation_rate(rate);
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        update_solar_generation
+            .run_if(on_timer(SAMPLE_INTERVAL))
+            .in_set(StructureSystemsSet::UpdateSystems)
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
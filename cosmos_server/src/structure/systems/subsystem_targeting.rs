@@ -0,0 +1,152 @@
+//! Lets pilots choose which subsystem of their targets their lasers favor, and figures out which blocks belong
+//! to each subsystem.
+
+use bevy::{prelude::*, utils::HashMap};
+use cosmos_core::{
+    block::{specific_blocks::solar_panel::SOLAR_PANEL_BLOCK, Block},
+    netty::{server::ServerLobby, sync::events::server_event::NettyEventReceived, system_sets::NetworkingSystemsSet},
+    prelude::{BlockCoordinate, UnboundBlockCoordinate},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        coordinates::UnboundCoordinateType,
+        ship::pilot::Pilot,
+        systems::{
+            energy_generation_system::EnergyGenerationBlocks,
+            energy_storage_system::EnergyStorageBlocks,
+            laser_cannon_system::LaserCannonProperty,
+            line_system::LineBlocks,
+            missile_launcher_system::MissileLauncherProperty,
+            subsystem_targeting::{SetTargetedSubsystemEvent, TargetedSubsystem},
+            thruster_system::ThrusterBlocks,
+        },
+        Structure,
+    },
+};
+
+/// How many blocks away from where a laser hit it will look for a block of the targeted subsystem
+const SUBSYSTEM_SEARCH_RADIUS: UnboundBlockCoordinate = UnboundBlockCoordinate::new(3, 3, 3);
+
+#[derive(Resource, Default, Debug)]
+/// Which subsystem each block is a part of. Blocks that aren't part of a targetable subsystem aren't in here.
+pub struct SubsystemBlocks(HashMap<u16, TargetedSubsystem>);
+
+impl SubsystemBlocks {
+    /// The subsystem this block is a part of, if any
+    pub fn subsystem(&self, block_id: u16) -> Option<TargetedSubsystem> {
+        self.0.get(&block_id).copied()
+    }
+
+    /// Finds the closest block to `coords` that is a part of this subsystem.
+    ///
+    /// Only blocks within a few blocks of `coords` are considered, so weapons still have to hit near the subsystem
+    /// for this to matter.
+    pub fn closest_subsystem_block(
+        &self,
+        structure: &Structure,
+        coords: BlockCoordinate,
+        subsystem: TargetedSubsystem,
+    ) -> Option<BlockCoordinate> {
+        let unbound = UnboundBlockCoordinate::from(coords);
+
+        let min = unbound - SUBSYSTEM_SEARCH_RADIUS;
+        let max = unbound + SUBSYSTEM_SEARCH_RADIUS;
+
+        let mut closest: Option<(BlockCoordinate, UnboundCoordinateType)> = None;
+
+        for z in min.z..=max.z {
+            for y in min.y..=max.y {
+                for x in min.x..=max.x {
+                    let Ok(here) = BlockCoordinate::try_from(UnboundBlockCoordinate::new(x, y, z)) else {
+                        continue;
+                    };
+
+                    if !structure.is_within_blocks(here) || self.subsystem(structure.block_id_at(here)) != Some(subsystem) {
+                        continue;
+                    }
+
+                    let (dx, dy, dz) = (x - unbound.x, y - unbound.y, z - unbound.z);
+                    let dist_sqrd = dx * dx + dy * dy + dz * dz;
+
+                    if closest.map(|(_, d)| dist_sqrd < d).unwrap_or(true) {
+                        closest = Some((here, dist_sqrd));
+                    }
+                }
+            }
+        }
+
+        closest.map(|(coords, _)| coords)
+    }
+}
+
+/// The systems fill out their block properties in [`GameState::PostLoading`], so this is done once they're all ready.
+fn register_subsystem_blocks(
+    blocks: Res<Registry<Block>>,
+    generation_blocks: Res<EnergyGenerationBlocks>,
+    storage_blocks: Res<EnergyStorageBlocks>,
+    thruster_blocks: Res<ThrusterBlocks>,
+    laser_cannon_blocks: Res<LineBlocks<LaserCannonProperty>>,
+    missile_launcher_blocks: Res<LineBlocks<MissileLauncherProperty>>,
+    mut subsystem_blocks: ResMut<SubsystemBlocks>,
+) {
+    for block in blocks.iter() {
+        let subsystem = if thruster_blocks.get(block).is_some() {
+            TargetedSubsystem::Engines
+        } else if laser_cannon_blocks.get(block).is_some() || missile_launcher_blocks.get(block).is_some() {
+            TargetedSubsystem::Weapons
+        } else if generation_blocks.get(block).is_some()
+            || storage_blocks.get(block).is_some()
+            || block.unlocalized_name() == SOLAR_PANEL_BLOCK
+        {
+            TargetedSubsystem::Power
+        } else {
+            continue;
+        };
+
+        subsystem_blocks.0.insert(block.id(), subsystem);
+    }
+}
+
+fn on_set_targeted_subsystem(
+    mut commands: Commands,
+    mut nevr_set_subsystem: EventReader<NettyEventReceived<SetTargetedSubsystemEvent>>,
+    lobby: Res<ServerLobby>,
+    q_pilot: Query<&Pilot>,
+    q_ship: Query<(), With<Structure>>,
+) {
+    for ev in nevr_set_subsystem.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        // Only the pilot controls where the ship's weapons are aimed
+        let Ok(pilot) = q_pilot.get(player_ent) else {
+            continue;
+        };
+
+        if !q_ship.contains(pilot.entity) {
+            continue;
+        }
+
+        match ev.subsystem {
+            Some(subsystem) => {
+                commands.entity(pilot.entity).insert(subsystem);
+            }
+            None => {
+                commands.entity(pilot.entity).remove::<TargetedSubsystem>();
+            }
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<SubsystemBlocks>()
+        .add_systems(OnEnter(GameState::Playing), register_subsystem_blocks)
+        .add_systems(
+            Update,
+            on_set_targeted_subsystem
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        );
+}
@@ -14,8 +14,11 @@ use bevy_renet2::renet2::RenetServer;
 use cosmos_core::{
     item::Item,
     netty::{
-        cosmos_encoder, server_replication::ReplicationMessage, sync::server_entity_syncing::RequestedEntityEvent, NettyChannelServer,
-        NoSendEntity,
+        cosmos_encoder,
+        network_stats::{NettyMessage, NettyServerMessages},
+        server_replication::ReplicationMessage,
+        sync::server_entity_syncing::RequestedEntityEvent,
+        NettyChannelServer, NoSendEntity,
     },
     registry::{identifiable::Identifiable, Registry},
     state::GameState,
@@ -27,9 +30,9 @@ fn sync_system<T: SyncableSystem>(
     q_changed_systems: Query<(&T, &StructureSystem), (Without<NoSendEntity>, Changed<T>)>,
 ) {
     for (changed_system, structure_system) in q_changed_systems.iter() {
-        server.broadcast_message(
+        server.broadcast_netty_message(
             NettyChannelServer::SystemReplication,
-            cosmos_encoder::serialize(&ReplicationMessage::SystemReplication {
+            NettyMessage::new(&ReplicationMessage::SystemReplication {
                 structure_entity: structure_system.structure_entity(),
                 system_id: structure_system.id(),
                 system_type_id: structure_system.system_type_id(),
@@ -54,10 +57,10 @@ fn on_request_systems_entity<T: SyncableSystem>(
             continue;
         };
 
-        server.send_message(
+        server.send_netty_message(
             ev.client_id,
             NettyChannelServer::SystemReplication,
-            cosmos_encoder::serialize(&ReplicationMessage::SystemReplication {
+            NettyMessage::new(&ReplicationMessage::SystemReplication {
                 structure_entity: structure_system.structure_entity(),
                 system_id: structure_system.id(),
                 system_type_id: structure_system.system_type_id(),
@@ -78,9 +81,9 @@ fn sync_active_systems(
             continue;
         };
 
-        server.broadcast_message(
+        server.broadcast_netty_message(
             NettyChannelServer::SystemReplication,
-            cosmos_encoder::serialize(&ReplicationMessage::SystemStatus {
+            NettyMessage::new(&ReplicationMessage::SystemStatus {
                 structure_entity: system.structure_entity(),
                 system_id: system.id(),
                 active: true,
@@ -93,9 +96,9 @@ fn sync_active_systems(
             continue;
         };
 
-        server.broadcast_message(
+        server.broadcast_netty_message(
             NettyChannelServer::SystemReplication,
-            cosmos_encoder::serialize(&ReplicationMessage::SystemStatus {
+            NettyMessage::new(&ReplicationMessage::SystemStatus {
                 structure_entity: system.structure_entity(),
                 system_id: system.id(),
                 active: false,
@@ -2,8 +2,8 @@ use std::ops::Mul;
 
 use bevy::{
     prelude::{
-        in_state, App, Commands, EventReader, IntoSystemConfigs, OnEnter, Quat, Query, Res, ResMut, SystemSet, Transform, Update, Vec3,
-        With,
+        in_state, App, Commands, EventReader, EventWriter, IntoSystemConfigs, OnEnter, Quat, Query, Res, ResMut, SystemSet, Transform,
+        Update, Vec3, With,
     },
     time::Time,
 };
@@ -12,17 +12,21 @@ use cosmos_core::{
     block::{block_events::BlockEventsSet, Block},
     events::block_events::BlockChangedEvent,
     netty::system_sets::NetworkingSystemsSet,
+    physics::structure_mass::StructureMass,
     registry::Registry,
     state::GameState,
     structure::{
         events::StructureLoadedEvent,
+        heat::StructureHeat,
         ship::{
+            flight_assist::FlightAssistMode,
             pilot::Pilot,
             ship_movement::{ShipMovement, ShipMovementSet},
             Ship,
         },
         systems::{
             dock_system::Docked,
+            energy_priority::{EnergyBrownout, EnergyConsumer},
             energy_storage_system::EnergyStorageSystem,
             thruster_system::{ThrusterBlocks, ThrusterProperty, ThrusterSystem},
             StructureSystem, StructureSystemType, StructureSystems, StructureSystemsSet,
@@ -31,10 +35,20 @@ use cosmos_core::{
     },
 };
 
+use crate::structure::heat::{GenerateHeatEvent, THRUSTER_HEAT_PER_ENERGY};
+
 use super::sync::register_structure_system;
 
 const MAX_SHIP_SPEED: f32 = 200.0;
-const MAX_BRAKE_DELTA_PER_THRUST: f32 = 300.0;
+/// How much braking impulse each unit of thrust provides per second
+pub(crate) const MAX_BRAKE_DELTA_PER_THRUST: f32 = 300.0;
+/// How much turning torque a ship has per unit of its mass.
+///
+/// Because torque scales with mass but rotational inertia also scales with how spread out that mass is,
+/// long ships turn much slower than compact ones.
+const TURNING_TORQUE_PER_MASS: f32 = 1000.0;
+/// The angular velocity (radians/sec) a ship is turned at for each unit of its [`ShipMovement::torque`]
+pub(crate) const ANGULAR_VELOCITY_PER_TORQUE: f32 = 5.0;
 
 fn register_thruster_blocks(blocks: Res<Registry<Block>>, mut storage: ResMut<ThrusterBlocks>) {
     if let Some(block) = blocks.from_id("cosmos:thruster") {
@@ -80,6 +94,20 @@ fn block_update_system(
     }
 }
 
+/// Ships with more rotational inertia (heavier, or with their mass spread further out) turn slower
+fn limit_turning(wanted_change: Vec3, structure_mass: &StructureMass, transform: &Transform, delta: f32) -> Vec3 {
+    let local_axis = transform.rotation.inverse() * wanted_change;
+    let inertia = structure_mass.angular_inertia_about(local_axis);
+
+    if inertia <= 0.0 {
+        return wanted_change;
+    }
+
+    let max_change = TURNING_TORQUE_PER_MASS * structure_mass.mass() / inertia * delta;
+
+    wanted_change.clamp_length_max(max_change)
+}
+
 pub(super) fn update_ship_force_and_velocity(
     thrusters_query: Query<(&ThrusterSystem, &StructureSystem)>,
     mut query: Query<
@@ -91,27 +119,63 @@ pub(super) fn update_ship_force_and_velocity(
             &mut ExternalImpulse,
             &ReadMassProperties,
             Option<&Docked>,
+            Option<&FlightAssistMode>,
+            Option<&StructureHeat>,
+            Option<&StructureMass>,
+            Option<&EnergyBrownout>,
         ),
         (With<Ship>, With<Pilot>),
     >,
     mut energy_query: Query<&mut EnergyStorageSystem>,
     time: Res<Time>,
+    mut evw_generate_heat: EventWriter<GenerateHeatEvent>,
 ) {
     for (thruster_system, system) in thrusters_query.iter() {
-        if let Ok((movement, systems, transform, mut velocity, mut external_impulse, readmass, docked)) =
-            query.get_mut(system.structure_entity())
+        if let Ok((
+            movement,
+            systems,
+            transform,
+            mut velocity,
+            mut external_impulse,
+            readmass,
+            docked,
+            flight_assist,
+            heat,
+            structure_mass,
+            brownout,
+        )) = query.get_mut(system.structure_entity())
         {
+            let flight_assist = flight_assist.copied().unwrap_or_default();
+
             // Rotation
             if docked.is_none() {
-                let torque = Quat::from_affine3(&transform.compute_affine()).mul(movement.torque * 5.0);
+                let torque = Quat::from_affine3(&transform.compute_affine()).mul(movement.torque * ANGULAR_VELOCITY_PER_TORQUE);
 
                 const MAX_ANGLE_PER_SECOND: f32 = 100.0;
 
                 let max = MAX_ANGLE_PER_SECOND * time.delta_secs();
 
-                velocity.angvel = torque.clamp_length(0.0, max);
+                let wanted_change = if flight_assist == FlightAssistMode::Decoupled {
+                    // Angular momentum is preserved, so turning only changes how fast the ship is already spinning
+                    torque * time.delta_secs()
+                } else {
+                    torque - velocity.angvel
+                };
+
+                let change = match structure_mass {
+                    Some(structure_mass) => limit_turning(wanted_change, structure_mass, transform, time.delta_secs()),
+                    None => wanted_change,
+                };
+
+                velocity.angvel = (velocity.angvel + change).clamp_length(0.0, max);
 
-                velocity.linvel = velocity.linvel.clamp_length(0.0, MAX_SHIP_SPEED);
+                let max_speed = if flight_assist == FlightAssistMode::PrecisionDocking {
+                    FlightAssistMode::PRECISION_DOCKING_MAX_SPEED
+                } else {
+                    MAX_SHIP_SPEED
+                };
+
+                velocity.linvel = velocity.linvel.clamp_length(0.0, max_speed);
             }
 
             // Position
@@ -133,7 +197,11 @@ pub(super) fn update_ship_force_and_velocity(
                 let ratio;
 
                 if let Ok(mut energy_system) = systems.query_mut(&mut energy_query) {
-                    if energy_used > energy_system.get_energy() {
+                    // Browned out thrusters get no energy, so the ship can only drift
+                    if brownout.is_some_and(|b| b.contains(EnergyConsumer::Thrusters)) {
+                        ratio = 0.0;
+                        energy_used = 0.0;
+                    } else if energy_used > energy_system.get_energy() {
                         ratio = energy_system.get_energy() / energy_used;
                         energy_used = energy_system.get_energy();
                     } else {
@@ -142,13 +210,30 @@ pub(super) fn update_ship_force_and_velocity(
 
                     energy_system.decrease_energy(energy_used);
 
-                    movement_vector * (thruster_system.thrust_total() * ratio)
+                    evw_generate_heat.send(GenerateHeatEvent {
+                        structure_entity: system.structure_entity(),
+                        heat: energy_used * THRUSTER_HEAT_PER_ENERGY,
+                    });
+
+                    // Overheating structures can't push their thrusters as hard
+                    let throttle = heat.map(|x| x.throttle()).unwrap_or(1.0);
+
+                    let thrust_multiplier = if flight_assist == FlightAssistMode::PrecisionDocking {
+                        FlightAssistMode::PRECISION_DOCKING_THRUST_MULTIPLIER
+                    } else {
+                        1.0
+                    };
+
+                    movement_vector * (thruster_system.thrust_total() * ratio * thrust_multiplier * throttle)
                 } else {
                     Vec3::ZERO
                 }
             };
 
-            if movement.braking {
+            // Precision docking holds the ship still whenever the pilot isn't moving it
+            let auto_brake = flight_assist == FlightAssistMode::PrecisionDocking && normal == Vec3::ZERO;
+
+            if movement.braking || auto_brake {
                 let mut brake_vec = -velocity.linvel * readmass.get().mass;
                 let delta = time.delta_secs() * MAX_BRAKE_DELTA_PER_THRUST * thruster_system.thrust_total();
 
@@ -167,7 +252,6 @@ pub(super) fn update_ship_force_and_velocity(
 fn structure_loaded_event(
     mut event_reader: EventReader<StructureLoadedEvent>,
     mut structure_query: Query<(&Structure, &mut StructureSystems)>,
-    blocks: Res<Registry<Block>>,
     mut commands: Commands,
     thruster_blocks: Res<ThrusterBlocks>,
     registry: Res<Registry<StructureSystemType>>,
@@ -176,9 +260,11 @@ fn structure_loaded_event(
         if let Ok((structure, mut systems)) = structure_query.get_mut(ev.structure_entity) {
             let mut system = ThrusterSystem::default();
 
-            for block in structure.all_blocks_iter(false) {
-                if let Some(prop) = thruster_blocks.get(structure.block_at(block, &blocks)) {
-                    system.block_added(prop);
+            if let Some(counts) = structure.block_counts() {
+                for (id, prop) in thruster_blocks.iter() {
+                    for _ in 0..counts.count(id) {
+                        system.block_added(prop);
+                    }
                 }
             }
 
@@ -0,0 +1,469 @@
+//! Mounts turrets to their turret mounts & aims them at nearby enemies
+
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::{Added, Or, With, Without},
+        removal_detection::RemovedComponents,
+    },
+    math::{Quat, Vec3},
+    prelude::{in_state, App, Commands, EventReader, Has, IntoSystemConfigs, Parent, Query, Res, Transform, Update},
+    time::Time,
+    transform::components::GlobalTransform,
+};
+use bevy_rapier3d::{
+    dynamics::{FixedJointBuilder, ImpulseJoint, Velocity},
+    geometry::{CollisionGroups, Group},
+    pipeline::QueryFilter,
+    plugin::{RapierContextEntityLink, ReadRapierContext},
+};
+use cosmos_core::{
+    block::{block_events::BlockEventsSet, block_face::BlockFace, Block},
+    events::block_events::BlockChangedEvent,
+    logic::BlockLogicData,
+    physics::{location::Location, structure_physics::ChunkPhysicsPart},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        events::StructureLoadedEvent,
+        shields::SHIELD_COLLISION_GROUP,
+        systems::{
+            cloaking_system::{cloaked_ancestor, Cloaked},
+            dock_system::Docked,
+            laser_cannon_system::{LaserCannonSystem, LASER_BASE_VELOCITY},
+            missile_launcher_system::MissileLauncherSystem,
+            turret_system::{Turret, TurretSystem, TURRET_BASE_BLOCK, TURRET_MOUNT_BLOCK},
+            StructureSystem, StructureSystemType, StructureSystems, StructureSystemsSet, SystemActive,
+        },
+        Structure,
+    },
+};
+
+use crate::{
+    ai::pirate::PirateTarget,
+    universe::{safe_zone::SafeZones, spawners::pirate::Pirate},
+};
+
+use super::{sync::register_structure_system, thruster_system::ThrusterSystemSet};
+
+/// How far away the turret mount can be from the turret base for them to attach
+const MAX_MOUNT_CHECK: f32 = 1.3;
+/// How far away a turret will look for targets
+const MAX_TARGET_DISTANCE: f32 = 2000.0;
+/// How fast a turret can rotate (radians/sec)
+const TURRET_TURN_SPEED: f32 = FRAC_PI_2;
+/// How far off (radians) a turret can be aimed from its target and still fire
+const MAX_FIRING_ERROR: f32 = 5.0 * PI / 180.0;
+/// The furthest a turret can pitch upwards (radians)
+const MAX_PITCH: f32 = FRAC_PI_2 * 0.9;
+/// The furthest a turret can pitch downwards (radians)
+const MIN_PITCH: f32 = 0.0;
+
+#[derive(Component, Default, Debug)]
+/// Where a turret is currently aiming, relative to its mount.
+///
+/// This isn't synced, since the turret's transform is enough for the client.
+struct TurretAim {
+    /// Rotation (radians) around the mount's up axis
+    yaw: f32,
+    /// Rotation (radians) of the turret up from the mount's surface
+    pitch: f32,
+}
+
+fn turret_block_update_system(
+    mut event: EventReader<BlockChangedEvent>,
+    blocks: Res<Registry<Block>>,
+    mut system_query: Query<&mut TurretSystem>,
+    q_systems: Query<&StructureSystems>,
+) {
+    for ev in event.read() {
+        let Ok(systems) = q_systems.get(ev.block.structure()) else {
+            continue;
+        };
+
+        let Ok(mut system) = systems.query_mut(&mut system_query) else {
+            continue;
+        };
+
+        if blocks.from_numeric_id(ev.old_block).unlocalized_name() == TURRET_BASE_BLOCK {
+            system.block_removed(ev.block.coords());
+        }
+
+        if blocks.from_numeric_id(ev.new_block).unlocalized_name() == TURRET_BASE_BLOCK {
+            system.block_added(ev.block.coords());
+        }
+    }
+}
+
+fn turret_structure_loaded_event_processor(
+    mut event_reader: EventReader<StructureLoadedEvent>,
+    mut structure_query: Query<(&Structure, &mut StructureSystems)>,
+    blocks: Res<Registry<Block>>,
+    mut commands: Commands,
+    registry: Res<Registry<StructureSystemType>>,
+) {
+    for ev in event_reader.read() {
+        if let Ok((structure, mut systems)) = structure_query.get_mut(ev.structure_entity) {
+            let mut system = TurretSystem::default();
+
+            for block in structure.all_blocks_iter(false) {
+                if structure.block_at(block, &blocks).unlocalized_name() == TURRET_BASE_BLOCK {
+                    system.block_added(block);
+                }
+            }
+
+            systems.add_system(&mut commands, system, &registry);
+        }
+    }
+}
+
+/// Looks below every turret base for a turret mount to attach to
+fn attach_turrets(
+    context_access: ReadRapierContext,
+    q_structure: Query<(&Structure, &GlobalTransform, &RapierContextEntityLink)>,
+    q_turret_systems: Query<(&StructureSystem, &TurretSystem)>,
+    q_is_mounted: Query<(), Or<(With<Turret>, With<Docked>)>>,
+    q_turrets: Query<&Turret>,
+    q_chunk_entity: Query<&ChunkPhysicsPart>,
+    blocks: Res<Registry<Block>>,
+    mut commands: Commands,
+) {
+    let mut newly_mounted: Vec<Turret> = vec![];
+
+    for (ss, ts) in q_turret_systems.iter() {
+        let structure_entity = ss.structure_entity();
+
+        if q_is_mounted.contains(structure_entity) {
+            continue;
+        }
+
+        let Ok((structure, g_trans, pw)) = q_structure.get(structure_entity) else {
+            continue;
+        };
+
+        for &base_block in ts.block_locations() {
+            let rel_pos = structure.block_relative_position(base_block);
+            let down = structure.block_rotation(base_block).direction_of(BlockFace::Bottom).as_vec3();
+
+            let abs_block_pos = g_trans.transform_point(rel_pos);
+            let my_rotation = Quat::from_affine3(&g_trans.affine());
+            let ray_dir = my_rotation.mul_vec3(down);
+
+            let context = context_access.get(*pw);
+
+            let Some((entity, intersection)) = context.cast_ray_and_get_normal(
+                abs_block_pos,
+                ray_dir,
+                MAX_MOUNT_CHECK,
+                false,
+                QueryFilter::new()
+                    .groups(CollisionGroups::new(
+                        Group::ALL & !SHIELD_COLLISION_GROUP,
+                        Group::ALL & !SHIELD_COLLISION_GROUP,
+                    ))
+                    .predicate(&|e| {
+                        let Ok(ce) = q_chunk_entity.get(e) else {
+                            return false;
+                        };
+
+                        ce.structure_entity != structure_entity
+                    }),
+            ) else {
+                continue;
+            };
+
+            let Ok(hit_structure_entity) = q_chunk_entity.get(entity).map(|x| x.structure_entity) else {
+                continue;
+            };
+
+            let Ok((hit_structure, hit_g_trans, _)) = q_structure.get(hit_structure_entity) else {
+                continue;
+            };
+
+            let moved_point = intersection.point - intersection.normal * 0.01;
+            let point = hit_g_trans.compute_matrix().inverse().transform_point3(moved_point);
+
+            let Ok(hit_coords) = hit_structure.relative_coords_to_local_coords_checked(point.x, point.y, point.z) else {
+                continue;
+            };
+
+            if hit_structure.block_at(hit_coords, &blocks).unlocalized_name() != TURRET_MOUNT_BLOCK {
+                continue;
+            }
+
+            let mount_up = Quat::from_affine3(&hit_g_trans.affine())
+                .mul_vec3(hit_structure.block_rotation(hit_coords).direction_of(BlockFace::Top).as_vec3());
+
+            // The turret base must be sitting on top of the mount
+            if ray_dir.dot(mount_up) > -0.92 {
+                continue;
+            }
+
+            let already_taken = q_turrets
+                .iter()
+                .chain(newly_mounted.iter())
+                .any(|t| t.mounted_to == hit_structure_entity && t.mount_block == hit_coords);
+
+            if already_taken {
+                continue;
+            }
+
+            let turret = Turret {
+                mounted_to: hit_structure_entity,
+                mount_block: hit_coords,
+                base_block,
+            };
+            newly_mounted.push(turret.clone());
+            commands.entity(structure_entity).insert((turret, TurretAim::default()));
+
+            break;
+        }
+    }
+}
+
+fn monitor_removed_turret_blocks(
+    blocks: Res<Registry<Block>>,
+    q_turrets: Query<(Entity, &Turret)>,
+    q_structure: Query<(), With<Structure>>,
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    mut commands: Commands,
+) {
+    for ev in evr_block_changed.read() {
+        let old_block = blocks.from_numeric_id(ev.old_block).unlocalized_name();
+        if old_block != TURRET_MOUNT_BLOCK && old_block != TURRET_BASE_BLOCK {
+            continue;
+        }
+
+        for (turret_entity, turret) in q_turrets.iter() {
+            if turret.mounted_to == ev.block.structure() && turret.mount_block == ev.block.coords()
+                || ev.block.structure() == turret_entity && turret.base_block == ev.block.coords()
+            {
+                commands.entity(turret_entity).remove::<(Turret, TurretAim)>();
+            }
+        }
+    }
+
+    // The structure this was mounted to no longer exists
+    for (turret_entity, turret) in q_turrets.iter() {
+        if !q_structure.contains(turret.mounted_to) {
+            commands.entity(turret_entity).remove::<(Turret, TurretAim)>();
+        }
+    }
+}
+
+/// Computes the rotation of the turret relative to the structure it is mounted to
+fn turret_joint(turret: &Turret, aim: &TurretAim, mount_structure: &Structure, turret_structure: &Structure) -> FixedJointBuilder {
+    let mount_rotation = mount_structure.block_rotation(turret.mount_block);
+    let mount_up = mount_rotation.direction_of(BlockFace::Top).as_vec3();
+    let mount_forward = mount_rotation.direction_of(BlockFace::Front).as_vec3();
+
+    let base_rotation = turret_structure.block_rotation(turret.base_block);
+    let base_up = base_rotation.direction_of(BlockFace::Top).as_vec3();
+    let base_forward = base_rotation.direction_of(BlockFace::Front).as_vec3();
+
+    let mount_frame = Transform::default().looking_to(mount_forward, mount_up).rotation;
+    let base_frame = Transform::default().looking_to(base_forward, base_up).rotation;
+
+    FixedJointBuilder::default()
+        .local_anchor1(mount_structure.block_relative_position(turret.mount_block) + mount_up * 0.5)
+        .local_basis1(mount_frame * Quat::from_rotation_y(aim.yaw) * Quat::from_rotation_x(aim.pitch))
+        .local_anchor2(turret_structure.block_relative_position(turret.base_block) - base_up * 0.5)
+        .local_basis2(base_frame)
+        .contacts_enabled(false)
+}
+
+fn add_turret_joints(
+    mut removed_turrets: RemovedComponents<Turret>,
+    q_added_turret: Query<(Entity, &Turret, &TurretAim), Added<Turret>>,
+    q_structure: Query<&Structure>,
+    mut commands: Commands,
+) {
+    for removed_turret in removed_turrets.read() {
+        if let Some(mut ecmds) = commands.get_entity(removed_turret) {
+            ecmds.remove::<ImpulseJoint>();
+        }
+    }
+
+    for (ent, turret, aim) in q_added_turret.iter() {
+        let (Ok(mount_structure), Ok(turret_structure)) = (q_structure.get(turret.mounted_to), q_structure.get(ent)) else {
+            continue;
+        };
+
+        let joint = turret_joint(turret, aim, mount_structure, turret_structure);
+
+        commands.entity(ent).insert(ImpulseJoint::new(turret.mounted_to, joint));
+    }
+}
+
+/// Moves `current` towards `target` by at most `max_delta`, going the short way around the circle
+fn rotate_angle_towards(current: f32, target: f32, max_delta: f32) -> f32 {
+    let diff = (target - current + PI).rem_euclid(TAU) - PI;
+
+    current + diff.clamp(-max_delta, max_delta)
+}
+
+/// Points every turret at the closest enemy & fires if its mount is receiving a logic signal
+fn aim_turrets(
+    time: Res<Time>,
+    mut q_turrets: Query<(Entity, &Turret, &mut TurretAim, &Location, &GlobalTransform, &StructureSystems)>,
+    mut q_joint: Query<&mut ImpulseJoint>,
+    q_structure: Query<(&Structure, &GlobalTransform, Has<Pirate>)>,
+    q_pirates: Query<(&Location, &Velocity), With<Pirate>>,
+    q_pirate_targets: Query<(Entity, &Location, &Velocity), (With<PirateTarget>, Without<Pirate>)>,
+    q_cloaked: Query<(), With<Cloaked>>,
+    q_parent: Query<&Parent>,
+    q_velocity: Query<&Velocity>,
+    q_logic_data: Query<&BlockLogicData>,
+    q_laser_cannon_system: Query<(Entity, Has<SystemActive>), With<LaserCannonSystem>>,
+    q_missile_launcher_system: Query<(Entity, Has<SystemActive>), With<MissileLauncherSystem>>,
+    safe_zones: SafeZones,
+    mut commands: Commands,
+) {
+    for (turret_entity, turret, mut aim, turret_loc, turret_g_trans, systems) in q_turrets.iter_mut() {
+        let Ok((mount_structure, mount_g_trans, is_pirate)) = q_structure.get(turret.mounted_to) else {
+            continue;
+        };
+
+        let Ok((turret_structure, _, _)) = q_structure.get(turret_entity) else {
+            continue;
+        };
+
+        let closest_target = if is_pirate {
+            // cloaked ships (and anyone on them) can't be seen by pirates
+            q_pirate_targets
+                .iter()
+                .filter(|(ent, _, _)| cloaked_ancestor(*ent, &q_cloaked, &q_parent).is_none())
+                .map(|(_, loc, vel)| (loc, vel))
+                .collect::<Vec<_>>()
+        } else {
+            q_pirates.iter().collect::<Vec<_>>()
+        }
+        .into_iter()
+        .filter(|(loc, _)| loc.is_within_reasonable_range(turret_loc))
+        .min_by_key(|(loc, _)| loc.distance_sqrd(turret_loc).floor() as u64)
+        .filter(|(loc, _)| loc.distance_sqrd(turret_loc) <= MAX_TARGET_DISTANCE * MAX_TARGET_DISTANCE);
+
+        let mut on_target = false;
+
+        if let Some((target_loc, target_vel)) = closest_target {
+            let turret_linvel = q_velocity.get(turret.mounted_to).map(|x| x.linvel).unwrap_or(Vec3::ZERO);
+
+            let distance = (*target_loc - *turret_loc).absolute_coords_f32();
+            let laser_secs_to_reach_target = distance.length() / LASER_BASE_VELOCITY;
+            let direction = (distance + (target_vel.linvel - turret_linvel) * laser_secs_to_reach_target).normalize_or_zero();
+
+            let mount_rotation = mount_structure.block_rotation(turret.mount_block);
+            let mount_frame = Quat::from_affine3(&mount_g_trans.affine())
+                * Transform::default()
+                    .looking_to(
+                        mount_rotation.direction_of(BlockFace::Front).as_vec3(),
+                        mount_rotation.direction_of(BlockFace::Top).as_vec3(),
+                    )
+                    .rotation;
+
+            let local_direction = mount_frame.inverse() * direction;
+
+            let target_yaw = (-local_direction.x).atan2(-local_direction.z);
+            let target_pitch = local_direction
+                .y
+                .atan2(Vec3::new(local_direction.x, 0.0, local_direction.z).length())
+                .clamp(MIN_PITCH, MAX_PITCH);
+
+            let max_delta = TURRET_TURN_SPEED * time.delta_secs();
+            aim.yaw = rotate_angle_towards(aim.yaw, target_yaw, max_delta);
+            aim.pitch = rotate_angle_towards(aim.pitch, target_pitch, max_delta).clamp(MIN_PITCH, MAX_PITCH);
+
+            if let Ok(mut joint) = q_joint.get_mut(turret_entity) {
+                *joint = ImpulseJoint::new(turret.mounted_to, turret_joint(turret, &aim, mount_structure, turret_structure));
+            }
+
+            let turret_forward = Quat::from_affine3(&turret_g_trans.affine()).mul_vec3(
+                turret_structure
+                    .block_rotation(turret.base_block)
+                    .direction_of(BlockFace::Front)
+                    .as_vec3(),
+            );
+
+            on_target = turret_forward.angle_between(direction) <= MAX_FIRING_ERROR;
+        }
+
+        let firing_enabled = mount_structure
+            .query_block_data(turret.mount_block, &q_logic_data)
+            .map(|data| data.on())
+            .unwrap_or(false);
+
+        let should_fire = on_target && firing_enabled && !safe_zones.in_safe_zone(turret_loc);
+
+        for (system, active) in [
+            systems.query(&q_laser_cannon_system).ok(),
+            systems.query(&q_missile_launcher_system).ok(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            // Only touch the system when it changes, so change detection isn't triggered every frame
+            if should_fire && !active {
+                commands.entity(system).insert(SystemActive);
+            } else if !should_fire && active {
+                commands.entity(system).remove::<SystemActive>();
+            }
+        }
+    }
+}
+
+/// Turrets that are no longer mounted should stop firing
+fn stop_unmounted_turrets(
+    mut removed_turrets: RemovedComponents<Turret>,
+    q_systems: Query<&StructureSystems>,
+    q_laser_cannon_system: Query<Entity, With<LaserCannonSystem>>,
+    q_missile_launcher_system: Query<Entity, With<MissileLauncherSystem>>,
+    mut commands: Commands,
+) {
+    for ent in removed_turrets.read() {
+        let Ok(systems) = q_systems.get(ent) else {
+            continue;
+        };
+
+        for system in [
+            systems.query(&q_laser_cannon_system).ok(),
+            systems.query(&q_missile_launcher_system).ok(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            commands.entity(system).remove::<SystemActive>();
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            turret_structure_loaded_event_processor
+                .in_set(StructureSystemsSet::InitSystems)
+                .ambiguous_with(StructureSystemsSet::InitSystems)
+                .run_if(in_state(GameState::Playing)),
+            (
+                turret_block_update_system
+                    .in_set(BlockEventsSet::ProcessEvents)
+                    .in_set(StructureSystemsSet::UpdateSystemsBlocks),
+                attach_turrets.after(ThrusterSystemSet::ApplyThrusters),
+                monitor_removed_turret_blocks
+                    .in_set(BlockEventsSet::ProcessEvents)
+                    .in_set(StructureSystemsSet::UpdateSystemsBlocks),
+                add_turret_joints,
+                stop_unmounted_turrets,
+                aim_turrets,
+            )
+                .chain()
+                .in_set(BlockEventsSet::ProcessEvents)
+                .run_if(in_state(GameState::Playing)),
+        ),
+    );
+
+    register_structure_system::<TurretSystem>(app, false, TURRET_BASE_BLOCK);
+}
@@ -0,0 +1,345 @@
+//! Validates requests from clients to weld a ship (the module) onto a larger ship or station, and merges
+//! the module into it.
+//!
+//! The module must be lined up with the target's block grid, touching it, and every one of its blocks must
+//! land on an empty spot within the target's bounds. The module's blocks are then placed on the target (sending
+//! the normal block changed events, so things like logic groups and block counts update), and the module's block data
+//! (inventories, logic data, etc.) is moved over to them.
+
+use bevy::{core::FrameCount, prelude::*};
+use cosmos_core::{
+    block::{
+        block_direction::{BlockDirection, ALL_BLOCK_DIRECTIONS},
+        block_events::BlockEventsSet,
+        block_face::BlockFace,
+        block_rotation::BlockRotation,
+        Block,
+    },
+    chat::ServerSendChatMessageEvent,
+    ecs::NeedsDespawned,
+    events::block_events::BlockChangedEvent,
+    inventory::{held_item_slot::HeldItemSlot, itemstack::ItemShouldHaveData, Inventory},
+    item::Item,
+    netty::{
+        server::ServerLobby,
+        sync::events::server_event::{NettyEventReceived, NettyEventWriter},
+        system_sets::NetworkingSystemsSet,
+    },
+    physics::location::Location,
+    prelude::{BlockCoordinate, Ship, Station, Structure, StructureBlock},
+    registry::{identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{
+        chunk::BlockInfo,
+        ship::pilot::Pilot,
+        welding::{WeldStructuresEvent, WELDING_TOOL_ITEM},
+    },
+};
+use renet2::ClientId;
+
+use crate::structure::{
+    ownership::{notify_no_permission, StructurePermissions},
+    shared::move_block_data::PendingBlockDataMoves,
+};
+
+/// Players can reach a little further than the client's raycast to account for latency
+const MAX_WELD_DISTANCE: f32 = 12.0;
+/// How far (in radians) the module's rotation can be from lining up with the target's grid
+const MAX_ROTATION_ERROR: f32 = 0.1;
+/// How far (in blocks) the module's blocks can be from lining up with the target's grid
+const MAX_OFFSET_ERROR: f32 = 0.25;
+/// Cores are not moved onto the target, and are given back to the player instead
+const CORE_BLOCK: &str = "cosmos:ship_core";
+
+struct MovedBlock {
+    from: BlockCoordinate,
+    to: BlockCoordinate,
+    block_id: u16,
+    info: BlockInfo,
+    health: f32,
+}
+
+/// The direction that is closest to pointing the same way as this vector
+fn nearest_direction(vec: Vec3) -> BlockDirection {
+    ALL_BLOCK_DIRECTIONS
+        .into_iter()
+        .max_by(|a, b| a.as_vec3().dot(vec).total_cmp(&b.as_vec3().dot(vec)))
+        .expect("There is always at least one direction")
+}
+
+/// Snaps this rotation to the nearest rotation that keeps the block grid axis-aligned.
+///
+/// Returns `None` if the rotation is not close enough to any of them.
+fn snap_rotation(rotation: Quat) -> Option<Quat> {
+    let x = nearest_direction(rotation * Vec3::X).as_vec3();
+    let y = nearest_direction(rotation * Vec3::Y).as_vec3();
+
+    if x.dot(y).abs() > 0.5 {
+        return None;
+    }
+
+    let snapped = Quat::from_mat3(&Mat3::from_cols(x, y, x.cross(y)));
+
+    (snapped.angle_between(rotation) <= MAX_ROTATION_ERROR).then_some(snapped)
+}
+
+/// Rotates a block's rotation by the (axis-aligned) rotation between the module and the target
+fn rotate_block_rotation(block_rotation: BlockRotation, rotation: Quat) -> BlockRotation {
+    let rotate = |face: BlockFace| nearest_direction(rotation * block_rotation.direction_of(face).as_vec3());
+
+    BlockRotation::from_face_directions(rotate(BlockFace::Top), rotate(BlockFace::Front))
+}
+
+fn reply(nevw_chat: &mut NettyEventWriter<ServerSendChatMessageEvent>, client_id: ClientId, message: impl Into<String>) {
+    nevw_chat.send(
+        ServerSendChatMessageEvent {
+            sender: None,
+            message: message.into(),
+        },
+        client_id,
+    );
+}
+
+/// Works out where every block of the module would go on the target, or why it can't be welded there
+fn plan_weld(
+    module: &Structure,
+    module_location: &Location,
+    module_rotation: Quat,
+    target: &Structure,
+    target_location: &Location,
+    target_rotation: Quat,
+    blocks: &Registry<Block>,
+) -> Result<Vec<MovedBlock>, &'static str> {
+    let rotation = snap_rotation(target_rotation.inverse() * module_rotation).ok_or("The ship isn't lined up with this structure.")?;
+    let offset = target_rotation.inverse() * target_location.relative_coords_to(module_location);
+
+    let mut moved = vec![];
+    let mut touching = false;
+
+    for from in module.all_blocks_iter(false) {
+        let position = rotation * module.block_relative_position(from) + offset;
+
+        let to = target
+            .relative_coords_to_local_coords_checked(position.x, position.y, position.z)
+            .map_err(|_| "The ship doesn't fit within this structure.")?;
+
+        if target.block_relative_position(to).distance_squared(position) > MAX_OFFSET_ERROR * MAX_OFFSET_ERROR {
+            return Err("The ship isn't lined up with this structure.");
+        }
+
+        if target.has_block_at(to) {
+            return Err("The ship overlaps blocks on this structure.");
+        }
+
+        touching = touching
+            || ALL_BLOCK_DIRECTIONS
+                .into_iter()
+                .filter_map(|dir| to.step(dir).ok())
+                .any(|c| target.is_within_blocks(c) && target.has_block_at(c));
+
+        let mut info = module.block_info_at(from);
+        info.set_rotation(rotate_block_rotation(info.get_rotation(), rotation));
+
+        moved.push(MovedBlock {
+            from,
+            to,
+            block_id: module.block_id_at(from),
+            info,
+            health: module.get_block_health(from, blocks),
+        });
+    }
+
+    if !touching {
+        return Err("The ship must be touching this structure to be welded to it.");
+    }
+
+    Ok(moved)
+}
+
+fn on_weld_structures(
+    mut commands: Commands,
+    mut nevr_weld: EventReader<NettyEventReceived<WeldStructuresEvent>>,
+    lobby: Res<ServerLobby>,
+    mut q_player: Query<(&GlobalTransform, &HeldItemSlot, &mut Inventory)>,
+    mut q_structure: Query<(&mut Structure, &Location, &GlobalTransform, Has<Ship>, Has<Station>, Has<Pilot>)>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    needs_data: Res<ItemShouldHaveData>,
+    permissions: StructurePermissions,
+    frame: Res<FrameCount>,
+    mut pending_data_moves: ResMut<PendingBlockDataMoves>,
+    mut evw_block_changed: EventWriter<BlockChangedEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    let Some(welding_tool) = items.from_id(WELDING_TOOL_ITEM) else {
+        return;
+    };
+
+    for ev in nevr_weld.read() {
+        let Some(player_ent) = lobby.player_from_id(ev.client_id) else {
+            warn!("Bad player - cid: {}", ev.client_id);
+            continue;
+        };
+
+        let Ok((player_g_trans, held_item, mut inventory)) = q_player.get_mut(player_ent) else {
+            continue;
+        };
+
+        if inventory
+            .itemstack_at(held_item.slot() as usize)
+            .is_none_or(|is| is.item_id() != welding_tool.id())
+        {
+            warn!("Player {player_ent:?} tried to weld without holding the welding tool.");
+            continue;
+        }
+
+        let module_ent = ev.module.structure();
+        let target_ent = ev.target.structure();
+
+        if module_ent == target_ent {
+            continue;
+        }
+
+        if !permissions.can_use(player_ent, module_ent) || !permissions.can_use(player_ent, target_ent) {
+            notify_no_permission(&mut nevw_chat, ev.client_id);
+            continue;
+        }
+
+        let Ok(
+            [(mut module, module_loc, module_g_trans, module_is_ship, _, module_piloted), (mut target, target_loc, target_g_trans, target_is_ship, target_is_station, _)],
+        ) = q_structure.get_many_mut([module_ent, target_ent])
+        else {
+            continue;
+        };
+
+        if !matches!(*module, Structure::Full(_)) || !matches!(*target, Structure::Full(_)) || !(target_is_ship || target_is_station) {
+            continue;
+        }
+
+        if !module.is_within_blocks(ev.module.coords()) || !target.is_within_blocks(ev.target.coords()) {
+            continue;
+        }
+
+        let max_distance_sqrd = MAX_WELD_DISTANCE * MAX_WELD_DISTANCE;
+        let in_reach = |structure: &Structure, g_trans: &GlobalTransform, coords: BlockCoordinate| {
+            g_trans
+                .transform_point(structure.block_relative_position(coords))
+                .distance_squared(player_g_trans.translation())
+                <= max_distance_sqrd
+        };
+
+        if !in_reach(&module, module_g_trans, ev.module.coords()) || !in_reach(&target, target_g_trans, ev.target.coords()) {
+            warn!("Player {player_ent:?} tried to weld a block that is too far away.");
+            continue;
+        }
+
+        if !module_is_ship {
+            reply(&mut nevw_chat, ev.client_id, "Only ships can be welded onto other structures.");
+            continue;
+        }
+
+        if module_piloted {
+            reply(&mut nevw_chat, ev.client_id, "Nobody can be piloting a ship while it is welded.");
+            continue;
+        }
+
+        let moved = match plan_weld(
+            &module,
+            module_loc,
+            module_g_trans.to_scale_rotation_translation().1,
+            &target,
+            target_loc,
+            target_g_trans.to_scale_rotation_translation().1,
+            &blocks,
+        ) {
+            Ok(moved) => moved,
+            Err(reason) => {
+                reply(&mut nevw_chat, ev.client_id, reason);
+                continue;
+            }
+        };
+
+        let core_id = blocks.from_id(CORE_BLOCK).map(|b| b.id());
+
+        let mut n_cores = 0;
+
+        for moved_block in moved {
+            if Some(moved_block.block_id) == core_id {
+                n_cores += 1;
+                continue;
+            }
+
+            let block = blocks.from_numeric_id(moved_block.block_id);
+
+            target.set_block_and_info_at(moved_block.to, block, moved_block.info, &blocks, Some(&mut evw_block_changed));
+            target.set_block_health(moved_block.to, moved_block.health, &blocks);
+
+            if let Some(data_entity) = module.block_data(moved_block.from) {
+                // Keeps the data from being despawned along with the module's chunks
+                commands.entity(data_entity).remove_parent();
+                module.set_block_data_entity(moved_block.from, None);
+
+                pending_data_moves.queue(
+                    data_entity,
+                    StructureBlock::new(moved_block.to, target_ent),
+                    moved_block.block_id,
+                    &frame,
+                );
+            }
+        }
+
+        if n_cores != 0 {
+            if let Some(core_item) = items.from_id(CORE_BLOCK) {
+                let (leftover, _) = inventory.insert_item(core_item, n_cores, &mut commands, &needs_data);
+                if leftover != 0 {
+                    reply(
+                        &mut nevw_chat,
+                        ev.client_id,
+                        "Your inventory was full, so the ship's core was lost.",
+                    );
+                }
+            }
+        }
+
+        commands.entity(module_ent).insert(NeedsDespawned);
+
+        info!("Player {player_ent:?} welded structure {module_ent:?} onto structure {target_ent:?}.");
+        reply(&mut nevw_chat, ev.client_id, "Welded the ship onto the structure.");
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        on_weld_structures
+            .in_set(NetworkingSystemsSet::Between)
+            .in_set(BlockEventsSet::ChangeBlocks)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearly_aligned_rotations_snap() {
+        let snapped = snap_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2 + 0.05)).expect("Close enough to snap");
+        assert!(snapped.angle_between(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2)) < 0.001);
+
+        assert!(snap_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_4)).is_none());
+        assert!(snap_rotation(Quat::IDENTITY).is_some_and(|q| q.angle_between(Quat::IDENTITY) < 0.001));
+    }
+
+    #[test]
+    fn block_rotations_follow_the_module() {
+        let rotation = Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
+        let rotated = rotate_block_rotation(BlockRotation::default(), rotation);
+
+        assert_eq!(rotated.direction_of(BlockFace::Top), BlockDirection::PosY);
+        assert_eq!(
+            rotated.direction_of(BlockFace::Front),
+            nearest_direction(rotation * BlockDirection::NegZ.as_vec3())
+        );
+    }
+}
@@ -0,0 +1,311 @@
+//! Keeps multiplayer servers from permanently running out of asteroids to mine.
+//!
+//! Once most of an asteroid has been mined away, what's left of it crumbles and the asteroid is
+//! recorded as depleted. After a while, a fresh asteroid is spawned somewhere in that same sector.
+//! Asteroids in systems with a shop (where most players spend their time) come back faster.
+//!
+//! The rates can be changed in `./config/cosmos/asteroid_respawn.json`.
+
+use std::{fs, time::SystemTime};
+
+use bevy::prelude::*;
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    entities::player::Player,
+    netty::{sync::IdentifiableComponent, system_sets::NetworkingSystemsSet},
+    physics::location::{Location, SystemUnit, SECTOR_DIMENSIONS},
+    state::GameState,
+    structure::{
+        asteroid::{asteroid_builder::TAsteroidBuilder, loading::AsteroidNeedsCreated, Asteroid, ASTEROID_LOAD_RADIUS},
+        coordinates::ChunkCoordinate,
+        events::StructureLoadedEvent,
+        full_structure::FullStructure,
+        loading::StructureLoadingSet,
+        Structure,
+    },
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    persistence::make_persistent::{make_persistent, DefaultPersistentComponent},
+    structure::asteroid::server_asteroid_builder::ServerAsteroidBuilder,
+};
+
+use super::generation::{SystemItem, UniverseSystems};
+
+const ASTEROID_RESPAWN_SETTINGS_PATH: &str = "./config/cosmos/asteroid_respawn.json";
+const DEPLETED_ASTEROIDS_PATH: &str = "world/depleted_asteroids.json";
+
+/// How often (in seconds) asteroids are checked to see if they have been depleted or should respawn
+const CHECK_INTERVAL_SECS: f32 = 5.0;
+
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+/// Server-configurable rates for how asteroids are depleted and respawned
+pub struct AsteroidRespawnSettings {
+    /// If depleted asteroids should respawn at all
+    pub enabled: bool,
+    /// An asteroid crumbles once it has less than this fraction (0.0 to 1.0) of its original blocks left
+    pub depleted_fraction: f32,
+    /// How long (in seconds) it takes a depleted asteroid to respawn
+    pub respawn_delay_secs: u64,
+    /// How long (in seconds) it takes a depleted asteroid to respawn in a system that has a shop in it
+    pub inhabited_respawn_delay_secs: u64,
+}
+
+impl Default for AsteroidRespawnSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            depleted_fraction: 0.1,
+            respawn_delay_secs: 60 * 60 * 4,
+            inhabited_respawn_delay_secs: 60 * 60,
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, Serialize, Deserialize, Reflect)]
+/// How many blocks this asteroid had when it was first loaded
+struct AsteroidOriginalBlocks(u32);
+
+impl IdentifiableComponent for AsteroidOriginalBlocks {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:asteroid_original_blocks"
+    }
+}
+
+impl DefaultPersistentComponent for AsteroidOriginalBlocks {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// An asteroid that was mined away and is waiting to be replaced
+struct DepletedAsteroid {
+    location: Location,
+    /// The chunk dimensions of the asteroid
+    size: u64,
+    temperature: f32,
+    /// When this asteroid was depleted, in seconds since the unix epoch
+    depleted_at: u64,
+}
+
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+/// Every asteroid that has been depleted and not yet respawned
+struct DepletedAsteroids(Vec<DepletedAsteroid>);
+
+impl DepletedAsteroids {
+    fn save(&self) {
+        let json = serde_json::to_string_pretty(self).expect("Depleted asteroids are always valid json");
+
+        if let Err(e) = fs::write(DEPLETED_ASTEROIDS_PATH, json) {
+            error!("Unable to save depleted asteroids to {DEPLETED_ASTEROIDS_PATH}.\n{e:?}");
+        }
+    }
+}
+
+#[derive(Resource, Debug)]
+struct AsteroidCheckTimer(Timer);
+
+impl Default for AsteroidCheckTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(CHECK_INTERVAL_SECS, TimerMode::Repeating))
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn total_blocks(structure: &Structure) -> u32 {
+    structure
+        .block_counts()
+        .map(|counts| counts.iter().map(|(_, n)| n).sum())
+        .unwrap_or(0)
+}
+
+fn record_original_blocks(
+    mut commands: Commands,
+    mut evr_structure_loaded: EventReader<StructureLoadedEvent>,
+    q_asteroid: Query<&Structure, (With<Asteroid>, Without<AsteroidOriginalBlocks>)>,
+) {
+    for ev in evr_structure_loaded.read() {
+        let Ok(structure) = q_asteroid.get(ev.structure_entity) else {
+            continue;
+        };
+
+        commands
+            .entity(ev.structure_entity)
+            .insert(AsteroidOriginalBlocks(total_blocks(structure)));
+    }
+}
+
+fn tick_check_timer(mut timer: ResMut<AsteroidCheckTimer>, time: Res<Time>) {
+    timer.0.tick(time.delta());
+}
+
+fn crumble_depleted_asteroids(
+    mut commands: Commands,
+    timer: Res<AsteroidCheckTimer>,
+    settings: Res<AsteroidRespawnSettings>,
+    q_asteroids: Query<(Entity, &Asteroid, &Structure, &Location, &AsteroidOriginalBlocks), Without<NeedsDespawned>>,
+    mut depleted: ResMut<DepletedAsteroids>,
+) {
+    if !timer.0.just_finished() || !settings.enabled {
+        return;
+    }
+
+    let mut changed = false;
+
+    for (ent, asteroid, structure, location, original) in q_asteroids.iter() {
+        if original.0 == 0 || total_blocks(structure) as f32 >= original.0 as f32 * settings.depleted_fraction {
+            continue;
+        }
+
+        info!("Asteroid {ent:?} at {location} has been depleted.");
+
+        commands.entity(ent).insert(NeedsDespawned);
+
+        depleted.0.push(DepletedAsteroid {
+            location: *location,
+            size: structure.chunk_dimensions().x,
+            temperature: asteroid.temperature(),
+            depleted_at: now_secs(),
+        });
+        changed = true;
+    }
+
+    if changed {
+        depleted.save();
+    }
+}
+
+/// Depleted asteroids are only respawned once a player is close enough that a new asteroid would be generated there,
+/// the same way asteroids are generated the first time.
+fn respawn_asteroids(
+    mut commands: Commands,
+    timer: Res<AsteroidCheckTimer>,
+    settings: Res<AsteroidRespawnSettings>,
+    systems: Res<UniverseSystems>,
+    q_players: Query<&Location, With<Player>>,
+    mut depleted: ResMut<DepletedAsteroids>,
+) {
+    if !timer.0.just_finished() || !settings.enabled || depleted.0.is_empty() {
+        return;
+    }
+
+    let now = now_secs();
+    let mut rng = rand::thread_rng();
+    let n_before = depleted.0.len();
+
+    depleted.0.retain(|asteroid| {
+        let inhabited = systems
+            .system(asteroid.location.get_system_coordinates())
+            .is_some_and(|system| system.iter().any(|item| matches!(item.item, SystemItem::Shop)));
+
+        let delay = if inhabited {
+            settings.inhabited_respawn_delay_secs
+        } else {
+            settings.respawn_delay_secs
+        };
+
+        if now.saturating_sub(asteroid.depleted_at) < delay {
+            return true;
+        }
+
+        let sector = asteroid.location.sector();
+
+        if !q_players
+            .iter()
+            .any(|loc| (loc.sector() - sector).abs().max_element() <= ASTEROID_LOAD_RADIUS as SystemUnit)
+        {
+            return true;
+        }
+
+        let loc = Location::new(
+            Vec3::new(rng.gen::<f32>(), rng.gen::<f32>(), rng.gen::<f32>()) * SECTOR_DIMENSIONS - SECTOR_DIMENSIONS / 2.0,
+            sector,
+        );
+
+        let mut structure = Structure::Full(FullStructure::new(ChunkCoordinate::new(
+            asteroid.size,
+            asteroid.size,
+            asteroid.size,
+        )));
+        let builder = ServerAsteroidBuilder::default();
+        let mut entity_cmd = commands.spawn_empty();
+
+        builder.insert_asteroid(&mut entity_cmd, loc, &mut structure, asteroid.temperature);
+
+        entity_cmd.insert((structure, AsteroidNeedsCreated));
+
+        info!("Respawning depleted asteroid at {loc}.");
+
+        false
+    });
+
+    if depleted.0.len() != n_before {
+        depleted.save();
+    }
+}
+
+fn load_asteroid_respawn_settings(mut commands: Commands) {
+    let settings = match fs::read_to_string(ASTEROID_RESPAWN_SETTINGS_PATH) {
+        Ok(json) => serde_json::from_str::<AsteroidRespawnSettings>(&json).unwrap_or_else(|e| {
+            error!("Invalid asteroid respawn settings in {ASTEROID_RESPAWN_SETTINGS_PATH} - using defaults.\n{e:?}");
+            AsteroidRespawnSettings::default()
+        }),
+        Err(_) => {
+            let settings = AsteroidRespawnSettings::default();
+
+            let json = serde_json::to_string_pretty(&settings).expect("Asteroid respawn settings are always valid json");
+            if let Err(e) = fs::create_dir_all("./config/cosmos").and_then(|_| fs::write(ASTEROID_RESPAWN_SETTINGS_PATH, json)) {
+                error!("Unable to write default asteroid respawn settings to {ASTEROID_RESPAWN_SETTINGS_PATH}.\n{e:?}");
+            }
+
+            settings
+        }
+    };
+
+    commands.insert_resource(settings);
+}
+
+fn load_depleted_asteroids(mut commands: Commands) {
+    let depleted = fs::read_to_string(DEPLETED_ASTEROIDS_PATH)
+        .ok()
+        .map(|json| {
+            serde_json::from_str::<DepletedAsteroids>(&json).unwrap_or_else(|e| {
+                error!("Invalid depleted asteroids in {DEPLETED_ASTEROIDS_PATH} - ignoring them.\n{e:?}");
+                DepletedAsteroids::default()
+            })
+        })
+        .unwrap_or_default();
+
+    commands.insert_resource(depleted);
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<AsteroidOriginalBlocks>(app);
+
+    app.init_resource::<AsteroidRespawnSettings>()
+        .init_resource::<DepletedAsteroids>()
+        .init_resource::<AsteroidCheckTimer>()
+        .add_systems(
+            OnEnter(GameState::PostLoading),
+            (load_asteroid_respawn_settings, load_depleted_asteroids),
+        )
+        .add_systems(
+            Update,
+            record_original_blocks
+                .after(StructureLoadingSet::StructureLoaded)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            Update,
+            (tick_check_timer, crumble_depleted_asteroids, respawn_asteroids)
+                .chain()
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .register_type::<AsteroidOriginalBlocks>();
+}
@@ -0,0 +1,514 @@
+//! Derelicts are damaged, abandoned ships and station wrecks found in deep space.
+//!
+//! Every derelict is generated from a template in `assets/cosmos/derelicts`, which points to the
+//! blueprint it is built from. When a derelict is first loaded, it is damaged by a random amount, has
+//! a few storage containers filled from its template's loot table, and has some of its blocks rigged
+//! to explode when they are broken.
+
+use std::{ffi::OsStr, fs, time::Duration};
+
+use bevy::{prelude::*, time::common_conditions::on_timer, utils::HashSet};
+use bevy_rapier3d::prelude::{RigidBody, Velocity};
+use cosmos_core::{
+    block::{block_events::BlockEventsSet, block_rotation::BlockRotation, Block},
+    entities::player::Player,
+    events::block_events::BlockChangedEvent,
+    inventory::{itemstack::ItemShouldHaveData, Inventory},
+    item::Item,
+    netty::{sync::IdentifiableComponent, system_sets::NetworkingSystemsSet},
+    persistence::LoadingDistance,
+    physics::location::{Location, Sector, SectorUnit, SECTOR_DIMENSIONS, SYSTEM_SECTORS},
+    projectiles::missile::Explosion,
+    registry::{create_registry, identifiable::Identifiable, Registry},
+    state::GameState,
+    structure::{coordinates::BlockCoordinate, events::StructureLoadedEvent, Structure},
+    utils::quat_math::random_quat,
+};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::{
+    init::init_world::ServerSeed,
+    loot::{LootSet, LootTable},
+    persistence::{
+        loading::{LoadingBlueprintSystemSet, NeedsBlueprintLoaded},
+        make_persistent::{make_persistent, DefaultPersistentComponent},
+    },
+    rng::get_rng_for_sector,
+};
+
+use super::generation::{GenerateSystemEvent, SystemGenerationSet, SystemItem, SystemItemDerelict, UniverseSystems};
+
+/// How close (in sectors) a player must be for a derelict to be spawned
+const DERELICT_LOAD_DISTANCE: SectorUnit = 2;
+
+/// Derelicts will only generate at least this many sectors away from anything else in the system
+const DEEP_SPACE_DISTANCE: SectorUnit = 4;
+
+/// The most times we'll try to find an empty sector for a single derelict
+const MAX_PLACEMENT_TRIES: usize = 20;
+
+/// How powerful the explosion of a rigged block is
+const HAZARD_EXPLOSION_POWER: f32 = 400.0;
+
+/// These blocks are never destroyed or replaced, since losing them would destroy the whole derelict
+const PROTECTED_BLOCKS: [&str; 2] = ["cosmos:ship_core", "cosmos:station_core"];
+
+const STORAGE_BLOCK: &str = "cosmos:storage";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RawDerelictTemplate {
+    /// The path to the blueprint this derelict is made from
+    blueprint: String,
+    /// How likely this template is to be picked compared to the other templates
+    weight: u32,
+    /// The smallest fraction (0.0 to 1.0) of blocks that will be destroyed
+    min_damage: f32,
+    /// The largest fraction (0.0 to 1.0) of blocks that will be destroyed
+    max_damage: f32,
+    loot_table: String,
+    loot_containers: usize,
+    hazards: usize,
+    /// Blocks that are always removed from this derelict, such as shop blocks on station wrecks
+    #[serde(default)]
+    stripped_blocks: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+/// A blueprint that derelicts can be generated from, and how it should be wrecked
+pub struct DerelictTemplate {
+    id: u16,
+    unlocalized_name: String,
+    blueprint: String,
+    weight: u32,
+    min_damage: f32,
+    max_damage: f32,
+    loot_table: u16,
+    loot_containers: usize,
+    hazards: usize,
+    stripped_blocks: Vec<u16>,
+}
+
+impl Identifiable for DerelictTemplate {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+#[derive(Component, Debug)]
+/// This structure is being loaded from a derelict template, and needs to be wrecked once it has loaded
+struct DerelictNeedsGenerated {
+    template: u16,
+}
+
+#[derive(Component, Debug)]
+/// Storage containers that were placed on this derelict and are waiting for their inventories to be filled
+struct DerelictLootPending {
+    containers: Vec<BlockCoordinate>,
+    loot_table: u16,
+}
+
+#[derive(Component, Debug, Default, Clone, Serialize, Deserialize, Reflect)]
+/// Blocks on this derelict that will explode when broken
+struct DerelictHazards(Vec<BlockCoordinate>);
+
+impl IdentifiableComponent for DerelictHazards {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:derelict_hazards"
+    }
+}
+
+impl DefaultPersistentComponent for DerelictHazards {}
+
+fn load_derelict_templates(
+    mut templates: ResMut<Registry<DerelictTemplate>>,
+    loot_tables: Res<Registry<LootTable>>,
+    blocks: Res<Registry<Block>>,
+) {
+    for entry in WalkDir::new("assets/cosmos/derelicts").max_depth(1) {
+        let Ok(entry) = entry else {
+            continue;
+        };
+
+        let path = entry.path();
+        if path.is_dir() || path.extension().and_then(OsStr::to_str) != Some("json") {
+            continue;
+        }
+
+        let Some(name) = path.file_stem().and_then(OsStr::to_str) else {
+            continue;
+        };
+
+        let template_json = fs::read(path).unwrap_or_else(|e| panic!("Unable to read derelict template file {path:?}\n{e:?}"));
+
+        let template = serde_json::from_slice::<RawDerelictTemplate>(&template_json)
+            .unwrap_or_else(|e| panic!("Invalid derelict template json {path:?}\n{e:?}"));
+
+        let Some(loot_table) = loot_tables.from_id(&template.loot_table) else {
+            error!(
+                "Unable to find loot table with id matching {:?} in derelict template {path:?}",
+                template.loot_table
+            );
+            continue;
+        };
+
+        if !fs::exists(&template.blueprint).unwrap_or(false) {
+            error!("Missing blueprint {:?} for derelict template {path:?}", template.blueprint);
+            continue;
+        }
+
+        templates.register(DerelictTemplate {
+            id: 0,
+            unlocalized_name: format!("cosmos:{name}"),
+            blueprint: template.blueprint,
+            weight: template.weight,
+            min_damage: template.min_damage.clamp(0.0, 1.0),
+            max_damage: template.max_damage.clamp(0.0, 1.0),
+            loot_table: loot_table.id(),
+            loot_containers: template.loot_containers,
+            hazards: template.hazards,
+            stripped_blocks: template
+                .stripped_blocks
+                .iter()
+                .flat_map(|block| blocks.from_id(block))
+                .map(|block| block.id())
+                .collect(),
+        });
+    }
+
+    info!("Loaded {} derelict templates", templates.iter().count());
+}
+
+fn generate_derelicts(
+    mut systems: ResMut<UniverseSystems>,
+    mut evr_generate_system: EventReader<GenerateSystemEvent>,
+    server_seed: Res<ServerSeed>,
+    templates: Res<Registry<DerelictTemplate>>,
+) {
+    let total_weight: u32 = templates.iter().map(|t| t.weight).sum();
+    if total_weight == 0 {
+        return;
+    }
+
+    for ev in evr_generate_system.read() {
+        let Some(system) = systems.system_mut(ev.system) else {
+            continue;
+        };
+
+        // Uses the opposite corner of the system from shops so derelicts don't share their rng.
+        let seed_sector = ev.system.negative_most_sector() + Sector::splat(SYSTEM_SECTORS as SectorUnit - 1);
+        let mut rng = get_rng_for_sector(&server_seed, &seed_sector);
+
+        let n_derelicts = rng.gen_range(3..=8);
+
+        let multiplier = SECTOR_DIMENSIONS;
+        let adder = -SECTOR_DIMENSIONS / 2.0;
+
+        for _ in 0..n_derelicts {
+            let mut pick = rng.gen_range(0..total_weight);
+            let Some(template) = templates.iter().find(|t| {
+                if pick < t.weight {
+                    true
+                } else {
+                    pick -= t.weight;
+                    false
+                }
+            }) else {
+                continue;
+            };
+
+            for _ in 0..MAX_PLACEMENT_TRIES {
+                let sector = Sector::new(
+                    rng.gen_range(0..SYSTEM_SECTORS as SectorUnit),
+                    rng.gen_range(0..SYSTEM_SECTORS as SectorUnit),
+                    rng.gen_range(0..SYSTEM_SECTORS as SectorUnit),
+                ) + ev.system.negative_most_sector();
+
+                let in_deep_space = system
+                    .iter()
+                    .all(|item| (item.location.sector() - sector).abs().max_element() > DEEP_SPACE_DISTANCE);
+
+                if !in_deep_space {
+                    continue;
+                }
+
+                let loc = Location::new(
+                    Vec3::new(
+                        rng.gen::<f32>() * multiplier + adder,
+                        rng.gen::<f32>() * multiplier + adder,
+                        rng.gen::<f32>() * multiplier + adder,
+                    ),
+                    sector,
+                );
+
+                system.add_item(
+                    loc,
+                    SystemItem::Derelict(SystemItemDerelict {
+                        template: template.unlocalized_name().to_owned(),
+                    }),
+                );
+
+                break;
+            }
+        }
+    }
+}
+
+fn spawn_derelicts(
+    q_players: Query<&Location, With<Player>>,
+    server_seed: Res<ServerSeed>,
+    templates: Res<Registry<DerelictTemplate>>,
+    mut commands: Commands,
+    mut systems: ResMut<UniverseSystems>,
+) {
+    let mut generated = HashSet::new();
+
+    for player_loc in q_players.iter() {
+        let Some(system) = systems.system_mut(player_loc.get_system_coordinates()) else {
+            continue;
+        };
+
+        for (derelict_loc, derelict) in system.iter().flat_map(|x| match &x.item {
+            SystemItem::Derelict(derelict) => Some((x.location, derelict)),
+            _ => None,
+        }) {
+            if generated.contains(&derelict_loc.sector()) || system.is_sector_generated_for(derelict_loc.sector(), "cosmos:derelict") {
+                continue;
+            }
+
+            if (derelict_loc.sector() - player_loc.sector()).abs().max_element() > DERELICT_LOAD_DISTANCE {
+                continue;
+            }
+
+            generated.insert(derelict_loc.sector());
+
+            let Some(template) = templates.from_id(&derelict.template) else {
+                warn!("Missing derelict template {} - skipping derelict.", derelict.template);
+                continue;
+            };
+
+            let mut rng = get_rng_for_sector(&server_seed, &derelict_loc.sector());
+
+            commands.spawn((
+                Name::new("Loading Derelict"),
+                NeedsBlueprintLoaded {
+                    path: template.blueprint.clone(),
+                    rotation: random_quat(&mut rng),
+                    spawn_at: derelict_loc,
+                },
+                DerelictNeedsGenerated { template: template.id() },
+            ));
+        }
+
+        for &sector in &generated {
+            system.mark_sector_generated_for(sector, "cosmos:derelict");
+        }
+    }
+}
+
+/// Damages the derelict, places its loot containers & rigs some of its blocks to explode
+fn wreck_derelicts(
+    mut commands: Commands,
+    mut evr_structure_loaded: EventReader<StructureLoadedEvent>,
+    mut q_structure: Query<(&mut Structure, &DerelictNeedsGenerated)>,
+    templates: Res<Registry<DerelictTemplate>>,
+    blocks: Res<Registry<Block>>,
+    mut evw_block_changed: EventWriter<BlockChangedEvent>,
+) {
+    let Some(storage) = blocks.from_id(STORAGE_BLOCK) else {
+        return;
+    };
+
+    let protected = PROTECTED_BLOCKS
+        .iter()
+        .flat_map(|name| blocks.from_id(name))
+        .map(|block| block.id())
+        .collect::<Vec<_>>();
+
+    let mut rng = rand::thread_rng();
+
+    for ev in evr_structure_loaded.read() {
+        let Ok((mut structure, needs_generated)) = q_structure.get_mut(ev.structure_entity) else {
+            continue;
+        };
+
+        commands.entity(ev.structure_entity).remove::<DerelictNeedsGenerated>();
+
+        let template = templates.from_numeric_id(needs_generated.template);
+
+        let mut remaining = Vec::new();
+
+        for coords in structure.all_blocks_iter(false).collect::<Vec<_>>() {
+            let id = structure.block_id_at(coords);
+
+            if template.stripped_blocks.contains(&id) {
+                structure.remove_block_at(coords, &blocks, Some(&mut evw_block_changed));
+            } else if !protected.contains(&id) {
+                remaining.push(coords);
+            }
+        }
+
+        // Blows holes in the derelict until enough of it has been destroyed
+        let damage = rng.gen_range(template.min_damage..=template.max_damage.max(template.min_damage));
+        let to_destroy = (remaining.len() as f32 * damage) as usize;
+        let mut destroyed = 0;
+
+        while destroyed < to_destroy && !remaining.is_empty() {
+            let center = remaining[rng.gen_range(0..remaining.len())];
+            let radius = rng.gen_range(2..=5) as f32;
+            let center_pos = structure.block_relative_position(center);
+
+            remaining.retain(|&coords| {
+                if destroyed >= to_destroy || structure.block_relative_position(coords).distance(center_pos) > radius {
+                    return true;
+                }
+
+                structure.remove_block_at(coords, &blocks, Some(&mut evw_block_changed));
+                destroyed += 1;
+
+                false
+            });
+        }
+
+        remaining.shuffle(&mut rng);
+
+        let containers = remaining.drain(..template.loot_containers.min(remaining.len())).collect::<Vec<_>>();
+
+        for &coords in &containers {
+            structure.set_block_at(coords, storage, BlockRotation::IDENTITY, &blocks, Some(&mut evw_block_changed));
+        }
+
+        let hazards = remaining.drain(..template.hazards.min(remaining.len())).collect::<Vec<_>>();
+
+        let mut ecmds = commands.entity(ev.structure_entity);
+
+        if !hazards.is_empty() {
+            ecmds.insert(DerelictHazards(hazards));
+        }
+
+        if !containers.is_empty() {
+            ecmds.insert(DerelictLootPending {
+                containers,
+                loot_table: template.loot_table,
+            });
+        }
+    }
+}
+
+/// Storage blocks only get their inventories the frame after they are placed, so this waits until every container
+/// has an inventory before filling them.
+fn fill_derelict_loot(
+    mut commands: Commands,
+    q_pending: Query<(Entity, &Structure, &DerelictLootPending)>,
+    mut q_inventory: Query<&mut Inventory>,
+    loot_tables: Res<Registry<LootTable>>,
+    items: Res<Registry<Item>>,
+    needs_data: Res<ItemShouldHaveData>,
+) {
+    let mut rng = rand::thread_rng();
+
+    for (ent, structure, pending) in q_pending.iter() {
+        let inventory_ents = pending
+            .containers
+            .iter()
+            .map(|&coords| structure.block_data(coords).filter(|&e| q_inventory.contains(e)))
+            .collect::<Option<Vec<_>>>();
+
+        let Some(inventory_ents) = inventory_ents else {
+            continue;
+        };
+
+        commands.entity(ent).remove::<DerelictLootPending>();
+
+        let loot_table = loot_tables.from_numeric_id(pending.loot_table);
+
+        for inventory_ent in inventory_ents {
+            let Ok(mut inventory) = q_inventory.get_mut(inventory_ent) else {
+                continue;
+            };
+
+            for (item_id, quantity) in loot_table.roll(&mut rng) {
+                let item = items.from_numeric_id(item_id);
+                inventory.insert_item(item, quantity, &mut commands, &needs_data);
+            }
+        }
+    }
+}
+
+fn trigger_hazards(
+    mut commands: Commands,
+    mut evr_block_changed: EventReader<BlockChangedEvent>,
+    mut q_hazards: Query<(&mut DerelictHazards, &Structure, &Location, &GlobalTransform)>,
+) {
+    for ev in evr_block_changed.read() {
+        if ev.old_block == ev.new_block {
+            continue;
+        }
+
+        let Ok((mut hazards, structure, location, g_trans)) = q_hazards.get_mut(ev.block.structure()) else {
+            continue;
+        };
+
+        let coords = ev.block.coords();
+
+        let Some(idx) = hazards.0.iter().position(|&c| c == coords) else {
+            continue;
+        };
+
+        hazards.0.swap_remove(idx);
+
+        let explosion_loc = *location + g_trans.rotation() * structure.block_relative_position(coords);
+
+        commands.spawn((
+            explosion_loc,
+            Velocity::default(),
+            RigidBody::Dynamic,
+            LoadingDistance::new(1, 2),
+            Explosion {
+                power: HAZARD_EXPLOSION_POWER,
+                color: Some(Color::srgb(1.0, 0.5, 0.1)),
+            },
+        ));
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    create_registry::<DerelictTemplate>(app, "cosmos:derelict_templates");
+    make_persistent::<DerelictHazards>(app);
+
+    app.add_systems(
+        OnEnter(GameState::PostLoading),
+        load_derelict_templates.after(LootSet::LoadLootTables),
+    )
+    .add_systems(
+        Update,
+        (
+            generate_derelicts.in_set(SystemGenerationSet::Derelict),
+            spawn_derelicts.run_if(on_timer(Duration::from_secs(1))),
+        )
+            .chain()
+            .before(LoadingBlueprintSystemSet::BeginLoadingBlueprints)
+            .run_if(in_state(GameState::Playing)),
+    )
+    .add_systems(
+        Update,
+        (
+            wreck_derelicts.in_set(BlockEventsSet::ChangeBlocks),
+            trigger_hazards.in_set(BlockEventsSet::ProcessEvents),
+            fill_derelict_loot,
+        )
+            .chain()
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    )
+    .register_type::<DerelictHazards>();
+}
@@ -0,0 +1,148 @@
+//! Keeps track of which systems each player has explored.
+//!
+//! Players can only see the contents of systems they have explored on their map, and are
+//! rewarded the first time they enter a new system.
+
+use bevy::{prelude::*, utils::HashSet};
+use cosmos_core::{
+    chat::ServerSendChatMessageEvent,
+    economy::Credits,
+    entities::player::{spectator::Spectator, Player},
+    netty::{
+        sync::{events::server_event::NettyEventWriter, IdentifiableComponent},
+        system_sets::NetworkingSystemsSet,
+    },
+    physics::location::{Location, SystemCoordinate},
+    state::GameState,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::persistence::make_persistent::{make_persistent, DefaultPersistentComponent};
+
+/// How many credits a player earns for discovering a new system
+const DISCOVERY_REWARD: u64 = 5_000;
+
+#[derive(Component, Debug, Default, Serialize, Deserialize)]
+/// Every system this player has explored. Players can only see what's in systems they have explored.
+///
+/// Spectators do not explore the systems they fly through.
+pub struct ExploredSystems(HashSet<SystemCoordinate>);
+
+impl ExploredSystems {
+    /// Returns true if the player has explored this system
+    pub fn contains(&self, system: SystemCoordinate) -> bool {
+        self.0.contains(&system)
+    }
+
+    /// Marks this system as explored.
+    ///
+    /// Returns true if this system had not been explored before.
+    pub fn explore(&mut self, system: SystemCoordinate) -> bool {
+        self.0.insert(system)
+    }
+
+    /// The number of systems explored
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if no systems have been explored
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl IdentifiableComponent for ExploredSystems {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:explored_systems"
+    }
+}
+
+impl DefaultPersistentComponent for ExploredSystems {}
+
+#[derive(Event, Debug, Clone, Copy)]
+/// Sent whenever a player explores a system for the first time.
+///
+/// This is not sent for the system a player first spawns in.
+pub struct SystemDiscoveredEvent {
+    /// The player that discovered this system
+    pub player_entity: Entity,
+    /// The system that was discovered
+    pub system: SystemCoordinate,
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+/// Updates the systems each player has explored
+pub enum ExplorationSet {
+    /// Updates the [`ExploredSystems`] of every player & sends out [`SystemDiscoveredEvent`]s
+    TrackExploration,
+}
+
+fn track_explored_systems(
+    mut commands: Commands,
+    mut evw_discovered: EventWriter<SystemDiscoveredEvent>,
+    mut q_players: Query<(Entity, &Location, Option<&mut ExploredSystems>), (With<Player>, Without<Spectator>, Changed<Location>)>,
+) {
+    for (player_entity, location, explored) in q_players.iter_mut() {
+        let system = location.get_system_coordinates();
+
+        let Some(mut explored) = explored else {
+            commands.entity(player_entity).insert(ExploredSystems(HashSet::from_iter([system])));
+            continue;
+        };
+
+        // Avoids triggering change detection every frame
+        if !explored.contains(system) {
+            explored.explore(system);
+            evw_discovered.send(SystemDiscoveredEvent { player_entity, system });
+        }
+    }
+}
+
+fn reward_discovery(
+    mut evr_discovered: EventReader<SystemDiscoveredEvent>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+    mut q_players: Query<(&Player, &mut Credits, &ExploredSystems)>,
+) {
+    for ev in evr_discovered.read() {
+        let Ok((player, mut credits, explored)) = q_players.get_mut(ev.player_entity) else {
+            continue;
+        };
+
+        credits.increase(DISCOVERY_REWARD);
+
+        let s = ev.system;
+        nevw_chat.send(
+            ServerSendChatMessageEvent {
+                sender: None,
+                message: format!(
+                    "Discovered system ({}, {}, {}) - you've been awarded {}. Systems explored: {}",
+                    s.x(),
+                    s.y(),
+                    s.z(),
+                    Credits::new(DISCOVERY_REWARD),
+                    explored.len()
+                ),
+            },
+            player.id(),
+        );
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<ExploredSystems>(app);
+
+    app.configure_sets(
+        Update,
+        ExplorationSet::TrackExploration
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    )
+    .add_event::<SystemDiscoveredEvent>()
+    .add_systems(
+        Update,
+        (track_explored_systems, reward_discovery)
+            .chain()
+            .in_set(ExplorationSet::TrackExploration),
+    );
+}
@@ -32,6 +32,8 @@ pub enum SystemGenerationSet {
     Asteroid,
     /// Add stations to the system
     Station,
+    /// Add derelicts to the system. This happens last so derelicts can avoid everything else.
+    Derelict,
 }
 
 #[derive(Event, Debug)]
@@ -164,6 +166,13 @@ pub struct SystemItemAsteroid {
     pub temperature: f32,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+/// Represents a derelict ship or station wreck within this [`UniverseSystem`]
+pub struct SystemItemDerelict {
+    /// The unlocalized name of the derelict template this will be generated from
+    pub template: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 /// Represents everything that can be generated in a system when it is loaded
 pub enum SystemItem {
@@ -176,6 +185,8 @@ pub enum SystemItem {
     Shop,
     /// An [`cosmos_core::structure::asteroid::Asteroid`] within the [`UniverseSystem`]
     Asteroid(SystemItemAsteroid),
+    /// A damaged, abandoned structure within the [`UniverseSystem`]
+    Derelict(SystemItemDerelict),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -276,6 +287,7 @@ pub(super) fn register(app: &mut App) {
             SystemGenerationSet::Planet,
             SystemGenerationSet::Asteroid,
             SystemGenerationSet::Station,
+            SystemGenerationSet::Derelict,
         )
             .in_set(NetworkingSystemsSet::Between)
             .chain(),
@@ -7,6 +7,7 @@ use bevy::{
 use cosmos_core::{
     entities::player::Player,
     netty::{
+        server::ServerLobby,
         sync::events::server_event::{NettyEventReceived, NettyEventWriter},
         system_sets::NetworkingSystemsSet,
     },
@@ -16,16 +17,23 @@ use cosmos_core::{
     universe::map::system::{
         AsteroidDestination, Destination, FactionStatus, GalaxyMap, GalaxyMapResponseEvent, PlanetDestination, PlayerDestination,
         RequestGalaxyMap, RequestSystemMap, ShipDestination, StarDestination, StationDestination, SystemMap, SystemMapResponseEvent,
+        UnknownDestination,
     },
 };
 
 use crate::universe::generation::SystemItem;
 
-use super::{galaxy_generation::Galaxy, generation::UniverseSystems};
+use super::{
+    exploration::{ExplorationSet, ExploredSystems},
+    galaxy_generation::Galaxy,
+    generation::UniverseSystems,
+};
 
 fn send_galaxy_map(
     mut evr_request_map: EventReader<NettyEventReceived<RequestGalaxyMap>>,
     mut nevw_galaxy_map: NettyEventWriter<GalaxyMapResponseEvent>,
+    lobby: Res<ServerLobby>,
+    q_explored: Query<&ExploredSystems>,
     q_galaxy: Query<&Galaxy>,
 ) {
     for ev in evr_request_map.read() {
@@ -33,13 +41,21 @@ fn send_galaxy_map(
             continue;
         };
 
+        let explored = lobby
+            .player_from_id(ev.client_id)
+            .and_then(|player_ent| q_explored.get(player_ent).ok());
+
         let mut g_map = GalaxyMap::default();
 
-        for (_, star) in galaxy.iter_stars() {
-            g_map.add_destination(
-                star.location.sector(),
-                Destination::Star(Box::new(StarDestination { star: star.star })),
-            );
+        for (&system, star) in galaxy.iter_stars() {
+            // Every star can be seen from afar, but what kind of star it is remains a mystery until its system is explored.
+            let destination = if explored.is_some_and(|explored| explored.contains(system)) {
+                Destination::Star(Box::new(StarDestination { star: star.star }))
+            } else {
+                Destination::Unknown(Box::new(UnknownDestination { status: None }))
+            };
+
+            g_map.add_destination(star.location.sector(), destination);
         }
 
         nevw_galaxy_map.send(GalaxyMapResponseEvent { map: g_map }, ev.client_id);
@@ -50,6 +66,8 @@ fn send_map(
     mut evr_request_map: EventReader<NettyEventReceived<RequestSystemMap>>,
     mut nevw_system_map: NettyEventWriter<SystemMapResponseEvent>,
 
+    lobby: Res<ServerLobby>,
+    q_explored: Query<&ExploredSystems>,
     q_players: Query<&Location, With<Player>>,
     q_stations: Query<&Location, With<Station>>,
     q_ships: Query<&Location, With<Ship>>,
@@ -59,7 +77,22 @@ fn send_map(
     for ev in evr_request_map.read() {
         let mut system_map = SystemMap::new(ev.system);
 
-        let Some(system) = systems.system(ev.system) else {
+        let explored = lobby
+            .player_from_id(ev.client_id)
+            .and_then(|player_ent| q_explored.get(player_ent).ok())
+            .is_some_and(|explored| explored.contains(ev.system));
+
+        // Nothing is known about systems the player hasn't been to yet
+        let Some(system) = systems.system(ev.system).filter(|_| explored) else {
+            nevw_system_map.send(
+                SystemMapResponseEvent {
+                    map: system_map,
+                    system: ev.system,
+                    explored,
+                },
+                ev.client_id,
+            );
+
             continue;
         };
 
@@ -82,10 +115,15 @@ fn send_map(
                         shop_count: 1,
                     })),
                 ),
+                SystemItem::Derelict(_) => {
+                    system_map.add_destination(sector, Destination::Unknown(Box::new(UnknownDestination { status: None })))
+                }
             }
         }
 
-        for loc in q_players.iter() {
+        let in_system = |loc: &&Location| loc.get_system_coordinates() == ev.system;
+
+        for loc in q_players.iter().filter(in_system) {
             system_map.add_destination(
                 loc.relative_sector(),
                 Destination::Player(Box::new(PlayerDestination {
@@ -94,7 +132,7 @@ fn send_map(
             );
         }
 
-        for loc in q_stations.iter() {
+        for loc in q_stations.iter().filter(in_system) {
             system_map.add_destination(
                 loc.relative_sector(),
                 Destination::Station(Box::new(StationDestination {
@@ -104,7 +142,7 @@ fn send_map(
             );
         }
 
-        for loc in q_ships.iter() {
+        for loc in q_ships.iter().filter(in_system) {
             system_map.add_destination(
                 loc.relative_sector(),
                 Destination::Ship(Box::new(ShipDestination {
@@ -117,6 +155,7 @@ fn send_map(
             SystemMapResponseEvent {
                 map: system_map,
                 system: ev.system,
+                explored,
             },
             ev.client_id,
         );
@@ -127,6 +166,7 @@ pub(super) fn register(app: &mut App) {
     app.add_systems(
         Update,
         (send_galaxy_map, send_map)
+            .after(ExplorationSet::TrackExploration)
             .in_set(NetworkingSystemsSet::Between)
             .run_if(in_state(GameState::Playing)),
     );
@@ -2,20 +2,32 @@
 
 use bevy::prelude::App;
 
+pub mod asteroid_respawn;
 pub mod asteroid_spawner;
+pub mod derelict;
+pub mod exploration;
 pub mod galaxy_generation;
 pub mod generation;
 pub mod map;
 pub mod planet_spawner;
+pub mod safe_zone;
+pub mod sector_load;
+pub mod sector_rules;
 pub mod spawners;
 pub mod star;
 
 pub(super) fn register(app: &mut App) {
     galaxy_generation::register(app);
+    exploration::register(app);
     map::register(app);
     star::register(app);
     generation::register(app);
     planet_spawner::register(app);
     asteroid_spawner::register(app);
+    asteroid_respawn::register(app);
+    derelict::register(app);
     spawners::register(app);
+    safe_zone::register(app);
+    sector_rules::register(app);
+    sector_load::register(app);
 }
@@ -0,0 +1,132 @@
+//! Safe zones are areas where weapons cannot be fired and blocks cannot be damaged.
+//!
+//! Every shop station (where new players spawn) is a safe zone, and server admins can turn any
+//! entity into the center of a safe zone with the `safezone` console command.
+//!
+//! New players and players who just respawned also get [`SpawnProtection`] for a short time, which stops the player
+//! themselves from being hurt. It doesn't protect the structure they're aboard.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use cosmos_core::{
+    chat::ServerSendChatMessageEvent,
+    entities::player::Player,
+    netty::{
+        sync::{events::server_event::NettyEventWriter, IdentifiableComponent},
+        system_sets::NetworkingSystemsSet,
+    },
+    physics::location::Location,
+    state::GameState,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::persistence::make_persistent::{make_persistent, DefaultPersistentComponent};
+
+use super::generation::{SystemItem, UniverseSystems};
+
+/// How far (in blocks) the safe zone around every shop station extends
+pub const SHOP_SAFE_ZONE_RADIUS: f32 = 1500.0;
+
+/// How long (in seconds) a newly spawned player is protected for
+pub const SPAWN_PROTECTION_SECONDS: f32 = 60.0;
+
+#[derive(Component, Debug, Clone, Copy, Serialize, Deserialize, Reflect)]
+/// Makes the area around this entity's [`Location`] a safe zone.
+///
+/// This is given/taken away via the `safezone [entity_id] [radius]` console command.
+pub struct SafeZone {
+    /// How far (in blocks) from this entity the safe zone extends
+    pub radius: f32,
+}
+
+impl IdentifiableComponent for SafeZone {
+    fn get_component_unlocalized_name() -> &'static str {
+        "cosmos:safe_zone"
+    }
+}
+
+impl DefaultPersistentComponent for SafeZone {}
+
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+/// A newly spawned player cannot be hurt for a short time.
+///
+/// Anything that damages players should skip players with this.
+pub struct SpawnProtection {
+    /// How many seconds of protection are left
+    pub remaining: f32,
+}
+
+impl Default for SpawnProtection {
+    fn default() -> Self {
+        Self {
+            remaining: SPAWN_PROTECTION_SECONDS,
+        }
+    }
+}
+
+#[derive(SystemParam)]
+/// Used to check if something is inside of a safe zone.
+///
+/// Systems that fire weapons or damage blocks should consult this before doing so.
+pub struct SafeZones<'w, 's> {
+    universe_systems: Res<'w, UniverseSystems>,
+    q_zones: Query<'w, 's, (&'static Location, &'static SafeZone)>,
+}
+
+impl SafeZones<'_, '_> {
+    /// Returns true if this location is within any safe zone
+    pub fn in_safe_zone(&self, location: &Location) -> bool {
+        let near_shop = self
+            .universe_systems
+            .system(location.get_system_coordinates())
+            .is_some_and(|system| {
+                system.iter().any(|item| {
+                    matches!(item.item, SystemItem::Shop)
+                        && item.location.is_within_reasonable_range(location)
+                        && item.location.distance_sqrd(location) < SHOP_SAFE_ZONE_RADIUS * SHOP_SAFE_ZONE_RADIUS
+                })
+            });
+
+        near_shop
+            || self.q_zones.iter().any(|(zone_loc, zone)| {
+                zone_loc.is_within_reasonable_range(location) && zone_loc.distance_sqrd(location) < zone.radius * zone.radius
+            })
+    }
+}
+
+fn tick_spawn_protection(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_protected: Query<(Entity, &Player, &mut SpawnProtection)>,
+    mut nevw_chat: NettyEventWriter<ServerSendChatMessageEvent>,
+) {
+    for (ent, player, mut protection) in q_protected.iter_mut() {
+        protection.remaining -= time.delta_secs();
+
+        if protection.remaining > 0.0 {
+            continue;
+        }
+
+        commands.entity(ent).remove::<SpawnProtection>();
+
+        nevw_chat.send(
+            ServerSendChatMessageEvent {
+                sender: None,
+                message: "Your spawn protection has worn off.".into(),
+            },
+            player.id(),
+        );
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    make_persistent::<SafeZone>(app);
+
+    app.add_systems(
+        Update,
+        tick_spawn_protection
+            .in_set(NetworkingSystemsSet::Between)
+            .run_if(in_state(GameState::Playing)),
+    )
+    .register_type::<SafeZone>()
+    .register_type::<SpawnProtection>();
+}
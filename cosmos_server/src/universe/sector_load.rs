@@ -0,0 +1,176 @@
+//! Measures how busy every sector is, and throttles low-priority work in sectors that are overloaded.
+//!
+//! Every second, the number of entities in each sector is counted and each sector is given its share of the
+//! server's average tick time. Sectors that have too many entities or take up too much of the tick are throttled
+//! until they calm down again. Throttled sectors update things like AI less often, and wait to place large
+//! world features.
+//!
+//! Use the `sectorload` console command to see these metrics.
+
+use std::time::{Duration, Instant};
+
+use bevy::{
+    core::FrameCount,
+    prelude::*,
+    time::common_conditions::on_timer,
+    utils::{HashMap, HashSet},
+};
+use cosmos_core::{
+    netty::system_sets::NetworkingSystemsSet,
+    physics::{
+        location::{Location, Sector},
+        player_world::PlayerWorld,
+    },
+    state::GameState,
+};
+
+/// A sector with at least this many entities is throttled
+const MAX_ENTITIES_PER_SECTOR: u32 = 300;
+/// A sector that uses at least this much of every tick (in milliseconds) is throttled
+const SECTOR_TICK_BUDGET_MS: f32 = 10.0;
+/// A throttled sector has to drop below this fraction of the limits above before it stops being throttled.
+///
+/// This prevents sectors right at the limit from constantly switching between being throttled & not.
+const UNTHROTTLE_FRACTION: f32 = 0.75;
+/// Low-priority work in throttled sectors only runs once every this many frames
+const THROTTLED_UPDATE_INTERVAL: u32 = 4;
+/// How much each new tick affects the average tick time
+const TICK_TIME_SMOOTHING: f32 = 0.05;
+
+#[derive(Debug, Default, Clone, Copy)]
+/// How busy a single sector is
+pub struct SectorLoadInfo {
+    /// The number of top-level entities in this sector
+    pub entities: u32,
+    /// Roughly how many milliseconds of each tick are spent on this sector
+    pub estimated_tick_ms: f32,
+    /// If low-priority work in this sector is currently being throttled
+    pub throttled: bool,
+}
+
+#[derive(Resource, Debug, Default)]
+/// How busy every sector with entities in it is.
+///
+/// Systems doing low-priority work (such as AI) should check [`SectorLoad::should_run_low_priority`] before
+/// doing that work for an entity.
+pub struct SectorLoad {
+    sectors: HashMap<Sector, SectorLoadInfo>,
+    average_tick_ms: f32,
+}
+
+impl SectorLoad {
+    /// How busy this sector is. Sectors without any entities will return `None`.
+    pub fn sector(&self, sector: Sector) -> Option<&SectorLoadInfo> {
+        self.sectors.get(&sector)
+    }
+
+    /// Every sector with entities in it
+    pub fn iter(&self) -> impl Iterator<Item = (Sector, &SectorLoadInfo)> {
+        self.sectors.iter().map(|(sector, info)| (*sector, info))
+    }
+
+    /// The average time (in milliseconds) each server tick takes
+    pub fn average_tick_ms(&self) -> f32 {
+        self.average_tick_ms
+    }
+
+    /// Returns true if low-priority work in this sector is being throttled
+    pub fn is_throttled(&self, sector: Sector) -> bool {
+        self.sectors.get(&sector).is_some_and(|x| x.throttled)
+    }
+
+    /// Returns true if low-priority work for this entity should be done this frame.
+    ///
+    /// In throttled sectors, this is only true once every few frames. Entities are spread out over those
+    /// frames so they don't all update at once.
+    pub fn should_run_low_priority(&self, sector: Sector, entity: Entity, frame: &FrameCount) -> bool {
+        !self.is_throttled(sector) || (frame.0 + entity.index()) % THROTTLED_UPDATE_INTERVAL == 0
+    }
+}
+
+#[derive(Resource, Debug)]
+/// When the current tick started being processed
+struct TickStart(Instant);
+
+fn start_tick(mut commands: Commands) {
+    commands.insert_resource(TickStart(Instant::now()));
+}
+
+/// Only measures how long the schedules took to run, so time spent waiting for the next tick isn't counted
+fn measure_tick_time(tick_start: Option<Res<TickStart>>, mut sector_load: ResMut<SectorLoad>) {
+    let Some(tick_start) = tick_start else {
+        return;
+    };
+
+    let tick_ms = tick_start.0.elapsed().as_secs_f32() * 1000.0;
+
+    let sector_load = sector_load.bypass_change_detection();
+    sector_load.average_tick_ms += (tick_ms - sector_load.average_tick_ms) * TICK_TIME_SMOOTHING;
+}
+
+fn is_overloaded(info: &SectorLoadInfo, fraction: f32) -> bool {
+    info.entities as f32 >= MAX_ENTITIES_PER_SECTOR as f32 * fraction || info.estimated_tick_ms >= SECTOR_TICK_BUDGET_MS * fraction
+}
+
+fn measure_sector_load(q_entities: Query<&Location, (Without<Parent>, Without<PlayerWorld>)>, mut sector_load: ResMut<SectorLoad>) {
+    let mut counts = HashMap::<Sector, u32>::default();
+
+    for location in q_entities.iter() {
+        *counts.entry(location.sector()).or_default() += 1;
+    }
+
+    let total_entities = counts.values().sum::<u32>().max(1);
+    let average_tick_ms = sector_load.average_tick_ms;
+
+    let was_throttled = sector_load
+        .sectors
+        .iter()
+        .filter(|(_, info)| info.throttled)
+        .map(|(sector, _)| *sector)
+        .collect::<HashSet<_>>();
+
+    sector_load.sectors = counts
+        .into_iter()
+        .map(|(sector, entities)| {
+            let mut info = SectorLoadInfo {
+                entities,
+                estimated_tick_ms: average_tick_ms * entities as f32 / total_entities as f32,
+                throttled: false,
+            };
+
+            let throttled = if was_throttled.contains(&sector) {
+                is_overloaded(&info, UNTHROTTLE_FRACTION)
+            } else {
+                is_overloaded(&info, 1.0)
+            };
+
+            if throttled != was_throttled.contains(&sector) {
+                if throttled {
+                    warn!(
+                        "Sector {sector} is overloaded ({entities} entities, ~{:.1}ms/tick) - throttling it.",
+                        info.estimated_tick_ms
+                    );
+                } else {
+                    info!("Sector {sector} is no longer overloaded.");
+                }
+            }
+
+            info.throttled = throttled;
+
+            (sector, info)
+        })
+        .collect();
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<SectorLoad>()
+        .add_systems(First, start_tick)
+        .add_systems(Last, measure_tick_time)
+        .add_systems(
+            Update,
+            measure_sector_load
+                .run_if(on_timer(Duration::from_secs(1)))
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        );
+}
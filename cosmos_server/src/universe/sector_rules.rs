@@ -0,0 +1,208 @@
+//! Decides the rules of every sector, and tells players when they cross into a sector with different rules.
+//!
+//! Sectors with a shop in them are lawful, sectors near a shop are policed, and everywhere else is lawless. Server
+//! admins can change all of these and override the rules of specific sectors in `./config/cosmos/sector_rules.json`.
+
+use std::fs;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use cosmos_core::{
+    entities::player::Player,
+    netty::{sync::events::server_event::NettyEventWriter, system_sets::NetworkingSystemsSet},
+    physics::location::{Location, Sector, SectorUnit},
+    state::GameState,
+    structure::ownership::StructureOwnership,
+    universe::sector_rules::{SectorRules, SectorRulesChangedEvent},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::entities::player::admin::Admin;
+
+use super::generation::{SystemItem, UniverseSystems};
+
+const SECTOR_RULES_PATH: &str = "./config/cosmos/sector_rules.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Rules for one specific sector, set by the server admin
+struct SectorRulesOverride {
+    sector: Sector,
+    rules: SectorRules,
+}
+
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+/// Server-configurable rules for every sector
+pub struct SectorRulesSettings {
+    /// The rules of any sector that isn't a shop sector and isn't overridden
+    default_rules: SectorRules,
+    /// The rules of sectors that have a shop in them
+    shop_rules: SectorRules,
+    /// The rules of sectors near a shop
+    #[serde(default = "default_policed_rules")]
+    policed_rules: SectorRules,
+    /// How many sectors away from a shop are policed
+    #[serde(default = "default_policed_radius")]
+    policed_radius: SectorUnit,
+    /// Sectors whose rules are set by the server admin. These take priority over everything else.
+    overrides: Vec<SectorRulesOverride>,
+}
+
+fn default_policed_rules() -> SectorRules {
+    SectorRules::POLICED
+}
+
+fn default_policed_radius() -> SectorUnit {
+    2
+}
+
+impl Default for SectorRulesSettings {
+    fn default() -> Self {
+        Self {
+            default_rules: SectorRules::LAWLESS,
+            shop_rules: SectorRules::LAWFUL,
+            policed_rules: default_policed_rules(),
+            policed_radius: default_policed_radius(),
+            overrides: vec![],
+        }
+    }
+}
+
+#[derive(SystemParam)]
+/// Used to look up the rules of any sector.
+///
+/// Combat and building systems should consult this before letting players do something.
+pub struct SectorRulesLookup<'w> {
+    settings: Res<'w, SectorRulesSettings>,
+    universe_systems: Res<'w, UniverseSystems>,
+}
+
+impl SectorRulesLookup<'_> {
+    /// Returns the rules of this sector
+    pub fn rules_at(&self, sector: Sector) -> SectorRules {
+        if let Some(rules_override) = self.settings.overrides.iter().find(|o| o.sector == sector) {
+            return rules_override.rules;
+        }
+
+        let location = Location::new(Vec3::ZERO, sector);
+
+        // Only shops within the same system are considered, since a system is far larger than the policed radius
+        let shop_distance = self.universe_systems.system(location.get_system_coordinates()).and_then(|system| {
+            system
+                .iter()
+                .filter(|item| matches!(item.item, SystemItem::Shop))
+                .map(|item| (item.location.sector() - sector).abs().max_element())
+                .min()
+        });
+
+        match shop_distance {
+            Some(0) => self.settings.shop_rules,
+            Some(distance) if distance <= self.settings.policed_radius => self.settings.policed_rules,
+            _ => self.settings.default_rules,
+        }
+    }
+}
+
+#[derive(SystemParam)]
+/// Checks if players are allowed to edit blocks based on the rules of the sector they're editing in.
+pub struct BlockEditRules<'w, 's> {
+    lookup: SectorRulesLookup<'w>,
+    q_admin: Query<'w, 's, (), (With<Player>, With<Admin>)>,
+}
+
+impl BlockEditRules<'_, '_> {
+    /// Returns true if this entity can place or break blocks in this sector.
+    ///
+    /// [`Admin`]s can always edit blocks.
+    pub fn can_edit_blocks(&self, entity: Entity, sector: Sector) -> bool {
+        self.q_admin.contains(entity) || self.lookup.rules_at(sector).block_editing
+    }
+}
+
+#[derive(SystemParam)]
+/// Checks if structures are allowed to damage each other based on the rules of the sector they're fighting in.
+pub struct CombatRules<'w, 's> {
+    lookup: SectorRulesLookup<'w>,
+    q_ownership: Query<'w, 's, &'static StructureOwnership>,
+}
+
+impl CombatRules<'_, '_> {
+    /// Returns true if the `causer` can damage the `target` structure at this location.
+    ///
+    /// Outside of PvP sectors, player-owned structures cannot damage structures owned by other players. Players can
+    /// always damage their own structures, and unowned structures (such as pirates and asteroids) can always be damaged.
+    pub fn can_damage(&self, causer: Option<Entity>, target: Entity, target_location: &Location) -> bool {
+        let Some(causer_owner) = causer.filter(|&c| c != target).and_then(|c| self.q_ownership.get(c).ok()) else {
+            return true;
+        };
+
+        let Ok(target_owner) = self.q_ownership.get(target) else {
+            return true;
+        };
+
+        if causer_owner.owner() == target_owner.owner() {
+            return true;
+        }
+
+        self.lookup.rules_at(target_location.sector()).pvp
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+/// The rules of the sector this player was last told about
+struct KnownSectorRules(SectorRules);
+
+fn notify_players_of_sector_rules(
+    mut commands: Commands,
+    q_players: Query<(Entity, &Player, &Location, Option<&KnownSectorRules>)>,
+    lookup: SectorRulesLookup,
+    mut nevw_sector_rules: NettyEventWriter<SectorRulesChangedEvent>,
+) {
+    for (ent, player, location, known_rules) in q_players.iter() {
+        let rules = lookup.rules_at(location.sector());
+
+        if known_rules.is_some_and(|known| known.0 == rules) {
+            continue;
+        }
+
+        commands.entity(ent).insert(KnownSectorRules(rules));
+
+        nevw_sector_rules.send(
+            SectorRulesChangedEvent {
+                sector: location.sector(),
+                rules,
+            },
+            player.id(),
+        );
+    }
+}
+
+fn load_sector_rules_settings(mut commands: Commands) {
+    let settings = match fs::read_to_string(SECTOR_RULES_PATH) {
+        Ok(json) => serde_json::from_str::<SectorRulesSettings>(&json).unwrap_or_else(|e| {
+            error!("Invalid sector rules in {SECTOR_RULES_PATH} - using defaults.\n{e:?}");
+            SectorRulesSettings::default()
+        }),
+        Err(_) => {
+            let settings = SectorRulesSettings::default();
+
+            let json = serde_json::to_string_pretty(&settings).expect("Sector rules are always valid json");
+            if let Err(e) = fs::create_dir_all("./config/cosmos").and_then(|_| fs::write(SECTOR_RULES_PATH, json)) {
+                error!("Unable to write default sector rules to {SECTOR_RULES_PATH}.\n{e:?}");
+            }
+
+            settings
+        }
+    };
+
+    commands.insert_resource(settings);
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<SectorRulesSettings>()
+        .add_systems(OnEnter(GameState::PostLoading), load_sector_rules_settings)
+        .add_systems(
+            Update,
+            notify_players_of_sector_rules
+                .in_set(NetworkingSystemsSet::Between)
+                .run_if(in_state(GameState::Playing)),
+        );
+}
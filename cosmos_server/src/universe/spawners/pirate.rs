@@ -36,6 +36,7 @@ use crate::{
         make_persistent::{make_persistent, DefaultPersistentComponent},
     },
     settings::ServerSettings,
+    universe::sector_rules::SectorRulesLookup,
 };
 
 /// TODO: Load this from config
@@ -138,6 +139,7 @@ fn spawn_pirates(
     time: Res<Time>,
     min_pirate_spawn_time: Res<MinPirateSpawnTime>,
     server_settings: Res<ServerSettings>,
+    sector_rules: SectorRulesLookup,
 ) {
     if server_settings.peaceful {
         return;
@@ -180,6 +182,11 @@ fn spawn_pirates(
             continue;
         }
 
+        // Pirates stay out of policed sectors
+        if sector_rules.rules_at(sector).police_response {
+            continue;
+        }
+
         let n_players = player_ents.len();
 
         let player_strength = PlayerStrength(player_strength.0 / n_players as f32);
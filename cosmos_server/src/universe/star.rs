@@ -4,15 +4,18 @@ use std::slice::Iter;
 
 use bevy::{
     core::Name,
-    prelude::{in_state, App, Commands, EventReader, IntoSystemConfigs, Query, Res, ResMut, Update, With},
+    prelude::{in_state, App, Commands, EventReader, GlobalTransform, IntoSystemConfigs, Query, Res, ResMut, Update, Vec3, With},
 };
 use bevy_rapier3d::prelude::Velocity;
 use bevy_renet2::renet2::RenetServer;
 use cosmos_core::{
     entities::player::Player,
     netty::{
-        cosmos_encoder, server_reliable_messages::ServerReliableMessages, sync::server_entity_syncing::RequestedEntityEvent,
-        system_sets::NetworkingSystemsSet, NettyChannelServer,
+        network_stats::{NettyMessage, NettyServerMessages},
+        server_reliable_messages::ServerReliableMessages,
+        sync::server_entity_syncing::RequestedEntityEvent,
+        system_sets::NetworkingSystemsSet,
+        NettyChannelServer,
     },
     persistence::LoadingDistance,
     physics::location::{Location, SYSTEM_SECTORS},
@@ -74,10 +77,10 @@ fn load_stars_in_universe(
 fn on_request_star(mut event_reader: EventReader<RequestedEntityEvent>, query: Query<&Star>, mut server: ResMut<RenetServer>) {
     for ev in event_reader.read() {
         if let Ok(star) = query.get(ev.entity) {
-            server.send_message(
+            server.send_netty_message(
                 ev.client_id,
                 NettyChannelServer::Reliable,
-                cosmos_encoder::serialize(&ServerReliableMessages::Star {
+                NettyMessage::new(&ServerReliableMessages::Star {
                     entity: ev.entity,
                     star: *star,
                 }),
@@ -114,6 +117,23 @@ pub fn calculate_temperature_at(stars: Iter<'_, (Location, Star)>, location: &Lo
     })
 }
 
+/// Returns how many degrees the sun is above the horizon at this position, using the planet's center as "down".
+///
+/// Without a planet the sun never sets, and without a star it never rises.
+pub fn sun_elevation_degrees(position: Vec3, planet: Option<(&Location, &GlobalTransform)>, star: Option<&Location>) -> f32 {
+    let Some((planet_loc, planet_g_trans)) = planet else {
+        return 90.0;
+    };
+    let Some(star_loc) = star else {
+        return -90.0;
+    };
+
+    let up = (position - planet_g_trans.translation()).normalize_or_zero();
+    let sun = Vec3::from(*star_loc - *planet_loc).normalize_or_zero();
+
+    up.dot(sun).clamp(-1.0, 1.0).asin().to_degrees()
+}
+
 fn generate_stars(
     mut evr_generate_system: EventReader<GenerateSystemEvent>,
     mut universe_systems: ResMut<UniverseSystems>,